@@ -0,0 +1,40 @@
+use std::{path::Path, sync::Arc};
+
+use brontes_database::libmdbx::LibmdbxInit;
+use brontes_types::init_thread_pools;
+use clap::Parser;
+
+use crate::{
+    cli::{get_env_vars, get_tracing_provider, load_clickhouse, load_database, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ApplyConfig;
+
+impl ApplyConfig {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+
+        init_thread_pools(10);
+        let task_executor = ctx.task_executor;
+
+        let libmdbx =
+            static_object(load_database(&task_executor, brontes_db_path, None, None).await?);
+        let clickhouse = static_object(load_clickhouse(Default::default(), None).await?);
+
+        let tracer = Arc::new(get_tracing_provider(Path::new(&db_path), 10, task_executor.clone()));
+
+        task_executor
+            .spawn_critical("apply config", async move {
+                if let Err(e) = libmdbx.reload_config(clickhouse, tracer).await {
+                    tracing::error!(%e, "failed to apply config");
+                } else {
+                    tracing::info!("applied config");
+                }
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use brontes_database::{libmdbx::tiering::TIERED_TABLES, Tables};
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Archive {
+    /// Tables to move to cold storage
+    #[arg(long, short, value_delimiter = ',', default_value = "TxTraces,DexPrice")]
+    pub tables:      Vec<Tables>,
+    /// First block to archive
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// Last block to archive, inclusive
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Blocks per archive file. Smaller files are cheaper to open
+    #[arg(long, default_value = "10000")]
+    pub chunk_size:  u64,
+}
+
+impl Archive {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if let Some(table) = self.tables.iter().find(|t| !TIERED_TABLES.contains(t)) {
+            eyre::bail!("{table} can't be moved to cold storage, only {TIERED_TABLES:?} can");
+        }
+        if self.start_block > self.end_block || self.chunk_size == 0 {
+            eyre::bail!("invalid block range or chunk size");
+        }
+
+        let db = load_libmdbx(&ctx.task_executor, brontes_db_path)?;
+
+        for table in &self.tables {
+            let mut start_block = self.start_block;
+            while start_block <= self.end_block {
+                let end_block = (start_block + self.chunk_size - 1).min(self.end_block);
+                let rows = db.archive_blocks(*table, start_block, end_block)?;
+                tracing::info!(%table, start_block, end_block, rows, "archived block range");
+                start_block = end_block + 1;
+            }
+        }
+
+        Ok(())
+    }
+}
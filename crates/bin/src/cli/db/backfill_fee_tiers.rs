@@ -0,0 +1,79 @@
+use std::{path::Path, sync::Arc};
+
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{db::pool_fee_tier::PoolFeeTier, make_call_request, Protocol};
+use clap::Parser;
+use futures::{stream, StreamExt};
+use itertools::Itertools;
+
+use crate::{
+    cli::{determine_max_tasks, get_env_vars, get_tracing_provider, load_libmdbx, static_object},
+    runner::CliContext,
+};
+
+alloy_sol_macro::sol!(
+    function fee() external view returns (uint24);
+);
+
+/// Protocols that deploy a pool per fee tier, which the pool returns from
+/// `fee()`
+const FEE_TIERED_PROTOCOLS: [Protocol; 3] =
+    [Protocol::UniswapV3, Protocol::SushiSwapV3, Protocol::PancakeSwapV3];
+
+#[derive(Debug, Parser)]
+pub struct BackfillFeeTiers {
+    /// Max number of concurrent calls
+    #[arg(long)]
+    pub max_tasks: Option<u64>,
+}
+
+impl BackfillFeeTiers {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+        let max_tasks = determine_max_tasks(self.max_tasks);
+
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+        let tracer = Arc::new(get_tracing_provider(
+            Path::new(&db_path),
+            max_tasks,
+            ctx.task_executor.clone(),
+        ));
+
+        // pools discovered before fee tiers were recorded
+        let pools = libmdbx
+            .protocols_created_before(u64::MAX)?
+            .into_keys()
+            .filter(|(_, protocol)| FEE_TIERED_PROTOCOLS.contains(protocol))
+            .map(|(pool, _)| pool)
+            .filter(|pool| !matches!(libmdbx.try_fetch_pool_fee_tier(*pool), Ok(Some(_))))
+            .collect_vec();
+        tracing::info!(pools = pools.len(), "backfilling pool fee tiers");
+
+        let fee_tiers = stream::iter(pools)
+            .map(|pool| {
+                let tracer = &tracer;
+                async move { (pool, make_call_request(feeCall {}, tracer, pool, None).await) }
+            })
+            .buffer_unordered(max_tasks as usize)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut failed = 0;
+        for (pool, fee) in fee_tiers {
+            match fee {
+                Ok(fee) => {
+                    libmdbx
+                        .insert_pool_fee_tier(pool, PoolFeeTier::new(fee._0.to()))
+                        .await?
+                }
+                Err(error) => {
+                    failed += 1;
+                    tracing::warn!(?pool, %error, "failed to query the pool fee");
+                }
+            }
+        }
+        tracing::info!(failed, "backfilled pool fee tiers");
+
+        Ok(())
+    }
+}
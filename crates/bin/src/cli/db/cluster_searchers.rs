@@ -0,0 +1,58 @@
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::db::searcher_fingerprint::cluster_contracts;
+use clap::Parser;
+
+use crate::{
+    cli::{load_clickhouse, load_libmdbx, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ClusterSearchers {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block:   u64,
+    /// End Block
+    #[arg(long, short)]
+    pub end_block:     u64,
+    /// Fingerprints shared by more contracts than this are ignored
+    #[arg(long, default_value = "10")]
+    pub max_contracts: u64,
+}
+
+impl ClusterSearchers {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+        let clickhouse = load_clickhouse(Default::default(), None).await?;
+
+        let shared = clickhouse
+            .shared_searcher_fingerprints(self.start_block, self.end_block, self.max_contracts)
+            .await?;
+        let clusters = cluster_contracts(&shared);
+
+        tracing::info!(
+            fingerprints = shared.len(),
+            clusters = clusters.len(),
+            "linking searcher contracts with matching calldata fingerprints"
+        );
+
+        for cluster in clusters {
+            let mut infos = libmdbx.try_fetch_searcher_contract_infos(cluster.clone())?;
+
+            for contract in &cluster {
+                let mut info = infos.remove(contract).unwrap_or_default();
+                for sibling in cluster.iter().filter(|sibling| *sibling != contract) {
+                    if !info.sibling_searchers.contains(sibling) {
+                        info.sibling_searchers.push(*sibling);
+                    }
+                }
+
+                libmdbx
+                    .write_searcher_contract_info(*contract, info)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
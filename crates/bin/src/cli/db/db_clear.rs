@@ -66,6 +66,17 @@ impl Clear {
                 AddressMeta,
                 SearcherEOAs,
                 SearcherContracts,
+                SanctionedAddresses,
+                CurvePoolParameters,
+                FunctionSelectors,
+                BackfillCheckpoints,
+                SearcherFundingSources,
+                TokenTaxes,
+                PoolFeeTiers,
+                ProtocolRegistry,
+                InspectorRuns,
+                BuilderPnls,
+                ClassificationFailures,
                 TxTraces
             )
         });
@@ -53,6 +53,17 @@ impl Insert {
             SearcherEOAs,
             SearcherContracts,
             InitializedState,
+            SanctionedAddresses,
+            CurvePoolParameters,
+            FunctionSelectors,
+            BackfillCheckpoints,
+            SearcherFundingSources,
+            TokenTaxes,
+            PoolFeeTiers,
+            ProtocolRegistry,
+            InspectorRuns,
+            BuilderPnls,
+            ClassificationFailures,
             PoolCreationBlocks = &self.key,
             &self.value
         );
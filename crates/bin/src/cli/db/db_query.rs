@@ -77,6 +77,17 @@ impl DatabaseQuery {
                     AddressMeta,
                     SearcherEOAs,
                     SearcherContracts,
+                    SanctionedAddresses,
+                    CurvePoolParameters,
+                    FunctionSelectors,
+                    BackfillCheckpoints,
+                    SearcherFundingSources,
+                    TokenTaxes,
+                    PoolFeeTiers,
+                    ProtocolRegistry,
+                    InspectorRuns,
+                    BuilderPnls,
+                    ClassificationFailures,
                     TxTraces
                 );
             } else {
@@ -96,6 +107,17 @@ impl DatabaseQuery {
                     AddressMeta,
                     SearcherEOAs,
                     SearcherContracts,
+                    SanctionedAddresses,
+                    CurvePoolParameters,
+                    FunctionSelectors,
+                    BackfillCheckpoints,
+                    SearcherFundingSources,
+                    TokenTaxes,
+                    PoolFeeTiers,
+                    ProtocolRegistry,
+                    InspectorRuns,
+                    BuilderPnls,
+                    ClassificationFailures,
                     TxTraces,
                     PoolCreationBlocks = &self.key
                 );
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use brontes_database::{parquet::ParquetExporter, Tables};
+use brontes_types::mev::MevType;
 use clap::Parser;
 use futures::future::join_all;
 use tokio::task::spawn;
@@ -14,29 +15,56 @@ use crate::{
 pub struct Export {
     /// Optional tables to exports, if omitted will export all supported tables
     #[arg(long, short, default_values = &["MevBlocks", "AddressMeta", "SearcherContracts", "Builder"], value_delimiter = ',', ignore_case=true)]
-    pub tables:      Vec<Tables>,
+    pub tables:         Vec<Tables>,
     /// Optional Start Block, if omitted it will export the entire range to
     /// parquet
     #[arg(long, short)]
-    pub start_block: Option<u64>,
+    pub start_block:    Option<u64>,
     /// Optional End Block
     #[arg(long, short)]
-    pub end_block:   Option<u64>,
+    pub end_block:      Option<u64>,
     /// Optional path, will default to "data_exports/"
     #[arg(long, short)]
-    pub path:        Option<String>,
+    pub path:           Option<String>,
+    /// Also export a flattened, labeled bundle dataset for training mev
+    /// classification models
+    #[arg(long, default_value_t = false)]
+    pub ml_dataset:     bool,
+    /// Splits the mev blocks and bundles into files of this many blocks, named
+    /// after the block range they hold and grouped by the day of their first
+    /// block
+    #[arg(long)]
+    pub partition_size: Option<u64>,
+    /// Only export the bundles of these mev types, if omitted all are exported
+    #[arg(long, value_delimiter = ',')]
+    pub mev_types:      Vec<MevType>,
 }
 
 impl Export {
     pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
         let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
-        let exporter =
-            Arc::new(ParquetExporter::new(self.start_block, self.end_block, self.path, libmdbx));
+        let mut exporter =
+            ParquetExporter::new(self.start_block, self.end_block, self.path, libmdbx)
+                .with_mev_types(self.mev_types);
+        if let Some(partition_size) = self.partition_size {
+            eyre::ensure!(partition_size > 0, "partition size must be at least one block");
+            exporter = exporter.with_partition_size(partition_size);
+        }
+        let exporter = Arc::new(exporter);
+
+        let mut futures = self
+            .tables
+            .into_iter()
+            .map(|t| {
+                let exporter = exporter.clone();
+                spawn(async move { t.export_to_parquet(exporter).await })
+            })
+            .collect::<Vec<_>>();
 
-        let futures = self.tables.into_iter().map(|t| {
+        if self.ml_dataset {
             let exporter = exporter.clone();
-            spawn(async move { t.export_to_parquet(exporter).await })
-        });
+            futures.push(spawn(async move { exporter.export_ml_dataset().await }));
+        }
 
         let results = join_all(futures).await;
 
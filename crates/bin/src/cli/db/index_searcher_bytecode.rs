@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{
+    db::searcher_bytecode::{BytecodeIndex, SearcherBytecode},
+    traits::TracingProvider,
+    FastHashMap,
+};
+use clap::Parser;
+use futures::{stream, StreamExt};
+use itertools::Itertools;
+
+use crate::{
+    cli::{
+        determine_max_tasks, get_env_vars, get_tracing_provider, load_clickhouse, load_libmdbx,
+        static_object,
+    },
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct IndexSearcherBytecode {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End Block
+    #[arg(long, short)]
+    pub end_block:   u64,
+}
+
+impl IndexSearcherBytecode {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+        let max_tasks = determine_max_tasks(None);
+
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+        let clickhouse = load_clickhouse(Default::default(), None).await?;
+        let tracer =
+            get_tracing_provider(Path::new(&db_path), max_tasks, ctx.task_executor.clone());
+
+        let known = clickhouse.searcher_bytecodes().await?;
+        let mut index = BytecodeIndex::new(&known);
+
+        // first block each contract was seen in, which is when its code is
+        // fetched
+        let mut contracts: FastHashMap<_, u64> = FastHashMap::default();
+        for mev_block in libmdbx.try_fetch_mev_blocks(Some(self.start_block), self.end_block)? {
+            for bundle in mev_block.mev {
+                let Some(contract) = bundle.header.mev_contract else { continue };
+                let block = contracts
+                    .entry(contract)
+                    .or_insert(bundle.header.block_number);
+                *block = (*block).min(bundle.header.block_number);
+            }
+        }
+        known.iter().for_each(|bytecode| {
+            contracts.remove(&bytecode.mev_contract);
+        });
+
+        let new = stream::iter(contracts)
+            .map(|(contract, block)| {
+                let tracer = &tracer;
+                async move {
+                    let code = tracer.get_bytecode(Some(block), contract).await?;
+                    eyre::Ok(code.and_then(|code| {
+                        SearcherBytecode::new(contract, block, &code.original_bytes())
+                    }))
+                }
+            })
+            .buffer_unordered(max_tasks as usize)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .filter_map_ok(|bytecode| bytecode)
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        clickhouse.insert_searcher_bytecodes(&new).await?;
+        new.iter().for_each(|bytecode| index.insert(bytecode));
+
+        let mut labelled = 0;
+        for bytecode in &new {
+            let matches = index.matches(bytecode);
+            if matches.is_empty() {
+                continue
+            }
+
+            let mut infos = libmdbx.try_fetch_searcher_contract_infos(
+                matches
+                    .iter()
+                    .map(|(contract, _)| *contract)
+                    .chain(std::iter::once(bytecode.mev_contract))
+                    .collect(),
+            )?;
+            let mut info = infos.remove(&bytecode.mev_contract).unwrap_or_default();
+
+            // matches are ordered exact first, so the closest labelled copy is
+            // the origin
+            if let Some((origin, kind)) = matches
+                .iter()
+                .find(|(contract, _)| infos.get(contract).is_some_and(|info| info.is_labelled()))
+            {
+                info.inherit_labels(&infos[origin]);
+                labelled += 1;
+                tracing::info!(
+                    contract = ?bytecode.mev_contract,
+                    ?origin,
+                    ?kind,
+                    "labelled searcher contract from its bytecode"
+                );
+            }
+
+            for (sibling, _) in &matches {
+                if !info.sibling_searchers.contains(sibling) {
+                    info.sibling_searchers.push(*sibling);
+                }
+
+                let mut sibling_info = infos.remove(sibling).unwrap_or_default();
+                if !sibling_info
+                    .sibling_searchers
+                    .contains(&bytecode.mev_contract)
+                {
+                    sibling_info.sibling_searchers.push(bytecode.mev_contract);
+                    libmdbx
+                        .write_searcher_contract_info(*sibling, sibling_info)
+                        .await?;
+                }
+            }
+
+            libmdbx
+                .write_searcher_contract_info(bytecode.mev_contract, info)
+                .await?;
+        }
+
+        tracing::info!(
+            indexed = new.len(),
+            labelled,
+            "indexed the bytecode of new searcher contracts"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use brontes_database::libmdbx::Libmdbx;
+use clap::Parser;
+use itertools::Itertools;
+
+#[derive(Debug, Parser)]
+pub struct Migrate;
+
+impl Migrate {
+    pub async fn execute(self, brontes_db_path: String) -> eyre::Result<()> {
+        let cleared = Libmdbx::migrate_db(brontes_db_path, None)?;
+        if cleared.is_empty() {
+            tracing::info!("database is up to date");
+        } else {
+            tracing::info!(
+                tables = %cleared.iter().join(", "),
+                "cleared the tables stored with an older layout, run `brontes db init` or sync \
+                 the range again to fill them"
+            );
+        }
+
+        Ok(())
+    }
+}
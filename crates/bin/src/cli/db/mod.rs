@@ -1,10 +1,15 @@
 use clap::{Parser, Subcommand};
+mod apply_config;
+mod archive;
+mod backfill_fee_tiers;
 mod r2_uploader;
 mod snapshot;
 use crate::runner::CliContext;
 mod cex_data;
 #[cfg(feature = "local-clickhouse")]
 mod clickhouse_download;
+#[cfg(feature = "local-clickhouse")]
+mod cluster_searchers;
 mod db_clear;
 mod db_insert;
 mod db_query;
@@ -13,10 +18,16 @@ mod discovery;
 #[cfg(feature = "local-clickhouse")]
 mod ensure_test_traces;
 mod export;
+#[cfg(feature = "local-clickhouse")]
+mod index_searcher_bytecode;
 mod init;
+mod migrate;
+mod proposer_report;
+mod protocol_summary;
 mod table_stats;
 #[cfg(feature = "local-clickhouse")]
 mod tip_tracer;
+mod token_flow;
 mod trace_range;
 pub mod utils;
 
@@ -37,6 +48,18 @@ pub enum DatabaseCommands {
     /// Clear a libmdbx table
     #[command(name = "clear")]
     DbClear(db_clear::Clear),
+    /// Upgrades a database written by an older version of brontes, clearing
+    /// the tables whose layout changed so they are initialized again
+    #[command(name = "migrate")]
+    Migrate(migrate::Migrate),
+    /// Moves older block ranges of the tx traces and dex quotes to compressed
+    /// archives, which are still read from when the blocks are queried
+    #[command(name = "archive")]
+    Archive(archive::Archive),
+    /// Queries the fee tiers of the pools that were discovered before fee
+    /// tiers were recorded
+    #[command(name = "backfill-fee-tiers")]
+    BackfillFeeTiers(backfill_fee_tiers::BackfillFeeTiers),
     /// Generates traces and store them in libmdbx (also clickhouse if
     /// --feature local-clickhouse)
     #[command(name = "generate-traces")]
@@ -44,10 +67,25 @@ pub enum DatabaseCommands {
     /// Fetches Cex data from the Sorella DB
     #[command(name = "cex-query")]
     CexData(cex_data::CexDB),
+    /// Aggregates the stored bundles by the protocols they touched
+    #[command(name = "protocol-summary")]
+    ProtocolSummary(protocol_summary::ProtocolSummary),
+    /// Aggregates the mev received by each proposer and the mev extracted in
+    /// the blocks it proposed
+    #[command(name = "proposer-report")]
+    ProposerReport(proposer_report::ProposerReport),
+    /// Exports the token transfers and swaps of a block as a graph for
+    /// visualization tools
+    #[command(name = "token-flow")]
+    TokenFlow(token_flow::TokenFlow),
     /// Fetch data from the api and insert it into
     /// libmdbx.
     #[command(name = "init")]
     Init(init::Init),
+    /// Apply the classifier, searcher, builder and address metadata toml
+    /// configs to libmdbx
+    #[command(name = "apply-config")]
+    ApplyConfig(apply_config::ApplyConfig),
     /// Libmbdx Table Stats
     #[command(name = "table-stats")]
     TableStats(table_stats::Stats),
@@ -80,6 +118,16 @@ pub enum DatabaseCommands {
     #[cfg(feature = "local-clickhouse")]
     #[command(name = "run-discovery")]
     Discovery(discovery::DiscoveryFill),
+    /// Links searcher contracts that share calldata fingerprints as sibling
+    /// searchers
+    #[cfg(feature = "local-clickhouse")]
+    #[command(name = "cluster-searchers")]
+    ClusterSearchers(cluster_searchers::ClusterSearchers),
+    /// Indexes the bytecode of searcher contracts and labels redeployed copies
+    /// of known bots
+    #[cfg(feature = "local-clickhouse")]
+    #[command(name = "index-searcher-bytecode")]
+    IndexSearcherBytecode(index_searcher_bytecode::IndexSearcherBytecode),
 }
 
 impl Database {
@@ -89,12 +137,19 @@ impl Database {
             DatabaseCommands::DbQuery(cmd) => cmd.execute(brontes_db_path).await,
             DatabaseCommands::TraceRange(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::Init(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::ApplyConfig(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::DbClear(cmd) => cmd.execute(brontes_db_path).await,
+            DatabaseCommands::Migrate(cmd) => cmd.execute(brontes_db_path).await,
+            DatabaseCommands::Archive(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::BackfillFeeTiers(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::UploadSnapshot(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::Export(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::TableStats(cmd) => cmd.execute(brontes_db_path),
             DatabaseCommands::DownloadSnapshot(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::CexData(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::ProtocolSummary(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::ProposerReport(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::TokenFlow(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
             DatabaseCommands::DownloadClickhouse(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
@@ -103,6 +158,10 @@ impl Database {
             DatabaseCommands::TestTracesInit(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
             DatabaseCommands::TraceAtTip(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            #[cfg(feature = "local-clickhouse")]
+            DatabaseCommands::ClusterSearchers(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            #[cfg(feature = "local-clickhouse")]
+            DatabaseCommands::IndexSearcherBytecode(cmd) => cmd.execute(brontes_db_path, ctx).await,
         }
     }
 }
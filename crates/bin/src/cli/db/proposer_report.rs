@@ -0,0 +1,106 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use brontes_database::{libmdbx::LibmdbxReader, parquet::ParquetExporter};
+use brontes_types::db::proposer_report::ProposerMevStats;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{
+    cli::{load_libmdbx, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ProposerReport {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End Block
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Only show the proposers that received the most mev
+    #[arg(long, default_value = "50")]
+    pub top:         usize,
+    /// Also write the full report to this csv file
+    #[arg(long)]
+    pub csv:         Option<PathBuf>,
+    /// Also export the full report to parquet
+    #[arg(long, default_value_t = false)]
+    pub parquet:     bool,
+    /// Optional path for the parquet export, will default to "data_exports/"
+    #[arg(long, short)]
+    pub path:        Option<String>,
+}
+
+impl ProposerReport {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if self.start_block > self.end_block {
+            return Err(eyre::eyre!("start block must be less than end block"))
+        }
+
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+        let blocks = libmdbx.try_fetch_mev_blocks(Some(self.start_block), self.end_block)?;
+        let mut report = ProposerMevStats::from_blocks(&blocks);
+
+        let metadata = libmdbx
+            .try_fetch_address_metadatas(report.iter().map(|s| s.fee_recipient).collect())?;
+        for stats in &mut report {
+            if let Some(metadata) = metadata.get(&stats.fee_recipient) {
+                stats.set_entity(metadata);
+            }
+        }
+
+        println!("{}", self.report_table(&report));
+
+        if let Some(csv) = &self.csv {
+            ProposerMevStats::write_csv(&report, BufWriter::new(File::create(csv)?))?;
+            tracing::info!(path = %csv.display(), "wrote proposer report");
+        }
+
+        if self.parquet {
+            let path = ParquetExporter::new(
+                Some(self.start_block),
+                Some(self.end_block),
+                self.path.clone(),
+                libmdbx,
+            )
+            .export_proposer_report(report)
+            .await?;
+            tracing::info!(path = %path.display(), "exported proposer report");
+        }
+
+        Ok(())
+    }
+
+    fn report_table(&self, report: &[ProposerMevStats]) -> ComfyTable {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Fee Recipient",
+            "Entity",
+            "# Blocks",
+            "# MEV-Boost Blocks",
+            "MEV Reward (ETH)",
+            "MEV Reward (USD)",
+            "# Bundles",
+            "Searcher Profit (USD)",
+            "Bribe (USD)",
+        ]);
+
+        for stats in report.iter().take(self.top) {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(stats.fee_recipient))
+                .add_cell(Cell::new(stats.entity.as_deref().unwrap_or("unknown")))
+                .add_cell(Cell::new(stats.blocks))
+                .add_cell(Cell::new(stats.mev_boost_blocks))
+                .add_cell(Cell::new(format!("{:.4}", stats.mev_reward_eth())))
+                .add_cell(Cell::new(format!("{:.2}", stats.mev_reward_usd)))
+                .add_cell(Cell::new(stats.bundles))
+                .add_cell(Cell::new(format!("{:.2}", stats.searcher_profit_usd)))
+                .add_cell(Cell::new(format!("{:.2}", stats.bribe_usd)));
+            table.add_row(row);
+        }
+
+        table
+    }
+}
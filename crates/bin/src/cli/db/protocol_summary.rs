@@ -0,0 +1,186 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    chain::chain_spec,
+    db::dex::{DexQuotes, PriceAt},
+    mev::{Bundle, Mev, MevType},
+    normalized_actions::NormalizedSwap,
+    pair::Pair,
+    FastHashMap, Protocol, ToFloatNearest,
+};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{
+    cli::{load_libmdbx, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ProtocolSummary {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End Block
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Number of blocks aggregated into each period, defaults to roughly a day
+    #[arg(long, short, default_value = "7200")]
+    pub period:      u64,
+    /// Mev types to include. If omitted it includes sandwiches, atomic arbs
+    /// and jit
+    #[arg(
+        long,
+        short,
+        default_value = "sandwich,atomic-arb,jit,jit-sandwich",
+        value_delimiter = ','
+    )]
+    pub mev_types:   Vec<MevType>,
+}
+
+/// Totals of the bundles that touched a protocol
+#[derive(Debug, Default, Clone, Copy)]
+struct ProtocolTotals {
+    bundles:               u64,
+    /// Profit of every bundle that touched the protocol
+    profit_usd:            f64,
+    /// Profit split evenly between the protocols of each bundle, so that the
+    /// attributed profit of all protocols adds up to the total profit
+    attributed_profit_usd: f64,
+    bribe_usd:             f64,
+    /// Value of the swaps of the bundles that went through the protocol,
+    /// victims included
+    volume_usd:            f64,
+}
+
+impl ProtocolTotals {
+    fn add(&mut self, bundle: &Bundle, protocol_count: usize) {
+        self.bundles += 1;
+        self.profit_usd += bundle.header.profit_usd;
+        self.attributed_profit_usd += bundle.header.profit_usd / protocol_count as f64;
+        self.bribe_usd += bundle.header.bribe_usd;
+    }
+}
+
+impl ProtocolSummary {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if self.start_block > self.end_block {
+            return Err(eyre::eyre!("start block must be less than end block"))
+        }
+        if self.period == 0 {
+            return Err(eyre::eyre!("period must be at least one block"))
+        }
+
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+        let blocks = libmdbx.try_fetch_mev_blocks(Some(self.start_block), self.end_block)?;
+        let quote_asset = chain_spec().quote_asset;
+
+        let mut summary: FastHashMap<(u64, Protocol, MevType), ProtocolTotals> =
+            FastHashMap::default();
+        let mut unpriced_swaps = 0usize;
+        for block in &blocks {
+            // swaps of blocks without stored dex quotes can't be valued
+            let quotes = libmdbx.get_dex_quotes(block.block.block_number).ok();
+
+            for bundle in &block.mev {
+                let mev_type = bundle.header.mev_type;
+                if !self.mev_types.contains(&mev_type) {
+                    continue
+                }
+
+                let period_start = self.period_start(bundle.header.block_number);
+                let protocols = bundle.data.protocols();
+                for protocol in &protocols {
+                    summary
+                        .entry((period_start, *protocol, mev_type))
+                        .or_default()
+                        .add(bundle, protocols.len());
+                }
+
+                // volume is attributed to the protocol of each swap rather than to
+                // every protocol of the bundle
+                for swap in bundle.data.swaps() {
+                    let Some(volume_usd) = quotes.as_ref().and_then(|quotes| {
+                        swap_volume_usd(swap, quotes, bundle.header.tx_index, quote_asset)
+                    }) else {
+                        unpriced_swaps += 1;
+                        continue
+                    };
+
+                    summary
+                        .entry((period_start, swap.protocol, mev_type))
+                        .or_default()
+                        .volume_usd += volume_usd;
+                }
+            }
+        }
+
+        println!("{}", self.summary_table(summary));
+        if unpriced_swaps > 0 {
+            println!("{unpriced_swaps} swaps without a dex price aren't counted in the volume");
+        }
+
+        Ok(())
+    }
+
+    fn period_start(&self, block_number: u64) -> u64 {
+        self.start_block + (block_number - self.start_block) / self.period * self.period
+    }
+
+    fn summary_table(
+        &self,
+        summary: FastHashMap<(u64, Protocol, MevType), ProtocolTotals>,
+    ) -> ComfyTable {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Start Block",
+            "End Block",
+            "Protocol",
+            "Mev Type",
+            "# Bundles",
+            "Profit (USD)",
+            "Attributed Profit (USD)",
+            "Bribe (USD)",
+            "Volume (USD)",
+        ]);
+
+        let mut summary = summary.into_iter().collect::<Vec<_>>();
+        summary.sort_by(
+            |((a_start, a_protocol, a_type), _), ((b_start, b_protocol, b_type), _)| {
+                (a_start, a_protocol, a_type.as_ref()).cmp(&(b_start, b_protocol, b_type.as_ref()))
+            },
+        );
+
+        for ((period_start, protocol, mev_type), totals) in summary {
+            let period_end = (period_start + self.period - 1).min(self.end_block);
+            let mut row = Row::new();
+            row.add_cell(Cell::new(period_start))
+                .add_cell(Cell::new(period_end))
+                .add_cell(Cell::new(protocol))
+                .add_cell(Cell::new(mev_type))
+                .add_cell(Cell::new(totals.bundles))
+                .add_cell(Cell::new(format!("{:.2}", totals.profit_usd)))
+                .add_cell(Cell::new(format!("{:.2}", totals.attributed_profit_usd)))
+                .add_cell(Cell::new(format!("{:.2}", totals.bribe_usd)))
+                .add_cell(Cell::new(format!("{:.2}", totals.volume_usd)));
+            table.add_row(row);
+        }
+
+        table
+    }
+}
+
+/// Value of the swap's input at the price of the bundle's transaction
+fn swap_volume_usd(
+    swap: &NormalizedSwap,
+    quotes: &DexQuotes,
+    tx_index: u64,
+    quote_asset: Address,
+) -> Option<f64> {
+    let price = quotes
+        .price_at_or_before(Pair(swap.token_in.address, quote_asset), tx_index as usize)?
+        .get_price(PriceAt::Before);
+
+    Some((&swap.amount_in * price).to_float())
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use brontes_classifier::Classifier;
+use brontes_core::decoding::Parser as DParser;
+use brontes_metrics::ParserMetricsListener;
+use brontes_types::{init_thread_pools, TokenFlowGraph, UnboundedYapperReceiver};
+use clap::{Parser, ValueEnum};
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::{
+    cli::{determine_max_tasks, get_env_vars, get_tracing_provider, load_database, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+pub struct TokenFlow {
+    /// Block to export the token flows of
+    #[arg(long, short)]
+    pub block:  u64,
+    /// Output format of the graph
+    #[arg(long, short, value_enum, default_value = "dot")]
+    pub format: GraphFormat,
+    /// File to write the graph to. Written to stdout if omitted
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl TokenFlow {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+
+        let max_tasks = determine_max_tasks(None);
+        init_thread_pools(max_tasks as usize);
+        let (metrics_tx, metrics_rx) = unbounded_channel();
+
+        let metrics_listener = ParserMetricsListener::new(UnboundedYapperReceiver::new(
+            metrics_rx,
+            10_000,
+            "metrics".to_string(),
+        ));
+
+        ctx.task_executor
+            .spawn_critical("metrics", metrics_listener);
+
+        let libmdbx =
+            static_object(load_database(&ctx.task_executor, brontes_db_path, None, None).await?);
+
+        let tracer =
+            get_tracing_provider(Path::new(&db_path), max_tasks, ctx.task_executor.clone());
+
+        let parser = DParser::new(metrics_tx, libmdbx, tracer.clone()).await;
+        let (traces, header) = parser
+            .execute(self.block, 0, None)
+            .await
+            .ok_or_else(|| eyre::eyre!("failed to trace block {}", self.block))?;
+
+        // the receiver has to outlive the classifier, which expects the pricing
+        // updates to be delivered
+        let (tx, _rx) = unbounded_channel();
+        let classifier = Classifier::new(libmdbx, tx, parser.get_tracer());
+        let tree = classifier.build_block_tree(traces, header, false).await?;
+
+        let graph = TokenFlowGraph::from_tree(&tree);
+        let rendered = match self.format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+        };
+
+        match self.output {
+            Some(path) => std::fs::write(path, rendered)?,
+            None => println!("{rendered}"),
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
+use brontes_types::chain::ChainSpec;
 use clap::{Parser, Subcommand};
 
 mod db;
 mod misc;
+mod replay;
 mod run;
+mod serve;
+mod simulate;
 mod utils;
+mod verify;
 mod version_data;
 pub use utils::*;
 pub use version_data::*;
@@ -23,10 +28,16 @@ pub struct Args {
     /// The verbosity level of the logs
     #[clap(flatten)]
     pub verbosity:       Verbosity,
+    /// Port the prometheus metrics endpoint is served on
     #[clap(long, default_value = "6923", global = true)]
     pub metrics_port:    u16,
+    /// Don't serve the prometheus metrics endpoint
     #[clap(long, default_value = "false", global = true)]
     pub skip_prometheus: bool,
+    /// The chain to run against, either its name or chain id. Chains other
+    /// than mainnet are traced over rpc
+    #[arg(long, default_value = "mainnet", global = true)]
+    pub chain:           &'static ChainSpec,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -38,4 +49,19 @@ pub enum Commands {
     /// Brontes database commands
     #[command(name = "db")]
     Database(db::Database),
+    /// Recompute the bundles of a stored block range and diff them against
+    /// the database
+    #[command(name = "verify")]
+    Verify(verify::VerifyArgs),
+    /// Reclassify the stored traces of a block range and rerun the inspectors
+    /// on them, replacing the stored results once the whole range is done
+    #[command(name = "replay")]
+    Replay(replay::ReplayArgs),
+    /// Serve the stored mev, searcher and dex price data over json-rpc
+    #[command(name = "serve")]
+    Serve(serve::ServeArgs),
+    /// Simulate raw signed transactions on top of a block and run the
+    /// inspectors on the hypothetical block they form
+    #[command(name = "simulate")]
+    Simulate(simulate::SimulateArgs),
 }
@@ -0,0 +1,234 @@
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use brontes_core::decoding::Parser as DParser;
+use brontes_database::{
+    clickhouse::cex_config::CexDownloadConfig,
+    libmdbx::{DBWriter, LibmdbxReader},
+};
+use brontes_inspect::{composer::init_inspector_cache, Inspectors};
+use brontes_metrics::ParserMetricsListener;
+use brontes_types::{
+    address_filter::init_address_filter,
+    chain::chain_spec,
+    db::cex::{fees::init_cex_fee_schedule, CexExchange},
+    denylist::init_denylist,
+    init_thread_pools, FastHashSet, UnboundedYapperReceiver,
+};
+use clap::Parser;
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::{
+    build_id, determine_max_tasks, get_env_vars, load_clickhouse, load_database,
+    load_inspector_config, run::TimeWindowArgs, static_object,
+};
+use crate::{
+    cli::{get_tracing_provider, init_inspectors},
+    runner::CliContext,
+    take_recomputed_blocks, BrontesRunConfig, RangeType, VerifyProcessor,
+};
+
+#[derive(Debug, Parser)]
+pub struct ReplayArgs {
+    /// Inclusive block range to replay, e.g. `19000000..19000100`
+    #[arg(long, value_parser = parse_block_range)]
+    pub blocks:           RangeInclusive<u64>,
+    /// Inspectors to run. The stored results of the range are replaced by the
+    /// results of these inspectors only
+    #[arg(long, short, value_delimiter = ',')]
+    pub inspectors:       Option<Vec<Inspectors>>,
+    /// Optional Max Tasks, if omitted it will default to 80% of the number of
+    /// physical cores on your machine
+    #[arg(long, short)]
+    pub max_tasks:        Option<u64>,
+    /// Optional minimum batch size
+    #[arg(long, default_value = "500")]
+    pub min_batch_size:   u64,
+    /// Optional quote asset, either an address or a symbol such as USDC, DAI
+    /// or WETH. If omitted it will default to the quote asset of the chain,
+    /// USDT on mainnet
+    #[arg(long, short)]
+    pub quote_asset:      Option<String>,
+    /// Inspector thresholds, denylist and cex fees
+    #[arg(long)]
+    pub inspector_config: Option<PathBuf>,
+    /// Optional directory to cache the inspector results in. Blocks whose
+    /// classified tree, metadata and inspector settings are unchanged load
+    /// their results from the cache instead of rerunning the inspectors
+    #[arg(long)]
+    pub inspector_cache:  Option<PathBuf>,
+    /// Time window arguments for cex data downloads
+    #[clap(flatten)]
+    pub time_window_args: TimeWindowArgs,
+    /// CEX exchanges to consider for cex-dex analysis
+    #[arg(
+        long,
+        short,
+        default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
+        value_delimiter = ','
+    )]
+    pub cex_exchanges:    Vec<CexExchange>,
+}
+
+impl ReplayArgs {
+    /// Reclassifies the stored traces of the range and reruns the inspectors
+    /// on them. The results are kept in memory until every block was
+    /// replayed and only then replace the stored ones, so an interrupted
+    /// replay leaves the database as it was.
+    ///
+    /// The trees aren't stored in a form the inspectors can run on, the
+    /// clickhouse tree only keeps the first action of each trace, so the
+    /// traces are always classified again.
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let snapshot_mode = !cfg!(feature = "local-clickhouse");
+        let reth_db_path = get_env_vars()?;
+        let quote_asset = match &self.quote_asset {
+            Some(quote_asset) => chain_spec().parse_quote_asset(quote_asset)?,
+            None => chain_spec().quote_asset,
+        };
+        let task_executor = ctx.task_executor;
+
+        let max_tasks = determine_max_tasks(self.max_tasks);
+        init_thread_pools(max_tasks as usize);
+
+        let (metrics_tx, metrics_rx) = unbounded_channel();
+        let metrics_listener = ParserMetricsListener::new(UnboundedYapperReceiver::new(
+            metrics_rx,
+            10_000,
+            "metrics".to_string(),
+        ));
+        task_executor.spawn_critical("metrics", metrics_listener);
+
+        let libmdbx =
+            static_object(load_database(&task_executor, brontes_db_path, None, None).await?);
+
+        // replaying never traces, so a block without stored traces would be
+        // missing from the results and have its stored ones cleared
+        let mut traced_blocks = FastHashSet::default();
+        for block in self.blocks.clone() {
+            let traces = libmdbx
+                .load_trace(block)
+                .map_err(|e| eyre::eyre!("no stored traces for block {block}: {e}"))?;
+            if !traces.is_empty() {
+                traced_blocks.insert(block);
+            }
+        }
+
+        let load_window = self.time_window_args.load_time_window();
+        let cex_download_config = CexDownloadConfig::new(
+            (load_window as u64, load_window as u64),
+            self.cex_exchanges.clone(),
+        );
+        let clickhouse = static_object(load_clickhouse(cex_download_config, None).await?);
+
+        let inspector_config = load_inspector_config(self.inspector_config.as_deref())?;
+        init_address_filter(inspector_config.address_filter.clone())?;
+        init_denylist(inspector_config.denylist.clone())?;
+        init_cex_fee_schedule(inspector_config.cex_fees.clone())?;
+        if let Some(dir) = &self.inspector_cache {
+            init_inspector_cache(dir, &build_id())?;
+        }
+        let inspectors = init_inspectors(
+            quote_asset,
+            libmdbx,
+            self.inspectors.clone(),
+            self.cex_exchanges.clone(),
+            self.time_window_args.trade_config(),
+            &inspector_config,
+            false,
+        )?;
+
+        let tracer =
+            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
+        let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
+
+        let range_type = RangeType::SingleRange {
+            start_block:   Some(*self.blocks.start()),
+            end_block:     Some(*self.blocks.end()),
+            back_from_tip: 0,
+        };
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let replay_finished = finished.clone();
+        let executor = task_executor.clone();
+        executor
+            .clone()
+            .spawn_critical_with_graceful_shutdown_signal("replay init", |shutdown| async move {
+                if let Ok(brontes) = BrontesRunConfig::<_, _, _, VerifyProcessor>::new(
+                    range_type,
+                    max_tasks,
+                    self.min_batch_size,
+                    quote_asset,
+                    false,
+                    false,
+                    inspectors,
+                    clickhouse,
+                    parser,
+                    libmdbx,
+                    libmdbx,
+                    true,
+                    false,
+                    snapshot_mode,
+                    load_window,
+                )
+                .with_venue_latencies(self.time_window_args.venue_latencies)
+                .build(task_executor, shutdown)
+                .await
+                .map_err(|e| {
+                    tracing::error!(%e);
+                    e
+                }) {
+                    brontes.await;
+                    replay_finished.store(true, Ordering::SeqCst);
+                }
+            })
+            .await?;
+
+        let replayed = take_recomputed_blocks();
+        let replayed_blocks = replayed
+            .iter()
+            .map(|block| block.block.block_number)
+            .collect::<FastHashSet<_>>();
+        let missing = traced_blocks.difference(&replayed_blocks).count();
+        if !finished.load(Ordering::SeqCst) || missing != 0 {
+            eyre::bail!(
+                "replay didn't finish, {missing} blocks weren't replayed. The stored results were \
+                 left untouched"
+            );
+        }
+
+        tracing::info!(
+            start_block = self.blocks.start(),
+            end_block = self.blocks.end(),
+            replayed_blocks = replayed.len(),
+            bundles = replayed.iter().map(|block| block.mev.len()).sum::<usize>(),
+            "replacing the stored results"
+        );
+        libmdbx.replace_mev_blocks(self.blocks, replayed).await
+    }
+}
+
+/// Parses an inclusive `start..end` block range
+fn parse_block_range(range: &str) -> Result<RangeInclusive<u64>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range: {}", range))?;
+    let start: u64 = start
+        .parse()
+        .map_err(|_| format!("invalid start block: {}", start))?;
+    let end: u64 = end
+        .trim_start_matches('=')
+        .parse()
+        .map_err(|_| format!("invalid end block: {}", end))?;
+    if start > end {
+        return Err(format!("start block {} must be less than or equal to end block {}", start, end))
+    }
+
+    Ok(start..=end)
+}
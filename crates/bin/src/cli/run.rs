@@ -1,22 +1,43 @@
-use std::{path::Path, time::Duration};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use brontes_core::decoding::Parser as DParser;
+use alloy_primitives::Address;
+use brontes_core::{
+    decoding::Parser as DParser,
+    relay_data::{init_relay_data, RelayData, FLASHBOTS_BLOCKS_API},
+};
 use brontes_database::clickhouse::cex_config::CexDownloadConfig;
-use brontes_inspect::Inspectors;
+use brontes_inspect::{
+    composer::init_inspector_cache, profit_verification::enable_profit_verification, Inspectors,
+};
 use brontes_metrics::ParserMetricsListener;
+use brontes_pricing::graph_snapshot::enable_price_graph_snapshots;
 use brontes_types::{
-    constants::USDT_ADDRESS_STRING,
-    db::cex::{trades::CexDexTradeConfig, CexExchange},
+    address_filter::init_address_filter,
+    chain::chain_spec,
+    db::cex::{
+        fees::init_cex_fee_schedule,
+        trades::{CexDexTradeConfig, CexWindowMode, VenueLatencies},
+        CexExchange,
+    },
     db_write_trigger::{backup_server_heartbeat, start_hr_monitor, HeartRateMonitor},
-    init_thread_pools, UnboundedYapperReceiver,
+    denylist::init_denylist,
+    enable_deterministic_mode, init_thread_pools, UnboundedYapperReceiver,
 };
 use clap::Parser;
 use tokio::sync::mpsc::unbounded_channel;
 
-use super::{determine_max_tasks, get_env_vars, load_clickhouse, load_database, static_object};
+use super::{
+    build_id, determine_max_tasks, get_env_vars, load_clickhouse, load_database,
+    load_inspector_config, static_object,
+};
 use crate::{
     banner::rain,
-    cli::{get_tracing_provider, init_inspectors, load_tip_database},
+    cli::{get_tracing_provider, init_inspector_registry, load_tip_database},
+    rpc::RpcServer,
     runner::CliContext,
     BrontesRunConfig, MevProcessor, RangeType,
 };
@@ -44,12 +65,31 @@ pub struct RunArgs {
     /// Optional minimum batch size
     #[arg(long, default_value = "500")]
     pub min_batch_size:       u64,
-    /// Optional quote asset, if omitted it will default to USDT
-    #[arg(long, short, default_value = USDT_ADDRESS_STRING)]
-    pub quote_asset:          String,
+    /// Optional quote asset, either an address or a symbol such as USDC, DAI
+    /// or WETH. If omitted it will default to the quote asset of the chain,
+    /// USDT on mainnet
+    #[arg(long, short)]
+    pub quote_asset:          Option<String>,
     /// Inspectors to run. If omitted it defaults to running all inspectors
     #[arg(long, short, value_delimiter = ',')]
     pub inspectors:           Option<Vec<Inspectors>>,
+    /// Optional toml file with per inspector thresholds (min profit, max bribe
+    /// ratio, min victims, pool blacklist) applied to the detected bundles
+    #[arg(long)]
+    pub inspector_config:     Option<PathBuf>,
+    /// Only inspect the transactions sent by or calling these EOAs and
+    /// contracts, on top of the allowlist of the inspector config
+    #[arg(long, value_delimiter = ',')]
+    pub allow_addresses:      Vec<Address>,
+    /// Never attribute the transactions sent by or calling these EOAs and
+    /// contracts to a searcher, on top of the denylist of the inspector config
+    #[arg(long, value_delimiter = ',')]
+    pub deny_addresses:       Vec<Address>,
+    /// Optional directory to cache the inspector results in. Blocks whose
+    /// classified tree, metadata and inspector settings are unchanged load
+    /// their results from the cache instead of rerunning the inspectors
+    #[arg(long)]
+    pub inspector_cache:      Option<PathBuf>,
     /// Time window arguments for cex data downloads
     #[clap(flatten)]
     pub time_window_args:     TimeWindowArgs,
@@ -70,9 +110,14 @@ pub struct RunArgs {
     /// database.
     #[arg(long, default_value = "false")]
     pub force_no_dex_pricing: bool,
-    /// Number of blocks to lag behind the chain tip when processing.
+    /// Number of blocks to lag behind the chain tip when processing. A block
+    /// is only processed once this many blocks are built on top of it
     #[arg(long, default_value = "10")]
     pub behind_tip:           u64,
+    /// Seconds between polls of the node for a new chain tip, when following
+    /// the tip
+    #[arg(long, default_value = "3")]
+    pub tip_poll_interval:    u64,
     /// Legacy, run in CLI only mode (no TUI) - will output progress bars to
     /// stdout
     #[arg(long, default_value = "true")]
@@ -94,6 +139,43 @@ pub struct RunArgs {
     /// stored in the Clickhouse database.
     #[arg(long, short)]
     pub run_id:               Option<u64>,
+    /// Deterministic replay mode. Fixes hash seeds, pricing update order and
+    /// block processing order so that two runs over the same inputs produce
+    /// identical outputs. Much slower, meant for debugging
+    #[arg(long, default_value_t = false)]
+    pub deterministic:        bool,
+    /// Classifies calls to contracts without a protocol classifier from the
+    /// function selector table as generic swap, transfer or approval calls
+    #[arg(long, default_value_t = false)]
+    pub selector_fallback:    bool,
+    /// Skips the blocks of the range that a previous run checkpointed as
+    /// processed, picking an interrupted backfill up where it stopped
+    #[arg(long, default_value_t = false)]
+    pub resume:               bool,
+    /// Re-executes the transactions of every bundle and stores the profit
+    /// computed from the actual balance changes of the searcher next to the
+    /// estimated one, flagging the bundles where they diverge
+    #[arg(long, default_value_t = false)]
+    pub verify_profits:       bool,
+    /// Writes a snapshot of the pricing graph of every block into this
+    /// directory, to replay and inspect the dex quotes of the block offline
+    #[arg(long)]
+    pub graph_snapshot_dir:   Option<PathBuf>,
+    /// Labels the transactions with the submission channel reported by the
+    /// blocks api of the Flashbots relay
+    #[arg(long, default_value_t = false)]
+    pub flashbots_blocks_api: bool,
+    /// Labels the transactions with the submission channels of a csv of
+    /// `block_number,tx_hash,channel` rows, the channel being one of
+    /// `public`, `private`, `private_rpc` or `bundle`
+    #[arg(long)]
+    pub relay_dataset:        Option<PathBuf>,
+    /// Serves the json-rpc methods over the db of the run on this address. When
+    /// following the tip, admin methods that enable and disable inspectors are
+    /// served as well. The server isn't authenticated, so it only listens on
+    /// loopback addresses
+    #[arg(long)]
+    pub admin_rpc:            Option<SocketAddr>,
 
     /// shows a cool display at startup
     #[arg(long, short, default_value_t = false)]
@@ -103,6 +185,23 @@ pub struct RunArgs {
 impl RunArgs {
     pub async fn execute(mut self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
         self.check_proper_range()?;
+        if let Some(addr) = self.admin_rpc.filter(|addr| !addr.ip().is_loopback()) {
+            eyre::bail!("the admin rpc isn't authenticated, it can't listen on {addr}");
+        }
+        let follows_tip = self.follows_tip();
+
+        // has to happen before any state is built
+        if self.deterministic {
+            enable_deterministic_mode();
+            tracing::info!(target: "brontes", "running in deterministic replay mode");
+        }
+        if self.verify_profits {
+            enable_profit_verification();
+        }
+        if let Some(dir) = self.graph_snapshot_dir.take() {
+            enable_price_graph_snapshots(dir)?;
+        }
+        self.init_relay_data()?;
 
         if self.waterfall {
             rain();
@@ -114,7 +213,10 @@ impl RunArgs {
         // Fetch required environment variables.
         let reth_db_path = get_env_vars()?;
         tracing::info!(target: "brontes", "got env vars");
-        let quote_asset = self.quote_asset.parse()?;
+        let quote_asset = match &self.quote_asset {
+            Some(quote_asset) => chain_spec().parse_quote_asset(quote_asset)?,
+            None => chain_spec().quote_asset,
+        };
         tracing::info!(target: "brontes", "parsed quote asset");
         let task_executor = ctx.task_executor;
 
@@ -165,15 +267,39 @@ impl RunArgs {
         }
 
         let trade_config = self.time_window_args.trade_config();
+        let mut inspector_config = load_inspector_config(self.inspector_config.as_deref())?;
+        inspector_config
+            .address_filter
+            .extend(self.allow_addresses.clone(), self.deny_addresses.clone());
+        init_address_filter(inspector_config.address_filter.clone())?;
+        init_denylist(inspector_config.denylist.clone())?;
+        init_cex_fee_schedule(inspector_config.cex_fees.clone())?;
+        if let Some(dir) = &self.inspector_cache {
+            init_inspector_cache(dir, &build_id())?;
+        }
 
-        let inspectors = init_inspectors(
+        let registry = init_inspector_registry(
             quote_asset,
             libmdbx,
             self.inspectors,
             self.cex_exchanges,
             trade_config,
+            &inspector_config,
             self.with_metrics,
-        );
+        )?;
+        let inspectors = registry.init();
+        if let Some(addr) = self.admin_rpc {
+            let mut server = RpcServer::new(libmdbx);
+            // toggling inspectors during a backfill would leave gaps in its results
+            if follows_tip {
+                server = server.with_inspector_registry(registry);
+            }
+            task_executor.spawn(async move {
+                if let Err(e) = server.serve(addr).await {
+                    tracing::error!(%e, "admin rpc server stopped");
+                }
+            });
+        }
 
         let tracer =
             get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
@@ -200,6 +326,11 @@ impl RunArgs {
                     snapshot_mode,
                     load_window,
                 )
+                .with_selector_fallback(self.selector_fallback)
+                .with_resume(self.resume)
+                .with_tip_poll_interval(Duration::from_secs(self.tip_poll_interval))
+                .with_inspector_reload(registry, self.inspector_config)
+                .with_venue_latencies(self.time_window_args.venue_latencies)
                 .build(task_executor, shutdown)
                 .await
                 .map_err(|e| {
@@ -250,11 +381,12 @@ impl RunArgs {
 
     /// the time window in seconds for downloading
     fn load_time_window(&self) -> usize {
-        self.time_window_args
-            .max_vwap_pre
-            .max(self.time_window_args.max_vwap_post)
-            .max(self.time_window_args.max_optimistic_pre)
-            .max(self.time_window_args.max_optimistic_post) as usize
+        self.time_window_args.load_time_window()
+    }
+
+    /// Whether the run keeps following the tip once the range is done
+    fn follows_tip(&self) -> bool {
+        self.ranges.is_none() && self.end_block.is_none()
     }
 
     fn check_proper_range(&self) -> eyre::Result<()> {
@@ -265,6 +397,21 @@ impl RunArgs {
         }
         Ok(())
     }
+
+    fn init_relay_data(&self) -> eyre::Result<()> {
+        let mut relay_data = RelayData::default();
+        if self.flashbots_blocks_api {
+            relay_data = relay_data.with_flashbots_blocks_api(FLASHBOTS_BLOCKS_API)?;
+        }
+        if let Some(path) = &self.relay_dataset {
+            relay_data = relay_data.with_dataset(path)?;
+        }
+
+        if !relay_data.is_empty() {
+            init_relay_data(relay_data)?;
+        }
+        Ok(())
+    }
 }
 
 fn parse_ranges(ranges: &[String]) -> Result<Vec<(u64, u64)>, String> {
@@ -382,10 +529,34 @@ pub struct TimeWindowArgs {
     /// Cex Dex Quotes price time offset from block timestamp
     #[arg(long = "quote-offset", default_value = "0.0")]
     pub quote_offset: f64,
+
+    /// Latency in seconds of each venue used by the cex-dex markout, either a
+    /// fixed offset or a uniform range. e.g `okex=0.05,kraken=0.1-0.3`
+    #[arg(long = "venue-latencies", default_value = "")]
+    pub venue_latencies: VenueLatencies,
+
+    /// How the initial cex-dex markout windows are picked, either `fixed` for
+    /// the windows above or `adaptive` to pick them per block from the relay
+    /// and p2p timestamps and the trade density around the block
+    #[arg(long = "cex-window", default_value = "fixed")]
+    pub cex_window: CexWindowMode,
+
+    /// Trades the venues need around the block in the adaptive window mode,
+    /// below which the markets are thin and the windows are widened
+    #[arg(long = "min-window-trades", default_value = "20")]
+    pub min_window_trades: u64,
 }
 
 impl TimeWindowArgs {
-    fn trade_config(&self) -> CexDexTradeConfig {
+    /// the time window in seconds for downloading
+    pub fn load_time_window(&self) -> usize {
+        self.max_vwap_pre
+            .max(self.max_vwap_post)
+            .max(self.max_optimistic_pre)
+            .max(self.max_optimistic_post) as usize
+    }
+
+    pub fn trade_config(&self) -> CexDexTradeConfig {
         CexDexTradeConfig {
             initial_vwap_pre_block_us:  (self.initial_vwap_pre * SECONDS_TO_US_FLOAT) as u64,
             initial_vwap_post_block_us: (self.initial_vwap_post * SECONDS_TO_US_FLOAT) as u64,
@@ -413,6 +584,8 @@ impl TimeWindowArgs {
             pre_decay_weight_op:               self.pre_decay_weight_optimistic,
             post_decay_weight_op:              self.post_decay_weight_optimistic,
             quote_offset_from_block_us:        (self.quote_offset * SECONDS_TO_US_FLOAT) as u64,
+            cex_window_mode:                   self.cex_window,
+            min_window_trades:                 self.min_window_trades,
         }
     }
 }
@@ -0,0 +1,29 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clap::Parser;
+
+use crate::{
+    cli::{load_libmdbx, static_object},
+    rpc::RpcServer,
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ServeArgs {
+    /// Address the json-rpc server listens on
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    pub addr: IpAddr,
+    /// Port the json-rpc server listens on
+    #[arg(long, default_value = "8547")]
+    pub port: u16,
+}
+
+impl ServeArgs {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
+
+        RpcServer::new(libmdbx)
+            .serve(SocketAddr::new(self.addr, self.port))
+            .await
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::Bytes;
+use brontes_classifier::Classifier;
+use brontes_core::decoding::Parser as DParser;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_inspect::{
+    composer::{run_block_inspection, ComposerResults},
+    liquidation_context::attach_liquidation_contexts,
+    Inspectors,
+};
+use brontes_metrics::ParserMetricsListener;
+use brontes_types::{
+    address_filter::init_address_filter,
+    chain::chain_spec,
+    db::{
+        cex::{fees::init_cex_fee_schedule, CexExchange},
+        dex::DexQuotes,
+        mev_block::MevBlockWithClassified,
+    },
+    denylist::init_denylist,
+    execute_on, init_thread_pools, BlockData, MultiBlockData, UnboundedYapperReceiver,
+};
+use clap::Parser;
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::{
+    determine_max_tasks, get_env_vars, get_tracing_provider, init_inspectors,
+    load_inspector_config, load_libmdbx, run::TimeWindowArgs, static_object,
+};
+use crate::runner::CliContext;
+
+#[derive(Debug, Parser)]
+pub struct SimulateArgs {
+    /// Block the transactions are executed on top of
+    #[arg(long, short)]
+    pub parent_block:     u64,
+    /// Raw signed transactions, hex encoded, in the order they are executed
+    #[arg(long, value_delimiter = ',')]
+    pub txs:              Vec<Bytes>,
+    /// File with a raw signed transaction per line, executed after the
+    /// transactions passed with `--txs`
+    #[arg(long)]
+    pub txs_file:         Option<PathBuf>,
+    /// Optional quote asset, either an address or a symbol such as USDC, DAI
+    /// or WETH. If omitted it will default to the quote asset of the chain,
+    /// USDT on mainnet
+    #[arg(long, short)]
+    pub quote_asset:      Option<String>,
+    /// Inspectors to run. If omitted it defaults to running all inspectors
+    #[arg(long, short, value_delimiter = ',')]
+    pub inspectors:       Option<Vec<Inspectors>>,
+    /// Inspector thresholds, denylist and cex fees
+    #[arg(long)]
+    pub inspector_config: Option<PathBuf>,
+    /// Time window arguments for cex data
+    #[clap(flatten)]
+    pub time_window_args: TimeWindowArgs,
+    /// CEX exchanges to consider for cex-dex analysis
+    #[arg(
+        long,
+        short,
+        default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
+        value_delimiter = ','
+    )]
+    pub cex_exchanges:    Vec<CexExchange>,
+    /// File to write the json results to. Written to stdout if omitted
+    #[arg(long, short)]
+    pub output:           Option<PathBuf>,
+}
+
+impl SimulateArgs {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let txs = self.load_txs()?;
+        if txs.is_empty() {
+            return Err(eyre::eyre!("no transactions to simulate"))
+        }
+
+        let reth_db_path = get_env_vars()?;
+        let quote_asset = match &self.quote_asset {
+            Some(quote_asset) => chain_spec().parse_quote_asset(quote_asset)?,
+            None => chain_spec().quote_asset,
+        };
+        let task_executor = ctx.task_executor;
+
+        let max_tasks = determine_max_tasks(None);
+        init_thread_pools(max_tasks as usize);
+
+        let (metrics_tx, metrics_rx) = unbounded_channel();
+        let metrics_listener = ParserMetricsListener::new(UnboundedYapperReceiver::new(
+            metrics_rx,
+            10_000,
+            "metrics".to_string(),
+        ));
+        task_executor.spawn_critical("metrics", metrics_listener);
+
+        // the results of a hypothetical block are never written, so the plain
+        // libmdbx handle is used
+        let libmdbx = static_object(load_libmdbx(&task_executor, brontes_db_path)?);
+
+        let inspector_config = load_inspector_config(self.inspector_config.as_deref())?;
+        init_address_filter(inspector_config.address_filter.clone())?;
+        init_denylist(inspector_config.denylist.clone())?;
+        init_cex_fee_schedule(inspector_config.cex_fees.clone())?;
+        let inspectors = init_inspectors(
+            quote_asset,
+            libmdbx,
+            self.inspectors.clone(),
+            self.cex_exchanges.clone(),
+            self.time_window_args.trade_config(),
+            &inspector_config,
+            false,
+        )?;
+
+        let tracer =
+            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
+        let parser = DParser::new(metrics_tx, libmdbx, tracer).await;
+        let (traces, header) = parser.simulate_block(self.parent_block, txs).await?;
+
+        // the receiver has to outlive the classifier, which expects the pricing
+        // updates to be delivered
+        let (tx, _rx) = unbounded_channel();
+        let classifier = Classifier::new(libmdbx, tx, parser.get_tracer());
+        let tree = classifier.build_block_tree(traces, header, false).await?;
+
+        // the hypothetical block has no metadata of its own, the cex quotes of
+        // the parent are the closest ones. There are no dex quotes as the dex
+        // pricer isn't run
+        let mut metadata = libmdbx.get_metadata_no_dex_price(self.parent_block, quote_asset)?;
+        let block = &mut metadata.block_metadata;
+        block.block_num = tree.header.number;
+        block.block_hash = Default::default();
+        block.block_timestamp = tree.header.timestamp;
+        block.relay_timestamp = None;
+        block.p2p_timestamp = None;
+        block.proposer_fee_recipient = None;
+        block.proposer_mev_reward = None;
+        block.private_flow.clear();
+        let metadata = metadata.into_full_metadata(DexQuotes(vec![]));
+
+        let data = MultiBlockData {
+            per_block_data: vec![BlockData { metadata: metadata.into(), tree: tree.into() }],
+            blocks:         1,
+        };
+        let ComposerResults { block_details, mut mev_details, .. } =
+            execute_on!(async_inspect, { run_block_inspection(inspectors, data, libmdbx) }).await;
+        attach_liquidation_contexts(&mut mev_details, &parser.get_tracer(), libmdbx).await;
+
+        tracing::info!(
+            parent_block = self.parent_block,
+            bundles = mev_details.len(),
+            "finished simulating block"
+        );
+
+        let results = serde_json::to_string_pretty(&MevBlockWithClassified {
+            block: block_details,
+            mev:   mev_details,
+        })?;
+        match self.output {
+            Some(path) => std::fs::write(path, results)?,
+            None => println!("{results}"),
+        }
+
+        Ok(())
+    }
+
+    fn load_txs(&self) -> eyre::Result<Vec<Bytes>> {
+        let mut txs = self.txs.clone();
+        if let Some(path) = &self.txs_file {
+            for line in std::fs::read_to_string(path)?.lines() {
+                let line = line.trim();
+                if !line.is_empty() {
+                    txs.push(line.parse()?);
+                }
+            }
+        }
+
+        Ok(txs)
+    }
+}
@@ -2,24 +2,27 @@ use std::{env, path::Path};
 
 use alloy_primitives::Address;
 #[cfg(not(feature = "local-reth"))]
-use brontes_core::local_provider::LocalProvider;
+use brontes_core::remote_provider::{RemoteEndpoint, RemoteProvider};
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::clickhouse_config;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::Clickhouse;
-#[cfg(not(feature = "local-clickhouse"))]
-use brontes_database::clickhouse::ClickhouseHttpClient;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::ClickhouseMiddleware;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::ReadOnlyMiddleware;
 #[cfg(feature = "local-clickhouse")]
-use brontes_database::clickhouse::{dbms::BrontesClickhouseData, ClickhouseBuffered};
+use brontes_database::clickhouse::{ClickhouseBuffered, ClickhouseWriterMessage};
+#[cfg(not(feature = "local-clickhouse"))]
+use brontes_database::clickhouse::{ClickhouseHttpClient, ClickhouseHttpConfig};
 use brontes_database::{clickhouse::cex_config::CexDownloadConfig, libmdbx::LibmdbxReadWriter};
-use brontes_inspect::{Inspector, Inspectors};
+use brontes_inspect::{
+    config::{init_inspector_config_hash, FilteredInspector, InspectorConfig},
+    registry::InspectorRegistry,
+    shared_utils::init_pricing_quote_asset,
+    Inspector, Inspectors,
+};
 use brontes_metrics::inspectors::OutlierMetrics;
-#[cfg(feature = "local-clickhouse")]
-use brontes_types::UnboundedYapperReceiver;
 use brontes_types::{
     db::{
         cex::{trades::CexDexTradeConfig, CexExchange},
@@ -29,12 +32,25 @@ use brontes_types::{
     mev::Bundle,
     BrontesTaskExecutor,
 };
+use eyre::WrapErr;
 use itertools::Itertools;
 #[cfg(feature = "local-reth")]
 use reth_tracing_ext::TracingClient;
 use strum::IntoEnumIterator;
 use tracing::info;
 
+/// Batches of rows that can be queued for the clickhouse writer before the
+/// processors have to wait for it
+#[cfg(feature = "local-clickhouse")]
+const CLICKHOUSE_CHANNEL_CAPACITY: usize = 1500;
+/// Tables that don't fill a batch are still written after this long
+#[cfg(feature = "local-clickhouse")]
+const CLICKHOUSE_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+/// Rows the clickhouse writer buffers or is inserting before it stops
+/// receiving new ones
+#[cfg(feature = "local-clickhouse")]
+const CLICKHOUSE_MAX_PENDING_ROWS: usize = 100_000;
+
 #[cfg(not(feature = "local-clickhouse"))]
 pub async fn load_database(
     executor: &BrontesTaskExecutor,
@@ -60,7 +76,7 @@ pub async fn load_database(
 ) -> eyre::Result<ClickhouseMiddleware<LibmdbxReadWriter>> {
     let inner = LibmdbxReadWriter::init_db(db_endpoint, None, executor, true)?;
 
-    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let (tx, rx) = tokio::sync::mpsc::channel(CLICKHOUSE_CHANNEL_CAPACITY);
     spawn_db_writer_thread(executor, rx, hr);
     let mut clickhouse = Clickhouse::new_default(run_id).await;
     clickhouse.buffered_insert_tx = Some(tx);
@@ -116,15 +132,29 @@ pub async fn load_clickhouse(
 ) -> eyre::Result<ClickhouseHttpClient> {
     let clickhouse_api = env::var("CLICKHOUSE_API")?;
     let clickhouse_api_key = env::var("CLICKHOUSE_API_KEY").ok();
-    Ok(ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await)
+    let cache_dir = env::var("CLICKHOUSE_API_CACHE_DIR")
+        .ok()
+        .map(std::path::PathBuf::from);
+
+    ClickhouseHttpClient::new(
+        ClickhouseHttpConfig::new(clickhouse_api, clickhouse_api_key).with_cache_dir(cache_dir),
+    )
+    .await
 }
 
 #[cfg(not(feature = "local-reth"))]
-pub fn get_tracing_provider(_: &Path, _: u64, _: BrontesTaskExecutor) -> LocalProvider {
-    let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
-    let db_port = env::var("RETH_PORT").expect("No DB port.env");
-    let url = format!("{db_endpoint}:{db_port}");
-    LocalProvider::new(url, 5)
+pub fn get_tracing_provider(_: &Path, _: u64, _: BrontesTaskExecutor) -> RemoteProvider {
+    let endpoint = match env::var("RETH_IPC_PATH") {
+        Ok(path) if !path.is_empty() => RemoteEndpoint::Ipc(path.into()),
+        _ => {
+            let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
+            let db_port = env::var("RETH_PORT").expect("No DB port.env");
+            format!("{db_endpoint}:{db_port}")
+                .parse()
+                .expect("invalid reth endpoint")
+        }
+    };
+    RemoteProvider::new(endpoint, 5)
 }
 
 #[cfg(feature = "local-reth")]
@@ -156,24 +186,81 @@ pub fn init_inspectors<DB: LibmdbxReader>(
     inspectors: Option<Vec<Inspectors>>,
     cex_exchanges: Vec<CexExchange>,
     trade_config: CexDexTradeConfig,
+    inspector_config: &InspectorConfig,
+    metrics: bool,
+) -> eyre::Result<&'static [&'static dyn Inspector<Result = Vec<Bundle>>]> {
+    Ok(init_inspector_registry(
+        quote_token,
+        db,
+        inspectors,
+        cex_exchanges,
+        trade_config,
+        inspector_config,
+        metrics,
+    )?
+    .init())
+}
+
+/// Registers the inspectors to run, with the inspectors disabled in the config
+/// registered but disabled so that they can be enabled while the run is going
+pub fn init_inspector_registry<DB: LibmdbxReader>(
+    quote_token: Address,
+    db: &'static DB,
+    inspectors: Option<Vec<Inspectors>>,
+    cex_exchanges: Vec<CexExchange>,
+    trade_config: CexDexTradeConfig,
+    inspector_config: &InspectorConfig,
     metrics: bool,
-) -> &'static [&'static dyn Inspector<Result = Vec<Bundle>>] {
-    let mut res = Vec::new();
+) -> eyre::Result<&'static InspectorRegistry> {
+    // the dex prices are denominated in the quote of the run, inspectors with
+    // another quote asset convert through it
+    init_pricing_quote_asset(quote_token)?;
+    init_inspector_config_hash(inspector_config)?;
+    let inspectors = inspectors.unwrap_or_else(|| Inspectors::iter().collect_vec());
+    let quote = inspector_config.quote_asset(quote_token, &inspectors)?;
+    let disabled = inspector_config.disabled_inspectors()?;
+
+    let mut registry = InspectorRegistry::default();
     let metrics = metrics.then(OutlierMetrics::new);
-    for inspector in inspectors
-        .map(|i| i.into_iter())
-        .unwrap_or_else(|| Inspectors::iter().collect_vec().into_iter())
-    {
-        res.push(inspector.init_mev_inspector(
-            quote_token,
-            db,
-            &cex_exchanges,
-            trade_config,
-            metrics.clone(),
-        ));
+    for inspector in inspectors {
+        let cex_exchanges = cex_exchanges.clone();
+        let sandwich_mode = inspector_config.sandwich_mode;
+        let metrics = metrics.clone();
+        let thresholds = inspector_config.thresholds(inspector).clone();
+
+        registry.register(inspector, move || {
+            let inner = inspector.init_mev_inspector(
+                quote,
+                db,
+                &cex_exchanges,
+                trade_config,
+                sandwich_mode,
+                thresholds.limits(),
+                metrics.clone(),
+            );
+
+            if thresholds.is_default() {
+                inner
+            } else {
+                static_object(FilteredInspector { inner, thresholds: thresholds.clone() })
+                    as &'static dyn Inspector<Result = Vec<Bundle>>
+            }
+        });
     }
+    registry.apply_disabled(&disabled);
+
+    Ok(static_object(registry))
+}
+
+/// Loads the inspector thresholds, defaulting to no extra filtering if no
+/// config is given
+pub fn load_inspector_config(path: Option<&Path>) -> eyre::Result<InspectorConfig> {
+    let Some(path) = path else { return Ok(InspectorConfig::default()) };
+
+    let config = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read {}", path.display()))?;
 
-    &*Box::leak(res.into_boxed_slice())
+    toml::from_str(&config).wrap_err_with(|| format!("failed to parse {}", path.display()))
 }
 
 pub fn get_env_vars() -> eyre::Result<String> {
@@ -186,15 +273,17 @@ pub fn get_env_vars() -> eyre::Result<String> {
 #[cfg(feature = "local-clickhouse")]
 fn spawn_db_writer_thread(
     executor: &BrontesTaskExecutor,
-    buffered_rx: tokio::sync::mpsc::UnboundedReceiver<Vec<BrontesClickhouseData>>,
+    buffered_rx: tokio::sync::mpsc::Receiver<ClickhouseWriterMessage>,
     hr: Option<HeartRateMonitor>,
 ) {
     let shutdown = executor.get_graceful_shutdown();
     ClickhouseBuffered::new(
-        UnboundedYapperReceiver::new(buffered_rx, 1500, "clickhouse buffered".to_string()),
+        buffered_rx,
         clickhouse_config(),
         5000,
         800,
+        CLICKHOUSE_FLUSH_INTERVAL,
+        CLICKHOUSE_MAX_PENDING_ROWS,
         hr,
     )
     .run(shutdown);
@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use brontes_core::decoding::Parser as DParser;
+use brontes_database::{clickhouse::cex_config::CexDownloadConfig, libmdbx::LibmdbxReader};
+use brontes_inspect::{composer::init_inspector_cache, Inspectors};
+use brontes_metrics::ParserMetricsListener;
+use brontes_types::{
+    address_filter::init_address_filter,
+    chain::chain_spec,
+    db::{
+        cex::{fees::init_cex_fee_schedule, CexExchange},
+        verify_report::VerifyReport,
+    },
+    denylist::init_denylist,
+    init_thread_pools, UnboundedYapperReceiver,
+};
+use clap::Parser;
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::{
+    build_id, determine_max_tasks, get_env_vars, load_clickhouse, load_inspector_config,
+    load_libmdbx, run::TimeWindowArgs, static_object,
+};
+use crate::{
+    cli::{get_tracing_provider, init_inspectors},
+    runner::CliContext,
+    take_recomputed_blocks, BrontesRunConfig, RangeType, VerifyProcessor,
+};
+
+#[derive(Debug, Parser)]
+pub struct VerifyArgs {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block:      u64,
+    /// End Block
+    #[arg(long, short)]
+    pub end_block:        u64,
+    /// Profit differences in USD at or below this are not reported
+    #[arg(long, default_value = "0.01")]
+    pub profit_tolerance: f64,
+    /// Optional path to write the json diff report to, if omitted it is
+    /// printed to stdout
+    #[arg(long, short)]
+    pub output:           Option<PathBuf>,
+    /// Optional Max Tasks, if omitted it will default to 80% of the number of
+    /// physical cores on your machine
+    #[arg(long, short)]
+    pub max_tasks:        Option<u64>,
+    /// Optional minimum batch size
+    #[arg(long, default_value = "500")]
+    pub min_batch_size:   u64,
+    /// Optional quote asset, either an address or a symbol such as USDC, DAI
+    /// or WETH. If omitted it will default to the quote asset of the chain,
+    /// USDT on mainnet
+    #[arg(long, short)]
+    pub quote_asset:      Option<String>,
+    /// Inspectors to run. Should match the inspectors the stored range was
+    /// run with, otherwise the bundles of the missing inspectors are reported
+    /// as removed
+    #[arg(long, short, value_delimiter = ',')]
+    pub inspectors:       Option<Vec<Inspectors>>,
+    /// Inspector thresholds the results were produced with
+    #[arg(long)]
+    pub inspector_config: Option<PathBuf>,
+    /// Optional directory to cache the inspector results in. Blocks whose
+    /// classified tree, metadata and inspector settings are unchanged load
+    /// their results from the cache instead of rerunning the inspectors
+    #[arg(long)]
+    pub inspector_cache:  Option<PathBuf>,
+    /// Time window arguments for cex data downloads
+    #[clap(flatten)]
+    pub time_window_args: TimeWindowArgs,
+    /// CEX exchanges to consider for cex-dex analysis
+    #[arg(
+        long,
+        short,
+        default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
+        value_delimiter = ','
+    )]
+    pub cex_exchanges:    Vec<CexExchange>,
+}
+
+impl VerifyArgs {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if self.start_block > self.end_block {
+            return Err(eyre::eyre!("start block must be less than end block"))
+        }
+
+        let snapshot_mode = !cfg!(feature = "local-clickhouse");
+        let reth_db_path = get_env_vars()?;
+        let quote_asset = match &self.quote_asset {
+            Some(quote_asset) => chain_spec().parse_quote_asset(quote_asset)?,
+            None => chain_spec().quote_asset,
+        };
+        let task_executor = ctx.task_executor;
+
+        let max_tasks = determine_max_tasks(self.max_tasks);
+        init_thread_pools(max_tasks as usize);
+
+        let (metrics_tx, metrics_rx) = unbounded_channel();
+        let metrics_listener = ParserMetricsListener::new(UnboundedYapperReceiver::new(
+            metrics_rx,
+            10_000,
+            "metrics".to_string(),
+        ));
+        task_executor.spawn_critical("metrics", metrics_listener);
+
+        // the recomputed results are never written, so the plain libmdbx handle is
+        // used to make sure nothing ends up in clickhouse either
+        let libmdbx = static_object(load_libmdbx(&task_executor, brontes_db_path)?);
+
+        let load_window = self.time_window_args.load_time_window();
+        let cex_download_config = CexDownloadConfig::new(
+            (load_window as u64, load_window as u64),
+            self.cex_exchanges.clone(),
+        );
+        let clickhouse = static_object(load_clickhouse(cex_download_config, None).await?);
+
+        let inspector_config = load_inspector_config(self.inspector_config.as_deref())?;
+        init_address_filter(inspector_config.address_filter.clone())?;
+        init_denylist(inspector_config.denylist.clone())?;
+        init_cex_fee_schedule(inspector_config.cex_fees.clone())?;
+        if let Some(dir) = &self.inspector_cache {
+            init_inspector_cache(dir, &build_id())?;
+        }
+        let inspectors = init_inspectors(
+            quote_asset,
+            libmdbx,
+            self.inspectors.clone(),
+            self.cex_exchanges.clone(),
+            self.time_window_args.trade_config(),
+            &inspector_config,
+            false,
+        )?;
+
+        let tracer =
+            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
+        let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
+
+        let range_type = RangeType::SingleRange {
+            start_block:   Some(self.start_block),
+            end_block:     Some(self.end_block),
+            back_from_tip: 0,
+        };
+
+        let executor = task_executor.clone();
+        executor
+            .clone()
+            .spawn_critical_with_graceful_shutdown_signal("verify init", |shutdown| async move {
+                if let Ok(brontes) = BrontesRunConfig::<_, _, _, VerifyProcessor>::new(
+                    range_type,
+                    max_tasks,
+                    self.min_batch_size,
+                    quote_asset,
+                    false,
+                    false,
+                    inspectors,
+                    clickhouse,
+                    parser,
+                    libmdbx,
+                    libmdbx,
+                    true,
+                    false,
+                    snapshot_mode,
+                    load_window,
+                )
+                .with_venue_latencies(self.time_window_args.venue_latencies)
+                .build(task_executor, shutdown)
+                .await
+                .map_err(|e| {
+                    tracing::error!(%e);
+                    e
+                }) {
+                    brontes.await;
+                }
+            })
+            .await?;
+
+        let stored = libmdbx.try_fetch_mev_blocks(Some(self.start_block), self.end_block)?;
+        let report = VerifyReport::new(
+            self.start_block,
+            self.end_block,
+            self.profit_tolerance,
+            stored,
+            take_recomputed_blocks(),
+        );
+
+        tracing::info!(
+            verified_blocks = report.verified_blocks,
+            unverified_blocks = report.unverified_blocks.len(),
+            added = report.added.len(),
+            removed = report.removed.len(),
+            profit_deltas = report.profit_deltas.len(),
+            "finished verifying range"
+        );
+
+        let report = serde_json::to_string_pretty(&report)?;
+        match self.output {
+            Some(path) => std::fs::write(path, report)?,
+            None => println!("{report}"),
+        }
+
+        Ok(())
+    }
+}
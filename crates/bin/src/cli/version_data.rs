@@ -13,6 +13,9 @@ pub const VERGEN_GIT_SHA: &str = const_format::str_index!(VERGEN_GIT_SHA_LONG, .
 /// The build timestamp.
 pub const VERGEN_BUILD_TIMESTAMP: &str = env!("VERGEN_BUILD_TIMESTAMP");
 
+/// Whether the build had uncommitted changes.
+pub const VERGEN_GIT_DIRTY: &str = env!("VERGEN_GIT_DIRTY");
+
 /// The target triple.
 pub const VERGEN_CARGO_TARGET_TRIPLE: &str = env!("VERGEN_CARGO_TARGET_TRIPLE");
 
@@ -86,3 +89,14 @@ pub const BUILD_PROFILE_NAME: &str = {
         win_parts[win_parts.len() - 4]
     }
 };
+
+/// Identifies the build the on disk caches were written by. Builds with
+/// uncommitted changes share the sha of their commit, so they are told apart
+/// by their build timestamp.
+pub fn build_id() -> String {
+    if VERGEN_GIT_DIRTY == "true" {
+        format!("{VERGEN_GIT_SHA_LONG}-dirty-{VERGEN_BUILD_TIMESTAMP}")
+    } else {
+        VERGEN_GIT_SHA_LONG.to_string()
+    }
+}
@@ -0,0 +1,348 @@
+//! Embeds the brontes pipeline into another service.
+//!
+//! [`BrontesRunner`] wires the parser, classifier, pricer and inspectors the
+//! same way `brontes run` does, but leaves driving them to the caller. A
+//! started runner returns a [`BrontesHandle`] that processes one block at a
+//! time, while [`BrontesRunner::run`] processes a whole range with the range
+//! and tip executors of the cli.
+use std::{marker::PhantomData, sync::atomic::Ordering};
+
+use alloy_primitives::Address;
+use brontes_core::decoding::{Parser, TracingProvider};
+use brontes_database::{clickhouse::ClickhouseHandle, libmdbx::LibmdbxInit};
+use brontes_inspect::Inspector;
+use brontes_metrics::{
+    anomaly::{BlockAnomalyMonitor, SharedAnomalyMonitor},
+    ParserMetricsListener,
+};
+use brontes_types::{chain::chain_spec, BrontesTaskExecutor, UnboundedYapperReceiver};
+use futures::StreamExt;
+use reth_tasks::shutdown::GracefulShutdown;
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::{
+    shared::state_collector::StateCollector, Brontes, BrontesRunConfig, Processor, RangeType,
+};
+use crate::cli::static_object;
+
+/// Cex data window in seconds, the largest of the default cex-dex windows of
+/// the cli
+const DEFAULT_CEX_WINDOW: usize = 20;
+
+/// Builds the brontes pipeline from a tracer, the databases, the inspectors
+/// and the [`Processor`] that handles their results.
+pub struct BrontesRunner<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor> {
+    tracer:               T,
+    libmdbx:              &'static DB,
+    tip_db:               &'static DB,
+    clickhouse:           &'static CH,
+    inspectors:           &'static [&'static dyn Inspector<Result = P::InspectType>],
+    quote_asset:          Address,
+    force_dex_pricing:    bool,
+    force_no_dex_pricing: bool,
+    cex_window:           usize,
+    selector_fallback:    bool,
+    is_snapshot:          bool,
+    max_tasks:            u64,
+    min_batch_size:       u64,
+    _p:                   PhantomData<P>,
+}
+
+impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
+    BrontesRunner<T, DB, CH, P>
+{
+    pub fn new(
+        tracer: T,
+        libmdbx: &'static DB,
+        clickhouse: &'static CH,
+        inspectors: &'static [&'static dyn Inspector<Result = P::InspectType>],
+    ) -> Self {
+        Self {
+            tracer,
+            libmdbx,
+            tip_db: libmdbx,
+            clickhouse,
+            inspectors,
+            quote_asset: chain_spec().quote_asset,
+            force_dex_pricing: false,
+            force_no_dex_pricing: false,
+            cex_window: DEFAULT_CEX_WINDOW,
+            selector_fallback: false,
+            is_snapshot: false,
+            max_tasks: num_cpus::get() as u64,
+            min_batch_size: 500,
+            _p: PhantomData,
+        }
+    }
+
+    /// Database the results of blocks processed through a [`BrontesHandle`]
+    /// or past the end of a range are written to. Defaults to `libmdbx`
+    pub fn with_tip_db(mut self, tip_db: &'static DB) -> Self {
+        self.tip_db = tip_db;
+        self
+    }
+
+    pub fn with_quote_asset(mut self, quote_asset: Address) -> Self {
+        self.quote_asset = quote_asset;
+        self
+    }
+
+    pub fn with_dex_pricing(mut self, force_dex_pricing: bool, force_no_dex_pricing: bool) -> Self {
+        self.force_dex_pricing = force_dex_pricing;
+        self.force_no_dex_pricing = force_no_dex_pricing;
+        self
+    }
+
+    /// Size of the window of cex data loaded around a block in seconds
+    pub fn with_cex_window(mut self, cex_window: usize) -> Self {
+        self.cex_window = cex_window;
+        self
+    }
+
+    /// Classifies calls to unknown contracts from the function selector table
+    pub fn with_selector_fallback(mut self, selector_fallback: bool) -> Self {
+        self.selector_fallback = selector_fallback;
+        self
+    }
+
+    /// The database is a downloaded snapshot, so the global tables aren't
+    /// verified and no range state is initialized
+    pub fn with_snapshot(mut self, is_snapshot: bool) -> Self {
+        self.is_snapshot = is_snapshot;
+        self
+    }
+
+    /// Max amount of range executors [`Self::run`] splits a range into and
+    /// the least amount of blocks each of them processes
+    pub fn with_range_batching(mut self, max_tasks: u64, min_batch_size: u64) -> Self {
+        self.max_tasks = max_tasks;
+        self.min_batch_size = min_batch_size;
+        self
+    }
+
+    /// Starts the pipeline at `start_block`. The blocks are then processed
+    /// in order through the returned handle.
+    pub async fn start(
+        self,
+        start_block: u64,
+        executor: BrontesTaskExecutor,
+    ) -> eyre::Result<BrontesHandle<T, DB, CH, P>> {
+        if self.inspectors.is_empty() {
+            eyre::bail!("no inspectors loaded");
+        }
+
+        let config = self
+            .into_config(RangeType::SingleRange {
+                start_block:   Some(start_block),
+                end_block:     None,
+                back_from_tip: 0,
+            })
+            .await;
+        if !config.is_snapshot {
+            config.verify_global_tables().await?;
+        }
+
+        let collector = config.init_state_collector(
+            0,
+            executor,
+            start_block,
+            start_block,
+            true,
+            start_block,
+            None,
+        );
+
+        Ok(BrontesHandle {
+            collector,
+            next_block: start_block,
+            db: config.tip_db,
+            inspectors: config.inspectors,
+            anomalies: BlockAnomalyMonitor::shared("handle"),
+            _p: PhantomData,
+        })
+    }
+
+    /// Processes the range the same way `brontes run` does, the returned
+    /// future resolves once every block was processed or `shutdown` fired
+    pub async fn run(
+        self,
+        range_type: RangeType,
+        executor: BrontesTaskExecutor,
+        shutdown: GracefulShutdown,
+    ) -> eyre::Result<Brontes> {
+        self.into_config(range_type)
+            .await
+            .build(executor, shutdown)
+            .await
+    }
+
+    async fn into_config(self, range_type: RangeType) -> BrontesRunConfig<T, DB, CH, P> {
+        let (metrics_tx, metrics_rx) = unbounded_channel();
+        tokio::spawn(ParserMetricsListener::new(UnboundedYapperReceiver::new(
+            metrics_rx,
+            10_000,
+            "metrics".to_string(),
+        )));
+        let parser = static_object(Parser::new(metrics_tx, self.libmdbx, self.tracer).await);
+
+        BrontesRunConfig::new(
+            range_type,
+            self.max_tasks,
+            self.min_batch_size,
+            self.quote_asset,
+            self.force_dex_pricing,
+            self.force_no_dex_pricing,
+            self.inspectors,
+            self.clickhouse,
+            parser,
+            self.libmdbx,
+            self.tip_db,
+            false,
+            false,
+            self.is_snapshot,
+            self.cex_window,
+        )
+        .with_selector_fallback(self.selector_fallback)
+    }
+}
+
+/// Processes the blocks of a started [`BrontesRunner`] one at a time
+pub struct BrontesHandle<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor> {
+    collector:  StateCollector<T, DB, CH>,
+    next_block: u64,
+    db:         &'static DB,
+    inspectors: &'static [&'static dyn Inspector<Result = P::InspectType>],
+    anomalies:  SharedAnomalyMonitor,
+    _p:         PhantomData<P>,
+}
+
+impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
+    BrontesHandle<T, DB, CH, P>
+{
+    /// The block the next call to [`Self::process_next_block`] processes
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// Traces, classifies and prices the next block, then hands the block to
+    /// the inspectors and their results to the processor. The pricing graph
+    /// is built block after block, so blocks can't be skipped.
+    pub async fn process_next_block(&mut self) -> eyre::Result<u64> {
+        let block = self.next_block;
+        self.collector.fetch_state_for(block, 0, None);
+
+        let data = self
+            .collector
+            .next()
+            .await
+            .ok_or_else(|| eyre::eyre!("failed to collect the state of block {block}"))?;
+        P::process_results(
+            self.db,
+            self.collector.tracer(),
+            self.inspectors,
+            data,
+            self.anomalies.clone(),
+        )
+        .await;
+
+        self.next_block += 1;
+        Ok(block)
+    }
+
+    /// Processes every block up to and including `end_block`
+    pub async fn process_until(&mut self, end_block: u64) -> eyre::Result<()> {
+        while self.next_block <= end_block {
+            self.process_next_block().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Stops the pricer and waits for the pipeline to wind down
+    pub async fn shutdown(mut self) {
+        self.collector.get_shutdown().store(true, Ordering::SeqCst);
+        while self.collector.next().await.is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_core::test_utils::{init_tracer, load_clickhouse, TraceLoader};
+    use brontes_inspect::{config::InspectorConfig, Inspectors};
+    use brontes_types::{
+        constants::USDT_ADDRESS,
+        db::{cex::trades::CexDexTradeConfig, traits::LibmdbxReader},
+        BrontesTaskManager,
+    };
+    use tokio::runtime::Handle;
+
+    use super::*;
+    use crate::{cli::init_inspectors, MevProcessor};
+
+    #[brontes_macros::test]
+    async fn test_handle_processes_blocks_in_order() {
+        let loader = TraceLoader::new().await;
+        for block in [18_500_000, 18_500_001] {
+            loader.fetch_missing_metadata(block).await.unwrap();
+        }
+        let libmdbx = loader.libmdbx;
+        let clickhouse = static_object(load_clickhouse().await);
+        let inspectors = init_inspectors(
+            USDT_ADDRESS,
+            libmdbx,
+            Some(vec![Inspectors::AtomicArb, Inspectors::Sandwich]),
+            vec![],
+            CexDexTradeConfig::default(),
+            &InspectorConfig::default(),
+            false,
+        )
+        .unwrap();
+
+        let task_manager = BrontesTaskManager::new(Handle::current(), true);
+        let executor = task_manager.executor();
+        tokio::spawn(task_manager);
+
+        let mut handle = BrontesRunner::<_, _, _, MevProcessor>::new(
+            init_tracer(Handle::current(), 10),
+            libmdbx,
+            clickhouse,
+            inspectors,
+        )
+        .with_quote_asset(USDT_ADDRESS)
+        .start(18_500_000, executor)
+        .await
+        .unwrap();
+
+        assert_eq!(handle.process_next_block().await.unwrap(), 18_500_000);
+        handle.process_until(18_500_001).await.unwrap();
+        assert_eq!(handle.next_block(), 18_500_002);
+
+        for block in [18_500_000, 18_500_001] {
+            let mev_block = libmdbx
+                .try_fetch_mev_block(block)
+                .unwrap()
+                .unwrap_or_else(|| panic!("the results of block {block} weren't written"));
+            assert_eq!(mev_block.block.block_number, block);
+        }
+
+        handle.shutdown().await;
+    }
+
+    #[brontes_macros::test]
+    async fn test_start_without_inspectors_fails() {
+        let loader = TraceLoader::new().await;
+        let clickhouse = static_object(load_clickhouse().await);
+        let task_manager = BrontesTaskManager::new(Handle::current(), true);
+
+        let res = BrontesRunner::<_, _, _, MevProcessor>::new(
+            init_tracer(Handle::current(), 10),
+            loader.libmdbx,
+            clickhouse,
+            &[],
+        )
+        .start(18_500_000, task_manager.executor())
+        .await;
+
+        assert!(res.is_err());
+    }
+}
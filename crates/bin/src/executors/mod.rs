@@ -1,3 +1,4 @@
+mod brontes_runner;
 pub mod discovery_only;
 mod processors;
 mod range;
@@ -18,6 +19,7 @@ use shared::multi_block_window::MultiBlockWindow;
 mod tip;
 use std::{
     marker::PhantomData,
+    path::PathBuf,
     pin::Pin,
     sync::{atomic::AtomicBool, Arc},
     task::{Context, Poll},
@@ -26,10 +28,17 @@ use std::{
 use alloy_primitives::Address;
 use brontes_classifier::Classifier;
 use brontes_core::decoding::{Parser, TracingProvider};
-use brontes_database::libmdbx::LibmdbxInit;
-use brontes_inspect::Inspector;
+use brontes_database::libmdbx::{initialize::config_modified_times, LibmdbxInit};
+use brontes_inspect::{registry::InspectorRegistry, Inspector};
 use brontes_pricing::{BrontesBatchPricer, GraphManager, LoadState};
-use brontes_types::{BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver};
+pub use brontes_runner::{BrontesHandle, BrontesRunner};
+use brontes_types::{
+    db::{
+        backfill_checkpoint::{priced_until, remaining_ranges, BackfillCheckpoint},
+        cex::trades::VenueLatencies,
+    },
+    is_deterministic_mode, BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver,
+};
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use indicatif::MultiProgress;
 use itertools::Itertools;
@@ -39,12 +48,19 @@ pub use tip::TipInspector;
 use tokio::{sync::mpsc::unbounded_channel, task::JoinHandle};
 
 use self::shared::{
-    dex_pricing::WaitingForPricerFuture, metadata_loader::MetadataLoader,
-    state_collector::StateCollector,
+    adaptive_concurrency::AdaptiveConcurrency, dex_pricing::WaitingForPricerFuture,
+    metadata_loader::MetadataLoader, state_collector::StateCollector,
 };
-use crate::cli::static_object;
+use crate::cli::{load_inspector_config, static_object};
 
 pub const PROMETHEUS_ENDPOINT_IP: [u8; 4] = [0u8, 0u8, 0u8, 0u8];
+pub const CONFIG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+pub const SANCTIONS_REFRESH_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(6 * 60 * 60);
+/// How often the node is polled for a new chain tip by default
+pub const DEFAULT_TIP_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+/// Max amount of blocks a range executor traces at once
+pub const MAX_BLOCKS_IN_FLIGHT: usize = 8;
 
 pub struct BrontesRunConfig<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
 {
@@ -63,6 +79,11 @@ pub struct BrontesRunConfig<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseH
     pub metrics: bool,
     pub is_snapshot: bool,
     pub cex_window: usize,
+    pub selector_fallback: bool,
+    pub resume: bool,
+    pub tip_poll_interval: std::time::Duration,
+    pub inspector_reload: Option<(&'static InspectorRegistry, PathBuf)>,
+    pub venue_latencies: VenueLatencies,
     _p: PhantomData<P>,
 }
 
@@ -103,10 +124,51 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             tip_db,
             is_snapshot,
             cex_window,
+            selector_fallback: false,
+            resume: false,
+            tip_poll_interval: DEFAULT_TIP_POLL_INTERVAL,
+            inspector_reload: None,
+            venue_latencies: VenueLatencies::default(),
             _p: PhantomData,
         }
     }
 
+    /// Classifies calls to unknown contracts from the function selector table
+    pub fn with_selector_fallback(mut self, selector_fallback: bool) -> Self {
+        self.selector_fallback = selector_fallback;
+        self
+    }
+
+    /// Skips the blocks of the range that a previous run already checkpointed
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// How often the node is polled for a new chain tip when following it
+    pub fn with_tip_poll_interval(mut self, tip_poll_interval: std::time::Duration) -> Self {
+        self.tip_poll_interval = tip_poll_interval;
+        self
+    }
+
+    /// Re-applies the disabled inspectors of the inspector config to the
+    /// registry when the config changes, when following the tip
+    pub fn with_inspector_reload(
+        mut self,
+        registry: &'static InspectorRegistry,
+        inspector_config: Option<PathBuf>,
+    ) -> Self {
+        self.inspector_reload = inspector_config.map(|path| (registry, path));
+        self
+    }
+
+    /// Latency of each cex venue, the cex trades are shifted by it when they
+    /// are loaded for a block
+    pub fn with_venue_latencies(mut self, venue_latencies: VenueLatencies) -> Self {
+        self.venue_latencies = venue_latencies;
+        self
+    }
+
     pub async fn build(
         self,
         executor: BrontesTaskExecutor,
@@ -196,6 +258,10 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                 "Tip Inspector",
                 |shutdown| async move { tip_inspector.run_until_graceful_shutdown(shutdown).await },
             ));
+
+            self.spawn_sanctions_refresh(&executor);
+            self.spawn_config_watcher(&executor);
+            self.spawn_inspector_config_watcher(&executor);
         }
 
         let metrics = FinishedRange::default();
@@ -250,6 +316,24 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
 
         let progress_bar = self.initialize_global_progress_bar();
 
+        let checkpoints = if self.resume { self.backfill_checkpoints() } else { vec![] };
+        let chunks = if self.resume {
+            let remaining = self.remaining_chunks(&chunks, &checkpoints);
+            let skipped = chunks
+                .iter()
+                .map(|(start, end)| end - start)
+                .sum::<u64>()
+                .saturating_sub(remaining.iter().map(|(start, end)| end - start).sum());
+            tracing::info!(skipped, "resuming range, skipping checkpointed blocks");
+
+            if let Some(pb) = progress_bar.as_ref() {
+                pb.inc(skipped)
+            }
+            remaining
+        } else {
+            chunks
+        };
+
         let state_to_init = Arc::new(self.state_to_initialize(end_block));
 
         #[cfg(feature = "sorella-server")]
@@ -278,8 +362,16 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             GlobalRangeMetrics::new(chunks.iter().map(|(start, end)| end - start).collect_vec())
         });
 
+        // blocks whose quotes were persisted before the restart aren't priced again
+        let chunks = chunks
+            .into_iter()
+            .map(|(start_block, end_block)| {
+                (start_block, end_block, priced_until(start_block, &checkpoints))
+            })
+            .collect_vec();
+
         futures::stream::iter(chunks.into_iter().enumerate().map(
-            move |(batch_id, (start_block, end_block))| {
+            move |(batch_id, (start_block, end_block, priced_until))| {
                 let ranges =
                     state_to_init.get_state_for_ranges(start_block as usize, end_block as usize);
 
@@ -312,6 +404,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                             start_block,
                             end_block,
                             false,
+                            priced_until,
                             pricing_metrics,
                         ),
                         self.libmdbx,
@@ -333,18 +426,25 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         back_from_tip: u64,
         pricing_metrics: Option<DexPricingMetrics>,
     ) -> TipInspector<T, DB, CH, P> {
-        let state_collector = self.init_state_collector(
-            range_id,
-            executor,
-            start_block,
-            start_block,
-            true,
-            pricing_metrics,
-        );
+        // only what the state collectors are built from
+        let config =
+            Self { range_type: RangeType::MultipleRanges(vec![]), inspector_reload: None, ..*self };
+        let new_state_collector = Box::new(move |block| {
+            config.init_state_collector(
+                range_id,
+                executor.clone(),
+                block,
+                block,
+                true,
+                block,
+                pricing_metrics.clone(),
+            )
+        });
         TipInspector::new(
             start_block,
             back_from_tip,
-            state_collector,
+            self.tip_poll_interval,
+            new_state_collector,
             self.parser,
             self.tip_db,
             self.inspectors,
@@ -364,6 +464,8 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
     /// * `start_block` - The first block in the range.
     /// * `end_block` - The last block in the range.
     /// * `tip` - Boolean flag indicating if this is for tip processing.
+    /// * `priced_until` - First block whose dex quotes weren't persisted by a
+    ///   previous run.
     /// * `pricing_metrics` - Optional metrics for DEX pricing.
     ///
     /// # Returns
@@ -377,11 +479,16 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         start_block: u64,
         end_block: u64,
         tip: bool,
+        priced_until: u64,
         pricing_metrics: Option<DexPricingMetrics>,
     ) -> StateCollector<T, DB, CH> {
         let shutdown = Arc::new(AtomicBool::new(false));
         let (tx, rx) = unbounded_channel();
-        let classifier = static_object(Classifier::new(self.libmdbx, tx, self.parser.get_tracer()));
+        let mut classifier = Classifier::new(self.libmdbx, tx, self.parser.get_tracer());
+        if self.selector_fallback {
+            classifier = classifier.with_selector_fallback();
+        }
+        let classifier = static_object(classifier);
 
         let pairs = self.libmdbx.protocols_created_before(start_block).unwrap();
 
@@ -417,7 +524,13 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             executor.clone(),
         );
 
-        let pricing = WaitingForPricerFuture::new(pricer, executor);
+        // the tip has no range to checkpoint and writes its results to the tip db
+        let pricing = if tip {
+            WaitingForPricerFuture::new(pricer, executor).with_quote_flushing(self.tip_db, None)
+        } else {
+            WaitingForPricerFuture::new(pricer, executor)
+                .with_quote_flushing(self.libmdbx, Some((start_block, end_block)))
+        };
         let fetcher = MetadataLoader::new(
             tip.then_some(self.clickhouse),
             pricing,
@@ -425,7 +538,9 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             self.force_no_dex_pricing,
             data_req,
             self.cex_window,
-        );
+        )
+        .with_priced_until(priced_until)
+        .with_venue_latencies(self.venue_latencies);
 
         let block_window_size = self
             .inspectors
@@ -436,6 +551,14 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
 
         let window = MultiBlockWindow::new(block_window_size);
 
+        // the tip follows the chain one block at a time and deterministic
+        // replays process the blocks strictly in order
+        let concurrency = if tip || is_deterministic_mode() {
+            AdaptiveConcurrency::fixed(1)
+        } else {
+            AdaptiveConcurrency::new(1, MAX_BLOCKS_IN_FLIGHT)
+        };
+
         StateCollector::new(
             shutdown,
             fetcher,
@@ -444,6 +567,8 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             self.libmdbx,
             window,
             self.quote_asset,
+            concurrency,
+            (!tip).then_some(end_block),
         )
     }
 
@@ -511,9 +636,136 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         Ok(())
     }
 
+    /// Periodically reloads the sanctioned address set while following the tip
+    /// so that newly listed addresses get picked up without a restart.
+    fn spawn_sanctions_refresh(&self, executor: &BrontesTaskExecutor) {
+        let libmdbx = self.libmdbx;
+        let clickhouse = self.clickhouse;
+        let tracer = self.parser.get_tracer();
+
+        executor.spawn_critical_with_graceful_shutdown_signal(
+            "Sanctions Refresh",
+            |shutdown| async move {
+                let refresh = async move {
+                    let mut interval = tokio::time::interval(SANCTIONS_REFRESH_INTERVAL);
+                    // the first tick completes immediately and the set was just loaded with
+                    // the full range tables
+                    interval.tick().await;
+                    loop {
+                        interval.tick().await;
+                        if let Err(e) = libmdbx
+                            .refresh_sanctioned_addresses(clickhouse, tracer.clone())
+                            .await
+                        {
+                            tracing::error!(%e, "failed to refresh sanctioned addresses");
+                        }
+                    }
+                };
+
+                pin_mut!(refresh, shutdown);
+                tokio::select! {
+                    _ = &mut refresh => {},
+                    guard = shutdown => drop(guard),
+                }
+            },
+        );
+    }
+
+    /// Watches the toml configs and re-applies them to the database when they
+    /// change, so that new pools, tokens and labels can be added to a tip
+    /// following instance without restarting it.
+    fn spawn_config_watcher(&self, executor: &BrontesTaskExecutor) {
+        let libmdbx = self.libmdbx;
+        let clickhouse = self.clickhouse;
+        let tracer = self.parser.get_tracer();
+
+        executor.spawn_critical_with_graceful_shutdown_signal(
+            "Config Watcher",
+            |shutdown| async move {
+                let watch = async move {
+                    let mut last_modified = tokio::task::spawn_blocking(config_modified_times)
+                        .await
+                        .unwrap_or_default();
+                    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        let Ok(modified) = tokio::task::spawn_blocking(config_modified_times).await
+                        else {
+                            continue
+                        };
+                        if modified == last_modified {
+                            continue
+                        }
+                        last_modified = modified;
+
+                        tracing::info!("config changed, reloading");
+                        if let Err(e) = libmdbx.reload_config(clickhouse, tracer.clone()).await {
+                            tracing::error!(%e, "failed to reload config");
+                        }
+                    }
+                };
+
+                pin_mut!(watch, shutdown);
+                tokio::select! {
+                    _ = &mut watch => {},
+                    guard = shutdown => drop(guard),
+                }
+            },
+        );
+    }
+
+    /// Watches the inspector config and enables and disables the inspectors
+    /// of the registry when it changes, so that heavy inspectors can be
+    /// toggled on a tip following instance without restarting it. Only the
+    /// disabled inspectors are reloaded, the other settings need a restart.
+    fn spawn_inspector_config_watcher(&self, executor: &BrontesTaskExecutor) {
+        let Some((registry, path)) = self.inspector_reload.clone() else { return };
+
+        executor.spawn_critical_with_graceful_shutdown_signal(
+            "Inspector Config Watcher",
+            |shutdown| async move {
+                let watch = async move {
+                    let modified_at = |path: PathBuf| {
+                        tokio::task::spawn_blocking(move || std::fs::metadata(path)?.modified())
+                    };
+                    let mut last_modified =
+                        modified_at(path.clone()).await.ok().and_then(Result::ok);
+                    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        let Ok(Ok(modified)) = modified_at(path.clone()).await else { continue };
+                        if last_modified == Some(modified) {
+                            continue
+                        }
+                        last_modified = Some(modified);
+
+                        tracing::info!(path = %path.display(), "reloading inspector config");
+                        match load_inspector_config(Some(&path))
+                            .and_then(|config| config.disabled_inspectors())
+                        {
+                            Ok(disabled) => registry.apply_disabled(&disabled),
+                            Err(e) => tracing::error!(%e, "failed to reload inspector config"),
+                        }
+                    }
+                };
+
+                pin_mut!(watch, shutdown);
+                tokio::select! {
+                    _ = &mut watch => {},
+                    guard = shutdown => drop(guard),
+                }
+            },
+        );
+    }
+
     ///Calculate the block chunks using min batch size and max_tasks.
     /// Max tasks defaults to 50% of physical cores of the system if not set
     fn calculate_chunks(&self, start_block: u64, end_block: u64) -> Vec<(u64, u64)> {
+        // a single executor, so blocks are processed in order
+        if is_deterministic_mode() {
+            return vec![(start_block, end_block)]
+        }
+
         let range = end_block - start_block;
         let cpus_min = range / self.min_batch_size + 1;
         let cpus = std::cmp::min(cpus_min, self.max_tasks);
@@ -531,6 +783,30 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             .collect_vec()
     }
 
+    fn backfill_checkpoints(&self) -> Vec<(u64, BackfillCheckpoint)> {
+        self.libmdbx
+            .fetch_backfill_checkpoints()
+            .unwrap_or_else(|e| {
+                tracing::error!(
+                    err=%e,
+                    "failed to load backfill checkpoints, processing the full range"
+                );
+                vec![]
+            })
+    }
+
+    /// Removes the blocks a previous run checkpointed as done from the chunks
+    fn remaining_chunks(
+        &self,
+        chunks: &[(u64, u64)],
+        checkpoints: &[(u64, BackfillCheckpoint)],
+    ) -> Vec<(u64, u64)> {
+        chunks
+            .iter()
+            .flat_map(|(start, end)| remaining_ranges(*start, *end, checkpoints))
+            .collect_vec()
+    }
+
     async fn should_run_tip_inspector(&self) -> (bool, u64) {
         match &self.range_type {
             RangeType::MultipleRanges(ranges) => {
@@ -1,28 +1,45 @@
-#[cfg(feature = "local-clickhouse")]
 use std::sync::Arc;
 
+use alloy_primitives::Address;
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_inspect::{
     composer::{run_block_inspection, ComposerResults},
+    config::inspector_config_hash,
+    liquidation_context::attach_liquidation_contexts,
+    profit_verification::{is_profit_verification_enabled, verify_bundle_profits},
     Inspector,
 };
+use brontes_metrics::anomaly::{BlockOutputs, SharedAnomalyMonitor};
 #[cfg(feature = "local-clickhouse")]
 use brontes_types::frontend_prunes::{
     remove_burn_transfers, remove_collect_transfers, remove_mint_transfers, remove_swap_transfers,
 };
-#[cfg(feature = "local-clickhouse")]
-use brontes_types::normalized_actions::Action;
-#[cfg(feature = "local-clickhouse")]
-use brontes_types::tree::BlockTree;
 use brontes_types::{
-    db::block_analysis::BlockAnalysis,
+    db::{
+        block_analysis::BlockAnalysis,
+        block_provenance::{unix_micros, BlockProvenance},
+        gas_bids::{GasBid, SearcherBlockBids},
+        possible_mev_timeline::PossibleMevTimeline,
+        searcher_fingerprint::SearcherFingerprint,
+        searcher_funding::SearcherFunding,
+        searcher_inventory::SearcherInventoryDelta,
+        suppressed_bundle::SuppressedBundle,
+        victim_execution::VictimExecution,
+    },
     execute_on,
     mev::{Bundle, MevBlock, MevType},
+    normalized_actions::{Action, NormalizedAction},
+    traits::TracingProvider,
+    tree::BlockTree,
     BlockData, MultiBlockData,
 };
 use tracing::debug;
 
-use crate::Processor;
+use super::stream;
+use crate::{
+    cli::{SHORT_VERSION, VERGEN_GIT_SHA_LONG},
+    Processor,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MevProcessor;
@@ -30,19 +47,17 @@ pub struct MevProcessor;
 impl Processor for MevProcessor {
     type InspectType = Vec<Bundle>;
 
-    async fn process_results<DB: DBWriter + LibmdbxReader>(
+    async fn process_results<DB: DBWriter + LibmdbxReader, T: TracingProvider>(
         db: &'static DB,
+        tracer: Arc<T>,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
+        anomaly_monitor: SharedAnomalyMonitor,
     ) {
+        let started_at = unix_micros();
         let last = data.get_most_recent_block().clone();
+        // the dex quotes were already written when the block was priced
         let BlockData { metadata, tree } = last;
-        if let Err(e) = db
-            .write_dex_quotes(metadata.block_num, metadata.dex_quotes.clone())
-            .await
-        {
-            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert dex pricing and state into db");
-        }
 
         #[cfg(feature = "local-clickhouse")]
         {
@@ -54,10 +69,122 @@ impl Processor for MevProcessor {
             return
         }
 
-        let ComposerResults { block_details, mev_details, block_analysis, .. } =
-            execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await;
+        let ComposerResults {
+            block_details,
+            mut mev_details,
+            block_analysis,
+            gas_bids,
+            fingerprints,
+            inventory,
+            funding,
+            victim_executions,
+            possible_mev_timeline,
+            suppressed_bundles,
+            inspector_runs,
+            builder_pnl,
+            ..
+        } = execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await;
+
+        attach_liquidation_contexts(&mut mev_details, &tracer, db).await;
+        if is_profit_verification_enabled() {
+            if let Some(inspector) = inspectors.first() {
+                verify_bundle_profits(
+                    &mut mev_details,
+                    &metadata,
+                    inspector.get_quote_token(),
+                    &tracer,
+                    db,
+                )
+                .await;
+            }
+        }
 
-        insert_mev_results(db, block_details, mev_details, block_analysis).await;
+        anomaly_monitor
+            .lock()
+            .unwrap()
+            .observe(block_outputs(&block_details, &mev_details, &tree));
+
+        if stream::has_subscribers() {
+            stream::publish_finished_block(block_details.clone(), mev_details.clone());
+        }
+
+        insert_mev_results(
+            db,
+            block_details,
+            mev_details,
+            block_analysis,
+            gas_bids,
+            fingerprints,
+            inventory,
+            funding,
+            victim_executions,
+            possible_mev_timeline,
+            suppressed_bundles,
+        )
+        .await;
+
+        let provenance = BlockProvenance::new(
+            metadata.block_num,
+            SHORT_VERSION,
+            VERGEN_GIT_SHA_LONG,
+            inspectors.iter().map(|inspector| {
+                (
+                    inspector.get_id().to_string(),
+                    format!("{}:{:?}", inspector.cache_id(), inspector.get_quote_token()),
+                )
+            }),
+            inspector_config_hash(),
+            started_at,
+        )
+        .finish();
+        if let Err(e) = db.write_block_provenance(provenance).await {
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert block provenance into db");
+        }
+        if let Err(e) = db
+            .write_inspector_runs(metadata.block_num, inspector_runs)
+            .await
+        {
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert inspector runs into db");
+        }
+        if let Err(e) = db.write_builder_pnl(builder_pnl).await {
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert builder pnl into db");
+        }
+    }
+}
+
+/// The outputs of the block tracked for anomalies. Coverage is the share of
+/// the actions that emitted logs which were classified, as an unclassified
+/// call that emitted logs is most likely a protocol we failed to decode.
+fn block_outputs(
+    block_details: &MevBlock,
+    mev_details: &[Bundle],
+    tree: &BlockTree<Action>,
+) -> BlockOutputs {
+    let (classified, unclassified) = tree
+        .tx_roots
+        .iter()
+        .flat_map(|root| root.data_store.0.iter().flatten().flatten())
+        .fold((0usize, 0usize), |(classified, unclassified), action| {
+            if action.is_classified() {
+                (classified + 1, unclassified)
+            } else if action.emitted_logs() {
+                (classified, unclassified + 1)
+            } else {
+                (classified, unclassified)
+            }
+        });
+    let unpriced = mev_details
+        .iter()
+        .filter(|bundle| bundle.header.no_pricing_calculated)
+        .count();
+
+    BlockOutputs {
+        block_number:            block_details.block_number,
+        total_mev_usd:           block_details.total_mev_profit_usd,
+        classification_coverage: (classified + unclassified > 0)
+            .then(|| classified as f64 / (classified + unclassified) as f64),
+        pricing_failures:        (!mev_details.is_empty())
+            .then(|| unpriced as f64 / mev_details.len() as f64),
     }
 }
 
@@ -82,6 +209,13 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
     block_details: MevBlock,
     mev_details: Vec<Bundle>,
     analysis: BlockAnalysis,
+    gas_bids: Vec<GasBid>,
+    fingerprints: Vec<SearcherFingerprint>,
+    inventory: Vec<SearcherInventoryDelta>,
+    funding: Vec<(Address, SearcherFunding)>,
+    victim_executions: Vec<VictimExecution>,
+    possible_mev_timeline: Vec<PossibleMevTimeline>,
+    suppressed_bundles: Vec<SuppressedBundle>,
 ) {
     debug!(
         target: "brontes::results",
@@ -110,7 +244,66 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
             block_number
         );
     }
+
+    let block_bids = SearcherBlockBids::from_gas_bids(&gas_bids);
+    update_searcher_bid_behavior(database, &block_bids).await;
+    if let Err(e) = database.write_gas_bids(gas_bids).await {
+        tracing::error!("Failed to insert gas bids into db: {:?} at block: {}", e, block_number);
+    }
+    if let Err(e) = database.write_searcher_block_bids(block_bids).await {
+        tracing::error!(
+            "Failed to insert searcher block bids into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database.write_searcher_fingerprints(fingerprints).await {
+        tracing::error!(
+            "Failed to insert searcher fingerprints into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database.write_searcher_inventory(inventory).await {
+        tracing::error!(
+            "Failed to insert searcher inventory into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database.write_searcher_funding(funding).await {
+        tracing::error!(
+            "Failed to insert searcher funding into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database.write_victim_execution(victim_executions).await {
+        tracing::error!(
+            "Failed to insert victim execution into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database
+        .write_possible_mev_timeline(possible_mev_timeline)
+        .await
+    {
+        tracing::error!(
+            "Failed to insert possible mev timeline into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
+    if let Err(e) = database.write_suppressed_bundles(suppressed_bundles).await {
+        tracing::error!(
+            "Failed to insert suppressed bundles into db: {:?} at block: {}",
+            e,
+            block_number
+        );
+    }
 }
+
 async fn output_mev_and_update_searcher_info<DB: DBWriter + LibmdbxReader>(
     database: &DB,
     mev_details: &Vec<Bundle>,
@@ -149,3 +342,26 @@ async fn output_mev_and_update_searcher_info<DB: DBWriter + LibmdbxReader>(
         }
     }
 }
+
+/// Folds the bids of the block into the bidding behaviour of each searcher
+/// EOA. Runs after the bundles updated the searcher info so that it builds on
+/// the updated info.
+async fn update_searcher_bid_behavior<DB: DBWriter + LibmdbxReader>(
+    database: &DB,
+    block_bids: &[SearcherBlockBids],
+) {
+    for bids in block_bids {
+        let mut eoa_info = match database.try_fetch_searcher_eoa_info(bids.eoa) {
+            Ok(info) => info.unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(eoa = ?bids.eoa, "Failed to fetch searcher info: {:?}", e);
+                continue
+            }
+        };
+        eoa_info.update_with_block_bids(bids);
+
+        if let Err(e) = database.write_searcher_eoa_info(bids.eoa, eoa_info).await {
+            tracing::error!("Failed to update searcher info in the database: {:?}", e);
+        }
+    }
+}
@@ -1,17 +1,27 @@
 pub mod mev;
+pub mod stream;
+pub mod verify;
+
+use std::sync::Arc;
 
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_inspect::Inspector;
-use brontes_types::MultiBlockData;
+use brontes_metrics::anomaly::SharedAnomalyMonitor;
+use brontes_types::{traits::TracingProvider, MultiBlockData};
 use futures::Future;
 pub use mev::*;
+pub use stream::{subscribe_finished_blocks, FinishedBlock};
+pub use verify::*;
 
 pub trait Processor: Send + Sync + 'static + Unpin + Copy + Clone {
     type InspectType: Send + Sync + Unpin;
 
-    fn process_results<DB: DBWriter + LibmdbxReader>(
+    /// `anomaly_monitor` is the monitor of the executor the block belongs to
+    fn process_results<DB: DBWriter + LibmdbxReader, T: TracingProvider>(
         db: &'static DB,
+        tracer: Arc<T>,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
+        anomaly_monitor: SharedAnomalyMonitor,
     ) -> impl Future<Output = ()> + Send;
 }
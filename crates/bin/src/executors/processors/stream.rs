@@ -0,0 +1,37 @@
+//! Live feed of the processed blocks, so services embedding brontes can
+//! consume the results as each block completes instead of polling the
+//! database.
+use std::sync::{Arc, OnceLock};
+
+use brontes_types::mev::{Bundle, MevBlock};
+use tokio::sync::broadcast;
+
+/// Blocks a subscriber can fall behind before it starts missing them
+const STREAM_CAPACITY: usize = 1024;
+
+/// A processed block together with its bundles
+pub type FinishedBlock = Arc<(MevBlock, Vec<Bundle>)>;
+
+static STREAM: OnceLock<broadcast::Sender<FinishedBlock>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<FinishedBlock> {
+    STREAM.get_or_init(|| broadcast::channel(STREAM_CAPACITY).0)
+}
+
+/// Subscribes to the blocks finished after this call. A subscriber that
+/// falls more than [`STREAM_CAPACITY`] blocks behind receives
+/// [`broadcast::error::RecvError::Lagged`] and skips ahead.
+pub fn subscribe_finished_blocks() -> broadcast::Receiver<FinishedBlock> {
+    sender().subscribe()
+}
+
+pub(crate) fn has_subscribers() -> bool {
+    STREAM
+        .get()
+        .is_some_and(|sender| sender.receiver_count() > 0)
+}
+
+pub(crate) fn publish_finished_block(block: MevBlock, bundles: Vec<Bundle>) {
+    // errors only if every receiver was dropped in the meantime
+    let _ = sender().send(Arc::new((block, bundles)));
+}
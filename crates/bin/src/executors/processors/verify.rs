@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_inspect::{
+    composer::{run_block_inspection, ComposerResults},
+    Inspector,
+};
+use brontes_metrics::anomaly::SharedAnomalyMonitor;
+use brontes_types::{
+    db::mev_block::MevBlockWithClassified, execute_on, mev::Bundle, traits::TracingProvider,
+    MultiBlockData,
+};
+
+use crate::Processor;
+
+/// Blocks recomputed since the last call to [`take_recomputed_blocks`]
+static RECOMPUTED: Mutex<Vec<MevBlockWithClassified>> = Mutex::new(Vec::new());
+
+/// Runs the inspectors like the [`MevProcessor`](super::MevProcessor) but
+/// keeps the results in memory instead of writing them to the database, so
+/// that they can be compared against the stored results.
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyProcessor;
+
+impl Processor for VerifyProcessor {
+    type InspectType = Vec<Bundle>;
+
+    async fn process_results<DB: DBWriter + LibmdbxReader, T: TracingProvider>(
+        db: &'static DB,
+        // the stored results are compared on profit only, so the bundles aren't
+        // enriched with on-chain state
+        _tracer: Arc<T>,
+        inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
+        data: MultiBlockData,
+        // the recomputed blocks are compared against the stored ones instead
+        _anomaly_monitor: SharedAnomalyMonitor,
+    ) {
+        // empty blocks are never stored, see `MevProcessor`
+        if data.get_most_recent_block().tree.tx_roots.is_empty() {
+            return
+        }
+
+        let ComposerResults { block_details, mev_details, .. } =
+            execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await;
+
+        RECOMPUTED
+            .lock()
+            .unwrap()
+            .push(MevBlockWithClassified { block: block_details, mev: mev_details });
+    }
+}
+
+/// Drains the blocks recomputed by the [`VerifyProcessor`]
+pub fn take_recomputed_blocks() -> Vec<MevBlockWithClassified> {
+    std::mem::take(&mut *RECOMPUTED.lock().unwrap())
+}
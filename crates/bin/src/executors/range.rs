@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
@@ -10,16 +11,22 @@ use brontes_database::{
     libmdbx::{DBWriter, LibmdbxReader},
 };
 use brontes_inspect::Inspector;
-use brontes_metrics::range::GlobalRangeMetrics;
-use brontes_types::MultiBlockData;
+use brontes_metrics::{
+    anomaly::{BlockAnomalyMonitor, SharedAnomalyMonitor},
+    range::GlobalRangeMetrics,
+};
+use brontes_types::{
+    db::backfill_checkpoint::BackfillCheckpoint, is_deterministic_mode, MultiBlockData,
+};
 use futures::{pin_mut, stream::FuturesUnordered, Future, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
-use tracing::debug;
+use tracing::{debug, error};
 
 use super::shared::state_collector::StateCollector;
 use crate::{executors::ProgressBar, Processor};
 
-type InsertFutures = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+/// Resolves to the block whose results were processed
+type InsertFutures = Pin<Box<dyn Future<Output = u64> + Send + 'static>>;
 
 pub struct RangeExecutorWithPricing<
     T: TracingProvider,
@@ -32,10 +39,12 @@ pub struct RangeExecutorWithPricing<
     insert_futures: FuturesUnordered<InsertFutures>,
     current_block:  u64,
     end_block:      u64,
+    checkpoint:     RangeCheckpoint,
     libmdbx:        &'static DB,
     inspectors:     &'static [&'static dyn Inspector<Result = P::InspectType>],
     progress_bar:   Option<ProgressBar>,
     global_metrics: Option<GlobalRangeMetrics>,
+    anomalies:      SharedAnomalyMonitor,
     _p:             PhantomData<P>,
 }
 
@@ -58,10 +67,12 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             insert_futures: FuturesUnordered::default(),
             current_block: start_block,
             end_block,
+            checkpoint: RangeCheckpoint::new(start_block, end_block),
             libmdbx,
             inspectors,
             progress_bar,
             global_metrics,
+            anomalies: BlockAnomalyMonitor::shared(format!("range_{id}")),
             _p: PhantomData,
         }
     }
@@ -79,16 +90,27 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             },
         }
 
-        while data_batching.insert_futures.next().await.is_some() {
+        while let Some(block) = data_batching.insert_futures.next().await {
             data_batching
                 .global_metrics
                 .as_ref()
                 .inspect(|m| m.finished_block(data_batching.id));
+            data_batching.on_block_processed(block);
         }
 
         drop(graceful_guard);
     }
 
+    /// In deterministic replay mode a block's results are processed before
+    /// the next block is collected
+    fn max_pending_inserts(&self) -> usize {
+        if is_deterministic_mode() {
+            1
+        } else {
+            5
+        }
+    }
+
     fn on_price_finish(&mut self, data: MultiBlockData) {
         debug!(target:"brontes","Completed DEX pricing");
         self.global_metrics
@@ -98,16 +120,44 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         let metrics = self.global_metrics.clone();
         let inspectors = self.inspectors;
         let libmdbx = self.libmdbx;
+        let tracer = self.collector.tracer();
+        let anomalies = self.anomalies.clone();
+        let block = data.get_most_recent_block().block_number();
         self.insert_futures.push(Box::pin(async move {
             if let Some(metrics) = metrics {
                 metrics
-                    .meter_processing(|| Box::pin(P::process_results(libmdbx, inspectors, data)))
+                    .meter_processing(|| {
+                        Box::pin(P::process_results(libmdbx, tracer, inspectors, data, anomalies))
+                    })
                     .await
             } else {
-                P::process_results(libmdbx, inspectors, data).await
+                P::process_results(libmdbx, tracer, inspectors, data, anomalies).await
             }
+            block
         }));
     }
+
+    /// Persists the progress of the range once the processed block extends
+    /// the run of finished blocks from its start
+    fn on_block_processed(&mut self, block: u64) {
+        let Some(checkpoint) = self.checkpoint.finished(block) else { return };
+
+        let start_block = self.checkpoint.start_block;
+        let libmdbx = self.libmdbx;
+        tokio::spawn(async move {
+            if let Err(e) = libmdbx
+                .save_backfill_checkpoint(start_block, checkpoint)
+                .await
+            {
+                error!(
+                    target: "brontes",
+                    err=%e,
+                    start_block,
+                    "failed to save backfill checkpoint"
+                );
+            }
+        });
+    }
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P: Processor> Future
@@ -117,10 +167,10 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
 
     #[brontes_macros::metrics_call(ptr=global_metrics, poll_rate, self.id)]
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if !self.collector.is_collecting_state()
+        if self.collector.can_collect_more()
             && self.collector.should_process_next_block()
             && self.current_block != self.end_block
-            && self.insert_futures.len() < 5
+            && self.insert_futures.len() < self.max_pending_inserts()
         {
             cx.waker().wake_by_ref();
             let block = self.current_block;
@@ -152,11 +202,12 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             }
         }
 
-        while let Poll::Ready(Some(_)) = self.insert_futures.poll_next_unpin(cx) {
+        while let Poll::Ready(Some(block)) = self.insert_futures.poll_next_unpin(cx) {
             self.global_metrics.as_ref().inspect(|m| {
                 m.dec_inspector(self.id);
                 m.finished_block(self.id);
             });
+            self.on_block_processed(block);
         }
 
         // mark complete if we are done with the range
@@ -170,3 +221,41 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         Poll::Pending
     }
 }
+
+/// Tracks which blocks of the range are done. Blocks finish out of order, so
+/// only the run of finished blocks from the start of the range is checkpointed,
+/// a restart then never skips a block that wasn't processed.
+struct RangeCheckpoint {
+    start_block: u64,
+    end_block:   u64,
+    next_block:  u64,
+    /// Finished blocks past `next_block`
+    finished:    BTreeSet<u64>,
+}
+
+impl RangeCheckpoint {
+    fn new(start_block: u64, end_block: u64) -> Self {
+        Self { start_block, end_block, next_block: start_block, finished: BTreeSet::new() }
+    }
+
+    /// Marks the block as finished, returning the new checkpoint if the
+    /// progress of the range moved forward
+    fn finished(&mut self, block: u64) -> Option<BackfillCheckpoint> {
+        if block < self.next_block {
+            return None
+        }
+        self.finished.insert(block);
+
+        let prev = self.next_block;
+        while self.finished.remove(&self.next_block) {
+            self.next_block += 1;
+        }
+
+        // a processed block has been priced
+        (self.next_block != prev).then_some(BackfillCheckpoint {
+            end_block:    self.end_block,
+            next_block:   self.next_block,
+            priced_block: self.next_block,
+        })
+    }
+}
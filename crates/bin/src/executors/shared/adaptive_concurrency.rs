@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the smoothed tracing latency
+const LATENCY_SMOOTHING: f64 = 0.2;
+/// The in-flight block count grows while the smoothed latency stays within
+/// this factor of the baseline
+const LATENCY_TOLERANCE: f64 = 1.5;
+/// Past this factor of the baseline the node is considered overloaded and the
+/// in-flight block count is halved
+const OVERLOAD_FACTOR: f64 = 3.0;
+/// The baseline drifts up by this factor per block so a single fast block
+/// doesn't pin it forever
+const BASELINE_DRIFT: f64 = 1.01;
+/// Below this share of available memory, the in-flight block count is halved
+const MIN_AVAILABLE_MEMORY: f64 = 0.1;
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tunes how many blocks are traced at once. The count grows by one per
+/// block while the node answers as fast as it does unloaded and nothing
+/// downstream is backed up, and is halved when the tracing latency spikes or
+/// the system runs low on memory.
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    min:               usize,
+    max:               usize,
+    limit:             usize,
+    /// Smoothed tracing latency of a block in seconds
+    latency:           Option<f64>,
+    /// Lowest smoothed latency seen, the latency of the node when unloaded
+    baseline:          Option<f64>,
+    memory_pressure:   bool,
+    last_memory_check: Option<Instant>,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        Self {
+            min,
+            max: max.max(min),
+            limit: min,
+            latency: None,
+            baseline: None,
+            memory_pressure: false,
+            last_memory_check: None,
+        }
+    }
+
+    /// Always traces `limit` blocks at once
+    pub fn fixed(limit: usize) -> Self {
+        Self::new(limit, limit)
+    }
+
+    /// Max amount of blocks to trace at once
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Updates the limit with the tracing latency of a block and the amount
+    /// of blocks waiting on metadata and pricing
+    pub fn on_block_traced(&mut self, latency: Duration, queue_depth: usize, max_queue: usize) {
+        if self.min == self.max {
+            return
+        }
+
+        let sample = latency.as_secs_f64();
+        let latency = self
+            .latency
+            .map_or(sample, |prev| prev + LATENCY_SMOOTHING * (sample - prev));
+        let baseline = self
+            .baseline
+            .map_or(latency, |baseline| (baseline * BASELINE_DRIFT).min(latency));
+        self.latency = Some(latency);
+        self.baseline = Some(baseline);
+        self.check_memory();
+
+        let prev = self.limit;
+        if self.memory_pressure || latency > baseline * OVERLOAD_FACTOR {
+            self.limit = (self.limit / 2).max(self.min);
+        } else if queue_depth < max_queue && latency <= baseline * LATENCY_TOLERANCE {
+            self.limit = (self.limit + 1).min(self.max);
+        }
+
+        if prev != self.limit {
+            tracing::debug!(
+                target: "brontes::executors",
+                limit = self.limit,
+                latency_ms = latency * 1000.0,
+                baseline_ms = baseline * 1000.0,
+                queue_depth,
+                memory_pressure = self.memory_pressure,
+                "adjusted blocks in flight"
+            );
+        }
+    }
+
+    fn check_memory(&mut self) {
+        if self
+            .last_memory_check
+            .is_some_and(|last| last.elapsed() < MEMORY_CHECK_INTERVAL)
+        {
+            return
+        }
+
+        self.last_memory_check = Some(Instant::now());
+        self.memory_pressure =
+            available_memory_share().is_some_and(|available| available < MIN_AVAILABLE_MEMORY);
+    }
+}
+
+/// Share of the system memory that is available, `None` if it can't be read
+fn available_memory_share() -> Option<f64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let read = |key: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse::<f64>()
+            .ok()
+    };
+
+    let total = read("MemTotal:")?;
+    let available = read("MemAvailable:")?;
+    (total > 0.0).then(|| available / total)
+}
@@ -0,0 +1,109 @@
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use brontes_core::decoding::{Parser, TracingProvider};
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_metrics::range::TipMetrics;
+use futures::{Future, FutureExt, Stream};
+use tokio::time::{interval, Interval};
+
+/// Notification that a block has `confirmations` blocks built on top of it,
+/// and so can be processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalBlock {
+    pub number:    u64,
+    pub chain_tip: u64,
+}
+
+/// Follows the chain tip by polling the tracing provider for its best block.
+/// Yields a notification for every block once it is `confirmations` deep, in
+/// order and without gaps, so a tip that jumps several blocks between polls
+/// still has all of its blocks processed. The provider is only queried once
+/// all confirmed blocks were yielded.
+pub struct ChainTipFollower<T: TracingProvider, DB: LibmdbxReader + DBWriter> {
+    parser:        &'static Parser<T, DB>,
+    confirmations: u64,
+    next_block:    u64,
+    chain_tip:     u64,
+    poll_interval: Interval,
+    /// the in flight query for the best block
+    best_block:    Option<Pin<Box<dyn Future<Output = eyre::Result<u64>> + Send>>>,
+    metrics:       TipMetrics,
+}
+
+impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> ChainTipFollower<T, DB> {
+    pub fn new(
+        parser: &'static Parser<T, DB>,
+        start_block: u64,
+        confirmations: u64,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            parser,
+            confirmations,
+            next_block: start_block,
+            chain_tip: 0,
+            poll_interval: interval(poll_interval),
+            best_block: None,
+            metrics: TipMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &TipMetrics {
+        &self.metrics
+    }
+
+    fn confirmed_block(&self) -> u64 {
+        self.chain_tip.saturating_sub(self.confirmations)
+    }
+
+    #[cfg(feature = "local-reth")]
+    fn query_best_block(&self) -> Pin<Box<dyn Future<Output = eyre::Result<u64>> + Send>> {
+        Box::pin(std::future::ready(self.parser.get_latest_block_number()))
+    }
+
+    #[cfg(not(feature = "local-reth"))]
+    fn query_best_block(&self) -> Pin<Box<dyn Future<Output = eyre::Result<u64>> + Send>> {
+        let parser = self.parser;
+        Box::pin(async move { parser.get_latest_block_number().await })
+    }
+
+    fn on_best_block(&mut self, best_block: u64) {
+        // a reorg to a shorter chain can lower the tip, the blocks that were
+        // yielded are checked against the canonical hashes by the reorg tracker
+        self.chain_tip = best_block;
+        self.metrics.chain_tip.set(best_block as f64);
+    }
+}
+
+impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> Stream for ChainTipFollower<T, DB> {
+    type Item = CanonicalBlock;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.next_block <= self.confirmed_block() {
+                let block =
+                    CanonicalBlock { number: self.next_block, chain_tip: self.chain_tip };
+                self.next_block += 1;
+
+                return Poll::Ready(Some(block))
+            }
+
+            if let Some(best_block) = self.best_block.as_mut() {
+                let res = ready!(best_block.poll_unpin(cx));
+                self.best_block = None;
+                match res {
+                    Ok(best_block) => self.on_best_block(best_block),
+                    Err(e) => tracing::error!(error = %e, "failed to fetch the chain tip"),
+                }
+                continue
+            }
+
+            ready!(self.poll_interval.poll_tick(cx));
+            self.best_block = Some(self.query_best_block());
+        }
+    }
+}
@@ -8,13 +8,19 @@ use brontes_core::decoding::TracingProvider;
 use brontes_pricing::BrontesBatchPricer;
 use brontes_types::{
     constants::START_OF_CHAINBOUND_MEMPOOL_DATA,
-    db::{dex::DexQuotes, metadata::Metadata},
+    db::{
+        backfill_checkpoint::BackfillCheckpoint, dex::DexQuotes, metadata::Metadata,
+        traits::DBWriter,
+    },
     normalized_actions::Action,
     tree::BlockTree,
     BrontesTaskExecutor, FastHashMap, FastHashSet,
 };
 use futures::{Stream, StreamExt};
-use tokio::sync::mpsc::{channel, error::TrySendError, Receiver, Sender};
+use tokio::sync::mpsc::{
+    channel, error::TrySendError, unbounded_channel, Receiver, Sender, UnboundedReceiver,
+    UnboundedSender,
+};
 use tracing::{debug, span, Instrument, Level};
 
 pub type PricingReceiver<T> = Receiver<(BrontesBatchPricer<T>, Option<(u64, DexQuotes)>)>;
@@ -29,6 +35,9 @@ pub struct WaitingForPricerFuture<T: TracingProvider> {
     // trees and cause memory overflows
     pub tmp_trees:            FastHashSet<u64>,
     task_executor:            BrontesTaskExecutor,
+    /// Hands the quotes of every priced block to the flush task, so that they
+    /// are persisted without waiting for the block to be inspected
+    flush_tx:                 Option<UnboundedSender<(u64, DexQuotes)>>,
 }
 
 impl<T: TracingProvider> WaitingForPricerFuture<T> {
@@ -44,6 +53,54 @@ impl<T: TracingProvider> WaitingForPricerFuture<T> {
             tx,
             receiver: rx,
             tmp_trees: FastHashSet::default(),
+            flush_tx: None,
+        }
+    }
+
+    /// Writes the quotes of each block to the db as soon as the block is
+    /// priced. If the pricer runs for a backfill range, `range` is the start
+    /// and end of the range and the first block without persisted quotes is
+    /// checkpointed after each write, so that a restarted range doesn't price
+    /// these blocks again.
+    pub fn with_quote_flushing<DB: DBWriter + Sync>(
+        mut self,
+        db: &'static DB,
+        range: Option<(u64, u64)>,
+    ) -> Self {
+        let (tx, rx) = unbounded_channel();
+        self.task_executor
+            .spawn_critical("dex quote flush", Self::flush_quotes(db, rx, range));
+        self.flush_tx = Some(tx);
+
+        self
+    }
+
+    /// Runs as a single task so that the quotes are written in block order and
+    /// the checkpoint never gets ahead of the written quotes. Once a write
+    /// fails the range isn't checkpointed anymore, as the block would
+    /// otherwise be skipped on restart.
+    async fn flush_quotes<DB: DBWriter + Sync>(
+        db: &'static DB,
+        mut rx: UnboundedReceiver<(u64, DexQuotes)>,
+        mut range: Option<(u64, u64)>,
+    ) {
+        while let Some((block, quotes)) = rx.recv().await {
+            if let Err(e) = db.write_dex_quotes(block, Some(quotes)).await {
+                tracing::error!(err=%e, block_num=block, "failed to insert dex pricing and state into db");
+                range = None;
+                continue
+            }
+
+            let Some((start_block, end_block)) = range else { continue };
+            let checkpoint =
+                BackfillCheckpoint { end_block, next_block: start_block, priced_block: block + 1 };
+            // the save waits for the buffered clickhouse inserts, so it doesn't
+            // hold back the next block's quotes
+            tokio::spawn(async move {
+                if let Err(e) = db.save_backfill_checkpoint(start_block, checkpoint).await {
+                    tracing::error!(err=%e, start_block, "failed to save dex pricing checkpoint");
+                }
+            });
         }
     }
 
@@ -129,6 +186,12 @@ impl<T: TracingProvider> Stream for WaitingForPricerFuture<T> {
                     tree.label_private_txes(&meta);
                 }
 
+                if let Some(flush_tx) = self.flush_tx.as_ref() {
+                    if flush_tx.send((block, prices.clone())).is_err() {
+                        tracing::error!(?block, "dex quote flush task exited");
+                    }
+                }
+
                 let finalized_meta = meta.into_full_metadata(prices);
 
                 return Poll::Ready(Some((tree, finalized_meta)))
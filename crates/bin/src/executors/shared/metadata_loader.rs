@@ -13,7 +13,7 @@ use alloy_primitives::Address;
 use brontes_database::clickhouse::ClickhouseHandle;
 use brontes_types::{
     db::{
-        cex::trades::{window_loader::CexWindow, CexTradeMap},
+        cex::trades::{window_loader::CexWindow, CexTradeMap, VenueLatencies},
         dex::DexQuotes,
         metadata::Metadata,
         traits::{DBWriter, LibmdbxReader},
@@ -30,7 +30,7 @@ use super::dex_pricing::WaitingForPricerFuture;
 /// Limits the amount we work ahead in the processing. This is done
 /// as the Pricer is a slow process and otherwise we will end up caching 100+ gb
 /// of processed trees
-const MAX_PENDING_TREES: usize = 5;
+pub const MAX_PENDING_TREES: usize = 5;
 
 pub type ClickhouseMetadataFuture =
     FuturesOrdered<Pin<Box<dyn Future<Output = (u64, BlockTree<Action>, Metadata)> + Send>>>;
@@ -43,8 +43,12 @@ pub struct MetadataLoader<T: TracingProvider, CH: ClickhouseHandle> {
     result_buf:            VecDeque<BlockData>,
     needs_more_data:       Arc<AtomicBool>,
     cex_window_data:       CexWindow,
+    /// Latency of each cex venue, applied to the trades once they are loaded
+    venue_latencies:       VenueLatencies,
     always_generate_price: bool,
     force_no_dex_pricing:  bool,
+    /// Blocks before this one had their quotes persisted by a previous run
+    priced_until:          u64,
 }
 
 impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
@@ -58,6 +62,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     ) -> Self {
         Self {
             cex_window_data: CexWindow::new(cex_window_sec),
+            venue_latencies: VenueLatencies::default(),
             clickhouse,
             dex_pricer_stream,
             needs_more_data,
@@ -65,15 +70,36 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
             result_buf: VecDeque::new(),
             always_generate_price,
             force_no_dex_pricing,
+            priced_until: 0,
         }
     }
 
+    /// Loads the stored quotes of the blocks before `block` instead of pricing
+    /// them, even if the block had no quotes
+    pub fn with_priced_until(mut self, block: u64) -> Self {
+        self.priced_until = block;
+        self
+    }
+
+    /// Shifts the cex trades of each venue by its latency before they are
+    /// handed to the inspectors with the metadata of the block
+    pub fn with_venue_latencies(mut self, venue_latencies: VenueLatencies) -> Self {
+        self.cex_window_data = self.cex_window_data.with_venue_latencies(venue_latencies);
+        self.venue_latencies = venue_latencies;
+        self
+    }
+
     pub fn should_process_next_block(&self) -> bool {
         self.needs_more_data.load(Ordering::SeqCst)
             && self.dex_pricer_stream.pending_trees() < MAX_PENDING_TREES
             && self.result_buf.len() < MAX_PENDING_TREES
     }
 
+    /// Trees waiting on their metadata or dex pricing
+    pub fn pending_trees(&self) -> usize {
+        self.dex_pricer_stream.pending_trees() + self.result_buf.len()
+    }
+
     pub fn is_finished(&self) -> bool {
         self.result_buf.is_empty()
             && self.dex_pricer_stream.is_done()
@@ -87,10 +113,11 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     ) -> bool {
         !self.force_no_dex_pricing
             && (self.always_generate_price
-                || libmdbx
-                    .get_dex_quotes(block)
-                    .map(|f| f.0.is_empty())
-                    .unwrap_or(true))
+                || (block >= self.priced_until
+                    && libmdbx
+                        .get_dex_quotes(block)
+                        .map(|f| f.0.is_empty())
+                        .unwrap_or(true)))
     }
 
     //TODO: remove unecessary dex pricing query
@@ -247,6 +274,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
         // given every download is -6 + 6 around the block
         // we calculate the offset from the current block that we need
         let offsets = (window / 12) as u64;
+        let venue_latencies = self.venue_latencies;
         let future = Box::pin(async move {
             let builder_info = libmdbx
                 .try_fetch_builder_info(tree.header.beneficiary)
@@ -281,6 +309,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
                     for range in ranges.into_iter().sorted_unstable_by_key(|k| k.key) {
                         trades.merge_in_map(range.value);
                     }
+                    trades.apply_latency(&venue_latencies);
 
                     break trades
                 } else {
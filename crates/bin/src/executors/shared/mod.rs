@@ -1,4 +1,7 @@
+pub mod adaptive_concurrency;
+pub mod chain_tip;
 pub mod dex_pricing;
 pub mod metadata_loader;
 pub mod multi_block_window;
+pub mod reorg;
 pub mod state_collector;
@@ -27,4 +27,10 @@ impl MultiBlockWindow {
 
         MultiBlockData { blocks: block_count, per_block_data: block_data }
     }
+
+    /// Drops the cached blocks from the given block onwards
+    pub fn drop_blocks_from(&mut self, block: u64) {
+        self.block_window_queue
+            .retain(|data| data.tree.header.number < block);
+    }
 }
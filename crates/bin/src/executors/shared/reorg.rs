@@ -0,0 +1,193 @@
+use std::{collections::VecDeque, future::Future, ops::RangeInclusive};
+
+use alloy_primitives::B256;
+use brontes_core::decoding::Parser;
+use brontes_types::{
+    db::traits::{DBWriter, LibmdbxReader},
+    traits::TracingProvider,
+};
+use eyre::eyre;
+
+/// Amount of blocks behind the tip whose hashes are kept. Reorgs deeper than
+/// this can't be fully reverted
+const REORG_WINDOW: usize = 64;
+
+/// Follows the hashes of the blocks started at the tip, so that a block whose
+/// parent isn't the block brontes processed reveals a reorg.
+#[derive(Debug, Default)]
+pub struct ReorgTracker {
+    hashes: VecDeque<(u64, B256)>,
+}
+
+impl ReorgTracker {
+    /// Records the canonical hash of the block that is about to be started.
+    /// If its parent hash doesn't match the hash recorded for the previous
+    /// block, returns the blocks that were reorged out.
+    pub async fn check_block<T: TracingProvider, DB: LibmdbxReader + DBWriter>(
+        mut self,
+        parser: &'static Parser<T, DB>,
+        block: u64,
+    ) -> (Self, eyre::Result<Option<RangeInclusive<u64>>>) {
+        let header = match parser.get_tracer().header_by_number(block).await {
+            Ok(Some(header)) => header,
+            Ok(None) => return (self, Err(eyre!("no header found for block {block}"))),
+            Err(e) => return (self, Err(e)),
+        };
+
+        let res = self
+            .check_header(block, header.parent_hash, header.hash_slow(), |block| {
+                parser.get_block_hash_for_number(block)
+            })
+            .await;
+
+        (self, res)
+    }
+
+    async fn check_header<F, Fut>(
+        &mut self,
+        block: u64,
+        parent_hash: B256,
+        hash: B256,
+        canonical_hash: F,
+    ) -> eyre::Result<Option<RangeInclusive<u64>>>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = eyre::Result<Option<B256>>>,
+    {
+        let reorged = match self.hashes.back() {
+            Some(&(prev, prev_hash)) if prev + 1 == block && prev_hash != parent_hash => {
+                self.stale_blocks(canonical_hash).await?
+            }
+            _ => None,
+        };
+
+        // the block is only recorded once it is started, which it isn't on a
+        // reorg until the stale blocks are reverted
+        if reorged.is_none() {
+            self.record(block, hash);
+        }
+
+        Ok(reorged)
+    }
+
+    fn record(&mut self, block: u64, hash: B256) {
+        self.hashes.retain(|(recorded, _)| *recorded < block);
+        self.hashes.push_back((block, hash));
+
+        if self.hashes.len() > REORG_WINDOW {
+            self.hashes.pop_front();
+        }
+    }
+
+    /// Walks back from the most recent block until a recorded hash that is
+    /// still canonical is found, dropping the stale ones on the way
+    async fn stale_blocks<F, Fut>(
+        &mut self,
+        mut canonical_hash: F,
+    ) -> eyre::Result<Option<RangeInclusive<u64>>>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = eyre::Result<Option<B256>>>,
+    {
+        let Some(&(newest, _)) = self.hashes.back() else { return Ok(None) };
+
+        let mut oldest_stale = None;
+        while let Some(&(block, hash)) = self.hashes.back() {
+            if canonical_hash(block).await? == Some(hash) {
+                break
+            }
+
+            self.hashes.pop_back();
+            oldest_stale = Some(block);
+        }
+
+        if self.hashes.is_empty() {
+            tracing::warn!(
+                %newest,
+                "reorg is deeper than the tracked window, older blocks might be stale"
+            );
+        }
+
+        Ok(oldest_stale.map(|oldest| oldest..=newest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::FastHashMap;
+    use futures::{executor::block_on, future::ready};
+
+    use super::*;
+
+    fn hash(block: u64, fork: u8) -> B256 {
+        let mut hash = B256::with_last_byte(fork);
+        hash[..8].copy_from_slice(&block.to_be_bytes());
+        hash
+    }
+
+    /// Starts the blocks of the fork, checking each against the fork's parent
+    fn follow(
+        tracker: &mut ReorgTracker,
+        blocks: RangeInclusive<u64>,
+        fork: u8,
+        canonical: &FastHashMap<u64, B256>,
+    ) -> Option<RangeInclusive<u64>> {
+        blocks
+            .map(|block| {
+                block_on(
+                    tracker.check_header(
+                        block,
+                        canonical
+                            .get(&(block - 1))
+                            .copied()
+                            .unwrap_or_else(|| hash(block - 1, fork)),
+                        hash(block, fork),
+                        |block| ready(Ok(canonical.get(&block).copied())),
+                    ),
+                )
+                .unwrap()
+            })
+            .find(Option::is_some)
+            .flatten()
+    }
+
+    #[test]
+    fn test_no_reorg() {
+        let mut tracker = ReorgTracker::default();
+        let canonical = (10..=20).map(|block| (block, hash(block, 0))).collect();
+
+        assert_eq!(follow(&mut tracker, 10..=20, 0, &canonical), None);
+        assert_eq!(tracker.hashes.len(), 11);
+    }
+
+    #[test]
+    fn test_reorg_returns_stale_blocks() {
+        let mut tracker = ReorgTracker::default();
+        let mut canonical: FastHashMap<_, _> =
+            (10..=15).map(|block| (block, hash(block, 0))).collect();
+        assert_eq!(follow(&mut tracker, 10..=15, 0, &canonical), None);
+
+        // blocks 13 to 15 were replaced
+        canonical.extend((13..=16).map(|block| (block, hash(block, 1))));
+        assert_eq!(follow(&mut tracker, 16..=16, 1, &canonical), Some(13..=15));
+        assert_eq!(tracker.hashes.back(), Some(&(12, hash(12, 0))));
+
+        // the canonical blocks are started from the first stale one
+        assert_eq!(follow(&mut tracker, 13..=16, 1, &canonical), None);
+        assert_eq!(tracker.hashes.back(), Some(&(16, hash(16, 1))));
+    }
+
+    #[test]
+    fn test_reorg_deeper_than_window() {
+        let mut tracker = ReorgTracker::default();
+        let mut canonical: FastHashMap<_, _> =
+            (0..=100).map(|block| (block, hash(block, 0))).collect();
+        assert_eq!(follow(&mut tracker, 1..=100, 0, &canonical), None);
+        assert_eq!(tracker.hashes.len(), REORG_WINDOW);
+
+        canonical.extend((0..=101).map(|block| (block, hash(block, 1))));
+        let oldest_tracked = 101 - REORG_WINDOW as u64;
+        assert_eq!(follow(&mut tracker, 101..=101, 1, &canonical), Some(oldest_tracked..=100));
+        assert!(tracker.hashes.is_empty());
+    }
+}
@@ -1,34 +1,56 @@
 use std::{
+    collections::VecDeque,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering::SeqCst},
         Arc,
     },
     task::{Poll, Waker},
+    time::{Duration, Instant},
 };
 
 use alloy_primitives::Address;
 use brontes_classifier::Classifier;
-use brontes_core::decoding::Parser;
+use brontes_core::{decoding::Parser, relay_data::relay_data};
 use brontes_database::clickhouse::ClickhouseHandle;
 use brontes_metrics::range::GlobalRangeMetrics;
 use brontes_types::{
     db::traits::{DBWriter, LibmdbxReader},
     normalized_actions::Action,
+    order_flow::SubmissionChannels,
     structured_trace::TxTrace,
     traits::TracingProvider,
     BlockTree, MultiBlockData,
 };
 use eyre::eyre;
-use futures::{Future, FutureExt, Stream, StreamExt};
+use futures::{stream::FuturesOrdered, Future, FutureExt, Stream, StreamExt};
 use reth_primitives::Header;
 use tracing::{span, trace, Instrument, Level};
 
-use super::{metadata_loader::MetadataLoader, multi_block_window::MultiBlockWindow};
+use super::{
+    adaptive_concurrency::AdaptiveConcurrency,
+    metadata_loader::{MetadataLoader, MAX_PENDING_TREES},
+    multi_block_window::MultiBlockWindow,
+};
 
-type CollectionFut<'a> = Pin<Box<dyn Future<Output = eyre::Result<BlockTree<Action>>> + Send + 'a>>;
+type TracingFut<'a> = Pin<Box<dyn Future<Output = eyre::Result<TracedBlock>> + Send + 'a>>;
+type ClassificationFut<'a> =
+    Pin<Box<dyn Future<Output = eyre::Result<BlockTree<Action>>> + Send + 'a>>;
 type ExecutionFut<'a> = Pin<Box<dyn Future<Output = Option<(Vec<TxTrace>, Header)>> + Send + 'a>>;
 
+/// A traced block waiting to be classified
+struct TracedBlock {
+    block:               u64,
+    traces:              Vec<TxTrace>,
+    header:              Header,
+    generate_pricing:    bool,
+    id:                  usize,
+    metrics:             Option<GlobalRangeMetrics>,
+    latency:             Duration,
+    /// Queried from the relay data while the block was traced
+    submission_channels: Option<SubmissionChannels>,
+}
+
 pub struct StateCollector<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle> {
     mark_as_finished: Arc<AtomicBool>,
     metadata_fetcher: MetadataLoader<T, CH>,
@@ -36,9 +58,19 @@ pub struct StateCollector<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH:
     parser:           &'static Parser<T, DB>,
     db:               &'static DB,
 
-    collection_future: Option<CollectionFut<'static>>,
-    multi_block:       MultiBlockWindow,
-    quote_asset:       Address,
+    /// blocks being traced, in block order
+    tracing_futures: FuturesOrdered<TracingFut<'static>>,
+    /// traced blocks waiting to be classified, in block order
+    traced:          VecDeque<TracedBlock>,
+    /// Classification sends the pool updates of the block to the pricer, which
+    /// has to get them in block order, so blocks are classified one at a time
+    classifying:     Option<ClassificationFut<'static>>,
+    concurrency:     AdaptiveConcurrency,
+    multi_block:     MultiBlockWindow,
+    quote_asset:     Address,
+    /// Last block of the range, the relay data is fetched up to it. `None` at
+    /// the tip
+    last_block:      Option<u64>,
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
@@ -52,6 +84,8 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
         db: &'static DB,
         multi_block: MultiBlockWindow,
         quote_asset: Address,
+        concurrency: AdaptiveConcurrency,
+        last_block: Option<u64>,
     ) -> Self {
         Self {
             mark_as_finished,
@@ -59,9 +93,13 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
             classifier,
             parser,
             db,
-            collection_future: None,
+            tracing_futures: FuturesOrdered::new(),
+            traced: VecDeque::new(),
+            classifying: None,
+            concurrency,
             multi_block,
             quote_asset,
+            last_block,
         }
     }
 
@@ -69,30 +107,96 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
         self.mark_as_finished.clone()
     }
 
+    /// Blocks being traced or classified
+    fn in_flight(&self) -> usize {
+        self.tracing_futures.len() + self.traced.len() + self.classifying.is_some() as usize
+    }
+
     pub fn is_collecting_state(&self) -> bool {
-        self.collection_future.is_some()
+        self.in_flight() != 0
+    }
+
+    /// Whether another block can be traced alongside the ones in flight
+    pub fn can_collect_more(&self) -> bool {
+        self.in_flight() < self.concurrency.limit()
+    }
+
+    /// Whether no block is being traced, priced or waiting on its metadata
+    pub fn is_idle(&self) -> bool {
+        self.in_flight() == 0 && self.metadata_fetcher.is_finished()
+    }
+
+    pub fn tracer(&self) -> Arc<T> {
+        self.parser.get_tracer()
+    }
+
+    /// Takes the block window without the blocks from `block` onwards, which
+    /// were reorged out. It is handed to the state collector that continues
+    /// from `block` with [`set_window`](Self::set_window).
+    pub fn take_window_before(&mut self, block: u64) -> MultiBlockWindow {
+        let size = self.multi_block.block_window_size;
+        let mut window = std::mem::replace(&mut self.multi_block, MultiBlockWindow::new(size));
+        window.drop_blocks_from(block);
+
+        window
+    }
+
+    pub fn set_window(&mut self, window: MultiBlockWindow) {
+        self.multi_block = window;
     }
 
     pub fn should_process_next_block(&self) -> bool {
         self.metadata_fetcher.should_process_next_block()
     }
 
-    async fn state_future(
+    /// Traces the block and queries its relay data alongside, so that
+    /// classifying the blocks in order doesn't wait on the relays
+    async fn tracing_future(
         generate_pricing: bool,
         block: u64,
+        last_block: u64,
         fut: ExecutionFut<'static>,
         classifier: &'static Classifier<'static, T, DB>,
         id: usize,
         metrics: Option<GlobalRangeMetrics>,
-    ) -> eyre::Result<BlockTree<Action>> {
-        let Some((traces, header)) = fut.await else {
+    ) -> eyre::Result<TracedBlock> {
+        let started = Instant::now();
+        let tracing = fut.map(|res| (res, started.elapsed()));
+        let submission_channels = async {
+            match relay_data() {
+                Some(relay_data) => Some(relay_data.submission_channels(block, last_block).await),
+                None => None,
+            }
+        };
+        let ((res, latency), submission_channels) = futures::join!(tracing, submission_channels);
+
+        let Some((traces, header)) = res else {
             classifier.block_load_failure(block);
             return Err(eyre!("no traces found {block}"))
         };
-
         trace!("Got {} traces + header", traces.len());
 
-        let res = if let Some(metrics) = metrics {
+        Ok(TracedBlock {
+            block,
+            traces,
+            header,
+            generate_pricing,
+            id,
+            metrics,
+            latency,
+            submission_channels,
+        })
+    }
+
+    async fn classification_future(
+        traced: TracedBlock,
+        classifier: &'static Classifier<'static, T, DB>,
+    ) -> eyre::Result<BlockTree<Action>> {
+        let TracedBlock {
+            traces, header, generate_pricing, id, metrics, submission_channels, ..
+        } = traced;
+
+        let mut res = if let Some(metrics) = metrics {
             metrics.add_pending_tree(id);
             metrics
                 .tree_builder(id, || {
@@ -103,13 +207,17 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
                     )))
                 })
                 .await
-                .unwrap()
+                .unwrap()?
         } else {
             tokio::spawn(classifier.build_block_tree(traces, header, generate_pricing))
                 .await
-                .unwrap()
+                .unwrap()?
         };
 
+        if let Some(channels) = submission_channels {
+            res.label_submission_channels(&channels);
+        }
+
         Ok(res)
     }
 
@@ -117,12 +225,46 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
         let execute_fut = self.parser.execute(block, id, metrics.clone());
 
         let generate_pricing = self.metadata_fetcher.generate_dex_pricing(block, self.db);
-        self.collection_future = Some(Box::pin(
-            Self::state_future(generate_pricing, block, execute_fut, self.classifier, id, metrics)
-                .instrument(span!(Level::ERROR, "mev processor", block_number=%block)),
+        let last_block = self.last_block.unwrap_or(block);
+        self.tracing_futures.push_back(Box::pin(
+            Self::tracing_future(
+                generate_pricing,
+                block,
+                last_block,
+                execute_fut,
+                self.classifier,
+                id,
+                metrics,
+            )
+            .instrument(span!(Level::ERROR, "mev processor", block_number=%block)),
         ))
     }
 
+    /// Classifies the next traced block once the previous one is classified
+    fn poll_classification(&mut self, cx: &mut std::task::Context<'_>) -> eyre::Result<()> {
+        loop {
+            if self.classifying.is_none() {
+                let Some(traced) = self.traced.pop_front() else { return Ok(()) };
+                let block = traced.block;
+                self.classifying = Some(Box::pin(
+                    Self::classification_future(traced, self.classifier)
+                        .instrument(span!(Level::ERROR, "mev processor", block_number=%block)),
+                ));
+            }
+
+            let Poll::Ready(result) = self.classifying.as_mut().unwrap().poll_unpin(cx) else {
+                return Ok(())
+            };
+            self.classifying = None;
+
+            let tree = result?;
+            let db = self.db;
+            let quote_asset = self.quote_asset;
+            self.metadata_fetcher
+                .load_metadata_for_tree(tree, db, quote_asset);
+        }
+    }
+
     pub fn range_finished(&self, waker: &Waker) {
         if !self.mark_as_finished.swap(true, SeqCst) {
             waker.wake_by_ref();
@@ -139,29 +281,32 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle> Str
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        if let Some(mut collection_future) = self.collection_future.take() {
-            match collection_future.poll_unpin(cx) {
-                Poll::Ready(Ok(tree)) => {
-                    let db = self.db;
-                    let quote_asset = self.quote_asset;
-                    self.metadata_fetcher
-                        .load_metadata_for_tree(tree, db, quote_asset);
-
-                    cx.waker().wake_by_ref();
+        while let Poll::Ready(Some(result)) = self.tracing_futures.poll_next_unpin(cx) {
+            match result {
+                Ok(traced) => {
+                    let queue_depth = self.metadata_fetcher.pending_trees() + self.traced.len();
+                    self.concurrency.on_block_traced(
+                        traced.latency,
+                        queue_depth,
+                        MAX_PENDING_TREES,
+                    );
+                    self.traced.push_back(traced);
                 }
-                Poll::Ready(Err(e)) => {
+                Err(e) => {
                     tracing::error!(error = %e, "state collector");
                     return Poll::Ready(None)
                 }
-                Poll::Pending => {
-                    self.collection_future = Some(collection_future);
-                }
             }
         }
 
+        if let Err(e) = self.poll_classification(cx) {
+            tracing::error!(error = %e, "state collector");
+            return Poll::Ready(None)
+        }
+
         if self.mark_as_finished.load(SeqCst)
             && self.metadata_fetcher.is_finished()
-            && self.collection_future.is_none()
+            && self.in_flight() == 0
         {
             return Poll::Ready(None)
         }
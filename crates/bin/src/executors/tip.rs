@@ -1,8 +1,9 @@
 use std::{
     marker::PhantomData,
+    ops::RangeInclusive,
     pin::Pin,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use brontes_core::decoding::{Parser, TracingProvider};
@@ -11,30 +12,57 @@ use brontes_database::{
     libmdbx::{DBWriter, LibmdbxReader},
 };
 use brontes_inspect::Inspector;
+use brontes_metrics::{
+    anomaly::{BlockAnomalyMonitor, SharedAnomalyMonitor},
+    range::TipMetrics,
+};
 use brontes_types::MultiBlockData;
-use futures::{pin_mut, stream::FuturesUnordered, Future, StreamExt};
+use futures::{future::BoxFuture, pin_mut, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
 use tokio::time::{interval, Interval};
 use tracing::debug;
 
-use super::shared::state_collector::StateCollector;
+use super::shared::{
+    chain_tip::ChainTipFollower, reorg::ReorgTracker, state_collector::StateCollector,
+};
 use crate::Processor;
 
+/// Builds a state collector that starts at the given block. After a reorg the
+/// canonical blocks are collected by a new one, so that they are priced from
+/// the canonical state instead of the state the stale blocks left behind.
+pub type StateCollectorFactory<T, DB, CH> = Box<dyn Fn(u64) -> StateCollector<T, DB, CH> + Send>;
+
+type ReorgCheck = BoxFuture<'static, (ReorgTracker, eyre::Result<Option<RangeInclusive<u64>>>)>;
+
 pub struct TipInspector<
     T: TracingProvider,
     DB: LibmdbxReader + DBWriter,
     CH: ClickhouseHandle,
     P: Processor,
 > {
-    current_block:      u64,
-    back_from_tip:      u64,
-    parser:             &'static Parser<T, DB>,
-    state_collector:    StateCollector<T, DB, CH>,
-    database:           &'static DB,
-    inspectors:         &'static [&'static dyn Inspector<Result = P::InspectType>],
-    processing_futures: FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
-    poll_interval:      Interval,
-    _p:                 PhantomData<P>,
+    current_block:       u64,
+    /// the newest block with enough confirmations to be processed
+    confirmed_block:     Option<u64>,
+    chain_tip:           ChainTipFollower<T, DB>,
+    parser:              &'static Parser<T, DB>,
+    state_collector:     StateCollector<T, DB, CH>,
+    new_state_collector: StateCollectorFactory<T, DB, CH>,
+    database:            &'static DB,
+    inspectors:          &'static [&'static dyn Inspector<Result = P::InspectType>],
+    processing_futures:  FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
+    /// wakes the inspector up to check whether the next block can be started
+    poll_interval:       Interval,
+    metrics:             TipMetrics,
+    reorg_tracker:       ReorgTracker,
+    /// checks whether the next block builds on the processed ones, holds the
+    /// tracker until it is done
+    reorg_check:         Option<ReorgCheck>,
+    /// blocks that were reorged out, reverted once the blocks in flight are
+    /// done
+    stale_blocks:        Option<RangeInclusive<u64>>,
+    revert_future:       Option<BoxFuture<'static, eyre::Result<()>>>,
+    anomalies:           SharedAnomalyMonitor,
+    _p:                  PhantomData<P>,
 }
 
 impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P: Processor>
@@ -43,20 +71,32 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
     pub fn new(
         current_block: u64,
         back_from_tip: u64,
-        state_collector: StateCollector<T, DB, CH>,
+        poll_interval: Duration,
+        new_state_collector: StateCollectorFactory<T, DB, CH>,
         parser: &'static Parser<T, DB>,
         database: &'static DB,
         inspectors: &'static [&'static dyn Inspector<Result = P::InspectType>],
     ) -> Self {
+        let chain_tip = ChainTipFollower::new(parser, current_block, back_from_tip, poll_interval);
+        let metrics = chain_tip.metrics().clone();
+
         Self {
-            back_from_tip,
-            state_collector,
+            state_collector: new_state_collector(current_block),
+            new_state_collector,
             inspectors,
             current_block,
+            confirmed_block: None,
+            chain_tip,
             parser,
             processing_futures: FuturesUnordered::new(),
             database,
-            poll_interval: interval(Duration::from_secs(3)),
+            poll_interval: interval(poll_interval),
+            metrics,
+            reorg_tracker: ReorgTracker::default(),
+            reorg_check: None,
+            stale_blocks: None,
+            revert_future: None,
+            anomalies: BlockAnomalyMonitor::shared("tip"),
             _p: PhantomData,
         }
     }
@@ -79,48 +119,114 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
         drop(graceful_guard);
     }
 
-    #[cfg(feature = "local-reth")]
-    fn start_block_inspector(&mut self) -> bool {
-        if self.state_collector.is_collecting_state() {
-            return false
-        }
+    fn start_block_inspector(&self) -> bool {
+        !self.state_collector.is_collecting_state()
+            && self
+                .confirmed_block
+                .is_some_and(|confirmed| confirmed >= self.current_block)
+    }
 
-        match self.parser.get_latest_block_number() {
-            Ok(chain_tip) => chain_tip - self.back_from_tip > self.current_block,
-            Err(e) => {
-                tracing::error!("Error: {:?}", e);
-                false
+    /// Checks that the block builds on the blocks that were processed before
+    /// starting it. On a reorg, no new block is started until the stale ones
+    /// are reverted.
+    fn check_for_reorg(&mut self, block: u64) {
+        let tracker = std::mem::take(&mut self.reorg_tracker);
+        self.reorg_check = Some(Box::pin(tracker.check_block(self.parser, block)));
+    }
+
+    fn on_reorg_checked(
+        &mut self,
+        tracker: ReorgTracker,
+        res: eyre::Result<Option<RangeInclusive<u64>>>,
+    ) {
+        self.reorg_tracker = tracker;
+        let block = self.current_block;
+
+        match res {
+            Ok(None) => {
+                tracing::info!(%block,"starting new tip block");
+                self.state_collector.fetch_state_for(block, 0, None);
+                self.metrics.current_block.set(block as f64);
+                self.current_block += 1;
             }
+            Ok(Some(blocks)) => {
+                tracing::warn!(?blocks, "chain reorged, reprocessing the canonical blocks");
+                self.stale_blocks = Some(blocks);
+            }
+            Err(e) => tracing::error!(%block, error = %e, "failed to check for a reorg"),
         }
     }
 
-    #[cfg(not(feature = "local-reth"))]
-    fn start_block_inspector(&mut self) -> bool {
-        if self.state_collector.is_collecting_state() {
-            return false
+    /// Removes the results of the reorged out blocks once nothing that could
+    /// still write results for them is in flight
+    fn try_start_reverting(&mut self) {
+        if self.revert_future.is_some()
+            || !self.processing_futures.is_empty()
+            || !self.state_collector.is_idle()
+        {
+            return
         }
+        let Some(blocks) = self.stale_blocks.clone() else { return };
 
-        let cur_block = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(async { self.parser.get_latest_block_number().await })
-        });
+        self.revert_future = Some(Box::pin(self.database.invalidate_blocks(blocks)));
+    }
 
-        match cur_block {
-            Ok(chain_tip) => chain_tip - self.back_from_tip > self.current_block,
-            Err(e) => {
-                tracing::error!("Error: {:?}", e);
-                false
-            }
+    /// Continues from the first stale block with a new state collector, so
+    /// the canonical blocks are traced, priced and inspected like any other
+    /// tip block
+    fn on_reverted(&mut self, res: eyre::Result<()>) {
+        let Some(blocks) = self.stale_blocks.clone() else { return };
+        if let Err(e) = res {
+            // retried on the next poll
+            tracing::error!(?blocks, error = %e, "failed to revert reorged blocks");
+            return
         }
+        self.stale_blocks = None;
+
+        let first_stale = *blocks.start();
+        let window = self.state_collector.take_window_before(first_stale);
+        let mut state_collector = (self.new_state_collector)(first_stale);
+        state_collector.set_window(window);
+
+        let stale_collector = std::mem::replace(&mut self.state_collector, state_collector);
+        stale_collector
+            .get_shutdown()
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        self.current_block = first_stale;
     }
 
     fn on_price_finish(&mut self, data: MultiBlockData) {
         debug!(target:"brontes::tip_inspector","Completed DEX pricing");
-        self.processing_futures.push(Box::pin(P::process_results(
+        let block = data.get_most_recent_block().tree.header.number;
+        if self
+            .stale_blocks
+            .as_ref()
+            .is_some_and(|blocks| blocks.contains(&block))
+        {
+            debug!(target:"brontes::tip_inspector", %block, "dropping reorged block");
+            return
+        }
+
+        let block_timestamp = data.get_most_recent_block().tree.header.timestamp;
+        let process = P::process_results(
             self.database,
+            self.parser.get_tracer(),
             self.inspectors,
             data,
-        )));
+            self.anomalies.clone(),
+        );
+        let metrics = self.metrics.clone();
+        self.processing_futures.push(Box::pin(async move {
+            process.await;
+            // how long after the block was built its results were written
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            metrics
+                .block_lag_secs
+                .record(now.saturating_sub(block_timestamp) as f64);
+        }));
     }
 }
 
@@ -130,15 +236,35 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
     type Output = ();
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        // given we pull the next block sync, we use this to trigger looking
-        // for the next block.
+        // the state collector can become ready for the next block without
+        // waking us, so we periodically look for the next block.
         while self.poll_interval.poll_tick(cx).is_ready() {}
 
-        if self.start_block_inspector() && self.state_collector.should_process_next_block() {
-            let block = self.current_block;
-            tracing::info!(%block,"starting new tip block");
-            self.state_collector.fetch_state_for(block, 0, None);
-            self.current_block += 1;
+        while let Poll::Ready(Some(block)) = self.chain_tip.poll_next_unpin(cx) {
+            self.confirmed_block = Some(block.number);
+            self.metrics
+                .blocks_behind
+                .set(block.chain_tip.saturating_sub(self.current_block) as f64);
+        }
+
+        if let Some(Poll::Ready(res)) = self.revert_future.as_mut().map(|f| f.poll_unpin(cx)) {
+            self.revert_future = None;
+            self.on_reverted(res);
+        }
+
+        if self.stale_blocks.is_none()
+            && self.reorg_check.is_none()
+            && self.start_block_inspector()
+            && self.state_collector.should_process_next_block()
+        {
+            self.check_for_reorg(self.current_block);
+        }
+
+        if let Some(Poll::Ready((tracker, res))) =
+            self.reorg_check.as_mut().map(|f| f.poll_unpin(cx))
+        {
+            self.reorg_check = None;
+            self.on_reorg_checked(tracker, res);
         }
 
         if let Poll::Ready(item) = self.state_collector.poll_next_unpin(cx) {
@@ -150,6 +276,14 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
         }
         while let Poll::Ready(Some(_)) = self.processing_futures.poll_next_unpin(cx) {}
 
+        if self.stale_blocks.is_some() && self.revert_future.is_none() {
+            self.try_start_reverting();
+            // the revert future hasn't been polled yet
+            if self.revert_future.is_some() {
+                cx.waker().wake_by_ref();
+            }
+        }
+
         Poll::Pending
     }
 }
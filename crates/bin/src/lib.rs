@@ -29,4 +29,5 @@ pub mod misc;
 pub use executors::*;
 pub use misc::banner;
 
+pub mod rpc;
 pub mod runner;
@@ -14,6 +14,7 @@ use brontes::{
     cli::{Args, Commands},
     runner,
 };
+use brontes_types::chain::init_chain_spec;
 use clap::Parser;
 use eyre::eyre;
 use tracing::{error, info};
@@ -49,6 +50,7 @@ fn run() -> eyre::Result<()> {
         .unwrap_or(env::var("BRONTES_DB_PATH").expect("No BRONTES_DB_PATH in .env"));
 
     init_tracing(opt.verbosity.directive());
+    init_chain_spec(opt.chain)?;
 
     let metrics_port = if opt.skip_prometheus { None } else { Some(opt.metrics_port) };
 
@@ -58,6 +60,26 @@ fn run() -> eyre::Result<()> {
                 command.execute(brontes_db_path, ctx)
             })
         }
+        Commands::Verify(command) => {
+            runner::run_command_until_exit(metrics_port, Duration::from_secs(3600), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+        Commands::Replay(command) => {
+            runner::run_command_until_exit(metrics_port, Duration::from_secs(3600), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+        Commands::Simulate(command) => {
+            runner::run_command_until_exit(metrics_port, Duration::from_secs(5), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+        Commands::Serve(command) => {
+            runner::run_command_until_exit(None, Duration::from_secs(5), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
         Commands::Database(command) => {
             runner::run_command_until_exit(None, Duration::from_secs(5), |ctx| {
                 command.execute(brontes_db_path, ctx)
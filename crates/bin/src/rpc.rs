@@ -0,0 +1,377 @@
+//! JSON-RPC server over the brontes libmdbx db, so dashboards can query the
+//! stored results without direct access to the database.
+//!
+//! Methods:
+//! - `getMevByBlock(block)`: the mev block and its bundles
+//! - `getBundlesBySearcher(address, startBlock, endBlock)`: the bundles whose
+//!   eoa or mev contract is `address`
+//! - `getSearcherStats(address)`: the stored searcher info of the address as an
+//!   eoa and as a contract
+//! - `getDexPricesAt(block, txIndex, token0, token1)`: the dex price of the
+//!   pair before and after the tx
+//! - `getBuilderPnl(startBlock, endBlock)`: revenue, proposer payments and
+//!   subsidies of each builder over the blocks it built in the range
+//!
+//! Admin methods, only served alongside a run that follows the tip:
+//! - `adminListInspectors()`: the inspectors of the run and whether they are
+//!   enabled
+//! - `adminSetInspectorEnabled(inspector, enabled)`: enables or disables an
+//!   inspector from the next block on
+use std::{convert::Infallible, net::SocketAddr};
+
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_inspect::{registry::InspectorRegistry, Inspectors};
+use brontes_types::{
+    db::{builder_pnl::BuilderPnlStats, searcher::SearcherInfo},
+    mev::{Bundle, MevBlock},
+    pair::Pair,
+    ToFloatNearest,
+};
+use eyre::WrapErr;
+use hyper::{
+    body::to_bytes,
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+/// Max blocks a single range query may scan
+const MAX_BLOCK_RANGE: u64 = 50_000;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id:     Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id:      Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result:  Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error:   Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        match result {
+            Ok(result) => Self { jsonrpc: "2.0", id, result: Some(result), error: None },
+            Err(error) => Self { jsonrpc: "2.0", id, result: None, error: Some(error) },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code:    i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self { code, message: message.to_string() }
+    }
+
+    fn internal(err: eyre::Report) -> Self {
+        Self::new(INTERNAL_ERROR, err)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MevByBlock {
+    block:   MevBlock,
+    bundles: Vec<Bundle>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearcherStats {
+    eoa:      Option<SearcherInfo>,
+    contract: Option<SearcherInfo>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InspectorStatus {
+    inspector: String,
+    enabled:   bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DexPriceAt {
+    pre_state:    f64,
+    post_state:   f64,
+    goes_through: Pair,
+}
+
+/// Serves the query methods of the module over http
+pub struct RpcServer<DB: LibmdbxReader> {
+    db:         &'static DB,
+    inspectors: Option<&'static InspectorRegistry>,
+}
+
+impl<DB: LibmdbxReader> RpcServer<DB> {
+    pub fn new(db: &'static DB) -> Self {
+        Self { db, inspectors: None }
+    }
+
+    /// Serves the admin methods that enable and disable the inspectors of the
+    /// registry
+    pub fn with_inspector_registry(mut self, inspectors: &'static InspectorRegistry) -> Self {
+        self.inspectors = Some(inspectors);
+        self
+    }
+
+    /// Binds to the address and serves requests until the server errors
+    pub async fn serve(self, listen_addr: SocketAddr) -> eyre::Result<()> {
+        let Self { db, inspectors } = self;
+        let make_svc = make_service_fn(move |_| async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| async move {
+                Ok::<_, Infallible>(Self { db, inspectors }.handle_http(req).await)
+            }))
+        });
+
+        let server = Server::try_bind(&listen_addr)
+            .wrap_err("Could not bind to address")?
+            .serve(make_svc);
+        info!(target: "brontes::rpc", "serving json-rpc on {listen_addr}");
+
+        server.await.wrap_err("rpc server crashed")
+    }
+
+    async fn handle_http(&self, req: Request<Body>) -> Response<Body> {
+        if req.method() != Method::POST {
+            return Response::builder()
+                .status(StatusCode::METHOD_NOT_ALLOWED)
+                .body(Body::empty())
+                .unwrap()
+        }
+
+        let response = match to_bytes(req.into_body()).await {
+            Ok(body) => self.handle_body(&body),
+            Err(e) => serde_json::to_value(RpcResponse::new(
+                Value::Null,
+                Err(RpcError::new(PARSE_ERROR, e)),
+            ))
+            .unwrap(),
+        };
+
+        Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(response.to_string()))
+            .unwrap()
+    }
+
+    /// Handles a single request or a batch of them
+    fn handle_body(&self, body: &[u8]) -> Value {
+        let parsed = match serde_json::from_slice::<Value>(body) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return serde_json::to_value(RpcResponse::new(
+                    Value::Null,
+                    Err(RpcError::new(PARSE_ERROR, e)),
+                ))
+                .unwrap()
+            }
+        };
+
+        match parsed {
+            Value::Array(batch) => Value::Array(
+                batch
+                    .into_iter()
+                    .map(|req| self.handle_request(req))
+                    .collect(),
+            ),
+            req => self.handle_request(req),
+        }
+    }
+
+    fn handle_request(&self, req: Value) -> Value {
+        let response = match serde_json::from_value::<RpcRequest>(req) {
+            Ok(req) => {
+                debug!(target: "brontes::rpc", method = %req.method, "handling request");
+                RpcResponse::new(req.id, self.call(&req.method, req.params))
+            }
+            Err(e) => RpcResponse::new(Value::Null, Err(RpcError::new(INVALID_REQUEST, e))),
+        };
+
+        serde_json::to_value(response).unwrap()
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let result = match method {
+            "getMevByBlock" => {
+                let (block,) = parse_params(params)?;
+                serde_json::to_value(self.mev_by_block(block)?)
+            }
+            "getBundlesBySearcher" => {
+                let (searcher, start_block, end_block) = parse_params(params)?;
+                serde_json::to_value(self.bundles_by_searcher(searcher, start_block, end_block)?)
+            }
+            "getSearcherStats" => {
+                let (searcher,) = parse_params(params)?;
+                serde_json::to_value(self.searcher_stats(searcher)?)
+            }
+            "getDexPricesAt" => {
+                let (block, tx_index, token0, token1) = parse_params(params)?;
+                serde_json::to_value(self.dex_prices_at(block, tx_index, Pair(token0, token1))?)
+            }
+            "getBuilderPnl" => {
+                let (start_block, end_block) = parse_params(params)?;
+                serde_json::to_value(self.builder_pnl(start_block, end_block)?)
+            }
+            "adminListInspectors" => serde_json::to_value(self.list_inspectors()?),
+            "adminSetInspectorEnabled" => {
+                let (inspector, enabled) = parse_params::<(String, bool)>(params)?;
+                serde_json::to_value(self.set_inspector_enabled(&inspector, enabled)?)
+            }
+            method => {
+                return Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method {method}")))
+            }
+        };
+
+        result.map_err(|e| RpcError::new(INTERNAL_ERROR, e))
+    }
+
+    fn mev_by_block(&self, block: u64) -> Result<Option<MevByBlock>, RpcError> {
+        Ok(self
+            .db
+            .try_fetch_mev_block(block)
+            .map_err(RpcError::internal)?
+            .map(|mev_block| MevByBlock { block: mev_block.block, bundles: mev_block.mev }))
+    }
+
+    fn bundles_by_searcher(
+        &self,
+        searcher: Address,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<Bundle>, RpcError> {
+        check_block_range(start_block, end_block)?;
+
+        Ok(self
+            .db
+            .try_fetch_mev_blocks(Some(start_block), end_block)
+            .map_err(RpcError::internal)?
+            .into_iter()
+            .flat_map(|mev_block| mev_block.mev)
+            .filter(|bundle| {
+                bundle.header.eoa == searcher || bundle.header.mev_contract == Some(searcher)
+            })
+            .collect())
+    }
+
+    fn searcher_stats(&self, searcher: Address) -> Result<SearcherStats, RpcError> {
+        Ok(SearcherStats {
+            eoa:      self
+                .db
+                .try_fetch_searcher_eoa_info(searcher)
+                .map_err(RpcError::internal)?,
+            contract: self
+                .db
+                .try_fetch_searcher_contract_info(searcher)
+                .map_err(RpcError::internal)?,
+        })
+    }
+
+    fn dex_prices_at(
+        &self,
+        block: u64,
+        tx_index: usize,
+        pair: Pair,
+    ) -> Result<Option<DexPriceAt>, RpcError> {
+        let quotes = self.db.get_dex_quotes(block).map_err(RpcError::internal)?;
+
+        Ok(quotes.price_at(pair, tx_index).map(|prices| DexPriceAt {
+            pre_state:    prices.pre_state.to_float(),
+            post_state:   prices.post_state.to_float(),
+            goes_through: prices.goes_through,
+        }))
+    }
+
+    fn builder_pnl(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<BuilderPnlStats>, RpcError> {
+        check_block_range(start_block, end_block)?;
+
+        self.db
+            .fetch_builder_pnl_stats(start_block, end_block)
+            .map_err(RpcError::internal)
+    }
+
+    fn inspector_registry(&self) -> Result<&'static InspectorRegistry, RpcError> {
+        self.inspectors.ok_or_else(|| {
+            RpcError::new(
+                METHOD_NOT_FOUND,
+                "admin methods are only served alongside a run that follows the tip",
+            )
+        })
+    }
+
+    fn list_inspectors(&self) -> Result<Vec<InspectorStatus>, RpcError> {
+        Ok(self
+            .inspector_registry()?
+            .status()
+            .into_iter()
+            .map(|(inspector, enabled)| InspectorStatus {
+                inspector: inspector.to_string(),
+                enabled,
+            })
+            .collect())
+    }
+
+    fn set_inspector_enabled(
+        &self,
+        inspector: &str,
+        enabled: bool,
+    ) -> Result<InspectorStatus, RpcError> {
+        let registry = self.inspector_registry()?;
+        let parsed = inspector
+            .parse::<Inspectors>()
+            .map_err(|_| RpcError::new(INVALID_PARAMS, format!("unknown inspector {inspector}")))?;
+        registry
+            .set_enabled(parsed, enabled)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e))?;
+
+        Ok(InspectorStatus { inspector: parsed.to_string(), enabled })
+    }
+}
+
+fn check_block_range(start_block: u64, end_block: u64) -> Result<(), RpcError> {
+    if start_block > end_block {
+        return Err(RpcError::new(INVALID_PARAMS, "start block must be less than end block"))
+    }
+    if end_block - start_block > MAX_BLOCK_RANGE {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("block range can span at most {MAX_BLOCK_RANGE} blocks"),
+        ))
+    }
+
+    Ok(())
+}
+
+/// Params are passed positionally, as a json array
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!([]) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
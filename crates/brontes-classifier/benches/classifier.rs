@@ -9,6 +9,9 @@ fn bench_tree_building(c: &mut Criterion) {
     utils
         .bench_block_tree_building("build 28m gas tree", 18672183, c)
         .unwrap();
+    utils
+        .bench_block_tree_building_serial("build 28m gas tree serial", 18672183, c)
+        .unwrap();
 }
 
 criterion_group!(tree, bench_tree_building);
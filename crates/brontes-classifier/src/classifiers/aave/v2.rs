@@ -34,6 +34,8 @@ action_impl!(
             // filled in later
             liquidated_collateral: Rational::ZERO,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -70,6 +72,8 @@ action_impl!(
             repayments: vec![],
             fees_paid: vec![],
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -35,6 +35,8 @@ action_impl!(
             // filled in later
             liquidated_collateral: Rational::ZERO,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -71,8 +73,8 @@ action_impl!(
             repayments: vec![],
             fees_paid: vec![],
             msg_value: info.msg_value,
-
-
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -106,8 +108,8 @@ action_impl!(
             repayments: vec![],
             fees_paid: vec![],
             msg_value: info.msg_value,
-
-
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -131,18 +133,20 @@ mod tests {
             B256::from(hex!("dd951e0fc5dc4c98b8daaccdb750ff3dc9ad24a7f689aad2a088757266ab1d55"));
 
         let eq_action = Action::Liquidation(NormalizedLiquidation {
-            protocol:              Protocol::AaveV3,
+            protocol: Protocol::AaveV3,
             liquidated_collateral: Rational::from_signeds(165516722, 100000000),
-            covered_debt:          Rational::from_signeds(63857746423_i64, 1000000),
-            debtor:                Address::from(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3")),
-            debt_asset:            classifier_utils
+            covered_debt: Rational::from_signeds(63857746423_i64, 1000000),
+            debtor: Address::from(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3")),
+            debt_asset: classifier_utils
                 .get_token_info(Address::from(hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"))),
-            collateral_asset:      classifier_utils
+            collateral_asset: classifier_utils
                 .get_token_info(Address::from(hex!("2260fac5e5542a773aa44fbcfedf7c193bc2c599"))),
-            liquidator:            Address::from(hex!("80d4230c0a68fc59cb264329d3a717fcaa472a13")),
-            pool:                  Address::from(hex!("87870bca3f3fd6335c3f4ce8392d69350b4fa4e2")),
-            trace_index:           6,
-            msg_value:             U256::ZERO,
+            liquidator: Address::from(hex!("80d4230c0a68fc59cb264329d3a717fcaa472a13")),
+            pool: Address::from(hex!("87870bca3f3fd6335c3f4ce8392d69350b4fa4e2")),
+            trace_index: 6,
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -0,0 +1,196 @@
+use alloy_primitives::{Log, U256};
+use alloy_sol_types::SolCall;
+use brontes_core::missing_token_info::TokenDecimalResolver;
+use brontes_types::{
+    constants::PERMIT2_ADDRESS,
+    db::{
+        token_info::TokenInfoWithAddress,
+        traits::{DBWriter, LibmdbxReader},
+    },
+    normalized_actions::{Action, ApprovalKind, NormalizedApproval},
+    structured_trace::{TraceActions, TransactionTraceWithLogs},
+    traits::TracingProvider,
+};
+use itertools::Itertools;
+
+use super::weth::decode_log;
+
+alloy_sol_macro::sol!(
+    function approve(address spender, uint256 amount) returns (bool);
+    function permit(
+        address owner,
+        address spender,
+        uint256 value,
+        uint256 deadline,
+        uint8 v,
+        bytes32 r,
+        bytes32 s
+    );
+
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+);
+
+// Permit2 emits its own events for the allowances it holds, both for
+// approvals and for single and batch permits
+alloy_sol_macro::sol! {
+    interface Permit2 {
+        event Approval(
+            address indexed owner,
+            address indexed token,
+            address indexed spender,
+            uint160 amount,
+            uint48 expiration
+        );
+        event Permit(
+            address indexed owner,
+            address indexed token,
+            address indexed spender,
+            uint160 amount,
+            uint48 expiration,
+            uint48 nonce
+        );
+    }
+}
+
+/// Classifies the allowances granted by `approve` and EIP-2612 `permit` calls
+/// on a token, as well as the allowances granted through Permit2. The token
+/// doesn't have to be known, its decimals are resolved like for a transfer. A
+/// Permit2 batch permit grants several allowances in one call.
+pub async fn try_decode_approvals<T: TracingProvider, DB: LibmdbxReader + DBWriter>(
+    trace_index: u64,
+    trace: &TransactionTraceWithLogs,
+    db: &DB,
+    resolver: &TokenDecimalResolver<T>,
+    block: u64,
+) -> Option<Vec<Action>> {
+    if trace.is_delegate_call() {
+        return None
+    }
+
+    let approvals = if trace.get_to_address() == PERMIT2_ADDRESS {
+        trace
+            .logs
+            .iter()
+            .filter(|log| log.address == PERMIT2_ADDRESS)
+            .filter_map(|log| decode_permit2_approval(trace_index, trace.get_msg_value(), log))
+            .collect_vec()
+    } else {
+        decode_token_approval(trace_index, trace)
+            .into_iter()
+            .collect_vec()
+    };
+    if approvals.is_empty() {
+        return None
+    }
+
+    let mut actions = Vec::with_capacity(approvals.len());
+    for mut approval in approvals {
+        approval.token = resolver
+            .token_info(db, block, approval.token.address)
+            .await
+            .ok()?;
+        actions.push(Action::Custom(approval.into()));
+    }
+
+    Some(actions)
+}
+
+/// The allowance is taken from the `Approval` log of the token, the call data
+/// is the fallback for the tokens that don't emit it
+fn decode_token_approval(
+    trace_index: u64,
+    trace: &TransactionTraceWithLogs,
+) -> Option<NormalizedApproval> {
+    let token = trace.get_to_address();
+    let calldata = trace.get_calldata();
+
+    let (kind, owner, spender, amount) = if let Ok(call) = approveCall::abi_decode(&calldata, false)
+    {
+        (ApprovalKind::Approve, trace.get_from_addr(), call.spender, call.amount)
+    } else if let Ok(call) = permitCall::abi_decode(&calldata, false) {
+        (ApprovalKind::Permit, call.owner, call.spender, call.value)
+    } else {
+        return None
+    };
+
+    let amount = trace
+        .logs
+        .iter()
+        .filter(|log| log.address == token)
+        .filter_map(decode_log::<Approval>)
+        .find(|event| event.owner == owner && event.spender == spender)
+        .map(|event| event.value)
+        .unwrap_or(amount);
+
+    Some(NormalizedApproval {
+        kind,
+        trace_index,
+        token: TokenInfoWithAddress { address: token, ..Default::default() },
+        owner,
+        spender,
+        amount,
+        msg_value: trace.get_msg_value(),
+    })
+}
+
+fn decode_permit2_approval(
+    trace_index: u64,
+    msg_value: U256,
+    log: &Log,
+) -> Option<NormalizedApproval> {
+    let (owner, token, spender, amount) = if let Some(event) = decode_log::<Permit2::Permit>(log) {
+        (event.owner, event.token, event.spender, event.amount)
+    } else {
+        let event = decode_log::<Permit2::Approval>(log)?;
+        (event.owner, event.token, event.spender, event.amount)
+    };
+
+    Some(NormalizedApproval {
+        kind: ApprovalKind::Permit2,
+        trace_index,
+        token: TokenInfoWithAddress { address: token, ..Default::default() },
+        owner,
+        spender,
+        amount: U256::from(amount),
+        msg_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U160};
+    use alloy_sol_types::SolEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_permit2_events() {
+        let [owner, token, spender] = [1, 2, 3].map(Address::with_last_byte);
+        let topics = vec![
+            Permit2::Permit::SIGNATURE_HASH,
+            owner.into_word(),
+            token.into_word(),
+            spender.into_word(),
+        ];
+        let data = [U256::from(U160::MAX), U256::from(1_000u64), U256::ZERO]
+            .iter()
+            .flat_map(|word| word.to_be_bytes::<32>())
+            .collect::<Vec<_>>();
+        let permit = Log::new_unchecked(PERMIT2_ADDRESS, topics, data.into());
+
+        let approval = decode_permit2_approval(0, U256::ZERO, &permit).unwrap();
+        assert_eq!(
+            (approval.owner, approval.token.address, approval.spender),
+            (owner, token, spender)
+        );
+        assert!(approval.is_unlimited());
+
+        // an erc20 approval isn't decoded as a permit2 one
+        let erc20 = Log::new_unchecked(
+            token,
+            vec![Approval::SIGNATURE_HASH, owner.into_word(), spender.into_word()],
+            U256::ZERO.to_be_bytes_vec().into(),
+        );
+        assert!(decode_permit2_approval(0, U256::ZERO, &erc20).is_none());
+    }
+}
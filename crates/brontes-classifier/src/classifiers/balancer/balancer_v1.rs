@@ -34,6 +34,9 @@ action_impl!(
             amount_in,
             amount_out,
             msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -66,6 +69,9 @@ action_impl!(
             amount_in,
             amount_out,
             msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -90,7 +96,10 @@ action_impl!(
             protocol: Protocol::BalancerV1,
             trace_index: info.trace_idx,
             pool_address: info.target_address,
-            tokens
+            tokens,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -126,13 +135,16 @@ mod tests {
             amount_in:   U256::from_str("72712976").unwrap().to_scaled_rational(6),
             token_out:   TokenInfoWithAddress {
                 address: Address::new(hex!("f8C3527CC04340b208C854E985240c02F7B7793f")),
-                inner:   TokenInfo { decimals: 18, symbol: "FRONT".to_string() },
+                inner:   TokenInfo::new(18, "FRONT".to_string()),
             },
             amount_out:  U256::from_str("229136254468181839981")
                 .unwrap()
                 .to_scaled_rational(18),
 
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -166,10 +178,13 @@ mod tests {
             B256::from(hex!("400146b33d7e9c5ba7626c927a93ddc4dbd5e13161609011681450c19eaebfed"));
 
         let expected_action = Action::NewPool(NormalizedNewPool {
-            protocol:     Protocol::BalancerV1,
-            trace_index:  0,
+            protocol: Protocol::BalancerV1,
+            trace_index: 0,
             pool_address: Address::new(hex!("9A2181cf0bC57FC0177517dB21d457BDd1b2b32e")),
-            tokens:       vec![Address::new(hex!("5eD9e47679422c2F78568af8728EC3C3C8591146"))],
+            tokens: vec![Address::new(hex!("5eD9e47679422c2F78568af8728EC3C3C8591146"))],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -42,6 +42,9 @@ action_impl!(
             token_out,
             amount_out,
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -71,6 +74,9 @@ action_impl!(
             token_out,
             amount_out,
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -127,7 +133,9 @@ action_impl!(
             child_actions: vec![],
             repayments: vec![],
             fees_paid: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -150,7 +158,9 @@ action_impl!(
             recipient: call_data.recipient,
             pool: pool_id_to_address(call_data.poolId),
             token: tokens,
-            amount: amounts
+            amount: amounts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -173,7 +183,9 @@ action_impl!(
             recipient: call_data.recipient,
             pool: pool_id_to_address(call_data.poolId),
             token: tokens,
-            amount: amounts
+            amount: amounts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -192,6 +204,9 @@ action_impl!(
             protocol: Protocol::BalancerV2,
             pool_address: logs.poolAddress,
             tokens: vec![],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -210,7 +225,9 @@ action_impl!(
             trace_index: info.trace_idx,
             protocol: Protocol::BalancerV2,
             pool_address,
-            tokens: logs.tokens
+            tokens: logs.tokens,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -245,7 +262,7 @@ mod tests {
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("6C22910c6F75F828B305e57c6a54855D8adeAbf8")),
-            inner:   TokenInfo { decimals: 9, symbol: "SATS".to_string() },
+            inner:   TokenInfo::new(9, "SATS".to_string()),
         });
 
         classifier_utils.ensure_protocol(
@@ -272,13 +289,16 @@ mod tests {
                 .to_scaled_rational(18),
             token_out:   TokenInfoWithAddress {
                 address: Address::new(hex!("6C22910c6F75F828B305e57c6a54855D8adeAbf8")),
-                inner:   TokenInfo { decimals: 9, symbol: "SATS".to_string() },
+                inner:   TokenInfo::new(9, "SATS".to_string()),
             },
             amount_out:  U256::from_str("7727102831493")
                 .unwrap()
                 .to_scaled_rational(9),
 
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -300,27 +320,29 @@ mod tests {
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee")),
-            inner:   TokenInfo { decimals: 18, symbol: "weETH".to_string() },
+            inner:   TokenInfo::new(18, "weETH".to_string()),
         });
 
         let eq_action = Action::FlashLoan(NormalizedFlashLoan {
-            protocol:          Protocol::BalancerV2,
-            trace_index:       3,
-            from:              Address::new(hex!("97c1a26482099363cb055f0f3ca1d6057fe55447")),
-            pool:              Address::new(hex!("ba12222222228d8ba445958a75a0704d566bf2c8")),
+            protocol: Protocol::BalancerV2,
+            trace_index: 3,
+            from: Address::new(hex!("97c1a26482099363cb055f0f3ca1d6057fe55447")),
+            pool: Address::new(hex!("ba12222222228d8ba445958a75a0704d566bf2c8")),
             receiver_contract: Address::new(hex!("97c1a26482099363cb055f0f3ca1d6057fe55447")),
-            assets:            vec![TokenInfoWithAddress {
+            assets: vec![TokenInfoWithAddress {
                 address: Address::new(hex!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")),
-                inner:   TokenInfo { decimals: 18, symbol: "WETH".to_string() },
+                inner:   TokenInfo::new(18, "WETH".to_string()),
             }],
-            amounts:           vec![U256::from_str("653220647374307183")
+            amounts: vec![U256::from_str("653220647374307183")
                 .unwrap()
                 .to_scaled_rational(18)],
-            aave_mode:         None,
-            child_actions:     vec![],
-            repayments:        vec![],
-            fees_paid:         vec![],
-            msg_value:         U256::ZERO,
+            aave_mode: None,
+            child_actions: vec![],
+            repayments: vec![],
+            fees_paid: vec![],
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -343,22 +365,24 @@ mod tests {
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee")),
-            inner:   TokenInfo { decimals: 18, symbol: "weETH".to_string() },
+            inner:   TokenInfo::new(18, "weETH".to_string()),
         });
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    Protocol::BalancerV2,
+            protocol: Protocol::BalancerV2,
             trace_index: 0,
-            from:        Address::new(hex!("750c31d2290c456fcca1c659b6add80e7a88f881")),
-            recipient:   Address::new(hex!("750c31d2290c456fcca1c659b6add80e7a88f881")),
-            pool:        Address::new(hex!("848a5564158d84b8A8fb68ab5D004Fae11619A54")),
-            token:       vec![TokenInfoWithAddress {
+            from: Address::new(hex!("750c31d2290c456fcca1c659b6add80e7a88f881")),
+            recipient: Address::new(hex!("750c31d2290c456fcca1c659b6add80e7a88f881")),
+            pool: Address::new(hex!("848a5564158d84b8A8fb68ab5D004Fae11619A54")),
+            token: vec![TokenInfoWithAddress {
                 address: Address::new(hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee")),
-                inner:   TokenInfo { decimals: 18, symbol: "weETH".to_string() },
+                inner:   TokenInfo::new(18, "weETH".to_string()),
             }],
-            amount:      vec![U256::from_str("1935117712922949743")
+            amount: vec![U256::from_str("1935117712922949743")
                 .unwrap()
                 .to_scaled_rational(18)],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -380,40 +404,40 @@ mod tests {
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("bf5495efe5db9ce00f80364c8b423567e58d2110")),
-            inner:   TokenInfo { decimals: 18, symbol: "ezETH".to_string() },
+            inner:   TokenInfo::new(18, "ezETH".to_string()),
         });
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee")),
-            inner:   TokenInfo { decimals: 18, symbol: "weETH".to_string() },
+            inner:   TokenInfo::new(18, "weETH".to_string()),
         });
 
         classifier_utils.ensure_token(TokenInfoWithAddress {
             address: Address::new(hex!("fae103dc9cf190ed75350761e95403b7b8afa6c0")),
-            inner:   TokenInfo { decimals: 18, symbol: "rswETH".to_string() },
+            inner:   TokenInfo::new(18, "rswETH".to_string()),
         });
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::BalancerV2,
+            protocol: Protocol::BalancerV2,
             trace_index: 0,
-            from:        Address::new(hex!("f4283d13ba1e17b33bb3310c3149136a2ef79ef7")),
-            recipient:   Address::new(hex!("f4283d13ba1e17b33bb3310c3149136a2ef79ef7")),
-            pool:        Address::new(hex!("848a5564158d84b8A8fb68ab5D004Fae11619A54")),
-            token:       vec![
+            from: Address::new(hex!("f4283d13ba1e17b33bb3310c3149136a2ef79ef7")),
+            recipient: Address::new(hex!("f4283d13ba1e17b33bb3310c3149136a2ef79ef7")),
+            pool: Address::new(hex!("848a5564158d84b8A8fb68ab5D004Fae11619A54")),
+            token: vec![
                 TokenInfoWithAddress {
                     address: Address::new(hex!("bf5495efe5db9ce00f80364c8b423567e58d2110")),
-                    inner:   TokenInfo { decimals: 18, symbol: "ezETH".to_string() },
+                    inner:   TokenInfo::new(18, "ezETH".to_string()),
                 },
                 TokenInfoWithAddress {
                     address: Address::new(hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee")),
-                    inner:   TokenInfo { decimals: 18, symbol: "weETH".to_string() },
+                    inner:   TokenInfo::new(18, "weETH".to_string()),
                 },
                 TokenInfoWithAddress {
                     address: Address::new(hex!("fae103dc9cf190ed75350761e95403b7b8afa6c0")),
-                    inner:   TokenInfo { decimals: 18, symbol: "rswETH".to_string() },
+                    inner:   TokenInfo::new(18, "rswETH".to_string()),
                 },
             ],
-            amount:      vec![
+            amount: vec![
                 U256::from_str("471937215318872937")
                     .unwrap()
                     .to_scaled_rational(18),
@@ -424,6 +448,8 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
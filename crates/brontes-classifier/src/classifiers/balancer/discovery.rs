@@ -14,6 +14,9 @@ discovery_impl!(
             protocol: Protocol::BalancerV1,
             pool_address: deployed_address,
             tokens: vec![],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -28,6 +31,9 @@ discovery_impl!(
             protocol: Protocol::BalancerV1CRP,
             pool_address: deployed_address,
             tokens: vec![],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -46,13 +52,16 @@ mod tests {
             B256::new(hex!("f5b9b2c23fa3ddf58c31a9377d37439740913f526910cca947c0a3e4bb9bb1d7"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::BalancerV1,
+            trace_index: 1,
+            protocol: Protocol::BalancerV1,
             pool_address: Address::new(hex!("1FA0d58e663017cdd80B87fd24C46818364fc9B6")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("b2b88912edc5f5fece07ed821de80440c0bae618").into(),
                 hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -31,6 +31,9 @@ action_impl!(
                 amount_in,
                 amount_out,
                 msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
 );
@@ -62,6 +65,9 @@ action_impl!(
                 amount_in,
                 amount_out,
                 msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
 );
@@ -93,6 +99,9 @@ action_impl!(
                 amount_in,
                 amount_out,
                 msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
 );
@@ -124,6 +133,9 @@ action_impl!(
                 amount_in,
                 amount_out,
                 msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
 );
@@ -155,6 +167,9 @@ action_impl!(
                 amount_in,
                 amount_out,
                 msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
 );
@@ -179,18 +194,21 @@ mod tests {
             B256::from(hex!("3d9186d1cce43df1b3365d2faa19a35093412c583a9130e12e81cb8d389c3e45"));
 
         let eq_action = Action::Swap(NormalizedSwap {
-            protocol:    ClipperExchange,
+            protocol: ClipperExchange,
             trace_index: 0,
-            from:        Address::new(hex!("aeaC71B09AeaeDC6A52CEe06373a648CAd620c20")),
-            recipient:   Address::new(hex!("aeaC71B09AeaeDC6A52CEe06373a648CAd620c20")),
-            pool:        Address::new(hex!("655eDCE464CC797526600a462A8154650EEe4B77")),
-            token_in:    TokenInfoWithAddress::usdc(),
-            amount_in:   U256::from_str("1213920000").unwrap().to_scaled_rational(6),
-            token_out:   TokenInfoWithAddress::weth(),
-            amount_out:  U256::from_str("360342259234585088")
+            from: Address::new(hex!("aeaC71B09AeaeDC6A52CEe06373a648CAd620c20")),
+            recipient: Address::new(hex!("aeaC71B09AeaeDC6A52CEe06373a648CAd620c20")),
+            pool: Address::new(hex!("655eDCE464CC797526600a462A8154650EEe4B77")),
+            token_in: TokenInfoWithAddress::usdc(),
+            amount_in: U256::from_str("1213920000").unwrap().to_scaled_rational(6),
+            token_out: TokenInfoWithAddress::weth(),
+            amount_out: U256::from_str("360342259234585088")
                 .unwrap()
                 .to_scaled_rational(18),
-            msg_value:   U256::ZERO,
+            msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -33,6 +33,8 @@ action_impl!(
             covered_debt: debt_covered,
             liquidated_collateral: collateral_liquidated,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -65,18 +67,15 @@ mod tests {
 
         let debt = TokenInfoWithAddress {
             address: hex!("39aa39c021dfbae8fac545936693ac917d5e7563").into(),
-            inner:   brontes_types::db::token_info::TokenInfo {
-                decimals: 8,
-                symbol:   "cUSDC".to_string(),
-            },
+            inner:   brontes_types::db::token_info::TokenInfo::new(8, "cUSDC".to_string()),
         };
 
         let collateral = TokenInfoWithAddress {
             address: hex!("70e36f6BF80a52b3B46b3aF8e106CC0ed743E8e4").into(),
-            inner:   brontes_types::db::token_info::TokenInfo {
-                decimals: 8,
-                symbol:   "CompoundCollateral".to_string(),
-            },
+            inner:   brontes_types::db::token_info::TokenInfo::new(
+                8,
+                "CompoundCollateral".to_string(),
+            ),
         };
 
         classifier_utils.ensure_token(debt);
@@ -86,18 +85,20 @@ mod tests {
             B256::from(hex!("3a3ba6b0a6b69a8e316e1c20f97b9ce2de790b2f3bf90aaef5b29b06aafa5fda"));
 
         let eq_action = Action::Liquidation(NormalizedLiquidation {
-            protocol:              Protocol::CompoundV2,
+            protocol: Protocol::CompoundV2,
             liquidated_collateral: Rational::from_signeds(6140057900131i64, 100000000),
-            covered_debt:          Rational::from_signeds(48779241727i64, 100000000),
-            debtor:                Address::from(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d")),
-            debt_asset:            classifier_utils
+            covered_debt: Rational::from_signeds(48779241727i64, 100000000),
+            debtor: Address::from(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d")),
+            debt_asset: classifier_utils
                 .get_token_info(Address::from(hex!("39aa39c021dfbae8fac545936693ac917d5e7563"))),
-            collateral_asset:      classifier_utils
+            collateral_asset: classifier_utils
                 .get_token_info(Address::from(hex!("70e36f6BF80a52b3B46b3aF8e106CC0ed743E8e4"))),
-            liquidator:            Address::from(hex!("D911560979B78821D7b045C79E36E9CbfC2F6C6F")),
-            pool:                  Address::from(hex!("39AA39c021dfbaE8faC545936693aC917d5E7563")),
-            trace_index:           2,
-            msg_value:             U256::ZERO,
+            liquidator: Address::from(hex!("D911560979B78821D7b045C79E36E9CbfC2F6C6F")),
+            pool: Address::from(hex!("39AA39c021dfbaE8faC545936693aC917d5E7563")),
+            trace_index: 2,
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -0,0 +1,49 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::NormalizedLiquidation, structured_trace::CallInfo, utils::ToScaledRational,
+};
+
+// `absorb` only moves the debt and collateral of underwater accounts to the
+// protocol, the absorber isn't paid for it. Liquidators realize their profit
+// when they buy the absorbed collateral out of the reserves at a discount
+// through `buyCollateral`, which is classified as the liquidation. The
+// reserves take the place of the debtor.
+action_impl!(
+    Protocol::CompoundV3,
+    crate::CompoundV3Comet::buyCollateralCall,
+    Liquidation,
+    [..BuyCollateral],
+    logs: true,
+    include_delegated_logs: true,
+    |
+    info: CallInfo,
+    log_data: CompoundV3BuyCollateralCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.buy_collateral_field?;
+
+        let base_token = db_tx.get_protocol_details(info.target_address)?.token0;
+        let debt_info = db_tx.try_fetch_token_info(base_token)?;
+        let collateral_info = db_tx.try_fetch_token_info(logs.asset)?;
+
+        let covered_debt = logs.baseAmount.to_scaled_rational(debt_info.decimals);
+        let liquidated_collateral = logs
+            .collateralAmount
+            .to_scaled_rational(collateral_info.decimals);
+
+        Ok(NormalizedLiquidation {
+            protocol: Protocol::CompoundV3,
+            trace_index: info.trace_idx,
+            pool: info.target_address,
+            liquidator: logs.buyer,
+            debtor: info.target_address,
+            collateral_asset: collateral_info,
+            debt_asset: debt_info,
+            covered_debt,
+            liquidated_collateral,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
@@ -13,7 +13,10 @@ action_impl!(
             trace_index: info.trace_idx,
             protocol: Protocol::CompoundV2,
             pool_address: info.from_address,
-            tokens: vec![info.from_address]
+            tokens: vec![info.from_address],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -29,7 +32,10 @@ action_impl!(
             trace_index: info.trace_idx,
             protocol: Protocol::CompoundV2,
             pool_address: info.from_address,
-            tokens: vec![info.from_address]
+            tokens: vec![info.from_address],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -52,10 +58,13 @@ mod tests {
             B256::from(hex!("090ce7d33359e5d288ce169f41bb3d2cb55ac17b026a10cf80b3fc4f0c85c827"));
 
         let eq_action = Action::NewPool(NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::CompoundV2,
+            trace_index: 1,
+            protocol: Protocol::CompoundV2,
             pool_address: hex!("5d3a536e4d6dbd6114cc1ead35777bab948e3643").into(),
-            tokens:       vec![hex!("5d3a536e4d6dbd6114cc1ead35777bab948e3643").into()],
+            tokens: vec![hex!("5d3a536e4d6dbd6114cc1ead35777bab948e3643").into()],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
         let search = TreeSearchBuilder::default().with_action(Action::is_new_pool);
 
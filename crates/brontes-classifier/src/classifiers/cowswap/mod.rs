@@ -36,6 +36,9 @@ fn create_normalized_swap<DB: LibmdbxReader + DBWriter>(
         amount_in,
         amount_out,
         msg_value: U256::ZERO,
+        fee_tier: None,
+        #[cfg(feature = "provenance")]
+        provenance: None,
     })
 }
 
@@ -59,6 +62,8 @@ action_impl!(
             user_swaps: vec![swap],
             solver_swaps: None,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -86,6 +91,8 @@ action_impl!(
             user_swaps,
             solver_swaps: None,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -112,40 +119,41 @@ mod tests {
             B256::from(hex!("23e459142f904e8aef751f1ca2b95bf75a45b1d7823692eb8b7eca3a9bf5c0fe"));
 
         let eq_action = Action::Batch(NormalizedBatch {
-            protocol:            Protocol::Cowswap,
-            trace_index:         0,
-            solver:              Address::from_str("0x8646ee3c5e82b495be8f9fe2f2f213701eed0edc")
-                .unwrap(),
+            protocol: Protocol::Cowswap,
+            trace_index: 0,
+            solver: Address::from_str("0x8646ee3c5e82b495be8f9fe2f2f213701eed0edc").unwrap(),
             settlement_contract: Address::from_str("0x9008d19f58aabd9ed0d60971565aa8510560ab41")
                 .unwrap(),
-            user_swaps:          vec![NormalizedSwap {
-                protocol:    Protocol::Cowswap,
+            user_swaps: vec![NormalizedSwap {
+                protocol: Protocol::Cowswap,
                 trace_index: 0,
-                from:        Address::from_str("0x54e047e98c44b27f79dcfb6d2e35e41183b8dff6")
-                    .unwrap(),
-                recipient:   Address::from_str("0x54e047e98c44b27f79dcfb6d2e35e41183b8dff6")
-                    .unwrap(),
-                pool:        Address::from_str("0x9008d19f58aabd9ed0d60971565aa8510560ab41")
-                    .unwrap(),
-                token_in:    TokenInfoWithAddress {
+                from: Address::from_str("0x54e047e98c44b27f79dcfb6d2e35e41183b8dff6").unwrap(),
+                recipient: Address::from_str("0x54e047e98c44b27f79dcfb6d2e35e41183b8dff6").unwrap(),
+                pool: Address::from_str("0x9008d19f58aabd9ed0d60971565aa8510560ab41").unwrap(),
+                token_in: TokenInfoWithAddress {
                     address: Address::from_str("0xae78736cd615f374d3085123a210448e74fc6393")
                         .unwrap(),
-                    inner:   TokenInfo { decimals: 18, symbol: "rETH".to_string() },
+                    inner:   TokenInfo::new(18, "rETH".to_string()),
                 },
-                token_out:   TokenInfoWithAddress {
+                token_out: TokenInfoWithAddress {
                     address: ETH_ADDRESS,
-                    inner:   TokenInfo { decimals: 18, symbol: "ETH".to_string() },
+                    inner:   TokenInfo::new(18, "ETH".to_string()),
                 },
-                amount_in:   U256::from_str("750005967291428997")
+                amount_in: U256::from_str("750005967291428997")
                     .unwrap()
                     .to_scaled_rational(18),
-                amount_out:  U256::from_str("823443483581865908")
+                amount_out: U256::from_str("823443483581865908")
                     .unwrap()
                     .to_scaled_rational(18),
-                msg_value:   U256::ZERO,
+                msg_value: U256::ZERO,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             }],
-            solver_swaps:        Some(vec![]),
-            msg_value:           U256::ZERO,
+            solver_swaps: Some(vec![]),
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -40,6 +40,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -80,6 +82,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -123,6 +127,8 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -42,6 +42,8 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
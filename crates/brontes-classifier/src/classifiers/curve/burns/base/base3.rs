@@ -38,6 +38,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -76,6 +78,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -119,6 +123,8 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -39,6 +39,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -77,6 +79,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
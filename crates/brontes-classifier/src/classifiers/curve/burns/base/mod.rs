@@ -40,17 +40,17 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("EB4C2781e4ebA804CE9a9803C67d0893436bB27D")),
-            inner:   TokenInfo { decimals: 8, symbol: "renBTC".to_string() },
+            inner:   TokenInfo::new(8, "renBTC".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
-            inner:   TokenInfo { decimals: 8, symbol: "WBTC".to_string() },
+            inner:   TokenInfo::new(8, "WBTC".to_string()),
         };
 
         let token2 = TokenInfoWithAddress {
             address: Address::new(hex!("fE18be6b3Bd88A2D2A7f928d00292E7a9963CfC6")),
-            inner:   TokenInfo { decimals: 18, symbol: "sBTC".to_string() },
+            inner:   TokenInfo::new(18, "sBTC".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
@@ -58,17 +58,19 @@ mod tests {
         classifier_utils.ensure_token(token2.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveBasePool3,
+            protocol: Protocol::CurveBasePool3,
             trace_index: 0,
-            from:        Address::new(hex!("aEBd1F6272Bc7E2d406595cc2E98AAE21a47F03d")),
-            recipient:   Address::new(hex!("aEBd1F6272Bc7E2d406595cc2E98AAE21a47F03d")),
-            pool:        Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
-            token:       vec![token0, token1, token2],
-            amount:      vec![
+            from: Address::new(hex!("aEBd1F6272Bc7E2d406595cc2E98AAE21a47F03d")),
+            recipient: Address::new(hex!("aEBd1F6272Bc7E2d406595cc2E98AAE21a47F03d")),
+            pool: Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
+            token: vec![token0, token1, token2],
+            amount: vec![
                 U256::from(135971).to_scaled_rational(8),
                 U256::from(253273).to_scaled_rational(8),
                 U256::from(2022770990903219_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -101,34 +103,36 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("EB4C2781e4ebA804CE9a9803C67d0893436bB27D")),
-            inner:   TokenInfo { decimals: 8, symbol: "renBTC".to_string() },
+            inner:   TokenInfo::new(8, "renBTC".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
-            inner:   TokenInfo { decimals: 8, symbol: "WBTC".to_string() },
+            inner:   TokenInfo::new(8, "WBTC".to_string()),
         };
 
         let token2 = TokenInfoWithAddress {
             address: Address::new(hex!("fE18be6b3Bd88A2D2A7f928d00292E7a9963CfC6")),
-            inner:   TokenInfo { decimals: 18, symbol: "sBTC".to_string() },
+            inner:   TokenInfo::new(18, "sBTC".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveBasePool3,
+            protocol: Protocol::CurveBasePool3,
             trace_index: 0,
-            from:        Address::new(hex!("13ca2cf84365BD2daffd4A7e364Ea11388607C37")),
-            recipient:   Address::new(hex!("13ca2cf84365BD2daffd4A7e364Ea11388607C37")),
-            pool:        Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
-            token:       vec![token0, token1, token2],
-            amount:      vec![
+            from: Address::new(hex!("13ca2cf84365BD2daffd4A7e364Ea11388607C37")),
+            recipient: Address::new(hex!("13ca2cf84365BD2daffd4A7e364Ea11388607C37")),
+            pool: Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
+            token: vec![token0, token1, token2],
+            amount: vec![
                 U256::from(0).to_scaled_rational(8),
                 U256::from(50000000).to_scaled_rational(8),
                 U256::from(0).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -161,19 +165,21 @@ mod tests {
 
         let token = TokenInfoWithAddress {
             address: Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
-            inner:   TokenInfo { decimals: 8, symbol: "WBTC".to_string() },
+            inner:   TokenInfo::new(8, "WBTC".to_string()),
         };
 
         classifier_utils.ensure_token(token.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveBasePool3,
+            protocol: Protocol::CurveBasePool3,
             trace_index: 0,
-            from:        Address::new(hex!("045929aF66312685d143B96C9d44Ce5ddCBAB768")),
-            recipient:   Address::new(hex!("045929aF66312685d143B96C9d44Ce5ddCBAB768")),
-            pool:        Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
-            token:       vec![token],
-            amount:      vec![U256::from(38855798316741927_u128).to_scaled_rational(8)],
+            from: Address::new(hex!("045929aF66312685d143B96C9d44Ce5ddCBAB768")),
+            recipient: Address::new(hex!("045929aF66312685d143B96C9d44Ce5ddCBAB768")),
+            pool: Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
+            token: vec![token],
+            amount: vec![U256::from(38855798316741927_u128).to_scaled_rational(8)],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
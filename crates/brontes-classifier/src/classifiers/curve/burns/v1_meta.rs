@@ -41,6 +41,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -82,6 +84,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -122,6 +126,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -163,6 +169,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -204,6 +212,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -249,6 +259,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -286,28 +298,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("6967299e9F3d5312740Aa61dEe6E9ea658958e31")),
-            inner:   TokenInfo { decimals: 18, symbol: "T".to_string() },
+            inner:   TokenInfo::new(18, "T".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("6c3f90f043a72fa612cbac8115ee7e52bde6e490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV1MetaPool,
+            protocol: Protocol::CurveV1MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("95e0022e62A9e13fc9F38A3E288521f2FD042357")),
-            recipient:   Address::new(hex!("95e0022e62A9e13fc9F38A3E288521f2FD042357")),
-            pool:        Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("95e0022e62A9e13fc9F38A3E288521f2FD042357")),
+            recipient: Address::new(hex!("95e0022e62A9e13fc9F38A3E288521f2FD042357")),
+            pool: Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(125377210391915440945_u128).to_scaled_rational(18),
                 U256::from(2121542034308448729_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -340,28 +354,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("6967299e9F3d5312740Aa61dEe6E9ea658958e31")),
-            inner:   TokenInfo { decimals: 18, symbol: "T".to_string() },
+            inner:   TokenInfo::new(18, "T".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("6c3f90f043a72fa612cbac8115ee7e52bde6e490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV1MetaPool,
+            protocol: Protocol::CurveV1MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("a30C1d2f7Bf871FE70827fc438c5A3Fe80eF4f4C")),
-            recipient:   Address::new(hex!("a30C1d2f7Bf871FE70827fc438c5A3Fe80eF4f4C")),
-            pool:        Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("a30C1d2f7Bf871FE70827fc438c5A3Fe80eF4f4C")),
+            recipient: Address::new(hex!("a30C1d2f7Bf871FE70827fc438c5A3Fe80eF4f4C")),
+            pool: Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(5782689815360000000000_u128).to_scaled_rational(18),
                 U256::from(60598295710000000000_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -394,19 +410,21 @@ mod tests {
 
         let token = TokenInfoWithAddress {
             address: Address::new(hex!("6c3f90f043a72fa612cbac8115ee7e52bde6e490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV1MetaPool,
+            protocol: Protocol::CurveV1MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("b5e452a90280A978aA8DAe4306F960167c7C528A")),
-            recipient:   Address::new(hex!("b5e452a90280A978aA8DAe4306F960167c7C528A")),
-            pool:        Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
-            token:       vec![token],
-            amount:      vec![U256::from(1976026334539568105482_u128).to_scaled_rational(18)],
+            from: Address::new(hex!("b5e452a90280A978aA8DAe4306F960167c7C528A")),
+            recipient: Address::new(hex!("b5e452a90280A978aA8DAe4306F960167c7C528A")),
+            pool: Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
+            token: vec![token],
+            amount: vec![U256::from(1976026334539568105482_u128).to_scaled_rational(18)],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -41,6 +41,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -82,6 +84,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -123,6 +127,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -164,6 +170,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -205,6 +213,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -250,6 +260,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -287,28 +299,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("530824DA86689C9C17CdC2871Ff29B058345b44a")),
-            inner:   TokenInfo { decimals: 18, symbol: "STBT".to_string() },
+            inner:   TokenInfo::new(18, "STBT".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV2MetaPool,
+            protocol: Protocol::CurveV2MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("81BD585940501b583fD092BC8397F2119A96E5ba")),
-            recipient:   Address::new(hex!("81BD585940501b583fD092BC8397F2119A96E5ba")),
-            pool:        Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("81BD585940501b583fD092BC8397F2119A96E5ba")),
+            recipient: Address::new(hex!("81BD585940501b583fD092BC8397F2119A96E5ba")),
+            pool: Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(627992358239302043763875_u128).to_scaled_rational(18),
                 U256::from(579890756974932941933194_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -341,19 +355,21 @@ mod tests {
 
         let token = TokenInfoWithAddress {
             address: Address::new(hex!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV2MetaPool,
+            protocol: Protocol::CurveV2MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("3F7734E28ed8856B89e13137bd2E9112C40ebD51")),
-            recipient:   Address::new(hex!("3F7734E28ed8856B89e13137bd2E9112C40ebD51")),
-            pool:        Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
-            token:       vec![token],
-            amount:      vec![U256::from(183708410783845567136_u128).to_scaled_rational(18)],
+            from: Address::new(hex!("3F7734E28ed8856B89e13137bd2E9112C40ebD51")),
+            recipient: Address::new(hex!("3F7734E28ed8856B89e13137bd2E9112C40ebD51")),
+            pool: Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
+            token: vec![token],
+            amount: vec![U256::from(183708410783845567136_u128).to_scaled_rational(18)],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
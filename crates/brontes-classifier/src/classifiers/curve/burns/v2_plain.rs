@@ -40,6 +40,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -80,6 +82,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -120,6 +124,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -160,6 +166,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -183,14 +191,9 @@ action_impl!(
         let details = db_tx.get_protocol_details(info.from_address)?;
         let protocol = details.protocol;
 
-        let token = match call_data.i {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for burn token, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for burn token, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for burn token, found None"))?,
-            _ => unreachable!()
-        };
+        let token = details
+            .token(call_data.i as usize)
+            .ok_or(eyre::eyre!("Expected token{} for burn token, found None", call_data.i))?;
 
         let token_info = db_tx.try_fetch_token_info(token)?;
         let amt = log.token_amount.to_scaled_rational(token_info.decimals);
@@ -204,6 +207,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -228,14 +233,9 @@ action_impl!(
         let details = db_tx.get_protocol_details(info.from_address)?;
         let protocol = details.protocol;
 
-        let token = match call_data.i {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for burn token, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for burn token, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for burn token, found None"))?,
-            _ => unreachable!()
-        };
+        let token = details
+            .token(call_data.i as usize)
+            .ok_or(eyre::eyre!("Expected token{} for burn token, found None", call_data.i))?;
 
         let token_info = db_tx.try_fetch_token_info(token)?;
         let amt = log.token_amount.to_scaled_rational(token_info.decimals);
@@ -249,6 +249,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -286,28 +288,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("D533a949740bb3306d119CC777fa900bA034cd52")),
-            inner:   TokenInfo { decimals: 18, symbol: "CRV".to_string() },
+            inner:   TokenInfo::new(18, "CRV".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV2PlainPool,
+            protocol: Protocol::CurveV2PlainPool,
             trace_index: 1,
-            from:        Address::new(hex!("598C5E19a132a5c433a80C908f05D87bFDaAC4ae")),
-            recipient:   Address::new(hex!("598C5E19a132a5c433a80C908f05D87bFDaAC4ae")),
-            pool:        Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("598C5E19a132a5c433a80C908f05D87bFDaAC4ae")),
+            recipient: Address::new(hex!("598C5E19a132a5c433a80C908f05D87bFDaAC4ae")),
+            pool: Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(7558238951551444616838_u128).to_scaled_rational(18),
                 U256::from(33415347097773187822792_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -340,28 +344,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("D533a949740bb3306d119CC777fa900bA034cd52")),
-            inner:   TokenInfo { decimals: 18, symbol: "CRV".to_string() },
+            inner:   TokenInfo::new(18, "CRV".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV2PlainPool,
+            protocol: Protocol::CurveV2PlainPool,
             trace_index: 1,
-            from:        Address::new(hex!("a0f75491720835b36edC92D06DDc468D201e9b73")),
-            recipient:   Address::new(hex!("a0f75491720835b36edC92D06DDc468D201e9b73")),
-            pool:        Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("a0f75491720835b36edC92D06DDc468D201e9b73")),
+            recipient: Address::new(hex!("a0f75491720835b36edC92D06DDc468D201e9b73")),
+            pool: Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(827904920210000000000000_u128).to_scaled_rational(18),
                 U256::from(332024620000000000000000_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -394,19 +400,21 @@ mod tests {
 
         let token = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         classifier_utils.ensure_token(token.clone());
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    Protocol::CurveV2PlainPool,
+            protocol: Protocol::CurveV2PlainPool,
             trace_index: 1,
-            from:        Address::new(hex!("F94F7b6b956225BcE60A5f0C7B82D347071E48dC")),
-            recipient:   Address::new(hex!("F94F7b6b956225BcE60A5f0C7B82D347071E48dC")),
-            pool:        Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
-            token:       vec![token],
-            amount:      vec![U256::from(915720089431618525538_u128).to_scaled_rational(18)],
+            from: Address::new(hex!("F94F7b6b956225BcE60A5f0C7B82D347071E48dC")),
+            recipient: Address::new(hex!("F94F7b6b956225BcE60A5f0C7B82D347071E48dC")),
+            pool: Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
+            token: vec![token],
+            amount: vec![U256::from(915720089431618525538_u128).to_scaled_rational(18)],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
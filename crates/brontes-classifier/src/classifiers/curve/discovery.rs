@@ -38,6 +38,9 @@ discovery_impl!(
             protocol: Protocol::CurveCryptoSwapPool,
             pool_address: deployed_address,
             tokens: call_data._coins.to_vec(),
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -58,6 +61,9 @@ discovery_impl!(
             protocol: Protocol::CurveTriCryptoPool,
             pool_address: deployed_address,
             tokens: call_data._coins.to_vec(),
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -101,7 +107,15 @@ async fn parse_plain_pool<const N: usize>(
 ) -> Vec<NormalizedNewPool> {
     let tokens = tokens.into_iter().filter(|t| t != &Address::ZERO).collect();
 
-    vec![NormalizedNewPool { pool_address: deployed_address, trace_index, protocol, tokens }]
+    vec![NormalizedNewPool {
+        pool_address: deployed_address,
+        trace_index,
+        protocol,
+        tokens,
+        fee_tier: None,
+        #[cfg(feature = "provenance")]
+        provenance: None,
+    }]
 }
 
 async fn parse_meta_pool<T: TracingProvider>(
@@ -112,10 +126,20 @@ async fn parse_meta_pool<T: TracingProvider>(
     trace_index: u64,
     tracer: Arc<T>,
 ) -> Vec<NormalizedNewPool> {
-    let mut tokens = query_base_pool(&tracer, &base_pool).await;
-    tokens.push(meta_token);
-
-    vec![NormalizedNewPool { pool_address: deployed_address, trace_index, protocol, tokens }]
+    // registered in the order of the underlying coins of the pool, the meta
+    // coin followed by the coins of the base pool
+    let mut tokens = vec![meta_token];
+    tokens.extend(query_base_pool(&tracer, &base_pool).await);
+
+    vec![NormalizedNewPool {
+        pool_address: deployed_address,
+        trace_index,
+        protocol,
+        tokens,
+        fee_tier: None,
+        #[cfg(feature = "provenance")]
+        provenance: None,
+    }]
 }
 
 #[cfg(test)]
@@ -134,7 +158,15 @@ mod tests {
     ) {
         let utils = ClassifierTestUtils::new().await;
 
-        let eq_create = NormalizedNewPool { trace_index: 1, protocol, pool_address, tokens };
+        let eq_create = NormalizedNewPool {
+            trace_index: 1,
+            protocol,
+            pool_address,
+            tokens,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        };
 
         utils
             .test_discovery_classification(tx, pool_address, |mut pool| {
@@ -155,10 +187,10 @@ mod tests {
             Protocol::CurveV1MetaPool,
             hex!("5a6a4d54456819380173272a5e8e9b9904bdf41b").into(),
             vec![
+                hex!("99d8a9c45b2eca8864373a26d1459e3dff1e17f3").into(),
                 hex!("6b175474e89094c44da98b954eedeac495271d0f").into(),
                 hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").into(),
                 hex!("dac17f958d2ee523a2206206994597c13d831ec7").into(),
-                hex!("99d8a9c45b2eca8864373a26d1459e3dff1e17f3").into(),
             ],
         )
         .await;
@@ -234,10 +266,10 @@ mod tests {
             Protocol::CurveV2MetaPool,
             hex!("6d0bd8365e2fcd0c2acf7d218f629a319b6c9d47").into(),
             vec![
+                Address::new(hex!("fd8e70e83E399307db3978D3F34B060a06792c36")),
                 Address::new(hex!("EB4C2781e4ebA804CE9a9803C67d0893436bB27D")),
                 Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
                 Address::new(hex!("fE18be6b3Bd88A2D2A7f928d00292E7a9963CfC6")),
-                Address::new(hex!("fd8e70e83E399307db3978D3F34B060a06792c36")),
             ],
         )
         .await;
@@ -250,9 +282,9 @@ mod tests {
             Protocol::CurveV2MetaPool,
             hex!("e60986759872393a8360a4a7abeab3a6e0ba7848").into(),
             vec![
+                hex!("466a756E9A7401B5e2444a3fCB3c2C12FBEa0a54").into(),
                 hex!("853d955acef822db058eb8505911ed77f175b99e").into(),
                 hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").into(),
-                hex!("466a756E9A7401B5e2444a3fCB3c2C12FBEa0a54").into(),
             ],
         )
         .await;
@@ -281,13 +313,16 @@ mod tests {
             B256::new(hex!("b8225567ede93bc296b5ac263d5419f8910bc6c93554fbf5d7a643a945011743"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::CurveCryptoSwapPool,
+            trace_index: 1,
+            protocol: Protocol::CurveCryptoSwapPool,
             pool_address: Address::new(hex!("d4092e32b3517ee0daa4da48fdc40cab6058e187")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("81cb62d2cd9261f63a1ae96df715748dcbc97d46").into(),
                 hex!("dac17f958d2ee523a2206206994597c13d831ec7").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -313,14 +348,17 @@ mod tests {
             B256::new(hex!("28359dab5f78b92fb89f826f37296d86174ff6c62b0e14b44ad8b6abd0de92da"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::CurveTriCryptoPool,
+            trace_index: 1,
+            protocol: Protocol::CurveTriCryptoPool,
             pool_address: Address::new(hex!("84cecb5525c6b1c20070e742da870062e84da178")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("a71d0588EAf47f12B13cF8eC750430d21DF04974").into(),
                 hex!("b53ecF1345caBeE6eA1a65100Ebb153cEbcac40f").into(),
                 hex!("f3b9569F82B18aEf890De263B84189bd33EBe452").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -37,6 +37,8 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
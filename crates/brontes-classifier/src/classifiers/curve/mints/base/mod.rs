@@ -37,34 +37,36 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("EB4C2781e4ebA804CE9a9803C67d0893436bB27D")),
-            inner:   TokenInfo { decimals: 8, symbol: "renBTC".to_string() },
+            inner:   TokenInfo::new(8, "renBTC".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
-            inner:   TokenInfo { decimals: 8, symbol: "WBTC".to_string() },
+            inner:   TokenInfo::new(8, "WBTC".to_string()),
         };
 
         let token2 = TokenInfoWithAddress {
             address: Address::new(hex!("fE18be6b3Bd88A2D2A7f928d00292E7a9963CfC6")),
-            inner:   TokenInfo { decimals: 18, symbol: "sBTC".to_string() },
+            inner:   TokenInfo::new(18, "sBTC".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    Protocol::CurveBasePool3,
+            protocol: Protocol::CurveBasePool3,
             trace_index: 0,
-            from:        Address::new(hex!("DaD7ef2EfA3732892d33aAaF9B3B1844395D9cbE")),
-            recipient:   Address::new(hex!("DaD7ef2EfA3732892d33aAaF9B3B1844395D9cbE")),
-            pool:        Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
-            token:       vec![token0, token1, token2],
-            amount:      vec![
+            from: Address::new(hex!("DaD7ef2EfA3732892d33aAaF9B3B1844395D9cbE")),
+            recipient: Address::new(hex!("DaD7ef2EfA3732892d33aAaF9B3B1844395D9cbE")),
+            pool: Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
+            token: vec![token0, token1, token2],
+            amount: vec![
                 U256::from(0).to_scaled_rational(8),
                 U256::from(27506).to_scaled_rational(8),
                 U256::from(0).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -39,6 +39,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -79,6 +81,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -115,28 +119,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("6967299e9F3d5312740Aa61dEe6E9ea658958e31")),
-            inner:   TokenInfo { decimals: 18, symbol: "T".to_string() },
+            inner:   TokenInfo::new(18, "T".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("6c3f90f043a72fa612cbac8115ee7e52bde6e490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    Protocol::CurveV1MetaPool,
+            protocol: Protocol::CurveV1MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("1a734e9bDa6893915928eE8edBA75cA17536d385")),
-            recipient:   Address::new(hex!("1a734e9bDa6893915928eE8edBA75cA17536d385")),
-            pool:        Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("1a734e9bDa6893915928eE8edBA75cA17536d385")),
+            recipient: Address::new(hex!("1a734e9bDa6893915928eE8edBA75cA17536d385")),
+            pool: Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(1000000000000000000000_u128).to_scaled_rational(18),
                 U256::from(1000000000000000000000_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
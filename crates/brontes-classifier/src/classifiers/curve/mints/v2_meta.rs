@@ -39,6 +39,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -79,6 +81,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -117,28 +121,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("530824DA86689C9C17CdC2871Ff29B058345b44a")),
-            inner:   TokenInfo { decimals: 18, symbol: "STBT".to_string() },
+            inner:   TokenInfo::new(18, "STBT".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    Protocol::CurveV2MetaPool,
+            protocol: Protocol::CurveV2MetaPool,
             trace_index: 1,
-            from:        Address::new(hex!("d236A1a8340DE9d4f91C7bDB72eF0e4B3a90e4fd")),
-            recipient:   Address::new(hex!("d236A1a8340DE9d4f91C7bDB72eF0e4B3a90e4fd")),
-            pool:        Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("d236A1a8340DE9d4f91C7bDB72eF0e4B3a90e4fd")),
+            recipient: Address::new(hex!("d236A1a8340DE9d4f91C7bDB72eF0e4B3a90e4fd")),
+            pool: Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(0_u128).to_scaled_rational(18),
                 U256::from(100000000000000000000_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -37,6 +37,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -74,6 +76,8 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -110,28 +114,30 @@ mod tests {
 
         let token0 = TokenInfoWithAddress {
             address: Address::new(hex!("D533a949740bb3306d119CC777fa900bA034cd52")),
-            inner:   TokenInfo { decimals: 18, symbol: "CRV".to_string() },
+            inner:   TokenInfo::new(18, "CRV".to_string()),
         };
 
         let token1 = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         classifier_utils.ensure_token(token0.clone());
         classifier_utils.ensure_token(token1.clone());
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    Protocol::CurveV2PlainPool,
+            protocol: Protocol::CurveV2PlainPool,
             trace_index: 1,
-            from:        Address::new(hex!("fE894446bfaD2993B16428C990D69c99623b89B7")),
-            recipient:   Address::new(hex!("fE894446bfaD2993B16428C990D69c99623b89B7")),
-            pool:        Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
-            token:       vec![token0, token1],
-            amount:      vec![
+            from: Address::new(hex!("fE894446bfaD2993B16428C990D69c99623b89B7")),
+            recipient: Address::new(hex!("fE894446bfaD2993B16428C990D69c99623b89B7")),
+            pool: Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
+            token: vec![token0, token1],
+            amount: vec![
                 U256::from(2503890709681717311281_u128).to_scaled_rational(18),
                 U256::from(798080784008874713734_u128).to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
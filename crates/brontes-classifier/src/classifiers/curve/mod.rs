@@ -11,3 +11,7 @@ pub use mints::*;
 
 pub(crate) mod burns;
 pub use burns::*;
+
+#[allow(non_snake_case)]
+mod pool_params;
+pub use pool_params::update_curve_pool_params;
@@ -0,0 +1,68 @@
+use alloy_primitives::Log;
+use alloy_sol_types::SolEvent;
+use brontes_types::db::{
+    curve_pool_params::CurveParamsUpdate,
+    traits::{DBWriter, LibmdbxReader},
+};
+use tracing::error;
+
+alloy_sol_types::sol!(
+    event RampA(uint256 old_A, uint256 new_A, uint256 initial_time, uint256 future_time);
+    event StopRampA(uint256 A, uint256 t);
+    event NewFee(uint256 fee, uint256 admin_fee);
+);
+
+fn decode_params_update(log: &Log) -> Option<CurveParamsUpdate> {
+    let topic = log.topics().first()?;
+
+    if *topic == RampA::SIGNATURE_HASH {
+        let ramp = RampA::decode_log_data(&log.data, false).ok()?;
+        Some(CurveParamsUpdate::RampA {
+            initial_a:    ramp.old_A.saturating_to(),
+            future_a:     ramp.new_A.saturating_to(),
+            initial_time: ramp.initial_time.saturating_to(),
+            future_time:  ramp.future_time.saturating_to(),
+        })
+    } else if *topic == StopRampA::SIGNATURE_HASH {
+        let stop = StopRampA::decode_log_data(&log.data, false).ok()?;
+        Some(CurveParamsUpdate::StopRampA {
+            a:    stop.A.saturating_to(),
+            time: stop.t.saturating_to(),
+        })
+    } else if *topic == NewFee::SIGNATURE_HASH {
+        let fee = NewFee::decode_log_data(&log.data, false).ok()?;
+        Some(CurveParamsUpdate::NewFee {
+            fee:       fee.fee.saturating_to(),
+            admin_fee: fee.admin_fee.saturating_to(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Stores the parameter changes emitted by curve stableswap pools, so that
+/// their pricing follows A ramps and fee changes instead of relying on the
+/// deployment values.
+pub async fn update_curve_pool_params<DB: LibmdbxReader + DBWriter>(
+    db: &DB,
+    block: u64,
+    logs: &[Log],
+) {
+    for log in logs {
+        let Some(update) = decode_params_update(log) else { continue };
+        if !db
+            .get_protocol(log.address)
+            .is_ok_and(|protocol| protocol.is_curve_stableswap())
+        {
+            continue
+        }
+
+        if db
+            .update_curve_pool_params(log.address, block, update)
+            .await
+            .is_err()
+        {
+            error!(pool=?log.address, "failed to update curve pool params");
+        }
+    }
+}
@@ -54,7 +54,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -38,12 +38,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599")),
-            inner:   TokenInfo { decimals: 8, symbol: "WBTC".to_string() },
+            inner:   TokenInfo::new(8, "WBTC".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("EB4C2781e4ebA804CE9a9803C67d0893436bB27D")),
-            inner:   TokenInfo { decimals: 8, symbol: "renBTC".to_string() },
+            inner:   TokenInfo::new(8, "renBTC".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -60,6 +60,9 @@ mod tests {
             token_out,
             amount_out: U256::from_str("61329579").unwrap().to_scaled_rational(8),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
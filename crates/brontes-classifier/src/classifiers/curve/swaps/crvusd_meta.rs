@@ -0,0 +1,171 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::NormalizedSwap, structured_trace::CallInfo, ToScaledRational,
+};
+
+use super::{metapool_coin, metapool_underlying_coin};
+
+action_impl!(
+    Protocol::CurvecrvUSDMetapoolImpl,
+    crate::CurvecrvUSDMetapoolImpl::exchange_0Call,
+    Swap,
+    [..TokenExchange],
+    logs: true,
+    |
+    info: CallInfo,
+    log: CurvecrvUSDMetapoolImplExchange_0CallLogs,
+    db_tx: &DB|{
+        let log = log.token_exchange_field?;
+
+        let details = db_tx.get_protocol_details(info.from_address)?;
+
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
+
+        let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
+        let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
+
+        let amount_in = log.tokens_sold.to_scaled_rational(token_in.decimals);
+        let amount_out = log.tokens_bought.to_scaled_rational(token_out.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: details.protocol,
+            pool: info.from_address,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: info.msg_sender,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::CurvecrvUSDMetapoolImpl,
+    crate::CurvecrvUSDMetapoolImpl::exchange_1Call,
+    Swap,
+    [..TokenExchange],
+    logs: true,
+    |
+    info: CallInfo,
+    log: CurvecrvUSDMetapoolImplExchange_1CallLogs,
+    db_tx: &DB|{
+        let log = log.token_exchange_field?;
+
+        let details = db_tx.get_protocol_details(info.from_address)?;
+
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
+
+        let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
+        let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
+
+        let amount_in = log.tokens_sold.to_scaled_rational(token_in.decimals);
+        let amount_out = log.tokens_bought.to_scaled_rational(token_out.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: details.protocol,
+            pool: info.from_address,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: info.msg_sender,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::CurvecrvUSDMetapoolImpl,
+    crate::CurvecrvUSDMetapoolImpl::exchange_underlying_0Call,
+    Swap,
+    [..TokenExchangeUnderlying],
+    logs: true,
+    |
+    info: CallInfo,
+    log: CurvecrvUSDMetapoolImplExchange_underlying_0CallLogs,
+    db_tx: &DB|{
+        let log = log.token_exchange_underlying_field?;
+
+        let details = db_tx.get_protocol_details(info.from_address)?;
+
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
+
+        let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
+        let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
+
+        let amount_in = log.tokens_sold.to_scaled_rational(token_in.decimals);
+        let amount_out = log.tokens_bought.to_scaled_rational(token_out.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: details.protocol,
+            pool: info.from_address,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: info.msg_sender,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::CurvecrvUSDMetapoolImpl,
+    crate::CurvecrvUSDMetapoolImpl::exchange_underlying_1Call,
+    Swap,
+    [..TokenExchangeUnderlying],
+    logs: true,
+    |
+    info: CallInfo,
+    log: CurvecrvUSDMetapoolImplExchange_underlying_1CallLogs,
+    db_tx: &DB|{
+        let log = log.token_exchange_underlying_field?;
+
+        let details = db_tx.get_protocol_details(info.from_address)?;
+
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
+
+        let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
+        let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
+
+        let amount_in = log.tokens_sold.to_scaled_rational(token_in.decimals);
+        let amount_out = log.tokens_bought.to_scaled_rational(token_out.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: details.protocol,
+            pool: info.from_address,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: info.msg_sender,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
@@ -1,3 +1,6 @@
+use alloy_primitives::Address;
+use brontes_types::db::address_to_protocol_info::ProtocolInfo;
+
 pub(crate) mod base;
 pub use base::*;
 
@@ -9,3 +12,59 @@ pub use v2_meta::*;
 
 mod v2_plain;
 pub use v2_plain::*;
+
+mod crvusd_plain;
+pub use crvusd_plain::*;
+
+mod crvusd_meta;
+pub use crvusd_meta::*;
+
+/// Coin of a metapool for `exchange`. Coin 0 is the meta coin and coin 1 the
+/// LP token of the base pool.
+pub(crate) fn metapool_coin(details: &ProtocolInfo, index: i128) -> eyre::Result<Address> {
+    match index {
+        0 => Ok(details.token0),
+        1 => details
+            .curve_lp_token
+            .ok_or(eyre::eyre!("Expected curve_lp_token for coin 1, found None")),
+        i => Err(eyre::eyre!("metapools only have two coins, found coin {i}")),
+    }
+}
+
+/// Coin of a metapool for `exchange_underlying`. The underlying coins are the
+/// meta coin followed by the coins of the base pool, which is the order the
+/// pool's tokens are registered in.
+pub(crate) fn metapool_underlying_coin(
+    details: &ProtocolInfo,
+    index: i128,
+) -> eyre::Result<Address> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| details.token(index))
+        .ok_or(eyre::eyre!("Expected underlying coin {index}, found None"))
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::Protocol;
+
+    use super::*;
+
+    #[test]
+    fn resolves_metapool_underlying_coins() {
+        let meta = Address::with_last_byte(1);
+        let base = [Address::with_last_byte(2), Address::with_last_byte(3)];
+        let lp = Address::with_last_byte(4);
+        let details =
+            ProtocolInfo::new(Protocol::CurveV2MetaPool, &[meta, base[0], base[1]], Some(lp), 0);
+
+        assert_eq!(metapool_coin(&details, 0).unwrap(), meta);
+        assert_eq!(metapool_coin(&details, 1).unwrap(), lp);
+        assert!(metapool_coin(&details, 2).is_err());
+
+        assert_eq!(metapool_underlying_coin(&details, 0).unwrap(), meta);
+        assert_eq!(metapool_underlying_coin(&details, 2).unwrap(), base[1]);
+        assert!(metapool_underlying_coin(&details, 3).is_err());
+        assert!(metapool_underlying_coin(&details, -1).is_err());
+    }
+}
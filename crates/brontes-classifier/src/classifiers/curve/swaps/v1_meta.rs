@@ -4,6 +4,8 @@ use brontes_types::{
     normalized_actions::NormalizedSwap, structured_trace::CallInfo, ToScaledRational,
 };
 
+use super::{metapool_coin, metapool_underlying_coin};
+
 // couldn't find a V1 metapool calling this
 action_impl!(
     Protocol::CurveV1MetapoolImpl,
@@ -19,17 +21,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -48,7 +41,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -67,17 +63,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -96,7 +83,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -116,23 +106,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -151,7 +126,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -170,23 +148,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -205,7 +168,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -243,12 +209,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("6967299e9F3d5312740Aa61dEe6E9ea658958e31")),
-            inner:   TokenInfo { decimals: 18, symbol: "T".to_string() },
+            inner:   TokenInfo::new(18, "T".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("6c3f90f043a72fa612cbac8115ee7e52bde6e490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -269,6 +235,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -301,12 +270,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("6967299e9F3d5312740Aa61dEe6E9ea658958e31")),
-            inner:   TokenInfo { decimals: 18, symbol: "T".to_string() },
+            inner:   TokenInfo::new(18, "T".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("6B175474E89094C44Da98b954EedeAC495271d0F")),
-            inner:   TokenInfo { decimals: 18, symbol: "DAI".to_string() },
+            inner:   TokenInfo::new(18, "DAI".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -327,6 +296,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -4,6 +4,8 @@ use brontes_types::{
     normalized_actions::NormalizedSwap, structured_trace::CallInfo, ToScaledRational,
 };
 
+use super::{metapool_coin, metapool_underlying_coin};
+
 action_impl!(
     Protocol::CurveV2MetapoolImpl,
     crate::CurveV2MetapoolImpl::exchange_0Call,
@@ -18,17 +20,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -46,7 +39,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -65,17 +61,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.curve_lp_token.ok_or(eyre::eyre!("Expected curve_lp_token for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -93,7 +80,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -112,23 +102,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -147,7 +122,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -166,23 +144,8 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
-
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = metapool_underlying_coin(&details, log.sold_id)?;
+        let token_out_addr = metapool_underlying_coin(&details, log.bought_id)?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -201,7 +164,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -239,12 +205,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490")),
-            inner:   TokenInfo { decimals: 18, symbol: "3Crv".to_string() },
+            inner:   TokenInfo::new(18, "3Crv".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("530824DA86689C9C17CdC2871Ff29B058345b44a")),
-            inner:   TokenInfo { decimals: 18, symbol: "STBT".to_string() },
+            inner:   TokenInfo::new(18, "STBT".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -265,6 +231,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -297,12 +266,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("c56c2b7e71B54d38Aab6d52E94a04Cbfa8F604fA")),
-            inner:   TokenInfo { decimals: 6, symbol: "ZUSD".to_string() },
+            inner:   TokenInfo::new(6, "ZUSD".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("3175Df0976dFA876431C2E9eE6Bc45b65d3473CC")),
-            inner:   TokenInfo { decimals: 18, symbol: "crvFRAX".to_string() },
+            inner:   TokenInfo::new(18, "crvFRAX".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -321,6 +290,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -353,12 +325,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("530824DA86689C9C17CdC2871Ff29B058345b44a")),
-            inner:   TokenInfo { decimals: 18, symbol: "STBT".to_string() },
+            inner:   TokenInfo::new(18, "STBT".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("dAC17F958D2ee523a2206206994597C13D831ec7")),
-            inner:   TokenInfo { decimals: 6, symbol: "USDT".to_string() },
+            inner:   TokenInfo::new(6, "USDT".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -377,6 +349,9 @@ mod tests {
             token_out,
             amount_out: U256::from_str("4987470").unwrap().to_scaled_rational(6),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -409,12 +384,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")),
-            inner:   TokenInfo { decimals: 6, symbol: "USDC".to_string() },
+            inner:   TokenInfo::new(6, "USDC".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("530824DA86689C9C17CdC2871Ff29B058345b44a")),
-            inner:   TokenInfo { decimals: 18, symbol: "STBT".to_string() },
+            inner:   TokenInfo::new(18, "STBT".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -433,6 +408,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -18,23 +18,13 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = details
+            .token(log.sold_id as usize)
+            .ok_or(eyre::eyre!("Expected token{} for token in, found None", log.sold_id))?;
 
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_out_addr = details
+            .token(log.bought_id as usize)
+            .ok_or(eyre::eyre!("Expected token{} for token out, found None", log.bought_id))?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -53,7 +43,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -72,23 +65,13 @@ action_impl!(
 
         let details = db_tx.get_protocol_details(info.from_address)?;
 
-        let token_in_addr = match log.sold_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token in, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token in, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token in, found None"))?,
-            _ => unreachable!()
-        };
+        let token_in_addr = details
+            .token(log.sold_id as usize)
+            .ok_or(eyre::eyre!("Expected token{} for token in, found None", log.sold_id))?;
 
-        let token_out_addr = match log.bought_id {
-            0 => details.token0,
-            1 => details.token1,
-            2 => details.token2.ok_or(eyre::eyre!("Expected token2 for token out, found None"))?,
-            3 => details.token3.ok_or(eyre::eyre!("Expected token3 for token out, found None"))?,
-            4 => details.token4.ok_or(eyre::eyre!("Expected token4 for token out, found None"))?,
-            _ => unreachable!()
-        };
+        let token_out_addr = details
+            .token(log.bought_id as usize)
+            .ok_or(eyre::eyre!("Expected token{} for token out, found None", log.bought_id))?;
 
         let token_in = db_tx.try_fetch_token_info(token_in_addr)?;
         let token_out = db_tx.try_fetch_token_info(token_out_addr)?;
@@ -107,7 +90,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -145,12 +131,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("D533a949740bb3306d119CC777fa900bA034cd52")),
-            inner:   TokenInfo { decimals: 18, symbol: "CRV".to_string() },
+            inner:   TokenInfo::new(18, "CRV".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -171,6 +157,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -203,12 +192,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::new(hex!("62B9c7356A2Dc64a1969e19C23e4f579F9810Aa7")),
-            inner:   TokenInfo { decimals: 18, symbol: "cvxCRV".to_string() },
+            inner:   TokenInfo::new(18, "cvxCRV".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::new(hex!("D533a949740bb3306d119CC777fa900bA034cd52")),
-            inner:   TokenInfo { decimals: 18, symbol: "CRV".to_string() },
+            inner:   TokenInfo::new(18, "CRV".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -229,6 +218,9 @@ mod tests {
                 .unwrap()
                 .to_scaled_rational(18),
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -16,6 +16,9 @@ action_impl!(
             protocol: Protocol::Dodo,
             pool_address: logs.dvm,
             tokens: vec![logs.baseToken, logs.quoteToken],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -34,6 +37,9 @@ action_impl!(
             protocol: Protocol::Dodo,
             pool_address: logs.DSP,
             tokens: vec![logs.baseToken, logs.quoteToken],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -55,6 +61,9 @@ action_impl!(
             protocol: Protocol::Dodo,
             pool_address: logs.dpp,
             tokens: vec![base_token, quote_token],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -74,13 +83,16 @@ mod tests {
             B256::from(hex!("620f07fc5d7781598214e2524b8c226ae8e475ec422fdad1272ab2775a80bf0a"));
 
         let new_pool = Action::NewPool(NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::Dodo,
+            trace_index: 1,
+            protocol: Protocol::Dodo,
             pool_address: Address::new(hex!("0f5814de3581cb1d8ad2b608d6ef2e6409738c36")),
-            tokens:       vec![
+            tokens: vec![
                 Address::new(hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")),
                 Address::new(hex!("9aFa9999e45484Adf5d8EED8D9Dfe0693BACd838")),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -101,13 +113,16 @@ mod tests {
             B256::from(hex!("feb3000cd801ad15204235813eab94004d697ccba75cc9e082dc96c5e63c1529"));
 
         let new_pool = Action::NewPool(NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::Dodo,
+            trace_index: 1,
+            protocol: Protocol::Dodo,
             pool_address: Address::new(hex!("ea2c9470aec6251ef10a28d783ab877d17706bc4")),
-            tokens:       vec![
+            tokens: vec![
                 Address::new(hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")),
                 Address::new(hex!("99ea4dB9EE77ACD40B119BD1dC4E33e1C070b80d")),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -128,13 +143,16 @@ mod tests {
             B256::from(hex!("6268fa8c5bf169e319d9e16734adc34199c8b0d7256bd9cec6aa18b7c18f1bcc"));
 
         let new_pool = Action::NewPool(NormalizedNewPool {
-            trace_index:  10,
-            protocol:     Protocol::Dodo,
+            trace_index: 10,
+            protocol: Protocol::Dodo,
             pool_address: Address::new(hex!("0b16EeAb0f35f07011886F3e72A8cd468a0009ed")),
-            tokens:       vec![
+            tokens: vec![
                 Address::new(hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")),
                 Address::new(hex!("9d71CE49ab8A0E6D2a1e7BFB89374C9392FD6804")),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
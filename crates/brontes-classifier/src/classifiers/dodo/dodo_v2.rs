@@ -32,7 +32,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -63,7 +66,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -101,7 +107,9 @@ action_impl!(
             child_actions: vec![],
             repayments: vec![],
             fees_paid: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -141,7 +149,9 @@ action_impl!(
             recipient: call_data.to,
             pool: info.target_address,
             token,
-            amount
+            amount,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -181,7 +191,9 @@ action_impl!(
             recipient: call_data.to,
             pool: info.target_address,
             token,
-            amount
+            amount,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -210,7 +222,7 @@ mod tests {
         let token = vec![
             TokenInfoWithAddress {
                 address: Address::new(hex!("888f538aa0634472d3f038f225c59b5847cde015")),
-                inner:   TokenInfo { decimals: 18, symbol: "NGN".to_string() },
+                inner:   TokenInfo::new(18, "NGN".to_string()),
             },
             TokenInfoWithAddress::weth(),
         ];
@@ -243,6 +255,8 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -265,7 +279,7 @@ mod tests {
         let token = vec![
             TokenInfoWithAddress {
                 address: Address::new(hex!("9bf1d7d63dd7a4ce167cf4866388226eeefa702e")),
-                inner:   TokenInfo { decimals: 18, symbol: "BEN".to_string() },
+                inner:   TokenInfo::new(18, "BEN".to_string()),
             },
             TokenInfoWithAddress::weth(),
         ];
@@ -298,6 +312,8 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -323,7 +339,7 @@ mod tests {
     //     let token_out = TokenInfoWithAddress {
     //         address:
     // Address::new(hex!("9bf1d7d63dd7a4ce167cf4866388226eeefa702e")),
-    //         inner:   TokenInfo { decimals: 18, symbol: "BEN".to_string() },
+    //         inner:   TokenInfo::new(18, "BEN".to_string()),
     //     };
 
     //     classifier_utils.ensure_token(token_out.clone());
@@ -0,0 +1,127 @@
+use alloy_primitives::{Address, Log};
+use alloy_sol_types::{SolCall, SolEvent};
+use brontes_core::missing_token_info::TokenDecimalResolver;
+use brontes_types::{
+    db::{
+        token_info::TokenInfoWithAddress,
+        traits::{DBWriter, LibmdbxReader},
+    },
+    make_call_request,
+    normalized_actions::{Action, NormalizedVaultDeposit, NormalizedVaultWithdraw},
+    structured_trace::{TraceActions, TransactionTraceWithLogs},
+    traits::TracingProvider,
+    Protocol, ToScaledRational,
+};
+
+alloy_sol_macro::sol!(
+    function deposit(uint256 assets, address receiver) returns (uint256 shares);
+    function mint(uint256 shares, address receiver) returns (uint256 assets);
+    function withdraw(uint256 assets, address receiver, address owner) returns (uint256 shares);
+    function redeem(uint256 shares, address receiver, address owner) returns (uint256 assets);
+    function asset() returns (address);
+
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(
+        address indexed sender,
+        address indexed receiver,
+        address indexed owner,
+        uint256 assets,
+        uint256 shares
+    );
+);
+
+/// Whether the calldata calls one of the vault entry points
+pub fn is_vault_call(calldata: &[u8]) -> bool {
+    [depositCall::SELECTOR, mintCall::SELECTOR, withdrawCall::SELECTOR, redeemCall::SELECTOR]
+        .iter()
+        .any(|selector| calldata.starts_with(selector))
+}
+
+/// Classifies calls to any ERC-4626 vault. The call has to be one of the vault
+/// entry points and the vault has to emit the matching `Deposit` or `Withdraw`
+/// event, as the selectors alone are shared with plenty of other contracts.
+/// The amounts are taken from the event, so `deposit` & `mint` and `withdraw`
+/// & `redeem` end up as the same action.
+pub async fn try_decode_vault_action<'a, T: TracingProvider, DB: LibmdbxReader + DBWriter>(
+    trace_index: u64,
+    trace: &TransactionTraceWithLogs,
+    logs: impl IntoIterator<Item = &'a Log>,
+    db: &DB,
+    resolver: &TokenDecimalResolver<T>,
+    block: u64,
+) -> Option<Action> {
+    let calldata = trace.get_calldata();
+    if !is_vault_call(&calldata) {
+        return None
+    }
+    let is_deposit =
+        calldata.starts_with(&depositCall::SELECTOR) || calldata.starts_with(&mintCall::SELECTOR);
+
+    let vault = trace.get_to_address();
+    let mut vault_logs = logs.into_iter().filter(|log| log.address == vault);
+
+    if is_deposit {
+        let event = vault_logs.find_map(|log| {
+            (log.topics().first() == Some(&Deposit::SIGNATURE_HASH))
+                .then(|| Deposit::decode_log_data(&log.data, false).ok())
+                .flatten()
+        })?;
+        let (asset, share) = vault_tokens(vault, db, resolver, block).await?;
+
+        Some(Action::VaultDeposit(NormalizedVaultDeposit {
+            protocol: Protocol::Erc4626,
+            trace_index,
+            from: event.sender,
+            owner: event.owner,
+            vault,
+            assets: event.assets.to_scaled_rational(asset.decimals),
+            shares: event.shares.to_scaled_rational(share.decimals),
+            asset,
+            share,
+            msg_value: trace.get_msg_value(),
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }))
+    } else {
+        let event = vault_logs.find_map(|log| {
+            (log.topics().first() == Some(&Withdraw::SIGNATURE_HASH))
+                .then(|| Withdraw::decode_log_data(&log.data, false).ok())
+                .flatten()
+        })?;
+        let (asset, share) = vault_tokens(vault, db, resolver, block).await?;
+
+        Some(Action::VaultWithdraw(NormalizedVaultWithdraw {
+            protocol: Protocol::Erc4626,
+            trace_index,
+            from: event.sender,
+            receiver: event.receiver,
+            owner: event.owner,
+            vault,
+            assets: event.assets.to_scaled_rational(asset.decimals),
+            shares: event.shares.to_scaled_rational(share.decimals),
+            asset,
+            share,
+            msg_value: trace.get_msg_value(),
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }))
+    }
+}
+
+/// The underlying asset of the vault and the vault share token
+async fn vault_tokens<T: TracingProvider, DB: LibmdbxReader + DBWriter>(
+    vault: Address,
+    db: &DB,
+    resolver: &TokenDecimalResolver<T>,
+    block: u64,
+) -> Option<(TokenInfoWithAddress, TokenInfoWithAddress)> {
+    let asset = make_call_request(assetCall::new(()), resolver.provider(), vault, Some(block))
+        .await
+        .ok()?
+        ._0;
+
+    Some((
+        resolver.token_info(db, block, asset).await.ok()?,
+        resolver.token_info(db, block, vault).await.ok()?,
+    ))
+}
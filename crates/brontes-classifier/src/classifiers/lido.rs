@@ -0,0 +1,196 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    constants::STETH_ADDRESS,
+    normalized_actions::{CustomActionBox, NormalizedStake, NormalizedWrap},
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+
+// stETH is minted 1:1 for the eth submitted
+action_impl!(
+    Protocol::Lido,
+    crate::Lido::submitCall,
+    Custom,
+    [..],
+    |info: CallInfo, db_tx: &DB| {
+        let steth = db_tx.try_fetch_token_info(info.target_address)?;
+        let amount = info.msg_value.to_scaled_rational(18);
+
+        Ok(CustomActionBox::new(NormalizedStake {
+            protocol:    Protocol::Lido,
+            trace_index: info.trace_idx,
+            from:        info.from_address,
+            recipient:   info.from_address,
+            pool:        info.target_address,
+            amount_in:   amount.clone(),
+            token:       steth,
+            amount_out:  amount,
+            msg_value:   info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::Lido,
+    crate::WstETH::wrapCall,
+    Custom,
+    [..],
+    call_data: true,
+    return_data: true,
+    |info: CallInfo, call_data: wrapCall, return_data: wrapReturn, db_tx: &DB| {
+        let steth = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+        let wsteth = db_tx.try_fetch_token_info(info.target_address)?;
+
+        Ok(CustomActionBox::new(NormalizedWrap {
+            protocol: Protocol::Lido,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            wrapper: info.target_address,
+            amount_in: call_data._stETHAmount.to_scaled_rational(steth.decimals),
+            token_in: steth,
+            amount_out: return_data._0.to_scaled_rational(wsteth.decimals),
+            token_out: wsteth,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::Lido,
+    crate::WstETH::unwrapCall,
+    Custom,
+    [..],
+    call_data: true,
+    return_data: true,
+    |info: CallInfo, call_data: unwrapCall, return_data: unwrapReturn, db_tx: &DB| {
+        let steth = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+        let wsteth = db_tx.try_fetch_token_info(info.target_address)?;
+
+        Ok(CustomActionBox::new(NormalizedWrap {
+            protocol: Protocol::Lido,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            wrapper: info.target_address,
+            amount_in: call_data._wstETHAmount.to_scaled_rational(wsteth.decimals),
+            token_in: wsteth,
+            amount_out: return_data._0.to_scaled_rational(steth.decimals),
+            token_out: steth,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::Action,
+    };
+    use malachite::Rational;
+
+    use super::*;
+    use crate::{
+        Lido::submitCall,
+        WstETH::{unwrapCall, wrapCall},
+    };
+
+    const STAKING: Address = Address::new(hex!("00000000000000000000000000000000feed1d00"));
+    const WRAPPER: Address = Address::new(hex!("00000000000000000000000000000000feed1d01"));
+
+    /// Registers a staking token and its wrapper, both with 18 decimals, and
+    /// stETH the wrapper classifier looks up
+    fn ensure_contracts(utils: &ClassifierTestUtils) {
+        let tokens = [(STAKING, "stTEST"), (WRAPPER, "wstTEST"), (STETH_ADDRESS, "stETH")].map(
+            |(address, symbol)| TokenInfoWithAddress {
+                address,
+                inner: TokenInfo::new(18, symbol.to_string()),
+            },
+        );
+        for address in [STAKING, WRAPPER] {
+            utils.ensure_protocol_with_tokens(
+                Protocol::Lido,
+                address,
+                STETH_ADDRESS,
+                None,
+                &tokens,
+            );
+        }
+    }
+
+    fn classify(
+        utils: &ClassifierTestUtils,
+        target: Address,
+        call_data: Vec<u8>,
+        return_data: Vec<u8>,
+        msg_value: U256,
+    ) -> Action {
+        utils
+            .classify_call(target, call_data, return_data, &[], msg_value)
+            .expect("lido call wasn't classified")
+    }
+
+    #[brontes_macros::test]
+    async fn test_lido_submit() {
+        let utils = ClassifierTestUtils::new().await;
+        ensure_contracts(&utils);
+
+        let two_eth = U256::from(2 * 10u64.pow(18));
+        let action = classify(
+            &utils,
+            STAKING,
+            submitCall { _referral: Address::ZERO }.abi_encode(),
+            submitCall::abi_encode_returns(&(two_eth,)),
+            two_eth,
+        );
+
+        let stake = action.try_custom_ref::<NormalizedStake>().unwrap();
+        assert_eq!(stake.protocol, Protocol::Lido);
+        assert_eq!(stake.token.address, STAKING);
+        assert_eq!(
+            (stake.from, stake.recipient),
+            (Address::with_last_byte(1), Address::with_last_byte(1))
+        );
+        assert_eq!(stake.amount_in, Rational::from(2));
+        assert_eq!(stake.amount_out, Rational::from(2));
+        assert_eq!(stake.msg_value, two_eth);
+    }
+
+    #[brontes_macros::test]
+    async fn test_lido_wrap_and_unwrap() {
+        let utils = ClassifierTestUtils::new().await;
+        ensure_contracts(&utils);
+
+        // 1.15 stETH per wstETH
+        let steth = U256::from(115 * 10u64.pow(16));
+        let wsteth = U256::from(10u64.pow(18));
+
+        let action = classify(
+            &utils,
+            WRAPPER,
+            wrapCall { _stETHAmount: steth }.abi_encode(),
+            wrapCall::abi_encode_returns(&(wsteth,)),
+            U256::ZERO,
+        );
+        let wrap = action.try_custom_ref::<NormalizedWrap>().unwrap();
+        assert_eq!((wrap.token_in.address, wrap.token_out.address), (STETH_ADDRESS, WRAPPER));
+        assert_eq!(wrap.amount_in, Rational::from_unsigneds(23u32, 20u32));
+        assert_eq!(wrap.amount_out, Rational::from(1));
+        assert_eq!(wrap.wrapper, WRAPPER);
+
+        let action = classify(
+            &utils,
+            WRAPPER,
+            unwrapCall { _wstETHAmount: wsteth }.abi_encode(),
+            unwrapCall::abi_encode_returns(&(steth,)),
+            U256::ZERO,
+        );
+        let unwrap = action.try_custom_ref::<NormalizedWrap>().unwrap();
+        assert_eq!((unwrap.token_in.address, unwrap.token_out.address), (WRAPPER, STETH_ADDRESS));
+        assert_eq!(unwrap.amount_in, Rational::from(1));
+        assert_eq!(unwrap.amount_out, Rational::from_unsigneds(23u32, 20u32));
+    }
+}
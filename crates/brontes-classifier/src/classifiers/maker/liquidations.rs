@@ -0,0 +1,95 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::{CustomActionBox, NormalizedAuctionStart, NormalizedLiquidation},
+    structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+/// Decimals of a vat `rad`, the unit debt is accounted in
+const RAD: u8 = 45;
+/// Decimals of a vat `ray`, the unit auction prices are quoted in
+const RAY: u8 = 27;
+/// Decimals of a vat `wad`. Collateral in the vat is always normalized to 18
+/// decimals by its join adapter
+const WAD: u8 = 18;
+
+// `bark` starts a collateral auction for an unsafe vault. The keeper only
+// earns the auction incentive and the collateral is sold later through
+// `Clipper.take`, which is the liquidation, so the start of the auction is
+// classified as an action of its own. The protocol details of the clipper
+// hold the collateral and DAI.
+action_impl!(
+    Protocol::MakerDog,
+    crate::MakerDog::barkCall,
+    Custom,
+    [Bark],
+    call_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: barkCall,
+    log_data: MakerDogBarkCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.bark_field?;
+
+        let details = db_tx.get_protocol_details(logs.clip)?;
+        let collateral_info = db_tx.try_fetch_token_info(details.token0)?;
+        let debt_info = db_tx.try_fetch_token_info(details.token1)?;
+
+        Ok(CustomActionBox::new(NormalizedAuctionStart {
+            protocol:         Protocol::MakerDog,
+            trace_index:      info.trace_idx,
+            from:             info.msg_sender,
+            keeper:           call_data.kpr,
+            pool:             logs.clip,
+            auction_id:       logs.id,
+            collateral_type:  logs.ilk,
+            debtor:           logs.urn,
+            collateral_asset: collateral_info,
+            debt_asset:       debt_info,
+            collateral:       logs.ink.to_scaled_rational(WAD),
+            debt:             logs.due.to_scaled_rational(RAD),
+            msg_value:        info.msg_value,
+        }))
+    }
+);
+
+// `take` buys collateral out of a running auction. Both legs settle inside the
+// vat, `who` is credited the collateral and the caller pays `owe` DAI for it.
+// The caller is the liquidator and `usr` the owner of the liquidated vault.
+action_impl!(
+    Protocol::MakerClipper,
+    crate::MakerClipper::takeCall,
+    Liquidation,
+    [Take],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: MakerClipperTakeCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.take_field?;
+
+        let details = db_tx.get_protocol_details(info.target_address)?;
+        let collateral_info = db_tx.try_fetch_token_info(details.token0)?;
+        let debt_info = db_tx.try_fetch_token_info(details.token1)?;
+
+        let covered_debt = logs.owe.to_scaled_rational(RAD);
+        // owe = slice * price
+        let liquidated_collateral = &covered_debt / logs.price.to_scaled_rational(RAY);
+
+        Ok(NormalizedLiquidation {
+            protocol: Protocol::MakerClipper,
+            trace_index: info.trace_idx,
+            pool: info.target_address,
+            liquidator: info.msg_sender,
+            debtor: logs.usr,
+            collateral_asset: collateral_info,
+            debt_asset: debt_info,
+            covered_debt,
+            liquidated_collateral,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
@@ -6,3 +6,7 @@ pub use psm::*;
 mod dss_flash;
 
 pub use dss_flash::*;
+
+mod liquidations;
+
+pub use liquidations::*;
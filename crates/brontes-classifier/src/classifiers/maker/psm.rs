@@ -55,6 +55,9 @@ action_impl!(
             amount_in,
             amount_out,
             msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
 
     }
@@ -104,6 +107,9 @@ action_impl!(
             amount_in,
             amount_out,
             msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
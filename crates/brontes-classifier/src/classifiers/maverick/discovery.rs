@@ -0,0 +1,20 @@
+use alloy_primitives::Address;
+use brontes_macros::discovery_impl;
+use brontes_pricing::Protocol;
+
+discovery_impl!(
+    MaverickV2Discovery,
+    crate::MaverickV2Factory::createCall,
+    0x0A7e848Aca42d879EF06507Fca0E7b33A0a63c1e,
+    |deployed_address: Address, trace_index: u64, call_data: createCall, _| async move {
+        vec![NormalizedNewPool {
+            pool_address: deployed_address,
+            trace_index,
+            protocol: Protocol::MaverickV2,
+            tokens: vec![call_data.tokenA, call_data.tokenB],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }]
+    }
+);
@@ -0,0 +1,255 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::{NormalizedBurn, NormalizedMint, NormalizedSwap},
+    structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+action_impl!(
+    Protocol::MaverickV2,
+    crate::MaverickV2Pool::swapCall,
+    Swap,
+    [..],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: swapCall,
+    return_data: swapReturn,
+    db_tx: &DB| {
+        let details = db_tx.get_protocol_details_sorted(info.target_address)?;
+        let [token_a, token_b] = [details.token0, details.token1];
+
+        let (token_in, token_out) = if call_data.params.tokenAIn {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+        let token_in = db_tx.try_fetch_token_info(token_in)?;
+        let token_out = db_tx.try_fetch_token_info(token_out)?;
+
+        let amount_in = return_data.amountIn.to_scaled_rational(token_in.decimals);
+        let amount_out = return_data.amountOut.to_scaled_rational(token_out.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::MaverickV2,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.recipient,
+            pool: info.target_address,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::MaverickV2,
+    crate::MaverickV2Pool::addLiquidityCall,
+    Mint,
+    [..],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: addLiquidityCall,
+    return_data: addLiquidityReturn,
+    db_tx: &DB| {
+        let details = db_tx.get_protocol_details_sorted(info.target_address)?;
+        let [token_a, token_b] = [details.token0, details.token1];
+
+        let t0_info = db_tx.try_fetch_token_info(token_a)?;
+        let t1_info = db_tx.try_fetch_token_info(token_b)?;
+
+        let am0 = return_data.tokenAAmount.to_scaled_rational(t0_info.decimals);
+        let am1 = return_data.tokenBAmount.to_scaled_rational(t1_info.decimals);
+
+        Ok(NormalizedMint {
+            protocol: Protocol::MaverickV2,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.recipient,
+            pool: info.target_address,
+            token: vec![t0_info, t1_info],
+            amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::MaverickV2,
+    crate::MaverickV2Pool::removeLiquidityCall,
+    Burn,
+    [..],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: removeLiquidityCall,
+    return_data: removeLiquidityReturn,
+    db_tx: &DB| {
+        let details = db_tx.get_protocol_details_sorted(info.target_address)?;
+        let [token_a, token_b] = [details.token0, details.token1];
+
+        let t0_info = db_tx.try_fetch_token_info(token_a)?;
+        let t1_info = db_tx.try_fetch_token_info(token_b)?;
+
+        let am0 = return_data.tokenAOut.to_scaled_rational(t0_info.decimals);
+        let am1 = return_data.tokenBOut.to_scaled_rational(t1_info.decimals);
+
+        Ok(NormalizedBurn {
+            protocol: Protocol::MaverickV2,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.recipient,
+            pool: info.target_address,
+            token: vec![t0_info, t1_info],
+            amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::Action,
+    };
+    use malachite::Rational;
+
+    use super::*;
+    use crate::MaverickV2Pool::{
+        addLiquidityCall, removeLiquidityCall, swapCall, AddLiquidityParams, RemoveLiquidityParams,
+        SwapParams,
+    };
+
+    const POOL: Address = Address::new(hex!("00000000000000000000000000000000feed4d02"));
+
+    /// Registers the pool with token a, the lower address with 18 decimals, and
+    /// token b with 6 decimals
+    fn ensure_pool(utils: &ClassifierTestUtils) -> [TokenInfoWithAddress; 2] {
+        let tokens = [(0x0a, 18, "MAVA"), (0x0b, 6, "MAVB")].map(|(byte, decimals, symbol)| {
+            TokenInfoWithAddress {
+                address: Address::left_padding_from(&[0xfe, 0xed, 0x4d, byte]),
+                inner:   TokenInfo::new(decimals, symbol.to_string()),
+            }
+        });
+        utils.ensure_protocol_with_tokens(
+            Protocol::MaverickV2,
+            POOL,
+            tokens[1].address,
+            Some(tokens[0].address),
+            &tokens,
+        );
+
+        tokens
+    }
+
+    fn classify(utils: &ClassifierTestUtils, call_data: Vec<u8>, return_data: Vec<u8>) -> Action {
+        utils
+            .classify_call(POOL, call_data, return_data, &[], U256::ZERO)
+            .expect("maverick call wasn't classified")
+    }
+
+    fn swap(token_a_in: bool) -> Vec<u8> {
+        swapCall {
+            recipient: Address::with_last_byte(2),
+            params:    SwapParams {
+                amount:      U256::from(10u64.pow(18)),
+                tokenAIn:    token_a_in,
+                exactOutput: false,
+                tickLimit:   0,
+            },
+            data:      Bytes::new(),
+        }
+        .abi_encode()
+    }
+
+    #[brontes_macros::test]
+    async fn test_maverick_v2_swap_direction() {
+        let utils = ClassifierTestUtils::new().await;
+        let [token_a, token_b] = ensure_pool(&utils);
+
+        let Action::Swap(swap_a_in) = classify(
+            &utils,
+            swap(true),
+            swapCall::abi_encode_returns(&(U256::from(2 * 10u64.pow(18)), U256::from(3_000_000))),
+        ) else {
+            panic!("expected a swap")
+        };
+        assert_eq!(swap_a_in.token_in, token_a);
+        assert_eq!(swap_a_in.token_out, token_b);
+        assert_eq!(swap_a_in.amount_in, Rational::from(2));
+        assert_eq!(swap_a_in.amount_out, Rational::from(3));
+        assert_eq!(swap_a_in.recipient, Address::with_last_byte(2));
+
+        let Action::Swap(swap_b_in) = classify(
+            &utils,
+            swap(false),
+            swapCall::abi_encode_returns(&(U256::from(3_000_000), U256::from(2 * 10u64.pow(18)))),
+        ) else {
+            panic!("expected a swap")
+        };
+        assert_eq!(swap_b_in.token_in, token_b);
+        assert_eq!(swap_b_in.token_out, token_a);
+        assert_eq!(swap_b_in.amount_in, Rational::from(3));
+        assert_eq!(swap_b_in.amount_out, Rational::from(2));
+    }
+
+    #[brontes_macros::test]
+    async fn test_maverick_v2_liquidity() {
+        let utils = ClassifierTestUtils::new().await;
+        let tokens = ensure_pool(&utils);
+
+        let add = addLiquidityCall {
+            recipient:  Address::with_last_byte(2),
+            subaccount: U256::ZERO,
+            params:     AddLiquidityParams { kind: 0, ticks: vec![0, 1], amounts: vec![1, 1] },
+            data:       Bytes::new(),
+        };
+        let Action::Mint(mint) = classify(
+            &utils,
+            add.abi_encode(),
+            addLiquidityCall::abi_encode_returns(&(
+                U256::from(5 * 10u64.pow(18)),
+                U256::from(7_000_000),
+                vec![1, 2],
+            )),
+        ) else {
+            panic!("expected a mint")
+        };
+        assert_eq!(mint.token, tokens.to_vec());
+        assert_eq!(mint.amount, vec![Rational::from(5), Rational::from(7)]);
+
+        let remove = removeLiquidityCall {
+            recipient:  Address::with_last_byte(2),
+            subaccount: U256::ZERO,
+            params:     RemoveLiquidityParams { binIds: vec![1, 2], amounts: vec![1, 1] },
+        };
+        let Action::Burn(burn) = classify(
+            &utils,
+            remove.abi_encode(),
+            removeLiquidityCall::abi_encode_returns(&(
+                U256::from(10u64.pow(18)),
+                U256::from(4_000_000),
+            )),
+        ) else {
+            panic!("expected a burn")
+        };
+        assert_eq!(burn.token, tokens.to_vec());
+        assert_eq!(burn.amount, vec![Rational::from(1), Rational::from(4)]);
+    }
+}
@@ -0,0 +1,10 @@
+//! Maverick V2 swaps, liquidity changes and pool deployments. The bin
+//! liquidity of the pools isn't simulated, so they aren't part of the pricing
+//! graph: the legs of an arb through a Maverick pool are valued with the
+//! prices of the pair on the pools that are, and a pair that only trades on
+//! Maverick has no dex price.
+mod discovery;
+mod maverick_v2;
+
+pub use discovery::*;
+pub use maverick_v2::*;
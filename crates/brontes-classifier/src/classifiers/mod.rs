@@ -1,6 +1,9 @@
 use brontes_macros::{action_dispatch, discovery_dispatch};
 use futures::StreamExt;
+pub mod approval;
 pub mod erc20;
+pub mod erc4626;
+pub mod weth;
 
 pub mod uniswap;
 pub use uniswap::*;
@@ -41,6 +44,18 @@ pub use clipper::*;
 pub mod dodo;
 pub use dodo::*;
 
+pub mod maverick;
+pub use maverick::*;
+
+pub mod nft;
+pub use nft::*;
+
+pub mod lido;
+pub use lido::*;
+
+pub mod rocket_pool;
+pub use rocket_pool::*;
+
 discovery_dispatch!(
     DiscoveryClassifier,
     SushiSwapV2Discovery,
@@ -49,6 +64,7 @@ discovery_dispatch!(
     UniswapV3Discovery,
     PancakeSwapV3Discovery,
     PancakeSwapV2Discovery,
+    MaverickV2Discovery,
     CurveV1MetaDiscovery,
     CurveV2PlainDiscovery0,
     CurveV2PlainDiscovery1,
@@ -89,6 +105,21 @@ action_dispatch!(
     PancakeSwapV3MintCall,
     PancakeSwapV3BurnCall,
     PancakeSwapV3CollectCall,
+    UniswapV4InitializeCall,
+    UniswapV4SwapCall,
+    UniswapV4ModifyLiquidityCall,
+    UniswapV4SettleCall,
+    UniswapV4TakeCall,
+    SeaportFulfillBasicOrderCall,
+    SeaportFulfillBasicOrder_efficient_6GL6ycCall,
+    SeaportMatchOrdersCall,
+    BlurExecuteCall,
+    BlurBulkExecuteCall,
+    LidoSubmitCall,
+    LidoWrapCall,
+    LidoUnwrapCall,
+    RocketPoolMintCall,
+    RocketPoolBurnCall,
     UniswapXExecuteCall,
     UniswapXExecuteBatchCall,
     UniswapXExecuteBatchWithCallbackCall,
@@ -142,9 +173,17 @@ action_dispatch!(
     CurveV2PlainPoolImplRemove_liquidity_imbalance_1Call,
     CurveV2PlainPoolImplRemove_liquidity_one_coin_0Call,
     CurveV2PlainPoolImplRemove_liquidity_one_coin_1Call,
+    CurvecrvUSDPlainPoolImplExchange_0Call,
+    CurvecrvUSDPlainPoolImplExchange_1Call,
+    CurvecrvUSDMetapoolImplExchange_0Call,
+    CurvecrvUSDMetapoolImplExchange_1Call,
+    CurvecrvUSDMetapoolImplExchange_underlying_0Call,
+    CurvecrvUSDMetapoolImplExchange_underlying_1Call,
     MakerPSMBuyGemCall,
     MakerPSMSellGemCall,
     MakerDssFlashFlashLoanCall,
+    MakerDogBarkCall,
+    MakerClipperTakeCall,
     AaveV2LiquidationCallCall,
     AaveV3LiquidationCallCall,
     AaveV2FlashLoanCall,
@@ -160,6 +199,7 @@ action_dispatch!(
     BalancerV2ExitPoolCall,
     BalancerV2RegisterTokensCall,
     CompoundV2LiquidateBorrowCall,
+    CompoundV3BuyCollateralCall,
     CompoundV2Initialize_0Call,
     CompoundV2Initialize_1Call,
     OneInchV5SwapCall,
@@ -170,6 +210,7 @@ action_dispatch!(
     OneInchV5UnoswapToWithPermitCall,
     OneInchV5UniswapV3SwapToCall,
     OneInchV5UniswapV3SwapToWithPermitCall,
+    OneInchV5FillOrderCall,
     OneInchFusionSettleOrdersCall,
     ClipperExchangeSwapCall,
     ClipperExchangeSellEthForTokenCall,
@@ -208,5 +249,8 @@ action_dispatch!(
     DodoSellSharesCall,
     DodoSellBaseCall,
     DodoSellQuoteCall,
-    DodoFlashLoanCall
+    DodoFlashLoanCall,
+    MaverickV2SwapCall,
+    MaverickV2AddLiquidityCall,
+    MaverickV2RemoveLiquidityCall
 );
@@ -0,0 +1,179 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::{CustomActionBox, NftFill, NormalizedNftTrade},
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+
+use super::payment_token_info;
+use crate::BlurExchange::{Execution, Input, Order, OrdersMatched};
+
+action_impl!(
+    Protocol::Blur,
+    crate::BlurExchange::executeCall,
+    Custom,
+    [],
+    call_data: true,
+    |info: CallInfo, call_data: executeCall, db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedNftTrade {
+            protocol: Protocol::Blur,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            marketplace: info.target_address,
+            fills: vec![fill(&call_data.sell, &call_data.buy, db_tx)?],
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+// the executions of a bulk execute are delegate calls to the exchange that
+// don't revert it when they fail, so only the executions that emitted their
+// `OrdersMatched` are filled
+action_impl!(
+    Protocol::Blur,
+    crate::BlurExchange::bulkExecuteCall,
+    Custom,
+    [..OrdersMatched*],
+    call_data: true,
+    logs: true,
+    include_delegated_logs: true,
+    |info: CallInfo, call_data: bulkExecuteCall, logs: BlurBulkExecuteCallLogs, db_tx: &DB| {
+        let matched = logs.orders_matched_field.unwrap_or_default();
+        let fills = matched_executions(&call_data.executions, &matched)
+            .map(|execution| fill(&execution.sell, &execution.buy, db_tx))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(CustomActionBox::new(NormalizedNftTrade {
+            protocol: Protocol::Blur,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            marketplace: info.target_address,
+            fills,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+/// The executions whose orders were matched, each `OrdersMatched` log matching
+/// at most one execution
+fn matched_executions<'a>(
+    executions: &'a [Execution],
+    matched: &[OrdersMatched],
+) -> impl Iterator<Item = &'a Execution> {
+    let mut unmatched = matched.iter().collect::<Vec<_>>();
+
+    executions.iter().filter(move |execution| {
+        let Some(i) = unmatched.iter().position(|log| {
+            same_order(&log.sell, &execution.sell.order)
+                && same_order(&log.buy, &execution.buy.order)
+        }) else {
+            return false
+        };
+        unmatched.swap_remove(i);
+        true
+    })
+}
+
+fn same_order(a: &Order, b: &Order) -> bool {
+    a.trader == b.trader
+        && a.side == b.side
+        && a.collection == b.collection
+        && a.tokenId == b.tokenId
+        && a.amount == b.amount
+        && a.price == b.price
+        && a.salt == b.salt
+        && a.listingTime == b.listingTime
+}
+
+/// The matched orders trade at the price of the sell order. The fees are taken
+/// out of the seller's share, so the buyer pays the full price.
+fn fill<DB: LibmdbxReader>(sell: &Input, buy: &Input, db: &DB) -> eyre::Result<NftFill> {
+    let payment_token = payment_token_info(sell.order.paymentToken, db)?;
+
+    Ok(NftFill {
+        seller: sell.order.trader,
+        buyer: buy.order.trader,
+        collection: sell.order.collection,
+        token_id: sell.order.tokenId,
+        amount: sell.order.amount,
+        price: sell.order.price.to_scaled_rational(payment_token.decimals),
+        payment_token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, Bytes, B256, U256};
+
+    use super::*;
+
+    fn order(trader: u8, side: u8, token_id: u64) -> Order {
+        Order {
+            trader: Address::with_last_byte(trader),
+            side,
+            matchingPolicy: Address::ZERO,
+            collection: Address::with_last_byte(0xbb),
+            tokenId: U256::from(token_id),
+            amount: U256::from(1),
+            paymentToken: Address::ZERO,
+            price: U256::from(10u64.pow(18)),
+            listingTime: U256::from(1),
+            expirationTime: U256::from(2),
+            fees: vec![],
+            salt: U256::from(token_id),
+            extraParams: Bytes::new(),
+        }
+    }
+
+    fn input(order: Order) -> Input {
+        Input {
+            order,
+            v: 27,
+            r: B256::ZERO,
+            s: B256::ZERO,
+            extraSignature: Bytes::new(),
+            signatureVersion: 0,
+            blockNumber: U256::ZERO,
+        }
+    }
+
+    fn execution(token_id: u64) -> Execution {
+        Execution { sell: input(order(1, 1, token_id)), buy: input(order(2, 0, token_id)) }
+    }
+
+    fn orders_matched(execution: &Execution) -> OrdersMatched {
+        OrdersMatched {
+            maker:    execution.sell.order.trader,
+            taker:    execution.buy.order.trader,
+            sell:     execution.sell.order.clone(),
+            sellHash: B256::ZERO,
+            buy:      execution.buy.order.clone(),
+            buyHash:  B256::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_bulk_execute_skips_failed_executions() {
+        let executions = vec![execution(1), execution(2), execution(3)];
+        // the second execution failed and emitted no log
+        let logs = vec![orders_matched(&executions[0]), orders_matched(&executions[2])];
+
+        let filled = matched_executions(&executions, &logs)
+            .map(|execution| execution.sell.order.tokenId)
+            .collect::<Vec<_>>();
+        assert_eq!(filled, vec![U256::from(1), U256::from(3)]);
+
+        assert_eq!(matched_executions(&executions, &[]).count(), 0);
+    }
+
+    #[test]
+    fn test_bulk_execute_matches_each_log_once() {
+        // the same orders submitted twice, only one of them went through
+        let executions = vec![execution(1), execution(1)];
+        let logs = vec![orders_matched(&executions[0])];
+
+        assert_eq!(matched_executions(&executions, &logs).count(), 1);
+    }
+}
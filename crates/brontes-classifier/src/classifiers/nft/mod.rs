@@ -0,0 +1,22 @@
+#[allow(non_snake_case)]
+mod blur;
+#[allow(non_snake_case, non_camel_case_types)]
+mod seaport;
+
+use alloy_primitives::Address;
+pub use blur::*;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::token_info::TokenInfoWithAddress;
+pub use seaport::*;
+
+/// The marketplaces use the zero address for native eth payments
+fn payment_token_info<DB: LibmdbxReader>(
+    token: Address,
+    db: &DB,
+) -> eyre::Result<TokenInfoWithAddress> {
+    if token == Address::ZERO {
+        return Ok(TokenInfoWithAddress::native_eth())
+    }
+
+    db.try_fetch_token_info(token)
+}
@@ -0,0 +1,173 @@
+use alloy_primitives::{Address, U256};
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::{CustomActionBox, NftFill, NormalizedNftTrade},
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+use itertools::Itertools;
+
+use super::payment_token_info;
+use crate::Seaport::{BasicOrderParameters, Execution};
+
+/// Basic order routes below this one pay for a listed nft, the ones from it
+/// on accept a bid on the nft
+const FIRST_BID_ROUTE: u8 = 4;
+
+action_impl!(
+    Protocol::Seaport,
+    crate::Seaport::fulfillBasicOrderCall,
+    Custom,
+    [],
+    call_data: true,
+    |info: CallInfo, call_data: fulfillBasicOrderCall, db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedNftTrade {
+            protocol: Protocol::Seaport,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            marketplace: info.target_address,
+            fills: vec![basic_order_fill(&call_data.parameters, info.from_address, db_tx)?],
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::Seaport,
+    crate::Seaport::fulfillBasicOrder_efficient_6GL6ycCall,
+    Custom,
+    [],
+    call_data: true,
+    |info: CallInfo, call_data: fulfillBasicOrder_efficient_6GL6ycCall, db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedNftTrade {
+            protocol: Protocol::Seaport,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            marketplace: info.target_address,
+            fills: vec![basic_order_fill(&call_data.parameters, info.from_address, db_tx)?],
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::Seaport,
+    crate::Seaport::matchOrdersCall,
+    Custom,
+    [],
+    return_data: true,
+    |info: CallInfo, return_data: matchOrdersReturn, db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedNftTrade {
+            protocol: Protocol::Seaport,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            marketplace: info.target_address,
+            fills: execution_fills(&return_data.executions, db_tx)?,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+/// A basic order is a single nft against eth or an erc20. The order type packs
+/// the route and the order type as `route * 4 + order type`.
+fn basic_order_fill<DB: LibmdbxReader>(
+    params: &BasicOrderParameters,
+    fulfiller: Address,
+    db: &DB,
+) -> eyre::Result<NftFill> {
+    if params.basicOrderType / 4 < FIRST_BID_ROUTE {
+        // the buyer pays the consideration and every additional recipient
+        let payment_token = payment_token_info(params.considerationToken, db)?;
+        let price = params
+            .additionalRecipients
+            .iter()
+            .fold(params.considerationAmount, |price, recipient| price + recipient.amount);
+
+        Ok(NftFill {
+            seller: params.offerer,
+            buyer: fulfiller,
+            collection: params.offerToken,
+            token_id: params.offerIdentifier,
+            amount: params.offerAmount,
+            price: price.to_scaled_rational(payment_token.decimals),
+            payment_token,
+        })
+    } else {
+        // the fees of an accepted bid are taken out of the bid
+        let payment_token = payment_token_info(params.offerToken, db)?;
+
+        Ok(NftFill {
+            seller: fulfiller,
+            buyer: params.offerer,
+            collection: params.considerationToken,
+            token_id: params.considerationIdentifier,
+            amount: params.considerationAmount,
+            price: params
+                .offerAmount
+                .to_scaled_rational(payment_token.decimals),
+            payment_token,
+        })
+    }
+}
+
+/// Builds the fills from the transfers seaport executed for the matched
+/// orders. A buyer's payments can't be matched to the individual nfts it
+/// received, so they are split evenly over them.
+fn execution_fills<DB: LibmdbxReader>(
+    executions: &[Execution],
+    db: &DB,
+) -> eyre::Result<Vec<NftFill>> {
+    let (nfts, payments): (Vec<_>, Vec<_>) = executions
+        .iter()
+        .partition(|execution| is_nft(execution.item.itemType));
+
+    nfts.iter()
+        .map(|nft| {
+            let buyer = nft.item.recipient;
+            let paid = payments
+                .iter()
+                .filter(|payment| payment.offerer == buyer)
+                .collect_vec();
+            let token = paid
+                .first()
+                .map(|payment| payment.item.token)
+                .unwrap_or_default();
+            let total = paid
+                .iter()
+                .filter(|payment| payment.item.token == token)
+                .fold(U256::ZERO, |total, payment| total + payment.item.amount);
+            let bought = nfts.iter().filter(|n| n.item.recipient == buyer).count();
+
+            let payment_token = payment_token_info(token, db)?;
+            Ok(NftFill {
+                seller: nft.offerer,
+                buyer,
+                collection: nft.item.token,
+                token_id: nft.item.identifier,
+                amount: nft.item.amount,
+                price: (total / U256::from(bought)).to_scaled_rational(payment_token.decimals),
+                payment_token,
+            })
+        })
+        .collect()
+}
+
+/// Erc721 and erc1155 items, including the criteria based ones
+fn is_nft(item_type: u8) -> bool {
+    (2..=5).contains(&item_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seaport_item_types() {
+        assert!(!is_nft(0));
+        assert!(!is_nft(1));
+        assert!((2..=5).all(is_nft));
+        assert!(!is_nft(6));
+    }
+}
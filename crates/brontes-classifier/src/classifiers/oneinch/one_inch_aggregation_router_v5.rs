@@ -1,6 +1,9 @@
 use brontes_macros::action_impl;
 use brontes_pricing::Protocol;
-use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+use brontes_types::{
+    normalized_actions::{CustomActionBox, NormalizedAggregator, NormalizedOrderFill},
+    structured_trace::CallInfo,
+};
 
 action_impl!(
     Protocol::OneInchV5,
@@ -20,7 +23,9 @@ action_impl!(
             to: info.target_address
                 , recipient: dst_receiver,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -43,7 +48,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -55,13 +62,15 @@ action_impl!(
     [],
     |info: CallInfo, _db_tx: &DB| {
         return Ok(NormalizedAggregator {
-            protocol:      Protocol::OneInchV5,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -84,7 +93,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -107,7 +118,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -130,7 +143,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -153,7 +168,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -176,7 +193,9 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -199,11 +218,39 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
 
+// the taker fills the limit order of the maker, the tokens are moved by the
+// transfers of the call
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderCall,
+    Custom,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderCall,
+    return_data: fillOrderReturn,
+    _db_tx: &DB | {
+        Ok(CustomActionBox::new(NormalizedOrderFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data._2,
+            filler: info.from_address,
+            swapper: call_data.order.maker,
+            settlement_contract: info.target_address,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -235,87 +282,104 @@ mod tests {
             child_actions: vec![
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 1,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("f4f8845cede63e79de1b2c3bba395e8547fe4283")),
-                    to:          Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    token:       TokenInfoWithAddress::usdc(),
-                    amount:      U256::from_str("126000000000")
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("f4f8845cede63e79de1b2c3bba395e8547fe4283")),
+                    to: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    token: TokenInfoWithAddress::usdc(),
+                    amount: U256::from_str("126000000000")
                         .unwrap()
                         .to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 5,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    to:          Address::new(hex!("beec796a4a2a27b687e1d48efad3805d78800522")),
-                    token:       TokenInfoWithAddress::usdc(),
-                    amount:      U256::from_str("441000000").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    to: Address::new(hex!("beec796a4a2a27b687e1d48efad3805d78800522")),
+                    token: TokenInfoWithAddress::usdc(),
+                    amount: U256::from_str("441000000").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Swap(NormalizedSwap {
-                    protocol:    UniswapV3,
+                    protocol: UniswapV3,
                     trace_index: 11,
-                    from:        Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    recipient:   Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    pool:        Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
-                    token_in:    TokenInfoWithAddress::usdc(),
-                    token_out:   TokenInfoWithAddress::usdt(),
-                    amount_in:   U256::from_str("125559000000")
+                    from: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    recipient: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    pool: Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
+                    token_in: TokenInfoWithAddress::usdc(),
+                    token_out: TokenInfoWithAddress::usdt(),
+                    amount_in: U256::from_str("125559000000")
                         .unwrap()
                         .to_scaled_rational(6),
-                    amount_out:  U256::from_str("125475168379")
+                    amount_out: U256::from_str("125475168379")
                         .unwrap()
                         .to_scaled_rational(6),
-                    msg_value:   U256::ZERO,
+                    msg_value: U256::ZERO,
+                    fee_tier: None,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 12,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
-                    to:          Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("125475168379")
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
+                    to: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("125475168379")
                         .unwrap()
                         .to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 16,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    to:          Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
-                    token:       TokenInfoWithAddress::usdc(),
-                    amount:      U256::from_str("125559000000")
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    to: Address::new(hex!("3416cf6c708da44db2624d63ea0aaef7113527c6")),
+                    token: TokenInfoWithAddress::usdc(),
+                    amount: U256::from_str("125559000000")
                         .unwrap()
                         .to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 21,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
-                    to:          Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("125475168379")
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("e37e799d5077682fa0a244d46e5649f71457bd09")),
+                    to: Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("125475168379")
                         .unwrap()
                         .to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 23,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
-                    to:          Address::new(hex!("f4f8845cede63e79de1b2c3bba395e8547fe4283")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("125475168379")
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
+                    to: Address::new(hex!("f4f8845cede63e79de1b2c3bba395e8547fe4283")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("125475168379")
                         .unwrap()
                         .to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
             ],
 
             msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
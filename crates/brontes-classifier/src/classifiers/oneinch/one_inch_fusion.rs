@@ -10,13 +10,15 @@ action_impl!(
     [],
     |info: CallInfo, _db_tx: &DB| {
         return Ok(NormalizedAggregator {
-            protocol:      Protocol::OneInchFusion,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     Address::default(),
+            protocol: Protocol::OneInchFusion,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: Address::default(),
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -51,64 +53,79 @@ mod tests {
             child_actions: vec![
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 5,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("d10f17699137dd6215c01f539726227fc042c9b2")),
-                    to:          Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    token:       TokenInfoWithAddress::usdc(),
-                    amount:      U256::from_str("269875186").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("d10f17699137dd6215c01f539726227fc042c9b2")),
+                    to: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    token: TokenInfoWithAddress::usdc(),
+                    amount: U256::from_str("269875186").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 9,
-                    msg_value:   U256::ZERO,
-                    from:        Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    to:          Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
-                    token:       TokenInfoWithAddress::usdc(),
-                    amount:      U256::from_str("269875186").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    from: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    to: Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
+                    token: TokenInfoWithAddress::usdc(),
+                    amount: U256::from_str("269875186").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Swap(NormalizedSwap {
-                    protocol:    ClipperExchange,
+                    protocol: ClipperExchange,
                     trace_index: 11,
-                    from:        Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    recipient:   Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    pool:        Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
-                    token_in:    TokenInfoWithAddress::usdc(),
-                    token_out:   TokenInfoWithAddress::usdt(),
-                    amount_in:   U256::from_str("269875186").unwrap().to_scaled_rational(6),
-                    amount_out:  U256::from_str("269716012").unwrap().to_scaled_rational(6),
-                    msg_value:   U256::ZERO,
+                    from: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    recipient: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    pool: Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
+                    token_in: TokenInfoWithAddress::usdc(),
+                    token_out: TokenInfoWithAddress::usdt(),
+                    amount_in: U256::from_str("269875186").unwrap().to_scaled_rational(6),
+                    amount_out: U256::from_str("269716012").unwrap().to_scaled_rational(6),
+                    msg_value: U256::ZERO,
+                    fee_tier: None,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 15,
-                    from:        Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
-                    to:          Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("269716012").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
-                    msg_value:   U256::ZERO,
+                    from: Address::new(hex!("655edce464cc797526600a462a8154650eee4b77")),
+                    to: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("269716012").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 16,
-                    from:        Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
-                    to:          Address::new(hex!("a88800cd213da5ae406ce248380802bd53b47647")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("216122672").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
-                    msg_value:   U256::ZERO,
+                    from: Address::new(hex!("235d3afac42f5e5ff346cb6c19af13194988551f")),
+                    to: Address::new(hex!("a88800cd213da5ae406ce248380802bd53b47647")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("216122672").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 18,
-                    from:        Address::new(hex!("a88800cd213da5ae406ce248380802bd53b47647")),
-                    to:          Address::new(hex!("d10f17699137dd6215c01f539726227fc042c9b2")),
-                    token:       TokenInfoWithAddress::usdt(),
-                    amount:      U256::from_str("216122672").unwrap().to_scaled_rational(6),
-                    fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
-                    msg_value:   U256::ZERO,
+                    from: Address::new(hex!("a88800cd213da5ae406ce248380802bd53b47647")),
+                    to: Address::new(hex!("d10f17699137dd6215c01f539726227fc042c9b2")),
+                    token: TokenInfoWithAddress::usdt(),
+                    amount: U256::from_str("216122672").unwrap().to_scaled_rational(6),
+                    fee: U256::from_str("0").unwrap().to_scaled_rational(1),
+                    msg_value: U256::ZERO,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 }),
             ],
 
             msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -19,6 +19,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::PancakeSwapV3,
             tokens: vec![token_a, token_b],
+            fee_tier: Some(call_data.fee.to()),
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -40,6 +43,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::PancakeSwapV2,
             tokens: vec![token_a, token_b],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -60,13 +66,16 @@ pub mod test {
             TxHash::new(hex!("2b16d7a3937375d50b29bbec621b3f33bee00c76d1f4c907ae483fa49f63e2f1"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::PancakeSwapV3,
+            trace_index: 1,
+            protocol: Protocol::PancakeSwapV3,
             pool_address: Address::new(hex!("Ed4D5317823Ff7BC8BB868C1612Bb270a8311179")),
-            tokens:       vec![
+            tokens: vec![
                 Address::new(hex!("186eF81fd8E77EEC8BfFC3039e7eC41D5FC0b457")),
                 TokenInfoWithAddress::usdt().address,
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -78,6 +87,7 @@ pub mod test {
                     let pool = pool.remove(0);
                     assert_eq!(pool.protocol, eq_create.protocol);
                     assert_eq!(pool.tokens, eq_create.tokens);
+                    assert!(pool.fee_tier.is_some());
                 },
             )
             .await
@@ -54,6 +54,9 @@ action_impl!(
             amount_in,
             amount_out,
             msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -87,6 +90,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -119,6 +124,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -152,6 +159,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -186,7 +195,7 @@ mod tests {
         );
         let token_info = TokenInfoWithAddress {
             address: Address::new(hex!("186eF81fd8E77EEC8BfFC3039e7eC41D5FC0b457")),
-            inner:   TokenInfo { decimals: 18, symbol: "INSP".to_owned() },
+            inner:   TokenInfo::new(18, "INSP".to_owned()),
         };
 
         classifier_utils.ensure_token(TokenInfoWithAddress::usdt());
@@ -196,18 +205,21 @@ mod tests {
             B256::from(hex!("649b792d819826302eb2859a9a1b8f3bb1a78bb5c480d433cdc6cc4ab129337f"));
 
         let eq_action = Action::Swap(NormalizedSwap {
-            protocol:    PancakeSwapV3,
+            protocol: PancakeSwapV3,
             trace_index: 1,
-            from:        Address::new(hex!("1b81D678ffb9C0263b24A97847620C99d213eB14")),
-            recipient:   Address::new(hex!("6Dbe61E7c69AF3bF5d20C15494bD69eD1905A335")),
-            pool:        Address::new(hex!("Ed4D5317823Ff7BC8BB868C1612Bb270a8311179")),
-            token_in:    token_info,
-            amount_in:   U256::from_str("8888693999999999016960")
+            from: Address::new(hex!("1b81D678ffb9C0263b24A97847620C99d213eB14")),
+            recipient: Address::new(hex!("6Dbe61E7c69AF3bF5d20C15494bD69eD1905A335")),
+            pool: Address::new(hex!("Ed4D5317823Ff7BC8BB868C1612Bb270a8311179")),
+            token_in: token_info,
+            amount_in: U256::from_str("8888693999999999016960")
                 .unwrap()
                 .to_scaled_rational(18),
-            token_out:   TokenInfoWithAddress::usdt(),
-            amount_out:  U256::from_str("1568955344").unwrap().to_scaled_rational(6),
-            msg_value:   U256::ZERO,
+            token_out: TokenInfoWithAddress::usdt(),
+            amount_out: U256::from_str("1568955344").unwrap().to_scaled_rational(6),
+            msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -0,0 +1,159 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::{CustomActionBox, NormalizedStake, NormalizedUnstake},
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+
+// the deposit pool mints the rETH of a deposit, the eth stays with the deposit
+// pool
+action_impl!(
+    Protocol::RocketPool,
+    crate::RocketTokenRETH::mintCall,
+    Custom,
+    [..TokensMinted],
+    call_data: true,
+    logs: true,
+    |info: CallInfo, call_data: mintCall, log_data: RocketPoolMintCallLogs, db_tx: &DB| {
+        let minted = log_data.tokens_minted_field?;
+        let reth = db_tx.try_fetch_token_info(info.target_address)?;
+
+        Ok(CustomActionBox::new(NormalizedStake {
+            protocol: Protocol::RocketPool,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data._to,
+            pool: info.target_address,
+            amount_in: minted.ethAmount.to_scaled_rational(18),
+            amount_out: minted.amount.to_scaled_rational(reth.decimals),
+            token: reth,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::RocketPool,
+    crate::RocketTokenRETH::burnCall,
+    Custom,
+    [..TokensBurned],
+    logs: true,
+    |info: CallInfo, log_data: RocketPoolBurnCallLogs, db_tx: &DB| {
+        let burned = log_data.tokens_burned_field?;
+        let reth = db_tx.try_fetch_token_info(info.target_address)?;
+
+        Ok(CustomActionBox::new(NormalizedUnstake {
+            protocol: Protocol::RocketPool,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            pool: info.target_address,
+            amount_in: burned.amount.to_scaled_rational(reth.decimals),
+            amount_out: burned.ethAmount.to_scaled_rational(18),
+            token: reth,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Log, U256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::Action,
+    };
+    use malachite::Rational;
+
+    use super::*;
+    use crate::RocketTokenRETH::{burnCall, mintCall, TokensBurned, TokensMinted};
+
+    const RETH: Address = Address::new(hex!("00000000000000000000000000000000feed0e00"));
+
+    fn ensure_reth(utils: &ClassifierTestUtils) {
+        utils.ensure_protocol_with_tokens(
+            Protocol::RocketPool,
+            RETH,
+            Address::ZERO,
+            None,
+            &[TokenInfoWithAddress {
+                address: RETH,
+                inner:   TokenInfo::new(18, "rTEST".to_string()),
+            }],
+        );
+    }
+
+    /// `amount` of rETH for `eth_amount` eth, the time is left at zero
+    fn reth_log(
+        signature: alloy_primitives::B256,
+        account: Address,
+        amount: U256,
+        eth_amount: U256,
+    ) -> Log {
+        Log::new_unchecked(
+            RETH,
+            vec![signature, account.into_word()],
+            [amount, eth_amount, U256::ZERO]
+                .into_iter()
+                .flat_map(|word| word.to_be_bytes_vec())
+                .collect::<Vec<_>>()
+                .into(),
+        )
+    }
+
+    fn classify(utils: &ClassifierTestUtils, call_data: Vec<u8>, logs: &[Log]) -> Option<Action> {
+        utils.classify_call(RETH, call_data, vec![], logs, U256::ZERO)
+    }
+
+    #[brontes_macros::test]
+    async fn test_rocket_pool_mint() {
+        let utils = ClassifierTestUtils::new().await;
+        ensure_reth(&utils);
+
+        let staker = Address::with_last_byte(2);
+        // 1.1 eth for 1 rETH
+        let eth = U256::from(11 * 10u64.pow(17));
+        let reth = U256::from(10u64.pow(18));
+        let mint = mintCall { _ethAmount: eth, _to: staker }.abi_encode();
+
+        let action = classify(
+            &utils,
+            mint.clone(),
+            &[reth_log(TokensMinted::SIGNATURE_HASH, staker, reth, eth)],
+        )
+        .expect("rETH mint wasn't classified");
+        let stake = action.try_custom_ref::<NormalizedStake>().unwrap();
+        assert_eq!(stake.protocol, Protocol::RocketPool);
+        assert_eq!(stake.recipient, staker);
+        assert_eq!(stake.token.address, RETH);
+        assert_eq!(stake.amount_in, Rational::from_unsigneds(11u32, 10u32));
+        assert_eq!(stake.amount_out, Rational::from(1));
+
+        // the minted amount comes from the log, without it there is no action
+        assert!(classify(&utils, mint, &[]).is_none());
+    }
+
+    #[brontes_macros::test]
+    async fn test_rocket_pool_burn() {
+        let utils = ClassifierTestUtils::new().await;
+        ensure_reth(&utils);
+
+        let owner = Address::with_last_byte(1);
+        let eth = U256::from(11 * 10u64.pow(17));
+        let reth = U256::from(10u64.pow(18));
+
+        let action = classify(
+            &utils,
+            burnCall { _rethAmount: reth }.abi_encode(),
+            &[reth_log(TokensBurned::SIGNATURE_HASH, owner, reth, eth)],
+        )
+        .expect("rETH burn wasn't classified");
+        let unstake = action.try_custom_ref::<NormalizedUnstake>().unwrap();
+        assert_eq!(unstake.from, owner);
+        assert_eq!(unstake.token.address, RETH);
+        assert_eq!(unstake.amount_in, Rational::from(1));
+        assert_eq!(unstake.amount_out, Rational::from_unsigneds(11u32, 10u32));
+    }
+}
@@ -18,6 +18,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::SushiSwapV2,
             tokens: vec![token_a, token_b],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -39,6 +42,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::SushiSwapV3,
             tokens: vec![token_a, token_b],
+            fee_tier: Some(call_data.fee.to()),
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -57,13 +63,16 @@ mod tests {
             B256::new(hex!("d0acb944bf0f45dddc92e73376825a6395a3badf82f86283fa0b3ac5139a46eb"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::SushiSwapV2,
+            trace_index: 1,
+            protocol: Protocol::SushiSwapV2,
             pool_address: Address::new(hex!("4c5be0fea74c33455f81c85561146bdaf09633da")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("189564397643D9e6173A002f1BA98da7d40a0FA6").into(),
                 hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -54,7 +54,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -92,6 +95,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -125,6 +130,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -157,6 +164,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
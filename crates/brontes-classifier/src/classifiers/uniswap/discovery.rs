@@ -18,6 +18,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::UniswapV2,
             tokens: vec![token_a, token_b],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -39,6 +42,9 @@ discovery_impl!(
             trace_index,
             protocol: Protocol::UniswapV3,
             tokens: vec![token_a, token_b],
+            fee_tier: Some(call_data.fee.to()),
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }]
     }
 );
@@ -57,13 +63,16 @@ mod tests {
             B256::new(hex!("16bba367585045f6c87ec2beca8243575d7a5891f58c1af5e70bc45de4d3e347"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::UniswapV2,
+            trace_index: 1,
+            protocol: Protocol::UniswapV2,
             pool_address: Address::new(hex!("082366f442ea46a608f3c2c5e7abd5f53a86125b")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("52c6889677E514BDD0f09E32003C15B33E88DccE").into(),
                 hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -89,13 +98,16 @@ mod tests {
             B256::new(hex!("06c8ae6cc8705d3c6c8da07f2cb14af08ce981788ef237dcd204992ad207ddf1"));
 
         let eq_create = NormalizedNewPool {
-            trace_index:  1,
-            protocol:     Protocol::UniswapV3,
+            trace_index: 1,
+            protocol: Protocol::UniswapV3,
             pool_address: Address::new(hex!("602c70f43c7436975aec3113b316e7912d5ee2e3")),
-            tokens:       vec![
+            tokens: vec![
                 hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").into(),
                 hex!("edB357b55BC2DA1882B629EaDD3DF06202092d69").into(),
             ],
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         utils
@@ -108,6 +120,7 @@ mod tests {
                     assert_eq!(pool.protocol, eq_create.protocol);
                     assert_eq!(pool.pool_address, eq_create.pool_address);
                     assert_eq!(pool.tokens, eq_create.tokens);
+                    assert!(pool.fee_tier.is_some());
                 },
             )
             .await
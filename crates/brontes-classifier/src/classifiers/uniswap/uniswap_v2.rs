@@ -43,7 +43,10 @@ action_impl!(
                 token_out: t0_info,
                 amount_in,
                 amount_out,
-                msg_value: info.msg_value
+                msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         } else {
             let amount_in = logs.amount0In.to_scaled_rational(t0_info.decimals);
@@ -59,7 +62,10 @@ action_impl!(
                 token_out: t1_info,
                 amount_in,
                 amount_out,
-                msg_value: info.msg_value
+                msg_value: info.msg_value,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         }
     }
@@ -95,6 +101,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -128,6 +136,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -54,7 +54,10 @@ action_impl!(
             token_out,
             amount_in,
             amount_out,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -90,6 +93,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -122,6 +127,8 @@ action_impl!(
             from: info.from_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -155,6 +162,8 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -192,6 +201,9 @@ mod tests {
             amount_out:  U256::from_str("98019119714").unwrap().to_scaled_rational(6),
 
             msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -212,13 +224,13 @@ mod tests {
             B256::from(hex!("0089210683170b3f17201c8abeafdc4c022a26c7af1e44d351556eaa48d0fee8"));
 
         let eq_action = Action::Mint(NormalizedMint {
-            protocol:    UniswapV3,
+            protocol: UniswapV3,
             trace_index: 21,
-            from:        Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            recipient:   Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            pool:        Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
-            token:       vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
-            amount:      vec![
+            from: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            recipient: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            pool: Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
+            token: vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
+            amount: vec![
                 U256::from_str("102642322850")
                     .unwrap()
                     .to_scaled_rational(6),
@@ -226,6 +238,8 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(6),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -246,18 +260,20 @@ mod tests {
             B256::from(hex!("f179f349434a59d0dc899fc03a5754c7e50f52de1709d9523e7cbd09c4ba13eb"));
 
         let eq_action = Action::Burn(NormalizedBurn {
-            protocol:    UniswapV3,
+            protocol: UniswapV3,
             trace_index: 12,
-            from:        Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            recipient:   Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            pool:        Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
-            token:       vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
-            amount:      vec![
+            from: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            recipient: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            pool: Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
+            token: vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
+            amount: vec![
                 U256::from_str("347057356182")
                     .unwrap()
                     .to_scaled_rational(6),
                 U256::from_str("5793599811").unwrap().to_scaled_rational(6),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -278,18 +294,20 @@ mod tests {
             B256::from(hex!("f179f349434a59d0dc899fc03a5754c7e50f52de1709d9523e7cbd09c4ba13eb"));
 
         let eq_action = Action::Collect(NormalizedCollect {
-            protocol:    UniswapV3,
+            protocol: UniswapV3,
             trace_index: 13,
-            from:        Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            recipient:   Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
-            pool:        Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
-            token:       vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
-            amount:      vec![
+            from: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            recipient: Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80")),
+            pool: Address::new(hex!("3416cF6C708Da44DB2624D63ea0AAef7113527C6")),
+            token: vec![TokenInfoWithAddress::usdc(), TokenInfoWithAddress::usdt()],
+            amount: vec![
                 U256::from_str("347081800129")
                     .unwrap()
                     .to_scaled_rational(6),
                 U256::from_str("5793599811").unwrap().to_scaled_rational(6),
             ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
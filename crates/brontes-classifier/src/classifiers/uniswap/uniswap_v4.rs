@@ -0,0 +1,267 @@
+use alloy_primitives::{keccak256, Address, B256, I256, U256};
+use alloy_sol_types::SolValue;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    chain::chain_spec,
+    db::token_info::TokenInfoWithAddress,
+    normalized_actions::{
+        Action, CustomActionBox, NormalizedBurn, NormalizedCollect, NormalizedMint,
+        NormalizedPoolConfigUpdate, NormalizedSettlement, NormalizedSwap, SettlementKind,
+    },
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+
+use crate::UniswapV4PoolManager::PoolKey;
+
+// registers the pool under its pseudo address, so that the swaps and liquidity
+// changes on it are known to the pricer and the inspectors
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4PoolManager::initializeCall,
+    PoolConfigUpdate,
+    [..],
+    call_data: true,
+    |info: CallInfo, call_data: initializeCall, _db_tx: &DB| {
+        Ok(NormalizedPoolConfigUpdate {
+            trace_index: info.trace_idx,
+            protocol: Protocol::UniswapV4,
+            pool_address: pool_id_to_address(pool_id(&call_data.key)),
+            tokens: vec![
+                currency_address(call_data.key.currency0),
+                currency_address(call_data.key.currency1),
+            ],
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4PoolManager::swapCall,
+    Swap,
+    [..],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: swapCall,
+    return_data: swapReturn,
+    db_tx: &DB| {
+        let pool = pool_id_to_address(pool_id(&call_data.key));
+        let t0_info = currency_info(call_data.key.currency0, db_tx)?;
+        let t1_info = currency_info(call_data.key.currency1, db_tx)?;
+        let (amount0, amount1) = balance_delta(return_data.swapDelta);
+
+        // the deltas are from the side of the caller, the negative one was paid in
+        let (amount_in, amount_out, token_in, token_out) = if amount0.is_negative() {
+            (
+                amount0.unsigned_abs().to_scaled_rational(t0_info.decimals),
+                amount1.unsigned_abs().to_scaled_rational(t1_info.decimals),
+                t0_info,
+                t1_info,
+            )
+        } else {
+            (
+                amount1.unsigned_abs().to_scaled_rational(t1_info.decimals),
+                amount0.unsigned_abs().to_scaled_rational(t0_info.decimals),
+                t1_info,
+                t0_info,
+            )
+        };
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::UniswapV4,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            pool,
+            recipient: info.from_address,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4PoolManager::modifyLiquidityCall,
+    Action,
+    [..],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: modifyLiquidityCall,
+    return_data: modifyLiquidityReturn,
+    db_tx: &DB| {
+        let pool = pool_id_to_address(pool_id(&call_data.key));
+        let t0_info = currency_info(call_data.key.currency0, db_tx)?;
+        let t1_info = currency_info(call_data.key.currency1, db_tx)?;
+
+        // the caller delta includes the fees the position accrued
+        let (amount0, amount1) = balance_delta(return_data.callerDelta);
+        let amount = vec![
+            amount0.unsigned_abs().to_scaled_rational(t0_info.decimals),
+            amount1.unsigned_abs().to_scaled_rational(t1_info.decimals),
+        ];
+        let token = vec![t0_info, t1_info];
+
+        let liquidity_delta = call_data.params.liquidityDelta;
+        Ok(if liquidity_delta.is_negative() {
+            Action::Burn(NormalizedBurn {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                token,
+                amount,
+                #[cfg(feature = "provenance")]
+                provenance: None,
+            })
+        } else if liquidity_delta.is_zero() {
+            // modifying by zero liquidity only collects the accrued fees
+            Action::Collect(NormalizedCollect {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                token,
+                amount,
+                #[cfg(feature = "provenance")]
+                provenance: None,
+            })
+        } else {
+            Action::Mint(NormalizedMint {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                token,
+                amount,
+                #[cfg(feature = "provenance")]
+                provenance: None,
+            })
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4PoolManager::settleCall,
+    Custom,
+    [..],
+    return_data: true,
+    |info: CallInfo, return_data: settleReturn, _db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedSettlement {
+            protocol: Protocol::UniswapV4,
+            trace_index: info.trace_idx,
+            kind: SettlementKind::Settle,
+            from: info.from_address,
+            manager: info.target_address,
+            currency: None,
+            recipient: info.target_address,
+            amount: return_data.paid,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4PoolManager::takeCall,
+    Custom,
+    [..],
+    call_data: true,
+    |info: CallInfo, call_data: takeCall, _db_tx: &DB| {
+        Ok(CustomActionBox::new(NormalizedSettlement {
+            protocol: Protocol::UniswapV4,
+            trace_index: info.trace_idx,
+            kind: SettlementKind::Take,
+            from: info.from_address,
+            manager: info.target_address,
+            currency: Some(call_data.currency),
+            recipient: call_data.to,
+            amount: call_data.amount,
+            msg_value: info.msg_value,
+        }))
+    }
+);
+
+/// The id of a V4 pool, the hash of its key
+fn pool_id(key: &PoolKey) -> B256 {
+    keccak256(key.abi_encode())
+}
+
+// V4 pools have no contract of their own, they all live in the pool manager.
+// As actions are keyed on the pool address, a pool is addressed by the low 20
+// bytes of its id.
+fn pool_id_to_address(pool_id: B256) -> Address {
+    Address::from_word(pool_id)
+}
+
+/// Splits a packed balance delta into the amounts of currency0 and currency1
+fn balance_delta(delta: I256) -> (i128, i128) {
+    let raw = delta.into_raw();
+    let amount0 = (raw >> 128).to::<u128>() as i128;
+    let amount1 = (raw & U256::from(u128::MAX)).to::<u128>() as i128;
+
+    (amount0, amount1)
+}
+
+/// The zero currency is native eth, which is priced as the wrapped native token
+fn currency_address(currency: Address) -> Address {
+    if currency == Address::ZERO {
+        return chain_spec().wrapped_native
+    }
+
+    currency
+}
+
+/// The zero currency is native eth
+fn currency_info<DB: LibmdbxReader>(
+    currency: Address,
+    db: &DB,
+) -> eyre::Result<TokenInfoWithAddress> {
+    if currency == Address::ZERO {
+        return Ok(TokenInfoWithAddress::native_eth())
+    }
+
+    db.try_fetch_token_info(currency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_univ4_balance_delta() {
+        let pack = |amount0: i128, amount1: i128| {
+            I256::from_raw((U256::from(amount0 as u128) << 128) | U256::from(amount1 as u128))
+        };
+
+        assert_eq!(balance_delta(pack(-1_000, 2_500)), (-1_000, 2_500));
+        assert_eq!(balance_delta(pack(7, -3)), (7, -3));
+        assert_eq!(balance_delta(pack(i128::MIN, i128::MAX)), (i128::MIN, i128::MAX));
+        assert_eq!(balance_delta(I256::ZERO), (0, 0));
+    }
+
+    #[test]
+    fn test_univ4_native_currency_is_wrapped() {
+        let usdc = alloy_primitives::address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+        assert_eq!(currency_address(Address::ZERO), chain_spec().wrapped_native);
+        assert_eq!(currency_address(usdc), usdc);
+    }
+}
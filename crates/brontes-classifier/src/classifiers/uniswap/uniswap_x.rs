@@ -1,9 +1,12 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, Log, U256};
+use alloy_sol_types::SolEvent;
 use brontes_macros::action_impl;
 use brontes_pricing::Protocol;
 use brontes_types::{
     db::token_info::TokenInfoWithAddress,
-    normalized_actions::{NormalizedBatch, NormalizedSwap},
+    normalized_actions::{
+        Action, CustomActionBox, NormalizedBatch, NormalizedOrderFill, NormalizedSwap,
+    },
     structured_trace::CallInfo,
 };
 use malachite::Rational;
@@ -34,8 +37,9 @@ action_impl!(
             settlement_contract: info.target_address,
             user_swaps,
             solver_swaps: None,
-            msg_value: info.msg_value
-
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -66,6 +70,8 @@ action_impl!(
             user_swaps,
             solver_swaps: None,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 
@@ -95,7 +101,9 @@ action_impl!(
             settlement_contract: info.target_address,
             user_swaps,
             solver_swaps: None,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -124,7 +132,9 @@ action_impl!(
             settlement_contract: info.target_address,
             user_swaps,
             solver_swaps: None,
-            msg_value: info.msg_value
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -136,20 +146,52 @@ impl Fill {
         let swapper = fill_log.swapper;
 
         NormalizedSwap {
-            protocol:    Protocol::UniswapX,
+            protocol: Protocol::UniswapX,
             trace_index: 0,
-            from:        swapper,
-            recipient:   swapper,
-            pool:        settlement_contract,
-            token_in:    TokenInfoWithAddress::default(),
-            token_out:   TokenInfoWithAddress::default(),
-            amount_in:   Rational::default(),
-            amount_out:  Rational::default(),
-            msg_value:   U256::ZERO,
+            from: swapper,
+            recipient: swapper,
+            pool: settlement_contract,
+            token_in: TokenInfoWithAddress::default(),
+            token_out: TokenInfoWithAddress::default(),
+            amount_in: Rational::default(),
+            amount_out: Rational::default(),
+            msg_value: U256::ZERO,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         }
     }
 }
 
+/// The orders filled by a UniswapX execute, one per `Fill` event of the
+/// reactor. They are kept next to the batch, which accounts for the tokens of
+/// the fills.
+pub fn try_decode_order_fills(action: &Action, logs: &[Log]) -> Vec<Action> {
+    let Action::Batch(batch) = action else { return vec![] };
+    if batch.protocol != Protocol::UniswapX {
+        return vec![]
+    }
+
+    logs.iter()
+        .filter(|log| {
+            log.address == batch.settlement_contract
+                && log.topics().first() == Some(&Fill::SIGNATURE_HASH)
+        })
+        .filter_map(|log| Fill::decode_log_data(&log.data, false).ok())
+        .map(|fill| {
+            Action::Custom(CustomActionBox::new(NormalizedOrderFill {
+                protocol:            Protocol::UniswapX,
+                trace_index:         batch.trace_index,
+                order_hash:          fill.orderHash,
+                filler:              fill.filler,
+                swapper:             fill.swapper,
+                settlement_contract: batch.settlement_contract,
+                msg_value:           U256::ZERO,
+            }))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -168,14 +210,14 @@ mod tests {
             B256::from(hex!("3d8fbccb1b0b7f8140f255f0980d897d87394903ad7bf4d08534402d2bf35872"));
 
         let eq_action = Action::Batch(NormalizedBatch {
-            protocol:            Protocol::UniswapX,
-            trace_index:         1,
-            solver:              Address::new(hex!(
+            protocol: Protocol::UniswapX,
+            trace_index: 1,
+            solver: Address::new(hex!(
                 "
             919f9173E2Dc833Ec708812B4f1CB11B1a17eFDe"
             )),
             settlement_contract: Address::new(hex!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4")),
-            user_swaps:          vec![
+            user_swaps: vec![
                 NormalizedSwap {
                     protocol:    UniswapX,
                     trace_index: 4,
@@ -199,30 +241,38 @@ mod tests {
                         .to_scaled_rational(18),
 
                     msg_value: U256::ZERO,
+                    fee_tier: None,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 },
                 NormalizedSwap {
-                    protocol:    UniswapX,
+                    protocol: UniswapX,
                     trace_index: 7,
-                    from:        Address::new(hex!(
+                    from: Address::new(hex!(
                         "
                     569d9f244e4ed4f0731f39675492740dcdab6b15"
                     )),
-                    recipient:   Address::new(hex!(
+                    recipient: Address::new(hex!(
                         "
                     569d9f244e4ed4f0731f39675492740dcdab6b15"
                     )),
-                    pool:        Address::new(hex!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4")),
-                    token_in:    TokenInfoWithAddress::usdt(),
-                    amount_in:   U256::from_str("106496770").unwrap().to_scaled_rational(6),
-                    token_out:   TokenInfoWithAddress::native_eth(),
-                    amount_out:  U256::from_str("43925992451078510")
+                    pool: Address::new(hex!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4")),
+                    token_in: TokenInfoWithAddress::usdt(),
+                    amount_in: U256::from_str("106496770").unwrap().to_scaled_rational(6),
+                    token_out: TokenInfoWithAddress::native_eth(),
+                    amount_out: U256::from_str("43925992451078510")
                         .unwrap()
                         .to_scaled_rational(18),
-                    msg_value:   U256::ZERO,
+                    msg_value: U256::ZERO,
+                    fee_tier: None,
+                    #[cfg(feature = "provenance")]
+                    provenance: None,
                 },
             ],
-            solver_swaps:        None,
-            msg_value:           U256::ZERO,
+            solver_swaps: None,
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -243,11 +293,11 @@ mod tests {
             B256::from(hex!("f9e7365f9c9c2859effebe61d5d19f44dcbf4d2412e7bcc5c511b3b8fbfb8b8d"));
 
         let eq_action = Action::Batch(NormalizedBatch {
-            protocol:            Protocol::UniswapX,
-            trace_index:         0,
-            solver:              Address::new(hex!("ff8Ba4D1fC3762f6154cc942CCF30049A2A0cEC6")),
+            protocol: Protocol::UniswapX,
+            trace_index: 0,
+            solver: Address::new(hex!("ff8Ba4D1fC3762f6154cc942CCF30049A2A0cEC6")),
             settlement_contract: Address::new(hex!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4")),
-            user_swaps:          vec![NormalizedSwap {
+            user_swaps: vec![NormalizedSwap {
                 protocol:    UniswapX,
                 trace_index: 3,
                 from:        Address::new(hex!(
@@ -270,9 +320,14 @@ mod tests {
                 amount_out:  U256::from_str("1182060728").unwrap().to_scaled_rational(6),
 
                 msg_value: U256::ZERO,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             }],
-            solver_swaps:        None,
-            msg_value:           U256::ZERO,
+            solver_swaps: None,
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -285,4 +340,37 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[test]
+    fn test_decode_order_fills() {
+        let [reactor, filler, swapper] = [1, 2, 3].map(Address::with_last_byte);
+        let order_hash = B256::with_last_byte(4);
+        let fill = Log::new_unchecked(
+            reactor,
+            vec![Fill::SIGNATURE_HASH, order_hash, filler.into_word(), swapper.into_word()],
+            U256::from(7).to_be_bytes_vec().into(),
+        );
+        let batch = Action::Batch(NormalizedBatch {
+            protocol: Protocol::UniswapX,
+            trace_index: 2,
+            settlement_contract: reactor,
+            ..Default::default()
+        });
+
+        let [order_fill] = try_decode_order_fills(&batch, &[fill.clone()])
+            .try_into()
+            .unwrap();
+        let order_fill = order_fill.try_custom_ref::<NormalizedOrderFill>().unwrap();
+        assert_eq!(order_fill.order_hash, order_hash);
+        assert_eq!((order_fill.filler, order_fill.swapper), (filler, swapper));
+        assert_eq!(order_fill.trace_index, 2);
+
+        // fills of another reactor aren't part of the batch
+        let other = Action::Batch(NormalizedBatch {
+            protocol: Protocol::UniswapX,
+            settlement_contract: filler,
+            ..Default::default()
+        });
+        assert!(try_decode_order_fills(&other, &[fill]).is_empty());
+    }
 }
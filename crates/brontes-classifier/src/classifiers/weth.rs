@@ -0,0 +1,119 @@
+use alloy_primitives::Log;
+use alloy_sol_types::{SolCall, SolEvent};
+use brontes_types::{
+    chain::chain_spec,
+    db::traits::LibmdbxReader,
+    normalized_actions::{Action, NormalizedEthTransfer, NormalizedTransfer},
+    structured_trace::{TraceActions, TransactionTraceWithLogs},
+    ToScaledRational,
+};
+use malachite::{num::basic::traits::Zero, Rational};
+
+alloy_sol_macro::sol!(
+    function deposit();
+    function withdraw(uint wad);
+
+    event Deposit(address indexed dst, uint wad);
+    event Withdrawal(address indexed src, uint wad);
+);
+
+/// Classifies the wrapping and unwrapping of the native token. WETH doesn't
+/// emit a `Transfer` on `deposit` & `withdraw`, so the wrapped token is
+/// accounted as moving between the WETH contract and the caller, mirroring the
+/// eth sent to and received from the contract. This way wrapping or unwrapping
+/// nets out for the WETH contract and the caller swaps eth for WETH 1:1.
+pub fn try_decode_weth_action<DB: LibmdbxReader>(
+    trace_index: u64,
+    trace: &TransactionTraceWithLogs,
+    db: &DB,
+) -> Option<Vec<Action>> {
+    let weth = chain_spec().wrapped_native;
+    if trace.is_delegate_call() || trace.get_to_address() != weth {
+        return None
+    }
+
+    let calldata = trace.get_calldata();
+    // eth sent to WETH without calldata is wrapped by the fallback
+    let is_deposit = calldata.is_empty() || calldata.starts_with(&depositCall::SELECTOR);
+    if !is_deposit && !calldata.starts_with(&withdrawCall::SELECTOR) {
+        return None
+    }
+
+    let mut weth_logs = trace.logs.iter().filter(|log| log.address == weth);
+    let token = db.try_fetch_token_info(weth).ok()?;
+
+    let transfer = if is_deposit {
+        let event = weth_logs.find_map(decode_log::<Deposit>)?;
+        NormalizedTransfer {
+            trace_index,
+            from: weth,
+            to: event.dst,
+            amount: event.wad.to_scaled_rational(token.decimals),
+            token,
+            fee: Rational::ZERO,
+            msg_value: trace.get_msg_value(),
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }
+    } else {
+        // the eth is sent back in a child call, which is classified as an eth
+        // transfer from the WETH contract
+        let event = weth_logs.find_map(decode_log::<Withdrawal>)?;
+        NormalizedTransfer {
+            trace_index,
+            from: event.src,
+            to: weth,
+            amount: event.wad.to_scaled_rational(token.decimals),
+            token,
+            fee: Rational::ZERO,
+            msg_value: trace.get_msg_value(),
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }
+    };
+
+    let mut actions = vec![Action::Transfer(transfer)];
+    if is_deposit {
+        actions.push(Action::EthTransfer(NormalizedEthTransfer {
+            trace_index,
+            from: trace.get_from_addr(),
+            to: weth,
+            value: trace.get_msg_value(),
+            coinbase_transfer: false,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }));
+    }
+
+    Some(actions)
+}
+
+pub(crate) fn decode_log<E: SolEvent>(log: &Log) -> Option<E> {
+    (log.topics().first() == Some(&E::SIGNATURE_HASH))
+        .then(|| E::decode_log_data(&log.data, false).ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_weth_events() {
+        let account = Address::with_last_byte(1);
+        let wad = U256::from(10u64.pow(18));
+
+        let deposit = Log::new_unchecked(
+            chain_spec().wrapped_native,
+            vec![Deposit::SIGNATURE_HASH, account.into_word()],
+            wad.to_be_bytes_vec().into(),
+        );
+        let event = decode_log::<Deposit>(&deposit).unwrap();
+        assert_eq!((event.dst, event.wad), (account, wad));
+
+        // a withdrawal isn't decoded as a deposit
+        assert!(decode_log::<Withdrawal>(&deposit).is_none());
+    }
+}
@@ -15,13 +15,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -42,6 +44,8 @@ action_impl!(
             recipient: call_data.recipient,
             child_actions: vec![],
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -61,6 +65,8 @@ action_impl!(
             recipient: call_data.recipient,
             child_actions: vec![],
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -80,6 +86,8 @@ action_impl!(
             recipient: call_data.recipient,
             child_actions: vec![],
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -92,13 +100,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -111,13 +121,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -151,7 +163,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -184,7 +199,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -217,7 +235,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -250,7 +271,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -283,7 +307,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -318,7 +345,10 @@ action_impl!(
                 token_in,
                 token_out,
                 amount_in,
-                amount_out
+                amount_out,
+                fee_tier: None,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             });
         }
 
@@ -330,6 +360,8 @@ action_impl!(
             solver_swaps: None,
             user_swaps,
             msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -353,6 +385,8 @@ action_impl!(
             recipient: logs.recipient,
             msg_value :info.msg_value,
             child_actions: vec![],
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 
@@ -366,13 +400,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -384,13 +420,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -402,13 +440,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -420,13 +460,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -438,13 +480,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -456,13 +500,15 @@ action_impl!(
     [],
     |info: CallInfo, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
+            protocol: Protocol::ZeroX,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient: info.msg_sender,
             child_actions: vec![],
-            msg_value:     info.msg_value,
+            msg_value: info.msg_value,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -496,7 +542,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -529,7 +578,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -562,7 +614,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -595,7 +650,10 @@ action_impl!(
             token_in,
             token_out,
             amount_in,
-            amount_out
+            amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         })
     }
 );
@@ -708,7 +766,7 @@ mod tests {
         let token_in = TokenInfoWithAddress::weth();
         let token_out = TokenInfoWithAddress {
             address: Address::from_str("0xae7ab96520DE3A18E5e111B5EaAb095312D7fE84").unwrap(),
-            inner:   TokenInfo { decimals: 18, symbol: "stETH".to_string() },
+            inner:   TokenInfo::new(18, "stETH".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -731,6 +789,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -752,7 +813,7 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap(),
-            inner:   TokenInfo { decimals: 6, symbol: "USDT".to_string() },
+            inner:   TokenInfo::new(6, "USDT".to_string()),
         };
         let token_out = TokenInfoWithAddress::weth();
 
@@ -776,6 +837,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -798,7 +862,7 @@ mod tests {
         let token_in = TokenInfoWithAddress::weth();
         let token_out = TokenInfoWithAddress {
             address: Address::from_str("0xfAbA6f8e4a5E8Ab82F62fe7C39859FA577269BE3").unwrap(),
-            inner:   TokenInfo { decimals: 18, symbol: "ONDO".to_string() },
+            inner:   TokenInfo::new(18, "ONDO".to_string()),
         };
 
         classifier_utils.ensure_token(token_out.clone());
@@ -821,6 +885,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -842,11 +909,11 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::from_str("0x6De037ef9aD2725EB40118Bb1702EBb27e4Aeb24").unwrap(),
-            inner:   TokenInfo { decimals: 18, symbol: "RNDR".to_string() },
+            inner:   TokenInfo::new(18, "RNDR".to_string()),
         };
         let token_out = TokenInfoWithAddress {
             address: Address::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap(),
-            inner:   TokenInfo { decimals: 6, symbol: "USDC".to_string() },
+            inner:   TokenInfo::new(6, "USDC".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -869,6 +936,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -890,7 +960,7 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::from_str("0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap(),
-            inner:   TokenInfo { decimals: 6, symbol: "USDT".to_string() },
+            inner:   TokenInfo::new(6, "USDT".to_string()),
         };
         let token_out = TokenInfoWithAddress::weth();
 
@@ -914,6 +984,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -1054,12 +1127,12 @@ mod tests {
 
         let token_in = TokenInfoWithAddress {
             address: Address::from_str("0x57Ab1ec28D129707052df4dF418D58a2D46d5f51").unwrap(),
-            inner:   TokenInfo { decimals: 18, symbol: "sUSD".to_string() },
+            inner:   TokenInfo::new(18, "sUSD".to_string()),
         };
 
         let token_out = TokenInfoWithAddress {
             address: Address::from_str("0xa59b7e1c08b95d433f3438741eb8bf5683adc4ad").unwrap(),
-            inner:   TokenInfo { decimals: 18, symbol: "sSHORT".to_string() },
+            inner:   TokenInfo::new(18, "sSHORT".to_string()),
         };
 
         classifier_utils.ensure_token(token_in.clone());
@@ -1083,6 +1156,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -1123,6 +1199,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -1163,6 +1242,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -1203,6 +1285,9 @@ mod tests {
             token_out,
             amount_in,
             amount_out,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         });
 
         classifier_utils
@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
 use alloy_primitives::Log;
-use brontes_core::missing_token_info::load_missing_token_info;
+use brontes_core::missing_token_info::TokenDecimalResolver;
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_pricing::types::DexPriceMsg;
 use brontes_types::{
+    db::{pool_fee_tier::PoolFeeTier, protocol_metadata::canonical_factory},
     normalized_actions::{pool::NormalizedNewPool, Action, MultiFrameRequest},
+    order_flow::SubmissionChannel,
     structured_trace::{TraceActions, TransactionTraceWithLogs, TxTrace},
     traits::TracingProvider,
     tree::{root::NodeData, GasDetails, Node, Root},
@@ -17,27 +19,33 @@ use tracing::{error, trace};
 
 use self::erc20::try_decode_transfer;
 use crate::{
-    classifiers::*, tree_builder::utils::decode_transfer, ActionCollection,
-    FactoryDiscoveryDispatch,
+    classifiers::*,
+    tree_builder::{minimal_proxy::discover_minimal_proxy, utils::decode_transfer},
+    ActionCollection, FactoryDiscoveryDispatch,
 };
 
 #[derive(Debug)]
 pub struct DiscoveryOnlyClassifier<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> {
-    libmdbx:  &'db DB,
-    provider: Arc<T>,
+    libmdbx:          &'db DB,
+    provider:         Arc<T>,
+    decimal_resolver: TokenDecimalResolver<T>,
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Clone
     for DiscoveryOnlyClassifier<'db, T, DB>
 {
     fn clone(&self) -> Self {
-        Self { libmdbx: self.libmdbx, provider: self.provider.clone() }
+        Self {
+            libmdbx:          self.libmdbx,
+            provider:         self.provider.clone(),
+            decimal_resolver: self.decimal_resolver.clone(),
+        }
     }
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassifier<'db, T, DB> {
     pub fn new(libmdbx: &'db DB, provider: Arc<T>) -> Self {
-        Self { libmdbx, provider }
+        Self { libmdbx, decimal_resolver: TokenDecimalResolver::new(provider.clone()), provider }
     }
 
     pub async fn run_discovery(&self, traces: Vec<TxTrace>, header: Header) {
@@ -82,6 +90,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                         head: node,
                         tx_hash: trace.tx_hash,
                         private: false,
+                        submission_channel: SubmissionChannel::Public,
                         total_msg_value_transfers: vec![],
                         gas_details: GasDetails {
                             coinbase_transfer:   None,
@@ -89,8 +98,13 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                             effective_gas_price: trace.effective_price,
                             priority_fee:        trace.effective_price
                                 - (header.base_fee_per_gas.unwrap_or_default() as u128),
+                            gas_refunded:        trace.gas_refunded,
+                            access_list_gas:     trace.access_list_gas,
+                            blob_gas_used:       trace.blob_gas_used,
+                            blob_gas_price:      trace.blob_gas_price,
                         },
                         data_store: NodeData(vec![Some(action)]),
+                        user_ops: vec![],
                     };
 
                     let tx_trace = &trace.trace;
@@ -193,6 +207,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
             return
         }
 
+        update_curve_pool_params(self.libmdbx, block, &trace.logs).await;
+
         let mut call_info = trace.get_callframe_info();
         // Add logs of delegated calls to the root trace, only if the delegated call is
         // from the same address / in the same call frame.
@@ -251,20 +267,21 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
             trace.get_from_addr(),
             trace.get_to_address(),
             self.libmdbx,
-            &self.provider,
+            &self.decimal_resolver,
             block,
             trace.get_msg_value(),
         )
         .await
         .is_err()
         {
-            for log in &trace.logs {
-                if let Some((addr, ..)) = decode_transfer(log) {
-                    if self.libmdbx.try_fetch_token_info(addr).is_err() {
-                        load_missing_token_info(&self.provider, self.libmdbx, block, addr).await
-                    }
-                }
-            }
+            let tokens = trace
+                .logs
+                .iter()
+                .filter_map(|log| decode_transfer(log).map(|(token, ..)| token));
+
+            self.decimal_resolver
+                .resolve(self.libmdbx, block, tokens)
+                .await;
         }
     }
 
@@ -292,6 +309,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
             None => return,
         };
 
+        let registry = self.libmdbx.fetch_protocol_registry().unwrap_or_default();
         let search_data = all_nodes
             .iter()
             .filter_map(|node| {
@@ -299,17 +317,39 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                     .get_ref(node.data)
                     .and_then(|node| node.first())
             })
-            .filter_map(|node_data| Some((node_data.get_from_address(), node_data.get_calldata()?)))
+            .filter_map(|node_data| {
+                // factories deployed at a different address than on mainnet, or
+                // registered as another deployment of a protocol, are dispatched
+                // as the factory the discovery classifiers know
+                let factory = canonical_factory(&registry, node_data.get_from_address());
+                Some((factory, node_data.get_calldata()?))
+            })
             .collect::<Vec<_>>();
 
-        if search_data.is_empty() {
-            return
-        }
-
-        join_all(
+        let mut pools = if search_data.is_empty() {
+            vec![]
+        } else {
             DiscoveryClassifier::default()
                 .dispatch(self.provider.clone(), search_data, created_addr, trace_index)
                 .await
+        };
+
+        if pools.is_empty() {
+            pools.extend(
+                discover_minimal_proxy(
+                    self.libmdbx,
+                    &self.provider,
+                    &trace,
+                    created_addr,
+                    trace_index,
+                    block,
+                )
+                .await,
+            );
+        }
+
+        join_all(
+            pools
                 .into_iter()
                 // insert the pool returning if it has token values.
                 .filter(|pool| !self.contains_pool(pool.pool_address))
@@ -335,6 +375,17 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                 pool.pool_address
             );
         }
+
+        if let Some(fee) = pool.fee_tier {
+            if self
+                .libmdbx
+                .insert_pool_fee_tier(pool.pool_address, PoolFeeTier::new(fee))
+                .await
+                .is_err()
+            {
+                error!(pool=?pool.pool_address,"failed to insert pool fee tier into libmdbx");
+            }
+        }
     }
 }
 
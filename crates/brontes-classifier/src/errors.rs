@@ -0,0 +1,33 @@
+use alloy_primitives::{Address, B256};
+use brontes_types::Protocol;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClassifierError {
+    /// The pricing task stopped receiving, no block can be priced anymore
+    #[error("the dex pricing channel is closed")]
+    PricingChannelClosed,
+    /// A discovered pool couldn't be stored, the tx that created it is still
+    /// classified
+    #[error("failed to insert {protocol} pool {pool:?} into libmdbx: {error}")]
+    PoolInsertion { pool: Address, protocol: Protocol, error: eyre::Report },
+    #[error("classification of tx {tx_hash:?} at index {tx_idx} panicked: {message}")]
+    TxPanicked { tx_idx: usize, tx_hash: B256, message: String },
+}
+
+impl ClassifierError {
+    /// Errors that can't be isolated to the tx being classified, the block
+    /// tree can't be built if one occurs
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::PricingChannelClosed)
+    }
+
+    /// Label of the error in the classification metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::PricingChannelClosed => "pricing_channel_closed",
+            Self::PoolInsertion { .. } => "pool_insertion",
+            Self::TxPanicked { .. } => "tx_panicked",
+        }
+    }
+}
@@ -16,8 +16,10 @@ use brontes_types::{
 };
 use futures::Future;
 
+pub mod errors;
+pub use errors::ClassifierError;
 pub mod tree_builder;
-pub use tree_builder::Classifier;
+pub use tree_builder::{tree_pruning::TreePass, Classifier};
 pub mod discovery_only;
 pub mod multi_frame_classification;
 
@@ -66,6 +68,336 @@ sol!(ZeroXLiquidityProviderFeature, "./classifier-abis/zero-x/ZeroXLiquidityProv
 sol!(ZeroXInterface, "./classifier-abis/zero-x/ZeroXInterface.json");
 sol!(DodoDPPPool, "./classifier-abis/dodo/DPPPool.json");
 sol!(DodoDSPPool, "./classifier-abis/dodo/DSPPool.json");
+sol! {
+    interface CompoundV3Comet {
+        event BuyCollateral(
+            address indexed buyer,
+            address indexed asset,
+            uint256 baseAmount,
+            uint256 collateralAmount
+        );
+
+        function buyCollateral(
+            address asset,
+            uint256 minAmount,
+            uint256 baseAmount,
+            address recipient
+        ) external;
+    }
+}
+// the stableswap-ng metapools deployed by the crvUSD factory
+sol! {
+    interface CurvecrvUSDMetapoolImpl {
+        event TokenExchange(
+            address indexed buyer,
+            int128 sold_id,
+            uint256 tokens_sold,
+            int128 bought_id,
+            uint256 tokens_bought
+        );
+        event TokenExchangeUnderlying(
+            address indexed buyer,
+            int128 sold_id,
+            uint256 tokens_sold,
+            int128 bought_id,
+            uint256 tokens_bought
+        );
+
+        function exchange(int128 i, int128 j, uint256 _dx, uint256 _min_dy)
+            external returns (uint256);
+        function exchange(int128 i, int128 j, uint256 _dx, uint256 _min_dy, address _receiver)
+            external returns (uint256);
+        function exchange_underlying(int128 i, int128 j, uint256 _dx, uint256 _min_dy)
+            external returns (uint256);
+        function exchange_underlying(
+            int128 i,
+            int128 j,
+            uint256 _dx,
+            uint256 _min_dy,
+            address _receiver
+        ) external returns (uint256);
+    }
+}
+sol! {
+    interface MakerDog {
+        event Bark(
+            bytes32 indexed ilk,
+            address indexed urn,
+            uint256 ink,
+            uint256 art,
+            uint256 due,
+            address clip,
+            uint256 indexed id
+        );
+
+        function bark(bytes32 ilk, address urn, address kpr) external returns (uint256 id);
+    }
+}
+sol! {
+    interface MakerClipper {
+        event Take(
+            uint256 indexed id,
+            uint256 max,
+            uint256 price,
+            uint256 owe,
+            uint256 tab,
+            uint256 lot,
+            address indexed usr
+        );
+
+        function take(
+            uint256 id,
+            uint256 amt,
+            uint256 max,
+            address who,
+            bytes calldata data
+        ) external;
+    }
+}
+// the singleton that holds every Uniswap V4 pool. Balance deltas pack amount0
+// in the upper and amount1 in the lower 128 bits
+sol! {
+    interface UniswapV4PoolManager {
+        struct PoolKey {
+            address currency0;
+            address currency1;
+            uint24 fee;
+            int24 tickSpacing;
+            address hooks;
+        }
+        struct SwapParams {
+            bool zeroForOne;
+            int256 amountSpecified;
+            uint160 sqrtPriceLimitX96;
+        }
+        struct ModifyLiquidityParams {
+            int24 tickLower;
+            int24 tickUpper;
+            int256 liquidityDelta;
+            bytes32 salt;
+        }
+
+        function initialize(PoolKey memory key, uint160 sqrtPriceX96)
+            external returns (int24 tick);
+        function swap(PoolKey memory key, SwapParams memory params, bytes calldata hookData)
+            external returns (int256 swapDelta);
+        function modifyLiquidity(
+            PoolKey memory key,
+            ModifyLiquidityParams memory params,
+            bytes calldata hookData
+        ) external returns (int256 callerDelta, int256 feesAccrued);
+        function settle() external payable returns (uint256 paid);
+        function take(address currency, address to, uint256 amount) external;
+    }
+}
+
+// OpenSea's marketplace. Item types: 0 native, 1 erc20, 2 erc721, 3 erc1155,
+// 4 & 5 the criteria based erc721 & erc1155
+sol! {
+    interface Seaport {
+        struct AdditionalRecipient {
+            uint256 amount;
+            address recipient;
+        }
+        struct BasicOrderParameters {
+            address considerationToken;
+            uint256 considerationIdentifier;
+            uint256 considerationAmount;
+            address offerer;
+            address zone;
+            address offerToken;
+            uint256 offerIdentifier;
+            uint256 offerAmount;
+            uint8 basicOrderType;
+            uint256 startTime;
+            uint256 endTime;
+            bytes32 zoneHash;
+            uint256 salt;
+            bytes32 offererConduitKey;
+            bytes32 fulfillerConduitKey;
+            uint256 totalOriginalAdditionalRecipients;
+            AdditionalRecipient[] additionalRecipients;
+            bytes signature;
+        }
+        struct OfferItem {
+            uint8 itemType;
+            address token;
+            uint256 identifierOrCriteria;
+            uint256 startAmount;
+            uint256 endAmount;
+        }
+        struct ConsiderationItem {
+            uint8 itemType;
+            address token;
+            uint256 identifierOrCriteria;
+            uint256 startAmount;
+            uint256 endAmount;
+            address recipient;
+        }
+        struct OrderParameters {
+            address offerer;
+            address zone;
+            OfferItem[] offer;
+            ConsiderationItem[] consideration;
+            uint8 orderType;
+            uint256 startTime;
+            uint256 endTime;
+            bytes32 zoneHash;
+            uint256 salt;
+            bytes32 conduitKey;
+            uint256 totalOriginalConsiderationItems;
+        }
+        struct Order {
+            OrderParameters parameters;
+            bytes signature;
+        }
+        struct FulfillmentComponent {
+            uint256 orderIndex;
+            uint256 itemIndex;
+        }
+        struct Fulfillment {
+            FulfillmentComponent[] offerComponents;
+            FulfillmentComponent[] considerationComponents;
+        }
+        struct ReceivedItem {
+            uint8 itemType;
+            address token;
+            uint256 identifier;
+            uint256 amount;
+            address recipient;
+        }
+        struct Execution {
+            ReceivedItem item;
+            address offerer;
+            bytes32 conduitKey;
+        }
+
+        function fulfillBasicOrder(BasicOrderParameters calldata parameters)
+            external payable returns (bool fulfilled);
+        function fulfillBasicOrder_efficient_6GL6yc(BasicOrderParameters calldata parameters)
+            external payable returns (bool fulfilled);
+        function matchOrders(Order[] calldata orders, Fulfillment[] calldata fulfillments)
+            external payable returns (Execution[] memory executions);
+    }
+}
+sol! {
+    interface BlurExchange {
+        struct Fee {
+            uint16 rate;
+            address recipient;
+        }
+        struct Order {
+            address trader;
+            uint8 side;
+            address matchingPolicy;
+            address collection;
+            uint256 tokenId;
+            uint256 amount;
+            address paymentToken;
+            uint256 price;
+            uint256 listingTime;
+            uint256 expirationTime;
+            Fee[] fees;
+            uint256 salt;
+            bytes extraParams;
+        }
+        struct Input {
+            Order order;
+            uint8 v;
+            bytes32 r;
+            bytes32 s;
+            bytes extraSignature;
+            uint8 signatureVersion;
+            uint256 blockNumber;
+        }
+        struct Execution {
+            Input sell;
+            Input buy;
+        }
+
+        event OrdersMatched(
+            address indexed maker,
+            address indexed taker,
+            Order sell,
+            bytes32 sellHash,
+            Order buy,
+            bytes32 buyHash
+        );
+
+        function execute(Input calldata sell, Input calldata buy) external payable;
+        function bulkExecute(Execution[] calldata executions) external payable;
+    }
+}
+// tokenA is always the lower address, so it's token0 of the sorted pool details
+sol! {
+    interface MaverickV2Pool {
+        struct SwapParams {
+            uint256 amount;
+            bool tokenAIn;
+            bool exactOutput;
+            int32 tickLimit;
+        }
+        struct AddLiquidityParams {
+            uint8 kind;
+            int32[] ticks;
+            uint128[] amounts;
+        }
+        struct RemoveLiquidityParams {
+            uint32[] binIds;
+            uint128[] amounts;
+        }
+
+        function swap(address recipient, SwapParams memory params, bytes calldata data)
+            external returns (uint256 amountIn, uint256 amountOut);
+        function addLiquidity(
+            address recipient,
+            uint256 subaccount,
+            AddLiquidityParams calldata params,
+            bytes calldata data
+        ) external returns (uint256 tokenAAmount, uint256 tokenBAmount, uint32[] memory binIds);
+        function removeLiquidity(
+            address recipient,
+            uint256 subaccount,
+            RemoveLiquidityParams calldata params
+        ) external returns (uint256 tokenAOut, uint256 tokenBOut);
+    }
+
+    interface MaverickV2Factory {
+        function create(
+            uint64 feeAIn,
+            uint64 feeBIn,
+            uint16 tickSpacing,
+            uint32 lookback,
+            address tokenA,
+            address tokenB,
+            int32 activeTick,
+            uint8 kinds
+        ) external returns (address pool);
+    }
+}
+
+// stETH & wstETH. stETH is minted 1:1 for the eth submitted, wstETH wraps it
+// into a non rebasing token
+sol! {
+    interface Lido {
+        function submit(address _referral) external payable returns (uint256);
+    }
+
+    interface WstETH {
+        function wrap(uint256 _stETHAmount) external returns (uint256);
+        function unwrap(uint256 _wstETHAmount) external returns (uint256);
+    }
+}
+// only the RocketPool deposit pool can mint rETH
+sol! {
+    interface RocketTokenRETH {
+        event TokensMinted(address indexed to, uint256 amount, uint256 ethAmount, uint256 time);
+        event TokensBurned(address indexed from, uint256 amount, uint256 ethAmount, uint256 time);
+
+        function mint(uint256 _ethAmount, address _to) external;
+        function burn(uint256 _rethAmount) external;
+    }
+}
 
 // Discovery
 sol!(UniswapV2Factory, "./classifier-abis/UniswapV2Factory.json");
@@ -3,4 +3,104 @@ pub use balancer::*;
 pub mod maker_dss;
 pub use maker_dss::*;
 pub mod dodo;
+use brontes_types::{
+    normalized_actions::{Action, NodeDataIndex},
+    tree::Root,
+    SearchMemo, TreeSearchBuilder,
+};
 pub use dodo::*;
+use itertools::Itertools;
+use tracing::warn;
+
+/// Splits the actions inside the flash loan call into the repayments of the
+/// loan and the actions executed with the borrowed funds. Returns the nodes to
+/// prune.
+pub(crate) fn parse_flash_loan_children(
+    this_action: &mut Action,
+    child_nodes: Vec<(NodeDataIndex, Action)>,
+    protocol: &str,
+) -> Vec<NodeDataIndex> {
+    let this = this_action.try_flash_loan_mut().unwrap();
+    let mut nodes_to_prune = Vec::new();
+
+    for (index, action) in child_nodes.into_iter() {
+        match &action {
+            Action::Swap(_) | Action::SwapWithFee(_) | Action::EthTransfer(_) => {
+                this.child_actions.push(action);
+                nodes_to_prune.push(index);
+            }
+            Action::Transfer(t) => {
+                if !this.try_add_repayment(t) {
+                    this.child_actions.push(action);
+                }
+                nodes_to_prune.push(index);
+            }
+            _ => {
+                warn!(protocol, "flashloan, unknown call");
+                continue
+            }
+        }
+    }
+
+    nodes_to_prune
+}
+
+/// Some lenders let the loan be repaid outside of the flash loan call, e.g.
+/// by a sibling call once the callback returned. The repayments of the flash
+/// loans that weren't repaid inside the call are matched against the
+/// transfers after the loan in the rest of the transaction.
+pub(crate) fn match_repayments_outside_loan(root: &mut Root<Action>) {
+    let mut actions = Vec::new();
+    root.head.collect(
+        &mut actions,
+        &TreeSearchBuilder::default().with_actions([Action::is_flash_loan, Action::is_transfer]),
+        &|data| {
+            (
+                NodeDataIndex {
+                    trace_index:    data.node.index,
+                    data_idx:       data.node.data as u64,
+                    multi_data_idx: data.idx,
+                },
+                data.data.clone(),
+            )
+        },
+        &root.data_store,
+        &mut SearchMemo::default(),
+    );
+    actions.sort_by_key(|(index, _)| index.trace_index);
+
+    let mut repayments: Vec<NodeDataIndex> = Vec::new();
+    for (loan_index, action) in &actions {
+        let Action::FlashLoan(loan) = action else { continue };
+        if loan.is_repaid() {
+            continue
+        }
+
+        let mut loan = loan.clone();
+        for (index, action) in &actions {
+            let Action::Transfer(transfer) = action else { continue };
+            if index.trace_index <= loan.trace_index
+                || repayments.iter().any(|repayment| {
+                    (repayment.data_idx, repayment.multi_data_idx)
+                        == (index.data_idx, index.multi_data_idx)
+                })
+            {
+                continue
+            }
+
+            if loan.try_add_repayment(transfer) {
+                repayments.push(*index);
+            }
+        }
+
+        if let Some(data) = root.data_store.get_mut(loan_index.data_idx as usize) {
+            data[loan_index.multi_data_idx] = Action::FlashLoan(loan);
+        }
+    }
+
+    repayments
+        .into_iter()
+        // remove the outer indexes first to ensure no unreachable
+        .sorted_unstable_by(|a, b| b.multi_data_idx.cmp(&a.multi_data_idx))
+        .for_each(|index| root.head.clear_node_data(index, &mut root.data_store));
+}
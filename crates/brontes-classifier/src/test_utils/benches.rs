@@ -18,7 +18,7 @@ use reth_db::DatabaseError;
 use thiserror::Error;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
-use crate::{ActionCollection, Classifier, ProtocolClassifier};
+use crate::{ActionCollection, Classifier, ClassifierError, ProtocolClassifier};
 
 pub struct ClassifierBenchUtils {
     trace_loader:          TraceLoader,
@@ -97,6 +97,28 @@ impl ClassifierBenchUtils {
         bench_name: &str,
         block: u64,
         c: &mut Criterion,
+    ) -> Result<(), ClassifierBenchError> {
+        self.bench_block_tree_building_with(&self.classifier, bench_name, block, c)
+    }
+
+    /// Builds the tree classifying one tx after another, as a baseline for
+    /// the parallel classification
+    pub fn bench_block_tree_building_serial(
+        &self,
+        bench_name: &str,
+        block: u64,
+        c: &mut Criterion,
+    ) -> Result<(), ClassifierBenchError> {
+        let classifier = self.classifier.clone().with_serial_classification();
+        self.bench_block_tree_building_with(&classifier, bench_name, block, c)
+    }
+
+    fn bench_block_tree_building_with(
+        &self,
+        classifier: &Classifier<'static, Box<dyn TracingProvider>, LibmdbxReadWriter>,
+        bench_name: &str,
+        block: u64,
+        c: &mut Criterion,
     ) -> Result<(), ClassifierBenchError> {
         let BlockTracesWithHeaderAnd { traces, header, .. } = self
             .rt
@@ -106,7 +128,7 @@ impl ClassifierBenchUtils {
             b.to_async(&self.rt).iter_batched(
                 || (traces.clone(), header.clone()),
                 |(trace, header)| async move {
-                    black_box(self.classifier.build_block_tree(trace, header, true).await)
+                    black_box(classifier.build_block_tree(trace, header, true).await)
                 },
                 criterion::BatchSize::NumIterations(1),
             );
@@ -128,7 +150,7 @@ impl ClassifierBenchUtils {
 
         let tree = self
             .rt
-            .block_on(self.classifier.build_block_tree(vec![trace], header, true));
+            .block_on(self.classifier.build_block_tree(vec![trace], header, true))?;
         let tree = Arc::new(tree);
 
         c.bench_function(bench_name, move |b| b.iter(|| black_box(bench_fn(tree.clone()))));
@@ -149,7 +171,7 @@ impl ClassifierBenchUtils {
             .block_on(self.trace_loader.get_block_traces_with_header(block))?;
         let tree = self
             .rt
-            .block_on(self.classifier.build_block_tree(traces, header, true));
+            .block_on(self.classifier.build_block_tree(traces, header, true))?;
         let tree = Arc::new(tree);
 
         c.bench_function(bench_name, move |b| b.iter(|| black_box(bench_fn(tree.clone()))));
@@ -217,4 +239,6 @@ pub enum ClassifierBenchError {
     ProtocolDiscoveryError(Address),
     #[error("couldn't find trace that matched {0:?}")]
     ProtocolClassifierError(Address),
+    #[error(transparent)]
+    ClassifierError(#[from] ClassifierError),
 }
@@ -0,0 +1,317 @@
+//! Property testing of the classifier dispatch. Every classifier the
+//! [`ProtocolClassifier`] routes to is called with randomized call data, return
+//! data and logs for its selector, which must never panic. The actions that do
+//! get classified have to pass [`check_action_invariants`].
+use alloy_primitives::{Address, Bytes, Log, B256, U256};
+use alloy_sol_types::SolEvent;
+use brontes_types::{
+    db::token_info::{TokenInfo, TokenInfoWithAddress},
+    normalized_actions::{Action, NormalizedSwap},
+    structured_trace::CallFrameInfo,
+    Protocol, ToScaledRational,
+};
+use malachite::{num::basic::traits::Zero, Rational};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseError, TestRunner},
+};
+
+use super::ClassifierTestUtils;
+use crate::{ActionCollection, ProtocolClassifier, Transfer};
+
+const MAX_WORDS: usize = 16;
+const MAX_LOGS: usize = 6;
+
+/// A call to a classified function, with the return data and logs of the
+/// call frame
+#[derive(Debug, Clone)]
+pub struct FuzzedCall {
+    pub call_data:    Bytes,
+    pub return_data:  Bytes,
+    pub logs:         Vec<Log>,
+    pub from_address: Address,
+    pub msg_sender:   Address,
+    pub msg_value:    U256,
+}
+
+impl FuzzedCall {
+    pub fn call_frame_info(&self, target_address: Address) -> CallFrameInfo<'_> {
+        CallFrameInfo {
+            trace_idx: 0,
+            call_data: self.call_data.clone(),
+            return_data: self.return_data.clone(),
+            target_address,
+            from_address: self.from_address,
+            logs: &self.logs,
+            delegate_logs: vec![],
+            msg_sender: self.msg_sender,
+            msg_value: self.msg_value,
+        }
+    }
+}
+
+/// The pool the calls of a protocol's classifiers are made to. It is
+/// registered with two tokens, which the fuzzed calls and logs reference
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzPool {
+    pub protocol: Protocol,
+    pub address:  Address,
+    pub tokens:   [Address; 2],
+}
+
+impl FuzzPool {
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            address: Address::left_padding_from(&[0xfe, 0xed, 0xff, protocol.to_byte()]),
+            tokens: [
+                Address::left_padding_from(&[0xfe, 0xed, 0x0a]),
+                Address::left_padding_from(&[0xfe, 0xed, 0x0b]),
+            ],
+        }
+    }
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![self.address, self.tokens[0], self.tokens[1]]
+    }
+}
+
+/// Calls to the function with the selector, made to the pool
+pub fn fuzzed_call(selector: [u8; 4], pool: FuzzPool) -> impl Strategy<Value = FuzzedCall> {
+    (
+        prop::collection::vec(word(pool), 0..MAX_WORDS),
+        prop::collection::vec(word(pool), 0..MAX_WORDS),
+        prop::collection::vec(log(pool), 0..MAX_LOGS),
+        non_zero_address(),
+        non_zero_address(),
+        prop_oneof![Just(U256::ZERO), any::<u128>().prop_map(U256::from)],
+    )
+        .prop_map(
+            move |(call_words, return_words, logs, from_address, msg_sender, msg_value)| {
+                let call_data = selector
+                    .into_iter()
+                    .chain(call_words.iter().flat_map(|word| word.0))
+                    .collect::<Vec<_>>();
+
+                FuzzedCall {
+                    call_data: call_data.into(),
+                    return_data: concat_words(&return_words),
+                    logs,
+                    from_address,
+                    msg_sender,
+                    msg_value,
+                }
+            },
+        )
+}
+
+/// An abi word, biased towards the values abi decoding accepts so that the
+/// classifiers get past decoding
+fn word(pool: FuzzPool) -> impl Strategy<Value = B256> {
+    prop_oneof![
+        // amounts
+        (1u128..=u128::MAX).prop_map(uint_word),
+        // offsets and lengths of dynamic types
+        (0u64..=(MAX_WORDS * 32) as u64).prop_map(uint_word),
+        non_zero_address().prop_map(|address| address.into_word()),
+        prop::sample::select(pool.addresses()).prop_map(|address| address.into_word()),
+        any::<[u8; 32]>().prop_map(B256::from),
+    ]
+}
+
+fn log(pool: FuzzPool) -> impl Strategy<Value = Log> {
+    let topic = prop_oneof![Just(Transfer::SIGNATURE_HASH), word(pool)];
+    (
+        prop::sample::select(pool.addresses()),
+        prop::collection::vec(topic, 0..=4),
+        prop::collection::vec(word(pool), 0..4),
+    )
+        .prop_map(|(address, topics, data)| {
+            Log::new_unchecked(address, topics, concat_words(&data))
+        })
+}
+
+fn non_zero_address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>()
+        .prop_filter("zero address", |address| address != &[0u8; 20])
+        .prop_map(Address::from)
+}
+
+fn uint_word<T: Into<U256>>(value: T) -> B256 {
+    B256::from(value.into().to_be_bytes::<32>())
+}
+
+fn concat_words(words: &[B256]) -> Bytes {
+    words
+        .iter()
+        .flat_map(|word| word.0)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// Checks that a classified action is well formed: the pools and tokens it
+/// references are set, and its amounts are non negative and fit in a u256
+pub fn check_action_invariants(action: &Action) -> Result<(), String> {
+    match action {
+        Action::Swap(swap) => check_swap(swap),
+        Action::SwapWithFee(swap) => {
+            check_swap(swap)?;
+            check_address("fee token", swap.fee_token.address)?;
+            check_amount("fee amount", &swap.fee_amount)
+        }
+        Action::Mint(mint) => check_liquidity(mint.pool, &mint.token, &mint.amount),
+        Action::Burn(burn) => check_liquidity(burn.pool, &burn.token, &burn.amount),
+        Action::Collect(collect) => check_liquidity(collect.pool, &collect.token, &collect.amount),
+        Action::FlashLoan(flash_loan) => {
+            check_liquidity(flash_loan.pool, &flash_loan.assets, &flash_loan.amounts)
+        }
+        Action::Liquidation(liquidation) => {
+            check_address("pool", liquidation.pool)?;
+            check_address("collateral asset", liquidation.collateral_asset.address)?;
+            check_address("debt asset", liquidation.debt_asset.address)?;
+            check_amount("covered debt", &liquidation.covered_debt)?;
+            check_amount("liquidated collateral", &liquidation.liquidated_collateral)
+        }
+        Action::Transfer(transfer) => {
+            check_address("token", transfer.token.address)?;
+            check_amount("amount", &transfer.amount)?;
+            check_amount("fee", &transfer.fee)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_swap(swap: &NormalizedSwap) -> Result<(), String> {
+    check_address("pool", swap.pool)?;
+    check_address("token in", swap.token_in.address)?;
+    check_address("token out", swap.token_out.address)?;
+    check_amount("amount in", &swap.amount_in)?;
+    check_amount("amount out", &swap.amount_out)
+}
+
+fn check_liquidity(
+    pool: Address,
+    tokens: &[TokenInfoWithAddress],
+    amounts: &[Rational],
+) -> Result<(), String> {
+    check_address("pool", pool)?;
+    if tokens.len() != amounts.len() {
+        return Err(format!("{} tokens but {} amounts", tokens.len(), amounts.len()))
+    }
+    for (token, amount) in tokens.iter().zip(amounts) {
+        check_address("token", token.address)?;
+        check_amount("amount", amount)?;
+    }
+
+    Ok(())
+}
+
+fn check_address(field: &str, address: Address) -> Result<(), String> {
+    if address.is_zero() {
+        return Err(format!("{field} is the zero address"))
+    }
+
+    Ok(())
+}
+
+fn check_amount(field: &str, amount: &Rational) -> Result<(), String> {
+    if *amount < Rational::ZERO {
+        return Err(format!("{field} is negative: {amount}"))
+    }
+    if *amount > U256::MAX.to_scaled_rational(0) {
+        return Err(format!("{field} doesn't fit in a u256: {amount}"))
+    }
+
+    Ok(())
+}
+
+impl ClassifierTestUtils {
+    /// Runs the cases of the config against every classifier of the
+    /// [`ProtocolClassifier`], failing with the name of the first classifier
+    /// that panics or breaks an invariant
+    pub fn fuzz_classifier_dispatch(&self, config: Config) -> Result<(), String> {
+        let config = Config { failure_persistence: None, ..config };
+        let dispatcher = ProtocolClassifier::default();
+
+        for (name, sig) in ProtocolClassifier::CLASSIFIERS {
+            let protocol = Protocol::from_byte(sig[4])
+                .ok_or_else(|| format!("{name}: unknown protocol byte {}", sig[4]))?;
+            let pool = FuzzPool::new(protocol);
+            self.ensure_fuzz_pool(pool);
+
+            let selector = [sig[0], sig[1], sig[2], sig[3]];
+            TestRunner::new(config.clone())
+                .run(&fuzzed_call(selector, pool), |call| {
+                    let Some((_, action)) = dispatcher.dispatch(
+                        call.call_frame_info(pool.address),
+                        self.trace_loader.libmdbx,
+                        0,
+                        0,
+                    ) else {
+                        return Ok(())
+                    };
+
+                    check_action_invariants(&action).map_err(TestCaseError::fail)
+                })
+                .map_err(|e| format!("{name}: {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_fuzz_pool(&self, pool: FuzzPool) {
+        for (i, token) in pool.tokens.into_iter().enumerate() {
+            self.ensure_token(TokenInfoWithAddress {
+                address: token,
+                inner:   TokenInfo::new(18, format!("FUZZ{i}")),
+            });
+        }
+
+        self.ensure_protocol(
+            pool.protocol,
+            pool.address,
+            pool.tokens[0],
+            Some(pool.tokens[1]),
+            None,
+            None,
+            None,
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_malformed_actions() {
+        let token = |byte| TokenInfoWithAddress {
+            address: Address::with_last_byte(byte),
+            ..Default::default()
+        };
+        let swap = NormalizedSwap {
+            pool: Address::with_last_byte(1),
+            token_in: token(2),
+            token_out: token(3),
+            amount_in: Rational::from(10),
+            amount_out: Rational::from(5),
+            ..Default::default()
+        };
+        assert_eq!(check_action_invariants(&Action::Swap(swap.clone())), Ok(()));
+
+        let negative = NormalizedSwap { amount_out: Rational::from(-5), ..swap.clone() };
+        assert!(check_action_invariants(&Action::Swap(negative)).is_err());
+
+        let no_pool = NormalizedSwap { pool: Address::ZERO, ..swap };
+        assert!(check_action_invariants(&Action::Swap(no_pool)).is_err());
+    }
+
+    #[brontes_macros::test]
+    async fn test_classifier_dispatch_fuzz() {
+        let utils = ClassifierTestUtils::new().await;
+        utils
+            .fuzz_classifier_dispatch(Config::with_cases(64))
+            .unwrap();
+    }
+}
@@ -3,3 +3,6 @@ pub use tests::*;
 
 pub mod benches;
 pub use benches::*;
+
+pub mod fuzz;
+pub use fuzz::*;
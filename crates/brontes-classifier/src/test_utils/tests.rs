@@ -6,7 +6,7 @@ use std::{
     },
 };
 
-use alloy_primitives::{Address, TxHash};
+use alloy_primitives::{Address, Bytes, Log, TxHash, U256};
 use brontes_core::{
     decoding::TracingProvider, BlockTracesWithHeaderAnd, TraceLoader, TraceLoaderError,
     TxTracesWithHeaderAnd,
@@ -24,7 +24,7 @@ use brontes_types::{
         address_to_protocol_info::ProtocolInfo, dex::DexQuotes, token_info::TokenInfoWithAddress,
     },
     normalized_actions::{pool::NormalizedNewPool, NormalizedTransfer},
-    structured_trace::TraceActions,
+    structured_trace::{CallFrameInfo, TraceActions},
     tree::BlockTree,
     BrontesTaskManager, FastHashMap, TreeCollector, TreeSearchBuilder, UnboundedYapperReceiver,
 };
@@ -34,7 +34,7 @@ use serde_json::Value;
 use thiserror::Error;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
-use crate::{Action, ActionCollection, Classifier, ProtocolClassifier};
+use crate::{Action, ActionCollection, Classifier, ClassifierError, ProtocolClassifier};
 
 pub struct ClassifierTestUtils {
     pub trace_loader: TraceLoader,
@@ -122,7 +122,7 @@ impl ClassifierTestUtils {
         let TxTracesWithHeaderAnd { trace, header, .. } =
             self.trace_loader.get_tx_trace_with_header(tx_hash).await?;
 
-        let tx_roots = self.classifier.build_tx_trees(vec![trace], &header).await;
+        let tx_roots = self.classifier.build_tx_trees(vec![trace], &header).await?;
 
         let mut tree = BlockTree::new(header, tx_roots.len());
 
@@ -137,7 +137,7 @@ impl ClassifierTestUtils {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlockTree<Action>>, ClassifierTestUtilsError> {
-        Ok(join_all(
+        join_all(
             self.trace_loader
                 .get_tx_traces_with_header(tx_hashes)
                 .await?
@@ -146,7 +146,7 @@ impl ClassifierTestUtils {
                     let tx_roots = self
                         .classifier
                         .build_tx_trees(data.traces, &data.header)
-                        .await;
+                        .await?;
 
                     let mut tree = BlockTree::new(data.header, tx_roots.len());
 
@@ -154,10 +154,12 @@ impl ClassifierTestUtils {
                         tree.insert_root(root_data.root);
                     });
 
-                    tree
+                    Ok::<_, ClassifierTestUtilsError>(tree)
                 }),
         )
-        .await)
+        .await
+        .into_iter()
+        .collect()
     }
 
     pub async fn build_tree_tx(
@@ -169,7 +171,7 @@ impl ClassifierTestUtils {
         Ok(self
             .classifier
             .build_block_tree(vec![trace], header, true)
-            .await)
+            .await?)
     }
 
     pub async fn setup_pricing_for_bench(
@@ -188,7 +190,7 @@ impl ClassifierTestUtils {
         let (tx, rx) = unbounded_channel();
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -217,7 +219,7 @@ impl ClassifierTestUtils {
             .await?;
 
         let classifier = Classifier::new(self.libmdbx, tx, self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         Ok(())
     }
@@ -246,7 +248,7 @@ impl ClassifierTestUtils {
         let BlockTracesWithHeaderAnd { traces, header, .. } = range_traces.remove(0);
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -262,7 +264,7 @@ impl ClassifierTestUtils {
 
         // send rest of updates
         for BlockTracesWithHeaderAnd { traces, header, .. } in range_traces {
-            classifier.build_block_tree(traces, header, true).await;
+            classifier.build_block_tree(traces, header, true).await?;
         }
 
         ctr.store(true, SeqCst);
@@ -283,7 +285,9 @@ impl ClassifierTestUtils {
         let (tx, rx) = unbounded_channel();
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(vec![trace], header, true).await;
+        let tree = classifier
+            .build_block_tree(vec![trace], header, true)
+            .await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -313,7 +317,7 @@ impl ClassifierTestUtils {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlockTree<Action>>, ClassifierTestUtilsError> {
-        Ok(join_all(
+        join_all(
             self.trace_loader
                 .get_tx_traces_with_header(tx_hashes)
                 .await?
@@ -324,7 +328,10 @@ impl ClassifierTestUtils {
                         .await
                 }),
         )
-        .await)
+        .await
+        .into_iter()
+        .map(|tree| Ok(tree?))
+        .collect()
     }
 
     pub async fn test_pool_token_order(
@@ -364,7 +371,7 @@ impl ClassifierTestUtils {
 
             let tree = classifier
                 .build_block_tree(block_info.traces, block_info.header, true)
-                .await;
+                .await?;
 
             trees.push(tree);
         }
@@ -404,7 +411,10 @@ impl ClassifierTestUtils {
             .trace_loader
             .get_block_traces_with_header(block)
             .await?;
-        let tree = self.classifier.build_block_tree(traces, header, true).await;
+        let tree = self
+            .classifier
+            .build_block_tree(traces, header, true)
+            .await?;
 
         Ok(tree)
     }
@@ -422,7 +432,7 @@ impl ClassifierTestUtils {
 
         let (tx, rx) = unbounded_channel();
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(traces, header, true).await;
+        let tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -595,7 +605,9 @@ impl ClassifierTestUtils {
 
         let (tx, _rx) = unbounded_channel();
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(vec![trace], header, true).await;
+        let tree = classifier
+            .build_block_tree(vec![trace], header, true)
+            .await?;
         let res = Arc::new(tree)
             .collect(&txes, TreeSearchBuilder::default().with_action(Action::is_new_pool))
             .split_actions(Action::try_new_pool);
@@ -631,6 +643,7 @@ impl ClassifierTestUtils {
                         token4,
                         curve_lp_token,
                         init_block: 0,
+                        extra_tokens: vec![],
                     },
                 },
             ])
@@ -645,15 +658,57 @@ impl ClassifierTestUtils {
             .db
             .write_table::<TokenDecimals, TokenDecimalsData>(&[TokenDecimalsData {
                 key:   token.address,
-                value: brontes_types::db::token_info::TokenInfo {
-                    decimals: token.decimals,
-                    symbol:   token.symbol.clone(),
-                },
+                value: token.inner.clone(),
             }])
         {
             tracing::error!(error=%e, ?token, "failed to ensure token is in db");
         }
     }
+
+    /// Registers the tokens and the `protocol` contract at `address`, with
+    /// `token0` and `token1` as its protocol tokens
+    pub fn ensure_protocol_with_tokens(
+        &self,
+        protocol: Protocol,
+        address: Address,
+        token0: Address,
+        token1: Option<Address>,
+        tokens: &[TokenInfoWithAddress],
+    ) {
+        for token in tokens {
+            self.ensure_token(token.clone());
+        }
+        self.ensure_protocol(protocol, address, token0, token1, None, None, None, None);
+    }
+
+    /// Classifies a single call to `target`, made by
+    /// `Address::with_last_byte(1)`, the way the tree builder classifies a
+    /// traced call. Used to test classifiers against synthetic calls
+    /// instead of a traced transaction
+    pub fn classify_call(
+        &self,
+        target: Address,
+        call_data: Vec<u8>,
+        return_data: Vec<u8>,
+        logs: &[Log],
+        msg_value: U256,
+    ) -> Option<Action> {
+        let call_info = CallFrameInfo {
+            trace_idx: 0,
+            call_data: Bytes::from(call_data),
+            return_data: Bytes::from(return_data),
+            target_address: target,
+            from_address: Address::with_last_byte(1),
+            logs,
+            delegate_logs: vec![],
+            msg_sender: Address::with_last_byte(1),
+            msg_value,
+        };
+
+        ProtocolClassifier::default()
+            .dispatch(call_info, self.libmdbx, 0, 0)
+            .map(|(_, action)| action)
+    }
 }
 
 impl Deref for ClassifierTestUtils {
@@ -704,13 +759,15 @@ pub enum ClassifierTestUtilsError {
     ProtocolDiscoveryError(Address),
     #[error("couldn't find trace that matched {0:?}")]
     ProtocolClassifierError(Address),
+    #[error(transparent)]
+    ClassifierError(#[from] ClassifierError),
 }
 
 /// Makes a swap for initializing a virtual pool with the quote token.
 /// this swap is empty such that we don't effect the state
 fn make_fake_transfer(addr: Address) -> Action {
     let t_in = TokenInfoWithAddress {
-        inner:   brontes_types::db::token_info::TokenInfo { decimals: 0, symbol: String::new() },
+        inner:   brontes_types::db::token_info::TokenInfo::new(0, String::new()),
         address: addr,
     };
 
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use alloy_primitives::{hex, Address};
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    make_call_request, normalized_actions::pool::NormalizedNewPool,
+    structured_trace::TransactionTraceWithLogs, traits::TracingProvider,
+};
+use futures::join;
+use reth_rpc_types::trace::parity::{Action as TraceAction, TraceOutput};
+use tracing::trace;
+
+alloy_sol_macro::sol!(
+    function token0() returns (address);
+    function token1() returns (address);
+);
+
+/// Runtime code of an EIP-1167 minimal proxy up to the implementation address
+const MINIMAL_PROXY_PREFIX: [u8; 10] = hex!("363d3d373d3d3d363d73");
+/// Runtime code of an EIP-1167 minimal proxy after the implementation address
+const MINIMAL_PROXY_SUFFIX: [u8; 15] = hex!("5af43d82803e903d91602b57fd5bf3");
+const MINIMAL_PROXY_LEN: usize = MINIMAL_PROXY_PREFIX.len() + 20 + MINIMAL_PROXY_SUFFIX.len();
+
+/// Returns the implementation the contract created by the trace delegates to
+/// if it is an EIP-1167 minimal proxy. The deployed code is checked first,
+/// falling back to the runtime code embedded in the init code for traces that
+/// don't carry the create output.
+pub(crate) fn minimal_proxy_implementation(trace: &TransactionTraceWithLogs) -> Option<Address> {
+    if let Some(TraceOutput::Create(output)) = &trace.trace.result {
+        if let Some(implementation) = parse_minimal_proxy(&output.code) {
+            return Some(implementation)
+        }
+    }
+
+    let TraceAction::Create(create) = &trace.trace.action else { return None };
+    create
+        .init
+        .windows(MINIMAL_PROXY_LEN)
+        .find_map(parse_minimal_proxy)
+}
+
+fn parse_minimal_proxy(code: &[u8]) -> Option<Address> {
+    if code.len() != MINIMAL_PROXY_LEN {
+        return None
+    }
+
+    let (prefix, rest) = code.split_at(MINIMAL_PROXY_PREFIX.len());
+    let (implementation, suffix) = rest.split_at(20);
+
+    (prefix == MINIMAL_PROXY_PREFIX && suffix == MINIMAL_PROXY_SUFFIX)
+        .then(|| Address::from_slice(implementation))
+}
+
+/// Many factories deploy pools as clones of a single implementation without
+/// emitting anything the factory discovery classifiers match on. If the
+/// implementation of a created minimal proxy is registered to a protocol, the
+/// clone is discovered as a pool of the same protocol so that it is classified
+/// by that protocol's classifier.
+///
+/// If the implementation is registered with tokens the clone is a market of
+/// the same tokens. Otherwise the tokens are only set when the clone is
+/// initialized, which factories do in the same transaction as the creation,
+/// so they are queried from the clone at the end of the block.
+pub(crate) async fn discover_minimal_proxy<T: TracingProvider, DB: LibmdbxReader>(
+    libmdbx: &DB,
+    provider: &Arc<T>,
+    trace: &TransactionTraceWithLogs,
+    created_addr: Address,
+    trace_index: u64,
+    block: u64,
+) -> Option<NormalizedNewPool> {
+    let implementation = minimal_proxy_implementation(trace)?;
+    let info = libmdbx.get_protocol_details(implementation).ok()?;
+    let protocol = info.protocol;
+
+    let mut tokens = (0..5 + info.extra_tokens.len())
+        .filter_map(|i| info.token(i))
+        .filter(|token| !token.is_zero())
+        .collect::<Vec<_>>();
+    if tokens.is_empty() {
+        tokens = clone_tokens(provider, created_addr, block).await;
+    }
+
+    trace!(
+        target: "brontes_classifier::discovery",
+        ?created_addr,
+        ?implementation,
+        %protocol,
+        ?tokens,
+        "discovered minimal proxy of a registered implementation"
+    );
+
+    Some(NormalizedNewPool {
+        trace_index,
+        protocol,
+        pool_address: created_addr,
+        tokens,
+        fee_tier: None,
+        #[cfg(feature = "provenance")]
+        provenance: None,
+    })
+}
+
+/// Tokens of a two token pool, empty if the clone doesn't expose them
+async fn clone_tokens<T: TracingProvider>(
+    provider: &Arc<T>,
+    pool: Address,
+    block: u64,
+) -> Vec<Address> {
+    let (token0, token1) = join!(
+        make_call_request(token0Call::new(()), provider, pool, Some(block)),
+        make_call_request(token1Call::new(()), provider, pool, Some(block))
+    );
+
+    match (token0, token1) {
+        (Ok(token0), Ok(token1)) if !token0._0.is_zero() && !token1._0.is_zero() => {
+            vec![token0._0, token1._0]
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_minimal_proxy_code() {
+        let implementation = Address::with_last_byte(0xaa);
+        let mut code = MINIMAL_PROXY_PREFIX.to_vec();
+        code.extend_from_slice(implementation.as_slice());
+        code.extend_from_slice(&MINIMAL_PROXY_SUFFIX);
+
+        assert_eq!(parse_minimal_proxy(&code), Some(implementation));
+
+        // init code of the clone, the runtime code is returned by the constructor
+        let mut init = hex!("3d602d80600a3d3981f3").to_vec();
+        init.extend_from_slice(&code);
+        assert_eq!(
+            init.windows(MINIMAL_PROXY_LEN)
+                .find_map(parse_minimal_proxy),
+            Some(implementation)
+        );
+
+        code[0] = 0;
+        assert_eq!(parse_minimal_proxy(&code), None);
+        assert_eq!(parse_minimal_proxy(&code[1..]), None);
+    }
+}
@@ -1,50 +1,82 @@
-use std::{cmp::min, sync::Arc};
+use std::{any::Any, cmp::min, panic::AssertUnwindSafe, sync::Arc};
 
-use alloy_primitives::{Log, U256};
-use brontes_core::missing_token_info::load_missing_token_info;
+use alloy_primitives::{Log, Selector, U256};
+use brontes_core::missing_token_info::TokenDecimalResolver;
+use brontes_metrics::classifier::ClassificationMetrics;
 use brontes_pricing::types::PoolUpdate;
 use brontes_types::{
+    db::{
+        classification_failure::ClassificationFailure, pool_fee_tier::PoolFeeTier,
+        protocol_metadata::canonical_factory, token_tax::TokenTax,
+    },
+    execute_on,
     normalized_actions::{
-        pool::NormalizedNewPool, MultiCallFrameClassification, MultiFrameRequest, NormalizedAction,
-        NormalizedEthTransfer, NormalizedTransfer,
+        pool::NormalizedNewPool, register_custom_action, MultiCallFrameClassification,
+        MultiFrameRequest, NormalizedAction, NormalizedApproval, NormalizedAuctionStart,
+        NormalizedEthTransfer, NormalizedNftTrade, NormalizedOrderFill, NormalizedSelectorCall,
+        NormalizedSettlement, NormalizedStake, NormalizedTransfer, NormalizedUnstake,
+        NormalizedWrap,
     },
     tree::root::NodeData,
     ToScaledRational,
 };
 use malachite::{num::basic::traits::Zero, Rational};
 
-mod tree_pruning;
+pub(crate) mod minimal_proxy;
+pub(crate) mod multicall;
+pub mod tree_pruning;
 pub(crate) mod utils;
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_pricing::types::DexPriceMsg;
+#[cfg(feature = "provenance")]
+use brontes_types::normalized_actions::ActionProvenance;
 use brontes_types::{
     normalized_actions::{Action, SelfdestructWithIndex},
+    order_flow::SubmissionChannel,
     structured_trace::{TraceActions, TransactionTraceWithLogs, TxTrace},
     traits::TracingProvider,
-    tree::{BlockTree, GasDetails, Node, Root},
+    tree::{BlockTree, GasDetails, Node, Root, UserOperation},
 };
-use futures::future::join_all;
+use futures::{future::join_all, FutureExt};
 use itertools::Itertools;
 use malachite::num::arithmetic::traits::Abs;
+use minimal_proxy::discover_minimal_proxy;
+use multicall::MulticallFrames;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use reth_primitives::{Address, Header};
 use reth_rpc_types::trace::parity::{Action as TraceAction, CallType};
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::{
+    runtime::{Handle, RuntimeFlavor},
+    sync::mpsc::UnboundedSender,
+};
 use tracing::{error, trace};
-use tree_pruning::{account_for_tax_tokens, remove_possible_transfer_double_counts};
+use tree_pruning::{default_tree_passes, TreePass};
 use utils::{decode_transfer, get_coinbase_transfer};
 
-use self::erc20::try_decode_transfer;
+use self::{
+    approval::try_decode_approvals,
+    erc20::try_decode_transfer,
+    erc4626::{is_vault_call, try_decode_vault_action},
+    weth::try_decode_weth_action,
+};
 use crate::{
-    classifiers::*, multi_frame_classification::parse_multi_frame_requests, ActionCollection,
-    FactoryDiscoveryDispatch,
+    classifiers::*,
+    multi_frame_classification::{
+        flash_loan::match_repayments_outside_loan, parse_multi_frame_requests,
+    },
+    ActionCollection, ClassifierError, FactoryDiscoveryDispatch, CLASSIFICATION_METRICS,
 };
 
 //TODO: Document this module
 #[derive(Debug, Clone)]
 pub struct Classifier<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> {
-    libmdbx:               &'db DB,
-    provider:              Arc<T>,
-    pricing_update_sender: UnboundedSender<DexPriceMsg>,
+    libmdbx:                 &'db DB,
+    provider:                Arc<T>,
+    decimal_resolver:        TokenDecimalResolver<T>,
+    pricing_update_sender:   UnboundedSender<DexPriceMsg>,
+    tree_passes:             Vec<Arc<dyn TreePass>>,
+    selector_fallback:       bool,
+    parallel_classification: bool,
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, DB> {
@@ -53,42 +85,136 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         pricing_update_sender: UnboundedSender<DexPriceMsg>,
         provider: Arc<T>,
     ) -> Self {
-        Self { libmdbx, pricing_update_sender, provider }
+        // settles and takes on the v4 pool manager, nft marketplace trades,
+        // order fills, liquid staking, approvals and auction starts are
+        // classified as custom actions
+        register_custom_action::<NormalizedSettlement>();
+        register_custom_action::<NormalizedNftTrade>();
+        register_custom_action::<NormalizedOrderFill>();
+        register_custom_action::<NormalizedStake>();
+        register_custom_action::<NormalizedUnstake>();
+        register_custom_action::<NormalizedWrap>();
+        register_custom_action::<NormalizedApproval>();
+        register_custom_action::<NormalizedAuctionStart>();
+
+        Self {
+            libmdbx,
+            pricing_update_sender,
+            decimal_resolver: TokenDecimalResolver::new(provider.clone()),
+            provider,
+            tree_passes: default_tree_passes(),
+            selector_fallback: false,
+            parallel_classification: true,
+        }
+    }
+
+    /// Classifies calls to contracts without a protocol classifier from the
+    /// function selector table, instead of leaving them unclassified
+    pub fn with_selector_fallback(mut self) -> Self {
+        register_custom_action::<NormalizedSelectorCall>();
+        self.selector_fallback = true;
+        self
+    }
+
+    /// Replaces the passes run over the tree before the multi frame
+    /// classification. They are run in the given order
+    pub fn with_tree_passes(mut self, tree_passes: Vec<Arc<dyn TreePass>>) -> Self {
+        self.tree_passes = tree_passes;
+        self
+    }
+
+    /// Appends a pass to run after the configured ones
+    pub fn with_tree_pass(mut self, tree_pass: Arc<dyn TreePass>) -> Self {
+        self.tree_passes.push(tree_pass);
+        self
+    }
+
+    /// Classifies the txs of a block one after another on the calling task,
+    /// instead of in parallel on the classification pool
+    pub fn with_serial_classification(mut self) -> Self {
+        self.parallel_classification = false;
+        self
     }
 
     pub fn block_load_failure(&self, number: u64) {
-        self.pricing_update_sender
-            .send(DexPriceMsg::DisablePricingFor(number))
-            .unwrap();
+        if let Err(e) = self.send_pricing_update(DexPriceMsg::DisablePricingFor(number)) {
+            error!(block = number, error = %e, "failed to disable pricing for block");
+        }
     }
 
+    /// Builds the classified tree of the block. A tx whose classification
+    /// fails is skipped and recorded in the classification metrics, only
+    /// errors that affect the whole block are returned.
     pub async fn build_block_tree(
         &self,
         traces: Vec<TxTrace>,
         header: Header,
         generate_pricing: bool,
-    ) -> BlockTree<Action> {
+    ) -> Result<BlockTree<Action>, ClassifierError> {
         let block_number = header.number;
         if !generate_pricing {
-            self.pricing_update_sender
-                .send(DexPriceMsg::DisablePricingFor(block_number))
-                .unwrap();
+            self.send_pricing_update(DexPriceMsg::DisablePricingFor(block_number))?;
         }
 
-        let tx_roots = self.build_tx_trees(traces, &header).await;
+        self.prefetch_classification_data(&traces);
+        self.resolve_missing_decimals(&traces, block_number).await;
+        let tx_roots = self.build_tx_trees(traces, &header).await?;
         let mut tree = BlockTree::new(header, tx_roots.len());
 
         // send out all updates
         let further_classification_requests =
-            self.process_tx_roots(tx_roots, &mut tree, block_number);
+            self.process_tx_roots(tx_roots, &mut tree, block_number)?;
 
-        account_for_tax_tokens(&mut tree);
-        remove_possible_transfer_double_counts(&mut tree);
+        for pass in &self.tree_passes {
+            tracing::debug!(pass = pass.name(), "running tree pass");
+            pass.run(&mut tree);
+        }
 
         self.finish_classification(&mut tree, further_classification_requests);
         tree.finalize_tree();
 
-        tree
+        self.record_token_taxes(block_number, &tree).await;
+
+        Ok(tree)
+    }
+
+    fn send_pricing_update(&self, msg: DexPriceMsg) -> Result<(), ClassifierError> {
+        self.pricing_update_sender
+            .send(msg)
+            .map_err(|_| ClassifierError::PricingChannelClosed)
+    }
+
+    /// Loads the protocol and token info of every called contract and log
+    /// emitter of the block at once, instead of every trace opening its own
+    /// read transaction while being classified
+    fn prefetch_classification_data(&self, traces: &[TxTrace]) {
+        let addresses = traces
+            .iter()
+            .flat_map(|tx| &tx.trace)
+            .flat_map(|trace| {
+                std::iter::once(trace.get_to_address())
+                    .chain(trace.logs.iter().map(|log| log.address))
+            })
+            .unique()
+            .collect_vec();
+
+        if let Err(e) = self.libmdbx.prefetch_classification_data(&addresses) {
+            error!(?e, "failed to prefetch classification data");
+        }
+    }
+
+    /// Resolves the decimals of every token transferred in the block that isn't
+    /// in the database yet, so the classifiers never fail on a missing token
+    async fn resolve_missing_decimals(&self, traces: &[TxTrace], block: u64) {
+        let tokens = traces
+            .iter()
+            .flat_map(|tx| &tx.trace)
+            .flat_map(|trace| &trace.logs)
+            .filter_map(|log| decode_transfer(log).map(|(token, ..)| token));
+
+        self.decimal_resolver
+            .resolve(self.libmdbx, block, tokens)
+            .await;
     }
 
     fn process_tx_roots(
@@ -96,178 +222,314 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         tx_roots: Vec<TxTreeResult>,
         tree: &mut BlockTree<Action>,
         block: u64,
-    ) -> Vec<Option<(usize, Vec<MultiCallFrameClassification<Action>>)>> {
+    ) -> Result<Vec<Option<(usize, Vec<MultiCallFrameClassification<Action>>)>>, ClassifierError>
+    {
         let root_count = tx_roots.len();
-        let results = tx_roots
-            .into_iter()
-            .map(|root_data| {
-                tree.insert_root(root_data.root);
-                root_data.pool_updates.into_iter().for_each(|update| {
-                    tracing::trace!("sending dex price update: {:?}", update);
-                    self.pricing_update_sender.send(update).unwrap();
-                });
+        let mut results = Vec::with_capacity(root_count);
+        for root_data in tx_roots {
+            tree.insert_root(root_data.root);
+            for update in root_data.pool_updates {
+                tracing::trace!("sending dex price update: {:?}", update);
+                self.send_pricing_update(update)?;
+            }
 
+            results.push(
                 root_data
                     .further_classification_requests
-                    .map(|(tx, requests)| (tx, parse_multi_frame_requests(requests)))
-            })
-            .collect_vec();
+                    .map(|(tx, requests)| (tx, parse_multi_frame_requests(requests))),
+            );
+        }
 
         // ensure we always have eth price being generated
-        self.pricing_update_sender
-            .send(DexPriceMsg::Update(PoolUpdate {
-                block,
-                tx_idx: root_count as u64,
-                logs: vec![],
-                action: Action::EthTransfer(NormalizedEthTransfer::default()),
-            }))
-            .unwrap();
+        self.send_pricing_update(DexPriceMsg::Update(PoolUpdate {
+            block,
+            tx_idx: root_count as u64,
+            logs: vec![],
+            action: Action::EthTransfer(NormalizedEthTransfer::default()),
+        }))?;
 
-        results
+        Ok(results)
     }
 
+    /// Builds the tree of every tx in the block. Classifying a tx is cpu bound,
+    /// so on a multi threaded runtime the txs are classified in parallel on the
+    /// classification pool, which bounds how many are classified at once.
     pub(crate) async fn build_tx_trees(
         &self,
         traces: Vec<TxTrace>,
         header: &Header,
-    ) -> Vec<TxTreeResult> {
-        join_all(
-            traces
-                .into_iter()
-                .enumerate()
-                .map(|(tx_idx, mut trace)| async move {
-                    // here only traces where the root tx failed are filtered out
-                    if trace.trace.is_empty() || !trace.is_success {
-                        tracing::trace!(
-                            empty = trace.trace.is_empty(),
-                            is_success = trace.is_success
-                        );
-                        return None
-                    }
-                    // post classification processing collectors
-                    let mut further_classification_requests = Vec::new();
-                    let mut pool_updates: Vec<DexPriceMsg> = Vec::new();
-
-                    let root_trace = trace.trace.remove(0);
-
-                    let address = root_trace.get_from_addr();
-                    let trace_idx = root_trace.trace_idx;
-
-                    let classification = self
-                        .process_classification(
-                            header.number,
-                            None,
-                            &NodeData(vec![]),
-                            tx_idx as u64,
-                            trace_idx,
-                            root_trace,
-                            &trace.trace,
-                            &mut further_classification_requests,
-                            &mut pool_updates,
-                        )
-                        .await;
-
-                    let node = Node::new(trace_idx, address, vec![]);
-
-                    let total_msg_value_transfers = classification
-                        .iter()
-                        .filter_map(|s| s.get_msg_value_not_eth_transfer())
-                        .collect::<Vec<NormalizedEthTransfer>>();
-
-                    let mut tx_root = Root {
-                        position: tx_idx,
-                        head: node,
-                        tx_hash: trace.tx_hash,
-                        private: false,
-                        total_msg_value_transfers,
-                        gas_details: GasDetails {
-                            coinbase_transfer:   None,
-                            gas_used:            trace.gas_used,
-                            effective_gas_price: trace.effective_price,
-                            priority_fee:        trace.effective_price
-                                - (header.base_fee_per_gas.unwrap_or_default() as u128),
-                        },
-                        data_store: NodeData(vec![Some(classification)]),
-                    };
+    ) -> Result<Vec<TxTreeResult>, ClassifierError> {
+        let payees = self.builder_payees(header);
+        let build_tx_tree = |(tx_idx, trace): (usize, TxTrace)| {
+            self.build_tx_tree_isolated(tx_idx, trace, header, &payees)
+        };
 
-                    let tx_trace = &trace.trace;
-                    for trace in &trace.trace {
-                        let from_addr = trace.get_from_addr();
-
-                        let node = Node::new(
-                            trace.trace_idx,
-                            from_addr,
-                            trace.trace.trace_address.clone(),
-                        );
-
-                        if trace.trace.error.is_none() {
-                            if let Some(coinbase_transfer) =
-                                get_coinbase_transfer(header.beneficiary, &trace.trace.action)
-                            {
-                                if let Some(coinbase) = &mut tx_root.gas_details.coinbase_transfer {
-                                    *coinbase += coinbase_transfer;
-                                } else {
-                                    tx_root.gas_details.coinbase_transfer = Some(coinbase_transfer);
-                                }
-
-                                let classification = Action::EthTransfer(NormalizedEthTransfer {
-                                    from:              from_addr,
-                                    to:                trace.get_to_address(),
-                                    value:             trace.get_msg_value(),
-                                    trace_index:       trace.trace_idx,
-                                    coinbase_transfer: true,
-                                });
-
-                                tx_root.insert(node, vec![classification]);
-                                continue
-                            }
-                        }
+        let handle = Handle::current();
+        let results = if !self.parallel_classification
+            || handle.runtime_flavor() == RuntimeFlavor::CurrentThread
+        {
+            join_all(traces.into_iter().enumerate().map(build_tx_tree)).await
+        } else {
+            // the classification futures are driven to completion on the pool
+            // threads, any io they do is still handled by the runtime
+            tokio::task::block_in_place(|| {
+                execute_on!(target = classification, {
+                    traces
+                        .into_par_iter()
+                        .enumerate()
+                        .map(|tx| handle.block_on(build_tx_tree(tx)))
+                        .collect::<Vec<_>>()
+                })
+            })
+        };
 
-                        let classification = self
-                            .process_classification(
-                                header.number,
-                                Some(&tx_root.head),
-                                &tx_root.data_store,
-                                tx_idx as u64,
-                                trace.trace_idx,
-                                trace.clone(),
-                                tx_trace,
-                                &mut further_classification_requests,
-                                &mut pool_updates,
-                            )
-                            .await;
-
-                        tx_root.total_msg_value_transfers.extend(
-                            classification
-                                .iter()
-                                .filter_map(|s| s.get_msg_value_not_eth_transfer()),
-                        );
-
-                        tx_root.insert(node, classification);
-                    }
+        let (tx_roots, failed_txs) = isolate_failed_txs(results)?;
+        for (tx_idx, error) in failed_txs {
+            self.record_failure(header.number, tx_idx, &error, true)
+                .await;
+        }
+
+        Ok(tx_roots)
+    }
 
-                    // Here we reverse the requests to ensure that we always classify the most
-                    // nested action & its children first. This is to prevent the
-                    // case where we classify a parent action where its children also require
-                    // further classification.
-                    let tx_classification_requests = if !further_classification_requests.is_empty()
-                    {
-                        further_classification_requests.reverse();
-                        Some((tx_idx, further_classification_requests))
+    /// The addresses a payment to counts as a payment to the builder of the
+    /// block. Besides the fee recipient, this is the builder's collateral
+    /// address, which builders also have searchers pay to.
+    fn builder_payees(&self, header: &Header) -> Vec<Address> {
+        let collateral = self
+            .libmdbx
+            .try_fetch_builder_info(header.beneficiary)
+            .ok()
+            .flatten()
+            .and_then(|builder| builder.ultrasound_relay_collateral_address);
+
+        std::iter::once(header.beneficiary)
+            .chain(collateral)
+            .unique()
+            .collect()
+    }
+
+    /// Classifies the tx, catching a panic so that a single tx that can't be
+    /// classified doesn't take down the block
+    async fn build_tx_tree_isolated(
+        &self,
+        tx_idx: usize,
+        trace: TxTrace,
+        header: &Header,
+        payees: &[Address],
+    ) -> Result<Option<TxTreeResult>, ClassifierError> {
+        let tx_hash = trace.tx_hash;
+        AssertUnwindSafe(self.build_tx_tree(tx_idx, trace, header, payees))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|panic| {
+                Err(ClassifierError::TxPanicked { tx_idx, tx_hash, message: panic_message(panic) })
+            })
+    }
+
+    /// Records a failure the classification of the block recovered from in
+    /// the metrics and in libmdbx. `skipped_tx` is set when the tx was left
+    /// out of the tree.
+    async fn record_failure(
+        &self,
+        block: u64,
+        tx_idx: u64,
+        error: &ClassifierError,
+        skipped_tx: bool,
+    ) {
+        let metrics = CLASSIFICATION_METRICS.get_or_init(ClassificationMetrics::default);
+        if skipped_tx {
+            error!(%block, tx_idx, %error, "skipping tx that failed classification");
+            metrics.failed_tx_classification(error.kind());
+        } else {
+            error!(%block, tx_idx, %error, "continuing the classification of the tx");
+            if let ClassifierError::PoolInsertion { protocol, .. } = error {
+                metrics.failed_pool_insertion(*protocol);
+            }
+        }
+
+        let failure = ClassificationFailure {
+            tx_idx,
+            kind: error.kind().to_string(),
+            error: error.to_string(),
+            skipped_tx,
+        };
+        if let Err(e) = self
+            .libmdbx
+            .write_classification_failure(block, failure)
+            .await
+        {
+            error!(%block, err=%e, "failed to record the classification failure in libmdbx");
+        }
+    }
+
+    async fn build_tx_tree(
+        &self,
+        tx_idx: usize,
+        mut trace: TxTrace,
+        header: &Header,
+        payees: &[Address],
+    ) -> Result<Option<TxTreeResult>, ClassifierError> {
+        // here only traces where the root tx failed are filtered out
+        if trace.trace.is_empty() || !trace.is_success {
+            tracing::trace!(empty = trace.trace.is_empty(), is_success = trace.is_success);
+            return Ok(None)
+        }
+        // post classification processing collectors
+        let mut further_classification_requests = Vec::new();
+        let mut pool_updates: Vec<DexPriceMsg> = Vec::new();
+
+        let user_ops = UserOperation::from_traces(&trace.trace);
+        let multicalls = MulticallFrames::new(&trace.trace);
+        let root_trace = trace.trace.remove(0);
+
+        let address = root_trace.get_from_addr();
+        let trace_idx = root_trace.trace_idx;
+        #[cfg(feature = "provenance")]
+        let root_provenance = ActionProvenance::new(trace.tx_hash, &root_trace);
+
+        // a transaction paying the builder directly
+        let root_coinbase_transfer = get_coinbase_transfer(payees, &root_trace.trace.action);
+
+        let classification = if let Some((payee, value)) = root_coinbase_transfer {
+            vec![Action::EthTransfer(NormalizedEthTransfer {
+                from: address,
+                to: payee,
+                value: U256::from(value),
+                trace_index: trace_idx,
+                coinbase_transfer: true,
+                #[cfg(feature = "provenance")]
+                provenance: None,
+            })]
+        } else {
+            self.process_classification(
+                header.number,
+                None,
+                &NodeData(vec![]),
+                tx_idx as u64,
+                trace_idx,
+                root_trace,
+                &trace.trace,
+                &mut further_classification_requests,
+                &mut pool_updates,
+            )
+            .await?
+        };
+
+        #[cfg(feature = "provenance")]
+        let classification = with_provenance(classification, root_provenance);
+
+        let node = Node::new(trace_idx, address, vec![]);
+
+        let total_msg_value_transfers = classification
+            .iter()
+            .filter_map(|s| s.get_msg_value_not_eth_transfer())
+            .collect::<Vec<NormalizedEthTransfer>>();
+
+        let mut tx_root = Root {
+            position: tx_idx,
+            head: node,
+            tx_hash: trace.tx_hash,
+            private: false,
+            submission_channel: SubmissionChannel::Public,
+            total_msg_value_transfers,
+            gas_details: GasDetails {
+                coinbase_transfer:   root_coinbase_transfer.map(|(_, value)| value),
+                gas_used:            trace.gas_used,
+                effective_gas_price: trace.effective_price,
+                priority_fee:        trace.effective_price
+                    - (header.base_fee_per_gas.unwrap_or_default() as u128),
+                gas_refunded:        trace.gas_refunded,
+                access_list_gas:     trace.access_list_gas,
+                blob_gas_used:       trace.blob_gas_used,
+                blob_gas_price:      trace.blob_gas_price,
+            },
+            data_store: NodeData(vec![Some(classification)]),
+            user_ops,
+        };
+
+        let tx_trace = &trace.trace;
+        for trace in &trace.trace {
+            let from_addr = trace.get_from_addr();
+
+            let node = Node::new(trace.trace_idx, from_addr, trace.trace.trace_address.clone());
+
+            if trace.trace.error.is_none() {
+                if let Some((payee, coinbase_transfer)) =
+                    get_coinbase_transfer(payees, &trace.trace.action)
+                {
+                    if let Some(coinbase) = &mut tx_root.gas_details.coinbase_transfer {
+                        *coinbase += coinbase_transfer;
                     } else {
-                        None
-                    };
-                    Some(TxTreeResult {
-                        root: tx_root,
-                        further_classification_requests: tx_classification_requests,
-                        pool_updates,
-                    })
-                }),
-        )
-        .await
-        .into_iter()
-        .flatten()
-        .collect_vec()
+                        tx_root.gas_details.coinbase_transfer = Some(coinbase_transfer);
+                    }
+
+                    // the recipient and value are taken from the payment, as a
+                    // self destruct doesn't carry them as a call does
+                    let classification = vec![Action::EthTransfer(NormalizedEthTransfer {
+                        from: from_addr,
+                        to: payee,
+                        value: U256::from(coinbase_transfer),
+                        trace_index: trace.trace_idx,
+                        coinbase_transfer: true,
+                        #[cfg(feature = "provenance")]
+                        provenance: None,
+                    })];
+                    #[cfg(feature = "provenance")]
+                    let classification = with_provenance(
+                        classification,
+                        ActionProvenance::new(tx_root.tx_hash, trace),
+                    );
+
+                    tx_root.insert(node, classification);
+                    continue
+                }
+            }
+
+            let mut classification = self
+                .process_classification(
+                    header.number,
+                    Some(&tx_root.head),
+                    &tx_root.data_store,
+                    tx_idx as u64,
+                    trace.trace_idx,
+                    trace.clone(),
+                    tx_trace,
+                    &mut further_classification_requests,
+                    &mut pool_updates,
+                )
+                .await?;
+            if !multicalls.is_empty() {
+                multicalls.attribute_to_sender(trace, &mut classification);
+            }
+            #[cfg(feature = "provenance")]
+            let classification =
+                with_provenance(classification, ActionProvenance::new(tx_root.tx_hash, trace));
+
+            tx_root.total_msg_value_transfers.extend(
+                classification
+                    .iter()
+                    .filter_map(|s| s.get_msg_value_not_eth_transfer()),
+            );
+
+            tx_root.insert(node, classification);
+        }
+
+        // Here we reverse the requests to ensure that we always classify the most
+        // nested action & its children first. This is to prevent the
+        // case where we classify a parent action where its children also require
+        // further classification.
+        let tx_classification_requests = if !further_classification_requests.is_empty() {
+            further_classification_requests.reverse();
+            Some((tx_idx, further_classification_requests))
+        } else {
+            None
+        };
+        Ok(Some(TxTreeResult {
+            root: tx_root,
+            further_classification_requests: tx_classification_requests,
+            pool_updates,
+        }))
     }
 
     async fn process_classification(
@@ -281,7 +543,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         full_trace: &[TransactionTraceWithLogs],
         further_classification_requests: &mut Vec<MultiFrameRequest>,
         pool_updates: &mut Vec<DexPriceMsg>,
-    ) -> Vec<Action> {
+    ) -> Result<Vec<Action>, ClassifierError> {
         let (update, classification) = self
             .classify_node(
                 block_number,
@@ -292,7 +554,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 full_trace,
                 trace_index,
             )
-            .await;
+            .await?;
 
         // Here we are marking more complex actions that require data
         // that can only be retrieved by classifying it's action and
@@ -303,18 +565,18 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 .filter_map(|action| action.multi_frame_classification()),
         );
 
-        update.into_iter().for_each(|update| {
+        for update in update {
             match update {
                 pool @ DexPriceMsg::DiscoveredPool(_) => {
-                    self.pricing_update_sender.send(pool).unwrap();
+                    self.send_pricing_update(pool)?;
                 }
                 rest => {
                     pool_updates.push(rest);
                 }
             };
-        });
+        }
 
-        classification
+        Ok(classification)
     }
 
     async fn classify_node(
@@ -326,11 +588,11 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         trace: TransactionTraceWithLogs,
         full_trace: &[TransactionTraceWithLogs],
         trace_index: u64,
-    ) -> (Vec<DexPriceMsg>, Vec<Action>) {
+    ) -> Result<(Vec<DexPriceMsg>, Vec<Action>), ClassifierError> {
         if trace.trace.error.is_some() {
-            return (vec![], vec![Action::Revert])
+            return Ok((vec![], vec![Action::Revert]))
         }
-        let (pricing, base_action) = match trace.action_type() {
+        match trace.action_type() {
             TraceAction::Call(_) => {
                 self.classify_call(block, tx_idx, trace.clone(), full_trace, trace_index)
                     .await
@@ -346,13 +608,12 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 )
                 .await
             }
-            TraceAction::Selfdestruct(sd) => {
-                (vec![], vec![Action::SelfDestruct(SelfdestructWithIndex::new(trace_index, *sd))])
-            }
-            TraceAction::Reward(_) => (vec![], vec![Action::Unclassified(trace.clone())]),
-        };
-
-        (pricing, base_action)
+            TraceAction::Selfdestruct(sd) => Ok((
+                vec![],
+                vec![Action::SelfDestruct(SelfdestructWithIndex::new(trace_index, *sd))],
+            )),
+            TraceAction::Reward(_) => Ok((vec![], vec![Action::Unclassified(trace.clone())])),
+        }
     }
 
     async fn classify_call(
@@ -362,10 +623,13 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         trace: TransactionTraceWithLogs,
         full_trace: &[TransactionTraceWithLogs],
         trace_index: u64,
-    ) -> (Vec<DexPriceMsg>, Vec<Action>) {
+    ) -> Result<(Vec<DexPriceMsg>, Vec<Action>), ClassifierError> {
         if trace.is_static_call() {
-            return (vec![], vec![Action::Unclassified(trace)])
+            return Ok((vec![], vec![Action::Unclassified(trace)]))
         }
+
+        update_curve_pool_params(self.libmdbx, block, &trace.logs).await;
+
         let mut call_info = trace.get_callframe_info();
 
         // Add logs of delegated calls to the root trace, only if the delegated call is
@@ -386,40 +650,107 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             }
         }
 
-        if let Some(results) =
+        if let Some(mut results) =
             ProtocolClassifier::default().dispatch(call_info, self.libmdbx, block, tx_idx)
         {
+            self.set_swap_fee_tier(&mut results.1);
             if results.1.is_new_pool() {
                 let Action::NewPool(p) = &results.1 else { unreachable!() };
-                self.insert_new_pool(block, p).await;
+                self.insert_new_pool(block, tx_idx, p).await;
             } else if results.1.is_pool_config_update() {
                 let Action::PoolConfigUpdate(p) = &results.1 else { unreachable!() };
-                if self
+                if let Err(error) = self
                     .libmdbx
                     .insert_pool(block, p.pool_address, p.tokens.as_slice(), None, p.protocol)
                     .await
-                    .is_err()
                 {
-                    error!(pool=?p.pool_address,"failed to update pool config");
+                    let error = ClassifierError::PoolInsertion {
+                        pool: p.pool_address,
+                        protocol: p.protocol,
+                        error,
+                    };
+                    self.record_failure(block, tx_idx, &error, false).await;
                 }
             }
 
-            (vec![results.0], vec![results.1])
+            // the orders filled by a batch are kept next to it
+            let order_fills = try_decode_order_fills(&results.1, &trace.logs);
+            Ok((vec![results.0], std::iter::once(results.1).chain(order_fills).collect()))
+        } else if let Some(vault_action) = self
+            .classify_vault_action(trace_index, &trace, full_trace, block)
+            .await
+        {
+            Ok((vec![], vec![vault_action]))
+        } else if let Some(actions) = try_decode_weth_action(trace_index, &trace, self.libmdbx) {
+            let pricing = actions
+                .iter()
+                .filter(|action| action.is_transfer())
+                .map(|action| {
+                    DexPriceMsg::Update(PoolUpdate {
+                        block,
+                        tx_idx,
+                        logs: vec![],
+                        action: action.clone(),
+                    })
+                })
+                .collect();
+
+            Ok((pricing, actions))
+        } else if let Some(approvals) =
+            try_decode_approvals(trace_index, &trace, self.libmdbx, &self.decimal_resolver, block)
+                .await
+        {
+            Ok((vec![], approvals))
         } else if let Some(transfer) = self
             .classify_transfer(tx_idx, trace_index, &trace, block)
             .await
         {
-            return transfer
+            Ok(transfer)
         } else {
-            return (
+            Ok((
                 vec![],
                 vec![self
                     .classify_eth_transfer(&trace, trace_index)
+                    .or_else(|| self.classify_selector_call(&trace, trace_index))
                     .unwrap_or(Action::Unclassified(trace))],
-            )
+            ))
         }
     }
 
+    /// ERC-4626 vaults are classified generically, the vault doesn't have to
+    /// be a known protocol
+    async fn classify_vault_action(
+        &self,
+        trace_index: u64,
+        trace: &TransactionTraceWithLogs,
+        full_trace: &[TransactionTraceWithLogs],
+        block: u64,
+    ) -> Option<Action> {
+        if trace.is_delegate_call() || !is_vault_call(&trace.get_calldata()) {
+            return None
+        }
+
+        // proxied vaults emit their events from the implementation frame
+        let mut delegated_traces = Vec::new();
+        collect_delegated_traces(full_trace, &trace.trace.trace_address, &mut delegated_traces);
+        let delegate_logs = delegated_traces
+            .into_iter()
+            .filter(|delegated| {
+                delegated.is_delegate_call() && delegated.get_from_addr() == trace.get_to_address()
+            })
+            .flat_map(|delegated| &delegated.logs);
+
+        try_decode_vault_action(
+            trace_index,
+            trace,
+            trace.logs.iter().chain(delegate_logs),
+            self.libmdbx,
+            &self.decimal_resolver,
+            block,
+        )
+        .await
+    }
+
     async fn classify_transfer(
         &self,
         tx_idx: u64,
@@ -438,7 +769,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             trace.get_from_addr(),
             trace.get_to_address(),
             self.libmdbx,
-            &self.provider,
+            &self.decimal_resolver,
             block,
             trace.get_msg_value(),
         )
@@ -472,10 +803,12 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 if trace.get_msg_value() != U256::ZERO {
                     result.push(Action::EthTransfer(NormalizedEthTransfer {
                         coinbase_transfer: false,
-                        trace_index:       trace_idx,
-                        to:                trace.get_to_address(),
-                        from:              trace.get_from_addr(),
-                        value:             trace.get_msg_value(),
+                        trace_index: trace_idx,
+                        to: trace.get_to_address(),
+                        from: trace.get_from_addr(),
+                        value: trace.get_msg_value(),
+                        #[cfg(feature = "provenance")]
+                        provenance: None,
                     }));
                 }
 
@@ -493,11 +826,11 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             Err(_) => {
                 for log in &trace.logs {
                     if let Some((addr, from, to, amount)) = decode_transfer(log) {
-                        if self.libmdbx.try_fetch_token_info(addr).is_err() {
-                            load_missing_token_info(&self.provider, self.libmdbx, block, addr).await
-                        }
-
-                        let token_info = self.libmdbx.try_fetch_token_info(addr).ok()?;
+                        let token_info = self
+                            .decimal_resolver
+                            .token_info(self.libmdbx, block, addr)
+                            .await
+                            .ok()?;
                         let amount = amount.to_scaled_rational(token_info.decimals);
                         let transfer = NormalizedTransfer {
                             amount,
@@ -507,6 +840,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                             fee: Rational::ZERO,
                             trace_index: trace_idx,
                             msg_value: trace.get_msg_value(),
+                            #[cfg(feature = "provenance")]
+                            provenance: None,
                         };
 
                         return Some((
@@ -537,24 +872,65 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 value: trace.get_msg_value(),
                 trace_index,
                 coinbase_transfer: false,
+                #[cfg(feature = "provenance")]
+                provenance: None,
             })
         })
     }
 
+    /// Generic action for a call to an unknown contract, from the signature of
+    /// its function selector. Delegate calls are skipped as they share the
+    /// calldata of the call into the proxy
+    fn classify_selector_call(
+        &self,
+        trace: &TransactionTraceWithLogs,
+        trace_index: u64,
+    ) -> Option<Action> {
+        if !self.selector_fallback || trace.is_delegate_call() {
+            return None
+        }
+
+        let target = trace.get_to_address();
+        if self.libmdbx.get_protocol_details(target).is_ok() {
+            return None
+        }
+
+        let calldata = trace.get_calldata();
+        let selector = Selector::try_from(calldata.get(..4)?).ok()?;
+        let function = self
+            .libmdbx
+            .try_fetch_function_selector(selector)
+            .ok()
+            .flatten()?;
+
+        Some(Action::Custom(
+            NormalizedSelectorCall {
+                trace_index,
+                from: trace.get_from_addr(),
+                target,
+                selector,
+                signature: function.signature,
+                kind: function.kind,
+                msg_value: trace.get_msg_value(),
+            }
+            .into(),
+        ))
+    }
+
     async fn classify_create(
         &self,
         block: u64,
         root_head: Option<&Node>,
         node_data_store: &NodeData<Action>,
-        _tx_idx: u64,
+        tx_idx: u64,
         trace: TransactionTraceWithLogs,
         trace_index: u64,
-    ) -> (Vec<DexPriceMsg>, Vec<Action>) {
+    ) -> Result<(Vec<DexPriceMsg>, Vec<Action>), ClassifierError> {
         let created_addr = trace.get_create_output();
 
         if created_addr == Address::ZERO {
             tracing::error!(target: "brontes_classifier::discovery", "created address is zero address");
-            return (vec![], vec![Action::Unclassified(trace)])
+            return Ok((vec![], vec![Action::Unclassified(trace)]))
         }
 
         // get the immediate parent node of this create action so that we can decode the
@@ -588,30 +964,57 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                     "No root head found for trace index: {}",
                     trace_index
                 );
-                return (vec![], vec![Action::Unclassified(trace)])
+                return Ok((vec![], vec![Action::Unclassified(trace)]))
             }
         };
 
+        let registry = self.libmdbx.fetch_protocol_registry().unwrap_or_default();
         let search_data = all_nodes
             .iter()
             .filter_map(|node| node_data_store.get_ref(node.data))
             .flatten()
-            .filter_map(|node_data| Some((node_data.get_to_address(), node_data.get_calldata()?)))
+            .filter_map(|node_data| {
+                // factories deployed at a different address than on mainnet, or
+                // registered as another deployment of a protocol, are dispatched
+                // as the factory the discovery classifiers know
+                let factory = canonical_factory(&registry, node_data.get_to_address());
+                Some((factory, node_data.get_calldata()?))
+            })
             .collect::<Vec<_>>();
 
-        if search_data.is_empty() {
+        let mut pools = if search_data.is_empty() {
             trace!(
                 target: "brontes_classifier::discovery",
                 "No parent calldata found for created address: {}",
                 created_addr
             );
-            return (vec![], vec![Action::Unclassified(trace)])
-        }
-
-        join_all(
+            vec![]
+        } else {
             DiscoveryClassifier::default()
                 .dispatch(self.provider.clone(), search_data, created_addr, trace_index)
                 .await
+        };
+
+        if pools.is_empty() {
+            pools.extend(
+                discover_minimal_proxy(
+                    self.libmdbx,
+                    &self.provider,
+                    &trace,
+                    created_addr,
+                    trace_index,
+                    block,
+                )
+                .await,
+            );
+        }
+
+        if pools.is_empty() {
+            return Ok((vec![], vec![Action::Unclassified(trace)]))
+        }
+
+        let pools = join_all(
+            pools
                 .into_iter()
                 // insert the pool returning if it has token values.
                 .map(|pool| async {
@@ -623,34 +1026,92 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                         pool.pool_address,
                         pool.protocol,
                     );
-                    self.insert_new_pool(block, &pool).await;
-                    Some((pool.clone().try_into().ok()?, pool))
+                    self.insert_new_pool(block, tx_idx, &pool).await;
+                    pool.clone().try_into().ok().map(|config| (config, pool))
                 }),
         )
-        .await
-        .into_iter()
-        .flatten()
-        .map(|(config, output)| (DexPriceMsg::DiscoveredPool(config), Action::NewPool(output)))
-        .unzip()
+        .await;
+
+        Ok(pools
+            .into_iter()
+            .flatten()
+            .map(|(config, output)| (DexPriceMsg::DiscoveredPool(config), Action::NewPool(output)))
+            .unzip())
     }
 
-    async fn insert_new_pool(&self, block: u64, pool: &NormalizedNewPool) {
-        if self
+    /// Stores the discovered pool. A pool that can't be stored is recorded as
+    /// a classification failure, the tx that created it is still classified.
+    async fn insert_new_pool(&self, block: u64, tx_idx: u64, pool: &NormalizedNewPool) {
+        let mut inserted = self
             .libmdbx
             .insert_pool(block, pool.pool_address, &pool.tokens, None, pool.protocol)
-            .await
-            .is_err()
-        {
-            error!(pool=?pool.pool_address,"failed to insert discovered pool into libmdbx");
-        } else {
-            trace!("Inserting new {} pool: Address:{}", pool.protocol, pool.pool_address);
+            .await;
+        trace!("Inserting new {} pool: Address:{}", pool.protocol, pool.pool_address);
+
+        if let (Ok(()), Some(fee)) = (&inserted, pool.fee_tier) {
+            inserted = self
+                .libmdbx
+                .insert_pool_fee_tier(pool.pool_address, PoolFeeTier::new(fee))
+                .await;
+        }
+
+        if let Err(error) = inserted {
+            let error = ClassifierError::PoolInsertion {
+                pool: pool.pool_address,
+                protocol: pool.protocol,
+                error,
+            };
+            self.record_failure(block, tx_idx, &error, false).await;
+        }
+    }
+
+    /// Notes the fee tier of the pool on swaps through pools that were
+    /// discovered with one
+    fn set_swap_fee_tier(&self, action: &mut Action) {
+        let swap = match action {
+            Action::Swap(swap) => swap,
+            Action::SwapWithFee(swap) => &mut swap.swap,
+            _ => return,
+        };
+
+        swap.fee_tier = self
+            .libmdbx
+            .try_fetch_pool_fee_tier(swap.pool)
+            .ok()
+            .flatten()
+            .map(|tier| tier.fee)
+            .or_else(|| {
+                // the fee of curve pools changes over time, so it is taken from the last
+                // fee change of the pool
+                if !swap.protocol.is_curve_stableswap() {
+                    return None
+                }
+
+                self.libmdbx
+                    .try_fetch_curve_pool_params(swap.pool)
+                    .ok()
+                    .flatten()?
+                    .fee_tier()
+            });
+    }
+
+    /// Stores the transfer taxes of the tokens that took a fee on a swap in the
+    /// block, so that inspectors can account for them
+    async fn record_token_taxes(&self, block: u64, tree: &BlockTree<Action>) {
+        let taxes = TokenTax::from_tree(block, tree);
+        if taxes.is_empty() {
+            return
+        }
+
+        if let Err(e) = self.libmdbx.write_token_taxes(taxes).await {
+            error!(%block, err=%e, "failed to insert token taxes into libmdbx");
         }
     }
 
     pub fn close(&self) {
-        self.pricing_update_sender
-            .send(DexPriceMsg::Closed)
-            .unwrap();
+        if self.send_pricing_update(DexPriceMsg::Closed).is_err() {
+            tracing::warn!("dex pricing was already closed");
+        }
     }
 
     /// This function is used to finalize the classification of complex actions
@@ -663,10 +1124,49 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             Option<(usize, Vec<MultiCallFrameClassification<Action>>)>,
         >,
     ) {
-        tree.collect_and_classify(&further_classification_requests)
+        tree.collect_and_classify(&further_classification_requests);
+        tree.tx_roots
+            .iter_mut()
+            .for_each(match_repayments_outside_loan);
     }
 }
 
+/// Splits the classified txs of a block from the ones whose classification
+/// failed, along with their index in the block. A fatal error fails the whole
+/// block.
+fn isolate_failed_txs<R>(
+    results: Vec<Result<Option<R>, ClassifierError>>,
+) -> Result<(Vec<R>, Vec<(u64, ClassifierError)>), ClassifierError> {
+    let mut tx_roots = Vec::with_capacity(results.len());
+    let mut failed_txs = Vec::new();
+    for (tx_idx, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(tx_root) => tx_roots.extend(tx_root),
+            Err(e) if e.is_fatal() => return Err(e),
+            Err(e) => failed_txs.push((tx_idx as u64, e)),
+        }
+    }
+
+    Ok((tx_roots, failed_txs))
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    panic
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| panic.downcast_ref::<&str>().map(|s| (*s).to_string()))
+        .unwrap_or_default()
+}
+
+/// Records the frame the actions were classified from on each of them
+#[cfg(feature = "provenance")]
+fn with_provenance(mut actions: Vec<Action>, provenance: ActionProvenance) -> Vec<Action> {
+    actions
+        .iter_mut()
+        .for_each(|action| action.set_provenance(provenance.clone()));
+    actions
+}
+
 fn collect_delegated_traces<'a>(
     traces: &'a [TransactionTraceWithLogs],
     parent_trace_address: &[usize],
@@ -688,3 +1188,34 @@ pub struct TxTreeResult {
     pub further_classification_requests: Option<(usize, Vec<MultiFrameRequest>)>,
     pub root: Root<Action>,
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+
+    fn panicked(tx_idx: usize) -> ClassifierError {
+        ClassifierError::TxPanicked { tx_idx, tx_hash: B256::ZERO, message: String::new() }
+    }
+
+    #[test]
+    fn test_failed_txs_are_isolated() {
+        let results = vec![Ok(Some(0)), Err(panicked(1)), Ok(None), Ok(Some(3)), Err(panicked(4))];
+
+        let (tx_roots, failed_txs) = isolate_failed_txs(results).unwrap();
+        assert_eq!(tx_roots, vec![0, 3]);
+        assert_eq!(failed_txs.iter().map(|(tx_idx, _)| *tx_idx).collect_vec(), vec![1, 4]);
+        assert!(failed_txs
+            .iter()
+            .all(|(_, error)| error.kind() == "tx_panicked"));
+    }
+
+    #[test]
+    fn test_fatal_error_fails_the_block() {
+        let results: Vec<Result<Option<u64>, _>> =
+            vec![Ok(Some(0)), Err(panicked(1)), Err(ClassifierError::PricingChannelClosed)];
+
+        assert!(matches!(isolate_failed_txs(results), Err(ClassifierError::PricingChannelClosed)));
+    }
+}
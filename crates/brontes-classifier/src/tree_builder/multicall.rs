@@ -0,0 +1,353 @@
+use alloy_primitives::{address, Address, Selector};
+use alloy_sol_types::{sol, SolCall};
+use brontes_types::{
+    normalized_actions::{Action, NormalizedSwap},
+    structured_trace::{TraceActions, TransactionTraceWithLogs},
+};
+use reth_rpc_types::trace::parity::Action as TraceAction;
+
+sol! {
+    interface IMulticall {
+        struct Call { address target; bytes callData; }
+        struct Call3 { address target; bool allowFailure; bytes callData; }
+        struct Call3Value { address target; bool allowFailure; uint256 value; bytes callData; }
+        struct CallResult { bool success; bytes returnData; }
+
+        function aggregate(Call[] calls) returns (uint256 blockNumber, bytes[] returnData);
+        function tryAggregate(bool requireSuccess, Call[] calls)
+            returns (CallResult[] returnData);
+        function blockAndAggregate(Call[] calls)
+            returns (uint256 blockNumber, bytes32 blockHash, CallResult[] returnData);
+        function tryBlockAndAggregate(bool requireSuccess, Call[] calls)
+            returns (uint256 blockNumber, bytes32 blockHash, CallResult[] returnData);
+        function aggregate3(Call3[] calls) returns (CallResult[] returnData);
+        function aggregate3Value(Call3Value[] calls) returns (CallResult[] returnData);
+    }
+
+    interface ISelfMulticall {
+        function multicall(bytes[] data) returns (bytes[] results);
+    }
+
+    interface ISelfMulticallWithDeadline {
+        function multicall(uint256 deadline, bytes[] data) returns (bytes[] results);
+    }
+
+    interface ISelfMulticallWithBlockHash {
+        function multicall(bytes32 previousBlockhash, bytes[] data) returns (bytes[] results);
+    }
+}
+
+/// Mainnet deployments of Multicall, Multicall2 and Multicall3. Other
+/// contracts exposing the same selectors, e.g. searcher contracts, hold the
+/// funds they move and act on their own behalf.
+const MULTICALL_DEPLOYMENTS: [Address; 3] = [
+    address!("eefBa1e63905eF1D7ACbA5a8513c70307C1cE441"),
+    address!("5BA1e12693Dc8F9c48aAD8770482f4739bEeD696"),
+    address!("cA11bde05977b3631167028862bE2a173976CA11"),
+];
+
+/// Multicall, Multicall2 and Multicall3 style aggregators, which make the
+/// calls themselves
+const FORWARDING_SELECTORS: [Selector; 6] = [
+    IMulticall::aggregateCall::SELECTOR,
+    IMulticall::tryAggregateCall::SELECTOR,
+    IMulticall::blockAndAggregateCall::SELECTOR,
+    IMulticall::tryBlockAndAggregateCall::SELECTOR,
+    IMulticall::aggregate3Call::SELECTOR,
+    IMulticall::aggregate3ValueCall::SELECTOR,
+];
+
+/// Routers that delegate each call to themselves, e.g. the uniswap routers
+const SELF_DELEGATING_SELECTORS: [Selector; 3] = [
+    ISelfMulticall::multicallCall::SELECTOR,
+    ISelfMulticallWithDeadline::multicallCall::SELECTOR,
+    ISelfMulticallWithBlockHash::multicallCall::SELECTOR,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MulticallKind {
+    /// The multicall contract calls the targets itself, so the wrapped calls
+    /// act on behalf of the sender. Tokens sent to the contract stay in it
+    /// though.
+    Forwarding,
+    /// Each call is delegated back into the contract and runs with the
+    /// sender as msg.sender. Only the actions of the delegated frames act on
+    /// behalf of the sender, calls the contract makes from there are its own
+    SelfDelegating,
+}
+
+#[derive(Debug, Clone)]
+struct MulticallFrame {
+    trace_address: Vec<usize>,
+    contract:      Address,
+    sender:        Address,
+    kind:          MulticallKind,
+}
+
+/// The multicall frames of a transaction. The wrapped calls classify on their
+/// own, but the multicall contract shows up in their actions in place of the
+/// address that sent the multicall. The actions are attributed back to the
+/// sender once classified.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MulticallFrames(Vec<MulticallFrame>);
+
+impl MulticallFrames {
+    pub(crate) fn new(traces: &[TransactionTraceWithLogs]) -> Self {
+        Self(
+            traces
+                .iter()
+                .filter(|trace| trace.trace.error.is_none() && !trace.is_static_call())
+                .filter_map(|trace| {
+                    let TraceAction::Call(call) = &trace.trace.action else { return None };
+                    let selector = Selector::try_from(call.input.get(..4)?).ok()?;
+
+                    let kind = if FORWARDING_SELECTORS.contains(&selector)
+                        && !trace.is_delegate_call()
+                        && MULTICALL_DEPLOYMENTS.contains(&call.to)
+                    {
+                        MulticallKind::Forwarding
+                    } else if SELF_DELEGATING_SELECTORS.contains(&selector) {
+                        MulticallKind::SelfDelegating
+                    } else {
+                        return None
+                    };
+
+                    // a delegated multicall runs in the context of its caller
+                    let contract = if trace.is_delegate_call() { call.from } else { call.to };
+
+                    Some(MulticallFrame {
+                        trace_address: trace.trace.trace_address.clone(),
+                        contract,
+                        sender: trace.msg_sender,
+                        kind,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Replaces the multicall contract in the actions classified from the
+    /// trace with the address that sent the multicall. Nested multicalls are
+    /// attributed to the sender of the outermost one.
+    pub(crate) fn attribute_to_sender(
+        &self,
+        trace: &TransactionTraceWithLogs,
+        actions: &mut [Action],
+    ) {
+        let trace_address = &trace.trace.trace_address;
+        let TraceAction::Call(call) = &trace.trace.action else { return };
+
+        for frame in self.0.iter().rev() {
+            let attributed = match frame.kind {
+                MulticallKind::Forwarding => {
+                    trace_address.len() > frame.trace_address.len()
+                        && trace_address.starts_with(&frame.trace_address)
+                }
+                MulticallKind::SelfDelegating => {
+                    trace_address.len() == frame.trace_address.len() + 1
+                        && trace_address.starts_with(&frame.trace_address)
+                        && trace.is_delegate_call()
+                        && call.from == frame.contract
+                }
+            };
+
+            if attributed {
+                let receivers = frame.kind == MulticallKind::SelfDelegating;
+                actions.iter_mut().for_each(|action| {
+                    replace_address(action, frame.contract, frame.sender, receivers)
+                });
+            }
+        }
+    }
+}
+
+/// Replaces the address in every field that names a participant of the
+/// action. The fields receiving tokens are only replaced if `receivers` is
+/// set. Pools and tokens are never the multicall contract, custom actions are
+/// left as they are.
+fn replace_address(action: &mut Action, old: Address, new: Address, receivers: bool) {
+    let replace = |address: &mut Address| {
+        if *address == old {
+            *address = new;
+        }
+    };
+    let replace_receiver = |address: &mut Address| {
+        if receivers {
+            replace(address);
+        }
+    };
+    let replace_swap = |swap: &mut NormalizedSwap| {
+        replace(&mut swap.from);
+        replace_receiver(&mut swap.recipient);
+    };
+
+    match action {
+        Action::Swap(swap) => replace_swap(swap),
+        Action::SwapWithFee(swap) => replace_swap(&mut swap.swap),
+        Action::Transfer(transfer) => {
+            replace(&mut transfer.from);
+            replace_receiver(&mut transfer.to);
+        }
+        Action::EthTransfer(transfer) => {
+            replace(&mut transfer.from);
+            replace_receiver(&mut transfer.to);
+        }
+        Action::Mint(mint) => {
+            replace(&mut mint.from);
+            replace_receiver(&mut mint.recipient);
+        }
+        Action::Burn(burn) => {
+            replace(&mut burn.from);
+            replace_receiver(&mut burn.recipient);
+        }
+        Action::Collect(collect) => {
+            replace(&mut collect.from);
+            replace_receiver(&mut collect.recipient);
+        }
+        Action::FlashLoan(flash_loan) => {
+            replace(&mut flash_loan.from);
+            replace_receiver(&mut flash_loan.receiver_contract);
+        }
+        Action::Liquidation(liquidation) => replace(&mut liquidation.liquidator),
+        Action::Aggregator(aggregator) => {
+            replace(&mut aggregator.from);
+            replace_receiver(&mut aggregator.recipient);
+        }
+        Action::VaultDeposit(deposit) => {
+            replace(&mut deposit.from);
+            replace_receiver(&mut deposit.owner);
+        }
+        Action::VaultWithdraw(withdraw) => {
+            replace(&mut withdraw.from);
+            replace_receiver(&mut withdraw.receiver);
+            replace(&mut withdraw.owner);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Bytes;
+    use brontes_types::normalized_actions::NormalizedTransfer;
+    use reth_rpc_types::trace::parity::{CallAction, CallType, TransactionTrace};
+
+    use super::*;
+
+    fn call(
+        from: Address,
+        to: Address,
+        msg_sender: Address,
+        call_type: CallType,
+        input: Vec<u8>,
+        trace_address: Vec<usize>,
+    ) -> TransactionTraceWithLogs {
+        TransactionTraceWithLogs {
+            trace: TransactionTrace {
+                action: TraceAction::Call(CallAction {
+                    from,
+                    to,
+                    input: Bytes::from(input),
+                    call_type,
+                    gas: Default::default(),
+                    value: Default::default(),
+                }),
+                error: None,
+                result: None,
+                subtraces: 0,
+                trace_address,
+            },
+            logs: vec![],
+            #[cfg(feature = "provenance")]
+            tx_log_indices: vec![],
+            msg_sender,
+            trace_idx: 0,
+            decoded_data: None,
+        }
+    }
+
+    fn swap(from: Address, pool: Address) -> Vec<Action> {
+        vec![Action::Swap(NormalizedSwap { from, recipient: from, pool, ..Default::default() })]
+    }
+
+    fn aggregate3(from: Address, multicall: Address) -> TransactionTraceWithLogs {
+        call(
+            from,
+            multicall,
+            from,
+            CallType::Call,
+            IMulticall::aggregate3Call::SELECTOR.to_vec(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn attributes_forwarded_calls_to_the_sender() {
+        let [eoa, pool] = [1, 3].map(Address::with_last_byte);
+        let multicall = MULTICALL_DEPLOYMENTS[2];
+        let child = call(multicall, pool, multicall, CallType::Call, vec![], vec![0]);
+
+        let frames = MulticallFrames::new(&[aggregate3(eoa, multicall), child.clone()]);
+        let mut actions = swap(multicall, pool);
+        frames.attribute_to_sender(&child, &mut actions);
+
+        // the swap output is sent to the multicall and stays there
+        let swap = actions[0].force_swap_ref();
+        assert_eq!((swap.from, swap.recipient, swap.pool), (eoa, multicall, pool));
+    }
+
+    #[test]
+    fn keeps_transfers_to_the_multicall() {
+        let [eoa, token] = [1, 4].map(Address::with_last_byte);
+        let multicall = MULTICALL_DEPLOYMENTS[2];
+        let child = call(multicall, token, multicall, CallType::Call, vec![], vec![0]);
+
+        let frames = MulticallFrames::new(&[aggregate3(eoa, multicall), child.clone()]);
+        let mut actions = vec![Action::Transfer(NormalizedTransfer {
+            from: eoa,
+            to: multicall,
+            ..Default::default()
+        })];
+        frames.attribute_to_sender(&child, &mut actions);
+
+        let Action::Transfer(transfer) = &actions[0] else { unreachable!() };
+        assert_eq!((transfer.from, transfer.to), (eoa, multicall));
+    }
+
+    #[test]
+    fn ignores_unknown_contracts_with_multicall_selectors() {
+        let [eoa, searcher_contract] = [1, 2].map(Address::with_last_byte);
+
+        assert!(MulticallFrames::new(&[aggregate3(eoa, searcher_contract)]).is_empty());
+    }
+
+    #[test]
+    fn attributes_only_the_delegated_frames_of_a_router() {
+        let [eoa, router, pool] = [1, 2, 3].map(Address::with_last_byte);
+        let root = call(
+            eoa,
+            router,
+            eoa,
+            CallType::Call,
+            ISelfMulticall::multicallCall::SELECTOR.to_vec(),
+            vec![],
+        );
+        let delegated = call(router, router, eoa, CallType::DelegateCall, vec![], vec![0]);
+        let pool_call = call(router, pool, router, CallType::Call, vec![], vec![0, 0]);
+
+        let frames = MulticallFrames::new(&[root, delegated.clone(), pool_call.clone()]);
+
+        let mut actions = swap(router, pool);
+        frames.attribute_to_sender(&delegated, &mut actions);
+        assert_eq!(actions[0].force_swap_ref().from, eoa);
+
+        // the router is the one trading with the pool
+        let mut actions = swap(router, pool);
+        frames.attribute_to_sender(&pool_call, &mut actions);
+        assert_eq!(actions[0].force_swap_ref().from, router);
+    }
+}
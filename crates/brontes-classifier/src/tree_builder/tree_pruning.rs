@@ -1,11 +1,54 @@
+use std::{fmt::Debug, sync::Arc};
+
 use brontes_types::{
     normalized_actions::{Action, NormalizedSwapWithFee},
     tree::BlockTree,
     unzip_either::IterExt,
-    TreeCollector, TreeSearchBuilder,
+    SearchMemo, TreeCollector, TreeSearchBuilder,
 };
 use malachite::{num::basic::traits::Zero, Rational};
 
+/// A pass over the block tree that cleans up the classified actions before
+/// the multi frame classification runs. Passes are run in order, so a pass
+/// can rely on the adjustments of the ones before it.
+pub trait TreePass: Debug + Send + Sync {
+    /// Used for logging which pass is running
+    fn name(&self) -> &'static str;
+
+    fn run(&self, tree: &mut BlockTree<Action>);
+}
+
+/// The passes every tree goes through unless configured otherwise
+pub fn default_tree_passes() -> Vec<Arc<dyn TreePass>> {
+    vec![Arc::new(AccountForTaxTokens), Arc::new(RemovePossibleTransferDoubleCounts)]
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccountForTaxTokens;
+
+impl TreePass for AccountForTaxTokens {
+    fn name(&self) -> &'static str {
+        "account_for_tax_tokens"
+    }
+
+    fn run(&self, tree: &mut BlockTree<Action>) {
+        account_for_tax_tokens(tree)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemovePossibleTransferDoubleCounts;
+
+impl TreePass for RemovePossibleTransferDoubleCounts {
+    fn name(&self) -> &'static str {
+        "remove_possible_transfer_double_counts"
+    }
+
+    fn run(&self, tree: &mut BlockTree<Action>) {
+        remove_possible_transfer_double_counts(tree)
+    }
+}
+
 /// When a tax token takes a fee, They will swap from there token to a more
 /// stable token like eth before taking the fee. However this creates an
 /// accounting inaccuracy as we will register this fee swap as
@@ -121,7 +164,6 @@ pub(crate) fn account_for_tax_tokens(tree: &mut BlockTree<Action>) {
 }
 
 pub(crate) fn remove_possible_transfer_double_counts(tree: &mut BlockTree<Action>) {
-    tracing::debug!("remove double transfer counts");
     tree.modify_node_if_contains_childs(
         TreeSearchBuilder::default().with_action(Action::is_transfer),
         |node, data| {
@@ -131,6 +173,7 @@ pub(crate) fn remove_possible_transfer_double_counts(tree: &mut BlockTree<Action
                 &TreeSearchBuilder::default().with_action(Action::is_transfer),
                 &|node| node.node.clone(),
                 data,
+                &mut SearchMemo::default(),
             );
 
             let this = data
@@ -2,16 +2,22 @@ use alloy_primitives::{Address, FixedBytes, Log, B256, U256};
 use hex_literal::hex;
 use reth_rpc_types::trace::parity::Action;
 
-pub(crate) fn get_coinbase_transfer(builder: Address, action: &Action) -> Option<u128> {
-    match action {
-        Action::Call(action) => {
-            if action.to == builder && !action.value.is_zero() {
-                return Some(action.value.to());
-            }
-            None
-        }
-        _ => None,
-    }
+/// Returns the address of the builder the action paid and the amount it paid.
+/// The payees are the fee recipient of the block and the builder's payout
+/// addresses. Besides direct calls, this follows the value of payout
+/// contracts that forward their balance to the builder by self destructing,
+/// which some searchers route their bribes through.
+pub(crate) fn get_coinbase_transfer(
+    payees: &[Address],
+    action: &Action,
+) -> Option<(Address, u128)> {
+    let (to, value) = match action {
+        Action::Call(action) => (action.to, action.value),
+        Action::Selfdestruct(action) => (action.refund_address, action.balance),
+        _ => return None,
+    };
+
+    (payees.contains(&to) && !value.is_zero()).then(|| (to, value.to()))
 }
 
 const TRANSFER_TOPIC: B256 =
@@ -31,3 +37,39 @@ pub(crate) fn decode_transfer(log: &Log) -> Option<(Address, Address, Address, U
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use reth_rpc_types::trace::parity::{CallAction, CallType, SelfdestructAction};
+
+    use super::*;
+
+    #[test]
+    fn finds_payments_to_every_payee() {
+        let (coinbase, payout, other) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let payees = [coinbase, payout];
+
+        let call = |to, value: u64| {
+            Action::Call(CallAction {
+                from: other,
+                to,
+                value: U256::from(value),
+                gas: Default::default(),
+                input: Default::default(),
+                call_type: CallType::Call,
+            })
+        };
+        assert_eq!(get_coinbase_transfer(&payees, &call(coinbase, 10)), Some((coinbase, 10)));
+        assert_eq!(get_coinbase_transfer(&payees, &call(payout, 5)), Some((payout, 5)));
+        assert_eq!(get_coinbase_transfer(&payees, &call(coinbase, 0)), None);
+        assert_eq!(get_coinbase_transfer(&payees, &call(other, 10)), None);
+
+        let selfdestruct = Action::Selfdestruct(SelfdestructAction {
+            address:        other,
+            refund_address: payout,
+            balance:        U256::from(7),
+        });
+        assert_eq!(get_coinbase_transfer(&payees, &selfdestruct), Some((payout, 7)));
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use alloy_json_abi::{Function, JsonAbi};
+use alloy_primitives::{Address, Selector};
+use brontes_metrics::abi_cache::AbiCacheMetrics;
+use brontes_types::FastHashMap;
+use schnellru::{ByLength, LruMap};
+
+/// Number of contract abis kept in memory
+pub const DEFAULT_ABI_CACHE_SIZE: u32 = 10_000;
+
+static ABI_CACHE_METRICS: OnceLock<AbiCacheMetrics> = OnceLock::new();
+
+/// A contract abi with its functions indexed by selector, so that decoding a
+/// call doesn't have to scan every function of the abi
+#[derive(Debug)]
+pub struct IndexedAbi {
+    pub abi:   JsonAbi,
+    functions: FastHashMap<Selector, Function>,
+}
+
+impl IndexedAbi {
+    pub fn new(abi: JsonAbi) -> Self {
+        let functions = abi
+            .functions()
+            .map(|function| (function.selector(), function.clone()))
+            .collect();
+
+        Self { abi, functions }
+    }
+
+    pub fn function(&self, selector: &Selector) -> Option<&Function> {
+        self.functions.get(selector)
+    }
+}
+
+/// In memory lru cache of the parsed contract abis, keyed by contract address.
+/// Shared between the clones of the [`TraceParser`](super::parser::TraceParser)
+/// so that contracts called in every block are only fetched and indexed once.
+/// Contracts that aren't verified are cached as well, so that they aren't
+/// requested again until they are evicted.
+pub struct AbiCache {
+    abis:    Mutex<LruMap<Address, Option<Arc<IndexedAbi>>, ByLength>>,
+    metrics: &'static AbiCacheMetrics,
+}
+
+impl Default for AbiCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_ABI_CACHE_SIZE)
+    }
+}
+
+impl AbiCache {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            abis:    Mutex::new(LruMap::new(ByLength::new(capacity))),
+            metrics: ABI_CACHE_METRICS.get_or_init(AbiCacheMetrics::new),
+        }
+    }
+
+    /// The cached abi of the contract, `Some(None)` if the contract is known
+    /// to be unverified
+    pub fn get(&self, address: &Address) -> Option<Option<Arc<IndexedAbi>>> {
+        let abi = self.abis.lock().unwrap().get(address).cloned();
+        if abi.is_some() {
+            self.metrics.hit();
+        } else {
+            self.metrics.miss();
+        }
+
+        abi
+    }
+
+    /// Indexes the abi and caches it, evicting the least recently used abi if
+    /// the cache is full
+    pub fn insert(&self, address: Address, abi: JsonAbi) -> Arc<IndexedAbi> {
+        let abi = Arc::new(IndexedAbi::new(abi));
+
+        self.insert_entry(address, Some(abi.clone()));
+
+        abi
+    }
+
+    /// Caches that the contract has no verified abi
+    pub fn insert_unverified(&self, address: Address) {
+        self.insert_entry(address, None);
+    }
+
+    fn insert_entry(&self, address: Address, abi: Option<Arc<IndexedAbi>>) {
+        let mut abis = self.abis.lock().unwrap();
+        abis.insert(address, abi);
+        self.metrics.set_entries(abis.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi_cache_evicts_least_recently_used() {
+        let abi = || JsonAbi::parse(["function transfer(address to, uint256 amount)"]).unwrap();
+        let cache = AbiCache::new(2);
+        let [a, b, c] = [1, 2, 3].map(Address::with_last_byte);
+
+        let indexed = cache.insert(a, abi());
+        let transfer = indexed
+            .function(&Selector::from([0xa9, 0x05, 0x9c, 0xbb]))
+            .unwrap();
+        assert_eq!(transfer.name, "transfer");
+
+        cache.insert(b, abi());
+        // touch a so that b is the least recently used
+        assert!(cache.get(&a).is_some());
+        cache.insert(c, abi());
+
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+    }
+
+    #[test]
+    fn test_abi_cache_remembers_unverified_contracts() {
+        let cache = AbiCache::new(2);
+        let [a, b, c] = [1, 2, 3].map(Address::with_last_byte);
+
+        cache.insert_unverified(a);
+        assert!(matches!(cache.get(&a), Some(None)));
+        assert!(cache.get(&b).is_none());
+
+        // unverified contracts are evicted like any other entry, after which
+        // they are requested again
+        cache.insert_unverified(b);
+        cache.insert_unverified(c);
+        assert!(cache.get(&a).is_none());
+    }
+}
@@ -1,81 +1,78 @@
 use alloy_dyn_abi::*;
-use alloy_json_abi::JsonAbi;
+use alloy_primitives::Selector;
 use brontes_types::structured_trace::{DecodedCallData, DecodedParams};
 use reth_rpc_types::trace::parity::{Action, TraceOutput, TransactionTrace};
 
+use super::abi_cache::IndexedAbi;
 use crate::errors::TraceParseError;
 
 pub fn decode_input_with_abi(
-    abi: &JsonAbi,
+    abi: &IndexedAbi,
     trace: &TransactionTrace,
 ) -> Result<Option<DecodedCallData>, TraceParseError> {
     let Action::Call(ref action) = trace.action else {
         return Ok(None);
     };
+    let Some(function) = action
+        .input
+        .get(..4)
+        .and_then(|selector| abi.function(&Selector::from_slice(selector)))
+    else {
+        return Ok(None);
+    };
 
-    for functions in abi.functions.values() {
-        for function in functions {
-            if function.selector() == action.input[..4] {
-                // Resolve all inputs
-                let resolved_params: Vec<DynSolType> = function
-                    .inputs
-                    .iter()
-                    .filter_map(|param| param.resolve().ok())
-                    .collect();
+    // Resolve all inputs
+    let resolved_params: Vec<DynSolType> = function
+        .inputs
+        .iter()
+        .filter_map(|param| param.resolve().ok())
+        .collect();
 
-                let mut input_names = function
-                    .inputs
-                    .iter()
-                    .map(|f| f.name.clone())
-                    .collect::<Vec<_>>();
-                let input_params_type = DynSolType::Tuple(resolved_params);
+    let mut input_names = function
+        .inputs
+        .iter()
+        .map(|f| f.name.clone())
+        .collect::<Vec<_>>();
+    let input_params_type = DynSolType::Tuple(resolved_params);
 
-                let resolved_output_params: Vec<DynSolType> = function
-                    .outputs
-                    .iter()
-                    .filter_map(|param| param.resolve().ok())
-                    .collect();
+    let resolved_output_params: Vec<DynSolType> = function
+        .outputs
+        .iter()
+        .filter_map(|param| param.resolve().ok())
+        .collect();
 
-                let mut output_names = function
-                    .outputs
-                    .iter()
-                    .map(|f| f.name.clone())
-                    .collect::<Vec<_>>();
-                let output_type = DynSolType::Tuple(resolved_output_params);
+    let mut output_names = function
+        .outputs
+        .iter()
+        .map(|f| f.name.clone())
+        .collect::<Vec<_>>();
+    let output_type = DynSolType::Tuple(resolved_output_params);
 
-                // Remove the function selector from the input.
-                let inputs = &action.input[4..];
-                let mut input_results = Vec::new();
+    // Remove the function selector from the input.
+    let inputs = &action.input[4..];
+    let mut input_results = Vec::new();
 
-                // decode input
-                decode_params(
-                    input_params_type.abi_decode(inputs)?,
-                    &mut input_names,
-                    &mut input_results,
-                );
+    // decode input
+    decode_params(input_params_type.abi_decode(inputs)?, &mut input_names, &mut input_results);
 
-                // decode output if exists
-                let output = if let Some(TraceOutput::Call(output)) = &trace.result {
-                    let mut output_results = Vec::new();
-                    decode_params(
-                        output_type.abi_decode(&output.output)?,
-                        &mut output_names,
-                        &mut output_results,
-                    );
-                    output_results
-                } else {
-                    vec![]
-                };
+    // decode output if exists
+    let output = if let Some(TraceOutput::Call(output)) = &trace.result {
+        let mut output_results = Vec::new();
+        decode_params(
+            output_type.abi_decode(&output.output)?,
+            &mut output_names,
+            &mut output_results,
+        );
+        output_results
+    } else {
+        vec![]
+    };
 
-                return Ok(Some(DecodedCallData {
-                    function_name: function.name.clone(),
-                    call_data:     input_results,
-                    return_data:   output,
-                }));
-            }
-        }
-    }
-    Ok(None)
+    Ok(Some(DecodedCallData {
+        function_name: function.name.clone(),
+        call_data:     input_results,
+        return_data:   output,
+    }))
 }
 
 fn decode_params(
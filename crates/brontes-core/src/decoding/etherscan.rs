@@ -0,0 +1,83 @@
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Address;
+use brontes_types::{chain::chain_spec, FastHashMap};
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+
+/// Requests in flight at once, etherscan rate limits keys to a few requests a
+/// second
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Fetches the abis of verified contracts from the etherscan api of the chain
+/// brontes runs against
+#[derive(Debug, Clone)]
+pub struct EtherscanClient {
+    client:  reqwest::Client,
+    url:     &'static str,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EtherscanResponse {
+    status: String,
+    result: String,
+}
+
+impl EtherscanClient {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self { client: reqwest::Client::new(), url: chain_spec().etherscan_api, api_key }
+    }
+
+    /// Reads the api key from `ETHERSCAN_API_KEY`, requests without a key are
+    /// heavily rate limited
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("ETHERSCAN_API_KEY").ok())
+    }
+
+    /// The abi of the contract, `None` if its source isn't verified
+    pub async fn contract_abi(&self, address: Address) -> eyre::Result<Option<JsonAbi>> {
+        let mut query = vec![
+            ("module", "contract".to_string()),
+            ("action", "getabi".to_string()),
+            ("address", format!("{address:?}")),
+        ];
+        query.extend(self.api_key.clone().map(|key| ("apikey", key)));
+
+        let response = self
+            .client
+            .get(self.url)
+            .query(&query)
+            .send()
+            .await?
+            .json::<EtherscanResponse>()
+            .await?;
+
+        match response.status.as_str() {
+            "1" => Ok(Some(serde_json::from_str(&response.result)?)),
+            _ if response.result.contains("not verified") => Ok(None),
+            _ => Err(eyre::eyre!("etherscan request for {address:?} failed: {}", response.result)),
+        }
+    }
+
+    /// The abis of `addresses`, `None` for the contracts that aren't
+    /// verified. Contracts whose abi couldn't be fetched are left out.
+    pub async fn contract_abis(
+        &self,
+        addresses: Vec<Address>,
+    ) -> FastHashMap<Address, Option<JsonAbi>> {
+        stream::iter(addresses)
+            .map(|address| async move { (address, self.contract_abi(address).await) })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .filter_map(|(address, abi)| async move {
+                match abi {
+                    Ok(abi) => Some((address, abi)),
+                    Err(e) => {
+                        tracing::debug!(?address, error = %e, "failed to fetch contract abi");
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+}
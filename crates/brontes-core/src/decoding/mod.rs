@@ -4,13 +4,17 @@ use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_types::structured_trace::TxTrace;
 pub use brontes_types::traits::TracingProvider;
 use futures::Future;
-use reth_primitives::{BlockNumberOrTag, Header, B256};
+use reth_primitives::{BlockNumberOrTag, Bytes, Header, B256};
 use tokio::sync::mpsc::UnboundedSender;
 
 use self::parser::TraceParser;
 
+#[cfg(feature = "dyn-decode")]
+pub mod abi_cache;
 #[cfg(feature = "dyn-decode")]
 mod dyn_decode;
+#[cfg(feature = "dyn-decode")]
+pub mod etherscan;
 
 pub mod parser;
 mod utils;
@@ -82,6 +86,16 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> Parser<T, DB> {
         }
     }
 
+    /// Traces the raw signed transactions as the block after `parent_block`.
+    /// Nothing is written to libmdbx
+    pub async fn simulate_block(
+        &self,
+        parent_block: u64,
+        txs: Vec<Bytes>,
+    ) -> eyre::Result<(Vec<TxTrace>, Header)> {
+        self.parser.simulate_block(parent_block, txs).await
+    }
+
     /// ensures no libmdbx write
     pub fn execute_discovery(&self, block_num: u64) -> ParserFuture {
         // This will satisfy its lifetime scope do to the lifetime itself living longer
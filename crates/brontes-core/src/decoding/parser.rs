@@ -1,23 +1,28 @@
 use std::time::Duration;
 
-#[cfg(feature = "dyn-decode")]
-use alloy_json_abi::JsonAbi;
 #[cfg(feature = "dyn-decode")]
 use alloy_primitives::Address;
 use brontes_metrics::trace::types::{BlockStats, TraceParseErrorKind, TransactionStats};
+use brontes_types::chain::chain_spec;
 #[cfg(feature = "dyn-decode")]
 use brontes_types::FastHashMap;
 use futures::future::join_all;
 #[cfg(feature = "dyn-decode")]
+use itertools::Itertools;
+#[cfg(feature = "dyn-decode")]
 use reth_rpc_types::trace::parity::Action;
 use reth_rpc_types::{AnyReceiptEnvelope, Log, TransactionReceipt};
-use tracing::error;
 #[cfg(feature = "dyn-decode")]
-use tracing::info;
+use tracing::debug;
+use tracing::error;
 
 use super::*;
 #[cfg(feature = "dyn-decode")]
-use crate::decoding::dyn_decode::decode_input_with_abi;
+use crate::decoding::{
+    abi_cache::{AbiCache, IndexedAbi},
+    dyn_decode::decode_input_with_abi,
+    etherscan::EtherscanClient,
+};
 use crate::errors::TraceParseError;
 
 /// A [`TraceParser`] will iterate through a block's Parity traces and attempt
@@ -26,14 +31,22 @@ pub struct TraceParser<T: TracingProvider, DB: LibmdbxReader + DBWriter> {
     libmdbx:               &'static DB,
     pub tracer:            Arc<T>,
     pub(crate) metrics_tx: Arc<UnboundedSender<ParserMetricEvents>>,
+    #[cfg(feature = "dyn-decode")]
+    abi_cache:             Arc<AbiCache>,
+    #[cfg(feature = "dyn-decode")]
+    etherscan:             EtherscanClient,
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> Clone for TraceParser<T, DB> {
     fn clone(&self) -> Self {
         Self {
-            libmdbx:    self.libmdbx,
-            tracer:     self.tracer.clone(),
+            libmdbx: self.libmdbx,
+            tracer: self.tracer.clone(),
             metrics_tx: self.metrics_tx.clone(),
+            #[cfg(feature = "dyn-decode")]
+            abi_cache: self.abi_cache.clone(),
+            #[cfg(feature = "dyn-decode")]
+            etherscan: self.etherscan.clone(),
         }
     }
 }
@@ -44,7 +57,15 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
         tracer: Arc<T>,
         metrics_tx: Arc<UnboundedSender<ParserMetricEvents>>,
     ) -> Self {
-        Self { libmdbx, tracer, metrics_tx }
+        Self {
+            libmdbx,
+            tracer,
+            metrics_tx,
+            #[cfg(feature = "dyn-decode")]
+            abi_cache: Arc::new(AbiCache::default()),
+            #[cfg(feature = "dyn-decode")]
+            etherscan: EtherscanClient::from_env(),
+        }
     }
 
     pub fn get_tracer(&self) -> Arc<T> {
@@ -184,12 +205,44 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
         Some((traces.0, traces.2))
     }
 
+    /// Traces the raw signed transactions as the block after `parent_block`.
+    /// The header of the hypothetical block is derived from the parent, its
+    /// beneficiary is unknown and left empty.
+    pub async fn simulate_block(
+        &self,
+        parent_block: u64,
+        txs: Vec<Bytes>,
+    ) -> eyre::Result<(Vec<TxTrace>, Header)> {
+        let parent = self
+            .tracer
+            .header_by_number(parent_block)
+            .await?
+            .ok_or_else(|| eyre::eyre!("no header found for block {parent_block}"))?;
+        let traces = self
+            .tracer
+            .simulate_transactions(BlockId::Number(BlockNumberOrTag::Number(parent_block)), txs)
+            .await?;
+
+        let chain = chain_spec();
+        let header = Header {
+            parent_hash: parent.hash_slow(),
+            number: parent_block + 1,
+            timestamp: parent.timestamp + chain.block_time,
+            gas_limit: parent.gas_limit,
+            gas_used: traces.iter().map(|trace| trace.gas_used as u64).sum(),
+            base_fee_per_gas: chain.next_block_base_fee(&parent),
+            ..Default::default()
+        };
+
+        Ok((traces, header))
+    }
+
     #[cfg(feature = "dyn-decode")]
     /// traces a block into a vec of tx traces
     pub(crate) async fn trace_block(
         &self,
         block_num: u64,
-    ) -> (Option<Vec<TxTrace>>, FastHashMap<Address, JsonAbi>, BlockStats) {
+    ) -> (Option<Vec<TxTrace>>, FastHashMap<Address, Arc<IndexedAbi>>, BlockStats) {
         let merged_trace = self
             .tracer
             .replay_block_transactions(BlockId::Number(BlockNumberOrTag::Number(block_num)))
@@ -220,16 +273,36 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
                         })
                 })
                 .filter(|addr| self.libmdbx.get_protocol(*addr).is_err())
+                .unique()
                 .collect::<Vec<Address>>();
-            info!("addresses for dyn decoding: {:#?}", addresses);
-            //self.libmdbx.get_abis(addresses).await.unwrap()
-            FastHashMap::default()
+
+            let mut abis = FastHashMap::default();
+            let mut missing = vec![];
+            for address in addresses {
+                match self.abi_cache.get(&address) {
+                    Some(Some(abi)) => {
+                        abis.insert(address, abi);
+                    }
+                    Some(None) => {}
+                    None => missing.push(address),
+                }
+            }
+
+            debug!(block_num, missing = missing.len(), "fetching abis for dyn decoding");
+            for (address, abi) in self.etherscan.contract_abis(missing).await {
+                match abi {
+                    Some(abi) => {
+                        abis.insert(address, self.abi_cache.insert(address, abi));
+                    }
+                    None => self.abi_cache.insert_unverified(address),
+                }
+            }
+
+            abis
         } else {
             FastHashMap::default()
         };
 
-        info!("{:#?}", json);
-
         (trace, json, stats)
     }
 
@@ -282,7 +355,7 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
     pub(crate) async fn fill_metadata(
         &self,
         block_trace: Vec<TxTrace>,
-        #[cfg(feature = "dyn-decode")] dyn_json: FastHashMap<Address, JsonAbi>,
+        #[cfg(feature = "dyn-decode")] dyn_json: FastHashMap<Address, Arc<IndexedAbi>>,
         block_receipts: Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>,
         block_num: u64,
     ) -> (Vec<TxTrace>, BlockStats, Header) {
@@ -327,7 +400,7 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
     async fn parse_transaction(
         &self,
         mut tx_trace: TxTrace,
-        #[cfg(feature = "dyn-decode")] dyn_json: &FastHashMap<Address, JsonAbi>,
+        #[cfg(feature = "dyn-decode")] dyn_json: &FastHashMap<Address, Arc<IndexedAbi>>,
         block_num: u64,
         tx_hash: B256,
         tx_idx: u64,
@@ -5,6 +5,8 @@ pub mod executor;
 #[cfg(not(feature = "local-reth"))]
 pub mod local_provider;
 pub mod missing_token_info;
+pub mod relay_data;
+pub mod remote_provider;
 
 #[cfg(feature = "tests")]
 pub mod test_utils;
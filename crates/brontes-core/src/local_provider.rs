@@ -76,6 +76,10 @@ impl TracingProvider for LocalProvider {
         );
     }
 
+    async fn simulate_transactions(&self, _: BlockId, _: Vec<Bytes>) -> eyre::Result<Vec<TxTrace>> {
+        unreachable!("the local provider can't trace with the custom tracing model");
+    }
+
     async fn block_receipts(
         &self,
         number: BlockNumberOrTag,
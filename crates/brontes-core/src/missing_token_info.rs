@@ -1,11 +1,13 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use alloy_primitives::Address;
 use alloy_sol_macro::sol;
 use alloy_sol_types::SolCall;
-use brontes_database::libmdbx::DBWriter;
-use brontes_types::make_call_request;
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{db::token_info::TokenInfoWithAddress, make_call_request};
 use futures::{join, stream::FuturesUnordered, StreamExt};
+use itertools::Itertools;
+use schnellru::{ByLength, LruMap};
 use tracing::error;
 
 use crate::decoding::TracingProvider;
@@ -22,68 +24,200 @@ sol!(
     }
 );
 
-pub async fn load_missing_token_info<T: TracingProvider, W: DBWriter>(
-    provider: &Arc<T>,
-    db: &W,
-    block: u64,
-    missing_address: Address,
-) {
-    let data = query_missing_data(provider, block, missing_address).await;
-    on_decimal_query_resolution(db, data).await;
+/// Max amount of addresses remembered as not being a token
+const UNRESOLVABLE_CACHE_SIZE: u32 = 100_000;
+
+/// Resolves the decimals of the tokens the classifier encounters that aren't
+/// in `TokenDecimals` yet. `decimals()` and `symbol()` are queried through the
+/// tracing provider at the block the token was seen in and the result is
+/// written to the database, so the classifiers reading the token info never
+/// miss it. Tokens without a `decimals()` function are stored with 0 decimals.
+/// Addresses whose calls revert or return nothing, e.g. non erc20 contracts
+/// emitting a transfer event, are cached and not queried again, while calls
+/// that failed to reach the node are retried the next time the token is seen.
+pub struct TokenDecimalResolver<T: TracingProvider> {
+    provider:     Arc<T>,
+    unresolvable: Arc<Mutex<LruMap<Address, ()>>>,
 }
 
-pub async fn load_missing_token_infos<T: TracingProvider, W: DBWriter>(
-    provider: &Arc<T>,
-    db: &W,
-    block: u64,
-    missing: Vec<Address>,
-) {
-    let mut pending_decimals = FuturesUnordered::new();
-    missing
-        .into_iter()
-        .for_each(|addr| pending_decimals.push(query_missing_data(provider, block, addr)));
-
-    while let Some(res) = pending_decimals.next().await {
-        on_decimal_query_resolution(db, res).await;
+impl<T: TracingProvider> Clone for TokenDecimalResolver<T> {
+    fn clone(&self) -> Self {
+        Self { provider: self.provider.clone(), unresolvable: self.unresolvable.clone() }
     }
 }
 
+impl<T: TracingProvider> std::fmt::Debug for TokenDecimalResolver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenDecimalResolver")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: TracingProvider> TokenDecimalResolver<T> {
+    pub fn new(provider: Arc<T>) -> Self {
+        let unresolvable = LruMap::new(ByLength::new(UNRESOLVABLE_CACHE_SIZE));
+        Self { provider, unresolvable: Arc::new(Mutex::new(unresolvable)) }
+    }
+
+    pub fn provider(&self) -> &Arc<T> {
+        &self.provider
+    }
+
+    /// Fetches the info of the token, resolving it first if it is missing
+    pub async fn token_info<DB: LibmdbxReader + DBWriter>(
+        &self,
+        db: &DB,
+        block: u64,
+        token: Address,
+    ) -> eyre::Result<TokenInfoWithAddress> {
+        if db.try_fetch_token_info(token).is_err() {
+            self.resolve(db, block, [token]).await;
+        }
+
+        db.try_fetch_token_info(token)
+    }
+
+    /// Resolves all tokens that are neither in the database nor known to be
+    /// unresolvable at once
+    pub async fn resolve<DB: LibmdbxReader + DBWriter>(
+        &self,
+        db: &DB,
+        block: u64,
+        tokens: impl IntoIterator<Item = Address>,
+    ) {
+        let mut pending =
+            tokens
+                .into_iter()
+                .unique()
+                .filter(|token| {
+                    !self.is_unresolvable(*token) && db.try_fetch_token_info(*token).is_err()
+                })
+                .map(|token| async move {
+                    (token, query_missing_data(&self.provider, block, token).await)
+                })
+                .collect::<FuturesUnordered<_>>();
+
+        while let Some((token, res)) = pending.next().await {
+            match res {
+                Resolution::Resolved(decimals, symbol) => {
+                    if let Err(e) = db.write_token_info(token, decimals, symbol).await {
+                        error!(error = %e, "failed to write token info into database");
+                    }
+                }
+                Resolution::Unresolvable => {
+                    tracing::debug!(?token, "address isn't a token");
+                    self.unresolvable.lock().unwrap().insert(token, ());
+                }
+                Resolution::Failed(e) => {
+                    tracing::debug!(?token, error = %e, "token info request failed");
+                }
+            }
+        }
+    }
+
+    fn is_unresolvable(&self, token: Address) -> bool {
+        self.unresolvable.lock().unwrap().peek(&token).is_some()
+    }
+}
+
+#[derive(Debug)]
+enum Resolution {
+    Resolved(u8, String),
+    /// The address doesn't implement `decimals()` nor `symbol()`
+    Unresolvable,
+    /// The calls didn't reach the node, the token can be resolved later
+    Failed(eyre::Report),
+}
+
+impl Resolution {
+    fn new(decimals: eyre::Result<u8>, symbol: String) -> Self {
+        match decimals {
+            Ok(decimals) => Self::Resolved(decimals, symbol),
+            // decimals are optional in erc20, tokens that don't implement them
+            // are stored with 0 decimals
+            Err(e) if is_definitive(&e) && !symbol.is_empty() => Self::Resolved(0, symbol),
+            Err(e) if is_definitive(&e) => Self::Unresolvable,
+            Err(e) => Self::Failed(e),
+        }
+    }
+}
+
+/// Whether the call executed and reverted or returned data that doesn't
+/// decode, rather than failing to reach the node
+fn is_definitive(error: &eyre::Report) -> bool {
+    error.downcast_ref::<alloy_sol_types::Error>().is_some()
+        || error.to_string().to_lowercase().contains("revert")
+}
+
 async fn query_missing_data<T: TracingProvider>(
     provider: &Arc<T>,
     block: u64,
     missing_address: Address,
-) -> eyre::Result<(Address, u8, String)> {
+) -> Resolution {
     let (decimals, symbol, symbol_autistic) = join!(
         make_call_request(normal::decimalsCall::new(()), provider, missing_address, Some(block)),
         make_call_request(normal::symbolCall::new(()), provider, missing_address, Some(block)),
         make_call_request(autistic::symbolCall::new(()), provider, missing_address, Some(block))
     );
 
-    Ok(decimals.map(|d| d._0).unwrap_or_default()).map(|d| {
-        (
-            missing_address,
-            d,
-            symbol.map(|s| s._0).unwrap_or_else(|_| {
-                symbol_autistic
-                    .map(|s| String::from_utf8((s._0).to_vec()).unwrap_or_default())
-                    .unwrap_or_default()
-            }),
-        )
-    })
+    let symbol = symbol.map(|s| s._0).unwrap_or_else(|_| {
+        symbol_autistic
+            .map(|s| String::from_utf8((s._0).to_vec()).unwrap_or_default())
+            .unwrap_or_default()
+    });
+
+    Resolution::new(decimals.map(|d| d._0), symbol)
 }
 
-async fn on_decimal_query_resolution<W: DBWriter>(
-    database: &W,
-    result: eyre::Result<(Address, u8, String)>,
-) {
-    match result {
-        Ok((address, decimals, symbol)) => {
-            if let Err(e) = database.write_token_info(address, decimals, symbol).await {
-                error!(error= %e, "failed to write token info into database");
-            }
-        }
-        Err(e) => {
-            error!(error= %e, "token info request failed");
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_error() -> eyre::Report {
+        normal::decimalsCall::abi_decode_returns(&[], false)
+            .unwrap_err()
+            .into()
+    }
+
+    #[test]
+    fn test_tokens_without_decimals_fall_back_to_zero() {
+        assert!(matches!(
+            Resolution::new(Ok(18), "WETH".to_string()),
+            Resolution::Resolved(18, symbol) if symbol == "WETH"
+        ));
+        assert!(matches!(
+            Resolution::new(Err(decode_error()), "OLD".to_string()),
+            Resolution::Resolved(0, symbol) if symbol == "OLD"
+        ));
+        assert!(matches!(
+            Resolution::new(Err(eyre::eyre!("execution reverted")), "OLD".to_string()),
+            Resolution::Resolved(0, _)
+        ));
+    }
+
+    #[test]
+    fn test_only_definitive_failures_are_unresolvable() {
+        // no code or a contract without the functions
+        assert!(matches!(
+            Resolution::new(Err(decode_error()), String::new()),
+            Resolution::Unresolvable
+        ));
+        assert!(matches!(
+            Resolution::new(
+                Err(eyre::eyre!("server returned an error: execution reverted")),
+                String::new()
+            ),
+            Resolution::Unresolvable
+        ));
+
+        // the node couldn't be reached, the token is queried again later
+        assert!(matches!(
+            Resolution::new(Err(eyre::eyre!("connection reset by peer")), String::new()),
+            Resolution::Failed(_)
+        ));
+        assert!(matches!(
+            Resolution::new(Err(eyre::eyre!("request timed out")), "WETH".to_string()),
+            Resolution::Failed(_)
+        ));
     }
 }
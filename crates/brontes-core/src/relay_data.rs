@@ -0,0 +1,364 @@
+//! Submission channels of the transactions of a block, from relay data.
+//!
+//! The public mempool data only tells whether a transaction was private, not
+//! how it reached the builder. The relays that saw the private order flow
+//! can, so the trees are labelled with the channels they report before the
+//! inspectors run. The sources are:
+//!
+//! - the blocks api of the Flashbots relay, which lists the bundles of every
+//!   block they landed in. Ranges are queried in batches that the blocks in
+//!   flight share, a block at the tip on its own
+//! - a local dataset, a csv of `block_number,tx_hash,channel` rows. Data of
+//!   relays without a public api, e.g. bloXroute, or of private rpcs can be
+//!   exported into it.
+//!
+//! When the sources disagree the most specific channel is kept.
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    path::Path,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use alloy_primitives::TxHash;
+use brontes_types::{
+    order_flow::{SubmissionChannel, SubmissionChannels},
+    FastHashMap,
+};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+pub const FLASHBOTS_BLOCKS_API: &str = "https://blocks.flashbots.net/v1/blocks";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Blocks queried from the Flashbots blocks api in one request
+const FLASHBOTS_BATCH_SIZE: u64 = 100;
+/// Batches kept for the blocks still in flight
+const CACHED_BATCHES: usize = 16;
+
+static RELAY_DATA: OnceLock<RelayData> = OnceLock::new();
+
+/// Labels the trees of the run with the submission channels of the sources
+pub fn init_relay_data(relay_data: RelayData) -> eyre::Result<()> {
+    RELAY_DATA
+        .set(relay_data)
+        .map_err(|_| eyre::eyre!("relay data already initialized"))
+}
+
+pub fn relay_data() -> Option<&'static RelayData> {
+    RELAY_DATA.get()
+}
+
+#[derive(Debug, Default)]
+pub struct RelayData {
+    sources: Vec<RelaySource>,
+}
+
+#[derive(Debug)]
+enum RelaySource {
+    FlashbotsBlocks(FlashbotsBlocksApi),
+    Dataset(FastHashMap<u64, SubmissionChannels>),
+}
+
+impl RelayData {
+    /// Queries the blocks api of the Flashbots relay at `url`
+    pub fn with_flashbots_blocks_api(mut self, url: impl Into<String>) -> eyre::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        self.sources
+            .push(RelaySource::FlashbotsBlocks(FlashbotsBlocksApi::new(client, url.into())));
+
+        Ok(self)
+    }
+
+    /// Loads the csv dataset at `path`
+    pub fn with_dataset(mut self, path: &Path) -> eyre::Result<Self> {
+        let dataset = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("failed to read {}: {e}", path.display()))?;
+        self.sources
+            .push(RelaySource::Dataset(parse_dataset(&dataset)?));
+
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// The channels the sources report for the transactions of the block.
+    /// `last_block` is the last block the caller is going to ask for, the
+    /// blocks up to it are fetched along with `block`. A source that fails is
+    /// skipped, the block is still labelled with the others
+    pub async fn submission_channels(&self, block: u64, last_block: u64) -> SubmissionChannels {
+        let mut channels = SubmissionChannels::default();
+
+        for source in &self.sources {
+            let reported = match source {
+                RelaySource::FlashbotsBlocks(api) => {
+                    match api.submission_channels(block, last_block).await {
+                        Ok(reported) => reported,
+                        Err(err) => {
+                            warn!(%err, block, "failed to query the flashbots blocks api");
+                            continue
+                        }
+                    }
+                }
+                RelaySource::Dataset(dataset) => {
+                    let Some(reported) = dataset.get(&block) else { continue };
+                    reported.clone()
+                }
+            };
+
+            for (tx_hash, channel) in reported {
+                let entry = channels.entry(tx_hash).or_default();
+                *entry = entry.merge(channel);
+            }
+        }
+
+        channels
+    }
+}
+
+/// Rows of `block_number,tx_hash,channel`, a header and empty lines are
+/// skipped
+fn parse_dataset(dataset: &str) -> eyre::Result<FastHashMap<u64, SubmissionChannels>> {
+    let mut blocks: FastHashMap<u64, SubmissionChannels> = FastHashMap::default();
+
+    for (line_no, line) in dataset.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (line_no == 0 && line.starts_with("block_number")) {
+            continue
+        }
+
+        let parse = || -> eyre::Result<(u64, TxHash, SubmissionChannel)> {
+            let [block, tx_hash, channel] = line
+                .split(',')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| eyre::eyre!("expected 3 columns"))?;
+
+            Ok((block.parse()?, tx_hash.parse()?, channel.parse()?))
+        };
+        let (block, tx_hash, channel) =
+            parse().map_err(|e| eyre::eyre!("invalid relay dataset row {}: {e}", line_no + 1))?;
+
+        let entry = blocks.entry(block).or_default().entry(tx_hash).or_default();
+        *entry = entry.merge(channel);
+    }
+
+    Ok(blocks)
+}
+
+#[derive(Debug)]
+struct FlashbotsBlocksApi {
+    client:  reqwest::Client,
+    url:     String,
+    /// The most recent batches, queried or being queried
+    batches: Mutex<VecDeque<Arc<FlashbotsBatch>>>,
+}
+
+/// The channels of a range of blocks, queried by the first block of the range
+/// that is asked for
+#[derive(Debug)]
+struct FlashbotsBatch {
+    blocks:   Range<u64>,
+    channels: OnceCell<FastHashMap<u64, SubmissionChannels>>,
+}
+
+impl FlashbotsBlocksApi {
+    fn new(client: reqwest::Client, url: String) -> Self {
+        Self { client, url, batches: Mutex::default() }
+    }
+
+    async fn submission_channels(
+        &self,
+        block: u64,
+        last_block: u64,
+    ) -> eyre::Result<SubmissionChannels> {
+        let batch = self.batch_for(block, last_block);
+        let blocks = batch
+            .channels
+            .get_or_try_init(|| {
+                query_flashbots_blocks(&self.client, &self.url, batch.blocks.clone())
+            })
+            .await?;
+
+        Ok(blocks.get(&block).cloned().unwrap_or_default())
+    }
+
+    /// The batch holding `block`, a new one that runs up to `last_block` if
+    /// none does. Single blocks, which is how the tip is processed, aren't
+    /// kept, so that a block reprocessed after a reorg is queried again
+    fn batch_for(&self, block: u64, last_block: u64) -> Arc<FlashbotsBatch> {
+        let end = (last_block.max(block) + 1).min(block + FLASHBOTS_BATCH_SIZE);
+        let new_batch =
+            || Arc::new(FlashbotsBatch { blocks: block..end, channels: OnceCell::new() });
+        if end == block + 1 {
+            return new_batch()
+        }
+
+        let mut batches = self.batches.lock().unwrap();
+        if let Some(batch) = batches.iter().find(|batch| batch.blocks.contains(&block)) {
+            return batch.clone()
+        }
+
+        let batch = new_batch();
+        if batches.len() == CACHED_BATCHES {
+            batches.pop_front();
+        }
+        batches.push_back(batch.clone());
+
+        batch
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FlashbotsBlocks {
+    blocks: Vec<FlashbotsBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlashbotsBlock {
+    block_number: u64,
+    transactions: Vec<FlashbotsTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlashbotsTransaction {
+    transaction_hash: TxHash,
+    bundle_type:      String,
+}
+
+impl FlashbotsTransaction {
+    /// `flashbots` transactions were part of a bundle sent through the relay,
+    /// `rogue` ones were private but didn't come through the relay and
+    /// `mempool` ones were public
+    fn channel(&self) -> Option<SubmissionChannel> {
+        match self.bundle_type.as_str() {
+            "flashbots" => Some(SubmissionChannel::Bundle),
+            "rogue" => Some(SubmissionChannel::Private),
+            "mempool" => Some(SubmissionChannel::Public),
+            _ => None,
+        }
+    }
+}
+
+/// Queries the blocks of the range. The api only lists the blocks that
+/// included a bundle, at most `limit` of the ones from `from` up to `before`
+async fn query_flashbots_blocks(
+    client: &reqwest::Client,
+    url: &str,
+    blocks: Range<u64>,
+) -> eyre::Result<FastHashMap<u64, SubmissionChannels>> {
+    let response: FlashbotsBlocks = client
+        .get(url)
+        .query(&[
+            ("from", blocks.start),
+            ("before", blocks.end),
+            ("limit", blocks.end - blocks.start),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(channels_by_block(response))
+}
+
+fn channels_by_block(response: FlashbotsBlocks) -> FastHashMap<u64, SubmissionChannels> {
+    let mut blocks: FastHashMap<u64, SubmissionChannels> = FastHashMap::default();
+    for block in response.blocks {
+        blocks.entry(block.block_number).or_default().extend(
+            block
+                .transactions
+                .iter()
+                .filter_map(|tx| Some((tx.transaction_hash, tx.channel()?))),
+        );
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dataset() {
+        let [a, b] = [1, 2].map(TxHash::with_last_byte);
+        let dataset = format!(
+            "block_number,tx_hash,channel\n10,{a:?},private_rpc\n10,{a:?},bundle\n\n11,{b:?},\
+             private\n"
+        );
+
+        let blocks = parse_dataset(&dataset).unwrap();
+        assert_eq!(blocks[&10][&a], SubmissionChannel::Bundle);
+        assert_eq!(blocks[&11][&b], SubmissionChannel::Private);
+
+        assert!(parse_dataset(&format!("10,{a:?},carrier_pigeon")).is_err());
+    }
+
+    #[test]
+    fn maps_flashbots_bundle_types() {
+        let response: FlashbotsBlocks = serde_json::from_str(&format!(
+            r#"{{"blocks":[{{"block_number":10,"transactions":[
+                {{"transaction_hash":"{:?}","bundle_type":"flashbots","tx_index":0}},
+                {{"transaction_hash":"{:?}","bundle_type":"mempool","tx_index":1}}
+            ]}}]}}"#,
+            TxHash::with_last_byte(1),
+            TxHash::with_last_byte(2),
+        ))
+        .unwrap();
+
+        let channels = response.blocks[0]
+            .transactions
+            .iter()
+            .map(FlashbotsTransaction::channel)
+            .collect::<Vec<_>>();
+        assert_eq!(channels, [Some(SubmissionChannel::Bundle), Some(SubmissionChannel::Public)]);
+    }
+
+    #[test]
+    fn groups_flashbots_batches_by_block() {
+        let [a, b] = [1, 2].map(TxHash::with_last_byte);
+        let response: FlashbotsBlocks = serde_json::from_str(&format!(
+            r#"{{"blocks":[
+                {{"block_number":11,"transactions":[
+                    {{"transaction_hash":"{b:?}","bundle_type":"rogue","tx_index":0}}
+                ]}},
+                {{"block_number":10,"transactions":[
+                    {{"transaction_hash":"{a:?}","bundle_type":"flashbots","tx_index":0}}
+                ]}}
+            ]}}"#,
+        ))
+        .unwrap();
+
+        let blocks = channels_by_block(response);
+        assert_eq!(blocks[&10][&a], SubmissionChannel::Bundle);
+        assert_eq!(blocks[&11][&b], SubmissionChannel::Private);
+        assert!(!blocks[&10].contains_key(&b));
+    }
+
+    #[test]
+    fn blocks_in_flight_share_a_batch() {
+        let api = FlashbotsBlocksApi::new(reqwest::Client::new(), FLASHBOTS_BLOCKS_API.into());
+
+        let batch = api.batch_for(10, 1_000);
+        assert_eq!(batch.blocks, 10..10 + FLASHBOTS_BATCH_SIZE);
+        assert!(Arc::ptr_eq(&batch, &api.batch_for(42, 1_000)));
+
+        // the last batch of a range stops at its end
+        assert_eq!(api.batch_for(950, 1_000).blocks, 950..1_001);
+
+        // a block at the tip is queried on its own each time
+        let tip = api.batch_for(2_000, 2_000);
+        assert_eq!(tip.blocks, 2_000..2_001);
+        assert!(!Arc::ptr_eq(&tip, &api.batch_for(2_000, 2_000)));
+    }
+}
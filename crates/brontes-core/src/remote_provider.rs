@@ -0,0 +1,813 @@
+//! Tracing provider for a remote archive node reached over a websocket, an ipc
+//! socket or http. Over a websocket or ipc, all requests share one persistent
+//! connection on which they are pipelined, so concurrent block loads don't pay
+//! for a connection each. The connection is opened lazily and re-established
+//! when it drops.
+//!
+//! Blocks are replayed with `trace_replayBlockTransactions`. Parity traces
+//! don't carry the logs of a call frame, so the logs of the receipts are
+//! attached to the frames of the contracts that emitted them. Transactions
+//! that aren't in a block are simulated with `debug_traceCallMany` and the
+//! call tracer, whose frames carry their logs.
+use std::{future::Future, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use alloy_json_rpc::RpcError;
+use alloy_primitives::{LogData, U256, U64};
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_client::{ClientBuilder, IpcConnect, WsConnect};
+use alloy_rpc_types::{AnyReceiptEnvelope, Header as RpcHeader};
+use alloy_transport::{BoxTransport, TransportResult};
+use brontes_types::{
+    chain::chain_spec,
+    structured_trace::{TraceActions, TransactionTraceWithLogs, TxTrace},
+    traits::TracingProvider,
+};
+use itertools::Itertools;
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue,
+    TransactionSigned, TxHash, B256,
+};
+use reth_rpc_types::{
+    state::StateOverride,
+    trace::{
+        geth::{
+            CallConfig, CallFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
+            GethDebugTracingCallOptions, GethDebugTracingOptions,
+        },
+        parity::{
+            Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+            SelfdestructAction, TraceOutput, TraceResultsWithTransactionHash, TraceType,
+            TransactionTrace,
+        },
+    },
+    BlockOverrides, Bundle, Log, StateContext, TransactionInput, TransactionReceipt,
+    TransactionRequest,
+};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Backoff before the first reconnect, grows linearly with the attempts
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+
+type BlockReceipts = Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteEndpoint {
+    Http(reqwest::Url),
+    Ws(String),
+    Ipc(PathBuf),
+}
+
+impl FromStr for RemoteEndpoint {
+    type Err = eyre::Report;
+
+    /// `http(s)://` and `ws(s)://` urls are http and websocket endpoints,
+    /// anything else is taken as the path of an ipc socket
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(Self::Http(s.parse()?))
+        } else if s.starts_with("ws://") || s.starts_with("wss://") {
+            Ok(Self::Ws(s.to_string()))
+        } else {
+            Ok(Self::Ipc(s.into()))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteProvider {
+    endpoint:   RemoteEndpoint,
+    retries:    u8,
+    connection: Arc<RwLock<Option<RootProvider<BoxTransport>>>>,
+}
+
+impl RemoteProvider {
+    pub fn new(endpoint: RemoteEndpoint, retries: u8) -> Self {
+        Self { endpoint, retries, connection: Arc::new(RwLock::new(None)) }
+    }
+
+    /// Replays the blocks with a single batch request, fetching the receipts
+    /// the logs are taken from in the same batch. Blocks the node doesn't
+    /// have are `None`.
+    pub async fn replay_blocks(
+        &self,
+        blocks: &[BlockId],
+    ) -> eyre::Result<Vec<Option<Vec<TxTrace>>>> {
+        let responses = self
+            .with_retries(|provider| async move {
+                let client = provider.client();
+                let mut batch = client.new_batch();
+                let waiters = blocks
+                    .iter()
+                    .map(|block| {
+                        let traces = batch
+                            .add_call::<_, Option<Vec<TraceResultsWithTransactionHash>>>(
+                                "trace_replayBlockTransactions",
+                                &(block, [TraceType::Trace]),
+                            )?;
+                        let receipts = batch.add_call::<_, Option<BlockReceipts>>(
+                            "eth_getBlockReceipts",
+                            &(block,),
+                        )?;
+                        Ok((traces, receipts))
+                    })
+                    .collect::<TransportResult<Vec<_>>>()?;
+                batch.send().await?;
+
+                let mut responses = Vec::with_capacity(waiters.len());
+                for (traces, receipts) in waiters {
+                    responses.push((traces.await?, receipts.await?));
+                }
+
+                Ok(responses)
+            })
+            .await?;
+
+        Ok(responses
+            .into_iter()
+            .map(|(traces, receipts)| Some(into_tx_traces(traces?, receipts?)))
+            .collect())
+    }
+
+    /// Runs the request, reconnecting and trying again if the connection
+    /// failed
+    async fn with_retries<'a, R, F, Fut>(&'a self, request: F) -> eyre::Result<R>
+    where
+        F: Fn(RootProvider<BoxTransport>) -> Fut,
+        Fut: Future<Output = TransportResult<R>> + 'a,
+    {
+        let mut attempts = 0;
+        loop {
+            let res = match self.connection().await {
+                Ok(provider) => request(provider).await,
+                Err(e) => Err(e),
+            };
+
+            match res {
+                Err(RpcError::Transport(e)) if attempts < self.retries => {
+                    attempts += 1;
+                    warn!(
+                        target: "brontes::remote_provider",
+                        endpoint = ?self.endpoint,
+                        attempts,
+                        error = %e,
+                        "connection to the remote node failed, reconnecting"
+                    );
+                    self.connection.write().await.take();
+                    tokio::time::sleep(RECONNECT_BACKOFF * attempts as u32).await;
+                }
+                res => return res.map_err(Into::into),
+            }
+        }
+    }
+
+    async fn connection(&self) -> TransportResult<RootProvider<BoxTransport>> {
+        if let Some(provider) = self.connection.read().await.as_ref() {
+            return Ok(provider.clone())
+        }
+
+        let mut connection = self.connection.write().await;
+        // another request might have connected while we waited for the lock
+        if let Some(provider) = connection.as_ref() {
+            return Ok(provider.clone())
+        }
+
+        let client = match &self.endpoint {
+            RemoteEndpoint::Http(url) => ClientBuilder::default().http(url.clone()).boxed(),
+            RemoteEndpoint::Ws(url) => ClientBuilder::default()
+                .ws(WsConnect::new(url.clone()))
+                .await?
+                .boxed(),
+            RemoteEndpoint::Ipc(path) => ClientBuilder::default()
+                .ipc(IpcConnect::new(path.clone()))
+                .await?
+                .boxed(),
+        };
+        let provider = RootProvider::new(client);
+        *connection = Some(provider.clone());
+
+        Ok(provider)
+    }
+}
+
+#[async_trait::async_trait]
+impl TracingProvider for RemoteProvider {
+    async fn eth_call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> eyre::Result<Bytes> {
+        let block = block_number.unwrap_or(BlockId::latest());
+        self.with_retries(|provider| {
+            let (request, state_overrides, block_overrides) =
+                (request.clone(), state_overrides.clone(), block_overrides.clone());
+            async move {
+                match (state_overrides, block_overrides) {
+                    (None, None) => provider.call(&request, block).await,
+                    (state_overrides, block_overrides) => {
+                        provider
+                            .client()
+                            .request(
+                                "eth_call",
+                                (
+                                    request,
+                                    block,
+                                    state_overrides.unwrap_or_default(),
+                                    block_overrides,
+                                ),
+                            )
+                            .await
+                    }
+                }
+            }
+        })
+        .await
+    }
+
+    async fn block_hash_for_id(&self, block_num: u64) -> eyre::Result<Option<B256>> {
+        self.with_retries(|provider| async move {
+            provider
+                .get_block(BlockId::Number(BlockNumberOrTag::Number(block_num)), false)
+                .await
+        })
+        .await
+        .map(|block| block.and_then(|block| block.header.hash))
+    }
+
+    #[cfg(feature = "local-reth")]
+    fn best_block_number(&self) -> eyre::Result<u64> {
+        unreachable!("remote provider should only be used without the local-reth feature")
+    }
+
+    #[cfg(not(feature = "local-reth"))]
+    async fn best_block_number(&self) -> eyre::Result<u64> {
+        self.with_retries(|provider| async move { provider.get_block_number().await })
+            .await
+    }
+
+    async fn replay_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<Option<Vec<TxTrace>>> {
+        Ok(self.replay_blocks(&[block_id]).await?.pop().flatten())
+    }
+
+    /// Simulated with `debug_traceCallMany` as one bundle executed in the
+    /// block after `parent`, with the base fee that block would have. The gas
+    /// used of the root frame of the call tracer is the gas used of the
+    /// transaction, including the intrinsic gas and after the refund.
+    async fn simulate_transactions(
+        &self,
+        parent: BlockId,
+        txs: Vec<Bytes>,
+    ) -> eyre::Result<Vec<TxTrace>> {
+        let txs = txs
+            .iter()
+            .map(|raw| TransactionSigned::decode_enveloped(&mut raw.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let calls = txs
+            .iter()
+            .map(call_request)
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let parent_block = self
+            .with_retries(|provider| async move { provider.get_block(parent, false).await })
+            .await?
+            .ok_or_else(|| eyre::eyre!("parent block {parent:?} not found"))?;
+        let parent_header = into_header(parent_block.header)?;
+        let block_number = parent_header.number + 1;
+        let base_fee = chain_spec().next_block_base_fee(&parent_header);
+
+        let bundle = Bundle {
+            transactions:   calls,
+            block_override: Some(BlockOverrides {
+                number: Some(U256::from(block_number)),
+                time: Some(U64::from(parent_header.timestamp + chain_spec().block_time)),
+                base_fee: base_fee.map(U256::from),
+                ..Default::default()
+            }),
+        };
+        let state = StateContext { block_number: Some(parent), transaction_index: None };
+        let options = GethDebugTracingCallOptions {
+            tracing_options: GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::BuiltInTracer(
+                    GethDebugBuiltInTracerType::CallTracer,
+                ))
+                .with_call_config(CallConfig::default().with_log()),
+            ..Default::default()
+        };
+
+        let frames = self
+            .with_retries(|provider| {
+                let params = (vec![bundle.clone()], state.clone(), options.clone());
+                async move {
+                    provider
+                        .client()
+                        .request::<_, Vec<Vec<CallFrame>>>("debug_traceCallMany", params)
+                        .await
+                }
+            })
+            .await?;
+        // a single bundle was traced
+        let frames = frames.into_iter().next().unwrap_or_default();
+
+        Ok(txs
+            .into_iter()
+            .zip(frames)
+            .enumerate()
+            .map(|(tx_index, (tx, root))| {
+                let gas_used = root.gas_used.saturating_to::<u128>();
+                let is_success = root.error.is_none();
+
+                TxTrace::new(
+                    block_number,
+                    flatten_call_frame(root),
+                    tx.hash(),
+                    tx_index as u64,
+                    gas_used,
+                    tx.effective_gas_price(base_fee),
+                    is_success,
+                )
+            })
+            .collect())
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrTag,
+    ) -> eyre::Result<Option<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>>> {
+        self.with_retries(|provider| async move {
+            provider
+                .client()
+                .request::<_, Option<BlockReceipts>>("eth_getBlockReceipts", (number,))
+                .await
+        })
+        .await
+    }
+
+    async fn header_by_number(&self, number: BlockNumber) -> eyre::Result<Option<Header>> {
+        let Some(block) = self
+            .with_retries(|provider| async move {
+                provider
+                    .get_block(BlockId::Number(BlockNumberOrTag::Number(number)), false)
+                    .await
+            })
+            .await?
+        else {
+            return Ok(None)
+        };
+
+        into_header(block.header).map(Some)
+    }
+
+    async fn block_and_tx_index(&self, hash: TxHash) -> eyre::Result<(u64, usize)> {
+        let tx = self
+            .with_retries(|provider| async move { provider.get_transaction_by_hash(hash).await })
+            .await?;
+        let err = || eyre::eyre!("failed to unwrap option");
+
+        Ok((tx.block_number.ok_or_else(err)?, tx.transaction_index.ok_or_else(err)? as usize))
+    }
+
+    async fn get_storage(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+        storage_key: B256,
+    ) -> eyre::Result<Option<StorageValue>> {
+        let block_id = block_number
+            .map(|number| BlockId::Number(BlockNumberOrTag::Number(number)))
+            .unwrap_or(BlockId::latest());
+
+        self.with_retries(|provider| async move {
+            provider
+                .get_storage_at(address, storage_key.into(), block_id)
+                .await
+        })
+        .await
+        .map(Some)
+    }
+
+    async fn get_bytecode(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+    ) -> eyre::Result<Option<Bytecode>> {
+        let block_id = block_number
+            .map(|number| BlockId::Number(BlockNumberOrTag::Number(number)))
+            .unwrap_or(BlockId::latest());
+
+        self.with_retries(|provider| async move { provider.get_code_at(address, block_id).await })
+            .await
+            .map(|bytes| Some(Bytecode::new_raw(bytes)))
+    }
+}
+
+fn into_header(header: RpcHeader) -> eyre::Result<Header> {
+    let err = || eyre::eyre!("failed to unwrap option");
+    Ok(Header {
+        number:                   header.number.ok_or_else(err)?,
+        base_fee_per_gas:         header.base_fee_per_gas.map(|f| f as u64),
+        mix_hash:                 header.mix_hash.ok_or_else(err)?,
+        withdrawals_root:         header.withdrawals_root,
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        nonce:                    header
+            .nonce
+            .map(|i| u64::from_be_bytes(*i))
+            .ok_or_else(err)?,
+        gas_used:                 header.gas_used as u64,
+        gas_limit:                header.gas_limit as u64,
+        timestamp:                header.timestamp,
+        difficulty:               header.difficulty,
+        state_root:               header.state_root,
+        parent_hash:              header.parent_hash,
+        receipts_root:            header.receipts_root,
+        transactions_root:        header.transactions_root,
+        logs_bloom:               header.logs_bloom,
+        extra_data:               header.extra_data,
+        blob_gas_used:            header.blob_gas_used.map(|f| f as u64),
+        excess_blob_gas:          header.excess_blob_gas.map(|f| f as u64),
+        ommers_hash:              header.uncles_hash,
+        beneficiary:              header.miner,
+    })
+}
+
+fn into_tx_traces(
+    traces: Vec<TraceResultsWithTransactionHash>,
+    receipts: BlockReceipts,
+) -> Vec<TxTrace> {
+    traces
+        .into_iter()
+        .zip(receipts)
+        .map(|(results, receipt)| {
+            let block_number = receipt.block_number.unwrap_or_default();
+            let logs = receipt
+                .inner
+                .logs()
+                .iter()
+                .map(|log| log.inner.clone())
+                .collect_vec();
+
+            let mut trace = with_msg_senders(results.full_trace.trace);
+            attach_logs(&mut trace, logs);
+
+            TxTrace::new(
+                block_number,
+                trace,
+                results.transaction_hash,
+                receipt.transaction_index.unwrap_or_default(),
+                receipt.gas_used,
+                receipt.effective_gas_price,
+                receipt.inner.status(),
+            )
+        })
+        .collect()
+}
+
+fn with_msg_senders(traces: Vec<TransactionTrace>) -> Vec<TransactionTraceWithLogs> {
+    let mut with_senders = Vec::with_capacity(traces.len());
+    for (trace_idx, tx_trace) in traces.into_iter().enumerate() {
+        let msg_sender = msg_sender(&with_senders, &tx_trace);
+        with_senders.push(TransactionTraceWithLogs {
+            trace: tx_trace,
+            logs: vec![],
+            #[cfg(feature = "provenance")]
+            tx_log_indices: vec![],
+            msg_sender,
+            trace_idx: trace_idx as u64,
+            decoded_data: None,
+        });
+    }
+
+    with_senders
+}
+
+/// Flattens the call tracer frames of a transaction into parity style traces
+/// in execution order. Logs of frames that reverted, or whose parents did,
+/// were rolled back and are dropped.
+fn flatten_call_frame(root: CallFrame) -> Vec<TransactionTraceWithLogs> {
+    let mut frames = Vec::new();
+    let mut stack = vec![(root, vec![], false)];
+    while let Some((mut frame, trace_address, parent_reverted)) = stack.pop() {
+        let reverted = parent_reverted || frame.error.is_some();
+        let calls = std::mem::take(&mut frame.calls);
+        let subtraces = calls.len();
+        // pushed in reverse so that the first subcall is popped next
+        stack.extend(calls.into_iter().enumerate().rev().map(|(i, call)| {
+            let mut address = trace_address.clone();
+            address.push(i);
+            (call, address, reverted)
+        }));
+        frames.push((frame, trace_address, subtraces, reverted));
+    }
+
+    let mut traces = Vec::with_capacity(frames.len());
+    for (trace_idx, (frame, trace_address, subtraces, reverted)) in frames.into_iter().enumerate() {
+        let logs = if reverted {
+            vec![]
+        } else {
+            frame
+                .logs
+                .iter()
+                .map(|log| alloy_primitives::Log {
+                    address: log.address.unwrap_or_default(),
+                    data:    LogData::new_unchecked(
+                        log.topics.clone().unwrap_or_default(),
+                        log.data.clone().unwrap_or_default(),
+                    ),
+                })
+                .collect()
+        };
+        let trace = into_parity_trace(frame, trace_address, subtraces);
+
+        traces.push(TransactionTraceWithLogs {
+            msg_sender: msg_sender(&traces, &trace),
+            trace,
+            logs,
+            // the call tracer doesn't record where the logs were emitted in
+            // between the subcalls
+            #[cfg(feature = "provenance")]
+            tx_log_indices: vec![],
+            trace_idx: trace_idx as u64,
+            decoded_data: None,
+        });
+    }
+
+    traces
+}
+
+fn into_parity_trace(
+    frame: CallFrame,
+    trace_address: Vec<usize>,
+    subtraces: usize,
+) -> TransactionTrace {
+    let gas = U64::from(frame.gas.saturating_to::<u64>());
+    let gas_used = U64::from(frame.gas_used.saturating_to::<u64>());
+    let value = frame.value.unwrap_or_default();
+    let to = frame.to.unwrap_or_default();
+    let output = frame.output.unwrap_or_default();
+    let succeeded = frame.error.is_none();
+
+    let (action, result) = match frame.typ.as_str() {
+        "CREATE" | "CREATE2" => (
+            Action::Create(CreateAction { from: frame.from, gas, init: frame.input, value }),
+            Some(TraceOutput::Create(CreateOutput { address: to, code: output, gas_used })),
+        ),
+        "SELFDESTRUCT" => (
+            Action::Selfdestruct(SelfdestructAction {
+                address:        frame.from,
+                balance:        value,
+                refund_address: to,
+            }),
+            None,
+        ),
+        typ => {
+            let call_type = match typ {
+                "DELEGATECALL" => CallType::DelegateCall,
+                "STATICCALL" => CallType::StaticCall,
+                "CALLCODE" => CallType::CallCode,
+                _ => CallType::Call,
+            };
+            (
+                Action::Call(CallAction {
+                    from: frame.from,
+                    call_type,
+                    gas,
+                    input: frame.input,
+                    to,
+                    value,
+                }),
+                Some(TraceOutput::Call(CallOutput { gas_used, output })),
+            )
+        }
+    };
+
+    TransactionTrace {
+        action,
+        error: frame.error,
+        result: result.filter(|_| succeeded),
+        subtraces,
+        trace_address,
+    }
+}
+
+/// The call `debug_traceCallMany` executes for the signed transaction
+fn call_request(tx: &TransactionSigned) -> eyre::Result<TransactionRequest> {
+    let from = tx
+        .recover_signer()
+        .ok_or_else(|| eyre::eyre!("invalid signature for transaction {:?}", tx.hash()))?;
+    let (gas_price, max_fee_per_gas) = if tx.is_dynamic_fee() {
+        (None, Some(tx.max_fee_per_gas()))
+    } else {
+        (Some(tx.max_fee_per_gas()), None)
+    };
+
+    Ok(TransactionRequest {
+        from: Some(from),
+        to: tx.to(),
+        gas_price,
+        max_fee_per_gas,
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+        gas: Some(tx.gas_limit() as u128),
+        value: Some(tx.value()),
+        input: TransactionInput::new(tx.input().clone()),
+        nonce: Some(tx.nonce()),
+        chain_id: tx.chain_id(),
+        ..Default::default()
+    })
+}
+
+/// The caller of a frame, which for a delegate call is the caller of the
+/// frame it was made from
+fn msg_sender(parents: &[TransactionTraceWithLogs], trace: &TransactionTrace) -> Address {
+    match &trace.action {
+        Action::Call(call) if call.call_type == CallType::DelegateCall => {
+            let parent_address =
+                &trace.trace_address[..trace.trace_address.len().saturating_sub(1)];
+            parents
+                .iter()
+                .rev()
+                .find(|parent| parent.trace.trace_address == parent_address)
+                .map(|parent| parent.msg_sender)
+                .unwrap_or(call.from)
+        }
+        Action::Call(call) => call.from,
+        Action::Create(create) => create.from,
+        Action::Selfdestruct(sd) => sd.address,
+        Action::Reward(reward) => reward.author,
+    }
+}
+
+/// The address logs of the frame are emitted from
+fn emitter(trace: &TransactionTraceWithLogs) -> Option<Address> {
+    match &trace.trace.action {
+        Action::Call(call)
+            if matches!(call.call_type, CallType::DelegateCall | CallType::CallCode) =>
+        {
+            Some(call.from)
+        }
+        Action::Call(call) => Some(call.to),
+        Action::Create(_) => Some(trace.get_create_output()),
+        _ => None,
+    }
+}
+
+/// Attaches the logs of the receipt to the frames that emitted them. Logs are
+/// emitted in execution order, so each log goes to the first matching frame
+/// at or after the frame of the previous log, or back to an earlier one if a
+/// frame emitted it after its subcalls returned. Frames that reverted, or
+/// whose parents did, can't have emitted any logs of the receipt. With the
+/// `provenance` feature the index of each log in the receipt is kept with the
+/// frame.
+fn attach_logs(traces: &mut [TransactionTraceWithLogs], logs: Vec<alloy_primitives::Log>) {
+    let reverted = traces
+        .iter()
+        .map(|trace| {
+            traces.iter().any(|parent| {
+                parent.trace.error.is_some()
+                    && trace
+                        .trace
+                        .trace_address
+                        .starts_with(&parent.trace.trace_address)
+            })
+        })
+        .collect_vec();
+    let emitters = traces.iter().map(emitter).collect_vec();
+
+    let mut cursor = 0;
+    #[cfg_attr(not(feature = "provenance"), allow(unused_variables))]
+    for (log_idx, log) in logs.into_iter().enumerate() {
+        let mut candidates = (0..traces.len())
+            .filter(|&i| !reverted[i] && emitters[i] == Some(log.address))
+            .peekable();
+        let Some(&first) = candidates.peek() else { continue };
+
+        let frame = candidates
+            .clone()
+            .find(|&i| i >= cursor)
+            .or_else(|| candidates.filter(|&i| i < cursor).last())
+            .unwrap_or(first);
+
+        traces[frame].logs.push(log);
+        #[cfg(feature = "provenance")]
+        traces[frame].tx_log_indices.push(log_idx as u64);
+        cursor = frame;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn address(byte: u8) -> Address {
+        Address::with_last_byte(byte)
+    }
+
+    #[test]
+    fn test_flatten_call_frame() {
+        let log = |emitter: u8| {
+            json!({
+                "address": address(emitter),
+                "topics": [B256::with_last_byte(emitter)],
+                "data": "0x01"
+            })
+        };
+        let root: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": address(1),
+            "to": address(2),
+            "gas": "0x30000",
+            "gasUsed": "0x12345",
+            "input": "0x",
+            "value": "0x0",
+            "logs": [log(2)],
+            "calls": [
+                {
+                    "type": "DELEGATECALL",
+                    "from": address(2),
+                    "to": address(3),
+                    "gas": "0x10000",
+                    "gasUsed": "0x100",
+                    "input": "0x",
+                    "logs": [log(2)],
+                    "calls": [{
+                        "type": "STATICCALL",
+                        "from": address(2),
+                        "to": address(4),
+                        "gas": "0x1000",
+                        "gasUsed": "0x10",
+                        "input": "0x"
+                    }]
+                },
+                {
+                    "type": "CALL",
+                    "from": address(2),
+                    "to": address(5),
+                    "gas": "0x10000",
+                    "gasUsed": "0x10000",
+                    "input": "0x",
+                    "value": "0x0",
+                    "error": "execution reverted",
+                    "calls": [{
+                        "type": "CALL",
+                        "from": address(5),
+                        "to": address(6),
+                        "gas": "0x1000",
+                        "gasUsed": "0x10",
+                        "input": "0x",
+                        "value": "0x0",
+                        "logs": [log(6)]
+                    }]
+                },
+                {
+                    "type": "CREATE2",
+                    "from": address(2),
+                    "to": address(7),
+                    "gas": "0x10000",
+                    "gasUsed": "0x1000",
+                    "input": "0x60",
+                    "output": "0x00",
+                    "value": "0x0"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let traces = flatten_call_frame(root);
+
+        assert_eq!(
+            traces
+                .iter()
+                .map(|trace| trace.trace.trace_address.clone())
+                .collect_vec(),
+            vec![vec![], vec![0], vec![0, 0], vec![1], vec![1, 0], vec![2]]
+        );
+        assert_eq!(
+            traces
+                .iter()
+                .map(|trace| trace.trace.subtraces)
+                .collect_vec(),
+            vec![3, 1, 0, 1, 0, 0]
+        );
+        assert_eq!(traces.iter().map(|trace| trace.trace_idx).collect_vec(), (0..6).collect_vec());
+
+        // the delegate call and the static call made from it act for the caller
+        // of the root frame
+        assert_eq!(traces[1].msg_sender, address(1));
+        assert_eq!(traces[2].msg_sender, address(2));
+        assert_eq!(traces[1].logs.len(), 1);
+
+        // the logs of the reverted call and its subcall were rolled back
+        assert!(traces[3].trace.error.is_some());
+        assert!(traces[3].trace.result.is_none());
+        assert!(traces[4].logs.is_empty());
+
+        assert_eq!(traces[0].logs[0].address, address(2));
+        assert_eq!(traces[0].trace.result.as_ref().unwrap().gas_used(), U64::from(0x12345));
+        assert_eq!(traces[5].get_create_output(), address(7));
+    }
+}
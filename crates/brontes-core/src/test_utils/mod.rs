@@ -6,7 +6,7 @@ use alloy_primitives::Address;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::Clickhouse;
 #[cfg(not(feature = "local-clickhouse"))]
-use brontes_database::clickhouse::ClickhouseHttpClient;
+use brontes_database::clickhouse::{ClickhouseHttpClient, ClickhouseHttpConfig};
 pub use brontes_database::libmdbx::{DBWriter, LibmdbxReadWriter, LibmdbxReader};
 use brontes_database::{
     libmdbx::LibmdbxInit, AddressToProtocolInfo, PoolCreationBlocks, Tables, TokenDecimals,
@@ -546,13 +546,19 @@ pub fn init_tracing() {
     brontes_tracing::init(layers);
 }
 
-#[cfg(feature = "local-reth")]
 pub async fn init_trace_parser(
     handle: Handle,
     metrics_tx: UnboundedSender<ParserMetricEvents>,
     libmdbx: &'static LibmdbxReadWriter,
     max_tasks: u32,
 ) -> TraceParser<Box<dyn TracingProvider>, LibmdbxReadWriter> {
+    let tracer = init_tracer(handle, max_tasks);
+
+    TraceParser::new(libmdbx, Arc::new(tracer), Arc::new(metrics_tx)).await
+}
+
+#[cfg(feature = "local-reth")]
+pub fn init_tracer(handle: Handle, max_tasks: u32) -> Box<dyn TracingProvider> {
     let executor = brontes_types::BrontesTaskManager::new(handle.clone(), true);
 
     let db_path = env::var("DB_PATH").expect("No DB_PATH in .env");
@@ -568,24 +574,17 @@ pub async fn init_trace_parser(
         static_files,
     );
     handle.spawn(executor);
-    let tracer = Box::new(client) as Box<dyn TracingProvider>;
 
-    TraceParser::new(libmdbx, Arc::new(tracer), Arc::new(metrics_tx)).await
+    Box::new(client)
 }
 
 #[cfg(not(feature = "local-reth"))]
-pub async fn init_trace_parser(
-    _handle: Handle,
-    metrics_tx: UnboundedSender<ParserMetricEvents>,
-    libmdbx: &'static LibmdbxReadWriter,
-    _max_tasks: u32,
-) -> TraceParser<Box<dyn TracingProvider>, LibmdbxReadWriter> {
+pub fn init_tracer(_handle: Handle, _max_tasks: u32) -> Box<dyn TracingProvider> {
     let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
     let db_port = env::var("RETH_PORT").expect("No DB port.env");
     let url = format!("{db_endpoint}:{db_port}");
-    let tracer = Box::new(LocalProvider::new(url, 15)) as Box<dyn TracingProvider>;
 
-    TraceParser::new(libmdbx, Arc::new(tracer), Arc::new(metrics_tx)).await
+    Box::new(LocalProvider::new(url, 15))
 }
 
 #[cfg(feature = "local-clickhouse")]
@@ -597,5 +596,7 @@ pub async fn load_clickhouse() -> Clickhouse {
 pub async fn load_clickhouse() -> ClickhouseHttpClient {
     let clickhouse_api = env::var("CLICKHOUSE_API").expect("No CLICKHOUSE_API in .env");
     let clickhouse_api_key = env::var("CLICKHOUSE_API_KEY").ok();
-    ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await
+    ClickhouseHttpClient::new(ClickhouseHttpConfig::new(clickhouse_api, clickhouse_api_key))
+        .await
+        .expect("failed to connect to the clickhouse api")
 }
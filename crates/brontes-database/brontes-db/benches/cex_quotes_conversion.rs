@@ -170,11 +170,7 @@ fn prepare_test_data(
 ) -> Vec<(u64, FastHashMap<Pair, Vec<usize>>)> {
     data.into_iter()
         .map(|((_, block_time), exchange_maps)| {
-            let mut exchange_pair_index_map: std::collections::HashMap<
-                Pair,
-                Vec<usize>,
-                ahash::RandomState,
-            > = FastHashMap::default();
+            let mut exchange_pair_index_map: FastHashMap<Pair, Vec<usize>> = FastHashMap::default();
 
             exchange_maps.into_iter().for_each(|(_, index)| {
                 index.into_iter().for_each(|index| {
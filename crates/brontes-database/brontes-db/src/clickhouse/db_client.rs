@@ -1,25 +1,37 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::RangeInclusive};
 
 use ::clickhouse::DbRow;
 use alloy_primitives::Address;
 use backon::{ExponentialBuilder, Retryable};
 #[cfg(feature = "local-clickhouse")]
-use brontes_types::db::{block_times::BlockTimes, cex::CexSymbols};
+use brontes_types::db::{
+    block_times::BlockTimes, cex::CexSymbols, searcher_bytecode::SearcherBytecode,
+};
 use brontes_types::{
     db::{
         address_to_protocol_info::ProtocolInfoClickhouse,
         block_analysis::BlockAnalysis,
+        block_provenance::BlockProvenance,
         builder::BuilderInfo,
+        builder_pnl::{BuilderPnl, BuilderPnlStats},
         cex::{
             quotes::{CexQuotesConverter, RawCexQuotes},
             trades::{CexTradesConverter, RawCexTrades},
             BestCexPerPair,
         },
         dex::{DexQuotes, DexQuotesWithBlockNumber},
+        gas_bids::{GasBid, SearcherBlockBids},
+        inspector_run::InspectorRun,
         metadata::{BlockMetadata, Metadata},
+        mev_block::MevBlockWithClassified,
         normalized_actions::TransactionRoot,
+        possible_mev_timeline::PossibleMevTimeline,
         searcher::SearcherInfo,
+        searcher_fingerprint::{SearcherFingerprint, SharedFingerprint},
+        searcher_inventory::SearcherInventoryDelta,
+        suppressed_bundle::SuppressedBundle,
         token_info::{TokenInfo, TokenInfoWithAddress},
+        victim_execution::VictimExecution,
     },
     mev::{Bundle, BundleData, MevBlock},
     normalized_actions::Action,
@@ -39,15 +51,23 @@ use db_interfaces::{
 use eyre::Result;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use tokio::{sync::mpsc::UnboundedSender, time::Duration};
+use tokio::{
+    sync::{mpsc::Sender, oneshot},
+    time::Duration,
+};
 use tracing::{debug, error, warn};
 
 use super::{
-    cex_config::CexDownloadConfig, dbms::*, ClickhouseHandle, MOST_VOLUME_PAIR_EXCHANGE,
-    RAW_CEX_QUOTES, RAW_CEX_TRADES,
+    cex_config::CexDownloadConfig,
+    dbms::*,
+    query::{Clause, QueryBuilder},
+    ClickhouseHandle, ClickhouseWriterMessage, MOST_VOLUME_PAIR_EXCHANGE, RAW_CEX_QUOTES,
+    RAW_CEX_TRADES,
 };
 #[cfg(feature = "local-clickhouse")]
-use super::{BLOCK_TIMES, CEX_SYMBOLS};
+use super::{
+    BLOCK_TIMES, BUILDER_PNL_STATS, CEX_SYMBOLS, SEARCHER_BYTECODES, SHARED_SEARCHER_FINGERPRINTS,
+};
 #[cfg(feature = "local-clickhouse")]
 use crate::libmdbx::cex_utils::CexRangeOrArbitrary;
 use crate::{
@@ -63,20 +83,29 @@ use crate::{
 const SECONDS_TO_US: f64 = 1_000_000.0;
 const MAX_MARKOUT_TIME: f64 = 300.0;
 
+/// Block range filter of the block times and `*InitQuery` templates
+const BLOCK_RANGE_FILTER: &str = "block_number >= ? AND block_number < ?";
+/// Timestamp range filter of the raw cex quotes and trades templates
+const TIMESTAMP_RANGE_FILTER: &str = "c.timestamp >= ? AND c.timestamp < ?";
+/// Month range filter of the most volume pair exchange template
+const MONTH_RANGE_FILTER: &str = "month >= toStartOfMonth(toDateTime(? / 1000000) - \
+                                  toIntervalMonth(1))) AND (month <= toStartOfMonth(toDateTime(? \
+                                  / 1000000) - toIntervalMonth(1))";
+
 #[derive(Clone)]
 pub struct Clickhouse {
     pub tip:                 bool,
     pub run_id:              u64,
     pub client:              ClickhouseClient<BrontesClickhouseTables>,
     pub cex_download_config: CexDownloadConfig,
-    pub buffered_insert_tx:  Option<UnboundedSender<Vec<BrontesClickhouseData>>>,
+    pub buffered_insert_tx:  Option<Sender<ClickhouseWriterMessage>>,
 }
 
 impl Clickhouse {
     pub async fn new(
         config: ClickhouseConfig,
         cex_download_config: CexDownloadConfig,
-        buffered_insert_tx: Option<UnboundedSender<Vec<BrontesClickhouseData>>>,
+        buffered_insert_tx: Option<Sender<ClickhouseWriterMessage>>,
         tip: bool,
         run_id: Option<u64>,
     ) -> Self {
@@ -102,6 +131,20 @@ impl Clickhouse {
         &self.client
     }
 
+    /// Waits until every row sent to the buffered writer so far has been
+    /// inserted
+    pub async fn wait_for_inserts(&self) -> eyre::Result<()> {
+        let Some(tx) = self.buffered_insert_tx.as_ref() else { return Ok(()) };
+
+        let (done, inserted) = oneshot::channel();
+        tx.send(ClickhouseWriterMessage::Barrier(done)).await?;
+        if !inserted.await? {
+            eyre::bail!("failed to insert the buffered rows into clickhouse")
+        }
+
+        Ok(())
+    }
+
     pub async fn get_and_inc_run_id(&self) -> eyre::Result<u64> {
         let id = (self
             .client
@@ -154,41 +197,38 @@ impl Clickhouse {
         mev: Vec<Bundle>,
     ) -> eyre::Result<()> {
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
-            tx.send(vec![(block, self.tip, self.run_id).into()])?;
+            tx.send(ClickhouseWriterMessage::Rows(vec![(block, self.tip, self.run_id).into()]))
+                .await?;
 
             let (bundle_headers, bundle_data): (Vec<_>, Vec<_>) = mev
                 .into_iter()
                 .map(|bundle| (bundle.header, bundle.data))
                 .unzip();
 
-            tx.send(
+            tx.send(ClickhouseWriterMessage::Rows(
                 bundle_headers
                     .into_iter()
                     .map(|a| (a, self.tip, self.run_id))
                     .map(Into::into)
                     .collect(),
-            )?;
-
-            bundle_data.into_iter().try_for_each(|data| {
-                match data {
-                    BundleData::Sandwich(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::AtomicArb(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::JitSandwich(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Jit(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::CexDex(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::CexDexQuote(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Liquidation(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Unknown(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                };
+            ))
+            .await?;
 
-                Ok(()) as eyre::Result<()>
-            })?;
+            for data in bundle_data {
+                let row: BrontesClickhouseData = match data {
+                    BundleData::Sandwich(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::AtomicArb(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::JitSandwich(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::Jit(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::CexDex(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::CexDexQuote(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::Liquidation(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::Frontrun(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::ApprovalRace(s) => (s, self.tip, self.run_id).into(),
+                    BundleData::Unknown(s) => (s, self.tip, self.run_id).into(),
+                };
+                tx.send(ClickhouseWriterMessage::Rows(vec![row])).await?;
+            }
         }
 
         Ok(())
@@ -203,13 +243,14 @@ impl Clickhouse {
             let quotes_with_block = DexQuotesWithBlockNumber::new_with_block(block_num, q);
 
             if let Some(tx) = self.buffered_insert_tx.as_ref() {
-                tx.send(
+                tx.send(ClickhouseWriterMessage::Rows(
                     quotes_with_block
                         .into_iter()
                         .zip(vec![self.tip].into_iter().cycle())
                         .map(Into::into)
                         .collect(),
-                )?;
+                ))
+                .await?;
             }
         }
 
@@ -224,13 +265,77 @@ impl Clickhouse {
             .collect::<Vec<_>>();
 
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
-            tx.send(
+            tx.send(ClickhouseWriterMessage::Rows(
                 roots
                     .into_iter()
                     .map(|root| (root, self.tip, self.run_id))
                     .map(Into::into)
                     .collect(),
-            )?;
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the rows of the blocks that were reorged out, so that the
+    /// results of the canonical blocks don't end up next to the stale ones
+    pub async fn invalidate_blocks(&self, blocks: RangeInclusive<u64>) -> eyre::Result<()> {
+        for table in BLOCK_KEYED_TABLES {
+            self.client
+                .execute_remote(
+                    &format!(
+                        "DELETE FROM {table} ON CLUSTER eth_cluster0 WHERE block_number >= ? AND \
+                         block_number <= ?"
+                    ),
+                    &(*blocks.start(), *blocks.end()),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserts the recomputed results under this run's id and only once they
+    /// are in deletes the rows earlier runs wrote for the blocks, so that
+    /// bundles that aren't found anymore don't linger. A failure never leaves
+    /// the blocks without results and calling this again finishes the
+    /// replacement, the mev tables collapse rows that are inserted twice.
+    pub async fn replace_mev_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> eyre::Result<()> {
+        if self.buffered_insert_tx.is_none() {
+            eyre::bail!("no clickhouse writer to insert the recomputed results with")
+        }
+
+        for MevBlockWithClassified { block, mev } in mev_blocks {
+            self.save_mev_blocks(block.block_number, block, mev).await?;
+        }
+        self.wait_for_inserts().await?;
+
+        let retry_strategy = ExponentialBuilder::default()
+            .with_max_times(10)
+            .with_min_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(30));
+
+        for table in BLOCK_KEYED_TABLES
+            .iter()
+            .filter(|table| table.starts_with("mev."))
+        {
+            let query = format!(
+                "DELETE FROM {table} ON CLUSTER eth_cluster0 WHERE block_number >= ? AND \
+                 block_number <= ? AND run_id != ?"
+            );
+            (|| async {
+                self.client
+                    .execute_remote(&query, &(*blocks.start(), *blocks.end(), self.run_id))
+                    .await
+            })
+            .retry(&retry_strategy)
+            .when(is_transient_error)
+            .await?;
         }
 
         Ok(())
@@ -242,10 +347,11 @@ impl Clickhouse {
         decimals: u8,
         symbol: String,
     ) -> eyre::Result<()> {
-        let data = TokenInfoWithAddress { address, inner: TokenInfo { symbol, decimals } };
+        let data = TokenInfoWithAddress { address, inner: TokenInfo::new(decimals, symbol) };
 
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
-            tx.send(vec![(data, self.tip).into()])?
+            tx.send(ClickhouseWriterMessage::Rows(vec![(data, self.tip).into()]))
+                .await?
         };
 
         Ok(())
@@ -263,7 +369,8 @@ impl Clickhouse {
             ProtocolInfoClickhouse::new(block, address, tokens, curve_lp_token, classifier_name);
 
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
-            tx.send(vec![(data, self.tip).into()])?
+            tx.send(ClickhouseWriterMessage::Rows(vec![(data, self.tip).into()]))
+                .await?
         };
 
         Ok(())
@@ -271,7 +378,159 @@ impl Clickhouse {
 
     pub async fn block_analysis(&self, block_analysis: BlockAnalysis) -> eyre::Result<()> {
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
-            tx.send(vec![(block_analysis, self.tip, self.run_id).into()])?
+            tx.send(ClickhouseWriterMessage::Rows(vec![
+                (block_analysis, self.tip, self.run_id).into()
+            ]))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_gas_bids(&self, gas_bids: Vec<GasBid>) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                gas_bids
+                    .into_iter()
+                    .map(|bid| (bid, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_searcher_block_bids(
+        &self,
+        block_bids: Vec<SearcherBlockBids>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                block_bids
+                    .into_iter()
+                    .map(|bids| (bids, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_searcher_fingerprints(
+        &self,
+        fingerprints: Vec<SearcherFingerprint>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                fingerprints
+                    .into_iter()
+                    .map(|fingerprint| (fingerprint, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_searcher_inventory(
+        &self,
+        inventory: Vec<SearcherInventoryDelta>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                inventory
+                    .into_iter()
+                    .map(|delta| (delta, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_victim_execution(
+        &self,
+        executions: Vec<VictimExecution>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                executions
+                    .into_iter()
+                    .map(|execution| (execution, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_possible_mev_timeline(
+        &self,
+        timeline: Vec<PossibleMevTimeline>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                timeline
+                    .into_iter()
+                    .map(|entry| (entry, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_block_provenance(&self, provenance: BlockProvenance) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(vec![(provenance, self.tip, self.run_id).into()]))
+                .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_suppressed_bundles(
+        &self,
+        bundles: Vec<SuppressedBundle>,
+    ) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                bundles
+                    .into_iter()
+                    .map(|bundle| (bundle, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_inspector_runs(&self, runs: Vec<InspectorRun>) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                runs.into_iter()
+                    .map(|run| (run, self.tip, self.run_id).into())
+                    .collect(),
+            ))
+            .await?
+        };
+
+        Ok(())
+    }
+
+    pub async fn write_builder_pnl(&self, builder_pnl: BuilderPnl) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            tx.send(ClickhouseWriterMessage::Rows(
+                vec![(builder_pnl, self.tip, self.run_id).into()],
+            ))
+            .await?
         };
 
         Ok(())
@@ -298,15 +557,7 @@ impl Clickhouse {
         let mut try_count = 1;
         let res = (|| async { self.client.query_many::<Q, P>(query.as_ref(), params).await })
             .retry(&retry_strategy)
-            .when(|e| match e {
-                DatabaseError::ClickhouseError(ClickhouseError::ClickhouseNative(Network(_))) => {
-                    true
-                }
-                DatabaseError::ClickhouseError(ClickhouseError::ClickhouseNative(BadResponse(
-                    s,
-                ))) if s.to_string().contains("MEMORY_LIMIT_EXCEEDED") => true,
-                _ => false,
-            })
+            .when(is_transient_error)
             .notify(|err, dur| {
                 warn!(
                     "Query failed after {} attempt(s).  Retrying in {:?}... Error: {}",
@@ -327,6 +578,17 @@ impl Clickhouse {
     }
 }
 
+/// Errors that are worth retrying the query or insert on
+pub(crate) fn is_transient_error(e: &DatabaseError) -> bool {
+    match e {
+        DatabaseError::ClickhouseError(ClickhouseError::ClickhouseNative(Network(_))) => true,
+        DatabaseError::ClickhouseError(ClickhouseError::ClickhouseNative(BadResponse(s))) => {
+            s.to_string().contains("MEMORY_LIMIT_EXCEEDED")
+        }
+        _ => false,
+    }
+}
+
 impl ClickhouseHandle for Clickhouse {
     async fn get_init_crit_tables(&self) -> eyre::Result<ClickhouseCritTableCount> {
         let res: ClickhouseCritTableCount = self.client.query_one(CRIT_INIT_TABLES, &()).await?;
@@ -362,6 +624,8 @@ impl ClickhouseHandle for Clickhouse {
             block_meta.proposer_mev_reward,
             eth_price.unwrap_or_default(),
             block_meta.private_flow.into_iter().collect(),
+            block_meta.blob_gas_used,
+            block_meta.excess_blob_gas,
         )
         .into_metadata(cex_quotes.value, None, None, None);
 
@@ -388,7 +652,7 @@ impl ClickhouseHandle for Clickhouse {
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
         D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + 'static,
     {
-        let query = format_arbitrary_query::<T>(range);
+        let query = format_arbitrary_query::<T>(range)?;
 
         self.query_many_with_retry::<D, _>(&query, &())
             .await
@@ -427,23 +691,7 @@ impl ClickhouseHandle for Clickhouse {
             }
 
             CexRangeOrArbitrary::Arbitrary(vals) => {
-                let mut query = BLOCK_TIMES.to_string();
-
-                let vals = vals
-                    .iter()
-                    .flat_map(|v| {
-                        (v - self.cex_download_config.run_time_window.0
-                            ..=v + self.cex_download_config.run_time_window.1)
-                            .collect_vec()
-                    })
-                    .unique()
-                    .collect::<Vec<_>>();
-
-                query = query.replace(
-                    "block_number >= ? AND block_number < ?",
-                    &format!("block_number IN (SELECT arrayJoin({:?}) AS block_number)", vals),
-                );
-
+                let query = self.arbitrary_block_times_query(vals)?;
                 self.client.query_many(query, &()).await?
             }
         };
@@ -455,14 +703,7 @@ impl ClickhouseHandle for Clickhouse {
 
         let symbols: Vec<CexSymbols> = self.client.query_many(CEX_SYMBOLS, &()).await?;
 
-        let exchanges_str = self
-            .cex_download_config
-            .clone()
-            .exchanges_to_use
-            .into_iter()
-            .map(|s| s.to_clickhouse_filter().to_string())
-            .collect::<Vec<_>>()
-            .join(" OR ");
+        let exchanges = self.exchanges_filter();
 
         tracing::trace!("Fetching symbol ranks");
         let symbol_rank = self
@@ -486,35 +727,28 @@ impl ClickhouseHandle for Clickhouse {
                     .unwrap() as f64
                     + (MAX_MARKOUT_TIME * SECONDS_TO_US);
 
-                let mut query = RAW_CEX_QUOTES.to_string();
-                query = query.replace(
-                    "c.timestamp >= ? AND c.timestamp < ?",
-                    &format!(
-                        "c.timestamp >= {} AND c.timestamp < {} AND ({})",
-                        start_time, end_time, exchanges_str
-                    ),
-                );
+                let query = QueryBuilder::new(RAW_CEX_QUOTES)
+                    .replace(
+                        TIMESTAMP_RANGE_FILTER,
+                        &Clause::new(TIMESTAMP_RANGE_FILTER, &[&start_time, &end_time])
+                            .and(exchanges),
+                    )?
+                    .build();
 
                 self.query_many_with_retry(query, &()).await?
             }
             CexRangeOrArbitrary::Arbitrary(_) => {
-                let mut query = RAW_CEX_QUOTES.to_string();
-
-                let query_mod = block_times
-                    .iter()
-                    .map(|b| {
-                        b.convert_to_timestamp_query(
+                let query = QueryBuilder::new(RAW_CEX_QUOTES)
+                    .replace(
+                        TIMESTAMP_RANGE_FILTER,
+                        &block_times_filter(
+                            &block_times,
                             MAX_MARKOUT_TIME * SECONDS_TO_US,
                             MAX_MARKOUT_TIME * SECONDS_TO_US,
                         )
-                    })
-                    .collect::<Vec<String>>()
-                    .join(" OR ");
-
-                query = query.replace(
-                    "c.timestamp >= ? AND c.timestamp < ?",
-                    &format!("({query_mod}) AND ({exchanges_str})"),
-                );
+                        .and(exchanges),
+                    )?
+                    .build();
 
                 self.query_many_with_retry(query, &()).await?
             }
@@ -547,22 +781,8 @@ impl ClickhouseHandle for Clickhouse {
                 self.client.query_many(BLOCK_TIMES, &(s, e)).await?
             }
             CexRangeOrArbitrary::Arbitrary(vals) => {
-                let vals = vals
-                    .iter()
-                    .flat_map(|v| {
-                        (v - self.cex_download_config.run_time_window.0
-                            ..=v + self.cex_download_config.run_time_window.1)
-                            .collect_vec()
-                    })
-                    .unique()
-                    .collect::<Vec<_>>();
-
                 debug!("Querying block times for arbitrary values: {:?}", vals);
-                let mut query = BLOCK_TIMES.to_string();
-                query = query.replace(
-                    "block_number >= ? AND block_number < ?",
-                    &format!("block_number IN (SELECT arrayJoin({:?}) AS block_number)", vals),
-                );
+                let query = self.arbitrary_block_times_query(vals)?;
                 self.client.query_many(query, &()).await?
             }
         };
@@ -578,15 +798,8 @@ impl ClickhouseHandle for Clickhouse {
         let symbols: Vec<CexSymbols> = self.client.query_many(CEX_SYMBOLS, &()).await?;
         debug!("Retrieved {} CEX symbols", symbols.len());
 
-        let exchanges_str = self
-            .cex_download_config
-            .clone()
-            .exchanges_to_use
-            .into_iter()
-            .map(|s| s.to_clickhouse_filter().to_string())
-            .collect::<Vec<String>>()
-            .join(" OR ");
-        debug!("Using exchanges filter: {}", exchanges_str);
+        let exchanges = self.exchanges_filter();
+        debug!("Using exchanges filter: {}", exchanges.as_str());
 
         let data: Vec<RawCexTrades> = match range_or_arbitrary {
             CexRangeOrArbitrary::Range(..) => {
@@ -608,30 +821,25 @@ impl ClickhouseHandle for Clickhouse {
                     start_time, end_time
                 );
 
-                let mut query = RAW_CEX_TRADES.to_string();
-                query = query.replace(
-                    "c.timestamp >= ? AND c.timestamp < ?",
-                    &format!(
-                        "c.timestamp >= {start_time} AND c.timestamp < {end_time} 
-                        and ({exchanges_str})"
-                    ),
-                );
+                let query = QueryBuilder::new(RAW_CEX_TRADES)
+                    .replace(
+                        TIMESTAMP_RANGE_FILTER,
+                        &Clause::new(TIMESTAMP_RANGE_FILTER, &[&start_time, &end_time])
+                            .and(exchanges),
+                    )?
+                    .build();
                 self.query_many_with_retry(query, &()).await?
             }
             CexRangeOrArbitrary::Arbitrary(_) => {
-                let mut query = RAW_CEX_TRADES.to_string();
-                let query_mod = block_times
-                    .iter()
-                    .map(|b| b.convert_to_timestamp_query(6.0 * SECONDS_TO_US, 6.0 * SECONDS_TO_US))
-                    .collect::<Vec<String>>()
-                    .join(" OR ");
-
                 debug!("Querying raw CEX trades for arbitrary block times");
 
-                query = query.replace(
-                    "c.timestamp >= ? AND c.timestamp < ?",
-                    &format!("({query_mod}) AND ({exchanges_str})"),
-                );
+                let query = QueryBuilder::new(RAW_CEX_TRADES)
+                    .replace(
+                        TIMESTAMP_RANGE_FILTER,
+                        &block_times_filter(&block_times, 6.0 * SECONDS_TO_US, 6.0 * SECONDS_TO_US)
+                            .and(exchanges),
+                    )?
+                    .build();
                 self.query_many_with_retry(query, &()).await?
             }
         };
@@ -681,30 +889,23 @@ impl Clickhouse {
                     .await?
             }
             CexRangeOrArbitrary::Arbitrary(_) => {
-                let mut query = MOST_VOLUME_PAIR_EXCHANGE.to_string();
-
                 let times = block_times
                     .iter()
-                    .map(|block| {
-                        format!(
-                            "toStartOfMonth(toDateTime({} /  1000000) - INTERVAL 1 MONTH)",
-                            block.timestamp as f64
-                        )
-                    })
-                    .fold(String::new(), |mut acc, x| {
-                        if !acc.is_empty() {
-                            acc += ",";
-                        }
-
-                        acc += &x;
-                        acc
-                    });
-
-                query = query.replace(
-                    "month >= toStartOfMonth(toDateTime(? / 1000000) - toIntervalMonth(1))) AND \
-                     (month <= toStartOfMonth(toDateTime(? / 1000000) - toIntervalMonth(1))",
-                    &format!("month in (select arrayJoin([{}]) as month)", times),
-                );
+                    .map(|block| block.timestamp)
+                    .unique()
+                    .collect_vec();
+
+                let query = QueryBuilder::new(MOST_VOLUME_PAIR_EXCHANGE)
+                    .replace(
+                        MONTH_RANGE_FILTER,
+                        &Clause::new(
+                            "month IN (SELECT toStartOfMonth(toDateTime(arrayJoin(?) / 1000000) - \
+                             INTERVAL 1 MONTH) AS month)",
+                            &[&times],
+                        ),
+                    )
+                    .map_err(|e| DatabaseError::from(Custom(e.to_string())))?
+                    .build();
 
                 self.query_many_with_retry(query, &()).await?
             }
@@ -724,6 +925,55 @@ impl Clickhouse {
         self.query_many_with_retry(BLOCK_TIMES, &(start, end)).await
     }
 
+    /// Revenue, proposer payments and subsidies of each builder over the
+    /// inclusive block range, aggregated from the `brontes.builder_pnl` table.
+    pub async fn builder_pnl_stats(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<BuilderPnlStats>, DatabaseError> {
+        self.query_many_with_retry(BUILDER_PNL_STATS, &(start_block, end_block))
+            .await
+    }
+
+    /// Calldata fingerprints that were seen on more than one searcher contract
+    /// over the given block range, from the `brontes.searcher_fingerprints`
+    /// table. Fingerprints shared by more than `max_contracts` contracts are
+    /// skipped, as those are generic call layouts rather than a single bot.
+    pub async fn shared_searcher_fingerprints(
+        &self,
+        start_block: u64,
+        end_block: u64,
+        max_contracts: u64,
+    ) -> Result<Vec<SharedFingerprint>, DatabaseError> {
+        self.query_many_with_retry(
+            SHARED_SEARCHER_FINGERPRINTS,
+            &(start_block, end_block, max_contracts),
+        )
+        .await
+    }
+
+    /// All searcher contract bytecode hashes in the `brontes.searcher_bytecode`
+    /// table
+    pub async fn searcher_bytecodes(&self) -> Result<Vec<SearcherBytecode>, DatabaseError> {
+        self.query_many_with_retry(SEARCHER_BYTECODES, &()).await
+    }
+
+    pub async fn insert_searcher_bytecodes(
+        &self,
+        bytecodes: &[SearcherBytecode],
+    ) -> eyre::Result<()> {
+        if bytecodes.is_empty() {
+            return Ok(())
+        }
+
+        self.client
+            .insert_many::<BrontesSearcher_Bytecode>(bytecodes)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn get_cex_symbols(
         &self,
     ) -> Result<Vec<CexSymbols>, db_interfaces::errors::DatabaseError> {
@@ -735,38 +985,86 @@ impl Clickhouse {
         start_time: u64,
         end_time: u64,
     ) -> Result<Vec<RawCexQuotes>, db_interfaces::errors::DatabaseError> {
-        let exchanges_str = self.get_exchanges_filter_string();
-
-        let query = self.build_range_cex_quotes_query(start_time, end_time, &exchanges_str);
+        let query = QueryBuilder::new(RAW_CEX_QUOTES)
+            .replace(
+                TIMESTAMP_RANGE_FILTER,
+                &Clause::new(TIMESTAMP_RANGE_FILTER, &[&start_time, &end_time])
+                    .and(self.exchanges_filter()),
+            )
+            .map_err(|e| DatabaseError::from(Custom(e.to_string())))?
+            .build();
         self.query_many_with_retry(&query, &()).await
     }
 
-    fn get_exchanges_filter_string(&self) -> String {
-        self.cex_download_config
+    /// Filters cex data down to the exchanges we are configured to use
+    fn exchanges_filter(&self) -> Clause {
+        let exchanges = self
+            .cex_download_config
             .exchanges_to_use
             .iter()
-            .map(|s| s.to_clickhouse_filter().to_string())
-            .collect::<Vec<_>>()
-            .join(" OR ")
+            .map(|exchange| exchange.to_clickhouse_name())
+            .collect_vec();
+
+        Clause::new("has(?, c.exchange)", &[&exchanges])
     }
 
-    fn build_range_cex_quotes_query(
-        &self,
-        start_time: u64,
-        end_time: u64,
-        exchanges_str: &str,
-    ) -> String {
-        let query = RAW_CEX_QUOTES.to_string();
-        query.replace(
-            "c.timestamp >= ? AND c.timestamp < ?",
-            &format!(
-                "c.timestamp >= {} AND c.timestamp < {} AND ({})",
-                start_time, end_time, exchanges_str
-            ),
-        )
+    /// Block times query for the given blocks, padded by the run time window
+    fn arbitrary_block_times_query(&self, blocks: &[u64]) -> eyre::Result<String> {
+        let blocks = blocks
+            .iter()
+            .flat_map(|v| {
+                v - self.cex_download_config.run_time_window.0
+                    ..=v + self.cex_download_config.run_time_window.1
+            })
+            .unique()
+            .collect_vec();
+
+        Ok(QueryBuilder::new(BLOCK_TIMES)
+            .replace(BLOCK_RANGE_FILTER, &Clause::in_array("block_number", &blocks))?
+            .build())
     }
 }
 
+/// `OR`s together a timestamp window around each of the block times
+fn block_times_filter(block_times: &[BlockTimes], before_block: f64, after_block: f64) -> Clause {
+    Clause::any(block_times.iter().map(|b| {
+        Clause::new(
+            TIMESTAMP_RANGE_FILTER,
+            &[&(b.timestamp as f64 - before_block), &(b.timestamp as f64 + after_block)],
+        )
+    }))
+    .expect("block times are checked to be non empty")
+}
+
+/// Tables whose rows are derived from a single block
+const BLOCK_KEYED_TABLES: [&str; 25] = [
+    "mev.mev_blocks",
+    "mev.bundle_header",
+    "mev.searcher_tx",
+    "mev.cex_dex",
+    "mev.cex_dex_quotes",
+    "mev.liquidations",
+    "mev.jit_sandwich",
+    "mev.jit",
+    "mev.sandwiches",
+    "mev.atomic_arbs",
+    "mev.frontruns",
+    "mev.approval_races",
+    "brontes.dex_price_mapping",
+    "brontes.tree",
+    "brontes.block_analysis",
+    "brontes.gas_bids",
+    "brontes.searcher_block_bids",
+    "brontes.searcher_inventory",
+    "brontes.victim_execution",
+    "brontes.possible_mev_timeline",
+    "brontes.block_provenance",
+    "brontes.suppressed_bundles",
+    "brontes.searcher_fingerprints",
+    "brontes.inspector_runs",
+    "brontes.builder_pnl",
+];
+
 pub fn clickhouse_config() -> db_interfaces::clickhouse::config::ClickhouseConfig {
     let url = format!(
         "{}:{}",
@@ -779,38 +1077,35 @@ pub fn clickhouse_config() -> db_interfaces::clickhouse::config::ClickhouseConfi
     db_interfaces::clickhouse::config::ClickhouseConfig::new(user, pass, url, true, None)
 }
 
-fn format_arbitrary_query<T>(range: &'static [u64]) -> String
+fn format_arbitrary_query<T>(range: &'static [u64]) -> eyre::Result<String>
 where
     T: CompressedTable,
     T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
 {
-    let mut query = T::INIT_QUERY
-        .expect("no init query found for clickhouse query")
-        .to_string();
-
-    query = query.replace(
-        "block_number >= ? AND block_number < ?",
-        &format!("block_number IN (SELECT arrayJoin({:?}) AS block_number)", range),
-    );
-
-    query = query.replace(
-        "    ? AS start_block,
-    ? AS end_block",
-        &format!(
-            "    block_numbers AS (
-        SELECT
-            arrayJoin({:?}) AS block_number
-    )",
-            range
-        ),
-    );
-
-    query = query.replace(
-        "block_number >= start_block AND block_number < end_block",
-        "block_number in block_numbers",
-    );
+    let query = QueryBuilder::new(T::INIT_QUERY.expect("no init query found for clickhouse query"));
+
+    // the block range is either bound directly in the where clause or declared
+    // upfront in a `WITH` clause
+    if query.contains(BLOCK_RANGE_FILTER) {
+        return Ok(query
+            .replace(BLOCK_RANGE_FILTER, &Clause::in_array("block_number", range))?
+            .build())
+    }
 
-    query
+    Ok(query
+        .replace(
+            "    ? AS start_block,\n    ? AS end_block",
+            &Clause::new(
+                "    block_numbers AS (\n        SELECT\n            arrayJoin(?) AS \
+                 block_number\n    )",
+                &[&range],
+            ),
+        )?
+        .replace(
+            "block_number >= start_block AND block_number < end_block",
+            &Clause::new("block_number in block_numbers", &[]),
+        )?
+        .build())
 }
 
 #[derive(Debug, Serialize, Deserialize, clickhouse::Row)]
@@ -955,19 +1250,22 @@ mod tests {
 
     async fn cex_dex_quotes(db: &ClickhouseTestClient<BrontesClickhouseTables>) {
         let swap = NormalizedSwap {
-            protocol:    Protocol::UniswapV2,
-            from:        hex!("a69babef1ca67a37ffaf7a485dfff3382056e78c").into(),
-            recipient:   hex!("a69babef1ca67a37ffaf7a485dfff3382056e78c").into(),
-            pool:        hex!("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640").into(),
-            token_in:    TokenInfoWithAddress::weth(),
-            token_out:   TokenInfoWithAddress::usdc(),
-            amount_in:   Rational::from_unsigneds(
+            protocol: Protocol::UniswapV2,
+            from: hex!("a69babef1ca67a37ffaf7a485dfff3382056e78c").into(),
+            recipient: hex!("a69babef1ca67a37ffaf7a485dfff3382056e78c").into(),
+            pool: hex!("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640").into(),
+            token_in: TokenInfoWithAddress::weth(),
+            token_out: TokenInfoWithAddress::usdc(),
+            amount_in: Rational::from_unsigneds(
                 3122757495341445439573u128,
                 1000000000000000000u128,
             ),
-            amount_out:  Rational::from_unsigneds(1254253571443u64, 250000u64),
+            amount_out: Rational::from_unsigneds(1254253571443u64, 250000u64),
             trace_index: 2,
-            msg_value:   Uint::from(0),
+            msg_value: Uint::from(0),
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
         };
 
         let case0 = CexDexQuote {
@@ -991,6 +1289,10 @@ mod tests {
                 priority_fee:        0,
                 gas_used:            271686,
                 effective_gas_price: 8875282233,
+                gas_refunded:        0,
+                access_list_gas:     0,
+                blob_gas_used:       0,
+                blob_gas_price:      0,
             },
         };
 
@@ -1068,7 +1370,9 @@ mod tests {
             frontrun_swaps: vec![vec![swap0]],
             victim_swaps: vec![vec![swap1]],
             victim_swaps_gas_details: vec![gas_details],
-            backrun_swaps: vec![swap2],
+            backrun_tx_hash: vec![Default::default()],
+            backrun_swaps: vec![vec![swap2]],
+            backrun_gas_details: vec![gas_details],
             ..Sandwich::default()
         };
 
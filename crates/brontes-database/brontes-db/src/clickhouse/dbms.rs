@@ -1,8 +1,21 @@
 use brontes_types::{
     db::{
-        address_to_protocol_info::ProtocolInfoClickhouse, block_analysis::BlockAnalysis,
-        dex::DexQuotesWithBlockNumber, normalized_actions::TransactionRoot,
-        token_info::TokenInfoWithAddress, DbDataWithRunId, RunId,
+        address_to_protocol_info::ProtocolInfoClickhouse,
+        block_analysis::BlockAnalysis,
+        block_provenance::BlockProvenance,
+        builder_pnl::BuilderPnl,
+        dex::DexQuotesWithBlockNumber,
+        gas_bids::{GasBid, SearcherBlockBids},
+        inspector_run::InspectorRun,
+        normalized_actions::TransactionRoot,
+        possible_mev_timeline::PossibleMevTimeline,
+        searcher_bytecode::SearcherBytecode,
+        searcher_fingerprint::SearcherFingerprint,
+        searcher_inventory::SearcherInventoryDelta,
+        suppressed_bundle::SuppressedBundle,
+        token_info::TokenInfoWithAddress,
+        victim_execution::VictimExecution,
+        DbDataWithRunId, RunId,
     },
     mev::*,
 };
@@ -14,12 +27,25 @@ clickhouse_dbms!(
     [
         BrontesDex_Price_Mapping,
         BrontesBlock_Analysis,
+        BrontesGas_Bids,
+        BrontesSearcher_Block_Bids,
+        BrontesSearcher_Bytecode,
+        BrontesSearcher_Fingerprints,
+        BrontesSearcher_Inventory,
+        BrontesVictim_Execution,
+        BrontesPossible_Mev_Timeline,
+        BrontesBlock_Provenance,
+        BrontesSuppressed_Bundles,
+        BrontesInspector_Runs,
+        BrontesBuilder_Pnl,
         MevMev_Blocks,
         MevBundle_Header,
         MevSearcher_Tx,
         MevCex_Dex_Quotes,
         MevCex_Dex,
         MevLiquidations,
+        MevFrontruns,
+        MevApproval_Races,
         MevJit_Sandwich,
         MevJit,
         MevSandwiches,
@@ -55,6 +81,83 @@ remote_clickhouse_table!(
     "crates/brontes-database/brontes-db/src/clickhouse/tables/"
 );
 
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Gas_Bids],
+    DbDataWithRunId<GasBid>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Searcher_Block_Bids],
+    DbDataWithRunId<SearcherBlockBids>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Searcher_Bytecode],
+    SearcherBytecode,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Searcher_Fingerprints],
+    DbDataWithRunId<SearcherFingerprint>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Searcher_Inventory],
+    DbDataWithRunId<SearcherInventoryDelta>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Victim_Execution],
+    DbDataWithRunId<VictimExecution>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Possible_Mev_Timeline],
+    DbDataWithRunId<PossibleMevTimeline>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Block_Provenance],
+    DbDataWithRunId<BlockProvenance>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Suppressed_Bundles],
+    DbDataWithRunId<SuppressedBundle>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Inspector_Runs],
+    DbDataWithRunId<InspectorRun>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Brontes, Builder_Pnl],
+    DbDataWithRunId<BuilderPnl>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
 remote_clickhouse_table!(
     BrontesClickhouseTables,
     [Mev, Mev_Blocks],
@@ -97,6 +200,20 @@ remote_clickhouse_table!(
     "crates/brontes-database/brontes-db/src/clickhouse/tables/"
 );
 
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Mev, Frontruns],
+    DbDataWithRunId<Frontrun>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Mev, Approval_Races],
+    DbDataWithRunId<ApprovalRace>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
 remote_clickhouse_table!(
     BrontesClickhouseTables,
     [Mev, Jit_Sandwich],
@@ -236,6 +353,8 @@ db_types!(
     (CexDex, MevCex_Dex, true),
     (CexDexQuote, MevCex_Dex_Quotes, true),
     (Liquidation, MevLiquidations, true),
+    (Frontrun, MevFrontruns, true),
+    (ApprovalRace, MevApproval_Races, true),
     (JitLiquiditySandwich, MevJit_Sandwich, true),
     (JitLiquidity, MevJit, true),
     (Sandwich, MevSandwiches, true),
@@ -244,5 +363,16 @@ db_types!(
     (ProtocolInfoClickhouse, EthereumPools, false),
     (TransactionRoot, BrontesTree, true),
     (BlockAnalysis, BrontesBlock_Analysis, true),
+    (GasBid, BrontesGas_Bids, true),
+    (SearcherBlockBids, BrontesSearcher_Block_Bids, true),
+    (SearcherBytecode, BrontesSearcher_Bytecode, false),
+    (SearcherFingerprint, BrontesSearcher_Fingerprints, true),
+    (SearcherInventoryDelta, BrontesSearcher_Inventory, true),
+    (VictimExecution, BrontesVictim_Execution, true),
+    (PossibleMevTimeline, BrontesPossible_Mev_Timeline, true),
+    (BlockProvenance, BrontesBlock_Provenance, true),
+    (SuppressedBundle, BrontesSuppressed_Bundles, true),
+    (InspectorRun, BrontesInspector_Runs, true),
+    (BuilderPnl, BrontesBuilder_Pnl, true),
     (RunId, BrontesRun_Id, false)
 );
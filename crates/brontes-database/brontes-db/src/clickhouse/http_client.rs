@@ -1,18 +1,25 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address};
+use backon::{ExponentialBuilder, Retryable};
 use brontes_types::{
     db::{
         dex::{DexPrices, DexQuotes},
         metadata::{BlockMetadata, Metadata},
     },
     pair::Pair,
-    FastHashMap,
+    FastHashMap, FastHashSet,
 };
+use bytes::Bytes;
 use clickhouse::{remote_cursor::RemoteCursor, DbRow};
 use futures::TryStreamExt;
 use itertools::Itertools;
 use reqwest::StatusCode;
+use reth_db::table::Encode;
 use serde::Deserialize;
 
 use crate::{
@@ -21,31 +28,208 @@ use crate::{
     BlockInfo, BlockInfoData, CexPrice, CexPriceData, CompressedTable, DexPrice, DexPriceData,
 };
 
+/// Connection settings of a hosted brontes data api
+#[derive(Debug, Clone)]
+pub struct ClickhouseHttpConfig {
+    pub url:         String,
+    /// Key sent with every request. A new one is registered with the api if
+    /// none is set
+    pub api_key:     Option<String>,
+    /// Times a request is retried on network errors and server errors
+    pub max_retries: usize,
+    /// Directory the responses of block queries are cached in. Historical
+    /// block data doesn't change, so cached blocks are never fetched again.
+    /// Only responses holding every requested block are cached, the
+    /// directory is created once the first one is
+    pub cache_dir:   Option<PathBuf>,
+}
+
+impl ClickhouseHttpConfig {
+    pub fn new(url: String, api_key: Option<String>) -> Self {
+        Self { url, api_key, max_retries: 10, cache_dir: None }
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+}
+
+/// Client for the hosted brontes data api, or any api serving the same
+/// endpoints. Allows running the full pipeline without access to the
+/// clickhouse database
 pub struct ClickhouseHttpClient {
-    client:  reqwest::Client,
-    url:     String,
-    api_key: String,
+    client:      reqwest::Client,
+    url:         String,
+    api_key:     String,
+    max_retries: usize,
+    cache_dir:   Option<PathBuf>,
 }
 
 impl ClickhouseHttpClient {
-    pub async fn new(url: String, api_key: Option<String>) -> Self {
+    pub async fn new(config: ClickhouseHttpConfig) -> eyre::Result<Self> {
         let client = reqwest::Client::new();
-        let api_key = if let Some(key) = api_key {
-            key
-        } else {
-            let resp = client
-                .get(format!("{}/register", url))
-                .send()
-                .await
-                .unwrap();
-            if resp.status() == StatusCode::OK {
-                resp.text().await.unwrap()
+        let api_key = match config.api_key {
+            Some(key) => key,
+            None => Self::register(&client, &config.url).await?,
+        };
+
+        Ok(Self {
+            client,
+            url: config.url,
+            api_key,
+            max_retries: config.max_retries,
+            cache_dir: config.cache_dir,
+        })
+    }
+
+    async fn register(client: &reqwest::Client, url: &str) -> eyre::Result<String> {
+        let resp = client.get(format!("{url}/register")).send().await?;
+        let status = resp.status();
+        let text = resp.text().await?;
+
+        if status == StatusCode::OK {
+            return Ok(text)
+        }
+
+        // the api returns the existing key if this ip already registered one
+        text.split("key: ")
+            .nth(1)
+            .map(str::to_string)
+            .ok_or_else(|| eyre::eyre!("failed to register an api key, {status}: {text}"))
+    }
+
+    fn endpoint<T: CompressedTable>(&self) -> eyre::Result<&'static str> {
+        T::HTTP_ENDPOINT.ok_or_else(|| eyre::eyre!("no http endpoint is set for table {}", T::NAME))
+    }
+
+    fn cache_path(&self, endpoint: &str, params: &[(&'static str, String)]) -> Option<PathBuf> {
+        let cache_dir = self.cache_dir.as_ref()?;
+        let key = params
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .join("&");
+
+        Some(cache_dir.join(format!("{endpoint}-{}.bin", keccak256(key))))
+    }
+
+    /// Fetches the raw response of the endpoint, retrying on network and
+    /// server errors. Responses of block queries are served from the cache
+    /// when one is configured, otherwise the response is returned along with
+    /// the path to cache it at once it is known to be complete
+    async fn fetch(
+        &self,
+        endpoint: &str,
+        params: Vec<(&'static str, String)>,
+        cache: bool,
+    ) -> eyre::Result<(Bytes, Option<PathBuf>)> {
+        let cache_path = cache.then(|| self.cache_path(endpoint, &params)).flatten();
+
+        if let Some(path) = &cache_path {
+            match tokio::fs::read(path).await {
+                Ok(bytes) => {
+                    tracing::debug!(?path, "serving clickhouse http query from cache");
+                    return Ok((bytes.into(), None))
+                }
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    tracing::warn!(?path, error = %e, "failed to read cached response")
+                }
+                Err(_) => {}
+            }
+        }
+
+        let retry_strategy = ExponentialBuilder::default()
+            .with_max_times(self.max_retries)
+            .with_min_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(30));
+
+        let url = format!("{}/{endpoint}", self.url);
+        let bytes = (|| async {
+            let mut request = self.client.get(&url).header("api-key", &self.api_key);
+            for (name, value) in &params {
+                request = request.header(*name, value);
+            }
+
+            tracing::debug!(?request, "querying endpoint");
+            request.send().await?.error_for_status()?.bytes().await
+        })
+        .retry(&retry_strategy)
+        .when(|e: &reqwest::Error| {
+            e.is_connect()
+                || e.is_timeout()
+                || e.is_body()
+                || e.status().is_some_and(|status| {
+                    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                })
+        })
+        .notify(|e, dur| tracing::warn!(error = %e, ?dur, "retrying clickhouse http query"))
+        .await
+        .map_err(|e| match e.status() {
+            Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                eyre::eyre!("clickhouse http api rejected the api key: {e}")
+            }
+            Some(status_code) => {
+                tracing::error!(%status_code, "clickhouse http query");
+                e.into()
+            }
+            None => e.into(),
+        })?;
+
+        Ok((bytes, cache_path))
+    }
+
+    /// Writes the response to the cache. It is written to a temporary file
+    /// first, so that a response cut short by a crash is never served
+    async fn store(path: &Path, bytes: &Bytes) {
+        let tmp_path = path.with_extension("tmp");
+        let res = async {
+            if let Some(cache_dir) = path.parent() {
+                tokio::fs::create_dir_all(cache_dir).await?;
+            }
+            tokio::fs::write(&tmp_path, bytes).await?;
+            tokio::fs::rename(&tmp_path, path).await
+        }
+        .await;
+
+        if let Err(e) = res {
+            tracing::warn!(?path, error = %e, "failed to cache response");
+        }
+    }
+
+    /// Fetches and decodes the rows of the endpoint. Fetched responses are
+    /// only cached if `is_complete` holds for their rows, as the api answers
+    /// queries reaching past the blocks it has processed with the blocks it
+    /// has so far
+    async fn fetch_rows<D>(
+        &self,
+        endpoint: &str,
+        params: Vec<(&'static str, String)>,
+        cache: bool,
+        is_complete: impl FnOnce(&[D]) -> bool,
+    ) -> eyre::Result<Vec<D>>
+    where
+        D: DbRow + for<'de> Deserialize<'de> + Unpin,
+    {
+        let (bytes, cache_path) = self.fetch(endpoint, params, cache).await?;
+        let mut cur = RemoteCursor::new(futures::stream::once({
+            let bytes = bytes.clone();
+            async move { Ok::<_, reqwest::Error>(bytes) }
+        }));
+
+        let mut res = Vec::new();
+        while let Some(next) = cur.try_next().await? {
+            res.push(next)
+        }
+
+        if let Some(path) = cache_path {
+            if is_complete(&res) {
+                Self::store(&path, &bytes).await;
             } else {
-                let text = resp.text().await.unwrap();
-                text.split("key: ").collect_vec()[1].to_string()
+                tracing::debug!(?path, "not caching incomplete clickhouse http response");
             }
-        };
-        Self { url, api_key, client }
+        }
+
+        Ok(res)
     }
 
     fn process_dex_quotes(val: DexPriceData) -> DexQuotes {
@@ -112,6 +296,8 @@ impl ClickhouseHandle for ClickhouseHttpClient {
                 block_meta.value.proposer_mev_reward,
                 eth_price.unwrap_or_default(),
                 block_meta.value.private_flow.into_iter().collect(),
+                block_meta.value.blob_gas_used,
+                block_meta.value.excess_blob_gas,
             );
             metadata.into_metadata(cex_quotes.value, dex_quotes, None, None)
         })
@@ -130,40 +316,20 @@ impl ClickhouseHandle for ClickhouseHttpClient {
             + Unpin
             + 'static,
     {
-        let request = self
-            .client
-            .get(format!(
-                "{}/{}",
-                self.url,
-                T::HTTP_ENDPOINT.unwrap_or_else(|| panic!(
-                    "tried to init remote when no http endpoint was set {}",
-                    T::NAME
-                ))
-            ))
-            .header("api-key", &self.api_key)
-            .header("start-block", start_block)
-            .header("end-block", end_block)
-            .build()?;
-
-        tracing::debug!(?request, "querying endpoint");
-
-        let mut cur = RemoteCursor::new(
-            self.client
-                .execute(request)
-                .await
-                .inspect_err(|e| {
-                    if let Some(status_code) = e.status() {
-                        tracing::error!(%status_code, "clickhouse http query")
-                    }
-                })?
-                .bytes_stream(),
-        );
-        let mut res = Vec::new();
-        while let Some(next) = cur.try_next().await? {
-            res.push(next)
-        }
+        let params =
+            vec![("start-block", start_block.to_string()), ("end-block", end_block.to_string())];
+
+        // the range is exclusive of the end block
+        let is_complete = |rows: &[D]| {
+            start_block >= end_block
+                || rows
+                    .iter()
+                    .rev()
+                    .any(|row| key_block_number(row.into_key_val().key) == Some(end_block - 1))
+        };
 
-        Ok(res)
+        self.fetch_rows(self.endpoint::<T>()?, params, true, is_complete)
+            .await
     }
 
     async fn query_many<T, D>(&self) -> eyre::Result<Vec<D>>
@@ -179,28 +345,9 @@ impl ClickhouseHandle for ClickhouseHttpClient {
             + Unpin
             + 'static,
     {
-        let mut cur = RemoteCursor::new(
-            self.client
-                .get(format!(
-                    "{}/{}",
-                    self.url,
-                    T::HTTP_ENDPOINT.unwrap_or_else(|| panic!(
-                        "tried to init remote when no http endpoint was set {}",
-                        T::NAME
-                    ))
-                ))
-                .header("api-key", &self.api_key)
-                .send()
-                .await?
-                .bytes_stream(),
-        );
-
-        let mut res = Vec::new();
-        while let Some(next) = cur.try_next().await? {
-            res.push(next)
-        }
-
-        Ok(res)
+        // whole tables are updated over time, so they are never cached
+        self.fetch_rows(self.endpoint::<T>()?, vec![], false, |_| false)
+            .await
     }
 
     async fn query_many_arbitrary<T, D>(&self, range: &'static [u64]) -> eyre::Result<Vec<D>>
@@ -216,70 +363,67 @@ impl ClickhouseHandle for ClickhouseHttpClient {
             + Unpin
             + 'static,
     {
-        let range_str = range
-            .iter()
-            .map(|n| n.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let request = self
-            .client
-            .get(format!(
-                "{}/{}",
-                self.url,
-                T::HTTP_ENDPOINT.unwrap_or_else(|| panic!(
-                    "tried to init remote when no http endpoint was set {}",
-                    T::NAME
-                ))
-            ))
-            .header("api-key", &self.api_key)
-            .header("block-set", range_str)
-            .build()?;
-
-        tracing::debug!(?request, "querying endpoint");
-
-        let mut cur = RemoteCursor::new(
-            self.client
-                .execute(request)
-                .await
-                .inspect_err(|e| {
-                    if let Some(status_code) = e.status() {
-                        tracing::error!(%status_code, "clickhouse http query")
-                    }
-                })?
-                .bytes_stream(),
-        );
-
-        let mut res = Vec::new();
-        while let Some(next) = cur.try_next().await? {
-            res.push(next)
-        }
+        let range_str = range.iter().map(|n| n.to_string()).join(",");
+
+        let is_complete = |rows: &[D]| {
+            let blocks = rows
+                .iter()
+                .filter_map(|row| key_block_number(row.into_key_val().key))
+                .collect::<FastHashSet<_>>();
+            range.iter().all(|block| blocks.contains(block))
+        };
 
-        Ok(res)
+        self.fetch_rows(self.endpoint::<T>()?, vec![("block-set", range_str)], true, is_complete)
+            .await
     }
 
     async fn get_cex_prices(
         &self,
-        _range_or_arbitrary: CexRangeOrArbitrary,
+        range_or_arbitrary: CexRangeOrArbitrary,
     ) -> eyre::Result<Vec<crate::CexPriceData>> {
-        unimplemented!()
+        match range_or_arbitrary {
+            CexRangeOrArbitrary::Range(start_block, end_block) => {
+                self.query_many_range::<CexPrice, CexPriceData>(start_block, end_block)
+                    .await
+            }
+            CexRangeOrArbitrary::Arbitrary(blocks) => {
+                self.query_many_arbitrary::<CexPrice, CexPriceData>(blocks)
+                    .await
+            }
+        }
     }
 
     async fn get_cex_trades(
         &self,
         _range_or_arbitrary: CexRangeOrArbitrary,
     ) -> eyre::Result<Vec<crate::CexTradesData>> {
-        unimplemented!()
+        Err(eyre::eyre!("cex trades are not served by the clickhouse http api"))
     }
 }
 
+/// Block number of the key of a table keyed by block. These keys are encoded
+/// with the big endian block number first, so that they sort by block
+fn key_block_number<K: Encode>(key: K) -> Option<u64> {
+    let encoded = key.encode();
+    let block = encoded.as_ref().get(..8)?;
+
+    Some(u64::from_be_bytes(block.try_into().ok()?))
+}
+
 #[cfg(test)]
 pub mod test {
 
-    use brontes_types::constants::USDT_ADDRESS;
+    use brontes_types::{constants::USDT_ADDRESS, db::dex::make_key};
 
+    use super::key_block_number;
     use crate::{clickhouse::ClickhouseHandle, libmdbx::test_utils::load_clickhouse};
 
+    #[test]
+    fn test_key_block_number() {
+        assert_eq!(key_block_number(18500000u64), Some(18500000));
+        assert_eq!(key_block_number(make_key(18500000, 42)), Some(18500000));
+    }
+
     #[brontes_macros::test]
     async fn test_metadata_query() {
         let click_house = load_clickhouse().await;
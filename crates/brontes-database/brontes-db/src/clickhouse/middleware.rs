@@ -1,18 +1,36 @@
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, Selector};
 use brontes_types::{
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        backfill_checkpoint::BackfillCheckpoint,
         block_analysis::BlockAnalysis,
+        block_provenance::BlockProvenance,
         builder::BuilderInfo,
+        builder_pnl::BuilderPnl,
+        classification_failure::BlockClassificationFailures,
+        curve_pool_params::{CurveParamsUpdate, CurvePoolParams},
         dex::DexQuotes,
+        function_selector::FunctionSelector,
+        gas_bids::{GasBid, SearcherBlockBids},
+        inspector_run::{BlockInspectorRuns, InspectorRun},
         metadata::Metadata,
         mev_block::MevBlockWithClassified,
+        pool_fee_tier::PoolFeeTier,
+        possible_mev_timeline::PossibleMevTimeline,
+        protocol_metadata::ProtocolMetadata,
+        sanctions::SanctionInfo,
         searcher::SearcherInfo,
+        searcher_fingerprint::SearcherFingerprint,
+        searcher_funding::SearcherFunding,
+        searcher_inventory::SearcherInventoryDelta,
+        suppressed_bundle::SuppressedBundle,
         token_info::TokenInfoWithAddress,
+        token_tax::TokenTax,
         traits::{DBWriter, LibmdbxReader, ProtocolCreatedRange},
+        victim_execution::VictimExecution,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -43,7 +61,7 @@ impl<I: DBWriter> ClickhouseMiddleware<I> {
     }
 }
 
-impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
+impl<I: DBWriter + LibmdbxReader> DBWriter for ClickhouseMiddleware<I> {
     type Inner = I;
 
     fn inner(&self) -> &Self::Inner {
@@ -54,6 +72,66 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
         self.client.block_analysis(block_analysis).await
     }
 
+    async fn write_gas_bids(&self, gas_bids: Vec<GasBid>) -> eyre::Result<()> {
+        self.client.write_gas_bids(gas_bids).await
+    }
+
+    async fn write_searcher_block_bids(
+        &self,
+        block_bids: Vec<SearcherBlockBids>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_block_bids(block_bids).await
+    }
+
+    async fn write_searcher_fingerprints(
+        &self,
+        fingerprints: Vec<SearcherFingerprint>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_fingerprints(fingerprints).await
+    }
+
+    async fn write_searcher_inventory(
+        &self,
+        inventory: Vec<SearcherInventoryDelta>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_inventory(inventory).await
+    }
+
+    async fn write_victim_execution(&self, executions: Vec<VictimExecution>) -> eyre::Result<()> {
+        self.client.write_victim_execution(executions).await
+    }
+
+    async fn write_possible_mev_timeline(
+        &self,
+        timeline: Vec<PossibleMevTimeline>,
+    ) -> eyre::Result<()> {
+        self.client.write_possible_mev_timeline(timeline).await
+    }
+
+    async fn write_block_provenance(&self, provenance: BlockProvenance) -> eyre::Result<()> {
+        self.client.write_block_provenance(provenance).await
+    }
+
+    async fn write_suppressed_bundles(&self, bundles: Vec<SuppressedBundle>) -> eyre::Result<()> {
+        self.client.write_suppressed_bundles(bundles).await
+    }
+
+    async fn write_inspector_runs(
+        &self,
+        block_number: u64,
+        runs: Vec<InspectorRun>,
+    ) -> eyre::Result<()> {
+        self.client.write_inspector_runs(runs.clone()).await?;
+
+        self.inner().write_inspector_runs(block_number, runs).await
+    }
+
+    async fn write_builder_pnl(&self, builder_pnl: BuilderPnl) -> eyre::Result<()> {
+        self.client.write_builder_pnl(builder_pnl.clone()).await?;
+
+        self.inner().write_builder_pnl(builder_pnl).await
+    }
+
     async fn write_dex_quotes(
         &self,
         block_number: u64,
@@ -94,6 +172,30 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
         self.inner().save_mev_blocks(block_number, block, mev).await
     }
 
+    async fn invalidate_blocks(&self, blocks: RangeInclusive<u64>) -> eyre::Result<()> {
+        self.client.invalidate_blocks(blocks.clone()).await?;
+
+        self.inner().invalidate_blocks(blocks).await
+    }
+
+    /// Replaces libmdbx first and then brings clickhouse in line with what
+    /// libmdbx stored. The clickhouse step is idempotent, so if it fails the
+    /// replay can be rerun without the two stores diverging.
+    async fn replace_mev_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> eyre::Result<()> {
+        self.inner()
+            .replace_mev_blocks(blocks.clone(), mev_blocks)
+            .await?;
+
+        let mev_blocks = self
+            .inner()
+            .try_fetch_mev_blocks(Some(*blocks.start()), *blocks.end())?;
+        self.client.replace_mev_blocks(blocks, mev_blocks).await
+    }
+
     async fn write_searcher_eoa_info(
         &self,
         searcher_eoa: Address,
@@ -166,6 +268,20 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
 
         self.inner().save_traces(block, traces).await
     }
+
+    /// The checkpoint marks the blocks as done, so it's only saved once their
+    /// buffered clickhouse rows are inserted
+    async fn save_backfill_checkpoint(
+        &self,
+        start_block: u64,
+        checkpoint: BackfillCheckpoint,
+    ) -> eyre::Result<()> {
+        self.client.wait_for_inserts().await?;
+
+        self.inner()
+            .save_backfill_checkpoint(start_block, checkpoint)
+            .await
+    }
 }
 
 impl<I: LibmdbxInit> LibmdbxInit for ClickhouseMiddleware<I> {
@@ -231,6 +347,24 @@ impl<I: LibmdbxInit> LibmdbxInit for ClickhouseMiddleware<I> {
             .await
     }
 
+    async fn refresh_sanctioned_addresses<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        self.inner
+            .refresh_sanctioned_addresses(clickhouse, tracer)
+            .await
+    }
+
+    async fn reload_config<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        self.inner.reload_config(clickhouse, tracer).await
+    }
+
     fn state_to_initialize(
         &self,
         start_block: u64,
@@ -313,6 +447,88 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.fetch_all_builder_info()
     }
 
+    fn try_fetch_sanction_info(&self, address: Address) -> eyre::Result<Option<SanctionInfo>> {
+        self.inner.try_fetch_sanction_info(address)
+    }
+
+    fn try_fetch_curve_pool_params(
+        &self,
+        address: Address,
+    ) -> eyre::Result<Option<CurvePoolParams>> {
+        self.inner.try_fetch_curve_pool_params(address)
+    }
+
+    fn try_fetch_function_selector(
+        &self,
+        selector: Selector,
+    ) -> eyre::Result<Option<FunctionSelector>> {
+        self.inner.try_fetch_function_selector(selector)
+    }
+
+    fn try_fetch_searcher_funding(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<SearcherFunding>> {
+        self.inner.try_fetch_searcher_funding(searcher_eoa)
+    }
+
+    fn try_fetch_token_tax(&self, token: Address) -> eyre::Result<Option<TokenTax>> {
+        self.inner.try_fetch_token_tax(token)
+    }
+
+    fn try_fetch_pool_fee_tier(&self, pool: Address) -> eyre::Result<Option<PoolFeeTier>> {
+        self.inner.try_fetch_pool_fee_tier(pool)
+    }
+
+    fn try_fetch_inspector_runs(&self, block_num: u64) -> eyre::Result<Option<BlockInspectorRuns>> {
+        self.inner.try_fetch_inspector_runs(block_num)
+    }
+
+    fn try_fetch_classification_failures(
+        &self,
+        block_num: u64,
+    ) -> eyre::Result<Option<BlockClassificationFailures>> {
+        self.inner.try_fetch_classification_failures(block_num)
+    }
+
+    fn try_fetch_builder_pnl(&self, block_num: u64) -> eyre::Result<Option<BuilderPnl>> {
+        self.inner.try_fetch_builder_pnl(block_num)
+    }
+
+    fn fetch_builder_pnls(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<BuilderPnl>> {
+        self.inner.fetch_builder_pnls(start_block, end_block)
+    }
+
+    fn try_fetch_protocol_metadata(
+        &self,
+        protocol: Protocol,
+    ) -> eyre::Result<Option<ProtocolMetadata>> {
+        self.inner.try_fetch_protocol_metadata(protocol)
+    }
+
+    fn fetch_protocol_registry(&self) -> eyre::Result<Vec<ProtocolMetadata>> {
+        self.inner.fetch_protocol_registry()
+    }
+
+    fn fetch_backfill_checkpoints(&self) -> eyre::Result<Vec<(u64, BackfillCheckpoint)>> {
+        self.inner.fetch_backfill_checkpoints()
+    }
+
+    fn fetch_sanctioned(
+        &self,
+        addresses: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SanctionInfo>> {
+        self.inner.fetch_sanctioned(addresses)
+    }
+
+    fn try_fetch_mev_block(&self, block: u64) -> eyre::Result<Option<MevBlockWithClassified>> {
+        self.inner.try_fetch_mev_block(block)
+    }
+
     //TODO: JOE
     fn try_fetch_mev_blocks(
         &self,
@@ -378,6 +594,10 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.get_protocol_details(address)
     }
 
+    fn prefetch_classification_data(&self, addresses: &[Address]) -> eyre::Result<()> {
+        self.inner.prefetch_classification_data(addresses)
+    }
+
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
         self.inner.load_trace(block_num)
     }
@@ -406,6 +626,62 @@ impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
         self.client.block_analysis(block_analysis).await
     }
 
+    async fn write_gas_bids(&self, gas_bids: Vec<GasBid>) -> eyre::Result<()> {
+        self.client.write_gas_bids(gas_bids).await
+    }
+
+    async fn write_searcher_block_bids(
+        &self,
+        block_bids: Vec<SearcherBlockBids>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_block_bids(block_bids).await
+    }
+
+    async fn write_searcher_fingerprints(
+        &self,
+        fingerprints: Vec<SearcherFingerprint>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_fingerprints(fingerprints).await
+    }
+
+    async fn write_searcher_inventory(
+        &self,
+        inventory: Vec<SearcherInventoryDelta>,
+    ) -> eyre::Result<()> {
+        self.client.write_searcher_inventory(inventory).await
+    }
+
+    async fn write_victim_execution(&self, executions: Vec<VictimExecution>) -> eyre::Result<()> {
+        self.client.write_victim_execution(executions).await
+    }
+
+    async fn write_possible_mev_timeline(
+        &self,
+        timeline: Vec<PossibleMevTimeline>,
+    ) -> eyre::Result<()> {
+        self.client.write_possible_mev_timeline(timeline).await
+    }
+
+    async fn write_block_provenance(&self, provenance: BlockProvenance) -> eyre::Result<()> {
+        self.client.write_block_provenance(provenance).await
+    }
+
+    async fn write_suppressed_bundles(&self, bundles: Vec<SuppressedBundle>) -> eyre::Result<()> {
+        self.client.write_suppressed_bundles(bundles).await
+    }
+
+    async fn write_inspector_runs(
+        &self,
+        _block_number: u64,
+        runs: Vec<InspectorRun>,
+    ) -> eyre::Result<()> {
+        self.client.write_inspector_runs(runs).await
+    }
+
+    async fn write_builder_pnl(&self, builder_pnl: BuilderPnl) -> eyre::Result<()> {
+        self.client.write_builder_pnl(builder_pnl).await
+    }
+
     async fn write_dex_quotes(
         &self,
         block_number: u64,
@@ -438,6 +714,18 @@ impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
             .await
     }
 
+    async fn invalidate_blocks(&self, blocks: RangeInclusive<u64>) -> eyre::Result<()> {
+        self.client.invalidate_blocks(blocks).await
+    }
+
+    async fn replace_mev_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> eyre::Result<()> {
+        self.client.replace_mev_blocks(blocks, mev_blocks).await
+    }
+
     async fn write_searcher_eoa_info(
         &self,
         searcher_eoa: Address,
@@ -487,6 +775,65 @@ impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
         Ok(())
     }
 
+    /// the parameters are only kept in libmdbx, which this middleware doesn't
+    /// write to
+    async fn update_curve_pool_params(
+        &self,
+        _address: Address,
+        _block: u64,
+        _update: CurveParamsUpdate,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// funding is only kept in libmdbx, which this middleware doesn't write to
+    async fn write_searcher_funding(
+        &self,
+        _funding: Vec<(Address, SearcherFunding)>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// fee tiers are only kept in libmdbx, which this middleware doesn't write
+    /// to
+    async fn insert_pool_fee_tier(
+        &self,
+        _address: Address,
+        _fee_tier: PoolFeeTier,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// taxes are only kept in libmdbx, which this middleware doesn't write to
+    async fn write_token_taxes(&self, _taxes: Vec<(Address, TokenTax)>) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// the registry is only kept in libmdbx, which this middleware doesn't
+    /// write to
+    async fn write_protocol_metadata(&self, _metadata: ProtocolMetadata) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// the selectors are only kept in libmdbx, which this middleware doesn't
+    /// write to
+    async fn write_function_selectors(
+        &self,
+        _selectors: Vec<FunctionSelector>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// checkpoints are only kept in libmdbx, which this middleware doesn't
+    /// write to
+    async fn save_backfill_checkpoint(
+        &self,
+        _start_block: u64,
+        _checkpoint: BackfillCheckpoint,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
     async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
         self.client.save_traces(block, traces.clone()).await
     }
@@ -555,6 +902,24 @@ impl<I: LibmdbxInit> LibmdbxInit for ReadOnlyMiddleware<I> {
             .await
     }
 
+    async fn refresh_sanctioned_addresses<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        self.inner
+            .refresh_sanctioned_addresses(clickhouse, tracer)
+            .await
+    }
+
+    async fn reload_config<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        self.inner.reload_config(clickhouse, tracer).await
+    }
+
     fn state_to_initialize(
         &self,
         start_block: u64,
@@ -631,6 +996,88 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.fetch_all_builder_info()
     }
 
+    fn try_fetch_sanction_info(&self, address: Address) -> eyre::Result<Option<SanctionInfo>> {
+        self.inner.try_fetch_sanction_info(address)
+    }
+
+    fn try_fetch_curve_pool_params(
+        &self,
+        address: Address,
+    ) -> eyre::Result<Option<CurvePoolParams>> {
+        self.inner.try_fetch_curve_pool_params(address)
+    }
+
+    fn try_fetch_function_selector(
+        &self,
+        selector: Selector,
+    ) -> eyre::Result<Option<FunctionSelector>> {
+        self.inner.try_fetch_function_selector(selector)
+    }
+
+    fn try_fetch_searcher_funding(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<SearcherFunding>> {
+        self.inner.try_fetch_searcher_funding(searcher_eoa)
+    }
+
+    fn try_fetch_token_tax(&self, token: Address) -> eyre::Result<Option<TokenTax>> {
+        self.inner.try_fetch_token_tax(token)
+    }
+
+    fn try_fetch_pool_fee_tier(&self, pool: Address) -> eyre::Result<Option<PoolFeeTier>> {
+        self.inner.try_fetch_pool_fee_tier(pool)
+    }
+
+    fn try_fetch_inspector_runs(&self, block_num: u64) -> eyre::Result<Option<BlockInspectorRuns>> {
+        self.inner.try_fetch_inspector_runs(block_num)
+    }
+
+    fn try_fetch_classification_failures(
+        &self,
+        block_num: u64,
+    ) -> eyre::Result<Option<BlockClassificationFailures>> {
+        self.inner.try_fetch_classification_failures(block_num)
+    }
+
+    fn try_fetch_builder_pnl(&self, block_num: u64) -> eyre::Result<Option<BuilderPnl>> {
+        self.inner.try_fetch_builder_pnl(block_num)
+    }
+
+    fn fetch_builder_pnls(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<BuilderPnl>> {
+        self.inner.fetch_builder_pnls(start_block, end_block)
+    }
+
+    fn try_fetch_protocol_metadata(
+        &self,
+        protocol: Protocol,
+    ) -> eyre::Result<Option<ProtocolMetadata>> {
+        self.inner.try_fetch_protocol_metadata(protocol)
+    }
+
+    fn fetch_protocol_registry(&self) -> eyre::Result<Vec<ProtocolMetadata>> {
+        self.inner.fetch_protocol_registry()
+    }
+
+    fn fetch_backfill_checkpoints(&self) -> eyre::Result<Vec<(u64, BackfillCheckpoint)>> {
+        self.inner.fetch_backfill_checkpoints()
+    }
+
+    fn fetch_sanctioned(
+        &self,
+        addresses: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SanctionInfo>> {
+        self.inner.fetch_sanctioned(addresses)
+    }
+
+    fn try_fetch_mev_block(&self, block: u64) -> eyre::Result<Option<MevBlockWithClassified>> {
+        self.inner.try_fetch_mev_block(block)
+    }
+
     //TODO: JOE
     fn try_fetch_mev_blocks(
         &self,
@@ -696,6 +1143,10 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.get_protocol_details(address)
     }
 
+    fn prefetch_classification_data(&self, addresses: &[Address]) -> eyre::Result<()> {
+        self.inner.prefetch_classification_data(addresses)
+    }
+
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
         self.inner.load_trace(block_num)
     }
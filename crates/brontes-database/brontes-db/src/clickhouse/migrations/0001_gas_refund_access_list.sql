@@ -0,0 +1,96 @@
+-- Adds the gas refund and access list gas of GasDetails to the tables created
+-- before they were written. Tuple columns can't grow in place, so they are
+-- rebuilt from the old elements with the new ones set to 0.
+
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`frontrun_gas_details`, 1), tupleElement(`frontrun_gas_details`, 2), tupleElement(`frontrun_gas_details`, 3), tupleElement(`frontrun_gas_details`, 4), 0, 0);
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `frontrun_gas_details_new`;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 MODIFY COLUMN `frontrun_gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 DROP COLUMN `frontrun_gas_details`;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 RENAME COLUMN `frontrun_gas_details_new` TO `frontrun_gas_details`;
+
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`victim_gas_details`, 1), tupleElement(`victim_gas_details`, 2), tupleElement(`victim_gas_details`, 3), tupleElement(`victim_gas_details`, 4), 0, 0);
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `victim_gas_details_new`;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 MODIFY COLUMN `victim_gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 DROP COLUMN `victim_gas_details`;
+ALTER TABLE mev.approval_races ON CLUSTER eth_cluster0 RENAME COLUMN `victim_gas_details_new` TO `victim_gas_details`;
+
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`frontrun_gas_details`, 1), tupleElement(`frontrun_gas_details`, 2), tupleElement(`frontrun_gas_details`, 3), tupleElement(`frontrun_gas_details`, 4), 0, 0);
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `frontrun_gas_details_new`;
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 MODIFY COLUMN `frontrun_gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 DROP COLUMN `frontrun_gas_details`;
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 RENAME COLUMN `frontrun_gas_details_new` TO `frontrun_gas_details`;
+
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_mint_gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`frontrun_mint_gas_details`, 1), tupleElement(`frontrun_mint_gas_details`, 2), tupleElement(`frontrun_mint_gas_details`, 3), tupleElement(`frontrun_mint_gas_details`, 4), 0, 0);
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `frontrun_mint_gas_details_new`;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 MODIFY COLUMN `frontrun_mint_gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 DROP COLUMN `frontrun_mint_gas_details`;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 RENAME COLUMN `frontrun_mint_gas_details_new` TO `frontrun_mint_gas_details`;
+
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_burn_gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`backrun_burn_gas_details`, 1), tupleElement(`backrun_burn_gas_details`, 2), tupleElement(`backrun_burn_gas_details`, 3), tupleElement(`backrun_burn_gas_details`, 4), 0, 0);
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `backrun_burn_gas_details_new`;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 MODIFY COLUMN `backrun_burn_gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 DROP COLUMN `backrun_burn_gas_details`;
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 RENAME COLUMN `backrun_burn_gas_details_new` TO `backrun_burn_gas_details`;
+
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(`coinbase_transfer` Nullable(UInt128), `priority_fee` UInt128, `gas_used` UInt128, `effective_gas_price` UInt128, `gas_refunded` UInt128, `access_list_gas` UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE mev.searcher_tx ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(Nullable(UInt128), UInt128, UInt128, UInt128, UInt128, UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE mev.searcher_tx ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE mev.searcher_tx ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE mev.searcher_tx ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE mev.searcher_tx ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE brontes.tree ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `gas_details_new` Tuple(coinbase_transfer Nullable(UInt128), priority_fee UInt128, gas_used UInt128, effective_gas_price UInt128, gas_refunded UInt128, access_list_gas UInt128) DEFAULT tuple(tupleElement(`gas_details`, 1), tupleElement(`gas_details`, 2), tupleElement(`gas_details`, 3), tupleElement(`gas_details`, 4), 0, 0);
+ALTER TABLE brontes.tree ON CLUSTER eth_cluster0 MATERIALIZE COLUMN `gas_details_new`;
+ALTER TABLE brontes.tree ON CLUSTER eth_cluster0 MODIFY COLUMN `gas_details_new` REMOVE DEFAULT;
+ALTER TABLE brontes.tree ON CLUSTER eth_cluster0 DROP COLUMN `gas_details`;
+ALTER TABLE brontes.tree ON CLUSTER eth_cluster0 RENAME COLUMN `gas_details_new` TO `gas_details`;
+
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `frontrun_gas_details.tx_hash`);
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `frontrun_gas_details.tx_hash`);
+
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `backrun_gas_details.tx_hash`);
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `backrun_gas_details.tx_hash`);
+
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `frontrun_gas_details.tx_hash`);
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `frontrun_gas_details.tx_hash`);
+
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `victim_gas_details.tx_hash`);
+
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `backrun_gas_details.tx_hash`);
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `backrun_gas_details.tx_hash`);
+
+ALTER TABLE mev.mev_blocks ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `possible_mev.gas_details.gas_refunded` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `possible_mev.tx_hash`);
+ALTER TABLE mev.mev_blocks ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `possible_mev.gas_details.access_list_gas` Array(UInt128) DEFAULT arrayMap(x -> toUInt128(0), `possible_mev.tx_hash`);
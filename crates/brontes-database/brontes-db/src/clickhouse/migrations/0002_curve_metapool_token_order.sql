@@ -0,0 +1,12 @@
+-- Curve metapools used to be stored with the meta coin after the coins of the
+-- base pool, while the classifiers resolve the underlying coins with the meta
+-- coin first. Moves the meta coin to the front of the pools that are still in
+-- the old order: the tokens before the last one are the coins of a base pool
+-- and the tokens after the first one aren't, so pools already in the current
+-- order are left as they are. The subqueries read the table itself, which
+-- mutations only allow with `allow_nondeterministic_mutations`.
+-- libmdbx reorders its AddressToProtocolInfo entries on start.
+
+SET allow_nondeterministic_mutations = 1;
+
+ALTER TABLE ethereum.pools ON CLUSTER eth_cluster0 UPDATE `tokens` = arrayPushFront(arrayPopBack(`tokens`), `tokens`[-1]) WHERE `protocol` = 'Curve.fi' AND `protocol_subtype` IN ('V1 Metapool', 'V1 Metapool Impl', 'V2 Metapool', 'V2 Metapool Impl', 'crvUSD Metapool', 'crvUSD Metapool Impl') AND arrayPopBack(`tokens`) IN (SELECT `tokens` FROM ethereum.pools WHERE `protocol` = 'Curve.fi' AND `protocol_subtype` IN ('Base', 'V2 Plain', 'V2 Plain Impl', 'crvUSD Plain', 'crvUSD Plain Impl')) AND arrayPopFront(`tokens`) NOT IN (SELECT `tokens` FROM ethereum.pools WHERE `protocol` = 'Curve.fi' AND `protocol_subtype` IN ('Base', 'V2 Plain', 'V2 Plain Impl', 'crvUSD Plain', 'crvUSD Plain Impl'));
@@ -0,0 +1,24 @@
+-- Adds the fee tier of the pool to the swaps of the tables created before it
+-- was written. Nested columns are separate arrays, so the new one is filled
+-- with a null per swap of the existing rows.
+
+ALTER TABLE mev.atomic_arbs ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `swaps.trace_idx`);
+
+ALTER TABLE mev.cex_dex ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `swaps.trace_idx`);
+
+ALTER TABLE mev.cex_dex_quotes ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `swaps.trace_idx`);
+
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `frontrun_swaps.trace_idx`);
+ALTER TABLE mev.frontruns ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `victim_swaps.tx_hash`);
+
+ALTER TABLE mev.jit ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `victim_swaps.tx_hash`);
+
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `frontrun_swaps.tx_hash`);
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `victim_swaps.tx_hash`);
+ALTER TABLE mev.jit_sandwich ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `backrun_swaps.tx_hash`);
+
+ALTER TABLE mev.liquidations ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `liquidation_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `liquidation_swaps.trace_idx`);
+
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `frontrun_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `frontrun_swaps.tx_hash`);
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `victim_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `victim_swaps.tx_hash`);
+ALTER TABLE mev.sandwiches ON CLUSTER eth_cluster0 ADD COLUMN IF NOT EXISTS `backrun_swaps.fee_tier` Array(Nullable(UInt32)) DEFAULT arrayMap(x -> CAST(NULL, 'Nullable(UInt32)'), `backrun_swaps.tx_hash`);
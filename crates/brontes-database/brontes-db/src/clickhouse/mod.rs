@@ -8,6 +8,8 @@ pub mod errors;
 #[cfg(feature = "local-clickhouse")]
 pub use db_client::*;
 #[cfg(feature = "local-clickhouse")]
+pub mod query;
+#[cfg(feature = "local-clickhouse")]
 pub mod split_db;
 #[cfg(feature = "local-clickhouse")]
 pub use db_interfaces::clickhouse::config::ClickhouseConfig;
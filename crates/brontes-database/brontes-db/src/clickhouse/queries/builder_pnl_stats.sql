@@ -0,0 +1,15 @@
+SELECT
+    builder_address,
+    any(builder_name) AS builder_name,
+    CAST(count(), 'UInt64') AS blocks,
+    sum(priority_fees + coinbase_transfers) AS revenue,
+    sum(proposer_payment) AS proposer_payments,
+    sum(sponsorship) AS sponsorship,
+    sum(profit_eth) AS profit_eth,
+    sum(profit_usd) AS profit_usd,
+    CAST(countIf(subsidy > 0), 'UInt64') AS subsidized_blocks,
+    sum(subsidy) AS total_subsidy
+FROM brontes.builder_pnl FINAL
+WHERE block_number >= ? AND block_number <= ?
+GROUP BY builder_address
+ORDER BY blocks DESC
@@ -0,0 +1,7 @@
+SELECT
+    block_number,
+    mev_contract,
+    code_hash,
+    normalized_hash,
+    code_size
+FROM brontes.searcher_bytecode FINAL
@@ -0,0 +1,7 @@
+SELECT
+    fingerprint,
+    groupUniqArray(mev_contract) AS contracts
+FROM brontes.searcher_fingerprints FINAL
+WHERE block_number >= ? AND block_number < ?
+GROUP BY fingerprint
+HAVING length(contracts) > 1 AND length(contracts) <= ?
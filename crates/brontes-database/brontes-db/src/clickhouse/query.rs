@@ -0,0 +1,207 @@
+//! Helpers for building clickhouse queries out of the sql templates in
+//! `queries/`. Values are rendered as escaped clickhouse literals instead of
+//! being formatted straight into the query string.
+
+use std::fmt::Write;
+
+/// A value that can be bound to a `?` placeholder of a [`Clause`].
+pub trait ToSql {
+    fn write_sql(&self, out: &mut String);
+}
+
+macro_rules! number_to_sql {
+    ($($ty:ty),*) => {
+        $(
+            impl ToSql for $ty {
+                fn write_sql(&self, out: &mut String) {
+                    write!(out, "{}", self).unwrap();
+                }
+            }
+        )*
+    };
+}
+
+number_to_sql!(u8, u16, u32, u64, u128, i32, i64, f64);
+
+impl ToSql for str {
+    fn write_sql(&self, out: &mut String) {
+        out.push('\'');
+        for c in self.chars() {
+            if c == '\\' || c == '\'' {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out.push('\'');
+    }
+}
+
+impl ToSql for String {
+    fn write_sql(&self, out: &mut String) {
+        self.as_str().write_sql(out)
+    }
+}
+
+impl<T: ToSql + ?Sized> ToSql for &T {
+    fn write_sql(&self, out: &mut String) {
+        (**self).write_sql(out)
+    }
+}
+
+impl<T: ToSql> ToSql for [T] {
+    fn write_sql(&self, out: &mut String) {
+        out.push('[');
+        for (i, value) in self.iter().enumerate() {
+            if i != 0 {
+                out.push(',');
+            }
+            value.write_sql(out);
+        }
+        out.push(']');
+    }
+}
+
+impl<T: ToSql> ToSql for Vec<T> {
+    fn write_sql(&self, out: &mut String) {
+        self.as_slice().write_sql(out)
+    }
+}
+
+/// A sql fragment with all of its parameters bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause(String);
+
+impl Clause {
+    /// Binds each `?` in `sql` to the next value in `params`.
+    ///
+    /// # Panics
+    /// If the number of placeholders doesn't match the number of params.
+    pub fn new(sql: &str, params: &[&dyn ToSql]) -> Self {
+        let mut out = String::with_capacity(sql.len());
+        let mut params = params.iter();
+
+        for (i, part) in sql.split('?').enumerate() {
+            if i != 0 {
+                params
+                    .next()
+                    .unwrap_or_else(|| panic!("missing parameter for clause: {sql}"))
+                    .write_sql(&mut out);
+            }
+            out.push_str(part);
+        }
+        assert!(params.next().is_none(), "too many parameters for clause: {sql}");
+
+        Self(out)
+    }
+
+    /// `column IN (SELECT arrayJoin(values) AS column)`
+    pub fn in_array<T: ToSql>(column: &str, values: &[T]) -> Self {
+        Self::new(&format!("{column} IN (SELECT arrayJoin(?) AS {column})"), &[&values])
+    }
+
+    pub fn and(self, other: Clause) -> Self {
+        Self(format!("({}) AND ({})", self.0, other.0))
+    }
+
+    /// Joins the clauses with `OR`, returning `None` if there are none.
+    pub fn any(clauses: impl IntoIterator<Item = Clause>) -> Option<Self> {
+        clauses
+            .into_iter()
+            .map(|clause| format!("({})", clause.0))
+            .reduce(|acc, clause| format!("{acc} OR {clause}"))
+            .map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Builds a query from a sql template by swapping out parts of the template
+/// with [`Clause`]s.
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    query: String,
+}
+
+impl QueryBuilder {
+    pub fn new(template: &str) -> Self {
+        Self { query: template.to_string() }
+    }
+
+    /// Whether the template contains `target`. Used for templates that are
+    /// shared across tables with slightly different shapes.
+    pub fn contains(&self, target: &str) -> bool {
+        self.query.contains(target)
+    }
+
+    /// Replaces `target` in the template with `clause`. Errors if `target`
+    /// isn't part of the template so that a template change can't silently
+    /// turn into an unfiltered query.
+    pub fn replace(mut self, target: &str, clause: &Clause) -> eyre::Result<Self> {
+        if !self.contains(target) {
+            eyre::bail!("query template doesn't contain `{target}`");
+        }
+        self.query = self.query.replace(target, clause.as_str());
+
+        Ok(self)
+    }
+
+    /// Same as [`QueryBuilder::replace`] but leaves the template untouched
+    /// when there is no clause.
+    pub fn replace_if(self, target: &str, clause: Option<&Clause>) -> eyre::Result<Self> {
+        match clause {
+            Some(clause) => self.replace(target, clause),
+            None => Ok(self),
+        }
+    }
+
+    pub fn build(self) -> String {
+        self.query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_params() {
+        let clause =
+            Clause::new("a >= ? AND b = ? AND has(?, c)", &[&1u64, &"x'y", &vec![1.5, 2.0]]);
+        assert_eq!(clause.as_str(), "a >= 1 AND b = 'x\\'y' AND has([1.5,2], c)");
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_param() {
+        Clause::new("a = ? AND b = ?", &[&1u64]);
+    }
+
+    #[test]
+    fn joins_clauses() {
+        let any = Clause::any([Clause::new("a = ?", &[&1u64]), Clause::new("a = ?", &[&2u64])]);
+        assert_eq!(any.unwrap().as_str(), "(a = 1) OR (a = 2)");
+        assert!(Clause::any(vec![]).is_none());
+
+        let and = Clause::in_array("block_number", &[1u64, 2]).and(Clause::new("x", &[]));
+        assert_eq!(
+            and.as_str(),
+            "(block_number IN (SELECT arrayJoin([1,2]) AS block_number)) AND (x)"
+        );
+    }
+
+    #[test]
+    fn replace_requires_target() {
+        let clause = Clause::new("a = ?", &[&1u64]);
+        let query = QueryBuilder::new("SELECT * FROM t WHERE ?")
+            .replace("?", &clause)
+            .unwrap()
+            .build();
+        assert_eq!(query, "SELECT * FROM t WHERE a = 1");
+
+        assert!(QueryBuilder::new("SELECT 1")
+            .replace("b = ?", &clause)
+            .is_err());
+    }
+}
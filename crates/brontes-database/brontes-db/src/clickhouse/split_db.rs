@@ -1,29 +1,61 @@
 use std::{
+    collections::BTreeSet,
     pin::Pin,
+    sync::OnceLock,
     task::{Context, Poll},
     time::{Duration, Instant},
 };
 
-use brontes_types::{db_write_trigger::HeartRateMonitor, FastHashMap, UnboundedYapperReceiver};
+use backon::{ExponentialBuilder, Retryable};
+use brontes_metrics::db_writer::ClickhouseWriterMetrics;
+use brontes_types::{db_write_trigger::HeartRateMonitor, FastHashMap, FastHashSet};
 use db_interfaces::{
     clickhouse::{client::ClickhouseClient, config::ClickhouseConfig},
     Database,
 };
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
-use tokio::task::JoinError;
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    task::JoinError,
+    time::Interval,
+};
+
+use crate::clickhouse::{db_client::is_transient_error, dbms::*};
 
-use crate::clickhouse::dbms::*;
+static CLICKHOUSE_WRITER_METRICS: OnceLock<ClickhouseWriterMetrics> = OnceLock::new();
 
-type InsertFut = Pin<Box<dyn Future<Output = Result<eyre::Result<()>, JoinError>> + Send>>;
+/// Resolves to the id of the insert, its amount of rows and its result
+type InsertFut =
+    Pin<Box<dyn Future<Output = (u64, usize, Result<eyre::Result<()>, JoinError>)> + Send>>;
+
+pub enum ClickhouseWriterMessage {
+    Rows(Vec<BrontesClickhouseData>),
+    /// Resolves once every row sent before it has been inserted, with `false`
+    /// if one of their inserts failed
+    Barrier(oneshot::Sender<bool>),
+}
 
 pub struct ClickhouseBuffered {
     client:            ClickhouseClient<BrontesClickhouseTables>,
-    rx:                UnboundedYapperReceiver<Vec<BrontesClickhouseData>>,
+    rx:                Receiver<ClickhouseWriterMessage>,
     value_map:         FastHashMap<BrontesClickhouseTables, Vec<BrontesClickhouseTableDataTypes>>,
+    /// when the oldest row of each table buffer was received
+    buffered_since:    FastHashMap<BrontesClickhouseTables, Instant>,
     buffer_size_small: usize,
     buffer_size_big:   usize,
+    /// buffers are flushed once their oldest row is this old, even if they
+    /// aren't full
+    flush_interval:    Duration,
+    /// created on the first poll, so that it runs on the writer's runtime
+    flush_timer:       Option<Interval>,
+    /// once this many rows are buffered or being inserted, no new rows are
+    /// received, which blocks the senders on the bounded channel
+    max_pending_rows:  usize,
+    buffered_rows:     usize,
+    in_flight_rows:    usize,
     futs:              FuturesUnordered<InsertFut>,
+    barriers:          InsertBarriers,
     /// if none, will always write to db. if some. will only start writing if
     heart_rate:        Option<HeartRateMonitor>,
     skip:              bool,
@@ -31,28 +63,51 @@ pub struct ClickhouseBuffered {
 
 impl ClickhouseBuffered {
     pub fn new(
-        rx: UnboundedYapperReceiver<Vec<BrontesClickhouseData>>,
+        rx: Receiver<ClickhouseWriterMessage>,
         config: ClickhouseConfig,
         buffer_size_small: usize,
         buffer_size_big: usize,
+        flush_interval: Duration,
+        max_pending_rows: usize,
         heart_rate: Option<HeartRateMonitor>,
     ) -> Self {
         Self {
             client: config.build(),
             rx,
             value_map: FastHashMap::default(),
+            buffered_since: FastHashMap::default(),
             buffer_size_small,
             buffer_size_big,
+            flush_interval,
+            flush_timer: None,
+            max_pending_rows,
+            buffered_rows: 0,
+            in_flight_rows: 0,
             skip: heart_rate.is_some(),
             heart_rate,
             futs: FuturesUnordered::default(),
+            barriers: InsertBarriers::default(),
         }
     }
 
+    fn handle_barrier(&mut self, done: oneshot::Sender<bool>) {
+        let buffered = self
+            .value_map
+            .iter()
+            .filter(|(_, rows)| !rows.is_empty())
+            .map(|(table, _)| table.clone())
+            .collect();
+        self.barriers.add(done, buffered);
+    }
+
     fn handle_incoming(&mut self, value: Vec<BrontesClickhouseData>) {
         let enum_kind = value.first().as_ref().unwrap().data.get_db_enum();
         let mut force_insert = false;
 
+        self.buffered_rows += value.len();
+        self.buffered_since
+            .entry(enum_kind.clone())
+            .or_insert_with(Instant::now);
         let entry = self.value_map.entry(enum_kind.clone()).or_default();
 
         entry.extend(value.into_iter().map(|value| {
@@ -63,20 +118,56 @@ impl ClickhouseBuffered {
         let size = if enum_kind.is_big() { self.buffer_size_big } else { self.buffer_size_small };
 
         if entry.len() >= size || force_insert {
-            let client = self.client.clone();
-            self.futs.push(Box::pin(tokio::spawn(Self::insert(
-                client,
-                std::mem::take(entry),
-                enum_kind,
-            ))));
+            self.flush(enum_kind);
         }
     }
 
+    /// Flushes the buffers whose oldest row has waited for the flush interval
+    fn flush_expired(&mut self) {
+        let expired = self
+            .buffered_since
+            .iter()
+            .filter(|(_, since)| since.elapsed() >= self.flush_interval)
+            .map(|(table, _)| table.clone())
+            .collect::<Vec<_>>();
+
+        expired.into_iter().for_each(|table| self.flush(table));
+    }
+
+    fn flush(&mut self, table: BrontesClickhouseTables) {
+        self.buffered_since.remove(&table);
+        let Some(entry) = self.value_map.get_mut(&table) else { return };
+        if entry.is_empty() {
+            return
+        }
+
+        let data = std::mem::take(entry);
+        let rows = data.len();
+        self.buffered_rows -= rows;
+        self.in_flight_rows += rows;
+
+        let id = self.barriers.started(&table);
+        let insert = tokio::spawn(Self::insert(self.client.clone(), data, table));
+        self.futs
+            .push(Box::pin(async move { (id, rows, insert.await) }));
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.buffered_rows + self.in_flight_rows < self.max_pending_rows
+    }
+
+    /// Inserts the rows, retrying with an exponential backoff on transient
+    /// errors. The rows are dropped once the retries are exhausted.
     async fn insert(
         client: ClickhouseClient<BrontesClickhouseTables>,
         data: Vec<BrontesClickhouseTableDataTypes>,
         table: BrontesClickhouseTables,
     ) -> eyre::Result<()> {
+        let retry_strategy = ExponentialBuilder::default()
+            .with_max_times(8)
+            .with_min_delay(Duration::from_millis(200))
+            .with_max_delay(Duration::from_secs(30));
+
         macro_rules! inserts {
             ($(($table_id:ident, $inner:ident)),+) => {
                 match table {
@@ -95,9 +186,36 @@ impl ClickhouseBuffered {
                             if insert_data.is_empty() {
                                 panic!("you did this wrong idiot");
                             }
-                            client
-                                .insert_many::<$table_id>(&insert_data)
-                                .await?
+
+                            let mut try_count = 1;
+                            let start = Instant::now();
+                            let res = (|| async {
+                                client.insert_many::<$table_id>(&insert_data).await
+                            })
+                                .retry(&retry_strategy)
+                                .when(is_transient_error)
+                                .notify(|err, dur| {
+                                    tracing::warn!(
+                                        table = stringify!($table_id),
+                                        rows = insert_data.len(),
+                                        "insert failed after {} attempt(s). Retrying in {:?}... \
+                                         Error: {}",
+                                        try_count,
+                                        dur,
+                                        err
+                                    );
+                                    try_count += 1;
+                                })
+                                .await;
+
+                            CLICKHOUSE_WRITER_METRICS
+                                .get_or_init(ClickhouseWriterMetrics::new)
+                                .observe_insert(
+                                    stringify!($table_id),
+                                    start.elapsed(),
+                                    res.is_err(),
+                                );
+                            res?
                         },
                     )+
                 }
@@ -115,11 +233,24 @@ impl ClickhouseBuffered {
             (MevSandwiches, Sandwich),
             (MevAtomic_Arbs, AtomicArb),
             (MevLiquidations, Liquidation),
+            (MevFrontruns, Frontrun),
+            (MevApproval_Races, ApprovalRace),
             (BrontesDex_Price_Mapping, DexQuotesWithBlockNumber),
             (BrontesToken_Info, TokenInfoWithAddress),
             (EthereumPools, ProtocolInfoClickhouse),
             (BrontesTree, TransactionRoot),
             (BrontesBlock_Analysis, BlockAnalysis),
+            (BrontesGas_Bids, GasBid),
+            (BrontesSearcher_Block_Bids, SearcherBlockBids),
+            (BrontesSearcher_Bytecode, SearcherBytecode),
+            (BrontesSearcher_Fingerprints, SearcherFingerprint),
+            (BrontesSearcher_Inventory, SearcherInventoryDelta),
+            (BrontesVictim_Execution, VictimExecution),
+            (BrontesPossible_Mev_Timeline, PossibleMevTimeline),
+            (BrontesBlock_Provenance, BlockProvenance),
+            (BrontesSuppressed_Bundles, SuppressedBundle),
+            (BrontesInspector_Runs, InspectorRun),
+            (BrontesBuilder_Pnl, BuilderPnl),
             (BrontesRun_Id, RunId)
         );
 
@@ -163,36 +294,56 @@ impl ClickhouseBuffered {
         // if we go 1s without a message, we assume shutdown was complete
         while last_message.elapsed() < Duration::from_secs(1) {
             let mut message = false;
-            while let Ok(value) = self.rx.try_recv() {
-                if value.is_empty() {
-                    continue
-                }
+            while let Ok(message_kind) = self.rx.try_recv() {
+                let value = match message_kind {
+                    ClickhouseWriterMessage::Rows(value) if !value.is_empty() => value,
+                    ClickhouseWriterMessage::Rows(_) => continue,
+                    ClickhouseWriterMessage::Barrier(done) => {
+                        self.handle_barrier(done);
+                        continue
+                    }
+                };
 
                 message = true;
 
                 let enum_kind = value.first().as_ref().unwrap().data.get_db_enum();
+                self.buffered_rows += value.len();
                 let entry = self.value_map.entry(enum_kind.clone()).or_default();
                 entry.extend(value.into_iter().map(|v| v.data));
             }
 
-            for (enum_kind, entry) in &mut self.value_map {
-                if entry.is_empty() {
-                    continue
-                }
-
-                self.futs.push(Box::pin(tokio::spawn(Self::insert(
-                    self.client.clone(),
-                    std::mem::take(entry),
-                    enum_kind.clone(),
-                ))));
-            }
+            let tables = self.value_map.keys().cloned().collect::<Vec<_>>();
+            tables.into_iter().for_each(|table| self.flush(table));
             // inserts take some time so we update last message here
             if message {
                 last_message = Instant::now();
             }
         }
 
-        while (self.futs.next().await).is_some() {}
+        while let Some((id, rows, res)) = self.futs.next().await {
+            self.on_insert_finished(id, rows, res);
+        }
+    }
+
+    fn on_insert_finished(
+        &mut self,
+        id: u64,
+        rows: usize,
+        res: Result<eyre::Result<()>, JoinError>,
+    ) {
+        self.in_flight_rows -= rows;
+        let inserted = match res {
+            Ok(Ok(())) => true,
+            Ok(Err(e)) => {
+                tracing::error!(target: "brontes", rows, "error writing to clickhouse {:?}", e);
+                false
+            }
+            Err(e) => {
+                tracing::error!(target: "brontes", rows, "clickhouse insert task failed {:?}", e);
+                false
+            }
+        };
+        self.barriers.finished(id, inserted);
     }
 }
 
@@ -215,14 +366,18 @@ impl Future for ClickhouseBuffered {
             }
 
             let mut cnt = 500;
-            while let Poll::Ready(val) = this.rx.poll_recv(cx) {
+            // the channel isn't read while at capacity, so the senders wait
+            // for the inserts to catch up
+            while this.has_capacity() {
+                let Poll::Ready(val) = this.rx.poll_recv(cx) else { break };
                 match val {
-                    Some(val) if !this.skip => {
+                    Some(ClickhouseWriterMessage::Rows(val)) if !this.skip => {
                         if !val.is_empty() {
                             this.handle_incoming(val)
                         }
                     }
-                    Some(_) => {}
+                    Some(ClickhouseWriterMessage::Rows(_)) => {}
+                    Some(ClickhouseWriterMessage::Barrier(done)) => this.handle_barrier(done),
                     None => return Poll::Ready(()),
                 }
 
@@ -232,10 +387,16 @@ impl Future for ClickhouseBuffered {
                 }
             }
 
-            while let Poll::Ready(Some(val)) = this.futs.poll_next_unpin(cx) {
-                if let Err(e) = val {
-                    tracing::error!(target: "brontes", "error writing to clickhouse {:?}", e);
-                }
+            let flush_interval = this.flush_interval;
+            let flush_timer = this
+                .flush_timer
+                .get_or_insert_with(|| tokio::time::interval(flush_interval));
+            if flush_timer.poll_tick(cx).is_ready() {
+                this.flush_expired();
+            }
+
+            while let Poll::Ready(Some((id, rows, res))) = this.futs.poll_next_unpin(cx) {
+                this.on_insert_finished(id, rows, res);
             }
 
             work -= 1;
@@ -246,3 +407,122 @@ impl Future for ClickhouseBuffered {
         }
     }
 }
+
+/// Tracks the inserts that the rows sent before a barrier end up in. These are
+/// the inserts started before the barrier and the next flush of every table
+/// that had buffered rows when the barrier was received.
+#[derive(Default)]
+struct InsertBarriers {
+    next_insert: u64,
+    in_flight:   BTreeSet<u64>,
+    pending:     Vec<InsertBarrier>,
+}
+
+struct InsertBarrier {
+    done:        oneshot::Sender<bool>,
+    /// tables whose buffered rows haven't been flushed yet
+    buffered:    FastHashSet<BrontesClickhouseTables>,
+    /// the last insert holding rows of the barrier
+    last_insert: Option<u64>,
+    failed:      bool,
+}
+
+impl InsertBarriers {
+    fn add(&mut self, done: oneshot::Sender<bool>, buffered: FastHashSet<BrontesClickhouseTables>) {
+        self.pending.push(InsertBarrier {
+            done,
+            buffered,
+            last_insert: self.next_insert.checked_sub(1),
+            failed: false,
+        });
+        self.release();
+    }
+
+    /// Registers the insert of the flushed table and returns its id
+    fn started(&mut self, table: &BrontesClickhouseTables) -> u64 {
+        let id = self.next_insert;
+        self.next_insert += 1;
+        self.in_flight.insert(id);
+
+        self.pending
+            .iter_mut()
+            .filter(|barrier| barrier.buffered.remove(table))
+            .for_each(|barrier| barrier.last_insert = Some(id));
+
+        id
+    }
+
+    fn finished(&mut self, id: u64, inserted: bool) {
+        self.in_flight.remove(&id);
+        if !inserted {
+            self.pending
+                .iter_mut()
+                .filter(|barrier| barrier.last_insert >= Some(id))
+                .for_each(|barrier| barrier.failed = true);
+        }
+        self.release();
+    }
+
+    fn release(&mut self) {
+        let oldest_in_flight = self.in_flight.first().copied();
+        let (released, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|barrier| {
+                barrier.buffered.is_empty()
+                    && match (oldest_in_flight, barrier.last_insert) {
+                        (Some(oldest), Some(last)) => oldest > last,
+                        _ => true,
+                    }
+            });
+        self.pending = pending;
+
+        for barrier in released {
+            let _ = barrier.done.send(!barrier.failed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn barrier_waits_for_earlier_inserts_and_buffered_tables() {
+        let mut barriers = InsertBarriers::default();
+        let tree = BrontesClickhouseTables::BrontesTree;
+        let headers = BrontesClickhouseTables::MevBundle_Header;
+
+        let in_flight = barriers.started(&tree);
+        let (done, mut released) = oneshot::channel();
+        barriers.add(done, [headers.clone()].into_iter().collect());
+
+        // the header rows are still buffered
+        barriers.finished(in_flight, true);
+        assert!(released.try_recv().is_err());
+
+        // inserts started after the barrier don't hold it back once the
+        // buffered rows are inserted
+        let flushed = barriers.started(&headers);
+        let later = barriers.started(&tree);
+        barriers.finished(flushed, true);
+        assert_eq!(released.try_recv(), Ok(true));
+        barriers.finished(later, true);
+    }
+
+    #[test]
+    fn barrier_fails_with_its_insert() {
+        let mut barriers = InsertBarriers::default();
+        let tree = BrontesClickhouseTables::BrontesTree;
+
+        let (done, mut released) = oneshot::channel();
+        barriers.add(done, [tree.clone()].into_iter().collect());
+        let insert = barriers.started(&tree);
+        barriers.finished(insert, false);
+        assert_eq!(released.try_recv(), Ok(false));
+
+        // nothing to wait for
+        let (done, mut released) = oneshot::channel();
+        barriers.add(done, FastHashSet::default());
+        assert_eq!(released.try_recv(), Ok(true));
+    }
+}
@@ -0,0 +1,42 @@
+CREATE TABLE mev.approval_races ON CLUSTER eth_cluster0
+(
+    `block_number` UInt64,
+    `frontrun_tx_hash` String,
+    `frontrun_transfers` Nested(
+        `trace_idx` UInt64,
+        `from` String,
+        `to` String,
+        `token` Tuple(String, String),
+        `amount` Tuple(UInt256, UInt256),
+        `fee` Tuple(UInt256, UInt256)
+    ),
+    `frontrun_gas_details` Tuple(
+        `coinbase_transfer` Nullable(UInt128), 
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `victim_tx_hash` String,
+    `victim` String,
+    `spender` String,
+    `token` Tuple(String, String),
+    `new_allowance` UInt256,
+    `victim_gas_details` Tuple(
+        `coinbase_transfer` Nullable(UInt128), 
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/approval_races', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `frontrun_tx_hash`)
+ORDER BY (`block_number`, `frontrun_tx_hash`)
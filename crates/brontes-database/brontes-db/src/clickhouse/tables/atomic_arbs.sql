@@ -11,13 +11,18 @@ CREATE TABLE mev.atomic_arbs ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `gas_details` Tuple(
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `arb_type` String,
     `run_id` UInt64
@@ -0,0 +1,14 @@
+CREATE TABLE brontes.block_provenance ON CLUSTER eth_cluster0
+(
+    `block_number`    UInt64,
+    `brontes_version` String,
+    `git_sha`         String,
+    `inspectors`      Array(String),
+    `config_hash`     String,
+    `started_at`      UInt64,
+    `finished_at`     UInt64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/block_provenance', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`)
+ORDER BY (`block_number`)
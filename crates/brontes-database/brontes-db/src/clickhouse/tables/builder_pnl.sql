@@ -0,0 +1,17 @@
+CREATE TABLE brontes.builder_pnl ON CLUSTER eth_cluster0
+(
+    `block_number`       UInt64,
+    `builder_address`    String,
+    `builder_name`       Nullable(String),
+    `priority_fees`      UInt128,
+    `coinbase_transfers` UInt128,
+    `proposer_payment`   UInt128,
+    `sponsorship`        UInt128,
+    `profit_eth`         Float64,
+    `profit_usd`         Float64,
+    `subsidy`            UInt128,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/builder_pnl', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`)
+ORDER BY (`block_number`)
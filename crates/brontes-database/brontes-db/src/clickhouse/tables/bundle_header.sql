@@ -16,6 +16,24 @@ CREATE TABLE mev.bundle_header ON CLUSTER eth_cluster0
         `name` Nullable(String),
         `token_deltas` Array(Tuple(Tuple(String, UInt8, String), Float64, Float64))
     ),
+    `sanctioned_addresses` Array(String),
+    `taxonomy_version` UInt16 DEFAULT 0,
+    `superseded` Nested (
+        `mev_type` String,
+        `tx_hash` String,
+        `profit_usd` Float64,
+        `merged` Bool
+    ),
+    `simulated_profit_usd` Nullable(Float64),
+    `profit_discrepancy` Bool DEFAULT false,
+    `submission_channel` LowCardinality(String) DEFAULT 'public',
+    `provenance` Nested (
+        `tx_hash` String,
+        `trace_idx` UInt64,
+        `trace_address` Array(UInt64),
+        `selector` Nullable(String),
+        `log_indices` Array(UInt64)
+    ),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/bundle_header', '{replica}', `run_id`)
@@ -12,7 +12,8 @@ CREATE TABLE mev.cex_dex ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `global_vmap_details` Nested(
         `pairs` Array(Tuple(String, String)),
@@ -88,7 +89,20 @@ CREATE TABLE mev.cex_dex ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128),
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `cex_window` Tuple(
+        `mode` String,
+        `time_source` String,
+        `vwap_pre_block_us` UInt64,
+        `vwap_post_block_us` UInt64,
+        `optimistic_pre_block_us` UInt64,
+        `optimistic_post_block_us` UInt64,
+        `trade_count` UInt64
     ),
     `run_id` UInt64
 )
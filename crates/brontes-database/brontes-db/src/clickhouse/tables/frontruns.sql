@@ -0,0 +1,54 @@
+CREATE TABLE mev.frontruns ON CLUSTER eth_cluster0
+(
+    `block_number` UInt64,
+    `frontrun_tx_hash` String,
+    `frontrun_swaps` Nested(
+        `trace_idx` UInt64,
+        `from` String,
+        `recipient` String,
+        `pool` String,
+        `token_in` Tuple(String, String),
+        `token_out` Tuple(String, String),
+        `amount_in` Tuple(UInt256, UInt256),
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
+    ),
+    `frontrun_gas_details` Tuple(
+        `coinbase_transfer` Nullable(UInt128), 
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `victim_swaps` Nested(
+        `tx_hash` String,
+        `trace_idx` UInt64,
+        `from` String,
+        `recipient` String,
+        `pool` String,
+        `token_in` Tuple(String, String),
+        `token_out` Tuple(String, String),
+        `amount_in` Tuple(UInt256, UInt256),
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
+    ),
+    `victim_gas_details` Nested(
+        `tx_hash` String,
+        `coinbase_transfer` Nullable(UInt128), 
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `displacement_profit_usd` Float64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/frontruns', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `frontrun_tx_hash`)
+ORDER BY (`block_number`, `frontrun_tx_hash`)
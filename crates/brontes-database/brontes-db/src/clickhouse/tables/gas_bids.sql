@@ -0,0 +1,21 @@
+CREATE TABLE brontes.gas_bids ON CLUSTER eth_cluster0
+(
+    `block_number`            UInt64,
+    `tx_hash`                 String,
+    `tx_index`                UInt64,
+    `eoa`                     String,
+    `mev_contract`            Nullable(String),
+    `mev_type`                String,
+    `priority_fee`            UInt128,
+    `priority_fee_percentile` Float64,
+    `coinbase_transfer`       UInt128,
+    `priority_fee_paid`       UInt128,
+    `coinbase_transfer_ratio` Float64,
+    `block_position`          Float64,
+    `bribe_usd`               Float64,
+    `profit_usd`              Float64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/gas_bids', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `tx_hash`)
+ORDER BY (`block_number`, `tx_hash`)
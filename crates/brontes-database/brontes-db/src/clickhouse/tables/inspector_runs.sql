@@ -0,0 +1,12 @@
+CREATE TABLE brontes.inspector_runs ON CLUSTER eth_cluster0
+(
+    `block_number` UInt64,
+    `inspector`    String,
+    `elapsed_us`   UInt64,
+    `candidates`   UInt64,
+    `bundles`      UInt64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/inspector_runs', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `inspector`)
+ORDER BY (`block_number`, `inspector`)
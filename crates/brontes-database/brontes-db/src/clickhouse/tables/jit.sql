@@ -14,7 +14,11 @@ CREATE TABLE mev.jit ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `victim_swaps` Nested(
         `tx_hash` String,
@@ -25,14 +29,19 @@ CREATE TABLE mev.jit ON CLUSTER eth_cluster0
         `token_in` String,
         `token_out` String,
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `victim_gas_details` Nested(
         `tx_hash` String,
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `backrun_burn_tx_hash` String,
     `backrun_burns` Nested(
@@ -47,7 +56,11 @@ CREATE TABLE mev.jit ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `run_id` UInt64
 ) 
@@ -11,7 +11,8 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `frontrun_mints` Nested(
         `tx_hash` String,
@@ -27,7 +28,11 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `victim_swaps` Nested(
         `tx_hash` String,
@@ -38,14 +43,19 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `victim_gas_details` Nested(
         `tx_hash` String,
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `backrun_tx_hash` String,
     `backrun_swaps` Nested(
@@ -57,7 +67,8 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `backrun_burns` Nested(
         `tx_hash` String,
@@ -73,7 +84,11 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
     ),
     `run_id` UInt64
 ) 
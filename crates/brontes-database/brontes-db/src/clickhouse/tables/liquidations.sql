@@ -10,7 +10,8 @@ CREATE TABLE mev.liquidations ON CLUSTER eth_cluster0
         `token_in` Tuple(String, String),
         `token_out` Tuple(String, String),
         `amount_in` Tuple(UInt256, UInt256),
-        `amount_out` Tuple(UInt256, UInt256)
+        `amount_out` Tuple(UInt256, UInt256),
+        `fee_tier` Nullable(UInt32)
     ),
     `liquidations` Nested(
         `trace_idx` UInt64,
@@ -26,7 +27,19 @@ CREATE TABLE mev.liquidations ON CLUSTER eth_cluster0
         `coinbase_transfer` Nullable(UInt128), 
         `priority_fee` UInt128,
         `gas_used` UInt128,
-        `effective_gas_price` UInt128
+        `effective_gas_price` UInt128,
+        `gas_refunded` UInt128,
+        `access_list_gas` UInt128,
+        `blob_gas_used` UInt128,
+        `blob_gas_price` UInt128
+    ),
+    `liquidation_contexts` Nested(
+        `protocol` String,
+        `debtor` String,
+        `block_number` UInt64,
+        `health_factor` Nullable(Float64),
+        `liquidatable` Bool,
+        `collateral` Array(Tuple(String, Float64))
     ),
     `run_id` UInt64
 ) 
@@ -11,7 +11,9 @@ CREATE TABLE mev.mev_blocks ON CLUSTER eth_cluster0
         `jit_count` UInt64,
         `jit_sandwich_count` UInt64,
         `atomic_backrun_count` UInt64,
-        `liquidation_count` UInt64
+        `liquidation_count` UInt64,
+        `frontrun_count` UInt64,
+        `approval_race_count` UInt64
     ),
     `eth_price` Float64,
     `total_gas_used` UInt128,
@@ -39,10 +41,15 @@ CREATE TABLE mev.mev_blocks ON CLUSTER eth_cluster0
         `gas_details.priority_fee` UInt128,
         `gas_details.gas_used` UInt128,
         `gas_details.effective_gas_price` UInt128,
+        `gas_details.gas_refunded` UInt128,
+        `gas_details.access_list_gas` UInt128,
+        `gas_details.blob_gas_used` UInt128,
+        `gas_details.blob_gas_price` UInt128,
         `triggers.is_private` Bool,
         `triggers.coinbase_transfer` Bool,
         `triggers.high_priority_fee` Bool
     ),
+    `sanctioned_addresses` Array(String),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/mev_blocks', '{replica}', `run_id`)
@@ -0,0 +1,29 @@
+CREATE TABLE brontes.possible_mev_timeline ON CLUSTER eth_cluster0
+(
+    `block_number`            UInt64,
+    `tx_hash`                 String,
+    `tx_index`                UInt64,
+    `eoa`                     String,
+    `to`                      Nullable(String),
+    `is_private`              Bool,
+    `has_coinbase_transfer`   Bool,
+    `high_priority_fee`       Bool,
+    `gas_used`                UInt128,
+    `priority_fee`            UInt128,
+    `priority_fee_percentile` Float64,
+    `priority_fee_z_score`    Float64,
+    `coinbase_transfer`       UInt128,
+    `block_position`          Float64,
+    `prev_tx_hash`            Nullable(String),
+    `prev_eoa`                Nullable(String),
+    `prev_to`                 Nullable(String),
+    `prev_mev_type`           Nullable(String),
+    `next_tx_hash`            Nullable(String),
+    `next_eoa`                Nullable(String),
+    `next_to`                 Nullable(String),
+    `next_mev_type`           Nullable(String),
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/possible_mev_timeline', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `tx_hash`)
+ORDER BY (`block_number`, `tx_hash`)
@@ -0,0 +1,18 @@
+CREATE TABLE brontes.searcher_block_bids ON CLUSTER eth_cluster0
+(
+    `block_number`            UInt64,
+    `eoa`                     String,
+    `bundle_count`            UInt64,
+    `priority_fee_paid`       UInt128,
+    `coinbase_transfer`       UInt128,
+    `coinbase_transfer_ratio` Float64,
+    `bid_to_profit_ratio`     Float64,
+    `top_block_position`      Float64,
+    `avg_block_position`      Float64,
+    `bribe_usd`               Float64,
+    `profit_usd`              Float64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/searcher_block_bids', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `eoa`)
+ORDER BY (`block_number`, `eoa`)
@@ -0,0 +1,11 @@
+CREATE TABLE brontes.searcher_bytecode ON CLUSTER eth_cluster0
+(
+    `block_number`    UInt64,
+    `mev_contract`    String,
+    `code_hash`       String,
+    `normalized_hash` String,
+    `code_size`       UInt64
+)
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/searcher_bytecode', '{replica}')
+PRIMARY KEY `mev_contract`
+ORDER BY `mev_contract`
@@ -0,0 +1,14 @@
+CREATE TABLE brontes.searcher_fingerprints ON CLUSTER eth_cluster0
+(
+    `block_number` UInt64,
+    `tx_hash`      String,
+    `eoa`          String,
+    `mev_contract` String,
+    `mev_type`     String,
+    `selector`     UInt32,
+    `fingerprint`  UInt64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/searcher_fingerprints', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `tx_hash`)
+ORDER BY (`block_number`, `tx_hash`)
@@ -0,0 +1,15 @@
+CREATE TABLE brontes.searcher_inventory ON CLUSTER eth_cluster0
+(
+    `block_number` UInt64,
+    `eoa`          String,
+    `mev_contract` Nullable(String),
+    `token`        String,
+    `symbol`       String,
+    `amount`       Float64,
+    `usd_value`    Float64,
+    `bundle_count` UInt64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/searcher_inventory', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `eoa`, `token`)
+ORDER BY (`block_number`, `eoa`, `token`)
@@ -12,7 +12,7 @@ CREATE TABLE mev.searcher_tx ON CLUSTER eth_cluster0
         `fee` Tuple(UInt256, UInt256),
         `msg_value` UInt256
     ),
-    `gas_details` Tuple(Nullable(UInt128), UInt128, UInt128, UInt128),
+    `gas_details` Tuple(Nullable(UInt128), UInt128, UInt128, UInt128, UInt128, UInt128, UInt128, UInt128),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/searcher_tx', '{replica}', `run_id`)
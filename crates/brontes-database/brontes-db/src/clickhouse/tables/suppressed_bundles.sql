@@ -0,0 +1,16 @@
+CREATE TABLE brontes.suppressed_bundles ON CLUSTER eth_cluster0
+(
+    `block_number`   UInt64,
+    `tx_hash`        String,
+    `mev_type`       String,
+    `eoa`            String,
+    `mev_contract`   Nullable(String),
+    `profit_usd`     Float64,
+    `denied_address` String,
+    `kind`           String,
+    `reason`         String,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/suppressed_bundles', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `tx_hash`)
+ORDER BY (`block_number`, `tx_hash`)
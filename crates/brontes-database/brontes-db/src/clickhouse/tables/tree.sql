@@ -5,11 +5,13 @@ CREATE TABLE brontes.tree ON CLUSTER eth_cluster0
     `tx_idx` UInt64,
     `from` String,
     `to` Nullable(String),
-    `gas_details` Tuple(coinbase_transfer Nullable(UInt128), priority_fee UInt128, gas_used UInt128, effective_gas_price UInt128),
+    `gas_details` Tuple(coinbase_transfer Nullable(UInt128), priority_fee UInt128, gas_used UInt128, effective_gas_price UInt128, gas_refunded UInt128, access_list_gas UInt128, blob_gas_used UInt128, blob_gas_price UInt128),
     `trace_nodes.trace_idx` Array(UInt64),
     `trace_nodes.trace_address` Array(Array(UInt64)),
     `trace_nodes.action_kind` Array(Nullable(String)),
     `trace_nodes.action` Array(Nullable(String)),
+    `trace_nodes.selector` Array(Nullable(String)),
+    `trace_nodes.log_indices` Array(Array(UInt64)),
     `run_id` UInt64
 )
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/tree', '{replica}', `run_id`)
@@ -0,0 +1,20 @@
+CREATE TABLE brontes.victim_execution ON CLUSTER eth_cluster0
+(
+    `block_number`         UInt64,
+    `tx_hash`              String,
+    `tx_index`             UInt64,
+    `trace_index`          UInt64,
+    `bundle_tx_hash`       String,
+    `mev_type`             String,
+    `pool`                 String,
+    `token_in`             String,
+    `token_out`            String,
+    `amount_in`            Float64,
+    `amount_out`           Float64,
+    `benchmark_amount_out` Float64,
+    `routing_inefficiency` Float64,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/brontes/victim_execution', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `tx_hash`, `trace_index`)
+ORDER BY (`block_number`, `tx_hash`, `trace_index`)
@@ -32,6 +32,8 @@ pub fn merge_libmdbx_dbs(
             SearcherContracts,
             Builder,
             AddressToProtocolInfo,
+            CurvePoolParameters,
+            FunctionSelectors,
             TokenDecimals,
             DexPrice
             );
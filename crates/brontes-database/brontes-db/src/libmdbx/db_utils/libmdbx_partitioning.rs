@@ -129,6 +129,8 @@ impl LibmdbxPartitioner {
             SearcherContracts,
             Builder,
             AddressToProtocolInfo,
+            CurvePoolParameters,
+            FunctionSelectors,
             TokenDecimals
         );
 
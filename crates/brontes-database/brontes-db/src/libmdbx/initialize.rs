@@ -1,18 +1,28 @@
-use std::{fmt::Debug, path, pin::Pin, sync::Arc};
+use std::{
+    fmt::Debug,
+    path,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    time::SystemTime,
+};
 
 use ::clickhouse::DbRow;
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address};
 use brontes_types::{
+    chain::chain_spec,
     db::{
         address_metadata::{AddressMetadata, ContractInfo, Socials},
         builder::BuilderInfo,
+        function_selector::FunctionSelector,
+        protocol_metadata::{verify_code_hash, ProtocolMetadataConfig},
         searcher::SearcherInfo,
         traits::{DBWriter, LibmdbxReader},
     },
     traits::TracingProvider,
     unordered_buffer_map::BrontesStreamExt,
-    FastHashMap, Protocol,
+    FastHashMap, FastHashSet, Protocol,
 };
+use eyre::WrapErr;
 use futures::{join, stream::iter, Future, StreamExt};
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
 use itertools::Itertools;
@@ -24,12 +34,20 @@ use tracing::{error, info};
 use super::{libmdbx_writer::WriterMessage, tables::Tables};
 use crate::{
     clickhouse::ClickhouseHandle,
-    libmdbx::{types::CompressedTable, LibmdbxData, LibmdbxReadWriter},
+    libmdbx::{
+        tables::{
+            AddressToProtocolInfo, AddressToProtocolInfoData, SanctionedAddresses,
+            SanctionedAddressesData,
+        },
+        types::CompressedTable,
+        LibmdbxData, LibmdbxReadWriter,
+    },
 };
-const CLASSIFIER_CONFIG_FILE: &str = "config/classifier_config.toml";
 const SEARCHER_CONFIG_FILE: &str = "config/searcher_config.toml";
 const BUILDER_CONFIG_FILE: &str = "config/builder_config.toml";
 const METADATA_CONFIG_FILE: &str = "config/metadata_config.toml";
+const PROTOCOL_REGISTRY_CONFIG_FILE: &str = "config/protocol_registry.toml";
+const FUNCTION_SELECTORS_CONFIG_FILE: &str = "config/function_selectors.toml";
 const DEFAULT_START_BLOCK: u64 = 0;
 use brontes_metrics::db_initialization::InitMetrics;
 type FnOutput<D> = Pin<Box<dyn Future<Output = eyre::Result<Vec<D>>> + Send>>;
@@ -85,6 +103,8 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             Tables::TokenDecimals,
             Tables::Builder,
             Tables::AddressMeta,
+            Tables::SanctionedAddresses,
+            Tables::FunctionSelectors,
         ];
 
         #[cfg(feature = "local-clickhouse")]
@@ -93,13 +113,15 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             let libmdbx_cnt = self.libmdbx.get_crit_table_count()?;
             tracing::info!(?clickhouse_cnt, ?libmdbx_cnt);
 
-            // if all libmdbx tables have more entries, we don't init
+            // if all libmdbx tables have more entries, we don't init. The config
+            // entries don't come from clickhouse so they still get applied
             if libmdbx_cnt.all_greater(clickhouse_cnt) {
-                return Ok(())
+                self.load_config().await?;
+                return self.reorder_legacy_metapool_tokens().await
             }
         }
 
-        let progress_bar = Self::build_critical_state_progress_bar(5).unwrap();
+        let progress_bar = Self::build_critical_state_progress_bar(7).unwrap();
 
         futures::stream::iter(tables.to_vec())
             .map(|table| {
@@ -110,18 +132,109 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             .collect::<Vec<_>>()
             .await;
         self.load_config().await?;
+        self.reorder_legacy_metapool_tokens().await?;
 
         Ok(())
     }
 
+    /// Curve metapools used to be stored with the meta coin after the coins of
+    /// the base pool, while the classifiers resolve the underlying coins with
+    /// the meta coin first. Rewrites the entries that are still in the old
+    /// order, the base pools are the stored stableswap pools that aren't
+    /// metapools themselves. Entries that are already in the current order
+    /// are left as they are, so this is safe to run on every start.
+    pub async fn reorder_legacy_metapool_tokens(&self) -> eyre::Result<()> {
+        let reordered = self.libmdbx.db.view_db(|tx| {
+            let mut base_pools = FastHashSet::default();
+            for row in tx.cursor_read::<AddressToProtocolInfo>()?.walk(None)? {
+                let info = row?.1;
+                if info.protocol.is_curve_stableswap() && !info.protocol.is_curve_metapool() {
+                    base_pools.insert(info.into_iter().collect_vec());
+                }
+            }
+
+            let mut reordered = vec![];
+            for row in tx.cursor_read::<AddressToProtocolInfo>()?.walk(None)? {
+                let row = row?;
+                let (address, mut info) = (row.0, row.1);
+                if info.reorder_legacy_metapool_tokens(&base_pools) {
+                    reordered.push(AddressToProtocolInfoData::new(address, info));
+                }
+            }
+
+            Ok(reordered)
+        })?;
+
+        if reordered.is_empty() {
+            return Ok(())
+        }
+        info!(target: "brontes::init", pools = reordered.len(), "reordering curve metapool tokens");
+
+        let not = Arc::new(Notify::new());
+        self.libmdbx
+            .send_message(WriterMessage::Init(reordered.into(), not.clone()))?;
+        not.notified().await;
+
+        Ok(())
+    }
+
+    /// Replaces the sanctioned address set with the latest one from
+    /// clickhouse. The table is only cleared once the new set has been
+    /// downloaded so a failed query leaves the previous set in place, and it's
+    /// cleared and rewritten in one transaction so readers never see it empty.
+    pub async fn refresh_sanctioned_addresses(&self) -> eyre::Result<()> {
+        let data = self
+            .clickhouse
+            .query_many::<SanctionedAddresses, SanctionedAddressesData>()
+            .await?;
+        info!(target: "brontes::init", entries = data.len(), "refreshing sanctioned addresses");
+
+        self.libmdbx.db.update_db(|tx| {
+            tx.clear::<SanctionedAddresses>()?;
+            for entry in &data {
+                let entry = entry.into_key_val();
+                tx.put::<SanctionedAddresses>(entry.key, entry.value)?;
+            }
+            Ok::<_, reth_db::DatabaseError>(())
+        })??;
+
+        Ok(())
+    }
+
+    /// Applies the toml configs to the database. Entries are merged with what
+    /// is already stored, so this is safe to run repeatedly.
     pub async fn load_config(&self) -> eyre::Result<()> {
-        join!(
+        let (classifier, searcher, builder, metadata, protocol_registry, selectors) = join!(
             self.load_classifier_config_data(),
             self.load_searcher_config_data(),
             self.load_builder_config_data(),
             self.load_address_metadata_config(),
+            self.load_protocol_registry_config(),
+            self.load_function_selectors_config(),
         );
 
+        // every config is applied on its own, so log each one that failed rather
+        // than only the first
+        let failed = [
+            ("classifier", classifier),
+            ("searcher", searcher),
+            ("builder", builder),
+            ("address metadata", metadata),
+            ("protocol registry", protocol_registry),
+            ("function selectors", selectors),
+        ]
+        .into_iter()
+        .filter_map(|(config, res)| {
+            let e = res.err()?;
+            error!(%config, %e, "failed to apply config");
+            Some(config)
+        })
+        .collect_vec();
+
+        if !failed.is_empty() {
+            eyre::bail!("failed to apply the {} configs", failed.join(", "))
+        }
+
         Ok(())
     }
 
@@ -343,28 +456,24 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
         Some(progress_bar)
     }
 
-    /// loads up the `classifier_config.toml` and ensures the values are in the
-    /// database
-    async fn load_classifier_config_data(&self) {
-        let mut workspace_dir = workspace_dir();
-        workspace_dir.push(CLASSIFIER_CONFIG_FILE);
+    /// loads up the classifier config of the chain and ensures the values are
+    /// in the database
+    async fn load_classifier_config_data(&self) -> eyre::Result<()> {
+        let config: tomlTable = read_config(chain_spec().classifier_config)?;
 
-        let Ok(config) = toml::from_str::<tomlTable>(&{
-            let Ok(path) = std::fs::read_to_string(workspace_dir) else {
-                tracing::error!(target: "brontes::init", "failed to read classifier_config");
-                return;
+        for (protocol, inner) in config {
+            let protocol: Protocol = protocol.parse()?;
+            let Some(pools) = inner.as_table() else {
+                eyre::bail!("classifier config entry for {protocol} isn't a table");
             };
-            path
-        }) else {
-            tracing::error!(target: "brontes::init", "failed to load toml");
-            return;
-        };
 
-        for (protocol, inner) in config {
-            let protocol: Protocol = protocol.parse().unwrap();
-            for (address, table) in inner.as_table().unwrap() {
-                let token_addr: Address = address.parse().unwrap();
-                let init_block = table.get("init_block").unwrap().as_integer().unwrap() as u64;
+            for (address, table) in pools {
+                let token_addr: Address = address.parse()?;
+                let init_block = table
+                    .get("init_block")
+                    .and_then(|block| block.as_integer())
+                    .ok_or_else(|| eyre::eyre!("pool {token_addr} is missing an init_block"))?
+                    as u64;
 
                 let table: Vec<TokenInfoWithAddressToml> = table
                     .get("token_info")
@@ -375,161 +484,252 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
                 for t_info in &table {
                     self.libmdbx
                         .write_token_info(t_info.address, t_info.decimals, t_info.symbol.clone())
-                        .await
-                        .unwrap();
+                        .await?;
                 }
 
-                let token_addrs = if table.len() < 2 {
-                    [Address::default(), Address::default()]
-                } else {
-                    [table[0].address, table[1].address]
+                // single token entries, e.g. the base token of a lending market, are
+                // stored as token0
+                let token_addrs = match table.as_slice() {
+                    [] => [Address::default(), Address::default()],
+                    [token] => [token.address, Address::default()],
+                    [token0, token1, ..] => [token0.address, token1.address],
                 };
 
                 self.libmdbx
                     .insert_pool(init_block, token_addr, &token_addrs, None, protocol)
-                    .await
-                    .unwrap();
+                    .await?;
             }
         }
-    }
-
-    async fn load_builder_config_data(&self) {
-        let mut builder_config_path = workspace_dir();
-        builder_config_path.push(BUILDER_CONFIG_FILE);
 
-        let builder_config_str = std::fs::read_to_string(builder_config_path)
-            .expect("Failed to read builder config file");
+        Ok(())
+    }
 
-        let builder_config: BuilderConfig =
-            toml::from_str(&builder_config_str).expect("Failed to parse builder TOML");
+    async fn load_builder_config_data(&self) -> eyre::Result<()> {
+        let builder_config: BuilderConfig = read_config(BUILDER_CONFIG_FILE)?;
 
         // Process builders
         for (address_str, builder_info) in builder_config.builders {
             let address: Address = address_str
                 .parse()
-                .unwrap_or_else(|_| panic!("Failed to parse address '{}'", address_str));
-            let existing_info = self.libmdbx.try_fetch_builder_info(address);
+                .wrap_err_with(|| format!("Failed to parse address '{}'", address_str))?;
 
-            match existing_info.expect("Failed to query builder table") {
+            match self.libmdbx.try_fetch_builder_info(address)? {
                 Some(mut existing) => {
                     existing.merge(builder_info);
-                    self.libmdbx
-                        .write_builder_info(address, existing) // Assuming this method exists
-                        .await
-                        .expect("Failed to update builder info");
+                    self.libmdbx.write_builder_info(address, existing).await?;
                 }
                 None => {
                     self.libmdbx
                         .write_builder_info(address, builder_info)
-                        .await
-                        .expect("Failed to write new builder info");
+                        .await?;
                 }
             }
         }
-    }
-
-    async fn load_searcher_config_data(&self) {
-        let mut searcher_config_path = workspace_dir();
 
-        searcher_config_path.push(SEARCHER_CONFIG_FILE);
-
-        let searcher_config_str = std::fs::read_to_string(searcher_config_path)
-            .expect("Failed to read searcher config file");
+        Ok(())
+    }
 
-        let searcher_config: SearcherConfig =
-            toml::from_str(&searcher_config_str).expect("Failed to parse searcher TOML");
+    async fn load_searcher_config_data(&self) -> eyre::Result<()> {
+        let searcher_config: SearcherConfig = read_config(SEARCHER_CONFIG_FILE)?;
 
         // Process SearcherEOAs
         for (address_str, searcher_info) in searcher_config.searcher_eoas {
             let address = address_str
                 .parse()
-                .unwrap_or_else(|_| panic!("Failed to parse address '{}'", address_str));
+                .wrap_err_with(|| format!("Failed to parse address '{}'", address_str))?;
 
-            let existing_info = self.libmdbx.try_fetch_searcher_eoa_info(address);
-
-            match existing_info.expect("Failed to query builder table") {
+            match self.libmdbx.try_fetch_searcher_eoa_info(address)? {
                 Some(mut existing) => {
                     existing.merge(searcher_info);
                     self.libmdbx
-                        .write_searcher_eoa_info(address, existing) // Assuming this method exists
-                        .await
-                        .expect("Failed to update searcher info");
+                        .write_searcher_eoa_info(address, existing)
+                        .await?;
                 }
                 None => {
                     self.libmdbx
                         .write_searcher_eoa_info(address, searcher_info)
-                        .await
-                        .expect("Failed to write new builder info");
+                        .await?;
                 }
             }
         }
         // Process SearcherContracts
         for (address_str, searcher_info) in searcher_config.searcher_contracts {
-            let address = address_str.parse().unwrap();
-            let existing_info = self.libmdbx.try_fetch_searcher_contract_info(address);
+            let address = address_str
+                .parse()
+                .wrap_err_with(|| format!("Failed to parse address '{}'", address_str))?;
 
-            match existing_info.expect("Failed to query builder table") {
+            match self.libmdbx.try_fetch_searcher_contract_info(address)? {
                 Some(mut existing) => {
                     existing.merge(searcher_info);
                     self.libmdbx
-                        .write_searcher_contract_info(address, existing) // Assuming this method exists
-                        .await
-                        .expect("Failed to update searcher info");
+                        .write_searcher_contract_info(address, existing)
+                        .await?;
                 }
                 None => {
                     self.libmdbx
                         .write_searcher_contract_info(address, searcher_info)
-                        .await
-                        .expect("Failed to write new builder info");
+                        .await?;
                 }
             }
         }
-    }
-
-    async fn load_address_metadata_config(&self) {
-        let mut workspace_dir = workspace_dir();
-        workspace_dir.push(METADATA_CONFIG_FILE);
 
-        let config_str =
-            std::fs::read_to_string(workspace_dir).expect("Failed to read config file");
+        Ok(())
+    }
 
-        let config: MetadataConfig = toml::from_str(&config_str).expect("Failed to parse TOML");
+    async fn load_address_metadata_config(&self) -> eyre::Result<()> {
+        let config: MetadataConfig = read_config(METADATA_CONFIG_FILE)?;
 
         for (address_str, toml_metadata) in config.metadata {
-            let address = address_str.parse().unwrap();
+            let address = address_str
+                .parse()
+                .wrap_err_with(|| format!("Failed to parse address '{}'", address_str))?;
             let metadata: AddressMetadata = toml_metadata.into_address_metadata();
 
-            let existing_info = self.libmdbx.try_fetch_address_metadata(address);
-
-            match existing_info.expect("Failed to query address metadata table") {
+            match self.libmdbx.try_fetch_address_metadata(address)? {
                 Some(mut existing) => {
                     existing.merge(metadata);
-                    self.libmdbx
-                        .write_address_meta(address, existing)
-                        .await
-                        .expect("Failed to write address metadata");
+                    self.libmdbx.write_address_meta(address, existing).await?;
                 }
                 None => {
-                    self.libmdbx
-                        .write_address_meta(address, metadata)
-                        .await
-                        .expect("Failed to write address metadata");
+                    self.libmdbx.write_address_meta(address, metadata).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the protocol registry config. The addresses with a configured
+    /// code hash are checked against the code deployed at the tip before
+    /// anything is written, so a misconfigured entry fails the whole config.
+    /// Pools of the factories after the first one of a protocol are
+    /// discovered as if the first one created them, so their code has to be
+    /// the same.
+    async fn load_protocol_registry_config(&self) -> eyre::Result<()> {
+        let config: FastHashMap<String, ProtocolMetadataConfig> =
+            read_config(PROTOCOL_REGISTRY_CONFIG_FILE)?;
+
+        let mut registry = Vec::with_capacity(config.len());
+        for (protocol, entry) in config {
+            let protocol: Protocol = protocol.parse()?;
+
+            let unknown = entry.unknown_code_hashes();
+            if !unknown.is_empty() {
+                eyre::bail!(
+                    "{protocol} has code hashes for {unknown:?}, which aren't factories or \
+                     routers of the protocol"
+                );
+            }
+
+            for (address, expected) in &entry.code_hashes {
+                let code = self
+                    .tracer
+                    .get_bytecode(None, *address)
+                    .await?
+                    .map(|code| code.original_bytes());
+                verify_code_hash(*address, *expected, code.as_deref())
+                    .wrap_err_with(|| format!("invalid protocol registry entry for {protocol}"))?;
+            }
+
+            if let Some((first, others)) = entry.factories.split_first() {
+                if !others.is_empty() {
+                    let expected = keccak256(
+                        self.tracer
+                            .get_bytecode(None, *first)
+                            .await?
+                            .map(|code| code.original_bytes())
+                            .unwrap_or_default(),
+                    );
+                    for factory in others {
+                        let code = self
+                            .tracer
+                            .get_bytecode(None, *factory)
+                            .await?
+                            .map(|code| code.original_bytes());
+                        verify_code_hash(*factory, expected, code.as_deref()).wrap_err_with(
+                            || {
+                                format!(
+                                    "{factory} is registered as a factory of {protocol} but \
+                                     doesn't run the code of {first}"
+                                )
+                            },
+                        )?;
+                    }
                 }
             }
+
+            registry.push(entry.into_protocol_metadata(protocol));
         }
+
+        for metadata in registry {
+            self.libmdbx.write_protocol_metadata(metadata).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Seeds the selector table from the shipped signatures, so the selector
+    /// fallback works without a clickhouse source for the table
+    async fn load_function_selectors_config(&self) -> eyre::Result<()> {
+        let config: FunctionSelectorConfig = read_config(FUNCTION_SELECTORS_CONFIG_FILE)?;
+
+        self.libmdbx
+            .write_function_selectors(config.into_selectors())
+            .await
     }
 }
 
+/// Reads and parses one of the toml configs in the workspace `config` dir
+fn read_config<T: for<'de> Deserialize<'de>>(file: &str) -> eyre::Result<T> {
+    let mut path = workspace_dir();
+    path.push(file);
+
+    let config = std::fs::read_to_string(&path)
+        .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+    toml::from_str(&config).wrap_err_with(|| format!("failed to parse {}", path.display()))
+}
+
+/// Returns the last modified time of each of the config files that get loaded
+/// into the database. Used to detect when the configs have been edited while
+/// running.
+pub fn config_modified_times() -> Vec<Option<SystemTime>> {
+    let workspace_dir = workspace_dir();
+    [
+        chain_spec().classifier_config,
+        SEARCHER_CONFIG_FILE,
+        BUILDER_CONFIG_FILE,
+        METADATA_CONFIG_FILE,
+        PROTOCOL_REGISTRY_CONFIG_FILE,
+        FUNCTION_SELECTORS_CONFIG_FILE,
+    ]
+    .iter()
+    .map(|file| {
+        std::fs::metadata(workspace_dir.join(file))
+            .and_then(|meta| meta.modified())
+            .ok()
+    })
+    .collect()
+}
+
+static WORKSPACE_DIR: OnceLock<path::PathBuf> = OnceLock::new();
+
+/// Locates the workspace once, the configs are polled for changes while
+/// following the tip and spawning cargo on every poll adds up
 fn workspace_dir() -> path::PathBuf {
-    let output = std::process::Command::new(env!("CARGO"))
-        .arg("locate-project")
-        .arg("--workspace")
-        .arg("--message-format=plain")
-        .output()
-        .unwrap()
-        .stdout;
-    let cargo_path = path::Path::new(std::str::from_utf8(&output).unwrap().trim());
-    cargo_path.parent().unwrap().to_path_buf()
+    WORKSPACE_DIR
+        .get_or_init(|| {
+            let output = std::process::Command::new(env!("CARGO"))
+                .arg("locate-project")
+                .arg("--workspace")
+                .arg("--message-format=plain")
+                .output()
+                .unwrap()
+                .stdout;
+            let cargo_path = path::Path::new(std::str::from_utf8(&output).unwrap().trim());
+            cargo_path.parent().unwrap().to_path_buf()
+        })
+        .clone()
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -583,6 +783,20 @@ struct MetadataConfig {
     pub metadata: FastHashMap<String, AddressMetadataConfig>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct FunctionSelectorConfig {
+    signatures: Vec<String>,
+}
+
+impl FunctionSelectorConfig {
+    fn into_selectors(self) -> Vec<FunctionSelector> {
+        self.signatures
+            .into_iter()
+            .map(FunctionSelector::new)
+            .collect()
+    }
+}
+
 impl AddressMetadataConfig {
     fn into_address_metadata(self) -> AddressMetadata {
         AddressMetadata {
@@ -624,6 +838,22 @@ mod tests {
     use itertools::Itertools;
     use tokio::sync::mpsc::unbounded_channel;
 
+    #[test]
+    fn test_function_selector_seed() {
+        let config: super::FunctionSelectorConfig =
+            super::read_config(super::FUNCTION_SELECTORS_CONFIG_FILE).unwrap();
+        let selectors = config.into_selectors();
+
+        assert!(!selectors.is_empty());
+        assert!(
+            selectors.iter().map(|s| s.selector()).all_unique(),
+            "the seed has colliding or duplicate signatures"
+        );
+        assert!(selectors
+            .iter()
+            .any(|s| s.selector().0 == [0xa9, 0x05, 0x9c, 0xbb]));
+    }
+
     #[brontes_macros::test]
     async fn test_intialize_clickhouse_tables() {
         let block_range = (19000000, 19000010);
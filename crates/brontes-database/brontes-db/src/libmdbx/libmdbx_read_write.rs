@@ -1,24 +1,36 @@
 use std::{ops::RangeInclusive, path::Path, sync::Arc};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, Selector};
 use brontes_metrics::db_reads::LibmdbxMetrics;
 use brontes_pricing::Protocol;
 use brontes_types::{
-    constants::{ETH_ADDRESS, WETH_ADDRESS},
+    chain::chain_spec,
+    constants::ETH_ADDRESS,
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        backfill_checkpoint::BackfillCheckpoint,
         builder::BuilderInfo,
+        builder_pnl::BuilderPnl,
         cex::{quotes::CexPriceMap, trades::CexTradeMap},
+        classification_failure::{BlockClassificationFailures, ClassificationFailure},
+        curve_pool_params::{CurveParamsUpdate, CurvePoolParams},
         dex::{make_filter_key_range, DexPrices, DexQuotes},
+        function_selector::FunctionSelector,
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DATA_NOT_PRESENT_NOT_AVAILABLE,
             DATA_PRESENT, DEX_PRICE_FLAG, META_FLAG,
         },
+        inspector_run::{BlockInspectorRuns, InspectorRun},
         metadata::{BlockMetadata, BlockMetadataInner, Metadata},
         mev_block::MevBlockWithClassified,
+        pool_fee_tier::PoolFeeTier,
+        protocol_metadata::ProtocolMetadata,
+        sanctions::SanctionInfo,
         searcher::SearcherInfo,
+        searcher_funding::SearcherFunding,
         token_info::{TokenInfo, TokenInfoWithAddress},
+        token_tax::TokenTax,
         traits::{DBWriter, LibmdbxReader},
     },
     mev::{Bundle, MevBlock},
@@ -35,11 +47,15 @@ use itertools::Itertools;
 use malachite::Rational;
 use reth_db::table::{Compress, Encode};
 use reth_interfaces::db::LogLevel;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
-use tracing::{info, instrument};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    oneshot, Notify,
+};
+use tracing::{info, instrument, warn};
 
 use super::{
     libmdbx_writer::{LibmdbxWriter, StampedWriterMessage, WriterMessage},
+    tiering::ColdStorage,
     types::ReturnKV,
     ReadWriteCache,
 };
@@ -72,6 +88,22 @@ pub trait LibmdbxInit: LibmdbxReader + DBWriter {
         metrics: bool,
     ) -> impl Future<Output = eyre::Result<()>> + Send;
 
+    /// Reloads the sanctioned address set, dropping addresses that have been
+    /// delisted since the last load
+    fn refresh_sanctioned_addresses<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send;
+
+    /// Re-applies the toml configs (classifier, searcher, builder and address
+    /// metadata) to the database
+    fn reload_config<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send;
+
     /// initializes all the tables with missing data ranges via the CLI
     fn initialize_table_arbitrary<T: TracingProvider, CH: ClickhouseHandle>(
         &'static self,
@@ -94,11 +126,13 @@ pub trait LibmdbxInit: LibmdbxReader + DBWriter {
 
 #[derive(Clone)]
 pub struct LibmdbxReadWriter {
-    pub db:  Arc<Libmdbx>,
-    pub tx:  UnboundedSender<StampedWriterMessage>,
-    metrics: Option<LibmdbxMetrics>,
+    pub db:          Arc<Libmdbx>,
+    pub tx:          UnboundedSender<StampedWriterMessage>,
+    metrics:         Option<LibmdbxMetrics>,
     // 100 shards for now, might change in future
-    cache:   ReadWriteCache,
+    cache:           ReadWriteCache,
+    /// archived block ranges, see [`ColdStorage`]
+    pub(super) cold: Arc<ColdStorage>,
 }
 
 impl LibmdbxReadWriter {
@@ -112,6 +146,7 @@ impl LibmdbxReadWriter {
         let memory_per_table_mb = 1_000;
         let (tx, rx) = unbounded_channel();
         let yapper = UnboundedYapperReceiver::new(rx, 1500, "libmdbx write channel".to_string());
+        let cold = Arc::new(ColdStorage::open(path.as_ref())?);
         let db = Arc::new(Libmdbx::init_db(path, log_level)?);
         let shutdown = ex.get_graceful_shutdown();
 
@@ -124,6 +159,7 @@ impl LibmdbxReadWriter {
             tx,
             metrics: metrics.then(LibmdbxMetrics::default),
             cache: ReadWriteCache::new(memory_per_table_mb, metrics),
+            cold,
         })
     }
 
@@ -132,13 +168,20 @@ impl LibmdbxReadWriter {
         let memory_per_table_mb = 1_000;
         let (tx, rx) = unbounded_channel();
         let yapper = UnboundedYapperReceiver::new(rx, 1500, "libmdbx write channel".to_string());
+        let cold = Arc::new(ColdStorage::open(path.as_ref())?);
         let db = Arc::new(Libmdbx::init_db(path, None)?);
 
         // start writing task on own thread
         let writer = LibmdbxWriter::new(db.clone(), yapper, false);
         writer.run_no_shutdown();
 
-        Ok(Self { db, tx, metrics: None, cache: ReadWriteCache::new(memory_per_table_mb, false) })
+        Ok(Self {
+            db,
+            tx,
+            metrics: None,
+            cache: ReadWriteCache::new(memory_per_table_mb, false),
+            cold,
+        })
     }
 }
 
@@ -194,6 +237,24 @@ impl LibmdbxInit for LibmdbxReadWriter {
         Ok(())
     }
 
+    async fn refresh_sanctioned_addresses<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        let initializer = LibmdbxInitializer::new(self, clickhouse, tracer, false);
+        initializer.refresh_sanctioned_addresses().await
+    }
+
+    async fn reload_config<T: TracingProvider, CH: ClickhouseHandle>(
+        &'static self,
+        clickhouse: &'static CH,
+        tracer: Arc<T>,
+    ) -> eyre::Result<()> {
+        let initializer = LibmdbxInitializer::new(self, clickhouse, tracer, false);
+        initializer.load_config().await
+    }
+
     fn state_to_initialize(
         &self,
         start_block: u64,
@@ -423,38 +484,88 @@ impl LibmdbxReader for LibmdbxReadWriter {
 
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"load_trace")]
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
-        self.db.view_db(|tx| {
-            tx.get::<TxTraces>(block_num)?
-                .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
-                .map(|i| {
-                    i.traces
-                        .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
-                })?
-        })
+        let traces = match self.db.view_db(|tx| Ok(tx.get::<TxTraces>(block_num)?))? {
+            Some(traces) => traces,
+            // older blocks might have been moved to cold storage
+            None => self
+                .cold
+                .get_block::<TxTraces>(block_num)?
+                .and_then(|traces| traces.into_iter().next())
+                .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))?,
+        };
+
+        traces
+            .traces
+            .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
     }
 
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"protocol_info")]
     fn get_protocol_details(&self, address: Address) -> eyre::Result<ProtocolInfo> {
-        self.db.view_db(|tx| {
-            match self
-                .cache
-                .protocol_info(true, |handle| handle.get(&address))
-            {
-                Some(Some(e)) => Ok(e.clone()),
-                Some(None) => {
-                    Err(eyre::eyre!("entry for key {:?} in AddressToProtocolInfo", address))
-                }
-                None => tx
-                    .get::<AddressToProtocolInfo>(address)
+        // only open a transaction if the entry isn't cached
+        let entry = match self
+            .cache
+            .protocol_info(true, |handle| handle.get(&address))
+        {
+            Some(entry) => entry,
+            None => self.db.view_db(|tx| {
+                tx.get::<AddressToProtocolInfo>(address)
                     .inspect(|data| {
                         self.cache.protocol_info(false, |lock| {
                             lock.get_with(address, || data.clone());
                         })
-                    })?
-                    .ok_or_else(|| {
-                        eyre::eyre!("entry for key {:?} in AddressToProtocolInfo", address)
-                    }),
+                    })
+                    .map_err(Into::into)
+            })?,
+        };
+
+        entry.ok_or_else(|| eyre::eyre!("entry for key {:?} in AddressToProtocolInfo", address))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"prefetch_classification_data")]
+    fn prefetch_classification_data(&self, addresses: &[Address]) -> eyre::Result<()> {
+        let protocols = addresses
+            .iter()
+            .filter(|address| {
+                !self
+                    .cache
+                    .protocol_info(true, |handle| handle.contains_key(*address))
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        let tokens = addresses
+            .iter()
+            .map(|address| {
+                if *address == ETH_ADDRESS {
+                    chain_spec().wrapped_native
+                } else {
+                    *address
+                }
+            })
+            .filter(|address| {
+                !self
+                    .cache
+                    .token_info(true, |handle| handle.contains_key(address))
+            })
+            .unique()
+            .collect::<Vec<_>>();
+
+        if protocols.is_empty() && tokens.is_empty() {
+            return Ok(())
+        }
+
+        self.db.view_db(|tx| {
+            for address in protocols {
+                let data = tx.get::<AddressToProtocolInfo>(address)?;
+                self.cache
+                    .protocol_info(false, |lock| lock.insert(address, data));
+            }
+            for address in tokens {
+                let data = tx.get::<TokenDecimals>(address)?;
+                self.cache
+                    .token_info(false, |lock| lock.insert(address, data));
             }
+
+            Ok(())
         })
     }
 
@@ -480,6 +591,8 @@ impl LibmdbxReader for LibmdbxReadWriter {
             block_meta.proposer_mev_reward,
             eth_price.unwrap_or_default(),
             block_meta.private_flow.into_iter().collect(),
+            block_meta.blob_gas_used,
+            block_meta.excess_blob_gas,
         )
         .into_metadata(cex_quotes, None, None, None))
     }
@@ -504,6 +617,8 @@ impl LibmdbxReader for LibmdbxReadWriter {
                 block_meta.proposer_mev_reward,
                 eth_price.unwrap_or_default(),
                 block_meta.private_flow.into_iter().collect(),
+                block_meta.blob_gas_used,
+                block_meta.excess_blob_gas,
             )
             .into_metadata(cex_quotes, Some(dex_quotes), None, None)
         })
@@ -511,37 +626,33 @@ impl LibmdbxReader for LibmdbxReadWriter {
 
     #[brontes_macros::metrics_call(ptr=metrics,scope, db_read, "try_fetch_token_info")]
     fn try_fetch_token_info(&self, og_address: Address) -> eyre::Result<TokenInfoWithAddress> {
-        let address = if og_address == ETH_ADDRESS { WETH_ADDRESS } else { og_address };
-
-        self.db
-            .view_db(|tx| match self.cache.token_info(true, |lock| lock.get(&address)) {
-                Some(Some(e)) => {
-                    let mut info = TokenInfoWithAddress { inner: e, address: og_address };
-                    if og_address == ETH_ADDRESS {
-                        info.symbol = "ETH".to_string();
-                    }
-                    Ok(info)
-                }
-                Some(None) => Err(eyre::eyre!("entry for key {:?} in TokenDecimals", address)),
-                None => tx
-                    .get::<TokenDecimals>(address)
+        let address =
+            if og_address == ETH_ADDRESS { chain_spec().wrapped_native } else { og_address };
+
+        // only open a transaction if the entry isn't cached
+        let entry = match self.cache.token_info(true, |lock| lock.get(&address)) {
+            Some(entry) => entry,
+            None => self.db.view_db(|tx| {
+                tx.get::<TokenDecimals>(address)
                     .inspect(|data| {
                         self.cache.token_info(false, |lock| {
                             lock.get_with(address, || data.clone());
                         })
-                    })?
-                    .map(|inner| TokenInfoWithAddress { inner, address: og_address })
-                    .map(|mut inner| {
-                        // quick patch
-                        if og_address == ETH_ADDRESS {
-                            inner.symbol = "ETH".to_string();
-                            inner
-                        } else {
-                            inner
-                        }
                     })
-                    .ok_or_else(|| eyre::eyre!("entry for key {:?} in TokenDecimals", address)),
+                    .map_err(Into::into)
+            })?,
+        };
+
+        entry
+            .map(|inner| TokenInfoWithAddress { inner, address: og_address })
+            .map(|mut inner| {
+                // quick patch
+                if og_address == ETH_ADDRESS {
+                    inner.symbol = "ETH".to_string();
+                }
+                inner
             })
+            .ok_or_else(|| eyre::eyre!("entry for key {:?} in TokenDecimals", address))
     }
 
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_searcher_eoa_infos")]
@@ -708,10 +819,7 @@ impl LibmdbxReader for LibmdbxReadWriter {
                     continue;
                 };
 
-                map.insert(
-                    (addr, protocol_info.protocol),
-                    Pair(protocol_info.token0, protocol_info.token1),
-                );
+                map.insert((addr, protocol_info.protocol), protocol_info.pair());
             }
         }
 
@@ -740,7 +848,7 @@ impl LibmdbxReader for LibmdbxReadWriter {
                 map.entry(block).or_insert(vec![]).push((
                     addr,
                     protocol_info.protocol,
-                    Pair(protocol_info.token0, protocol_info.token1),
+                    protocol_info.pair(),
                 ));
             }
         }
@@ -812,6 +920,155 @@ impl LibmdbxReader for LibmdbxReadWriter {
         })
     }
 
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_sanction_info")]
+    fn try_fetch_sanction_info(&self, address: Address) -> eyre::Result<Option<SanctionInfo>> {
+        self.db.view_db(|tx| {
+            tx.get::<SanctionedAddresses>(address)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    fn fetch_sanctioned(
+        &self,
+        addresses: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SanctionInfo>> {
+        self.db.view_db(|tx| {
+            let mut res = FastHashMap::default();
+            for addr in addresses {
+                if let Some(info) = tx.get::<SanctionedAddresses>(addr)? {
+                    res.insert(addr, info);
+                }
+            }
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_curve_pool_params")]
+    fn try_fetch_curve_pool_params(
+        &self,
+        address: Address,
+    ) -> eyre::Result<Option<CurvePoolParams>> {
+        self.db.view_db(|tx| {
+            tx.get::<CurvePoolParameters>(address)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_searcher_funding")]
+    fn try_fetch_searcher_funding(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<SearcherFunding>> {
+        self.db.view_db(|tx| {
+            tx.get::<SearcherFundingSources>(searcher_eoa)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_token_tax")]
+    fn try_fetch_token_tax(&self, token: Address) -> eyre::Result<Option<TokenTax>> {
+        self.db
+            .view_db(|tx| tx.get::<TokenTaxes>(token).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_pool_fee_tier")]
+    fn try_fetch_pool_fee_tier(&self, pool: Address) -> eyre::Result<Option<PoolFeeTier>> {
+        self.db
+            .view_db(|tx| tx.get::<PoolFeeTiers>(pool).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_inspector_runs")]
+    fn try_fetch_inspector_runs(&self, block_num: u64) -> eyre::Result<Option<BlockInspectorRuns>> {
+        self.db
+            .view_db(|tx| tx.get::<InspectorRuns>(block_num).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_classification_failures")]
+    fn try_fetch_classification_failures(
+        &self,
+        block_num: u64,
+    ) -> eyre::Result<Option<BlockClassificationFailures>> {
+        self.db.view_db(|tx| {
+            tx.get::<ClassificationFailures>(block_num)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_builder_pnl")]
+    fn try_fetch_builder_pnl(&self, block_num: u64) -> eyre::Result<Option<BuilderPnl>> {
+        self.db
+            .view_db(|tx| tx.get::<BuilderPnls>(block_num).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_builder_pnls")]
+    fn fetch_builder_pnls(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<BuilderPnl>> {
+        self.db.view_db(|tx| {
+            tx.cursor_read::<BuilderPnls>()?
+                .walk_range(start_block..=end_block)?
+                .map(|row| {
+                    row.map(|(_, builder_pnl)| builder_pnl)
+                        .map_err(ErrReport::from)
+                })
+                .collect()
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_protocol_metadata")]
+    fn try_fetch_protocol_metadata(
+        &self,
+        protocol: Protocol,
+    ) -> eyre::Result<Option<ProtocolMetadata>> {
+        self.db.view_db(|tx| {
+            tx.get::<ProtocolRegistry>(protocol.to_byte() as u32)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_protocol_registry")]
+    fn fetch_protocol_registry(&self) -> eyre::Result<Vec<ProtocolMetadata>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<ProtocolRegistry>()?;
+            cursor
+                .walk(None)?
+                .map(|row| row.map(|row| row.1).map_err(ErrReport::from))
+                .collect()
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_function_selector")]
+    fn try_fetch_function_selector(
+        &self,
+        selector: Selector,
+    ) -> eyre::Result<Option<FunctionSelector>> {
+        self.db.view_db(|tx| {
+            tx.get::<FunctionSelectors>(u32::from_be_bytes(selector.0))
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[instrument(level = "error", skip_all)]
+    fn fetch_backfill_checkpoints(&self) -> eyre::Result<Vec<(u64, BackfillCheckpoint)>> {
+        self.db.export_db(
+            None,
+            |start_key, tx| {
+                let mut cur = tx.cursor_read::<BackfillCheckpoints>()?;
+                if let Some(key) = start_key {
+                    let _ = cur.seek(key);
+                } else {
+                    // move to first entry and make sure .next() is first
+                    let _ = cur.first();
+                    let _ = cur.prev();
+                }
+                Ok(cur)
+            },
+            |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
+        )
+    }
+
     #[instrument(level = "error", skip_all)]
     fn fetch_all_builder_info(&self) -> eyre::Result<Vec<(Address, BuilderInfo)>> {
         self.db.export_db(
@@ -858,6 +1115,12 @@ impl LibmdbxReader for LibmdbxReadWriter {
         )
     }
 
+    #[instrument(level = "error", skip_all)]
+    fn try_fetch_mev_block(&self, block: u64) -> eyre::Result<Option<MevBlockWithClassified>> {
+        self.db
+            .view_db(|tx| tx.get::<MevBlocks>(block).map_err(ErrReport::from))
+    }
+
     #[instrument(level = "error", skip_all)]
     fn fetch_all_mev_blocks(
         &self,
@@ -1009,14 +1272,22 @@ impl DBWriter for LibmdbxReadWriter {
         decimals: u8,
         symbol: String,
     ) -> eyre::Result<()> {
+        let mut info = TokenInfo::new(decimals, symbol);
+        if let Ok(previous) = self.try_fetch_token_info(address) {
+            info = info.checked_against(&previous);
+        }
+
+        if let Some(anomaly) = info.decimals_anomaly {
+            warn!(?address, symbol=%info.symbol, ?anomaly, "token has anomalous decimals, it won't be priced");
+        }
+
         self.cache.token_info(false, |handle| {
-            let token_info = TokenInfo::new(decimals, symbol.clone());
-            handle.insert(address, Some(token_info.clone()));
+            handle.insert(address, Some(info.clone()));
         });
 
         Ok(self
             .tx
-            .send(WriterMessage::TokenInfo { address, decimals, symbol }.stamp())?)
+            .send(WriterMessage::TokenInfo { address, info }.stamp())?)
     }
 
     async fn insert_pool(
@@ -1028,19 +1299,8 @@ impl DBWriter for LibmdbxReadWriter {
         classifier_name: Protocol,
     ) -> eyre::Result<()> {
         self.cache.protocol_info(false, |handle| {
-            let mut tokens_i = tokens.iter();
-            let default = Address::ZERO;
-            let details = ProtocolInfo {
-                protocol: classifier_name,
-                init_block: block,
-                token0: *tokens_i.next().unwrap_or(&default),
-                token1: *tokens_i.next().unwrap_or(&default),
-                token2: tokens_i.next().cloned(),
-                token3: tokens_i.next().cloned(),
-                token4: tokens_i.next().cloned(),
-                curve_lp_token,
-            };
-            handle.insert(address, Some(details.clone()));
+            let details = ProtocolInfo::new(classifier_name, tokens, curve_lp_token, block);
+            handle.insert(address, Some(details));
         });
 
         Ok(self.tx.send(
@@ -1072,6 +1332,136 @@ impl DBWriter for LibmdbxReadWriter {
         )?)
     }
 
+    async fn update_curve_pool_params(
+        &self,
+        address: Address,
+        block: u64,
+        update: CurveParamsUpdate,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::CurvePoolParams { address, block, update }.stamp())?)
+    }
+
+    /// Also sets the funding parent of the searchers that don't have one
+    /// configured from their funding, including the block's
+    async fn write_searcher_funding(
+        &self,
+        funding: Vec<(Address, SearcherFunding)>,
+    ) -> eyre::Result<()> {
+        for (eoa, block_funding) in &funding {
+            let Some(mut info) = self
+                .try_fetch_searcher_eoa_info(*eoa)?
+                .filter(|info| info.funding_parent.is_none())
+            else {
+                continue
+            };
+            let parent = match self.try_fetch_searcher_funding(*eoa)? {
+                Some(stored) => stored.merge(block_funding.clone()).funding_parent(),
+                None => block_funding.funding_parent(),
+            };
+
+            if parent.is_some() {
+                info.funding_parent = parent;
+                self.write_searcher_eoa_info(*eoa, info).await?;
+            }
+        }
+
+        Ok(self
+            .tx
+            .send(WriterMessage::SearcherFunding { funding }.stamp())?)
+    }
+
+    async fn insert_pool_fee_tier(
+        &self,
+        address: Address,
+        fee_tier: PoolFeeTier,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::PoolFeeTier { address, fee_tier }.stamp())?)
+    }
+
+    async fn write_token_taxes(&self, taxes: Vec<(Address, TokenTax)>) -> eyre::Result<()> {
+        Ok(self.tx.send(WriterMessage::TokenTaxes { taxes }.stamp())?)
+    }
+
+    async fn write_protocol_metadata(&self, metadata: ProtocolMetadata) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::ProtocolMetadata { metadata }.stamp())?)
+    }
+
+    async fn write_function_selectors(&self, selectors: Vec<FunctionSelector>) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::FunctionSelectors { selectors }.stamp())?)
+    }
+
+    async fn write_inspector_runs(
+        &self,
+        block_number: u64,
+        runs: Vec<InspectorRun>,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::InspectorRuns { block: block_number, runs }.stamp())?)
+    }
+
+    async fn write_classification_failure(
+        &self,
+        block_number: u64,
+        failure: ClassificationFailure,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::ClassificationFailure { block: block_number, failure }.stamp())?)
+    }
+
+    async fn write_builder_pnl(&self, builder_pnl: BuilderPnl) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::BuilderPnl { builder_pnl }.stamp())?)
+    }
+
+    /// Waits for the removal, so that the blocks aren't loaded from the cached
+    /// traces when they are traced again
+    async fn invalidate_blocks(&self, blocks: RangeInclusive<u64>) -> eyre::Result<()> {
+        // archived traces and quotes of the blocks are stale as well
+        self.cold.invalidate_blocks(&blocks)?;
+
+        let done = Arc::new(Notify::new());
+        self.tx
+            .send(WriterMessage::InvalidateBlocks { blocks, done: done.clone() }.stamp())?;
+        done.notified().await;
+
+        Ok(())
+    }
+
+    /// Waits for the replacement, so that the results are in place once the
+    /// replay returns and a failed replacement is reported
+    async fn replace_mev_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> eyre::Result<()> {
+        let (done, replaced) = oneshot::channel();
+        self.tx
+            .send(WriterMessage::ReplaceMevBlocks { blocks, mev_blocks, done }.stamp())?;
+
+        replaced.await?
+    }
+
+    async fn save_backfill_checkpoint(
+        &self,
+        start_block: u64,
+        checkpoint: BackfillCheckpoint,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::BackfillCheckpoint { start_block, checkpoint }.stamp())?)
+    }
+
     /// only for internal functionality (i.e. clickhouse)
     async fn insert_tree(&self, _tree: BlockTree<Action>) -> eyre::Result<()> {
         Ok(())
@@ -1084,6 +1474,70 @@ impl DBWriter for LibmdbxReadWriter {
     ) -> eyre::Result<()> {
         Ok(())
     }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_gas_bids(
+        &self,
+        _: Vec<brontes_types::db::gas_bids::GasBid>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_searcher_block_bids(
+        &self,
+        _: Vec<brontes_types::db::gas_bids::SearcherBlockBids>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_searcher_fingerprints(
+        &self,
+        _: Vec<brontes_types::db::searcher_fingerprint::SearcherFingerprint>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_searcher_inventory(
+        &self,
+        _: Vec<brontes_types::db::searcher_inventory::SearcherInventoryDelta>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_victim_execution(
+        &self,
+        _: Vec<brontes_types::db::victim_execution::VictimExecution>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_possible_mev_timeline(
+        &self,
+        _: Vec<brontes_types::db::possible_mev_timeline::PossibleMevTimeline>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_block_provenance(
+        &self,
+        _: brontes_types::db::block_provenance::BlockProvenance,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// only for internal functionality (i.e. clickhouse)
+    async fn write_suppressed_bundles(
+        &self,
+        _: Vec<brontes_types::db::suppressed_bundle::SuppressedBundle>,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
 }
 
 impl LibmdbxReadWriter {
@@ -1252,35 +1706,44 @@ impl LibmdbxReadWriter {
     }
 
     pub fn fetch_dex_quotes(&self, block_num: u64) -> eyre::Result<DexQuotes> {
-        let mut dex_quotes: Vec<Option<FastHashMap<Pair, DexPrices>>> = Vec::new();
         let (start_range, end_range) = make_filter_key_range(block_num);
-        self.db.view_db(|tx| {
-            tx.cursor_read::<DexPrice>()?
+        let mut quotes = self.db.view_db(|tx| {
+            Ok(tx
+                .cursor_read::<DexPrice>()?
                 .walk_range(start_range..=end_range)?
-                .for_each(|inner| {
-                    if let Ok((_, val)) = inner.map(|row| (row.0, row.1)) {
-                        for _ in dex_quotes.len()..=val.tx_idx as usize {
-                            dex_quotes.push(None);
-                        }
+                .filter_map(|inner| inner.ok().map(|row| row.1))
+                .collect_vec())
+        })?;
+        // older blocks might have been moved to cold storage
+        if quotes.is_empty() {
+            quotes = self
+                .cold
+                .get_block::<DexPrice>(block_num)?
+                .unwrap_or_default();
+        }
 
-                        let tx = dex_quotes.get_mut(val.tx_idx as usize).unwrap();
+        let mut dex_quotes: Vec<Option<FastHashMap<Pair, DexPrices>>> = Vec::new();
+        for val in quotes {
+            for _ in dex_quotes.len()..=val.tx_idx as usize {
+                dex_quotes.push(None);
+            }
 
-                        if let Some(tx) = tx.as_mut() {
-                            for (pair, price) in val.quote {
-                                tx.insert(pair, price);
-                            }
-                        } else {
-                            let mut tx_pairs = FastHashMap::default();
-                            for (pair, price) in val.quote {
-                                tx_pairs.insert(pair, price);
-                            }
-                            *tx = Some(tx_pairs);
-                        }
-                    }
-                });
+            let tx = dex_quotes.get_mut(val.tx_idx as usize).unwrap();
 
-            Ok(DexQuotes(dex_quotes))
-        })
+            if let Some(tx) = tx.as_mut() {
+                for (pair, price) in val.quote {
+                    tx.insert(pair, price);
+                }
+            } else {
+                let mut tx_pairs = FastHashMap::default();
+                for (pair, price) in val.quote {
+                    tx_pairs.insert(pair, price);
+                }
+                *tx = Some(tx_pairs);
+            }
+        }
+
+        Ok(DexQuotes(dex_quotes))
     }
 
     pub fn send_message(&self, message: WriterMessage) -> eyre::Result<()> {
@@ -1295,12 +1758,17 @@ impl LibmdbxReadWriter {
         Ok(self.db.ro_tx()?.entries::<T>()?)
     }
 
+    /// The highest block with traces, in the database or in cold storage
     pub fn get_highest_block_number(&self) -> eyre::Result<u64> {
-        self.db
+        let hot = self
+            .db
             .ro_tx()?
             .cursor_read::<TxTraces>()?
             .last()?
-            .map(|v| v.0)
+            .map(|v| v.0);
+        let cold = self.cold.highest_block(Tables::TxTraces)?;
+
+        hot.max(cold)
             .ok_or_else(|| eyre::eyre!("no max block found"))
     }
 }
@@ -1313,7 +1781,7 @@ pub fn determine_eth_prices(
     Some(
         cex_quotes
             .get_quote_from_most_liquid_exchange(
-                &Pair(quote_asset, WETH_ADDRESS),
+                &Pair(quote_asset, chain_spec().wrapped_native),
                 block_timestamp,
                 None,
             )?
@@ -1,5 +1,5 @@
 use std::{
-    ops::Deref,
+    ops::{Deref, RangeInclusive},
     sync::Arc,
     task::Poll,
     time::{Duration, Instant},
@@ -11,13 +11,23 @@ use brontes_types::{
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        backfill_checkpoint::BackfillCheckpoint,
         builder::BuilderInfo,
-        dex::{make_key, DexQuoteWithIndex, DexQuotes},
-        initialized_state::{DATA_PRESENT, DEX_PRICE_FLAG, TRACE_FLAG},
+        builder_pnl::BuilderPnl,
+        classification_failure::ClassificationFailure,
+        curve_pool_params::CurveParamsUpdate,
+        dex::{make_filter_key_range, make_key, DexQuoteWithIndex, DexQuotes},
+        function_selector::FunctionSelector,
+        initialized_state::{DATA_NOT_PRESENT_UNKNOWN, DATA_PRESENT, DEX_PRICE_FLAG, TRACE_FLAG},
+        inspector_run::{BlockInspectorRuns, InspectorRun},
         mev_block::MevBlockWithClassified,
         pool_creation_block::PoolsToAddresses,
+        pool_fee_tier::PoolFeeTier,
+        protocol_metadata::ProtocolMetadata,
         searcher::SearcherInfo,
+        searcher_funding::SearcherFunding,
         token_info::TokenInfo,
+        token_tax::TokenTax,
         traces::TxTracesInner,
     },
     mev::{Bundle, MevBlock},
@@ -31,7 +41,7 @@ use reth_db::{
     DatabaseError,
 };
 use reth_tasks::shutdown::GracefulShutdown;
-use tokio::sync::Notify;
+use tokio::sync::{oneshot, Notify};
 use tracing::instrument;
 
 use crate::{
@@ -55,9 +65,8 @@ pub enum WriterMessage {
         quotes:       Option<DexQuotes>,
     },
     TokenInfo {
-        address:  Address,
-        decimals: u8,
-        symbol:   String,
+        address: Address,
+        info:    TokenInfo,
     },
     MevBlocks {
         block_number: u64,
@@ -97,6 +106,51 @@ pub enum WriterMessage {
         block:  u64,
         traces: Vec<TxTrace>,
     },
+    CurvePoolParams {
+        address: Address,
+        block:   u64,
+        update:  CurveParamsUpdate,
+    },
+    BackfillCheckpoint {
+        start_block: u64,
+        checkpoint:  BackfillCheckpoint,
+    },
+    SearcherFunding {
+        funding: Vec<(Address, SearcherFunding)>,
+    },
+    TokenTaxes {
+        taxes: Vec<(Address, TokenTax)>,
+    },
+    PoolFeeTier {
+        address:  Address,
+        fee_tier: PoolFeeTier,
+    },
+    ProtocolMetadata {
+        metadata: ProtocolMetadata,
+    },
+    FunctionSelectors {
+        selectors: Vec<FunctionSelector>,
+    },
+    InspectorRuns {
+        block: u64,
+        runs:  Vec<InspectorRun>,
+    },
+    BuilderPnl {
+        builder_pnl: BuilderPnl,
+    },
+    ClassificationFailure {
+        block:   u64,
+        failure: ClassificationFailure,
+    },
+    InvalidateBlocks {
+        blocks: RangeInclusive<u64>,
+        done:   Arc<Notify>,
+    },
+    ReplaceMevBlocks {
+        blocks:     RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+        done:       oneshot::Sender<eyre::Result<()>>,
+    },
     Init(InitTables, Arc<Notify>),
 }
 
@@ -214,7 +268,18 @@ init!(
     MevBlocks,
     SearcherEOAs,
     SearcherContracts,
-    InitializedState
+    InitializedState,
+    SanctionedAddresses,
+    CurvePoolParameters,
+    FunctionSelectors,
+    BackfillCheckpoints,
+    SearcherFundingSources,
+    TokenTaxes,
+    PoolFeeTiers,
+    ProtocolRegistry,
+    InspectorRuns,
+    BuilderPnls,
+    ClassificationFailures
 );
 
 /// due to libmdbx's 1 write tx limit. it makes sense
@@ -250,8 +315,8 @@ impl LibmdbxWriter {
                 self.write_dex_quotes(block_number, quotes)?;
                 "dexquotes"
             }
-            WriterMessage::TokenInfo { address, decimals, symbol } => {
-                self.write_token_info(address, decimals, symbol)?;
+            WriterMessage::TokenInfo { address, info } => {
+                self.write_token_info(address, info)?;
                 "tokeninfo"
             }
             WriterMessage::MevBlocks { block_number, block, mev } => {
@@ -283,6 +348,58 @@ impl LibmdbxWriter {
                 self.write_searcher_contract_info(searcher_contract, *searcher_info)?;
                 "searchercontractinfo"
             }
+            WriterMessage::CurvePoolParams { address, block, update } => {
+                self.update_curve_pool_params(address, block, update)?;
+                "curvepoolparams"
+            }
+            WriterMessage::BackfillCheckpoint { start_block, checkpoint } => {
+                self.save_backfill_checkpoint(start_block, checkpoint)?;
+                "backfillcheckpoint"
+            }
+            WriterMessage::SearcherFunding { funding } => {
+                self.write_searcher_funding(funding)?;
+                "searcherfunding"
+            }
+            WriterMessage::TokenTaxes { taxes } => {
+                self.write_token_taxes(taxes)?;
+                "tokentaxes"
+            }
+            WriterMessage::PoolFeeTier { address, fee_tier } => {
+                self.write_pool_fee_tier(address, fee_tier)?;
+                "poolfeetier"
+            }
+            WriterMessage::ProtocolMetadata { metadata } => {
+                self.write_protocol_metadata(metadata)?;
+                "protocolmetadata"
+            }
+            WriterMessage::FunctionSelectors { selectors } => {
+                self.write_function_selectors(selectors)?;
+                "functionselectors"
+            }
+            WriterMessage::InspectorRuns { block, runs } => {
+                self.write_inspector_runs(block, runs)?;
+                "inspectorruns"
+            }
+            WriterMessage::BuilderPnl { builder_pnl } => {
+                self.write_builder_pnl(builder_pnl)?;
+                "builderpnl"
+            }
+            WriterMessage::ClassificationFailure { block, failure } => {
+                self.write_classification_failure(block, failure)?;
+                "classificationfailure"
+            }
+            WriterMessage::InvalidateBlocks { blocks, done } => {
+                let res = self.invalidate_blocks(blocks);
+                done.notify_one();
+                res?;
+                "invalidateblocks"
+            }
+            WriterMessage::ReplaceMevBlocks { blocks, mev_blocks, done } => {
+                // the outcome goes back to the replay, a failed replacement
+                // left the stored results untouched
+                let _ = done.send(self.replace_mev_blocks(blocks, mev_blocks));
+                "replacemevblocks"
+            }
             WriterMessage::Init(init, not) => {
                 init.write_data(self.db.clone())?;
                 not.notify_one();
@@ -458,10 +575,9 @@ impl LibmdbxWriter {
     }
 
     #[instrument(target = "libmdbx_read_write::write_token_info", skip_all, level = "warn")]
-    fn write_token_info(&self, address: Address, decimals: u8, symbol: String) -> eyre::Result<()> {
+    fn write_token_info(&self, address: Address, info: TokenInfo) -> eyre::Result<()> {
         self.instrumented_write::<TokenDecimals, TokenDecimalsData>(&[TokenDecimalsData::new(
-            address,
-            TokenInfo::new(decimals, symbol),
+            address, info,
         )])
         .expect("libmdbx write failure");
         Ok(())
@@ -477,21 +593,10 @@ impl LibmdbxWriter {
         classifier_name: Protocol,
     ) -> eyre::Result<()> {
         // add to default table
-        let mut tokens = tokens.iter();
-        let default = Address::ZERO;
         self.instrumented_write::<AddressToProtocolInfo, AddressToProtocolInfoData>(&[
             AddressToProtocolInfoData::new(
                 address,
-                ProtocolInfo {
-                    protocol: classifier_name,
-                    init_block: block,
-                    token0: *tokens.next().unwrap_or(&default),
-                    token1: *tokens.next().unwrap_or(&default),
-                    token2: tokens.next().cloned(),
-                    token3: tokens.next().cloned(),
-                    token4: tokens.next().cloned(),
-                    curve_lp_token,
-                },
+                ProtocolInfo::new(classifier_name, tokens, curve_lp_token, block),
             ),
         ])
         .expect("libmdbx write failure");
@@ -542,6 +647,292 @@ impl LibmdbxWriter {
         Ok(())
     }
 
+    /// Updates are applied on top of the stored parameters here, as the writer
+    /// is the only one that sees every update in order
+    #[instrument(target = "libmdbx_read_write::update_curve_pool_params", skip_all, level = "warn")]
+    fn update_curve_pool_params(
+        &self,
+        address: Address,
+        block: u64,
+        update: CurveParamsUpdate,
+    ) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let mut params = tx.get::<CurvePoolParameters>(address)?.unwrap_or_default();
+        tx.commit()?;
+
+        params.apply(block, update);
+        self.instrumented_write::<CurvePoolParameters, CurvePoolParametersData>(&[
+            CurvePoolParametersData::new(address, params),
+        ])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// Checkpoints of a range can arrive out of order, so the stored progress
+    /// is only ever moved forward
+    #[instrument(target = "libmdbx_read_write::save_backfill_checkpoint", skip_all, level = "warn")]
+    fn save_backfill_checkpoint(
+        &self,
+        start_block: u64,
+        checkpoint: BackfillCheckpoint,
+    ) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let checkpoint = tx
+            .get::<BackfillCheckpoints>(start_block)?
+            .map_or(checkpoint, |stored| stored.merge(checkpoint));
+        tx.commit()?;
+
+        self.instrumented_write::<BackfillCheckpoints, BackfillCheckpointsData>(&[
+            BackfillCheckpointsData::new(start_block, checkpoint),
+        ])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// The funding of a block is only what was seen in the block, so it is
+    /// added to the funding stored for the searcher
+    #[instrument(target = "libmdbx_read_write::write_searcher_funding", skip_all, level = "warn")]
+    fn write_searcher_funding(&self, funding: Vec<(Address, SearcherFunding)>) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let data = funding
+            .into_iter()
+            .map(|(eoa, funding)| {
+                let funding = match tx.get::<SearcherFundingSources>(eoa)? {
+                    Some(stored) => stored.merge(funding),
+                    None => funding,
+                };
+                Ok(SearcherFundingSourcesData::new(eoa, funding))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        tx.commit()?;
+
+        self.instrumented_write::<SearcherFundingSources, SearcherFundingSourcesData>(&data)
+            .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// Removes the results, dex prices and traces of blocks that were reorged
+    /// out. Traces are cached per block number, so they have to go for the
+    /// canonical block to be traced again. Searcher funding and backfill
+    /// progress accumulate over blocks, so only the stale blocks' part of them
+    /// is reverted. The queued writes are flushed first so that none of them
+    /// land after the removal.
+    #[instrument(target = "libmdbx_read_write::invalidate_blocks", skip_all, level = "warn")]
+    fn invalidate_blocks(&mut self, blocks: RangeInclusive<u64>) -> eyre::Result<()> {
+        self.insert_remaining();
+
+        let tx = self.db.rw_tx()?;
+
+        // progress past the first stale block is reverted so a backfill
+        // processes the blocks again
+        let checkpoints = tx
+            .cursor_read::<BackfillCheckpoints>()?
+            .walk(None)?
+            .map(|row| row.map(|row| (row.0, row.1)))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (start, checkpoint) in checkpoints {
+            let reverted = checkpoint.revert(start, *blocks.start());
+            if reverted != checkpoint {
+                tx.put::<BackfillCheckpoints>(start, reverted)?;
+            }
+        }
+
+        let fundings = tx
+            .cursor_read::<SearcherFundingSources>()?
+            .walk(None)?
+            .map(|row| row.map(|row| (row.0, row.1)))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (eoa, mut funding) in fundings {
+            let before = funding.sources.clone();
+            if !funding.remove_blocks(&blocks) {
+                tx.delete::<SearcherFundingSources>(eoa, None)?;
+            } else if funding.sources != before {
+                tx.put::<SearcherFundingSources>(eoa, funding)?;
+            }
+        }
+
+        for block in blocks {
+            tx.delete::<MevBlocks>(block, None)?;
+            tx.delete::<TxTraces>(block, None)?;
+            tx.delete::<InspectorRuns>(block, None)?;
+            tx.delete::<BuilderPnls>(block, None)?;
+            tx.delete::<ClassificationFailures>(block, None)?;
+
+            let mut cursor = tx.cursor_write::<DexPrice>()?;
+            let (start, end) = make_filter_key_range(block);
+            let mut walker = cursor.walk_range(start..=end)?;
+            while let Some(row) = walker.next() {
+                row?;
+                walker.delete_current()?;
+            }
+
+            if let Some(mut state) = tx.get::<InitializedState>(block)? {
+                state.set(TRACE_FLAG, DATA_NOT_PRESENT_UNKNOWN);
+                state.set(DEX_PRICE_FLAG, DATA_NOT_PRESENT_UNKNOWN);
+                tx.put::<InitializedState>(block, state)?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Swaps the stored mev of the blocks for the replayed results within one
+    /// transaction, so that a failed replay leaves the stored results as they
+    /// were. The queued writes are flushed first so that none of them land
+    /// after the replacement.
+    #[instrument(target = "libmdbx_read_write::replace_mev_blocks", skip_all, level = "warn")]
+    fn replace_mev_blocks(
+        &mut self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> eyre::Result<()> {
+        self.insert_remaining();
+
+        let tx = self.db.rw_tx()?;
+        for block in blocks {
+            tx.delete::<MevBlocks>(block, None)?;
+        }
+        for mev_block in mev_blocks {
+            let data = MevBlocksData::new(mev_block.block.block_number, mev_block).into_key_val();
+            tx.put::<MevBlocks>(data.key, data.value)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_inspector_runs", skip_all, level = "warn")]
+    fn write_inspector_runs(&self, block: u64, runs: Vec<InspectorRun>) -> eyre::Result<()> {
+        self.instrumented_write::<InspectorRuns, InspectorRunsData>(&[InspectorRunsData::new(
+            block,
+            BlockInspectorRuns { runs },
+        )])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_builder_pnl", skip_all, level = "warn")]
+    fn write_builder_pnl(&self, builder_pnl: BuilderPnl) -> eyre::Result<()> {
+        self.instrumented_write::<BuilderPnls, BuilderPnlsData>(&[BuilderPnlsData::new(
+            builder_pnl.block_number,
+            builder_pnl,
+        )])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// The failures of a block are recorded as they occur, so each one is
+    /// added to the failures already stored for the block
+    #[instrument(
+        target = "libmdbx_read_write::write_classification_failure",
+        skip_all,
+        level = "warn"
+    )]
+    fn write_classification_failure(
+        &self,
+        block: u64,
+        failure: ClassificationFailure,
+    ) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let mut failures = tx.get::<ClassificationFailures>(block)?.unwrap_or_default();
+        tx.commit()?;
+        failures.insert(failure);
+
+        self.instrumented_write::<ClassificationFailures, ClassificationFailuresData>(&[
+            ClassificationFailuresData::new(block, failures),
+        ])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    fn write_pool_fee_tier(&self, address: Address, fee_tier: PoolFeeTier) -> eyre::Result<()> {
+        self.instrumented_write::<PoolFeeTiers, PoolFeeTiersData>(&[PoolFeeTiersData::new(
+            address, fee_tier,
+        )])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// The taxes of a block are only the rates observed in the block, so they
+    /// are merged into the taxes stored for the tokens, replacing the rates of
+    /// the block if it was processed before
+    #[instrument(target = "libmdbx_read_write::write_token_taxes", skip_all, level = "warn")]
+    fn write_token_taxes(&self, taxes: Vec<(Address, TokenTax)>) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let data = taxes
+            .into_iter()
+            .map(|(token, tax)| {
+                let tax = match tx.get::<TokenTaxes>(token)? {
+                    Some(stored) => stored.merge(tax),
+                    None => tax,
+                };
+                Ok(TokenTaxesData::new(token, tax))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        tx.commit()?;
+
+        self.instrumented_write::<TokenTaxes, TokenTaxesData>(&data)
+            .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// The seeded selectors only fill the gaps of the table, a selector that
+    /// already has a signature keeps it
+    #[instrument(target = "libmdbx_read_write::write_function_selectors", skip_all, level = "warn")]
+    fn write_function_selectors(&self, selectors: Vec<FunctionSelector>) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let data = selectors
+            .into_iter()
+            .map(|selector| {
+                let key = u32::from_be_bytes(selector.selector().0);
+                Ok(tx
+                    .get::<FunctionSelectors>(key)?
+                    .is_none()
+                    .then(|| FunctionSelectorsData::new(key, selector)))
+            })
+            .filter_map(Result::transpose)
+            .collect::<eyre::Result<Vec<_>>>()?;
+        tx.commit()?;
+
+        self.instrumented_write::<FunctionSelectors, FunctionSelectorsData>(&data)
+            .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    /// The config is applied on every start, so the entries are merged with
+    /// the stored metadata of the protocol
+    #[instrument(target = "libmdbx_read_write::write_protocol_metadata", skip_all, level = "warn")]
+    fn write_protocol_metadata(&self, metadata: ProtocolMetadata) -> eyre::Result<()> {
+        let key = metadata.protocol.to_byte() as u32;
+
+        let tx = self.db.ro_tx()?;
+        let metadata = match tx.get::<ProtocolRegistry>(key)? {
+            Some(mut stored) => {
+                stored.merge(metadata);
+                stored
+            }
+            None => metadata,
+        };
+        tx.commit()?;
+
+        self.instrumented_write::<ProtocolRegistry, ProtocolRegistryData>(&[
+            ProtocolRegistryData::new(key, metadata),
+        ])
+        .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
     #[instrument(target = "libmdbx_read_write::init_state_updating", skip_all, level = "warn")]
     fn init_state_updating(&mut self, block: u64, flag: u16) -> eyre::Result<()> {
         let tx = self.db.ro_tx()?;
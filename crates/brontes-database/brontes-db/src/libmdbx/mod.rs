@@ -44,7 +44,9 @@ use self::{
 
 pub mod implementation;
 pub use implementation::compressed_wrappers::*;
+pub mod schema;
 pub mod tables;
+pub mod tiering;
 pub mod types;
 pub mod utils;
 
@@ -67,10 +69,30 @@ pub(crate) fn mdbx_result(err_code: c_int) -> eyre::Result<bool> {
 
 impl Libmdbx {
     /// Opens up an existing database or creates a new one at the specified
-    /// path. Creates tables if necessary. Opens in read/write mode.
+    /// path. Creates tables if necessary. Databases stored with an older
+    /// layout are refused until they are migrated, see [`schema`]. Opens in
+    /// read/write mode.
     pub fn init_db<P: AsRef<Path>>(path: P, log_level: Option<LogLevel>) -> eyre::Result<Self> {
-        let rpath = path.as_ref();
-        if is_database_empty(rpath) {
+        let (this, is_new) = Self::open(path.as_ref(), log_level)?;
+        schema::check(path.as_ref(), is_new)?;
+
+        Ok(this)
+    }
+
+    /// Opens the database at the specified path and brings it up to the
+    /// current schema version, clearing the tables stored with an older
+    /// layout. Returns the cleared tables
+    pub fn migrate_db<P: AsRef<Path>>(
+        path: P,
+        log_level: Option<LogLevel>,
+    ) -> eyre::Result<Vec<Tables>> {
+        let (this, is_new) = Self::open(path.as_ref(), log_level)?;
+        schema::migrate(&this, path.as_ref(), is_new)
+    }
+
+    fn open(rpath: &Path, log_level: Option<LogLevel>) -> eyre::Result<(Self, bool)> {
+        let is_new = is_database_empty(rpath);
+        if is_new {
             std::fs::create_dir_all(rpath).wrap_err_with(|| {
                 format!("Could not create database directory {}", rpath.display())
             })?;
@@ -101,7 +123,7 @@ impl Libmdbx {
         let this = Self(db);
         this.create_tables()?;
 
-        Ok(this)
+        Ok((this, is_new))
     }
 
     /// Creates all the defined tables, opens if already created
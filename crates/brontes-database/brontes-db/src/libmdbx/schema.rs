@@ -0,0 +1,234 @@
+//! Version of the layout the table values are stored with.
+//!
+//! Values are stored as rkyv archives, which can't be read back once fields
+//! are added to the archived type. The version the database was written with
+//! is kept in a file in the database folder. A database of an older version
+//! isn't opened until it is migrated with `brontes db migrate`, which clears
+//! the tables whose layout changed since and marks their ranges as
+//! uninitialized, so they are initialized again instead of being misread.
+//!
+//! The traces and bundles carry extra fields when brontes is built with the
+//! `provenance` feature, so the file also records whether the database was
+//! written with it. Such a database is only opened by builds of the same
+//! setting.
+use std::{cmp::Ordering, path::Path};
+
+use itertools::Itertools;
+use tracing::info;
+
+use super::{tables::*, types::CompressedTable, Libmdbx};
+
+/// Current version of the layout of the table values
+pub const SCHEMA_VERSION: u64 = 1;
+pub const SCHEMA_VERSION_FILE: &str = "brontes-schema.version";
+/// Follows the version in the file of databases written with the
+/// `provenance` feature
+const PROVENANCE_MARKER: &str = "provenance";
+
+/// The tables whose values changed layout with each version. Tables added in
+/// a version don't have to be listed as they hold no older rows.
+const MIGRATIONS: [(u64, &[Tables]); 1] = [(
+    1,
+    &[
+        // blob gas of the block metadata
+        Tables::BlockInfo,
+        // decimals anomaly of tokens
+        Tables::TokenDecimals,
+        // gas refunds, access list gas and blob gas of the traced transactions
+        Tables::TxTraces,
+        // funding parent and bid behaviour of searchers
+        Tables::SearcherEOAs,
+        Tables::SearcherContracts,
+        // taxonomy version, submission channels and sanctioned addresses of the
+        // mev blocks
+        Tables::MevBlocks,
+    ],
+)];
+
+/// Checks that the database at the path is stored with the current schema
+/// version. Newly created databases are written with the current version.
+pub(crate) fn check(path: &Path, is_new: bool) -> eyre::Result<()> {
+    if is_new {
+        return write_version(path)
+    }
+
+    let (version, provenance) = read_version(path)?;
+    match version.cmp(&SCHEMA_VERSION) {
+        Ordering::Equal => check_provenance(path, provenance),
+        Ordering::Less => eyre::bail!(
+            "database at {} has schema version {version}, run `brontes db migrate` to upgrade it \
+             to {SCHEMA_VERSION}. This clears the tables {} to be initialized again",
+            path.display(),
+            tables_to_migrate(version).iter().join(", ")
+        ),
+        Ordering::Greater => eyre::bail!(
+            "database at {} has schema version {version}, this version of brontes supports up to \
+             {SCHEMA_VERSION}",
+            path.display()
+        ),
+    }
+}
+
+/// Brings the database at the path up to the current schema version, returns
+/// the tables that were cleared
+pub(crate) fn migrate(db: &Libmdbx, path: &Path, is_new: bool) -> eyre::Result<Vec<Tables>> {
+    let (version, provenance) =
+        if is_new { (SCHEMA_VERSION, cfg!(feature = "provenance")) } else { read_version(path)? };
+    // databases from before the provenance fields existed have no stored
+    // provenance either
+    if version == SCHEMA_VERSION {
+        check_provenance(path, provenance)?;
+    }
+    if version > SCHEMA_VERSION {
+        eyre::bail!(
+            "database at {} has schema version {version}, this version of brontes supports up to \
+             {SCHEMA_VERSION}",
+            path.display()
+        );
+    }
+
+    let tables = tables_to_migrate(version);
+    for table in &tables {
+        info!(
+            target: "brontes::db",
+            %table,
+            from = version,
+            to = SCHEMA_VERSION,
+            "table was stored with an older layout, clearing it to be initialized again"
+        );
+        clear_table(db, *table)?;
+    }
+    write_version(path)?;
+
+    Ok(tables)
+}
+
+fn tables_to_migrate(version: u64) -> Vec<Tables> {
+    MIGRATIONS
+        .iter()
+        .filter(|(to, _)| *to > version)
+        .flat_map(|(_, tables)| tables.iter().copied())
+        .unique()
+        .collect()
+}
+
+fn check_provenance(path: &Path, provenance: bool) -> eyre::Result<()> {
+    if provenance != cfg!(feature = "provenance") {
+        eyre::bail!(
+            "database at {} was written {} the `provenance` feature, build brontes with the same \
+             setting to open it",
+            path.display(),
+            if provenance { "with" } else { "without" }
+        );
+    }
+
+    Ok(())
+}
+
+/// The version of the database and whether it was written with the
+/// `provenance` feature. Databases from before the version file was
+/// introduced are at version 0
+fn read_version(path: &Path) -> eyre::Result<(u64, bool)> {
+    let contents = match std::fs::read_to_string(path.join(SCHEMA_VERSION_FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, false)),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut parts = contents.split_whitespace();
+    let version = parts
+        .next()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|e| eyre::eyre!("invalid schema version file in {}: {e}", path.display()))?;
+
+    Ok((version, parts.next() == Some(PROVENANCE_MARKER)))
+}
+
+fn write_version(path: &Path) -> eyre::Result<()> {
+    let contents = if cfg!(feature = "provenance") {
+        format!("{SCHEMA_VERSION} {PROVENANCE_MARKER}")
+    } else {
+        SCHEMA_VERSION.to_string()
+    };
+
+    Ok(std::fs::write(path.join(SCHEMA_VERSION_FILE), contents)?)
+}
+
+fn clear_table(db: &Libmdbx, table: Tables) -> eyre::Result<()> {
+    macro_rules! clear_table {
+        ($($tables:ident),+) => {
+            match table {
+                $(
+                    Tables::$tables => clear_and_reset::<$tables>(db),
+                )+
+                _ => unreachable!("{table} has no migration"),
+            }
+        };
+    }
+    clear_table!(BlockInfo, TokenDecimals, TxTraces, SearcherEOAs, SearcherContracts, MevBlocks)
+}
+
+/// Clears the table and marks its ranges as uninitialized
+fn clear_and_reset<T>(db: &Libmdbx) -> eyre::Result<()>
+where
+    T: CompressedTable,
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+{
+    db.clear_table::<T>()?;
+
+    let Some(flag) = T::INIT_FLAG else { return Ok(()) };
+    let reset = db.view_db(|tx| {
+        let mut cur = tx.new_cursor::<InitializedState>()?;
+        let mut reset = Vec::new();
+        for (block, mut state) in cur.walk_range(..)?.flatten() {
+            state.apply_reset_key(flag);
+            reset.push(InitializedStateData::new(block, state));
+        }
+
+        Ok(reset)
+    })?;
+    db.write_table::<InitializedState, InitializedStateData>(&reset)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_databases_are_only_opened_after_migrating() {
+        let path = std::env::temp_dir().join(format!("brontes-schema-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+
+        drop(Libmdbx::init_db(&path, None).unwrap());
+        assert_eq!(read_version(&path).unwrap().0, SCHEMA_VERSION);
+
+        // a database written before the version file existed has to be migrated
+        std::fs::remove_file(path.join(SCHEMA_VERSION_FILE)).unwrap();
+        assert!(Libmdbx::init_db(&path, None).is_err());
+        assert_eq!(read_version(&path).unwrap().0, 0);
+
+        let cleared = Libmdbx::migrate_db(&path, None).unwrap();
+        assert_eq!(cleared, tables_to_migrate(0));
+        assert_eq!(read_version(&path).unwrap().0, SCHEMA_VERSION);
+        drop(Libmdbx::init_db(&path, None).unwrap());
+
+        std::fs::write(path.join(SCHEMA_VERSION_FILE), (SCHEMA_VERSION + 1).to_string()).unwrap();
+        assert!(Libmdbx::init_db(&path, None).is_err());
+        assert!(Libmdbx::migrate_db(&path, None).is_err());
+
+        // a database of the other provenance setting is refused
+        let other = if cfg!(feature = "provenance") {
+            SCHEMA_VERSION.to_string()
+        } else {
+            format!("{SCHEMA_VERSION} {PROVENANCE_MARKER}")
+        };
+        std::fs::write(path.join(SCHEMA_VERSION_FILE), other).unwrap();
+        assert!(Libmdbx::init_db(&path, None).is_err());
+        assert!(Libmdbx::migrate_db(&path, None).is_err());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
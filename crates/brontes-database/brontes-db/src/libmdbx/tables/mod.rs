@@ -10,22 +10,35 @@ use brontes_types::{
     db::{
         address_metadata::{AddressMetadata, AddressMetadataRedefined},
         address_to_protocol_info::{ProtocolInfo, ProtocolInfoRedefined},
+        backfill_checkpoint::{BackfillCheckpoint, BackfillCheckpointRedefined},
         builder::{BuilderInfo, BuilderInfoRedefined},
+        builder_pnl::{BuilderPnl, BuilderPnlRedefined},
         cex::{
             quotes::{CexPriceMap, CexPriceMapRedefined},
             trades::{CexTradeMap, CexTradeMapRedefined},
         },
+        classification_failure::{
+            BlockClassificationFailures, BlockClassificationFailuresRedefined,
+        },
         clickhouse_serde::tx_trace::tx_traces_inner,
+        curve_pool_params::{CurvePoolParams, CurvePoolParamsRedefined},
         dex::{DexKey, DexQuoteWithIndex, DexQuoteWithIndexRedefined},
+        function_selector::{FunctionSelector, FunctionSelectorRedefined},
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DEX_PRICE_FLAG, META_FLAG,
             TRACE_FLAG,
         },
+        inspector_run::{BlockInspectorRuns, BlockInspectorRunsRedefined},
         metadata::{BlockMetadataInner, BlockMetadataInnerRedefined},
         mev_block::{MevBlockWithClassified, MevBlockWithClassifiedRedefined},
         pool_creation_block::{PoolsToAddresses, PoolsToAddressesRedefined},
+        pool_fee_tier::{PoolFeeTier, PoolFeeTierRedefined},
+        protocol_metadata::{ProtocolMetadata, ProtocolMetadataRedefined},
+        sanctions::{SanctionInfo, SanctionInfoRedefined},
         searcher::{SearcherInfo, SearcherInfoRedefined},
+        searcher_funding::{SearcherFunding, SearcherFundingRedefined},
         token_info::TokenInfo,
+        token_tax::{TokenTax, TokenTaxRedefined},
         traces::{TxTracesInner, TxTracesInnerRedefined},
         traits::LibmdbxReader,
     },
@@ -58,7 +71,7 @@ use super::{
     CompressedTable,
 };
 
-pub const NUM_TABLES: usize = 14;
+pub const NUM_TABLES: usize = 24;
 
 macro_rules! tables {
     ($($table:ident),*) => {
@@ -206,6 +219,22 @@ impl Tables {
                     )
                     .await
             }
+            Tables::SanctionedAddresses => {
+                initializer
+                    .clickhouse_init_no_args::<SanctionedAddresses, SanctionedAddressesData>(
+                        crit_progress,
+                        |f, not| handle.send_message(WriterMessage::Init(f.into(), not)),
+                    )
+                    .await
+            }
+            Tables::FunctionSelectors => {
+                initializer
+                    .clickhouse_init_no_args::<FunctionSelectors, FunctionSelectorsData>(
+                        crit_progress,
+                        |f, not| handle.send_message(WriterMessage::Init(f.into(), not)),
+                    )
+                    .await
+            }
             _ => unimplemented!("{:?} isn't a full range table", self),
         }
     }
@@ -276,7 +305,18 @@ impl Tables {
                     )
                     .await
             }
-            Tables::SearcherEOAs | Tables::SearcherContracts | Tables::InitializedState => Ok(()),
+            Tables::SearcherEOAs
+            | Tables::SearcherContracts
+            | Tables::InitializedState
+            | Tables::CurvePoolParameters
+            | Tables::BackfillCheckpoints
+            | Tables::SearcherFundingSources
+            | Tables::TokenTaxes
+            | Tables::PoolFeeTiers
+            | Tables::ProtocolRegistry
+            | Tables::InspectorRuns
+            | Tables::BuilderPnls
+            | Tables::ClassificationFailures => Ok(()),
             _ => unimplemented!("'initialize_table' not implemented for {:?}", self),
         }
     }
@@ -347,7 +387,9 @@ impl Tables {
             | Tables::AddressToProtocolInfo
             | Tables::PoolCreationBlocks
             | Tables::Builder
-            | Tables::AddressMeta) => {
+            | Tables::AddressMeta
+            | Tables::SanctionedAddresses
+            | Tables::FunctionSelectors) => {
                 unimplemented!("'initialize_table_arbitrary_state' not implemented for {}", table);
             }
             _ => Ok(()),
@@ -537,7 +579,18 @@ tables!(
     SearcherEOAs,
     SearcherContracts,
     InitializedState,
-    CexTrades
+    CexTrades,
+    SanctionedAddresses,
+    CurvePoolParameters,
+    FunctionSelectors,
+    BackfillCheckpoints,
+    SearcherFundingSources,
+    TokenTaxes,
+    PoolFeeTiers,
+    ProtocolRegistry,
+    InspectorRuns,
+    BuilderPnls,
+    ClassificationFailures
 );
 
 /// Must be in this order when defining
@@ -872,6 +925,211 @@ compressed_table!(
     }
 );
 
+compressed_table!(
+    Table SanctionedAddresses {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: SanctionInfo,
+            compressed_value: SanctionInfoRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Clickhouse,
+            http_endpoint: Some("sanctioned-addresses"),
+            init_flag:None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table CurvePoolParameters {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: CurvePoolParams,
+            compressed_value: CurvePoolParamsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table FunctionSelectors {
+        Data {
+            key: u32,
+            value: FunctionSelector,
+            compressed_value: FunctionSelectorRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Clickhouse,
+            http_endpoint: Some("function-selectors"),
+            init_flag:None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BackfillCheckpoints {
+        Data {
+            key: u64,
+            value: BackfillCheckpoint,
+            compressed_value: BackfillCheckpointRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table TokenTaxes {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: TokenTax,
+            compressed_value: TokenTaxRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table PoolFeeTiers {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: PoolFeeTier,
+            compressed_value: PoolFeeTierRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table InspectorRuns {
+        Data {
+            key: u64,
+            value: BlockInspectorRuns,
+            compressed_value: BlockInspectorRunsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BuilderPnls {
+        Data {
+            key: u64,
+            value: BuilderPnl,
+            compressed_value: BuilderPnlRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table ClassificationFailures {
+        Data {
+            key: u64,
+            value: BlockClassificationFailures,
+            compressed_value: BlockClassificationFailuresRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table ProtocolRegistry {
+        Data {
+            key: u32,
+            value: ProtocolMetadata,
+            compressed_value: ProtocolMetadataRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table SearcherFundingSources {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: SearcherFunding,
+            compressed_value: SearcherFundingRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
 compressed_table!(
     Table SearcherEOAs {
         Data {
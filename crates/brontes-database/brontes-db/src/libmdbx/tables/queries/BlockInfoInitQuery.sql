@@ -26,7 +26,9 @@ WITH
         SELECT
             block_number,
             block_hash,
-            anyLast(block_timestamp) AS block_timestamp
+            anyLast(block_timestamp) AS block_timestamp,
+            anyLast(blob_gas_used) AS blob_gas_used,
+            anyLast(excess_blob_gas) AS excess_blob_gas
         FROM ethereum.blocks
         WHERE block_number >= start_block AND block_number < end_block AND valid = 1
         GROUP BY block_number, block_hash
@@ -56,7 +58,9 @@ SELECT
     CAST(o.p2p_timestamp, 'Nullable(UInt64)') AS p2p_timestamp,
     CAST(ifNull(r.proposer_fee_recipient, p.proposer_fee_recipient), 'Nullable(String)') AS proposer_fee_recipient,
     CAST(ifNull(r.proposer_mev_reward, p.proposer_mev_reward), 'Nullable(UInt128)') AS proposer_mev_reward,
-    CAST(ifNull(v.private_flow, []), 'Array(String)') AS private_flow
+    CAST(ifNull(v.private_flow, []), 'Array(String)') AS private_flow,
+    CAST(b.blob_gas_used, 'Nullable(UInt64)') AS blob_gas_used,
+    CAST(b.excess_blob_gas, 'Nullable(UInt64)') AS excess_blob_gas
 FROM raw_blocks b
 LEFT JOIN relay_bids r ON b.block_number = r.block_number AND b.block_hash = r.block_hash
 LEFT JOIN relay_payloads p ON b.block_number = p.block_number AND b.block_hash = p.block_hash
@@ -0,0 +1,5 @@
+SELECT
+    reinterpretAsUInt32(reverse(unhex(substring(selector, 3, 8)))) AS selector,
+    signature,
+    kind
+FROM brontes_api.function_selectors
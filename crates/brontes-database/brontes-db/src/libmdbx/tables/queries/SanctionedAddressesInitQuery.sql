@@ -0,0 +1,6 @@
+SELECT
+    toString(address),
+    list,
+    entity,
+    toUInt64(listed_at) AS listed_at
+FROM brontes_api.sanctioned_addresses
@@ -24,7 +24,12 @@ pub async fn load_clickhouse() -> Clickhouse {
 pub async fn load_clickhouse() -> crate::clickhouse::ClickhouseHttpClient {
     let clickhouse_api = env::var("CLICKHOUSE_API").expect("No CLICKHOUSE_API in .env");
     let clickhouse_api_key = env::var("CLICKHOUSE_API_KEY").ok();
-    crate::clickhouse::ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await
+    crate::clickhouse::ClickhouseHttpClient::new(crate::clickhouse::ClickhouseHttpConfig::new(
+        clickhouse_api,
+        clickhouse_api_key,
+    ))
+    .await
+    .expect("failed to connect to the clickhouse api")
 }
 
 pub async fn clickhouse_data<T, D, CH: ClickhouseHandle>(
@@ -0,0 +1,635 @@
+//! Cold storage for the block keyed tables that make up most of the database
+//! on long backfills, the tx traces and the dex quotes.
+//!
+//! Older block ranges of a table are moved out of libmdbx into archive files
+//! in the `cold-archives` folder of the database. An archive holds a zstd
+//! frame per block, followed by an index of where each block's frame starts,
+//! so a block is read without decompressing the rest of the archive. Reads of
+//! blocks that aren't in the hot database fall back to the archives, the
+//! most recently read blocks are kept decompressed in memory. A read that no
+//! archive covers lists the folder again if it changed, so archives created
+//! while the node is running are picked up.
+//!
+//! Both tables are keyed by the block number first, so the rows of a block are
+//! found from their encoded key.
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use brontes_types::{db::dex::make_filter_key_range, FastHashMap};
+use itertools::Itertools;
+use moka::sync::Cache;
+use parking_lot::{Mutex, RwLock};
+use reth_db::table::{Compress, Decompress, Encode};
+
+use super::{
+    tables::{DexPrice, Tables, TxTraces},
+    types::CompressedTable,
+    LibmdbxReadWriter,
+};
+
+pub const COLD_STORAGE_DIR: &str = "cold-archives";
+/// Tables whose block ranges can be moved to cold storage
+pub const TIERED_TABLES: [Tables; 2] = [Tables::TxTraces, Tables::DexPrice];
+
+const ARCHIVE_EXTENSION: &str = "archive";
+const ARCHIVE_MAGIC: &[u8; 8] = b"BRNTSARC";
+const COMPRESSION_LEVEL: i32 = 9;
+/// Memory the decompressed blocks are allowed to take up
+const LOADED_BLOCKS_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Raw key and value of a row, as stored in libmdbx
+type RawRow = (Vec<u8>, Vec<u8>);
+
+/// An archive of a table's rows for an inclusive block range
+#[derive(Debug)]
+pub struct Archive {
+    pub table:       Tables,
+    pub start_block: u64,
+    pub end_block:   u64,
+    path:            PathBuf,
+    /// block -> (offset, length) of its frame
+    index:           FastHashMap<u64, (u64, u64)>,
+}
+
+impl Archive {
+    fn path(dir: &Path, table: Tables, start_block: u64, end_block: u64) -> PathBuf {
+        dir.join(format!("{}-{start_block}-{end_block}.{ARCHIVE_EXTENSION}", table.name()))
+    }
+
+    /// Opens the archive and reads its index. The range is taken from the file
+    /// name, `<table>-<start block>-<end block>.archive`
+    fn open(path: PathBuf) -> eyre::Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| eyre::eyre!("invalid archive name {}", path.display()))?;
+        let mut parts = name.rsplitn(3, '-');
+        let (Some(end_block), Some(start_block), Some(table)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            eyre::bail!("invalid archive name {}", path.display())
+        };
+
+        let index = read_index(&path)?;
+
+        Ok(Self {
+            table: table.parse().map_err(|e: String| eyre::eyre!(e))?,
+            start_block: start_block.parse()?,
+            end_block: end_block.parse()?,
+            path,
+            index,
+        })
+    }
+
+    pub fn contains(&self, block: u64) -> bool {
+        (self.start_block..=self.end_block).contains(&block)
+    }
+
+    fn overlaps(&self, start_block: u64, end_block: u64) -> bool {
+        self.start_block <= end_block && start_block <= self.end_block
+    }
+
+    /// Reads the rows of the block, empty if the block had none
+    fn read_block(&self, block: u64) -> eyre::Result<Vec<RawRow>> {
+        let Some(entry) = self.index.get(&block) else { return Ok(vec![]) };
+
+        decode_rows(&zstd::decode_all(
+            read_frame(&mut File::open(&self.path)?, *entry)?.as_slice(),
+        )?)
+    }
+
+    fn has_blocks_in(&self, blocks: &RangeInclusive<u64>) -> bool {
+        self.index.keys().any(|block| blocks.contains(block))
+    }
+
+    /// Rewrites the archive without the blocks, copying the frames of the
+    /// other blocks as they are. The file is removed and None returned if no
+    /// block is left.
+    fn without_blocks(&self, blocks: &RangeInclusive<u64>) -> eyre::Result<Option<Self>> {
+        let kept = self
+            .index
+            .iter()
+            .filter(|(block, _)| !blocks.contains(block))
+            .map(|(block, entry)| (*block, *entry))
+            .sorted_unstable_by_key(|(block, _)| *block)
+            .collect_vec();
+        if kept.is_empty() {
+            std::fs::remove_file(&self.path)?;
+            return Ok(None)
+        }
+
+        let mut file = File::open(&self.path)?;
+        let mut writer = ArchiveWriter::create(&self.path)?;
+        for (block, entry) in kept {
+            writer.push_frame(block, &read_frame(&mut file, entry)?)?;
+        }
+        writer.finish()?;
+
+        Self::open(self.path.clone()).map(Some)
+    }
+}
+
+/// The archives of the database, see the [module docs](self)
+#[derive(Debug)]
+pub struct ColdStorage {
+    dir:       PathBuf,
+    archives:  RwLock<Vec<Arc<Archive>>>,
+    /// Modification time of the folder when the archives were last listed
+    listed_at: Mutex<Option<SystemTime>>,
+    loaded:    Cache<(Tables, u64), Arc<Vec<RawRow>>>,
+}
+
+impl ColdStorage {
+    /// Opens the archives in the cold storage folder of the database, the
+    /// folder is only created once a range is archived
+    pub fn open(db_path: &Path) -> eyre::Result<Self> {
+        let loaded = Cache::builder()
+            .weigher(|_, rows: &Arc<Vec<RawRow>>| {
+                let bytes = rows.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>();
+                u32::try_from(bytes).unwrap_or(u32::MAX)
+            })
+            .max_capacity(LOADED_BLOCKS_BYTES)
+            .build();
+
+        let this = Self {
+            dir: db_path.join(COLD_STORAGE_DIR),
+            archives: RwLock::new(vec![]),
+            listed_at: Mutex::new(None),
+            loaded,
+        };
+        this.refresh()?;
+        if this.listed_at.lock().is_some() {
+            tracing::info!(
+                archives = this.archives.read().len(),
+                dir = %this.dir.display(),
+                "opened cold storage"
+            );
+        }
+
+        Ok(this)
+    }
+
+    /// Lists the folder again if it was modified since it was last listed,
+    /// e.g. by `brontes db archive` while the node is running
+    fn refresh(&self) -> eyre::Result<()> {
+        let Ok(modified) = std::fs::metadata(&self.dir).and_then(|meta| meta.modified()) else {
+            return Ok(())
+        };
+        let mut listed_at = self.listed_at.lock();
+        if *listed_at == Some(modified) {
+            return Ok(())
+        }
+
+        let mut paths = vec![];
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            // leftovers of an interrupted archival are ignored
+            if path.extension().and_then(|ext| ext.to_str()) == Some(ARCHIVE_EXTENSION) {
+                paths.push(path);
+            }
+        }
+
+        let mut archives = self.archives.write();
+        let before = archives.len();
+        archives.retain(|archive| paths.contains(&archive.path));
+        if archives.len() != before {
+            self.loaded.invalidate_all();
+        }
+        for path in paths {
+            if !archives.iter().any(|archive| archive.path == path) {
+                archives.push(Arc::new(Archive::open(path)?));
+            }
+        }
+        *listed_at = Some(modified);
+
+        Ok(())
+    }
+
+    pub fn archives(&self) -> Vec<Arc<Archive>> {
+        self.archives.read().clone()
+    }
+
+    fn find_archive(&self, table: Tables, block: u64) -> Option<Arc<Archive>> {
+        self.archives
+            .read()
+            .iter()
+            .find(|archive| archive.table == table && archive.contains(block))
+            .cloned()
+    }
+
+    fn archive_for(&self, table: Tables, block: u64) -> eyre::Result<Option<Arc<Archive>>> {
+        if let Some(archive) = self.find_archive(table, block) {
+            return Ok(Some(archive))
+        }
+        // the block might have been archived since the folder was listed
+        self.refresh()?;
+
+        Ok(self.find_archive(table, block))
+    }
+
+    /// The highest block of the table that was archived
+    pub fn highest_block(&self, table: Tables) -> eyre::Result<Option<u64>> {
+        self.refresh()?;
+
+        Ok(self
+            .archives
+            .read()
+            .iter()
+            .filter(|archive| archive.table == table)
+            .filter_map(|archive| archive.index.keys().max().copied())
+            .max())
+    }
+
+    /// Removes the blocks from the archives, used when the blocks were reorged
+    /// out of the canonical chain
+    pub fn invalidate_blocks(&self, blocks: &RangeInclusive<u64>) -> eyre::Result<()> {
+        self.refresh()?;
+
+        let mut archives = self.archives.write();
+        let mut emptied = vec![];
+        for (i, archive) in archives.iter_mut().enumerate() {
+            if !archive.has_blocks_in(blocks) {
+                continue
+            }
+
+            archive
+                .index
+                .keys()
+                .filter(|block| blocks.contains(block))
+                .for_each(|block| self.loaded.invalidate(&(archive.table, *block)));
+            match archive.without_blocks(blocks)? {
+                Some(rewritten) => *archive = Arc::new(rewritten),
+                None => emptied.push(i),
+            }
+        }
+        emptied.into_iter().rev().for_each(|i| {
+            archives.remove(i);
+        });
+
+        Ok(())
+    }
+
+    /// The rows of the block if it was archived. None if no archive covers
+    /// the block, in which case it might still be in the hot database
+    pub fn get_block<T>(&self, block: u64) -> eyre::Result<Option<Vec<T::DecompressedValue>>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        let table: Tables = T::NAME.parse().map_err(|e: String| eyre::eyre!(e))?;
+        let Some(archive) = self.archive_for(table, block)? else { return Ok(None) };
+
+        let rows = self
+            .loaded
+            .try_get_with((table, block), || archive.read_block(block).map(Arc::new))
+            .map_err(|e| {
+                eyre::eyre!("failed to read {table} of block {block} from cold storage: {e}")
+            })?;
+
+        rows.iter()
+            .map(|(_, value)| Ok(T::Value::decompress(value)?.into()))
+            .collect::<eyre::Result<Vec<_>>>()
+            .map(Some)
+    }
+
+    /// Writes the rows to a new archive of the range. The rows have to be
+    /// sorted by key, as a cursor returns them. Returns the number of rows
+    /// archived, no archive is created if there were none.
+    fn write_archive(
+        &self,
+        table: Tables,
+        blocks: RangeInclusive<u64>,
+        rows: impl Iterator<Item = eyre::Result<RawRow>>,
+    ) -> eyre::Result<usize> {
+        let (start_block, end_block) = (*blocks.start(), *blocks.end());
+        self.refresh()?;
+        if let Some(archive) = self
+            .archives
+            .read()
+            .iter()
+            .find(|archive| archive.table == table && archive.overlaps(start_block, end_block))
+        {
+            eyre::bail!(
+                "{table} of blocks {start_block}..={end_block} overlap with the archive of {}..={}",
+                archive.start_block,
+                archive.end_block
+            )
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        let path = Archive::path(&self.dir, table, start_block, end_block);
+        let rows = write_archive_file(&path, rows)?;
+        if rows == 0 {
+            std::fs::remove_file(&path)?;
+            return Ok(0)
+        }
+
+        self.archives.write().push(Arc::new(Archive::open(path)?));
+
+        Ok(rows)
+    }
+}
+
+/// Writes the archive to a temporary file first, so that an interrupted write
+/// never leaves a partial archive behind
+fn write_archive_file(
+    path: &Path,
+    rows: impl Iterator<Item = eyre::Result<RawRow>>,
+) -> eyre::Result<usize> {
+    let mut writer = ArchiveWriter::create(path)?;
+    let mut count = 0;
+    let mut block_rows: Vec<RawRow> = vec![];
+
+    let write_block = |block_rows: &mut Vec<RawRow>, writer: &mut ArchiveWriter| {
+        let Some((key, _)) = block_rows.first() else { return Ok::<_, eyre::Report>(()) };
+        let block = block_of(key)?;
+        let frame = zstd::encode_all(encode_rows(block_rows).as_slice(), COMPRESSION_LEVEL)?;
+        writer.push_frame(block, &frame)?;
+        block_rows.clear();
+        Ok(())
+    };
+
+    for row in rows {
+        let row = row?;
+        if let Some((key, _)) = block_rows.first() {
+            if block_of(key)? != block_of(&row.0)? {
+                write_block(&mut block_rows, &mut writer)?;
+            }
+        }
+        block_rows.push(row);
+        count += 1;
+    }
+    write_block(&mut block_rows, &mut writer)?;
+    writer.finish()?;
+
+    Ok(count)
+}
+
+/// Writes the frames of an archive to a temporary file, which replaces the
+/// archive once the index is written
+struct ArchiveWriter {
+    path:   PathBuf,
+    tmp:    PathBuf,
+    file:   BufWriter<File>,
+    /// (block, offset, length) of each frame
+    index:  Vec<(u64, u64, u64)>,
+    offset: u64,
+}
+
+impl ArchiveWriter {
+    fn create(path: &Path) -> eyre::Result<Self> {
+        let tmp = path.with_extension("tmp");
+        let file = BufWriter::new(File::create(&tmp)?);
+
+        Ok(Self { path: path.to_path_buf(), tmp, file, index: vec![], offset: 0 })
+    }
+
+    fn push_frame(&mut self, block: u64, frame: &[u8]) -> eyre::Result<()> {
+        self.file.write_all(frame)?;
+        self.index.push((block, self.offset, frame.len() as u64));
+        self.offset += frame.len() as u64;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> eyre::Result<()> {
+        for (block, offset, len) in &self.index {
+            self.file.write_all(&block.to_le_bytes())?;
+            self.file.write_all(&offset.to_le_bytes())?;
+            self.file.write_all(&len.to_le_bytes())?;
+        }
+        self.file
+            .write_all(&(self.index.len() as u64).to_le_bytes())?;
+        self.file.write_all(ARCHIVE_MAGIC)?;
+
+        self.file
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        std::fs::rename(&self.tmp, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Reads the frame at the (offset, length) of the index entry
+fn read_frame(file: &mut File, (offset, len): (u64, u64)) -> eyre::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut frame = vec![0; len as usize];
+    file.read_exact(&mut frame)?;
+
+    Ok(frame)
+}
+
+fn read_index(path: &Path) -> eyre::Result<FastHashMap<u64, (u64, u64)>> {
+    let mut file = File::open(path)?;
+    let mut footer = [0u8; 16];
+    file.seek(SeekFrom::End(-16))?;
+    file.read_exact(&mut footer)?;
+    if &footer[8..] != ARCHIVE_MAGIC {
+        eyre::bail!("{} isn't an archive", path.display())
+    }
+
+    let blocks = u64::from_le_bytes(footer[..8].try_into().unwrap());
+    let mut index = vec![0u8; blocks as usize * 24];
+    file.seek(SeekFrom::End(-16 - index.len() as i64))?;
+    file.read_exact(&mut index)?;
+
+    Ok(index
+        .chunks_exact(24)
+        .map(|entry| {
+            let field =
+                |i: usize| u64::from_le_bytes(entry[i * 8..(i + 1) * 8].try_into().unwrap());
+            (field(0), (field(1), field(2)))
+        })
+        .collect())
+}
+
+/// Block of an encoded key, the tiered tables are keyed by the block number
+/// first
+fn block_of(key: &[u8]) -> eyre::Result<u64> {
+    Ok(u64::from_be_bytes(
+        key.get(..8)
+            .ok_or_else(|| eyre::eyre!("key isn't prefixed with a block number"))?
+            .try_into()
+            .unwrap(),
+    ))
+}
+
+fn encode_rows(rows: &[RawRow]) -> Vec<u8> {
+    let mut buf = vec![];
+    for (key, value) in rows {
+        buf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        buf.extend_from_slice(key);
+        buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        buf.extend_from_slice(value);
+    }
+    buf
+}
+
+fn decode_rows(mut buf: &[u8]) -> eyre::Result<Vec<RawRow>> {
+    let next = |buf: &mut &[u8]| -> eyre::Result<Vec<u8>> {
+        let data: &[u8] = buf;
+        let (len, rest) = data
+            .split_first_chunk::<4>()
+            .ok_or_else(|| eyre::eyre!("truncated archive frame"))?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            eyre::bail!("truncated archive frame")
+        }
+        let (bytes, rest) = rest.split_at(len);
+        *buf = rest;
+        Ok(bytes.to_vec())
+    };
+
+    let mut rows = vec![];
+    while !buf.is_empty() {
+        rows.push((next(&mut buf)?, next(&mut buf)?));
+    }
+    Ok(rows)
+}
+
+impl LibmdbxReadWriter {
+    /// Moves the table's rows of the blocks from the hot database to a new
+    /// archive. The rows are only removed from the hot database once the
+    /// archive was written. The initialized state of the blocks is kept, so
+    /// the archived data isn't downloaded again. Libmdbx reuses the freed
+    /// pages, so the database file stops growing rather than shrinking.
+    pub fn archive_blocks(
+        &self,
+        table: Tables,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<usize> {
+        match table {
+            Tables::TxTraces => self.archive_table_range::<TxTraces>(
+                table,
+                start_block,
+                end_block,
+                start_block..=end_block,
+            ),
+            Tables::DexPrice => {
+                let keys =
+                    make_filter_key_range(start_block).0..=make_filter_key_range(end_block).1;
+                self.archive_table_range::<DexPrice>(table, start_block, end_block, keys)
+            }
+            table => {
+                eyre::bail!("{table} can't be moved to cold storage, only {TIERED_TABLES:?} can")
+            }
+        }
+    }
+
+    fn archive_table_range<T>(
+        &self,
+        table: Tables,
+        start_block: u64,
+        end_block: u64,
+        keys: RangeInclusive<T::Key>,
+    ) -> eyre::Result<usize>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        let tx = self.db.no_timeout_ro_tx()?;
+        let mut cursor = tx.cursor_read::<T>()?;
+        let rows = cursor
+            .walk_range(keys.clone())?
+            .map(|row| -> eyre::Result<RawRow> {
+                let row = row?;
+                Ok((row.0.encode().into(), T::Value::from(row.1).compress().into()))
+            });
+        let archived = self
+            .cold
+            .write_archive(table, start_block..=end_block, rows)?;
+        drop(cursor);
+        tx.commit()?;
+
+        if archived != 0 {
+            self.db.update_db(|tx| {
+                let mut cursor = tx.cursor_write::<T>()?;
+                let mut walker = cursor.walk_range(keys)?;
+                while let Some(row) = walker.next() {
+                    row?;
+                    walker.delete_current()?;
+                }
+                Ok::<_, reth_db::DatabaseError>(())
+            })??;
+        }
+
+        Ok(archived)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(block: u64, idx: u8, value: &[u8]) -> RawRow {
+        let mut key = block.to_be_bytes().to_vec();
+        key.push(idx);
+        (key, value.to_vec())
+    }
+
+    #[test]
+    fn archive_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("brontes-cold-storage-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = Archive::path(&dir, Tables::DexPrice, 10, 19);
+
+        let rows = vec![row(10, 0, b"a"), row(10, 1, b"bb"), row(12, 0, b"ccc")];
+        let written = write_archive_file(&path, rows.clone().into_iter().map(Ok)).unwrap();
+        assert_eq!(written, 3);
+
+        let archive = Archive::open(path).unwrap();
+        assert_eq!(
+            (archive.table, archive.start_block, archive.end_block),
+            (Tables::DexPrice, 10, 19)
+        );
+        assert_eq!(archive.read_block(10).unwrap(), rows[..2]);
+        assert_eq!(archive.read_block(12).unwrap(), rows[2..]);
+        assert!(archive.read_block(11).unwrap().is_empty());
+        assert!(archive.contains(19) && !archive.contains(20));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn picks_up_new_archives_and_drops_invalidated_blocks() {
+        let db_path = std::env::temp_dir()
+            .join(format!("brontes-cold-storage-rescan-{}", std::process::id()));
+        std::fs::create_dir_all(&db_path).unwrap();
+        let cold = ColdStorage::open(&db_path).unwrap();
+        assert!(cold.archives().is_empty());
+
+        // archived by another process after the storage was opened
+        let dir = db_path.join(COLD_STORAGE_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let rows = vec![row(10, 0, b"a"), row(12, 0, b"b")];
+        write_archive_file(
+            &Archive::path(&dir, Tables::TxTraces, 10, 19),
+            rows.into_iter().map(Ok),
+        )
+        .unwrap();
+
+        assert!(cold.archive_for(Tables::TxTraces, 12).unwrap().is_some());
+        assert_eq!(cold.highest_block(Tables::TxTraces).unwrap(), Some(12));
+
+        cold.invalidate_blocks(&(12..=20)).unwrap();
+        let archive = cold.archive_for(Tables::TxTraces, 10).unwrap().unwrap();
+        assert_eq!(archive.read_block(10).unwrap(), vec![row(10, 0, b"a")]);
+        assert!(archive.read_block(12).unwrap().is_empty());
+        assert_eq!(cold.highest_block(Tables::TxTraces).unwrap(), Some(10));
+
+        cold.invalidate_blocks(&(10..=10)).unwrap();
+        assert!(cold.archives().is_empty());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(db_path).unwrap();
+    }
+}
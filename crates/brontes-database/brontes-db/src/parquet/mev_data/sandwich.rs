@@ -11,10 +11,9 @@ use itertools::Itertools;
 
 use crate::parquet::{
     normalized_actions::{
-        gas_details::{get_gas_details_array, get_gas_details_list_array},
-        swaps::get_normalized_swap_list_array,
+        gas_details::get_gas_details_list_array, swaps::get_normalized_swap_list_array,
     },
-    utils::{get_list_string_array_from_owned, get_string_array_from_owned},
+    utils::get_list_string_array_from_owned,
 };
 
 pub fn sandwich_to_record_batch(sandwiches: Vec<Sandwich>) -> Result<RecordBatch, ArrowError> {
@@ -70,22 +69,31 @@ pub fn sandwich_to_record_batch(sandwiches: Vec<Sandwich>) -> Result<RecordBatch
             .collect_vec(),
     );
 
-    let backrun_tx_hash_array = get_string_array_from_owned(
+    let backrun_tx_hash_array = get_list_string_array_from_owned(
         sandwiches
             .iter()
-            .map(|s| Some(s.backrun_tx_hash.to_string()))
+            .map(|s| {
+                s.backrun_tx_hash
+                    .iter()
+                    .map(|hash| hash.to_string())
+                    .collect_vec()
+            })
             .collect_vec(),
     );
 
     let backrun_swaps_array = get_normalized_swap_list_array(
         sandwiches
             .iter()
-            .map(|s| s.backrun_swaps.iter().collect_vec())
+            .map(|s| s.backrun_swaps.iter().flatten().collect_vec())
             .collect_vec(),
     );
 
-    let backrun_gas_details_array =
-        get_gas_details_array(sandwiches.iter().map(|s| s.backrun_gas_details).collect());
+    let backrun_gas_details_array = get_gas_details_list_array(
+        sandwiches
+            .iter()
+            .map(|s| &s.backrun_gas_details)
+            .collect_vec(),
+    );
 
     let schema = Schema::new(vec![
         Field::new("frontrun_tx_hash", frontrun_tx_hash_array.data_type().clone(), false),
@@ -0,0 +1,361 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, BooleanArray, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::{
+    db::{metadata::BlockMetadata, searcher::SearcherInfo},
+    mev::{Bundle, BundleData, Mev, MevBlock, MevType},
+    normalized_actions::NormalizedSwap,
+};
+use itertools::Itertools;
+
+use super::utils::{
+    build_float64_array, build_record_batch, build_string_array, build_uint64_array,
+    get_list_string_array_from_owned, get_string_array_from_owned,
+};
+
+/// A flattened, feature engineered view of a single bundle meant to be used as
+/// a training sample for mev classification models. Every bundle produces one
+/// record, including the non-mev searcher transactions, which are labeled as
+/// such so they can be used as negative samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MlBundleRecord {
+    pub block_number:       u64,
+    pub tx_index:           u64,
+    pub tx_hash:            String,
+    pub eoa:                String,
+    pub mev_contract:       Option<String>,
+    pub fund:               String,
+    /// The mev type the bundle was classified as
+    pub label:              String,
+    /// False for searcher transactions that didn't match any inspector
+    pub is_mev:             bool,
+    pub profit_usd:         f64,
+    pub bribe_usd:          f64,
+    pub no_pricing:         bool,
+    pub gas_paid_wei:       f64,
+    pub bribe_wei:          f64,
+    pub tx_count:           u64,
+    pub swap_count:         u64,
+    /// Pools swapped through, in execution order
+    pub pool_path:          Vec<String>,
+    pub protocols:          Vec<String>,
+    pub victim_tx_count:    u64,
+    pub victim_swap_count:  u64,
+    pub block_eth_price:    f64,
+    pub block_bundle_count: u64,
+    /// Timing features, none if the block metadata isn't stored
+    pub block_timestamp:    Option<u64>,
+    pub relay_timestamp:    Option<u64>,
+    pub p2p_timestamp:      Option<u64>,
+    /// Whether the tx wasn't seen in the public mempool
+    pub is_private:         Option<bool>,
+    /// Searcher features, taken from the stored info of the eoa
+    pub searcher_name:      Option<String>,
+    pub searcher_bundles:   u64,
+    pub searcher_builder:   Option<String>,
+}
+
+impl MlBundleRecord {
+    pub fn new(
+        bundle: &Bundle,
+        block: &MevBlock,
+        metadata: Option<&BlockMetadata>,
+        searcher: Option<&SearcherInfo>,
+    ) -> Self {
+        let header = &bundle.header;
+        let swaps = bundle_swaps(&bundle.data);
+        let (victim_tx_count, victim_swap_count) = victim_stats(&bundle.data);
+
+        Self {
+            block_number: header.block_number,
+            tx_index: header.tx_index,
+            tx_hash: header.tx_hash.to_string(),
+            eoa: header.eoa.to_string(),
+            mev_contract: header.mev_contract.map(|addr| addr.to_string()),
+            fund: header.fund.to_string(),
+            label: header.mev_type.to_string(),
+            is_mev: !matches!(header.mev_type, MevType::SearcherTx | MevType::Unknown),
+            profit_usd: header.profit_usd,
+            bribe_usd: header.bribe_usd,
+            no_pricing: header.no_pricing_calculated,
+            gas_paid_wei: bundle.data.total_gas_paid() as f64,
+            bribe_wei: bundle.data.bribe() as f64,
+            tx_count: bundle.data.mev_transaction_hashes().len() as u64,
+            swap_count: swaps.len() as u64,
+            pool_path: swaps.iter().map(|s| s.pool.to_string()).collect(),
+            protocols: bundle
+                .data
+                .protocols()
+                .into_iter()
+                .map(|p| p.to_string())
+                .sorted()
+                .collect(),
+            victim_tx_count,
+            victim_swap_count,
+            block_eth_price: block.eth_price,
+            block_bundle_count: block.mev_count.bundle_count,
+            block_timestamp: metadata.map(|m| m.block_timestamp),
+            relay_timestamp: metadata.and_then(|m| m.relay_timestamp),
+            p2p_timestamp: metadata.and_then(|m| m.p2p_timestamp),
+            is_private: metadata.map(|m| m.private_flow.contains(&header.tx_hash)),
+            searcher_name: searcher.and_then(|s| s.name.clone()),
+            searcher_bundles: searcher
+                .map(|s| s.mev_count.bundle_count)
+                .unwrap_or_default(),
+            searcher_builder: searcher.and_then(|s| s.builder.map(|addr| addr.to_string())),
+        }
+    }
+}
+
+/// Swaps executed by the searcher, victims are excluded.
+fn bundle_swaps(data: &BundleData) -> Vec<&NormalizedSwap> {
+    match data {
+        BundleData::Sandwich(s) => s
+            .frontrun_swaps
+            .iter()
+            .chain(&s.backrun_swaps)
+            .flatten()
+            .collect(),
+        BundleData::JitSandwich(s) => s
+            .frontrun_swaps
+            .iter()
+            .flatten()
+            .chain(s.backrun_swaps.iter())
+            .collect(),
+        BundleData::AtomicArb(a) => a.swaps.iter().collect(),
+        BundleData::CexDex(c) => c.swaps.iter().collect(),
+        BundleData::CexDexQuote(c) => c.swaps.iter().collect(),
+        BundleData::Liquidation(l) => l.liquidation_swaps.iter().collect(),
+        BundleData::Frontrun(f) => f.frontrun_swaps.iter().collect(),
+        BundleData::Jit(_) | BundleData::ApprovalRace(_) | BundleData::Unknown(_) => vec![],
+    }
+}
+
+fn victim_stats(data: &BundleData) -> (u64, u64) {
+    match data {
+        BundleData::Sandwich(s) => (
+            s.victim_swaps_tx_hashes.iter().flatten().count() as u64,
+            s.victim_swaps.iter().flatten().count() as u64,
+        ),
+        BundleData::JitSandwich(s) => (
+            s.victim_swaps_tx_hashes.iter().flatten().count() as u64,
+            s.victim_swaps.iter().flatten().count() as u64,
+        ),
+        BundleData::Jit(j) => {
+            (j.victim_swaps_tx_hashes.len() as u64, j.victim_swaps.iter().flatten().count() as u64)
+        }
+        BundleData::Frontrun(f) => {
+            (f.victim_swaps_tx_hashes.len() as u64, f.victim_swaps.iter().flatten().count() as u64)
+        }
+        _ => (0, 0),
+    }
+}
+
+pub fn ml_dataset_to_record_batch(records: Vec<MlBundleRecord>) -> Result<RecordBatch, ArrowError> {
+    let block_number_array = build_uint64_array(records.iter().map(|r| r.block_number).collect());
+    let tx_index_array = build_uint64_array(records.iter().map(|r| r.tx_index).collect());
+    let tx_hash_array = build_string_array(records.iter().map(|r| r.tx_hash.clone()).collect());
+    let eoa_array = build_string_array(records.iter().map(|r| r.eoa.clone()).collect());
+    let mev_contract_array = get_string_array_from_owned(
+        records
+            .iter()
+            .map(|r| r.mev_contract.as_ref())
+            .collect_vec(),
+    );
+    let fund_array = build_string_array(records.iter().map(|r| r.fund.clone()).collect());
+    let label_array = build_string_array(records.iter().map(|r| r.label.clone()).collect());
+    let is_mev_array = BooleanArray::from(records.iter().map(|r| r.is_mev).collect_vec());
+    let profit_usd_array = build_float64_array(records.iter().map(|r| r.profit_usd).collect());
+    let bribe_usd_array = build_float64_array(records.iter().map(|r| r.bribe_usd).collect());
+    let no_pricing_array = BooleanArray::from(records.iter().map(|r| r.no_pricing).collect_vec());
+    let gas_paid_array = build_float64_array(records.iter().map(|r| r.gas_paid_wei).collect());
+    let bribe_wei_array = build_float64_array(records.iter().map(|r| r.bribe_wei).collect());
+    let tx_count_array = build_uint64_array(records.iter().map(|r| r.tx_count).collect());
+    let swap_count_array = build_uint64_array(records.iter().map(|r| r.swap_count).collect());
+    let pool_path_array =
+        get_list_string_array_from_owned(records.iter().map(|r| r.pool_path.clone()).collect());
+    let protocols_array =
+        get_list_string_array_from_owned(records.iter().map(|r| r.protocols.clone()).collect());
+    let victim_tx_count_array =
+        build_uint64_array(records.iter().map(|r| r.victim_tx_count).collect());
+    let victim_swap_count_array =
+        build_uint64_array(records.iter().map(|r| r.victim_swap_count).collect());
+    let block_eth_price_array =
+        build_float64_array(records.iter().map(|r| r.block_eth_price).collect());
+    let block_bundle_count_array =
+        build_uint64_array(records.iter().map(|r| r.block_bundle_count).collect());
+    let block_timestamp_array =
+        UInt64Array::from(records.iter().map(|r| r.block_timestamp).collect_vec());
+    let relay_timestamp_array =
+        UInt64Array::from(records.iter().map(|r| r.relay_timestamp).collect_vec());
+    let p2p_timestamp_array =
+        UInt64Array::from(records.iter().map(|r| r.p2p_timestamp).collect_vec());
+    let is_private_array = BooleanArray::from(records.iter().map(|r| r.is_private).collect_vec());
+    let searcher_name_array = get_string_array_from_owned(
+        records
+            .iter()
+            .map(|r| r.searcher_name.as_ref())
+            .collect_vec(),
+    );
+    let searcher_bundles_array =
+        build_uint64_array(records.iter().map(|r| r.searcher_bundles).collect());
+    let searcher_builder_array = get_string_array_from_owned(
+        records
+            .iter()
+            .map(|r| r.searcher_builder.as_ref())
+            .collect_vec(),
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("tx_index", DataType::UInt64, false),
+        Field::new("tx_hash", DataType::Utf8, false),
+        Field::new("eoa", DataType::Utf8, false),
+        Field::new("mev_contract", DataType::Utf8, true),
+        Field::new("fund", DataType::Utf8, false),
+        Field::new("label", DataType::Utf8, false),
+        Field::new("is_mev", DataType::Boolean, false),
+        Field::new("profit_usd", DataType::Float64, false),
+        Field::new("bribe_usd", DataType::Float64, false),
+        Field::new("no_pricing", DataType::Boolean, false),
+        Field::new("gas_paid_wei", DataType::Float64, false),
+        Field::new("bribe_wei", DataType::Float64, false),
+        Field::new("tx_count", DataType::UInt64, false),
+        Field::new("swap_count", DataType::UInt64, false),
+        Field::new("pool_path", pool_path_array.data_type().clone(), true),
+        Field::new("protocols", protocols_array.data_type().clone(), true),
+        Field::new("victim_tx_count", DataType::UInt64, false),
+        Field::new("victim_swap_count", DataType::UInt64, false),
+        Field::new("block_eth_price", DataType::Float64, false),
+        Field::new("block_bundle_count", DataType::UInt64, false),
+        Field::new("block_timestamp", DataType::UInt64, true),
+        Field::new("relay_timestamp", DataType::UInt64, true),
+        Field::new("p2p_timestamp", DataType::UInt64, true),
+        Field::new("is_private", DataType::Boolean, true),
+        Field::new("searcher_name", DataType::Utf8, true),
+        Field::new("searcher_bundles", DataType::UInt64, false),
+        Field::new("searcher_builder", DataType::Utf8, true),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(block_number_array),
+            Arc::new(tx_index_array),
+            Arc::new(tx_hash_array),
+            Arc::new(eoa_array),
+            Arc::new(mev_contract_array),
+            Arc::new(fund_array),
+            Arc::new(label_array),
+            Arc::new(is_mev_array),
+            Arc::new(profit_usd_array),
+            Arc::new(bribe_usd_array),
+            Arc::new(no_pricing_array),
+            Arc::new(gas_paid_array),
+            Arc::new(bribe_wei_array),
+            Arc::new(tx_count_array),
+            Arc::new(swap_count_array),
+            Arc::new(pool_path_array),
+            Arc::new(protocols_array),
+            Arc::new(victim_tx_count_array),
+            Arc::new(victim_swap_count_array),
+            Arc::new(block_eth_price_array),
+            Arc::new(block_bundle_count_array),
+            Arc::new(block_timestamp_array),
+            Arc::new(relay_timestamp_array),
+            Arc::new(p2p_timestamp_array),
+            Arc::new(is_private_array),
+            Arc::new(searcher_name_array),
+            Arc::new(searcher_bundles_array),
+            Arc::new(searcher_builder_array),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, B256};
+    use brontes_types::{
+        mev::{BundleHeader, Sandwich},
+        normalized_actions::NormalizedSwap,
+        FastHashSet,
+    };
+
+    use super::*;
+
+    fn swap(pool: Address) -> NormalizedSwap {
+        NormalizedSwap { pool, ..Default::default() }
+    }
+
+    fn sandwich() -> Bundle {
+        let (victim, frontrun) = (B256::with_last_byte(2), B256::with_last_byte(1));
+        let (pool_a, pool_b) = (Address::with_last_byte(10), Address::with_last_byte(11));
+
+        Bundle {
+            header: BundleHeader {
+                block_number: 1,
+                tx_index: 3,
+                tx_hash: frontrun,
+                mev_type: MevType::Sandwich,
+                profit_usd: 10.0,
+                ..Default::default()
+            },
+            data:   BundleData::Sandwich(Sandwich {
+                frontrun_tx_hash: vec![frontrun],
+                frontrun_swaps: vec![vec![swap(pool_a)]],
+                victim_swaps_tx_hashes: vec![vec![victim]],
+                victim_swaps: vec![vec![swap(pool_a), swap(pool_b)]],
+                backrun_swaps: vec![vec![swap(pool_a)]],
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn flattens_bundle_features() {
+        let bundle = sandwich();
+        let block = MevBlock { eth_price: 3000.0, ..Default::default() };
+        let metadata = BlockMetadata {
+            block_timestamp: 12,
+            relay_timestamp: Some(11_500_000),
+            private_flow: FastHashSet::from_iter([bundle.header.tx_hash]),
+            ..Default::default()
+        };
+        let searcher = SearcherInfo { name: Some("searcher".to_string()), ..Default::default() };
+
+        let record = MlBundleRecord::new(&bundle, &block, Some(&metadata), Some(&searcher));
+
+        assert!(record.is_mev);
+        assert_eq!(record.label, MevType::Sandwich.to_string());
+        assert_eq!(record.swap_count, 2);
+        assert_eq!(record.pool_path, vec![Address::with_last_byte(10).to_string(); 2]);
+        assert_eq!((record.victim_tx_count, record.victim_swap_count), (1, 2));
+        assert_eq!(record.block_eth_price, 3000.0);
+        assert_eq!(record.block_timestamp, Some(12));
+        assert_eq!(record.relay_timestamp, Some(11_500_000));
+        assert_eq!(record.is_private, Some(true));
+        assert_eq!(record.searcher_name.as_deref(), Some("searcher"));
+    }
+
+    #[test]
+    fn labels_searcher_txs_as_negative_samples() {
+        let mut bundle = sandwich();
+        bundle.header.mev_type = MevType::SearcherTx;
+        bundle.data = BundleData::Unknown(Default::default());
+
+        let record = MlBundleRecord::new(&bundle, &MevBlock::default(), None, None);
+        assert!(!record.is_mev);
+        assert_eq!(record.swap_count, 0);
+        assert_eq!(record.block_timestamp, None);
+        assert_eq!(record.is_private, None);
+
+        let batch = ml_dataset_to_record_batch(vec![record]).unwrap();
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.num_columns(), 28);
+    }
+}
@@ -5,12 +5,16 @@ use std::{
 
 use arrow::record_batch::RecordBatch;
 use brontes_types::{
-    db::traits::LibmdbxReader,
+    chain::chain_spec,
+    db::{
+        mev_block::MevBlockWithClassified, proposer_report::ProposerMevStats, traits::LibmdbxReader,
+    },
     mev::{BundleData, MevType},
 };
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveDate};
 use eyre::{Error, Ok, Result, WrapErr};
 use futures::future::try_join_all;
+use itertools::Itertools;
 use parquet::{
     arrow::{async_writer::AsyncArrowWriter, ArrowWriter},
     basic::Compression,
@@ -26,7 +30,9 @@ mod builder;
 mod bundle_header;
 mod mev_block;
 mod mev_data;
+mod ml_dataset;
 mod normalized_actions;
+mod proposer_report;
 mod searcher;
 pub mod utils;
 
@@ -35,13 +41,20 @@ use builder::builder_info_to_record_batch;
 use bundle_header::bundle_headers_to_record_batch;
 use mev_block::mev_block_to_record_batch;
 use mev_data::*;
+use ml_dataset::ml_dataset_to_record_batch;
+pub use ml_dataset::MlBundleRecord;
+use proposer_report::proposer_report_to_record_batch;
 use searcher::searcher_info_to_record_batch;
 
 pub struct ParquetExporter<DB: LibmdbxReader> {
-    pub start_block:   Option<u64>,
-    pub end_block:     Option<u64>,
-    pub base_dir_path: Option<String>,
-    pub db:            &'static DB,
+    pub start_block:    Option<u64>,
+    pub end_block:      Option<u64>,
+    pub base_dir_path:  Option<String>,
+    pub db:             &'static DB,
+    /// Splits the mev exports into files of this many blocks
+    pub partition_size: Option<u64>,
+    /// Only exports the bundles of these types, all if empty
+    pub mev_types:      Vec<MevType>,
 }
 
 impl<DB> ParquetExporter<DB>
@@ -54,10 +67,22 @@ where
         base_dir_path: Option<String>,
         db: &'static DB,
     ) -> Self {
-        Self { start_block, end_block, base_dir_path, db }
+        Self { start_block, end_block, base_dir_path, db, partition_size: None, mev_types: vec![] }
     }
 
-    pub async fn export_mev_blocks(&self) -> Result<(), Error> {
+    /// Writes the mev blocks and bundles in files of `blocks` blocks each,
+    /// named after the block range they hold
+    pub fn with_partition_size(mut self, blocks: u64) -> Self {
+        self.partition_size = Some(blocks);
+        self
+    }
+
+    pub fn with_mev_types(mut self, mev_types: Vec<MevType>) -> Self {
+        self.mev_types = mev_types;
+        self
+    }
+
+    fn fetch_mev_blocks(&self) -> Result<Vec<MevBlockWithClassified>, Error> {
         let mev_blocks = if let Some(end_block) = self.end_block {
             self.db
                 .try_fetch_mev_blocks(self.start_block, end_block)
@@ -73,6 +98,58 @@ where
             return Err(Error::msg("No MEV blocks fetched for the given range."))
         }
 
+        Ok(mev_blocks)
+    }
+
+    pub async fn export_mev_blocks(&self) -> Result<(), Error> {
+        let mev_blocks = self.fetch_mev_blocks()?;
+
+        let Some(partition_size) = self.partition_size.filter(|size| *size > 0) else {
+            return self.write_mev_blocks(mev_blocks, None).await
+        };
+
+        let partitions = mev_blocks
+            .into_iter()
+            .into_group_map_by(|mb| mb.block.block_number / partition_size);
+
+        for (partition, mev_blocks) in partitions.into_iter().sorted_by_key(|(p, _)| *p) {
+            let start_block = partition * partition_size;
+            let first_block = mev_blocks
+                .iter()
+                .map(|mb| mb.block.block_number)
+                .min()
+                .unwrap_or(start_block);
+            let partition = ExportPartition {
+                day: self.block_day(first_block)?,
+                start_block,
+                end_block: start_block + partition_size,
+            };
+            self.write_mev_blocks(mev_blocks, Some(partition)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The UTC day the block was built on
+    fn block_day(&self, block: u64) -> Result<NaiveDate, Error> {
+        let timestamp = self
+            .db
+            .get_metadata_no_dex_price(block, chain_spec().quote_asset)
+            .wrap_err_with(|| format!("Failed to fetch the metadata of block {block}"))?
+            .block_timestamp;
+
+        DateTime::from_timestamp(timestamp as i64, 0)
+            .map(|time| time.date_naive())
+            .ok_or_else(|| eyre::eyre!("invalid timestamp {timestamp} of block {block}"))
+    }
+
+    /// Writes the blocks, the bundle headers and the data of each bundle type
+    /// to their own file. With a partition, the files are named after it.
+    async fn write_mev_blocks(
+        &self,
+        mev_blocks: Vec<MevBlockWithClassified>,
+        partition: Option<ExportPartition>,
+    ) -> Result<(), Error> {
         let mev_blocks_iter = mev_blocks.into_iter();
         let (
             blocks,
@@ -97,7 +174,9 @@ where
 
             for mb in mev_blocks_iter {
                 blocks.push(mb.block);
-                for bundle in mb.mev {
+                for bundle in mb.mev.into_iter().filter(|bundle| {
+                    self.mev_types.is_empty() || self.mev_types.contains(&bundle.mev_type())
+                }) {
                     bundle_headers.push(bundle.header);
                     match bundle.data {
                         BundleData::CexDex(cex_dex) => cex_dex_arbs.push(cex_dex),
@@ -141,7 +220,7 @@ where
                         .wrap_err("Failed to convert MEV block data to record batch")?;
                     sync_write_parquet(
                         block_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, None)?,
+                        get_export_path(base_dir_path, Tables::MevBlocks, None, partition)?,
                     )
                 }
             }));
@@ -155,7 +234,12 @@ where
                         .wrap_err("Failed to convert CEX-DEX data to record batch")?;
                     sync_write_parquet(
                         cex_dex_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::CexDexTrades))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::CexDexTrades),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -169,7 +253,12 @@ where
                         .wrap_err("Failed to convert AtomicArb data to record batch")?;
                     sync_write_parquet(
                         atomic_arb_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::AtomicArb))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::AtomicArb),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -183,7 +272,12 @@ where
                         .wrap_err("Failed to convert JIT data to record batch")?;
                     sync_write_parquet(
                         jit_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Jit))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::Jit),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -197,7 +291,12 @@ where
                         .wrap_err("Failed to convert Sandwich data to record batch")?;
                     sync_write_parquet(
                         sandwich_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Sandwich))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::Sandwich),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -211,7 +310,12 @@ where
                         .wrap_err("Failed to convert JIT Sandwich data to record batch")?;
                     sync_write_parquet(
                         jit_sandwich_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::JitSandwich))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::JitSandwich),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -225,7 +329,12 @@ where
                         .wrap_err("Failed to convert Searcher Tx data to record batch")?;
                     sync_write_parquet(
                         searcher_tx_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::SearcherTx))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::SearcherTx),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -239,7 +348,12 @@ where
                         .wrap_err("Failed to convert Liquidation data to record batch")?;
                     sync_write_parquet(
                         liquidation_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Liquidation))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::Liquidation),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -253,7 +367,12 @@ where
                         .wrap_err("Failed to convert bundle headers to record batch")?;
                     sync_write_parquet(
                         bundle_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Unknown))?,
+                        get_export_path(
+                            base_dir_path,
+                            Tables::MevBlocks,
+                            Some(MevType::Unknown),
+                            partition,
+                        )?,
                     )
                 }
             }));
@@ -263,6 +382,81 @@ where
         Ok(())
     }
 
+    /// Exports one flattened, labeled record per bundle (searcher txs
+    /// included) for training mev classification models.
+    pub async fn export_ml_dataset(&self) -> Result<(), Error> {
+        let mev_blocks = self.fetch_mev_blocks()?;
+        let base_dir_path = self.base_dir_path.clone();
+        let db = self.db;
+
+        tokio::task::spawn_blocking(move || {
+            let searchers = db.try_fetch_searcher_eoa_infos(
+                mev_blocks
+                    .iter()
+                    .flat_map(|mb| mb.mev.iter().map(|bundle| bundle.header.eoa))
+                    .unique()
+                    .collect(),
+            )?;
+
+            let records = mev_blocks
+                .iter()
+                .flat_map(|mb| {
+                    // blocks without stored metadata are exported without the timing features
+                    let metadata = db
+                        .get_metadata_no_dex_price(mb.block.block_number, chain_spec().quote_asset)
+                        .ok();
+
+                    mb.mev
+                        .iter()
+                        .map(|bundle| {
+                            MlBundleRecord::new(
+                                bundle,
+                                &mb.block,
+                                metadata.as_ref().map(|m| &m.block_metadata),
+                                searchers.get(&bundle.header.eoa),
+                            )
+                        })
+                        .collect_vec()
+                })
+                .collect::<Vec<_>>();
+
+            let ml_batch = ml_dataset_to_record_batch(records)
+                .wrap_err("Failed to convert bundles to ML dataset record batch")?;
+
+            let mut path = PathBuf::from(
+                base_dir_path
+                    .as_deref()
+                    .unwrap_or("../brontes-notebook/data/brontes-exports"),
+            );
+            path.push(DEFAULT_ML_DATASET_DIR);
+
+            sync_write_parquet(ml_batch, create_file_path(path)?)
+        })
+        .await?
+    }
+
+    /// Writes the per proposer mev report, returns the path of the file
+    pub async fn export_proposer_report(&self, stats: Vec<ProposerMevStats>) -> Result<PathBuf> {
+        let base_dir_path = self.base_dir_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let report_batch = proposer_report_to_record_batch(stats)
+                .wrap_err("Failed to convert proposer report to record batch")?;
+
+            let mut path = PathBuf::from(
+                base_dir_path
+                    .as_deref()
+                    .unwrap_or("../brontes-notebook/data/brontes-exports"),
+            );
+            path.push(DEFAULT_PROPOSER_REPORT_DIR);
+
+            let path = create_file_path(path)?;
+            sync_write_parquet(report_batch, path.clone())?;
+            Ok(path)
+        })
+        .await?
+    }
+
     pub async fn export_address_metadata(&self) -> Result<(), Error> {
         let address_metadata = self
             .db
@@ -386,6 +580,53 @@ pub fn get_path(
     batch_type: Tables,
     mev_type: Option<MevType>,
 ) -> Result<PathBuf> {
+    create_file_path(get_dir_path(custom_path, batch_type, mev_type))
+}
+
+/// A file of a partitioned export, holding the blocks
+/// `start_block..end_block`. The files are grouped by the day of their first
+/// block.
+#[derive(Debug, Clone, Copy)]
+pub struct ExportPartition {
+    pub day:         NaiveDate,
+    pub start_block: u64,
+    pub end_block:   u64,
+}
+
+/// Path of the partition, e.g.
+/// "mev/bundles/atomic-arb/2023-09-01/18000000-18010000.parquet". Unlike
+/// [`get_path`] it doesn't depend on the time of the export, so exporting a
+/// range again replaces its partitions.
+pub fn get_partition_path(
+    custom_path: Option<String>,
+    batch_type: Tables,
+    mev_type: Option<MevType>,
+    partition: ExportPartition,
+) -> Result<PathBuf> {
+    let dir_path = get_dir_path(custom_path, batch_type, mev_type)
+        .join(partition.day.format("%Y-%m-%d").to_string());
+    std::fs::create_dir_all(&dir_path)?;
+
+    Ok(dir_path.join(format!("{}-{}.parquet", partition.start_block, partition.end_block)))
+}
+
+fn get_export_path(
+    custom_path: Option<String>,
+    batch_type: Tables,
+    mev_type: Option<MevType>,
+    partition: Option<ExportPartition>,
+) -> Result<PathBuf> {
+    match partition {
+        Some(partition) => get_partition_path(custom_path, batch_type, mev_type, partition),
+        None => get_path(custom_path, batch_type, mev_type),
+    }
+}
+
+fn get_dir_path(
+    custom_path: Option<String>,
+    batch_type: Tables,
+    mev_type: Option<MevType>,
+) -> PathBuf {
     let base_path = custom_path
         .as_deref()
         .unwrap_or("../brontes-notebook/data/brontes-exports");
@@ -399,7 +640,7 @@ pub fn get_path(
         path.push("bundles");
         path.push(mev_type.get_parquet_path());
     }
-    create_file_path(path)
+    path
 }
 
 pub fn create_file_path<P: AsRef<Path>>(base_dir: P) -> Result<PathBuf> {
@@ -433,3 +674,5 @@ pub const DEFAULT_BLOCK_DIR: &str = "mev";
 pub const DEFAULT_METADATA_DIR: &str = "address_metadata";
 pub const DEFAULT_SEARCHER_INFO_DIR: &str = "searcher_info";
 pub const DEFAULT_BUILDER_INFO_DIR: &str = "builder-info";
+pub const DEFAULT_ML_DATASET_DIR: &str = "ml-dataset";
+pub const DEFAULT_PROPOSER_REPORT_DIR: &str = "proposer-report";
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use arrow::{
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::db::proposer_report::ProposerMevStats;
+
+use super::utils::{
+    build_float64_array, build_record_batch, build_string_array, build_uint64_array,
+    get_string_array_from_owned, u128_to_binary_array,
+};
+
+pub fn proposer_report_to_record_batch(
+    stats: Vec<ProposerMevStats>,
+) -> Result<RecordBatch, ArrowError> {
+    let fee_recipient_array =
+        build_string_array(stats.iter().map(|s| s.fee_recipient.to_string()).collect());
+    let entity_array =
+        get_string_array_from_owned(stats.iter().map(|s| s.entity.as_ref()).collect());
+    let blocks_array = build_uint64_array(stats.iter().map(|s| s.blocks).collect());
+    let mev_boost_blocks_array =
+        build_uint64_array(stats.iter().map(|s| s.mev_boost_blocks).collect());
+    let mev_reward_array = u128_to_binary_array(stats.iter().map(|s| s.mev_reward).collect());
+    let mev_reward_eth_array =
+        build_float64_array(stats.iter().map(|s| s.mev_reward_eth()).collect());
+    let mev_reward_usd_array =
+        build_float64_array(stats.iter().map(|s| s.mev_reward_usd).collect());
+    let bundles_array = build_uint64_array(stats.iter().map(|s| s.bundles).collect());
+    let searcher_profit_usd_array =
+        build_float64_array(stats.iter().map(|s| s.searcher_profit_usd).collect());
+    let bribe_usd_array = build_float64_array(stats.iter().map(|s| s.bribe_usd).collect());
+
+    let schema = Schema::new(vec![
+        Field::new("fee_recipient", DataType::Utf8, false),
+        Field::new("entity", DataType::Utf8, true),
+        Field::new("blocks", DataType::UInt64, false),
+        Field::new("mev_boost_blocks", DataType::UInt64, false),
+        Field::new("mev_reward", DataType::Binary, false),
+        Field::new("mev_reward_eth", DataType::Float64, false),
+        Field::new("mev_reward_usd", DataType::Float64, false),
+        Field::new("bundles", DataType::UInt64, false),
+        Field::new("searcher_profit_usd", DataType::Float64, false),
+        Field::new("bribe_usd", DataType::Float64, false),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(fee_recipient_array),
+            Arc::new(entity_array),
+            Arc::new(blocks_array),
+            Arc::new(mev_boost_blocks_array),
+            Arc::new(mev_reward_array),
+            Arc::new(mev_reward_eth_array),
+            Arc::new(mev_reward_usd_array),
+            Arc::new(bundles_array),
+            Arc::new(searcher_profit_usd_array),
+            Arc::new(bribe_usd_array),
+        ],
+    )
+}
@@ -0,0 +1,47 @@
+use brontes_types::{db::suppressed_bundle::SuppressedBundle, denylist::denylist, mev::Bundle};
+use itertools::Itertools;
+
+use crate::config::bundle_pools;
+
+/// Drops the bundles that touch a denylisted pool or token, returning the kept
+/// bundles and the record of the dropped ones
+pub(super) fn apply_denylist(bundles: Vec<Bundle>) -> (Vec<Bundle>, Vec<SuppressedBundle>) {
+    let denylist = denylist();
+    if denylist.is_empty() {
+        return (bundles, vec![])
+    }
+
+    let mut suppressed = vec![];
+    let bundles = bundles
+        .into_iter()
+        .filter(|bundle| {
+            let tokens = bundle
+                .header
+                .balance_deltas
+                .iter()
+                .flat_map(|tx| &tx.address_deltas)
+                .flat_map(|address| &address.token_deltas)
+                .map(|delta| delta.token.address)
+                .unique();
+
+            let Some(exclusion) = denylist.find_exclusion(bundle_pools(&bundle.data), tokens)
+            else {
+                return true
+            };
+
+            tracing::info!(
+                block = bundle.header.block_number,
+                tx_hash = ?bundle.header.tx_hash,
+                mev_type = %bundle.header.mev_type,
+                address = ?exclusion.address,
+                kind = %exclusion.kind,
+                reason = %exclusion.reason,
+                "suppressed bundle touching a denylisted address"
+            );
+            suppressed.push(SuppressedBundle::new(bundle, exclusion));
+            false
+        })
+        .collect();
+
+    (bundles, suppressed)
+}
@@ -0,0 +1,58 @@
+use std::{sync::OnceLock, time::Duration};
+
+use alloy_primitives::B256;
+use brontes_metrics::inspectors::InspectorRunMetrics;
+use brontes_types::{
+    db::inspector_run::InspectorRun,
+    mev::{Bundle, MevType},
+    FastHashSet,
+};
+
+static INSPECTOR_RUN_METRICS: OnceLock<InspectorRunMetrics> = OnceLock::new();
+
+/// How long an inspector took on a block and the bundles it returned
+#[derive(Debug)]
+pub(super) struct InspectorTiming {
+    pub inspector:  String,
+    pub elapsed:    Duration,
+    pub candidates: Vec<(B256, MevType)>,
+}
+
+impl InspectorTiming {
+    pub fn new(inspector: &str, elapsed: Duration, bundles: &[Bundle]) -> Self {
+        Self {
+            inspector: inspector.to_string(),
+            elapsed,
+            candidates: bundles.iter().map(InspectorRun::bundle_key).collect(),
+        }
+    }
+}
+
+/// Matches the candidates of every inspector against the final bundles of the
+/// block and adds the runs to the inspector totals of the metrics endpoint
+pub(super) fn record_inspector_runs(
+    block_number: u64,
+    timings: Vec<InspectorTiming>,
+    results: &[Bundle],
+) -> Vec<InspectorRun> {
+    let results = results
+        .iter()
+        .map(InspectorRun::bundle_key)
+        .collect::<FastHashSet<_>>();
+    let metrics = INSPECTOR_RUN_METRICS.get_or_init(InspectorRunMetrics::new);
+
+    timings
+        .into_iter()
+        .map(|timing| {
+            let run = InspectorRun::new(
+                block_number,
+                &timing.inspector,
+                timing.elapsed,
+                &timing.candidates,
+                &results,
+            );
+            metrics.inspector_run(&run.inspector, run.elapsed_us, run.candidates, run.bundles);
+            run
+        })
+        .collect()
+}
@@ -106,12 +106,18 @@ define_mev_precedence!(
 
     // filter out all atomic arbs that we kept as cex dex
     AtomicArb => CexDexTrades;
-    Unknown, SearcherTx => CexDexQuotes;
-    Unknown, SearcherTx => CexDexTrades;
+    // a directional trade with a cex leg is a cex dex arb rather than a frontrun
+    Unknown, SearcherTx, Frontrun => CexDexQuotes;
+    Unknown, SearcherTx, Frontrun => CexDexTrades;
     Unknown, SearcherTx => AtomicArb;
+    // a frontrun trade that also closes a cycle is ranked as the frontrun, the
+    // victims make up most of its profit
+    Unknown, SearcherTx, AtomicArb => Frontrun;
+    Unknown, SearcherTx => ApprovalRace;
     Unknown, SearcherTx, AtomicArb => Jit;
-    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Liquidation;
-    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Sandwich;
-    Unknown, SearcherTx, AtomicArb, Jit, CexDexQuotes, CexDexTrades=> JitCexDex;
-    Unknown, SearcherTx, AtomicArb, CexDexQuotes, CexDexTrades, Jit, Sandwich => JitSandwich;
+    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades, Frontrun  => Liquidation;
+    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades, Frontrun  => Sandwich;
+    Unknown, SearcherTx, AtomicArb, Jit, CexDexQuotes, CexDexTrades, Frontrun => JitCexDex;
+    Unknown, SearcherTx, AtomicArb, CexDexQuotes, CexDexTrades, Jit, Sandwich,
+        Frontrun => JitSandwich;
 );
@@ -27,11 +27,17 @@
 //! let composer = Composer::new(&orchestra, tree, metadata);
 //! // Future execution of the composer to process MEV data
 //! ```
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use brontes_types::{
-    db::{block_analysis::BlockAnalysis, traits::LibmdbxReader},
+    db::{
+        block_analysis::BlockAnalysis, builder_pnl::BuilderPnl, gas_bids::GasBid,
+        inspector_run::InspectorRun, possible_mev_timeline::PossibleMevTimeline,
+        searcher_fingerprint::SearcherFingerprint, searcher_funding::SearcherFunding,
+        searcher_inventory::SearcherInventoryDelta, suppressed_bundle::SuppressedBundle,
+        traits::LibmdbxReader, victim_execution::VictimExecution,
+    },
     mev::Mev,
     BlockData, FastHashMap, MultiBlockData,
 };
@@ -39,7 +45,10 @@ use itertools::Itertools;
 use tracing::{span, Level};
 
 mod composer_filters;
+mod denylist;
+mod inspector_runs;
 mod mev_filters;
+mod result_cache;
 mod utils;
 use brontes_types::{
     db::metadata::Metadata,
@@ -48,24 +57,48 @@ use brontes_types::{
     tree::BlockTree,
 };
 use composer_filters::{ComposeFunction, MEV_COMPOSABILITY_FILTER};
+use denylist::apply_denylist;
+use inspector_runs::{record_inspector_runs, InspectorTiming};
 use mev_filters::{FilterFn, MEV_DEDUPLICATION_FILTER};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+pub use result_cache::{init_inspector_cache, inspector_cache, InspectorCache};
+#[cfg(feature = "provenance")]
+use utils::annotate_provenance;
 use utils::{
-    build_mev_header, filter_and_count_bundles, find_mev_with_matching_tx_hashes, sort_mev_by_type,
-    try_deduping_mev,
+    annotate_sanctioned_addresses, build_mev_header, filter_and_count_bundles,
+    find_mev_with_matching_tx_hashes, sort_mev_by_type, superseded_by, try_deduping_mev,
 };
 
 const DISCOVERY_PRIORITY_FEE_MULTIPLIER: f64 = 2.0;
 
-use crate::{discovery::DiscoveryInspector, Inspector};
+use crate::{discovery::DiscoveryInspector, shared_utils::pricing_quote_asset, Inspector};
 
 #[derive(Debug)]
 pub struct ComposerResults {
-    pub block_details:     MevBlock,
-    pub mev_details:       Vec<Bundle>,
+    pub block_details:         MevBlock,
+    pub mev_details:           Vec<Bundle>,
     /// all txes with coinbase.transfers that weren't classified
-    pub possible_mev_txes: PossibleMevCollection,
-    pub block_analysis:    BlockAnalysis,
+    pub possible_mev_txes:     PossibleMevCollection,
+    pub block_analysis:        BlockAnalysis,
+    /// bidding features of every bundle in the block
+    pub gas_bids:              Vec<GasBid>,
+    /// calldata fingerprints of the calls to the searcher contracts
+    pub fingerprints:          Vec<SearcherFingerprint>,
+    /// net inventory change of every searcher with a bundle in the block
+    pub inventory:             Vec<SearcherInventoryDelta>,
+    /// transfers to searcher EOAs from sources that hint at who runs them
+    pub funding:               Vec<(Address, SearcherFunding)>,
+    /// execution quality of the victim swaps of the sandwiches in the block
+    pub victim_executions:     Vec<VictimExecution>,
+    /// block context of the possible mev txes that weren't classified
+    pub possible_mev_timeline: Vec<PossibleMevTimeline>,
+    /// bundles dropped because they touched a denylisted token or pool
+    pub suppressed_bundles:    Vec<SuppressedBundle>,
+    /// timings and result counts of the inspectors, empty if the results
+    /// were loaded from the inspector cache
+    pub inspector_runs:        Vec<InspectorRun>,
+    /// revenue, proposer payment and subsidy of the builder of the block
+    pub builder_pnl:           BuilderPnl,
 }
 
 pub fn run_block_inspection<DB: LibmdbxReader>(
@@ -76,44 +109,108 @@ pub fn run_block_inspection<DB: LibmdbxReader>(
     let this_data = data.get_most_recent_block().clone();
     let BlockData { metadata, tree } = this_data;
 
-    let (possible_mev_txes, classified_mev) = run_inspectors(orchestra, data);
+    let (possible_mev_txes, classified_mev, timings) = run_inspectors(orchestra, data);
+    let (classified_mev, suppressed_bundles) = apply_denylist(classified_mev);
 
     let possible_arbs = possible_mev_txes.clone();
 
-    let quote_token = orchestra[0].get_quote_token();
-
-    let (block_details, mev_details) =
-        on_orchestra_resolution(tree, possible_mev_txes, metadata, classified_mev, quote_token, db);
+    // the inspectors of a run quote in the same asset, see
+    // `InspectorConfig::quote_asset`, so the block totals sum their bundle
+    // profits in it
+    let quote_token = orchestra
+        .first()
+        .map(|inspector| inspector.get_quote_token())
+        .or_else(pricing_quote_asset)
+        .unwrap_or_default();
+
+    let (block_details, mev_details) = on_orchestra_resolution(
+        tree.clone(),
+        possible_mev_txes,
+        metadata.clone(),
+        classified_mev,
+        quote_token,
+        db,
+    );
 
+    let inspector_runs = record_inspector_runs(metadata.block_num, timings, &mev_details);
     let block_analysis = BlockAnalysis::new(&block_details, &mev_details);
-
-    ComposerResults { block_details, mev_details, possible_mev_txes: possible_arbs, block_analysis }
+    let builder_pnl = BuilderPnl::new(&block_details);
+    let gas_bids = GasBid::from_bundles(&mev_details, &tree);
+    let fingerprints = SearcherFingerprint::from_bundles(&mev_details, &tree);
+    let inventory = SearcherInventoryDelta::from_bundles(&mev_details, &tree, db);
+    let funding =
+        SearcherFunding::from_block(metadata.block_num, tree.clone(), db).unwrap_or_else(|e| {
+            tracing::error!(
+                err=%e,
+                block_num=metadata.block_num,
+                "failed to collect searcher funding"
+            );
+            vec![]
+        });
+    let victim_executions = VictimExecution::from_bundles(&mev_details, &tree, &metadata);
+    let possible_mev_timeline =
+        PossibleMevTimeline::from_possible_mev(&possible_arbs, &mev_details, &tree);
+
+    ComposerResults {
+        block_details,
+        mev_details,
+        possible_mev_txes: possible_arbs,
+        block_analysis,
+        gas_bids,
+        fingerprints,
+        inventory,
+        funding,
+        victim_executions,
+        possible_mev_timeline,
+        suppressed_bundles,
+        inspector_runs,
+        builder_pnl,
+    }
 }
 
 fn run_inspectors(
     orchestra: &[&dyn Inspector<Result = Vec<Bundle>>],
     data: MultiBlockData,
-) -> (PossibleMevCollection, Vec<Bundle>) {
+) -> (PossibleMevCollection, Vec<Bundle>, Vec<InspectorTiming>) {
     let this_data = data.get_most_recent_block().clone();
     let BlockData { metadata, tree } = this_data;
     let mut possible_mev_txes =
         DiscoveryInspector::new(DISCOVERY_PRIORITY_FEE_MULTIPLIER).find_possible_mev(tree.clone());
 
-    let results = orchestra
-        .par_iter()
-        .flat_map(|inspector| {
-            let window = inspector.block_window();
-            // not sufficient size yet
-            if data.blocks < window {
-                return vec![]
-            };
-            let data = data.split_to_size(window);
-            let span =
-                span!(Level::ERROR, "Inspector", inspector = %inspector.get_id(),block=&metadata.block_num);
-
-            span.in_scope(|| inspector.inspect_block(data))
-        })
-        .collect::<Vec<_>>();
+    let cached = inspector_cache().and_then(|cache| Some((cache, cache.key(orchestra, &data)?)));
+    let (results, timings) = if let Some(bundles) = cached
+        .as_ref()
+        .and_then(|(cache, key)| cache.get(metadata.block_num, *key))
+    {
+        tracing::debug!(block = metadata.block_num, "loaded inspector results from cache");
+        (bundles, vec![])
+    } else {
+        let (timings, results): (Vec<_>, Vec<_>) = orchestra
+            .par_iter()
+            .filter_map(|inspector| {
+                let window = inspector.block_window();
+                // not sufficient size yet, or switched off while running
+                if data.blocks < window || !inspector.is_enabled() {
+                    return None
+                };
+                let data = data.split_to_size(window);
+                let span =
+                    span!(Level::ERROR, "Inspector", inspector = %inspector.get_id(),block=&metadata.block_num);
+
+                let start = Instant::now();
+                let bundles = span.in_scope(|| inspector.inspect_block(data));
+                let timing = InspectorTiming::new(inspector.get_id(), start.elapsed(), &bundles);
+
+                Some((timing, bundles))
+            })
+            .unzip();
+        let results = results.into_iter().flatten().collect::<Vec<_>>();
+
+        if let Some((cache, key)) = cached {
+            cache.insert(metadata.block_num, key, &results);
+        }
+        (results, timings)
+    };
 
     results.iter().for_each(|bundle| {
         bundle
@@ -131,7 +228,7 @@ fn run_inspectors(
         .0
         .sort_by(|a, b| a.tx_idx.cmp(&b.tx_idx));
 
-    (possible_mev_collection, results)
+    (possible_mev_collection, results, timings)
 }
 
 fn on_orchestra_resolution<DB: LibmdbxReader>(
@@ -163,7 +260,12 @@ fn on_orchestra_resolution<DB: LibmdbxReader>(
         },
     );
 
+    resolve_remaining_overlaps(&mut sorted_mev);
+
     let (mev_count, mut filtered_bundles) = filter_and_count_bundles(sorted_mev);
+    let sanctioned_addresses = annotate_sanctioned_addresses(&tree, &mut filtered_bundles, db);
+    #[cfg(feature = "provenance")]
+    annotate_provenance(&tree, &mut filtered_bundles);
 
     let header = build_mev_header(
         &metadata,
@@ -172,6 +274,7 @@ fn on_orchestra_resolution<DB: LibmdbxReader>(
         mev_count,
         &filtered_bundles,
         quote_token,
+        sanctioned_addresses,
         db,
     );
     // keep order
@@ -190,16 +293,16 @@ fn deduplicate_mev<DB: LibmdbxReader>(
 ) {
     let Some(dominant_mev_list) = sorted_mev.get(dominant_mev_type) else { return };
 
-    let mut indexes = Vec::new();
+    let mut removals = Vec::new();
 
-    for dominate_mev in dominant_mev_list {
+    for (dominant_index, dominate_mev) in dominant_mev_list.iter().enumerate() {
         let hashes = dominate_mev.data.mev_transaction_hashes();
 
         for &sub_mev_type in subordinate_mev_types {
             let Some(sub_mev_list) = sorted_mev.get(&sub_mev_type) else {
                 continue;
             };
-            indexes.extend(
+            removals.extend(
                 try_deduping_mev(
                     tree.clone(),
                     Box::new(db),
@@ -208,16 +311,94 @@ fn deduplicate_mev<DB: LibmdbxReader>(
                     extra_filter_function,
                     &hashes,
                 )
-                .zip(vec![sub_mev_type].into_iter().cycle()),
+                .map(|index| (sub_mev_type, index, dominant_index)),
             )
         }
     }
 
-    indexes
+    // a bundle overlapping several dominant bundles is recorded on the first
+    let removals = removals
         .into_iter()
-        .unique()
-        .sorted_unstable_by(|a, b| b.0.cmp(&a.0))
-        .for_each(|(index, mev_type)| {
+        .unique_by(|(mev_type, index, _)| (*mev_type, *index))
+        .collect_vec();
+
+    remove_superseded(
+        sorted_mev,
+        removals
+            .into_iter()
+            .map(|(mev_type, index, dominant_index)| {
+                ((mev_type, index), (*dominant_mev_type, dominant_index))
+            })
+            .collect(),
+    );
+}
+
+/// Resolves the overlaps between bundles of different mev types that no
+/// precedence rule covers, so that the profit of a transaction isn't counted
+/// twice. The bundle with the highest profit keeps the transactions. Cex dex
+/// bundles of the different pricing methods are alternative estimates of the
+/// same arb and are kept side by side.
+fn resolve_remaining_overlaps(sorted_mev: &mut FastHashMap<MevType, Vec<Bundle>>) {
+    let by_profit = sorted_mev
+        .iter()
+        .flat_map(|(mev_type, bundles)| {
+            bundles
+                .iter()
+                .enumerate()
+                .map(move |(index, bundle)| (*mev_type, index, bundle))
+        })
+        .sorted_by(|(a_type, _, a), (b_type, _, b)| {
+            b.header
+                .profit_usd
+                .total_cmp(&a.header.profit_usd)
+                .then(a.header.tx_index.cmp(&b.header.tx_index))
+                .then(a_type.as_ref().cmp(b_type.as_ref()))
+        })
+        .collect_vec();
+
+    let mut kept: Vec<(MevType, usize, Vec<B256>)> = Vec::new();
+    let mut removals = Vec::new();
+    for (mev_type, index, bundle) in by_profit {
+        let hashes = bundle.data.mev_transaction_hashes();
+        let winner = kept.iter().find(|(kept_type, _, kept_hashes)| {
+            *kept_type != mev_type
+                && !(kept_type.is_cex_dex_estimate() && mev_type.is_cex_dex_estimate())
+                && hashes.iter().any(|hash| kept_hashes.contains(hash))
+        });
+
+        match winner {
+            Some(&(winner_type, winner_index, _)) => {
+                removals.push(((mev_type, index), (winner_type, winner_index)))
+            }
+            None => kept.push((mev_type, index, hashes)),
+        }
+    }
+
+    remove_superseded(sorted_mev, removals);
+}
+
+/// Records each losing bundle on the bundle it lost to and removes it. Takes
+/// `(loser, winner)` pairs of mev types and indexes into `sorted_mev`
+fn remove_superseded(
+    sorted_mev: &mut FastHashMap<MevType, Vec<Bundle>>,
+    removals: Vec<((MevType, usize), (MevType, usize))>,
+) {
+    for &((loser_type, loser_index), (winner_type, winner_index)) in &removals {
+        let superseded =
+            superseded_by(&sorted_mev[&loser_type][loser_index].header, false).collect_vec();
+        if let Some(winner) = sorted_mev
+            .get_mut(&winner_type)
+            .and_then(|bundles| bundles.get_mut(winner_index))
+        {
+            winner.header.superseded.extend(superseded);
+        }
+    }
+
+    removals
+        .into_iter()
+        .map(|(loser, _)| loser)
+        .sorted_unstable_by(|a, b| b.1.cmp(&a.1))
+        .for_each(|(mev_type, index)| {
             let Some(mev_list) = sorted_mev.get_mut(&mev_type) else { return };
             mev_list.remove(index);
         });
@@ -307,14 +488,115 @@ fn try_compose_mev(
 
 #[cfg(test)]
 pub mod tests {
-    use alloy_primitives::hex;
+    use alloy_primitives::{hex, TxHash};
+    use brontes_types::{
+        db::cex::{trades::CexDexTradeConfig, CexExchange},
+        mev::{
+            AtomicArb, BundleData, BundleHeader, CexDexQuote, Frontrun, JitLiquidity, Liquidation,
+        },
+    };
+    use reth_primitives::Header;
 
     use super::*;
     use crate::{
+        sandwich::SandwichMode,
         test_utils::{ComposerRunConfig, InspectorTestUtils, USDC_ADDRESS},
-        Inspectors,
+        DetectionLimits, Inspectors,
     };
 
+    fn bundle(mev_type: MevType, profit_usd: f64, data: BundleData) -> Bundle {
+        Bundle { header: BundleHeader { mev_type, profit_usd, ..Default::default() }, data }
+    }
+
+    #[test]
+    pub fn test_remaining_overlaps_keep_most_profitable() {
+        let [a, b, c] = [1, 2, 3].map(B256::with_last_byte);
+        let liquidation = bundle(
+            MevType::Liquidation,
+            10.0,
+            BundleData::Liquidation(Liquidation { liquidation_tx_hash: a, ..Default::default() }),
+        );
+        let jit = bundle(
+            MevType::Jit,
+            4.0,
+            BundleData::Jit(JitLiquidity {
+                frontrun_mint_tx_hash: a,
+                backrun_burn_tx_hash: b,
+                ..Default::default()
+            }),
+        );
+        let cex_dex = |mev_type, profit_usd| {
+            bundle(
+                mev_type,
+                profit_usd,
+                BundleData::CexDexQuote(CexDexQuote { tx_hash: c, ..Default::default() }),
+            )
+        };
+
+        let mut sorted_mev = sort_mev_by_type(vec![
+            jit,
+            liquidation,
+            cex_dex(MevType::CexDexQuotes, 5.0),
+            cex_dex(MevType::CexDexTrades, 6.0),
+        ]);
+        resolve_remaining_overlaps(&mut sorted_mev);
+
+        assert!(sorted_mev[&MevType::Jit].is_empty());
+        let [liquidation] = &sorted_mev[&MevType::Liquidation][..] else { panic!() };
+        assert_eq!(liquidation.header.superseded.len(), 1);
+        let superseded = &liquidation.header.superseded[0];
+        assert_eq!((superseded.mev_type, superseded.profit_usd), (MevType::Jit, 4.0));
+        assert!(!superseded.merged);
+
+        // the cex dex estimates don't supersede each other
+        assert_eq!(sorted_mev[&MevType::CexDexQuotes].len(), 1);
+        assert_eq!(sorted_mev[&MevType::CexDexTrades].len(), 1);
+    }
+
+    #[brontes_macros::test]
+    pub async fn test_frontrun_supersedes_atomic_arb() {
+        let db = brontes_core::test_utils::get_db_handle(tokio::runtime::Handle::current()).await;
+        let tree = Arc::new(BlockTree::new(Header::default(), 0));
+        let [frontrun_tx, victim_tx] = [1, 2].map(B256::with_last_byte);
+
+        let frontrun = bundle(
+            MevType::Frontrun,
+            2.0,
+            BundleData::Frontrun(Frontrun {
+                frontrun_tx_hash: frontrun_tx,
+                victim_swaps_tx_hashes: vec![victim_tx],
+                ..Default::default()
+            }),
+        );
+        // the frontrun swaps close a cycle, which the atomic arb inspector
+        // picks up with a higher profit than the displacement
+        let atomic_arb = bundle(
+            MevType::AtomicArb,
+            5.0,
+            BundleData::AtomicArb(AtomicArb { tx_hash: frontrun_tx, ..Default::default() }),
+        );
+
+        let mut sorted_mev = sort_mev_by_type(vec![atomic_arb, frontrun]);
+        MEV_DEDUPLICATION_FILTER
+            .iter()
+            .for_each(|(dominant, filter_fn, subordinates)| {
+                deduplicate_mev(
+                    tree.clone(),
+                    db,
+                    dominant,
+                    filter_fn,
+                    subordinates,
+                    &mut sorted_mev,
+                )
+            });
+        resolve_remaining_overlaps(&mut sorted_mev);
+
+        assert!(sorted_mev[&MevType::AtomicArb].is_empty());
+        let [frontrun] = &sorted_mev[&MevType::Frontrun][..] else { panic!() };
+        let [superseded] = &frontrun.header.superseded[..] else { panic!() };
+        assert_eq!((superseded.mev_type, superseded.profit_usd), (MevType::AtomicArb, 5.0));
+    }
+
     #[brontes_macros::test]
     pub async fn test_jit_sandwich() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.2).await;
@@ -333,4 +615,56 @@ pub mod tests {
 
         inspector_util.run_composer(config, None).await.unwrap();
     }
+
+    #[brontes_macros::test]
+    pub async fn test_victim_execution_of_sandwich() {
+        let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 1.0).await;
+        let classifier = &inspector_util.classifier_inspector;
+        let victim: TxHash =
+            hex!("19122ffe65a714f0551edbb16a24551031056df16ccaab39db87a73ac657b722").into();
+
+        let (tree, quotes) = classifier
+            .build_tree_txes_with_pricing(
+                vec![
+                    hex!("ff79c471b191c0021cfb62408cb1d7418d09334665a02106191f6ed16a47e36c").into(),
+                    victim,
+                    hex!("67771f2e3b0ea51c11c5af156d679ccef6933db9a4d4d6cd7605b4eee27f9ac8").into(),
+                ],
+                USDC_ADDRESS,
+                vec![Address::new(hex!("28cf5263108c1c40cf30e0fe390bd9ccf929bf82"))],
+            )
+            .await
+            .unwrap()
+            .remove(0);
+
+        let inspector = Inspectors::Sandwich.init_mev_inspector(
+            USDC_ADDRESS,
+            classifier.libmdbx,
+            &[CexExchange::Binance],
+            CexDexTradeConfig::default(),
+            SandwichMode::default(),
+            DetectionLimits::default(),
+            None,
+        );
+        let metadata = Metadata { dex_quotes: Some(quotes), ..Default::default() };
+        let data = BlockData { metadata: metadata.into(), tree: tree.into() };
+        let results = run_block_inspection(
+            &[inspector],
+            MultiBlockData { blocks: 1, per_block_data: vec![data] },
+            classifier.libmdbx,
+        );
+
+        assert!(!results.victim_executions.is_empty());
+        for execution in results.victim_executions {
+            assert_eq!(execution.tx_hash, victim);
+            assert_eq!(execution.mev_type, MevType::Sandwich);
+            // the victim pays the pool fee and its own price impact, neither of
+            // which the benchmark price has
+            assert!(execution.benchmark_amount_out > execution.amount_out, "{execution:#?}");
+            assert!(
+                execution.routing_inefficiency > 0.0 && execution.routing_inefficiency < 1.0,
+                "{execution:#?}"
+            );
+        }
+    }
 }
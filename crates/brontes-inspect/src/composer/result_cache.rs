@@ -0,0 +1,236 @@
+//! On disk cache of the inspector results. The results of a block are keyed by
+//! a content hash of the classified trees and metadata the inspectors were run
+//! on, together with the settings of the inspectors, so a rerun over unchanged
+//! inputs loads the bundles instead of recomputing them. The key also includes
+//! the build the results were produced with, so entries of a different
+//! inspector implementation are never reused. Entries are validated when they
+//! are read and written through a temporary file, so a truncated or foreign
+//! file is treated as a miss.
+//!
+//! The data is hashed by its canonical encoding, see
+//! [`brontes_types::content_hash`], so the key doesn't depend on the iteration
+//! order of the maps of a run. Address labels and searcher info are read from
+//! the database by the inspectors and are not part of the key, the cache needs
+//! to be cleared after they change.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use alloy_primitives::{Keccak256, B256};
+use brontes_types::{
+    address_filter::address_filter,
+    content_hash::{content_hash, ContentHashError},
+    db::metadata::Metadata,
+    mev::{Bundle, BundleRedefined},
+    normalized_actions::Action,
+    BlockData, MultiBlockData, Root,
+};
+use itertools::Itertools;
+use redefined::RedefinedConvert;
+use rkyv::AlignedVec;
+
+use crate::Inspector;
+
+static INSPECTOR_CACHE: OnceLock<InspectorCache> = OnceLock::new();
+
+/// Enables caching of the inspector results in the given directory.
+/// `build_id` identifies the build, e.g. its git sha. Builds with uncommitted
+/// changes need an id of their own, as they share the sha of their commit
+pub fn init_inspector_cache(dir: impl AsRef<Path>, build_id: &str) -> eyre::Result<()> {
+    let cache = InspectorCache::new(dir, build_id)?;
+    INSPECTOR_CACHE
+        .set(cache)
+        .map_err(|_| eyre::eyre!("inspector cache already initialized"))
+}
+
+pub fn inspector_cache() -> Option<&'static InspectorCache> {
+    INSPECTOR_CACHE.get()
+}
+
+#[derive(Debug, Clone)]
+pub struct InspectorCache {
+    dir:      PathBuf,
+    build_id: String,
+}
+
+impl InspectorCache {
+    pub fn new(dir: impl AsRef<Path>, build_id: &str) -> eyre::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, build_id: build_id.to_string() })
+    }
+
+    /// Content hash of the blocks and the inspectors they are run with, `None`
+    /// if the data can't be encoded, in which case the results aren't cached
+    pub fn key(
+        &self,
+        orchestra: &[&dyn Inspector<Result = Vec<Bundle>>],
+        data: &MultiBlockData,
+    ) -> Option<B256> {
+        self.try_key(orchestra, data)
+            .inspect_err(|e| tracing::warn!(err=%e, "failed to hash the inspector cache key"))
+            .ok()
+    }
+
+    fn try_key(
+        &self,
+        orchestra: &[&dyn Inspector<Result = Vec<Bundle>>],
+        data: &MultiBlockData,
+    ) -> Result<B256, ContentHashError> {
+        let mut hasher = Keccak256::new();
+        hasher.update(&self.build_id);
+
+        for inspector in orchestra {
+            hasher.update(inspector.cache_id());
+            hasher.update(inspector.get_quote_token());
+        }
+        hasher.update(address_filter().cache_id());
+
+        for BlockData { metadata, tree } in &data.per_block_data {
+            hasher.update(content_hash(&tree.header)?);
+            hasher.update(content_hash(&(tree.priority_fee_std_dev, tree.avg_priority_fee))?);
+            for root in &tree.tx_roots {
+                hasher.update(root_digest(root)?);
+            }
+            hasher.update(metadata_digest(metadata)?);
+        }
+
+        Ok(hasher.finalize())
+    }
+
+    pub fn get(&self, block_number: u64, key: B256) -> Option<Vec<Bundle>> {
+        let bytes = fs::read(self.path(block_number, key)).ok()?;
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+
+        let archived = rkyv::check_archived_root::<Vec<BundleRedefined>>(&aligned)
+            .inspect_err(|e| {
+                tracing::warn!(err=%e, block_number, "invalid inspector cache entry, ignoring it")
+            })
+            .ok()?;
+        let bundles: Vec<BundleRedefined> =
+            rkyv::Deserialize::deserialize(archived, &mut rkyv::Infallible).ok()?;
+
+        Some(
+            bundles
+                .into_iter()
+                .map(|bundle| bundle.to_source())
+                .collect(),
+        )
+    }
+
+    pub fn insert(&self, block_number: u64, key: B256, bundles: &[Bundle]) {
+        let bundles = bundles
+            .iter()
+            .cloned()
+            .map(BundleRedefined::from_source)
+            .collect::<Vec<_>>();
+
+        // written to a temporary file first, so that an entry cut short by a
+        // crash is never read
+        let path = self.path(block_number, key);
+        let tmp_path = path.with_extension("tmp");
+        let res = rkyv::to_bytes::<_, 256>(&bundles)
+            .map_err(|e| eyre::eyre!("{e:?}"))
+            .and_then(|bytes| {
+                fs::write(&tmp_path, bytes)?;
+                Ok(fs::rename(&tmp_path, path)?)
+            });
+
+        if let Err(e) = res {
+            tracing::warn!(err=%e, block_number, "failed to write inspector cache entry");
+        }
+    }
+
+    fn path(&self, block_number: u64, key: B256) -> PathBuf {
+        self.dir.join(format!("{block_number}-{key:x}"))
+    }
+}
+
+fn root_digest(root: &Root<Action>) -> Result<B256, ContentHashError> {
+    let mut nodes = vec![];
+    let mut stack = vec![&root.head];
+    while let Some(node) = stack.pop() {
+        nodes.push((
+            node.index,
+            &node.trace_address,
+            node.address,
+            node.data,
+            &node.subactions,
+            node.finalized,
+        ));
+        stack.extend(node.inner.iter().rev());
+    }
+
+    content_hash(&(
+        root.position,
+        root.tx_hash,
+        root.private,
+        root.submission_channel,
+        root.gas_details,
+        &root.total_msg_value_transfers,
+        &root.user_ops,
+        nodes,
+        &root.data_store.0,
+    ))
+}
+
+fn metadata_digest(metadata: &Metadata) -> Result<B256, ContentHashError> {
+    let block = &metadata.block_metadata;
+    let mut hasher = Keccak256::new();
+
+    hasher.update(content_hash(&(
+        block.block_num,
+        block.block_hash,
+        block.block_timestamp,
+        block.relay_timestamp,
+        block.p2p_timestamp,
+        block.proposer_fee_recipient,
+        block.proposer_mev_reward,
+        block.eth_prices.to_string(),
+        block.blob_gas_used,
+        block.excess_blob_gas,
+    ))?);
+    hasher.update(content_hash(&block.private_flow.iter().sorted_unstable().collect_vec())?);
+
+    // maps are encoded with their entries sorted, so these don't depend on the
+    // iteration order
+    hasher.update(content_hash(&metadata.cex_quotes.quotes)?);
+    hasher.update(content_hash(&metadata.cex_quotes.most_liquid_ex)?);
+    hasher.update(content_hash(&metadata.dex_quotes.as_ref().map(|quotes| &quotes.0))?);
+    hasher.update(content_hash(&metadata.builder_info)?);
+    hasher.update(content_hash(&metadata.cex_trades.as_ref().map(|trades| &trades.0))?);
+
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::{BundleData, BundleHeader};
+
+    use super::*;
+
+    #[test]
+    fn cached_bundles_round_trip() {
+        let dir = std::env::temp_dir().join("brontes-inspector-cache-test");
+        let cache = InspectorCache::new(&dir, "test").unwrap();
+        let bundles =
+            vec![Bundle { header: BundleHeader::default(), data: BundleData::default() }];
+
+        let key = B256::with_last_byte(1);
+        assert!(cache.get(1, key).is_none());
+        cache.insert(1, key, &bundles);
+        assert_eq!(cache.get(1, key), Some(bundles));
+        assert!(cache.get(2, key).is_none());
+
+        // a truncated entry is a miss
+        let path = cache.path(1, key);
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(cache.get(1, key).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
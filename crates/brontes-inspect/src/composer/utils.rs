@@ -3,15 +3,20 @@ use std::sync::Arc;
 use alloy_primitives::{Address, FixedBytes};
 use brontes_types::{
     db::{builder::BuilderInfo, metadata::Metadata, traits::LibmdbxReader},
-    mev::{Bundle, Mev, MevBlock, MevCount, MevType, PossibleMevCollection},
+    mev::{
+        Bundle, BundleHeader, Mev, MevBlock, MevCount, MevType, PossibleMevCollection,
+        SupersededBundle,
+    },
     normalized_actions::Action,
-    tree::BlockTree,
-    FastHashMap, GasDetails, ToFloatNearest, ToScaledRational, TreeSearchBuilder,
+    tree::{BlockTree, Root},
+    FastHashMap, FastHashSet, GasDetails, ToFloatNearest, ToScaledRational, TreeSearchBuilder,
 };
+use itertools::Itertools;
 use malachite::{num::conversion::traits::RoundingFrom, rounding_modes::RoundingMode};
 
 use crate::composer::FilterFn;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_mev_header<DB: LibmdbxReader>(
     metadata: &Arc<Metadata>,
     tree: Arc<BlockTree<Action>>,
@@ -19,6 +24,7 @@ pub(crate) fn build_mev_header<DB: LibmdbxReader>(
     mev_count: MevCount,
     orchestra_data: &[Bundle],
     quote_token: Address,
+    sanctioned_addresses: Vec<Address>,
     db: &'static DB,
 ) -> MevBlock {
     let (total_mev_priority_fee_paid, total_mev_profit_usd, total_mev_bribe) =
@@ -79,9 +85,116 @@ pub(crate) fn build_mev_header<DB: LibmdbxReader>(
         proposer_profit_usd,
         total_mev_profit_usd,
         possible_mev,
+        sanctioned_addresses,
     }
 }
 
+/// Copies the provenance of the classified actions of each bundle's
+/// transactions from the tree onto the bundle header
+#[cfg(feature = "provenance")]
+pub(crate) fn annotate_provenance(tree: &BlockTree<Action>, bundles: &mut [Bundle]) {
+    for bundle in bundles {
+        bundle.header.provenance = bundle
+            .data
+            .mev_transaction_hashes()
+            .into_iter()
+            .filter_map(|tx_hash| tree.get_root(tx_hash))
+            .flat_map(|root| root.classified_provenance())
+            .collect();
+    }
+}
+
+/// Sets the sanctioned addresses each bundle interacted with and returns the
+/// sanctioned addresses touched by any transaction in the block.
+pub(crate) fn annotate_sanctioned_addresses<DB: LibmdbxReader>(
+    tree: &BlockTree<Action>,
+    bundles: &mut [Bundle],
+    db: &'static DB,
+) -> Vec<Address> {
+    let tx_addresses = tree
+        .tx_roots
+        .iter()
+        .map(|root| (root.tx_hash, touched_addresses(root)))
+        .collect::<FastHashMap<_, _>>();
+    let block_addresses = tx_addresses
+        .values()
+        .flatten()
+        .copied()
+        .collect::<FastHashSet<_>>();
+
+    let addresses = bundles
+        .iter()
+        .flat_map(bundle_addresses)
+        .chain(block_addresses.iter().copied())
+        .unique()
+        .collect_vec();
+
+    let sanctioned = match db.fetch_sanctioned(addresses) {
+        Ok(sanctioned) => sanctioned,
+        Err(e) => {
+            tracing::error!(%e, "failed to fetch sanctioned addresses");
+            return vec![]
+        }
+    };
+
+    if sanctioned.is_empty() {
+        return vec![]
+    }
+
+    for bundle in bundles {
+        let bundle_txs = bundle
+            .data
+            .mev_transaction_hashes()
+            .into_iter()
+            .filter_map(|tx_hash| tx_addresses.get(&tx_hash))
+            .flatten()
+            .copied()
+            .collect_vec();
+
+        bundle.header.sanctioned_addresses = bundle_addresses(bundle)
+            .chain(bundle_txs)
+            .filter(|address| sanctioned.contains_key(address))
+            .unique()
+            .sorted()
+            .collect();
+    }
+
+    block_addresses
+        .into_iter()
+        .filter(|address| sanctioned.contains_key(address))
+        .sorted()
+        .collect()
+}
+
+/// Every address the transaction touched: the callers and callees of all of
+/// its traces and the parties of its classified actions
+fn touched_addresses(root: &Root<Action>) -> FastHashSet<Address> {
+    let actions = root.data_store.0.iter().flatten().flatten();
+
+    root.head
+        .all_sub_addresses()
+        .into_iter()
+        .chain(actions.flat_map(|action| {
+            let from = (!action.is_revert()).then(|| action.get_from_address());
+            from.into_iter()
+                .chain(std::iter::once(action.get_to_address()))
+        }))
+        .filter(|address| !address.is_zero())
+        .collect()
+}
+
+fn bundle_addresses(bundle: &Bundle) -> impl Iterator<Item = Address> + '_ {
+    let header = &bundle.header;
+    std::iter::once(header.eoa)
+        .chain(header.mev_contract)
+        .chain(
+            header
+                .balance_deltas
+                .iter()
+                .flat_map(|tx| tx.address_deltas.iter().map(|delta| delta.address)),
+        )
+}
+
 /// Sorts the given MEV data by type.
 ///
 /// This function takes a vector of tuples, where each tuple contains a
@@ -119,6 +232,15 @@ pub(crate) fn find_mev_with_matching_tx_hashes<'a>(
         })
 }
 
+/// The bundle that lost an overlap, followed by the bundles it had superseded
+/// itself, so that they carry over to the winner
+pub(crate) fn superseded_by(
+    loser: &BundleHeader,
+    merged: bool,
+) -> impl Iterator<Item = SupersededBundle> + '_ {
+    std::iter::once(SupersededBundle::new(loser, merged)).chain(loser.superseded.iter().cloned())
+}
+
 /// Finds the index of the first classified mev in the list whose transaction
 /// hashes match any of the provided hashes.
 pub(crate) fn try_deduping_mev<'a>(
@@ -191,6 +313,8 @@ fn update_mev_count(mev_count: &mut MevCount, mev_type: MevType, count: u64) {
         MevType::AtomicArb => mev_count.atomic_backrun_count = Some(count),
         MevType::Liquidation => mev_count.liquidation_count = Some(count),
         MevType::SearcherTx => mev_count.searcher_tx_count = Some(count),
+        MevType::Frontrun => mev_count.frontrun_count = Some(count),
+        MevType::ApprovalRace => mev_count.approval_race_count = Some(count),
         MevType::Unknown => (),
     }
 }
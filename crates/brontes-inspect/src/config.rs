@@ -0,0 +1,519 @@
+//! Operator tunables for the inspectors, loaded from the run config so that
+//! detection can be tightened or loosened without recompiling.
+//!
+//! ```toml
+//! sandwich_mode = "big_mac"
+//! disabled = ["cex_dex_markout"]
+//!
+//! [sandwich]
+//! min_profit_usd = 1.0
+//! max_bribe_ratio = 0.99
+//! min_victims = 2
+//! pool_blacklist = ["0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"]
+//!
+//! [frontrun]
+//! min_victim_volume_usd = 5000.0
+//!
+//! [denylist.tokens]
+//! "0x0000000000000000000000000000000000000001" = "honeypot"
+//!
+//! [address_filter]
+//! deny = ["0x0000000000000000000000000000000000000002"]
+//!
+//! [cex_fees.binance]
+//! maker_bps = 1.0
+//! taker_bps = 2.5
+//!
+//! # every inspector of the run has to end up with the same quote asset
+//! [quote_assets]
+//! jit = "WETH"
+//! sandwich = "WETH"
+//! ```
+use std::sync::OnceLock;
+
+use alloy_primitives::{Address, B256};
+use brontes_types::{
+    address_filter::AddressFilter,
+    chain::chain_spec,
+    content_hash::{content_hash, serialize_sorted},
+    db::cex::fees::CexFeeSchedule,
+    denylist::Denylist,
+    mev::{Bundle, BundleData},
+    normalized_actions::NormalizedSwap,
+    FastHashMap, FastHashSet, MultiBlockData,
+};
+use itertools::Itertools;
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{sandwich::SandwichMode, DetectionLimits, Inspector, Inspectors};
+
+static CONFIG_HASH: OnceLock<B256> = OnceLock::new();
+
+/// Sets the config the inspectors of the run were built with, so that it is
+/// recorded with the results of every block
+pub fn init_inspector_config_hash(config: &InspectorConfig) -> eyre::Result<()> {
+    CONFIG_HASH
+        .set(config.content_hash())
+        .map_err(|_| eyre::eyre!("inspector config hash already initialized"))
+}
+
+/// Hash of the inspector config of the run, the default config's if none was
+/// set
+pub fn inspector_config_hash() -> B256 {
+    *CONFIG_HASH.get_or_init(|| InspectorConfig::default().content_hash())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InspectorConfig {
+    pub atomic_arb:        InspectorThresholds,
+    pub aggregator_arb:    InspectorThresholds,
+    pub approval_race:     InspectorThresholds,
+    pub cex_dex:           InspectorThresholds,
+    pub cex_dex_markout:   InspectorThresholds,
+    pub frontrun:          InspectorThresholds,
+    pub jit:               InspectorThresholds,
+    pub jit_cex_dex:       InspectorThresholds,
+    pub liquidations:      InspectorThresholds,
+    pub nft_arb:           InspectorThresholds,
+    pub sandwich:          InspectorThresholds,
+    pub searcher_activity: InspectorThresholds,
+    /// Whether sequential frontruns and backruns are kept in one sandwich
+    pub sandwich_mode:     SandwichMode,
+    /// Inspectors, by the name of their section, that are skipped. Reloaded
+    /// while following the tip, so it isn't part of the config hash. Disabled
+    /// inspectors are told apart by their cache id instead
+    #[serde(skip_serializing)]
+    pub disabled:          Vec<String>,
+    /// Tokens and pools excluded from pricing and from every inspector
+    pub denylist:          Denylist,
+    /// Searcher EOAs and contracts every inspector skips or is restricted to
+    pub address_filter:    AddressFilter,
+    /// Maker & taker fees per exchange the cex-dex inspectors price the cex
+    /// leg with
+    pub cex_fees:          CexFeeSchedule,
+    /// Quote asset of an inspector, by the name of its section, if it should
+    /// differ from the quote asset of the run. Either a symbol or an address.
+    /// The inspectors of a run have to quote in the same asset.
+    pub quote_assets:      FastHashMap<String, String>,
+}
+
+impl InspectorConfig {
+    /// Hash of every setting that changes the results of the inspectors
+    pub fn content_hash(&self) -> B256 {
+        content_hash(self).expect("the inspector config only holds plain values")
+    }
+
+    pub fn thresholds(&self, inspector: Inspectors) -> &InspectorThresholds {
+        match inspector {
+            Inspectors::AtomicArb => &self.atomic_arb,
+            Inspectors::AggregatorArb => &self.aggregator_arb,
+            Inspectors::ApprovalRace => &self.approval_race,
+            Inspectors::CexDex => &self.cex_dex,
+            Inspectors::CexDexMarkout => &self.cex_dex_markout,
+            Inspectors::Frontrun => &self.frontrun,
+            Inspectors::Jit => &self.jit,
+            Inspectors::JitCexDex => &self.jit_cex_dex,
+            Inspectors::Liquidations => &self.liquidations,
+            Inspectors::NftArb => &self.nft_arb,
+            Inspectors::Sandwich => &self.sandwich,
+            Inspectors::SearcherActivity => &self.searcher_activity,
+        }
+    }
+
+    /// The quote asset the inspectors of the run denominate their bundles
+    /// in, `default` for those without one configured. The block totals sum
+    /// the profits of the bundles of every inspector, so configs that give
+    /// the inspectors different quote assets are rejected.
+    pub fn quote_asset(
+        &self,
+        default: Address,
+        inspectors: &[Inspectors],
+    ) -> eyre::Result<Address> {
+        if let Some(unknown) = self
+            .quote_assets
+            .keys()
+            .find(|name| !Inspectors::iter().any(|inspector| section(inspector) == *name))
+        {
+            return Err(eyre::eyre!("quote asset configured for unknown inspector {unknown}"))
+        }
+
+        let quotes = inspectors
+            .iter()
+            .map(|inspector| {
+                let quote = match self.quote_assets.get(section(*inspector)) {
+                    Some(quote) => chain_spec().parse_quote_asset(quote)?,
+                    None => default,
+                };
+                Ok((*inspector, quote))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let mut quote_assets = quotes.iter().map(|(_, quote)| *quote).unique();
+        let quote = quote_assets.next().unwrap_or(default);
+        if quote_assets.next().is_some() {
+            let quotes = quotes
+                .iter()
+                .map(|(inspector, quote)| format!("{}: {quote:?}", section(*inspector)))
+                .join(", ");
+            return Err(eyre::eyre!(
+                "the inspectors quote in different assets ({quotes}), the block totals can only \
+                 sum bundle profits of a single quote asset"
+            ))
+        }
+
+        Ok(quote)
+    }
+
+    /// The inspectors that are skipped
+    pub fn disabled_inspectors(&self) -> eyre::Result<FastHashSet<Inspectors>> {
+        self.disabled
+            .iter()
+            .map(|name| {
+                Inspectors::iter()
+                    .find(|inspector| section(*inspector) == name)
+                    .ok_or_else(|| eyre::eyre!("unknown inspector {name} disabled"))
+            })
+            .collect()
+    }
+}
+
+/// Name of the config section of the inspector
+fn section(inspector: Inspectors) -> &'static str {
+    match inspector {
+        Inspectors::AtomicArb => "atomic_arb",
+        Inspectors::AggregatorArb => "aggregator_arb",
+        Inspectors::ApprovalRace => "approval_race",
+        Inspectors::CexDex => "cex_dex",
+        Inspectors::CexDexMarkout => "cex_dex_markout",
+        Inspectors::Frontrun => "frontrun",
+        Inspectors::Jit => "jit",
+        Inspectors::JitCexDex => "jit_cex_dex",
+        Inspectors::Liquidations => "liquidations",
+        Inspectors::NftArb => "nft_arb",
+        Inspectors::Sandwich => "sandwich",
+        Inspectors::SearcherActivity => "searcher_activity",
+    }
+}
+
+/// Filters applied to the bundles of an inspector. Every filter is disabled
+/// by default, which keeps the inspector's own heuristics as the only ones.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InspectorThresholds {
+    /// Bundles with a lower usd profit are dropped. Bundles that couldn't be
+    /// priced are kept
+    pub min_profit_usd:               Option<f64>,
+    /// Bundles that paid a larger share of their revenue to the builder are
+    /// dropped
+    pub max_bribe_ratio:              Option<f64>,
+    /// Sandwiches, jits and frontruns with fewer victim transactions are
+    /// dropped
+    pub min_victims:                  usize,
+    /// Bundles that touch any of these pools are dropped
+    #[serde(serialize_with = "serialize_sorted")]
+    pub pool_blacklist:               FastHashSet<Address>,
+    /// Overrides the profit above which the inspector takes a bundle to be
+    /// mispriced, see [`DetectionLimits`]
+    pub max_profit_usd:               Option<f64>,
+    /// Overrides the usd volume the victims of a frontrun have to trade
+    pub min_victim_volume_usd:        Option<f64>,
+    /// Overrides the number of cex-dex bundles a searcher needs for its
+    /// unprofitable trades to still be cex-dex
+    pub min_cex_dex_searcher_bundles: Option<u64>,
+}
+
+impl InspectorThresholds {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Hash of the filters and of the overridden cutoffs
+    pub fn content_hash(&self) -> B256 {
+        content_hash(self).expect("the thresholds only hold plain values")
+    }
+
+    /// The inspector's own cutoffs with the configured overrides
+    pub fn limits(&self) -> DetectionLimits {
+        let defaults = DetectionLimits::default();
+        let usd = |value: Option<f64>, default| {
+            value
+                .and_then(|value| Rational::try_from_float_simplest(value).ok())
+                .unwrap_or(default)
+        };
+
+        DetectionLimits {
+            max_profit_usd:               usd(self.max_profit_usd, defaults.max_profit_usd),
+            min_victim_volume_usd:        usd(
+                self.min_victim_volume_usd,
+                defaults.min_victim_volume_usd,
+            ),
+            min_cex_dex_searcher_bundles: self
+                .min_cex_dex_searcher_bundles
+                .unwrap_or(defaults.min_cex_dex_searcher_bundles),
+        }
+    }
+
+    pub fn retain(&self, bundle: &Bundle) -> bool {
+        let header = &bundle.header;
+
+        if !header.no_pricing_calculated
+            && self
+                .min_profit_usd
+                .is_some_and(|min| header.profit_usd < min)
+        {
+            return false
+        }
+
+        let revenue = header.profit_usd + header.bribe_usd;
+        if revenue > 0.0
+            && self
+                .max_bribe_ratio
+                .is_some_and(|max| header.bribe_usd / revenue > max)
+        {
+            return false
+        }
+
+        if victim_count(&bundle.data).is_some_and(|victims| victims < self.min_victims) {
+            return false
+        }
+
+        self.pool_blacklist.is_empty()
+            || !bundle_pools(&bundle.data).any(|pool| self.pool_blacklist.contains(&pool))
+    }
+}
+
+fn victim_count(data: &BundleData) -> Option<usize> {
+    match data {
+        BundleData::Sandwich(s) => Some(s.victim_swaps_tx_hashes.iter().flatten().count()),
+        BundleData::JitSandwich(j) => Some(j.victim_swaps_tx_hashes.iter().flatten().count()),
+        BundleData::Jit(j) => Some(j.victim_swaps_tx_hashes.len()),
+        BundleData::Frontrun(f) => Some(f.victim_swaps_tx_hashes.len()),
+        _ => None,
+    }
+}
+
+pub(crate) fn bundle_pools(data: &BundleData) -> Box<dyn Iterator<Item = Address> + '_> {
+    match data {
+        BundleData::Sandwich(s) => Box::new(
+            s.frontrun_swaps
+                .iter()
+                .chain(&s.victim_swaps)
+                .chain(&s.backrun_swaps)
+                .flat_map(|swaps| swap_pools(swaps)),
+        ),
+        BundleData::JitSandwich(j) => Box::new(
+            j.frontrun_swaps
+                .iter()
+                .chain(&j.victim_swaps)
+                .flat_map(|swaps| swap_pools(swaps))
+                .chain(swap_pools(&j.backrun_swaps))
+                .chain(
+                    j.frontrun_mints
+                        .iter()
+                        .flatten()
+                        .flatten()
+                        .map(|mint| mint.pool),
+                )
+                .chain(j.backrun_burns.iter().map(|burn| burn.pool)),
+        ),
+        BundleData::Jit(j) => Box::new(
+            j.victim_swaps
+                .iter()
+                .flat_map(|swaps| swap_pools(swaps))
+                .chain(j.frontrun_mints.iter().map(|mint| mint.pool))
+                .chain(j.backrun_burns.iter().map(|burn| burn.pool)),
+        ),
+        BundleData::AtomicArb(a) => Box::new(swap_pools(&a.swaps)),
+        BundleData::CexDex(c) => Box::new(swap_pools(&c.swaps)),
+        BundleData::CexDexQuote(c) => Box::new(swap_pools(&c.swaps)),
+        BundleData::Liquidation(l) => Box::new(swap_pools(&l.liquidation_swaps)),
+        BundleData::Frontrun(f) => Box::new(
+            swap_pools(&f.frontrun_swaps)
+                .chain(f.victim_swaps.iter().flat_map(|swaps| swap_pools(swaps))),
+        ),
+        BundleData::ApprovalRace(_) | BundleData::Unknown(_) => Box::new(std::iter::empty()),
+    }
+}
+
+fn swap_pools(swaps: &[NormalizedSwap]) -> impl Iterator<Item = Address> + '_ {
+    swaps.iter().map(|swap| swap.pool)
+}
+
+/// Applies the configured thresholds to the bundles of the wrapped inspector
+pub struct FilteredInspector {
+    pub inner:      &'static dyn Inspector<Result = Vec<Bundle>>,
+    pub thresholds: InspectorThresholds,
+}
+
+impl Inspector for FilteredInspector {
+    type Result = Vec<Bundle>;
+
+    fn block_window(&self) -> usize {
+        self.inner.block_window()
+    }
+
+    fn get_id(&self) -> &str {
+        self.inner.get_id()
+    }
+
+    fn cache_id(&self) -> String {
+        format!("{}:{}", self.inner.cache_id(), self.thresholds.content_hash())
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.inner.get_quote_token()
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        self.inner
+            .inspect_block(data)
+            .into_iter()
+            .filter(|bundle| self.thresholds.retain(bundle))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::{BundleHeader, Sandwich};
+
+    use super::*;
+
+    fn sandwich(profit_usd: f64, bribe_usd: f64, victims: usize, pool: Address) -> Bundle {
+        Bundle {
+            header: BundleHeader { profit_usd, bribe_usd, ..Default::default() },
+            data:   BundleData::Sandwich(Sandwich {
+                victim_swaps_tx_hashes: vec![vec![Default::default(); victims]],
+                victim_swaps: vec![vec![NormalizedSwap { pool, ..Default::default() }]; victims],
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn thresholds_filter_bundles() {
+        let pool = Address::with_last_byte(1);
+        let thresholds = InspectorThresholds {
+            min_profit_usd: Some(1.0),
+            max_bribe_ratio: Some(0.9),
+            min_victims: 2,
+            pool_blacklist: FastHashSet::from_iter([Address::with_last_byte(2)]),
+            ..Default::default()
+        };
+
+        assert!(thresholds.retain(&sandwich(10.0, 10.0, 2, pool)));
+        assert!(!thresholds.retain(&sandwich(0.5, 10.0, 2, pool)));
+        assert!(!thresholds.retain(&sandwich(1.0, 10.0, 2, pool)));
+        assert!(!thresholds.retain(&sandwich(10.0, 10.0, 1, pool)));
+        assert!(!thresholds.retain(&sandwich(10.0, 10.0, 2, Address::with_last_byte(2))));
+        assert!(InspectorThresholds::default().retain(&sandwich(0.0, 10.0, 0, pool)));
+    }
+
+    #[test]
+    fn limits_override_the_inspector_defaults() {
+        assert_eq!(InspectorThresholds::default().limits(), DetectionLimits::default());
+
+        let frontrun =
+            InspectorThresholds { min_victim_volume_usd: Some(2500.5), ..Default::default() }
+                .limits();
+        assert_eq!(frontrun.min_victim_volume_usd, Rational::from_unsigneds(5001u32, 2u32));
+        assert_eq!(frontrun.max_profit_usd, DetectionLimits::default().max_profit_usd);
+
+        let cex_dex =
+            InspectorThresholds { min_cex_dex_searcher_bundles: Some(5), ..Default::default() }
+                .limits();
+        assert_eq!(cex_dex.min_cex_dex_searcher_bundles, 5);
+    }
+
+    #[test]
+    fn resolves_quote_assets() {
+        use brontes_types::constants::{USDT_ADDRESS, WETH_ADDRESS};
+
+        let mut config = InspectorConfig::default();
+        config
+            .quote_assets
+            .insert("jit".to_string(), "weth".to_string());
+
+        assert_eq!(
+            config
+                .quote_asset(USDT_ADDRESS, &[Inspectors::Jit])
+                .unwrap(),
+            WETH_ADDRESS
+        );
+        assert_eq!(
+            config
+                .quote_asset(USDT_ADDRESS, &[Inspectors::Sandwich])
+                .unwrap(),
+            USDT_ADDRESS
+        );
+
+        config
+            .quote_assets
+            .insert("sandwich".to_string(), "WETH".to_string());
+        assert_eq!(
+            config
+                .quote_asset(USDT_ADDRESS, &[Inspectors::Jit, Inspectors::Sandwich])
+                .unwrap(),
+            WETH_ADDRESS
+        );
+
+        config
+            .quote_assets
+            .insert("sandwiches".to_string(), "DAI".to_string());
+        assert!(config
+            .quote_asset(USDT_ADDRESS, &[Inspectors::Jit])
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_mixed_quote_assets() {
+        use brontes_types::constants::USDT_ADDRESS;
+
+        let mut config = InspectorConfig::default();
+        config
+            .quote_assets
+            .insert("jit".to_string(), "weth".to_string());
+
+        // the sandwich inspector quotes in the quote asset of the run
+        assert!(config
+            .quote_asset(USDT_ADDRESS, &[Inspectors::Jit, Inspectors::Sandwich])
+            .is_err());
+    }
+
+    #[test]
+    fn resolves_disabled_inspectors() {
+        let mut config = InspectorConfig {
+            disabled: vec!["cex_dex_markout".to_string(), "jit".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.disabled_inspectors().unwrap(),
+            FastHashSet::from_iter([Inspectors::CexDexMarkout, Inspectors::Jit])
+        );
+
+        config.disabled.push("jit_sandwich".to_string());
+        assert!(config.disabled_inspectors().is_err());
+    }
+
+    #[test]
+    fn config_hash_covers_the_detection_limits() {
+        let defaults = InspectorThresholds::default();
+        let overridden = InspectorThresholds { max_profit_usd: Some(1e6), ..Default::default() };
+        assert_ne!(defaults.content_hash(), overridden.content_hash());
+
+        let blacklist = |pools: [u8; 3]| InspectorThresholds {
+            pool_blacklist: pools.into_iter().map(Address::with_last_byte).collect(),
+            ..Default::default()
+        };
+        assert_eq!(blacklist([1, 2, 3]).content_hash(), blacklist([3, 1, 2]).content_hash());
+
+        let config = InspectorConfig { frontrun: overridden, ..Default::default() };
+        assert_ne!(config.content_hash(), InspectorConfig::default().content_hash());
+
+        let disabled = InspectorConfig { disabled: vec!["jit".to_string()], ..config.clone() };
+        assert_eq!(disabled.content_hash(), config.content_hash());
+    }
+}
@@ -29,10 +29,14 @@
 //! defined in their respective modules:
 //!
 //! - [`atomic_backrun`](atomic_backrun/index.html)
+//! - [`aggregator_arb`](aggregator_arb/index.html)
 //! - [`cex_dex`](cex_dex/index.html)
 //! - [`jit`](jit/index.html)
 //! - [`sandwich`](sandwich/index.html)
 //! - [`liquidations`](liquidations/index.html)
+//! - [`frontrun`](frontrun/index.html)
+//! - [`approval_race`](approval_race/index.html)
+//! - [`nft_arb`](nft_arb/index.html)
 //! - [`long_tail`](long_tail/index.html)
 //!
 //! Each inspector implements the `Inspector` trait and provides its own
@@ -58,8 +62,12 @@
 //! composition.
 
 pub mod composer;
+pub mod config;
 pub mod discovery;
+pub mod liquidation_context;
 pub mod mev_inspectors;
+pub mod profit_verification;
+pub mod registry;
 use brontes_metrics::inspectors::OutlierMetrics;
 use mev_inspectors::searcher_activity::SearcherActivity;
 pub use mev_inspectors::*;
@@ -67,7 +75,9 @@ pub use mev_inspectors::*;
 #[cfg(feature = "tests")]
 pub mod test_utils;
 
+use aggregator_arb::AggregatorArbInspector;
 use alloy_primitives::Address;
+use approval_race::ApprovalRaceInspector;
 use atomic_arb::AtomicArbInspector;
 use brontes_types::{
     db::{
@@ -81,9 +91,11 @@ use brontes_types::{
     MultiBlockData,
 };
 use cex_dex::{markout::CexDexMarkoutInspector, quotes::CexDexQuotesInspector};
+use frontrun::FrontrunInspector;
 use jit::JitCexDex;
 use liquidations::LiquidationInspector;
-use sandwich::SandwichInspector;
+use nft_arb::NftArbInspector;
+use sandwich::{SandwichInspector, SandwichMode};
 
 use crate::jit::jit_liquidity::JitInspector;
 
@@ -96,6 +108,15 @@ pub trait Inspector: Send + Sync {
     }
     /// Used for log span so we know which errors come from which inspector
     fn get_id(&self) -> &str;
+    /// Identifies the inspector and every setting that changes its results,
+    /// used to key the cached results. Defaults to the id
+    fn cache_id(&self) -> String {
+        self.get_id().to_string()
+    }
+    /// Disabled inspectors are skipped, without a run being recorded for them
+    fn is_enabled(&self) -> bool {
+        true
+    }
     fn inspect_block(&self, data: MultiBlockData) -> Self::Result;
     fn get_quote_token(&self) -> Address;
 }
@@ -105,16 +126,20 @@ pub trait Inspector: Send + Sync {
 )]
 pub enum Inspectors {
     AtomicArb,
+    AggregatorArb,
     CexDex,
     Jit,
     Liquidations,
+    NftArb,
     Sandwich,
     SearcherActivity,
     CexDexMarkout,
     JitCexDex,
+    Frontrun,
+    ApprovalRace,
 }
 
-type DynMevInspector = &'static (dyn Inspector<Result = Vec<Bundle>> + 'static);
+pub type DynMevInspector = &'static (dyn Inspector<Result = Vec<Bundle>> + 'static);
 
 impl Inspectors {
     pub fn init_mev_inspector<DB: LibmdbxReader>(
@@ -123,40 +148,53 @@ impl Inspectors {
         db: &'static DB,
         cex_exchanges: &[CexExchange],
         trade_config: CexDexTradeConfig,
+        sandwich_mode: SandwichMode,
+        limits: DetectionLimits,
         metrics: Option<OutlierMetrics>,
     ) -> DynMevInspector {
         match &self {
             Self::AtomicArb => {
-                static_object(AtomicArbInspector::new(quote_token, db, metrics)) as DynMevInspector
+                static_object(AtomicArbInspector::new(quote_token, db, metrics).with_limits(limits))
+                    as DynMevInspector
             }
+            Self::AggregatorArb => static_object(
+                AggregatorArbInspector::new(quote_token, db, metrics).with_limits(limits),
+            ) as DynMevInspector,
             Self::Jit => {
-                static_object(JitInspector::new(quote_token, db, metrics)) as DynMevInspector
+                static_object(JitInspector::new(quote_token, db, metrics).with_limits(limits))
+                    as DynMevInspector
             }
 
-            Self::CexDex => static_object(CexDexQuotesInspector::new(
-                quote_token,
-                db,
-                cex_exchanges,
-                trade_config.quote_offset_from_block_us,
-                metrics,
-            )) as DynMevInspector,
-            Self::Sandwich => {
-                static_object(SandwichInspector::new(quote_token, db, metrics)) as DynMevInspector
-            }
-            Self::Liquidations => {
-                static_object(LiquidationInspector::new(quote_token, db, metrics))
+            Self::CexDex => static_object(
+                CexDexQuotesInspector::new(
+                    quote_token,
+                    db,
+                    cex_exchanges,
+                    trade_config.quote_offset_from_block_us,
+                    metrics,
+                )
+                .with_limits(limits),
+            ) as DynMevInspector,
+            Self::Sandwich => static_object(
+                SandwichInspector::new(quote_token, db, metrics)
+                    .with_mode(sandwich_mode)
+                    .with_limits(limits),
+            ) as DynMevInspector,
+            Self::Liquidations => static_object(
+                LiquidationInspector::new(quote_token, db, metrics).with_limits(limits),
+            ) as DynMevInspector,
+            Self::NftArb => {
+                static_object(NftArbInspector::new(quote_token, db, metrics).with_limits(limits))
                     as DynMevInspector
             }
             Self::SearcherActivity => {
-                static_object(SearcherActivity::new(quote_token, db, metrics)) as DynMevInspector
+                static_object(SearcherActivity::new(quote_token, db, metrics).with_limits(limits))
+                    as DynMevInspector
             }
-            Self::CexDexMarkout => static_object(CexDexMarkoutInspector::new(
-                quote_token,
-                db,
-                cex_exchanges,
-                trade_config,
-                metrics,
-            )) as DynMevInspector,
+            Self::CexDexMarkout => static_object(
+                CexDexMarkoutInspector::new(quote_token, db, cex_exchanges, trade_config, metrics)
+                    .with_limits(limits),
+            ) as DynMevInspector,
             Self::JitCexDex => static_object(JitCexDex {
                 cex_dex: CexDexMarkoutInspector::new(
                     quote_token,
@@ -164,9 +202,17 @@ impl Inspectors {
                     cex_exchanges,
                     trade_config,
                     metrics.clone(),
-                ),
-                jit:     JitInspector::new(quote_token, db, metrics),
+                )
+                .with_limits(limits.clone()),
+                jit:     JitInspector::new(quote_token, db, metrics).with_limits(limits),
             }) as DynMevInspector,
+            Self::Frontrun => {
+                static_object(FrontrunInspector::new(quote_token, db, metrics).with_limits(limits))
+                    as DynMevInspector
+            }
+            Self::ApprovalRace => static_object(
+                ApprovalRaceInspector::new(quote_token, db, metrics).with_limits(limits),
+            ) as DynMevInspector,
         }
     }
 }
@@ -0,0 +1,472 @@
+//! Attaches the state of the liquidated positions to the liquidation bundles.
+//! The lending protocols are queried through the [`TracingProvider`] at the
+//! block before the liquidation, which is the state the liquidator saw when
+//! deciding to liquidate the position.
+
+use std::sync::Arc;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_macro::sol;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    make_call_request,
+    mev::{Bundle, BundleData, LiquidationContext},
+    normalized_actions::NormalizedLiquidation,
+    traits::TracingProvider,
+    Protocol, ToFloatNearest, ToScaledRational,
+};
+use futures::future::join_all;
+use itertools::Itertools;
+
+sol!(
+    interface IAavePool {
+        function getUserAccountData(address user) external view returns (
+            uint256 totalCollateralBase,
+            uint256 totalDebtBase,
+            uint256 availableBorrowsBase,
+            uint256 currentLiquidationThreshold,
+            uint256 ltv,
+            uint256 healthFactor
+        );
+        function getUserConfiguration(address user) external view returns (uint256);
+        function getReservesList() external view returns (address[] memory);
+    }
+
+    interface IAaveV2Pool {
+        struct ReserveData {
+            uint256 configuration;
+            uint128 liquidityIndex;
+            uint128 variableBorrowIndex;
+            uint128 currentLiquidityRate;
+            uint128 currentVariableBorrowRate;
+            uint128 currentStableBorrowRate;
+            uint40 lastUpdateTimestamp;
+            address aTokenAddress;
+            address stableDebtTokenAddress;
+            address variableDebtTokenAddress;
+            address interestRateStrategyAddress;
+            uint8 id;
+        }
+        function getReserveData(address asset) external view returns (ReserveData memory);
+    }
+
+    interface IAaveV3Pool {
+        struct ReserveData {
+            uint256 configuration;
+            uint128 liquidityIndex;
+            uint128 currentLiquidityRate;
+            uint128 variableBorrowIndex;
+            uint128 currentVariableBorrowRate;
+            uint128 currentStableBorrowRate;
+            uint40 lastUpdateTimestamp;
+            uint16 id;
+            address aTokenAddress;
+            address stableDebtTokenAddress;
+            address variableDebtTokenAddress;
+            address interestRateStrategyAddress;
+            uint128 accruedToTreasury;
+            uint128 unbacked;
+            uint128 isolationModeTotalDebt;
+        }
+        function getReserveData(address asset) external view returns (ReserveData memory);
+    }
+
+    interface IERC20Balance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+
+    interface ICompoundV2CToken {
+        function comptroller() external view returns (address);
+        function getAccountSnapshot(address account) external view returns (
+            uint256 err,
+            uint256 cTokenBalance,
+            uint256 borrowBalance,
+            uint256 exchangeRateMantissa
+        );
+    }
+
+    interface ICompoundV2Comptroller {
+        function getAccountLiquidity(address account) external view returns (
+            uint256 err,
+            uint256 liquidity,
+            uint256 shortfall
+        );
+        function getAssetsIn(address account) external view returns (address[] memory);
+    }
+
+    interface ICompoundV3Comet {
+        struct AssetInfo {
+            uint8 offset;
+            address asset;
+            address priceFeed;
+            uint64 scale;
+            uint64 borrowCollateralFactor;
+            uint64 liquidateCollateralFactor;
+            uint64 liquidationFactor;
+            uint128 supplyCap;
+        }
+        function isLiquidatable(address account) external view returns (bool);
+        function numAssets() external view returns (uint8);
+        function getAssetInfo(uint8 i) external view returns (AssetInfo memory);
+        function collateralBalanceOf(address account, address asset)
+            external view returns (uint128);
+        function getReserves() external view returns (int256);
+        function targetReserves() external view returns (uint256);
+        function getCollateralReserves(address asset) external view returns (uint256);
+    }
+);
+
+/// Aave health factors have 18 decimals
+const HEALTH_FACTOR_DECIMALS: u8 = 18;
+/// cTokens all have 8 decimals
+const CTOKEN_DECIMALS: u8 = 8;
+
+/// Reads the state of every position liquidated in the liquidation bundles
+/// and attaches it to the bundle. Positions of unsupported protocols, or whose
+/// state can't be read, get no context.
+pub async fn attach_liquidation_contexts<T: TracingProvider, DB: LibmdbxReader>(
+    bundles: &mut [Bundle],
+    tracer: &Arc<T>,
+    db: &DB,
+) {
+    for bundle in bundles {
+        let BundleData::Liquidation(liquidation) = &mut bundle.data else { continue };
+        let block = liquidation.block_number.saturating_sub(1);
+
+        // a position liquidated several times in the tx is only read once
+        let positions = liquidation
+            .liquidations
+            .iter()
+            .unique_by(|liquidation| (liquidation.protocol, liquidation.pool, liquidation.debtor));
+
+        liquidation.liquidation_contexts = join_all(
+            positions.map(|liquidation| liquidation_context(liquidation, block, tracer, db)),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+    }
+}
+
+async fn liquidation_context<T: TracingProvider, DB: LibmdbxReader>(
+    liquidation: &NormalizedLiquidation,
+    block: u64,
+    tracer: &Arc<T>,
+    db: &DB,
+) -> Option<LiquidationContext> {
+    let context = match liquidation.protocol {
+        Protocol::AaveV2 | Protocol::AaveV3 => aave_context(liquidation, block, tracer, db).await,
+        Protocol::CompoundV2 => compound_v2_context(liquidation, block, tracer, db).await,
+        Protocol::CompoundV3 => compound_v3_context(liquidation, block, tracer, db).await,
+        _ => return None,
+    };
+
+    context
+        .inspect_err(|e| {
+            tracing::debug!(
+                protocol = %liquidation.protocol,
+                debtor = ?liquidation.debtor,
+                %block,
+                err = %e,
+                "failed to read the liquidation context"
+            )
+        })
+        .ok()
+}
+
+/// The pool exposes the health factor of the account. The collateral is the
+/// aToken balance of every reserve the account uses as collateral.
+async fn aave_context<T: TracingProvider, DB: LibmdbxReader>(
+    liquidation: &NormalizedLiquidation,
+    block: u64,
+    tracer: &Arc<T>,
+    db: &DB,
+) -> eyre::Result<LiquidationContext> {
+    let (pool, debtor) = (liquidation.pool, liquidation.debtor);
+
+    let account = make_call_request(
+        IAavePool::getUserAccountDataCall::new((debtor,)),
+        tracer,
+        pool,
+        Some(block),
+    )
+    .await?;
+    let configuration = make_call_request(
+        IAavePool::getUserConfigurationCall::new((debtor,)),
+        tracer,
+        pool,
+        Some(block),
+    )
+    .await?
+    ._0;
+    let reserves =
+        make_call_request(IAavePool::getReservesListCall::new(()), tracer, pool, Some(block))
+            .await?
+            ._0;
+
+    let mut collateral_assets = Vec::new();
+    let mut collateral_amounts = Vec::new();
+    for asset in collateral_reserves(configuration, reserves) {
+        let a_token = if liquidation.protocol == Protocol::AaveV2 {
+            make_call_request(
+                IAaveV2Pool::getReserveDataCall::new((asset,)),
+                tracer,
+                pool,
+                Some(block),
+            )
+            .await?
+            ._0
+            .aTokenAddress
+        } else {
+            make_call_request(
+                IAaveV3Pool::getReserveDataCall::new((asset,)),
+                tracer,
+                pool,
+                Some(block),
+            )
+            .await?
+            ._0
+            .aTokenAddress
+        };
+        let balance = make_call_request(
+            IERC20Balance::balanceOfCall::new((debtor,)),
+            tracer,
+            a_token,
+            Some(block),
+        )
+        .await?
+        ._0;
+
+        collateral_amounts.push(scaled(balance, db.try_fetch_token_info(asset)?.decimals));
+        collateral_assets.push(asset);
+    }
+
+    // the health factor is U256::MAX for accounts without debt
+    let health_factor = account.healthFactor;
+    Ok(LiquidationContext {
+        protocol: liquidation.protocol,
+        debtor,
+        block_number: block,
+        health_factor: Some(scaled(health_factor, HEALTH_FACTOR_DECIMALS)),
+        liquidatable: health_factor < U256::from(10).pow(U256::from(HEALTH_FACTOR_DECIMALS)),
+        collateral_assets,
+        collateral_amounts,
+    })
+}
+
+/// The comptroller only exposes the shortfall of the account, so there is no
+/// health factor. The collateral is the cToken balance of every market the
+/// account entered.
+async fn compound_v2_context<T: TracingProvider, DB: LibmdbxReader>(
+    liquidation: &NormalizedLiquidation,
+    block: u64,
+    tracer: &Arc<T>,
+    db: &DB,
+) -> eyre::Result<LiquidationContext> {
+    let debtor = liquidation.debtor;
+    let comptroller = make_call_request(
+        ICompoundV2CToken::comptrollerCall::new(()),
+        tracer,
+        liquidation.pool,
+        Some(block),
+    )
+    .await?
+    ._0;
+
+    let account_liquidity = make_call_request(
+        ICompoundV2Comptroller::getAccountLiquidityCall::new((debtor,)),
+        tracer,
+        comptroller,
+        Some(block),
+    )
+    .await?;
+    let markets = make_call_request(
+        ICompoundV2Comptroller::getAssetsInCall::new((debtor,)),
+        tracer,
+        comptroller,
+        Some(block),
+    )
+    .await?
+    ._0;
+
+    let mut collateral_assets = Vec::new();
+    let mut collateral_amounts = Vec::new();
+    for c_token in markets {
+        let snapshot = make_call_request(
+            ICompoundV2CToken::getAccountSnapshotCall::new((debtor,)),
+            tracer,
+            c_token,
+            Some(block),
+        )
+        .await?;
+        if snapshot.cTokenBalance.is_zero() {
+            continue
+        }
+
+        let decimals = db
+            .try_fetch_token_info(c_token)
+            .map(|info| info.decimals)
+            .unwrap_or(CTOKEN_DECIMALS);
+        collateral_amounts.push(scaled(snapshot.cTokenBalance, decimals));
+        collateral_assets.push(c_token);
+    }
+
+    Ok(LiquidationContext {
+        protocol: liquidation.protocol,
+        debtor,
+        block_number: block,
+        health_factor: None,
+        liquidatable: !account_liquidity.shortfall.is_zero(),
+        collateral_assets,
+        collateral_amounts,
+    })
+}
+
+/// The reserves the account uses as collateral. The configuration has a
+/// borrowing bit followed by a collateral bit for every reserve, in the order
+/// of the pool's reserves list
+fn collateral_reserves(configuration: U256, reserves: Vec<Address>) -> Vec<Address> {
+    reserves
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| configuration.bit(i * 2 + 1))
+        .map(|(_, asset)| asset)
+        .collect()
+}
+
+/// Liquidators buy absorbed collateral out of the reserves of the market,
+/// which takes the place of the debtor. The market only sells collateral
+/// while its reserves are below the target, and its collateral is the
+/// reserves of every collateral asset. For other debtors, Comet only exposes
+/// whether the account can be absorbed and the collateral is the balance of
+/// every collateral asset of the market.
+async fn compound_v3_context<T: TracingProvider, DB: LibmdbxReader>(
+    liquidation: &NormalizedLiquidation,
+    block: u64,
+    tracer: &Arc<T>,
+    db: &DB,
+) -> eyre::Result<LiquidationContext> {
+    let (comet, debtor) = (liquidation.pool, liquidation.debtor);
+    let from_reserves = debtor == comet;
+
+    let liquidatable = if from_reserves {
+        let reserves = make_call_request(
+            ICompoundV3Comet::getReservesCall::new(()),
+            tracer,
+            comet,
+            Some(block),
+        )
+        .await?
+        ._0;
+        let target = make_call_request(
+            ICompoundV3Comet::targetReservesCall::new(()),
+            tracer,
+            comet,
+            Some(block),
+        )
+        .await?
+        ._0;
+
+        reserves.is_negative() || reserves.unsigned_abs() < target
+    } else {
+        make_call_request(
+            ICompoundV3Comet::isLiquidatableCall::new((debtor,)),
+            tracer,
+            comet,
+            Some(block),
+        )
+        .await?
+        ._0
+    };
+    let num_assets =
+        make_call_request(ICompoundV3Comet::numAssetsCall::new(()), tracer, comet, Some(block))
+            .await?
+            ._0;
+
+    let mut collateral_assets = Vec::new();
+    let mut collateral_amounts = Vec::new();
+    for i in 0..num_assets {
+        let asset = make_call_request(
+            ICompoundV3Comet::getAssetInfoCall::new((i,)),
+            tracer,
+            comet,
+            Some(block),
+        )
+        .await?
+        ._0
+        .asset;
+        let balance = if from_reserves {
+            make_call_request(
+                ICompoundV3Comet::getCollateralReservesCall::new((asset,)),
+                tracer,
+                comet,
+                Some(block),
+            )
+            .await?
+            ._0
+        } else {
+            U256::from(
+                make_call_request(
+                    ICompoundV3Comet::collateralBalanceOfCall::new((debtor, asset)),
+                    tracer,
+                    comet,
+                    Some(block),
+                )
+                .await?
+                ._0,
+            )
+        };
+        if balance.is_zero() {
+            continue
+        }
+
+        collateral_amounts.push(scaled(balance, db.try_fetch_token_info(asset)?.decimals));
+        collateral_assets.push(asset);
+    }
+
+    Ok(LiquidationContext {
+        protocol: liquidation.protocol,
+        debtor,
+        block_number: block,
+        health_factor: None,
+        liquidatable,
+        collateral_assets,
+        collateral_amounts,
+    })
+}
+
+fn scaled(amount: U256, decimals: u8) -> f64 {
+    amount.to_scaled_rational(decimals).to_float()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aave_collateral_bits() {
+        let reserves = (0..128).map(Address::with_last_byte).collect_vec();
+
+        // borrowing reserve 0, collateral in reserves 1 and 2
+        let configuration = U256::from(0b101001);
+        assert_eq!(
+            collateral_reserves(configuration, reserves.clone()),
+            vec![Address::with_last_byte(1), Address::with_last_byte(2)]
+        );
+
+        // a reserve used as collateral and borrowed at once, and the last of the
+        // 128 reserves the configuration has room for
+        let configuration = (U256::from(0b11) << 6) | (U256::from(1) << 255);
+        assert_eq!(
+            collateral_reserves(configuration, reserves.clone()),
+            vec![Address::with_last_byte(3), Address::with_last_byte(127)]
+        );
+
+        // only borrowing
+        let configuration = U256::from(0b0101);
+        assert!(collateral_reserves(configuration, reserves).is_empty());
+
+        assert_eq!(scaled(U256::from(15u64 * 10u64.pow(17)), HEALTH_FACTOR_DECIMALS), 1.5);
+    }
+}
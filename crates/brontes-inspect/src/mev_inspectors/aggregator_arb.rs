@@ -0,0 +1,366 @@
+//! Atomic arbs that route some of their legs through aggregators or batch
+//! settlements. The legs are unwrapped from the `Aggregator` and `Batch`
+//! actions and a tx only counts as an arb if its swaps form a cycle, every
+//! token it sells is bought back within the tx. The generic atomic arb
+//! inspector leaves these txs to this one, as flattening a settlement mixes the
+//! swaps of its users with the swaps of the solver.
+use std::sync::Arc;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    address_filter::address_filter,
+    db::dex::PriceAt,
+    mev::{AtomicArb, AtomicArbType, Bundle, BundleData, MevType},
+    normalized_actions::{Action, NormalizedEthTransfer, NormalizedSwap, NormalizedTransfer},
+    BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase, TreeCollector,
+    TreeSearchBuilder, TxInfo,
+};
+use itertools::Itertools;
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::Address;
+
+use crate::{
+    atomic_arb::AtomicArbInspector, shared_utils::SharedInspectorUtils, BlockTree, DetectionLimits,
+    Inspector, Metadata,
+};
+
+const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
+
+pub struct AggregatorArbInspector<'db, DB: LibmdbxReader> {
+    utils:  SharedInspectorUtils<'db, DB>,
+    atomic: AtomicArbInspector<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> AggregatorArbInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self {
+            utils:  SharedInspectorUtils::new(quote, db, metrics.clone()),
+            atomic: AtomicArbInspector::new(quote, db, metrics),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.atomic = self.atomic.with_limits(limits.clone());
+        self.utils.limits = limits;
+        self
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for AggregatorArbInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    // we use a 2 block window so that we can always have a trigger tx
+    fn block_window(&self) -> usize {
+        2
+    }
+
+    fn get_id(&self) -> &str {
+        "AggregatorArb"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let BlockData { metadata, tree } = data.get_most_recent_block();
+
+        let execution = || {
+            tree.clone()
+                .collect_all(TreeSearchBuilder::default().with_actions([
+                    Action::is_swap,
+                    Action::is_transfer,
+                    Action::is_eth_transfer,
+                    Action::is_nested_action,
+                ]))
+                .t_full_map(|(tree, v)| {
+                    let (tx_hashes, v): (Vec<_>, Vec<_>) = v.unzip();
+                    (tree.get_tx_info_batch(&tx_hashes, self.utils.db), v.into_iter())
+                })
+                .into_zip()
+                .filter_map(|(info, actions)| {
+                    let actions = actions.filter(|actions| is_routed(actions))?;
+                    let info = tree.attribute_to_user_op(info??, &actions);
+                    if !address_filter().allows_tx(&info) {
+                        return None
+                    }
+
+                    self.process_routed_swaps(
+                        data.per_block_data
+                            .iter()
+                            .map(|inner| inner.tree.clone())
+                            .collect_vec(),
+                        info,
+                        metadata.clone(),
+                        actions,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::AtomicArb, execution))
+            .unwrap_or_else(&execution)
+    }
+}
+
+impl<DB: LibmdbxReader> AggregatorArbInspector<'_, DB> {
+    fn process_routed_swaps(
+        &self,
+        trees: Vec<Arc<BlockTree<Action>>>,
+        info: TxInfo,
+        metadata: Arc<Metadata>,
+        actions: Vec<Action>,
+    ) -> Option<Bundle> {
+        tracing::trace!(?info, "trying aggregator arb");
+        let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
+
+        // the aggregators and settlements hold the tokens in between the legs,
+        // so they can't be mistaken for pools when building swaps from transfers
+        let mut ignore_addresses = mev_addresses.clone();
+        ignore_addresses.extend(actions.iter().filter_map(router));
+
+        let mut swaps = actions.iter().cloned().flat_map(arb_legs).collect_vec();
+
+        let (transfers, eth_transfers, vault_actions): (
+            Vec<NormalizedTransfer>,
+            Vec<NormalizedEthTransfer>,
+            Vec<Action>,
+        ) = self
+            .utils
+            .flatten_nested_actions_default(actions.into_iter())
+            .split_actions((Action::try_transfer, Action::try_eth_transfer, try_vault_action));
+
+        ignore_addresses.extend(swaps.iter().map(|s| s.pool));
+        swaps.extend(self.utils.try_create_swaps(&transfers, ignore_addresses));
+
+        if !is_cyclical(&swaps) {
+            return None
+        }
+
+        let tx_deltas = self.utils.calculate_token_deltas(
+            &swaps,
+            transfers
+                .into_iter()
+                .map(Action::from)
+                .chain(eth_transfers.into_iter().map(Action::from))
+                .chain(vault_actions)
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from)),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let account_deltas = tx_deltas.deltas;
+
+        let mut has_dex_price = self.utils.valid_pricing(
+            metadata.clone(),
+            &swaps,
+            account_deltas
+                .values()
+                .flat_map(|k| {
+                    k.iter()
+                        .filter(|(_, v)| *v != &Rational::ZERO)
+                        .map(|(k, _)| k)
+                })
+                .unique(),
+            info.tx_index as usize,
+            MAX_PRICE_DIFF,
+            MevType::AtomicArb,
+        );
+
+        let rev = self.utils.get_deltas_usd(
+            info.tx_index,
+            PriceAt::Average,
+            &mev_addresses,
+            &account_deltas,
+            metadata.clone(),
+            false,
+        );
+        // swaps that their transfers don't back can't be trusted to price the
+        // arb
+        has_dex_price &= rev.is_some() && balanced;
+
+        let gas_used = info.gas_details.gas_paid();
+        let gas_used_usd = self.utils.get_gas_price_usd(gas_used, &metadata);
+
+        let mut profit = rev
+            .map(|rev| rev - &gas_used_usd)
+            .filter(|_| has_dex_price)
+            .unwrap_or_default();
+
+        if profit >= self.utils.limits.max_profit_usd {
+            has_dex_price = false;
+            profit = Rational::ZERO;
+        }
+
+        // without prices the round trip could just as well be a user trading
+        // back and forth, so only known arbers are kept
+        let is_arb = if has_dex_price { profit > Rational::ZERO } else { self.is_arber(&info) };
+        if !is_arb {
+            self.utils.get_metrics().inspect(|m| {
+                m.branch_filtering_trigger(MevType::AtomicArb, "aggregator_arb_unprofitable")
+            });
+            return None
+        }
+
+        let trigger_tx = self.atomic.find_trigger_tx(&info, trees, &swaps);
+
+        let backrun = AtomicArb {
+            block_number: metadata.block_num,
+            trigger_tx,
+            tx_hash: info.tx_hash,
+            gas_details: info.gas_details,
+            swaps,
+            arb_type: AtomicArbType::Aggregator,
+        };
+        let data = BundleData::AtomicArb(backrun);
+
+        let header = self.utils.build_bundle_header(
+            vec![account_deltas],
+            vec![info.tx_hash],
+            &info,
+            profit.to_float(),
+            &[info.gas_details],
+            metadata.clone(),
+            MevType::AtomicArb,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &metadata,
+                )
+            },
+        );
+
+        Some(Bundle { header, data })
+    }
+
+    fn is_arber(&self, tx_info: &TxInfo) -> bool {
+        tx_info.is_searcher_of_type_with_count_threshold(MevType::AtomicArb, 20)
+            || tx_info.is_labelled_searcher_of_type(MevType::AtomicArb)
+            || tx_info.mev_contract.is_some()
+                && (tx_info.is_private || tx_info.gas_details.coinbase_transfer.is_some())
+    }
+}
+
+/// Whether any of the actions of the tx went through an aggregator or a batch
+/// settlement
+pub(crate) fn is_routed(actions: &[Action]) -> bool {
+    actions
+        .iter()
+        .any(|action| action.is_aggregator() || action.is_batch())
+}
+
+/// The contract routing the action, if it is an aggregator or a settlement
+fn router(action: &Action) -> Option<Address> {
+    match action {
+        Action::Aggregator(aggregator) => Some(aggregator.to),
+        Action::Batch(batch) => Some(batch.settlement_contract),
+        _ => None,
+    }
+}
+
+/// Unwraps the swaps the action is made of. Only the solver swaps of a batch
+/// are legs of the tx, the user swaps are trades against the settlement
+fn arb_legs(action: Action) -> Vec<NormalizedSwap> {
+    match action {
+        Action::Aggregator(aggregator) => aggregator
+            .child_actions
+            .into_iter()
+            .flat_map(arb_legs)
+            .collect(),
+        Action::FlashLoan(flash_loan) => flash_loan
+            .child_actions
+            .into_iter()
+            .flat_map(arb_legs)
+            .collect(),
+        Action::Batch(batch) => batch.solver_swaps.unwrap_or_default(),
+        action => action.try_swaps_merged().into_iter().collect(),
+    }
+}
+
+/// Whether every token the swaps sell is bought back by another swap and the
+/// other way around, so the tx ends up in the tokens it started with
+fn is_cyclical(swaps: &[NormalizedSwap]) -> bool {
+    if swaps.len() < 2 {
+        return false
+    }
+
+    let sold: FastHashSet<Address> = swaps.iter().map(|s| s.token_in.address).collect();
+    let bought: FastHashSet<Address> = swaps.iter().map(|s| s.token_out.address).collect();
+
+    sold == bought
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use brontes_types::db::token_info::TokenInfoWithAddress;
+
+    use super::*;
+    use crate::{
+        test_utils::{InspectorTestUtils, InspectorTxRunConfig, USDC_ADDRESS, WETH_ADDRESS},
+        Inspectors,
+    };
+
+    fn swap(token_in: u8, token_out: u8) -> NormalizedSwap {
+        let token = |byte| TokenInfoWithAddress {
+            address: Address::with_last_byte(byte),
+            ..Default::default()
+        };
+        NormalizedSwap {
+            token_in: token(token_in),
+            token_out: token(token_out),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cyclical_token_flows() {
+        // a -> b -> c -> a
+        assert!(is_cyclical(&[swap(1, 2), swap(2, 3), swap(3, 1)]));
+        // a -> b split over two routes, then b -> a
+        assert!(is_cyclical(&[swap(1, 2), swap(1, 2), swap(2, 1)]));
+        // a user trade routed a -> c -> b
+        assert!(!is_cyclical(&[swap(1, 3), swap(3, 2)]));
+        assert!(!is_cyclical(&[swap(1, 1)]));
+    }
+
+    #[brontes_macros::test]
+    async fn test_unix_with_1inch() {
+        let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
+
+        let config = InspectorTxRunConfig::new(Inspectors::AggregatorArb)
+            .with_mev_tx_hashes(vec![hex!(
+                "1cd6862577995835a9e5953845f1d6b5b0462f5762d44319b0e800bcd0c95945"
+            )
+            .into()])
+            .with_dex_prices()
+            .needs_tokens(vec![
+                WETH_ADDRESS,
+                hex!("88e08adb69f2618adf1a3ff6cc43c671612d1ca4").into(),
+            ])
+            .with_expected_profit_usd(7.47)
+            .with_gas_paid_usd(46.59);
+
+        inspector_util.run_inspector(config, None).await.unwrap();
+    }
+
+    #[brontes_macros::test]
+    async fn assert_no_mev_1inch() {
+        let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
+
+        let config = InspectorTxRunConfig::new(Inspectors::AggregatorArb)
+            .with_mev_tx_hashes(vec![hex!(
+                "cb70044718a016a75c811209552b7af57f64b27e6a502221f96e991968accef4"
+            )
+            .into()])
+            .with_dex_prices()
+            .needs_tokens(vec![WETH_ADDRESS]);
+
+        inspector_util.assert_no_mev(config).await.unwrap();
+    }
+}
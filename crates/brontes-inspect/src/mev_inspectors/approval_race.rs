@@ -0,0 +1,331 @@
+//! Approval races: an owner changing the allowance of a spender, usually to
+//! revoke or lower it, is raced by the spender. The spender moves the owner's
+//! tokens under the old allowance in a transaction placed directly before the
+//! approval, paying more for inclusion than it.
+use std::sync::Arc;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    address_filter::address_filter,
+    db::dex::PriceAt,
+    mev::{ApprovalRace, Bundle, BundleData, MevType},
+    normalized_actions::{Action, ApprovalKind, NormalizedApproval, NormalizedTransfer},
+    BlockData, FastHashSet, MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
+};
+use itertools::{multizip, Itertools};
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::Address;
+
+use super::frontrun::outbids;
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
+
+pub struct ApprovalRaceInspector<'db, DB: LibmdbxReader> {
+    utils: SharedInspectorUtils<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> ApprovalRaceInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
+    }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for ApprovalRaceInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    fn get_id(&self) -> &str {
+        "ApprovalRace"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let BlockData { metadata, tree } = data.get_most_recent_block();
+
+        let execution = || {
+            let (tx, actions): (Vec<_>, Vec<_>) = tree
+                .clone()
+                .collect_all(TreeSearchBuilder::default().with_actions([
+                    Action::is_transfer,
+                    Action::is_eth_transfer,
+                    Action::is_custom,
+                    Action::is_nested_action,
+                ]))
+                .unzip();
+            let tx_info = tree.get_tx_info_batch(&tx, self.utils.db);
+
+            let txs = multizip((tx, actions, tx_info))
+                .filter_map(|(tx_hash, actions, info)| {
+                    let info = info.filter(|info| address_filter().allows_tx(info))?;
+                    let actions = self
+                        .utils
+                        .flatten_nested_actions_default(actions.into_iter())
+                        .collect_vec();
+                    let to = tree
+                        .get_root(tx_hash)
+                        .and_then(|root| root.try_get_to_address());
+
+                    Some(ApprovalTx::new(info, to, actions))
+                })
+                .sorted_by_key(|tx| tx.info.tx_index)
+                .collect_vec();
+
+            txs.iter()
+                .tuple_windows()
+                .filter_map(|(frontrun, victim)| {
+                    self.process_race(frontrun, victim, metadata.clone())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::ApprovalRace, execution))
+            .unwrap_or_else(&execution)
+    }
+}
+
+impl<DB: LibmdbxReader> ApprovalRaceInspector<'_, DB> {
+    fn process_race(
+        &self,
+        frontrun: &ApprovalTx,
+        victim: &ApprovalTx,
+        metadata: Arc<Metadata>,
+    ) -> Option<Bundle> {
+        let (approval, drained) = frontrun.races(victim)?;
+
+        let info = &frontrun.info;
+        let mev_addresses = frontrun.spenders();
+        let tx_deltas = self.utils.calculate_token_deltas(
+            [],
+            frontrun
+                .transfers
+                .iter()
+                .cloned()
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from)),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let deltas = tx_deltas.deltas;
+
+        let rev = self.utils.get_deltas_usd(
+            info.tx_index,
+            PriceAt::After,
+            &mev_addresses,
+            &deltas,
+            metadata.clone(),
+            false,
+        );
+        let mut has_dex_price = rev.is_some() && balanced;
+
+        let gas_usd = self
+            .utils
+            .get_gas_price_usd(info.gas_details.gas_paid(), &metadata);
+        let mut profit_usd = rev.map(|rev| rev - gas_usd).unwrap_or_default();
+
+        if profit_usd >= self.utils.limits.max_profit_usd
+            || profit_usd <= -&self.utils.limits.max_profit_usd
+        {
+            has_dex_price = false;
+            profit_usd = Rational::ZERO;
+        }
+
+        let race = ApprovalRace {
+            block_number:         metadata.block_num,
+            frontrun_tx_hash:     info.tx_hash,
+            frontrun_transfers:   drained,
+            frontrun_gas_details: info.gas_details,
+            victim_tx_hash:       victim.info.tx_hash,
+            victim:               approval.owner,
+            spender:              approval.spender,
+            token:                approval.token.clone(),
+            new_allowance:        approval.amount,
+            victim_gas_details:   victim.info.gas_details,
+        };
+
+        let header = self.utils.build_bundle_header(
+            vec![deltas],
+            vec![info.tx_hash],
+            info,
+            profit_usd.to_float(),
+            &[info.gas_details],
+            metadata.clone(),
+            MevType::ApprovalRace,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &metadata,
+                )
+            },
+        );
+
+        Some(Bundle { header, data: BundleData::ApprovalRace(race) })
+    }
+}
+
+/// A tx with the approvals it sent and its token transfers
+struct ApprovalTx {
+    info:      TxInfo,
+    /// The contract the tx called
+    to:        Option<Address>,
+    approvals: Vec<NormalizedApproval>,
+    transfers: Vec<Action>,
+}
+
+impl ApprovalTx {
+    /// Only `approve` calls overwrite an allowance the spender can still use,
+    /// permits are submitted by the spender itself
+    fn new(info: TxInfo, to: Option<Address>, actions: Vec<Action>) -> Self {
+        let approvals = actions
+            .iter()
+            .filter_map(|action| action.try_custom_ref::<NormalizedApproval>())
+            .filter(|approval| approval.kind == ApprovalKind::Approve)
+            .cloned()
+            .collect_vec();
+        let transfers = actions
+            .into_iter()
+            .filter(|action| action.is_transfer() || action.is_eth_transfer())
+            .collect_vec();
+
+        Self { info, to, approvals, transfers }
+    }
+
+    /// The addresses the tx can spend an allowance from: the searcher
+    /// addresses and the contract it called
+    fn spenders(&self) -> FastHashSet<Address> {
+        let mut spenders = self.info.collect_address_set_for_accounting();
+        spenders.extend(self.to);
+        spenders
+    }
+
+    /// The approval sent by the victim directly after this tx, that this tx
+    /// raced by outbidding it and moving the owner's tokens as the spender,
+    /// along with the tokens it moved
+    fn races<'a>(
+        &self,
+        victim: &'a ApprovalTx,
+    ) -> Option<(&'a NormalizedApproval, Vec<NormalizedTransfer>)> {
+        if self.info.tx_index + 1 != victim.info.tx_index
+            || !outbids(&self.info.gas_details, &victim.info.gas_details)
+        {
+            return None
+        }
+
+        let spenders = self.spenders();
+        victim
+            .approvals
+            .iter()
+            .filter(|approval| {
+                (approval.owner == victim.info.eoa || Some(approval.owner) == victim.to)
+                    && spenders.contains(&approval.spender)
+                    && !spenders.contains(&approval.owner)
+            })
+            .find_map(|approval| {
+                let drained = self
+                    .transfers
+                    .iter()
+                    .filter_map(|action| match action {
+                        Action::Transfer(transfer)
+                            if transfer.from == approval.owner
+                                && transfer.token.address == approval.token.address =>
+                        {
+                            Some(transfer.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect_vec();
+
+                (!drained.is_empty()).then_some((approval, drained))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+    use brontes_types::{
+        db::token_info::TokenInfoWithAddress, order_flow::SubmissionChannel, GasDetails,
+    };
+    use reth_primitives::B256;
+
+    use super::*;
+
+    fn approval_tx(
+        tx_index: u64,
+        eoa: Address,
+        gas_price: u128,
+        actions: Vec<Action>,
+    ) -> ApprovalTx {
+        let gas_details = GasDetails { effective_gas_price: gas_price, ..Default::default() };
+        let info = TxInfo::new(
+            0,
+            tx_index,
+            eoa,
+            None,
+            None,
+            B256::ZERO,
+            gas_details,
+            true,
+            false,
+            false,
+            SubmissionChannel::Public,
+            false,
+            None,
+            None,
+            vec![],
+        );
+
+        ApprovalTx::new(info, None, actions)
+    }
+
+    #[test]
+    fn test_approval_race() {
+        let [owner, spender, drainer] = [1, 2, 3].map(Address::with_last_byte);
+        let token = TokenInfoWithAddress::usdc();
+        let revoke = Action::Custom(
+            NormalizedApproval {
+                kind: ApprovalKind::Approve,
+                token: token.clone(),
+                owner,
+                spender,
+                amount: U256::ZERO,
+                ..Default::default()
+            }
+            .into(),
+        );
+        let drain = Action::Transfer(NormalizedTransfer {
+            from: owner,
+            to: drainer,
+            token: token.clone(),
+            amount: Rational::from(100),
+            ..Default::default()
+        });
+
+        let victim = approval_tx(1, owner, 10, vec![revoke.clone()]);
+        let frontrun = approval_tx(0, spender, 20, vec![drain.clone()]);
+        let (approval, drained) = frontrun.races(&victim).unwrap();
+        assert!(approval.is_revoke());
+        assert_eq!(drained.len(), 1);
+
+        // outbid by the approval, or not placed directly before it
+        let outbid = approval_tx(0, spender, 5, vec![drain.clone()]);
+        assert!(outbid.races(&victim).is_none());
+        let later = approval_tx(2, owner, 10, vec![revoke]);
+        assert!(frontrun.races(&later).is_none());
+
+        // someone else moving the owner's tokens doesn't race the allowance of
+        // the spender
+        let other = approval_tx(0, drainer, 20, vec![drain]);
+        assert!(other.races(&victim).is_none());
+    }
+}
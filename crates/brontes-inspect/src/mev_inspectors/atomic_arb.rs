@@ -3,13 +3,11 @@ use std::sync::Arc;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     constants::{get_stable_type, is_euro_stable, is_gold_stable, is_usd_stable, StableType},
     db::dex::PriceAt,
     mev::{AtomicArb, AtomicArbType, Bundle, BundleData, MevType},
-    normalized_actions::{
-        accounting::ActionAccounting, Action, NormalizedEthTransfer, NormalizedSwap,
-        NormalizedTransfer,
-    },
+    normalized_actions::{Action, NormalizedEthTransfer, NormalizedSwap, NormalizedTransfer},
     BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase, TreeCollector,
     TreeSearchBuilder, TxInfo,
 };
@@ -17,7 +15,11 @@ use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
 use reth_primitives::{Address, B256};
 
-use crate::{shared_utils::SharedInspectorUtils, BlockTree, Inspector, Metadata, MAX_PROFIT};
+use crate::{
+    aggregator_arb::is_routed,
+    shared_utils::{try_vault_action, SharedInspectorUtils},
+    BlockTree, DetectionLimits, Inspector, Metadata,
+};
 
 const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
 
@@ -30,6 +32,11 @@ impl<'db, DB: LibmdbxReader> AtomicArbInspector<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
         Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
@@ -57,6 +64,8 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                     Action::is_swap,
                     Action::is_transfer,
                     Action::is_eth_transfer,
+                    Action::is_vault_deposit,
+                    Action::is_vault_withdraw,
                     Action::is_nested_action,
                 ]))
                 .t_full_map(|(tree, v)| {
@@ -64,16 +73,23 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                     (
                         tree.get_tx_info_batch(&tx_hashes, self.utils.db),
                         v.into_iter().map(|v| {
-                            self.utils
-                                .flatten_nested_actions_default(v.into_iter())
-                                .collect::<Vec<_>>()
+                            // arbs routed through aggregators and settlements are left to the
+                            // aggregator arb inspector
+                            (!is_routed(&v)).then(|| {
+                                self.utils
+                                    .flatten_nested_actions_default(v.into_iter())
+                                    .collect::<Vec<_>>()
+                            })
                         }),
                     )
                 })
                 .into_zip()
                 .filter_map(|(info, action)| {
-                    let info = info??;
-                    let actions = action?;
+                    let actions = action??;
+                    let info = tree.attribute_to_user_op(info??, &actions);
+                    if !address_filter().allows_tx(&info) {
+                        return None
+                    }
 
                     self.process_swaps(
                         data.per_block_data
@@ -84,10 +100,11 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                         metadata.clone(),
                         actions
                             .into_iter()
-                            .split_actions::<(Vec<_>, Vec<_>, Vec<_>), _>((
+                            .split_actions::<(Vec<_>, Vec<_>, Vec<_>, Vec<_>), _>((
                                 Action::try_swaps_merged,
                                 Action::try_transfer,
                                 Action::try_eth_transfer,
+                                try_vault_action,
                             )),
                     )
                 })
@@ -107,10 +124,15 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         trees: Vec<Arc<BlockTree<Action>>>,
         info: TxInfo,
         metadata: Arc<Metadata>,
-        data: (Vec<NormalizedSwap>, Vec<NormalizedTransfer>, Vec<NormalizedEthTransfer>),
+        data: (
+            Vec<NormalizedSwap>,
+            Vec<NormalizedTransfer>,
+            Vec<NormalizedEthTransfer>,
+            Vec<Action>,
+        ),
     ) -> Option<Bundle> {
         tracing::trace!(?info, "trying atomic");
-        let (mut swaps, transfers, eth_transfers) = data;
+        let (mut swaps, transfers, eth_transfers, vault_actions) = data;
         let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
 
         let mut ignore_addresses = mev_addresses.clone();
@@ -123,12 +145,17 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
 
         let possible_arb_type = self.is_possible_arb(&swaps)?;
 
-        let account_deltas = transfers
-            .into_iter()
-            .map(Action::from)
-            .chain(eth_transfers.into_iter().map(Action::from))
-            .chain(info.get_total_eth_value().iter().cloned().map(Action::from))
-            .account_for_actions();
+        let tx_deltas = self.utils.calculate_token_deltas(
+            &swaps,
+            transfers
+                .into_iter()
+                .map(Action::from)
+                .chain(eth_transfers.into_iter().map(Action::from))
+                .chain(vault_actions)
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from)),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let account_deltas = tx_deltas.deltas;
 
         let mut has_dex_price = self.utils.valid_pricing(
             metadata.clone(),
@@ -146,6 +173,12 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
             MevType::AtomicArb,
         );
 
+        // swaps that their transfers don't back can't be trusted to price the
+        // arb
+        if !balanced {
+            has_dex_price = false;
+        }
+
         let rev = if let Some(rev) = self.utils.get_deltas_usd(
             info.tx_index,
             PriceAt::Average,
@@ -161,14 +194,14 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         };
 
         let gas_used = info.gas_details.gas_paid();
-        let gas_used_usd = metadata.get_gas_price_usd(gas_used, self.utils.quote);
+        let gas_used_usd = self.utils.get_gas_price_usd(gas_used, &metadata);
 
         let mut profit = rev
             .map(|rev| rev - &gas_used_usd)
             .filter(|_| has_dex_price)
             .unwrap_or_default();
 
-        if profit >= MAX_PROFIT {
+        if profit >= self.utils.limits.max_profit_usd {
             has_dex_price = false;
             profit = Rational::ZERO;
         }
@@ -193,6 +226,8 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
                 && is_profitable
                 || self.is_long_tail(&info, requirement_multiplier) & !has_dex_price)
                 .then_some(profit),
+            // routed and nft arbs are left to their own inspectors
+            AtomicArbType::Aggregator | AtomicArbType::Nft => None,
         }?;
 
         // given we have a atomic arb now, we will go and try to find the trigger
@@ -234,7 +269,7 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
 
     /// goes back through the tree until it finds a transaction that occurred
     /// before the atomic arb that use the same liquidity pool for a swap.
-    fn find_trigger_tx(
+    pub(crate) fn find_trigger_tx(
         &self,
         arb_info: &TxInfo,
         mut trees: Vec<Arc<BlockTree<Action>>>,
@@ -526,26 +561,6 @@ mod tests {
         inspector_util.run_inspector(config, None).await.unwrap();
     }
 
-    #[brontes_macros::test]
-    async fn test_unix_with_1inch() {
-        let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
-
-        let config = InspectorTxRunConfig::new(Inspectors::AtomicArb)
-            .with_mev_tx_hashes(vec![hex!(
-                "1cd6862577995835a9e5953845f1d6b5b0462f5762d44319b0e800bcd0c95945"
-            )
-            .into()])
-            .with_dex_prices()
-            .needs_tokens(vec![
-                WETH_ADDRESS,
-                hex!("88e08adb69f2618adf1a3ff6cc43c671612d1ca4").into(),
-            ])
-            .with_expected_profit_usd(7.47)
-            .with_gas_paid_usd(46.59);
-
-        inspector_util.run_inspector(config, None).await.unwrap();
-    }
-
     #[brontes_macros::test]
     async fn test_seawise_resolver() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 2.5).await;
@@ -7,11 +7,14 @@ use alloy_primitives::FixedBytes;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     db::cex::{
+        fees::cex_fee_schedule,
         trades::{
             config::CexDexTradeConfig,
             optimistic::OptimisticPrice,
             time_window_vwam::{ExchangePath, WindowExchangePrice},
+            CexWindow,
         },
         CexExchange,
     },
@@ -46,7 +49,7 @@ use super::{
 // to classify a a negative pnl cex-dex trade as a CEX-DEX trade
 pub const FILTER_THRESHOLD: u64 = 20;
 
-use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
 
 pub struct CexDexMarkoutInspector<'db, DB: LibmdbxReader> {
     pub utils:     SharedInspectorUtils<'db, DB>,
@@ -68,6 +71,11 @@ impl<'db, DB: LibmdbxReader> CexDexMarkoutInspector<'db, DB> {
             cex_exchanges: cex_exchanges.to_owned(),
         }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for CexDexMarkoutInspector<'_, DB> {
@@ -77,6 +85,16 @@ impl<DB: LibmdbxReader> Inspector for CexDexMarkoutInspector<'_, DB> {
         "CexDexMarkout"
     }
 
+    fn cache_id(&self) -> String {
+        format!(
+            "{}{:?}{:?}{}",
+            self.get_id(),
+            self.cex_exchanges,
+            self.trade_config,
+            cex_fee_schedule().cache_id()
+        )
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -107,6 +125,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         tree: Arc<BlockTree<Action>>,
         metadata: Arc<Metadata>,
     ) -> Vec<Bundle> {
+        let window = self.block_window(&metadata);
         let (hashes, actions): (Vec<_>, Vec<_>) = tree
             .clone()
             .collect_all(TreeSearchBuilder::default().with_actions([
@@ -122,20 +141,38 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
 
         multizip((actions, tx_info))
             .filter_map(|(actions, tx_info)| {
-                let tx_info = tx_info?;
+                let tx_info = tx_info
+                    .map(|info| tree.attribute_to_user_op(info, &actions))
+                    .filter(|info| address_filter().allows_tx(info))?;
                 if self.should_filter_tx(&tx_info) {
                     return None
                 }
 
                 if actions.iter().any(Action::is_batch) {
-                    self.process_batch_swaps(actions, tx_info, metadata.clone())
+                    self.process_batch_swaps(actions, tx_info, metadata.clone(), &window)
                 } else {
-                    self.process_dex_swaps(actions, tx_info, metadata.clone())
+                    self.process_dex_swaps(actions, tx_info, metadata.clone(), &window)
                 }
             })
             .collect()
     }
 
+    /// The initial windows the cex trades of the block are selected in
+    pub fn block_window(&self, metadata: &Metadata) -> CexWindow {
+        let Some(trades) = metadata.cex_trades.as_ref() else {
+            return CexWindow::fixed(&self.trade_config)
+        };
+
+        CexWindow::for_block(
+            &self.trade_config,
+            metadata.microseconds_block_timestamp(),
+            metadata.block_metadata.relay_timestamp,
+            metadata.block_metadata.p2p_timestamp,
+            trades,
+            &self.cex_exchanges,
+        )
+    }
+
     fn should_filter_tx(&self, tx_info: &TxInfo) -> bool {
         if let Some(contract_type) = tx_info.contract_type.as_ref() {
             if contract_type.is_defi_automation() {
@@ -159,6 +196,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         actions: Vec<Action>,
         tx_info: TxInfo,
         metadata: Arc<Metadata>,
+        window: &CexWindow,
     ) -> Option<Bundle> {
         let deltas = actions
             .clone()
@@ -201,7 +239,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             return None
         }
 
-        self.process_swaps(dex_swaps, tx_info, metadata, deltas, false)
+        self.process_swaps(dex_swaps, tx_info, metadata, deltas, window, false)
     }
 
     fn process_batch_swaps(
@@ -209,6 +247,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         actions: Vec<Action>,
         tx_info: TxInfo,
         metadata: Arc<Metadata>,
+        window: &CexWindow,
     ) -> Option<Bundle> {
         let deltas = actions
             .clone()
@@ -231,7 +270,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             .flatten()
             .collect();
 
-        self.process_swaps(dex_swaps, tx_info, metadata, deltas, true)
+        self.process_swaps(dex_swaps, tx_info, metadata, deltas, window, true)
     }
 
     fn process_swaps(
@@ -240,6 +279,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         tx_info: TxInfo,
         metadata: Arc<Metadata>,
         deltas: AddressDeltas,
+        window: &CexWindow,
         batch_swap: bool,
     ) -> Option<Bundle> {
         if dex_swaps.is_empty() {
@@ -259,6 +299,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                 || tx_info.is_labelled_searcher_of_type(MevType::CexDexRfq)
                 || tx_info.is_searcher_of_type(MevType::JitCexDex),
             &tx_info,
+            window,
         )?;
 
         self.gas_accounting(&mut possible_cex_dex, &tx_info.gas_details, metadata.clone());
@@ -295,21 +336,23 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         metadata: &Metadata,
         marked_cex_dex: bool,
         tx_info: &TxInfo,
+        window: &CexWindow,
     ) -> Option<CexDexProcessing> {
+        let config = self.trade_config.with_window(window);
         let cex_prices =
-            self.cex_prices_for_swaps(dex_swaps, metadata, marked_cex_dex, tx_info.tx_hash);
+            self.cex_prices_for_swaps(dex_swaps, metadata, marked_cex_dex, tx_info.tx_hash, config);
 
         let merged_swaps = cex_prices.dex_swaps.clone();
 
         let global_vwam: Option<PossibleCexDex> =
-            self.process_global_vwam(&cex_prices, metadata, tx_info);
+            self.process_global_vwam(&cex_prices, metadata, tx_info, config);
 
-        let per_exchange_pnl = self.process_per_exchange(&cex_prices, metadata, tx_info);
+        let per_exchange_pnl = self.process_per_exchange(&cex_prices, metadata, tx_info, config);
 
         let optimstic_res: Option<OptimisticDetails> =
-            self.process_optimistic(cex_prices, metadata, tx_info);
+            self.process_optimistic(cex_prices, metadata, tx_info, config);
 
-        CexDexProcessing::new(merged_swaps, global_vwam, per_exchange_pnl, optimstic_res)
+        CexDexProcessing::new(merged_swaps, global_vwam, per_exchange_pnl, optimstic_res, *window)
     }
 
     fn process_global_vwam(
@@ -317,6 +360,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         cex_prices: &CexPricesForSwaps,
         metadata: &Metadata,
         tx_info: &TxInfo,
+        config: CexDexTradeConfig,
     ) -> Option<PossibleCexDex> {
         cex_prices.global_price().and_then(|global_prices| {
             PossibleCexDex::from_arb_legs(
@@ -333,6 +377,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                             metadata,
                             tx_info,
                             PriceCalcType::TimeWindowGlobal,
+                            config,
                         )
                     })
                     .collect(),
@@ -345,6 +390,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         cex_prices: &CexPricesForSwaps,
         metadata: &Metadata,
         tx_info: &TxInfo,
+        config: CexDexTradeConfig,
     ) -> Vec<Option<PossibleCexDex>> {
         cex_prices
             .per_exchange_trades(self.cex_exchanges.as_slice())
@@ -364,6 +410,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                                 metadata,
                                 tx_info,
                                 PriceCalcType::TimeWindowPerEx,
+                                config,
                             )
                         })
                     })
@@ -380,6 +427,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         cex_prices: CexPricesForSwaps,
         metadata: &Metadata,
         tx_info: &TxInfo,
+        config: CexDexTradeConfig,
     ) -> Option<OptimisticDetails> {
         let arb_legs_and_trades: Vec<(Option<ArbLeg>, Vec<OptimisticTrade>)> = cex_prices
             .dex_swaps
@@ -395,6 +443,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                         metadata,
                         tx_info,
                         PriceCalcType::Optimistic,
+                        config,
                     );
                     (arb_leg, price.trades_used)
                 })
@@ -425,6 +474,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         metadata: &Metadata,
         tx_info: &TxInfo,
         price_calculation_type: PriceCalcType,
+        config: CexDexTradeConfig,
     ) -> Option<ArbLeg> {
         let (output_of_cex_trade_maker, output_of_cex_trade_taker) =
             (&cex_quote.price_maker * &swap.amount_out, &cex_quote.price_taker * &swap.amount_out);
@@ -443,7 +493,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             .as_ref()
             .unwrap()
             .calculate_time_window_vwam(
-                self.trade_config,
+                config,
                 &self.cex_exchanges,
                 pair,
                 &vol,
@@ -514,6 +564,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         metadata: &Metadata,
         marked_cex_dex: bool,
         tx_hash: FixedBytes<32>,
+        config: CexDexTradeConfig,
     ) -> CexPricesForSwaps {
         let merged_swaps = SharedInspectorUtils::<DB>::cex_merge_possible_swaps(dex_swaps);
 
@@ -521,7 +572,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             .clone()
             .iter()
             .filter(|swap| swap.amount_out != Rational::ZERO)
-            .map(|swap| self.calculate_cex_price(swap, metadata, marked_cex_dex, tx_hash))
+            .map(|swap| self.calculate_cex_price(swap, metadata, marked_cex_dex, tx_hash, config))
             .unzip();
 
         CexPricesForSwaps { dex_swaps: merged_swaps, time_window_vwam, optimistic }
@@ -533,6 +584,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         metadata: &Metadata,
         marked_cex_dex: bool,
         tx_hash: FixedBytes<32>,
+        config: CexDexTradeConfig,
     ) -> (Option<WindowExchangePrice>, Option<OptimisticPrice>) {
         let pair = Pair(swap.token_in.address, swap.token_out.address);
 
@@ -542,7 +594,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                 .as_ref()
                 .unwrap()
                 .calculate_time_window_vwam(
-                    self.trade_config,
+                    config,
                     &self.cex_exchanges,
                     pair,
                     &swap.amount_out,
@@ -561,7 +613,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
 
         let optimistic = || {
             metadata.cex_trades.as_ref().unwrap().get_optimistic_vmap(
-                self.trade_config,
+                config,
                 &self.cex_exchanges,
                 pair,
                 &swap.amount_out,
@@ -610,7 +662,9 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         gas_details: &GasDetails,
         metadata: Arc<Metadata>,
     ) {
-        let gas_cost = metadata.get_gas_price_usd(gas_details.gas_paid(), self.utils.quote);
+        let gas_cost = self
+            .utils
+            .get_gas_price_usd(gas_details.gas_paid(), &metadata);
         cex_dex.adjust_for_gas_cost(&gas_cost);
 
         cex_dex.per_exchange_pnl.retain(|entry| entry.is_some());
@@ -643,7 +697,10 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
         let is_profitable_outlier = sanity_check_arb.is_profitable_outlier();
 
         let is_cex_dex_bot_with_significant_activity = info
-            .is_searcher_of_type_with_count_threshold(MevType::CexDexTrades, FILTER_THRESHOLD * 2);
+            .is_searcher_of_type_with_count_threshold(
+                MevType::CexDexTrades,
+                self.utils.limits.min_cex_dex_searcher_bundles * 2,
+            );
         let is_labelled_cex_dex_bot = info.is_labelled_searcher_of_type(MevType::CexDexTrades);
 
         let is_profitable_on_one_exchange = sanity_check_arb.profitable_exchanges_maker.len() == 1
@@ -662,12 +719,13 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
 
         let tx_attributes_meet_cex_dex_criteria = !info.is_classified
             && info.is_private
-            && (info
-                .is_searcher_of_type_with_count_threshold(MevType::CexDexTrades, FILTER_THRESHOLD)
-                || info
-                    .contract_type
-                    .as_ref()
-                    .map_or(false, |contract_type| contract_type.could_be_mev_contract()));
+            && (info.is_searcher_of_type_with_count_threshold(
+                MevType::CexDexTrades,
+                self.utils.limits.min_cex_dex_searcher_bundles,
+            ) || info
+                .contract_type
+                .as_ref()
+                .map_or(false, |contract_type| contract_type.could_be_mev_contract()));
 
         let is_cex_dex_based_on_historical_activity =
             is_cex_dex_bot_with_significant_activity || is_labelled_cex_dex_bot;
@@ -6,6 +6,7 @@ use brontes_types::{
         trades::{
             optimistic::OptimisticPrice,
             time_window_vwam::{ExchangePath, WindowExchangePrice},
+            CexWindow,
         },
         CexExchange,
     },
@@ -115,6 +116,7 @@ pub struct CexDexProcessing {
     pub per_exchange_pnl:    Vec<Option<PossibleCexDex>>,
     pub max_profit:          Option<PossibleCexDex>,
     pub optimistic_details:  Option<OptimisticDetails>,
+    pub window:              CexWindow,
 }
 
 impl CexDexProcessing {
@@ -123,6 +125,7 @@ impl CexDexProcessing {
         global_vmam_cex_dex: Option<PossibleCexDex>,
         per_exchange_pnl: Vec<Option<PossibleCexDex>>,
         optimistic_details: Option<OptimisticDetails>,
+        window: CexWindow,
     ) -> Option<Self> {
         let mut this = Self {
             per_exchange_pnl,
@@ -130,6 +133,7 @@ impl CexDexProcessing {
             max_profit: None,
             global_vmam_cex_dex,
             optimistic_details,
+            window,
         };
         this.construct_max_profit_route()?;
         Some(this)
@@ -319,6 +323,7 @@ impl CexDexProcessing {
                     .collect(),
 
                 gas_details: tx_info.gas_details,
+                cex_window: self.window,
                 swaps: self.dex_swaps,
             }),
             self.max_profit
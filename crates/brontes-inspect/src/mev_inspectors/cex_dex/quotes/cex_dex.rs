@@ -50,7 +50,8 @@ use alloy_primitives::Address;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::cex::{quotes::FeeAdjustedQuote, CexExchange},
+    address_filter::address_filter,
+    db::cex::{fees::cex_fee_schedule, quotes::FeeAdjustedQuote, CexExchange},
     display::utils::format_etherscan_url,
     mev::{Bundle, BundleData, MevType},
     normalized_actions::{accounting::ActionAccounting, Action, NormalizedSwap},
@@ -73,7 +74,7 @@ pub const FILTER_THRESHOLD: u64 = 20;
 
 use itertools::Itertools;
 
-use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
 pub struct CexDexQuotesInspector<'db, DB: LibmdbxReader> {
     utils:                SharedInspectorUtils<'db, DB>,
     _quotes_fetch_offset: u64,
@@ -102,6 +103,11 @@ impl<'db, DB: LibmdbxReader> CexDexQuotesInspector<'db, DB> {
             _cex_exchanges:       cex_exchanges.to_owned(),
         }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for CexDexQuotesInspector<'_, DB> {
@@ -111,6 +117,10 @@ impl<DB: LibmdbxReader> Inspector for CexDexQuotesInspector<'_, DB> {
         "CexDex"
     }
 
+    fn cache_id(&self) -> String {
+        format!("{}{}", self.get_id(), cex_fee_schedule().cache_id())
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -166,7 +176,10 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
                 Action::is_aggregator,
             ]))
             .filter_map(|(tx, swaps)| {
-                let tx_info = tree.get_tx_info(tx, self.utils.db)?;
+                let tx_info = tree
+                    .get_tx_info(tx, self.utils.db)
+                    .map(|info| tree.attribute_to_user_op(info, &swaps))
+                    .filter(|info| address_filter().allows_tx(info))?;
 
                 // Return early if this is an defi automation contract
                 if let Some(contract_type) = tx_info.contract_type.as_ref() {
@@ -431,7 +444,9 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
         gas_details: &GasDetails,
         metadata: Arc<Metadata>,
     ) {
-        let gas_cost = metadata.get_gas_price_usd(gas_details.gas_paid(), self.utils.quote);
+        let gas_cost = self
+            .utils
+            .get_gas_price_usd(gas_details.gas_paid(), &metadata);
 
         cex_dex.pnl.adjust_for_gas_cost(gas_cost);
     }
@@ -452,8 +467,11 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
         info: &TxInfo,
         metadata: &Metadata,
     ) -> Option<(f64, BundleData)> {
-        let is_cex_dex_bot_with_significant_activity =
-            info.is_searcher_of_type_with_count_threshold(MevType::CexDexQuotes, FILTER_THRESHOLD);
+        let is_cex_dex_bot_with_significant_activity = info
+            .is_searcher_of_type_with_count_threshold(
+                MevType::CexDexQuotes,
+                self.utils.limits.min_cex_dex_searcher_bundles,
+            );
         let is_labelled_cex_dex_bot = info.is_labelled_searcher_of_type(MevType::CexDexQuotes);
 
         let should_include_based_on_pnl = possible_cex_dex.pnl.aggregate_pnl > 1.5;
@@ -1,5 +1,5 @@
 mod cex_dex;
 mod types;
 
-pub use cex_dex::CexDexQuotesInspector;
+pub use cex_dex::{CexDexQuotesInspector, FILTER_THRESHOLD};
 pub use types::{log_cex_dex_quote_delta, CexDexProcessing, ExchangeLeg, PossibleCexDex};
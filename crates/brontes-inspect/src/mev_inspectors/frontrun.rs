@@ -0,0 +1,369 @@
+//! Generalized frontruns: a searcher trade placed directly before large trades
+//! of other senders on the same pools and in the same direction, paying more
+//! for inclusion than them. Unlike a sandwich the searcher doesn't close the
+//! position in the block, its profit is the value of the position at the
+//! prices the victims pushed the pools to.
+use std::sync::Arc;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    address_filter::address_filter,
+    db::dex::PriceAt,
+    mev::{Bundle, BundleData, Frontrun, MevType},
+    normalized_actions::{Action, NormalizedSwap},
+    BlockData, FastHashSet, GasDetails, MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
+};
+use itertools::{multizip, Itertools};
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::Address;
+
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
+
+/// Victim trades smaller than this don't move the pools enough to be worth
+/// frontrunning
+pub(crate) const MIN_VICTIM_VOLUME_USD: Rational = Rational::const_from_unsigned(10_000);
+
+pub struct FrontrunInspector<'db, DB: LibmdbxReader> {
+    utils: SharedInspectorUtils<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> FrontrunInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
+    }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for FrontrunInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    fn get_id(&self) -> &str {
+        "Frontrun"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let BlockData { metadata, tree } = data.get_most_recent_block();
+
+        let execution = || {
+            let (tx, actions): (Vec<_>, Vec<_>) = tree
+                .clone()
+                .collect_all(TreeSearchBuilder::default().with_actions([
+                    Action::is_swap,
+                    Action::is_transfer,
+                    Action::is_eth_transfer,
+                    Action::is_nested_action,
+                ]))
+                .unzip();
+            let tx_info = tree.get_tx_info_batch(&tx, self.utils.db);
+
+            let txs = multizip((actions, tx_info))
+                .filter_map(|(actions, info)| {
+                    let info = info.filter(|info| address_filter().allows_tx(info))?;
+                    let actions = self
+                        .utils
+                        .flatten_nested_actions_default(actions.into_iter())
+                        .collect_vec();
+
+                    SwapTx::new(info, actions)
+                })
+                .sorted_by_key(|tx| tx.info.tx_index)
+                .collect_vec();
+
+            (0..txs.len())
+                .filter_map(|i| self.process_frontrun(&txs, i, metadata.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        self.utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::Frontrun, execution))
+            .unwrap_or_else(&execution)
+    }
+}
+
+impl<DB: LibmdbxReader> FrontrunInspector<'_, DB> {
+    /// Checks if the tx at `i` frontruns the txs directly following it
+    fn process_frontrun(
+        &self,
+        txs: &[SwapTx],
+        i: usize,
+        metadata: Arc<Metadata>,
+    ) -> Option<Bundle> {
+        let frontrun = &txs[i];
+        // without a searcher contract, a user trade that happens to land
+        // before a larger one can't be told apart from a frontrun
+        frontrun.info.mev_contract?;
+
+        let mev_addresses = frontrun.info.collect_address_set_for_accounting();
+        let victims = txs[i + 1..]
+            .iter()
+            .zip(frontrun.info.tx_index + 1..)
+            .take_while(|(tx, tx_index)| {
+                tx.info.tx_index == *tx_index && frontrun.is_frontrun_of(tx, &mev_addresses)
+            })
+            .map(|(tx, _)| tx)
+            .collect_vec();
+        let last_victim = victims.last()?;
+
+        let victim_swaps = victims
+            .iter()
+            .map(|victim| victim.swaps_along(&frontrun.swaps))
+            .collect_vec();
+        if !self.is_large(&victims, &victim_swaps, &metadata) {
+            self.utils
+                .get_metrics()
+                .inspect(|m| m.branch_filtering_trigger(MevType::Frontrun, "small_victim_volume"));
+            return None
+        }
+
+        // a searcher closing the position later in the block sandwiched the
+        // victims, which is left to the sandwich inspector
+        if txs[i + 1 + victims.len()..].iter().any(|tx| {
+            !tx.info
+                .collect_address_set_for_accounting()
+                .is_disjoint(&mev_addresses)
+                && tx.unwinds(&frontrun.swaps)
+        }) {
+            return None
+        }
+
+        let info = &frontrun.info;
+        let tx_deltas = self.utils.calculate_token_deltas(
+            &frontrun.swaps,
+            frontrun
+                .transfers
+                .iter()
+                .cloned()
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from)),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let deltas = tx_deltas.deltas;
+
+        let rev = self.utils.get_deltas_usd(
+            last_victim.info.tx_index,
+            PriceAt::After,
+            &mev_addresses,
+            &deltas,
+            metadata.clone(),
+            false,
+        );
+        let mut has_dex_price = rev.is_some() && balanced;
+
+        let gas_usd = self
+            .utils
+            .get_gas_price_usd(info.gas_details.gas_paid(), &metadata);
+        let mut profit_usd = rev.map(|rev| rev - gas_usd).unwrap_or_default();
+
+        if profit_usd >= self.utils.limits.max_profit_usd
+            || profit_usd <= -&self.utils.limits.max_profit_usd
+        {
+            has_dex_price = false;
+            profit_usd = Rational::ZERO;
+        }
+        let profit_usd = profit_usd.to_float();
+
+        let frontrun_bundle = Frontrun {
+            block_number: metadata.block_num,
+            frontrun_tx_hash: info.tx_hash,
+            frontrun_swaps: frontrun.swaps.clone(),
+            frontrun_gas_details: info.gas_details,
+            victim_swaps_tx_hashes: victims.iter().map(|v| v.info.tx_hash).collect(),
+            victim_swaps,
+            victim_swaps_gas_details: victims.iter().map(|v| v.info.gas_details).collect(),
+            displacement_profit_usd: profit_usd,
+        };
+
+        let header = self.utils.build_bundle_header(
+            vec![deltas],
+            vec![info.tx_hash],
+            info,
+            profit_usd,
+            &[info.gas_details],
+            metadata.clone(),
+            MevType::Frontrun,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &metadata,
+                )
+            },
+        );
+
+        Some(Bundle { header, data: BundleData::Frontrun(frontrun_bundle) })
+    }
+
+    /// The victims traded at least the minimum victim volume through the
+    /// frontrun pools, valued at the price before each victim. Trades that
+    /// can't be priced can't be shown to be large.
+    fn is_large(
+        &self,
+        victims: &[&SwapTx],
+        victim_swaps: &[Vec<NormalizedSwap>],
+        metadata: &Arc<Metadata>,
+    ) -> bool {
+        victims
+            .iter()
+            .zip(victim_swaps)
+            .flat_map(|(victim, swaps)| swaps.iter().map(|swap| (victim.info.tx_index, swap)))
+            .try_fold(Rational::ZERO, |volume, (tx_index, swap)| {
+                let value = self.utils.get_token_value_dex(
+                    tx_index as usize,
+                    PriceAt::Before,
+                    swap.token_in.address,
+                    &swap.amount_in,
+                    metadata,
+                )?;
+                Some(volume + value)
+            })
+            .is_some_and(|volume| volume >= self.utils.limits.min_victim_volume_usd)
+    }
+}
+
+/// A tx that swapped, with its swaps and its token transfers
+struct SwapTx {
+    info:      TxInfo,
+    swaps:     Vec<NormalizedSwap>,
+    transfers: Vec<Action>,
+}
+
+impl SwapTx {
+    fn new(info: TxInfo, actions: Vec<Action>) -> Option<Self> {
+        let swaps = actions
+            .iter()
+            .cloned()
+            .filter_map(Action::try_swaps_merged)
+            .collect_vec();
+        let transfers = actions
+            .into_iter()
+            .filter(|action| action.is_transfer() || action.is_eth_transfer())
+            .collect_vec();
+
+        (!swaps.is_empty()).then_some(Self { info, swaps, transfers })
+    }
+
+    /// The victim is sent by someone else, outbid by this tx and trades
+    /// through a pool of this tx in the same direction
+    fn is_frontrun_of(&self, victim: &SwapTx, mev_addresses: &FastHashSet<Address>) -> bool {
+        victim
+            .info
+            .collect_address_set_for_accounting()
+            .is_disjoint(mev_addresses)
+            && outbids(&self.info.gas_details, &victim.info.gas_details)
+            && !victim.swaps_along(&self.swaps).is_empty()
+    }
+
+    /// The swaps on the pools of `frontrun_swaps` in the same direction
+    fn swaps_along(&self, frontrun_swaps: &[NormalizedSwap]) -> Vec<NormalizedSwap> {
+        self.swaps
+            .iter()
+            .filter(|swap| frontrun_swaps.iter().any(|f| is_same_direction(f, swap)))
+            .cloned()
+            .collect()
+    }
+
+    /// Trades back through a pool of `frontrun_swaps` in the opposite direction
+    fn unwinds(&self, frontrun_swaps: &[NormalizedSwap]) -> bool {
+        self.swaps.iter().any(|swap| {
+            frontrun_swaps.iter().any(|f| {
+                f.pool == swap.pool
+                    && f.token_in.address == swap.token_out.address
+                    && f.token_out.address == swap.token_in.address
+            })
+        })
+    }
+}
+
+fn is_same_direction(a: &NormalizedSwap, b: &NormalizedSwap) -> bool {
+    a.pool == b.pool
+        && a.token_in.address == b.token_in.address
+        && a.token_out.address == b.token_out.address
+}
+
+/// The frontrun pays more for inclusion than the victim, either through a
+/// higher gas price or a direct payment to the builder
+pub(crate) fn outbids(frontrun: &GasDetails, victim: &GasDetails) -> bool {
+    frontrun.effective_gas_price > victim.effective_gas_price
+        || frontrun.coinbase_transfer.is_some_and(|bribe| bribe > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{db::token_info::TokenInfoWithAddress, order_flow::SubmissionChannel};
+    use reth_primitives::B256;
+
+    use super::*;
+
+    fn swap(pool: u8, token_in: TokenInfoWithAddress, token_out: TokenInfoWithAddress) -> Action {
+        Action::Swap(NormalizedSwap {
+            pool: Address::with_last_byte(pool),
+            token_in,
+            token_out,
+            amount_in: Rational::from(1),
+            amount_out: Rational::from(1),
+            ..Default::default()
+        })
+    }
+
+    fn swap_tx(eoa: u8, gas_price: u128, swaps: Vec<Action>) -> SwapTx {
+        let gas_details = GasDetails { effective_gas_price: gas_price, ..Default::default() };
+        let info = TxInfo::new(
+            0,
+            0,
+            Address::with_last_byte(eoa),
+            None,
+            None,
+            B256::ZERO,
+            gas_details,
+            true,
+            false,
+            false,
+            SubmissionChannel::Public,
+            false,
+            None,
+            None,
+            vec![],
+        );
+
+        SwapTx::new(info, swaps).unwrap()
+    }
+
+    #[test]
+    fn test_frontrun_victim() {
+        let (weth, usdc) = (TokenInfoWithAddress::weth(), TokenInfoWithAddress::usdc());
+        let frontrun = swap_tx(1, 20, vec![swap(10, weth.clone(), usdc.clone())]);
+        let searcher = frontrun.info.collect_address_set_for_accounting();
+
+        let victim = swap_tx(2, 10, vec![swap(10, weth.clone(), usdc.clone())]);
+        assert!(frontrun.is_frontrun_of(&victim, &searcher));
+
+        // paid more for inclusion than the frontrun
+        let outbidding = swap_tx(2, 30, vec![swap(10, weth.clone(), usdc.clone())]);
+        assert!(!frontrun.is_frontrun_of(&outbidding, &searcher));
+
+        // trades the other way, or through another pool
+        let reverse = swap_tx(2, 10, vec![swap(10, usdc.clone(), weth.clone())]);
+        assert!(!frontrun.is_frontrun_of(&reverse, &searcher));
+        let other_pool = swap_tx(2, 10, vec![swap(11, weth.clone(), usdc.clone())]);
+        assert!(!frontrun.is_frontrun_of(&other_pool, &searcher));
+
+        // the searcher's own trade isn't a victim
+        let own = swap_tx(1, 10, vec![swap(10, weth, usdc)]);
+        assert!(!frontrun.is_frontrun_of(&own, &searcher));
+
+        assert!(reverse.unwinds(&frontrun.swaps));
+        assert!(!victim.unwinds(&frontrun.swaps));
+    }
+}
@@ -39,6 +39,10 @@ impl<DB: LibmdbxReader> Inspector for JitCexDex<'_, DB> {
         "JitCexDex"
     }
 
+    fn cache_id(&self) -> String {
+        format!("{}{}", self.get_id(), self.cex_dex.cache_id())
+    }
+
     fn get_quote_token(&self) -> Address {
         self.jit.utils.quote
     }
@@ -68,6 +72,7 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
             tracing::warn!("no cex trades for block");
             return vec![]
         }
+        let window = self.cex_dex.block_window(&metadata);
         // call inner to avoid metrics
         let jit_bundles = self.jit.inspect_block_inner(tree.clone(), metadata.clone());
         jit_bundles
@@ -80,6 +85,11 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                 let BundleData::Jit(jit) = jits.data else { return None };
                 let details = [jit.backrun_burn_gas_details, jit.frontrun_mint_gas_details];
                 let tx_info = tree.get_tx_info(jits.header.tx_hash, self.jit.utils.db)?;
+                // jits of a user operation are labelled by the operation's sender
+                let tx_info = tree
+                    .get_user_op_root(tx_info.tx_hash, jits.header.eoa)
+                    .map(|user_op| user_op.tx_info(&tx_info))
+                    .unwrap_or(tx_info);
 
                 if !tx_info.is_searcher_of_type_with_count_threshold(MevType::JitCexDex, 10) {
                     return None
@@ -165,6 +175,7 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                     tx_info.is_searcher_of_type(MevType::JitCexDex)
                         || tx_info.is_labelled_searcher_of_type(MevType::JitCexDex),
                     &tx_info,
+                    &window,
                 )?;
 
                 self.cex_dex.gas_accounting(
@@ -4,14 +4,13 @@ use alloy_primitives::{Address, B256};
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     collect_address_set_for_accounting,
     db::dex::PriceAt,
     mev::{Bundle, JitLiquidity, MevType},
-    normalized_actions::{
-        accounting::ActionAccounting, NormalizedBurn, NormalizedCollect, NormalizedMint,
-    },
-    ActionIter, BlockData, FastHashMap, FastHashSet, GasDetails, MultiBlockData, ToFloatNearest,
-    TreeSearchBuilder, TxInfo,
+    normalized_actions::{NormalizedBurn, NormalizedCollect, NormalizedMint},
+    ActionIter, BlockData, FastHashMap, FastHashSet, GasDetails, MultiBlockData, Protocol, Root,
+    ToFloatNearest, TreeSearchBuilder, TxInfo,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -19,8 +18,8 @@ use reth_primitives::TxHash;
 
 use super::types::{PossibleJit, PossibleJitWithInfo};
 use crate::{
-    shared_utils::SharedInspectorUtils, Action, BlockTree, BundleData, Inspector, Metadata,
-    MAX_PROFIT,
+    shared_utils::SharedInspectorUtils, Action, BlockTree, BundleData, DetectionLimits, Inspector,
+    Metadata,
 };
 
 pub struct JitInspector<'db, DB: LibmdbxReader> {
@@ -31,6 +30,11 @@ impl<'db, DB: LibmdbxReader> JitInspector<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
         Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for JitInspector<'_, DB> {
@@ -71,7 +75,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                     |PossibleJitWithInfo {
                          inner:
                              PossibleJit {
-                                 frontrun_txes, backrun_tx, executor_contract, victims, ..
+                                 eoa, frontrun_txes, backrun_tx, executor_contract, victims,
                              },
                          victim_info,
                          backrun,
@@ -79,8 +83,17 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                      }| {
                         let searcher_actions = self.get_searcher_actions(
                             frontrun_txes.iter().chain([backrun_tx].iter()),
+                            eoa,
                             tree.clone(),
                         );
+                        let mut searcher_info = front_runs
+                            .into_iter()
+                            .chain([backrun])
+                            .zip(&searcher_actions)
+                            .map(|(info, actions)| tree.attribute_to_user_op(info, actions))
+                            .collect::<Vec<_>>();
+                        let backrun = searcher_info.pop()?;
+                        let front_runs = searcher_info;
 
                         tracing::trace!(?frontrun_txes, ?backrun_tx, "checking if jit");
 
@@ -108,32 +121,36 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
         )
     }
 
+    /// The actions of each searcher transaction. If the searcher is the sender
+    /// of a user operation in it, only the actions of that operation
     fn get_searcher_actions<'a>(
         &self,
         i: impl Iterator<Item = &'a TxHash>,
+        eoa: Address,
         tree: Arc<BlockTree<Action>>,
     ) -> Vec<Vec<Action>> {
+        let search_args = TreeSearchBuilder::default().with_actions([
+            Action::is_mint,
+            Action::is_burn,
+            Action::is_transfer,
+            Action::is_eth_transfer,
+            Action::is_nested_action,
+        ]);
+
         i.map(|tx| {
+            let actions = match tree.get_user_op_root(*tx, eoa) {
+                Some(user_op) => user_op.collect(&search_args),
+                None => tree.clone().collect(tx, search_args.clone()).collect_vec(),
+            };
+
             self.utils
-                .flatten_nested_actions(
-                    tree.clone().collect(
-                        tx,
-                        TreeSearchBuilder::default().with_actions([
-                            Action::is_mint,
-                            Action::is_burn,
-                            Action::is_transfer,
-                            Action::is_eth_transfer,
-                            Action::is_nested_action,
-                        ]),
-                    ),
-                    &|actions| {
-                        actions.is_mint()
-                            || actions.is_burn()
-                            || actions.is_collect()
-                            || actions.is_transfer()
-                            || actions.is_eth_transfer()
-                    },
-                )
+                .flatten_nested_actions(actions.into_iter(), &|actions| {
+                    actions.is_mint()
+                        || actions.is_burn()
+                        || actions.is_collect()
+                        || actions.is_transfer()
+                        || actions.is_eth_transfer()
+                })
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<Vec<Action>>>()
@@ -150,7 +167,11 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                 .take(searcher_actions.len() - 1)
                 .all(|h| h.iter().any(|a| a.is_mint()));
 
-        let matching_eoas = frontrun_info.first()?.eoa == backrun_info.eoa;
+        let frontrun_info = frontrun_info.first()?;
+        let matching_eoas = frontrun_info.eoa == backrun_info.eoa
+            || frontrun_info
+                .linked_eoa_group()
+                .is_some_and(|group| backrun_info.linked_eoa_group() == Some(group));
         // ensure tokens match
         let f = searcher_actions.first()?;
         let Some(Action::Mint(mint)) = f.iter().find(|f| f.is_mint()) else { return Some(true) };
@@ -204,17 +225,20 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
 
         let mev_addresses: FastHashSet<Address> = collect_address_set_for_accounting(&info_set);
 
-        let deltas = rem
-            .into_iter()
-            .filter(|f| f.is_transfer() || f.is_eth_transfer())
-            .chain(
-                info_set
-                    .iter()
-                    .flat_map(|info| info.get_total_eth_value())
-                    .cloned()
-                    .map(Action::from),
-            )
-            .account_for_actions();
+        let tx_deltas = self.utils.calculate_token_deltas(
+            [],
+            rem.into_iter()
+                .filter(|f| f.is_transfer() || f.is_eth_transfer())
+                .chain(
+                    info_set
+                        .iter()
+                        .flat_map(|info| info.get_total_eth_value())
+                        .cloned()
+                        .map(Action::from),
+                ),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let deltas = tx_deltas.deltas;
 
         let (rev, mut has_dex_price) = if let Some(rev) = self.utils.get_deltas_usd(
             info_set.last()?.tx_index,
@@ -224,7 +248,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             metadata.clone(),
             true,
         ) {
-            (Some(rev), true)
+            (Some(rev), balanced)
         } else {
             (Some(Rational::ZERO), false)
         };
@@ -246,7 +270,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             .filter(|_| has_dex_price)
             .unwrap_or_default();
 
-        if profit >= MAX_PROFIT {
+        if profit >= self.utils.limits.max_profit_usd {
             has_dex_price = false;
             profit = Rational::ZERO;
         }
@@ -331,13 +355,15 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                     collect
                         .into_iter()
                         .map(|c| NormalizedBurn {
-                            recipient:   c.recipient,
+                            recipient: c.recipient,
                             trace_index: c.trace_index,
-                            protocol:    c.protocol,
-                            amount:      c.amount,
-                            token:       c.token,
-                            pool:        c.pool,
-                            from:        c.from,
+                            protocol: c.protocol,
+                            amount: c.amount,
+                            token: c.token,
+                            pool: c.pool,
+                            from: c.from,
+                            #[cfg(feature = "provenance")]
+                            provenance: None,
                         })
                         .collect_vec()
                 })
@@ -479,6 +505,19 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
         let mut duplicate_senders: FastHashMap<Address, B256> = FastHashMap::default();
         let mut possible_victims: FastHashMap<B256, Vec<B256>> = FastHashMap::default();
 
+        // jits split across EOAs of the same searcher are grouped by the EOA group
+        let linked_eoas = self.utils.linked_eoa_groups(
+            tree.tx_roots
+                .iter()
+                .map(|root| root.head.address)
+                .unique()
+                .collect(),
+        );
+        let mut duplicate_groups: FastHashMap<Address, B256> = FastHashMap::default();
+        let mut duplicate_user_op_senders: FastHashMap<Address, B256> = FastHashMap::default();
+        let mut v4_pools: FastHashMap<(Address, Address), B256> = FastHashMap::default();
+        let mut v4_set: FastHashMap<(Address, Address), PossibleJit> = FastHashMap::default();
+
         for root in iter {
             if root.get_root_action().is_revert() {
                 continue
@@ -551,6 +590,93 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                 }
             }
 
+            // user operations of the same sender in different bundler transactions
+            let user_op_senders = root
+                .user_op_roots()
+                .map(|user_op| user_op.get_from_address())
+                .unique()
+                .collect_vec();
+            for sender in user_op_senders {
+                let Some(prev_tx_hash) = duplicate_user_op_senders.insert(sender, root.tx_hash)
+                else {
+                    continue
+                };
+                let Some(frontrun_victims) = possible_victims.remove(&prev_tx_hash) else {
+                    continue
+                };
+
+                match set.entry(sender) {
+                    Entry::Vacant(e) => {
+                        e.insert(PossibleJit {
+                            eoa:               sender,
+                            frontrun_txes:     vec![prev_tx_hash],
+                            backrun_tx:        root.tx_hash,
+                            executor_contract: sender,
+                            victims:           vec![frontrun_victims],
+                        });
+                    }
+                    Entry::Occupied(mut o) => {
+                        let jit = o.get_mut();
+                        jit.frontrun_txes.push(prev_tx_hash);
+                        jit.backrun_tx = root.tx_hash;
+                        jit.victims.push(frontrun_victims);
+                    }
+                }
+            }
+
+            if let Some(group) = linked_eoas.get(&root.head.address) {
+                if let Some(prev_tx_hash) = duplicate_groups.insert(*group, root.tx_hash) {
+                    if let Some(frontrun_victims) = possible_victims.remove(&prev_tx_hash) {
+                        match set.entry(*group) {
+                            Entry::Vacant(e) => {
+                                e.insert(PossibleJit {
+                                    eoa:               root.head.address,
+                                    frontrun_txes:     vec![prev_tx_hash],
+                                    backrun_tx:        root.tx_hash,
+                                    executor_contract: root.get_to_address(),
+                                    victims:           vec![frontrun_victims],
+                                });
+                            }
+                            Entry::Occupied(mut o) => {
+                                let jit = o.get_mut();
+                                jit.frontrun_txes.push(prev_tx_hash);
+                                jit.backrun_tx = root.tx_hash;
+                                jit.victims.push(frontrun_victims);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // V4 liquidity is added and removed through the pool manager, usually via a
+            // hook or a shared position manager rather than the searcher's contract, so
+            // these jits are grouped by the sender and the pool id instead
+            for pool in Self::v4_liquidity_pools(root) {
+                let key = (root.head.address, pool);
+                let Some(prev_tx_hash) = v4_pools.insert(key, root.tx_hash) else { continue };
+                let Some(frontrun_victims) = possible_victims.remove(&prev_tx_hash) else {
+                    continue
+                };
+
+                match v4_set.entry(key) {
+                    Entry::Vacant(e) => {
+                        e.insert(PossibleJit {
+                            eoa:               root.head.address,
+                            frontrun_txes:     vec![prev_tx_hash],
+                            backrun_tx:        root.tx_hash,
+                            executor_contract: root.get_to_address(),
+                            victims:           vec![frontrun_victims],
+                        });
+                    }
+                    Entry::Occupied(mut o) => {
+                        let jit = o.get_mut();
+                        jit.frontrun_txes.push(prev_tx_hash);
+                        jit.backrun_tx = root.tx_hash;
+                        jit.victims.push(frontrun_victims);
+                    }
+                }
+            }
+
             // Now, for each existing entry in possible_victims, we add the current
             // transaction hash as a potential victim, if it is not the same as
             // the key (which represents another transaction hash)
@@ -561,7 +687,8 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             possible_victims.insert(root.tx_hash, vec![]);
         }
 
-        let set = Itertools::unique(set.into_values())
+        let set = Itertools::unique(set.into_values().chain(v4_set.into_values()))
+            .filter(|jit| address_filter().allows(jit.eoa, Some(jit.executor_contract)))
             .flat_map(Self::partition_into_gaps)
             .collect::<Vec<_>>();
 
@@ -608,10 +735,24 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             .collect_vec()
     }
 
+    /// The V4 pools the tx added liquidity to or removed it from. V4 pools
+    /// are addressed by their pool id
+    fn v4_liquidity_pools(root: &Root<Action>) -> Vec<Address> {
+        root.collect(&TreeSearchBuilder::default().with_actions([Action::is_mint, Action::is_burn]))
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::Mint(mint) if mint.protocol == Protocol::UniswapV4 => Some(mint.pool),
+                Action::Burn(burn) if burn.protocol == Protocol::UniswapV4 => Some(burn.pool),
+                _ => None,
+            })
+            .unique()
+            .collect()
+    }
+
     fn get_bribes(&self, price: Arc<Metadata>, gas: &[GasDetails]) -> Rational {
         let bribe = gas.iter().map(|gas| gas.gas_paid()).sum::<u128>();
 
-        price.get_gas_price_usd(bribe, self.utils.quote)
+        self.utils.get_gas_price_usd(bribe, &price)
     }
 
     fn partition_into_gaps(ps: PossibleJit) -> Vec<PossibleJit> {
@@ -3,17 +3,21 @@ use std::sync::Arc;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     db::dex::PriceAt,
     mev::{Bundle, BundleData, Liquidation, MevType},
-    normalized_actions::{accounting::ActionAccounting, Action},
-    ActionIter, BlockData, FastHashSet, MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
+    normalized_actions::{
+        accounting::{apply_delta, AddressDeltas},
+        Action, NormalizedLiquidation,
+    },
+    ActionIter, BlockData, FastHashSet, MultiBlockData, Protocol, ToFloatNearest,
+    TreeSearchBuilder, TxInfo,
 };
 use itertools::multizip;
 use malachite::{num::basic::traits::Zero, Rational};
 use reth_primitives::{b256, Address};
 
-use super::MAX_PROFIT;
-use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
 
 pub struct LiquidationInspector<'db, DB: LibmdbxReader> {
     utils: SharedInspectorUtils<'db, DB>,
@@ -23,6 +27,11 @@ impl<'db, DB: LibmdbxReader> LiquidationInspector<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
         Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for LiquidationInspector<'_, DB> {
@@ -55,11 +64,14 @@ impl<DB: LibmdbxReader> Inspector for LiquidationInspector<'_, DB> {
 
             multizip((liq, tx_info))
                 .filter_map(|(liq, info)| {
-                    let info = info?;
                     let actions = self
                         .utils
                         .flatten_nested_actions_default(liq.into_iter())
                         .collect::<Vec<_>>();
+                    let info = tree.attribute_to_user_op(info?, &actions);
+                    if !address_filter().allows_tx(&info) {
+                        return None
+                    }
 
                     self.calculate_liquidation(info, metadata.clone(), actions)
                 })
@@ -91,11 +103,19 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
 
         let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
 
-        let deltas = actions
-            .into_iter()
-            .chain(info.get_total_eth_value().iter().cloned().map(Action::from))
-            .filter(|a| a.is_eth_transfer() || a.is_transfer())
-            .account_for_actions();
+        let tx_deltas = self.utils.calculate_token_deltas(
+            &swaps,
+            actions
+                .into_iter()
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from))
+                .filter(|a| a.is_eth_transfer() || a.is_transfer()),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let mut deltas = tx_deltas.deltas;
+
+        liqs.iter()
+            .filter(|liq| liq.protocol == Protocol::MakerClipper)
+            .for_each(|liq| apply_vat_settlement(liq, &mut deltas));
 
         let (rev, mut has_dex_price) = if let Some(rev) = self.utils.get_deltas_usd(
             info.tx_index,
@@ -105,20 +125,23 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
             metadata.clone(),
             false,
         ) {
-            (Some(rev), true)
+            (Some(rev), balanced)
         } else {
             (Some(Rational::ZERO), false)
         };
 
-        let gas_finalized =
-            metadata.get_gas_price_usd(info.gas_details.gas_paid(), self.utils.quote);
+        let gas_finalized = self
+            .utils
+            .get_gas_price_usd(info.gas_details.gas_paid(), &metadata);
 
         let mut profit_usd = rev
             .map(|rev| rev - &gas_finalized)
             .filter(|_| has_dex_price)
             .unwrap_or_default();
 
-        if profit_usd >= MAX_PROFIT || profit_usd <= -MAX_PROFIT {
+        if profit_usd >= self.utils.limits.max_profit_usd
+            || profit_usd <= -&self.utils.limits.max_profit_usd
+        {
             has_dex_price = false;
             profit_usd = Rational::ZERO;
         }
@@ -144,28 +167,107 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
         );
 
         let new_liquidation = Liquidation {
-            block_number:        metadata.block_num,
-            liquidation_tx_hash: info.tx_hash,
-            trigger:             b256!(),
-            liquidation_swaps:   swaps,
-            liquidations:        liqs,
-            gas_details:         info.gas_details,
+            block_number:         metadata.block_num,
+            liquidation_tx_hash:  info.tx_hash,
+            trigger:              b256!(),
+            liquidation_swaps:    swaps,
+            liquidations:         liqs,
+            gas_details:          info.gas_details,
+            // read from the lending protocols once the block is inspected
+            liquidation_contexts: vec![],
         };
 
         Some(Bundle { header, data: BundleData::Liquidation(new_liquidation) })
     }
 }
 
+/// Maker auctions settle inside the vat, the collateral bought and the DAI paid
+/// for it only show up as transfers if they are moved out of the vat in the
+/// same tx. If the collateral stays in the vat, the liquidator is credited the
+/// collateral and debited the DAI, so both are valued at the dex prices of the
+/// auction block.
+fn apply_vat_settlement(liquidation: &NormalizedLiquidation, deltas: &mut AddressDeltas) {
+    let collateral = liquidation.collateral_asset.address;
+    if deltas
+        .get(&liquidation.liquidator)
+        .is_some_and(|tokens| tokens.contains_key(&collateral))
+    {
+        return
+    }
+
+    apply_delta(
+        liquidation.liquidator,
+        collateral,
+        liquidation.liquidated_collateral.clone(),
+        deltas,
+    );
+    apply_delta(
+        liquidation.liquidator,
+        liquidation.debt_asset.address,
+        -liquidation.covered_debt.clone(),
+        deltas,
+    );
+}
+
 #[cfg(test)]
 mod tests {
 
-    use alloy_primitives::hex;
+    use alloy_primitives::{hex, Address};
+    use brontes_types::{
+        db::token_info::TokenInfoWithAddress, normalized_actions::NormalizedLiquidation,
+        FastHashMap, Protocol,
+    };
+    use malachite::Rational;
 
+    use super::apply_vat_settlement;
     use crate::{
         test_utils::{InspectorTestUtils, InspectorTxRunConfig, USDC_ADDRESS},
         Inspectors,
     };
 
+    fn maker_take(liquidator: Address, collateral: Address, dai: Address) -> NormalizedLiquidation {
+        NormalizedLiquidation {
+            protocol: Protocol::MakerClipper,
+            liquidator,
+            collateral_asset: TokenInfoWithAddress { address: collateral, ..Default::default() },
+            debt_asset: TokenInfoWithAddress { address: dai, ..Default::default() },
+            covered_debt: Rational::from(3000),
+            liquidated_collateral: Rational::from(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_maker_take_settles_in_vat() {
+        let (liquidator, collateral, dai) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let mut deltas = FastHashMap::default();
+
+        apply_vat_settlement(&maker_take(liquidator, collateral, dai), &mut deltas);
+
+        let liquidator_deltas = &deltas[&liquidator];
+        assert_eq!(liquidator_deltas[&collateral], Rational::from(2));
+        assert_eq!(liquidator_deltas[&dai], Rational::from(-3000));
+    }
+
+    #[test]
+    fn test_maker_take_collateral_moved_out_of_vat() {
+        let (liquidator, collateral, dai) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        // the collateral was exited, so the transfers already account for it
+        let mut deltas = FastHashMap::default();
+        deltas
+            .entry(liquidator)
+            .or_default()
+            .insert(collateral, Rational::from(2));
+
+        apply_vat_settlement(&maker_take(liquidator, collateral, dai), &mut deltas);
+
+        let liquidator_deltas = &deltas[&liquidator];
+        assert_eq!(liquidator_deltas[&collateral], Rational::from(2));
+        assert!(!liquidator_deltas.contains_key(&dai));
+    }
+
     #[brontes_macros::test]
     async fn test_aave_v3_liquidation() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 6.0).await;
@@ -1,8 +1,12 @@
+pub mod aggregator_arb;
+pub mod approval_race;
 pub mod atomic_arb;
 pub mod cex_dex;
+pub mod frontrun;
 
 pub mod jit;
 pub mod liquidations;
+pub mod nft_arb;
 pub mod sandwich;
 pub mod searcher_activity;
 pub mod shared_utils;
@@ -10,3 +14,27 @@ pub mod shared_utils;
 use malachite::Rational;
 /// Jokes for testing cur
 pub(crate) const MAX_PROFIT: Rational = Rational::const_from_unsigned(500_000_000);
+
+/// Cutoffs the inspectors use to tell mev apart from noise and mispricing.
+/// They default to the inspectors' own heuristics and, unlike the thresholds
+/// of the run config, can be loosened to detect more.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionLimits {
+    /// Larger profits, or losses, are taken to be a pricing error
+    pub max_profit_usd:               Rational,
+    /// Usd volume the victims of a frontrun have to trade through its pools
+    pub min_victim_volume_usd:        Rational,
+    /// Cex-dex bundles a searcher needs for its unprofitable trades to still
+    /// count as cex-dex
+    pub min_cex_dex_searcher_bundles: u64,
+}
+
+impl Default for DetectionLimits {
+    fn default() -> Self {
+        Self {
+            max_profit_usd:               MAX_PROFIT,
+            min_victim_volume_usd:        frontrun::MIN_VICTIM_VOLUME_USD,
+            min_cex_dex_searcher_bundles: cex_dex::quotes::FILTER_THRESHOLD,
+        }
+    }
+}
@@ -0,0 +1,479 @@
+//! Nft mev: atomic arbs that buy an nft on one marketplace and sell it on
+//! another within the same tx, and sandwiches of nft sweeps, where a searcher
+//! buys into a collection ahead of the txs sweeping it and sells after them.
+//! The nfts only match up the legs, the profit is the change of the searcher's
+//! balances of the payment tokens, priced against their dex price.
+use std::sync::Arc;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    address_filter::address_filter,
+    db::dex::PriceAt,
+    mev::{AtomicArb, AtomicArbType, Bundle, BundleData, MevType, Sandwich},
+    normalized_actions::{
+        accounting::AddressDeltas, Action, NftFill, NormalizedNftTrade, NormalizedSwap,
+    },
+    BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase, TreeCollector,
+    TreeSearchBuilder, TxInfo,
+};
+use itertools::Itertools;
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::{Address, U256};
+
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
+
+pub struct NftArbInspector<'db, DB: LibmdbxReader> {
+    utils: SharedInspectorUtils<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> NftArbInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
+    }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for NftArbInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    fn get_id(&self) -> &str {
+        "NftArb"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let BlockData { metadata, tree } = data.get_most_recent_block();
+
+        let execution = || {
+            let txs = tree
+                .clone()
+                .collect_all(TreeSearchBuilder::default().with_actions([
+                    is_nft_trade,
+                    Action::is_transfer,
+                    Action::is_eth_transfer,
+                ]))
+                .t_full_map(|(tree, v)| {
+                    let (tx_hashes, v): (Vec<_>, Vec<_>) = v.unzip();
+                    (tree.get_tx_info_batch(&tx_hashes, self.utils.db), v.into_iter())
+                })
+                .into_zip()
+                .filter_map(|(info, actions)| NftTx::new(info??, actions?))
+                .filter(|tx| address_filter().allows_tx(&tx.info))
+                .sorted_by_key(|tx| tx.info.tx_index)
+                .collect_vec();
+
+            txs.iter()
+                .filter_map(|tx| self.process_atomic_arb(tx, metadata.clone()))
+                .chain(self.process_sweep_sandwiches(&txs, metadata.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        self.utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::AtomicArb, execution))
+            .unwrap_or_else(&execution)
+    }
+}
+
+impl<DB: LibmdbxReader> NftArbInspector<'_, DB> {
+    fn process_atomic_arb(&self, tx: &NftTx, metadata: Arc<Metadata>) -> Option<Bundle> {
+        let mev_addresses = tx.info.collect_address_set_for_accounting();
+        if tx
+            .nfts(&mev_addresses, Side::Buy)
+            .is_disjoint(&tx.nfts(&mev_addresses, Side::Sell))
+        {
+            return None
+        }
+        tracing::trace!(?tx.info, "trying nft arb");
+
+        let (deltas, profit, has_dex_price) =
+            self.searcher_profit(&[tx], &mev_addresses, PriceAt::Average, &metadata);
+        if !is_profitable(&tx.info, &profit, has_dex_price) {
+            self.utils.get_metrics().inspect(|m| {
+                m.branch_filtering_trigger(MevType::AtomicArb, "nft_arb_unprofitable")
+            });
+            return None
+        }
+
+        let info = &tx.info;
+        let backrun = AtomicArb {
+            block_number: metadata.block_num,
+            // an nft arb takes a mispriced listing or bid, there is no tx that
+            // moved a price to backrun
+            trigger_tx:   info.tx_hash,
+            tx_hash:      info.tx_hash,
+            gas_details:  info.gas_details,
+            swaps:        tx.searcher_swaps(&mev_addresses),
+            arb_type:     AtomicArbType::Nft,
+        };
+
+        let header = self.utils.build_bundle_header(
+            vec![deltas],
+            vec![info.tx_hash],
+            info,
+            profit.to_float(),
+            &[info.gas_details],
+            metadata.clone(),
+            MevType::AtomicArb,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &metadata,
+                )
+            },
+        );
+
+        Some(Bundle { header, data: BundleData::AtomicArb(backrun) })
+    }
+
+    /// A searcher buys into collections and then sells into the same
+    /// collections from a later tx of the same eoa, with other txs buying into
+    /// the collections in between
+    fn process_sweep_sandwiches(&self, txs: &[NftTx], metadata: Arc<Metadata>) -> Vec<Bundle> {
+        let mut used: FastHashSet<usize> = FastHashSet::default();
+
+        txs.iter()
+            .enumerate()
+            .filter_map(|(i, frontrun)| {
+                if used.contains(&i) {
+                    return None
+                }
+                let mut mev_addresses = frontrun.info.collect_address_set_for_accounting();
+                let collections = frontrun.collections(&mev_addresses, Side::Buy);
+                if collections.is_empty() {
+                    return None
+                }
+
+                let (j, backrun) = txs.iter().enumerate().skip(i + 1).find(|(_, tx)| {
+                    tx.info.eoa == frontrun.info.eoa
+                        && !tx
+                            .collections(&mev_addresses, Side::Sell)
+                            .is_disjoint(&collections)
+                })?;
+                mev_addresses.extend(backrun.info.collect_address_set_for_accounting());
+
+                let victims = txs[i + 1..j]
+                    .iter()
+                    .filter(|tx| !tx.victim_swaps(&mev_addresses, &collections).is_empty())
+                    .collect_vec();
+                if victims.is_empty() {
+                    return None
+                }
+
+                let bundle = self.build_sandwich(
+                    frontrun,
+                    victims,
+                    backrun,
+                    &mev_addresses,
+                    &collections,
+                    metadata.clone(),
+                )?;
+                used.extend([i, j]);
+
+                Some(bundle)
+            })
+            .collect()
+    }
+
+    fn build_sandwich(
+        &self,
+        frontrun: &NftTx,
+        victims: Vec<&NftTx>,
+        backrun: &NftTx,
+        mev_addresses: &FastHashSet<Address>,
+        collections: &FastHashSet<Address>,
+        metadata: Arc<Metadata>,
+    ) -> Option<Bundle> {
+        tracing::trace!(?frontrun.info, ?backrun.info, "trying nft sweep sandwich");
+
+        let (deltas, profit, has_dex_price) =
+            self.searcher_profit(&[frontrun, backrun], mev_addresses, PriceAt::After, &metadata);
+        if !is_profitable(&backrun.info, &profit, has_dex_price) {
+            self.utils.get_metrics().inspect(|m| {
+                m.branch_filtering_trigger(MevType::Sandwich, "nft_sandwich_unprofitable")
+            });
+            return None
+        }
+
+        let sandwich = Sandwich {
+            block_number:             metadata.block_num,
+            frontrun_tx_hash:         vec![frontrun.info.tx_hash],
+            frontrun_swaps:           vec![frontrun.searcher_swaps(mev_addresses)],
+            frontrun_gas_details:     vec![frontrun.info.gas_details],
+            victim_swaps_tx_hashes:   vec![victims.iter().map(|v| v.info.tx_hash).collect()],
+            victim_swaps:             victims
+                .iter()
+                .map(|v| v.victim_swaps(mev_addresses, collections))
+                .collect(),
+            victim_swaps_gas_details: victims.iter().map(|v| v.info.gas_details).collect(),
+            backrun_tx_hash:          vec![backrun.info.tx_hash],
+            backrun_swaps:            vec![backrun.searcher_swaps(mev_addresses)],
+            backrun_gas_details:      vec![backrun.info.gas_details],
+        };
+
+        let header = self.utils.build_bundle_header(
+            vec![deltas],
+            vec![frontrun.info.tx_hash, backrun.info.tx_hash],
+            &backrun.info,
+            profit.to_float(),
+            &[frontrun.info.gas_details, backrun.info.gas_details],
+            metadata.clone(),
+            MevType::Sandwich,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    backrun.info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &metadata,
+                )
+            },
+        );
+
+        Some(Bundle { header, data: BundleData::Sandwich(sandwich) })
+    }
+
+    /// Deltas of the txs and the usd profit of the searcher after gas. The
+    /// transfers of the traded collections are left out, nfts can't be
+    /// priced.
+    fn searcher_profit(
+        &self,
+        txs: &[&NftTx],
+        mev_addresses: &FastHashSet<Address>,
+        at: PriceAt,
+        metadata: &Arc<Metadata>,
+    ) -> (AddressDeltas, Rational, bool) {
+        let collections: FastHashSet<Address> = txs
+            .iter()
+            .flat_map(|tx| tx.fills().map(|fill| fill.collection))
+            .collect();
+
+        let tx_deltas = self.utils.calculate_token_deltas(
+            [],
+            txs.iter().flat_map(|tx| {
+                tx.transfers
+                    .iter()
+                    .filter(|action| {
+                        action
+                            .try_transfer_ref()
+                            .map_or(true, |t| !collections.contains(&t.token.address))
+                    })
+                    .cloned()
+                    .chain(
+                        tx.info
+                            .get_total_eth_value()
+                            .iter()
+                            .cloned()
+                            .map(Action::from),
+                    )
+            }),
+        );
+        let balanced = tx_deltas.is_balanced();
+        let deltas = tx_deltas.deltas;
+
+        let last = txs.last().expect("profit of no txs");
+        let rev = self.utils.get_deltas_usd(
+            last.info.tx_index,
+            at,
+            mev_addresses,
+            &deltas,
+            metadata.clone(),
+            false,
+        );
+        let mut has_dex_price = rev.is_some() && balanced;
+
+        let gas_usd = txs.iter().fold(Rational::ZERO, |gas, tx| {
+            gas + self
+                .utils
+                .get_gas_price_usd(tx.info.gas_details.gas_paid(), &metadata)
+        });
+        let mut profit = rev.map(|rev| rev - gas_usd).unwrap_or_default();
+
+        if profit >= self.utils.limits.max_profit_usd {
+            has_dex_price = false;
+            profit = Rational::ZERO;
+        }
+
+        (deltas, profit, has_dex_price)
+    }
+}
+
+/// Without prices a searcher flipping nfts can't be told apart from a
+/// collector, so only trades through a searcher contract are kept
+fn is_profitable(info: &TxInfo, profit: &Rational, has_dex_price: bool) -> bool {
+    if has_dex_price {
+        *profit > Rational::ZERO
+    } else {
+        info.mev_contract.is_some()
+    }
+}
+
+fn is_nft_trade(action: &Action) -> bool {
+    action.try_custom_ref::<NormalizedNftTrade>().is_some()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+/// A tx that traded nfts, with its nft trades and its token transfers
+struct NftTx {
+    info:      TxInfo,
+    trades:    Vec<NormalizedNftTrade>,
+    transfers: Vec<Action>,
+}
+
+impl NftTx {
+    fn new(info: TxInfo, actions: Vec<Action>) -> Option<Self> {
+        let (trades, transfers): (Vec<_>, Vec<_>) = actions.into_iter().partition(is_nft_trade);
+        let trades = trades
+            .iter()
+            .filter_map(|trade| trade.try_custom_ref::<NormalizedNftTrade>().cloned())
+            .collect_vec();
+
+        (!trades.is_empty()).then_some(Self { info, trades, transfers })
+    }
+
+    fn fills(&self) -> impl Iterator<Item = &NftFill> + '_ {
+        self.trades.iter().flat_map(|trade| trade.fills.iter())
+    }
+
+    /// The (collection, token id) of the nfts the addresses bought or sold
+    fn nfts(&self, addresses: &FastHashSet<Address>, side: Side) -> FastHashSet<(Address, U256)> {
+        self.fills()
+            .filter(|fill| match side {
+                Side::Buy => addresses.contains(&fill.buyer),
+                Side::Sell => addresses.contains(&fill.seller),
+            })
+            .map(|fill| (fill.collection, fill.token_id))
+            .collect()
+    }
+
+    fn collections(&self, addresses: &FastHashSet<Address>, side: Side) -> FastHashSet<Address> {
+        self.nfts(addresses, side)
+            .into_iter()
+            .map(|(collection, _)| collection)
+            .collect()
+    }
+
+    /// The fills of the searcher, ordered as they were traded
+    fn searcher_swaps(&self, mev_addresses: &FastHashSet<Address>) -> Vec<NormalizedSwap> {
+        self.trades
+            .iter()
+            .flat_map(|trade| {
+                trade.fills.iter().filter_map(|fill| {
+                    [fill.buyer, fill.seller]
+                        .into_iter()
+                        .find(|address| mev_addresses.contains(address))
+                        .and_then(|address| trade.fill_as_swap(fill, address))
+                })
+            })
+            .collect()
+    }
+
+    /// Buys into the collections by anyone but the searcher
+    fn victim_swaps(
+        &self,
+        mev_addresses: &FastHashSet<Address>,
+        collections: &FastHashSet<Address>,
+    ) -> Vec<NormalizedSwap> {
+        self.trades
+            .iter()
+            .flat_map(|trade| {
+                trade
+                    .fills
+                    .iter()
+                    .filter(|fill| {
+                        collections.contains(&fill.collection)
+                            && !mev_addresses.contains(&fill.buyer)
+                    })
+                    .filter_map(|fill| trade.fill_as_swap(fill, fill.buyer))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::token_info::TokenInfoWithAddress, order_flow::SubmissionChannel, GasDetails, Protocol,
+    };
+    use reth_primitives::B256;
+
+    use super::*;
+
+    fn fill(seller: u8, buyer: u8, collection: u8, token_id: u64) -> NftFill {
+        NftFill {
+            seller:        Address::with_last_byte(seller),
+            buyer:         Address::with_last_byte(buyer),
+            collection:    Address::with_last_byte(collection),
+            token_id:      U256::from(token_id),
+            amount:        U256::from(1),
+            payment_token: TokenInfoWithAddress::weth(),
+            price:         Rational::from(1),
+        }
+    }
+
+    fn nft_tx(fills: Vec<NftFill>) -> NftTx {
+        let trade = NormalizedNftTrade { protocol: Protocol::Seaport, fills, ..Default::default() };
+        let info = TxInfo::new(
+            0,
+            0,
+            Address::with_last_byte(1),
+            None,
+            None,
+            B256::ZERO,
+            GasDetails::default(),
+            true,
+            false,
+            false,
+            SubmissionChannel::Public,
+            false,
+            None,
+            None,
+            vec![],
+        );
+
+        NftTx::new(info, vec![Action::Custom(trade.into()), Action::Revert]).unwrap()
+    }
+
+    #[test]
+    fn test_nft_legs() {
+        let searcher: FastHashSet<Address> = [Address::with_last_byte(1)].into_iter().collect();
+
+        // buys token 7 from 2 and sells it to 3
+        let arb = nft_tx(vec![fill(2, 1, 10, 7), fill(1, 3, 10, 7)]);
+        assert_eq!(arb.transfers, vec![Action::Revert]);
+        assert!(!arb
+            .nfts(&searcher, Side::Buy)
+            .is_disjoint(&arb.nfts(&searcher, Side::Sell)));
+        assert_eq!(arb.searcher_swaps(&searcher).len(), 2);
+
+        // sells a different token of the collection than it bought
+        let flip = nft_tx(vec![fill(2, 1, 10, 7), fill(1, 3, 10, 8)]);
+        assert!(flip
+            .nfts(&searcher, Side::Buy)
+            .is_disjoint(&flip.nfts(&searcher, Side::Sell)));
+        assert_eq!(flip.collections(&searcher, Side::Buy), flip.collections(&searcher, Side::Sell));
+
+        let collections = flip.collections(&searcher, Side::Buy);
+        let sweep = nft_tx(vec![fill(2, 4, 10, 9), fill(2, 4, 11, 1)]);
+        assert_eq!(sweep.victim_swaps(&searcher, &collections).len(), 1);
+        assert_eq!(arb.victim_swaps(&searcher, &collections).len(), 1);
+    }
+}
@@ -9,11 +9,10 @@ mod types;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     db::dex::PriceAt,
     mev::{Bundle, BundleData, MevType, Sandwich},
-    normalized_actions::{
-        accounting::ActionAccounting, Action, NormalizedSwap, NormalizedTransfer,
-    },
+    normalized_actions::{Action, NormalizedSwap, NormalizedTransfer},
     tree::{collect_address_set_for_accounting, BlockTree, GasDetails},
     ActionIter, BlockData, FastHashMap, FastHashSet, IntoZipTree, MultiBlockData, ToFloatNearest,
     TreeBase, TreeCollector, TreeIter, TreeSearchBuilder, TxInfo, UnzipPadded,
@@ -21,10 +20,10 @@ use brontes_types::{
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
 use reth_primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
 use types::{PossibleSandwich, PossibleSandwichWithTxInfo};
 
-use super::MAX_PROFIT;
-use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
 
 type GroupedVictims<'a> = HashMap<Address, Vec<&'a (Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>>;
 
@@ -36,13 +35,51 @@ type VictimSetActions = Option<Vec<Vec<(Vec<NormalizedSwap>, Vec<NormalizedTrans
 const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
 const MAX_NON_SWAP_FRONTRUN: Rational = Rational::const_from_unsigned(5000);
 
+/// How the legs of a sandwich were matched with its victims
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SandwichLinkage {
+    /// The victims traded on the pools of the frontrun and backrun
+    Pool,
+    /// The frontrun and backrun traded the pair of the victims on different
+    /// pools
+    Pair,
+}
+
+/// How searcher transactions that aren't separated by victims are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SandwichMode {
+    /// Adjacent searcher transactions split the sandwich
+    #[default]
+    Standard,
+    /// Sequential frontruns before the first victim and sequential backruns
+    /// after the last victim are kept as legs of a single "big mac" sandwich.
+    /// The legs after the last victim are stored as backruns.
+    BigMac,
+}
+
 pub struct SandwichInspector<'db, DB: LibmdbxReader> {
     utils: SharedInspectorUtils<'db, DB>,
+    mode:  SandwichMode,
 }
 
 impl<'db, DB: LibmdbxReader> SandwichInspector<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
-        Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
+        Self {
+            utils: SharedInspectorUtils::new(quote, db, metrics),
+            mode:  SandwichMode::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: SandwichMode) -> Self {
+        self.mode = mode;
+        self
     }
 }
 
@@ -53,6 +90,13 @@ impl<DB: LibmdbxReader> Inspector for SandwichInspector<'_, DB> {
         "Sandwich"
     }
 
+    fn cache_id(&self) -> String {
+        match self.mode {
+            SandwichMode::Standard => self.get_id().to_string(),
+            mode => format!("{}{}", self.get_id(), mode),
+        }
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -111,11 +155,11 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         let PossibleSandwichWithTxInfo {
             inner:
                 PossibleSandwich {
+                    eoa,
                     possible_frontruns,
                     possible_backrun,
                     mev_executor_contract,
                     victims,
-                    ..
                 },
             victims_info,
             possible_frontruns_info,
@@ -133,24 +177,38 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             mev_executor_contract,
         )?;
 
+        let searcher_txes = possible_frontruns
+            .iter()
+            .copied()
+            .chain(std::iter::once(possible_backrun))
+            .collect::<Vec<_>>();
         let searcher_actions: Vec<Vec<Action>> = tree
             .clone()
-            .collect_txes(
-                possible_frontruns
-                    .iter()
-                    .copied()
-                    .chain(std::iter::once(possible_backrun))
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-                search_args.clone(),
-            )
-            .map(|actions| {
+            .collect_txes(searcher_txes.as_slice(), search_args.clone())
+            .zip(&searcher_txes)
+            .map(|(actions, tx_hash)| {
+                // a sandwich of a user operation sender only consists of its operations,
+                // not of the other operations of the bundles they were submitted in
+                let actions = tree
+                    .get_user_op_root(*tx_hash, eoa)
+                    .map(|user_op| user_op.collect(&search_args))
+                    .unwrap_or(actions);
+
                 self.utils
                     .flatten_nested_actions_default(actions.into_iter())
                     .collect_vec()
             })
             .collect::<Vec<_>>();
 
+        let mut searcher_info = possible_frontruns_info
+            .into_iter()
+            .chain(std::iter::once(possible_backrun_info))
+            .zip(&searcher_actions)
+            .map(|(info, actions)| tree.attribute_to_user_op(info, actions))
+            .collect::<Vec<_>>();
+        let possible_backrun_info = searcher_info.pop()?;
+        let possible_frontruns_info = searcher_info;
+
         let black_list: FastHashSet<Address> =
             collect_address_set_for_accounting(&possible_frontruns_info);
 
@@ -179,8 +237,9 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         black_list: FastHashSet<Address>,
         recusive: u8,
     ) -> Option<Vec<Bundle>> {
-        // if all of the sandwichers have the same eoa or the to address is an mev
-        // contract then we can continue. otherwise false positive
+        // if all of the sandwichers have the same eoa, are eoas of the same
+        // searcher or the to address is an mev contract then we can continue.
+        // otherwise false positive
         if !(possible_front_runs_info
             .iter()
             .chain(vec![&backrun_info])
@@ -191,7 +250,8 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 .map(|f| f.eoa)
                 .unique()
                 .count()
-                == 1)
+                == 1
+            || Self::is_linked_eoa_set(&possible_front_runs_info, &backrun_info))
         {
             tracing::debug!(target: "brontes_inspect::sandwich", "all sandwiches don't have same eoa and aren't all verified contracts");
             return None
@@ -229,13 +289,26 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
         let back_run_actions = searcher_actions.pop()?;
 
-        if !Self::has_pool_overlap(
+        let linkage = if Self::has_pool_overlap(
             &searcher_actions,
             &back_run_actions,
             &victim_actions,
             &victim_info,
             &black_list,
         ) {
+            Some(SandwichLinkage::Pool)
+        } else if Self::has_pair_overlap(
+            &searcher_actions,
+            &back_run_actions,
+            &victim_actions,
+            &victim_info,
+        ) {
+            Some(SandwichLinkage::Pair)
+        } else {
+            None
+        };
+
+        let Some(linkage) = linkage else {
             // if the current set of front-run victim back-runs is not classified
             // as a sandwich, we will recursively remove orders in both directions
             // to cover the full order-set to ensure that we don't miss any
@@ -252,7 +325,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 black_list,
                 recusive,
             )
-        }
+        };
 
         // if we reach this part of the code, we have found a sandwich and
         // are now going to collect the details for the given sandwich
@@ -262,7 +335,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             .into_iter()
             .collect_action_vec(Action::try_swaps_merged);
 
-        let front_run_swaps = searcher_actions
+        let mut front_run_swaps = searcher_actions
             .clone()
             .into_iter()
             .map(|action| {
@@ -272,13 +345,14 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             })
             .collect::<Vec<_>>();
 
-        let (frontrun_tx_hash, frontrun_gas_details): (Vec<_>, Vec<_>) = possible_front_runs_info
-            .clone()
-            .into_iter()
-            .map(|info| info.split_to_storage_info())
-            .unzip();
+        let (mut frontrun_tx_hash, mut frontrun_gas_details): (Vec<_>, Vec<_>) =
+            possible_front_runs_info
+                .clone()
+                .into_iter()
+                .map(|info| info.split_to_storage_info())
+                .unzip();
 
-        let (victim_swaps_tx_hashes, victim_swaps_gas_details): (Vec<_>, Vec<_>) = victim_info
+        let (mut victim_swaps_tx_hashes, victim_swaps_gas_details): (Vec<_>, Vec<_>) = victim_info
             .clone()
             .into_iter()
             .map(|info| {
@@ -294,25 +368,29 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             .map(|g| g.gas_paid())
             .sum::<u128>();
 
-        let gas_used = metadata.get_gas_price_usd(gas_used, self.utils.quote);
+        let gas_used = self.utils.get_gas_price_usd(gas_used, &metadata);
 
-        let searcher_deltas = searcher_actions
-            .into_iter()
-            .flatten()
-            .chain(back_run_actions)
-            .filter(|f| f.is_transfer() || f.is_eth_transfer())
-            .chain(
-                possible_front_runs_info
-                    .iter()
-                    .chain(vec![backrun_info.clone()].iter())
-                    .flat_map(|info| info.get_total_eth_value())
-                    .cloned()
-                    .map(Action::from),
-            )
-            .account_for_actions();
+        let searcher_deltas = self.utils.calculate_token_deltas(
+            front_run_swaps.iter().flatten().chain(&back_run_swaps),
+            searcher_actions
+                .into_iter()
+                .flatten()
+                .chain(back_run_actions)
+                .filter(|f| f.is_transfer() || f.is_eth_transfer())
+                .chain(
+                    possible_front_runs_info
+                        .iter()
+                        .chain(vec![backrun_info.clone()].iter())
+                        .flat_map(|info| info.get_total_eth_value())
+                        .cloned()
+                        .map(Action::from),
+                ),
+        );
 
-        // ensure valid pricing
-        let mut has_dex_price = true;
+        // ensure valid pricing, swaps that their transfers don't back can't be
+        // trusted to price the sandwich
+        let mut has_dex_price = searcher_deltas.is_balanced();
+        let searcher_deltas = searcher_deltas.deltas;
         for (swaps, info) in front_run_swaps.iter().zip(&possible_front_runs_info) {
             has_dex_price &= self.utils.valid_pricing(
                 metadata.clone(),
@@ -373,11 +451,19 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             .filter(|_| has_dex_price)
             .unwrap_or_default();
 
-        if profit_usd >= MAX_PROFIT {
+        if profit_usd >= self.utils.limits.max_profit_usd {
             has_dex_price = false;
             profit_usd = Rational::ZERO;
         }
 
+        // legs on different pools of the pair are only linked through the price
+        // impact of the frontrun, without a profit there is nothing tying them
+        // together
+        if linkage == SandwichLinkage::Pair && (!has_dex_price || profit_usd <= Rational::ZERO) {
+            tracing::debug!(target: "brontes_inspect::sandwich", "unprofitable cross pool sandwich");
+            return None
+        }
+
         // sus threshold
         if front_run_swaps.iter().flatten().count() == 0 && profit_usd > MAX_NON_SWAP_FRONTRUN {
             tracing::warn!("frontrun has no swaps");
@@ -423,6 +509,17 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
         let victim_swaps = victim_swaps.into_iter().map(|(s, _)| s).collect_vec();
 
+        let first_backrun =
+            Self::first_sequential_backrun(&victim_swaps_tx_hashes).min(frontrun_tx_hash.len());
+        victim_swaps_tx_hashes.truncate(first_backrun);
+
+        let mut backrun_tx_hash = frontrun_tx_hash.split_off(first_backrun);
+        let mut backrun_swaps = front_run_swaps.split_off(first_backrun);
+        let mut backrun_gas_details = frontrun_gas_details.split_off(first_backrun);
+        backrun_tx_hash.push(backrun_info.tx_hash);
+        backrun_swaps.push(back_run_swaps);
+        backrun_gas_details.push(backrun_info.gas_details);
+
         let sandwich = Sandwich {
             block_number: metadata.block_num,
             frontrun_tx_hash,
@@ -431,9 +528,9 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             victim_swaps_tx_hashes,
             victim_swaps_gas_details: victim_swaps_gas_details.into_iter().flatten().collect(),
             victim_swaps,
-            backrun_tx_hash: backrun_info.tx_hash,
-            backrun_swaps: back_run_swaps,
-            backrun_gas_details: backrun_info.gas_details,
+            backrun_tx_hash,
+            backrun_swaps,
+            backrun_gas_details,
         };
         tracing::debug!("{:#?}\n{:#?}", header, sandwich);
 
@@ -566,6 +663,11 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         for (i, (chunk_victim_actions, chunk_victim_info)) in
             victim_actions.iter().zip(victim_info).enumerate()
         {
+            // sequential legs of a big mac have no victims in between
+            if chunk_victim_info.is_empty() {
+                continue
+            }
+
             let chunk_front_run_swaps = &front_run_swaps[0..=i];
             let chunk_back_run_swaps = if f_swap_len > i + 1 {
                 let mut res = vec![];
@@ -613,6 +715,67 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         true
     }
 
+    /// Matches the legs at the token pair level, for sandwiches whose frontrun
+    /// and backrun trade on different pools of the pair, e.g. a frontrun on a
+    /// uni v3 pool and a backrun on the v2 pool. A victim is linked to the
+    /// frontrun if it swaps in the same direction on a pool the frontrun
+    /// moved, and the backrun has to reverse that swap on any pool of the
+    /// pair. As there is no shared pool, every victim has to be linked.
+    fn has_pair_overlap(
+        front_run_actions: &[Vec<Action>],
+        back_run_actions: &[Action],
+        victim_actions: &[Vec<(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>],
+        victim_info: &[Vec<TxInfo>],
+    ) -> bool {
+        let mut has_victims = false;
+
+        for (i, (chunk_victim_actions, chunk_victim_info)) in
+            victim_actions.iter().zip(victim_info).enumerate()
+        {
+            if chunk_victim_info.is_empty() {
+                continue
+            }
+
+            let front_run_swaps = front_run_actions[0..=i]
+                .iter()
+                .flatten()
+                .cloned()
+                .collect_action_vec(Action::try_swaps_merged);
+            let back_run_swaps = front_run_actions[i + 1..]
+                .iter()
+                .flatten()
+                .chain(back_run_actions)
+                .cloned()
+                .collect_action_vec(Action::try_swaps_merged);
+
+            let grouped_victims = itertools::Itertools::into_group_map(
+                chunk_victim_info
+                    .iter()
+                    .zip(chunk_victim_actions)
+                    .map(|(info, (swaps, _))| (info.eoa, swaps)),
+            );
+
+            let all_linked = grouped_victims.into_values().all(|swaps| {
+                swaps.into_iter().flatten().any(|victim| {
+                    front_run_swaps.iter().any(|front| {
+                        front.pool == victim.pool
+                            && front.token_in.address == victim.token_in.address
+                            && front.token_out.address == victim.token_out.address
+                    }) && back_run_swaps.iter().any(|back| {
+                        back.token_in.address == victim.token_out.address
+                            && back.token_out.address == victim.token_in.address
+                    })
+                })
+            });
+            if !all_linked {
+                return false
+            }
+            has_victims = true;
+        }
+
+        has_victims
+    }
+
     // for each victim eoa, ensure they are a victim of a frontrun and a backrun
     // either through a pool or overlapping tokens. We also ensure that
     // there exists at-least one sandwich
@@ -850,6 +1013,10 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
     ///
     /// The results from both functions are combined and deduplicated to form a
     /// comprehensive set of potential sandwich attacks.
+    ///
+    /// Sandwiches split across EOAs of the same searcher that don't share an
+    /// executor contract are found through
+    /// `get_possible_sandwich_linked_eoas`.
     fn get_possible_sandwich(
         &self,
         tree: Arc<BlockTree<Action>>,
@@ -864,10 +1031,27 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         let result_senders = get_possible_sandwich_duplicate_senders(tree_clone_for_senders);
         let result_contracts = get_possible_sandwich_duplicate_contracts(tree_clone_for_contracts);
 
+        let linked_eoas = self.utils.linked_eoa_groups(
+            tree.tx_roots
+                .iter()
+                .map(|root| root.head.address)
+                .unique()
+                .collect(),
+        );
+        let result_linked = get_possible_sandwich_linked_eoas(tree.clone(), &linked_eoas);
+        let result_user_ops = get_possible_sandwich_user_op_senders(tree.clone());
+
         // Combine and deduplicate results
-        let set = Itertools::unique(result_senders.into_iter().chain(result_contracts))
-            .flat_map(Self::partition_into_gaps)
-            .collect::<Vec<_>>();
+        let set = Itertools::unique(
+            result_senders
+                .into_iter()
+                .chain(result_contracts)
+                .chain(result_linked)
+                .chain(result_user_ops),
+        )
+        .filter(|ps| address_filter().allows(ps.eoa, Some(ps.mev_executor_contract)))
+        .flat_map(|ps| Self::partition_into_gaps(ps, self.mode))
+        .collect::<Vec<_>>();
 
         let tx_set = set
             .iter()
@@ -901,7 +1085,29 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             .collect_vec()
     }
 
-    fn partition_into_gaps(ps: PossibleSandwich) -> Vec<PossibleSandwich> {
+    /// Whether all frontruns and the backrun are sent by EOAs of the same
+    /// searcher
+    fn is_linked_eoa_set(frontrun_info: &[TxInfo], backrun_info: &TxInfo) -> bool {
+        let Some(group) = backrun_info.linked_eoa_group() else { return false };
+
+        frontrun_info
+            .iter()
+            .all(|info| info.linked_eoa_group() == Some(group))
+    }
+
+    /// Index of the first searcher leg after the last victim. The sequential
+    /// legs from there on, which only a big mac sandwich has, are backruns.
+    fn first_sequential_backrun(victims: &[Vec<B256>]) -> usize {
+        victims
+            .iter()
+            .rposition(|set| !set.is_empty())
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Splits the possible sandwich at the searcher transactions that aren't
+    /// separated by victims. In big mac mode, the leading and trailing ones are
+    /// kept as sequential frontruns and backruns of the sandwich.
+    fn partition_into_gaps(ps: PossibleSandwich, mode: SandwichMode) -> Vec<PossibleSandwich> {
         let PossibleSandwich {
             eoa,
             possible_frontruns,
@@ -913,8 +1119,17 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         let mut victim_sets = vec![];
         let mut last_partition = 0;
 
+        let splits_at = |i: usize| match mode {
+            SandwichMode::Standard => true,
+            SandwichMode::BigMac => {
+                victims.iter().take(i).any(|set| !set.is_empty())
+                    && victims.iter().skip(i + 1).any(|set| !set.is_empty())
+            }
+        };
+        let splits = (0..victims.len()).map(splits_at).collect_vec();
+
         victims.into_iter().enumerate().for_each(|(i, group_set)| {
-            if group_set.is_empty() {
+            if group_set.is_empty() && splits[i] {
                 results.push(PossibleSandwich {
                     eoa,
                     mev_executor_contract,
@@ -974,6 +1189,12 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 )
             })
             .try_fold(vec![], |mut acc, (victim_set, hashes)| {
+                // sequential big mac legs have no victims in between
+                if hashes.is_empty() {
+                    acc.push(vec![]);
+                    return Some(acc)
+                }
+
                 let tree = victim_set.tree();
                 let actions = victim_set
                     .map(|s| {
@@ -1068,6 +1289,116 @@ fn get_possible_sandwich_duplicate_senders(tree: Arc<BlockTree<Action>>) -> Vec<
     possible_sandwiches.into_values().collect()
 }
 
+/// Identifies potential sandwiches whose frontruns and backrun are sent from
+/// different EOAs of the same searcher, i.e. EOAs that share a funding parent
+/// or are known siblings. Splitting a sandwich across EOAs is a common way to
+/// evade grouping by sender, so the transactions are grouped by the EOA group
+/// instead.
+fn get_possible_sandwich_linked_eoas(
+    tree: Arc<BlockTree<Action>>,
+    linked_eoas: &FastHashMap<Address, Address>,
+) -> Vec<PossibleSandwich> {
+    if linked_eoas.is_empty() {
+        return vec![]
+    }
+
+    let mut duplicate_groups: FastHashMap<Address, B256> = FastHashMap::default();
+    let mut possible_victims: FastHashMap<B256, Vec<B256>> = FastHashMap::default();
+    let mut possible_sandwiches: FastHashMap<Address, PossibleSandwich> = FastHashMap::default();
+
+    for root in tree.tx_roots.iter() {
+        if root.get_root_action().is_revert() {
+            continue
+        }
+
+        if let Some(group) = linked_eoas.get(&root.head.address) {
+            if let Some(prev_tx_hash) = duplicate_groups.insert(*group, root.tx_hash) {
+                if let Some(frontrun_victims) = possible_victims.remove(&prev_tx_hash) {
+                    match possible_sandwiches.entry(*group) {
+                        Entry::Vacant(e) => {
+                            e.insert(PossibleSandwich {
+                                eoa:                   root.head.address,
+                                possible_frontruns:    vec![prev_tx_hash],
+                                possible_backrun:      root.tx_hash,
+                                mev_executor_contract: root.get_to_address(),
+                                victims:               vec![frontrun_victims],
+                            });
+                        }
+                        Entry::Occupied(mut o) => {
+                            let sandwich = o.get_mut();
+                            sandwich.possible_frontruns.push(prev_tx_hash);
+                            sandwich.possible_backrun = root.tx_hash;
+                            sandwich.victims.push(frontrun_victims);
+                        }
+                    }
+                }
+            }
+        }
+
+        for v in possible_victims.values_mut() {
+            v.push(root.tx_hash);
+        }
+
+        possible_victims.insert(root.tx_hash, vec![]);
+    }
+
+    possible_sandwiches.into_values().collect()
+}
+
+/// Identifies potential sandwiches whose frontruns and backrun are user
+/// operations of the same sender, submitted in different bundler
+/// transactions. The sender's smart account takes the place of both the EOA
+/// and the executor contract.
+fn get_possible_sandwich_user_op_senders(tree: Arc<BlockTree<Action>>) -> Vec<PossibleSandwich> {
+    let mut duplicate_senders: FastHashMap<Address, B256> = FastHashMap::default();
+    let mut possible_victims: FastHashMap<B256, Vec<B256>> = FastHashMap::default();
+    let mut possible_sandwiches: FastHashMap<Address, PossibleSandwich> = FastHashMap::default();
+
+    for root in tree.tx_roots.iter() {
+        if root.get_root_action().is_revert() {
+            continue
+        }
+
+        let senders = root
+            .user_op_roots()
+            .map(|user_op| user_op.get_from_address())
+            .unique()
+            .collect_vec();
+        for sender in senders {
+            let Some(prev_tx_hash) = duplicate_senders.insert(sender, root.tx_hash) else {
+                continue
+            };
+            let Some(frontrun_victims) = possible_victims.remove(&prev_tx_hash) else { continue };
+
+            match possible_sandwiches.entry(sender) {
+                Entry::Vacant(e) => {
+                    e.insert(PossibleSandwich {
+                        eoa:                   sender,
+                        possible_frontruns:    vec![prev_tx_hash],
+                        possible_backrun:      root.tx_hash,
+                        mev_executor_contract: sender,
+                        victims:               vec![frontrun_victims],
+                    });
+                }
+                Entry::Occupied(mut o) => {
+                    let sandwich = o.get_mut();
+                    sandwich.possible_frontruns.push(prev_tx_hash);
+                    sandwich.possible_backrun = root.tx_hash;
+                    sandwich.victims.push(frontrun_victims);
+                }
+            }
+        }
+
+        for v in possible_victims.values_mut() {
+            v.push(root.tx_hash);
+        }
+
+        possible_victims.insert(root.tx_hash, vec![]);
+    }
+
+    possible_sandwiches.into_values().collect()
+}
+
 /// This function iterates through the block tree to identify potential
 /// sandwiches by looking for a contract that is involved in multiple
 /// transactions within a block.
@@ -1139,7 +1470,12 @@ fn get_possible_sandwich_duplicate_contracts(
 mod tests {
 
     use alloy_primitives::hex;
-    use brontes_types::constants::{DAI_ADDRESS, USDT_ADDRESS, WETH_ADDRESS};
+    use brontes_core::LibmdbxReadWriter;
+    use brontes_types::{
+        constants::{DAI_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        order_flow::SubmissionChannel,
+    };
 
     use super::*;
     use crate::{
@@ -1253,12 +1589,109 @@ mod tests {
                         panic!("given bundle wasn't a sandwich");
                     };
                     assert!(sando.frontrun_tx_hash.len() == 2, "didn't find the big mac");
+                    assert_eq!(
+                        sando.backrun_tx_hash,
+                        vec![hex!(
+                            "fb2ef488bf7b6ad09accb126330837198b0857d2ea0052795af520d470eb5e1d"
+                        )
+                        .into()]
+                    );
                 })),
             )
             .await
             .unwrap();
     }
 
+    #[test]
+    fn test_big_mac_partition_keeps_sequential_legs() {
+        let tx = B256::with_last_byte;
+        // two frontruns, a victim and two backruns
+        let ps = PossibleSandwich {
+            eoa:                   Address::with_last_byte(1),
+            possible_frontruns:    vec![tx(1), tx(2), tx(4)],
+            possible_backrun:      tx(5),
+            mev_executor_contract: Address::with_last_byte(2),
+            victims:               vec![vec![], vec![tx(3)], vec![]],
+        };
+        let partition =
+            |mode| SandwichInspector::<LibmdbxReadWriter>::partition_into_gaps(ps.clone(), mode);
+
+        let standard = partition(SandwichMode::Standard)
+            .into_iter()
+            .find(|ps| !ps.victims.is_empty())
+            .unwrap();
+        assert_eq!(standard.possible_frontruns, vec![tx(2)]);
+        assert_eq!(standard.possible_backrun, tx(4));
+
+        assert_eq!(partition(SandwichMode::BigMac), vec![ps.clone()]);
+    }
+
+    #[test]
+    fn test_big_mac_trailing_legs_are_backruns() {
+        let tx = B256::with_last_byte;
+        // frontruns 1 and 2, a victim and backruns 4 and 5
+        let victims = vec![vec![], vec![tx(3)], vec![]];
+        let first_backrun =
+            SandwichInspector::<LibmdbxReadWriter>::first_sequential_backrun(&victims);
+
+        let mut frontruns = vec![tx(1), tx(2), tx(4)];
+        let mut backruns = frontruns.split_off(first_backrun);
+        backruns.push(tx(5));
+        assert_eq!(frontruns, vec![tx(1), tx(2)]);
+        assert_eq!(backruns, vec![tx(4), tx(5)]);
+
+        // a standard sandwich keeps its single backrun
+        assert_eq!(
+            SandwichInspector::<LibmdbxReadWriter>::first_sequential_backrun(&[vec![tx(2)]]),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cross_pool_sandwich_pair_overlap() {
+        let (weth, usdc) = (TokenInfoWithAddress::weth(), TokenInfoWithAddress::usdc());
+        let swap = |pool: u8, token_in: &TokenInfoWithAddress, token_out: &TokenInfoWithAddress| {
+            NormalizedSwap {
+                pool: Address::with_last_byte(pool),
+                token_in: token_in.clone(),
+                token_out: token_out.clone(),
+                ..Default::default()
+            }
+        };
+        let victim_info = TxInfo::new(
+            0,
+            1,
+            Address::with_last_byte(9),
+            None,
+            None,
+            B256::with_last_byte(2),
+            GasDetails::default(),
+            true,
+            false,
+            false,
+            SubmissionChannel::Public,
+            false,
+            None,
+            None,
+            vec![],
+        );
+        let overlap = |victim: NormalizedSwap, backrun: NormalizedSwap| {
+            SandwichInspector::<LibmdbxReadWriter>::has_pair_overlap(
+                &[vec![Action::Swap(swap(1, &weth, &usdc))]],
+                &[Action::Swap(backrun)],
+                &[vec![(vec![victim], vec![])]],
+                &[vec![victim_info.clone()]],
+            )
+        };
+
+        // frontrun on the v3 pool the victim swaps on, backrun on the v2 pool
+        assert!(overlap(swap(1, &weth, &usdc), swap(2, &usdc, &weth)));
+        // the victim didn't trade on the pool the frontrun moved
+        assert!(!overlap(swap(3, &weth, &usdc), swap(2, &usdc, &weth)));
+        // the backrun doesn't reverse the victim's swap
+        assert!(!overlap(swap(1, &weth, &usdc), swap(2, &weth, &usdc)));
+    }
+
     #[brontes_macros::test]
     async fn test_related_victim_tx_sandwich() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 1.0).await;
@@ -3,9 +3,10 @@ use std::sync::Arc;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    address_filter::address_filter,
     db::dex::BlockPrice,
     mev::{Bundle, BundleData, MevType, SearcherTx},
-    normalized_actions::{accounting::ActionAccounting, Action},
+    normalized_actions::Action,
     tree::BlockTree,
     ActionIter, BlockData, FastHashSet, MultiBlockData, ToFloatNearest, TreeSearchBuilder,
 };
@@ -13,8 +14,7 @@ use itertools::multizip;
 use malachite::{num::basic::traits::Zero, Rational};
 use reth_primitives::Address;
 
-use super::MAX_PROFIT;
-use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
+use crate::{shared_utils::SharedInspectorUtils, DetectionLimits, Inspector, Metadata};
 
 pub struct SearcherActivity<'db, DB: LibmdbxReader> {
     utils: SharedInspectorUtils<'db, DB>,
@@ -24,6 +24,11 @@ impl<'db, DB: LibmdbxReader> SearcherActivity<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
         Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
     }
+
+    pub fn with_limits(mut self, limits: DetectionLimits) -> Self {
+        self.utils.limits = limits;
+        self
+    }
 }
 
 impl<DB: LibmdbxReader> Inspector for SearcherActivity<'_, DB> {
@@ -56,8 +61,12 @@ impl<DB: LibmdbxReader> SearcherActivity<'_, DB> {
         tree: Arc<BlockTree<Action>>,
         metadata: Arc<Metadata>,
     ) -> Vec<Bundle> {
-        let search_args = TreeSearchBuilder::default()
-            .with_actions([Action::is_transfer, Action::is_eth_transfer]);
+        let search_args = TreeSearchBuilder::default().with_actions([
+            Action::is_transfer,
+            Action::is_eth_transfer,
+            Action::is_vault_deposit,
+            Action::is_vault_withdraw,
+        ]);
 
         let (hashes, transfers): (Vec<_>, Vec<_>) = tree.clone().collect_all(search_args).unzip();
         let tx_info = tree.get_tx_info_batch(&hashes, self.utils.db);
@@ -67,15 +76,18 @@ impl<DB: LibmdbxReader> SearcherActivity<'_, DB> {
                 if transfers.is_empty() {
                     return None
                 }
-                let info = info?;
+                let info = info.filter(|info| address_filter().allows_tx(info))?;
 
                 (info.searcher_eoa_info.is_some() || info.searcher_contract_info.is_some()).then(
                     || {
-                        let deltas = transfers
-                            .clone()
-                            .into_iter()
-                            .chain(info.get_total_eth_value().iter().cloned().map(Action::from))
-                            .account_for_actions();
+                        let tx_deltas = self.utils.calculate_token_deltas(
+                            [],
+                            transfers.clone().into_iter().chain(
+                                info.get_total_eth_value().iter().cloned().map(Action::from),
+                            ),
+                        );
+                        let balanced = tx_deltas.is_balanced();
+                        let deltas = tx_deltas.deltas;
 
                         let mut searcher_address: FastHashSet<Address> = FastHashSet::default();
                         searcher_address.insert(info.eoa);
@@ -90,20 +102,23 @@ impl<DB: LibmdbxReader> SearcherActivity<'_, DB> {
                                 &deltas,
                                 metadata.clone(),
                             ) {
-                            (Some(rev), true)
+                            (Some(rev), balanced)
                         } else {
                             (Some(Rational::ZERO), false)
                         };
 
-                        let gas_paid = metadata
-                            .get_gas_price_usd(info.gas_details.gas_paid(), self.utils.quote);
+                        let gas_paid = self
+                            .utils
+                            .get_gas_price_usd(info.gas_details.gas_paid(), &metadata);
 
                         let mut profit = rev_usd
                             .map(|rev| rev - gas_paid)
                             .filter(|_| has_dex_price)
                             .unwrap_or_default();
 
-                        if profit >= MAX_PROFIT || profit <= -MAX_PROFIT {
+                        if profit >= self.utils.limits.max_profit_usd
+                            || profit <= -&self.utils.limits.max_profit_usd
+                        {
                             has_dex_price = false;
                             profit = Rational::ZERO;
                         }
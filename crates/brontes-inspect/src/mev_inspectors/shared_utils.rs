@@ -1,9 +1,11 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use alloy_primitives::{Address, FixedBytes};
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    chain::chain_spec,
+    constants::ETH_ADDRESS,
     db::{
         dex::{BlockPrice, PriceAt},
         metadata::Metadata,
@@ -11,15 +13,15 @@ use brontes_types::{
     },
     mev::{
         AddressBalanceDeltas, Bundle, BundleHeader, Mev, MevType, TokenBalanceDelta,
-        TransactionAccounting,
+        TransactionAccounting, MEV_TAXONOMY_VERSION,
     },
     normalized_actions::{
-        Action, NormalizedAggregator, NormalizedBatch, NormalizedFlashLoan, NormalizedSwap,
-        NormalizedTransfer,
+        Action, DeltaEngine, NormalizedAggregator, NormalizedBatch, NormalizedFlashLoan,
+        NormalizedSwap, NormalizedTransfer, TxDeltas,
     },
     pair::Pair,
     utils::ToFloatNearest,
-    ActionIter, FastHashMap, FastHashSet, GasDetails, TxInfo,
+    ActionIter, FastHashMap, FastHashSet, GasDetails, Protocol, TxInfo,
 };
 use itertools::Itertools;
 use malachite::{
@@ -31,27 +33,103 @@ use malachite::{
 };
 use reth_primitives::TxHash;
 
+use super::DetectionLimits;
+
+static PRICING_QUOTE_ASSET: OnceLock<Address> = OnceLock::new();
+
+/// Sets the quote asset the dex prices of the run are denominated in.
+/// Inspectors that quote in another asset convert the prices through it. If
+/// it isn't set, the dex prices are assumed to be in the quote of the
+/// inspector.
+pub fn init_pricing_quote_asset(quote: Address) -> eyre::Result<()> {
+    PRICING_QUOTE_ASSET
+        .set(quote)
+        .map_err(|_| eyre::eyre!("pricing quote asset already initialized"))
+}
+
+pub fn pricing_quote_asset() -> Option<Address> {
+    PRICING_QUOTE_ASSET.get().copied()
+}
+
 #[derive(Debug)]
 pub struct SharedInspectorUtils<'db, DB: LibmdbxReader> {
-    pub(crate) quote: Address,
-    pub(crate) db:    &'db DB,
-    pub metrics:      Option<OutlierMetrics>,
+    pub(crate) quote:         Address,
+    /// The quote asset the dex prices of the metadata are denominated in
+    pub(crate) pricing_quote: Address,
+    pub(crate) db:            &'db DB,
+    pub metrics:              Option<OutlierMetrics>,
+    pub(crate) limits:        DetectionLimits,
 }
 
 impl<'db, DB: LibmdbxReader> SharedInspectorUtils<'db, DB> {
     pub fn new(quote_address: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
-        SharedInspectorUtils { quote: quote_address, db, metrics }
+        SharedInspectorUtils {
+            quote: quote_address,
+            pricing_quote: pricing_quote_asset().unwrap_or(quote_address),
+            db,
+            metrics,
+            limits: DetectionLimits::default(),
+        }
     }
 }
 type TokenDeltas = FastHashMap<Address, Rational>;
 type AddressDeltas = FastHashMap<Address, TokenDeltas>;
 type PossibleSwapDetails = Vec<(TokenInfoWithAddress, bool, Rational, Address, u64)>;
 
+/// Keeps vault deposits and withdrawals, for splitting them off with the other
+/// actions that are accounted for
+pub(crate) fn try_vault_action(action: Action) -> Option<Action> {
+    (action.is_vault_deposit() || action.is_vault_withdraw()).then_some(action)
+}
+
 impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
     pub fn get_metrics(&self) -> Option<&OutlierMetrics> {
         self.metrics.as_ref()
     }
 
+    /// Prices the token in the quote of the inspector. `price` looks up a dex
+    /// price, which are denominated in the pricing quote of the run, so when
+    /// the inspector quotes in another asset the price is converted at the
+    /// rate `price` gives for the quote.
+    pub fn quote_price(
+        &self,
+        token: Address,
+        price: impl Fn(Pair) -> Option<Rational>,
+    ) -> Option<Rational> {
+        if token == self.quote
+            || (token == ETH_ADDRESS && self.quote == chain_spec().wrapped_native)
+        {
+            return Some(Rational::ONE)
+        }
+
+        let token_price = price(Pair(token, self.pricing_quote))?;
+        if self.quote == self.pricing_quote {
+            return Some(token_price)
+        }
+
+        let quote_price = price(Pair(self.quote, self.pricing_quote))?;
+        (quote_price != Rational::ZERO).then(|| token_price / quote_price)
+    }
+
+    /// The value of the gas paid in the quote of the inspector
+    pub fn get_gas_price_usd(&self, gas_paid: u128, metadata: &Metadata) -> Rational {
+        let gas_price = metadata.get_gas_price_usd(gas_paid, self.pricing_quote);
+        if self.quote == self.pricing_quote {
+            return gas_price
+        }
+
+        metadata
+            .dex_quotes
+            .as_ref()
+            .and_then(|dex_quotes| {
+                dex_quotes
+                    .price_for_block(Pair(self.quote, self.pricing_quote), BlockPrice::Average)
+            })
+            .filter(|quote_price| quote_price != &Rational::ZERO)
+            .map(|quote_price| gas_price / quote_price)
+            .unwrap_or(Rational::ZERO)
+    }
+
     /// Calculates the USD value of the token balance deltas by address
     pub fn usd_delta_by_address(
         &self,
@@ -70,34 +148,37 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                     continue
                 }
 
-                let pair = Pair(*token_addr, self.quote);
+                if self.has_anomalous_decimals(*token_addr) {
+                    return None
+                }
+
                 let price = if cex {
-                    metadata
-                        .cex_quotes
-                        .get_quote_from_most_liquid_exchange(
-                            &pair,
-                            metadata.microseconds_block_timestamp(),
-                            Some(1_000_000),
-                        )?
-                        .price_maker
-                        .1
-                } else if at_or_before {
-                    metadata
-                        .dex_quotes
-                        .as_ref()?
-                        .price_at_or_before(pair, tx_position as usize)
-                        .map(|price| price.get_price(at))?
-                        .clone()
+                    if token_addr == &self.quote {
+                        Rational::ONE
+                    } else {
+                        metadata
+                            .cex_quotes
+                            .get_quote_from_most_liquid_exchange(
+                                &Pair(*token_addr, self.quote),
+                                metadata.microseconds_block_timestamp(),
+                                Some(1_000_000),
+                            )?
+                            .price_maker
+                            .1
+                    }
                 } else {
-                    metadata
-                        .dex_quotes
-                        .as_ref()?
-                        .price_at(pair, tx_position as usize)
-                        .map(|price| price.get_price(at))?
-                        .clone()
+                    let dex_quotes = metadata.dex_quotes.as_ref()?;
+                    self.quote_price(*token_addr, |pair| {
+                        if at_or_before {
+                            dex_quotes.price_at_or_before(pair, tx_position as usize)
+                        } else {
+                            dex_quotes.price_at(pair, tx_position as usize)
+                        }
+                        .map(|price| price.get_price(at))
+                    })?
                 };
 
-                let usd_amount = amount.clone() * price.clone();
+                let usd_amount = self.after_transfer_tax(*token_addr, amount) * price.clone();
 
                 *usd_deltas.entry(*address).or_insert(Rational::ZERO) += usd_amount;
             }
@@ -106,14 +187,104 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         Some(usd_deltas)
     }
 
-    // will flatten nested and filter out actions that aren't swap, transfer or
-    // eth_transfer
+    /// Accounts for the token deltas of the actions, checking the swaps
+    /// against the transfers that settled them, see [`DeltaEngine`]. The
+    /// tokens a transfer tax was recorded for are treated as fee on transfer
+    /// tokens and the aTokens registered under the Aave protocols as rebasing
+    /// tokens. Callers should distrust the deltas if they aren't balanced.
+    ///
+    /// Vault shares have no dex price, so the shares moved by the vault
+    /// deposits and withdrawals in the actions are valued as the underlying
+    /// asset at the rate the vault exchanged them at. Eth is netted with the
+    /// wrapped native token.
+    pub fn calculate_token_deltas<'a>(
+        &self,
+        swaps: impl IntoIterator<Item = &'a NormalizedSwap>,
+        actions: impl Iterator<Item = Action>,
+    ) -> TxDeltas {
+        let actions = actions.collect_vec();
+
+        let mut share_rates: FastHashMap<Address, (Address, Rational)> = FastHashMap::default();
+        let mut transfer_tokens = FastHashSet::default();
+        for action in &actions {
+            match action {
+                Action::VaultDeposit(deposit) if deposit.shares != Rational::ZERO => {
+                    share_rates.insert(
+                        deposit.share.address,
+                        (deposit.asset.address, &deposit.assets / &deposit.shares),
+                    );
+                }
+                Action::VaultWithdraw(withdraw) if withdraw.shares != Rational::ZERO => {
+                    share_rates.insert(
+                        withdraw.share.address,
+                        (withdraw.asset.address, &withdraw.assets / &withdraw.shares),
+                    );
+                }
+                Action::Transfer(transfer) => {
+                    transfer_tokens.insert(transfer.token.address);
+                }
+                _ => {}
+            }
+        }
+
+        let taxed_tokens = transfer_tokens
+            .iter()
+            .copied()
+            .filter(|token| matches!(self.db.try_fetch_token_tax(*token), Ok(Some(_))));
+        let rebasing_tokens = transfer_tokens.iter().copied().filter(|token| {
+            matches!(self.db.get_protocol(*token), Ok(Protocol::AaveV2 | Protocol::AaveV3))
+        });
+
+        let mut tx_deltas = DeltaEngine::default()
+            .with_taxed_tokens(taxed_tokens)
+            .with_rebasing_tokens(rebasing_tokens)
+            .account_for_tx(swaps, actions);
+        if !tx_deltas.is_balanced() {
+            tracing::debug!(
+                discrepancies = ?tx_deltas.discrepancies,
+                unconserved = ?tx_deltas.unconserved,
+                "unbalanced token deltas"
+            );
+        }
+
+        for token_deltas in tx_deltas.deltas.values_mut() {
+            for (share, (asset, rate)) in &share_rates {
+                if let Some(shares) = token_deltas.remove(share) {
+                    *token_deltas.entry(*asset).or_insert(Rational::ZERO) += shares * rate;
+                }
+            }
+        }
+        Self::net_native_eth(&mut tx_deltas.deltas);
+
+        tx_deltas
+    }
+
+    /// Folds the eth deltas into the wrapped native token, so that wrapping
+    /// and unwrapping don't show up as a balance change of two separate tokens
+    pub(crate) fn net_native_eth(deltas: &mut AddressDeltas) {
+        let wrapped_native = chain_spec().wrapped_native;
+        for token_deltas in deltas.values_mut() {
+            let Some(eth) = token_deltas.remove(&ETH_ADDRESS) else { continue };
+            let wrapped = token_deltas.entry(wrapped_native).or_insert(Rational::ZERO);
+            *wrapped += eth;
+            if *wrapped == Rational::ZERO {
+                token_deltas.remove(&wrapped_native);
+            }
+        }
+    }
+
+    // will flatten nested and filter out actions that aren't swap, transfer,
+    // eth_transfer or vault deposit / withdrawal
     pub fn flatten_nested_actions_default<'a>(
         &self,
         iter: impl Iterator<Item = Action> + 'a,
     ) -> impl Iterator<Item = Action> + 'a {
         self.flatten_nested_actions(iter, &|action| {
-            action.is_swap() || action.is_transfer() || action.is_eth_transfer()
+            action.is_swap()
+                || action.is_transfer()
+                || action.is_eth_transfer()
+                || action.is_vault_deposit()
+                || action.is_vault_withdraw()
         })
     }
 
@@ -159,17 +330,18 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
 
         for (address, token_deltas) in deltas {
             for (token_addr, amount) in token_deltas {
-                if amount == &Rational::ZERO {
+                if amount == &Rational::ZERO || self.has_anomalous_decimals(*token_addr) {
                     continue
                 }
 
-                let pair = Pair(*token_addr, self.quote);
                 let price = metadata
                     .dex_quotes
                     .as_ref()
                     .and_then(|dq| {
-                        dq.price_at(pair, tx_index as usize)
-                            .map(|price| price.get_price(at))
+                        self.quote_price(*token_addr, |pair| {
+                            dq.price_at(pair, tx_index as usize)
+                                .map(|price| price.get_price(at))
+                        })
                     })
                     .unwrap_or_default();
 
@@ -291,19 +463,10 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         token_address: Address,
         metadata: &Arc<Metadata>,
     ) -> Option<Rational> {
-        if token_address == self.quote {
-            return Some(Rational::ONE)
-        }
-
-        let pair = Pair(token_address, self.quote);
-
-        Some(
-            metadata
-                .dex_quotes
-                .as_ref()?
-                .price_at(pair, tx_index)?
-                .get_price(at),
-        )
+        let dex_quotes = metadata.dex_quotes.as_ref()?;
+        self.quote_price(token_address, |pair| {
+            Some(dex_quotes.price_at(pair, tx_index)?.get_price(at))
+        })
     }
 
     pub fn get_token_price_on_dex_block(
@@ -312,13 +475,8 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         token_address: Address,
         metadata: &Arc<Metadata>,
     ) -> Option<Rational> {
-        if token_address == self.quote {
-            return Some(Rational::ONE)
-        }
-
-        let pair = Pair(token_address, self.quote);
-
-        metadata.dex_quotes.as_ref()?.price_for_block(pair, block)
+        let dex_quotes = metadata.dex_quotes.as_ref()?;
+        self.quote_price(token_address, |pair| dex_quotes.price_for_block(pair, block))
     }
 
     pub fn build_bundle_header_searcher_activity(
@@ -331,8 +489,11 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         gas_details: &[GasDetails],
         metadata: Arc<Metadata>,
         mev_type: MevType,
-        no_pricing_calculated: bool,
+        mut no_pricing_calculated: bool,
     ) -> BundleHeader {
+        if self.prices_anomalous_tokens(&bundle_deltas, info, mev_type) {
+            no_pricing_calculated = true;
+        }
         if no_pricing_calculated {
             profit_usd = 0.0;
         }
@@ -345,8 +506,7 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         let bribe_usd = gas_details
             .iter()
             .map(|details| {
-                metadata
-                    .get_gas_price_usd(details.gas_paid(), self.quote)
+                self.get_gas_price_usd(details.gas_paid(), &metadata)
                     .to_float()
             })
             .sum::<f64>();
@@ -369,6 +529,16 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             mev_type,
             no_pricing_calculated,
             balance_deltas,
+            // set by the composer once all bundles for the block are known
+            sanctioned_addresses: vec![],
+            taxonomy_version: MEV_TAXONOMY_VERSION,
+            superseded: vec![],
+            simulated_profit_usd: None,
+            profit_discrepancy: false,
+            submission_channel: info.submission_channel,
+            // set by the composer from the block tree
+            #[cfg(feature = "provenance")]
+            provenance: vec![],
         }
     }
 
@@ -381,9 +551,12 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         gas_details: &[GasDetails],
         metadata: Arc<Metadata>,
         mev_type: MevType,
-        no_pricing_calculated: bool,
+        mut no_pricing_calculated: bool,
         price_f: impl Fn(&Self, Address, Rational) -> Option<Rational>,
     ) -> BundleHeader {
+        if self.prices_anomalous_tokens(&bundle_deltas, info, mev_type) {
+            no_pricing_calculated = true;
+        }
         if no_pricing_calculated {
             profit_usd = 0.0;
         }
@@ -393,8 +566,7 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         let bribe_usd = gas_details
             .iter()
             .map(|details| {
-                metadata
-                    .get_gas_price_usd(details.gas_paid(), self.quote)
+                self.get_gas_price_usd(details.gas_paid(), &metadata)
                     .to_float()
             })
             .sum::<f64>();
@@ -423,7 +595,70 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             mev_type,
             no_pricing_calculated,
             balance_deltas,
+            // set by the composer once all bundles for the block are known
+            sanctioned_addresses: vec![],
+            taxonomy_version: MEV_TAXONOMY_VERSION,
+            superseded: vec![],
+            simulated_profit_usd: None,
+            profit_discrepancy: false,
+            submission_channel: info.submission_channel,
+            // set by the composer from the block tree
+            #[cfg(feature = "provenance")]
+            provenance: vec![],
+        }
+    }
+
+    /// Whether the decimals of the token can't be trusted, in which case any
+    /// usd value derived from its amounts would be garbage
+    pub fn has_anomalous_decimals(&self, token: Address) -> bool {
+        self.db
+            .try_fetch_token_info(token)
+            .is_ok_and(|info| info.is_anomalous())
+    }
+
+    /// A gain in a fee on transfer token is only worth what is left of it once
+    /// it is transferred out, so positive amounts are discounted by the
+    /// observed tax of the token
+    pub fn after_transfer_tax(&self, token: Address, amount: &Rational) -> Rational {
+        if amount <= &Rational::ZERO {
+            return amount.clone()
+        }
+
+        match self.db.try_fetch_token_tax(token) {
+            Ok(Some(tax)) => tax.after_tax(amount),
+            _ => amount.clone(),
+        }
+    }
+
+    /// Checks the tokens in the bundle's deltas for anomalous decimals, logging
+    /// the offending tokens so that the bundle can be skipped from pricing
+    fn prices_anomalous_tokens(
+        &self,
+        bundle_deltas: &[AddressDeltas],
+        info: &TxInfo,
+        mev_type: MevType,
+    ) -> bool {
+        let anomalous = bundle_deltas
+            .iter()
+            .flat_map(|deltas| deltas.values())
+            .flat_map(|token_deltas| token_deltas.keys())
+            .unique()
+            .filter_map(|token| self.db.try_fetch_token_info(*token).ok())
+            .filter(|token| token.is_anomalous())
+            .collect::<Vec<_>>();
+
+        for token in &anomalous {
+            tracing::warn!(
+                tx_hash = ?info.tx_hash,
+                ?mev_type,
+                token = ?token.address,
+                symbol = %token.symbol,
+                anomaly = ?token.decimals_anomaly,
+                "skipping pricing of bundle with a token with anomalous decimals"
+            );
         }
+
+        !anomalous.is_empty()
     }
 
     pub fn get_full_block_price(
@@ -437,13 +672,16 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
 
         for (address, token_deltas) in deltas {
             for (token_addr, amount) in token_deltas {
-                let pair = Pair(*token_addr, self.quote);
-                let price = metadata
-                    .dex_quotes
-                    .as_ref()?
-                    .price_for_block(pair, price_type)?;
+                if self.has_anomalous_decimals(*token_addr) {
+                    return None
+                }
 
-                let usd_amount = amount.clone() * price.clone();
+                let dex_quotes = metadata.dex_quotes.as_ref()?;
+                let price = self.quote_price(*token_addr, |pair| {
+                    dex_quotes.price_for_block(pair, price_type)
+                })?;
+
+                let usd_amount = self.after_transfer_tax(*token_addr, amount) * price.clone();
 
                 *usd_deltas.entry(*address).or_insert(Rational::ZERO) += usd_amount;
             }
@@ -492,16 +730,17 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                         let deltas: Vec<TokenBalanceDelta> = token_deltas
                             .into_iter()
                             .map(|(token, amount)| {
+                                let token_info =
+                                    self.db.try_fetch_token_info(token).ok().unwrap_or_default();
                                 //TODO: For cex-dex if we merge swap we won't have the intermediary
                                 //TODO: price so it will be marked as zero in the deltas
-                                let usd_value =
-                                    price_f(self, token, amount.clone()).unwrap_or(Rational::ZERO);
+                                let usd_value = if token_info.is_anomalous() {
+                                    Rational::ZERO
+                                } else {
+                                    price_f(self, token, amount.clone()).unwrap_or(Rational::ZERO)
+                                };
                                 TokenBalanceDelta {
-                                    token:     self
-                                        .db
-                                        .try_fetch_token_info(token)
-                                        .ok()
-                                        .unwrap_or_default(),
+                                    token:     token_info,
                                     amount:    amount.to_float(),
                                     usd_value: usd_value.to_float(),
                                 }
@@ -550,6 +789,19 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         })
     }
 
+    /// Maps the EOAs of the given transactions to the group of EOAs they share
+    /// with other accounts of the same searcher, see
+    /// [`SearcherInfo::linked_eoa_group`](brontes_types::db::searcher::SearcherInfo::linked_eoa_group).
+    /// EOAs that aren't linked to any other account are omitted.
+    pub fn linked_eoa_groups(&self, eoas: Vec<Address>) -> FastHashMap<Address, Address> {
+        self.db
+            .try_fetch_searcher_eoa_infos(eoas)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(eoa, info)| Some((eoa, info.linked_eoa_group(eoa)?)))
+            .collect()
+    }
+
     /// Evaluates the validity of swap prices against DEX quoted prices within a
     /// given metadata context.
     ///
@@ -586,12 +838,12 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                         let am_in_price = metadata
                             .dex_quotes
                             .as_ref()?
-                            .price_at(Pair(swap.token_in.address, self.quote), idx)?;
+                            .price_at(Pair(swap.token_in.address, self.pricing_quote), idx)?;
 
                         let am_out_price = metadata
                             .dex_quotes
                             .as_ref()?
-                            .price_at(Pair(swap.token_out.address, self.quote), idx)?;
+                            .price_at(Pair(swap.token_out.address, self.pricing_quote), idx)?;
 
                         // we reciprocal amount out because we won't have pricing for quote <> token
                         // out but we will have flipped
@@ -790,10 +1042,19 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
 
 #[cfg(test)]
 pub mod test {
+    use alloy_primitives::U256;
     use brontes_core::LibmdbxReadWriter;
+    use brontes_database::{AddressToProtocolInfo, AddressToProtocolInfoData};
     use brontes_types::{
-        constants::{USDC_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
-        normalized_actions::NormalizedSwap,
+        constants::{ETH_ADDRESS, USDC_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
+        db::{
+            address_to_protocol_info::ProtocolInfo,
+            token_info::{TokenInfo, TokenInfoWithAddress},
+        },
+        normalized_actions::{
+            accounting::AddressDeltas, DiscrepancyKind, NormalizedSwap, NormalizedTransfer,
+        },
+        Protocol, ToScaledRational,
     };
     use malachite::Rational;
 
@@ -811,17 +1072,11 @@ pub mod test {
         let swap1 = NormalizedSwap {
             token_in: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: WETH_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 18,
-                    symbol:   "WETH".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(18, "WETH".to_string()),
             },
             token_out: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: USDT_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 6,
-                    symbol:   "USDT".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(6, "USDT".to_string()),
             },
             from: address0,
             pool: pool1,
@@ -834,17 +1089,11 @@ pub mod test {
         let swap2 = NormalizedSwap {
             token_in: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: USDT_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 6,
-                    symbol:   "USDT".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(6, "USDT".to_string()),
             },
             token_out: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: USDC_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 6,
-                    symbol:   "USDC".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(6, "USDC".to_string()),
             },
             from: pool1,
             pool: pool2,
@@ -857,17 +1106,11 @@ pub mod test {
         let swap3 = NormalizedSwap {
             token_in: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: USDC_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 6,
-                    symbol:   "USDC".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(6, "USDC".to_string()),
             },
             token_out: brontes_types::db::token_info::TokenInfoWithAddress {
                 address: WETH_ADDRESS,
-                inner:   brontes_types::db::token_info::TokenInfo {
-                    decimals: 18,
-                    symbol:   "WETH".to_string(),
-                },
+                inner:   brontes_types::db::token_info::TokenInfo::new(18, "WETH".to_string()),
             },
             from: pool2,
             pool: pool3,
@@ -903,4 +1146,104 @@ pub mod test {
         let res = SharedInspectorUtils::<LibmdbxReadWriter>::cex_merge_possible_swaps(swaps);
         assert_eq!(res.len(), 2, "{:#?}", res);
     }
+
+    #[test]
+    pub fn test_net_native_eth() {
+        let wrapper = alloy_primitives::address!("76F36d497b51e48A288f03b4C1d7461e92247d5e");
+        let unwrapper = alloy_primitives::address!("76F36d497b51e48A288f03b4C1d7461e92247d52");
+
+        let mut deltas = AddressDeltas::default();
+        // wrapped 2 eth
+        deltas.entry(wrapper).or_default().extend([
+            (ETH_ADDRESS, Rational::from(-2)),
+            (WETH_ADDRESS, Rational::from(2)),
+            (USDT_ADDRESS, Rational::from(5)),
+        ]);
+        // unwrapped 1 weth out of the 3 received
+        deltas
+            .entry(unwrapper)
+            .or_default()
+            .extend([(ETH_ADDRESS, Rational::from(1)), (WETH_ADDRESS, Rational::from(2))]);
+
+        SharedInspectorUtils::<LibmdbxReadWriter>::net_native_eth(&mut deltas);
+
+        let wrapper_deltas = &deltas[&wrapper];
+        assert_eq!(wrapper_deltas.len(), 1);
+        assert_eq!(wrapper_deltas[&USDT_ADDRESS], Rational::from(5));
+        assert_eq!(
+            deltas[&unwrapper].clone().into_iter().collect::<Vec<_>>(),
+            vec![(WETH_ADDRESS, Rational::from(3))]
+        );
+    }
+
+    #[brontes_macros::test]
+    pub async fn test_atoken_leg_rounding_is_expected() {
+        let db = brontes_core::test_utils::get_db_handle(tokio::runtime::Handle::current()).await;
+        let user = alloy_primitives::address!("76F36d497b51e48A288f03b4C1d7461e92247d5e");
+        let pool = alloy_primitives::address!("16F36d497b51e48A288f03b4C1d7461e92247d52");
+        let a_token = alloy_primitives::address!("a6F36d497b51e48A288f03b4C1d7461e92247d52");
+
+        // aTokens are registered under the aave protocol with their underlying
+        db.db
+            .write_table::<AddressToProtocolInfo, AddressToProtocolInfoData>(&[
+                AddressToProtocolInfoData {
+                    key:   a_token,
+                    value: ProtocolInfo::new(Protocol::AaveV3, &[USDC_ADDRESS], None, 0),
+                },
+            ])
+            .unwrap();
+
+        let a_usdc = TokenInfoWithAddress {
+            address: a_token,
+            inner:   TokenInfo::new(6, "aUSDC".to_string()),
+        };
+        let usdt = TokenInfoWithAddress {
+            address: USDT_ADDRESS,
+            inner:   TokenInfo::new(6, "USDT".to_string()),
+        };
+        let one = Rational::from(1);
+        let wei = U256::from(1).to_scaled_rational(6);
+
+        let swap = NormalizedSwap {
+            trace_index: 1,
+            from: user,
+            recipient: user,
+            pool,
+            token_in: a_usdc.clone(),
+            token_out: usdt.clone(),
+            amount_in: one.clone(),
+            amount_out: one.clone(),
+            ..Default::default()
+        };
+        // the aToken moves a wei less than requested as the shares round down
+        let transfers = vec![
+            NormalizedTransfer {
+                trace_index: 2,
+                from: user,
+                to: pool,
+                token: a_usdc,
+                amount: &one - &wei,
+                ..Default::default()
+            }
+            .into(),
+            NormalizedTransfer {
+                trace_index: 3,
+                from: pool,
+                to: user,
+                token: usdt,
+                amount: one,
+                ..Default::default()
+            }
+            .into(),
+        ];
+
+        let utils = SharedInspectorUtils::new(USDT_ADDRESS, db, None);
+        let tx = utils.calculate_token_deltas([&swap], transfers.into_iter());
+
+        assert!(tx.is_balanced());
+        let [discrepancy] = &tx.discrepancies[..] else { panic!("{:#?}", tx.discrepancies) };
+        assert_eq!(discrepancy.token, a_token);
+        assert_eq!(discrepancy.kind, DiscrepancyKind::RebasingRounding);
+        assert_eq!(discrepancy.amount, wei);
+    }
 }
@@ -0,0 +1,261 @@
+//! Verifies the profit of the bundles against the balance changes of the
+//! searcher. The profit the inspectors report is computed from the classified
+//! actions, so a misclassified or unclassified action makes it diverge from
+//! what the searcher actually made. The block is re-executed through the
+//! [`TracingProvider`] and the ETH and ERC-20 balance changes of the searcher
+//! EOA and contract are read from the raw traces, independent of the
+//! classification, then priced the same way the inspectors price the deltas.
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use alloy_primitives::{Address, Log, B256, I256, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolEvent;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    chain::chain_spec,
+    db::{dex::PriceAt, metadata::Metadata},
+    mev::{Bundle, Mev},
+    pair::Pair,
+    structured_trace::{TraceActions, TxTrace},
+    traits::TracingProvider,
+    FastHashMap, ToFloatNearest, ToScaledRational,
+};
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::{BlockId, BlockNumberOrTag};
+
+sol!(
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Deposit(address indexed dst, uint256 wad);
+    event Withdrawal(address indexed src, uint256 wad);
+);
+
+/// Differences below this are rounding and price noise
+const ABS_TOLERANCE_USD: f64 = 1.0;
+/// Share of the estimated profit the simulated profit may diverge by
+const REL_TOLERANCE: f64 = 0.1;
+
+static PROFIT_VERIFICATION: AtomicBool = AtomicBool::new(false);
+
+/// Enables the verification of the bundle profits after the inspection of
+/// each block. Costs a replay of every block with bundles.
+pub fn enable_profit_verification() {
+    PROFIT_VERIFICATION.store(true, Ordering::SeqCst);
+}
+
+pub fn is_profit_verification_enabled() -> bool {
+    PROFIT_VERIFICATION.load(Ordering::Relaxed)
+}
+
+/// Sets the simulated profit of the bundles and flags the ones whose
+/// estimated profit diverges from it. Bundles with a cex leg are skipped, as
+/// only their on-chain leg can be re-executed. If the block can't be replayed
+/// or a token of the deltas has no price, the bundle is left unverified.
+pub async fn verify_bundle_profits<T: TracingProvider, DB: LibmdbxReader>(
+    bundles: &mut [Bundle],
+    metadata: &Metadata,
+    quote: Address,
+    tracer: &Arc<T>,
+    db: &DB,
+) {
+    if bundles
+        .iter()
+        .all(|bundle| bundle.header.mev_type.use_cex_pricing_for_deltas())
+    {
+        return
+    }
+
+    let block = metadata.block_num;
+    let traces = match tracer
+        .replay_block_transactions(BlockId::Number(BlockNumberOrTag::Number(block)))
+        .await
+    {
+        Ok(Some(traces)) => traces,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::debug!(%block, err = %e, "failed to replay block for profit verification");
+            return
+        }
+    };
+    let traces = traces
+        .iter()
+        .map(|trace| (trace.tx_hash, trace))
+        .collect::<FastHashMap<B256, &TxTrace>>();
+
+    for bundle in bundles {
+        if bundle.header.mev_type.use_cex_pricing_for_deltas() {
+            continue
+        }
+
+        let Some(txs) = bundle
+            .data
+            .mev_transaction_hashes()
+            .iter()
+            .map(|hash| traces.get(hash).copied())
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue
+        };
+        let Some(last_tx) = txs.iter().map(|tx| tx.tx_index).max() else { continue };
+
+        let searchers = std::iter::once(bundle.header.eoa)
+            .chain(bundle.header.mev_contract)
+            .collect::<Vec<_>>();
+        let deltas = searcher_deltas(&txs, &searchers, bundle.header.eoa);
+
+        let Some(simulated) = price_deltas(deltas, last_tx, metadata, quote, db) else {
+            tracing::debug!(
+                tx_hash = ?bundle.header.tx_hash,
+                "failed to price the simulated deltas of the bundle"
+            );
+            continue
+        };
+
+        bundle.header.simulated_profit_usd = Some(simulated);
+        bundle.header.profit_discrepancy = !bundle.header.no_pricing_calculated
+            && is_discrepancy(bundle.header.profit_usd, simulated);
+    }
+}
+
+/// The net token balance changes of the searcher addresses over the
+/// transactions. ETH is netted with the wrapped native token, and the gas of
+/// the transactions is paid by the EOA. Transfers between the searcher
+/// addresses net out.
+fn searcher_deltas(
+    txs: &[&TxTrace],
+    searchers: &[Address],
+    eoa: Address,
+) -> FastHashMap<Address, I256> {
+    let weth = chain_spec().wrapped_native;
+    let mut deltas: FastHashMap<Address, I256> = FastHashMap::default();
+    let mut apply = |token: Address, from: Option<Address>, to: Option<Address>, amount: U256| {
+        let amount = I256::from_raw(amount);
+        if from.is_some_and(|from| searchers.contains(&from)) {
+            *deltas.entry(token).or_insert(I256::ZERO) -= amount;
+        }
+        if to.is_some_and(|to| searchers.contains(&to)) {
+            *deltas.entry(token).or_insert(I256::ZERO) += amount;
+        }
+    };
+
+    for tx in txs {
+        let gas_paid = U256::from(tx.gas_used) * U256::from(tx.effective_price)
+            + U256::from(tx.blob_gas_used) * U256::from(tx.blob_gas_price);
+        apply(weth, Some(eoa), None, gas_paid);
+        if !tx.is_success {
+            continue
+        }
+
+        // the calls nested in a reverted call are reverted with it
+        let reverted = tx
+            .trace
+            .iter()
+            .filter(|trace| trace.trace.error.is_some())
+            .map(|trace| trace.trace.trace_address.clone())
+            .collect::<Vec<_>>();
+
+        for trace in &tx.trace {
+            if reverted
+                .iter()
+                .any(|reverted| trace.trace.trace_address.starts_with(reverted))
+            {
+                continue
+            }
+
+            let value = trace.get_msg_value();
+            if !value.is_zero() && !trace.is_delegate_call() {
+                let to = if trace.is_create() {
+                    trace.get_create_output()
+                } else {
+                    trace.get_to_address()
+                };
+                apply(weth, Some(trace.get_from_addr()), Some(to), value);
+            }
+
+            for log in &trace.logs {
+                // erc721 transfers share the signature but index the token id
+                let transfer = (log.topics().len() == 3)
+                    .then(|| decode_log::<Transfer>(log))
+                    .flatten();
+                if let Some(transfer) = transfer {
+                    apply(log.address, Some(transfer.from), Some(transfer.to), transfer.value);
+                } else if log.address == weth {
+                    // WETH doesn't emit a transfer when wrapping and unwrapping
+                    if let Some(deposit) = decode_log::<Deposit>(log) {
+                        apply(weth, None, Some(deposit.dst), deposit.wad);
+                    } else if let Some(withdrawal) = decode_log::<Withdrawal>(log) {
+                        apply(weth, Some(withdrawal.src), None, withdrawal.wad);
+                    }
+                }
+            }
+        }
+    }
+
+    deltas.retain(|_, delta| !delta.is_zero());
+    deltas
+}
+
+/// Values the deltas at the dex price after the last transaction of the
+/// bundle
+fn price_deltas<DB: LibmdbxReader>(
+    deltas: FastHashMap<Address, I256>,
+    tx_index: u64,
+    metadata: &Metadata,
+    quote: Address,
+    db: &DB,
+) -> Option<f64> {
+    let mut profit = Rational::ZERO;
+    for (token, delta) in deltas {
+        let decimals = db.try_fetch_token_info(token).ok()?.decimals;
+        let price = metadata
+            .dex_quotes
+            .as_ref()?
+            .price_at(Pair(token, quote), tx_index as usize)?
+            .get_price(PriceAt::After);
+
+        profit += delta.to_scaled_rational(decimals) * price;
+    }
+
+    Some(profit.to_float())
+}
+
+fn is_discrepancy(estimated: f64, simulated: f64) -> bool {
+    (simulated - estimated).abs() > ABS_TOLERANCE_USD.max(REL_TOLERANCE * estimated.abs())
+}
+
+fn decode_log<E: SolEvent>(log: &Log) -> Option<E> {
+    (log.topics().first() == Some(&E::SIGNATURE_HASH))
+        .then(|| E::decode_log_data(&log.data, false).ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profit_discrepancy() {
+        assert!(!is_discrepancy(100.0, 95.0));
+        assert!(is_discrepancy(100.0, 80.0));
+        // small bundles are within the absolute tolerance
+        assert!(!is_discrepancy(0.5, -0.2));
+        assert!(is_discrepancy(0.5, -2.0));
+    }
+
+    #[test]
+    fn test_decode_transfer_log() {
+        let [from, to] = [1, 2].map(Address::with_last_byte);
+        let value = U256::from(10u64.pow(18));
+        let log = Log::new_unchecked(
+            Address::with_last_byte(3),
+            vec![Transfer::SIGNATURE_HASH, from.into_word(), to.into_word()],
+            value.to_be_bytes_vec().into(),
+        );
+
+        let transfer = decode_log::<Transfer>(&log).unwrap();
+        assert_eq!((transfer.from, transfer.to, transfer.value), (from, to, value));
+        assert!(decode_log::<Deposit>(&log).is_none());
+    }
+}
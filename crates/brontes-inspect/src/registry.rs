@@ -0,0 +1,230 @@
+//! Registry of the inspectors of a run, keyed by [`Inspectors`]. Each
+//! inspector is registered with the closure that constructs it, and can be
+//! disabled and re-enabled while the run is going, so that a deployment
+//! following the tip can stop running a heavy inspector without a restart.
+//! Disabled inspectors are skipped by the composer, so no inspector run is
+//! recorded for them.
+//!
+//! ```ignore
+//! let registry = static_object(
+//!     InspectorRegistry::default()
+//!         .with(Inspectors::Sandwich, move || Inspectors::Sandwich.init_mev_inspector(..)),
+//! );
+//! let inspectors = registry.init();
+//!
+//! registry.set_enabled(Inspectors::Sandwich, false)?;
+//! ```
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use alloy_primitives::Address;
+use brontes_types::{mev::Bundle, FastHashMap, FastHashSet, MultiBlockData};
+use tracing::info;
+
+use crate::{DynMevInspector, Inspector, Inspectors};
+
+/// Constructs the inspector when the registry is initialized
+pub type InspectorConstructor = Box<dyn Fn() -> DynMevInspector + Send + Sync>;
+
+#[derive(Default)]
+pub struct InspectorRegistry {
+    /// In the order the inspectors were registered in
+    constructors: Vec<(Inspectors, InspectorConstructor)>,
+    enabled:      FastHashMap<Inspectors, AtomicBool>,
+}
+
+impl InspectorRegistry {
+    /// Registers the constructor of the inspector, replacing the one it was
+    /// registered with before. Inspectors are registered enabled
+    pub fn register(
+        &mut self,
+        inspector: Inspectors,
+        constructor: impl Fn() -> DynMevInspector + Send + Sync + 'static,
+    ) {
+        self.constructors
+            .retain(|(registered, _)| *registered != inspector);
+        self.constructors.push((inspector, Box::new(constructor)));
+        self.enabled.insert(inspector, AtomicBool::new(true));
+    }
+
+    pub fn with(
+        mut self,
+        inspector: Inspectors,
+        constructor: impl Fn() -> DynMevInspector + Send + Sync + 'static,
+    ) -> Self {
+        self.register(inspector, constructor);
+        self
+    }
+
+    /// Constructs every registered inspector. The returned inspectors check
+    /// the registry on each block and don't return any bundles while they are
+    /// disabled
+    pub fn init(&'static self) -> &'static [&'static dyn Inspector<Result = Vec<Bundle>>] {
+        let inspectors = self
+            .constructors
+            .iter()
+            .map(|(id, constructor)| {
+                &*Box::leak(Box::new(RegisteredInspector {
+                    id:       *id,
+                    inner:    constructor(),
+                    registry: self,
+                })) as &'static dyn Inspector<Result = Vec<Bundle>>
+            })
+            .collect::<Vec<_>>();
+
+        &*Box::leak(inspectors.into_boxed_slice())
+    }
+
+    pub fn is_enabled(&self, inspector: Inspectors) -> bool {
+        self.enabled
+            .get(&inspector)
+            .is_some_and(|enabled| enabled.load(Ordering::Relaxed))
+    }
+
+    /// Enables or disables a registered inspector, taking effect from the next
+    /// block that is inspected
+    pub fn set_enabled(&self, inspector: Inspectors, enabled: bool) -> eyre::Result<()> {
+        let Some(state) = self.enabled.get(&inspector) else {
+            eyre::bail!("inspector {inspector} isn't run by this instance")
+        };
+
+        if state.swap(enabled, Ordering::Relaxed) != enabled {
+            info!(target: "brontes::inspect", %inspector, enabled, "toggled inspector");
+        }
+
+        Ok(())
+    }
+
+    /// Disables the given inspectors and enables every other one. Inspectors
+    /// that aren't registered are ignored
+    pub fn apply_disabled(&self, disabled: &FastHashSet<Inspectors>) {
+        for inspector in self.enabled.keys() {
+            let _ = self.set_enabled(*inspector, !disabled.contains(inspector));
+        }
+    }
+
+    /// The registered inspectors and whether they are enabled, in the order
+    /// they were registered in
+    pub fn status(&self) -> Vec<(Inspectors, bool)> {
+        self.constructors
+            .iter()
+            .map(|(inspector, _)| (*inspector, self.is_enabled(*inspector)))
+            .collect()
+    }
+}
+
+/// An inspector of the registry, which is skipped while it is disabled
+struct RegisteredInspector {
+    id:       Inspectors,
+    inner:    DynMevInspector,
+    registry: &'static InspectorRegistry,
+}
+
+impl Inspector for RegisteredInspector {
+    type Result = Vec<Bundle>;
+
+    fn block_window(&self) -> usize {
+        self.inner.block_window()
+    }
+
+    fn get_id(&self) -> &str {
+        self.inner.get_id()
+    }
+
+    /// A disabled inspector finds no bundles, so its results must not be
+    /// mistaken for the ones of the enabled inspector
+    fn cache_id(&self) -> String {
+        if self.registry.is_enabled(self.id) {
+            self.inner.cache_id()
+        } else {
+            format!("{}:disabled", self.inner.get_id())
+        }
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.inner.get_quote_token()
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.registry.is_enabled(self.id)
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        if !self.is_enabled() {
+            return vec![]
+        }
+
+        self.inner.inspect_block(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::{BundleData, BundleHeader};
+
+    use super::*;
+
+    struct OneBundle;
+
+    impl Inspector for OneBundle {
+        type Result = Vec<Bundle>;
+
+        fn get_id(&self) -> &str {
+            "OneBundle"
+        }
+
+        fn get_quote_token(&self) -> Address {
+            Address::ZERO
+        }
+
+        fn inspect_block(&self, _: MultiBlockData) -> Self::Result {
+            vec![Bundle {
+                header: BundleHeader::default(),
+                data:   BundleData::Sandwich(Default::default()),
+            }]
+        }
+    }
+
+    fn registry() -> &'static InspectorRegistry {
+        &*Box::leak(Box::new(
+            InspectorRegistry::default()
+                .with(Inspectors::Sandwich, || &OneBundle)
+                .with(Inspectors::Jit, || &OneBundle),
+        ))
+    }
+
+    fn empty_block() -> MultiBlockData {
+        MultiBlockData { per_block_data: vec![], blocks: 0 }
+    }
+
+    #[test]
+    fn disabled_inspectors_find_no_bundles() {
+        let registry = registry();
+        let inspectors = registry.init();
+        assert_eq!(inspectors.len(), 2);
+        assert_eq!(inspectors[0].inspect_block(empty_block()).len(), 1);
+
+        registry.set_enabled(Inspectors::Sandwich, false).unwrap();
+        assert!(!inspectors[0].is_enabled());
+        assert!(inspectors[0].inspect_block(empty_block()).is_empty());
+        assert_eq!(inspectors[0].cache_id(), "OneBundle:disabled");
+        assert_eq!(inspectors[1].inspect_block(empty_block()).len(), 1);
+
+        registry.set_enabled(Inspectors::Sandwich, true).unwrap();
+        assert_eq!(inspectors[0].inspect_block(empty_block()).len(), 1);
+        assert_eq!(inspectors[0].cache_id(), "OneBundle");
+
+        assert!(registry.set_enabled(Inspectors::CexDex, false).is_err());
+    }
+
+    #[test]
+    fn applies_disabled_set() {
+        let registry = registry();
+
+        registry.apply_disabled(&FastHashSet::from_iter([Inspectors::Jit, Inspectors::CexDex]));
+        assert_eq!(registry.status(), vec![(Inspectors::Sandwich, true), (Inspectors::Jit, false)]);
+
+        registry.apply_disabled(&FastHashSet::default());
+        assert!(registry.is_enabled(Inspectors::Jit));
+        assert!(!registry.is_enabled(Inspectors::CexDex));
+    }
+}
@@ -38,7 +38,7 @@ use brontes_types::{
 };
 use thiserror::Error;
 
-use crate::{composer::run_block_inspection, Inspectors};
+use crate::{composer::run_block_inspection, sandwich::SandwichMode, DetectionLimits, Inspectors};
 
 type StateTests = Option<Box<dyn for<'a> Fn(&'a Bundle)>>;
 
@@ -168,6 +168,8 @@ impl InspectorTestUtils {
                 CexExchange::Kucoin,
             ],
             CexDexTradeConfig::default(),
+            SandwichMode::default(),
+            DetectionLimits::default(),
             None,
         );
         let data = BlockData { metadata: metadata.into(), tree: tree.into() };
@@ -270,6 +272,8 @@ impl InspectorTestUtils {
                 CexExchange::Upbit,
             ],
             cex_trade_config,
+            SandwichMode::default(),
+            DetectionLimits::default(),
             None,
         );
 
@@ -390,6 +394,8 @@ impl InspectorTestUtils {
                     self.classifier_inspector.libmdbx,
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
+                    SandwichMode::default(),
+                    DetectionLimits::default(),
                     None,
                 )
             })
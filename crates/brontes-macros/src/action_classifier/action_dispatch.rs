@@ -36,11 +36,20 @@ impl ActionDispatch {
             .unzip();
 
         let match_stmt = expand_match_dispatch(&rest, &var_name, i);
+        let classifier_count = rest.len();
 
         Ok(quote!(
                     #[derive(Default, Debug)]
                     pub struct #struct_name(#(pub #name,)*);
 
+                    impl #struct_name {
+                        /// The name of each classifier along with the function selector and
+                        /// protocol byte it is dispatched on
+                        pub const CLASSIFIERS: [(&'static str, [u8; 5]); #classifier_count] = [
+                            #((stringify!(#name), #const_fns()),)*
+                        ];
+                    }
+
                     impl crate::ActionCollection for #struct_name {
                         fn dispatch<DB: ::brontes_database::libmdbx::LibmdbxReader
         + ::brontes_database::libmdbx::DBWriter
@@ -59,6 +59,10 @@ use crate::action_classifier::{ActionDispatch, ActionMacro};
 ///      log_data: UniswapV2mintCallLogs|  { <body> });
 /// ```
 ///
+/// If the call can result in different actions, such as a liquidity change
+/// that is either a mint or a burn, use `Action` as the call type and return
+/// the `Action` from the closure.
+///
 /// # Logs Config
 /// NOTE: all log modifiers are compatible with each_other
 /// ## Log Ignore Before
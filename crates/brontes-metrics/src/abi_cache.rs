@@ -0,0 +1,50 @@
+use prometheus::{IntCounter, IntGauge};
+
+#[derive(Clone)]
+pub struct AbiCacheMetrics {
+    hits:    IntCounter,
+    misses:  IntCounter,
+    entries: IntGauge,
+}
+
+impl Default for AbiCacheMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AbiCacheMetrics {
+    pub fn new() -> Self {
+        let hits = prometheus::register_int_counter!(
+            "brontes_abi_cache_hits",
+            "abi lookups served from the in memory cache"
+        )
+        .unwrap();
+
+        let misses = prometheus::register_int_counter!(
+            "brontes_abi_cache_misses",
+            "abi lookups that missed the in memory cache"
+        )
+        .unwrap();
+
+        let entries = prometheus::register_int_gauge!(
+            "brontes_abi_cache_entries",
+            "abis held in the in memory cache"
+        )
+        .unwrap();
+
+        Self { hits, misses, entries }
+    }
+
+    pub fn hit(&self) {
+        self.hits.inc()
+    }
+
+    pub fn miss(&self) {
+        self.misses.inc()
+    }
+
+    pub fn set_entries(&self, entries: usize) {
+        self.entries.set(entries as i64)
+    }
+}
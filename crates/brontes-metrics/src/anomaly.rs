@@ -0,0 +1,226 @@
+//! Flags blocks whose outputs deviate wildly from the blocks before them. A
+//! sudden jump in the mev total, a drop in classification coverage or a spike
+//! in pricing failures is far more often a classifier or pricing regression
+//! than real mev, so these are surfaced before the data reaches dashboards.
+//!
+//! Every executor has a monitor of its own. The range executors process
+//! unrelated block ranges side by side, so a shared window would compare a
+//! block against blocks from another era of the chain.
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use prometheus::{GaugeVec, IntCounterVec};
+
+/// Amount of recent blocks the distributions are built from
+const WINDOW: usize = 1_000;
+/// Blocks needed before anything is flagged
+const MIN_SAMPLES: usize = 100;
+/// Robust z-score past which a block is flagged
+const MAX_DEVIATION: f64 = 8.0;
+/// Scales the median absolute deviation to the standard deviation of a normal
+/// distribution
+const MAD_SCALE: f64 = 1.4826;
+
+static METRICS: OnceLock<AnomalyMetrics> = OnceLock::new();
+
+/// The monitor of an executor, shared with the blocks it processes
+pub type SharedAnomalyMonitor = Arc<Mutex<BlockAnomalyMonitor>>;
+
+/// Registered once and labeled by the executor, as every monitor reports to
+/// the same registry
+struct AnomalyMetrics {
+    anomalies: IntCounterVec,
+    deviation: GaugeVec,
+}
+
+impl AnomalyMetrics {
+    fn get() -> &'static Self {
+        METRICS.get_or_init(|| Self {
+            anomalies: prometheus::register_int_counter_vec!(
+                "brontes_block_anomalies",
+                "blocks whose outputs deviated wildly from the recent blocks",
+                &["executor", "metric"]
+            )
+            .unwrap(),
+            deviation: prometheus::register_gauge_vec!(
+                "brontes_block_output_deviation",
+                "robust z-score of the last block's outputs against the recent blocks",
+                &["executor", "metric"]
+            )
+            .unwrap(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyMetric {
+    /// `ln(1 + usd)` of the total mev profit of the block
+    MevTotal,
+    /// Share of the actions that emitted logs which were classified
+    ClassificationCoverage,
+    /// Share of the bundles that couldn't be priced
+    PricingFailures,
+}
+
+impl AnomalyMetric {
+    const ALL: [Self; 3] = [Self::MevTotal, Self::ClassificationCoverage, Self::PricingFailures];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MevTotal => "mev_total",
+            Self::ClassificationCoverage => "classification_coverage",
+            Self::PricingFailures => "pricing_failures",
+        }
+    }
+
+    /// Lower bound of the spread, so a metric that is flat for a while
+    /// doesn't flag the smallest change
+    fn min_scale(&self) -> f64 {
+        match self {
+            Self::MevTotal => 0.5,
+            Self::ClassificationCoverage => 0.02,
+            Self::PricingFailures => 0.05,
+        }
+    }
+}
+
+/// The outputs of a block the monitor tracks. Metrics that don't apply to the
+/// block, e.g. pricing failures of a block without bundles, are `None`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlockOutputs {
+    pub block_number:            u64,
+    pub total_mev_usd:           f64,
+    pub classification_coverage: Option<f64>,
+    pub pricing_failures:        Option<f64>,
+}
+
+impl BlockOutputs {
+    fn value(&self, metric: AnomalyMetric) -> Option<f64> {
+        match metric {
+            AnomalyMetric::MevTotal => Some(self.total_mev_usd.max(0.0).ln_1p()),
+            AnomalyMetric::ClassificationCoverage => self.classification_coverage,
+            AnomalyMetric::PricingFailures => self.pricing_failures,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Anomaly {
+    pub metric:    AnomalyMetric,
+    pub value:     f64,
+    pub median:    f64,
+    /// Robust z-score of the value
+    pub deviation: f64,
+}
+
+pub struct BlockAnomalyMonitor {
+    /// Label of the executor the monitor belongs to
+    executor:      String,
+    distributions: [RollingDistribution; 3],
+}
+
+impl BlockAnomalyMonitor {
+    pub fn new(executor: impl Into<String>) -> Self {
+        Self {
+            executor:      executor.into(),
+            distributions: AnomalyMetric::ALL.map(RollingDistribution::new),
+        }
+    }
+
+    pub fn shared(executor: impl Into<String>) -> SharedAnomalyMonitor {
+        Arc::new(Mutex::new(Self::new(executor)))
+    }
+
+    /// Checks the outputs of the block against the recent blocks and adds
+    /// them to the distributions, returning the metrics that deviated
+    pub fn observe(&mut self, outputs: BlockOutputs) -> Vec<Anomaly> {
+        let mut anomalies = vec![];
+        let metrics = AnomalyMetrics::get();
+
+        for distribution in &mut self.distributions {
+            let metric = distribution.metric;
+            let Some(value) = outputs.value(metric).filter(|value| value.is_finite()) else {
+                continue
+            };
+
+            if let Some((median, deviation)) = distribution.deviation(value) {
+                metrics
+                    .deviation
+                    .with_label_values(&[&self.executor, metric.as_str()])
+                    .set(deviation);
+
+                if deviation.abs() > MAX_DEVIATION {
+                    metrics
+                        .anomalies
+                        .with_label_values(&[&self.executor, metric.as_str()])
+                        .inc();
+                    tracing::warn!(
+                        target: "brontes::anomaly",
+                        executor = %self.executor,
+                        block = outputs.block_number,
+                        metric = metric.as_str(),
+                        value,
+                        median,
+                        deviation,
+                        "block output deviates from the recent blocks, likely a classifier or \
+                         pricing regression"
+                    );
+                    anomalies.push(Anomaly { metric, value, median, deviation });
+                }
+            }
+
+            distribution.push(value);
+        }
+
+        anomalies
+    }
+}
+
+struct RollingDistribution {
+    metric:  AnomalyMetric,
+    samples: VecDeque<f64>,
+}
+
+impl RollingDistribution {
+    fn new(metric: AnomalyMetric) -> Self {
+        Self { metric, samples: VecDeque::with_capacity(WINDOW) }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    /// The median of the window and the robust z-score of the value against
+    /// it, `None` until enough blocks were seen
+    fn deviation(&self, value: f64) -> Option<(f64, f64)> {
+        if self.samples.len() < MIN_SAMPLES {
+            return None
+        }
+
+        let median = median_of(self.samples.iter().copied().collect());
+        let mad = median_of(
+            self.samples
+                .iter()
+                .map(|sample| (sample - median).abs())
+                .collect(),
+        );
+        let scale = (mad * MAD_SCALE).max(self.metric.min_scale());
+
+        Some((median, (value - median) / scale))
+    }
+}
+
+fn median_of(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
@@ -9,6 +9,8 @@ use prometheus::IntCounterVec;
 #[derive(Clone)]
 pub struct ClassificationMetrics {
     pub bad_protocol_classification: IntCounterVec,
+    pub failed_tx_classification:    IntCounterVec,
+    pub failed_pool_insertion:       IntCounterVec,
 }
 
 impl Default for ClassificationMetrics {
@@ -25,7 +27,19 @@ impl ClassificationMetrics {
             &["protocol"]
         )
         .unwrap();
-        Self { bad_protocol_classification }
+        let failed_tx_classification = prometheus::register_int_counter_vec!(
+            "brontes_failed_tx_classification",
+            "txs skipped as their classification failed",
+            &["error"]
+        )
+        .unwrap();
+        let failed_pool_insertion = prometheus::register_int_counter_vec!(
+            "brontes_failed_pool_insertion",
+            "discovered pools that couldn't be stored, the tx is still classified",
+            &["protocol"]
+        )
+        .unwrap();
+        Self { bad_protocol_classification, failed_tx_classification, failed_pool_insertion }
     }
 
     pub fn bad_protocol_classification(&self, protocol: Protocol) {
@@ -34,4 +48,18 @@ impl ClassificationMetrics {
             .unwrap()
             .inc()
     }
+
+    pub fn failed_tx_classification(&self, error: &str) {
+        self.failed_tx_classification
+            .get_metric_with_label_values(&[error])
+            .unwrap()
+            .inc()
+    }
+
+    pub fn failed_pool_insertion(&self, protocol: Protocol) {
+        self.failed_pool_insertion
+            .get_metric_with_label_values(&[&protocol.to_string()])
+            .unwrap()
+            .inc()
+    }
 }
@@ -217,3 +217,47 @@ impl WriterMetrics {
         }
     }
 }
+
+/// Latency and failures of the buffered clickhouse inserts, by table
+#[derive(Clone)]
+pub struct ClickhouseWriterMetrics {
+    insert_latency: HistogramVec,
+    insert_errors:  IntCounterVec,
+}
+
+impl Default for ClickhouseWriterMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClickhouseWriterMetrics {
+    pub fn new() -> Self {
+        let insert_latency = prometheus::register_histogram_vec!(
+            "clickhouse_insert_latency_ms",
+            "Latency of a batch insert, including its retries",
+            &["table"],
+            prometheus::exponential_buckets(1.0, 2.0, 20).unwrap()
+        )
+        .unwrap();
+
+        let insert_errors = prometheus::register_int_counter_vec!(
+            "clickhouse_insert_errors",
+            "Batch inserts whose rows were dropped after the retries were exhausted",
+            &["table"]
+        )
+        .unwrap();
+
+        Self { insert_latency, insert_errors }
+    }
+
+    pub fn observe_insert(&self, table: &str, duration: Duration, failed: bool) {
+        self.insert_latency
+            .with_label_values(&[table])
+            .observe(duration.as_secs_f64() * 1000.0);
+
+        if failed {
+            self.insert_errors.with_label_values(&[table]).inc();
+        }
+    }
+}
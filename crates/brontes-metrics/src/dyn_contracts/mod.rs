@@ -8,6 +8,12 @@ use tracing::trace;
 use self::types::DynamicContractMetricEvent;
 pub mod types;
 
+/// Functions that get a series of their own. Calls to any other function are
+/// counted under [`OTHER_FUNCTIONS`], so that the series stay bounded however
+/// many contracts are decoded
+pub const MAX_FUNCTION_LABELS: usize = 256;
+pub const OTHER_FUNCTIONS: &str = "other";
+
 #[derive(Debug, Default, Clone)]
 pub struct DynamicContractMetrics {
     contracts: ContractMetrics,
@@ -15,28 +21,35 @@ pub struct DynamicContractMetrics {
 }
 
 impl DynamicContractMetrics {
-    /// Returns existing or initializes a new instance of [ContractMetrics]
-    pub(crate) fn get_contract_metrics(&mut self, _address: String) -> &mut ContractMetrics {
-        &mut self.contracts
-    }
-
     /// Returns existing or initializes a new instance of
-    /// [ContractFunctionMetrics]
+    /// [ContractFunctionMetrics], the metrics of [`OTHER_FUNCTIONS`] once
+    /// [`MAX_FUNCTION_LABELS`] functions are tracked
     pub(crate) fn get_function_metrics(
         &mut self,
         function_name: String,
     ) -> &mut ContractFunctionMetrics {
+        let label = if self.functions.contains_key(&function_name)
+            || self.functions.len() < MAX_FUNCTION_LABELS
+        {
+            function_name
+        } else {
+            OTHER_FUNCTIONS.to_string()
+        };
+
         self.functions
-            .entry(function_name.clone())
-            .or_insert_with(|| {
-                ContractFunctionMetrics::new_with_labels(&[("functions", function_name)])
-            })
+            .entry(label.clone())
+            .or_insert_with(|| ContractFunctionMetrics::new_with_labels(&[("functions", label)]))
     }
 
     pub(crate) fn handle_event(&mut self, event: DynamicContractMetricEvent) {
         trace!(target: "tracing::metrics", ?event, "Metric event received");
         match event {
-            DynamicContractMetricEvent::ContractMetricRecieved(_) => panic!("NOT IMPLEMENTED YET"),
+            DynamicContractMetricEvent::ContractMetricRecieved(metric) => {
+                self.contracts.times_called.increment(1);
+                self.get_function_metrics(metric.function_called)
+                    .times_called
+                    .increment(1);
+            }
         }
     }
 }
@@ -54,3 +67,22 @@ pub(crate) struct ContractFunctionMetrics {
     /// The number of times the function on the contract has been called
     pub(crate) times_called: Counter,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_labels_are_bounded() {
+        let mut metrics = DynamicContractMetrics::default();
+        for i in 0..MAX_FUNCTION_LABELS + 10 {
+            metrics.get_function_metrics(format!("function_{i}"));
+        }
+        assert_eq!(metrics.functions.len(), MAX_FUNCTION_LABELS + 1);
+        assert!(metrics.functions.contains_key(OTHER_FUNCTIONS));
+
+        // functions that already have a series keep it
+        metrics.get_function_metrics("function_0".to_string());
+        assert_eq!(metrics.functions.len(), MAX_FUNCTION_LABELS + 1);
+    }
+}
@@ -161,6 +161,69 @@ impl OutlierMetrics {
     }
 }
 
+/// Totals of the inspector runs recorded by the composer, by inspector id
+#[derive(Clone)]
+pub struct InspectorRunMetrics {
+    runs:       IntCounterVec,
+    elapsed_us: IntCounterVec,
+    candidates: IntCounterVec,
+    bundles:    IntCounterVec,
+}
+
+impl Default for InspectorRunMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InspectorRunMetrics {
+    pub fn new() -> Self {
+        let runs = prometheus::register_int_counter_vec!(
+            "brontes_inspector_runs",
+            "the amount of blocks the inspector was run on",
+            &["inspector"]
+        )
+        .unwrap();
+        let elapsed_us = prometheus::register_int_counter_vec!(
+            "brontes_inspector_elapsed_us",
+            "the total runtime of the inspector in microseconds",
+            &["inspector"]
+        )
+        .unwrap();
+        let candidates = prometheus::register_int_counter_vec!(
+            "brontes_inspector_candidates",
+            "the amount of bundles returned by the inspector",
+            &["inspector"]
+        )
+        .unwrap();
+        let bundles = prometheus::register_int_counter_vec!(
+            "brontes_inspector_bundles",
+            "the amount of bundles of the inspector that were emitted after deduplication",
+            &["inspector"]
+        )
+        .unwrap();
+
+        Self { runs, elapsed_us, candidates, bundles }
+    }
+
+    pub fn inspector_run(&self, inspector: &str, elapsed_us: u64, candidates: u64, bundles: u64) {
+        self.runs.with_label_values(&[inspector]).inc();
+        self.elapsed_us
+            .with_label_values(&[inspector])
+            .inc_by(elapsed_us);
+        self.candidates
+            .with_label_values(&[inspector])
+            .inc_by(candidates);
+        self.bundles.with_label_values(&[inspector]).inc_by(bundles);
+    }
+}
+
+impl std::fmt::Debug for InspectorRunMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InspectorRunMetrics").finish()
+    }
+}
+
 impl std::fmt::Debug for OutlierMetrics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("OutlierMetrics").finish()
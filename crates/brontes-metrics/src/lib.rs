@@ -12,6 +12,8 @@ use futures::Future;
 use tracing::trace;
 
 use crate::trace::{types::TraceMetricEvent, TraceMetrics};
+pub mod abi_cache;
+pub mod anomaly;
 pub mod classifier;
 pub mod db_cache;
 pub mod db_initialization;
@@ -39,7 +41,7 @@ pub enum ParserMetricEvents {
 pub struct ParserMetricsListener {
     events_rx:        UnboundedYapperReceiver<ParserMetricEvents>,
     tx_metrics:       TraceMetrics,
-    contract_metrics: HashMap<String, DynamicContractMetrics>,
+    contract_metrics: DynamicContractMetrics,
 }
 
 impl ParserMetricsListener {
@@ -49,7 +51,7 @@ impl ParserMetricsListener {
         Self {
             events_rx,
             tx_metrics: TraceMetrics::default(),
-            contract_metrics: HashMap::default(),
+            contract_metrics: DynamicContractMetrics::default(),
         }
     }
 
@@ -57,9 +59,10 @@ impl ParserMetricsListener {
         trace!(target: "tracing::metrics", ?event, "Metric event received");
         match event {
             ParserMetricEvents::TraceMetricRecieved(val) => self.tx_metrics.handle_event(val),
+            // not keyed by contract, as every decoded contract would add
+            // its own series
             ParserMetricEvents::DynamicContractMetricRecieved(val) => {
-                let this = self.contract_metrics.entry(val.get_addr()).or_default();
-                this.handle_event(val)
+                self.contract_metrics.handle_event(val)
             }
         }
     }
@@ -72,8 +75,7 @@ impl Future for ParserMetricsListener {
         let this = self.get_mut();
 
         while let Poll::Ready(Some(event)) = this.events_rx.poll_recv(cx) {
-            drop(event);
-            // this.handle_event(event);
+            this.handle_event(event);
         }
 
         cx.waker().wake_by_ref();
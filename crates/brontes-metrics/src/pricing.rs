@@ -24,6 +24,8 @@ pub struct DexPricingMetrics {
     pub poll_rate:           IntCounterVec,
     /// wants more blocks
     pub needs_more_data:     IntGaugeVec,
+    /// how many blocks pricing is behind the latest block it has received
+    pub pricing_lag:         IntGaugeVec,
 }
 impl Default for DexPricingMetrics {
     fn default() -> Self {
@@ -71,8 +73,16 @@ impl DexPricingMetrics {
         )
         .unwrap();
 
+        let pricing_lag = prometheus::register_int_gauge_vec!(
+            "dex_pricing_lag_blocks",
+            "the amount of blocks dex pricing is behind the latest received block",
+            &["range_id"]
+        )
+        .unwrap();
+
         Self {
             needs_more_data,
+            pricing_lag,
             processed_blocks,
             state_load_time_ms,
             state_load_queries,
@@ -90,6 +100,12 @@ impl DexPricingMetrics {
             .set(enabled as i64);
     }
 
+    pub fn pricing_lag(&self, range_id: usize, lag: u64) {
+        self.pricing_lag
+            .with_label_values(&[&range_id.to_string()])
+            .set(lag as i64);
+    }
+
     pub fn poll_rate(&self, range_id: usize) {
         self.poll_rate
             .get_metric_with_label_values(&[&range_id.to_string()])
@@ -192,3 +192,16 @@ pub struct FinishedRange {
     /// if at tip, then this is the range at init
     pub total_set_range: Counter,
 }
+
+#[derive(Metrics, Clone)]
+#[metrics(scope = "brontes_tip")]
+pub struct TipMetrics {
+    /// the best block of the node
+    pub chain_tip:      Gauge,
+    /// the latest block started at the tip
+    pub current_block:  Gauge,
+    /// blocks between the chain tip and the latest block started
+    pub blocks_behind:  Gauge,
+    /// seconds between the block timestamp and its results being written
+    pub block_lag_secs: Histogram,
+}
@@ -6,11 +6,13 @@ use tracing::trace;
 pub mod types;
 pub mod utils;
 
+use self::types::{TraceStats, TransactionStats};
 use super::TraceMetricEvent;
 
 #[derive(Debug, Default, Clone)]
 pub struct TraceMetrics {
-    txs: HashMap<String, TransactionTracingMetrics>,
+    txs:     HashMap<String, TransactionTracingMetrics>,
+    parsing: TraceParsingMetrics,
 }
 
 impl TraceMetrics {
@@ -27,15 +29,62 @@ impl TraceMetrics {
 
     pub(crate) fn handle_event(&mut self, event: TraceMetricEvent) {
         trace!(target: "tracing::metrics", ?event, "Metric event received");
-        // match event {
-        //     TraceMetricEvent::TraceMetricRecieved(_) => panic!("NOT
-        // IMPLEMENTED YET"),
-        //     TraceMetricEvent::TransactionMetricRecieved(_) => panic!("NOT
-        // IMPLEMENTED YET"),
-        //     TraceMetricEvent::BlockMetricRecieved(_) => panic!("NOT
-        // IMPLEMENTED YET"), }
+        match event {
+            TraceMetricEvent::BlockMetricRecieved(block) => {
+                self.parsing.block_num.set(block.block_num as f64);
+                if block.err.is_some() {
+                    self.parsing.block_errors.increment(1);
+                } else {
+                    self.parsing.blocks_parsed.increment(1);
+                }
+                block.txs.into_iter().for_each(|tx| self.record_tx(tx));
+            }
+            TraceMetricEvent::TransactionMetricRecieved(tx) => self.record_tx(tx),
+            TraceMetricEvent::TraceMetricRecieved(trace) => self.record_trace(trace),
+        }
+    }
+
+    fn record_tx(&mut self, tx: TransactionStats) {
+        if tx.err.is_some() {
+            self.parsing.tx_errors.increment(1);
+        } else {
+            self.parsing.txs_parsed.increment(1);
+        }
+        tx.traces
+            .into_iter()
+            .for_each(|trace| self.record_trace(trace));
+    }
+
+    fn record_trace(&mut self, trace: TraceStats) {
+        if trace.err.is_some() {
+            self.parsing.trace_errors.increment(1);
+        } else {
+            self.parsing.traces_parsed.increment(1);
+        }
     }
 }
+
+/// Totals over all parsed blocks. Kept separate from the per transaction
+/// metrics so that the series don't grow with every transaction hash.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "trace_parsing")]
+pub(crate) struct TraceParsingMetrics {
+    /// The last block parsed
+    pub(crate) block_num:     Gauge,
+    /// Blocks whose traces were parsed
+    pub(crate) blocks_parsed: Counter,
+    /// Blocks whose traces or receipts couldn't be fetched
+    pub(crate) block_errors:  Counter,
+    /// Transactions whose traces were parsed
+    pub(crate) txs_parsed:    Counter,
+    /// Transactions that failed to parse
+    pub(crate) tx_errors:     Counter,
+    /// Individual call traces that were parsed
+    pub(crate) traces_parsed: Counter,
+    /// Individual call traces that failed to parse
+    pub(crate) trace_errors:  Counter,
+}
+
 #[allow(dead_code)]
 #[derive(Metrics, Clone)]
 #[metrics(scope = "transaction_tracing")]
@@ -0,0 +1,341 @@
+//! Snapshots of the pricing graph of a block, for debugging quotes offline.
+//!
+//! When enabled, the subgraphs every pair of the block was priced through are
+//! written to a file per block once the block is resolved, along with the end
+//! of block state of their pools, the route the price was derived over and
+//! the prices stored for each tx. The snapshot is taken before prices with a
+//! drastic movement are removed, so the removed quotes can be inspected too.
+//! The snapshots are stored bincode encoded and written on the blocking pool,
+//! off the task the pricer runs on.
+//!
+//! A snapshot is loaded with [`PriceGraphSnapshot::load_block`] and the price
+//! of a pair can be re-derived from the recorded state with
+//! [`PriceGraphSnapshot::replay_price`].
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use alloy_primitives::Address;
+use brontes_types::{
+    db::dex::{DexPrices, DexQuotes},
+    pair::Pair,
+    FastHashMap,
+};
+use malachite::{
+    num::{
+        arithmetic::traits::Reciprocal,
+        basic::traits::{One, Zero},
+    },
+    Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graphs::{weighted_edge_price, PairSubGraph},
+    types::{PoolState, PoolVariants},
+    Protocol,
+};
+
+const SNAPSHOT_EXTENSION: &str = "graph.bin";
+
+static SNAPSHOT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Writes a snapshot of the pricing graph of every resolved block into `dir`
+pub fn enable_price_graph_snapshots(dir: PathBuf) -> eyre::Result<()> {
+    std::fs::create_dir_all(&dir)?;
+    SNAPSHOT_DIR
+        .set(dir)
+        .map_err(|_| eyre::eyre!("price graph snapshots were already enabled"))
+}
+
+pub fn price_graph_snapshot_dir() -> Option<&'static Path> {
+    SNAPSHOT_DIR.get().map(PathBuf::as_path)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceGraphSnapshot {
+    pub block:     u64,
+    pub quote:     Address,
+    /// End of block state of the pools of the subgraphs
+    pub pools:     Vec<PoolSnapshot>,
+    pub subgraphs: Vec<SubGraphSnapshot>,
+    /// The prices stored for the block, by tx index
+    pub prices:    Vec<TxPriceSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub address:     Address,
+    pub protocol:    Protocol,
+    pub last_update: u64,
+    pub state:       PoolVariants,
+}
+
+impl PoolSnapshot {
+    fn new(state: &PoolState) -> Self {
+        Self {
+            address:     state.address(),
+            protocol:    state.dex(),
+            last_update: state.last_update,
+            state:       state.variant().clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubGraphSnapshot {
+    pub pair:          Pair,
+    pub goes_through:  Pair,
+    pub complete_pair: Pair,
+    /// The pair this subgraph is chained with to reach the quote
+    pub extends_to:    Option<Pair>,
+    pub edges:         Vec<EdgeSnapshot>,
+    /// The tokens of the path the price was derived over, empty if the
+    /// subgraph couldn't price the pair
+    pub route:         Vec<Address>,
+    pub price:         Option<Rational>,
+}
+
+impl SubGraphSnapshot {
+    fn new(
+        goes_through: Pair,
+        graph: &PairSubGraph,
+        state: &FastHashMap<Address, &PoolState>,
+    ) -> Self {
+        let (price, route) = graph
+            .priced_route(state)
+            .map(|(price, route)| (Some(price), route))
+            .unwrap_or_default();
+
+        Self {
+            pair: graph.get_unordered_pair(),
+            goes_through,
+            complete_pair: graph.complete_pair(),
+            extends_to: graph.extends_to(),
+            edges: graph
+                .edges()
+                .map(|(token_in, token_out, pools)| EdgeSnapshot { token_in, token_out, pools })
+                .collect(),
+            route,
+            price,
+        }
+    }
+
+    fn pools(&self) -> impl Iterator<Item = Address> + '_ {
+        self.edges
+            .iter()
+            .flat_map(|edge| edge.pools.iter().copied())
+    }
+
+    /// Re-derives the price along the recorded route from the pool states
+    fn route_price(&self, pools: &FastHashMap<Address, PoolState>) -> Option<Rational> {
+        if self.route.is_empty() {
+            return None
+        }
+
+        self.route.windows(2).try_fold(Rational::ONE, |price, hop| {
+            let edge = self
+                .edges
+                .iter()
+                .find(|edge| edge.token_in == hop[0] && edge.token_out == hop[1])?;
+            let (hop_price, ..) = weighted_edge_price(
+                edge.pools
+                    .iter()
+                    .filter_map(|pool| pools.get(pool))
+                    .map(|pool| (pool, hop[0])),
+            )?;
+
+            Some(price * hop_price)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeSnapshot {
+    pub token_in:  Address,
+    pub token_out: Address,
+    pub pools:     Vec<Address>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxPriceSnapshot {
+    pub tx_idx: u64,
+    pub pair:   Pair,
+    pub price:  DexPrices,
+}
+
+impl PriceGraphSnapshot {
+    pub(crate) fn new(
+        block: u64,
+        quote: Address,
+        quotes: &DexQuotes,
+        subgraphs: impl IntoIterator<Item = (Pair, &PairSubGraph)>,
+        state: &FastHashMap<Address, &PoolState>,
+    ) -> Self {
+        let subgraphs = subgraphs
+            .into_iter()
+            .map(|(goes_through, graph)| SubGraphSnapshot::new(goes_through, graph, state))
+            .collect::<Vec<_>>();
+
+        let mut pools = subgraphs
+            .iter()
+            .flat_map(SubGraphSnapshot::pools)
+            .filter_map(|pool| state.get(&pool))
+            .map(|state| PoolSnapshot::new(state))
+            .collect::<Vec<_>>();
+        pools.sort_by_key(|pool| pool.address);
+        pools.dedup_by_key(|pool| pool.address);
+
+        let prices = quotes
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(tx_idx, prices)| Some((tx_idx as u64, prices.as_ref()?)))
+            .flat_map(|(tx_idx, prices)| {
+                prices.iter().map(move |(pair, price)| TxPriceSnapshot {
+                    tx_idx,
+                    pair: *pair,
+                    price: price.clone(),
+                })
+            })
+            .collect();
+
+        Self { block, quote, pools, subgraphs, prices }
+    }
+
+    pub fn path(dir: &Path, block: u64) -> PathBuf {
+        dir.join(format!("{block}.{SNAPSHOT_EXTENSION}"))
+    }
+
+    /// Writes the snapshot into `dir`, returning the path of the file
+    pub fn write(&self, dir: &Path) -> eyre::Result<PathBuf> {
+        let path = Self::path(dir, self.block);
+        let mut writer = BufWriter::new(File::create(&path)?);
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(bincode::deserialize_from(BufReader::new(File::open(path)?))?)
+    }
+
+    pub fn load_block(dir: &Path, block: u64) -> eyre::Result<Self> {
+        Self::load(&Self::path(dir, block))
+    }
+
+    /// The prices stored for the pair over the block
+    pub fn prices_of(&self, pair: Pair) -> impl Iterator<Item = &TxPriceSnapshot> + '_ {
+        self.prices.iter().filter(move |price| price.pair == pair)
+    }
+
+    /// Re-derives the end of block price of the pair from the recorded pool
+    /// states, the same way the pricer does: through the subgraph for
+    /// `goes_through`, chained with the subgraphs of the pair it extends to,
+    /// or the average over all subgraphs of the pair if there is none.
+    pub fn replay_price(&self, pair: Pair, goes_through: Pair) -> Option<Rational> {
+        let pools = self
+            .pools
+            .iter()
+            .map(|pool| (pool.address, PoolState::new(pool.state.clone(), pool.last_update)))
+            .collect::<FastHashMap<_, _>>();
+
+        let Some(graph) = self.subgraphs.iter().find(|graph| {
+            graph.pair.ordered() == pair.ordered()
+                && graph.goes_through.ordered() == goes_through.ordered()
+        }) else {
+            return self.replay_average_price(pair, &pools)
+        };
+
+        let price = graph.route_price(&pools)?;
+        let Some(next) = graph.extends_to else { return Some(price) };
+        let price = self.replay_average_price(next, &pools)? * price;
+
+        if pair.eq_unordered(&graph.complete_pair) {
+            Some(price)
+        } else {
+            Some(price.reciprocal())
+        }
+    }
+
+    fn replay_average_price(
+        &self,
+        pair: Pair,
+        pools: &FastHashMap<Address, PoolState>,
+    ) -> Option<Rational> {
+        let (sum, cnt) = self
+            .subgraphs
+            .iter()
+            .filter(|graph| graph.pair.ordered() == pair.ordered() && graph.extends_to.is_none())
+            .filter_map(|graph| {
+                let price = graph.route_price(pools)?;
+                Some(if pair.eq_unordered(&graph.pair) { price } else { price.reciprocal() })
+            })
+            .fold((Rational::ZERO, Rational::ZERO), |(sum, cnt), price| {
+                (sum + price, cnt + Rational::ONE)
+            });
+
+        (cnt != Rational::ZERO).then(|| sum / cnt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uniswap_v2::UniswapV2Pool;
+
+    #[test]
+    fn test_replay_price() {
+        let [weth, usdt, pool] = [1, 2, 3].map(Address::with_last_byte);
+        let pair = Pair(weth, usdt);
+        let state = UniswapV2Pool::new(
+            pool,
+            weth,
+            18,
+            usdt,
+            6,
+            10 * 10u128.pow(18),
+            30_000 * 10u128.pow(6),
+            3,
+        );
+
+        let snapshot = PriceGraphSnapshot {
+            block:     1,
+            quote:     usdt,
+            pools:     vec![PoolSnapshot {
+                address:     pool,
+                protocol:    Protocol::UniswapV2,
+                last_update: 1,
+                state:       PoolVariants::UniswapV2(Box::new(state)),
+            }],
+            subgraphs: vec![SubGraphSnapshot {
+                pair,
+                goes_through: pair,
+                complete_pair: pair,
+                extends_to: None,
+                edges: vec![EdgeSnapshot {
+                    token_in:  weth,
+                    token_out: usdt,
+                    pools:     vec![pool],
+                }],
+                route: vec![weth, usdt],
+                price: None,
+            }],
+            prices:    vec![],
+        };
+        let dir = std::env::temp_dir().join(format!("price-graph-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = snapshot.write(&dir).unwrap();
+        let snapshot = PriceGraphSnapshot::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(snapshot.replay_price(pair, pair), Some(Rational::from(3_000)));
+        // without a subgraph for the goes through, all subgraphs of the pair are
+        // averaged
+        assert_eq!(snapshot.replay_price(pair, Pair(weth, pool)), Some(Rational::from(3_000)));
+    }
+}
@@ -12,6 +12,8 @@ mod subgraph_verifier;
 pub use all_pair_graph::AllPairGraph;
 use alloy_primitives::Address;
 use brontes_types::{
+    db::dex::DexQuotes,
+    denylist::denylist,
     pair::Pair,
     price_graph_types::{PoolPairInfoDirection, SubGraphEdge},
 };
@@ -19,6 +21,7 @@ use itertools::Itertools;
 use malachite::{num::basic::traits::One, Rational};
 use tracing::error_span;
 
+pub(crate) use self::subgraph::weighted_edge_price;
 pub use self::{
     registry::SubGraphRegistry,
     state_tracker::{StateTracker, StateWithDependencies},
@@ -27,6 +30,7 @@ pub use self::{
 };
 use super::PoolUpdate;
 use crate::{
+    graph_snapshot::PriceGraphSnapshot,
     types::{PairWithFirstPoolHop, PoolState},
     Protocol,
 };
@@ -71,6 +75,11 @@ impl GraphManager {
         all_pool_data: FastHashMap<(Address, Protocol), Pair>,
         metrics: Option<DexPricingMetrics>,
     ) -> Self {
+        let denylist = denylist();
+        let all_pool_data = all_pool_data
+            .into_iter()
+            .filter(|((pool, _), pair)| !denylist.denies_pool(*pool, *pair))
+            .collect();
         let graph = AllPairGraph::init_from_hash_map(all_pool_data);
         let registry = SubGraphRegistry::new(metrics.clone());
         let subgraph_verifier = SubgraphVerifier::new();
@@ -101,6 +110,9 @@ impl GraphManager {
     }
 
     pub fn add_pool(&mut self, pair: Pair, pool_addr: Address, dex: Protocol, block: u64) {
+        if denylist().denies_pool(pool_addr, pair) {
+            return
+        }
         self.all_pair_graph.add_node(pair, pool_addr, dex, block);
     }
 
@@ -347,4 +359,40 @@ impl GraphManager {
     pub fn verification_done_for_block(&self, block: u64) -> bool {
         self.subgraph_verifier.is_done_block(block)
     }
+
+    /// Snapshots the subgraphs the pairs of the quotes were priced through,
+    /// along with the subgraphs they extend to
+    pub fn snapshot_block(
+        &self,
+        block: u64,
+        quote: Address,
+        quotes: &DexQuotes,
+    ) -> PriceGraphSnapshot {
+        let mut pending = quotes
+            .0
+            .iter()
+            .flatten()
+            .flat_map(|prices| prices.keys().copied())
+            .collect_vec();
+        let mut seen = FastHashSet::default();
+        let mut subgraphs = vec![];
+
+        while let Some(pair) = pending.pop() {
+            if !seen.insert(pair.ordered()) {
+                continue
+            }
+            for (goes_through, graph) in self.sub_graph_registry.subgraphs_of(pair) {
+                pending.extend(graph.extends_to());
+                subgraphs.push((*goes_through, graph));
+            }
+        }
+
+        PriceGraphSnapshot::new(
+            block,
+            quote,
+            quotes,
+            subgraphs,
+            &self.graph_state.finalized_state(),
+        )
+    }
 }
@@ -152,6 +152,11 @@ impl SubGraphRegistry {
             .flatten()
     }
 
+    /// All subgraphs of the pair, keyed by the pair they go through
+    pub fn subgraphs_of(&self, pair: Pair) -> impl Iterator<Item = (&Pair, &PairSubGraph)> + '_ {
+        self.sub_graphs.get(&pair.ordered()).into_iter().flatten()
+    }
+
     pub fn all_pairs_with_quote(&self, addr: Address) -> Vec<Pair> {
         self.sub_graphs
             .iter()
@@ -868,6 +868,60 @@ impl PairSubGraph {
     }
 
     pub fn dijkstra_path<T>(&self, state: &FastHashMap<Address, &T>) -> Option<Rational>
+    where
+        T: ProtocolState,
+    {
+        self.dijkstra(state).map(|(price, _)| price)
+    }
+
+    /// The price along with the tokens of the path it was derived over, from
+    /// the start to the end token
+    pub fn priced_route<T>(
+        &self,
+        state: &FastHashMap<Address, &T>,
+    ) -> Option<(Rational, Vec<Address>)>
+    where
+        T: ProtocolState,
+    {
+        let (price, previous) = self.dijkstra(state)?;
+        let index_to_token = self
+            .token_to_index
+            .iter()
+            .map(|(token, index)| (NodeIndex::<u16>::from(*index), *token))
+            .collect::<FastHashMap<_, _>>();
+
+        let start: NodeIndex<u16> = self.start_node.into();
+        let mut node: NodeIndex<u16> = self.end_node.into();
+        let mut route = vec![*index_to_token.get(&node)?];
+        while node != start {
+            node = *previous.get(&node)?;
+            route.push(*index_to_token.get(&node)?);
+        }
+        route.reverse();
+
+        Some((price, route))
+    }
+
+    /// The edges of the subgraph as the token in, the token out and the pools
+    /// between them
+    pub fn edges(&self) -> impl Iterator<Item = (Address, Address, Vec<Address>)> + '_ {
+        self.graph.edge_weights().filter_map(|pools| {
+            let first = pools.first()?;
+            Some((
+                first.get_base_token(),
+                first.get_quote_token(),
+                pools.iter().map(|pool| pool.pool_addr).collect(),
+            ))
+        })
+    }
+
+    /// Returns the price at the goal node and the node each node on the
+    /// cheapest path was reached from
+    #[allow(clippy::type_complexity)]
+    fn dijkstra<T>(
+        &self,
+        state: &FastHashMap<Address, &T>,
+    ) -> Option<(Rational, FastHashMap<NodeIndex<u16>, NodeIndex<u16>>)>
     where
         T: ProtocolState,
     {
@@ -878,6 +932,7 @@ impl PairSubGraph {
         let mut visited = graph.visit_map();
         let mut scores = FastHashMap::default();
         let mut node_price = FastHashMap::default();
+        let mut previous = FastHashMap::default();
         let mut visit_next = BinaryHeap::new();
         let zero_score = Rational::ZERO;
         scores.insert(start, zero_score.clone());
@@ -900,38 +955,20 @@ impl PairSubGraph {
                     continue
                 }
 
-                let mut pxw = Rational::ZERO;
-                let mut weight = Rational::ZERO;
-                let mut token_0_am = Rational::ZERO;
-                let mut token_1_am = Rational::ZERO;
-
                 // calculate tvl of pool using the start token as the quote
-
-                for info in edge_weight {
+                let pools = edge_weight.iter().filter_map(|info| {
                     let Some(pool_state) = state.get(&info.pool_addr) else {
                         tracing::debug!(addr=?info.pool_addr,"failed to fetch pool state while generating price");
-                        continue;
-                    };
-
-                    let Ok(pool_price) = pool_state.price(info.get_base_token()) else {
-                        continue;
+                        return None
                     };
-
-                    let (t0, t1) = pool_state.tvl(info.get_base_token());
-
-                    let t0xt1 = &t0 * &t1;
-                    pxw += pool_price * &t0xt1;
-                    weight += t0xt1;
-
-                    token_0_am += t0;
-                    token_1_am += t1;
-                }
-
-                if weight == Rational::ZERO {
+                    Some((*pool_state, info.get_base_token()))
+                });
+                let Some((local_weighted_price, token_0_am, token_1_am)) =
+                    weighted_edge_price(pools)
+                else {
                     continue
-                }
+                };
 
-                let local_weighted_price = pxw / weight;
                 let token_0_priced = token_0_am * price.clone().reciprocal();
                 let new_price = &price * local_weighted_price;
                 let token_1_priced = token_1_am * new_price.clone().reciprocal();
@@ -944,20 +981,51 @@ impl PairSubGraph {
                             *ent.into_mut() = next_score.clone();
                             visit_next.push(MinScored(next_score, (next, new_price.clone())));
                             node_price.insert(next, new_price);
+                            previous.insert(next, node);
                         }
                     }
                     Vacant(ent) => {
                         ent.insert(next_score.clone());
                         visit_next.push(MinScored(next_score, (next, new_price.clone())));
                         node_price.insert(next, new_price);
+                        previous.insert(next, node);
                     }
                 }
             }
             visited.visit(node);
         }
 
-        node_price.remove(&goal)
+        node_price.remove(&goal).map(|price| (price, previous))
+    }
+}
+
+/// The price of the pools of an edge weighted by the product of their
+/// reserves, along with the summed reserves of both tokens. The pools are
+/// given with the token they are priced in.
+pub(crate) fn weighted_edge_price<'a, T: ProtocolState + 'a>(
+    pools: impl IntoIterator<Item = (&'a T, Address)>,
+) -> Option<(Rational, Rational, Rational)> {
+    let mut pxw = Rational::ZERO;
+    let mut weight = Rational::ZERO;
+    let mut token_0_am = Rational::ZERO;
+    let mut token_1_am = Rational::ZERO;
+
+    for (pool_state, base) in pools {
+        let Ok(pool_price) = pool_state.price(base) else {
+            continue;
+        };
+
+        let (t0, t1) = pool_state.tvl(base);
+
+        let t0xt1 = &t0 * &t1;
+        pxw += pool_price * &t0xt1;
+        weight += t0xt1;
+
+        token_0_am += t0;
+        token_1_am += t1;
     }
+
+    (weight != Rational::ZERO).then(|| (pxw / weight, token_0_am, token_1_am))
 }
 
 const MAX_TVL_WEIGHT: Rational = Rational::const_from_unsigned(100_000_000_000u64);
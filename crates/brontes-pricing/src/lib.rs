@@ -22,15 +22,17 @@
 
 use brontes_metrics::pricing::DexPricingMetrics;
 use brontes_types::{
-    db::dex::PriceAt, execute_on, normalized_actions::pool::NormalizedPoolConfigUpdate,
+    db::dex::PriceAt, execute_on, is_deterministic_mode, normalized_actions::NormalizedAction,
     BrontesTaskExecutor, UnboundedYapperReceiver,
 };
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::graphs::StateWithDependencies;
+use crate::{graphs::StateWithDependencies, staking::StakingRates};
 pub mod function_call_bench;
+pub mod graph_snapshot;
 mod graphs;
 pub mod protocols;
+mod staking;
 mod subgraph_query;
 pub mod types;
 use std::{
@@ -48,11 +50,13 @@ pub use brontes_types::price_graph_types::{
 };
 use brontes_types::{
     db::dex::{DexPrices, DexQuotes},
+    denylist::denylist,
     pair::Pair,
     traits::TracingProvider,
     FastHashMap, FastHashSet,
 };
 use futures::Stream;
+use graph_snapshot::price_graph_snapshot_dir;
 pub use graphs::{
     AllPairGraph, GraphManager, StateTracker, SubGraphRegistry, SubgraphVerifier,
     VerificationResults,
@@ -110,6 +114,9 @@ pub struct BrontesBatchPricer<T: TracingProvider> {
     overlap_update:  Option<PoolUpdate>,
     /// a queue of blocks that we should skip pricing for and just upkeep state
     skip_pricing:    VecDeque<u64>,
+    /// exchange rates of staking tokens, used to price the ones without a
+    /// route to the quote asset
+    staking_rates:   StakingRates,
     /// metrics
     metrics:         Option<DexPricingMetrics>,
 }
@@ -143,6 +150,7 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
             completed_block: current_block,
             overlap_update: None,
             skip_pricing: VecDeque::new(),
+            staking_rates: StakingRates::default(),
             needs_more_data,
             metrics,
         }
@@ -187,7 +195,7 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
     /// latest block data, maintaining the integrity and accuracy of
     /// the decentralized exchange pricing mechanism.
     #[brontes_macros::metrics_call(ptr=metrics,function_call_count, self.range_id, "on_pool_updates")]
-    fn on_pool_updates(&mut self, updates: Vec<PoolUpdate>) {
+    fn on_pool_updates(&mut self, mut updates: Vec<PoolUpdate>) {
         if updates.is_empty() {
             return
         };
@@ -198,6 +206,18 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
             }
         }
 
+        // never price through denylisted pools or tokens
+        let denylist = denylist();
+        updates.retain(|update| {
+            !denylist.denies_pool(
+                update.get_pool_address(),
+                update.get_pair(self.quote_asset).unwrap_or_default(),
+            )
+        });
+        if updates.is_empty() {
+            return
+        }
+
         // insert new pools accessed on this block.
         updates
             .iter()
@@ -1030,14 +1050,18 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
             .remove(&self.completed_block)
             .unwrap_or(DexQuotes(vec![]));
 
+        self.snapshot_price_graph(block, &res);
         self.handle_drastic_price_changes(&mut res);
+        self.staking_rates
+            .derive_prices(block, &mut res, self.quote_asset);
         // prune dead subgraphs
         self.graph_manager
             .prune_dead_subgraphs(self.completed_block);
 
-        self.metrics
-            .as_ref()
-            .inspect(|m| m.range_finished_block(self.range_id));
+        self.metrics.as_ref().inspect(|m| {
+            m.range_finished_block(self.range_id);
+            m.pricing_lag(self.range_id, self.current_block.saturating_sub(self.completed_block));
+        });
         self.should_return().then_some((block, res))
     }
 
@@ -1056,6 +1080,22 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
         res
     }
 
+    /// Writes the pricing graph the quotes of the block were derived from if
+    /// snapshots are enabled. The snapshot is taken here while the graph is in
+    /// the state of the block, the file is written on the blocking pool.
+    fn snapshot_price_graph(&self, block: u64, quotes: &DexQuotes) {
+        let Some(dir) = price_graph_snapshot_dir() else { return };
+
+        let snapshot = self
+            .graph_manager
+            .snapshot_block(block, self.quote_asset, quotes);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = snapshot.write(dir) {
+                error!(%block, error = %e, "failed to write price graph snapshot");
+            }
+        });
+    }
+
     /// For the given DexQuotes, checks to see if the start price vs the end
     /// price contains a drastic change. This is done to avoid incorrect
     /// prices. prices can have drastic changes within the block (think
@@ -1161,14 +1201,18 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
             .remove(&self.completed_block)
             .unwrap_or(DexQuotes(vec![]));
 
+        self.snapshot_price_graph(block, &res);
         self.handle_drastic_price_changes(&mut res);
+        self.staking_rates
+            .derive_prices(block, &mut res, self.quote_asset);
         // prune dead subgraphs
         self.graph_manager
             .prune_dead_subgraphs(self.completed_block);
 
-        self.metrics
-            .as_ref()
-            .inspect(|m| m.range_finished_block(self.range_id));
+        self.metrics.as_ref().inspect(|m| {
+            m.range_finished_block(self.range_id);
+            m.pricing_lag(self.range_id, self.current_block.saturating_sub(self.completed_block));
+        });
 
         self.should_return().then_some((block, res))
     }
@@ -1232,15 +1276,13 @@ impl<T: TracingProvider> Stream for BrontesBatchPricer<T> {
                             Some(PollResult::Skip)
                         }
                         DexPriceMsg::Update(update) => Some(PollResult::State(update)),
-                        DexPriceMsg::DiscoveredPool(NormalizedPoolConfigUpdate {
-                            protocol,
-                            tokens,
-                            pool_address,
-                            ..
-                        }) => {
-                            if protocol.has_state_updater() {
+                        DexPriceMsg::DiscoveredPool(pool) => {
+                            // only pools of two tokens have a state updater
+                            if let Some(pair) =
+                                pool.pair().filter(|_| pool.protocol.has_state_updater())
+                            {
                                 self.new_graph_pairs
-                                    .insert(pool_address, (protocol, Pair(tokens[0], tokens[1])));
+                                    .insert(pool.pool_address, (pool.protocol, pair));
                             };
                             Some(PollResult::DiscoveredPool)
                         }
@@ -1285,6 +1327,15 @@ impl<T: TracingProvider> Stream for BrontesBatchPricer<T> {
                 break 'outer
             }
 
+            // the classifier sends the updates in order, but to rule out the
+            // channel as a source of nondeterminism we order them explicitly
+            if is_deterministic_mode() {
+                block_updates
+                    .sort_by_key(|update| (update.tx_idx, update.action.get_trace_index()));
+            }
+
+            self.staking_rates.on_updates(&block_updates);
+
             #[allow(clippy::blocks_in_conditions)]
             if block_updates
                 .first()
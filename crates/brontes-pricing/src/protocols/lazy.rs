@@ -12,7 +12,7 @@ use tokio::task::JoinError;
 
 use crate::{
     errors::AmmError,
-    protocols::LoadState,
+    protocols::loader::PoolStateLoader,
     types::{PairWithFirstPoolHop, PoolState},
     Protocol,
 };
@@ -62,7 +62,7 @@ type BlockNumber = u64;
 /// Deals with the lazy loading of new exchange state, and tracks loading of new
 /// state for a given block.
 pub struct LazyExchangeLoader<T: TracingProvider> {
-    provider:          Arc<T>,
+    state_loader:      Arc<PoolStateLoader<T>>,
     pool_load_futures: MultiBlockPoolFutures,
     /// addresses currently being processed. to the blocks of the address we are
     /// fetching state for
@@ -79,7 +79,7 @@ impl<T: TracingProvider> LazyExchangeLoader<T> {
             state_tracking: LoadingStateTracker::default(),
             pool_buf: FastHashMap::default(),
             pool_load_futures: MultiBlockPoolFutures::new(),
-            provider,
+            state_loader: Arc::new(PoolStateLoader::new(provider)),
             req_per_block: FastHashMap::default(),
             ex,
         }
@@ -162,10 +162,12 @@ impl<T: TracingProvider> LazyExchangeLoader<T> {
         ex_type: Protocol,
         metrics: Option<DexPricingMetrics>,
     ) {
-        let provider = self.provider.clone();
         self.add_state_trackers(block_number, id, address, pair);
 
-        let fut = ex_type.try_load_state(address, provider, block_number, pool_pair, pair);
+        let fut = self
+            .state_loader
+            .clone()
+            .load(ex_type, address, block_number, pool_pair, pair);
         self.pool_load_futures.add_future(
             block_number,
             Box::pin(self.ex.handle().spawn(async move {
@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use alloy_primitives::{Address, FixedBytes, U256};
+use brontes_types::{pair::Pair, traits::TracingProvider, FastHashMap};
+use parking_lot::RwLock;
+use tracing::debug;
+
+use crate::{
+    errors::AmmError,
+    lazy::{PoolFetchError, PoolFetchSuccess},
+    protocols::LoadState,
+    types::{PairWithFirstPoolHop, PoolState, PoolVariants},
+    uniswap_v2::UniswapV2Pool,
+    uniswap_v3::{batch_request::get_v3_reserves_batch_request, UniswapV3Pool},
+    LoadResult, Protocol,
+};
+
+/// `reserve0`, `reserve1` and `blockTimestampLast` are packed into slot 8 of
+/// the uniswap v2 pair
+const V2_RESERVES_SLOT: FixedBytes<32> = FixedBytes::with_last_byte(8);
+const V3_SLOT0_SLOT: FixedBytes<32> = FixedBytes::new([0u8; 32]);
+const V3_LIQUIDITY_SLOT: FixedBytes<32> = FixedBytes::with_last_byte(4);
+
+/// How the state of a pool is fetched from the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HydrationStrategy {
+    /// Reads the storage slots holding the pool's state directly. Requires the
+    /// pool's immutable data (tokens, decimals, fee) to already be known.
+    StorageSlots,
+    /// Executes the protocol's batch request, which returns the full state of
+    /// the pool including its immutable data.
+    BatchCall,
+}
+
+/// The parts of a pool's state that never change after it is created
+#[derive(Debug, Clone, Copy)]
+struct PoolImmutables {
+    token_a:          Address,
+    token_a_decimals: u8,
+    token_b:          Address,
+    token_b_decimals: u8,
+    fee:              u32,
+    tick_spacing:     i32,
+}
+
+impl From<&PoolVariants> for PoolImmutables {
+    fn from(variant: &PoolVariants) -> Self {
+        match variant {
+            PoolVariants::UniswapV2(pool) => Self {
+                token_a:          pool.token_a,
+                token_a_decimals: pool.token_a_decimals,
+                token_b:          pool.token_b,
+                token_b_decimals: pool.token_b_decimals,
+                fee:              pool.fee,
+                tick_spacing:     0,
+            },
+            PoolVariants::UniswapV3(pool) => Self {
+                token_a:          pool.token_a,
+                token_a_decimals: pool.token_a_decimals,
+                token_b:          pool.token_b,
+                token_b_decimals: pool.token_b_decimals,
+                fee:              pool.fee,
+                tick_spacing:     pool.tick_spacing,
+            },
+            PoolVariants::MaverickV2(pool) => Self {
+                token_a:          pool.token_a,
+                token_a_decimals: pool.token_a_decimals,
+                token_b:          pool.token_b,
+                token_b_decimals: pool.token_b_decimals,
+                fee:              0,
+                tick_spacing:     pool.tick_spacing as i32,
+            },
+        }
+    }
+}
+
+/// Hydrates pool state for the block being priced using the cheapest strategy
+/// available. The first load of a pool always goes through the batch request,
+/// after which its immutables are remembered so that any later load of the
+/// same pool (e.g. once it was pruned from the graph) only has to read the
+/// storage slots that changed.
+pub struct PoolStateLoader<T: TracingProvider> {
+    provider:   Arc<T>,
+    immutables: RwLock<FastHashMap<Address, PoolImmutables>>,
+}
+
+impl<T: TracingProvider> PoolStateLoader<T> {
+    pub fn new(provider: Arc<T>) -> Self {
+        Self { provider, immutables: RwLock::new(FastHashMap::default()) }
+    }
+
+    pub fn strategy(&self, protocol: Protocol, address: &Address) -> HydrationStrategy {
+        if Self::supports_storage_slots(protocol) && self.immutables.read().contains_key(address) {
+            HydrationStrategy::StorageSlots
+        } else {
+            HydrationStrategy::BatchCall
+        }
+    }
+
+    /// Tick state isn't kept in a fixed set of slots, so v3 pools can only be
+    /// hydrated from storage when ticks aren't tracked.
+    fn supports_storage_slots(protocol: Protocol) -> bool {
+        match protocol {
+            Protocol::UniswapV2 | Protocol::SushiSwapV2 | Protocol::PancakeSwapV2 => true,
+            Protocol::UniswapV3 | Protocol::SushiSwapV3 | Protocol::PancakeSwapV3 => {
+                !cfg!(feature = "uni-v3-ticks")
+            }
+            _ => false,
+        }
+    }
+
+    pub async fn load(
+        self: Arc<Self>,
+        protocol: Protocol,
+        address: Address,
+        block_number: u64,
+        pool_pair: Pair,
+        full_pair: PairWithFirstPoolHop,
+    ) -> Result<PoolFetchSuccess, PoolFetchError> {
+        if self.strategy(protocol, &address) == HydrationStrategy::StorageSlots {
+            let immutables = self.immutables.read().get(&address).copied();
+            if let Some(immutables) = immutables {
+                // the pool was already loaded before, so it exists at the previous block
+                match self
+                    .load_from_storage(protocol, address, immutables, block_number - 1)
+                    .await
+                {
+                    Ok(variant) => {
+                        return Ok((
+                            block_number,
+                            address,
+                            PoolState::new(variant, block_number),
+                            LoadResult::Ok,
+                        ))
+                    }
+                    Err(e) => {
+                        debug!(
+                            ?pool_pair, %protocol, %block_number, pool_address=?address, err=%e,
+                            "storage hydration failed, falling back to batch call"
+                        );
+                    }
+                }
+            }
+        }
+
+        let res = protocol
+            .try_load_state(address, self.provider.clone(), block_number, pool_pair, full_pair)
+            .await?;
+
+        if Self::supports_storage_slots(protocol) {
+            self.immutables
+                .write()
+                .insert(address, PoolImmutables::from(res.2.variant()));
+        }
+
+        Ok(res)
+    }
+
+    async fn load_from_storage(
+        &self,
+        protocol: Protocol,
+        address: Address,
+        immutables: PoolImmutables,
+        block: u64,
+    ) -> Result<PoolVariants, AmmError> {
+        match protocol {
+            Protocol::UniswapV2 | Protocol::SushiSwapV2 | Protocol::PancakeSwapV2 => {
+                let reserves = self.read_slot(address, V2_RESERVES_SLOT, block).await?;
+                let (reserve_0, reserve_1) = decode_v2_reserves(reserves);
+
+                let pool = UniswapV2Pool::new(
+                    address,
+                    immutables.token_a,
+                    immutables.token_a_decimals,
+                    immutables.token_b,
+                    immutables.token_b_decimals,
+                    reserve_0,
+                    reserve_1,
+                    immutables.fee,
+                );
+                if !pool.data_is_populated() {
+                    return Err(AmmError::NoStateError(address))
+                }
+
+                Ok(PoolVariants::UniswapV2(Box::new(pool)))
+            }
+            Protocol::UniswapV3 | Protocol::SushiSwapV3 | Protocol::PancakeSwapV3 => {
+                let slot0 = self.read_slot(address, V3_SLOT0_SLOT, block).await?;
+                let liquidity = self.read_slot(address, V3_LIQUIDITY_SLOT, block).await?;
+                let (sqrt_price, tick) = decode_v3_slot0(slot0);
+
+                let mut pool = UniswapV3Pool {
+                    address,
+                    token_a: immutables.token_a,
+                    token_a_decimals: immutables.token_a_decimals,
+                    token_b: immutables.token_b,
+                    token_b_decimals: immutables.token_b_decimals,
+                    liquidity: low_bits(liquidity, 128).to::<u128>(),
+                    sqrt_price,
+                    fee: immutables.fee,
+                    tick,
+                    tick_spacing: immutables.tick_spacing,
+                    ..Default::default()
+                };
+
+                // balances aren't part of the pool's storage
+                get_v3_reserves_batch_request(&mut pool, block, self.provider.clone()).await?;
+
+                Ok(PoolVariants::UniswapV3(Box::new(pool)))
+            }
+            rest => {
+                Err(AmmError::CallError(eyre::eyre!("{rest} can't be hydrated from storage slots")))
+            }
+        }
+    }
+
+    async fn read_slot(
+        &self,
+        address: Address,
+        slot: FixedBytes<32>,
+        block: u64,
+    ) -> Result<U256, AmmError> {
+        self.provider
+            .get_storage(Some(block), address, slot)
+            .await?
+            .ok_or(AmmError::NoStateError(address))
+    }
+}
+
+fn low_bits(value: U256, bits: usize) -> U256 {
+    value & ((U256::from(1) << bits) - U256::from(1))
+}
+
+/// The two uint112 reserves are packed into the lower 224 bits of the slot
+fn decode_v2_reserves(slot: U256) -> (u128, u128) {
+    (low_bits(slot, 112).to::<u128>(), low_bits(slot >> 112, 112).to::<u128>())
+}
+
+/// sqrtPriceX96 is the lower 160 bits of slot0, followed by the 24 bit tick
+fn decode_v3_slot0(slot0: U256) -> (U256, i32) {
+    let raw_tick = low_bits(slot0 >> 160, 24).to::<u32>();
+    // sign extend the int24
+    (low_bits(slot0, 160), ((raw_tick << 8) as i32) >> 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[test]
+    fn decodes_packed_slots() {
+        let timestamp = U256::from(1_710_000_000u64) << 224;
+        let reserves = timestamp | (U256::from(7u128) << 112) | U256::from(5u128);
+        assert_eq!(decode_v2_reserves(reserves), (5, 7));
+
+        let sqrt_price = U256::from(79228162514264337593543950336u128);
+        let observations = U256::from(1u64) << 184;
+        for tick in [0i32, 259510, -201_000, -887272] {
+            let raw_tick = U256::from((tick as u32) & 0xffffff) << 160;
+            assert_eq!(decode_v3_slot0(observations | raw_tick | sqrt_price), (sqrt_price, tick));
+        }
+    }
+
+    #[brontes_macros::test]
+    #[cfg(feature = "local-reth")]
+    async fn storage_hydration_matches_batch_call() {
+        let loader = brontes_core::test_utils::TraceLoader::new().await;
+        let provider = loader.get_provider();
+        let state_loader = PoolStateLoader::new(provider.clone());
+        let block = 19450752;
+
+        // weth / usdc
+        let v2_address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+        let v2 = UniswapV2Pool::new_load_on_block(v2_address, provider.clone(), block)
+            .await
+            .unwrap();
+        let immutables = PoolImmutables::from(&PoolVariants::UniswapV2(Box::new(v2.clone())));
+        let PoolVariants::UniswapV2(from_storage) = state_loader
+            .load_from_storage(Protocol::UniswapV2, v2_address, immutables, block)
+            .await
+            .unwrap()
+        else {
+            panic!("expected a v2 pool")
+        };
+        assert_eq!((from_storage.reserve_0, from_storage.reserve_1), (v2.reserve_0, v2.reserve_1));
+
+        let v3_address = Address::new(hex!("cbcdf9626bc03e24f779434178a73a0b4bad62ed"));
+        let v3 = UniswapV3Pool::new_from_address(v3_address, block, provider.clone())
+            .await
+            .unwrap();
+        let immutables = PoolImmutables::from(&PoolVariants::UniswapV3(Box::new(v3.clone())));
+        let PoolVariants::UniswapV3(from_storage) = state_loader
+            .load_from_storage(Protocol::UniswapV3, v3_address, immutables, block)
+            .await
+            .unwrap()
+        else {
+            panic!("expected a v3 pool")
+        };
+        assert_eq!(
+            (from_storage.sqrt_price, from_storage.tick, from_storage.liquidity),
+            (v3.sqrt_price, v3.tick, v3.liquidity)
+        );
+        assert_eq!((from_storage.reserve_0, from_storage.reserve_1), (v3.reserve_0, v3.reserve_1));
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use alloy_primitives::{Address, Log};
+use alloy_sol_macro::sol;
+use async_trait::async_trait;
+use brontes_types::{normalized_actions::Action, traits::TracingProvider, ToScaledRational};
+use malachite::{
+    num::{arithmetic::traits::Reciprocal, basic::traits::Zero, conversion::traits::RoundingFrom},
+    rounding_modes::RoundingMode,
+    Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use super::make_call_request;
+use crate::{
+    errors::{AmmError, ArithmeticError, EventLogError},
+    UpdatableProtocol,
+};
+
+sol!(
+    interface IMaverickV2Pool {
+        struct State {
+            uint128 reserveA;
+            uint128 reserveB;
+            int64 lastTwaD8;
+            int64 lastLogPriceD8;
+            uint40 lastTimestamp;
+            int32 activeTick;
+            bool isLocked;
+            uint32 binCounter;
+            uint8 protocolFeeRatioD3;
+        }
+        struct TickState {
+            uint128 reserveA;
+            uint128 reserveB;
+            uint128 totalSupply;
+            uint32[4] binIdsByTick;
+        }
+
+        function tokenA() external view returns (address);
+        function tokenB() external view returns (address);
+        function tickSpacing() external view returns (uint256);
+        function getState() external view returns (State memory);
+        function getTick(int32 tick) external view returns (TickState memory);
+    }
+);
+
+sol!(
+    interface IMaverickV2Erc20 {
+        function balanceOf(address account) external view returns (uint256);
+        function decimals() external view returns (uint8);
+    }
+);
+
+/// Maverick scales the amounts of both tokens to 18 decimals
+const MAVERICK_DECIMALS: u8 = 18;
+
+/// A Maverick V2 pool, priced by its active tick.
+///
+/// Liquidity is concentrated per tick like Uniswap V3, tick `i` spans the
+/// prices `1.0001^(tick_spacing * i)` to `1.0001^(tick_spacing * (i + 1))` of
+/// token b per token a. The pool's price follows from the reserves of the
+/// active tick within these bounds.
+///
+/// Maverick doesn't log the price after a swap, so swaps are replayed on the
+/// active tick. A swap that moves the price out of the active tick can't be
+/// followed and leaves the price at the start of the swap.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MaverickV2Pool {
+    pub address:          Address,
+    pub token_a:          Address,
+    pub token_a_decimals: u8,
+    pub token_b:          Address,
+    pub token_b_decimals: u8,
+    pub tick_spacing:     u32,
+    pub active_tick:      i32,
+    /// Reserves of the active tick
+    pub tick_reserve_a:   Rational,
+    pub tick_reserve_b:   Rational,
+
+    // balances of the pool
+    pub reserve_a: Rational,
+    pub reserve_b: Rational,
+}
+
+#[async_trait]
+impl UpdatableProtocol for MaverickV2Pool {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sync_from_action(&mut self, action: Action) -> Result<(), AmmError> {
+        match action {
+            Action::Swap(swap) => {
+                self.apply_swap(swap.token_in.address, &swap.amount_in, &swap.amount_out)
+            }
+            Action::SwapWithFee(swap) => {
+                self.apply_swap(swap.token_in.address, &swap.swap.amount_in, &swap.swap.amount_out)
+            }
+            // added and removed liquidity is spread over bins the pool doesn't
+            // expose the ticks of, so only the balances are followed
+            Action::Mint(mint) => {
+                for (token, amount) in mint.token.iter().zip(&mint.amount) {
+                    *self.reserve_mut(token.address) += amount;
+                }
+                Ok(())
+            }
+            Action::Burn(burn) => {
+                for (token, amount) in burn.token.iter().zip(&burn.amount) {
+                    saturating_sub(self.reserve_mut(token.address), amount);
+                }
+                Ok(())
+            }
+            _ => Err(AmmError::SyncError(self.address)),
+        }
+    }
+
+    fn sync_from_log(&mut self, _log: Log) -> Result<(), AmmError> {
+        Err(AmmError::EventLogError(EventLogError::InvalidEventSignature))
+    }
+
+    fn tokens(&self) -> Vec<Address> {
+        vec![self.token_a, self.token_b]
+    }
+
+    fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError> {
+        let price = self.price_b_per_a()?;
+
+        if base_token == self.token_a {
+            Ok(price)
+        } else {
+            Ok(price.reciprocal())
+        }
+    }
+}
+
+impl MaverickV2Pool {
+    pub async fn new_load_on_block<M: TracingProvider>(
+        address: Address,
+        middleware: Arc<M>,
+        block: u64,
+    ) -> Result<Self, AmmError> {
+        let block = Some(block);
+        let token_a =
+            make_call_request(IMaverickV2Pool::tokenACall::new(()), &middleware, address, block)
+                .await?
+                ._0;
+        let token_b =
+            make_call_request(IMaverickV2Pool::tokenBCall::new(()), &middleware, address, block)
+                .await?
+                ._0;
+        let tick_spacing = make_call_request(
+            IMaverickV2Pool::tickSpacingCall::new(()),
+            &middleware,
+            address,
+            block,
+        )
+        .await?
+        ._0;
+        let state =
+            make_call_request(IMaverickV2Pool::getStateCall::new(()), &middleware, address, block)
+                .await?
+                ._0;
+        let tick = make_call_request(
+            IMaverickV2Pool::getTickCall::new((state.activeTick,)),
+            &middleware,
+            address,
+            block,
+        )
+        .await?
+        ._0;
+
+        let (token_a_decimals, reserve_a) =
+            Self::token_balance(token_a, address, &middleware, block).await?;
+        let (token_b_decimals, reserve_b) =
+            Self::token_balance(token_b, address, &middleware, block).await?;
+
+        let pool = Self {
+            address,
+            token_a,
+            token_a_decimals,
+            token_b,
+            token_b_decimals,
+            tick_spacing: tick_spacing.to::<u32>(),
+            active_tick: state.activeTick,
+            tick_reserve_a: tick.reserveA.to_scaled_rational(MAVERICK_DECIMALS),
+            tick_reserve_b: tick.reserveB.to_scaled_rational(MAVERICK_DECIMALS),
+            reserve_a,
+            reserve_b,
+        };
+
+        if !pool.data_is_populated() {
+            return Err(AmmError::NoStateError(address))
+        }
+
+        Ok(pool)
+    }
+
+    async fn token_balance<M: TracingProvider>(
+        token: Address,
+        pool: Address,
+        middleware: &Arc<M>,
+        block: Option<u64>,
+    ) -> Result<(u8, Rational), AmmError> {
+        let decimals =
+            make_call_request(IMaverickV2Erc20::decimalsCall::new(()), middleware, token, block)
+                .await?
+                ._0;
+        let balance = make_call_request(
+            IMaverickV2Erc20::balanceOfCall::new((pool,)),
+            middleware,
+            token,
+            block,
+        )
+        .await?
+        ._0;
+
+        Ok((decimals, balance.to_scaled_rational(decimals)))
+    }
+
+    pub fn data_is_populated(&self) -> bool {
+        !(self.token_a.is_zero()
+            || self.token_b.is_zero()
+            || self.tick_spacing == 0
+            || (self.tick_reserve_a == Rational::ZERO && self.tick_reserve_b == Rational::ZERO))
+    }
+
+    /// The price of the active tick, from its reserves and the bounds of the
+    /// tick. With `L` the liquidity of the tick and `a`, `b` its reserves,
+    /// `a = L * (1 / sqrt(p) - 1 / sqrt(upper))` and
+    /// `b = L * (sqrt(p) - sqrt(lower))`, which gives `L` as the positive root
+    /// of `L^2 * (1 - sqrt(lower / upper)) - L * (a * sqrt(lower) + b /
+    /// sqrt(upper)) - a * b` and `p = (b + L * sqrt(lower)) / (a + L /
+    /// sqrt(upper))`.
+    fn price_b_per_a(&self) -> Result<Rational, ArithmeticError> {
+        let a = f64::rounding_from(&self.tick_reserve_a, RoundingMode::Nearest).0;
+        let b = f64::rounding_from(&self.tick_reserve_b, RoundingMode::Nearest).0;
+        if a == 0.0 && b == 0.0 {
+            return Err(ArithmeticError::YIsZero)
+        }
+
+        let sqrt_price_at =
+            |tick: i32| 1.0001f64.powf(self.tick_spacing as f64 * tick as f64 / 2.0);
+        let (sqrt_lower, sqrt_upper) =
+            (sqrt_price_at(self.active_tick), sqrt_price_at(self.active_tick + 1));
+
+        let quadratic = 1.0 - sqrt_lower / sqrt_upper;
+        let linear = a * sqrt_lower + b / sqrt_upper;
+        let liquidity =
+            (linear + (linear * linear + 4.0 * quadratic * a * b).sqrt()) / (2.0 * quadratic);
+
+        let price = (b + liquidity * sqrt_lower) / (a + liquidity / sqrt_upper);
+
+        Rational::try_from(price).map_err(|_| ArithmeticError::RoundingError)
+    }
+
+    /// Replays a swap on the reserves of the active tick. Fails if the swap
+    /// takes more out of the tick than it holds, as the swap then moved the
+    /// price into ticks that aren't loaded.
+    fn apply_swap(
+        &mut self,
+        token_in: Address,
+        amount_in: &Rational,
+        amount_out: &Rational,
+    ) -> Result<(), AmmError> {
+        let a_in = token_in == self.token_a;
+        let tick_out = if a_in { &self.tick_reserve_b } else { &self.tick_reserve_a };
+        if amount_out > tick_out {
+            return Err(AmmError::SyncError(self.address))
+        }
+
+        if a_in {
+            self.tick_reserve_a += amount_in;
+            self.tick_reserve_b -= amount_out;
+            self.reserve_a += amount_in;
+            saturating_sub(&mut self.reserve_b, amount_out);
+        } else {
+            self.tick_reserve_b += amount_in;
+            self.tick_reserve_a -= amount_out;
+            self.reserve_b += amount_in;
+            saturating_sub(&mut self.reserve_a, amount_out);
+        }
+
+        Ok(())
+    }
+
+    fn reserve_mut(&mut self, token: Address) -> &mut Rational {
+        if token == self.token_a {
+            &mut self.reserve_a
+        } else {
+            &mut self.reserve_b
+        }
+    }
+
+    pub fn get_tvl(&self, base: Address) -> (Rational, Rational) {
+        if self.token_a == base {
+            (self.reserve_a.clone(), self.reserve_b.clone())
+        } else {
+            (self.reserve_b.clone(), self.reserve_a.clone())
+        }
+    }
+}
+
+fn saturating_sub(reserve: &mut Rational, amount: &Rational) {
+    if &*reserve > amount {
+        *reserve -= amount;
+    } else {
+        *reserve = Rational::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::NormalizedSwap,
+    };
+    use malachite::num::arithmetic::traits::Abs;
+
+    use super::*;
+
+    fn pool(tick_reserve_a: u64, tick_reserve_b: u64) -> MaverickV2Pool {
+        MaverickV2Pool {
+            address:          Address::with_last_byte(1),
+            token_a:          Address::with_last_byte(2),
+            token_a_decimals: 18,
+            token_b:          Address::with_last_byte(3),
+            token_b_decimals: 6,
+            tick_spacing:     10,
+            active_tick:      0,
+            tick_reserve_a:   Rational::from(tick_reserve_a),
+            tick_reserve_b:   Rational::from(tick_reserve_b),
+            reserve_a:        Rational::from(1_000),
+            reserve_b:        Rational::from(1_000),
+        }
+    }
+
+    #[test]
+    fn test_price_is_within_the_active_tick() {
+        let upper = Rational::try_from(1.0001f64.powf(10.0)).unwrap();
+
+        // only token b left, the price is at the top of the tick
+        let all_b = pool(0, 100);
+        let price = all_b.calculate_price(all_b.token_a).unwrap();
+        assert!((&price - &upper).abs() * Rational::from(10u64.pow(9)) < Rational::from(1));
+
+        // only token a left, the price is at the bottom of the tick
+        let all_a = pool(100, 0);
+        let price = all_a.calculate_price(all_a.token_a).unwrap();
+        assert!(
+            (&price - Rational::from(1)).abs() * Rational::from(10u64.pow(9)) < Rational::from(1)
+        );
+
+        let mixed = pool(100, 100);
+        let price = mixed.calculate_price(mixed.token_a).unwrap();
+        assert!(price > Rational::from(1) && price < upper);
+        assert_eq!(mixed.calculate_price(mixed.token_b).unwrap(), price.reciprocal());
+    }
+
+    #[test]
+    fn test_swap_is_replayed_on_the_active_tick() {
+        let token = |address, decimals| TokenInfoWithAddress {
+            address,
+            inner: TokenInfo::new(decimals, String::new()),
+        };
+        let mut pool = pool(100, 100);
+        let before = pool.calculate_price(pool.token_a).unwrap();
+
+        let swap = |amount_out: u64| NormalizedSwap {
+            token_in: token(pool.token_a, 18),
+            token_out: token(pool.token_b, 6),
+            amount_in: Rational::from(10),
+            amount_out: Rational::from(amount_out),
+            ..Default::default()
+        };
+        let (within, crossing) = (swap(10), swap(200));
+
+        pool.sync_from_action(Action::Swap(within)).unwrap();
+        assert_eq!(pool.tick_reserve_a, Rational::from(110));
+        assert_eq!(pool.tick_reserve_b, Rational::from(90));
+        assert_eq!(pool.get_tvl(pool.token_a), (Rational::from(1_010), Rational::from(990)));
+        // selling token a lowers its price
+        assert!(pool.calculate_price(pool.token_a).unwrap() < before);
+
+        assert!(pool.sync_from_action(Action::Swap(crossing)).is_err());
+        assert_eq!(pool.tick_reserve_b, Rational::from(90));
+    }
+}
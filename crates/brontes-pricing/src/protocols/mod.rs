@@ -1,5 +1,7 @@
 pub mod errors;
 pub mod lazy;
+pub mod loader;
+pub mod maverick_v2;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
 
@@ -14,6 +16,7 @@ use tracing::{debug, warn};
 
 use crate::{
     lazy::{PoolFetchError, PoolFetchSuccess},
+    maverick_v2::MaverickV2Pool,
     protocols::errors::{AmmError, ArithmeticError},
     types::PairWithFirstPoolHop,
     uniswap_v2::UniswapV2Pool,
@@ -43,6 +46,9 @@ pub trait LoadState {
 }
 
 impl LoadState for Protocol {
+    /// Only these pools are part of the pricing graph. Swaps on any other
+    /// protocol, e.g. Dodo, are still priced, through the subgraphs of their
+    /// pair built from these pools.
     fn has_state_updater(&self) -> bool {
         matches!(
             self,
@@ -52,6 +58,7 @@ impl LoadState for Protocol {
                 | Self::SushiSwapV3
                 | Self::PancakeSwapV2
                 | Self::PancakeSwapV3
+                | Self::MaverickV2
         )
     }
 
@@ -120,6 +127,34 @@ impl LoadState for Protocol {
                     res,
                 ))
             }
+            Self::MaverickV2 => {
+                let (pool, res) = if let Ok(pool) =
+                    MaverickV2Pool::new_load_on_block(address, provider.clone(), block_number - 1)
+                        .await
+                {
+                    (pool, LoadResult::Ok)
+                } else {
+                    (
+                        MaverickV2Pool::new_load_on_block(address, provider, block_number)
+                            .await
+                            .map_err(|e| {
+                                debug!(?pool_pair, protocol=%self, %block_number, pool_address=?address, err=%e, "lazy load failed");
+                                (address, Protocol::MaverickV2, block_number, pool_pair, fp, e)
+                            })?,
+                        LoadResult::PoolInitOnBlock,
+                    )
+                };
+
+                Ok((
+                    block_number,
+                    address,
+                    PoolState::new(
+                        crate::types::PoolVariants::MaverickV2(Box::new(pool)),
+                        block_number,
+                    ),
+                    res,
+                ))
+            }
             rest => {
                 warn!(protocol=?rest, "no state updater is build for");
                 Err((address, self, block_number, pool_pair, fp, AmmError::UnsupportedProtocol))
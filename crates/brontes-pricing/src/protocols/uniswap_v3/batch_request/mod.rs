@@ -10,7 +10,10 @@ use brontes_types::traits::TracingProvider;
 use reth_primitives::{Address, Bytecode, StorageValue};
 use reth_rpc_types::{request::TransactionInput, TransactionRequest};
 
+mod multicall;
 mod test_bytecodes;
+pub use multicall::*;
+
 use super::UniswapV3Pool;
 use crate::errors::AmmError;
 sol!(
@@ -144,6 +147,15 @@ pub async fn get_v3_pool_data_batch_request<M: TracingProvider>(
         pool.token_b = token1;
     }
 
+    get_v3_reserves_batch_request(pool, block_number.unwrap(), middleware).await
+}
+
+/// Fetches the pool's token balances and the token decimals
+pub async fn get_v3_reserves_batch_request<M: TracingProvider>(
+    pool: &mut UniswapV3Pool,
+    block_number: u64,
+    middleware: Arc<M>,
+) -> Result<(), AmmError> {
     let mut bytecode = IGetERC20DataRequest::BYTECODE.to_vec();
     getERC20DataCall::new((pool.token_a, pool.token_b, pool.address)).abi_encode_raw(&mut bytecode);
     let req = TransactionRequest {
@@ -152,7 +164,7 @@ pub async fn get_v3_pool_data_batch_request<M: TracingProvider>(
         ..Default::default()
     };
     let res = middleware
-        .eth_call_light(req, block_number.unwrap().into())
+        .eth_call_light(req, block_number.into())
         .await
         .map_err(|e| eyre::eyre!("v3 data fetch call failed, err={}", e))?;
 
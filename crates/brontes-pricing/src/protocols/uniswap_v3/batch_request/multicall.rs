@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use alloy_primitives::{address, Address, Bytes, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolCall;
+use brontes_types::traits::TracingProvider;
+use futures::future::try_join_all;
+
+use crate::{
+    errors::AmmError,
+    make_call_request,
+    uniswap_v3::{IUniswapV3Pool, Info, UniswapV3Pool},
+};
+
+sol!(
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+        struct CallResult {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls)
+            external
+            payable
+            returns (CallResult[] memory returnData);
+    }
+);
+
+/// Multicall3 is deployed to the same address on all chains
+pub const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+/// Block Multicall3 was deployed at on mainnet
+pub const MULTICALL3_DEPLOY_BLOCK: u64 = 14_353_601;
+
+/// Tick bitmap words fetched on each side of the word holding the current tick
+pub const TICK_BITMAP_WORD_RANGE: i16 = 2;
+/// Keeps a single `eth_call` well under the node's gas cap
+const MAX_CALLS_PER_MULTICALL: usize = 500;
+
+/// Hydrates slot0, liquidity and the ticks around the current tick of the pool
+/// at the given block in three round trips, regardless of how many ticks are
+/// initialized.
+pub async fn get_v3_pool_state_batch_request<M: TracingProvider>(
+    pool: &mut UniswapV3Pool,
+    block_number: u64,
+    word_range: i16,
+    middleware: Arc<M>,
+) -> Result<(), AmmError> {
+    // slot0, liquidity and tick spacing
+    let res = aggregate(
+        vec![
+            call(pool.address, IUniswapV3Pool::slot0Call::new(())),
+            call(pool.address, IUniswapV3Pool::liquidityCall::new(())),
+            call(pool.address, IUniswapV3Pool::tickSpacingCall::new(())),
+        ],
+        block_number,
+        &middleware,
+    )
+    .await?;
+
+    let slot0 = IUniswapV3Pool::slot0Call::abi_decode_returns(&res[0], false)?;
+    pool.sqrt_price = U256::from(slot0._0);
+    pool.tick = slot0._1;
+    pool.liquidity = IUniswapV3Pool::liquidityCall::abi_decode_returns(&res[1], false)?._0;
+    pool.tick_spacing = IUniswapV3Pool::tickSpacingCall::abi_decode_returns(&res[2], false)?._0;
+
+    if pool.tick_spacing <= 0 {
+        return Err(AmmError::NoStateError(pool.address))
+    }
+
+    // tick bitmap words around the current tick
+    let current_word = (pool.tick.div_euclid(pool.tick_spacing) >> 8) as i16;
+    let words = (current_word.saturating_sub(word_range)..=current_word.saturating_add(word_range))
+        .collect::<Vec<_>>();
+
+    let res = aggregate(
+        words
+            .iter()
+            .map(|word| call(pool.address, IUniswapV3Pool::tickBitmapCall::new((*word,))))
+            .collect(),
+        block_number,
+        &middleware,
+    )
+    .await?;
+
+    let mut initialized_ticks = Vec::new();
+    for (word, data) in words.into_iter().zip(res) {
+        let bitmap = IUniswapV3Pool::tickBitmapCall::abi_decode_returns(&data, false)?._0;
+        pool.tick_bitmap.insert(word, bitmap);
+
+        initialized_ticks.extend(
+            (0..256)
+                .filter(|bit| bitmap.bit(*bit))
+                .map(|bit| ((word as i32) * 256 + bit as i32) * pool.tick_spacing),
+        );
+    }
+
+    // liquidity of every initialized tick in the fetched words
+    let res = aggregate(
+        initialized_ticks
+            .iter()
+            .map(|tick| call(pool.address, IUniswapV3Pool::ticksCall::new((*tick,))))
+            .collect(),
+        block_number,
+        &middleware,
+    )
+    .await?;
+
+    for (tick, data) in initialized_ticks.into_iter().zip(res) {
+        let info = IUniswapV3Pool::ticksCall::abi_decode_returns(&data, false)?;
+        pool.ticks
+            .insert(tick, Info::new(info._0, info._1, info._7));
+    }
+
+    Ok(())
+}
+
+fn call<C: SolCall>(target: Address, call: C) -> IMulticall3::Call3 {
+    IMulticall3::Call3 { target, allowFailure: false, callData: call.abi_encode().into() }
+}
+
+/// Executes the calls through Multicall3, splitting them over multiple
+/// `eth_call`s if needed. Returns the raw return data of each call in order.
+async fn aggregate<M: TracingProvider>(
+    calls: Vec<IMulticall3::Call3>,
+    block_number: u64,
+    middleware: &Arc<M>,
+) -> Result<Vec<Bytes>, AmmError> {
+    if block_number < MULTICALL3_DEPLOY_BLOCK {
+        return Err(AmmError::CallError(eyre::eyre!(
+            "multicall3 isn't deployed at block {block_number}"
+        )))
+    }
+
+    let results = try_join_all(calls.chunks(MAX_CALLS_PER_MULTICALL).map(|chunk| {
+        make_call_request(
+            IMulticall3::aggregate3Call::new((chunk.to_vec(),)),
+            middleware,
+            MULTICALL3,
+            Some(block_number),
+        )
+    }))
+    .await?;
+
+    Ok(results
+        .into_iter()
+        .flat_map(|res| res.returnData)
+        .map(|res| res.returnData)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[brontes_macros::test]
+    #[cfg(feature = "local-reth")]
+    async fn test_v3_pool_state_multicall() {
+        let loader = brontes_core::test_utils::TraceLoader::new().await;
+        let provider = loader.get_provider();
+
+        let mut pool = UniswapV3Pool {
+            address: Address::new(hex!("cbcdf9626bc03e24f779434178a73a0b4bad62ed")),
+            ..Default::default()
+        };
+        get_v3_pool_state_batch_request(&mut pool, 19450752, TICK_BITMAP_WORD_RANGE, provider)
+            .await
+            .unwrap();
+
+        // Ref: https://evm.storage/eth/19450752/0xcbcdf9626bc03e24f779434178a73a0b4bad62ed/slot0#map
+        assert_eq!(pool.sqrt_price, U256::from_str("34181474658983484482097063224900296").unwrap());
+        assert_eq!(pool.tick, 259510);
+        // Ref: https://evm.storage/eth/19450752/0xcbcdf9626bc03e24f779434178a73a0b4bad62ed/liquidity#map
+        assert_eq!(pool.liquidity, 1266853986742771321);
+
+        // every word around the current tick is fetched, and each of their set
+        // bits is an initialized tick
+        assert_eq!(pool.tick_bitmap.len(), 2 * TICK_BITMAP_WORD_RANGE as usize + 1);
+        let initialized = pool
+            .tick_bitmap
+            .values()
+            .map(|word| word.count_ones())
+            .sum::<usize>();
+        assert_eq!(pool.ticks.len(), initialized);
+        assert!(pool
+            .ticks
+            .iter()
+            .all(|(tick, info)| tick % pool.tick_spacing == 0 && info.liquidity_gross > 0));
+    }
+}
@@ -1,23 +1,42 @@
 pub mod batch_request;
 pub mod uniswap_v3_math;
-use std::{cmp::Ordering, sync::Arc};
+use std::sync::Arc;
 
-use alloy_primitives::{Address, FixedBytes, Log, B256, U256};
+use alloy_primitives::{Address, FixedBytes, Log, B256, I256, U256};
 use alloy_sol_macro::sol;
 use alloy_sol_types::{SolCall, SolEvent};
 use async_trait::async_trait;
 use brontes_types::{
     normalized_actions::Action, traits::TracingProvider, FastHashMap, ToScaledRational,
 };
-use malachite::Rational;
+#[cfg(feature = "uni-v3-ticks")]
+use futures::future::join_all;
+use malachite::{
+    num::{
+        arithmetic::traits::{Pow, Reciprocal},
+        conversion::traits::RoundingFrom,
+    },
+    rounding_modes::RoundingMode,
+    Natural, Rational,
+};
 use serde::{Deserialize, Serialize};
 
-use self::batch_request::get_v3_pool_data_batch_request;
+use self::{
+    batch_request::get_v3_pool_data_batch_request,
+    uniswap_v3_math::{
+        liquidity_math::add_delta,
+        sqrt_price_math::Q96,
+        swap_math::compute_swap_step,
+        tick_bitmap::{next_initialized_tick_within_one_word, position},
+        tick_math::{get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio, MAX_TICK, MIN_TICK},
+    },
+};
 use super::make_call_request;
 #[cfg(feature = "uni-v3-ticks")]
-use crate::uniswap_v3::batch_request::get_uniswap_v3_tick_data_batch_request;
-#[cfg(feature = "uni-v3-ticks")]
-use crate::uniswap_v3::uniswap_v3_math::tick_math::{MAX_TICK, MIN_TICK};
+use crate::uniswap_v3::batch_request::{
+    get_uniswap_v3_tick_data_batch_request, get_v3_pool_state_batch_request,
+    MULTICALL3_DEPLOY_BLOCK, TICK_BITMAP_WORD_RANGE,
+};
 use crate::{
     errors::{AmmError, ArithmeticError, EventLogError},
     UpdatableProtocol,
@@ -162,8 +181,21 @@ impl UpdatableProtocol for UniswapV3Pool {
         self.address
     }
 
-    fn sync_from_action(&mut self, _action: Action) -> Result<(), AmmError> {
-        todo!("syncing from actions is currently not supported for v3")
+    /// Applies a swap that came without its pool logs by simulating it
+    /// through the loaded tick state
+    fn sync_from_action(&mut self, action: Action) -> Result<(), AmmError> {
+        let swap = match &action {
+            Action::Swap(swap) => swap,
+            Action::SwapWithFee(swap) => &swap.swap,
+            _ => return Err(AmmError::SyncError(self.address)),
+        };
+        let amount_in = raw_amount(&swap.amount_in, swap.token_in.decimals)
+            .ok_or(AmmError::SyncError(self.address))?;
+
+        let simulation = self.simulate_swap(swap.token_in.address, amount_in)?;
+        self.apply_swap(swap.token_in.address, &simulation);
+
+        Ok(())
     }
 
     fn sync_from_log(&mut self, log: Log) -> Result<(), AmmError> {
@@ -193,19 +225,10 @@ impl UpdatableProtocol for UniswapV3Pool {
             ))
         }
 
-        let tick = uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio(self.sqrt_price)?;
-        let shift = self.token_a_decimals as i8 - self.token_b_decimals as i8;
-        let price = match shift.cmp(&0) {
-            Ordering::Less => 1.0001_f64.powi(tick) / 10_f64.powi(-shift as i32),
-            Ordering::Greater => 1.0001_f64.powi(tick) * 10_f64.powi(shift as i32),
-            Ordering::Equal => 1.0001_f64.powi(tick),
-        };
+        // errors on a sqrt price outside of the valid range
+        get_tick_at_sqrt_ratio(self.sqrt_price)?;
 
-        if base_token == self.token_a {
-            Ok(Rational::try_from(price).unwrap())
-        } else {
-            Ok(Rational::try_from(1.0 / price).unwrap())
-        }
+        Ok(self.price_at_sqrt_ratio(self.sqrt_price, base_token))
     }
 }
 
@@ -243,8 +266,8 @@ impl UniswapV3Pool {
         //We need to get tick spacing before populating tick data because tick spacing
         // can not be uninitialized when syncing burn and mint logs
         #[cfg(feature = "uni-v3-ticks")]
-        pool.sync_ticks_around_current(block_number, 100, middleware.clone())
-            .await;
+        pool.sync_tick_state(block_number, middleware.clone())
+            .await?;
 
         pool.populate_data(Some(block_number), middleware).await?;
 
@@ -255,6 +278,23 @@ impl UniswapV3Pool {
         Ok(pool)
     }
 
+    /// Loads the tick state around the current tick. Uses batched multicalls
+    /// when available, which is far faster for pools with many initialized
+    /// ticks.
+    #[cfg(feature = "uni-v3-ticks")]
+    pub async fn sync_tick_state<M: 'static + TracingProvider>(
+        &mut self,
+        block: u64,
+        provider: Arc<M>,
+    ) -> Result<(), AmmError> {
+        if block < MULTICALL3_DEPLOY_BLOCK {
+            self.sync_ticks_around_current(block, 100, provider).await;
+            return Ok(())
+        }
+
+        get_v3_pool_state_batch_request(self, block, TICK_BITMAP_WORD_RANGE, provider).await
+    }
+
     #[cfg(feature = "uni-v3-ticks")]
     pub async fn sync_ticks_around_current<M: 'static + TracingProvider>(
         &mut self,
@@ -293,8 +333,42 @@ impl UniswapV3Pool {
         .unwrap()
         .0;
 
-        for tick in ticks {
-            self.update_tick(tick.tick, tick.liquidityNet, tick.initialized);
+        // the batch request doesn't return the gross liquidity, which decides when
+        // a tick flips on a burn, so it is read from the pool
+        let address = self.address;
+        let infos = join_all(
+            ticks
+                .into_iter()
+                .filter(|tick| tick.initialized)
+                .map(|tick| {
+                    let provider = provider.clone();
+                    async move {
+                        let info = make_call_request(
+                            IUniswapV3Pool::ticksCall::new((tick.tick,)),
+                            &provider,
+                            address,
+                            Some(block),
+                        )
+                        .await;
+                        (tick.tick, info)
+                    }
+                }),
+        )
+        .await;
+
+        for (tick, info) in infos {
+            let info = match info {
+                Ok(info) => info,
+                Err(e) => {
+                    tracing::debug!(pool = ?self.address, tick, err = %e, "tick info fetch failed");
+                    continue
+                }
+            };
+            self.ticks
+                .insert(tick, Info::new(info._0, info._1, info._7));
+
+            let (word_pos, bit_pos) = position(tick / self.tick_spacing);
+            *self.tick_bitmap.entry(word_pos).or_default() |= U256::from(1) << bit_pos;
         }
     }
 
@@ -348,7 +422,7 @@ impl UniswapV3Pool {
             burn_event.tickLower,
             burn_event.tickUpper,
             -(burn_event.amount as i128),
-        );
+        )?;
 
         Ok(())
     }
@@ -360,37 +434,47 @@ impl UniswapV3Pool {
         self.reserve_1 += mint_event.amount1;
 
         #[cfg(feature = "uni-v3-ticks")]
-        self.modify_position(mint_event.tickLower, mint_event.tickUpper, mint_event.amount as i128);
+        self.modify_position(
+            mint_event.tickLower,
+            mint_event.tickUpper,
+            mint_event.amount as i128,
+        )?;
 
         Ok(())
     }
 
-    pub fn modify_position(&mut self, tick_lower: i32, tick_upper: i32, liquidity_delta: i128) {
+    pub fn modify_position(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: i128,
+    ) -> Result<(), AmmError> {
         //We are only using this function when a mint or burn event is emitted,
         //therefore we do not need to checkTicks as that has happened before the event
         // is emitted
-        self.update_position(tick_lower, tick_upper, liquidity_delta);
+        self.update_position(tick_lower, tick_upper, liquidity_delta)?;
 
-        if liquidity_delta != 0 {
-            //if the tick is between the tick lower and tick upper, update the liquidity
-            // between the ticks
-            if self.tick > tick_lower && self.tick < tick_upper {
-                self.liquidity = if liquidity_delta < 0 {
-                    self.liquidity - ((-liquidity_delta) as u128)
-                } else {
-                    self.liquidity + (liquidity_delta as u128)
-                }
-            }
+        //if the current tick is in the range of the position, the active liquidity
+        // changes. the lower tick is inclusive and the upper exclusive
+        if liquidity_delta != 0 && self.tick >= tick_lower && self.tick < tick_upper {
+            self.liquidity = add_delta(self.liquidity, liquidity_delta)?;
         }
+
+        Ok(())
     }
 
-    pub fn update_position(&mut self, tick_lower: i32, tick_upper: i32, liquidity_delta: i128) {
+    pub fn update_position(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: i128,
+    ) -> Result<(), AmmError> {
         let mut flipped_lower = false;
         let mut flipped_upper = false;
 
         if liquidity_delta != 0 {
-            flipped_lower = self.update_tick(tick_lower, liquidity_delta, false);
-            flipped_upper = self.update_tick(tick_upper, liquidity_delta, true);
+            flipped_lower = self.update_tick(tick_lower, liquidity_delta, false)?;
+            flipped_upper = self.update_tick(tick_upper, liquidity_delta, true)?;
             if flipped_lower {
                 self.flip_tick(tick_lower, self.tick_spacing);
             }
@@ -408,9 +492,16 @@ impl UniswapV3Pool {
                 self.ticks.remove(&tick_upper);
             }
         }
+
+        Ok(())
     }
 
-    pub fn update_tick(&mut self, tick: i32, liquidity_delta: i128, upper: bool) -> bool {
+    pub fn update_tick(
+        &mut self,
+        tick: i32,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> Result<bool, AmmError> {
         let info = match self.ticks.get_mut(&tick) {
             Some(info) => info,
             None => {
@@ -423,11 +514,7 @@ impl UniswapV3Pool {
 
         let liquidity_gross_before = info.liquidity_gross;
 
-        let liquidity_gross_after = if liquidity_delta < 0 {
-            liquidity_gross_before - ((-liquidity_delta) as u128)
-        } else {
-            liquidity_gross_before + (liquidity_delta as u128)
-        };
+        let liquidity_gross_after = add_delta(liquidity_gross_before, liquidity_delta)?;
 
         //we do not need to check if liqudity_gross_after > maxLiquidity because we are
         // only calling update tick on a burn or mint log. this should already
@@ -446,7 +533,7 @@ impl UniswapV3Pool {
             info.liquidity_net + liquidity_delta
         };
 
-        flipped
+        Ok(flipped)
     }
 
     pub fn flip_tick(&mut self, tick: i32, tick_spacing: i32) {
@@ -491,6 +578,175 @@ impl UniswapV3Pool {
             )
         }
     }
+
+    /// Price of `base_token` in the other token of the pool at the given sqrt
+    /// price, adjusted for the token decimals
+    pub fn price_at_sqrt_ratio(&self, sqrt_price: U256, base_token: Address) -> Rational {
+        let sqrt_price = sqrt_price.to_scaled_rational(0) / Q96.to_scaled_rational(0);
+        let shift = self.token_a_decimals as i64 - self.token_b_decimals as i64;
+        let price = &sqrt_price * &sqrt_price * Rational::from(10u8).pow(shift);
+
+        if base_token == self.token_a {
+            price
+        } else {
+            price.reciprocal()
+        }
+    }
+
+    /// Replays an exact input swap through the pool the way the pool contract
+    /// does, crossing the initialized ticks on the way. The tick state is only
+    /// loaded with the `uni-v3-ticks` feature, the simulation errors when it
+    /// runs out of loaded tick bitmap words instead of assuming that the
+    /// liquidity stays the same.
+    pub fn simulate_swap(
+        &self,
+        token_in: Address,
+        amount_in: U256,
+    ) -> Result<SwapSimulation, AmmError> {
+        if self.tick_spacing <= 0 {
+            return Err(AmmError::NoStateError(self.address))
+        }
+
+        let zero_for_one = token_in == self.token_a;
+        let sqrt_price_limit = if zero_for_one {
+            MIN_SQRT_RATIO + U256::from(1)
+        } else {
+            MAX_SQRT_RATIO - U256::from(1)
+        };
+
+        let mut state = CurrentState {
+            amount_specified_remaining: I256::try_from(amount_in)
+                .map_err(|_| ArithmeticError::ShadowOverflow(amount_in))?,
+            amount_calculated: U256::ZERO,
+            sqrt_price_x_96: self.sqrt_price,
+            tick: self.tick,
+            liquidity: self.liquidity,
+        };
+
+        while !state.amount_specified_remaining.is_zero()
+            && state.sqrt_price_x_96 != sqrt_price_limit
+        {
+            let compressed = state.tick.div_euclid(self.tick_spacing);
+            let (word_pos, _) = position(if zero_for_one { compressed } else { compressed + 1 });
+            if !self.tick_bitmap.contains_key(&word_pos) {
+                return Err(AmmError::NoInitializedTicks)
+            }
+
+            let mut step = StepComputations {
+                sqrt_price_start_x_96: state.sqrt_price_x_96,
+                ..Default::default()
+            };
+
+            (step.tick_next, step.initialized) = next_initialized_tick_within_one_word(
+                &self.tick_bitmap,
+                state.tick,
+                self.tick_spacing,
+                zero_for_one,
+            )?;
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
+            step.sqrt_price_next_x96 = get_sqrt_ratio_at_tick(step.tick_next)?;
+
+            let sqrt_price_target = if zero_for_one {
+                step.sqrt_price_next_x96.max(sqrt_price_limit)
+            } else {
+                step.sqrt_price_next_x96.min(sqrt_price_limit)
+            };
+
+            (state.sqrt_price_x_96, step.amount_in, step.amount_out, step.fee_amount) =
+                compute_swap_step(
+                    state.sqrt_price_x_96,
+                    sqrt_price_target,
+                    state.liquidity,
+                    state.amount_specified_remaining,
+                    self.fee,
+                )?;
+
+            // the amount in and fee never exceed the remaining amount
+            state.amount_specified_remaining -= I256::from_raw(step.amount_in + step.fee_amount);
+            state.amount_calculated += step.amount_out;
+
+            if state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
+                if step.initialized {
+                    let liquidity_net = self
+                        .ticks
+                        .get(&step.tick_next)
+                        .ok_or(AmmError::NoLiquidityNet)?
+                        .liquidity_net;
+
+                    // crossing from right to left the liquidity net is subtracted
+                    let liquidity_net = if zero_for_one { -liquidity_net } else { liquidity_net };
+                    state.liquidity = add_delta(state.liquidity, liquidity_net)?;
+                }
+
+                state.tick = if zero_for_one { step.tick_next - 1 } else { step.tick_next };
+            } else if state.sqrt_price_x_96 != step.sqrt_price_start_x_96 {
+                state.tick = get_tick_at_sqrt_ratio(state.sqrt_price_x_96)?;
+            }
+        }
+
+        Ok(SwapSimulation {
+            amount_in:  amount_in - state.amount_specified_remaining.into_raw(),
+            amount_out: state.amount_calculated,
+            sqrt_price: state.sqrt_price_x_96,
+            tick:       state.tick,
+            liquidity:  state.liquidity,
+        })
+    }
+
+    /// Moves the pool to the state after the simulated swap
+    fn apply_swap(&mut self, token_in: Address, simulation: &SwapSimulation) {
+        if token_in == self.token_a {
+            self.reserve_0 += simulation.amount_in;
+            self.reserve_1 = self.reserve_1.saturating_sub(simulation.amount_out);
+        } else {
+            self.reserve_1 += simulation.amount_in;
+            self.reserve_0 = self.reserve_0.saturating_sub(simulation.amount_out);
+        }
+
+        self.sqrt_price = simulation.sqrt_price;
+        self.tick = simulation.tick;
+        self.liquidity = simulation.liquidity;
+    }
+
+    /// Price of `base_token` once `amount_in` of `token_in` is swapped through
+    /// the pool
+    pub fn price_after_swap(
+        &self,
+        token_in: Address,
+        amount_in: U256,
+        base_token: Address,
+    ) -> Result<Rational, AmmError> {
+        let simulation = self.simulate_swap(token_in, amount_in)?;
+        Ok(self.price_at_sqrt_ratio(simulation.sqrt_price, base_token))
+    }
+}
+
+/// The raw amount of a scaled token amount, rounded down
+fn raw_amount(amount: &Rational, decimals: u8) -> Option<U256> {
+    let scaled = amount * Rational::from(10u8).pow(decimals as u64);
+    let (raw, _) = Natural::rounding_from(&scaled, RoundingMode::Floor);
+
+    U256::checked_from_limbs_slice(&raw.to_limbs_asc())
+}
+
+/// Outcome of [`UniswapV3Pool::simulate_swap`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapSimulation {
+    /// The amount taken, fees included. Less than the amount given when the
+    /// price limit is hit
+    pub amount_in:  U256,
+    pub amount_out: U256,
+    pub sqrt_price: U256,
+    pub tick:       i32,
+    pub liquidity:  u128,
+}
+
+struct CurrentState {
+    amount_specified_remaining: I256,
+    amount_calculated: U256,
+    sqrt_price_x_96: U256,
+    tick: i32,
+    liquidity: u128,
 }
 
 #[derive(Default)]
@@ -514,3 +770,201 @@ pub struct Tick {
     pub seconds_outside: u32,
     pub initialized: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::NormalizedSwap,
+    };
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const FEE: u32 = 3_000;
+    const LIQUIDITY: u128 = 1_000_000_000_000_000_000_000;
+
+    /// A pool at tick 0 with the tick bitmap words around it loaded
+    fn pool_with_positions(positions: &[(i32, i32, u128)]) -> UniswapV3Pool {
+        let mut pool = UniswapV3Pool {
+            token_a: Address::with_last_byte(1),
+            token_a_decimals: 18,
+            token_b: Address::with_last_byte(2),
+            token_b_decimals: 18,
+            sqrt_price: Q96,
+            fee: FEE,
+            tick_spacing: 60,
+            ..Default::default()
+        };
+        for word in -4..=4 {
+            pool.tick_bitmap.insert(word, U256::ZERO);
+        }
+        for (lower, upper, liquidity) in positions {
+            pool.modify_position(*lower, *upper, *liquidity as i128)
+                .unwrap();
+        }
+
+        pool
+    }
+
+    fn exact_in(amount: U256) -> I256 {
+        I256::try_from(amount).unwrap()
+    }
+
+    #[test]
+    fn test_mint_and_burn_maintain_ticks() {
+        let mut pool = pool_with_positions(&[(-600, 600, LIQUIDITY), (60, 1200, LIQUIDITY)]);
+        // only the first position is in range
+        assert_eq!(pool.liquidity, LIQUIDITY);
+        assert_eq!(pool.ticks[&600].liquidity_net, -(LIQUIDITY as i128));
+        assert_eq!(pool.ticks[&60].liquidity_net, LIQUIDITY as i128);
+
+        pool.modify_position(-600, 600, -(LIQUIDITY as i128))
+            .unwrap();
+        pool.modify_position(60, 1200, -(LIQUIDITY as i128))
+            .unwrap();
+
+        assert_eq!(pool.liquidity, 0);
+        assert!(pool.ticks.is_empty());
+        assert!(pool.tick_bitmap.values().all(|word| word.is_zero()));
+    }
+
+    #[test]
+    fn test_swap_within_range() {
+        let pool = pool_with_positions(&[(-600, 600, LIQUIDITY)]);
+        let amount = U256::from(10u128.pow(18));
+
+        let simulation = pool.simulate_swap(pool.token_a, amount).unwrap();
+        let (sqrt_price, _, amount_out, _) = compute_swap_step(
+            Q96,
+            get_sqrt_ratio_at_tick(-600).unwrap(),
+            LIQUIDITY,
+            exact_in(amount),
+            FEE,
+        )
+        .unwrap();
+
+        assert_eq!(simulation.amount_in, amount);
+        assert_eq!(simulation.amount_out, amount_out);
+        assert_eq!(simulation.sqrt_price, sqrt_price);
+        assert_eq!(simulation.liquidity, LIQUIDITY);
+        assert_eq!(simulation.tick, get_tick_at_sqrt_ratio(sqrt_price).unwrap());
+    }
+
+    #[test]
+    fn test_swap_crosses_initialized_ticks() {
+        let pool = pool_with_positions(&[(-600, 600, LIQUIDITY), (-1200, 1200, LIQUIDITY)]);
+        assert_eq!(pool.liquidity, 2 * LIQUIDITY);
+
+        for (token_in, crossed, next) in [(pool.token_a, -600, -1200), (pool.token_b, 600, 1200)] {
+            let crossed_price = get_sqrt_ratio_at_tick(crossed).unwrap();
+            let next_price = get_sqrt_ratio_at_tick(next).unwrap();
+            let (_, first_in, first_out, first_fee) =
+                compute_swap_step(Q96, crossed_price, 2 * LIQUIDITY, exact_in(U256::MAX >> 2), FEE)
+                    .unwrap();
+
+            // enough to cross into the range of the second position only
+            let amount = first_in + first_fee + U256::from(10u128.pow(18));
+            let (sqrt_price, _, second_out, _) = compute_swap_step(
+                crossed_price,
+                next_price,
+                LIQUIDITY,
+                exact_in(U256::from(10u128.pow(18))),
+                FEE,
+            )
+            .unwrap();
+
+            let simulation = pool.simulate_swap(token_in, amount).unwrap();
+            assert_eq!(simulation.liquidity, LIQUIDITY);
+            assert_eq!(simulation.amount_out, first_out + second_out);
+            assert_eq!(simulation.sqrt_price, sqrt_price);
+            assert!(simulation.tick.abs() > crossed.abs() && simulation.tick.abs() < next.abs());
+        }
+    }
+
+    #[test]
+    fn test_swap_past_loaded_ticks() {
+        let pool = pool_with_positions(&[(-600, 600, LIQUIDITY)]);
+
+        assert!(matches!(
+            pool.simulate_swap(pool.token_a, U256::MAX >> 2),
+            Err(AmmError::NoInitializedTicks)
+        ));
+    }
+
+    #[test]
+    fn test_sync_from_action_applies_the_simulated_swap() {
+        let mut pool = pool_with_positions(&[(-600, 600, LIQUIDITY), (-1200, 1200, LIQUIDITY)]);
+        let amount = U256::from(3 * 10u128.pow(18));
+        let simulation = pool.simulate_swap(pool.token_b, amount).unwrap();
+
+        let swap = NormalizedSwap {
+            token_in: TokenInfoWithAddress {
+                address: pool.token_b,
+                inner:   TokenInfo::new(18, "B".to_string()),
+            },
+            amount_in: amount.to_scaled_rational(18),
+            ..Default::default()
+        };
+        pool.sync_from_action(Action::Swap(swap)).unwrap();
+
+        assert_eq!(pool.sqrt_price, simulation.sqrt_price);
+        assert_eq!(pool.tick, simulation.tick);
+        assert_eq!(pool.liquidity, simulation.liquidity);
+        assert_eq!(pool.reserve_1, amount);
+        assert!(pool.sync_from_action(Action::Revert).is_err());
+    }
+
+    #[test]
+    fn test_raw_amount() {
+        assert_eq!(
+            raw_amount(&Rational::from_unsigneds(3u64, 2u64), 6),
+            Some(U256::from(1_500_000))
+        );
+        // dust below the token's precision is dropped
+        assert_eq!(raw_amount(&Rational::from_unsigneds(1u64, 3u64), 2), Some(U256::from(33)));
+        assert_eq!(raw_amount(&Rational::from(0), 18), Some(U256::ZERO));
+    }
+
+    #[test]
+    fn test_price_at_sqrt_ratio() {
+        let mut pool = pool_with_positions(&[]);
+        pool.sqrt_price = get_sqrt_ratio_at_tick(0).unwrap();
+        assert_eq!(pool.price_at_sqrt_ratio(pool.sqrt_price, pool.token_a), Rational::from(1));
+
+        // 2 token b per token a, with token a having 12 more decimals like weth/usdc
+        pool.token_b_decimals = 6;
+        let sqrt_price = Q96 * U256::from(2);
+        assert_eq!(
+            pool.price_at_sqrt_ratio(sqrt_price, pool.token_a),
+            Rational::from(4 * 10u64.pow(12))
+        );
+        assert_eq!(
+            pool.price_at_sqrt_ratio(sqrt_price, pool.token_b),
+            Rational::from_unsigneds(1u64, 4 * 10u64.pow(12))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_swap_moves_price_against_the_input(
+            amount in 1u128..10u128.pow(19),
+            zero_for_one in any::<bool>(),
+        ) {
+            let pool = pool_with_positions(&[(-600, 600, LIQUIDITY), (-1200, 1200, LIQUIDITY)]);
+            let token_in = if zero_for_one { pool.token_a } else { pool.token_b };
+
+            let simulation = pool.simulate_swap(token_in, U256::from(amount)).unwrap();
+            prop_assert_eq!(simulation.amount_in, U256::from(amount));
+            prop_assert!(simulation.amount_out < U256::from(amount));
+
+            let spot = pool.calculate_price(token_in).unwrap();
+            let after = pool.price_at_sqrt_ratio(simulation.sqrt_price, token_in);
+            prop_assert!(after <= spot);
+
+            // the tick always matches the price
+            let tick = get_tick_at_sqrt_ratio(simulation.sqrt_price).unwrap();
+            prop_assert!(simulation.tick == tick || simulation.tick == tick - 1);
+        }
+    }
+}
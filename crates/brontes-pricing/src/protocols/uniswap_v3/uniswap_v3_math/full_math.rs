@@ -0,0 +1,103 @@
+use alloy_primitives::{U256, U512};
+
+use super::error::UniswapV3MathError;
+
+// Calculates floor(a×b÷denominator) with full precision. Errors if the result
+// overflows a uint256 or denominator == 0
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, UniswapV3MathError> {
+    if denominator.is_zero() {
+        return Err(UniswapV3MathError::DenominatorIsZero);
+    }
+
+    let result = U512::from(a) * U512::from(b) / U512::from(denominator);
+    if result > U512::from(U256::MAX) {
+        return Err(UniswapV3MathError::DenominatorIsLteProdOne);
+    }
+
+    Ok(result.to::<U256>())
+}
+
+// Calculates ceil(a×b÷denominator) with full precision. Errors if the result
+// overflows a uint256 or denominator == 0
+pub fn mul_div_rounding_up(
+    a: U256,
+    b: U256,
+    denominator: U256,
+) -> Result<U256, UniswapV3MathError> {
+    let result = mul_div(a, b, denominator)?;
+
+    if (U512::from(a) * U512::from(b) % U512::from(denominator)).is_zero() {
+        return Ok(result);
+    }
+
+    if result == U256::MAX {
+        return Err(UniswapV3MathError::ResultIsU256MAX);
+    }
+
+    Ok(result + U256::from(1))
+}
+
+// Returns ceil(x / y), the division by zero is checked unlike in UnsafeMath
+pub fn div_rounding_up(x: U256, y: U256) -> Result<U256, UniswapV3MathError> {
+    if y.is_zero() {
+        return Err(UniswapV3MathError::DenominatorIsZero);
+    }
+
+    let quotient = x / y;
+    if (x % y).is_zero() {
+        Ok(quotient)
+    } else {
+        Ok(quotient + U256::from(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const Q128: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+    #[test]
+    fn test_mul_div() {
+        assert!(mul_div(Q128, U256::from(5), U256::ZERO).is_err());
+        // the result overflows
+        assert!(mul_div(Q128, Q128, U256::from(1)).is_err());
+        // the product overflows but the result doesn't
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX).unwrap(), U256::MAX);
+        assert_eq!(
+            mul_div(
+                Q128,
+                U256::from(50) * Q128 / U256::from(100),
+                U256::from(150) * Q128 / U256::from(100)
+            )
+            .unwrap(),
+            Q128 / U256::from(3)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_rounding_up() {
+        assert_eq!(
+            mul_div_rounding_up(
+                Q128,
+                U256::from(50) * Q128 / U256::from(100),
+                U256::from(150) * Q128 / U256::from(100)
+            )
+            .unwrap(),
+            Q128 / U256::from(3) + U256::from(1)
+        );
+        // the rounded up result doesn't fit
+        assert!(mul_div_rounding_up(
+            U256::from_str_radix("535006138814359", 10).unwrap(),
+            U256::from_str_radix(
+                "432862656469423142931042426214547535783388063929571229938474969",
+                10
+            )
+            .unwrap(),
+            U256::from(2)
+        )
+        .is_err());
+        assert_eq!(div_rounding_up(U256::from(7), U256::from(2)).unwrap(), U256::from(4));
+        assert_eq!(div_rounding_up(U256::from(8), U256::from(2)).unwrap(), U256::from(4));
+    }
+}
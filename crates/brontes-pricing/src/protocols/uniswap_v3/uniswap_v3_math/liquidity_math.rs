@@ -0,0 +1,12 @@
+use super::error::UniswapV3MathError;
+
+// Adds a signed liquidity delta to liquidity, erroring on overflow or underflow
+pub fn add_delta(x: u128, y: i128) -> Result<u128, UniswapV3MathError> {
+    if y < 0 {
+        x.checked_sub(y.unsigned_abs())
+            .ok_or(UniswapV3MathError::LiquiditySub)
+    } else {
+        x.checked_add(y as u128)
+            .ok_or(UniswapV3MathError::LiquidityAdd)
+    }
+}
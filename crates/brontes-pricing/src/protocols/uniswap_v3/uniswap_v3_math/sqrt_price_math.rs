@@ -0,0 +1,234 @@
+use alloy_primitives::U256;
+
+use super::{
+    error::UniswapV3MathError,
+    full_math::{div_rounding_up, mul_div, mul_div_rounding_up},
+};
+
+pub const Q96: U256 = U256::from_limbs([0, 4294967296, 0, 0]);
+pub const MAX_U160: U256 = U256::from_limbs([u64::MAX, u64::MAX, u32::MAX as u64, 0]);
+
+// Gets the next sqrt price given an input amount of token0 or token1, rounding
+// so that the price doesn't pass the target price
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_price.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    } else if liquidity == 0 {
+        return Err(UniswapV3MathError::LiquidityIsZero);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+// Gets the next sqrt price given an output amount of token0 or token1
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_price.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    } else if liquidity == 0 {
+        return Err(UniswapV3MathError::LiquidityIsZero);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+// Always rounds up, in the exact output case (increasing price) the price has
+// to move at least far enough to get the desired output amount, in the exact
+// input case (decreasing price) the price can't move too far so that the input
+// amount isn't exceeded
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price_x_96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if amount.is_zero() {
+        return Ok(sqrt_price_x_96);
+    }
+
+    let numerator_1 = U256::from(liquidity) << 96;
+    let product = amount.wrapping_mul(sqrt_price_x_96);
+    let product_fits = product / amount == sqrt_price_x_96;
+
+    if add {
+        if product_fits {
+            let denominator = numerator_1.wrapping_add(product);
+            if denominator >= numerator_1 {
+                return mul_div_rounding_up(numerator_1, sqrt_price_x_96, denominator);
+            }
+        }
+
+        let denominator = (numerator_1 / sqrt_price_x_96)
+            .checked_add(amount)
+            .ok_or(UniswapV3MathError::ProductDivAmount)?;
+
+        div_rounding_up(numerator_1, denominator)
+    } else {
+        if !(product_fits && numerator_1 > product) {
+            return Err(UniswapV3MathError::ProductDivAmount);
+        }
+
+        mul_div_rounding_up(numerator_1, sqrt_price_x_96, numerator_1 - product)
+    }
+}
+
+// Always rounds down, in the exact output case (decreasing price) the price has
+// to move at least far enough to get the desired output amount, in the exact
+// input case (increasing price) the price can't move too far so that the input
+// amount isn't exceeded
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price_x_96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, UniswapV3MathError> {
+    let liquidity = U256::from(liquidity);
+
+    if add {
+        let quotient = if amount <= MAX_U160 {
+            (amount << 96) / liquidity
+        } else {
+            mul_div(amount, Q96, liquidity)?
+        };
+
+        let next_sqrt_price = sqrt_price_x_96
+            .checked_add(quotient)
+            .ok_or(UniswapV3MathError::SafeCastToU160Overflow)?;
+
+        if next_sqrt_price > MAX_U160 {
+            return Err(UniswapV3MathError::SafeCastToU160Overflow);
+        }
+
+        Ok(next_sqrt_price)
+    } else {
+        let quotient = if amount <= MAX_U160 {
+            div_rounding_up(amount << 96, liquidity)?
+        } else {
+            mul_div_rounding_up(amount, Q96, liquidity)?
+        };
+
+        if sqrt_price_x_96 <= quotient {
+            return Err(UniswapV3MathError::SqrtPriceIsLteQuotient);
+        }
+
+        Ok(sqrt_price_x_96 - quotient)
+    }
+}
+
+// Gets the amount0 delta between two prices, i.e.
+// liquidity / sqrt(lower) - liquidity / sqrt(upper)
+pub fn get_amount_0_delta(
+    mut sqrt_ratio_a_x_96: U256,
+    mut sqrt_ratio_b_x_96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_ratio_a_x_96 > sqrt_ratio_b_x_96 {
+        (sqrt_ratio_a_x_96, sqrt_ratio_b_x_96) = (sqrt_ratio_b_x_96, sqrt_ratio_a_x_96)
+    };
+
+    if sqrt_ratio_a_x_96.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    }
+
+    let numerator_1 = U256::from(liquidity) << 96;
+    let numerator_2 = sqrt_ratio_b_x_96 - sqrt_ratio_a_x_96;
+
+    if round_up {
+        div_rounding_up(
+            mul_div_rounding_up(numerator_1, numerator_2, sqrt_ratio_b_x_96)?,
+            sqrt_ratio_a_x_96,
+        )
+    } else {
+        Ok(mul_div(numerator_1, numerator_2, sqrt_ratio_b_x_96)? / sqrt_ratio_a_x_96)
+    }
+}
+
+// Gets the amount1 delta between two prices, i.e.
+// liquidity * (sqrt(upper) - sqrt(lower))
+pub fn get_amount_1_delta(
+    mut sqrt_ratio_a_x_96: U256,
+    mut sqrt_ratio_b_x_96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_ratio_a_x_96 > sqrt_ratio_b_x_96 {
+        (sqrt_ratio_a_x_96, sqrt_ratio_b_x_96) = (sqrt_ratio_b_x_96, sqrt_ratio_a_x_96)
+    };
+
+    let liquidity = U256::from(liquidity);
+    if round_up {
+        mul_div_rounding_up(liquidity, sqrt_ratio_b_x_96 - sqrt_ratio_a_x_96, Q96)
+    } else {
+        mul_div(liquidity, sqrt_ratio_b_x_96 - sqrt_ratio_a_x_96, Q96)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+    // sqrt(1.21) as a Q64.96
+    const SQRT_PRICE_1_21: U256 = U256::from_limbs([11068046444225730969, 4724464025, 0, 0]);
+
+    #[test]
+    fn test_get_amount_deltas() {
+        assert_eq!(get_amount_0_delta(Q96, Q96, 0, true).unwrap(), U256::ZERO);
+        assert_eq!(get_amount_1_delta(Q96, Q96, ONE_ETHER, true).unwrap(), U256::ZERO);
+
+        let amount_0 = get_amount_0_delta(Q96, SQRT_PRICE_1_21, ONE_ETHER, true).unwrap();
+        assert_eq!(amount_0, U256::from(90909090909090910u128));
+        let amount_0_down = get_amount_0_delta(Q96, SQRT_PRICE_1_21, ONE_ETHER, false).unwrap();
+        assert_eq!(amount_0_down, amount_0 - U256::from(1));
+
+        let amount_1 = get_amount_1_delta(Q96, SQRT_PRICE_1_21, ONE_ETHER, true).unwrap();
+        assert_eq!(amount_1, U256::from(100000000000000000u128));
+        let amount_1_down = get_amount_1_delta(Q96, SQRT_PRICE_1_21, ONE_ETHER, false).unwrap();
+        assert_eq!(amount_1_down, amount_1 - U256::from(1));
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price() {
+        assert!(get_next_sqrt_price_from_input(U256::ZERO, 1, U256::from(1), true).is_err());
+        assert!(get_next_sqrt_price_from_input(Q96, 0, U256::from(1), true).is_err());
+        assert_eq!(get_next_sqrt_price_from_input(Q96, 1, U256::ZERO, true).unwrap(), Q96);
+
+        // 0.1 token1 in at a price of 1 with a liquidity of 1
+        let next =
+            get_next_sqrt_price_from_input(Q96, ONE_ETHER, U256::from(ONE_ETHER / 10), false)
+                .unwrap();
+        assert_eq!(next, U256::from_str_radix("87150978765690771352898345369", 10).unwrap());
+
+        // 0.1 token0 in at a price of 1 with a liquidity of 1
+        let next = get_next_sqrt_price_from_input(Q96, ONE_ETHER, U256::from(ONE_ETHER / 10), true)
+            .unwrap();
+        assert_eq!(next, U256::from_str_radix("72025602285694852357767227579", 10).unwrap());
+
+        // can't take out more than the pool has
+        assert!(get_next_sqrt_price_from_output(
+            U256::from_str_radix("20282409603651670423947251286016", 10).unwrap(),
+            1024,
+            U256::from(262145),
+            true
+        )
+        .is_err());
+    }
+}
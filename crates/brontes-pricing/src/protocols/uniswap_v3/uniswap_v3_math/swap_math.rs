@@ -0,0 +1,213 @@
+use alloy_primitives::{I256, U256};
+
+use super::{
+    error::UniswapV3MathError,
+    full_math::{mul_div, mul_div_rounding_up},
+    sqrt_price_math::{
+        get_amount_0_delta, get_amount_1_delta, get_next_sqrt_price_from_input,
+        get_next_sqrt_price_from_output,
+    },
+};
+
+// Fees are in hundredths of a bip
+pub const FEE_DENOMINATOR: u32 = 1_000_000;
+
+// Computes the result of swapping some amount in, or amount out, given the
+// parameters of the swap. A positive amount remaining is an exact input, a
+// negative one an exact output. Returns the price after the swap, the amount
+// in, the amount out and the fee taken from the input
+pub fn compute_swap_step(
+    sqrt_ratio_current_x_96: U256,
+    sqrt_ratio_target_x_96: U256,
+    liquidity: u128,
+    amount_remaining: I256,
+    fee_pips: u32,
+) -> Result<(U256, U256, U256, U256), UniswapV3MathError> {
+    let zero_for_one = sqrt_ratio_current_x_96 >= sqrt_ratio_target_x_96;
+    let exact_in = !amount_remaining.is_negative();
+    let amount_remaining_abs = amount_remaining.unsigned_abs();
+
+    let mut amount_in = U256::ZERO;
+    let mut amount_out = U256::ZERO;
+
+    let sqrt_ratio_next_x_96 = if exact_in {
+        let amount_remaining_less_fee = mul_div(
+            amount_remaining_abs,
+            U256::from(FEE_DENOMINATOR - fee_pips),
+            U256::from(FEE_DENOMINATOR),
+        )?;
+
+        amount_in = if zero_for_one {
+            get_amount_0_delta(sqrt_ratio_target_x_96, sqrt_ratio_current_x_96, liquidity, true)?
+        } else {
+            get_amount_1_delta(sqrt_ratio_current_x_96, sqrt_ratio_target_x_96, liquidity, true)?
+        };
+
+        if amount_remaining_less_fee >= amount_in {
+            sqrt_ratio_target_x_96
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_ratio_current_x_96,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        }
+    } else {
+        amount_out = if zero_for_one {
+            get_amount_1_delta(sqrt_ratio_target_x_96, sqrt_ratio_current_x_96, liquidity, false)?
+        } else {
+            get_amount_0_delta(sqrt_ratio_current_x_96, sqrt_ratio_target_x_96, liquidity, false)?
+        };
+
+        if amount_remaining_abs >= amount_out {
+            sqrt_ratio_target_x_96
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_ratio_current_x_96,
+                liquidity,
+                amount_remaining_abs,
+                zero_for_one,
+            )?
+        }
+    };
+
+    let max = sqrt_ratio_target_x_96 == sqrt_ratio_next_x_96;
+
+    if zero_for_one {
+        if !max || !exact_in {
+            amount_in =
+                get_amount_0_delta(sqrt_ratio_next_x_96, sqrt_ratio_current_x_96, liquidity, true)?;
+        }
+        if !max || exact_in {
+            amount_out = get_amount_1_delta(
+                sqrt_ratio_next_x_96,
+                sqrt_ratio_current_x_96,
+                liquidity,
+                false,
+            )?;
+        }
+    } else {
+        if !max || !exact_in {
+            amount_in =
+                get_amount_1_delta(sqrt_ratio_current_x_96, sqrt_ratio_next_x_96, liquidity, true)?;
+        }
+        if !max || exact_in {
+            amount_out = get_amount_0_delta(
+                sqrt_ratio_current_x_96,
+                sqrt_ratio_next_x_96,
+                liquidity,
+                false,
+            )?;
+        }
+    }
+
+    // cap the output amount to not exceed the remaining output amount
+    if !exact_in && amount_out > amount_remaining_abs {
+        amount_out = amount_remaining_abs;
+    }
+
+    let fee_amount = if exact_in && sqrt_ratio_next_x_96 != sqrt_ratio_target_x_96 {
+        // we didn't reach the target, so take the remainder of the maximum input as
+        // fee
+        amount_remaining_abs - amount_in
+    } else {
+        mul_div_rounding_up(
+            amount_in,
+            U256::from(fee_pips),
+            U256::from(FEE_DENOMINATOR - fee_pips),
+        )?
+    };
+
+    Ok((sqrt_ratio_next_x_96, amount_in, amount_out, fee_amount))
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::uniswap_v3::uniswap_v3_math::{
+        sqrt_price_math::Q96,
+        tick_math::{get_sqrt_ratio_at_tick, MAX_TICK, MIN_TICK},
+    };
+
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_exact_in_capped_at_target() {
+        // sqrt(1.21), far enough that one ether in doesn't reach it
+        let target = U256::from_limbs([11068046444225730969, 4724464025, 0, 0]);
+        let amount = I256::try_from(ONE_ETHER).unwrap();
+
+        let (next, amount_in, amount_out, fee) =
+            compute_swap_step(Q96, target, 20 * ONE_ETHER, amount, 600).unwrap();
+
+        assert!(next > Q96 && next < target);
+        // the whole amount is used, fee included
+        assert_eq!(amount_in + fee, U256::from(ONE_ETHER));
+        assert!(amount_out < amount_in);
+    }
+
+    #[test]
+    fn test_exact_out_capped_at_remaining() {
+        let target = get_sqrt_ratio_at_tick(-1_000).unwrap();
+        let amount = -I256::try_from(ONE_ETHER / 100).unwrap();
+
+        let (next, amount_in, amount_out, fee) =
+            compute_swap_step(Q96, target, 2 * ONE_ETHER, amount, 3_000).unwrap();
+
+        assert!(next < Q96 && next > target);
+        assert_eq!(amount_out, U256::from(ONE_ETHER / 100));
+        assert!(amount_in > amount_out);
+        assert!(!fee.is_zero());
+    }
+
+    proptest! {
+        #[test]
+        fn test_swap_step_invariants(
+            current_tick in MIN_TICK + 1..MAX_TICK,
+            target_tick in MIN_TICK + 1..MAX_TICK,
+            liquidity in 1u128..u128::MAX >> 8,
+            amount in 1u128..u128::MAX,
+            exact_in in any::<bool>(),
+            fee_pips in prop::sample::select(vec![100u32, 500, 3_000, 10_000]),
+        ) {
+            let current = get_sqrt_ratio_at_tick(current_tick).unwrap();
+            let target = get_sqrt_ratio_at_tick(target_tick).unwrap();
+            let amount_remaining = if exact_in {
+                I256::try_from(amount).unwrap()
+            } else {
+                -I256::try_from(amount).unwrap()
+            };
+
+            let Ok((next, amount_in, amount_out, fee)) =
+                compute_swap_step(current, target, liquidity, amount_remaining, fee_pips)
+            else {
+                return Ok(())
+            };
+
+            // the price never goes past the target
+            if current >= target {
+                prop_assert!(next <= current && next >= target);
+            } else {
+                prop_assert!(next >= current && next <= target);
+            }
+
+            if exact_in {
+                prop_assert!(amount_in + fee <= U256::from(amount));
+            } else {
+                prop_assert!(amount_out <= U256::from(amount));
+            }
+
+            // a step that doesn't reach the target uses up the whole amount
+            if next != target {
+                if exact_in {
+                    prop_assert_eq!(amount_in + fee, U256::from(amount));
+                } else {
+                    prop_assert_eq!(amount_out, U256::from(amount));
+                }
+            }
+        }
+    }
+}
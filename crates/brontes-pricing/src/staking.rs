@@ -0,0 +1,164 @@
+//! Prices liquid staking tokens off the token they are redeemed for.
+//!
+//! Staking tokens like wstETH or rETH often have no pool in the price graph
+//! that routes to the quote asset, but their value follows the exchange rate
+//! of their protocol. The rate is taken from the stake, unstake and wrap
+//! actions of the classifier, and a token without a dex price in a
+//! transaction is priced as its underlying at that rate. stETH is pegged 1:1
+//! to eth, wstETH is then priced through stETH.
+use std::collections::BTreeMap;
+
+use alloy_primitives::Address;
+use brontes_types::{
+    constants::{ETH_ADDRESS, STETH_ADDRESS},
+    db::dex::{DexPrices, DexQuotes},
+    normalized_actions::StakingRate,
+    pair::Pair,
+    FastHashMap,
+};
+use itertools::Itertools;
+use malachite::Rational;
+
+use crate::types::PoolUpdate;
+
+#[derive(Debug)]
+pub struct StakingRates {
+    /// staking token -> (underlying, underlying per staking token)
+    rates:   FastHashMap<Address, (Address, Rational)>,
+    /// rates of blocks that haven't been resolved yet, so that a block is
+    /// never priced with the rates of a later one
+    pending: BTreeMap<u64, Vec<StakingRate>>,
+}
+
+impl Default for StakingRates {
+    /// stETH is redeemable 1:1 for eth, so it doesn't wait for an action to
+    /// be seen
+    fn default() -> Self {
+        let mut rates = FastHashMap::default();
+        rates.insert(STETH_ADDRESS, (ETH_ADDRESS, Rational::from(1)));
+
+        Self { rates, pending: BTreeMap::new() }
+    }
+}
+
+impl StakingRates {
+    pub fn on_updates(&mut self, updates: &[PoolUpdate]) {
+        updates.iter().for_each(|update| {
+            let Some(rate) = update.action.staking_rate() else { return };
+            self.pending.entry(update.block).or_default().push(rate);
+        });
+    }
+
+    /// Prices the staking tokens without a dex price in a transaction of the
+    /// block as their underlying, if that has one
+    pub fn derive_prices(&mut self, block: u64, quotes: &mut DexQuotes, quote: Address) {
+        let later = self.pending.split_off(&(block + 1));
+        std::mem::replace(&mut self.pending, later)
+            .into_values()
+            .flatten()
+            .for_each(|StakingRate { token, underlying, rate }| {
+                self.rates.insert(token, (underlying, rate));
+            });
+
+        if self.rates.is_empty() {
+            return
+        }
+
+        for tx in 0..quotes.0.len() {
+            if quotes.0[tx].is_none() {
+                continue
+            }
+
+            // a staking token can be redeemed for another one, so its price
+            // might only be derivable once the other one is priced
+            for _ in 0..self.rates.len() {
+                let derived = self.derive_tx_prices(quotes, tx, quote);
+                if derived.is_empty() {
+                    break
+                }
+                quotes.0[tx].as_mut().unwrap().extend(derived);
+            }
+        }
+    }
+
+    fn derive_tx_prices(
+        &self,
+        quotes: &DexQuotes,
+        tx: usize,
+        quote: Address,
+    ) -> Vec<(Pair, DexPrices)> {
+        self.rates
+            .iter()
+            .filter(|(token, _)| **token != quote && !quotes.has_quote(&Pair(**token, quote), tx))
+            .filter_map(|(token, (underlying, rate))| {
+                let price = quotes.price_at_or_before(Pair(*underlying, quote), tx)?;
+
+                Some((
+                    Pair(*token, quote),
+                    DexPrices {
+                        pre_state:    price.pre_state * rate,
+                        post_state:   price.post_state * rate,
+                        goes_through: Pair(*token, *underlying),
+                        is_transfer:  true,
+                    },
+                ))
+            })
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(price: u32) -> DexPrices {
+        DexPrices {
+            pre_state:    Rational::from(price),
+            post_state:   Rational::from(price),
+            goes_through: Pair::default(),
+            is_transfer:  false,
+        }
+    }
+
+    #[test]
+    fn derives_prices_through_the_underlying() {
+        let [quote, wsteth] = [1, 2].map(Address::with_last_byte);
+        let steth = STETH_ADDRESS;
+        let weth = brontes_types::chain::chain_spec().wrapped_native;
+
+        let mut rates = StakingRates::default();
+        rates.pending.insert(
+            10,
+            vec![StakingRate {
+                token:      wsteth,
+                underlying: steth,
+                rate:       Rational::from(2),
+            }],
+        );
+        // a later block's rate isn't used yet
+        rates.pending.insert(
+            11,
+            vec![StakingRate {
+                token:      wsteth,
+                underlying: steth,
+                rate:       Rational::from(3),
+            }],
+        );
+
+        let mut quotes = DexQuotes(vec![
+            Some(FastHashMap::from_iter([(Pair(weth, quote), prices(1000))])),
+            None,
+            Some(FastHashMap::from_iter([(Pair(wsteth, quote), prices(1900))])),
+        ]);
+        rates.derive_prices(10, &mut quotes, quote);
+
+        let first = quotes.0[0].as_ref().unwrap();
+        assert_eq!(first[&Pair(steth, quote)].post_state, Rational::from(1000));
+        assert_eq!(first[&Pair(wsteth, quote)].post_state, Rational::from(2000));
+        assert!(quotes.0[1].is_none());
+        // the dex price is kept
+        let last = quotes.0[2].as_ref().unwrap();
+        assert_eq!(last[&Pair(wsteth, quote)].post_state, Rational::from(1900));
+        assert_eq!(last[&Pair(steth, quote)].post_state, Rational::from(1000));
+    }
+}
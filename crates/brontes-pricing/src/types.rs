@@ -2,15 +2,16 @@ use std::fmt::{Debug, Display};
 
 use alloy_primitives::{wrap_fixed_bytes, Address, FixedBytes, Log};
 use brontes_types::{
-    constants::WETH_ADDRESS,
+    chain::chain_spec,
     normalized_actions::{pool::NormalizedPoolConfigUpdate, Action},
     pair::Pair,
 };
 use malachite::Rational;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    errors::ArithmeticError, uniswap_v2::UniswapV2Pool, uniswap_v3::UniswapV3Pool, LoadState,
-    Protocol, UpdatableProtocol,
+    errors::ArithmeticError, maverick_v2::MaverickV2Pool, uniswap_v2::UniswapV2Pool,
+    uniswap_v3::UniswapV3Pool, LoadState, Protocol, UpdatableProtocol,
 };
 
 wrap_fixed_bytes!(extra_derives:[],
@@ -88,10 +89,15 @@ impl PoolState {
         Self { variant, last_update }
     }
 
+    pub fn variant(&self) -> &PoolVariants {
+        &self.variant
+    }
+
     pub fn pair(&self) -> Pair {
         match &self.variant {
             PoolVariants::UniswapV2(v) => Pair(v.token_a, v.token_b),
             PoolVariants::UniswapV3(v) => Pair(v.token_a, v.token_b),
+            PoolVariants::MaverickV2(v) => Pair(v.token_a, v.token_b),
         }
     }
 
@@ -99,6 +105,7 @@ impl PoolState {
         match &self.variant {
             PoolVariants::UniswapV2(_) => Protocol::UniswapV2,
             PoolVariants::UniswapV3(_) => Protocol::UniswapV3,
+            PoolVariants::MaverickV2(_) => Protocol::MaverickV2,
         }
     }
 
@@ -108,13 +115,14 @@ impl PoolState {
             return
         }
         self.last_update = state.block;
-        self.variant.increment_state(state.logs);
+        self.variant.increment_state(state);
     }
 
     pub fn address(&self) -> Address {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.address(),
             PoolVariants::UniswapV3(v) => v.address(),
+            PoolVariants::MaverickV2(v) => v.address(),
         }
     }
 
@@ -122,6 +130,7 @@ impl PoolState {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.get_tvl(base),
             PoolVariants::UniswapV3(v) => v.get_tvl(base),
+            PoolVariants::MaverickV2(v) => v.get_tvl(base),
         }
     }
 
@@ -129,23 +138,43 @@ impl PoolState {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.calculate_price(base),
             PoolVariants::UniswapV3(v) => v.calculate_price(base),
+            PoolVariants::MaverickV2(v) => v.calculate_price(base),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PoolVariants {
     UniswapV2(Box<UniswapV2Pool>),
     UniswapV3(Box<UniswapV3Pool>),
+    MaverickV2(Box<MaverickV2Pool>),
 }
 
 impl PoolVariants {
-    fn increment_state(&mut self, logs: Vec<Log>) {
-        for log in logs {
-            let _ = match self {
+    fn increment_state(&mut self, update: PoolUpdate) {
+        let mut synced = false;
+        for log in update.logs {
+            synced |= match self {
                 PoolVariants::UniswapV3(a) => a.sync_from_log(log),
                 PoolVariants::UniswapV2(a) => a.sync_from_log(log),
-            };
+                PoolVariants::MaverickV2(a) => a.sync_from_log(log),
+            }
+            .is_ok();
+        }
+        if synced {
+            return
+        }
+
+        // a v3 swap without its pool logs is replayed through the pool's ticks,
+        // maverick pools don't log their state so their actions are always
+        // replayed
+        let (address, res) = match self {
+            PoolVariants::UniswapV3(a) => (a.address(), a.sync_from_action(update.action)),
+            PoolVariants::MaverickV2(a) => (a.address(), a.sync_from_action(update.action)),
+            PoolVariants::UniswapV2(_) => return,
+        };
+        if let Err(e) = res {
+            tracing::debug!(pool = ?address, err = %e, "swap replay failed");
         }
     }
 }
@@ -240,7 +269,7 @@ impl PoolUpdate {
                 b.token.get(1).map(|t| t.address).unwrap_or(quote),
             )),
             Action::Transfer(t) => Some(Pair(t.token.address, quote)),
-            Action::EthTransfer(_) => Some(Pair(WETH_ADDRESS, quote)),
+            Action::EthTransfer(_) => Some(Pair(chain_spec().wrapped_native, quote)),
             Action::Liquidation(l) => Some(Pair(l.collateral_asset.address, l.debt_asset.address)),
             Action::SwapWithFee(s) => Some(Pair(s.token_in.address, s.token_out.address)),
             rest => {
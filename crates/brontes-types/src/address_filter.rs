@@ -0,0 +1,118 @@
+//! Global allow and deny lists of searcher addresses. The inspectors skip the
+//! candidate transactions of denied EOAs and contracts when building their
+//! possible bundles, so known market makers or internal bots are never
+//! attributed as searchers. If the allowlist is set, only the transactions
+//! sent by or calling an allowed address are inspected. The addresses are
+//! never filtered out as victims.
+//!
+//! ```toml
+//! [address_filter]
+//! deny = ["0x0000000000000000000000000000000000000001"]
+//! allow = []
+//! ```
+use std::sync::OnceLock;
+
+use alloy_primitives::Address;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{content_hash::serialize_sorted, FastHashSet, TxInfo};
+
+static ADDRESS_FILTER: OnceLock<AddressFilter> = OnceLock::new();
+
+/// Sets the address filter of the run. Needs to be called before the
+/// inspectors are started.
+pub fn init_address_filter(filter: AddressFilter) -> eyre::Result<()> {
+    ADDRESS_FILTER
+        .set(filter)
+        .map_err(|_| eyre::eyre!("address filter already initialized"))
+}
+
+/// The address filter of the run, allowing every address if none was set
+pub fn address_filter() -> &'static AddressFilter {
+    ADDRESS_FILTER.get_or_init(AddressFilter::default)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AddressFilter {
+    /// If not empty, only EOAs and contracts in the list are inspected
+    #[serde(serialize_with = "serialize_sorted")]
+    pub allow: FastHashSet<Address>,
+    /// EOAs and contracts that are never inspected
+    #[serde(serialize_with = "serialize_sorted")]
+    pub deny:  FastHashSet<Address>,
+}
+
+impl AddressFilter {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Adds the addresses passed on the command line to the ones of the config
+    pub fn extend(
+        &mut self,
+        allow: impl IntoIterator<Item = Address>,
+        deny: impl IntoIterator<Item = Address>,
+    ) {
+        self.allow.extend(allow);
+        self.deny.extend(deny);
+    }
+
+    /// Whether a transaction sent by `eoa` to `contract` can be a candidate
+    /// searcher transaction. The deny list takes precedence over the allow
+    /// list
+    pub fn allows(&self, eoa: Address, contract: Option<Address>) -> bool {
+        if self.is_empty() {
+            return true
+        }
+
+        let mut addresses = std::iter::once(eoa).chain(contract);
+        if addresses
+            .clone()
+            .any(|address| self.deny.contains(&address))
+        {
+            return false
+        }
+
+        self.allow.is_empty() || addresses.any(|address| self.allow.contains(&address))
+    }
+
+    pub fn allows_tx(&self, info: &TxInfo) -> bool {
+        self.allows(info.eoa, info.mev_contract)
+    }
+
+    /// Identifier of the filter for the inspector result cache
+    pub fn cache_id(&self) -> String {
+        format!(
+            "allow:{}deny:{}",
+            self.allow.iter().sorted().join(","),
+            self.deny.iter().sorted().join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_filter_allows() {
+        let [eoa, contract, other] = [1, 2, 3].map(Address::with_last_byte);
+
+        assert!(AddressFilter::default().allows(eoa, Some(contract)));
+
+        let deny = AddressFilter { deny: FastHashSet::from_iter([contract]), ..Default::default() };
+        assert!(!deny.allows(eoa, Some(contract)));
+        assert!(deny.allows(eoa, Some(other)));
+        assert!(deny.allows(eoa, None));
+
+        let allow = AddressFilter {
+            allow: FastHashSet::from_iter([eoa]),
+            deny:  FastHashSet::from_iter([contract]),
+        };
+        assert!(allow.allows(eoa, None));
+        assert!(!allow.allows(eoa, Some(contract)));
+        assert!(!allow.allows(other, Some(other)));
+    }
+}
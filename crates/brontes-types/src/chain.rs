@@ -0,0 +1,273 @@
+//! The chain brontes runs against. Everything that differs between the
+//! supported chains, the wrapped native token, the default quote asset, the
+//! block explorer, the etherscan api and the known protocol addresses, lives
+//! in a [`ChainSpec`] so the pipeline isn't tied to mainnet.
+use std::{str::FromStr, sync::OnceLock};
+
+use alloy_primitives::{hex, Address, TxHash};
+#[cfg(feature = "native")]
+use reth_primitives::{BaseFeeParams, Header};
+
+use crate::constants::{DAI_ADDRESS, SLOT_TIME, USDC_ADDRESS, USDT_ADDRESS, WETH_ADDRESS};
+
+static CHAIN_SPEC: OnceLock<&'static ChainSpec> = OnceLock::new();
+
+/// Sets the chain of the run. Needs to be called before the database is
+/// initialized and the pipeline is started.
+pub fn init_chain_spec(chain: &'static ChainSpec) -> eyre::Result<()> {
+    CHAIN_SPEC
+        .set(chain)
+        .map_err(|_| eyre::eyre!("chain spec already initialized"))
+}
+
+/// The chain of the run, mainnet if none was set
+pub fn chain_spec() -> &'static ChainSpec {
+    CHAIN_SPEC.get_or_init(|| &MAINNET)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChainSpec {
+    pub chain_id:          u64,
+    pub name:              &'static str,
+    /// Base url of the block explorer of the chain
+    pub explorer_url:      &'static str,
+    /// Etherscan api endpoint of the chain
+    pub etherscan_api:     &'static str,
+    pub wrapped_native:    Address,
+    /// Seconds between blocks
+    pub block_time:        u64,
+    /// Max change denominator and elasticity multiplier of the eip-1559 base
+    /// fee
+    pub base_fee_params:   (u64, u64),
+    /// Quote asset used when none is specified
+    pub quote_asset:       Address,
+    /// Stablecoins that can be selected as the quote asset by symbol, the
+    /// wrapped native token can always be selected as `WETH`
+    pub quote_assets:      &'static [(&'static str, Address)],
+    /// Classifier config with the known protocol addresses of the chain,
+    /// relative to the workspace root
+    pub classifier_config: &'static str,
+    /// Factories deployed on the chain at a different address than on
+    /// mainnet, mapped to their mainnet address. The discovery classifiers are
+    /// keyed by the mainnet factories.
+    pub factories:         &'static [(Address, Address)],
+}
+
+pub const MAINNET: ChainSpec = ChainSpec {
+    chain_id:          1,
+    name:              "mainnet",
+    explorer_url:      "https://etherscan.io",
+    etherscan_api:     "https://api.etherscan.io/api",
+    wrapped_native:    WETH_ADDRESS,
+    block_time:        SLOT_TIME,
+    base_fee_params:   (8, 2),
+    quote_asset:       USDT_ADDRESS,
+    quote_assets:      &[("USDT", USDT_ADDRESS), ("USDC", USDC_ADDRESS), ("DAI", DAI_ADDRESS)],
+    classifier_config: "config/classifier_config.toml",
+    factories:         &[],
+};
+
+pub const ARBITRUM: ChainSpec = ChainSpec {
+    chain_id:          42161,
+    name:              "arbitrum",
+    explorer_url:      "https://arbiscan.io",
+    etherscan_api:     "https://api.arbiscan.io/api",
+    wrapped_native:    Address::new(hex!("82aF49447D8a07e3bd95BD0d56f35241523fBab1")),
+    // blocks are produced every ~250ms while timestamps are in seconds, and
+    // the base fee is set by the sequencer instead of eip-1559, so both are
+    // only an approximation
+    block_time:        1,
+    base_fee_params:   (8, 2),
+    quote_asset:       Address::new(hex!("Fd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9")),
+    quote_assets:      &[
+        ("USDT", Address::new(hex!("Fd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9"))),
+        ("USDC", Address::new(hex!("af88d065e77c8cC2239327C5EDb3A432268e5831"))),
+        ("DAI", Address::new(hex!("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1"))),
+    ],
+    classifier_config: "config/arbitrum/classifier_config.toml",
+    factories:         &[
+        // uniswap v2
+        (
+            Address::new(hex!("f1D7CC64Fb4452F05c498126312eBE29f30Fbcf9")),
+            Address::new(hex!("5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f")),
+        ),
+    ],
+};
+
+pub const BASE: ChainSpec = ChainSpec {
+    chain_id:          8453,
+    name:              "base",
+    explorer_url:      "https://basescan.org",
+    etherscan_api:     "https://api.basescan.org/api",
+    wrapped_native:    Address::new(hex!("4200000000000000000000000000000000000006")),
+    block_time:        2,
+    base_fee_params:   (250, 6),
+    quote_asset:       Address::new(hex!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")),
+    quote_assets:      &[
+        ("USDC", Address::new(hex!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"))),
+        ("DAI", Address::new(hex!("50c5725949A6F0c72E6C4a641F24049A917DB0Cb"))),
+    ],
+    classifier_config: "config/base/classifier_config.toml",
+    factories:         &[
+        // uniswap v2
+        (
+            Address::new(hex!("8909Dc15e40173Ff4699343b6eB8132c65e18eC6")),
+            Address::new(hex!("5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f")),
+        ),
+        // uniswap v3
+        (
+            Address::new(hex!("33128a8fC17869897dcE68Ed026d694621f6FDfD")),
+            Address::new(hex!("1F98431c8aD98523631AE4a59f267346ea31F984")),
+        ),
+    ],
+};
+
+pub const OPTIMISM: ChainSpec = ChainSpec {
+    chain_id:          10,
+    name:              "optimism",
+    explorer_url:      "https://optimistic.etherscan.io",
+    etherscan_api:     "https://api-optimistic.etherscan.io/api",
+    wrapped_native:    Address::new(hex!("4200000000000000000000000000000000000006")),
+    block_time:        2,
+    base_fee_params:   (250, 6),
+    quote_asset:       Address::new(hex!("94b008aA00579c1307B0EF2c499aD98a8ce58e58")),
+    quote_assets:      &[
+        ("USDT", Address::new(hex!("94b008aA00579c1307B0EF2c499aD98a8ce58e58"))),
+        ("USDC", Address::new(hex!("0b2C639c533813f4Aa9D7837CAf62653d097Ff85"))),
+        ("DAI", Address::new(hex!("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1"))),
+    ],
+    classifier_config: "config/optimism/classifier_config.toml",
+    factories:         &[],
+};
+
+pub const CHAIN_SPECS: [&ChainSpec; 4] = [&MAINNET, &ARBITRUM, &BASE, &OPTIMISM];
+
+impl ChainSpec {
+    pub fn from_chain_id(chain_id: u64) -> Option<&'static Self> {
+        CHAIN_SPECS
+            .into_iter()
+            .find(|chain| chain.chain_id == chain_id)
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        self.chain_id == MAINNET.chain_id
+    }
+
+    /// The mainnet address of the factory, which the discovery classifiers
+    /// are keyed by
+    pub fn canonical_factory(&self, factory: Address) -> Address {
+        self.factories
+            .iter()
+            .find_map(|(deployed, mainnet)| (*deployed == factory).then_some(*mainnet))
+            .unwrap_or(factory)
+    }
+
+    /// Resolves a quote asset given either as an address or as the symbol of
+    /// one of the stablecoins of the chain or `WETH`
+    pub fn parse_quote_asset(&self, s: &str) -> eyre::Result<Address> {
+        if let Ok(address) = s.parse::<Address>() {
+            return Ok(address)
+        }
+
+        let symbol = s.to_uppercase();
+        if symbol == "WETH" || symbol == "ETH" {
+            return Ok(self.wrapped_native)
+        }
+
+        self.quote_assets
+            .iter()
+            .find_map(|(asset, address)| (*asset == symbol).then_some(*address))
+            .ok_or_else(|| eyre::eyre!("unknown quote asset {s} on {}", self.name))
+    }
+
+    /// The eip-1559 base fee of the block after `parent`, `None` if the parent
+    /// predates london. Simulated blocks are executed and priced with it, like
+    /// the block that would be built on top of the parent.
+    #[cfg(feature = "native")]
+    pub fn next_block_base_fee(&self, parent: &Header) -> Option<u64> {
+        parent.next_block_base_fee(BaseFeeParams::new(
+            self.base_fee_params.0 as _,
+            self.base_fee_params.1 as _,
+        ))
+    }
+
+    pub fn tx_url(&self, tx_hash: TxHash) -> String {
+        format!("{}/tx/{:?}", self.explorer_url, tx_hash)
+    }
+
+    pub fn address_url(&self, address: Address) -> String {
+        format!("{}/address/{:?}", self.explorer_url, address)
+    }
+}
+
+impl FromStr for &'static ChainSpec {
+    type Err = eyre::Report;
+
+    /// Parses either the name or the chain id of a supported chain
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        s.parse::<u64>()
+            .ok()
+            .and_then(ChainSpec::from_chain_id)
+            .or_else(|| CHAIN_SPECS.into_iter().find(|chain| chain.name == s))
+            .ok_or_else(|| eyre::eyre!("unsupported chain: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_chains_and_factories() {
+        assert_eq!("Base".parse::<&ChainSpec>().unwrap(), &BASE);
+        assert_eq!("42161".parse::<&ChainSpec>().unwrap(), &ARBITRUM);
+        assert!("solana".parse::<&ChainSpec>().is_err());
+
+        let v3_factory = Address::new(hex!("1F98431c8aD98523631AE4a59f267346ea31F984"));
+        assert_eq!(
+            BASE.canonical_factory(Address::new(hex!("33128a8fC17869897dcE68Ed026d694621f6FDfD"))),
+            v3_factory
+        );
+        // deployed at the same address as on mainnet
+        assert_eq!(OPTIMISM.canonical_factory(v3_factory), v3_factory);
+    }
+
+    #[test]
+    fn resolves_quote_assets() {
+        assert_eq!(MAINNET.parse_quote_asset("usdc").unwrap(), USDC_ADDRESS);
+        assert_eq!(MAINNET.parse_quote_asset("WETH").unwrap(), WETH_ADDRESS);
+        assert_eq!(BASE.parse_quote_asset("weth").unwrap(), BASE.wrapped_native);
+        assert_eq!(
+            MAINNET
+                .parse_quote_asset(&format!("{DAI_ADDRESS:?}"))
+                .unwrap(),
+            DAI_ADDRESS
+        );
+        // no bridged usdt on base
+        assert!(BASE.parse_quote_asset("USDT").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn next_block_base_fee_follows_the_parent() {
+        let parent = |gas_used| Header {
+            gas_used,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+
+        // at the gas target the base fee doesn't move
+        assert_eq!(MAINNET.next_block_base_fee(&parent(15_000_000)), Some(1_000_000_000));
+        // a full block raises it by 1/8 and an empty one lowers it by 1/8
+        assert_eq!(MAINNET.next_block_base_fee(&parent(30_000_000)), Some(1_125_000_000));
+        assert_eq!(MAINNET.next_block_base_fee(&parent(0)), Some(875_000_000));
+        // the target of base is a sixth of the limit, a full block is five times
+        // over it and raises the fee by 5/250
+        assert_eq!(BASE.next_block_base_fee(&parent(30_000_000)), Some(1_020_000_000));
+
+        let pre_london = Header { gas_limit: 30_000_000, ..Default::default() };
+        assert_eq!(MAINNET.next_block_base_fee(&pre_london), None);
+    }
+}
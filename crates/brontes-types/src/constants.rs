@@ -1,5 +1,13 @@
 use alloy_primitives::{hex, Address};
 
+/// EIP-4844 blob gas parameters
+pub const DATA_GAS_PER_BLOB: u64 = 131_072;
+pub const MIN_BLOB_BASE_FEE: u128 = 1;
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u128 = 3_338_477;
+
+/// Seconds between two post-merge blocks
+pub const SLOT_TIME: u64 = 12;
+
 pub const USDT_ADDRESS_STRING: &str = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
 
 pub const ETH_ADDRESS: Address = Address::new(hex!("EeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE"));
@@ -39,6 +47,15 @@ pub const KCS_ADDRESS: Address = Address::new(hex!("f34960d9d60be18cc1d5afc1a6f0
 pub const EURT_ADDRESS: Address = Address::new(hex!("c581b735a1688071a1746c968e0798d642ede491"));
 pub const LINK_ADDRESS: Address = Address::new(hex!("514910771af9ca656af840dff83e8264ecf986ca"));
 pub const UNI_TOKEN: Address = Address::new(hex!("1f9840a85d5af5bf1d1762f925bdaddc4201f984"));
+pub const STETH_ADDRESS: Address = Address::new(hex!("ae7ab96520DE3A18E5e111B5EaAb095312D7fE84"));
+/// ERC-4337 EntryPoint v0.6
+pub const ENTRY_POINT_V06_ADDRESS: Address =
+    Address::new(hex!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789"));
+/// ERC-4337 EntryPoint v0.7
+pub const ENTRY_POINT_V07_ADDRESS: Address =
+    Address::new(hex!("0000000071727De22E5E9d8BAf0edAc6f37da032"));
+/// Uniswap's Permit2, deployed at the same address on every chain
+pub const PERMIT2_ADDRESS: Address = Address::new(hex!("000000000022D473030F116dDEE9F6B43aC78BA3"));
 pub const XAUT_ADDRESS: Address = Address::new(hex!("68749665ff8d2d112fa859aa293f07a622782f38"));
 
 /// The first block where the chainbound mempool data is available.
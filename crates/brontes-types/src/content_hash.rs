@@ -0,0 +1,486 @@
+//! Canonical encoding of serde values for content hashes that have to be
+//! stable across runs and processes, e.g. cache keys. Unlike the `Debug` output
+//! the encoding is unambiguous and doesn't change with formatting, and maps are
+//! encoded with their entries sorted, so the hash doesn't depend on the
+//! iteration order of a hash map. Sets are serialized as sequences by serde, so
+//! they still need to be serialized sorted by the caller, see
+//! [`serialize_sorted`].
+use std::fmt::Display;
+
+use alloy_primitives::{keccak256, B256};
+use serde::{ser, Serialize};
+
+const UNIT: u8 = 0;
+const BOOL: u8 = 1;
+const INT: u8 = 2;
+const UINT: u8 = 3;
+const FLOAT: u8 = 4;
+const CHAR: u8 = 5;
+const STR: u8 = 6;
+const BYTES: u8 = 7;
+const NONE: u8 = 8;
+const SOME: u8 = 9;
+const SEQ: u8 = 10;
+const TUPLE: u8 = 11;
+const MAP: u8 = 12;
+const STRUCT: u8 = 13;
+const VARIANT: u8 = 14;
+
+/// Keccak hash of the canonical encoding of the value
+pub fn content_hash<T: Serialize + ?Sized>(value: &T) -> Result<B256, ContentHashError> {
+    canonical_encoding(value).map(keccak256)
+}
+
+pub fn canonical_encoding<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, ContentHashError> {
+    let mut encoder = CanonicalEncoder::default();
+    value.serialize(&mut encoder)?;
+    Ok(encoder.out)
+}
+
+/// Serializes a set with its entries sorted, for use with
+/// `#[serde(serialize_with = "...")]` on the sets of hashed values
+pub fn serialize_sorted<'a, I, T, S>(set: &'a I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a I: IntoIterator<Item = &'a T>,
+    T: Ord + Serialize + 'a,
+    S: ser::Serializer,
+{
+    let mut entries = set.into_iter().collect::<Vec<_>>();
+    entries.sort_unstable();
+    serializer.collect_seq(entries)
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to encode the value: {0}")]
+pub struct ContentHashError(String);
+
+impl ser::Error for ContentHashError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Every value is prefixed with a tag of its kind. Strings and bytes are
+/// prefixed with their length and sequences, tuples and structs end with a
+/// terminator, so no encoding is a prefix of another
+#[derive(Default)]
+struct CanonicalEncoder {
+    out: Vec<u8>,
+}
+
+impl CanonicalEncoder {
+    fn len(&mut self, len: usize) {
+        self.out.extend_from_slice(&(len as u64).to_le_bytes());
+    }
+
+    fn bytes(&mut self, tag: u8, bytes: &[u8]) {
+        self.out.push(tag);
+        self.len(bytes.len());
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn int(&mut self, value: i128) {
+        self.out.push(INT);
+        self.out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn uint(&mut self, value: u128) {
+        self.out.push(UINT);
+        self.out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn variant(&mut self, variant_index: u32, variant: &str) {
+        self.out.push(VARIANT);
+        self.out.extend_from_slice(&variant_index.to_le_bytes());
+        self.bytes(STR, variant.as_bytes());
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut CanonicalEncoder {
+    type Error = ContentHashError;
+    type Ok = ();
+    type SerializeMap = MapEncoder<'a>;
+    type SerializeSeq = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.out.extend_from_slice(&[BOOL, v as u8]);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.int(v.into());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.int(v.into());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.int(v.into());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.int(v.into());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        self.int(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.uint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.uint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.uint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.uint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> {
+        self.uint(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.out.push(FLOAT);
+        self.out.extend_from_slice(&v.to_bits().to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> {
+        self.out.push(CHAR);
+        self.out.extend_from_slice(&(v as u32).to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.bytes(STR, v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.bytes(BYTES, v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.out.push(NONE);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Self::Error> {
+        self.out.push(SOME);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.out.push(UNIT);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Self::Error> {
+        self.out.push(UNIT);
+        self.bytes(STR, name.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.variant(variant_index, variant);
+        self.out.push(UNIT);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.variant(variant_index, variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.out.push(SEQ);
+        Ok(Compound { encoder: self })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.out.push(TUPLE);
+        Ok(Compound { encoder: self })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.out.push(TUPLE);
+        Ok(Compound { encoder: self })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.variant(variant_index, variant);
+        self.out.push(TUPLE);
+        Ok(Compound { encoder: self })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapEncoder { encoder: self, entries: vec![], key: None })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.out.push(STRUCT);
+        self.bytes(STR, name.as_bytes());
+        Ok(Compound { encoder: self })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.variant(variant_index, variant);
+        self.out.push(STRUCT);
+        Ok(Compound { encoder: self })
+    }
+}
+
+/// Sequences, tuples and structs. Each element is prefixed with a 1 and the
+/// end is marked with a 0
+struct Compound<'a> {
+    encoder: &'a mut CanonicalEncoder,
+}
+
+impl Compound<'_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ContentHashError> {
+        self.encoder.out.push(1);
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentHashError> {
+        self.encoder.out.push(1);
+        self.encoder.bytes(STR, key.as_bytes());
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn finish(self) -> Result<(), ContentHashError> {
+        self.encoder.out.push(0);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+/// Encodes every entry on its own and writes them sorted once the map is
+/// complete
+struct MapEncoder<'a> {
+    encoder: &'a mut CanonicalEncoder,
+    entries: Vec<Vec<u8>>,
+    key:     Option<Vec<u8>>,
+}
+
+impl ser::SerializeMap for MapEncoder<'_> {
+    type Error = ContentHashError;
+    type Ok = ();
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(canonical_encoding(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let mut entry = self
+            .key
+            .take()
+            .ok_or_else(|| <ContentHashError as ser::Error>::custom("map value without a key"))?;
+        entry.extend(canonical_encoding(value)?);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.entries.sort_unstable();
+        self.encoder.out.push(MAP);
+        self.encoder.len(self.entries.len());
+        self.entries
+            .into_iter()
+            .for_each(|entry| self.encoder.out.extend(entry));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::FastHashMap;
+
+    #[test]
+    fn maps_hash_the_same_in_any_order() {
+        let forward = (0..64u64)
+            .map(|i| (i, i.to_string()))
+            .collect::<FastHashMap<_, _>>();
+        let reverse = (0..64u64)
+            .rev()
+            .map(|i| (i, i.to_string()))
+            .collect::<FastHashMap<_, _>>();
+        let ordered = (0..64u64)
+            .map(|i| (i, i.to_string()))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(content_hash(&forward).unwrap(), content_hash(&reverse).unwrap());
+        assert_eq!(content_hash(&forward).unwrap(), content_hash(&ordered).unwrap());
+    }
+
+    #[test]
+    fn encoding_is_unambiguous() {
+        assert_ne!(content_hash(&("ab", "c")).unwrap(), content_hash(&("a", "bc")).unwrap());
+        assert_ne!(
+            content_hash(&vec![vec![1u64], vec![]]).unwrap(),
+            content_hash(&vec![vec![], vec![1u64]]).unwrap()
+        );
+        assert_ne!(content_hash(&Some(0u64)).unwrap(), content_hash(&0u64).unwrap());
+        assert_ne!(content_hash(&1u64).unwrap(), content_hash(&1i64).unwrap());
+    }
+}
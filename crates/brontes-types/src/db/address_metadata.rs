@@ -1,17 +1,26 @@
 use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
 use crate::{
-    db::redefined_types::primitives::AddressRedefined,
     implement_table_value_codecs_with_zc,
     serde_utils::{option_contract_info, socials},
 };
 
-#[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct AddressMetadata {
     pub entity_name:     Option<String>,
     pub nametag:         Option<String>,
@@ -23,7 +32,7 @@ pub struct AddressMetadata {
     pub ens:             Option<String>,
     #[serde(deserialize_with = "socials::deserialize")]
     #[serde(serialize_with = "socials::serialize")]
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub social_metadata: Socials,
 }
 
@@ -134,6 +143,7 @@ impl AddressMetadata {
                 l if l.contains("router") => Some(ContractType::Router),
                 l if l.contains("protocol") => Some(ContractType::Protocol),
                 l if l.contains("exchange") => Some(ContractType::Exchange),
+                l if l.contains("bridge") => Some(ContractType::Bridge),
                 _ => None,
             }
         })
@@ -207,8 +217,12 @@ impl ContractType {
 
 implement_table_value_codecs_with_zc!(AddressMetadataRedefined);
 
-#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct ContractInfo {
     pub verified_contract: Option<bool>,
     pub contract_creator:  Option<Address>,
@@ -231,9 +245,8 @@ impl ContractInfo {
     }
 }
 
-#[derive(
-    Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, rSerialize, rDeserialize, Archive,
-)]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
 pub struct Socials {
     pub twitter:           Option<String>,
     pub twitter_followers: Option<u64>,
@@ -262,4 +275,5 @@ impl Socials {
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(Socials);
@@ -1,32 +1,42 @@
 use std::str::FromStr;
 
 use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::{fixed_string::FixedString, Row};
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
 use crate::{
-    db::redefined_types::primitives::AddressRedefined,
     implement_table_value_codecs_with_zc,
-    serde_utils::{addresss, option_addresss, protocol},
-    Protocol,
+    pair::Pair,
+    serde_utils::{addresss, option_addresss, protocol, vec_address},
+    FastHashSet, Protocol,
 };
 
-#[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined, Hash)]
-#[redefined_attr(derive(
-    Debug,
-    PartialEq,
-    Clone,
-    Serialize,
-    rSerialize,
-    rDeserialize,
-    Archive,
-    Hash
-))]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        PartialEq,
+        Clone,
+        Serialize,
+        rSerialize,
+        rDeserialize,
+        Archive,
+        Hash
+    ))
+)]
 pub struct ProtocolInfo {
     #[serde(with = "protocol")]
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:       Protocol,
     #[serde(with = "addresss")]
     pub token0:         Address,
@@ -41,9 +51,52 @@ pub struct ProtocolInfo {
     #[serde(with = "option_addresss")]
     pub curve_lp_token: Option<Address>,
     pub init_block:     u64,
+    /// Tokens of pools with more than five tokens, e.g. balancer weighted
+    /// pools, past `token4`
+    #[serde(default, with = "vec_address")]
+    pub extra_tokens:   Vec<Address>,
 }
 
 impl ProtocolInfo {
+    /// Spreads the tokens of the pool over the token fields, in order
+    pub fn new(
+        protocol: Protocol,
+        tokens: &[Address],
+        curve_lp_token: Option<Address>,
+        init_block: u64,
+    ) -> Self {
+        let mut tokens = tokens.iter().copied();
+        Self {
+            protocol,
+            token0: tokens.next().unwrap_or_default(),
+            token1: tokens.next().unwrap_or_default(),
+            token2: tokens.next(),
+            token3: tokens.next(),
+            token4: tokens.next(),
+            curve_lp_token,
+            init_block,
+            extra_tokens: tokens.collect(),
+        }
+    }
+
+    /// The token at `index` of the pool's token list
+    pub fn token(&self, index: usize) -> Option<Address> {
+        match index {
+            0 => Some(self.token0),
+            1 => Some(self.token1),
+            2 => self.token2,
+            3 => self.token3,
+            4 => self.token4,
+            i => self.extra_tokens.get(i - 5).copied(),
+        }
+    }
+
+    /// The pair of the first two tokens, which the pricing graph knows the
+    /// pool by
+    pub fn pair(&self) -> Pair {
+        Pair(self.token0, self.token1)
+    }
+
     pub fn get_tokens(&self) -> Vec<Address> {
         let mut tokens = vec![self.token0, self.token1]
             .into_iter()
@@ -51,14 +104,42 @@ impl ProtocolInfo {
             .collect::<Vec<_>>();
 
         tokens.extend(
-            [self.token2, self.token3, self.token4, self.curve_lp_token]
+            [self.token2, self.token3, self.token4]
                 .into_iter()
-                .flatten()
-                .collect::<Vec<_>>(),
+                .flatten(),
         );
+        tokens.extend(self.extra_tokens.iter().copied());
+        tokens.extend(self.curve_lp_token);
 
         tokens
     }
+
+    /// Metapools used to be registered with the meta coin after the coins of
+    /// the base pool. Moves it to the front if the tokens before it are the
+    /// coins of one of the `base_pools` while the tokens after the first one
+    /// aren't, so entries that are already in the current order are kept.
+    /// Returns whether the tokens were reordered.
+    pub fn reorder_legacy_metapool_tokens(
+        &mut self,
+        base_pools: &FastHashSet<Vec<Address>>,
+    ) -> bool {
+        if !self.protocol.is_curve_metapool() {
+            return false
+        }
+
+        let mut tokens = self.clone().into_iter().collect::<Vec<_>>();
+        let Some((meta_coin, base_coins)) = tokens.split_last() else { return false };
+        if !base_pools.contains(base_coins) || base_pools.contains(&tokens[1..]) {
+            return false
+        }
+
+        let meta_coin = *meta_coin;
+        tokens.pop();
+        tokens.insert(0, meta_coin);
+        *self = Self::new(self.protocol, &tokens, self.curve_lp_token, self.init_block);
+
+        true
+    }
 }
 
 impl IntoIterator for ProtocolInfo {
@@ -69,6 +150,7 @@ impl IntoIterator for ProtocolInfo {
         vec![Some(self.token0), Some(self.token1), self.token2, self.token3, self.token4]
             .into_iter()
             .flatten()
+            .chain(self.extra_tokens)
             .collect::<Vec<_>>()
             .into_iter()
     }
@@ -79,24 +161,53 @@ impl From<(Vec<String>, u64, String, Option<String>)> for ProtocolInfo {
         let init_block = value.1;
         let protocol = Protocol::from_db_string(&value.2);
         let curve_lp_token = value.3.map(|s| Address::from_str(&s).unwrap());
-        let value = value.0;
-        let mut iter = value.into_iter();
-        ProtocolInfo {
-            protocol,
-            token0: Address::from_str(&iter.next().unwrap()).unwrap(),
-            token1: Address::from_str(&iter.next().unwrap()).unwrap(),
-            token2: iter.next().and_then(|a| Address::from_str(&a).ok()),
-            token3: iter.next().and_then(|a| Address::from_str(&a).ok()),
-            token4: iter.next().and_then(|a| Address::from_str(&a).ok()),
-            curve_lp_token,
-            init_block,
+        let tokens = value
+            .0
+            .iter()
+            .map(|token| Address::from_str(token).unwrap())
+            .collect::<Vec<_>>();
+
+        ProtocolInfo::new(protocol, &tokens, curve_lp_token, init_block)
+    }
+}
+
+/// Layout of [`ProtocolInfoRedefined`] from before pools could have more than
+/// five tokens. Entries that were written with it are still read, so existing
+/// databases don't have to be reinitialized.
+#[cfg(feature = "rkyv")]
+#[derive(Debug, Archive, rSerialize, rDeserialize)]
+pub struct LegacyProtocolInfoRedefined {
+    pub protocol:       Protocol,
+    pub token0:         AddressRedefined,
+    pub token1:         AddressRedefined,
+    pub token2:         Option<AddressRedefined>,
+    pub token3:         Option<AddressRedefined>,
+    pub token4:         Option<AddressRedefined>,
+    pub curve_lp_token: Option<AddressRedefined>,
+    pub init_block:     u64,
+}
+
+#[cfg(feature = "rkyv")]
+impl From<LegacyProtocolInfoRedefined> for ProtocolInfoRedefined {
+    fn from(value: LegacyProtocolInfoRedefined) -> Self {
+        Self {
+            protocol:       value.protocol,
+            token0:         value.token0,
+            token1:         value.token1,
+            token2:         value.token2,
+            token3:         value.token3,
+            token4:         value.token4,
+            curve_lp_token: value.curve_lp_token,
+            init_block:     value.init_block,
+            extra_tokens:   vec![],
         }
     }
 }
 
-implement_table_value_codecs_with_zc!(ProtocolInfoRedefined);
+implement_table_value_codecs_with_zc!(ProtocolInfoRedefined, legacy = LegacyProtocolInfoRedefined);
 
-#[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg(feature = "native")]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Row)]
 pub struct ProtocolInfoClickhouse {
     pub protocol:         String,
     pub protocol_subtype: String,
@@ -106,6 +217,7 @@ pub struct ProtocolInfoClickhouse {
     pub init_block:       u64,
 }
 
+#[cfg(feature = "native")]
 impl ProtocolInfoClickhouse {
     pub fn new(
         block: u64,
@@ -125,3 +237,65 @@ impl ProtocolInfoClickhouse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_rlp::{Decodable, Encodable};
+    #[cfg(feature = "rkyv")]
+    use redefined::RedefinedConvert;
+
+    use super::*;
+
+    #[test]
+    fn decodes_more_than_five_tokens_and_legacy_layout() {
+        let tokens = (1..=7).map(Address::with_last_byte).collect::<Vec<_>>();
+        let info = ProtocolInfo::new(Protocol::BalancerV2, &tokens, None, 10);
+        assert_eq!(info.token(6), Some(tokens[6]));
+        assert_eq!(info.get_tokens(), tokens);
+
+        let mut buf = Vec::new();
+        ProtocolInfoRedefined::from_source(info.clone()).encode(&mut buf);
+        let decoded = ProtocolInfoRedefined::decode(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded.to_source(), info);
+
+        let legacy = LegacyProtocolInfoRedefined {
+            protocol:       Protocol::CurveBasePool3,
+            token0:         AddressRedefined::from_source(tokens[0]),
+            token1:         AddressRedefined::from_source(tokens[1]),
+            token2:         Some(AddressRedefined::from_source(tokens[2])),
+            token3:         None,
+            token4:         None,
+            curve_lp_token: None,
+            init_block:     5,
+        };
+        let buf = rkyv::to_bytes::<_, 256>(&legacy).unwrap();
+        let decoded = ProtocolInfoRedefined::decode(&mut &buf[..])
+            .unwrap()
+            .to_source();
+        assert_eq!(decoded.get_tokens(), tokens[..3]);
+        assert_eq!(decoded.init_block, 5);
+    }
+
+    #[test]
+    fn reorders_legacy_metapool_tokens_once() {
+        let base_coins = (1..=3).map(Address::with_last_byte).collect::<Vec<_>>();
+        let meta_coin = Address::with_last_byte(4);
+        let lp = Address::with_last_byte(5);
+        let base_pools = FastHashSet::from_iter([base_coins.clone()]);
+
+        let legacy = [base_coins.clone(), vec![meta_coin]].concat();
+        let mut info = ProtocolInfo::new(Protocol::CurveV1MetaPool, &legacy, Some(lp), 10);
+        assert!(info.reorder_legacy_metapool_tokens(&base_pools));
+
+        let current = [vec![meta_coin], base_coins.clone()].concat();
+        assert_eq!(info, ProtocolInfo::new(Protocol::CurveV1MetaPool, &current, Some(lp), 10));
+
+        // already in the current order
+        assert!(!info.reorder_legacy_metapool_tokens(&base_pools));
+        assert_eq!(info.token0, meta_coin);
+
+        // only metapools are reordered
+        let mut plain = ProtocolInfo::new(Protocol::CurveV2PlainPool, &legacy, None, 10);
+        assert!(!plain.reorder_legacy_metapool_tokens(&base_pools));
+    }
+}
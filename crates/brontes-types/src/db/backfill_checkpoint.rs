@@ -0,0 +1,147 @@
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// Progress of a backfilled block range, keyed by the first block of the
+/// range. Blocks of a range finish out of order, so only the blocks before
+/// `next_block` are known to be done. Dex quotes are persisted as soon as a
+/// block is priced, which runs ahead of the processing, so `priced_block` is
+/// tracked separately.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct BackfillCheckpoint {
+    /// End of the range, exclusive
+    pub end_block:    u64,
+    /// First block of the range that isn't done yet
+    pub next_block:   u64,
+    /// First block of the range whose dex quotes haven't been persisted yet
+    pub priced_block: u64,
+}
+
+implement_table_value_codecs_with_zc!(BackfillCheckpointRedefined);
+
+impl BackfillCheckpoint {
+    pub fn is_finished(&self) -> bool {
+        self.next_block >= self.end_block
+    }
+
+    /// Keeps the further progress of the two
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            end_block:    self.end_block.max(other.end_block),
+            next_block:   self.next_block.max(other.next_block),
+            priced_block: self.priced_block.max(other.priced_block),
+        }
+    }
+
+    /// Moves the progress of the range starting at `start_block` back to the
+    /// first reorged out block, so that the stale blocks are processed again
+    pub fn revert(self, start_block: u64, first_stale: u64) -> Self {
+        let first_stale = first_stale.max(start_block);
+        Self {
+            end_block:    self.end_block,
+            next_block:   self.next_block.min(first_stale),
+            priced_block: self.priced_block.min(first_stale),
+        }
+    }
+}
+
+/// First block from `block` on whose dex quotes a checkpoint doesn't mark as
+/// persisted. Pricing runs in block order, so the blocks between `block` and
+/// the returned one can be inspected with the stored quotes.
+pub fn priced_until(block: u64, checkpoints: &[(u64, BackfillCheckpoint)]) -> u64 {
+    checkpoints
+        .iter()
+        .filter(|(start, checkpoint)| *start <= block && block < checkpoint.end_block)
+        .map(|(_, checkpoint)| checkpoint.priced_block.min(checkpoint.end_block))
+        .fold(block, u64::max)
+}
+
+/// Parts of the range `start_block..end_block` that no checkpoint marks as
+/// done
+pub fn remaining_ranges(
+    start_block: u64,
+    end_block: u64,
+    checkpoints: &[(u64, BackfillCheckpoint)],
+) -> Vec<(u64, u64)> {
+    let mut done = checkpoints
+        .iter()
+        .map(|(start, checkpoint)| (*start, checkpoint.next_block.min(checkpoint.end_block)))
+        .filter(|(start, next)| start < next)
+        .collect::<Vec<_>>();
+    done.sort_unstable();
+
+    let mut remaining = vec![];
+    let mut cursor = start_block;
+    for (start, next) in done {
+        if cursor >= end_block {
+            break
+        }
+        if start > cursor {
+            remaining.push((cursor, start.min(end_block)));
+        }
+        cursor = cursor.max(next);
+    }
+
+    if cursor < end_block {
+        remaining.push((cursor, end_block));
+    }
+
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(start: u64, next_block: u64, end_block: u64) -> (u64, BackfillCheckpoint) {
+        (start, BackfillCheckpoint { end_block, next_block, priced_block: next_block })
+    }
+
+    #[test]
+    fn remaining_ranges_skip_done_blocks() {
+        assert_eq!(remaining_ranges(0, 100, &[]), vec![(0, 100)]);
+
+        let checkpoints = [
+            checkpoint(50, 60, 100),
+            checkpoint(0, 25, 50),
+            // no progress
+            checkpoint(25, 25, 50),
+            // overlaps the first range
+            checkpoint(10, 30, 50),
+        ];
+        assert_eq!(remaining_ranges(0, 100, &checkpoints), vec![(30, 50), (60, 100)]);
+        assert_eq!(remaining_ranges(55, 58, &checkpoints), vec![]);
+        assert_eq!(remaining_ranges(40, 70, &checkpoints), vec![(40, 50), (60, 70)]);
+    }
+
+    #[test]
+    fn revert_moves_progress_back_to_stale_block() {
+        let (start, done) = checkpoint(50, 80, 100);
+        assert_eq!(done.revert(start, 70), checkpoint(50, 70, 100).1);
+        // blocks past the progress or before the range don't change it
+        assert_eq!(done.revert(start, 90), done);
+        assert_eq!(done.revert(start, 10), checkpoint(50, 50, 100).1);
+    }
+
+    #[test]
+    fn priced_until_skips_persisted_quotes() {
+        let checkpoints = [
+            (0, BackfillCheckpoint { end_block: 50, next_block: 20, priced_block: 35 }),
+            (50, BackfillCheckpoint { end_block: 100, next_block: 60, priced_block: 60 }),
+        ];
+        assert_eq!(priced_until(20, &checkpoints), 35);
+        // past the priced blocks of the range
+        assert_eq!(priced_until(40, &checkpoints), 40);
+        assert_eq!(priced_until(60, &checkpoints), 60);
+        assert_eq!(priced_until(100, &checkpoints), 100);
+    }
+}
@@ -1,9 +1,9 @@
 use std::{collections::HashMap, hash::Hash, str::FromStr};
 use crate::serde_utils::address;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use itertools::Itertools;
-use reth_primitives::TxHash;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use crate::serde_utils::vec_address;
@@ -18,7 +18,8 @@ use crate::{
 };
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct BlockAnalysis {
     pub block_number: u64,
     pub eth_price: f64,
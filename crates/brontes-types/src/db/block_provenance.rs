@@ -0,0 +1,69 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{keccak256, B256};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::serde_utils::txhash;
+
+/// Records how the results of a block were produced, so stored results can be
+/// traced back to the build and inspector configuration that produced them.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct BlockProvenance {
+    pub block_number:    u64,
+    /// Version of the brontes build, e.g. `0.1.0 (defa64b2)`
+    pub brontes_version: String,
+    pub git_sha:         String,
+    /// Ids of the inspectors that were run on the block
+    pub inspectors:      Vec<String>,
+    /// Hash of the inspectors, the inspector config and every other setting
+    /// that changes their results
+    #[serde(with = "txhash")]
+    pub config_hash:     B256,
+    /// Unix timestamp in microseconds of when processing the block started
+    pub started_at:      u64,
+    /// Unix timestamp in microseconds of when the results were written
+    pub finished_at:     u64,
+}
+
+impl BlockProvenance {
+    /// `inspectors` are the ids of the inspectors together with the
+    /// identifiers of their settings, see `Inspector::cache_id`.
+    /// `inspector_config` is the hash of the inspector config of the run
+    pub fn new(
+        block_number: u64,
+        brontes_version: &str,
+        git_sha: &str,
+        inspectors: impl IntoIterator<Item = (String, String)>,
+        inspector_config: B256,
+        started_at: u64,
+    ) -> Self {
+        let (inspectors, settings): (Vec<_>, Vec<_>) = inspectors.into_iter().unzip();
+        let mut config = settings.join("\n").into_bytes();
+        config.extend_from_slice(inspector_config.as_slice());
+
+        Self {
+            block_number,
+            brontes_version: brontes_version.to_string(),
+            git_sha: git_sha.to_string(),
+            inspectors,
+            config_hash: keccak256(config),
+            started_at,
+            finished_at: started_at,
+        }
+    }
+
+    pub fn finish(mut self) -> Self {
+        self.finished_at = unix_micros();
+        self
+    }
+}
+
+pub fn unix_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
@@ -1,22 +1,14 @@
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use serde::Deserialize;
 
-#[derive(Debug, Default, Clone, Row, PartialEq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct BlockTimes {
     pub block_number: u64,
     pub timestamp:    u64,
 }
 
-impl BlockTimes {
-    pub fn convert_to_timestamp_query(&self, before_block: f64, after_block: f64) -> String {
-        format!(
-            "(c.timestamp >= {} AND c.timestamp < {})",
-            self.timestamp as f64 - before_block,
-            self.timestamp as f64 + after_block
-        )
-    }
-}
-
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct CexBlockTimes {
     pub start_timestamp:   u64,
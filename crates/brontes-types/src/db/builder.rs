@@ -1,26 +1,35 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, FixedBytes};
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_rpc_types::beacon::BlsPublicKey;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::{AddressRedefined, BlsPublicKeyRedefined};
 use crate::{
-    db::{
-        redefined_types::primitives::{AddressRedefined, BlsPublicKeyRedefined},
-        searcher::Fund,
-    },
+    db::searcher::Fund,
     implement_table_value_codecs_with_zc,
     serde_utils::{addresss, option_addresss, option_fund, vec_address, vec_bls_pub_key},
     FastHashSet,
 };
 
-#[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+/// A builder's bls public key, as used by the beacon chain and the relays
+pub type BlsPublicKey = FixedBytes<48>;
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct BuilderInfo {
     pub name: Option<String>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(deserialize_with = "option_fund::deserialize")]
     #[serde(default)]
     pub fund: Option<Fund>,
@@ -92,7 +101,8 @@ impl BuilderInfo {
 implement_table_value_codecs_with_zc!(BuilderInfoRedefined);
 
 #[serde_as]
-#[derive(Debug, Default, Row, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct BuilderInfoWithAddress {
     #[serde(with = "addresss")]
     pub address: Address,
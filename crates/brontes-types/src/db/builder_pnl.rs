@@ -0,0 +1,181 @@
+use alloy_primitives::Address;
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use itertools::Itertools;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
+use crate::{
+    implement_table_value_codecs_with_zc,
+    mev::MevBlock,
+    serde_utils::{address, addresss},
+    FastHashMap,
+};
+
+/// What the builder of a block earned and what it paid out for it. All
+/// amounts are in wei unless stated otherwise.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct BuilderPnl {
+    pub block_number:       u64,
+    #[serde(with = "addresss")]
+    pub builder_address:    Address,
+    pub builder_name:       Option<String>,
+    /// Priority fees of the transactions in the block
+    pub priority_fees:      u128,
+    /// Coinbase transfers to the builder
+    pub coinbase_transfers: u128,
+    /// Paid to the proposer fee recipient
+    pub proposer_payment:   u128,
+    /// Gas and tips the builder refunded to transactions of the block
+    pub sponsorship:        u128,
+    /// Revenue minus the proposer payment and the sponsorship, in eth. The
+    /// builder profit of the mev block
+    pub profit_eth:         f64,
+    pub profit_usd:         f64,
+    /// Paid out beyond the revenue of the block, zero unless the builder
+    /// subsidized the block
+    pub subsidy:            u128,
+}
+
+implement_table_value_codecs_with_zc!(BuilderPnlRedefined);
+
+impl BuilderPnl {
+    /// The proposer payment, the sponsorship and the profit are the ones the
+    /// composer found for the block. Blocks without a proposer payment, e.g.
+    /// ones that weren't built through mev-boost, have the whole revenue as
+    /// profit.
+    pub fn new(block: &MevBlock) -> Self {
+        let revenue = block.total_priority_fee + block.total_bribe;
+        let paid = block.proposer_mev_reward.unwrap_or_default() + block.builder_sponsorship_amount;
+
+        Self {
+            block_number:       block.block_number,
+            builder_address:    block.builder_address,
+            builder_name:       block.builder_name.clone(),
+            priority_fees:      block.total_priority_fee,
+            coinbase_transfers: block.total_bribe,
+            proposer_payment:   block.proposer_mev_reward.unwrap_or_default(),
+            sponsorship:        block.builder_sponsorship_amount,
+            profit_eth:         block.builder_eth_profit,
+            profit_usd:         block.builder_profit_usd,
+            subsidy:            paid.saturating_sub(revenue),
+        }
+    }
+
+    pub fn revenue(&self) -> u128 {
+        self.priority_fees + self.coinbase_transfers
+    }
+
+    pub fn is_subsidized(&self) -> bool {
+        self.subsidy > 0
+    }
+}
+
+/// The pnl of a builder aggregated over the blocks it built in a range
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct BuilderPnlStats {
+    #[serde(with = "address")]
+    pub builder_address:   Address,
+    pub builder_name:      Option<String>,
+    pub blocks:            u64,
+    /// Priority fees and coinbase transfers, in wei
+    pub revenue:           u128,
+    pub proposer_payments: u128,
+    pub sponsorship:       u128,
+    pub profit_eth:        f64,
+    pub profit_usd:        f64,
+    pub subsidized_blocks: u64,
+    pub total_subsidy:     u128,
+}
+
+impl BuilderPnlStats {
+    /// Groups the blocks by builder, the builders that built the most blocks
+    /// come first
+    pub fn from_blocks<'a>(blocks: impl IntoIterator<Item = &'a BuilderPnl>) -> Vec<Self> {
+        let mut builders: FastHashMap<Address, Self> = FastHashMap::default();
+
+        for block in blocks {
+            let stats = builders
+                .entry(block.builder_address)
+                .or_insert_with(|| Self {
+                    builder_address: block.builder_address,
+                    ..Default::default()
+                });
+
+            stats.builder_name = block.builder_name.clone().or(stats.builder_name.take());
+            stats.blocks += 1;
+            stats.revenue += block.revenue();
+            stats.proposer_payments += block.proposer_payment;
+            stats.sponsorship += block.sponsorship;
+            stats.profit_eth += block.profit_eth;
+            stats.profit_usd += block.profit_usd;
+            stats.subsidized_blocks += block.is_subsidized() as u64;
+            stats.total_subsidy += block.subsidy;
+        }
+
+        builders
+            .into_values()
+            .sorted_by(|a, b| {
+                b.blocks
+                    .cmp(&a.blocks)
+                    .then(a.builder_address.cmp(&b.builder_address))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn detects_subsidized_blocks() {
+        let [a, b] = [1, 2].map(Address::with_last_byte);
+        // the profit is the one the composer stored on the block
+        let block =
+            |builder, total_priority_fee, total_bribe, proposer_mev_reward, profit| MevBlock {
+                builder_address: builder,
+                total_priority_fee,
+                total_bribe,
+                proposer_mev_reward: Some(proposer_mev_reward),
+                builder_eth_profit: profit,
+                builder_profit_usd: profit * 2_000.0,
+                eth_price: 2_000.0,
+                ..Default::default()
+            };
+
+        let profitable = BuilderPnl::new(&block(a, ETH, ETH / 2, ETH, 0.5));
+        assert_eq!(profitable.profit_eth, 0.5);
+        assert_eq!(profitable.profit_usd, 1_000.0);
+        assert!(!profitable.is_subsidized());
+
+        let subsidized = BuilderPnl::new(&block(a, ETH / 2, 0, ETH, -0.5));
+        assert_eq!(subsidized.profit_eth, -0.5);
+        assert_eq!(subsidized.subsidy, ETH / 2);
+
+        let other = BuilderPnl::new(&block(b, ETH, 0, ETH / 2, 0.5));
+
+        let [a_stats, b_stats] = BuilderPnlStats::from_blocks(&[profitable, subsidized, other])
+            .try_into()
+            .unwrap();
+        assert_eq!((a_stats.builder_address, a_stats.blocks), (a, 2));
+        assert_eq!(a_stats.revenue, 2 * ETH);
+        assert_eq!(a_stats.profit_eth, 0.0);
+        assert_eq!((a_stats.subsidized_blocks, a_stats.total_subsidy), (1, ETH / 2));
+        assert_eq!((b_stats.blocks, b_stats.profit_eth), (1, 0.5));
+    }
+}
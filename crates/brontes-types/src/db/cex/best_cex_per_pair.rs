@@ -1,3 +1,4 @@
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +8,8 @@ use crate::serde_utils::cex_exchange_vec;
 /// stores the best cex (most volume on the pair).
 /// this is used to choose what cex is most likely the
 /// driver of true price
-#[derive(Debug, Clone, Row, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct BestCexPerPair {
     pub symbol:    String,
     #[serde(with = "cex_exchange_vec")]
@@ -1,3 +1,4 @@
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use serde::Deserialize;
 
@@ -7,7 +8,8 @@ use crate::{
     serde_utils::{address_pair, cex_exchange},
 };
 
-#[derive(Debug, Default, Clone, Row, Hash, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct CexSymbols {
     #[serde(with = "cex_exchange")]
     pub exchange:     CexExchange,
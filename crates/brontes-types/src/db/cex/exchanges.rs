@@ -2,28 +2,18 @@ use std::default::Default;
 
 use alloy_primitives::Address;
 use malachite::{num::conversion::traits::FromSciString, Rational};
+#[cfg(feature = "rkyv")]
 use redefined::self_convert_redefined;
 use serde::Deserialize;
 use strum::Display;
 
+use super::fees::cex_fee_schedule;
 use crate::constants::*;
 
-#[derive(
-    Copy,
-    Display,
-    Debug,
-    Clone,
-    Default,
-    Eq,
-    PartialEq,
-    Hash,
-    serde::Serialize,
-    // serde::Deserialize,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
-)]
-#[archive_attr(derive(Eq, PartialEq, Hash))]
+#[derive(Copy, Display, Debug, Clone, Default, Eq, PartialEq, Hash, serde::Serialize)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+#[cfg_attr(feature = "rkyv", archive_attr(derive(Eq, PartialEq, Hash)))]
 pub enum CexExchange {
     Binance,
     Bitmex,
@@ -45,6 +35,7 @@ pub enum CexExchange {
     Unknown,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(CexExchange);
 
 impl<'de> serde::Deserialize<'de> for CexExchange {
@@ -58,25 +49,26 @@ impl<'de> serde::Deserialize<'de> for CexExchange {
 }
 
 impl CexExchange {
-    pub fn to_clickhouse_filter(&self) -> &str {
+    /// The name of the exchange in the clickhouse cex tables
+    pub fn to_clickhouse_name(&self) -> &'static str {
         match self {
-            CexExchange::Binance => "c.exchange = 'binance'",
-            CexExchange::Bitmex => "c.exchange = 'bitmex'",
-            CexExchange::Deribit => "c.exchange = 'deribit'",
-            CexExchange::Okex => "c.exchange = 'okex'",
-            CexExchange::Coinbase => "c.exchange = 'coinbase'",
-            CexExchange::Kraken => "c.exchange = 'kraken'",
-            CexExchange::BybitSpot => "c.exchange = 'bybit'",
-            CexExchange::Kucoin => "c.exchange = 'kucoin'",
-            CexExchange::Upbit => "c.exchange = 'upbit'",
-            CexExchange::Huobi => "c.exchange = 'huobi'",
-            CexExchange::GateIo => "c.exchange = 'gate-io",
-            CexExchange::Bitstamp => "c.exchange = 'bitstamp'",
-            CexExchange::Gemini => "c.exchange = 'gemini'",
-            CexExchange::Unknown => "c.exchange = ''",
-            CexExchange::Average => "c.exchange = ''",
-            CexExchange::VWAP => "c.exchange = ''",
-            CexExchange::OptimisticVWAP => "c.exchange = ''",
+            CexExchange::Binance => "binance",
+            CexExchange::Bitmex => "bitmex",
+            CexExchange::Deribit => "deribit",
+            CexExchange::Okex => "okex",
+            CexExchange::Coinbase => "coinbase",
+            CexExchange::Kraken => "kraken",
+            CexExchange::BybitSpot => "bybit",
+            CexExchange::Kucoin => "kucoin",
+            CexExchange::Upbit => "upbit",
+            CexExchange::Huobi => "huobi",
+            CexExchange::GateIo => "gate-io",
+            CexExchange::Bitstamp => "bitstamp",
+            CexExchange::Gemini => "gemini",
+            CexExchange::Unknown => "",
+            CexExchange::Average => "",
+            CexExchange::VWAP => "",
+            CexExchange::OptimisticVWAP => "",
         }
     }
 }
@@ -210,14 +202,20 @@ impl CexExchange {
         }
     }
 
-    /// Returns the maker & taker fees by exchange
+    /// Returns the maker & taker fees of the exchange from the fee schedule
+    /// of the run
+    pub fn fees(&self) -> (Rational, Rational) {
+        cex_fee_schedule().fees(self)
+    }
+
+    /// Returns the built in maker & taker fees by exchange
     /// Assumes best possible fee structure e.g Binanace VIP 9 for example
     /// Does not account for special market maker rebate programs or special
     /// pairs
     ///
     /// TODO: Account for special fee pairs & stableswap rates
     /// TODO: Account for futures & spot fee deltas
-    pub fn fees(&self) -> (Rational, Rational) {
+    pub fn default_fees(&self) -> (Rational, Rational) {
         match self {
             CexExchange::Binance => (
                 Rational::from_sci_string("0.00012").unwrap(),
@@ -0,0 +1,137 @@
+//! Maker and taker fees of the centralized exchanges. Cex quotes and trades
+//! are adjusted by them, so the profit of a cex-dex arb accounts for what the
+//! cex leg costs to execute. Exchanges without a configured entry keep the
+//! built in fees of [`CexExchange::default_fees`].
+//!
+//! ```toml
+//! [cex_fees.binance]
+//! maker_bps = 1.0
+//! taker_bps = 2.5
+//!
+//! [cex_fees.okex]
+//! maker_bps = -0.5
+//! taker_bps = 1.5
+//! ```
+use std::sync::OnceLock;
+
+use itertools::Itertools;
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+use super::CexExchange;
+use crate::FastHashMap;
+
+static FEE_SCHEDULE: OnceLock<CexFeeSchedule> = OnceLock::new();
+
+/// Sets the fee schedule of the run. Needs to be called before the inspectors
+/// are started.
+pub fn init_cex_fee_schedule(schedule: CexFeeSchedule) -> eyre::Result<()> {
+    schedule.validate()?;
+    FEE_SCHEDULE
+        .set(schedule)
+        .map_err(|_| eyre::eyre!("cex fee schedule already initialized"))
+}
+
+/// The fee schedule of the run, the built in fees if none was set
+pub fn cex_fee_schedule() -> &'static CexFeeSchedule {
+    FEE_SCHEDULE.get_or_init(CexFeeSchedule::default)
+}
+
+/// Fees of an exchange in basis points. A negative maker fee is a rebate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeFees {
+    pub maker_bps: f64,
+    pub taker_bps: f64,
+}
+
+impl ExchangeFees {
+    /// The maker & taker fees as a share of the traded amount
+    pub fn as_rationals(&self) -> (Rational, Rational) {
+        (bps_to_rational(self.maker_bps), bps_to_rational(self.taker_bps))
+    }
+}
+
+fn bps_to_rational(bps: f64) -> Rational {
+    Rational::try_from_float_simplest(bps).unwrap() / Rational::from(10_000u32)
+}
+
+/// Fee overrides per exchange
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CexFeeSchedule {
+    pub exchanges: FastHashMap<CexExchange, ExchangeFees>,
+}
+
+impl CexFeeSchedule {
+    pub fn is_default(&self) -> bool {
+        self.exchanges.is_empty()
+    }
+
+    /// Returns the maker & taker fees of the exchange
+    pub fn fees(&self, exchange: &CexExchange) -> (Rational, Rational) {
+        self.exchanges
+            .get(exchange)
+            .map(ExchangeFees::as_rationals)
+            .unwrap_or_else(|| exchange.default_fees())
+    }
+
+    /// Identifies the overrides, so results computed with different fees
+    /// aren't mixed up
+    pub fn cache_id(&self) -> String {
+        self.exchanges
+            .iter()
+            .map(|(exchange, fees)| (exchange.to_clickhouse_name(), fees.maker_bps, fees.taker_bps))
+            .sorted_by(|a, b| a.0.cmp(b.0))
+            .map(|(exchange, maker, taker)| format!("{exchange}:{maker}:{taker}"))
+            .join(",")
+    }
+
+    fn validate(&self) -> eyre::Result<()> {
+        for (exchange, fees) in &self.exchanges {
+            if exchange.to_clickhouse_name().is_empty() {
+                eyre::bail!("cex fees set for an unsupported exchange: {exchange}")
+            }
+
+            for bps in [fees.maker_bps, fees.taker_bps] {
+                if !bps.is_finite() || bps.abs() >= 10_000.0 {
+                    eyre::bail!("invalid fee of {bps} bps for {exchange}")
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::num::conversion::traits::FromSciString;
+
+    use super::*;
+
+    #[test]
+    fn overrides_fall_back_to_default_fees() {
+        let schedule: CexFeeSchedule = serde_json::from_str(
+            r#"{
+                "binance": { "maker_bps": -0.5, "taker_bps": 2.5 },
+                "gate-io": { "maker_bps": 0.0, "taker_bps": 10.0 }
+            }"#,
+        )
+        .unwrap();
+        schedule.validate().unwrap();
+
+        assert_eq!(
+            schedule.fees(&CexExchange::Binance),
+            (
+                Rational::from_sci_string("-0.00005").unwrap(),
+                Rational::from_sci_string("0.00025").unwrap()
+            )
+        );
+        assert_eq!(schedule.fees(&CexExchange::Kraken), CexExchange::Kraken.default_fees());
+        assert_eq!(schedule.cache_id(), "binance:-0.5:2.5,gate-io:0:10");
+
+        let unknown: CexFeeSchedule =
+            serde_json::from_str(r#"{ "nope": { "maker_bps": 0.0, "taker_bps": 1.0 } }"#).unwrap();
+        assert!(unknown.validate().is_err());
+    }
+}
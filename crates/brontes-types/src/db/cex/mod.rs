@@ -6,5 +6,6 @@ pub use best_cex_per_pair::*;
 pub use cex_symbols::*;
 pub use exchanges::*;
 
+pub mod fees;
 pub mod quotes;
 pub mod trades;
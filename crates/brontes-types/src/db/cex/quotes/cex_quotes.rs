@@ -16,6 +16,7 @@ use std::{cmp::min, default::Default, fmt, mem, ops::MulAssign};
 
 use ahash::HashSetExt;
 use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use colored::*;
 use itertools::Itertools;
@@ -26,7 +27,9 @@ use malachite::{
     },
     Natural, Rational,
 };
+#[cfg(feature = "rkyv")]
 use redefined::{Redefined, RedefinedConvert};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 #[allow(unused_imports)]
 use serde::{ser::SerializeSeq, Deserialize, Serialize};
@@ -34,23 +37,27 @@ use tracing::error;
 
 use super::types::CexQuote;
 use crate::{
-    db::{
-        cex::{quotes::CexQuoteRedefined, trades::Direction, CexExchange},
-        redefined_types::malachite::RationalRedefined,
-    },
+    db::cex::{trades::Direction, CexExchange},
     implement_table_value_codecs_with_zc,
     normalized_actions::NormalizedSwap,
-    pair::{Pair, PairRedefined},
+    pair::Pair,
     utils::ToFloatNearest,
     FastHashMap, FastHashSet,
 };
+#[cfg(feature = "rkyv")]
+use crate::{
+    db::{cex::quotes::CexQuoteRedefined, redefined_types::malachite::RationalRedefined},
+    pair::PairRedefined,
+};
 
-#[derive(Debug, Clone, Row, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct CexPriceMap {
     pub quotes:         FastHashMap<CexExchange, FastHashMap<Pair, Vec<CexQuote>>>,
     pub most_liquid_ex: FastHashMap<Pair, Vec<CexExchange>>,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Debug, PartialEq, Clone, serde::Serialize, rSerialize, rDeserialize, Archive, Redefined,
 )]
@@ -67,6 +74,7 @@ pub struct CexPriceMapRedefined {
     pub most_liquid_ex: Vec<(PairRedefined, Vec<CexExchange>)>,
 }
 
+#[cfg(feature = "rkyv")]
 impl CexPriceMapRedefined {
     fn new(
         map: FastHashMap<CexExchange, FastHashMap<Pair, Vec<CexQuote>>>,
@@ -669,19 +677,24 @@ impl<'de> serde::Deserialize<'de> for CexPriceMap {
     }
 }
 
-#[derive(Debug, Clone, Default, Row, Eq, serde::Serialize, serde::Deserialize, Redefined)]
-#[redefined_attr(derive(
-    Debug,
-    PartialEq,
-    Clone,
-    Hash,
-    Serialize,
-    rSerialize,
-    rDeserialize,
-    Archive
-))]
+#[derive(Debug, Clone, Default, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        PartialEq,
+        Clone,
+        Hash,
+        Serialize,
+        rSerialize,
+        rDeserialize,
+        Archive
+    ))
+)]
 pub struct FeeAdjustedQuote {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub exchange:    CexExchange,
     pub timestamp:   u64,
     pub pairs:       Vec<Pair>,
@@ -1,24 +1,31 @@
+#[cfg(feature = "native")]
 use std::mem;
 
-use alloy_primitives::{hex, Address};
+use alloy_primitives::hex;
+#[cfg(feature = "native")]
+use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "native")]
 use itertools::Itertools;
+#[cfg(feature = "native")]
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::Deserialize;
 
+#[cfg(feature = "native")]
 use super::{CexPriceMap, CexQuote};
+use crate::{constants::USDC_ADDRESS, db::cex::CexExchange, pair::Pair, serde_utils::cex_exchange};
+#[cfg(feature = "native")]
 use crate::{
-    constants::USDC_ADDRESS,
     db::{
         block_times::{BlockTimes, CexBlockTimes},
-        cex::{BestCexPerPair, CexExchange, CexSymbols},
+        cex::{BestCexPerPair, CexSymbols},
     },
-    pair::Pair,
-    serde_utils::cex_exchange,
     FastHashMap,
 };
 
-#[derive(Debug, Default, Clone, Row, PartialEq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct RawCexQuotes {
     #[serde(with = "cex_exchange")]
     pub exchange:   CexExchange,
@@ -30,6 +37,7 @@ pub struct RawCexQuotes {
     pub bid_amount: f64,
 }
 
+#[cfg(feature = "native")]
 #[derive(Debug)]
 pub struct CexQuotesConverter {
     pub block_times:       Vec<CexBlockTimes>,
@@ -38,6 +46,7 @@ pub struct CexQuotesConverter {
     pub best_cex_per_pair: Vec<BestCexPerPair>,
 }
 
+#[cfg(feature = "native")]
 impl CexQuotesConverter {
     pub fn new(
         block_times: Vec<BlockTimes>,
@@ -250,6 +259,7 @@ impl CexQuotesConverter {
     }
 }
 
+#[cfg(feature = "native")]
 const QUOTE_TIME_BOUNDARY: [u64; 6] = [0, 2, 12, 30, 60, 300];
 
 pub fn correct_usdc_address(pair: &Pair) -> Pair {
@@ -262,6 +272,7 @@ pub fn correct_usdc_address(pair: &Pair) -> Pair {
     corrected_pair
 }
 
+#[cfg(feature = "native")]
 #[allow(unused)]
 pub fn approximate_size_of_converter(converter: &CexQuotesConverter) -> usize {
     let mut total_size = mem::size_of_val(converter);
@@ -294,14 +305,17 @@ pub fn approximate_size_of_converter(converter: &CexQuotesConverter) -> usize {
     total_size
 }
 
+#[cfg(feature = "native")]
 fn size_of_cex_symbols(symbols: &CexSymbols) -> usize {
     mem::size_of_val(symbols) + symbols.symbol_pair.capacity() + mem::size_of::<Pair>()
 }
 
+#[cfg(feature = "native")]
 fn size_of_raw_cex_quotes(quotes: &RawCexQuotes) -> usize {
     mem::size_of_val(quotes) + quotes.symbol.capacity()
 }
 
+#[cfg(feature = "native")]
 fn size_of_best_cex_per_pair(best_cex: &BestCexPerPair) -> usize {
     mem::size_of_val(best_cex) + best_cex.symbol.capacity()
 }
@@ -4,33 +4,40 @@ use std::{
     ops::MulAssign,
 };
 
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use malachite::{num::arithmetic::traits::Reciprocal, Rational};
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::Serialize;
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::malachite::RationalRedefined;
 use crate::{
-    db::{
-        cex::{quotes::download::RawCexQuotes, trades::Direction, CexExchange},
-        redefined_types::malachite::RationalRedefined,
-    },
+    db::cex::{quotes::download::RawCexQuotes, trades::Direction, CexExchange},
     utils::ToFloatNearest,
 };
 
-#[derive(Debug, Clone, Default, Row, Eq, serde::Serialize, serde::Deserialize, Redefined)]
-#[redefined_attr(derive(
-    Debug,
-    PartialEq,
-    Clone,
-    Hash,
-    Serialize,
-    rSerialize,
-    rDeserialize,
-    Archive
-))]
+#[derive(Debug, Clone, Default, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        PartialEq,
+        Clone,
+        Hash,
+        Serialize,
+        rSerialize,
+        rDeserialize,
+        Archive
+    ))
+)]
 pub struct CexQuote {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub exchange:  CexExchange,
     pub timestamp: u64,
     /// Best Bid & Ask price
@@ -1,26 +1,31 @@
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "rkyv")]
 use itertools::Itertools;
 use malachite::{
     num::arithmetic::traits::{Reciprocal, ReciprocalAssign},
     Rational,
 };
+#[cfg(feature = "rkyv")]
 use redefined::{Redefined, RedefinedConvert};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::{download::RawCexTrades, time_window_vwam::Direction};
-use crate::{
-    db::{cex::CexExchange, redefined_types::malachite::RationalRedefined},
-    implement_table_value_codecs_with_zc,
-    pair::{Pair, PairRedefined},
-    FastHashMap,
-};
+use crate::{db::cex::CexExchange, implement_table_value_codecs_with_zc, pair::Pair, FastHashMap};
+#[cfg(feature = "rkyv")]
+use crate::{db::redefined_types::malachite::RationalRedefined, pair::PairRedefined};
+
+#[cfg(feature = "rkyv")]
 type RedefinedTradeMapVec = Vec<(PairRedefined, Vec<CexTradesRedefined>)>;
 
-#[derive(Debug, Default, Clone, Row, PartialEq, Eq, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct CexTradeMap(pub FastHashMap<CexExchange, FastHashMap<Pair, Vec<CexTrades>>>);
 
 impl CexTradeMap {
+    #[cfg(feature = "rkyv")]
     pub fn from_redefined(map: Vec<(CexExchange, RedefinedTradeMapVec)>) -> Self {
         Self(
             map.into_iter()
@@ -124,6 +129,7 @@ impl<'de> Deserialize<'de> for CexTradeMap {
     }
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive, Redefined)]
 #[redefined(CexTradeMap)]
 #[redefined_attr(
@@ -134,6 +140,7 @@ pub struct CexTradeMapRedefined {
     pub map: Vec<(CexExchange, RedefinedTradeMapVec)>,
 }
 
+#[cfg(feature = "rkyv")]
 impl CexTradeMapRedefined {
     fn new(map: FastHashMap<CexExchange, FastHashMap<Pair, Vec<CexTrades>>>) -> Self {
         Self {
@@ -160,20 +167,24 @@ impl CexTradeMapRedefined {
 
 implement_table_value_codecs_with_zc!(CexTradeMapRedefined);
 
-#[derive(Debug, Clone, Serialize, Redefined, PartialEq, Eq)]
-#[redefined_attr(derive(
-    Debug,
-    PartialEq,
-    Eq,
-    Clone,
-    Hash,
-    Serialize,
-    rSerialize,
-    rDeserialize,
-    Archive
-))]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        PartialEq,
+        Eq,
+        Clone,
+        Hash,
+        Serialize,
+        rSerialize,
+        rDeserialize,
+        Archive
+    ))
+)]
 pub struct CexTrades {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub exchange:  CexExchange,
     pub timestamp: u64,
     pub price:     Rational,
@@ -1,3 +1,5 @@
+use super::{CexWindow, CexWindowMode};
+
 #[derive(Debug, Clone, Copy)]
 pub struct CexDexTradeConfig {
     pub initial_vwap_pre_block_us:         u64,
@@ -19,6 +21,12 @@ pub struct CexDexTradeConfig {
     pub pre_decay_weight_op:               f64,
     pub post_decay_weight_op:              f64,
     pub quote_offset_from_block_us:        u64,
+    /// Whether the initial windows are picked per block, see
+    /// [`CexWindow::for_block`]
+    pub cex_window_mode:                   CexWindowMode,
+    /// Trades the venues need around the block for the markets not to be thin
+    /// in the adaptive window mode
+    pub min_window_trades:                 u64,
 }
 
 impl Default for CexDexTradeConfig {
@@ -43,6 +51,8 @@ impl Default for CexDexTradeConfig {
             pre_decay_weight_op:               -0.0000003,
             post_decay_weight_op:              -0.00000012,
             quote_offset_from_block_us:        0,
+            cex_window_mode:                   CexWindowMode::Fixed,
+            min_window_trades:                 20,
         }
     }
 }
@@ -52,4 +62,15 @@ impl CexDexTradeConfig {
         self.use_block_time_weights_optimistic = true;
         self.use_block_time_weights_vwap = true;
     }
+
+    /// The config with the initial windows of the block
+    pub fn with_window(self, window: &CexWindow) -> Self {
+        Self {
+            initial_vwap_pre_block_us: window.vwap_pre_block_us,
+            initial_vwap_post_block_us: window.vwap_post_block_us,
+            initial_optimistic_pre_block_us: window.optimistic_pre_block_us,
+            initial_optimistic_post_block_us: window.optimistic_post_block_us,
+            ..self
+        }
+    }
 }
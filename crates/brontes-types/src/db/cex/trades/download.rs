@@ -1,21 +1,30 @@
+#[cfg(feature = "native")]
 use alloy_primitives::{hex, Address};
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "native")]
 use itertools::Itertools;
+#[cfg(feature = "native")]
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::Deserialize;
 use strum::Display;
 
+#[cfg(feature = "native")]
 use crate::{
     constants::USDC_ADDRESS,
     db::{
         block_times::{BlockTimes, CexBlockTimes},
-        cex::{cex_symbols::CexSymbols, trades::CexTradeMap, CexExchange},
+        cex::{cex_symbols::CexSymbols, trades::CexTradeMap},
     },
-    serde_utils::{cex_exchange, trade_type},
     FastHashMap,
 };
+use crate::{
+    db::cex::CexExchange,
+    serde_utils::{cex_exchange, trade_type},
+};
 
-#[derive(Debug, Default, Clone, Row, PartialEq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct RawCexTrades {
     #[serde(with = "cex_exchange")]
     pub exchange:   CexExchange,
@@ -28,29 +37,24 @@ pub struct RawCexTrades {
     pub amount:     f64,
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Display,
-    PartialEq,
-    Deserialize,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
-    Default,
-)]
+#[derive(Debug, Clone, Display, PartialEq, Deserialize, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
 pub enum TradeType {
     Maker,
     #[default]
     Taker,
 }
 
+/// Sorts the trades downloaded from clickhouse into the blocks they were made
+/// in
+#[cfg(feature = "native")]
 pub struct CexTradesConverter {
     pub block_times: Vec<CexBlockTimes>,
     pub symbols:     FastHashMap<String, Vec<CexSymbols>>,
     pub trades:      Vec<RawCexTrades>,
 }
 
+#[cfg(feature = "native")]
 impl CexTradesConverter {
     pub fn new(
         block_times: Vec<BlockTimes>,
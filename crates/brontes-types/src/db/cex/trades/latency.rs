@@ -0,0 +1,177 @@
+use std::str::FromStr;
+
+use super::{CexTradeMap, CexTrades};
+use crate::db::cex::CexExchange;
+
+const SECONDS_TO_US: f64 = 1_000_000.0;
+const VENUE_COUNT: usize = CexExchange::Unknown as usize + 1;
+
+/// How long after an arbitrageur decides to hedge their trade shows up on a
+/// venue. Trades are moved back in time by this offset before the markout
+/// selects the trades that were executable at the time of the block, so that a
+/// slow venue isn't treated as if it were as fast as binance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LatencyModel {
+    /// Trades are taken at their timestamp
+    #[default]
+    None,
+    /// Every trade arrives `offset_us` after it was sent
+    Fixed { offset_us: u64 },
+    /// The delay of each trade is uniformly distributed between `min_us` and
+    /// `max_us`. The delay is derived from the trade itself so that reruns
+    /// select the same trades.
+    Uniform { min_us: u64, max_us: u64 },
+}
+
+impl LatencyModel {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None | Self::Fixed { offset_us: 0 })
+    }
+
+    /// Delay of the given trade in microseconds
+    pub fn offset_us(&self, trade: &CexTrades) -> u64 {
+        match *self {
+            Self::None => 0,
+            Self::Fixed { offset_us } => offset_us,
+            Self::Uniform { min_us, max_us } => {
+                let range = max_us.saturating_sub(min_us) + 1;
+                min_us + splitmix64(trade.timestamp ^ (trade.exchange as u64)) % range
+            }
+        }
+    }
+}
+
+impl FromStr for LatencyModel {
+    type Err = eyre::Report;
+
+    /// Parses `<seconds>` into a fixed offset and `<min>-<max>` into a uniform
+    /// distribution between the two
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let to_us = |s: &str| -> eyre::Result<u64> {
+            let seconds = s.trim().parse::<f64>()?;
+            if seconds < 0.0 {
+                eyre::bail!("latency can't be negative: {s}");
+            }
+            Ok((seconds * SECONDS_TO_US) as u64)
+        };
+
+        match s.split_once('-') {
+            Some((min, max)) => {
+                let (min_us, max_us) = (to_us(min)?, to_us(max)?);
+                if min_us > max_us {
+                    eyre::bail!("min latency is larger than max latency: {s}");
+                }
+                Ok(Self::Uniform { min_us, max_us })
+            }
+            None => Ok(Self::Fixed { offset_us: to_us(s)? }),
+        }
+    }
+}
+
+/// The [`LatencyModel`] of each venue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VenueLatencies([LatencyModel; VENUE_COUNT]);
+
+impl VenueLatencies {
+    pub fn with_venue(mut self, exchange: CexExchange, model: LatencyModel) -> Self {
+        self.0[exchange as usize] = model;
+        self
+    }
+
+    pub fn get(&self, exchange: CexExchange) -> LatencyModel {
+        self.0[exchange as usize]
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.0.iter().all(LatencyModel::is_none)
+    }
+}
+
+impl FromStr for VenueLatencies {
+    type Err = eyre::Report;
+
+    /// Parses a comma separated list of `<exchange>=<latency>`, e.g.
+    /// `okex=0.05,kraken=0.1-0.3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|venue| !venue.trim().is_empty())
+            .try_fold(Self::default(), |acc, venue| {
+                let (exchange, model) = venue
+                    .split_once('=')
+                    .ok_or_else(|| eyre::eyre!("expected <exchange>=<latency>, got {venue}"))?;
+
+                let exchange = CexExchange::from(exchange.trim());
+                if exchange == CexExchange::Unknown {
+                    eyre::bail!("unknown exchange in venue latencies: {venue}");
+                }
+
+                Ok(acc.with_venue(exchange, model.parse()?))
+            })
+    }
+}
+
+impl CexTradeMap {
+    /// Moves the trades of each venue back by the venue's latency, keeping the
+    /// trades of each pair sorted by timestamp.
+    pub fn apply_latency(&mut self, latencies: &VenueLatencies) {
+        for (exchange, pairs) in self.0.iter_mut() {
+            let model = latencies.get(*exchange);
+            if model.is_none() {
+                continue
+            }
+
+            for trades in pairs.values_mut() {
+                trades.iter_mut().for_each(|trade| {
+                    trade.timestamp = trade.timestamp.saturating_sub(model.offset_us(trade))
+                });
+
+                if let LatencyModel::Uniform { .. } = model {
+                    trades.sort_by_key(|trade| trade.timestamp);
+                }
+            }
+        }
+    }
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::{num::basic::traits::Zero, Rational};
+
+    use super::*;
+
+    #[test]
+    fn parse_venue_latencies() {
+        let latencies: VenueLatencies = "okex=0.05, kraken=0.1-0.3".parse().unwrap();
+
+        assert_eq!(latencies.get(CexExchange::Okex), LatencyModel::Fixed { offset_us: 50_000 });
+        assert_eq!(
+            latencies.get(CexExchange::Kraken),
+            LatencyModel::Uniform { min_us: 100_000, max_us: 300_000 }
+        );
+        assert!(latencies.get(CexExchange::Binance).is_none());
+
+        assert!("foo=0.1".parse::<VenueLatencies>().is_err());
+        assert!("okex=0.3-0.1".parse::<VenueLatencies>().is_err());
+    }
+
+    #[test]
+    fn uniform_offset_in_range() {
+        let model = LatencyModel::Uniform { min_us: 100, max_us: 200 };
+        for timestamp in 0..1000 {
+            let trade = CexTrades {
+                exchange: CexExchange::Kraken,
+                timestamp,
+                price: Rational::ZERO,
+                amount: Rational::ZERO,
+            };
+            assert!((100..=200).contains(&model.offset_us(&trade)));
+        }
+    }
+}
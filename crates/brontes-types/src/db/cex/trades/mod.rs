@@ -1,19 +1,23 @@
 mod cex_trades;
 pub mod config;
 mod download;
+pub mod latency;
 pub mod optimistic;
 pub mod time_window_vwam;
 pub mod utils;
+pub mod window;
 pub mod window_loader;
 
 use alloy_primitives::FixedBytes;
 pub use cex_trades::*;
 pub use config::*;
 pub use download::*;
+pub use latency::*;
 use malachite::Rational;
 pub use optimistic::*;
 pub use time_window_vwam::*;
 use utils::SortedTrades;
+pub use window::*;
 
 use super::CexExchange;
 use crate::{normalized_actions::NormalizedSwap, pair::Pair, FastHashMap};
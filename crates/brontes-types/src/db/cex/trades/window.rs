@@ -0,0 +1,292 @@
+use std::str::FromStr;
+
+#[cfg(feature = "rkyv")]
+use redefined::self_convert_redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::{CexDexTradeConfig, CexTradeMap};
+use crate::db::cex::CexExchange;
+
+/// Widening of the initial windows of a block that has neither a relay nor a
+/// p2p timestamp
+const UNTIMED_BLOCK_SCALE: u64 = 2;
+/// Largest widening of the initial windows when the markets are thin
+const MAX_THIN_MARKET_SCALE: u64 = 4;
+/// The relay and p2p timestamps of the block metadata are in milliseconds
+const US_PER_MS: u64 = 1_000;
+
+/// How the time window the cex trades of a block are selected in is picked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+pub enum CexWindowMode {
+    /// The configured initial windows, for every block
+    #[default]
+    Fixed,
+    /// Windows picked per block from when the block was first seen and how
+    /// many trades the venues had around it
+    Adaptive,
+}
+
+impl FromStr for CexWindowMode {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fixed" => Ok(Self::Fixed),
+            "adaptive" => Ok(Self::Adaptive),
+            _ => eyre::bail!("unknown cex window mode, expected fixed or adaptive: {s}"),
+        }
+    }
+}
+
+/// The timestamp that tells when the block was built
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+pub enum BlockTimeSource {
+    /// Only the header timestamp, which is the start of the slot
+    #[default]
+    Header,
+    /// When the block was first received by a relay
+    Relay,
+    /// When the block was first seen on the p2p network
+    P2p,
+}
+
+/// The initial windows the cex trades of a block were selected in. The markout
+/// extends them up to the configured maximum windows until it finds enough
+/// volume. They are recorded on the cex-dex bundles so that a run can be
+/// reproduced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+pub struct CexWindow {
+    pub mode:                     CexWindowMode,
+    pub time_source:              BlockTimeSource,
+    pub vwap_pre_block_us:        u64,
+    pub vwap_post_block_us:       u64,
+    pub optimistic_pre_block_us:  u64,
+    pub optimistic_post_block_us: u64,
+    /// Trades of the venues in the vwap window before it was widened. Only
+    /// counted in the adaptive mode
+    pub trade_count:              u64,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(CexWindow);
+
+impl CexWindow {
+    /// The configured initial windows
+    pub fn fixed(config: &CexDexTradeConfig) -> Self {
+        Self {
+            mode:                     CexWindowMode::Fixed,
+            time_source:              BlockTimeSource::Header,
+            vwap_pre_block_us:        config.initial_vwap_pre_block_us,
+            vwap_post_block_us:       config.initial_vwap_post_block_us,
+            optimistic_pre_block_us:  config.initial_optimistic_pre_block_us,
+            optimistic_post_block_us: config.initial_optimistic_post_block_us,
+            trade_count:              0,
+        }
+    }
+
+    /// Picks the windows of a block. In the adaptive mode the configured
+    /// initial windows are adjusted to the block:
+    ///
+    /// - The post block windows are extended to when the block was first
+    ///   received by a relay, or else first seen on the p2p network, as the
+    ///   block could be built on the trades up until then.
+    /// - Without either timestamp it is unknown when the block was built, so
+    ///   both sides are widened by [`UNTIMED_BLOCK_SCALE`].
+    /// - If the venues had fewer than `min_window_trades` trades in the vwap
+    ///   window the markets are thin, and the windows are widened by the
+    ///   shortfall, up to [`MAX_THIN_MARKET_SCALE`]. The trades of all pairs
+    ///   are counted, so that it is quiet markets that widen the windows rather
+    ///   than an illiquid pair.
+    ///
+    /// The windows never exceed the configured maximum windows.
+    pub fn for_block(
+        config: &CexDexTradeConfig,
+        block_timestamp_us: u64,
+        relay_timestamp_ms: Option<u64>,
+        p2p_timestamp_ms: Option<u64>,
+        trades: &CexTradeMap,
+        exchanges: &[CexExchange],
+    ) -> Self {
+        let mut window = Self::fixed(config);
+        if config.cex_window_mode == CexWindowMode::Fixed {
+            return window
+        }
+        window.mode = CexWindowMode::Adaptive;
+
+        let seen = relay_timestamp_ms
+            .map(|timestamp| (BlockTimeSource::Relay, timestamp))
+            .or_else(|| p2p_timestamp_ms.map(|timestamp| (BlockTimeSource::P2p, timestamp)));
+
+        if let Some((source, timestamp_ms)) = seen {
+            let delay = (timestamp_ms * US_PER_MS).saturating_sub(block_timestamp_us);
+            window.time_source = source;
+            window.vwap_post_block_us = window.vwap_post_block_us.max(delay);
+            window.optimistic_post_block_us = window.optimistic_post_block_us.max(delay);
+        } else {
+            window.scale(UNTIMED_BLOCK_SCALE);
+        }
+
+        window.trade_count = trades.trade_count_in_window(
+            exchanges,
+            block_timestamp_us.saturating_sub(window.vwap_pre_block_us),
+            block_timestamp_us + window.vwap_post_block_us,
+        ) as u64;
+
+        if window.trade_count < config.min_window_trades {
+            let shortfall = config.min_window_trades.div_ceil(window.trade_count.max(1));
+            window.scale(shortfall.min(MAX_THIN_MARKET_SCALE));
+        }
+
+        window.vwap_pre_block_us = window.vwap_pre_block_us.min(config.max_vwap_pre_block_us);
+        window.vwap_post_block_us = window.vwap_post_block_us.min(config.max_vwap_post_block_us);
+        window.optimistic_pre_block_us = window
+            .optimistic_pre_block_us
+            .min(config.max_optimistic_pre_block_us);
+        window.optimistic_post_block_us = window
+            .optimistic_post_block_us
+            .min(config.max_optimistic_post_block_us);
+
+        window
+    }
+
+    fn scale(&mut self, factor: u64) {
+        self.vwap_pre_block_us *= factor;
+        self.vwap_post_block_us *= factor;
+        self.optimistic_pre_block_us *= factor;
+        self.optimistic_post_block_us *= factor;
+    }
+}
+
+impl CexTradeMap {
+    /// Number of trades of the exchanges between `start` and `end`, both in
+    /// microseconds
+    pub fn trade_count_in_window(&self, exchanges: &[CexExchange], start: u64, end: u64) -> usize {
+        self.0
+            .iter()
+            .filter(|(exchange, _)| exchanges.contains(exchange))
+            .flat_map(|(_, pairs)| pairs.values())
+            .map(|trades| {
+                trades
+                    .partition_point(|trade| trade.timestamp <= end)
+                    .saturating_sub(trades.partition_point(|trade| trade.timestamp < start))
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::{num::basic::traits::Zero, Rational};
+
+    use super::*;
+    use crate::{db::cex::trades::CexTrades, pair::Pair, FastHashMap};
+
+    const BLOCK_US: u64 = 1_700_000_000_000_000;
+    const BLOCK_MS: u64 = BLOCK_US / US_PER_MS;
+
+    fn trade_map(timestamps: impl IntoIterator<Item = u64>) -> CexTradeMap {
+        let trades = timestamps
+            .into_iter()
+            .map(|timestamp| CexTrades {
+                exchange: CexExchange::Binance,
+                timestamp,
+                price: Rational::ZERO,
+                amount: Rational::ZERO,
+            })
+            .collect();
+        let pairs = FastHashMap::from_iter([(Pair::default(), trades)]);
+
+        CexTradeMap(FastHashMap::from_iter([(CexExchange::Binance, pairs)]))
+    }
+
+    fn adaptive_config() -> CexDexTradeConfig {
+        CexDexTradeConfig { cex_window_mode: CexWindowMode::Adaptive, ..Default::default() }
+    }
+
+    #[test]
+    fn parse_window_mode() {
+        assert_eq!("Adaptive".parse::<CexWindowMode>().unwrap(), CexWindowMode::Adaptive);
+        assert_eq!("fixed".parse::<CexWindowMode>().unwrap(), CexWindowMode::Fixed);
+        assert!("sliding".parse::<CexWindowMode>().is_err());
+    }
+
+    #[test]
+    fn fixed_window_ignores_block() {
+        let config = CexDexTradeConfig::default();
+        let window = CexWindow::for_block(
+            &config,
+            BLOCK_US,
+            Some(BLOCK_MS + 1_000),
+            None,
+            &CexTradeMap::default(),
+            &[CexExchange::Binance],
+        );
+
+        assert_eq!(window, CexWindow::fixed(&config));
+    }
+
+    #[test]
+    fn adaptive_window_covers_relay_delay() {
+        let config = adaptive_config();
+        let busy = trade_map((0..100).map(|i| BLOCK_US - 20_000 + i * 400));
+        let exchanges = [CexExchange::Binance];
+
+        let window = CexWindow::for_block(
+            &config,
+            BLOCK_US,
+            Some(BLOCK_MS + 400),
+            Some(BLOCK_MS + 900),
+            &busy,
+            &exchanges,
+        );
+        assert_eq!(window.time_source, BlockTimeSource::Relay);
+        assert_eq!(window.vwap_pre_block_us, config.initial_vwap_pre_block_us);
+        assert_eq!(window.vwap_post_block_us, 400_000);
+        assert_eq!(window.optimistic_post_block_us, 400_000);
+        assert_eq!(window.trade_count, 100);
+
+        let untimed = CexWindow::for_block(&config, BLOCK_US, None, None, &busy, &exchanges);
+        assert_eq!(untimed.time_source, BlockTimeSource::Header);
+        assert_eq!(untimed.vwap_pre_block_us, config.initial_vwap_pre_block_us * 2);
+        assert_eq!(untimed.vwap_post_block_us, config.initial_vwap_post_block_us * 2);
+    }
+
+    #[test]
+    fn adaptive_window_widens_in_thin_markets() {
+        let config = CexDexTradeConfig { min_window_trades: 10, ..adaptive_config() };
+        let thin = trade_map([BLOCK_US - 10_000, BLOCK_US + 10_000, BLOCK_US + 2_000_000]);
+
+        let window = CexWindow::for_block(
+            &config,
+            BLOCK_US,
+            None,
+            Some(BLOCK_MS),
+            &thin,
+            &[CexExchange::Binance],
+        );
+        assert_eq!(window.trade_count, 2);
+        assert_eq!(window.vwap_pre_block_us, config.initial_vwap_pre_block_us * 4);
+        // capped at the maximum window
+        assert_eq!(window.optimistic_pre_block_us, config.max_optimistic_pre_block_us);
+
+        // trades of venues that aren't used don't count
+        let window = CexWindow::for_block(
+            &config,
+            BLOCK_US,
+            None,
+            Some(BLOCK_MS),
+            &thin,
+            &[CexExchange::Okex],
+        );
+        assert_eq!(window.trade_count, 0);
+    }
+}
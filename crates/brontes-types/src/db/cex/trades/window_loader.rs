@@ -1,7 +1,10 @@
 use std::collections::VecDeque;
 
 use crate::{
-    db::cex::{trades::CexTradeMap, CexExchange},
+    db::cex::{
+        trades::{CexTradeMap, VenueLatencies},
+        CexExchange,
+    },
     pair::Pair,
     FastHashMap,
 };
@@ -15,6 +18,8 @@ pub struct CexWindow {
     /// this is used so that we don't double load data
     last_end_block_loaded: u64,
     window_size_seconds:   usize,
+    /// applied to the trades handed out for each block
+    venue_latencies:       VenueLatencies,
 }
 
 impl CexWindow {
@@ -24,9 +29,17 @@ impl CexWindow {
             global_map: CexTradeMap::default(),
             last_end_block_loaded: 0,
             window_size_seconds,
+            venue_latencies: VenueLatencies::default(),
         }
     }
 
+    /// Shifts the trades of each venue by its latency in the maps handed out
+    /// for the blocks, the window itself keeps the trades as they were loaded
+    pub fn with_venue_latencies(mut self, venue_latencies: VenueLatencies) -> Self {
+        self.venue_latencies = venue_latencies;
+        self
+    }
+
     /// used to get the initialized range going. Assumes maps are ordered
     pub fn init(&mut self, end_block: u64, maps: Vec<CexTradeMap>) {
         self.last_end_block_loaded = end_block;
@@ -66,6 +79,11 @@ impl CexWindow {
         // we gotta clone or else we get a race condition where when
         // we remove old data. processing might still be occurring thus shifting
         // the time window from whats expected.
-        self.global_map.clone()
+        let mut trades = self.global_map.clone();
+        if !self.venue_latencies.is_none() {
+            trades.apply_latency(&self.venue_latencies);
+        }
+
+        trades
     }
 }
@@ -0,0 +1,70 @@
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// A failure the classifier recovered from while building the tree of a block
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct ClassificationFailure {
+    pub tx_idx:     u64,
+    /// Label of the error, see `ClassifierError::kind`
+    pub kind:       String,
+    pub error:      String,
+    /// Whether the tx was left out of the tree. Otherwise the tx was
+    /// classified and only the failed step, e.g. storing a discovered pool,
+    /// was lost.
+    pub skipped_tx: bool,
+}
+
+/// The classification failures of a block
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct BlockClassificationFailures {
+    pub failures: Vec<ClassificationFailure>,
+}
+
+implement_table_value_codecs_with_zc!(BlockClassificationFailuresRedefined);
+
+impl BlockClassificationFailures {
+    /// Adds the failure unless it was already recorded, which is the case
+    /// when the block is classified again
+    pub fn insert(&mut self, failure: ClassificationFailure) {
+        if !self.failures.contains(&failure) {
+            self.failures.push(failure);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reclassified_failures_are_recorded_once() {
+        let failure = |tx_idx| ClassificationFailure {
+            tx_idx,
+            kind: "pool_insertion".to_string(),
+            error: "writer closed".to_string(),
+            skipped_tx: false,
+        };
+
+        let mut failures = BlockClassificationFailures::default();
+        failures.insert(failure(1));
+        failures.insert(failure(4));
+        failures.insert(failure(1));
+
+        assert_eq!(failures.failures, vec![failure(1), failure(4)]);
+    }
+}
@@ -1,15 +1,17 @@
 use std::str::FromStr;
 
 use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, B256, U256};
+#[cfg(feature = "native")]
 use clickhouse::{fixed_string::FixedString, Row};
-use reth_primitives::{Address, B256, U256};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
 use crate::serde_utils::vec_u256;
 
 #[serde_as]
-#[derive(Debug, Row, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct ClickhouseAbis {
     address: String,
     abi:     String,
@@ -23,7 +25,8 @@ impl From<ClickhouseAbis> for (Address, JsonAbi) {
     }
 }
 
-#[derive(Row, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct ClickhousePoolReserves {
     pub address:           FixedString,
     pub block_number:      u64,
@@ -39,14 +42,16 @@ pub struct ClickhousePoolReserves {
 }
 
 #[serde_as]
-#[derive(Debug, Row, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct ClickhouseTokenPrices {
     pub key: (String, String),
     pub val: Vec<ClickhouseExchangePrice>,
 }
 
 #[serde_as]
-#[derive(Debug, Row, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct ClickhouseExchangePrice {
     pub exchange: String,
     /// (base_address, quote_address)
@@ -55,7 +60,8 @@ pub struct ClickhouseExchangePrice {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct ClickhouseTimesFlow {
     pub block_number:    u64,
     #[serde_as(as = "DisplayFromStr")]
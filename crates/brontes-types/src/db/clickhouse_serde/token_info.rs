@@ -11,6 +11,6 @@ pub mod token_info_des {
     {
         let (decimals, symbol): (u8, String) = Deserialize::deserialize(deserializer)?;
 
-        Ok(TokenInfo { decimals, symbol })
+        Ok(TokenInfo::new(decimals, symbol))
     }
 }
@@ -1,5 +1,5 @@
+use alloy_rpc_types_trace::parity::{Action, TraceOutput};
 use itertools::Itertools;
-use reth_rpc_types::trace::parity::{Action, TraceOutput};
 
 use crate::structured_trace::TxTrace;
 
@@ -296,11 +296,11 @@ pub mod tx_traces_inner {
     use std::str::FromStr;
 
     use alloy_primitives::{Address, Bytes, Log, LogData, TxHash, U256, U64};
-    use itertools::Itertools;
-    use reth_rpc_types::trace::parity::{
+    use alloy_rpc_types_trace::parity::{
         Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput, RewardAction,
         RewardType, SelfdestructAction, TraceOutput, TransactionTrace,
     };
+    use itertools::Itertools;
     use serde::de::{Deserialize, Deserializer};
 
     use crate::{
@@ -333,7 +333,7 @@ pub mod tx_traces_inner {
         let mut tx_trace = TxTrace::default();
 
         let default_trace = TransactionTraceWithLogs {
-            trace:        TransactionTrace {
+            trace: TransactionTrace {
                 action:        Action::Selfdestruct(SelfdestructAction {
                     address:        Default::default(),
                     balance:        Default::default(),
@@ -344,9 +344,11 @@ pub mod tx_traces_inner {
                 subtraces:     0,
                 trace_address: Vec::new(),
             },
-            logs:         Vec::new(),
-            msg_sender:   Default::default(),
-            trace_idx:    Default::default(),
+            logs: Vec::new(),
+            #[cfg(feature = "provenance")]
+            tx_log_indices: Vec::new(),
+            msg_sender: Default::default(),
+            trace_idx: Default::default(),
             decoded_data: None,
         };
 
@@ -0,0 +1,156 @@
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// The mutable parameters of a curve stableswap pool, kept up to date from the
+/// pool's parameter change events. Values are stored as emitted by the pool,
+/// so `A` includes the `A_PRECISION` multiplier for pools that use one.
+///
+/// Parameters the pool hasn't changed since it was tracked are unknown, in
+/// which case the deployment values apply.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct CurvePoolParams {
+    /// A at the start of the current ramp
+    pub initial_a:         u64,
+    /// A at the end of the current ramp
+    pub future_a:          u64,
+    /// Unix timestamp in seconds of the start of the current ramp
+    pub initial_a_time:    u64,
+    /// Unix timestamp in seconds of the end of the current ramp
+    pub future_a_time:     u64,
+    /// Swap fee with 10 decimals
+    #[serde(default)]
+    pub fee:               Option<u64>,
+    /// Share of the swap fee that goes to the admin with 10 decimals
+    #[serde(default)]
+    pub admin_fee:         Option<u64>,
+    /// Block of the last parameter change
+    pub last_update_block: u64,
+}
+
+implement_table_value_codecs_with_zc!(CurvePoolParamsRedefined);
+
+impl CurvePoolParams {
+    /// A at the given timestamp, interpolating linearly while A is ramping.
+    /// Returns `None` if A was never changed since the pool was tracked.
+    pub fn amplification(&self, timestamp: u64) -> Option<u64> {
+        if self.future_a == 0 {
+            return None
+        }
+        if timestamp >= self.future_a_time || self.future_a_time <= self.initial_a_time {
+            return Some(self.future_a)
+        }
+
+        let elapsed = timestamp.saturating_sub(self.initial_a_time) as u128;
+        let duration = (self.future_a_time - self.initial_a_time) as u128;
+        let (initial, future) = (self.initial_a as u128, self.future_a as u128);
+
+        let a = if future > initial {
+            initial + (future - initial) * elapsed / duration
+        } else {
+            initial - (initial - future) * elapsed / duration
+        };
+
+        Some(a as u64)
+    }
+
+    /// The swap fee in hundredths of a basis point, the unit of the fee tier of
+    /// swaps. `None` if the fee was never changed since the pool was tracked
+    pub fn fee_tier(&self) -> Option<u32> {
+        self.fee.map(|fee| (fee / 10_000) as u32)
+    }
+
+    pub fn is_ramping(&self, timestamp: u64) -> bool {
+        self.initial_a != self.future_a && timestamp < self.future_a_time
+    }
+
+    pub fn apply(&mut self, block: u64, update: CurveParamsUpdate) {
+        match update {
+            CurveParamsUpdate::RampA { initial_a, future_a, initial_time, future_time } => {
+                self.initial_a = initial_a;
+                self.future_a = future_a;
+                self.initial_a_time = initial_time;
+                self.future_a_time = future_time;
+            }
+            CurveParamsUpdate::StopRampA { a, time } => {
+                self.initial_a = a;
+                self.future_a = a;
+                self.initial_a_time = time;
+                self.future_a_time = time;
+            }
+            CurveParamsUpdate::NewFee { fee, admin_fee } => {
+                self.fee = Some(fee);
+                self.admin_fee = Some(admin_fee);
+            }
+        }
+        self.last_update_block = block;
+    }
+}
+
+/// A parameter change emitted by a curve stableswap pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveParamsUpdate {
+    /// `RampA(old_A, new_A, initial_time, future_time)`
+    RampA { initial_a: u64, future_a: u64, initial_time: u64, future_time: u64 },
+    /// `StopRampA(A, t)`
+    StopRampA { a: u64, time: u64 },
+    /// `NewFee(fee, admin_fee)`
+    NewFee { fee: u64, admin_fee: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amplification_ramps_linearly() {
+        let mut params = CurvePoolParams::default();
+        assert_eq!(params.amplification(0), None);
+        assert_eq!(params.fee_tier(), None);
+
+        params.apply(
+            1,
+            CurveParamsUpdate::RampA {
+                initial_a:    100,
+                future_a:     200,
+                initial_time: 1_000,
+                future_time:  2_000,
+            },
+        );
+        assert_eq!(params.amplification(500), Some(100));
+        assert_eq!(params.amplification(1_500), Some(150));
+        assert_eq!(params.amplification(2_500), Some(200));
+        assert!(params.is_ramping(1_500));
+
+        params.apply(
+            2,
+            CurveParamsUpdate::RampA {
+                initial_a:    200,
+                future_a:     50,
+                initial_time: 3_000,
+                future_time:  4_000,
+            },
+        );
+        assert_eq!(params.amplification(3_500), Some(125));
+
+        params.apply(3, CurveParamsUpdate::StopRampA { a: 125, time: 3_500 });
+        assert_eq!(params.amplification(3_600), Some(125));
+        assert!(!params.is_ramping(3_600));
+
+        params.apply(4, CurveParamsUpdate::NewFee { fee: 4_000_000, admin_fee: 5_000_000_000 });
+        assert_eq!(params.fee, Some(4_000_000));
+        // 0.04%
+        assert_eq!(params.fee_tier(), Some(400));
+        assert_eq!(params.amplification(3_600), Some(125));
+        assert_eq!(params.last_update_block, 4);
+    }
+}
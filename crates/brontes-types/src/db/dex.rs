@@ -5,6 +5,7 @@ use std::{
 };
 
 use alloy_primitives::{wrap_fixed_bytes, Address, FixedBytes};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use itertools::Itertools;
 use malachite::{
@@ -14,36 +15,42 @@ use malachite::{
     },
     Natural, Rational,
 };
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "libmdbx")]
 use reth_db::DatabaseError;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::{
-    constants::{ETH_ADDRESS, WETH_ADDRESS},
-    db::{clickhouse_serde::dex::dex_quote, redefined_types::malachite::RationalRedefined},
-    implement_table_value_codecs_with_zc,
-    pair::{Pair, PairRedefined},
-    FastHashMap,
+    chain::chain_spec, constants::ETH_ADDRESS, db::clickhouse_serde::dex::dex_quote,
+    implement_table_value_codecs_with_zc, pair::Pair, FastHashMap,
 };
+#[cfg(feature = "rkyv")]
+use crate::{db::redefined_types::malachite::RationalRedefined, pair::PairRedefined};
 
 /// Represents the DEX prices of a token pair before (`pre_state`) and after a
 /// transaction (`post_state`)
 ///
 /// The `goes_through` field, indicates the token pair of the pool
 /// that generated the action that caused the pricing event.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Redefined)]
-#[redefined_attr(derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    Serialize,
-    rDeserialize,
-    rSerialize,
-    Archive
-))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        Serialize,
+        rDeserialize,
+        rSerialize,
+        Archive
+    ))
+)]
 pub struct DexPrices {
     pub pre_state:    Rational,
     pub post_state:   Rational,
@@ -100,7 +107,8 @@ impl DexPrices {
 ///
 /// For a given transaction, the value is `None` if it doesn't
 /// contain any token transfers
-#[derive(Debug, Clone, PartialEq, Row, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct DexQuotes(pub Vec<Option<FastHashMap<Pair, DexPrices>>>);
 
 impl DexQuotes {
@@ -109,10 +117,10 @@ impl DexQuotes {
     #[cfg(feature = "test_pricing")]
     pub fn price_at(&self, mut pair: Pair, mut tx: usize) -> Option<DexPrices> {
         if pair.0 == ETH_ADDRESS {
-            pair.0 = WETH_ADDRESS;
+            pair.0 = chain_spec().wrapped_native;
         }
         if pair.1 == ETH_ADDRESS {
-            pair.1 = WETH_ADDRESS;
+            pair.1 = chain_spec().wrapped_native;
         }
         let s_idx = tx;
 
@@ -146,10 +154,10 @@ impl DexQuotes {
     #[cfg(not(feature = "test_pricing"))]
     pub fn price_at(&self, mut pair: Pair, tx: usize) -> Option<DexPrices> {
         if pair.0 == ETH_ADDRESS {
-            pair.0 = WETH_ADDRESS;
+            pair.0 = chain_spec().wrapped_native;
         }
         if pair.1 == ETH_ADDRESS {
-            pair.1 = WETH_ADDRESS;
+            pair.1 = chain_spec().wrapped_native;
         }
         let s_idx = tx;
 
@@ -173,10 +181,10 @@ impl DexQuotes {
 
     pub fn price_at_or_before(&self, mut pair: Pair, mut tx: usize) -> Option<DexPrices> {
         if pair.0 == ETH_ADDRESS {
-            pair.0 = WETH_ADDRESS;
+            pair.0 = chain_spec().wrapped_native;
         }
         if pair.1 == ETH_ADDRESS {
-            pair.1 = WETH_ADDRESS;
+            pair.1 = chain_spec().wrapped_native;
         }
         let s_idx = tx;
 
@@ -207,10 +215,14 @@ impl DexQuotes {
 
     pub fn price_for_block(&self, mut pair: Pair, price_at: BlockPrice) -> Option<Rational> {
         if pair.0 == ETH_ADDRESS {
-            pair.0 = WETH_ADDRESS;
+            pair.0 = chain_spec().wrapped_native;
         }
         if pair.1 == ETH_ADDRESS {
-            pair.1 = WETH_ADDRESS;
+            pair.1 = chain_spec().wrapped_native;
+        }
+
+        if pair.0 == pair.1 {
+            return Some(Rational::ONE)
         }
 
         match price_at {
@@ -262,10 +274,10 @@ impl DexQuotes {
 
     fn get_price(&self, mut pair: Pair, tx: usize) -> Option<&DexPrices> {
         if pair.0 == ETH_ADDRESS {
-            pair.0 = WETH_ADDRESS;
+            pair.0 = chain_spec().wrapped_native;
         }
         if pair.1 == ETH_ADDRESS {
-            pair.1 = WETH_ADDRESS;
+            pair.1 = chain_spec().wrapped_native;
         }
         self.0.get(tx)?.as_ref()?.get(&pair)
     }
@@ -293,17 +305,21 @@ impl From<DexQuoteWithIndex> for DexQuote {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, Redefined)]
-#[redefined_attr(derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    Serialize,
-    rDeserialize,
-    rSerialize,
-    Archive
-))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        Serialize,
+        rDeserialize,
+        rSerialize,
+        Archive
+    ))
+)]
 pub struct DexQuoteWithIndex {
     pub tx_idx: u16,
     pub quote:  Vec<(Pair, DexPrices)>,
@@ -366,6 +382,7 @@ wrap_fixed_bytes!(
     pub struct DexKey<10>;
 );
 
+#[cfg(feature = "libmdbx")]
 impl reth_db::table::Encode for DexKey {
     type Encoded = [u8; 10];
 
@@ -374,6 +391,7 @@ impl reth_db::table::Encode for DexKey {
     }
 }
 
+#[cfg(feature = "libmdbx")]
 impl reth_db::table::Decode for DexKey {
     fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
         Ok(DexKey::from_slice(value.as_ref()))
@@ -403,7 +421,8 @@ pub fn make_filter_key_range(block_number: u64) -> (DexKey, DexKey) {
     (start_key.into(), end_key.into())
 }
 
-#[derive(Debug, Clone, PartialEq, Row, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct DexQuotesWithBlockNumber {
     pub block_number: u64,
     pub tx_idx:       u64,
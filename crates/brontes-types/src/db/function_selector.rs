@@ -0,0 +1,132 @@
+use alloy_primitives::{keccak256, Selector};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+#[cfg(feature = "rkyv")]
+use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// The signature of a function selector, used to classify calls to contracts
+/// that no protocol classifier knows about.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct FunctionSelector {
+    /// Text signature of the function, e.g. `transfer(address,uint256)`
+    pub signature: String,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    #[serde(default)]
+    pub kind:      SelectorKind,
+}
+
+implement_table_value_codecs_with_zc!(FunctionSelectorRedefined);
+
+impl FunctionSelector {
+    /// Builds the entry of a signature, inferring its kind from the function
+    /// name
+    pub fn new(signature: impl Into<String>) -> Self {
+        let signature = signature.into();
+        let kind = SelectorKind::from_signature(&signature);
+        Self { signature, kind }
+    }
+
+    /// Name of the function without its arguments
+    pub fn name(&self) -> &str {
+        function_name(&self.signature)
+    }
+
+    /// The 4-byte selector of the signature
+    pub fn selector(&self) -> Selector {
+        Selector::from_slice(&keccak256(self.signature.as_bytes())[..4])
+    }
+}
+
+/// What a call does, as far as it can be told from its signature alone
+#[derive(
+    Debug, Default, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, strum::Display,
+)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SelectorKind {
+    Swap,
+    Transfer,
+    Approval,
+    #[default]
+    Other,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(SelectorKind);
+
+impl SelectorKind {
+    pub fn from_signature(signature: &str) -> Self {
+        let name = function_name(signature).to_ascii_lowercase();
+
+        if name.starts_with("approve")
+            || name.starts_with("permit")
+            || name == "increaseallowance"
+            || name == "setapprovalforall"
+        {
+            Self::Approval
+        } else if name.contains("swap") || name.starts_with("exchange") {
+            Self::Swap
+        } else if name.starts_with("transfer") || name.starts_with("safetransfer") {
+            Self::Transfer
+        } else {
+            Self::Other
+        }
+    }
+}
+
+fn function_name(signature: &str) -> &str {
+    signature
+        .split_once('(')
+        .map_or(signature, |(name, _)| name)
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_from_signature() {
+        let kinds = [
+            ("transfer(address,uint256)", SelectorKind::Transfer),
+            ("safeTransferFrom(address,address,uint256)", SelectorKind::Transfer),
+            ("approve(address,uint256)", SelectorKind::Approval),
+            ("setApprovalForAll(address,bool)", SelectorKind::Approval),
+            (
+                "swapExactTokensForETH(uint256,uint256,address[],address,uint256)",
+                SelectorKind::Swap,
+            ),
+            ("exchange_underlying(int128,int128,uint256,uint256)", SelectorKind::Swap),
+            ("deposit(uint256)", SelectorKind::Other),
+        ];
+
+        for (signature, kind) in kinds {
+            assert_eq!(FunctionSelector::new(signature).kind, kind, "{signature}");
+        }
+        assert_eq!(FunctionSelector::new("deposit(uint256)").name(), "deposit");
+    }
+
+    #[test]
+    fn selector_of_signature() {
+        assert_eq!(
+            FunctionSelector::new("transfer(address,uint256)").selector(),
+            Selector::from([0xa9, 0x05, 0x9c, 0xbb])
+        );
+        assert_eq!(
+            FunctionSelector::new("approve(address,uint256)").selector(),
+            Selector::from([0x09, 0x5e, 0xa7, 0xb3])
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mev::{Bundle, MevType},
+    normalized_actions::NormalizedAction,
+    serde_utils::{address, option_address, txhash},
+    BlockTree,
+};
+
+/// How a bundle bid for its position in the block. Used to research the
+/// bidding strategies of searchers.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct GasBid {
+    pub block_number:            u64,
+    #[serde(with = "txhash")]
+    pub tx_hash:                 TxHash,
+    pub tx_index:                u64,
+    #[serde(with = "address")]
+    pub eoa:                     Address,
+    #[serde(with = "option_address")]
+    pub mev_contract:            Option<Address>,
+    pub mev_type:                MevType,
+    /// Priority fee per gas of the bundle's transaction
+    pub priority_fee:            u128,
+    /// Percentage of the transactions in the block that paid a lower
+    /// priority fee per gas
+    pub priority_fee_percentile: f64,
+    /// Sum of the coinbase transfers of all transactions in the bundle
+    pub coinbase_transfer:       u128,
+    /// Sum of the priority fees paid by all transactions in the bundle
+    pub priority_fee_paid:       u128,
+    /// Share of the bundle's payment to the builder that was made through a
+    /// coinbase transfer rather than the priority fee
+    pub coinbase_transfer_ratio: f64,
+    /// Position of the transaction in the block, 0 is the top and 1 the bottom
+    pub block_position:          f64,
+    pub bribe_usd:               f64,
+    pub profit_usd:              f64,
+}
+
+impl GasBid {
+    /// Bids of all bundles in the block. Bundles whose transaction isn't part
+    /// of the tree are skipped.
+    pub fn from_bundles<V: NormalizedAction>(bundles: &[Bundle], tree: &BlockTree<V>) -> Vec<Self> {
+        bundles
+            .iter()
+            .filter_map(|bundle| Self::new(bundle, tree))
+            .collect()
+    }
+
+    pub fn new<V: NormalizedAction>(bundle: &Bundle, tree: &BlockTree<V>) -> Option<Self> {
+        let header = &bundle.header;
+        let base_fee = tree.header.base_fee_per_gas.unwrap_or_default() as u128;
+        let priority_fee = tree.get_gas_details(header.tx_hash)?.priority_fee(base_fee);
+
+        let (coinbase_transfer, priority_fee_paid) = bundle
+            .data
+            .mev_transaction_hashes()
+            .into_iter()
+            .filter_map(|hash| tree.get_gas_details(hash))
+            .fold((0u128, 0u128), |(coinbase, priority), gas| {
+                (coinbase + gas.coinbase_transfer(), priority + gas.priority_fee_paid(base_fee))
+            });
+
+        let builder_payment = coinbase_transfer + priority_fee_paid;
+        let coinbase_transfer_ratio = if builder_payment == 0 {
+            0.0
+        } else {
+            coinbase_transfer as f64 / builder_payment as f64
+        };
+
+        let tx_count = tree.tx_roots.len();
+        let block_position =
+            if tx_count > 1 { header.tx_index as f64 / (tx_count - 1) as f64 } else { 0.0 };
+
+        Some(Self {
+            block_number: header.block_number,
+            tx_hash: header.tx_hash,
+            tx_index: header.tx_index,
+            eoa: header.eoa,
+            mev_contract: header.mev_contract,
+            mev_type: header.mev_type,
+            priority_fee,
+            priority_fee_percentile: priority_fee_percentile(tree, base_fee, priority_fee),
+            coinbase_transfer,
+            priority_fee_paid,
+            coinbase_transfer_ratio,
+            block_position,
+            bribe_usd: header.bribe_usd,
+            profit_usd: header.profit_usd,
+        })
+    }
+}
+
+/// How a searcher paid for their positions in a block, aggregated over all of
+/// their bundles in the block. The time series of these rows is what the
+/// bidding behaviour stored in the searcher info is built from.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SearcherBlockBids {
+    pub block_number:            u64,
+    #[serde(with = "address")]
+    pub eoa:                     Address,
+    pub bundle_count:            u64,
+    pub priority_fee_paid:       u128,
+    pub coinbase_transfer:       u128,
+    /// Share of the payment to the builder that was made through coinbase
+    /// transfers rather than priority fees
+    pub coinbase_transfer_ratio: f64,
+    /// Share of the profit before bribes that was paid to the builder
+    pub bid_to_profit_ratio:     f64,
+    /// Position of the searcher's highest transaction, 0 is the top of the
+    /// block and 1 the bottom
+    pub top_block_position:      f64,
+    pub avg_block_position:      f64,
+    pub bribe_usd:               f64,
+    pub profit_usd:              f64,
+}
+
+impl SearcherBlockBids {
+    /// One row per searcher EOA that placed a bid in the block
+    pub fn from_gas_bids(bids: &[GasBid]) -> Vec<Self> {
+        bids.iter()
+            .into_group_map_by(|bid| bid.eoa)
+            .into_iter()
+            .sorted_by_key(|(eoa, _)| *eoa)
+            .map(|(eoa, bids)| Self::new(eoa, &bids))
+            .collect()
+    }
+
+    fn new(eoa: Address, bids: &[&GasBid]) -> Self {
+        let priority_fee_paid = bids.iter().map(|bid| bid.priority_fee_paid).sum::<u128>();
+        let coinbase_transfer = bids.iter().map(|bid| bid.coinbase_transfer).sum::<u128>();
+        let bribe_usd = bids.iter().map(|bid| bid.bribe_usd).sum::<f64>();
+        let profit_usd = bids.iter().map(|bid| bid.profit_usd).sum::<f64>();
+
+        let builder_payment = coinbase_transfer + priority_fee_paid;
+        let coinbase_transfer_ratio = if builder_payment == 0 {
+            0.0
+        } else {
+            coinbase_transfer as f64 / builder_payment as f64
+        };
+
+        // the profit of a bundle is net of its bribe
+        let gross_profit = profit_usd + bribe_usd;
+        let bid_to_profit_ratio = if gross_profit > 0.0 { bribe_usd / gross_profit } else { 0.0 };
+
+        Self {
+            block_number: bids[0].block_number,
+            eoa,
+            bundle_count: bids.len() as u64,
+            priority_fee_paid,
+            coinbase_transfer,
+            coinbase_transfer_ratio,
+            bid_to_profit_ratio,
+            top_block_position: bids
+                .iter()
+                .map(|bid| bid.block_position)
+                .fold(f64::MAX, f64::min),
+            avg_block_position: bids.iter().map(|bid| bid.block_position).sum::<f64>()
+                / bids.len() as f64,
+            bribe_usd,
+            profit_usd,
+        }
+    }
+}
+
+pub(crate) fn priority_fee_percentile<V: NormalizedAction>(
+    tree: &BlockTree<V>,
+    base_fee: u128,
+    priority_fee: u128,
+) -> f64 {
+    let lower = tree
+        .tx_roots
+        .iter()
+        .filter(|root| root.gas_details.priority_fee(base_fee) < priority_fee)
+        .count();
+
+    lower as f64 / tree.tx_roots.len() as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_searcher_block_bids() {
+        let [a, b] = [1, 2].map(Address::with_last_byte);
+        let bid = |eoa, priority_fee_paid, coinbase_transfer, block_position| GasBid {
+            block_number: 10,
+            eoa,
+            priority_fee_paid,
+            coinbase_transfer,
+            block_position,
+            bribe_usd: 1.0,
+            profit_usd: 3.0,
+            ..Default::default()
+        };
+        let bids = vec![bid(b, 100, 0, 0.5), bid(a, 100, 300, 0.2), bid(a, 0, 400, 0.0)];
+
+        let [a_bids, b_bids] = SearcherBlockBids::from_gas_bids(&bids).try_into().unwrap();
+        assert_eq!((a_bids.eoa, a_bids.bundle_count), (a, 2));
+        assert_eq!(a_bids.coinbase_transfer_ratio, 0.875);
+        assert_eq!(a_bids.bid_to_profit_ratio, 0.25);
+        assert_eq!(a_bids.top_block_position, 0.0);
+        assert_eq!(a_bids.avg_block_position, 0.1);
+
+        assert_eq!(b_bids.coinbase_transfer_ratio, 0.0);
+        assert_eq!(b_bids.top_block_position, 0.5);
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "rkyv")]
 use redefined::self_convert_redefined;
 use serde::{Deserialize, Serialize};
 
@@ -19,20 +20,8 @@ pub const DATA_NOT_PRESENT_NOT_AVAILABLE: u16 = 0b01;
 pub const DATA_NOT_PRESENT_BUT_AVAILABLE: u16 = 0b10;
 pub const DATA_PRESENT: u16 = 0b11;
 
-#[derive(
-    Debug,
-    Default,
-    PartialEq,
-    Clone,
-    Copy,
-    Eq,
-    Hash,
-    Serialize,
-    Deserialize,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
-)]
+#[derive(Debug, Default, PartialEq, Clone, Copy, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
 #[repr(transparent)]
 /// InitializedState allows for us to mark up to 8 fields in
 /// the database as initialized
@@ -85,5 +74,6 @@ impl InitializedStateMeta {
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(InitializedStateMeta);
 implement_table_value_codecs_with_zc!(InitializedStateMeta);
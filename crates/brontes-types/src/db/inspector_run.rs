@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use alloy_primitives::B256;
+#[cfg(feature = "native")]
+use clickhouse::Row;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, MevType},
+    FastHashSet,
+};
+
+/// Timing and result counts of an inspector on a single block
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct InspectorRun {
+    pub block_number: u64,
+    /// Id of the inspector, see `Inspector::get_id`
+    pub inspector:    String,
+    /// Wall time the inspector took on the block in microseconds
+    pub elapsed_us:   u64,
+    /// Bundles the inspector returned, before they were composed and
+    /// deduplicated
+    pub candidates:   u64,
+    /// Candidates that made it into the results of the block
+    pub bundles:      u64,
+}
+
+/// The runs of every inspector on a block
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct BlockInspectorRuns {
+    pub runs: Vec<InspectorRun>,
+}
+
+implement_table_value_codecs_with_zc!(BlockInspectorRunsRedefined);
+
+impl InspectorRun {
+    /// `candidates` are the bundles the inspector returned and `results` the
+    /// bundles of the block once composed and deduplicated. A candidate is
+    /// counted as emitted if a bundle of the same type and tx is in the
+    /// results, candidates composed into another mev type aren't.
+    pub fn new(
+        block_number: u64,
+        inspector: &str,
+        elapsed: Duration,
+        candidates: &[(B256, MevType)],
+        results: &FastHashSet<(B256, MevType)>,
+    ) -> Self {
+        Self {
+            block_number,
+            inspector: inspector.to_string(),
+            elapsed_us: elapsed.as_micros() as u64,
+            candidates: candidates.len() as u64,
+            bundles: candidates
+                .iter()
+                .filter(|candidate| results.contains(candidate))
+                .count() as u64,
+        }
+    }
+
+    /// Identifies a bundle to match the candidates of an inspector against
+    /// the results of the block
+    pub fn bundle_key(bundle: &Bundle) -> (B256, MevType) {
+        (bundle.header.tx_hash, bundle.header.mev_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_emitted_candidates() {
+        let [a, b, c] = [1, 2, 3].map(B256::with_last_byte);
+        let candidates = [(a, MevType::Sandwich), (b, MevType::Sandwich), (c, MevType::Jit)];
+        // the jit was composed into a jit sandwich
+        let results = FastHashSet::from_iter([(a, MevType::Sandwich), (c, MevType::JitSandwich)]);
+
+        let run =
+            InspectorRun::new(10, "Sandwich", Duration::from_millis(3), &candidates, &results);
+        assert_eq!(run.elapsed_us, 3_000);
+        assert_eq!((run.candidates, run.bundles), (3, 1));
+    }
+}
@@ -1,7 +1,10 @@
 use alloy_primitives::{Address, TxHash, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use malachite::{num::basic::traits::Zero, Rational};
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::Serialize;
 use serde_with::serde_as;
@@ -12,9 +15,12 @@ use super::{
     dex::DexQuotes,
     traits::LibmdbxReader,
 };
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
 use crate::{
-    constants::WETH_ADDRESS,
-    db::{dex::BlockPrice, redefined_types::primitives::*},
+    chain::chain_spec,
+    constants::{BLOB_BASE_FEE_UPDATE_FRACTION, DATA_GAS_PER_BLOB, MIN_BLOB_BASE_FEE},
+    db::dex::BlockPrice,
     implement_table_value_codecs_with_zc,
     pair::Pair,
     serde_utils::{option_addresss, u256, vec_txhash},
@@ -25,19 +31,22 @@ use crate::{db::cex::CexExchange, normalized_actions::NormalizedSwap};
 
 /// libmdbx type
 #[serde_as]
-#[derive(
-    Debug, Default, Row, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, Redefined,
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        Serialize,
+        rDeserialize,
+        rSerialize,
+        Archive
+    ))
 )]
-#[redefined_attr(derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    Serialize,
-    rDeserialize,
-    rSerialize,
-    Archive
-))]
 pub struct BlockMetadataInner {
     #[serde(with = "u256")]
     pub block_hash:             U256,
@@ -49,6 +58,9 @@ pub struct BlockMetadataInner {
     pub proposer_mev_reward:    Option<u128>,
     #[serde(with = "vec_txhash")]
     pub private_flow:           Vec<TxHash>,
+    /// `None` for pre-dencun blocks
+    pub blob_gas_used:          Option<u64>,
+    pub excess_blob_gas:        Option<u64>,
 }
 
 implement_table_value_codecs_with_zc!(BlockMetadataInnerRedefined);
@@ -101,7 +113,10 @@ impl Metadata {
         self.dex_quotes
             .as_ref()
             .and_then(|dex_quotes| {
-                dex_quotes.price_for_block(Pair(WETH_ADDRESS, quote_token), BlockPrice::Average)
+                dex_quotes.price_for_block(
+                    Pair(chain_spec().wrapped_native, quote_token),
+                    BlockPrice::Average,
+                )
             })
             .unwrap_or(Rational::ZERO)
     }
@@ -135,6 +150,10 @@ pub struct BlockMetadata {
     pub eth_prices:             Rational,
     /// Tx
     pub private_flow:           FastHashSet<TxHash>,
+    /// Blob gas used by the block, `None` for pre-dencun blocks
+    pub blob_gas_used:          Option<u64>,
+    /// Excess blob gas of the block, `None` for pre-dencun blocks
+    pub excess_blob_gas:        Option<u64>,
 }
 
 impl BlockMetadata {
@@ -149,6 +168,8 @@ impl BlockMetadata {
         proposer_mev_reward: Option<u128>,
         eth_prices: Rational,
         private_flow: FastHashSet<TxHash>,
+        blob_gas_used: Option<u64>,
+        excess_blob_gas: Option<u64>,
     ) -> Self {
         Self {
             block_num,
@@ -160,6 +181,8 @@ impl BlockMetadata {
             proposer_mev_reward,
             private_flow,
             block_timestamp,
+            blob_gas_used,
+            excess_blob_gas,
         }
     }
 
@@ -167,6 +190,17 @@ impl BlockMetadata {
         self.block_timestamp * 1_000_000
     }
 
+    /// The blob base fee in wei, derived from the excess blob gas as
+    /// specified in EIP-4844
+    pub fn blob_base_fee(&self) -> Option<u128> {
+        self.excess_blob_gas.map(calc_blob_base_fee)
+    }
+
+    /// Number of blobs included in the block
+    pub fn blob_count(&self) -> Option<u64> {
+        self.blob_gas_used.map(|gas| gas / DATA_GAS_PER_BLOB)
+    }
+
     pub fn into_metadata(
         self,
         cex_quotes: CexPriceMap,
@@ -177,3 +211,44 @@ impl BlockMetadata {
         Metadata { block_metadata: self, cex_quotes, dex_quotes, builder_info, cex_trades }
     }
 }
+
+/// Blob base fee for the given excess blob gas, see `get_base_fee_per_blob_gas`
+/// in EIP-4844
+pub fn calc_blob_base_fee(excess_blob_gas: u64) -> u128 {
+    fake_exponential(MIN_BLOB_BASE_FEE, excess_blob_gas as u128, BLOB_BASE_FEE_UPDATE_FRACTION)
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor
+/// expansion
+fn fake_exponential(factor: u128, numerator: u128, denominator: u128) -> u128 {
+    let mut i = 1;
+    let mut output = 0;
+    let mut numerator_accum = factor * denominator;
+    while numerator_accum > 0 {
+        output += numerator_accum;
+        numerator_accum = numerator_accum * numerator / (denominator * i);
+        i += 1;
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_base_fee() {
+        assert_eq!(calc_blob_base_fee(0), 1);
+        assert_eq!(calc_blob_base_fee(2314057), 1);
+        assert_eq!(calc_blob_base_fee(2314058), 2);
+        assert_eq!(calc_blob_base_fee(10 * 1024 * 1024), 23);
+    }
+
+    #[test]
+    fn test_blob_count() {
+        let meta =
+            BlockMetadata { blob_gas_used: Some(3 * DATA_GAS_PER_BLOB), ..Default::default() };
+        assert_eq!(meta.blob_count(), Some(3));
+        assert_eq!(BlockMetadata::default().blob_count(), None);
+    }
+}
@@ -1,11 +1,17 @@
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::{implement_table_value_codecs_with_zc, mev::*};
 
-#[derive(Debug, Default, Serialize, PartialEq, Deserialize, Clone, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, PartialEq, Deserialize, Clone)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct MevBlockWithClassified {
     pub block: MevBlock,
     pub mev:   Vec<Bundle>,
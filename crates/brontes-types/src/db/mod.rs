@@ -1,44 +1,72 @@
+#[cfg(feature = "native")]
 use std::fmt::Debug;
 
+#[cfg(feature = "native")]
 use ::clickhouse::{DbRow, InsertRow};
 pub mod address_metadata;
 pub mod address_to_protocol_info;
 
+pub mod backfill_checkpoint;
 #[rustfmt::skip]
 pub mod block_analysis;
+pub mod block_provenance;
 pub mod block_times;
 pub mod builder;
+pub mod builder_pnl;
 pub mod cex;
+pub mod classification_failure;
 
+#[cfg(feature = "native")]
 pub mod clickhouse;
 pub mod clickhouse_serde;
 pub mod codecs;
+pub mod curve_pool_params;
 pub mod dex;
+pub mod function_selector;
+pub mod gas_bids;
 pub mod initialized_state;
+pub mod inspector_run;
 pub mod metadata;
 pub mod mev_block;
 pub mod normalized_actions;
 pub mod pool_creation_block;
+pub mod pool_fee_tier;
+pub mod possible_mev_timeline;
+pub mod proposer_report;
+pub mod protocol_metadata;
+#[cfg(feature = "rkyv")]
 pub mod redefined_types;
+pub mod sanctions;
 pub mod searcher;
+pub mod searcher_bytecode;
+pub mod searcher_fingerprint;
+pub mod searcher_funding;
+pub mod searcher_inventory;
+pub mod suppressed_bundle;
 pub mod token_info;
+pub mod token_tax;
 pub mod traces;
 pub mod traits;
+pub mod verify_report;
+pub mod victim_execution;
 
 /// This table is used to add run id inserts for each clickhouse table in order
 /// for us to not have to clear runs multiple times
+#[cfg(feature = "native")]
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct DbDataWithRunId<Table: Debug + Clone + serde::Serialize + DbRow + Sync + Send> {
     pub table:  Table,
     pub run_id: u64,
 }
 
+#[cfg(feature = "native")]
 impl<Table: Debug + Clone + serde::Serialize + DbRow + Sync + Send> DbDataWithRunId<Table> {
     pub fn new_with_run_id(table: Table, run_id: u64) -> Self {
         Self { table, run_id }
     }
 }
 
+#[cfg(feature = "native")]
 impl<Table: Debug + Clone + serde::Serialize + DbRow + Sync + Send> InsertRow
     for DbDataWithRunId<Table>
 {
@@ -55,7 +83,8 @@ impl<Table: Debug + Clone + serde::Serialize + DbRow + Sync + Send> InsertRow
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, ::clickhouse::Row)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(feature = "native", derive(::clickhouse::Row))]
 pub struct RunId {
     pub run_id: u64,
 }
@@ -1,10 +1,13 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
+#[cfg(feature = "native")]
 use clickhouse::DbRow;
 use itertools::MultiUnzip;
-use reth_primitives::B256;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
-use crate::{normalized_actions::Action, GasDetails, Node, Root};
+use crate::{
+    normalized_actions::{Action, NormalizedApproval},
+    GasDetails, Node, Root,
+};
 
 #[derive(Debug, Clone)]
 pub struct TransactionRoot {
@@ -56,6 +59,10 @@ impl Serialize for TransactionRoot {
                 self.gas_details.priority_fee,
                 self.gas_details.gas_used,
                 self.gas_details.effective_gas_price,
+                self.gas_details.gas_refunded,
+                self.gas_details.access_list_gas,
+                self.gas_details.blob_gas_used,
+                self.gas_details.blob_gas_price,
             ),
         )?;
 
@@ -69,21 +76,62 @@ impl Serialize for TransactionRoot {
                         node.action_kind,
                         node.action
                             .as_ref()
-                            .map(|a| serde_json::to_string(a).unwrap()),
+                            .map(serde_json::to_string)
+                            .transpose()
+                            .map_err(serde::ser::Error::custom),
                     )
                 })
                 .multiunzip();
+        let action = action.into_iter().collect::<Result<Vec<_>, S::Error>>()?;
 
         ser_struct.serialize_field("trace_nodes.trace_idx", &trace_idx)?;
         ser_struct.serialize_field("trace_nodes.trace_address", &trace_address)?;
         ser_struct.serialize_field("trace_nodes.action_kind", &action_kind)?;
         ser_struct.serialize_field("trace_nodes.action", &action)?;
 
+        #[cfg(feature = "provenance")]
+        {
+            let (selector, log_indices): (Vec<_>, Vec<_>) = self
+                .trace_nodes
+                .iter()
+                .map(|node| {
+                    let provenance = node.action.as_ref().and_then(Action::provenance);
+                    (
+                        provenance
+                            .and_then(|p| p.selector)
+                            .map(|selector| format!("{:?}", selector)),
+                        provenance
+                            .map(|p| p.log_indices.clone())
+                            .unwrap_or_default(),
+                    )
+                })
+                .unzip();
+            ser_struct.serialize_field("trace_nodes.selector", &selector)?;
+            ser_struct.serialize_field("trace_nodes.log_indices", &log_indices)?;
+        }
+
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for TransactionRoot {
+    #[cfg(not(feature = "provenance"))]
+    const COLUMN_NAMES: &'static [&'static str] = &[
+        "block_number",
+        "tx_hash",
+        "tx_idx",
+        "from",
+        "to",
+        "gas_details",
+        "trace_nodes.trace_idx",
+        "trace_nodes.trace_address",
+        "trace_nodes.action_kind",
+        "trace_nodes.action",
+    ];
+    /// The selector and logs of the call each action was classified from are
+    /// only written when built with the `provenance` feature
+    #[cfg(feature = "provenance")]
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_number",
         "tx_hash",
@@ -95,6 +143,8 @@ impl DbRow for TransactionRoot {
         "trace_nodes.trace_address",
         "trace_nodes.action_kind",
         "trace_nodes.action",
+        "trace_nodes.selector",
+        "trace_nodes.log_indices",
     ];
 }
 
@@ -157,6 +207,11 @@ pub enum ActionKind {
     NewPool,
     PoolConfigUpdate,
     Aggregator,
+    VaultDeposit,
+    VaultWithdraw,
+    /// A [`NormalizedApproval`], which is classified as a custom action
+    Approval,
+    Custom,
     Revert,
 }
 
@@ -178,6 +233,10 @@ impl From<&Action> for ActionKind {
             Action::NewPool(_) => ActionKind::NewPool,
             Action::PoolConfigUpdate(_) => ActionKind::PoolConfigUpdate,
             Action::Aggregator(_) => ActionKind::Aggregator,
+            Action::VaultDeposit(_) => ActionKind::VaultDeposit,
+            Action::VaultWithdraw(_) => ActionKind::VaultWithdraw,
+            Action::Custom(c) if c.is::<NormalizedApproval>() => ActionKind::Approval,
+            Action::Custom(_) => ActionKind::Custom,
             Action::Revert => ActionKind::Revert,
         }
     }
@@ -1,13 +1,23 @@
 use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::{db::redefined_types::primitives::*, implement_table_value_codecs_with_zc};
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
+use crate::implement_table_value_codecs_with_zc;
 
-#[derive(Debug, Default, Clone, Row, PartialEq, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct PoolsToAddresses(pub Vec<Address>);
 
 implement_table_value_codecs_with_zc!(PoolsToAddressesRedefined);
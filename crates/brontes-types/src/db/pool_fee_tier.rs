@@ -0,0 +1,49 @@
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// Fee units in a percent
+const FEE_PER_PERCENT: f64 = 10_000.0;
+
+/// Swap fee a pool charges, recorded when the pool is discovered. Pools of the
+/// same pair are deployed once per fee tier, so it is what tells them apart.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct PoolFeeTier {
+    /// Fee in hundredths of a basis point, as uniswap v3 encodes it. A 0.3%
+    /// pool has a fee of 3000
+    pub fee: u32,
+}
+
+implement_table_value_codecs_with_zc!(PoolFeeTierRedefined);
+
+impl PoolFeeTier {
+    pub fn new(fee: u32) -> Self {
+        Self { fee }
+    }
+
+    /// Fee as a percentage of the amount in, e.g. 0.05 for a 500 fee
+    pub fn as_percentage(&self) -> f64 {
+        self.fee as f64 / FEE_PER_PERCENT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_as_percentage() {
+        assert_eq!(PoolFeeTier::new(100).as_percentage(), 0.01);
+        assert_eq!(PoolFeeTier::new(3_000).as_percentage(), 0.3);
+        assert_eq!(PoolFeeTier::new(10_000).as_percentage(), 1.0);
+    }
+}
@@ -0,0 +1,135 @@
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use super::gas_bids::priority_fee_percentile;
+use crate::{
+    mev::{Bundle, MevType, PossibleMev, PossibleMevCollection},
+    normalized_actions::NormalizedAction,
+    serde_utils::{address, option_address, option_txhash, txhash},
+    BlockTree, FastHashMap,
+};
+
+/// A transaction that was flagged as possible mev but wasn't part of any
+/// classified bundle, together with its bidding features and the transactions
+/// around it. Used to triage the transactions the inspectors might have
+/// missed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct PossibleMevTimeline {
+    pub block_number: u64,
+    #[serde(with = "txhash")]
+    pub tx_hash: TxHash,
+    pub tx_index: u64,
+    #[serde(with = "address")]
+    pub eoa: Address,
+    #[serde(with = "option_address")]
+    pub to: Option<Address>,
+    pub is_private: bool,
+    pub has_coinbase_transfer: bool,
+    pub high_priority_fee: bool,
+    pub gas_used: u128,
+    /// Priority fee per gas of the transaction
+    pub priority_fee: u128,
+    /// Percentage of the transactions in the block that paid a lower
+    /// priority fee per gas
+    pub priority_fee_percentile: f64,
+    /// Standard deviations the priority fee is above the block's average
+    pub priority_fee_z_score: f64,
+    pub coinbase_transfer: u128,
+    /// Position of the transaction in the block, 0 is the top and 1 the bottom
+    pub block_position: f64,
+    #[serde(with = "option_txhash")]
+    pub prev_tx_hash: Option<TxHash>,
+    #[serde(with = "option_address")]
+    pub prev_eoa: Option<Address>,
+    #[serde(with = "option_address")]
+    pub prev_to: Option<Address>,
+    /// Type of the classified bundle the previous transaction is part of
+    pub prev_mev_type: Option<MevType>,
+    #[serde(with = "option_txhash")]
+    pub next_tx_hash: Option<TxHash>,
+    #[serde(with = "option_address")]
+    pub next_eoa: Option<Address>,
+    #[serde(with = "option_address")]
+    pub next_to: Option<Address>,
+    /// Type of the classified bundle the next transaction is part of
+    pub next_mev_type: Option<MevType>,
+}
+
+impl PossibleMevTimeline {
+    /// Timelines of all unclassified possible mev transactions of the block
+    pub fn from_possible_mev<V: NormalizedAction>(
+        possible_mev: &PossibleMevCollection,
+        bundles: &[Bundle],
+        tree: &BlockTree<V>,
+    ) -> Vec<Self> {
+        let mev_types = bundles
+            .iter()
+            .flat_map(|bundle| {
+                bundle
+                    .data
+                    .mev_transaction_hashes()
+                    .into_iter()
+                    .map(|tx_hash| (tx_hash, bundle.header.mev_type))
+            })
+            .collect::<FastHashMap<_, _>>();
+
+        possible_mev
+            .0
+            .iter()
+            .filter_map(|possible| Self::new(possible, &mev_types, tree))
+            .collect()
+    }
+
+    pub fn new<V: NormalizedAction>(
+        possible: &PossibleMev,
+        mev_types: &FastHashMap<TxHash, MevType>,
+        tree: &BlockTree<V>,
+    ) -> Option<Self> {
+        let tx_index = possible.tx_idx as usize;
+        let root = tree.tx_roots.get(tx_index)?;
+        let prev = tx_index
+            .checked_sub(1)
+            .and_then(|index| tree.tx_roots.get(index));
+        let next = tree.tx_roots.get(tx_index + 1);
+
+        let base_fee = tree.header.base_fee_per_gas.unwrap_or_default() as u128;
+        let priority_fee = possible.gas_details.priority_fee(base_fee);
+        let priority_fee_z_score = if tree.priority_fee_std_dev == 0.0 {
+            0.0
+        } else {
+            (priority_fee as f64 - tree.avg_priority_fee) / tree.priority_fee_std_dev
+        };
+
+        let tx_count = tree.tx_roots.len();
+        let block_position =
+            if tx_count > 1 { tx_index as f64 / (tx_count - 1) as f64 } else { 0.0 };
+
+        Some(Self {
+            block_number: tree.header.number,
+            tx_hash: possible.tx_hash,
+            tx_index: possible.tx_idx,
+            eoa: root.get_from_address(),
+            to: root.try_get_to_address(),
+            is_private: possible.triggers.is_private,
+            has_coinbase_transfer: possible.triggers.coinbase_transfer,
+            high_priority_fee: possible.triggers.high_priority_fee,
+            gas_used: possible.gas_details.gas_used,
+            priority_fee,
+            priority_fee_percentile: priority_fee_percentile(tree, base_fee, priority_fee),
+            priority_fee_z_score,
+            coinbase_transfer: possible.gas_details.coinbase_transfer(),
+            block_position,
+            prev_tx_hash: prev.map(|prev| prev.tx_hash),
+            prev_eoa: prev.map(|prev| prev.get_from_address()),
+            prev_to: prev.and_then(|prev| prev.try_get_to_address()),
+            prev_mev_type: prev.and_then(|prev| mev_types.get(&prev.tx_hash).copied()),
+            next_tx_hash: next.map(|next| next.tx_hash),
+            next_eoa: next.map(|next| next.get_from_address()),
+            next_to: next.and_then(|next| next.try_get_to_address()),
+            next_mev_type: next.and_then(|next| mev_types.get(&next.tx_hash).copied()),
+        })
+    }
+}
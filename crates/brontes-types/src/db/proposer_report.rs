@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+
+use alloy_primitives::Address;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{address_metadata::AddressMetadata, mev_block::MevBlockWithClassified},
+    serde_utils::address,
+    FastHashMap,
+};
+
+/// The mev a proposer received over a block range, along with the mev that
+/// was extracted in the blocks it proposed. Amounts are in wei unless stated
+/// otherwise.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposerMevStats {
+    /// Fee recipient of the proposer. Blocks that weren't built through
+    /// mev-boost are attributed to their coinbase
+    #[serde(with = "address")]
+    pub fee_recipient:       Address,
+    /// Known entity behind the fee recipient, e.g. a staking pool
+    pub entity:              Option<String>,
+    pub blocks:              u64,
+    /// Blocks with a payment from the builder
+    pub mev_boost_blocks:    u64,
+    /// Builder payments to the fee recipient
+    pub mev_reward:          u128,
+    pub mev_reward_usd:      f64,
+    pub bundles:             u64,
+    /// Profit of the searchers in the proposed blocks
+    pub searcher_profit_usd: f64,
+    /// Bribes the searchers paid to the builders of the proposed blocks
+    pub bribe_usd:           f64,
+}
+
+impl ProposerMevStats {
+    pub const CSV_HEADER: &'static str = "fee_recipient,entity,blocks,mev_boost_blocks,mev_reward,\
+                                          mev_reward_usd,bundles,searcher_profit_usd,bribe_usd";
+
+    /// Groups the blocks by the fee recipient of their proposer, the
+    /// proposers that received the most mev come first
+    pub fn from_blocks<'a>(
+        blocks: impl IntoIterator<Item = &'a MevBlockWithClassified>,
+    ) -> Vec<Self> {
+        let mut proposers: FastHashMap<Address, Self> = FastHashMap::default();
+
+        for MevBlockWithClassified { block, mev } in blocks {
+            let fee_recipient = block
+                .proposer_fee_recipient
+                .unwrap_or(block.builder_address);
+            let stats = proposers
+                .entry(fee_recipient)
+                .or_insert_with(|| Self { fee_recipient, ..Default::default() });
+
+            stats.blocks += 1;
+            if let Some(reward) = block.proposer_mev_reward {
+                stats.mev_boost_blocks += 1;
+                stats.mev_reward += reward;
+            }
+            stats.mev_reward_usd += block.proposer_profit_usd.unwrap_or_default();
+            stats.bundles += mev.len() as u64;
+            stats.searcher_profit_usd += mev.iter().map(|b| b.header.profit_usd).sum::<f64>();
+            stats.bribe_usd += mev.iter().map(|b| b.header.bribe_usd).sum::<f64>();
+        }
+
+        proposers
+            .into_values()
+            .sorted_by(|a, b| {
+                b.mev_reward
+                    .cmp(&a.mev_reward)
+                    .then(a.fee_recipient.cmp(&b.fee_recipient))
+            })
+            .collect()
+    }
+
+    /// Names the entity behind the fee recipient from its address metadata
+    pub fn set_entity(&mut self, metadata: &AddressMetadata) {
+        self.entity = metadata.entity_name.clone().or_else(|| metadata.describe());
+    }
+
+    pub fn mev_reward_eth(&self) -> f64 {
+        self.mev_reward as f64 / 1e18
+    }
+
+    /// Writes the stats as csv, headed by [`Self::CSV_HEADER`]
+    pub fn write_csv<'a>(
+        stats: impl IntoIterator<Item = &'a Self>,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", Self::CSV_HEADER)?;
+        for s in stats {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{}",
+                s.fee_recipient,
+                s.entity.as_deref().map(csv_escape).unwrap_or_default(),
+                s.blocks,
+                s.mev_boost_blocks,
+                s.mev_reward,
+                s.mev_reward_usd,
+                s.bundles,
+                s.searcher_profit_usd,
+                s.bribe_usd
+            )?;
+        }
+
+        writer.flush()
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::MevBlock;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn groups_blocks_by_fee_recipient() {
+        let [proposer, builder, solo] = [1, 2, 3].map(Address::with_last_byte);
+        let block = |fee_recipient, reward| MevBlockWithClassified {
+            block: MevBlock {
+                builder_address: builder,
+                proposer_fee_recipient: fee_recipient,
+                proposer_mev_reward: reward,
+                proposer_profit_usd: reward.map(|r| r as f64 / 1e18 * 2_000.0),
+                ..Default::default()
+            },
+            mev:   vec![],
+        };
+        let mut local = block(None, None);
+        local.block.builder_address = solo;
+
+        let [top, solo_stats] = ProposerMevStats::from_blocks(&[
+            block(Some(proposer), Some(ETH)),
+            block(Some(proposer), Some(ETH / 2)),
+            local,
+        ])
+        .try_into()
+        .unwrap();
+        assert_eq!((top.fee_recipient, top.blocks, top.mev_boost_blocks), (proposer, 2, 2));
+        assert_eq!(top.mev_reward, ETH + ETH / 2);
+        assert_eq!(top.mev_reward_usd, 3_000.0);
+        assert_eq!((solo_stats.fee_recipient, solo_stats.mev_boost_blocks), (solo, 0));
+
+        let mut csv = vec![];
+        ProposerMevStats::write_csv(&[top], &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with(&format!("{proposer},,2,2,{}", ETH + ETH / 2)));
+    }
+}
@@ -0,0 +1,199 @@
+use alloy_primitives::{keccak256, Address, B256};
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
+use crate::{chain::chain_spec, implement_table_value_codecs_with_zc, FastHashMap, Protocol};
+
+/// Metadata of a protocol as a whole rather than of one of its pools, keyed
+/// by the protocol in the `ProtocolRegistry` table. Seeded from the protocol
+/// registry config so new deployments don't need a code change.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct ProtocolMetadata {
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub protocol:  Protocol,
+    /// Factories the pools of the protocol are deployed by. The first one is
+    /// the factory the discovery classifier of the protocol is keyed by, the
+    /// others are deployments of the same code that are dispatched as it
+    pub factories: Vec<Address>,
+    pub routers:   Vec<Address>,
+    /// Fee tiers of the pools in hundredths of a basis point, as uniswap v3
+    /// encodes them
+    pub fee_tiers: Vec<u32>,
+}
+
+implement_table_value_codecs_with_zc!(ProtocolMetadataRedefined);
+
+impl ProtocolMetadata {
+    /// Adds the entries of `other` that aren't stored yet
+    pub fn merge(&mut self, other: Self) {
+        for factory in other.factories {
+            if !self.factories.contains(&factory) {
+                self.factories.push(factory);
+            }
+        }
+        for router in other.routers {
+            if !self.routers.contains(&router) {
+                self.routers.push(router);
+            }
+        }
+        for fee_tier in other.fee_tiers {
+            if !self.fee_tiers.contains(&fee_tier) {
+                self.fee_tiers.push(fee_tier);
+            }
+        }
+        self.fee_tiers.sort_unstable();
+    }
+
+    pub fn is_factory(&self, address: Address) -> bool {
+        self.factories.contains(&address)
+    }
+
+    pub fn is_router(&self, address: Address) -> bool {
+        self.routers.contains(&address)
+    }
+
+    /// The factory the discovery classifier of the protocol is keyed by, if
+    /// `factory` is one of the registered deployments of the protocol
+    pub fn canonical_factory(&self, factory: Address) -> Option<Address> {
+        self.is_factory(factory)
+            .then(|| self.factories.first().copied())
+            .flatten()
+    }
+}
+
+/// The factory the discovery classifiers dispatch the pools created by
+/// `factory` as. Factories of the chain deployed at a different address than on
+/// mainnet map to their mainnet address, and additional deployments in the
+/// protocol registry map to the first factory of their protocol.
+pub fn canonical_factory(registry: &[ProtocolMetadata], factory: Address) -> Address {
+    let factory = chain_spec().canonical_factory(factory);
+    registry
+        .iter()
+        .find_map(|metadata| metadata.canonical_factory(factory))
+        .unwrap_or(factory)
+}
+
+/// Entry of a protocol in the protocol registry config
+///
+/// ```toml
+/// [UniswapV3]
+/// factories = ["0x1F98431c8aD98523631AE4a59f267346ea31F984"]
+/// routers = ["0xE592427A0AEce92De3Edee1F18E0157C05861564"]
+/// fee_tiers = [100, 500, 3000, 10000]
+///
+/// [UniswapV3.code_hashes]
+/// "0x1F98431c8aD98523631AE4a59f267346ea31F984" = "0x..."
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProtocolMetadataConfig {
+    pub factories:   Vec<Address>,
+    pub routers:     Vec<Address>,
+    pub fee_tiers:   Vec<u32>,
+    /// Keccak of the deployed bytecode of the factories and routers. The
+    /// configured addresses with a hash are checked against the chain before
+    /// the entry is stored, so a misconfigured address is rejected
+    pub code_hashes: FastHashMap<Address, B256>,
+}
+
+impl ProtocolMetadataConfig {
+    pub fn into_protocol_metadata(self, protocol: Protocol) -> ProtocolMetadata {
+        let mut fee_tiers = self.fee_tiers;
+        fee_tiers.sort_unstable();
+        fee_tiers.dedup();
+
+        ProtocolMetadata { protocol, factories: self.factories, routers: self.routers, fee_tiers }
+    }
+
+    /// Hashes that are configured for an address which isn't a factory or
+    /// router of the protocol, which is most likely a typo
+    pub fn unknown_code_hashes(&self) -> Vec<Address> {
+        self.code_hashes
+            .keys()
+            .filter(|address| !self.factories.contains(address) && !self.routers.contains(address))
+            .copied()
+            .collect()
+    }
+}
+
+/// Checks the code deployed at `address` against the configured hash
+pub fn verify_code_hash(address: Address, expected: B256, code: Option<&[u8]>) -> eyre::Result<()> {
+    let Some(code) = code.filter(|code| !code.is_empty()) else {
+        eyre::bail!("no contract deployed at {address}")
+    };
+
+    let hash = keccak256(code);
+    if hash != expected {
+        eyre::bail!("code hash of {address} is {hash}, expected {expected}")
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_metadata_config() {
+        let [factory, router, other] = [1, 2, 3].map(Address::with_last_byte);
+        let code = [0x60, 0x80, 0x60, 0x40];
+
+        let config = ProtocolMetadataConfig {
+            factories:   vec![factory],
+            routers:     vec![router],
+            fee_tiers:   vec![3000, 500, 3000],
+            code_hashes: FastHashMap::from_iter([(factory, keccak256(code)), (other, B256::ZERO)]),
+        };
+        assert_eq!(config.unknown_code_hashes(), vec![other]);
+
+        assert!(verify_code_hash(factory, config.code_hashes[&factory], Some(&code)).is_ok());
+        assert!(verify_code_hash(factory, config.code_hashes[&factory], Some(&code[1..])).is_err());
+        assert!(verify_code_hash(factory, config.code_hashes[&factory], None).is_err());
+
+        let mut metadata = config.into_protocol_metadata(Protocol::UniswapV3);
+        assert_eq!(metadata.fee_tiers, vec![500, 3000]);
+
+        metadata.merge(ProtocolMetadata {
+            protocol:  Protocol::UniswapV3,
+            factories: vec![factory],
+            routers:   vec![other],
+            fee_tiers: vec![100],
+        });
+        assert_eq!(metadata.factories, vec![factory]);
+        assert!(metadata.is_router(router) && metadata.is_router(other));
+        assert_eq!(metadata.fee_tiers, vec![100, 500, 3000]);
+    }
+
+    #[test]
+    fn test_registered_factories_dispatch_as_the_first() {
+        let [v3_factory, redeployed, unknown] = [1, 2, 3].map(Address::with_last_byte);
+        let registry = vec![
+            ProtocolMetadata {
+                protocol: Protocol::UniswapV2,
+                factories: vec![Address::with_last_byte(4)],
+                ..Default::default()
+            },
+            ProtocolMetadata {
+                protocol: Protocol::UniswapV3,
+                factories: vec![v3_factory, redeployed],
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(canonical_factory(&registry, redeployed), v3_factory);
+        assert_eq!(canonical_factory(&registry, v3_factory), v3_factory);
+        assert_eq!(canonical_factory(&registry, unknown), unknown);
+        assert_eq!(canonical_factory(&[], redeployed), redeployed);
+    }
+}
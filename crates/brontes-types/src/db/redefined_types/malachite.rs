@@ -16,6 +16,7 @@ redefined_remote!(
         rDeserialize,
         Archive,
     )]
+    #[archive(check_bytes)]
     [Rational] : "malachite-q"
 );
 
@@ -32,6 +33,7 @@ redefined_remote!(
         rDeserialize,
         Archive,
     )]
+    #[archive(check_bytes)]
     [Natural] : "malachite-nz"
 );
 
@@ -48,6 +50,7 @@ redefined_remote!(
         rDeserialize,
         Archive,
     )]
+    #[archive(check_bytes)]
     [InnerNatural] : "malachite-nz" : no_impl
 );
 
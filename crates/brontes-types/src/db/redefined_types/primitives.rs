@@ -9,6 +9,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 // Uint
 redefined_remote!(
     #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, rSerialize, rDeserialize, Archive)]
+    #[archive(check_bytes)]
     [Uint] : "ruint"
 );
 
@@ -52,6 +53,7 @@ redefined_remote!(
         rDeserialize,
         Archive,
     )]
+    #[archive(check_bytes)]
     [FixedBytes] : "alloy-primitives"
 );
 
@@ -125,6 +127,7 @@ impl<const N: usize> Default for FixedBytesRedefined<N> {
     Archive,
 )]
 #[redefined(Address)]
+#[archive(check_bytes)]
 #[archive_attr(derive(Hash, PartialEq, Eq))]
 pub struct AddressRedefined(FixedBytesRedefined<20>);
 
@@ -152,6 +155,7 @@ impl FromStr for AddressRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(Bytes)]
 #[redefined_attr(to_source = "self.0.into()", from_source = "Self(src.to_vec())")]
 pub struct BytesRedefined(pub Vec<u8>);
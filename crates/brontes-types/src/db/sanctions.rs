@@ -0,0 +1,31 @@
+#[cfg(feature = "native")]
+use clickhouse::Row;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+/// An entry of a sanctions list (e.g. the OFAC SDN list) for a single
+/// address.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct SanctionInfo {
+    /// The list the address was sanctioned on
+    pub list:      String,
+    /// The sanctioned entity the address belongs to, if known
+    #[serde(default)]
+    pub entity:    Option<String>,
+    /// Unix timestamp in seconds of when the address was added to the list
+    #[serde(default)]
+    pub listed_at: u64,
+}
+
+implement_table_value_codecs_with_zc!(SanctionInfoRedefined);
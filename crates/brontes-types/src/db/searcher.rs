@@ -1,36 +1,46 @@
 use std::{fmt, ops::Add};
 
 use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::Row;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::serde_as;
 use strum::AsRefStr;
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
 use crate::{
-    db::redefined_types::primitives::AddressRedefined,
+    db::gas_bids::SearcherBlockBids,
     implement_table_value_codecs_with_zc,
     mev::{BundleHeader, MevCount, MevType},
     serde_utils::{addresss, option_addresss, vec_address},
 };
 
-#[derive(Debug, Default, Row, PartialEq, Clone, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct SearcherInfo {
     #[serde(default)]
     pub name:              Option<String>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     pub fund:              Fund,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     pub mev_count:         MevCount,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     pub pnl:               TollByType,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     pub gas_bids:          TollByType,
     /// If the searcher is vertically integrated, this will contain the
@@ -38,13 +48,22 @@ pub struct SearcherInfo {
     #[serde(with = "option_addresss")]
     #[serde(default)]
     pub builder:           Option<Address>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     #[serde(rename = "mev_types")]
     pub config_labels:     Vec<MevType>,
     #[serde(with = "vec_address")]
     #[serde(default)]
     pub sibling_searchers: Vec<Address>,
+    /// The address that funded the searcher. Searchers often split the legs of
+    /// their bundles across EOAs funded by the same parent to avoid being
+    /// grouped by sender.
+    #[serde(with = "option_addresss")]
+    #[serde(default)]
+    pub funding_parent:    Option<Address>,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    #[serde(default)]
+    pub bid_behavior:      BidBehavior,
 }
 
 impl SearcherInfo {
@@ -62,6 +81,24 @@ impl SearcherInfo {
         self.sibling_searchers.as_ref()
     }
 
+    /// Returns the address shared by all EOAs of the same searcher, which is
+    /// the funding parent if known and otherwise the lowest address among the
+    /// EOA and its siblings.
+    pub fn linked_eoa_group(&self, eoa: Address) -> Option<Address> {
+        if self.funding_parent.is_some() {
+            return self.funding_parent
+        }
+        if self.sibling_searchers.is_empty() {
+            return None
+        }
+
+        self.sibling_searchers
+            .iter()
+            .copied()
+            .chain(std::iter::once(eoa))
+            .min()
+    }
+
     pub fn get_bundle_count_for_type(&self, mev_type: MevType) -> Option<u64> {
         match mev_type {
             MevType::CexDexTrades => self.mev_count.cex_dex_trade_count,
@@ -74,6 +111,8 @@ impl SearcherInfo {
             MevType::AtomicArb => self.mev_count.atomic_backrun_count,
             MevType::Liquidation => self.mev_count.liquidation_count,
             MevType::SearcherTx => self.mev_count.searcher_tx_count,
+            MevType::Frontrun => self.mev_count.frontrun_count,
+            MevType::ApprovalRace => self.mev_count.approval_race_count,
             MevType::Unknown => None,
         }
     }
@@ -99,6 +138,37 @@ impl SearcherInfo {
         self.builder = other.builder.or(self.builder.take());
 
         self.sibling_searchers = other.sibling_searchers;
+        self.funding_parent = other.funding_parent.or(self.funding_parent.take());
+        if other.bid_behavior.blocks > 0 {
+            self.bid_behavior = other.bid_behavior;
+        }
+    }
+
+    /// Whether the searcher has been labelled, either by name, fund, builder
+    /// or config
+    pub fn is_labelled(&self) -> bool {
+        self.name.is_some()
+            || self.fund != Fund::None
+            || self.builder.is_some()
+            || !self.config_labels.is_empty()
+    }
+
+    /// Copies the labels of the searcher `origin` that aren't set yet, e.g
+    /// when the contract is a redeployed copy of it
+    pub fn inherit_labels(&mut self, origin: &SearcherInfo) {
+        if self.name.is_none() {
+            self.name = origin.name.clone();
+        }
+        if self.fund == Fund::None {
+            self.fund = origin.fund;
+        }
+        self.builder = self.builder.or(origin.builder);
+
+        for mev_type in &origin.config_labels {
+            if !self.config_labels.contains(mev_type) {
+                self.config_labels.push(*mev_type);
+            }
+        }
     }
 
     pub fn describe(&self) -> String {
@@ -172,23 +242,92 @@ impl SearcherInfo {
         self.mev_count.increment_count(header.mev_type);
         self.gas_bids.account_gas(header);
     }
+
+    pub fn update_with_block_bids(&mut self, bids: &SearcherBlockBids) {
+        self.bid_behavior.account_block(bids);
+    }
+
+    /// Average share of the profit before bribes the searcher paid to the
+    /// builder, `None` if they never bid
+    pub fn avg_bid_ratio(&self) -> Option<f64> {
+        (self.bid_behavior.blocks > 0).then_some(self.bid_behavior.avg_bid_ratio)
+    }
+
+    pub fn bidding_style(&self) -> BiddingStyle {
+        self.bid_behavior.style()
+    }
 }
 
 implement_table_value_codecs_with_zc!(SearcherInfoRedefined);
 
+/// Searchers that make at least this share of their builder payments through
+/// coinbase transfers are coinbase payers, the ones that make at most
+/// `1 - COINBASE_PAYER_THRESHOLD` of them that way are priority fee payers
+const COINBASE_PAYER_THRESHOLD: f64 = 0.8;
+
+/// Running averages of the per block bids of a searcher, see
+/// [`SearcherBlockBids`]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+pub struct BidBehavior {
+    /// Number of blocks the searcher bid in
+    pub blocks: u64,
+    pub avg_bid_ratio: f64,
+    pub avg_coinbase_transfer_ratio: f64,
+    pub avg_top_block_position: f64,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(BidBehavior);
+
+impl BidBehavior {
+    pub fn account_block(&mut self, bids: &SearcherBlockBids) {
+        self.blocks += 1;
+        let n = self.blocks as f64;
+        self.avg_bid_ratio += (bids.bid_to_profit_ratio - self.avg_bid_ratio) / n;
+        self.avg_coinbase_transfer_ratio +=
+            (bids.coinbase_transfer_ratio - self.avg_coinbase_transfer_ratio) / n;
+        self.avg_top_block_position += (bids.top_block_position - self.avg_top_block_position) / n;
+    }
+
+    pub fn style(&self) -> BiddingStyle {
+        if self.blocks == 0 {
+            BiddingStyle::Unknown
+        } else if self.avg_coinbase_transfer_ratio >= COINBASE_PAYER_THRESHOLD {
+            BiddingStyle::CoinbasePayer
+        } else if self.avg_coinbase_transfer_ratio <= 1.0 - COINBASE_PAYER_THRESHOLD {
+            BiddingStyle::PriorityFeePayer
+        } else {
+            BiddingStyle::Mixed
+        }
+    }
+}
+
+/// How a searcher pays the builder for their position in the block
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BiddingStyle {
+    /// Never bid in a processed block
+    #[default]
+    Unknown,
+    /// Pays mostly through coinbase transfers
+    CoinbasePayer,
+    /// Pays mostly through the priority fee
+    PriorityFeePayer,
+    Mixed,
+}
+
+impl fmt::Display for BiddingStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
 #[serde_as]
-#[derive(
-    Debug,
-    Deserialize,
-    PartialEq,
-    Serialize,
-    Row,
-    Clone,
-    Default,
-    rkyv::Serialize,
-    rDeserialize,
-    Archive,
-)]
+#[derive(Debug, Deserialize, PartialEq, Serialize, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct TollByType {
     pub total:          f64,
     pub sandwich:       Option<f64>,
@@ -201,6 +340,7 @@ pub struct TollByType {
     pub searcher_tx:    Option<f64>,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(TollByType);
 
 impl TollByType {
@@ -289,20 +429,9 @@ impl TollByType {
     }
 }
 
-#[derive(
-    Debug,
-    Default,
-    PartialEq,
-    Eq,
-    Clone,
-    rSerialize,
-    rDeserialize,
-    Archive,
-    Copy,
-    AsRefStr,
-    PartialOrd,
-    Hash,
-)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, AsRefStr, PartialOrd, Hash)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub enum Fund {
     #[default]
     None,
@@ -384,9 +513,11 @@ impl<'de> Deserialize<'de> for Fund {
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(Fund);
 
-#[derive(Debug, Row, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct JoinedSearcherInfo {
     #[serde(with = "addresss")]
     pub address:         Address,
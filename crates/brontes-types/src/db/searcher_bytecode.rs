@@ -0,0 +1,195 @@
+use alloy_primitives::{keccak256, Address, B256};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    serde_utils::{address, txhash},
+    FastHashMap,
+};
+
+const PUSH1: u8 = 0x60;
+const PUSH20: u8 = 0x73;
+const PUSH32: u8 = 0x7f;
+
+/// Hashes of the runtime bytecode of a searcher contract.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SearcherBytecode {
+    /// Block the bytecode was fetched at
+    pub block_number:    u64,
+    #[serde(with = "address")]
+    pub mev_contract:    Address,
+    #[serde(with = "txhash")]
+    pub code_hash:       B256,
+    /// Hash of the bytecode with the embedded addresses, immutables and the
+    /// compiler metadata cleared, see [`normalize_bytecode`]
+    #[serde(with = "txhash")]
+    pub normalized_hash: B256,
+    pub code_size:       u64,
+}
+
+impl SearcherBytecode {
+    /// Returns `None` if the contract has no code
+    pub fn new(mev_contract: Address, block_number: u64, code: &[u8]) -> Option<Self> {
+        if code.is_empty() {
+            return None
+        }
+
+        Some(Self {
+            block_number,
+            mev_contract,
+            code_hash: keccak256(code),
+            normalized_hash: keccak256(normalize_bytecode(code)),
+            code_size: code.len() as u64,
+        })
+    }
+}
+
+/// Strips the parts of the bytecode that change between deployments of the
+/// same source. Bots are usually redeployed with a new owner, router or token
+/// baked in, which ends up in the immediates of `PUSH20` and `PUSH32`
+/// (immutables are pushed as full words). The solidity metadata trailer is
+/// dropped as it changes with any change to the source files, including
+/// comments.
+pub fn normalize_bytecode(code: &[u8]) -> Vec<u8> {
+    let mut normalized = strip_metadata(code).to_vec();
+
+    let mut pc = 0;
+    while pc < normalized.len() {
+        let op = normalized[pc];
+        pc += 1;
+        if !(PUSH1..=PUSH32).contains(&op) {
+            continue
+        }
+
+        let size = (op - PUSH1 + 1) as usize;
+        let end = (pc + size).min(normalized.len());
+        if op == PUSH20 || op == PUSH32 {
+            normalized[pc..end].fill(0);
+        }
+        pc = end;
+    }
+
+    normalized
+}
+
+/// The solidity and vyper compilers append a cbor encoded map followed by its
+/// length as a two byte big endian integer
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    let Some(len_bytes) = code.len().checked_sub(2).map(|start| &code[start..]) else {
+        return code
+    };
+    let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+
+    match code.len().checked_sub(len + 2) {
+        // cbor maps with up to 15 entries start with 0xa0 to 0xaf
+        Some(start) if len > 0 && code[start] & 0xf0 == 0xa0 => &code[..start],
+        _ => code,
+    }
+}
+
+/// How the bytecode of two contracts matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BytecodeMatch {
+    /// Identical runtime bytecode
+    Exact,
+    /// Identical after [`normalize_bytecode`]
+    Normalized,
+}
+
+/// Index of searcher contracts by their exact and normalized bytecode hashes,
+/// used to find redeployed copies of known bots.
+#[derive(Debug, Default, Clone)]
+pub struct BytecodeIndex {
+    by_code_hash:       FastHashMap<B256, Vec<Address>>,
+    by_normalized_hash: FastHashMap<B256, Vec<Address>>,
+}
+
+impl BytecodeIndex {
+    pub fn new(bytecodes: &[SearcherBytecode]) -> Self {
+        let mut index = Self::default();
+        bytecodes.iter().for_each(|bytecode| index.insert(bytecode));
+        index
+    }
+
+    pub fn insert(&mut self, bytecode: &SearcherBytecode) {
+        for (hash, map) in [
+            (bytecode.code_hash, &mut self.by_code_hash),
+            (bytecode.normalized_hash, &mut self.by_normalized_hash),
+        ] {
+            let contracts = map.entry(hash).or_default();
+            if !contracts.contains(&bytecode.mev_contract) {
+                contracts.push(bytecode.mev_contract);
+            }
+        }
+    }
+
+    /// Other contracts with the same bytecode, exact matches first
+    pub fn matches(&self, bytecode: &SearcherBytecode) -> Vec<(Address, BytecodeMatch)> {
+        let exact = self
+            .by_code_hash
+            .get(&bytecode.code_hash)
+            .into_iter()
+            .flatten()
+            .map(|contract| (*contract, BytecodeMatch::Exact));
+
+        let normalized = self
+            .by_normalized_hash
+            .get(&bytecode.normalized_hash)
+            .into_iter()
+            .flatten()
+            .filter(|contract| {
+                !self
+                    .by_code_hash
+                    .get(&bytecode.code_hash)
+                    .is_some_and(|exact| exact.contains(contract))
+            })
+            .map(|contract| (*contract, BytecodeMatch::Normalized));
+
+        exact
+            .chain(normalized)
+            .filter(|(contract, _)| *contract != bytecode.mev_contract)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[test]
+    fn normalization_ignores_embedded_addresses_and_metadata() {
+        // PUSH20 <owner> PUSH1 0x01 STOP, followed by a metadata map of length 3
+        let a = hex!("73c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2600100a161610003");
+        let b = hex!("73a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48600100a161620003");
+        assert_ne!(keccak256(a), keccak256(b));
+        assert_eq!(normalize_bytecode(&a), normalize_bytecode(&b));
+
+        // the PUSH1 immediate is kept
+        let c = hex!("73a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48600200a161620003");
+        assert_ne!(normalize_bytecode(&a), normalize_bytecode(&c));
+    }
+
+    #[test]
+    fn index_finds_copies() {
+        let (a, b, c) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let code = hex!("73c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2600100");
+        let mut redeployed = code;
+        redeployed[1] = 0;
+
+        let original = SearcherBytecode::new(a, 1, &code).unwrap();
+        let copy = SearcherBytecode::new(b, 2, &code).unwrap();
+        let modified = SearcherBytecode::new(c, 3, &redeployed).unwrap();
+        let index = BytecodeIndex::new(&[original.clone(), copy, modified]);
+
+        assert_eq!(
+            index.matches(&original),
+            vec![(b, BytecodeMatch::Exact), (c, BytecodeMatch::Normalized)]
+        );
+        assert!(SearcherBytecode::new(a, 1, &[]).is_none());
+    }
+}
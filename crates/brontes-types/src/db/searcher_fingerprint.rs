@@ -0,0 +1,235 @@
+use alloy_primitives::{keccak256, Address, TxHash};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mev::{Bundle, MevType},
+    normalized_actions::NormalizedAction,
+    serde_utils::{address, txhash, vec_address},
+    BlockTree, FastHashMap,
+};
+
+const WORD_SIZE: usize = 32;
+
+/// Rough type of a 32 byte calldata word. Values are deliberately coarse so
+/// that the same call with different amounts maps to the same shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum WordShape {
+    /// Left padded 20 byte value
+    Address,
+    /// Offset into the calldata of a dynamic argument
+    Offset,
+    /// Any other value
+    Value,
+}
+
+impl WordShape {
+    fn new(word: &[u8], args_len: usize) -> Self {
+        let is_small = word[..24].iter().all(|b| *b == 0);
+        if is_small {
+            let value = u64::from_be_bytes(word[24..].try_into().unwrap()) as usize;
+            if value != 0 && value % WORD_SIZE == 0 && value < args_len {
+                return Self::Offset
+            }
+            return Self::Value
+        }
+
+        // amounts rarely exceed 128 bits, while only vanity addresses start with
+        // four zero bytes
+        if word[..12].iter().all(|b| *b == 0) && word[12..16].iter().any(|b| *b != 0) {
+            Self::Address
+        } else {
+            Self::Value
+        }
+    }
+}
+
+/// Hash of the selector and the shape of the arguments of a call. Bots tend to
+/// call their contracts with the same calldata layout, so the fingerprint
+/// stays the same when a bot is redeployed to a new address.
+///
+/// Runs of words with the same shape are collapsed so that dynamic arrays of
+/// different lengths, e.g swap paths, produce the same fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CalldataFingerprint(pub u64);
+
+impl CalldataFingerprint {
+    /// Returns `None` if the calldata doesn't contain a selector
+    pub fn new(calldata: &[u8]) -> Option<Self> {
+        if calldata.len() < 4 {
+            return None
+        }
+
+        let (selector, args) = calldata.split_at(4);
+        let mut preimage = selector.to_vec();
+
+        let mut words = args.chunks_exact(WORD_SIZE);
+        for word in words.by_ref() {
+            let shape = WordShape::new(word, args.len()) as u8;
+            if preimage.len() == 4 || preimage.last() != Some(&shape) {
+                preimage.push(shape);
+            }
+        }
+        // packed calldata that isn't abi encoded
+        preimage.push(u8::MAX);
+        preimage.push(words.remainder().len() as u8);
+
+        let hash = keccak256(&preimage);
+        Some(Self(u64::from_be_bytes(hash[..8].try_into().unwrap())))
+    }
+}
+
+/// The calldata fingerprint of a call to a searcher contract.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SearcherFingerprint {
+    pub block_number: u64,
+    #[serde(with = "txhash")]
+    pub tx_hash:      TxHash,
+    #[serde(with = "address")]
+    pub eoa:          Address,
+    #[serde(with = "address")]
+    pub mev_contract: Address,
+    pub mev_type:     MevType,
+    pub selector:     u32,
+    pub fingerprint:  u64,
+}
+
+impl SearcherFingerprint {
+    /// Fingerprints the top level call of each transaction in the bundles that
+    /// went to the searcher's contract
+    pub fn from_bundles<V: NormalizedAction>(bundles: &[Bundle], tree: &BlockTree<V>) -> Vec<Self> {
+        bundles
+            .iter()
+            .filter(|bundle| bundle.header.mev_type != MevType::Unknown)
+            .flat_map(|bundle| {
+                bundle
+                    .data
+                    .mev_transaction_hashes()
+                    .into_iter()
+                    .filter_map(|tx_hash| Self::new(bundle, tx_hash, tree))
+            })
+            .collect()
+    }
+
+    pub fn new<V: NormalizedAction>(
+        bundle: &Bundle,
+        tx_hash: TxHash,
+        tree: &BlockTree<V>,
+    ) -> Option<Self> {
+        let mev_contract = bundle.header.mev_contract?;
+        let root = tree.get_root(tx_hash)?;
+        if root.try_get_to_address()? != mev_contract {
+            return None
+        }
+
+        let calldata = root.get_root_action().get_action().get_calldata()?;
+        let fingerprint = CalldataFingerprint::new(&calldata)?;
+
+        Some(Self {
+            block_number: bundle.header.block_number,
+            tx_hash,
+            eoa: root.get_from_address(),
+            mev_contract,
+            mev_type: bundle.header.mev_type,
+            selector: u32::from_be_bytes(calldata[..4].try_into().unwrap()),
+            fingerprint: fingerprint.0,
+        })
+    }
+}
+
+/// A fingerprint that was seen on more than one searcher contract
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SharedFingerprint {
+    pub fingerprint: u64,
+    #[serde(with = "vec_address")]
+    pub contracts:   Vec<Address>,
+}
+
+/// Groups the contracts that are transitively linked through a shared
+/// fingerprint. Each group is most likely the same bot deployed to multiple
+/// addresses.
+pub fn cluster_contracts(shared: &[SharedFingerprint]) -> Vec<Vec<Address>> {
+    let mut parents: FastHashMap<Address, Address> = FastHashMap::default();
+
+    fn find(parents: &mut FastHashMap<Address, Address>, address: Address) -> Address {
+        let parent = *parents.entry(address).or_insert(address);
+        if parent == address {
+            return address
+        }
+        let root = find(parents, parent);
+        parents.insert(address, root);
+        root
+    }
+
+    for contracts in shared.iter().map(|shared| &shared.contracts) {
+        let Some(first) = contracts.first() else { continue };
+        let root = find(&mut parents, *first);
+        for contract in &contracts[1..] {
+            let other = find(&mut parents, *contract);
+            parents.insert(other, root);
+        }
+    }
+
+    let mut clusters: FastHashMap<Address, Vec<Address>> = FastHashMap::default();
+    for address in parents.keys().copied().collect::<Vec<_>>() {
+        let root = find(&mut parents, address);
+        clusters.entry(root).or_default().push(address);
+    }
+
+    clusters
+        .into_values()
+        .filter(|cluster| cluster.len() > 1)
+        .map(|mut cluster| {
+            cluster.sort();
+            cluster
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[test]
+    fn fingerprint_ignores_values() {
+        // swap(address,uint256) with different tokens and amounts
+        let a = hex!(
+            "12345678000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2\
+             0000000000000000000000000000000000000000000000000de0b6b3a7640000"
+        );
+        let b = hex!(
+            "12345678000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48\
+             00000000000000000000000000000000000000000000d3c21bcecceda1000000"
+        );
+        assert_eq!(CalldataFingerprint::new(&a), CalldataFingerprint::new(&b));
+
+        let mut other_selector = a;
+        other_selector[0] = 0;
+        assert_ne!(CalldataFingerprint::new(&a), CalldataFingerprint::new(&other_selector));
+        assert!(CalldataFingerprint::new(&a[..3]).is_none());
+    }
+
+    #[test]
+    fn clusters_transitively() {
+        let (a, b, c, d) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+            Address::with_last_byte(4),
+        );
+
+        let clusters = cluster_contracts(&[
+            SharedFingerprint { fingerprint: 1, contracts: vec![a, b] },
+            SharedFingerprint { fingerprint: 2, contracts: vec![c, b] },
+            SharedFingerprint { fingerprint: 3, contracts: vec![d] },
+        ]);
+
+        assert_eq!(clusters, vec![vec![a, b, c]]);
+    }
+}
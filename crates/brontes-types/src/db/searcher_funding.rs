@@ -0,0 +1,454 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use alloy_primitives::Address;
+use itertools::Itertools;
+#[cfg(feature = "rkyv")]
+use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
+use crate::{
+    db::{
+        address_metadata::{AddressMetadata, ContractType},
+        searcher::{Fund, SearcherInfo},
+        traits::LibmdbxReader,
+    },
+    implement_table_value_codecs_with_zc,
+    normalized_actions::Action,
+    BlockTree, FastHashMap, TreeSearchBuilder,
+};
+
+/// The addresses that sent funds to a searcher EOA, accumulated over every
+/// processed block. Only senders that can be tied to a fund or that are
+/// known to obscure where funds come from, cex hot wallets and bridges, are
+/// tracked.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct SearcherFunding {
+    pub sources: Vec<FundingSource>,
+}
+
+implement_table_value_codecs_with_zc!(SearcherFundingRedefined);
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct FundingSource {
+    pub address:   Address,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub kind:      FundingSourceKind,
+    /// Fund the source belongs to, `None` for cex and bridge sources
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub fund:      Fund,
+    /// Transfers from the source to the searcher in each block, sorted by
+    /// block. Processing a block again replaces its count.
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub transfers: Vec<BlockTransfers>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+pub struct BlockTransfers {
+    pub block: u64,
+    pub count: u64,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(BlockTransfers);
+
+impl FundingSource {
+    /// Number of transfers from the source to the searcher
+    pub fn transfer_count(&self) -> u64 {
+        self.transfers.iter().map(|transfers| transfers.count).sum()
+    }
+
+    /// Block of the first transfer
+    pub fn first_block(&self) -> u64 {
+        self.transfers
+            .first()
+            .map(|transfers| transfers.block)
+            .unwrap_or_default()
+    }
+
+    /// Block of the last transfer
+    pub fn last_block(&self) -> u64 {
+        self.transfers
+            .last()
+            .map(|transfers| transfers.block)
+            .unwrap_or_default()
+    }
+}
+
+/// How a funding source was identified
+#[derive(
+    Debug, Default, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize, strum::Display,
+)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FundingSourceKind {
+    /// Address metadata names the fund as the entity of the address
+    #[default]
+    Fund,
+    /// Another searcher EOA that is attributed to a fund
+    Searcher,
+    /// Cex hot wallet, anyone could have withdrawn to the searcher
+    Cex,
+    Bridge,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(FundingSourceKind);
+
+impl FundingSourceKind {
+    /// How much a transfer from a source of this kind says about who is
+    /// behind the searcher
+    pub fn weight(&self) -> f64 {
+        match self {
+            Self::Fund => 1.0,
+            Self::Searcher => 0.5,
+            Self::Cex | Self::Bridge => 0.25,
+        }
+    }
+
+    /// Identifies the sender of a transfer, `None` if it isn't a source that
+    /// is tracked
+    fn classify(
+        metadata: Option<&AddressMetadata>,
+        searcher: Option<&SearcherInfo>,
+    ) -> Option<(Self, Fund)> {
+        if let Some(fund) = metadata
+            .and_then(|metadata| metadata.entity_name.clone())
+            .map(Fund::from)
+            .filter(|fund| !fund.is_none())
+        {
+            return Some((Self::Fund, fund))
+        }
+
+        if let Some(searcher) = searcher.filter(|searcher| !searcher.fund.is_none()) {
+            return Some((Self::Searcher, searcher.fund))
+        }
+
+        match metadata?.get_contract_type() {
+            ContractType::Cex | ContractType::CexExchange => Some((Self::Cex, Fund::None)),
+            ContractType::Bridge => Some((Self::Bridge, Fund::None)),
+            _ => None,
+        }
+    }
+}
+
+/// The fund a searcher most likely belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FundAttribution {
+    pub fund:       Fund,
+    /// Weighted share of the searcher's funding that came from the fund,
+    /// between 0 and 1
+    pub confidence: f64,
+}
+
+impl SearcherFunding {
+    /// Records `transfers` transfers from the source in the given block
+    pub fn record(
+        &mut self,
+        address: Address,
+        kind: FundingSourceKind,
+        fund: Fund,
+        block: u64,
+        transfers: u64,
+    ) {
+        self.merge_source(FundingSource {
+            address,
+            kind,
+            fund,
+            transfers: vec![BlockTransfers { block, count: transfers }],
+        });
+    }
+
+    pub fn merge(mut self, other: Self) -> Self {
+        other
+            .sources
+            .into_iter()
+            .for_each(|source| self.merge_source(source));
+        self
+    }
+
+    /// Drops the transfers of reorged out blocks, returns whether any source
+    /// is left
+    pub fn remove_blocks(&mut self, blocks: &RangeInclusive<u64>) -> bool {
+        self.sources.iter_mut().for_each(|source| {
+            source
+                .transfers
+                .retain(|transfers| !blocks.contains(&transfers.block))
+        });
+        self.sources.retain(|source| !source.transfers.is_empty());
+
+        !self.sources.is_empty()
+    }
+
+    fn merge_source(&mut self, source: FundingSource) {
+        let Some(stored) = self
+            .sources
+            .iter_mut()
+            .find(|stored| stored.address == source.address)
+        else {
+            self.sources.push(source);
+            return
+        };
+
+        // the metadata of the source can change, the most recent one is kept
+        if source.last_block() >= stored.last_block() {
+            stored.kind = source.kind;
+            stored.fund = source.fund;
+        }
+        // a block that was already counted is replaced, so processing it
+        // again doesn't count its transfers twice
+        for transfers in source.transfers {
+            match stored
+                .transfers
+                .binary_search_by_key(&transfers.block, |stored| stored.block)
+            {
+                Ok(i) => stored.transfers[i] = transfers,
+                Err(i) => stored.transfers.insert(i, transfers),
+            }
+        }
+    }
+
+    /// Attributes the searcher to the fund with the largest weighted share of
+    /// its funding. Cex and bridge sources don't point to a fund, but lower
+    /// the confidence as the searcher could have been funded by anyone
+    /// through them.
+    pub fn attribution(&self) -> Option<FundAttribution> {
+        let total: f64 = self
+            .sources
+            .iter()
+            .map(|source| source.kind.weight() * source.transfer_count() as f64)
+            .sum();
+
+        let (fund, score) = self
+            .sources
+            .iter()
+            .filter(|source| !source.fund.is_none())
+            .map(|source| (source.fund, source.kind.weight() * source.transfer_count() as f64))
+            .into_grouping_map()
+            .sum()
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        (total > 0.0).then(|| FundAttribution { fund, confidence: score / total })
+    }
+
+    /// The earliest source that ties the searcher to a fund, which is shared
+    /// by the EOAs the fund splits its bundles across. Cex and bridge sources
+    /// fund unrelated accounts, so they are never the parent.
+    pub fn funding_parent(&self) -> Option<Address> {
+        self.sources
+            .iter()
+            .filter(|source| {
+                matches!(source.kind, FundingSourceKind::Fund | FundingSourceKind::Searcher)
+            })
+            .min_by_key(|source| (source.first_block(), std::cmp::Reverse(source.transfer_count())))
+            .map(|source| source.address)
+    }
+
+    /// Collects the transfers to known searcher EOAs in the block from the
+    /// tracked funding sources
+    pub fn from_block<DB: LibmdbxReader>(
+        block_number: u64,
+        tree: Arc<BlockTree<Action>>,
+        db: &DB,
+    ) -> eyre::Result<Vec<(Address, Self)>> {
+        let transfers = tree
+            .collect_all(
+                TreeSearchBuilder::default()
+                    .with_actions([Action::is_transfer, Action::is_eth_transfer]),
+            )
+            .flat_map(|(_, actions)| actions)
+            .filter_map(|action| match action {
+                Action::Transfer(transfer) => Some((transfer.from, transfer.to)),
+                Action::EthTransfer(transfer) => Some((transfer.from, transfer.to)),
+                _ => None,
+            })
+            .filter(|(from, to)| from != to)
+            .counts();
+
+        let searchers = db
+            .try_fetch_searcher_eoa_infos(transfers.keys().map(|(_, to)| *to).unique().collect())?;
+        let senders = transfers
+            .keys()
+            .filter(|(_, to)| searchers.contains_key(to))
+            .map(|(from, _)| *from)
+            .unique()
+            .collect_vec();
+
+        let metadata = db.try_fetch_address_metadatas(senders.clone())?;
+        let sending_searchers = db.try_fetch_searcher_eoa_infos(senders)?;
+
+        let mut funding: FastHashMap<Address, Self> = FastHashMap::default();
+        for ((from, to), count) in transfers {
+            if !searchers.contains_key(&to) {
+                continue
+            }
+            let Some((kind, fund)) =
+                FundingSourceKind::classify(metadata.get(&from), sending_searchers.get(&from))
+            else {
+                continue
+            };
+
+            funding
+                .entry(to)
+                .or_default()
+                .record(from, kind, fund, block_number, count as u64);
+        }
+
+        Ok(funding.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribution_is_weighted_by_source_kind() {
+        let mut funding = SearcherFunding::default();
+        assert_eq!(funding.attribution(), None);
+
+        funding.record(Address::with_last_byte(1), FundingSourceKind::Cex, Fund::None, 10, 4);
+        assert_eq!(funding.attribution(), None);
+
+        funding.record(
+            Address::with_last_byte(2),
+            FundingSourceKind::Fund,
+            Fund::Wintermute,
+            20,
+            1,
+        );
+        funding.record(
+            Address::with_last_byte(3),
+            FundingSourceKind::Searcher,
+            Fund::JaneStreet,
+            20,
+            1,
+        );
+        // 1.0 of 2.5 for wintermute, 0.5 for jane street
+        assert_eq!(
+            funding.attribution(),
+            Some(FundAttribution { fund: Fund::Wintermute, confidence: 0.4 })
+        );
+
+        let mut later = SearcherFunding::default();
+        later.record(
+            Address::with_last_byte(3),
+            FundingSourceKind::Searcher,
+            Fund::JaneStreet,
+            5,
+            3,
+        );
+        let merged = funding.merge(later);
+        let source = &merged.sources[2];
+        assert_eq!(
+            (source.transfer_count(), source.first_block(), source.last_block()),
+            (4, 5, 20)
+        );
+        // 2.0 of 4.0 for jane street
+        assert_eq!(
+            merged.attribution(),
+            Some(FundAttribution { fund: Fund::JaneStreet, confidence: 0.5 })
+        );
+    }
+
+    #[test]
+    fn funding_parent_is_the_earliest_fund_source() {
+        let mut funding = SearcherFunding::default();
+        funding.record(Address::with_last_byte(1), FundingSourceKind::Cex, Fund::None, 1, 10);
+        assert_eq!(funding.funding_parent(), None);
+
+        funding.record(
+            Address::with_last_byte(2),
+            FundingSourceKind::Searcher,
+            Fund::JaneStreet,
+            20,
+            1,
+        );
+        funding.record(
+            Address::with_last_byte(3),
+            FundingSourceKind::Fund,
+            Fund::Wintermute,
+            20,
+            2,
+        );
+        assert_eq!(funding.funding_parent(), Some(Address::with_last_byte(3)));
+
+        let mut earlier = SearcherFunding::default();
+        earlier.record(
+            Address::with_last_byte(2),
+            FundingSourceKind::Searcher,
+            Fund::JaneStreet,
+            5,
+            1,
+        );
+        assert_eq!(funding.merge(earlier).funding_parent(), Some(Address::with_last_byte(2)));
+    }
+
+    #[test]
+    fn processing_a_block_again_replaces_its_transfers() {
+        let record = |block, transfers| {
+            let mut funding = SearcherFunding::default();
+            funding.record(
+                Address::with_last_byte(1),
+                FundingSourceKind::Fund,
+                Fund::Wintermute,
+                block,
+                transfers,
+            );
+            funding
+        };
+
+        let funding = record(10, 2).merge(record(20, 1));
+        let rerun = funding.clone().merge(record(10, 2)).merge(record(20, 1));
+        assert_eq!(rerun, funding);
+        assert_eq!(rerun.sources.len(), 1);
+        assert_eq!(rerun.sources[0].transfer_count(), 3);
+
+        // the block's count is replaced, not added to
+        let reclassified = rerun.merge(record(10, 1));
+        assert_eq!(reclassified.sources[0].transfer_count(), 2);
+    }
+
+    #[test]
+    fn removing_reorged_blocks_drops_their_transfers() {
+        let mut funding = SearcherFunding::default();
+        funding.record(
+            Address::with_last_byte(1),
+            FundingSourceKind::Fund,
+            Fund::Wintermute,
+            10,
+            2,
+        );
+        funding.record(
+            Address::with_last_byte(1),
+            FundingSourceKind::Fund,
+            Fund::Wintermute,
+            20,
+            1,
+        );
+        funding.record(Address::with_last_byte(2), FundingSourceKind::Cex, Fund::None, 21, 3);
+
+        assert!(funding.remove_blocks(&(20..=25)));
+        assert_eq!(funding.sources.len(), 1);
+        assert_eq!(funding.sources[0].transfer_count(), 2);
+        assert_eq!(funding.sources[0].last_block(), 10);
+
+        assert!(!funding.remove_blocks(&(0..=10)));
+    }
+}
@@ -0,0 +1,216 @@
+use alloy_primitives::Address;
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::traits::LibmdbxReader,
+    mev::{Bundle, Mev},
+    normalized_actions::NormalizedAction,
+    serde_utils::{address, option_address},
+    tree::{collect_address_set_for_accounting, BlockTree},
+    FastHashMap, FastHashSet,
+};
+
+/// Net change of a token in a searcher's inventory over all of their bundles
+/// in a block. Unlike the per bundle balance deltas, the snapshots of
+/// consecutive blocks can be combined to follow a searcher's inventory over
+/// time, e.g. to measure how long a cex-dex searcher carries a position before
+/// hedging it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SearcherInventoryDelta {
+    pub block_number: u64,
+    #[serde(with = "address")]
+    pub eoa:          Address,
+    #[serde(with = "option_address")]
+    pub mev_contract: Option<Address>,
+    #[serde(with = "address")]
+    pub token:        Address,
+    pub symbol:       String,
+    /// Net amount of the token the searcher's addresses received in the block
+    pub amount:       f64,
+    /// Usd value of the net amount at the time of the bundles
+    pub usd_value:    f64,
+    /// Number of bundles of the searcher that moved the token
+    pub bundle_count: u64,
+}
+
+impl SearcherInventoryDelta {
+    /// Aggregates the balance deltas of the searcher's addresses in the given
+    /// bundles per searcher and token. The deltas of the bundles also cover
+    /// the pools and victims the searcher traded with, which aren't part of
+    /// the searcher's inventory. Tokens whose net change is zero are omitted.
+    pub fn from_bundles<V: NormalizedAction, DB: LibmdbxReader>(
+        bundles: &[Bundle],
+        tree: &BlockTree<V>,
+        db: &DB,
+    ) -> Vec<Self> {
+        Self::aggregate(bundles.iter().map(|bundle| {
+            let tx_infos = bundle
+                .data
+                .mev_transaction_hashes()
+                .into_iter()
+                .filter_map(|tx_hash| tree.get_tx_info(tx_hash, db))
+                .collect_vec();
+
+            let mut addresses = collect_address_set_for_accounting(&tx_infos);
+            addresses.insert(bundle.header.eoa);
+            addresses.extend(bundle.header.mev_contract);
+
+            (bundle, addresses)
+        }))
+    }
+
+    /// Aggregates the deltas of the given addresses of each bundle
+    fn aggregate<'a>(
+        bundles: impl Iterator<Item = (&'a Bundle, FastHashSet<Address>)>,
+    ) -> Vec<Self> {
+        let mut inventory: FastHashMap<(Address, Address), Self> = FastHashMap::default();
+
+        for (bundle, addresses) in bundles {
+            let header = &bundle.header;
+            let deltas = header
+                .balance_deltas
+                .iter()
+                .flat_map(|accounting| &accounting.address_deltas)
+                .filter(|address| addresses.contains(&address.address))
+                .flat_map(|address| &address.token_deltas);
+
+            let mut moved_tokens = Vec::new();
+            for delta in deltas {
+                let entry = inventory
+                    .entry((header.eoa, delta.token.address))
+                    .or_insert_with(|| Self {
+                        block_number: header.block_number,
+                        eoa: header.eoa,
+                        mev_contract: header.mev_contract,
+                        token: delta.token.address,
+                        symbol: delta.token.inner.symbol.clone(),
+                        ..Default::default()
+                    });
+
+                entry.amount += delta.amount;
+                entry.usd_value += delta.usd_value;
+                if !moved_tokens.contains(&delta.token.address) {
+                    moved_tokens.push(delta.token.address);
+                    entry.bundle_count += 1;
+                }
+            }
+        }
+
+        inventory
+            .into_values()
+            .filter(|delta| delta.amount != 0.0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        mev::{
+            AddressBalanceDeltas, BundleData, BundleHeader, TokenBalanceDelta,
+            TransactionAccounting,
+        },
+    };
+
+    fn address_deltas(address: Address, deltas: Vec<(Address, f64)>) -> AddressBalanceDeltas {
+        let token_deltas = deltas
+            .into_iter()
+            .map(|(token, amount)| TokenBalanceDelta {
+                token: TokenInfoWithAddress {
+                    address: token,
+                    inner:   TokenInfo::new(18, "TKN".to_string()),
+                },
+                amount,
+                usd_value: amount * 2.0,
+            })
+            .collect();
+
+        AddressBalanceDeltas { address, name: None, token_deltas }
+    }
+
+    fn bundle(eoa: Address, deltas: Vec<(Address, f64)>) -> Bundle {
+        bundle_with_counterparty(eoa, deltas, vec![])
+    }
+
+    /// Bundle whose deltas also cover the pool the searcher traded with
+    fn bundle_with_counterparty(
+        eoa: Address,
+        deltas: Vec<(Address, f64)>,
+        pool_deltas: Vec<(Address, f64)>,
+    ) -> Bundle {
+        Bundle {
+            header: BundleHeader {
+                block_number: 1,
+                eoa,
+                balance_deltas: vec![TransactionAccounting {
+                    tx_hash:        Default::default(),
+                    address_deltas: vec![
+                        address_deltas(eoa, deltas),
+                        address_deltas(Address::with_last_byte(100), pool_deltas),
+                    ],
+                }],
+                ..Default::default()
+            },
+            data:   BundleData::default(),
+        }
+    }
+
+    fn from_bundles(bundles: &[Bundle]) -> Vec<SearcherInventoryDelta> {
+        SearcherInventoryDelta::aggregate(
+            bundles
+                .iter()
+                .map(|bundle| (bundle, FastHashSet::from_iter([bundle.header.eoa]))),
+        )
+    }
+
+    #[test]
+    fn aggregates_inventory_per_searcher_and_token() {
+        let (searcher, other) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let (weth, usdc) = (Address::with_last_byte(10), Address::with_last_byte(11));
+
+        let bundles = vec![
+            bundle(searcher, vec![(weth, 1.0), (usdc, -1.0)]),
+            bundle(searcher, vec![(weth, 0.5), (usdc, 1.0)]),
+            bundle(other, vec![(weth, -2.0)]),
+        ];
+
+        let mut inventory = from_bundles(&bundles);
+        inventory.sort_by_key(|delta| (delta.eoa, delta.token));
+
+        // the usdc of the searcher nets out to zero
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[0].eoa, searcher);
+        assert_eq!(inventory[0].token, weth);
+        assert_eq!(inventory[0].amount, 1.5);
+        assert_eq!(inventory[0].usd_value, 3.0);
+        assert_eq!(inventory[0].bundle_count, 2);
+        assert_eq!(inventory[1].eoa, other);
+        assert_eq!(inventory[1].amount, -2.0);
+    }
+
+    #[test]
+    fn ignores_the_deltas_of_counterparties() {
+        let searcher = Address::with_last_byte(1);
+        let (weth, usdc) = (Address::with_last_byte(10), Address::with_last_byte(11));
+
+        let bundles = vec![bundle_with_counterparty(
+            searcher,
+            vec![(weth, 1.0), (usdc, -2000.0)],
+            vec![(weth, -1.0), (usdc, 2000.0)],
+        )];
+
+        let mut inventory = from_bundles(&bundles);
+        inventory.sort_by_key(|delta| delta.token);
+
+        assert_eq!(inventory.len(), 2);
+        assert!(inventory.iter().all(|delta| delta.eoa == searcher));
+        assert_eq!(inventory[0].amount, 1.0);
+        assert_eq!(inventory[1].amount, -2000.0);
+    }
+}
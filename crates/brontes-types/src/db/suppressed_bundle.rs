@@ -0,0 +1,49 @@
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    denylist::Exclusion,
+    mev::{Bundle, MevType},
+    serde_utils::{address, option_address, txhash},
+};
+
+/// A bundle that was dropped because it touched a denylisted token or pool,
+/// kept so that the exclusions of a run can be audited.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct SuppressedBundle {
+    pub block_number:   u64,
+    #[serde(with = "txhash")]
+    pub tx_hash:        TxHash,
+    pub mev_type:       MevType,
+    #[serde(with = "address")]
+    pub eoa:            Address,
+    #[serde(with = "option_address")]
+    pub mev_contract:   Option<Address>,
+    pub profit_usd:     f64,
+    /// The denylisted token or pool the bundle touched
+    #[serde(with = "address")]
+    pub denied_address: Address,
+    /// `token` or `pool`
+    pub kind:           String,
+    pub reason:         String,
+}
+
+impl SuppressedBundle {
+    pub fn new(bundle: &Bundle, exclusion: Exclusion) -> Self {
+        let header = &bundle.header;
+        Self {
+            block_number:   header.block_number,
+            tx_hash:        header.tx_hash,
+            mev_type:       header.mev_type,
+            eoa:            header.eoa,
+            mev_contract:   header.mev_contract,
+            profit_usd:     header.profit_usd,
+            denied_address: exclusion.address,
+            kind:           exclusion.kind.to_string(),
+            reason:         exclusion.reason.to_string(),
+        }
+    }
+}
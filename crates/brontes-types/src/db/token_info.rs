@@ -4,25 +4,35 @@ use std::{
 };
 
 use alloy_primitives::Address;
+#[cfg(feature = "native")]
 use clickhouse::{DbRow, Row};
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
 use super::clickhouse_serde::token_info::token_info_des;
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
 use crate::{
-    constants::{USDC_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
-    db::redefined_types::primitives::AddressRedefined,
+    chain::chain_spec,
+    constants::{USDC_ADDRESS, USDT_ADDRESS},
     implement_table_value_codecs_with_zc,
     serde_utils::addresss,
 };
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct TokenInfoWithAddress {
     #[serde(with = "addresss")]
     pub address: Address,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(deserialize_with = "token_info_des::deserialize")]
     pub inner:   TokenInfo,
 }
@@ -30,30 +40,24 @@ pub struct TokenInfoWithAddress {
 impl TokenInfoWithAddress {
     pub fn native_eth() -> Self {
         Self {
-            inner:   TokenInfo { decimals: 18, symbol: "ETH".to_string() },
-            address: WETH_ADDRESS,
+            inner:   TokenInfo::new(18, "ETH".to_string()),
+            address: chain_spec().wrapped_native,
         }
     }
 
     pub fn weth() -> Self {
         Self {
-            inner:   TokenInfo { decimals: 18, symbol: "WETH".to_string() },
-            address: WETH_ADDRESS,
+            inner:   TokenInfo::new(18, "WETH".to_string()),
+            address: chain_spec().wrapped_native,
         }
     }
 
     pub fn usdt() -> Self {
-        Self {
-            inner:   TokenInfo { decimals: 6, symbol: "USDT".to_string() },
-            address: USDT_ADDRESS,
-        }
+        Self { inner: TokenInfo::new(6, "USDT".to_string()), address: USDT_ADDRESS }
     }
 
     pub fn usdc() -> Self {
-        Self {
-            inner:   TokenInfo { decimals: 6, symbol: "USDC".to_string() },
-            address: USDC_ADDRESS,
-        }
+        Self { inner: TokenInfo::new(6, "USDC".to_string()), address: USDC_ADDRESS }
     }
 
     pub fn clickhouse_fmt(&self) -> (String, String) {
@@ -96,34 +100,103 @@ impl Serialize for TokenInfoWithAddress {
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for TokenInfoWithAddress {
     const COLUMN_NAMES: &'static [&'static str] = &["address", "symbol", "decimals"];
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Default,
-    Row,
-    Deserialize,
-    Serialize,
-    rSerialize,
-    rDeserialize,
-    Archive,
-    PartialEq,
-    Eq,
-    Hash,
-)]
+/// Amounts of a token with more decimals than this can't be represented in a
+/// `U256`
+pub const MAX_TOKEN_DECIMALS: u8 = 77;
+/// Decimals above this are non-standard and most likely a misconfigured or
+/// malicious token
+pub const STANDARD_TOKEN_DECIMALS: u8 = 18;
+
+/// Why the decimals a token reports can't be trusted for pricing
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+pub enum DecimalsAnomaly {
+    /// The token reports no decimals
+    Zero,
+    /// The token reports more than [`STANDARD_TOKEN_DECIMALS`]. Holds the
+    /// reported decimals as they might have been clamped
+    AboveStandard(u8),
+    /// The token reported different decimals than when it was first seen.
+    /// Holds the previously stored decimals
+    Changed(u8),
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub struct TokenInfo {
-    pub decimals: u8,
-    pub symbol:   String,
+    pub decimals:         u8,
+    pub symbol:           String,
+    #[serde(default)]
+    pub decimals_anomaly: Option<DecimalsAnomaly>,
 }
 
 impl TokenInfo {
+    /// Validates the decimals reported by the token, clamping them to
+    /// [`MAX_TOKEN_DECIMALS`] and flagging them if they are out of the
+    /// standard range
     pub fn new(decimals: u8, symbol: String) -> Self {
-        Self { symbol, decimals }
+        let decimals_anomaly = match decimals {
+            0 => Some(DecimalsAnomaly::Zero),
+            d if d > STANDARD_TOKEN_DECIMALS => Some(DecimalsAnomaly::AboveStandard(d)),
+            _ => None,
+        };
+
+        Self { symbol, decimals: decimals.min(MAX_TOKEN_DECIMALS), decimals_anomaly }
+    }
+
+    /// Flags the token if its decimals differ from the ones that were
+    /// previously stored for it. Once a token is flagged as changed it stays
+    /// flagged.
+    pub fn checked_against(mut self, previous: &TokenInfo) -> Self {
+        if let Some(DecimalsAnomaly::Changed(_)) = previous.decimals_anomaly {
+            self.decimals_anomaly = previous.decimals_anomaly;
+        } else if previous.decimals != self.decimals {
+            self.decimals_anomaly = Some(DecimalsAnomaly::Changed(previous.decimals));
+        }
+
+        self
+    }
+
+    /// Whether amounts of this token can't be trusted to be scaled correctly
+    pub fn is_anomalous(&self) -> bool {
+        self.decimals_anomaly.is_some()
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(TokenInfo);
 implement_table_value_codecs_with_zc!(TokenInfo);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_pathological_decimals() {
+        assert!(!TokenInfo::new(6, "USDC".to_string()).is_anomalous());
+        assert_eq!(
+            TokenInfo::new(0, "A".to_string()).decimals_anomaly,
+            Some(DecimalsAnomaly::Zero)
+        );
+
+        let clamped = TokenInfo::new(u8::MAX, "B".to_string());
+        assert_eq!(clamped.decimals, MAX_TOKEN_DECIMALS);
+        assert_eq!(clamped.decimals_anomaly, Some(DecimalsAnomaly::AboveStandard(u8::MAX)));
+
+        let changed = TokenInfo::new(8, "C".to_string())
+            .checked_against(&TokenInfo::new(18, "C".to_string()));
+        assert_eq!(changed.decimals_anomaly, Some(DecimalsAnomaly::Changed(18)));
+        // stays flagged even once the decimals are back to what they were
+        assert!(TokenInfo::new(18, "C".to_string())
+            .checked_against(&changed)
+            .is_anomalous());
+    }
+}
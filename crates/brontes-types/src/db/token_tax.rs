@@ -0,0 +1,285 @@
+use alloy_primitives::Address;
+use malachite::{num::basic::traits::Zero, Rational};
+#[cfg(feature = "rkyv")]
+use redefined::{self_convert_redefined, Redefined};
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    implement_table_value_codecs_with_zc,
+    normalized_actions::{Action, NormalizedSwapWithFee},
+    BlockTree, FastHashMap, ToFloatNearest, TreeSearchBuilder,
+};
+
+/// Tokens that take at least this share of a transfer, in basis points, can't
+/// be sold at any reasonable price
+pub const HONEYPOT_TAX_BPS: u64 = 5_000;
+
+const BPS: u64 = 10_000;
+
+/// Number of most recent blocks whose observed rates are kept per token
+pub const MAX_TAX_BLOCKS: usize = 256;
+
+/// Transfer tax of a fee on transfer token, inferred from the swaps where the
+/// pool received or sent a different amount than was transferred. The rates
+/// are kept per block, so that processing a block again replaces the rates it
+/// contributed instead of counting them twice.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+pub struct TokenTax {
+    /// Rates observed in the most recent [`MAX_TAX_BLOCKS`] blocks the token
+    /// was taxed in, sorted by block
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub blocks:      Vec<BlockTax>,
+    /// First block a taxed transfer of the token was observed in
+    pub first_block: u64,
+}
+
+/// Tax rates observed in a single block
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+pub struct BlockTax {
+    pub block:        u64,
+    /// Sum of the observed tax rates in basis points
+    pub rate_bps_sum: u64,
+    pub min_rate_bps: u64,
+    pub max_rate_bps: u64,
+    /// Number of taxed transfers the rate was observed on
+    pub observations: u64,
+}
+
+#[cfg(feature = "rkyv")]
+self_convert_redefined!(BlockTax);
+
+implement_table_value_codecs_with_zc!(TokenTaxRedefined);
+
+impl BlockTax {
+    pub fn observe(block: u64, rate_bps: u64) -> Self {
+        Self {
+            block,
+            rate_bps_sum: rate_bps,
+            min_rate_bps: rate_bps,
+            max_rate_bps: rate_bps,
+            observations: 1,
+        }
+    }
+
+    /// Adds up two sets of rates observed in the same block
+    fn add(self, other: Self) -> Self {
+        if self.observations == 0 {
+            return other
+        }
+
+        Self {
+            block:        self.block,
+            rate_bps_sum: self.rate_bps_sum + other.rate_bps_sum,
+            min_rate_bps: self.min_rate_bps.min(other.min_rate_bps),
+            max_rate_bps: self.max_rate_bps.max(other.max_rate_bps),
+            observations: self.observations + other.observations,
+        }
+    }
+}
+
+impl From<BlockTax> for TokenTax {
+    fn from(tax: BlockTax) -> Self {
+        Self { blocks: vec![tax], first_block: tax.block }
+    }
+}
+
+impl TokenTax {
+    pub fn observe(block: u64, rate_bps: u64) -> Self {
+        BlockTax::observe(block, rate_bps).into()
+    }
+
+    /// Merges the rates of other blocks into the stored ones. The rates of a
+    /// block that is already stored are replaced, so merging the same block
+    /// again leaves the tax as it was.
+    pub fn merge(mut self, other: Self) -> Self {
+        if self.blocks.is_empty() {
+            return other
+        }
+        if other.blocks.is_empty() {
+            return self
+        }
+
+        self.first_block = self.first_block.min(other.first_block);
+        for tax in other.blocks {
+            match self
+                .blocks
+                .binary_search_by_key(&tax.block, |stored| stored.block)
+            {
+                Ok(i) => self.blocks[i] = tax,
+                Err(i) => self.blocks.insert(i, tax),
+            }
+        }
+
+        let excess = self.blocks.len().saturating_sub(MAX_TAX_BLOCKS);
+        self.blocks.drain(..excess);
+        self
+    }
+
+    /// Number of taxed transfers the rate was observed on
+    pub fn observations(&self) -> u64 {
+        self.blocks.iter().map(|tax| tax.observations).sum()
+    }
+
+    /// Average tax rate over the observed transfers in basis points
+    pub fn rate_bps(&self) -> u64 {
+        let observations = self.observations();
+        if observations == 0 {
+            return 0
+        }
+        self.blocks.iter().map(|tax| tax.rate_bps_sum).sum::<u64>() / observations
+    }
+
+    pub fn min_rate_bps(&self) -> u64 {
+        self.blocks
+            .iter()
+            .map(|tax| tax.min_rate_bps)
+            .min()
+            .unwrap_or_default()
+    }
+
+    pub fn max_rate_bps(&self) -> u64 {
+        self.blocks
+            .iter()
+            .map(|tax| tax.max_rate_bps)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Last block a taxed transfer of the token was observed in
+    pub fn last_block(&self) -> u64 {
+        self.blocks.last().map(|tax| tax.block).unwrap_or_default()
+    }
+
+    pub fn is_honeypot(&self) -> bool {
+        self.rate_bps() >= HONEYPOT_TAX_BPS
+    }
+
+    /// The amount that is left of `amount` once it is transferred out
+    pub fn after_tax(&self, amount: &Rational) -> Rational {
+        let rate = self.rate_bps().min(BPS);
+        amount * Rational::from(BPS - rate) / Rational::from(BPS)
+    }
+
+    /// Collects the tax rates of the taxed swaps in the block. The
+    /// `account_for_tax_tokens` tree pass has to have run on the tree, as it
+    /// is what notes the fee on the swaps.
+    pub fn from_tree(block_number: u64, tree: &BlockTree<Action>) -> Vec<(Address, Self)> {
+        let search = TreeSearchBuilder::default().with_action(Action::is_swap_with_fee);
+
+        let mut taxes: FastHashMap<Address, BlockTax> = FastHashMap::default();
+        for action in tree.tx_roots.iter().flat_map(|root| root.collect(&search)) {
+            let Action::SwapWithFee(swap) = action else { continue };
+            let Some(rate_bps) = tax_rate_bps(&swap) else { continue };
+
+            let observed = BlockTax::observe(block_number, rate_bps);
+            let tax = taxes.remove(&swap.fee_token.address).unwrap_or_default();
+            taxes.insert(swap.fee_token.address, tax.add(observed));
+        }
+
+        taxes
+            .into_iter()
+            .map(|(token, tax)| (token, tax.into()))
+            .collect()
+    }
+}
+
+/// Share of the transferred amount the token took. The fee is already applied
+/// to the swap, the amount in includes it and the amount out excludes it.
+fn tax_rate_bps(swap: &NormalizedSwapWithFee) -> Option<u64> {
+    let transferred = if swap.fee_token.address == swap.token_in.address {
+        swap.amount_in.clone()
+    } else if swap.fee_token.address == swap.token_out.address {
+        &swap.amount_out + &swap.fee_amount
+    } else {
+        return None
+    };
+
+    if transferred <= Rational::ZERO || swap.fee_amount <= Rational::ZERO {
+        return None
+    }
+
+    let rate = (&swap.fee_amount / transferred * Rational::from(BPS)).to_float();
+    (rate >= 1.0).then(|| (rate.round() as u64).min(BPS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::token_info::TokenInfoWithAddress, normalized_actions::NormalizedSwap};
+
+    fn taxed_swap(
+        amount_in: u64,
+        amount_out: u64,
+        fee: u64,
+        fee_on_in: bool,
+    ) -> NormalizedSwapWithFee {
+        let token_in =
+            TokenInfoWithAddress { address: Address::with_last_byte(1), ..Default::default() };
+        let token_out =
+            TokenInfoWithAddress { address: Address::with_last_byte(2), ..Default::default() };
+        let fee_token = if fee_on_in { token_in.clone() } else { token_out.clone() };
+
+        NormalizedSwapWithFee {
+            swap: NormalizedSwap {
+                token_in,
+                token_out,
+                amount_in: Rational::from(amount_in),
+                amount_out: Rational::from(amount_out),
+                ..Default::default()
+            },
+            fee_token,
+            fee_amount: Rational::from(fee),
+        }
+    }
+
+    #[test]
+    fn infers_and_merges_tax_rates() {
+        // 5 of the 100 transferred into the pool were taken
+        assert_eq!(tax_rate_bps(&taxed_swap(100, 40, 5, true)), Some(500));
+        // the recipient got 90 of the 100 the pool sent
+        assert_eq!(tax_rate_bps(&taxed_swap(40, 90, 10, false)), Some(1_000));
+        assert_eq!(tax_rate_bps(&taxed_swap(40, 90, 0, false)), None);
+
+        let tax = TokenTax::observe(10, 500).merge(TokenTax::observe(12, 1_000));
+        assert_eq!(tax.rate_bps(), 750);
+        assert_eq!((tax.min_rate_bps(), tax.max_rate_bps()), (500, 1_000));
+        assert_eq!((tax.first_block, tax.last_block()), (10, 12));
+        assert_eq!(TokenTax::default().merge(tax.clone()), tax);
+
+        assert_eq!(tax.after_tax(&Rational::from(100)), Rational::from_unsigneds(185u64, 2u64));
+        assert!(!tax.is_honeypot());
+        assert!(TokenTax::observe(10, 9_900).is_honeypot());
+    }
+
+    #[test]
+    fn processing_a_block_again_replaces_its_rates() {
+        let block_10: TokenTax = BlockTax::observe(10, 500)
+            .add(BlockTax::observe(10, 700))
+            .into();
+        let tax = block_10.clone().merge(TokenTax::observe(12, 1_000));
+        assert_eq!(tax.observations(), 3);
+
+        let rerun = tax
+            .clone()
+            .merge(block_10)
+            .merge(TokenTax::observe(12, 1_000));
+        assert_eq!(rerun, tax);
+        assert_eq!(rerun.rate_bps(), 733);
+        assert_eq!(rerun.after_tax(&Rational::from(100)), tax.after_tax(&Rational::from(100)));
+
+        // only the most recent blocks are kept
+        let tax = (0..MAX_TAX_BLOCKS as u64 + 10)
+            .fold(TokenTax::default(), |tax, block| tax.merge(TokenTax::observe(block, 100)));
+        assert_eq!(tax.blocks.len(), MAX_TAX_BLOCKS);
+        assert_eq!(tax.first_block, 0);
+        assert_eq!(tax.blocks[0].block, 10);
+    }
+}
@@ -1,21 +1,30 @@
 use alloy_primitives::{Log, LogData};
-use clickhouse::Row;
-use redefined::Redefined;
-use reth_rpc_types::trace::parity::{
+use alloy_rpc_types_trace::parity::{
     Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput, RewardAction, RewardType,
     SelfdestructAction, TraceOutput, TransactionTrace,
 };
+#[cfg(feature = "native")]
+use clickhouse::Row;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "rkyv")]
 use super::redefined_types::primitives::*;
 use crate::{
     implement_table_value_codecs_with_zc,
     structured_trace::{DecodedCallData, TransactionTraceWithLogs, TxTrace},
 };
 
-#[derive(Debug, Default, PartialEq, Row, Clone, Serialize, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct TxTracesInner {
     pub traces: Option<Vec<TxTrace>>,
 }
@@ -28,16 +37,17 @@ impl TxTracesInner {
 
 implement_table_value_codecs_with_zc!(TxTracesInnerRedefined);
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Debug,
     Clone,
-    Redefined,
     PartialEq,
     serde::Serialize,
+    Default,
+    Redefined,
     rSerialize,
     rDeserialize,
     rkyv::Archive,
-    Default,
 )]
 #[redefined(TxTrace)]
 pub struct TxTraceRedefined {
@@ -49,22 +59,30 @@ pub struct TxTraceRedefined {
     pub tx_index:        u64,
     // False if the transaction reverted
     pub is_success:      bool,
+    pub gas_refunded:    u128,
+    pub access_list_gas: u128,
+    pub blob_gas_used:   u128,
+    pub blob_gas_price:  u128,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
-    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+    Debug, Clone, PartialEq, serde::Serialize, Redefined, rSerialize, rDeserialize, rkyv::Archive,
 )]
 #[redefined(TransactionTraceWithLogs)]
 pub struct TransactionTraceWithLogsRedefined {
-    pub trace:        TransactionTraceRedefined,
-    pub logs:         Vec<LogRedefined>,
-    pub msg_sender:   AddressRedefined,
-    pub trace_idx:    u64,
-    pub decoded_data: Option<DecodedCallData>,
+    pub trace:          TransactionTraceRedefined,
+    pub logs:           Vec<LogRedefined>,
+    #[cfg(feature = "provenance")]
+    pub tx_log_indices: Vec<u64>,
+    pub msg_sender:     AddressRedefined,
+    pub trace_idx:      u64,
+    pub decoded_data:   Option<DecodedCallData>,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
-    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+    Debug, Clone, PartialEq, serde::Serialize, Redefined, rSerialize, rDeserialize, rkyv::Archive,
 )]
 #[redefined(Log)]
 pub struct LogRedefined {
@@ -72,8 +90,9 @@ pub struct LogRedefined {
     pub data:    LogDataRedefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
-    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+    Debug, Clone, PartialEq, serde::Serialize, Redefined, rSerialize, rDeserialize, rkyv::Archive,
 )]
 #[redefined(LogData)]
 #[redefined_attr(to_source = "LogData::new_unchecked(self.topics.iter().copied().map(Into::into).\
@@ -84,6 +103,7 @@ pub struct LogDataRedefined {
     pub data:   BytesRedefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -104,6 +124,7 @@ pub struct TransactionTraceRedefined {
     pub trace_address: Vec<usize>,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -123,6 +144,7 @@ pub enum ActionRedefined {
     Reward(RewardActionRedefined),
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -144,6 +166,7 @@ pub struct CallActionRedefined {
     pub value:     U256Redefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -163,6 +186,7 @@ pub struct CreateActionRedefined {
     pub value: U256Redefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -181,6 +205,7 @@ pub struct SelfdestructActionRedefined {
     pub refund_address: AddressRedefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -199,6 +224,7 @@ pub struct RewardActionRedefined {
     pub value:       U256Redefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -216,6 +242,7 @@ pub enum RewardTypeRedefined {
     Uncle,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -236,6 +263,7 @@ pub enum CallTypeRedefined {
     StaticCall,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -253,6 +281,7 @@ pub enum TraceOutputRedefined {
     Create(CreateOutputRedefined),
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
@@ -270,6 +299,7 @@ pub struct CallOutputRedefined {
     pub output:   BytesRedefined,
 }
 
+#[cfg(feature = "rkyv")]
 #[derive(
     Clone,
     Debug,
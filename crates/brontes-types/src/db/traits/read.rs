@@ -1,11 +1,27 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, Selector};
 
 use crate::{
     db::{
-        address_metadata::AddressMetadata, address_to_protocol_info::ProtocolInfo,
-        builder::BuilderInfo, cex::trades::CexTradeMap, dex::DexQuotes, metadata::Metadata,
-        mev_block::MevBlockWithClassified, searcher::SearcherInfo,
+        address_metadata::AddressMetadata,
+        address_to_protocol_info::ProtocolInfo,
+        backfill_checkpoint::BackfillCheckpoint,
+        builder::BuilderInfo,
+        builder_pnl::{BuilderPnl, BuilderPnlStats},
+        cex::trades::CexTradeMap,
+        classification_failure::BlockClassificationFailures,
+        curve_pool_params::CurvePoolParams,
+        dex::DexQuotes,
+        function_selector::FunctionSelector,
+        inspector_run::BlockInspectorRuns,
+        metadata::Metadata,
+        mev_block::MevBlockWithClassified,
+        pool_fee_tier::PoolFeeTier,
+        protocol_metadata::ProtocolMetadata,
+        sanctions::SanctionInfo,
+        searcher::SearcherInfo,
+        searcher_funding::{FundAttribution, SearcherFunding},
         token_info::TokenInfoWithAddress,
+        token_tax::TokenTax,
     },
     pair::Pair,
     structured_trace::TxTrace,
@@ -95,6 +111,103 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
 
     fn fetch_all_builder_info(&self) -> eyre::Result<Vec<(Address, BuilderInfo)>>;
 
+    fn try_fetch_sanction_info(&self, address: Address) -> eyre::Result<Option<SanctionInfo>>;
+
+    /// Returns the subset of the given addresses that are on a sanctions list
+    fn fetch_sanctioned(
+        &self,
+        addresses: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SanctionInfo>> {
+        addresses
+            .into_iter()
+            .filter_map(|address| {
+                self.try_fetch_sanction_info(address)
+                    .map(|info| info.map(|info| (address, info)))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// Returns the parameters of a curve stableswap pool as of the last
+    /// parameter change that was classified, if any
+    fn try_fetch_curve_pool_params(
+        &self,
+        address: Address,
+    ) -> eyre::Result<Option<CurvePoolParams>>;
+
+    /// Returns the known sources that funded the searcher EOA
+    fn try_fetch_searcher_funding(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<SearcherFunding>>;
+
+    /// Returns the fund the searcher EOA is most likely run by, based on the
+    /// sources that funded it
+    fn try_fetch_fund_attribution(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<FundAttribution>> {
+        Ok(self
+            .try_fetch_searcher_funding(searcher_eoa)?
+            .and_then(|funding| funding.attribution()))
+    }
+
+    /// Returns the transfer tax inferred for the token, `None` if the token
+    /// was never seen taking a fee on a swap
+    fn try_fetch_token_tax(&self, token: Address) -> eyre::Result<Option<TokenTax>>;
+
+    /// Returns the fee tier the pool was created with, `None` for pools of
+    /// protocols without fee tiers
+    fn try_fetch_pool_fee_tier(&self, pool: Address) -> eyre::Result<Option<PoolFeeTier>>;
+
+    /// Returns the timings and result counts of the inspectors that were run
+    /// on the block
+    fn try_fetch_inspector_runs(&self, block_num: u64) -> eyre::Result<Option<BlockInspectorRuns>>;
+
+    /// Returns the failures the classifier recovered from on the block
+    fn try_fetch_classification_failures(
+        &self,
+        block_num: u64,
+    ) -> eyre::Result<Option<BlockClassificationFailures>>;
+
+    /// Returns the revenue, proposer payment and subsidy of the builder of
+    /// the block
+    fn try_fetch_builder_pnl(&self, block_num: u64) -> eyre::Result<Option<BuilderPnl>>;
+
+    /// Returns the builder pnl of every processed block in the inclusive range
+    fn fetch_builder_pnls(&self, start_block: u64, end_block: u64)
+        -> eyre::Result<Vec<BuilderPnl>>;
+
+    /// Returns the pnl of each builder aggregated over the blocks it built in
+    /// the inclusive range
+    fn fetch_builder_pnl_stats(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<BuilderPnlStats>> {
+        Ok(BuilderPnlStats::from_blocks(&self.fetch_builder_pnls(start_block, end_block)?))
+    }
+
+    /// Returns the factories, routers and fee tiers registered for the
+    /// protocol
+    fn try_fetch_protocol_metadata(
+        &self,
+        protocol: Protocol,
+    ) -> eyre::Result<Option<ProtocolMetadata>>;
+
+    /// Returns the metadata of all protocols in the registry
+    fn fetch_protocol_registry(&self) -> eyre::Result<Vec<ProtocolMetadata>>;
+
+    /// Returns the signature of a function selector, if it is known
+    fn try_fetch_function_selector(
+        &self,
+        selector: Selector,
+    ) -> eyre::Result<Option<FunctionSelector>>;
+
+    /// Returns the progress of every backfilled range, keyed by the first
+    /// block of the range
+    fn fetch_backfill_checkpoints(&self) -> eyre::Result<Vec<(u64, BackfillCheckpoint)>>;
+
     fn get_metadata(&self, block_num: u64, quote_asset: Address) -> eyre::Result<Metadata>;
 
     fn get_cex_trades(&self, block: u64) -> eyre::Result<CexTradeMap>;
@@ -118,6 +231,9 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
         end_block: u64,
     ) -> eyre::Result<Vec<MevBlockWithClassified>>;
 
+    /// The results of a single block, `None` if it wasn't processed
+    fn try_fetch_mev_block(&self, block: u64) -> eyre::Result<Option<MevBlockWithClassified>>;
+
     fn fetch_all_mev_blocks(
         &self,
         start_block: Option<u64>,
@@ -140,6 +256,13 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
 
     fn get_protocol_details(&self, address: Address) -> eyre::Result<ProtocolInfo>;
 
+    /// Loads the protocol and token info of the addresses with a single read
+    /// transaction, so the lookups made while classifying a block are served
+    /// from memory instead of each opening their own
+    fn prefetch_classification_data(&self, _addresses: &[Address]) -> eyre::Result<()> {
+        Ok(())
+    }
+
     /// returns protocol details with the tokens sorted from smallest to
     /// biggest. This is needed as for some reason the tokens in the
     /// database for a given protocol don't seems to always be ordered
@@ -1,10 +1,33 @@
+use std::ops::RangeInclusive;
+
 use alloy_primitives::Address;
 use futures::Future;
 
 use crate::{
     db::{
-        address_metadata::AddressMetadata, block_analysis::BlockAnalysis, builder::BuilderInfo,
-        dex::DexQuotes, searcher::SearcherInfo,
+        address_metadata::AddressMetadata,
+        backfill_checkpoint::BackfillCheckpoint,
+        block_analysis::BlockAnalysis,
+        block_provenance::BlockProvenance,
+        builder::BuilderInfo,
+        builder_pnl::BuilderPnl,
+        classification_failure::ClassificationFailure,
+        curve_pool_params::CurveParamsUpdate,
+        dex::DexQuotes,
+        function_selector::FunctionSelector,
+        gas_bids::{GasBid, SearcherBlockBids},
+        inspector_run::InspectorRun,
+        mev_block::MevBlockWithClassified,
+        pool_fee_tier::PoolFeeTier,
+        possible_mev_timeline::PossibleMevTimeline,
+        protocol_metadata::ProtocolMetadata,
+        searcher::SearcherInfo,
+        searcher_fingerprint::SearcherFingerprint,
+        searcher_funding::SearcherFunding,
+        searcher_inventory::SearcherInventoryDelta,
+        suppressed_bundle::SuppressedBundle,
+        token_tax::TokenTax,
+        victim_execution::VictimExecution,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -26,6 +49,123 @@ pub trait DBWriter: Send + Unpin + 'static {
         self.inner().write_block_analysis(block_analysis)
     }
 
+    fn write_gas_bids(
+        &self,
+        gas_bids: Vec<GasBid>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_gas_bids(gas_bids)
+    }
+
+    fn write_searcher_block_bids(
+        &self,
+        block_bids: Vec<SearcherBlockBids>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_searcher_block_bids(block_bids)
+    }
+
+    fn write_searcher_fingerprints(
+        &self,
+        fingerprints: Vec<SearcherFingerprint>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_searcher_fingerprints(fingerprints)
+    }
+
+    fn write_searcher_inventory(
+        &self,
+        inventory: Vec<SearcherInventoryDelta>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_searcher_inventory(inventory)
+    }
+
+    /// Adds the funding seen in a block to the stored funding of the searcher
+    /// EOAs
+    fn write_searcher_funding(
+        &self,
+        funding: Vec<(Address, SearcherFunding)>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_searcher_funding(funding)
+    }
+
+    /// Adds the tax rates observed in a block to the stored taxes of the
+    /// tokens
+    fn write_token_taxes(
+        &self,
+        taxes: Vec<(Address, TokenTax)>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_token_taxes(taxes)
+    }
+
+    /// Merges the metadata into the stored metadata of the protocol
+    fn write_protocol_metadata(
+        &self,
+        metadata: ProtocolMetadata,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_protocol_metadata(metadata)
+    }
+
+    /// Stores the selectors that have no entry yet, the existing entries are
+    /// kept
+    fn write_function_selectors(
+        &self,
+        selectors: Vec<FunctionSelector>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_function_selectors(selectors)
+    }
+
+    fn write_victim_execution(
+        &self,
+        executions: Vec<VictimExecution>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_victim_execution(executions)
+    }
+
+    fn write_possible_mev_timeline(
+        &self,
+        timeline: Vec<PossibleMevTimeline>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_possible_mev_timeline(timeline)
+    }
+
+    fn write_block_provenance(
+        &self,
+        provenance: BlockProvenance,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_block_provenance(provenance)
+    }
+
+    fn write_suppressed_bundles(
+        &self,
+        bundles: Vec<SuppressedBundle>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_suppressed_bundles(bundles)
+    }
+
+    fn write_inspector_runs(
+        &self,
+        block_number: u64,
+        runs: Vec<InspectorRun>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_inspector_runs(block_number, runs)
+    }
+
+    /// Adds a failure the classifier recovered from to the failures stored
+    /// for the block
+    fn write_classification_failure(
+        &self,
+        block_number: u64,
+        failure: ClassificationFailure,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner()
+            .write_classification_failure(block_number, failure)
+    }
+
+    fn write_builder_pnl(
+        &self,
+        builder_pnl: BuilderPnl,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_builder_pnl(builder_pnl)
+    }
+
     fn write_dex_quotes(
         &self,
         block_number: u64,
@@ -110,6 +250,57 @@ pub trait DBWriter: Send + Unpin + 'static {
             .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
     }
 
+    /// Records the fee tier of a newly discovered pool
+    fn insert_pool_fee_tier(
+        &self,
+        address: Address,
+        fee_tier: PoolFeeTier,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().insert_pool_fee_tier(address, fee_tier)
+    }
+
+    /// Applies a parameter change of a curve stableswap pool to its stored
+    /// parameters
+    fn update_curve_pool_params(
+        &self,
+        address: Address,
+        block: u64,
+        update: CurveParamsUpdate,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner()
+            .update_curve_pool_params(address, block, update)
+    }
+
+    /// Records the progress of the backfilled range starting at `start_block`
+    fn save_backfill_checkpoint(
+        &self,
+        start_block: u64,
+        checkpoint: BackfillCheckpoint,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner()
+            .save_backfill_checkpoint(start_block, checkpoint)
+    }
+
+    /// Removes everything stored for the given blocks, used when the blocks
+    /// were reorged out of the canonical chain
+    fn invalidate_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().invalidate_blocks(blocks)
+    }
+
+    /// Replaces the mev results of the blocks with recomputed ones, blocks of
+    /// the range without a recomputed block are cleared. A failed replacement
+    /// can be retried with the same results.
+    fn replace_mev_blocks(
+        &self,
+        blocks: RangeInclusive<u64>,
+        mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().replace_mev_blocks(blocks, mev_blocks)
+    }
+
     fn insert_tree(
         &self,
         tree: BlockTree<Action>,
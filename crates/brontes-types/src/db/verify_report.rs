@@ -0,0 +1,196 @@
+use alloy_primitives::B256;
+use serde::Serialize;
+
+use crate::{db::mev_block::MevBlockWithClassified, mev::MevType, FastHashMap, FastHashSet};
+
+/// Difference between the bundles stored for a block range and the bundles
+/// the current inspectors produce for it
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyReport {
+    pub start_block:       u64,
+    pub end_block:         u64,
+    pub profit_tolerance:  f64,
+    /// Number of blocks that were recomputed and compared
+    pub verified_blocks:   usize,
+    /// Stored blocks that couldn't be recomputed
+    pub unverified_blocks: Vec<u64>,
+    /// Bundles that are found now but aren't stored
+    pub added:             Vec<BundleRef>,
+    /// Bundles that are stored but aren't found anymore
+    pub removed:           Vec<BundleRef>,
+    /// Bundles whose profit changed by more than the tolerance
+    pub profit_deltas:     Vec<ProfitDelta>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleRef {
+    pub block_number: u64,
+    pub tx_hash:      B256,
+    pub mev_type:     MevType,
+    pub profit_usd:   f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfitDelta {
+    pub block_number:          u64,
+    pub tx_hash:               B256,
+    pub mev_type:              MevType,
+    pub stored_profit_usd:     f64,
+    pub recomputed_profit_usd: f64,
+    pub delta_usd:             f64,
+}
+
+impl VerifyReport {
+    pub fn new(
+        start_block: u64,
+        end_block: u64,
+        profit_tolerance: f64,
+        stored: Vec<MevBlockWithClassified>,
+        recomputed: Vec<MevBlockWithClassified>,
+    ) -> Self {
+        let recomputed_blocks = recomputed
+            .iter()
+            .map(|block| block.block.block_number)
+            .collect::<FastHashSet<_>>();
+
+        let mut report = Self {
+            start_block,
+            end_block,
+            profit_tolerance,
+            verified_blocks: recomputed_blocks.len(),
+            ..Default::default()
+        };
+
+        let mut stored_bundles = FastHashMap::default();
+        for block in stored {
+            if !recomputed_blocks.contains(&block.block.block_number) {
+                report.unverified_blocks.push(block.block.block_number);
+                continue
+            }
+
+            for bundle in block.mev {
+                let header = bundle.header;
+                stored_bundles.insert(
+                    (header.block_number, header.tx_hash, header.mev_type),
+                    header.profit_usd,
+                );
+            }
+        }
+
+        for bundle in recomputed.into_iter().flat_map(|block| block.mev) {
+            let header = bundle.header;
+            let key = (header.block_number, header.tx_hash, header.mev_type);
+
+            match stored_bundles.remove(&key) {
+                Some(stored_profit_usd) => {
+                    let delta_usd = header.profit_usd - stored_profit_usd;
+                    if delta_usd.abs() > profit_tolerance {
+                        report.profit_deltas.push(ProfitDelta {
+                            block_number: header.block_number,
+                            tx_hash: header.tx_hash,
+                            mev_type: header.mev_type,
+                            stored_profit_usd,
+                            recomputed_profit_usd: header.profit_usd,
+                            delta_usd,
+                        });
+                    }
+                }
+                None => report.added.push(BundleRef {
+                    block_number: header.block_number,
+                    tx_hash:      header.tx_hash,
+                    mev_type:     header.mev_type,
+                    profit_usd:   header.profit_usd,
+                }),
+            }
+        }
+
+        report.removed = stored_bundles
+            .into_iter()
+            .map(|((block_number, tx_hash, mev_type), profit_usd)| BundleRef {
+                block_number,
+                tx_hash,
+                mev_type,
+                profit_usd,
+            })
+            .collect();
+
+        report.unverified_blocks.sort_unstable();
+        report.added.sort_by_key(|bundle| bundle.block_number);
+        report.removed.sort_by_key(|bundle| bundle.block_number);
+        report.profit_deltas.sort_by_key(|delta| delta.block_number);
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::{Bundle, BundleHeader, MevBlock};
+
+    fn block(block_number: u64, bundles: &[(u8, MevType, f64)]) -> MevBlockWithClassified {
+        MevBlockWithClassified {
+            block: MevBlock { block_number, ..Default::default() },
+            mev:   bundles
+                .iter()
+                .map(|(tx, mev_type, profit_usd)| Bundle {
+                    header: BundleHeader {
+                        block_number,
+                        tx_hash: B256::with_last_byte(*tx),
+                        mev_type: *mev_type,
+                        profit_usd: *profit_usd,
+                        ..Default::default()
+                    },
+                    data:   Default::default(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diffs_stored_and_recomputed_bundles() {
+        let stored = vec![
+            block(1, &[(1, MevType::Sandwich, 10.0), (2, MevType::AtomicArb, 5.0)]),
+            block(2, &[(3, MevType::Jit, 1.0)]),
+            block(3, &[(4, MevType::Liquidation, 2.0)]),
+        ];
+        let recomputed = vec![
+            block(1, &[(1, MevType::Sandwich, 10.005), (5, MevType::CexDexTrades, 3.0)]),
+            block(2, &[(3, MevType::Jit, 4.0)]),
+        ];
+
+        let report = VerifyReport::new(1, 3, 0.01, stored, recomputed);
+
+        assert_eq!(report.verified_blocks, 2);
+        assert_eq!(report.unverified_blocks, vec![3]);
+
+        let [added] = report.added.try_into().unwrap();
+        assert_eq!(
+            (added.tx_hash, added.mev_type),
+            (B256::with_last_byte(5), MevType::CexDexTrades)
+        );
+
+        // the arb is gone, the liquidation wasn't recomputed so isn't reported
+        let [removed] = report.removed.try_into().unwrap();
+        assert_eq!(
+            (removed.tx_hash, removed.mev_type),
+            (B256::with_last_byte(2), MevType::AtomicArb)
+        );
+
+        // the sandwich is within the tolerance
+        let [delta] = report.profit_deltas.try_into().unwrap();
+        assert_eq!((delta.tx_hash, delta.delta_usd), (B256::with_last_byte(3), 3.0));
+    }
+
+    #[test]
+    fn reclassified_bundles_are_removed_and_added() {
+        let stored = vec![block(1, &[(1, MevType::AtomicArb, 1.0)])];
+        let recomputed = vec![block(1, &[(1, MevType::Frontrun, 1.0)])];
+
+        let report = VerifyReport::new(1, 1, 0.01, stored, recomputed);
+
+        assert_eq!(report.added[0].mev_type, MevType::Frontrun);
+        assert_eq!(report.removed[0].mev_type, MevType::AtomicArb);
+        assert!(report.profit_deltas.is_empty());
+    }
+}
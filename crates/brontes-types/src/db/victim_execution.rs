@@ -0,0 +1,121 @@
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use malachite::{num::basic::traits::Zero, Rational};
+use serde::{Deserialize, Serialize};
+
+use super::{dex::PriceAt, metadata::Metadata};
+use crate::{
+    mev::{Bundle, BundleData, MevType},
+    normalized_actions::{NormalizedAction, NormalizedSwap},
+    pair::Pair,
+    serde_utils::{address, txhash},
+    BlockTree, ToFloatNearest,
+};
+
+/// Execution quality of a swap made by the victim of a sandwich. The realized
+/// output is compared against what the input would have returned at the
+/// aggregate price of the pricing graph right before the victim's transaction,
+/// which approximates the best on-chain route at that index.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct VictimExecution {
+    pub block_number:         u64,
+    #[serde(with = "txhash")]
+    pub tx_hash:              TxHash,
+    pub tx_index:             u64,
+    pub trace_index:          u64,
+    /// Transaction hash of the bundle that made the swap a victim
+    #[serde(with = "txhash")]
+    pub bundle_tx_hash:       TxHash,
+    pub mev_type:             MevType,
+    #[serde(with = "address")]
+    pub pool:                 Address,
+    #[serde(with = "address")]
+    pub token_in:             Address,
+    #[serde(with = "address")]
+    pub token_out:            Address,
+    pub amount_in:            f64,
+    pub amount_out:           f64,
+    /// Output of the swap at the pricing graph's price before the transaction
+    pub benchmark_amount_out: f64,
+    /// Share of the benchmark output the victim missed out on. Negative if the
+    /// victim got a better execution than the benchmark
+    pub routing_inefficiency: f64,
+}
+
+impl VictimExecution {
+    /// Benchmarks the victim swaps of all sandwiches in the block. Swaps
+    /// without a dex price for their pair are skipped.
+    pub fn from_bundles<V: NormalizedAction>(
+        bundles: &[Bundle],
+        tree: &BlockTree<V>,
+        metadata: &Metadata,
+    ) -> Vec<Self> {
+        bundles
+            .iter()
+            .flat_map(|bundle| {
+                let victims = match &bundle.data {
+                    BundleData::Sandwich(s) => s
+                        .victim_swaps_tx_hashes
+                        .iter()
+                        .flatten()
+                        .zip(&s.victim_swaps),
+                    BundleData::JitSandwich(j) => j
+                        .victim_swaps_tx_hashes
+                        .iter()
+                        .flatten()
+                        .zip(&j.victim_swaps),
+                    _ => return vec![],
+                };
+
+                victims
+                    .filter_map(|(tx_hash, swaps)| {
+                        let tx_index = tree.get_root(*tx_hash)?.position;
+                        Some(swaps.iter().filter_map(move |swap| {
+                            Self::new(bundle, *tx_hash, tx_index, swap, metadata)
+                        }))
+                    })
+                    .flatten()
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn new(
+        bundle: &Bundle,
+        tx_hash: TxHash,
+        tx_index: usize,
+        swap: &NormalizedSwap,
+        metadata: &Metadata,
+    ) -> Option<Self> {
+        let price = metadata
+            .dex_quotes
+            .as_ref()?
+            .price_at(Pair(swap.token_in.address, swap.token_out.address), tx_index)?
+            .get_price(PriceAt::Before);
+
+        let benchmark_amount_out = &swap.amount_in * price;
+        let routing_inefficiency = if benchmark_amount_out == Rational::ZERO {
+            0.0
+        } else {
+            ((&benchmark_amount_out - &swap.amount_out) / &benchmark_amount_out).to_float()
+        };
+
+        Some(Self {
+            block_number: bundle.header.block_number,
+            tx_hash,
+            tx_index: tx_index as u64,
+            trace_index: swap.trace_index,
+            bundle_tx_hash: bundle.header.tx_hash,
+            mev_type: bundle.header.mev_type,
+            pool: swap.pool,
+            token_in: swap.token_in.address,
+            token_out: swap.token_out.address,
+            amount_in: swap.amount_in.clone().to_float(),
+            amount_out: swap.amount_out.clone().to_float(),
+            benchmark_amount_out: benchmark_amount_out.to_float(),
+            routing_inefficiency,
+        })
+    }
+}
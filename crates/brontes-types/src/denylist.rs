@@ -0,0 +1,144 @@
+//! Global denylist of known bad tokens and pools. Pricing never builds prices
+//! through them and bundles that touch them are suppressed, so scam tokens,
+//! honeypots and broken pools can be excluded from every part of a run.
+//!
+//! ```toml
+//! [denylist.tokens]
+//! "0x0000000000000000000000000000000000000001" = "scam_token"
+//!
+//! [denylist.pools]
+//! "0x0000000000000000000000000000000000000002" = "broken_pool"
+//! ```
+use std::sync::OnceLock;
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{pair::Pair, FastHashMap};
+
+static DENYLIST: OnceLock<Denylist> = OnceLock::new();
+
+/// Sets the denylist of the run. Needs to be called before pricing and the
+/// inspectors are started.
+pub fn init_denylist(denylist: Denylist) -> eyre::Result<()> {
+    DENYLIST
+        .set(denylist)
+        .map_err(|_| eyre::eyre!("denylist already initialized"))
+}
+
+/// The denylist of the run, empty if none was set
+pub fn denylist() -> &'static Denylist {
+    DENYLIST.get_or_init(Denylist::default)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DenyReason {
+    ScamToken,
+    Honeypot,
+    BrokenPool,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DenyKind {
+    Token,
+    Pool,
+}
+
+/// A denied address a bundle touched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exclusion {
+    pub address: Address,
+    pub kind:    DenyKind,
+    pub reason:  DenyReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Denylist {
+    pub tokens: FastHashMap<Address, DenyReason>,
+    pub pools:  FastHashMap<Address, DenyReason>,
+}
+
+impl Denylist {
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && self.pools.is_empty()
+    }
+
+    pub fn token(&self, token: Address) -> Option<Exclusion> {
+        self.tokens.get(&token).map(|&reason| Exclusion {
+            address: token,
+            kind: DenyKind::Token,
+            reason,
+        })
+    }
+
+    pub fn pool(&self, pool: Address) -> Option<Exclusion> {
+        self.pools.get(&pool).map(|&reason| Exclusion {
+            address: pool,
+            kind: DenyKind::Pool,
+            reason,
+        })
+    }
+
+    /// Whether the pool or one of the tokens of its pair is denied
+    pub fn denies_pool(&self, pool: Address, pair: Pair) -> bool {
+        !self.is_empty()
+            && (self.pools.contains_key(&pool)
+                || self.tokens.contains_key(&pair.0)
+                || self.tokens.contains_key(&pair.1))
+    }
+
+    /// The first denied pool or token in the iterators, pools first
+    pub fn find_exclusion(
+        &self,
+        pools: impl IntoIterator<Item = Address>,
+        tokens: impl IntoIterator<Item = Address>,
+    ) -> Option<Exclusion> {
+        if self.is_empty() {
+            return None
+        }
+
+        pools
+            .into_iter()
+            .find_map(|pool| self.pool(pool))
+            .or_else(|| tokens.into_iter().find_map(|token| self.token(token)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denylist_exclusions() {
+        let denylist: Denylist = serde_json::from_str(
+            r#"{
+                "tokens": { "0x0000000000000000000000000000000000000001": "honeypot" },
+                "pools": { "0x0000000000000000000000000000000000000002": "broken_pool" }
+            }"#,
+        )
+        .unwrap();
+
+        let token = Address::with_last_byte(1);
+        let pool = Address::with_last_byte(2);
+        let other = Address::with_last_byte(3);
+
+        assert_eq!(denylist.token(token).map(|e| e.reason), Some(DenyReason::Honeypot));
+        assert!(denylist.denies_pool(other, Pair(token, other)));
+        assert!(!denylist.denies_pool(other, Pair(other, other)));
+        assert_eq!(
+            denylist.find_exclusion([other, pool], [token]),
+            Some(Exclusion {
+                address: pool,
+                kind:    DenyKind::Pool,
+                reason:  DenyReason::BrokenPool,
+            })
+        );
+        assert_eq!(DenyReason::ScamToken.to_string(), "scam_token");
+    }
+}
@@ -1,13 +1,13 @@
 use std::fmt;
 
-use alloy_primitives::{Address, FixedBytes};
+use alloy_primitives::{Address, FixedBytes, B256};
 use colored::{ColoredString, Colorize};
 use indoc::indoc;
 use itertools::Itertools;
 use prettytable::{Cell, Row, Table};
-use reth_primitives::B256;
 
 use crate::{
+    chain::chain_spec,
     mev::{ArbDetails, AtomicArbType, Bundle, BundleData, CexDex, OptimisticTrade},
     utils::ToFloatNearest,
 };
@@ -110,23 +110,29 @@ pub fn display_sandwich(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result
     }
 
     // Backrun Section
-    writeln!(f, "\n{}:\n", "Backrun Transaction".bright_yellow().underline())?;
-    writeln!(
-        f,
-        " - {}: {}",
-        "Transaction".bright_blue(),
-        format_etherscan_url(&sandwich_data.backrun_tx_hash)
-    )?;
+    writeln!(f, "\n{}:\n", "Backrun Transactions".bright_yellow().underline())?;
+    for (i, ((tx_hash, swaps), gas_details)) in sandwich_data
+        .backrun_tx_hash
+        .iter()
+        .zip(&sandwich_data.backrun_swaps)
+        .zip(&sandwich_data.backrun_gas_details)
+        .enumerate()
+    {
+        writeln!(
+            f,
+            " - {}: {}",
+            format!("Backrun {}", i + 1).bright_blue(),
+            format_etherscan_url(tx_hash)
+        )?;
 
-    writeln!(f, "     - {}:", "Swaps".bright_blue())?;
-    for (i, swap) in sandwich_data.backrun_swaps.iter().enumerate() {
-        writeln!(f, "        {}: {}", format!(" - {}", i + 1).green(), swap)?;
-    }
+        writeln!(f, "     - {}:", "Swaps".bright_blue())?;
+        for (i, swap) in swaps.iter().enumerate() {
+            writeln!(f, "        {}: {}", format!(" - {}", i + 1).green(), swap)?;
+        }
 
-    writeln!(f, "     - {}:", "Gas Details".bright_blue())?;
-    sandwich_data
-        .backrun_gas_details
-        .pretty_print_with_spaces(f, 8)?;
+        writeln!(f, "     - {}:", "Gas Details".bright_blue())?;
+        gas_details.pretty_print_with_spaces(f, 8)?;
+    }
 
     // Profitability Section
     writeln!(f, "\n{}\n", "Profitability".bright_yellow().underline())?;
@@ -384,6 +390,16 @@ pub fn display_atomic_backrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::R
                 writeln!(f, "{}", line.bright_green())?;
             }
         }
+        AtomicArbType::Aggregator => {
+            for line in CROSS_PAIR.lines() {
+                writeln!(f, "{}", line.bright_cyan())?;
+            }
+        }
+        AtomicArbType::Nft => {
+            for line in CROSS_PAIR.lines() {
+                writeln!(f, "{}", line.bright_magenta())?;
+            }
+        }
     }
 
     // Tx details
@@ -400,12 +416,13 @@ pub fn display_atomic_backrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::R
         }
     }
 
-    let tx_url = format!("https://etherscan.io/tx/{:?}", bundle.header.tx_hash).underline();
+    let tx_url = chain_spec().tx_url(bundle.header.tx_hash).underline();
     writeln!(f, "   - Etherscan: {}", tx_url)?;
 
     if atomic_backrun_data.trigger_tx != B256::ZERO {
-        let tx_url =
-            format!("https://etherscan.io/tx/{:?}", atomic_backrun_data.trigger_tx).underline();
+        let tx_url = chain_spec()
+            .tx_url(atomic_backrun_data.trigger_tx)
+            .underline();
         writeln!(f, "   - Trigger Tx: {}", tx_url)?;
     }
 
@@ -554,6 +571,153 @@ pub fn display_liquidation(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Resu
     Ok(())
 }
 
+pub fn display_frontrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result {
+    let frontrun_data = match &bundle.data {
+        BundleData::Frontrun(data) => data,
+        _ => panic!("Wrong bundle type"),
+    };
+
+    writeln!(f, "{}", "Frontrun".bold().underline().bright_red())?;
+
+    // Tx details
+    writeln!(f, "\n{}: \n", "Transaction Details".bold().underline().bright_yellow())?;
+    writeln!(f, "   - Tx Index: {}", bundle.header.tx_index.to_string().bold())?;
+    writeln!(f, "   - EOA: {}", bundle.header.eoa)?;
+
+    match bundle.header.mev_contract {
+        Some(contract) => {
+            writeln!(f, "   - Mev Contract: {}", contract)?;
+        }
+        None => {
+            writeln!(f, "   - Mev Contract: None")?;
+        }
+    }
+
+    let tx_url = chain_spec()
+        .tx_url(frontrun_data.frontrun_tx_hash)
+        .underline();
+    writeln!(f, "   - Etherscan: {}", tx_url)?;
+
+    // Frontrun Section
+    writeln!(f, "\n{}\n", "Frontrun Swaps".bright_yellow().underline())?;
+    for (i, swap) in frontrun_data.frontrun_swaps.iter().enumerate() {
+        writeln!(f, "    {}: {}", format!(" - {}", i + 1).green(), swap)?;
+    }
+    writeln!(f, " - {}:", "Gas Details".bright_blue())?;
+    frontrun_data
+        .frontrun_gas_details
+        .pretty_print_with_spaces(f, 8)?;
+
+    // Victims Section
+    writeln!(f, "\n{}\n", "Victims".bright_yellow().underline())?;
+    for ((tx_hash, swaps), gas_details) in frontrun_data
+        .victim_swaps_tx_hashes
+        .iter()
+        .zip(&frontrun_data.victim_swaps)
+        .zip(&frontrun_data.victim_swaps_gas_details)
+    {
+        writeln!(f, " - {}: {}", "Transaction".bright_blue(), format_etherscan_url(tx_hash))?;
+        for (i, swap) in swaps.iter().enumerate() {
+            writeln!(f, "    {}: {}", format!(" - {}", i + 1).green(), swap)?;
+        }
+        gas_details.pretty_print_with_spaces(f, 8)?;
+    }
+
+    // Profitability Section
+    writeln!(f, "\n{}\n", "Profitability".bright_yellow().underline())?;
+    writeln!(
+        f,
+        " - {}: {}",
+        "Displacement Profit (USD)".bright_white(),
+        format_profit(frontrun_data.displacement_profit_usd)
+            .to_string()
+            .bright_white()
+    )?;
+    writeln!(
+        f,
+        " - {}: {}",
+        "Bribe (USD)".bright_white(),
+        format_bribe(bundle.header.bribe_usd)
+            .to_string()
+            .bright_red()
+    )?;
+
+    bundle
+        .header
+        .balance_deltas
+        .iter()
+        .for_each(|tx_delta| writeln!(f, "{}", tx_delta).expect("Failed to write balance deltas"));
+
+    Ok(())
+}
+
+pub fn display_approval_race(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result {
+    let race_data = match &bundle.data {
+        BundleData::ApprovalRace(data) => data,
+        _ => panic!("Wrong bundle type"),
+    };
+
+    writeln!(f, "{}", "Approval Race".bold().underline().bright_red())?;
+
+    // Tx details
+    writeln!(f, "\n{}: \n", "Transaction Details".bold().underline().bright_yellow())?;
+    writeln!(f, "   - Tx Index: {}", bundle.header.tx_index.to_string().bold())?;
+    writeln!(f, "   - EOA: {}", bundle.header.eoa)?;
+    writeln!(f, "   - Spender: {}", formate_etherscan_address_url(&race_data.spender))?;
+    writeln!(f, "   - Etherscan: {}", format_etherscan_url(&race_data.frontrun_tx_hash))?;
+
+    // Frontrun Section
+    writeln!(
+        f,
+        "\n{}\n",
+        "Drained Under The Old Allowance"
+            .bright_yellow()
+            .underline()
+    )?;
+    for (i, transfer) in race_data.frontrun_transfers.iter().enumerate() {
+        writeln!(
+            f,
+            "    {}: {} {} from {} to {}",
+            format!(" - {}", i + 1).green(),
+            transfer.amount.clone().to_float(),
+            transfer.token.symbol,
+            transfer.from,
+            transfer.to
+        )?;
+    }
+    writeln!(f, " - {}:", "Gas Details".bright_blue())?;
+    race_data
+        .frontrun_gas_details
+        .pretty_print_with_spaces(f, 8)?;
+
+    // Victim Section
+    writeln!(f, "\n{}\n", "Victim".bright_yellow().underline())?;
+    writeln!(
+        f,
+        " - {}: {}",
+        "Transaction".bright_blue(),
+        format_etherscan_url(&race_data.victim_tx_hash)
+    )?;
+    writeln!(f, "    - Owner: {}", race_data.victim)?;
+    writeln!(f, "    - New Allowance: {} {}", race_data.new_allowance, race_data.token.symbol)?;
+    race_data
+        .victim_gas_details
+        .pretty_print_with_spaces(f, 8)?;
+
+    // Profitability Section
+    writeln!(f, "\n{}\n", "Profitability".bright_yellow().underline())?;
+    writeln!(f, "   - Transaction Profit (USD): {}", format_profit(bundle.header.profit_usd))?;
+    writeln!(f, "   - Bribe (USD): {}", (format_bribe(bundle.header.bribe_usd)).to_string().red())?;
+
+    bundle
+        .header
+        .balance_deltas
+        .iter()
+        .for_each(|tx_delta| writeln!(f, "{}", tx_delta).expect("Failed to write balance deltas"));
+
+    Ok(())
+}
+
 pub fn display_jit_liquidity(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result {
     let ascii_header = indoc! {r#"
 
@@ -724,6 +888,17 @@ pub fn display_cex_dex(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result {
     writeln!(f, "   - Bribe (USD): {}", (format_bribe(bundle.header.bribe_usd)).to_string().red())?;
 
     writeln!(f, "Block Timestamp:\n {}", cex_dex_data.block_timestamp)?;
+    let window = &cex_dex_data.cex_window;
+    writeln!(
+        f,
+        "CEX Window ({}, {}): VWAP -{}us/+{}us, Optimistic -{}us/+{}us",
+        window.mode,
+        window.time_source,
+        window.vwap_pre_block_us,
+        window.vwap_post_block_us,
+        window.optimistic_pre_block_us,
+        window.optimistic_post_block_us
+    )?;
     // Cex-dex specific details
     writeln!(f, "\n{}", "Cex-Dex Details:\n".bold().bright_yellow().underline())?;
 
@@ -1030,13 +1205,9 @@ fn format_bribe(value: f64) -> ColoredString {
 }
 
 pub fn format_etherscan_url(tx_hash: &FixedBytes<32>) -> String {
-    format!("https://etherscan.io/tx/{:?}", tx_hash)
-        .underline()
-        .to_string()
+    chain_spec().tx_url(*tx_hash).underline().to_string()
 }
 
 pub fn formate_etherscan_address_url(tx_hash: &Address) -> String {
-    format!("https://etherscan.io/address/{:?}", tx_hash)
-        .underline()
-        .to_string()
+    chain_spec().address_url(*tx_hash).underline().to_string()
 }
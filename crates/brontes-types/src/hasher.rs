@@ -1,10 +1,70 @@
 //! default hashing types with custom hasher
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
 
-use ahash::RandomState;
+use ahash::{AHasher, RandomState};
+
+use crate::is_deterministic_mode;
+
+/// Seeds used for all hashers while in deterministic replay mode
+const REPLAY_SEEDS: [u64; 4] =
+    [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344, 0xa409_3822_299f_31d0, 0x082e_fa98_ec4e_6c89];
+
+/// ahash with a random seed, or a fixed seed when deterministic replay mode is
+/// enabled
+#[derive(Debug, Clone)]
+pub struct FastHasher(RandomState);
+
+impl FastHasher {
+    /// Hasher with the fixed seeds of deterministic replay mode
+    pub fn replay() -> Self {
+        let [k0, k1, k2, k3] = REPLAY_SEEDS;
+        Self(RandomState::with_seeds(k0, k1, k2, k3))
+    }
+}
+
+impl Default for FastHasher {
+    fn default() -> Self {
+        if is_deterministic_mode() {
+            Self::replay()
+        } else {
+            Self(RandomState::new())
+        }
+    }
+}
+
+impl BuildHasher for FastHasher {
+    type Hasher = AHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        self.0.build_hasher()
+    }
+}
 
-pub type FastHasher = RandomState;
 /// FastHashMap using ahash
 pub type FastHashMap<K, V> = HashMap<K, V, FastHasher>;
 /// FastHashSet using ahash
 pub type FastHashSet<V> = HashSet<V, FastHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // enabling the mode here would flip it for every other test of the crate, so
+    // the seeded hasher is checked directly
+    #[test]
+    fn replay_mode_seeds_hashers() {
+        let (a, b) = (FastHasher::replay(), FastHasher::replay());
+        assert_eq!(a.hash_one("brontes"), b.hash_one("brontes"));
+
+        let mut map = FastHashMap::with_hasher(FastHasher::replay());
+        let mut other = FastHashMap::with_hasher(FastHasher::replay());
+        for i in 0..64u64 {
+            map.insert(i, i);
+            other.insert(63 - i, 63 - i);
+        }
+        assert!(map.keys().eq(other.keys()));
+    }
+}
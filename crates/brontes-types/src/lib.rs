@@ -9,32 +9,48 @@
 
 pub mod multi_block;
 pub use multi_block::*;
+#[cfg(feature = "native")]
 pub mod buf_writer;
+#[cfg(feature = "native")]
 pub mod db_write_trigger;
 pub mod test_limiter;
 pub use test_limiter::*;
+pub mod content_hash;
 pub mod hasher;
+#[cfg(feature = "native")]
 pub mod rayon_utils;
 pub use hasher::*;
+#[cfg(feature = "native")]
 pub use rayon_utils::*;
+pub mod replay;
+pub use replay::*;
 pub mod action_iter;
+pub mod address_filter;
+pub mod denylist;
 pub use action_iter::*;
+#[cfg(feature = "native")]
 pub mod executor;
+#[cfg(feature = "native")]
 pub use executor::*;
+pub mod chain;
 pub mod constants;
 pub mod db;
 pub mod display;
 pub mod mev;
 pub mod normalized_actions;
+pub mod order_flow;
 pub mod pair;
 pub mod price_graph_types;
 pub use price_graph_types::*;
+#[cfg(feature = "native")]
 pub mod queries;
 pub mod serde_utils;
 pub mod unordered_buffer_map;
 pub mod unzip_either;
+#[cfg(feature = "native")]
 pub use queries::make_call_request;
 pub mod structured_trace;
+#[cfg(feature = "native")]
 pub mod traits;
 pub mod tree;
 pub use tree::*;
@@ -42,5 +58,7 @@ pub mod utils;
 pub use utils::*;
 pub mod protocol;
 pub use protocol::*;
+#[cfg(feature = "native")]
 pub mod channel_alerts;
+#[cfg(feature = "native")]
 pub use channel_alerts::*;
@@ -0,0 +1,165 @@
+use std::fmt::Debug;
+
+#[cfg(feature = "native")]
+use ::clickhouse::DbRow;
+use ::serde::ser::{SerializeStruct, Serializer};
+use ahash::{HashSet, HashSetExt};
+use alloy_primitives::{Address, B256, U256};
+#[allow(unused)]
+#[cfg(feature = "native")]
+use clickhouse::fixed_string::FixedString;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use super::{Mev, MevType};
+use crate::{
+    db::token_info::TokenInfoWithAddress,
+    normalized_actions::{ClickhouseVecNormalizedTransfer, NormalizedTransfer},
+    GasDetails, Protocol,
+};
+#[cfg(feature = "rkyv")]
+use crate::{
+    db::{redefined_types::primitives::*, token_info::TokenInfoWithAddressRedefined},
+    normalized_actions::NormalizedTransferRedefined,
+};
+
+/// A spender racing the owner's change of its allowance: the spender moves the
+/// owner's tokens under the old allowance in a transaction placed directly
+/// before the owner's approval, outbidding it. This is how the allowance of an
+/// owner that revokes or lowers an approval gets drained.
+#[serde_as]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
+pub struct ApprovalRace {
+    pub block_number:         u64,
+    pub frontrun_tx_hash:     B256,
+    /// Tokens of the owner moved under the old allowance
+    pub frontrun_transfers:   Vec<NormalizedTransfer>,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub frontrun_gas_details: GasDetails,
+    pub victim_tx_hash:       B256,
+    /// Owner of the tokens, who sent the approval
+    pub victim:               Address,
+    pub spender:              Address,
+    pub token:                TokenInfoWithAddress,
+    /// The allowance the owner set, in token units
+    pub new_allowance:        U256,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub victim_gas_details:   GasDetails,
+}
+
+impl Mev for ApprovalRace {
+    fn mev_type(&self) -> MevType {
+        MevType::ApprovalRace
+    }
+
+    /// The approval isn't part of the bundle, it's sent by the victim
+    fn mev_transaction_hashes(&self) -> Vec<B256> {
+        vec![self.frontrun_tx_hash]
+    }
+
+    fn total_gas_paid(&self) -> u128 {
+        self.frontrun_gas_details.gas_paid()
+    }
+
+    fn total_priority_fee_paid(&self, base_fee: u128) -> u128 {
+        self.frontrun_gas_details.priority_fee_paid(base_fee)
+    }
+
+    fn bribe(&self) -> u128 {
+        self.frontrun_gas_details.coinbase_transfer.unwrap_or(0)
+    }
+
+    fn protocols(&self) -> HashSet<Protocol> {
+        HashSet::new()
+    }
+}
+
+impl Serialize for ApprovalRace {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("ApprovalRace", 15)?;
+        ser_struct.serialize_field("block_number", &self.block_number)?;
+
+        // frontrun
+        ser_struct.serialize_field("frontrun_tx_hash", &format!("{:?}", self.frontrun_tx_hash))?;
+
+        let frontrun_transfers: ClickhouseVecNormalizedTransfer = self
+            .frontrun_transfers
+            .clone()
+            .try_into()
+            .map_err(serde::ser::Error::custom)?;
+        ser_struct
+            .serialize_field("frontrun_transfers.trace_idx", &frontrun_transfers.trace_index)?;
+        ser_struct.serialize_field("frontrun_transfers.from", &frontrun_transfers.from)?;
+        ser_struct.serialize_field("frontrun_transfers.to", &frontrun_transfers.to)?;
+        ser_struct.serialize_field("frontrun_transfers.token", &frontrun_transfers.token)?;
+        ser_struct.serialize_field("frontrun_transfers.amount", &frontrun_transfers.amount)?;
+        ser_struct.serialize_field("frontrun_transfers.fee", &frontrun_transfers.fee)?;
+
+        let gas_details = (
+            self.frontrun_gas_details.coinbase_transfer,
+            self.frontrun_gas_details.priority_fee,
+            self.frontrun_gas_details.gas_used,
+            self.frontrun_gas_details.effective_gas_price,
+            self.frontrun_gas_details.gas_refunded,
+            self.frontrun_gas_details.access_list_gas,
+            self.frontrun_gas_details.blob_gas_used,
+            self.frontrun_gas_details.blob_gas_price,
+        );
+        ser_struct.serialize_field("frontrun_gas_details", &gas_details)?;
+
+        // victim
+        ser_struct.serialize_field("victim_tx_hash", &format!("{:?}", self.victim_tx_hash))?;
+        ser_struct.serialize_field("victim", &format!("{:?}", self.victim))?;
+        ser_struct.serialize_field("spender", &format!("{:?}", self.spender))?;
+        ser_struct.serialize_field("token", &self.token.clickhouse_fmt())?;
+        ser_struct.serialize_field("new_allowance", &self.new_allowance.to_le_bytes::<32>())?;
+
+        let gas_details = (
+            self.victim_gas_details.coinbase_transfer,
+            self.victim_gas_details.priority_fee,
+            self.victim_gas_details.gas_used,
+            self.victim_gas_details.effective_gas_price,
+            self.victim_gas_details.gas_refunded,
+            self.victim_gas_details.access_list_gas,
+            self.victim_gas_details.blob_gas_used,
+            self.victim_gas_details.blob_gas_price,
+        );
+        ser_struct.serialize_field("victim_gas_details", &gas_details)?;
+
+        ser_struct.end()
+    }
+}
+
+#[cfg(feature = "native")]
+impl DbRow for ApprovalRace {
+    const COLUMN_NAMES: &'static [&'static str] = &[
+        "block_number",
+        "frontrun_tx_hash",
+        "frontrun_transfers.trace_idx",
+        "frontrun_transfers.from",
+        "frontrun_transfers.to",
+        "frontrun_transfers.token",
+        "frontrun_transfers.amount",
+        "frontrun_transfers.fee",
+        "frontrun_gas_details",
+        "victim_tx_hash",
+        "victim",
+        "spender",
+        "token",
+        "new_allowance",
+        "victim_gas_details",
+    ];
+}
@@ -3,60 +3,67 @@ use std::{
     fmt::{Debug, Display},
 };
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use ::serde::ser::{SerializeStruct, Serializer};
 use ahash::HashSet;
+use alloy_primitives::B256;
 #[allow(unused)]
+#[cfg(feature = "native")]
 use clickhouse::fixed_string::FixedString;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
+#[cfg(feature = "rkyv")]
 use crate::{
-    db::redefined_types::primitives::B256Redefined,
-    normalized_actions::{ClickhouseVecNormalizedSwap, NormalizedSwap, NormalizedSwapRedefined},
+    db::redefined_types::primitives::B256Redefined, normalized_actions::NormalizedSwapRedefined,
+};
+use crate::{
+    normalized_actions::{ClickhouseVecNormalizedSwap, NormalizedSwap},
     GasDetails, Protocol,
 };
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct AtomicArb {
     pub tx_hash:      B256,
     pub trigger_tx:   B256,
     pub block_number: u64,
     pub swaps:        Vec<NormalizedSwap>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub gas_details:  GasDetails,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub arb_type:     AtomicArbType,
 }
 /// Represents the different types of atomic arb
 /// A triangle arb is a simple arb that goes from token A -> B -> C -> A
 /// A cross pair arb is a more complex arb that goes from token A -> B -> C -> A
 
-#[derive(
-    Debug,
-    Default,
-    PartialEq,
-    Eq,
-    Clone,
-    Serialize,
-    Deserialize,
-    rSerialize,
-    rDeserialize,
-    Archive,
-    Copy,
-)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, Copy)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub enum AtomicArbType {
     #[default]
     Triangle,
     CrossPair(usize),
     StablecoinArb,
     LongTail,
+    /// Cyclical arb with legs routed through an aggregator or a batch
+    /// settlement
+    Aggregator,
+    /// Buys and sells the same nft across marketplaces
+    Nft,
 }
 impl Display for AtomicArbType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -65,12 +72,15 @@ impl Display for AtomicArbType {
             AtomicArbType::CrossPair(_) => writeln!(f, "Cross Pair Arbitrage"),
             AtomicArbType::StablecoinArb => writeln!(f, "Stablecoin Arbitrage"),
             AtomicArbType::LongTail => writeln!(f, "LongTail Arbitrage"),
+            AtomicArbType::Aggregator => writeln!(f, "Aggregator Arbitrage"),
+            AtomicArbType::Nft => writeln!(f, "NFT Arbitrage"),
         }
     }
 }
 
 //TODO: Ludwig, add flashloan arb support
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(AtomicArbType);
 
 impl Mev for AtomicArb {
@@ -104,7 +114,7 @@ impl Serialize for AtomicArb {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("AtomicArb", 37)?;
+        let mut ser_struct = serializer.serialize_struct("AtomicArb", 38)?;
         ser_struct.serialize_field("tx_hash", &format!("{:?}", self.tx_hash))?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
         ser_struct.serialize_field("trigger_tx", &format!("{:?}", self.trigger_tx))?;
@@ -121,11 +131,16 @@ impl Serialize for AtomicArb {
         ser_struct.serialize_field("swaps.token_out", &swaps.token_out)?;
         ser_struct.serialize_field("swaps.amount_in", &swaps.amount_in)?;
         ser_struct.serialize_field("swaps.amount_out", &swaps.amount_out)?;
+        ser_struct.serialize_field("swaps.fee_tier", &swaps.fee_tier)?;
         let gas_details = (
             self.gas_details.coinbase_transfer,
             self.gas_details.priority_fee,
             self.gas_details.gas_used,
             self.gas_details.effective_gas_price,
+            self.gas_details.gas_refunded,
+            self.gas_details.access_list_gas,
+            self.gas_details.blob_gas_used,
+            self.gas_details.blob_gas_price,
         );
         ser_struct.serialize_field("gas_details", &gas_details)?;
         ser_struct.serialize_field("arb_type", &self.arb_type.to_string())?;
@@ -133,6 +148,7 @@ impl Serialize for AtomicArb {
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for AtomicArb {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "tx_hash",
@@ -146,6 +162,7 @@ impl DbRow for AtomicArb {
         "swaps.token_out",
         "swaps.amount_in",
         "swaps.amount_out",
+        "swaps.fee_tier",
         "gas_details",
         "arb_type",
     ];
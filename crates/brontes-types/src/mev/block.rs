@@ -3,22 +3,25 @@ use std::{
     ops::Add,
 };
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 #[allow(unused)]
+#[cfg(feature = "native")]
 use clickhouse::{fixed_string::FixedString, row::*, Row};
 use colored::Colorize;
 use indoc::indoc;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeser, Serialize as rSer};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::MevType;
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::{AddressRedefined, B256Redefined};
 use crate::{
-    db::redefined_types::primitives::{AddressRedefined, B256Redefined},
-    display::utils::formate_etherscan_address_url,
-    ToFloatNearest, ToScaledRational,
+    chain::chain_spec, display::utils::formate_etherscan_address_url, ToFloatNearest,
+    ToScaledRational,
 };
 #[allow(unused_imports)]
 use crate::{
@@ -28,12 +31,16 @@ use crate::{
 };
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))
+)]
 pub struct MevBlock {
     pub block_hash:                  B256,
     pub block_number:                u64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub mev_count:                   MevCount,
     pub eth_price:                   f64,
     pub total_gas_used:              u128,
@@ -58,6 +65,10 @@ pub struct MevBlock {
     pub proposer_profit_usd:         Option<f64>,
     pub total_mev_profit_usd:        f64,
     pub possible_mev:                PossibleMevCollection,
+    /// Addresses touched by any transaction in the block that are on a
+    /// sanctions list
+    #[serde(default)]
+    pub sanctioned_addresses:        Vec<Address>,
 }
 
 impl fmt::Display for MevBlock {
@@ -186,7 +197,9 @@ fn format_profit(value: f64) -> String {
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Serialize, Row, Clone, Default, rDeser, rSer, Archive)]
+#[derive(Debug, Deserialize, PartialEq, Serialize, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(rDeser, rSer, Archive))]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct MevCount {
     pub bundle_count:         u64,
     pub sandwich_count:       Option<u64>,
@@ -199,6 +212,8 @@ pub struct MevCount {
     pub atomic_backrun_count: Option<u64>,
     pub liquidation_count:    Option<u64>,
     pub searcher_tx_count:    Option<u64>,
+    pub frontrun_count:       Option<u64>,
+    pub approval_race_count:  Option<u64>,
 }
 
 impl MevCount {
@@ -231,10 +246,17 @@ impl MevCount {
             MevType::JitCexDex => {
                 self.jit_cex_dex_count = Some(self.jit_cex_dex_count.unwrap_or_default().add(1))
             }
+            MevType::Frontrun => {
+                self.frontrun_count = Some(self.frontrun_count.unwrap_or_default().add(1))
+            }
+            MevType::ApprovalRace => {
+                self.approval_race_count = Some(self.approval_race_count.unwrap_or_default().add(1))
+            }
             _ => {}
         }
     }
 }
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(MevCount);
 
 impl fmt::Display for MevCount {
@@ -265,6 +287,12 @@ impl fmt::Display for MevCount {
         if let Some(count) = self.liquidation_count {
             writeln!(f, "    - Liquidation: {}", count.to_string().bold())?;
         }
+        if let Some(count) = self.frontrun_count {
+            writeln!(f, "    - Frontrun: {}", count.to_string().bold())?;
+        }
+        if let Some(count) = self.approval_race_count {
+            writeln!(f, "    - Approval Race: {}", count.to_string().bold())?;
+        }
         if let Some(count) = self.searcher_tx_count {
             writeln!(f, "    - Searcher TXs: {}", count.to_string().bold())?;
         }
@@ -274,8 +302,13 @@ impl fmt::Display for MevCount {
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Row, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))
+)]
 pub struct PossibleMevCollection(pub Vec<PossibleMev>);
 
 impl fmt::Display for PossibleMevCollection {
@@ -301,7 +334,7 @@ impl fmt::Display for PossibleMevCollection {
 impl fmt::Display for PossibleMev {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let eth_paid = self.gas_details.gas_paid() as f64 * 1e-18;
-        let tx_url = format!("https://etherscan.io/tx/{:?}", self.tx_hash);
+        let tx_url = chain_spec().tx_url(self.tx_hash);
         writeln!(f, "        Paid {} Eth for inclusion", eth_paid.to_string().bold().green())?;
         write!(f, "{}", self.triggers)?;
         writeln!(f, "        Etherscan: {}", tx_url.underline())
@@ -309,25 +342,33 @@ impl fmt::Display for PossibleMev {
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Row, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))
+)]
 pub struct PossibleMev {
     pub tx_hash:     B256,
     pub tx_idx:      u64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub gas_details: GasDetails,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub triggers:    PossibleMevTriggers,
 }
 
 #[serde_as]
-#[derive(Debug, PartialEq, Deserialize, Row, Clone, Default, Serialize, rSer, rDeser, Archive)]
+#[derive(Debug, PartialEq, Deserialize, Clone, Default, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(rSer, rDeser, Archive))]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct PossibleMevTriggers {
     pub is_private:        bool,
     pub coinbase_transfer: bool,
     pub high_priority_fee: bool,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(PossibleMevTriggers);
 
 impl fmt::Display for PossibleMevTriggers {
@@ -358,7 +399,7 @@ impl Serialize for MevBlock {
     where
         S: serde::Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("MevBlock", 33)?;
+        let mut ser_struct = serializer.serialize_struct("MevBlock", 34)?;
 
         ser_struct.serialize_field("block_hash", &format!("{:?}", self.block_hash))?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
@@ -396,6 +437,14 @@ impl Serialize for MevBlock {
             "mev_count.liquidation_count",
             &vec![self.mev_count.liquidation_count.unwrap_or_default()],
         )?;
+        ser_struct.serialize_field(
+            "mev_count.frontrun_count",
+            &vec![self.mev_count.frontrun_count.unwrap_or_default()],
+        )?;
+        ser_struct.serialize_field(
+            "mev_count.approval_race_count",
+            &vec![self.mev_count.approval_race_count.unwrap_or_default()],
+        )?;
 
         ser_struct.serialize_field("eth_price", &self.eth_price)?;
         ser_struct.serialize_field("total_gas_used", &self.total_gas_used)?;
@@ -433,6 +482,10 @@ impl Serialize for MevBlock {
         let mut possible_priority_fees = Vec::new();
         let mut possible_gas_useds = Vec::new();
         let mut possible_effective_gas_prices = Vec::new();
+        let mut possible_gas_refundeds = Vec::new();
+        let mut possible_access_list_gases = Vec::new();
+        let mut possible_blob_gas_useds = Vec::new();
+        let mut possible_blob_gas_prices = Vec::new();
         let mut possible_is_privates = Vec::new();
         let mut possible_trigger_coinbases = Vec::new();
         let mut possible_high_priority_fee = Vec::new();
@@ -448,6 +501,10 @@ impl Serialize for MevBlock {
                         tx.gas_details.priority_fee,
                         tx.gas_details.gas_used,
                         tx.gas_details.effective_gas_price,
+                        tx.gas_details.gas_refunded,
+                        tx.gas_details.access_list_gas,
+                        tx.gas_details.blob_gas_used,
+                        tx.gas_details.blob_gas_price,
                     ),
                     (
                         tx.triggers.is_private,
@@ -460,7 +517,16 @@ impl Serialize for MevBlock {
                 |(
                     hash,
                     idx,
-                    (gas_coinbase, priority_fee, gas_used, effective_gas_price),
+                    (
+                        gas_coinbase,
+                        priority_fee,
+                        gas_used,
+                        effective_gas_price,
+                        gas_refunded,
+                        access_list_gas,
+                        blob_gas_used,
+                        blob_gas_price,
+                    ),
                     (is_private, trigger_coinbase, high_priority_fee),
                 )| {
                     possible_tx_hashes.push(hash);
@@ -469,6 +535,10 @@ impl Serialize for MevBlock {
                     possible_priority_fees.push(priority_fee);
                     possible_gas_useds.push(gas_used);
                     possible_effective_gas_prices.push(effective_gas_price);
+                    possible_gas_refundeds.push(gas_refunded);
+                    possible_access_list_gases.push(access_list_gas);
+                    possible_blob_gas_useds.push(blob_gas_used);
+                    possible_blob_gas_prices.push(blob_gas_price);
                     possible_is_privates.push(is_private);
                     possible_trigger_coinbases.push(trigger_coinbase);
                     possible_high_priority_fee.push(high_priority_fee);
@@ -488,6 +558,18 @@ impl Serialize for MevBlock {
             "possible_mev.gas_details.effective_gas_price",
             &possible_effective_gas_prices,
         )?;
+        ser_struct
+            .serialize_field("possible_mev.gas_details.gas_refunded", &possible_gas_refundeds)?;
+        ser_struct.serialize_field(
+            "possible_mev.gas_details.access_list_gas",
+            &possible_access_list_gases,
+        )?;
+        ser_struct
+            .serialize_field("possible_mev.gas_details.blob_gas_used", &possible_blob_gas_useds)?;
+        ser_struct.serialize_field(
+            "possible_mev.gas_details.blob_gas_price",
+            &possible_blob_gas_prices,
+        )?;
         ser_struct.serialize_field("possible_mev.triggers.is_private", &possible_is_privates)?;
         ser_struct.serialize_field(
             "possible_mev.triggers.coinbase_transfer",
@@ -498,10 +580,18 @@ impl Serialize for MevBlock {
             &possible_high_priority_fee,
         )?;
 
+        let sanctioned_addresses = self
+            .sanctioned_addresses
+            .iter()
+            .map(|address| format!("{:?}", address))
+            .collect::<Vec<_>>();
+        ser_struct.serialize_field("sanctioned_addresses", &sanctioned_addresses)?;
+
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for MevBlock {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_hash",
@@ -515,6 +605,8 @@ impl DbRow for MevBlock {
         "mev_count.jit_sandwich_count",
         "mev_count.atomic_backrun_count",
         "mev_count.liquidation_count",
+        "mev_count.frontrun_count",
+        "mev_count.approval_race_count",
         "eth_price",
         "total_gas_used",
         "total_priority_fee",
@@ -540,8 +632,13 @@ impl DbRow for MevBlock {
         "possible_mev.gas_details.priority_fee",
         "possible_mev.gas_details.gas_used",
         "possible_mev.gas_details.effective_gas_price",
+        "possible_mev.gas_details.gas_refunded",
+        "possible_mev.gas_details.access_list_gas",
+        "possible_mev.gas_details.blob_gas_used",
+        "possible_mev.gas_details.blob_gas_price",
         "possible_mev.triggers.is_private",
         "possible_mev.triggers.coinbase_transfer",
         "possible_mev.triggers.high_priority_fee",
+        "sanctioned_addresses",
     ];
 }
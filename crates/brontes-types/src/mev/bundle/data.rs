@@ -1,9 +1,12 @@
 use std::fmt::Debug;
 
 use ahash::HashSet;
+use alloy_primitives::B256;
+#[cfg(feature = "native")]
 use clickhouse::InsertRow;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize, Serializer};
 use strum::{Display, EnumIter};
@@ -21,8 +24,13 @@ pub struct BundleDataWithRevenue {
     pub data:    BundleData,
 }
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug, Deserialize, PartialEq, EnumIter, Clone, Display, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, EnumIter, Clone, Display)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub enum BundleData {
     Sandwich(Sandwich),
     AtomicArb(AtomicArb),
@@ -32,6 +40,8 @@ pub enum BundleData {
     CexDex(CexDex),
     Liquidation(Liquidation),
     Unknown(SearcherTx),
+    Frontrun(Frontrun),
+    ApprovalRace(ApprovalRace),
 }
 
 impl Default for BundleData {
@@ -40,6 +50,39 @@ impl Default for BundleData {
     }
 }
 
+impl BundleData {
+    /// Every swap of the bundle, the searcher's along with the victims'
+    pub fn swaps(&self) -> Vec<&NormalizedSwap> {
+        match self {
+            BundleData::Sandwich(s) => s
+                .frontrun_swaps
+                .iter()
+                .chain(&s.victim_swaps)
+                .chain(&s.backrun_swaps)
+                .flatten()
+                .collect(),
+            BundleData::JitSandwich(s) => s
+                .frontrun_swaps
+                .iter()
+                .chain(&s.victim_swaps)
+                .flatten()
+                .chain(&s.backrun_swaps)
+                .collect(),
+            BundleData::Jit(j) => j.victim_swaps.iter().flatten().collect(),
+            BundleData::Frontrun(f) => f
+                .frontrun_swaps
+                .iter()
+                .chain(f.victim_swaps.iter().flatten())
+                .collect(),
+            BundleData::AtomicArb(a) => a.swaps.iter().collect(),
+            BundleData::CexDex(c) => c.swaps.iter().collect(),
+            BundleData::CexDexQuote(c) => c.swaps.iter().collect(),
+            BundleData::Liquidation(l) => l.liquidation_swaps.iter().collect(),
+            BundleData::ApprovalRace(_) | BundleData::Unknown(_) => vec![],
+        }
+    }
+}
+
 impl Mev for BundleData {
     fn mev_type(&self) -> MevType {
         match self {
@@ -50,6 +93,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.mev_type(),
             BundleData::CexDexQuote(m) => m.mev_type(),
             BundleData::Liquidation(m) => m.mev_type(),
+            BundleData::Frontrun(m) => m.mev_type(),
+            BundleData::ApprovalRace(m) => m.mev_type(),
             BundleData::Unknown(m) => m.mev_type(),
         }
     }
@@ -63,6 +108,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.total_gas_paid(),
             BundleData::CexDexQuote(m) => m.total_gas_paid(),
             BundleData::Liquidation(m) => m.total_gas_paid(),
+            BundleData::Frontrun(m) => m.total_gas_paid(),
+            BundleData::ApprovalRace(m) => m.total_gas_paid(),
             BundleData::Unknown(s) => s.total_gas_paid(),
         }
     }
@@ -76,6 +123,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.total_priority_fee_paid(base_fee),
             BundleData::CexDexQuote(m) => m.total_priority_fee_paid(base_fee),
             BundleData::Liquidation(m) => m.total_priority_fee_paid(base_fee),
+            BundleData::Frontrun(m) => m.total_priority_fee_paid(base_fee),
+            BundleData::ApprovalRace(m) => m.total_priority_fee_paid(base_fee),
             BundleData::Unknown(s) => s.total_priority_fee_paid(base_fee),
         }
     }
@@ -89,6 +138,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.bribe(),
             BundleData::CexDexQuote(m) => m.bribe(),
             BundleData::Liquidation(m) => m.bribe(),
+            BundleData::Frontrun(m) => m.bribe(),
+            BundleData::ApprovalRace(m) => m.bribe(),
             BundleData::Unknown(s) => s.bribe(),
         }
     }
@@ -102,6 +153,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.mev_transaction_hashes(),
             BundleData::CexDexQuote(m) => m.mev_transaction_hashes(),
             BundleData::Liquidation(m) => m.mev_transaction_hashes(),
+            BundleData::Frontrun(m) => m.mev_transaction_hashes(),
+            BundleData::ApprovalRace(m) => m.mev_transaction_hashes(),
             BundleData::Unknown(s) => s.mev_transaction_hashes(),
         }
     }
@@ -115,6 +168,8 @@ impl Mev for BundleData {
             BundleData::CexDex(m) => m.protocols(),
             BundleData::CexDexQuote(m) => m.protocols(),
             BundleData::Liquidation(m) => m.protocols(),
+            BundleData::Frontrun(m) => m.protocols(),
+            BundleData::ApprovalRace(m) => m.protocols(),
             BundleData::Unknown(s) => s.protocols(),
         }
     }
@@ -162,6 +217,18 @@ impl From<Liquidation> for BundleData {
     }
 }
 
+impl From<Frontrun> for BundleData {
+    fn from(value: Frontrun) -> Self {
+        Self::Frontrun(value)
+    }
+}
+
+impl From<ApprovalRace> for BundleData {
+    fn from(value: ApprovalRace) -> Self {
+        Self::ApprovalRace(value)
+    }
+}
+
 impl Serialize for BundleData {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -175,11 +242,14 @@ impl Serialize for BundleData {
             BundleData::CexDex(cex_dex) => cex_dex.serialize(serializer),
             BundleData::CexDexQuote(cex_dex) => cex_dex.serialize(serializer),
             BundleData::Liquidation(liquidation) => liquidation.serialize(serializer),
+            BundleData::Frontrun(frontrun) => frontrun.serialize(serializer),
+            BundleData::ApprovalRace(race) => race.serialize(serializer),
             BundleData::Unknown(s) => s.serialize(serializer),
         }
     }
 }
 
+#[cfg(feature = "native")]
 impl InsertRow for BundleData {
     fn get_column_names(&self) -> &'static [&'static str] {
         match self {
@@ -190,6 +260,8 @@ impl InsertRow for BundleData {
             BundleData::CexDex(cex_dex) => cex_dex.get_column_names(),
             BundleData::CexDexQuote(cex_dex) => cex_dex.get_column_names(),
             BundleData::Liquidation(liquidation) => liquidation.get_column_names(),
+            BundleData::Frontrun(frontrun) => frontrun.get_column_names(),
+            BundleData::ApprovalRace(race) => race.get_column_names(),
             BundleData::Unknown(s) => s.get_column_names(),
         }
     }
@@ -1,23 +1,28 @@
 use std::fmt::{self, Debug, Display};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
+#[cfg(feature = "native")]
 use clickhouse::{DbRow, Row};
 use colored::Colorize;
 use itertools::Itertools;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
 
-use super::MevType;
+use super::{MevType, LEGACY_TAXONOMY_VERSION, MEV_TAXONOMY_VERSION};
+#[cfg(feature = "rkyv")]
+use crate::db::{redefined_types::primitives::*, token_info::TokenInfoWithAddressRedefined};
+#[cfg(feature = "provenance")]
+use crate::normalized_actions::ActionProvenance;
+#[cfg(all(feature = "provenance", feature = "rkyv"))]
+use crate::normalized_actions::ActionProvenanceRedefined;
 use crate::{
-    db::{
-        redefined_types::primitives::*,
-        searcher::Fund,
-        token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
-    },
-    serde_utils::{addresss, option_addresss, txhash},
+    db::{searcher::Fund, token_info::TokenInfoWithAddress},
+    order_flow::SubmissionChannel,
+    serde_utils::{addresss, option_addresss, txhash, vec_address},
 };
 #[allow(unused_imports)]
 use crate::{
@@ -27,8 +32,13 @@ use crate::{
 };
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct BundleHeader {
     pub block_number: u64,
 
@@ -40,22 +50,103 @@ pub struct BundleHeader {
     pub eoa:                   Address,
     #[serde(with = "option_addresss")]
     pub mev_contract:          Option<Address>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     #[serde(default)]
     pub fund:                  Fund,
     pub profit_usd:            f64,
     // Total tx cost in USD
     pub bribe_usd:             f64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub mev_type:              MevType,
     // if we generated this arb without pricing
     pub no_pricing_calculated: bool,
     pub balance_deltas:        Vec<TransactionAccounting>,
+    /// Addresses involved in the bundle that are on a sanctions list
+    #[serde(with = "vec_address")]
+    #[serde(default)]
+    pub sanctioned_addresses:  Vec<Address>,
+    /// The [`MEV_TAXONOMY_VERSION`] the bundle was classified with
+    #[serde(default = "legacy_taxonomy_version")]
+    pub taxonomy_version:      u16,
+    /// Bundles found for the same transactions that were merged into this one
+    /// or dropped in its favour. Their profit isn't counted again
+    #[serde(default)]
+    pub superseded:            Vec<SupersededBundle>,
+    /// Profit of the bundle computed from the balance changes of the searcher
+    /// addresses when the bundle's transactions are re-executed. Only set when
+    /// profit verification is enabled
+    #[serde(default)]
+    pub simulated_profit_usd:  Option<f64>,
+    /// Whether the simulated profit diverges from `profit_usd` by more than
+    /// the tolerance
+    #[serde(default)]
+    pub profit_discrepancy:    bool,
+    /// How the transaction of the bundle was submitted, for a sandwich the
+    /// first frontrun
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    #[serde(default)]
+    pub submission_channel:    SubmissionChannel,
+    /// The calls and logs the classified actions of the bundle's transactions
+    /// were produced by. Only collected with the `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(default)]
+    pub provenance:            Vec<ActionProvenance>,
+}
+
+impl BundleHeader {
+    /// Whether the bundle was classified with the current taxonomy, and so can
+    /// be compared with the bundles produced by this release.
+    pub fn is_current_taxonomy(&self) -> bool {
+        self.taxonomy_version == MEV_TAXONOMY_VERSION
+    }
+}
+
+/// Rows written before the taxonomy version was persisted don't have the
+/// column, so they are marked as legacy when deserialized.
+fn legacy_taxonomy_version() -> u16 {
+    LEGACY_TAXONOMY_VERSION
+}
+
+/// A classification of some of the transactions of a bundle that lost to the
+/// bundle. It was either merged into the bundle, e.g. the sandwich and the jit
+/// of a jit sandwich, or the mev type of the bundle takes precedence over it.
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
+pub struct SupersededBundle {
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub mev_type:   MevType,
+    #[serde(with = "txhash")]
+    pub tx_hash:    B256,
+    pub profit_usd: f64,
+    /// Whether it was merged into the bundle rather than dropped
+    pub merged:     bool,
+}
+
+impl SupersededBundle {
+    pub fn new(header: &BundleHeader, merged: bool) -> Self {
+        Self {
+            mev_type: header.mev_type,
+            tx_hash: header.tx_hash,
+            profit_usd: header.profit_usd,
+            merged,
+        }
+    }
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct TransactionAccounting {
     pub tx_hash:        B256,
     pub address_deltas: Vec<AddressBalanceDeltas>,
@@ -73,16 +164,28 @@ impl Display for TransactionAccounting {
     }
 }
 #[serde_as]
-#[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct AddressBalanceDeltas {
     pub address:      Address,
     pub name:         Option<String>,
     pub token_deltas: Vec<TokenBalanceDelta>,
 }
 #[serde_as]
-#[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, Serialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct TokenBalanceDelta {
     pub token:     TokenInfoWithAddress,
     pub amount:    f64,
@@ -124,7 +227,7 @@ impl Serialize for BundleHeader {
     where
         S: serde::Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("BundleHeader", 12)?;
+        let mut ser_struct = serializer.serialize_struct("BundleHeader", 23)?;
 
         ser_struct.serialize_field("block_number", &self.block_number)?;
         ser_struct.serialize_field("tx_index", &self.tx_index)?;
@@ -193,11 +296,115 @@ impl Serialize for BundleHeader {
             .collect_vec();
         ser_struct.serialize_field("balance_deltas.token_deltas", &balance_deltas_token_deltas)?;
 
+        let sanctioned_addresses = self
+            .sanctioned_addresses
+            .iter()
+            .map(|address| format!("{:?}", address))
+            .collect_vec();
+        ser_struct.serialize_field("sanctioned_addresses", &sanctioned_addresses)?;
+        ser_struct.serialize_field("taxonomy_version", &self.taxonomy_version)?;
+
+        let superseded_mev_types = self
+            .superseded
+            .iter()
+            .map(|bundle| bundle.mev_type)
+            .collect_vec();
+        ser_struct.serialize_field("superseded.mev_type", &superseded_mev_types)?;
+        let superseded_tx_hashes = self
+            .superseded
+            .iter()
+            .map(|bundle| format!("{:?}", bundle.tx_hash))
+            .collect_vec();
+        ser_struct.serialize_field("superseded.tx_hash", &superseded_tx_hashes)?;
+        let superseded_profits = self
+            .superseded
+            .iter()
+            .map(|bundle| bundle.profit_usd)
+            .collect_vec();
+        ser_struct.serialize_field("superseded.profit_usd", &superseded_profits)?;
+        let superseded_merged = self
+            .superseded
+            .iter()
+            .map(|bundle| bundle.merged)
+            .collect_vec();
+        ser_struct.serialize_field("superseded.merged", &superseded_merged)?;
+        ser_struct.serialize_field("simulated_profit_usd", &self.simulated_profit_usd)?;
+        ser_struct.serialize_field("profit_discrepancy", &self.profit_discrepancy)?;
+        ser_struct.serialize_field("submission_channel", &self.submission_channel)?;
+
+        #[cfg(feature = "provenance")]
+        {
+            let provenance_tx_hashes = self
+                .provenance
+                .iter()
+                .map(|provenance| format!("{:?}", provenance.tx_hash))
+                .collect_vec();
+            ser_struct.serialize_field("provenance.tx_hash", &provenance_tx_hashes)?;
+            let provenance_trace_idxs = self
+                .provenance
+                .iter()
+                .map(|provenance| provenance.trace_idx)
+                .collect_vec();
+            ser_struct.serialize_field("provenance.trace_idx", &provenance_trace_idxs)?;
+            let provenance_trace_addresses = self
+                .provenance
+                .iter()
+                .map(|provenance| provenance.trace_address.clone())
+                .collect_vec();
+            ser_struct.serialize_field("provenance.trace_address", &provenance_trace_addresses)?;
+            let provenance_selectors = self
+                .provenance
+                .iter()
+                .map(|provenance| {
+                    provenance
+                        .selector
+                        .map(|selector| format!("{:?}", selector))
+                })
+                .collect_vec();
+            ser_struct.serialize_field("provenance.selector", &provenance_selectors)?;
+            let provenance_log_indices = self
+                .provenance
+                .iter()
+                .map(|provenance| provenance.log_indices.clone())
+                .collect_vec();
+            ser_struct.serialize_field("provenance.log_indices", &provenance_log_indices)?;
+        }
+
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for BundleHeader {
+    #[cfg(not(feature = "provenance"))]
+    const COLUMN_NAMES: &'static [&'static str] = &[
+        "block_number",
+        "tx_index",
+        "tx_hash",
+        "eoa",
+        "mev_contract",
+        "fund",
+        "profit_usd",
+        "bribe_usd",
+        "mev_type",
+        "no_pricing_calculated",
+        "balance_deltas.tx_hash",
+        "balance_deltas.address",
+        "balance_deltas.name",
+        "balance_deltas.token_deltas",
+        "sanctioned_addresses",
+        "taxonomy_version",
+        "superseded.mev_type",
+        "superseded.tx_hash",
+        "superseded.profit_usd",
+        "superseded.merged",
+        "simulated_profit_usd",
+        "profit_discrepancy",
+        "submission_channel",
+    ];
+    /// The provenance of the classified actions is only written when built
+    /// with the `provenance` feature
+    #[cfg(feature = "provenance")]
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_number",
         "tx_index",
@@ -213,5 +420,43 @@ impl DbRow for BundleHeader {
         "balance_deltas.address",
         "balance_deltas.name",
         "balance_deltas.token_deltas",
+        "sanctioned_addresses",
+        "taxonomy_version",
+        "superseded.mev_type",
+        "superseded.tx_hash",
+        "superseded.profit_usd",
+        "superseded.merged",
+        "simulated_profit_usd",
+        "profit_discrepancy",
+        "submission_channel",
+        "provenance.tx_hash",
+        "provenance.trace_idx",
+        "provenance.trace_address",
+        "provenance.selector",
+        "provenance.log_indices",
     ];
 }
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn taxonomy_version_tracks_mev_types() {
+        // adding a mev type changes the taxonomy, bump the version along with
+        // the count
+        assert_eq!((MEV_TAXONOMY_VERSION, MevType::iter().count()), (5, 13));
+    }
+
+    #[test]
+    fn legacy_rows_are_not_current() {
+        let header =
+            BundleHeader { taxonomy_version: legacy_taxonomy_version(), ..Default::default() };
+        assert!(!header.is_current_taxonomy());
+
+        let header = BundleHeader { taxonomy_version: MEV_TAXONOMY_VERSION, ..Default::default() };
+        assert!(header.is_current_taxonomy());
+    }
+}
@@ -3,14 +3,16 @@ pub mod header;
 use std::fmt::{self, Debug};
 
 use ahash::HashSet;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use clap::ValueEnum;
+#[cfg(feature = "native")]
 use clickhouse::Row;
 pub use data::*;
 use dyn_clone::DynClone;
 pub use header::*;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -25,8 +27,14 @@ use crate::{
 };
 
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize, PartialEq, Row, Clone, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct Bundle {
     pub header: BundleHeader,
     pub data:   BundleData,
@@ -66,6 +74,8 @@ impl fmt::Display for Bundle {
             MevType::Jit => display_jit_liquidity(self, f)?,
             MevType::AtomicArb => display_atomic_backrun(self, f)?,
             MevType::Liquidation => display_liquidation(self, f)?,
+            MevType::Frontrun => display_frontrun(self, f)?,
+            MevType::ApprovalRace => display_approval_race(self, f)?,
             MevType::JitSandwich => display_jit_liquidity_sandwich(self, f)?,
             MevType::SearcherTx => display_searcher_tx(self, f)?,
             MevType::Unknown => (),
@@ -75,22 +85,25 @@ impl fmt::Display for Bundle {
     }
 }
 
+/// Version of the mev taxonomy: the set of [`MevType`]s and the heuristics the
+/// inspectors use to classify bundles into them. Bump this whenever a change
+/// makes newly classified bundles incomparable with previous results.
+///
+/// 5: maker and compound v3 liquidations only count the liquidator's
+/// purchase, atomic arb revenue
+/// comes from the transfer deltas, bundles whose deltas aren't balanced are
+/// treated as having no dex price and big mac sandwiches store their backruns
+/// in the backrun fields
+pub const MEV_TAXONOMY_VERSION: u16 = 5;
+
+/// Taxonomy version of results written before the version was recorded.
+pub const LEGACY_TAXONOMY_VERSION: u16 = 0;
+
 #[derive(
-    Debug,
-    PartialEq,
-    Eq,
-    Hash,
-    EnumIter,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
-    Clone,
-    Copy,
-    Default,
-    Display,
-    ValueEnum,
-    AsRefStr,
+    Debug, PartialEq, Eq, Hash, EnumIter, Clone, Copy, Default, Display, ValueEnum, AsRefStr,
 )]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub enum MevType {
     CexDexTrades,
     CexDexQuotes,
@@ -104,6 +117,9 @@ pub enum MevType {
     SearcherTx,
     #[default]
     Unknown,
+    // appended so the archived discriminants of the other types stay stable
+    Frontrun,
+    ApprovalRace,
 }
 
 impl MevType {
@@ -114,6 +130,8 @@ impl MevType {
             | MevType::Jit
             | MevType::AtomicArb
             | MevType::Liquidation
+            | MevType::Frontrun
+            | MevType::ApprovalRace
             | MevType::SearcherTx
             | MevType::Unknown => false,
             MevType::CexDexRfq
@@ -123,6 +141,13 @@ impl MevType {
         }
     }
 
+    /// Cex dex arbs found with the different cex pricing methods. The same
+    /// transaction can be priced by several of them, and those bundles are
+    /// alternative estimates rather than overlapping classifications
+    pub fn is_cex_dex_estimate(&self) -> bool {
+        matches!(self, MevType::CexDexQuotes | MevType::CexDexTrades | MevType::CexDexRfq)
+    }
+
     pub fn get_parquet_path(&self) -> &'static str {
         match self {
             MevType::CexDexRfq
@@ -135,6 +160,8 @@ impl MevType {
             MevType::JitSandwich => "jit-sandwich",
             MevType::SearcherTx => "searcher-tx",
             MevType::Liquidation => "liquidation",
+            MevType::Frontrun => "frontrun",
+            MevType::ApprovalRace => "approval-race",
             MevType::Unknown => "header",
         }
     }
@@ -151,6 +178,8 @@ impl From<String> for MevType {
             "Sandwich" => MevType::Sandwich,
             "Jit" => MevType::Jit,
             "Liquidation" => MevType::Liquidation,
+            "Frontrun" => MevType::Frontrun,
+            "ApprovalRace" => MevType::ApprovalRace,
             "JitSandwich" => MevType::JitSandwich,
             "AtomicArb" => MevType::AtomicArb,
             "SearcherTx" => MevType::SearcherTx,
@@ -181,6 +210,7 @@ impl<'de> Deserialize<'de> for MevType {
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(MevType);
 
 pub trait Mev: erased_serde::Serialize + Send + Sync + Debug + 'static + DynClone {
@@ -1,29 +1,34 @@
 use std::{fmt, fmt::Debug};
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use ::serde::{
     ser::{SerializeStruct, Serializer},
     Deserialize, Serialize,
 };
 use ahash::HashSet;
+use alloy_primitives::B256;
 use colored::Colorize;
 use malachite::Rational;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, Redefined};
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde_with::serde_as;
 use strum::Display;
 
 use super::{Mev, MevType};
 use crate::{
-    db::{
-        cex::CexExchange,
-        redefined_types::{malachite::RationalRedefined, primitives::*},
-    },
+    db::cex::{trades::CexWindow, CexExchange},
     normalized_actions::*,
-    pair::{Pair, PairRedefined},
+    pair::Pair,
     Protocol, ToFloatNearest,
 };
+#[cfg(feature = "rkyv")]
+use crate::{
+    db::redefined_types::{malachite::RationalRedefined, primitives::*},
+    pair::PairRedefined,
+};
 #[allow(unused_imports)]
 use crate::{
     display::utils::display_sandwich,
@@ -31,10 +36,15 @@ use crate::{
     rational_to_u256_fraction, GasDetails,
 };
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct OptimisticTrade {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub exchange:  CexExchange,
     pub pair:      Pair,
     pub timestamp: u64,
@@ -61,13 +71,18 @@ impl Serialize for OptimisticTrade {
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct CexDex {
     pub tx_hash: B256,
     pub block_timestamp: u64,
     pub block_number: u64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub header_pnl_methodology: CexMethodology,
     pub swaps: Vec<NormalizedSwap>,
     pub global_vmap_details: Vec<ArbDetails>,
@@ -81,10 +96,13 @@ pub struct CexDex {
     pub optimistic_route_pnl_maker: Rational,
     pub optimistic_route_pnl_taker: Rational,
     pub per_exchange_details: Vec<Vec<ArbDetails>>,
-    #[redefined(field((CexExchange, same)))]
+    #[cfg_attr(feature = "rkyv", redefined(field((CexExchange, same))))]
     pub per_exchange_pnl: Vec<(CexExchange, (Rational, Rational))>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub gas_details: GasDetails,
+    /// The initial windows the cex trades were selected in
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub cex_window: CexWindow,
 }
 
 impl Mev for CexDex {
@@ -114,20 +132,10 @@ impl Mev for CexDex {
 }
 
 #[derive(
-    Copy,
-    Display,
-    Default,
-    Debug,
-    Clone,
-    Eq,
-    PartialEq,
-    Hash,
-    serde::Serialize,
-    serde::Deserialize,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
+    Copy, Display, Default, Debug, Clone, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize,
 )]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub enum CexMethodology {
     GlobalWWAP,
     OptimalRouteVWAP,
@@ -136,13 +144,14 @@ pub enum CexMethodology {
     None,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(CexMethodology);
 impl Serialize for CexDex {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("CexDex", 68)?;
+        let mut ser_struct = serializer.serialize_struct("CexDex", 70)?;
 
         ser_struct.serialize_field("tx_hash", &format!("{:?}", self.tx_hash))?;
         ser_struct.serialize_field("block_timestamp", &self.block_timestamp)?;
@@ -164,6 +173,7 @@ impl Serialize for CexDex {
         ser_struct.serialize_field("swaps.token_out", &swaps.token_out)?;
         ser_struct.serialize_field("swaps.amount_in", &swaps.amount_in)?;
         ser_struct.serialize_field("swaps.amount_out", &swaps.amount_out)?;
+        ser_struct.serialize_field("swaps.fee_tier", &swaps.fee_tier)?;
 
         let transposed: ArbDetailsTransposed = self.global_vmap_details.clone().into();
         ser_struct.serialize_field(
@@ -613,14 +623,31 @@ impl Serialize for CexDex {
             self.gas_details.priority_fee,
             self.gas_details.gas_used,
             self.gas_details.effective_gas_price,
+            self.gas_details.gas_refunded,
+            self.gas_details.access_list_gas,
+            self.gas_details.blob_gas_used,
+            self.gas_details.blob_gas_price,
         );
 
         ser_struct.serialize_field("gas_details", &gas_details)?;
 
+        let cex_window = (
+            self.cex_window.mode.to_string(),
+            self.cex_window.time_source.to_string(),
+            self.cex_window.vwap_pre_block_us,
+            self.cex_window.vwap_post_block_us,
+            self.cex_window.optimistic_pre_block_us,
+            self.cex_window.optimistic_post_block_us,
+            self.cex_window.trade_count,
+        );
+
+        ser_struct.serialize_field("cex_window", &cex_window)?;
+
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for CexDex {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "tx_hash",
@@ -635,6 +662,7 @@ impl DbRow for CexDex {
         "swaps.token_out",
         "swaps.amount_in",
         "swaps.amount_out",
+        "swaps.fee_tier",
         "global_vmap_details.pairs",
         "global_vmap_details.trade_start_time",
         "global_vmap_details.trade_end_time",
@@ -690,23 +718,27 @@ impl DbRow for CexDex {
         "per_exchange_pnl.pnl_maker",
         "per_exchange_pnl.pnl_taker",
         "gas_details",
+        "cex_window",
     ];
 }
 
 #[serde_as]
-#[derive(
-    Debug, Deserialize, PartialEq, Clone, Default, Redefined, brontes_macros::Transposable,
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, brontes_macros::Transposable)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
 )]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct ArbDetails {
     pub pairs:            Vec<Pair>,
     pub trade_start_time: u64,
     pub trade_end_time:   u64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub cex_exchange:     CexExchange,
     pub price_maker:      Rational,
     pub price_taker:      Rational,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub dex_exchange:     Protocol,
     pub dex_price:        Rational,
     pub dex_amount:       Rational,
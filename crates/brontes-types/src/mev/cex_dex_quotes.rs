@@ -1,26 +1,32 @@
 use std::fmt::Debug;
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use ::serde::{
     ser::{SerializeStruct, Serializer},
     Deserialize, Serialize,
 };
 use ahash::HashSet;
+use alloy_primitives::B256;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
-use crate::{
-    db::{cex::CexExchange, redefined_types::primitives::*},
-    normalized_actions::*,
-    GasDetails, Protocol,
-};
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
+use crate::{db::cex::CexExchange, normalized_actions::*, GasDetails, Protocol};
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct CexDexQuote {
     pub tx_hash:           B256,
     pub block_timestamp:   u64,
@@ -32,10 +38,10 @@ pub struct CexDexQuote {
     pub t30_mid_price:     Vec<f64>,
     pub t60_mid_price:     Vec<f64>,
     pub t300_mid_price:    Vec<f64>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub exchange:          CexExchange,
     pub pnl:               f64,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub gas_details:       GasDetails,
 }
 
@@ -70,7 +76,7 @@ impl Serialize for CexDexQuote {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("CexDexQuote", 19)?;
+        let mut ser_struct = serializer.serialize_struct("CexDexQuote", 20)?;
         ser_struct.serialize_field("tx_hash", &format!("{:?}", self.tx_hash))?;
         ser_struct.serialize_field("block_timestamp", &self.block_timestamp)?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
@@ -87,6 +93,7 @@ impl Serialize for CexDexQuote {
         ser_struct.serialize_field("swaps.token_out", &swaps.token_out)?;
         ser_struct.serialize_field("swaps.amount_in", &swaps.amount_in)?;
         ser_struct.serialize_field("swaps.amount_out", &swaps.amount_out)?;
+        ser_struct.serialize_field("swaps.fee_tier", &swaps.fee_tier)?;
         ser_struct.serialize_field("pnl", &self.pnl)?;
         ser_struct.serialize_field("instant_mid_price", &self.instant_mid_price)?;
         ser_struct.serialize_field("t2_mid_price", &self.t2_mid_price)?;
@@ -102,12 +109,17 @@ impl Serialize for CexDexQuote {
                 self.gas_details.priority_fee,
                 self.gas_details.gas_used,
                 self.gas_details.effective_gas_price,
+                self.gas_details.gas_refunded,
+                self.gas_details.access_list_gas,
+                self.gas_details.blob_gas_used,
+                self.gas_details.blob_gas_price,
             ),
         )?;
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for CexDexQuote {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "tx_hash",
@@ -121,6 +133,7 @@ impl DbRow for CexDexQuote {
         "swaps.token_out",
         "swaps.amount_in",
         "swaps.amount_out",
+        "swaps.fee_tier",
         "pnl",
         "instant_mid_price",
         "t2_mid_price",
@@ -1,13 +1,14 @@
 use std::fmt::{self, Debug};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
+#[cfg(feature = "native")]
+use clickhouse::{fixed_string::FixedString, InsertRow, Row};
 use colored::Colorize;
 use dyn_clone::DynClone;
 use indoc::indoc;
+#[cfg(feature = "rkyv")]
 use redefined::{self_convert_redefined, RedefinedConvert};
-use reth_primitives::B256;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::serde_as;
-use clickhouse::{fixed_string::FixedString, Row, InsertRow};
 use strum::{Display, EnumIter};
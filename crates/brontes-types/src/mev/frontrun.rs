@@ -0,0 +1,214 @@
+use std::fmt::Debug;
+
+#[cfg(feature = "native")]
+use ::clickhouse::DbRow;
+use ::serde::ser::{SerializeStruct, Serializer};
+use ahash::HashSet;
+use alloy_primitives::B256;
+#[allow(unused)]
+#[cfg(feature = "native")]
+use clickhouse::fixed_string::FixedString;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use super::{Mev, MevType};
+#[cfg(feature = "rkyv")]
+use crate::{db::redefined_types::primitives::*, normalized_actions::NormalizedSwapRedefined};
+use crate::{
+    normalized_actions::{
+        ClickhouseDoubleVecNormalizedSwap, ClickhouseVecNormalizedSwap, NormalizedSwap,
+    },
+    ClickhouseVecGasDetails, GasDetails, Protocol,
+};
+
+/// A searcher trade placed directly before large trades of other senders on
+/// the same pool and in the same direction, outbidding them on gas. Unlike a
+/// sandwich there is no backrun, the searcher keeps the position and profits
+/// from the price the victims push the pool to.
+#[serde_as]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
+pub struct Frontrun {
+    pub block_number:             u64,
+    pub frontrun_tx_hash:         B256,
+    pub frontrun_swaps:           Vec<NormalizedSwap>,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub frontrun_gas_details:     GasDetails,
+    /// The victim transactions following the frontrun, in block order
+    pub victim_swaps_tx_hashes:   Vec<B256>,
+    /// Swaps of each victim transaction on the frontrun pools
+    pub victim_swaps:             Vec<Vec<NormalizedSwap>>,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub victim_swaps_gas_details: Vec<GasDetails>,
+    /// Value of the frontrun position at the pool prices after the last
+    /// victim, net of the gas paid for the frontrun
+    pub displacement_profit_usd:  f64,
+}
+
+impl Mev for Frontrun {
+    fn mev_type(&self) -> MevType {
+        MevType::Frontrun
+    }
+
+    /// The victim transactions aren't part of the bundle, they aren't sent by
+    /// the searcher
+    fn mev_transaction_hashes(&self) -> Vec<B256> {
+        vec![self.frontrun_tx_hash]
+    }
+
+    fn total_gas_paid(&self) -> u128 {
+        self.frontrun_gas_details.gas_paid()
+    }
+
+    fn total_priority_fee_paid(&self, base_fee: u128) -> u128 {
+        self.frontrun_gas_details.priority_fee_paid(base_fee)
+    }
+
+    fn bribe(&self) -> u128 {
+        self.frontrun_gas_details.coinbase_transfer.unwrap_or(0)
+    }
+
+    fn protocols(&self) -> HashSet<Protocol> {
+        self.frontrun_swaps
+            .iter()
+            .chain(self.victim_swaps.iter().flatten())
+            .map(|swap| swap.protocol)
+            .collect()
+    }
+}
+
+impl Serialize for Frontrun {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("Frontrun", 28)?;
+        ser_struct.serialize_field("block_number", &self.block_number)?;
+
+        // frontrun
+        ser_struct.serialize_field("frontrun_tx_hash", &format!("{:?}", self.frontrun_tx_hash))?;
+
+        let frontrun_swaps: ClickhouseVecNormalizedSwap = self
+            .frontrun_swaps
+            .clone()
+            .try_into()
+            .map_err(serde::ser::Error::custom)?;
+        ser_struct.serialize_field("frontrun_swaps.trace_idx", &frontrun_swaps.trace_index)?;
+        ser_struct.serialize_field("frontrun_swaps.from", &frontrun_swaps.from)?;
+        ser_struct.serialize_field("frontrun_swaps.recipient", &frontrun_swaps.recipient)?;
+        ser_struct.serialize_field("frontrun_swaps.pool", &frontrun_swaps.pool)?;
+        ser_struct.serialize_field("frontrun_swaps.token_in", &frontrun_swaps.token_in)?;
+        ser_struct.serialize_field("frontrun_swaps.token_out", &frontrun_swaps.token_out)?;
+        ser_struct.serialize_field("frontrun_swaps.amount_in", &frontrun_swaps.amount_in)?;
+        ser_struct.serialize_field("frontrun_swaps.amount_out", &frontrun_swaps.amount_out)?;
+        ser_struct.serialize_field("frontrun_swaps.fee_tier", &frontrun_swaps.fee_tier)?;
+
+        let gas_details = (
+            self.frontrun_gas_details.coinbase_transfer,
+            self.frontrun_gas_details.priority_fee,
+            self.frontrun_gas_details.gas_used,
+            self.frontrun_gas_details.effective_gas_price,
+            self.frontrun_gas_details.gas_refunded,
+            self.frontrun_gas_details.access_list_gas,
+            self.frontrun_gas_details.blob_gas_used,
+            self.frontrun_gas_details.blob_gas_price,
+        );
+        ser_struct.serialize_field("frontrun_gas_details", &gas_details)?;
+
+        // victims
+        let victim_swaps: ClickhouseDoubleVecNormalizedSwap =
+            (self.victim_swaps_tx_hashes.clone(), self.victim_swaps.clone())
+                .try_into()
+                .map_err(serde::ser::Error::custom)?;
+        ser_struct.serialize_field("victim_swaps.tx_hash", &victim_swaps.tx_hash)?;
+        ser_struct.serialize_field("victim_swaps.trace_idx", &victim_swaps.trace_index)?;
+        ser_struct.serialize_field("victim_swaps.from", &victim_swaps.from)?;
+        ser_struct.serialize_field("victim_swaps.recipient", &victim_swaps.recipient)?;
+        ser_struct.serialize_field("victim_swaps.pool", &victim_swaps.pool)?;
+        ser_struct.serialize_field("victim_swaps.token_in", &victim_swaps.token_in)?;
+        ser_struct.serialize_field("victim_swaps.token_out", &victim_swaps.token_out)?;
+        ser_struct.serialize_field("victim_swaps.amount_in", &victim_swaps.amount_in)?;
+        ser_struct.serialize_field("victim_swaps.amount_out", &victim_swaps.amount_out)?;
+        ser_struct.serialize_field("victim_swaps.fee_tier", &victim_swaps.fee_tier)?;
+
+        let victim_gas_details: ClickhouseVecGasDetails =
+            (self.victim_swaps_tx_hashes.clone(), self.victim_swaps_gas_details.clone()).into();
+        ser_struct.serialize_field("victim_gas_details.tx_hash", &victim_gas_details.tx_hash)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.coinbase_transfer",
+            &victim_gas_details.coinbase_transfer,
+        )?;
+        ser_struct
+            .serialize_field("victim_gas_details.priority_fee", &victim_gas_details.priority_fee)?;
+        ser_struct.serialize_field("victim_gas_details.gas_used", &victim_gas_details.gas_used)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.effective_gas_price",
+            &victim_gas_details.effective_gas_price,
+        )?;
+        ser_struct
+            .serialize_field("victim_gas_details.gas_refunded", &victim_gas_details.gas_refunded)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.access_list_gas",
+            &victim_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_used",
+            &victim_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_price",
+            &victim_gas_details.blob_gas_price,
+        )?;
+
+        ser_struct.serialize_field("displacement_profit_usd", &self.displacement_profit_usd)?;
+
+        ser_struct.end()
+    }
+}
+
+#[cfg(feature = "native")]
+impl DbRow for Frontrun {
+    const COLUMN_NAMES: &'static [&'static str] = &[
+        "block_number",
+        "frontrun_tx_hash",
+        "frontrun_swaps.trace_idx",
+        "frontrun_swaps.from",
+        "frontrun_swaps.recipient",
+        "frontrun_swaps.pool",
+        "frontrun_swaps.token_in",
+        "frontrun_swaps.token_out",
+        "frontrun_swaps.amount_in",
+        "frontrun_swaps.amount_out",
+        "frontrun_swaps.fee_tier",
+        "frontrun_gas_details",
+        "victim_swaps.tx_hash",
+        "victim_swaps.trace_idx",
+        "victim_swaps.from",
+        "victim_swaps.recipient",
+        "victim_swaps.pool",
+        "victim_swaps.token_in",
+        "victim_swaps.token_out",
+        "victim_swaps.amount_in",
+        "victim_swaps.amount_out",
+        "victim_swaps.fee_tier",
+        "victim_gas_details.tx_hash",
+        "victim_gas_details.coinbase_transfer",
+        "victim_gas_details.priority_fee",
+        "victim_gas_details.gas_used",
+        "victim_gas_details.effective_gas_price",
+        "victim_gas_details.gas_refunded",
+        "victim_gas_details.access_list_gas",
+        "victim_gas_details.blob_gas_used",
+        "victim_gas_details.blob_gas_price",
+        "displacement_profit_usd",
+    ];
+}
@@ -1,9 +1,12 @@
 use std::fmt::Debug;
 
 use ahash::HashSet;
+use alloy_primitives::B256;
+#[cfg(feature = "native")]
 use clickhouse::DbRow;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{
     ser::{SerializeStruct, Serializer},
@@ -12,34 +15,38 @@ use serde::{
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
-use crate::{
-    db::redefined_types::primitives::*, normalized_actions::*, tree::ClickhouseVecGasDetails,
-    Protocol,
-};
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
 #[allow(unused_imports)]
 use crate::{
     display::utils::display_sandwich,
     normalized_actions::{NormalizedBurn, NormalizedLiquidation, NormalizedMint, NormalizedSwap},
     tree::GasDetails,
 };
+use crate::{normalized_actions::*, tree::ClickhouseVecGasDetails, Protocol};
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct JitLiquidity {
     pub frontrun_mint_tx_hash: B256,
     pub block_number: u64,
     pub frontrun_mints: Vec<NormalizedMint>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub frontrun_mint_gas_details: GasDetails,
     pub victim_swaps_tx_hashes: Vec<B256>,
     pub victim_swaps: Vec<Vec<NormalizedSwap>>,
     pub victim_swaps_gas_details_tx_hashes: Vec<B256>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub victim_swaps_gas_details: Vec<GasDetails>,
     pub backrun_burn_tx_hash: B256,
     pub backrun_burns: Vec<NormalizedBurn>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub backrun_burn_gas_details: GasDetails,
 }
 
@@ -83,7 +90,7 @@ impl Serialize for JitLiquidity {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 31)?;
+        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 32)?;
 
         // frontrun mint
         ser_struct.serialize_field(
@@ -110,6 +117,10 @@ impl Serialize for JitLiquidity {
             self.frontrun_mint_gas_details.priority_fee,
             self.frontrun_mint_gas_details.gas_used,
             self.frontrun_mint_gas_details.effective_gas_price,
+            self.frontrun_mint_gas_details.gas_refunded,
+            self.frontrun_mint_gas_details.access_list_gas,
+            self.frontrun_mint_gas_details.blob_gas_used,
+            self.frontrun_mint_gas_details.blob_gas_price,
         );
 
         ser_struct.serialize_field("frontrun_mint_gas_details", &(frontrun_mint_gas_details))?;
@@ -128,6 +139,7 @@ impl Serialize for JitLiquidity {
         ser_struct.serialize_field("victim_swaps.token_out", &victim_swaps.token_out)?;
         ser_struct.serialize_field("victim_swaps.amount_in", &victim_swaps.amount_in)?;
         ser_struct.serialize_field("victim_swaps.amount_out", &victim_swaps.amount_out)?;
+        ser_struct.serialize_field("victim_swaps.fee_tier", &victim_swaps.fee_tier)?;
 
         let victim_gas_details: ClickhouseVecGasDetails = (
             self.victim_swaps_gas_details_tx_hashes.clone(),
@@ -146,6 +158,20 @@ impl Serialize for JitLiquidity {
             "victim_gas_details.effective_gas_price",
             &victim_gas_details.effective_gas_price,
         )?;
+        ser_struct
+            .serialize_field("victim_gas_details.gas_refunded", &victim_gas_details.gas_refunded)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.access_list_gas",
+            &victim_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_used",
+            &victim_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_price",
+            &victim_gas_details.blob_gas_price,
+        )?;
 
         // backrun burn
         ser_struct
@@ -169,6 +195,10 @@ impl Serialize for JitLiquidity {
             self.backrun_burn_gas_details.priority_fee,
             self.backrun_burn_gas_details.gas_used,
             self.backrun_burn_gas_details.effective_gas_price,
+            self.backrun_burn_gas_details.gas_refunded,
+            self.backrun_burn_gas_details.access_list_gas,
+            self.backrun_burn_gas_details.blob_gas_used,
+            self.backrun_burn_gas_details.blob_gas_price,
         );
 
         ser_struct.serialize_field("backrun_burn_gas_details", &(backrun_burn_gas_details))?;
@@ -177,6 +207,7 @@ impl Serialize for JitLiquidity {
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for JitLiquidity {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "frontrun_mint_tx_hash",
@@ -197,11 +228,16 @@ impl DbRow for JitLiquidity {
         "victim_swaps.token_out",
         "victim_swaps.amount_in",
         "victim_swaps.amount_out",
+        "victim_swaps.fee_tier",
         "victim_gas_details.tx_hash",
         "victim_gas_details.coinbase_transfer",
         "victim_gas_details.priority_fee",
         "victim_gas_details.gas_used",
         "victim_gas_details.effective_gas_price",
+        "victim_gas_details.gas_refunded",
+        "victim_gas_details.access_list_gas",
+        "victim_gas_details.blob_gas_used",
+        "victim_gas_details.blob_gas_price",
         "backrun_burn_tx_hash",
         "backrun_burns.trace_idx",
         "backrun_burns.from",
@@ -2,47 +2,58 @@ use std::fmt::Debug;
 
 use ::serde::ser::{SerializeStruct, Serializer};
 use ahash::HashSet;
+use alloy_primitives::B256;
 #[allow(unused)]
+#[cfg(feature = "native")]
 use clickhouse::row::*;
+use itertools::Itertools;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use super::{Bundle, BundleData, BundleHeader, JitLiquidity, Mev, MevType, Sandwich};
-use crate::{
-    db::redefined_types::primitives::*, normalized_actions::*, tree::ClickhouseVecGasDetails,
-    Protocol,
+use super::{
+    Bundle, BundleData, BundleHeader, JitLiquidity, Mev, MevType, Sandwich, SupersededBundle,
+    MEV_TAXONOMY_VERSION,
 };
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
 #[allow(unused_imports)]
 use crate::{
     display::utils::display_sandwich,
     normalized_actions::{NormalizedBurn, NormalizedLiquidation, NormalizedMint, NormalizedSwap},
     GasDetails,
 };
+use crate::{normalized_actions::*, tree::ClickhouseVecGasDetails, Protocol};
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct JitLiquiditySandwich {
     pub block_number:         u64,
     pub frontrun_tx_hash:     Vec<B256>,
     pub frontrun_swaps:       Vec<Vec<NormalizedSwap>>,
     pub frontrun_mints:       Vec<Option<Vec<NormalizedMint>>>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub frontrun_gas_details: Vec<GasDetails>,
 
     pub victim_swaps_tx_hashes:   Vec<Vec<B256>>,
     pub victim_swaps:             Vec<Vec<NormalizedSwap>>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub victim_swaps_gas_details: Vec<GasDetails>,
 
     // Similar to frontrun fields, backrun fields are also vectors to handle multiple transactions.
     pub backrun_tx_hash:     B256,
     pub backrun_swaps:       Vec<NormalizedSwap>,
     pub backrun_burns:       Vec<NormalizedBurn>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub backrun_gas_details: GasDetails,
 }
 
@@ -132,6 +143,10 @@ pub fn compose_sandwich_jit(mev: Vec<Bundle>) -> Option<Bundle> {
         classified_sandwich.expect("Expected Classified MEV data for Sandwich");
     let jit_classified = jit_classified.expect("Expected Classified MEV data for JIT");
 
+    // a jit sandwich closes with a single backrun, the sequential backruns of a
+    // big mac sandwich are kept as the sandwich and jit they were found as
+    let [backrun_tx_hash] = sandwich.backrun_tx_hash[..] else { return None };
+
     let mut frontrun_mints: Vec<Option<Vec<NormalizedMint>>> =
         vec![None; sandwich.frontrun_tx_hash.len()];
     frontrun_mints
@@ -166,25 +181,47 @@ pub fn compose_sandwich_jit(mev: Vec<Bundle>) -> Option<Bundle> {
         victim_swaps_tx_hashes: sandwich.victim_swaps_tx_hashes,
         victim_swaps: sandwich.victim_swaps,
         victim_swaps_gas_details: sandwich.victim_swaps_gas_details,
-        backrun_tx_hash: sandwich.backrun_tx_hash,
-        backrun_swaps: sandwich.backrun_swaps,
+        backrun_tx_hash,
+        backrun_swaps: sandwich.backrun_swaps.into_iter().flatten().collect(),
         backrun_burns: jit.backrun_burns,
-        backrun_gas_details: sandwich.backrun_gas_details,
+        backrun_gas_details: sandwich.backrun_gas_details[0],
     };
 
+    let superseded = [&classified_sandwich, &jit_classified]
+        .into_iter()
+        .flat_map(|header| {
+            std::iter::once(SupersededBundle::new(header, true)).chain(header.superseded.clone())
+        })
+        .collect();
+
     // Create new classified MEV data
     let new_classified = BundleHeader {
-        tx_index:              classified_sandwich.tx_index,
-        tx_hash:               *sandwich.frontrun_tx_hash.first().unwrap_or_default(),
-        mev_type:              MevType::JitSandwich,
-        fund:                  classified_sandwich.fund,
-        block_number:          classified_sandwich.block_number,
-        eoa:                   jit_classified.eoa,
-        mev_contract:          classified_sandwich.mev_contract,
-        profit_usd:            classified_sandwich.profit_usd,
-        balance_deltas:        classified_sandwich.balance_deltas,
-        bribe_usd:             classified_sandwich.bribe_usd,
+        tx_index: classified_sandwich.tx_index,
+        tx_hash: *sandwich.frontrun_tx_hash.first().unwrap_or_default(),
+        mev_type: MevType::JitSandwich,
+        fund: classified_sandwich.fund,
+        block_number: classified_sandwich.block_number,
+        eoa: jit_classified.eoa,
+        mev_contract: classified_sandwich.mev_contract,
+        profit_usd: classified_sandwich.profit_usd,
+        balance_deltas: classified_sandwich.balance_deltas,
+        bribe_usd: classified_sandwich.bribe_usd,
         no_pricing_calculated: classified_sandwich.no_pricing_calculated,
+        sanctioned_addresses: classified_sandwich
+            .sanctioned_addresses
+            .into_iter()
+            .chain(jit_classified.sanctioned_addresses)
+            .unique()
+            .collect(),
+        taxonomy_version: MEV_TAXONOMY_VERSION,
+        superseded,
+        // verified after the bundles of the block are composed
+        simulated_profit_usd: None,
+        profit_discrepancy: false,
+        submission_channel: classified_sandwich.submission_channel,
+        // set once the bundles of the block are composed
+        #[cfg(feature = "provenance")]
+        provenance: vec![],
     };
 
     Some(Bundle { header: new_classified, data: BundleData::JitSandwich(jit_sand) })
@@ -195,7 +232,7 @@ impl Serialize for JitLiquiditySandwich {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("JitLiquiditySandwich", 35)?;
+        let mut ser_struct = serializer.serialize_struct("JitLiquiditySandwich", 38)?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
 
         // frontruns
@@ -217,6 +254,7 @@ impl Serialize for JitLiquiditySandwich {
         ser_struct.serialize_field("frontrun_swaps.token_out", &frontrun_swaps.token_out)?;
         ser_struct.serialize_field("frontrun_swaps.amount_in", &frontrun_swaps.amount_in)?;
         ser_struct.serialize_field("frontrun_swaps.amount_out", &frontrun_swaps.amount_out)?;
+        ser_struct.serialize_field("frontrun_swaps.fee_tier", &frontrun_swaps.fee_tier)?;
 
         let frontrun_mints: ClickhouseVecNormalizedMintOrBurnWithTxHash =
             (self.frontrun_tx_hash.clone(), self.frontrun_mints.clone())
@@ -249,6 +287,22 @@ impl Serialize for JitLiquiditySandwich {
             "frontrun_gas_details.effective_gas_price",
             &frontrun_gas_details.effective_gas_price,
         )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.gas_refunded",
+            &frontrun_gas_details.gas_refunded,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.access_list_gas",
+            &frontrun_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.blob_gas_used",
+            &frontrun_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.blob_gas_price",
+            &frontrun_gas_details.blob_gas_price,
+        )?;
 
         // victims
         let victim_swaps: ClickhouseDoubleVecNormalizedSwap =
@@ -264,6 +318,7 @@ impl Serialize for JitLiquiditySandwich {
         ser_struct.serialize_field("victim_swaps.token_out", &victim_swaps.token_out)?;
         ser_struct.serialize_field("victim_swaps.amount_in", &victim_swaps.amount_in)?;
         ser_struct.serialize_field("victim_swaps.amount_out", &victim_swaps.amount_out)?;
+        ser_struct.serialize_field("victim_swaps.fee_tier", &victim_swaps.fee_tier)?;
 
         let victim_gas_details: ClickhouseVecGasDetails =
             (self.victim_swaps_tx_hashes.clone(), self.victim_swaps_gas_details.clone()).into();
@@ -279,6 +334,20 @@ impl Serialize for JitLiquiditySandwich {
             "victim_gas_details.effective_gas_price",
             &victim_gas_details.effective_gas_price,
         )?;
+        ser_struct
+            .serialize_field("victim_gas_details.gas_refunded", &victim_gas_details.gas_refunded)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.access_list_gas",
+            &victim_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_used",
+            &victim_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_price",
+            &victim_gas_details.blob_gas_price,
+        )?;
 
         // backrun
         let fixed_str_backrun_tx_hash = format!("{:?}", &self.backrun_tx_hash);
@@ -304,6 +373,7 @@ impl Serialize for JitLiquiditySandwich {
         ser_struct.serialize_field("backrun_swaps.token_out", &backrun_swaps.token_out)?;
         ser_struct.serialize_field("backrun_swaps.amount_in", &backrun_swaps.amount_in)?;
         ser_struct.serialize_field("backrun_swaps.amount_out", &backrun_swaps.amount_out)?;
+        ser_struct.serialize_field("backrun_swaps.fee_tier", &backrun_swaps.fee_tier)?;
 
         let backrun_burns: ClickhouseVecNormalizedMintOrBurn = self
             .backrun_burns
@@ -343,11 +413,28 @@ impl Serialize for JitLiquiditySandwich {
             "backrun_gas_details.effective_gas_price",
             &vec![self.backrun_gas_details.effective_gas_price],
         )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.gas_refunded",
+            &vec![self.backrun_gas_details.gas_refunded],
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.access_list_gas",
+            &vec![self.backrun_gas_details.access_list_gas],
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.blob_gas_used",
+            &vec![self.backrun_gas_details.blob_gas_used],
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.blob_gas_price",
+            &vec![self.backrun_gas_details.blob_gas_price],
+        )?;
 
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for JitLiquiditySandwich {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_number",
@@ -361,6 +448,7 @@ impl DbRow for JitLiquiditySandwich {
         "frontrun_swaps.token_out",
         "frontrun_swaps.amount_in",
         "frontrun_swaps.amount_out",
+        "frontrun_swaps.fee_tier",
         "frontrun_mints.tx_hash",
         "frontrun_mints.trace_idx",
         "frontrun_mints.from",
@@ -373,6 +461,10 @@ impl DbRow for JitLiquiditySandwich {
         "frontrun_gas_details.priority_fee",
         "frontrun_gas_details.gas_used",
         "frontrun_gas_details.effective_gas_price",
+        "frontrun_gas_details.gas_refunded",
+        "frontrun_gas_details.access_list_gas",
+        "frontrun_gas_details.blob_gas_used",
+        "frontrun_gas_details.blob_gas_price",
         "victim_swaps.tx_hash",
         "victim_swaps.trace_idx",
         "victim_swaps.from",
@@ -382,11 +474,16 @@ impl DbRow for JitLiquiditySandwich {
         "victim_swaps.token_out",
         "victim_swaps.amount_in",
         "victim_swaps.amount_out",
+        "victim_swaps.fee_tier",
         "victim_gas_details.tx_hash",
         "victim_gas_details.coinbase_transfer",
         "victim_gas_details.priority_fee",
         "victim_gas_details.gas_used",
         "victim_gas_details.effective_gas_price",
+        "victim_gas_details.gas_refunded",
+        "victim_gas_details.access_list_gas",
+        "victim_gas_details.blob_gas_used",
+        "victim_gas_details.blob_gas_price",
         "backrun_tx_hash",
         "backrun_swaps.tx_hash",
         "backrun_swaps.trace_idx",
@@ -397,6 +494,7 @@ impl DbRow for JitLiquiditySandwich {
         "backrun_swaps.token_out",
         "backrun_swaps.amount_in",
         "backrun_swaps.amount_out",
+        "backrun_swaps.fee_tier",
         "backrun_burns.tx_hash",
         "backrun_burns.trace_idx",
         "backrun_burns.from",
@@ -409,5 +507,9 @@ impl DbRow for JitLiquiditySandwich {
         "backrun_gas_details.priority_fee",
         "backrun_gas_details.gas_used",
         "backrun_gas_details.effective_gas_price",
+        "backrun_gas_details.gas_refunded",
+        "backrun_gas_details.access_list_gas",
+        "backrun_gas_details.blob_gas_used",
+        "backrun_gas_details.blob_gas_price",
     ];
 }
@@ -1,32 +1,71 @@
 use std::fmt::Debug;
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use ::serde::ser::{SerializeStruct, Serializer};
 use ahash::HashSet;
+use alloy_primitives::{Address, B256};
 #[allow(unused)]
+#[cfg(feature = "native")]
 use clickhouse::fixed_string::FixedString;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
-use crate::{db::redefined_types::primitives::*, Protocol};
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
+use crate::Protocol;
 #[allow(unused_imports)]
 use crate::{display::utils::display_sandwich, normalized_actions::*, GasDetails};
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct Liquidation {
-    pub liquidation_tx_hash: B256,
-    pub block_number:        u64,
-    pub trigger:             B256,
-    pub liquidation_swaps:   Vec<NormalizedSwap>,
-    pub liquidations:        Vec<NormalizedLiquidation>,
-    #[redefined(same_fields)]
-    pub gas_details:         GasDetails,
+    pub liquidation_tx_hash:  B256,
+    pub block_number:         u64,
+    pub trigger:              B256,
+    pub liquidation_swaps:    Vec<NormalizedSwap>,
+    pub liquidations:         Vec<NormalizedLiquidation>,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub gas_details:          GasDetails,
+    /// State of the liquidated positions at the end of the prior block
+    #[serde(default)]
+    pub liquidation_contexts: Vec<LiquidationContext>,
+}
+
+/// Health of a liquidated position right before the liquidation, read from the
+/// lending protocol at the block before the liquidation
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
+pub struct LiquidationContext {
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub protocol:           Protocol,
+    pub debtor:             Address,
+    pub block_number:       u64,
+    /// Risk adjusted collateral value over the debt value, the position can
+    /// be liquidated below 1. `None` for the protocols that only expose
+    /// whether a position can be liquidated
+    pub health_factor:      Option<f64>,
+    pub liquidatable:       bool,
+    /// The collateral of the debtor, the amounts are scaled by the decimals
+    /// of the collateral token. Compound V2 collateral is held in cTokens
+    pub collateral_assets:  Vec<Address>,
+    pub collateral_amounts: Vec<f64>,
 }
 
 impl Mev for Liquidation {
@@ -70,7 +109,7 @@ impl Serialize for Liquidation {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("Liquidation", 34)?;
+        let mut ser_struct = serializer.serialize_struct("Liquidation", 35)?;
 
         // frontrun
         ser_struct
@@ -93,6 +132,7 @@ impl Serialize for Liquidation {
         ser_struct.serialize_field("liquidation_swaps.amount_in", &liquidation_swaps.amount_in)?;
         ser_struct
             .serialize_field("liquidation_swaps.amount_out", &liquidation_swaps.amount_out)?;
+        ser_struct.serialize_field("liquidation_swaps.fee_tier", &liquidation_swaps.fee_tier)?;
 
         // victims
         let liquidations: ClickhouseVecNormalizedLiquidation = self
@@ -119,14 +159,70 @@ impl Serialize for Liquidation {
             self.gas_details.priority_fee,
             self.gas_details.gas_used,
             self.gas_details.effective_gas_price,
+            self.gas_details.gas_refunded,
+            self.gas_details.access_list_gas,
+            self.gas_details.blob_gas_used,
+            self.gas_details.blob_gas_price,
         );
         //serializer.seri
         ser_struct.serialize_field("gas_details", &(gas_details))?;
 
+        let contexts = &self.liquidation_contexts;
+        ser_struct.serialize_field(
+            "liquidation_contexts.protocol",
+            &contexts
+                .iter()
+                .map(|context| context.protocol.to_string())
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field(
+            "liquidation_contexts.debtor",
+            &contexts
+                .iter()
+                .map(|context| format!("{:?}", context.debtor))
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field(
+            "liquidation_contexts.block_number",
+            &contexts
+                .iter()
+                .map(|context| context.block_number)
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field(
+            "liquidation_contexts.health_factor",
+            &contexts
+                .iter()
+                .map(|context| context.health_factor)
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field(
+            "liquidation_contexts.liquidatable",
+            &contexts
+                .iter()
+                .map(|context| context.liquidatable)
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field(
+            "liquidation_contexts.collateral",
+            &contexts
+                .iter()
+                .map(|context| {
+                    context
+                        .collateral_assets
+                        .iter()
+                        .zip(&context.collateral_amounts)
+                        .map(|(asset, amount)| (format!("{:?}", asset), *amount))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+        )?;
+
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for Liquidation {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "liquidation_tx_hash",
@@ -139,6 +235,7 @@ impl DbRow for Liquidation {
         "liquidation_swaps.token_out",
         "liquidation_swaps.amount_in",
         "liquidation_swaps.amount_out",
+        "liquidation_swaps.fee_tier",
         "liquidations.trace_idx",
         "liquidations.pool",
         "liquidations.liquidator",
@@ -148,5 +245,11 @@ impl DbRow for Liquidation {
         "liquidations.covered_debt",
         "liquidations.liquidated_collateral",
         "gas_details",
+        "liquidation_contexts.protocol",
+        "liquidation_contexts.debtor",
+        "liquidation_contexts.block_number",
+        "liquidation_contexts.health_factor",
+        "liquidation_contexts.liquidatable",
+        "liquidation_contexts.collateral",
     ];
 }
@@ -12,6 +12,10 @@ pub mod liquidation;
 pub use liquidation::*;
 pub mod jit_sandwich;
 pub use jit_sandwich::*;
+pub mod frontrun;
+pub use frontrun::*;
+pub mod approval_race;
+pub use approval_race::*;
 pub mod block;
 pub use block::*;
 pub mod searcher_tx;
@@ -1,20 +1,23 @@
 use std::fmt::Debug;
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use ::serde::ser::{SerializeStruct, Serializer};
 use ahash::HashSet;
+use alloy_primitives::{Address, B256};
 use malachite::Rational;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::{Address, B256};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
 use crate::{
-    db::{redefined_types::primitives::*, token_info::TokenInfoWithAddress},
-    normalized_actions::*,
-    ClickhouseVecGasDetails, Protocol,
+    db::token_info::TokenInfoWithAddress, normalized_actions::*, ClickhouseVecGasDetails, Protocol,
 };
 #[allow(unused_imports)]
 use crate::{
@@ -57,8 +60,13 @@ use crate::{
 /// - Victim 2: [Etherscan Link](https://etherscan.io/tx/0x0b428553bc2ccc8047b0da46e6c1c1e8a338d9a461850fcd67ddb233f6984677)
 /// - Backrun: [Etherscan Link](https://etherscan.io/tx/0xfb2ef488bf7b6ad09accb126330837198b0857d2ea0052795af520d470eb5e1d)
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct Sandwich {
     pub block_number:             u64,
     /// Transaction hashes of the frontrunning transactions.
@@ -68,26 +76,29 @@ pub struct Sandwich {
     /// Nested vectors represent multiple swaps within each transaction.
     pub frontrun_swaps:           Vec<Vec<NormalizedSwap>>,
     /// Gas details for each frontrunning transaction.
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub frontrun_gas_details:     Vec<GasDetails>,
     /// Transaction hashes of the victim transactions, logically grouped by
     /// their corresponding frontrunning transaction. Each outer vector
     /// index corresponds to a frontrun transaction, grouping victims targeted
-    /// by that specific frontrun.
+    /// by that specific frontrun. Sequential legs of a big mac sandwich have
+    /// no victims of their own.
     pub victim_swaps_tx_hashes:   Vec<Vec<B256>>,
     /// Swaps executed by victims, each outer vector corresponds to a victim
     /// transaction.
     pub victim_swaps:             Vec<Vec<NormalizedSwap>>,
     /// Gas details for each victim transaction.
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub victim_swaps_gas_details: Vec<GasDetails>,
-    /// Transaction hashes of the backrunning transactions.
-    pub backrun_tx_hash:          B256,
+    /// Transaction hashes of the backrunning transactions. The sequential
+    /// legs of a big mac sandwich that follow its last victim are backruns,
+    /// the last hash is the one closing the sandwich.
+    pub backrun_tx_hash:          Vec<B256>,
     /// Swaps executed in each backrunning transaction.
-    pub backrun_swaps:            Vec<NormalizedSwap>,
+    pub backrun_swaps:            Vec<Vec<NormalizedSwap>>,
     /// Gas details for each backrunning transaction.
-    #[redefined(same_fields)]
-    pub backrun_gas_details:      GasDetails,
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
+    pub backrun_gas_details:      Vec<GasDetails>,
 }
 
 /// calcuation for the loss per user
@@ -109,35 +120,32 @@ impl Mev for Sandwich {
     fn total_gas_paid(&self) -> u128 {
         self.frontrun_gas_details
             .iter()
+            .chain(&self.backrun_gas_details)
             .map(|gd| gd.gas_paid())
             .sum::<u128>()
-            + self.backrun_gas_details.gas_paid()
     }
 
     fn total_priority_fee_paid(&self, base_fee: u128) -> u128 {
         self.frontrun_gas_details
             .iter()
+            .chain(&self.backrun_gas_details)
             .map(|gd| gd.priority_fee_paid(base_fee))
             .sum::<u128>()
-            + self.backrun_gas_details.priority_fee_paid(base_fee)
     }
 
     // Should always be on the backrun, but you never know
     fn bribe(&self) -> u128 {
         self.frontrun_gas_details
             .iter()
+            .chain(&self.backrun_gas_details)
             .filter_map(|gd| gd.coinbase_transfer)
             .sum::<u128>()
-            + self
-                .backrun_gas_details
-                .coinbase_transfer
-                .unwrap_or_default()
     }
 
     fn mev_transaction_hashes(&self) -> Vec<B256> {
         let mut txs = self.frontrun_tx_hash.clone();
         txs.extend(self.victim_swaps_tx_hashes.iter().flatten().copied());
-        txs.push(self.backrun_tx_hash);
+        txs.extend(self.backrun_tx_hash.iter().copied());
         txs
     }
 
@@ -153,7 +161,7 @@ impl Mev for Sandwich {
             protocols.insert(swap.protocol);
         });
 
-        self.backrun_swaps.iter().for_each(|swap| {
+        self.backrun_swaps.iter().flatten().for_each(|swap| {
             protocols.insert(swap.protocol);
         });
 
@@ -166,7 +174,7 @@ impl Serialize for Sandwich {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("Sandwich", 35)?;
+        let mut ser_struct = serializer.serialize_struct("Sandwich", 38)?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
 
         // frontrun
@@ -188,6 +196,7 @@ impl Serialize for Sandwich {
         ser_struct.serialize_field("frontrun_swaps.token_out", &frontrun_swaps.token_out)?;
         ser_struct.serialize_field("frontrun_swaps.amount_in", &frontrun_swaps.amount_in)?;
         ser_struct.serialize_field("frontrun_swaps.amount_out", &frontrun_swaps.amount_out)?;
+        ser_struct.serialize_field("frontrun_swaps.fee_tier", &frontrun_swaps.fee_tier)?;
 
         let frontrun_gas_details: ClickhouseVecGasDetails =
             (self.frontrun_tx_hash.clone(), self.frontrun_gas_details.clone()).into();
@@ -207,6 +216,22 @@ impl Serialize for Sandwich {
             "frontrun_gas_details.effective_gas_price",
             &frontrun_gas_details.effective_gas_price,
         )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.gas_refunded",
+            &frontrun_gas_details.gas_refunded,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.access_list_gas",
+            &frontrun_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.blob_gas_used",
+            &frontrun_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "frontrun_gas_details.blob_gas_price",
+            &frontrun_gas_details.blob_gas_price,
+        )?;
 
         // victims
         let victim_swaps: ClickhouseDoubleVecNormalizedSwap =
@@ -222,6 +247,7 @@ impl Serialize for Sandwich {
         ser_struct.serialize_field("victim_swaps.token_out", &victim_swaps.token_out)?;
         ser_struct.serialize_field("victim_swaps.amount_in", &victim_swaps.amount_in)?;
         ser_struct.serialize_field("victim_swaps.amount_out", &victim_swaps.amount_out)?;
+        ser_struct.serialize_field("victim_swaps.fee_tier", &victim_swaps.fee_tier)?;
 
         let victim_gas_details: ClickhouseVecGasDetails =
             (self.victim_swaps_tx_hashes.clone(), self.victim_swaps_gas_details.clone()).into();
@@ -237,23 +263,32 @@ impl Serialize for Sandwich {
             "victim_gas_details.effective_gas_price",
             &victim_gas_details.effective_gas_price,
         )?;
+        ser_struct
+            .serialize_field("victim_gas_details.gas_refunded", &victim_gas_details.gas_refunded)?;
+        ser_struct.serialize_field(
+            "victim_gas_details.access_list_gas",
+            &victim_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_used",
+            &victim_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "victim_gas_details.blob_gas_price",
+            &victim_gas_details.blob_gas_price,
+        )?;
 
         // backrun
-        let fixed_str_backrun_tx_hash = format!("{:?}", &self.backrun_tx_hash);
-        ser_struct.serialize_field("backrun_tx_hash", &fixed_str_backrun_tx_hash)?;
-
-        let backrun_swaps: ClickhouseVecNormalizedSwap = self
-            .backrun_swaps
-            .clone()
-            .try_into()
-            .map_err(serde::ser::Error::custom)?;
-        let backrun_tx_hash_repeated = [&self.backrun_tx_hash]
-            .repeat(backrun_swaps.amount_in.len())
-            .into_iter()
-            .map(|tx| format!("{:?}", &tx))
-            .collect::<Vec<_>>();
+        ser_struct.serialize_field(
+            "backrun_tx_hash",
+            &format!("{:?}", self.backrun_tx_hash.last().unwrap_or_default()),
+        )?;
 
-        ser_struct.serialize_field("backrun_swaps.tx_hash", &backrun_tx_hash_repeated)?;
+        let backrun_swaps: ClickhouseDoubleVecNormalizedSwap =
+            (self.backrun_tx_hash.clone(), self.backrun_swaps.clone())
+                .try_into()
+                .map_err(serde::ser::Error::custom)?;
+        ser_struct.serialize_field("backrun_swaps.tx_hash", &backrun_swaps.tx_hash)?;
         ser_struct.serialize_field("backrun_swaps.trace_idx", &backrun_swaps.trace_index)?;
         ser_struct.serialize_field("backrun_swaps.from", &backrun_swaps.from)?;
         ser_struct.serialize_field("backrun_swaps.recipient", &backrun_swaps.recipient)?;
@@ -262,30 +297,47 @@ impl Serialize for Sandwich {
         ser_struct.serialize_field("backrun_swaps.token_out", &backrun_swaps.token_out)?;
         ser_struct.serialize_field("backrun_swaps.amount_in", &backrun_swaps.amount_in)?;
         ser_struct.serialize_field("backrun_swaps.amount_out", &backrun_swaps.amount_out)?;
+        ser_struct.serialize_field("backrun_swaps.fee_tier", &backrun_swaps.fee_tier)?;
 
-        ser_struct
-            .serialize_field("backrun_gas_details.tx_hash", &vec![fixed_str_backrun_tx_hash])?;
+        let backrun_gas_details: ClickhouseVecGasDetails =
+            (self.backrun_tx_hash.clone(), self.backrun_gas_details.clone()).into();
+        ser_struct.serialize_field("backrun_gas_details.tx_hash", &backrun_gas_details.tx_hash)?;
         ser_struct.serialize_field(
             "backrun_gas_details.coinbase_transfer",
-            &vec![self.backrun_gas_details.coinbase_transfer],
+            &backrun_gas_details.coinbase_transfer,
         )?;
         ser_struct.serialize_field(
             "backrun_gas_details.priority_fee",
-            &vec![self.backrun_gas_details.priority_fee],
+            &backrun_gas_details.priority_fee,
         )?;
+        ser_struct
+            .serialize_field("backrun_gas_details.gas_used", &backrun_gas_details.gas_used)?;
         ser_struct.serialize_field(
-            "backrun_gas_details.gas_used",
-            &vec![self.backrun_gas_details.gas_used],
+            "backrun_gas_details.effective_gas_price",
+            &backrun_gas_details.effective_gas_price,
         )?;
         ser_struct.serialize_field(
-            "backrun_gas_details.effective_gas_price",
-            &vec![self.backrun_gas_details.effective_gas_price],
+            "backrun_gas_details.gas_refunded",
+            &backrun_gas_details.gas_refunded,
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.access_list_gas",
+            &backrun_gas_details.access_list_gas,
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.blob_gas_used",
+            &backrun_gas_details.blob_gas_used,
+        )?;
+        ser_struct.serialize_field(
+            "backrun_gas_details.blob_gas_price",
+            &backrun_gas_details.blob_gas_price,
         )?;
 
         ser_struct.end()
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for Sandwich {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_number",
@@ -299,11 +351,16 @@ impl DbRow for Sandwich {
         "frontrun_swaps.token_out",
         "frontrun_swaps.amount_in",
         "frontrun_swaps.amount_out",
+        "frontrun_swaps.fee_tier",
         "frontrun_gas_details.tx_hash",
         "frontrun_gas_details.coinbase_transfer",
         "frontrun_gas_details.priority_fee",
         "frontrun_gas_details.gas_used",
         "frontrun_gas_details.effective_gas_price",
+        "frontrun_gas_details.gas_refunded",
+        "frontrun_gas_details.access_list_gas",
+        "frontrun_gas_details.blob_gas_used",
+        "frontrun_gas_details.blob_gas_price",
         "victim_swaps.tx_hash",
         "victim_swaps.trace_idx",
         "victim_swaps.from",
@@ -313,11 +370,16 @@ impl DbRow for Sandwich {
         "victim_swaps.token_out",
         "victim_swaps.amount_in",
         "victim_swaps.amount_out",
+        "victim_swaps.fee_tier",
         "victim_gas_details.tx_hash",
         "victim_gas_details.coinbase_transfer",
         "victim_gas_details.priority_fee",
         "victim_gas_details.gas_used",
         "victim_gas_details.effective_gas_price",
+        "victim_gas_details.gas_refunded",
+        "victim_gas_details.access_list_gas",
+        "victim_gas_details.blob_gas_used",
+        "victim_gas_details.blob_gas_price",
         "backrun_tx_hash",
         "backrun_swaps.tx_hash",
         "backrun_swaps.trace_idx",
@@ -328,10 +390,15 @@ impl DbRow for Sandwich {
         "backrun_swaps.token_out",
         "backrun_swaps.amount_in",
         "backrun_swaps.amount_out",
+        "backrun_swaps.fee_tier",
         "backrun_gas_details.tx_hash",
         "backrun_gas_details.coinbase_transfer",
         "backrun_gas_details.priority_fee",
         "backrun_gas_details.gas_used",
         "backrun_gas_details.effective_gas_price",
+        "backrun_gas_details.gas_refunded",
+        "backrun_gas_details.access_list_gas",
+        "backrun_gas_details.blob_gas_used",
+        "backrun_gas_details.blob_gas_price",
     ];
 }
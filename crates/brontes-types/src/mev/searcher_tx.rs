@@ -2,30 +2,39 @@ use std::fmt::Debug;
 
 use ::serde::ser::Serializer;
 use ahash::{HashSet, HashSetExt};
+use alloy_primitives::B256;
+#[cfg(feature = "native")]
 use clickhouse::DbRow;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::B256;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
 
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
+#[allow(unused_imports)]
+use crate::{display::utils::display_sandwich, normalized_actions::NormalizedTransfer, GasDetails};
 use crate::{
-    db::redefined_types::primitives::*,
     mev::{Mev, MevType},
     normalized_actions::*,
     Protocol,
 };
-#[allow(unused_imports)]
-use crate::{display::utils::display_sandwich, normalized_actions::NormalizedTransfer, GasDetails};
 
 #[serde_as]
-#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct SearcherTx {
     pub tx_hash:      B256,
     pub block_number: u64,
     pub transfers:    Vec<NormalizedTransfer>,
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub gas_details:  GasDetails,
 }
 
@@ -82,6 +91,10 @@ impl Serialize for SearcherTx {
             self.gas_details.priority_fee,
             self.gas_details.gas_used,
             self.gas_details.effective_gas_price,
+            self.gas_details.gas_refunded,
+            self.gas_details.access_list_gas,
+            self.gas_details.blob_gas_used,
+            self.gas_details.blob_gas_price,
         );
 
         ser_struct.serialize_field("gas_details", &(gas_details))?;
@@ -90,6 +103,7 @@ impl Serialize for SearcherTx {
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for SearcherTx {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "tx_hash",
@@ -1,12 +1,16 @@
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
-use reth_primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
 pub use super::{Action, NormalizedSwap, NormalizedTransfer};
 use crate::Protocol;
 
-#[derive(Debug, Serialize, Clone, Row, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Clone, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedAggregator {
     pub protocol:    Protocol,
     pub trace_index: u64,
@@ -24,6 +28,11 @@ pub struct NormalizedAggregator {
     //  - Transfers
     pub child_actions: Vec<Action>,
     pub msg_value:     U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:    Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedAggregator {
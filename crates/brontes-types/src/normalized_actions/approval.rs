@@ -0,0 +1,94 @@
+use alloy_primitives::{Address, U160, U256};
+use serde::{Deserialize, Serialize};
+
+use super::custom::CustomAction;
+use crate::db::token_info::TokenInfoWithAddress;
+
+/// How the owner granted the allowance
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApprovalKind {
+    /// An `approve` call of the owner on the token
+    #[default]
+    Approve,
+    /// An EIP-2612 `permit` signed by the owner, which anyone can submit
+    Permit,
+    /// An allowance on the Permit2 contract, either approved by the owner or
+    /// from a signed permit. Permit2 itself holds the token allowance
+    Permit2,
+}
+
+/// An allowance granted by the owner of a token to a spender.
+///
+/// Approvals don't move tokens, so the action has no token deltas. They are
+/// classified so that the transactions spending an allowance, or racing a
+/// change to it, can be tied to the approval.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedApproval {
+    pub kind:        ApprovalKind,
+    pub trace_index: u64,
+    pub token:       TokenInfoWithAddress,
+    pub owner:       Address,
+    pub spender:     Address,
+    /// The allowance in token units, not scaled by the decimals, so that
+    /// unlimited approvals are kept as is
+    pub amount:      U256,
+    pub msg_value:   U256,
+}
+
+impl NormalizedApproval {
+    /// Permit2 allowances are capped at a `uint160`
+    pub fn is_unlimited(&self) -> bool {
+        match self.kind {
+            ApprovalKind::Permit2 => self.amount >= U256::from(U160::MAX),
+            ApprovalKind::Approve | ApprovalKind::Permit => self.amount == U256::MAX,
+        }
+    }
+
+    pub fn is_revoke(&self) -> bool {
+        self.amount.is_zero()
+    }
+}
+
+impl CustomAction for NormalizedApproval {
+    const KIND: &'static str = "approval";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.owner
+    }
+
+    fn to_address(&self) -> Address {
+        self.spender
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::{register_custom_action, Action, CustomActionBox};
+
+    #[test]
+    fn test_approval_round_trip() {
+        let approval = NormalizedApproval {
+            kind: ApprovalKind::Permit2,
+            trace_index: 2,
+            owner: Address::with_last_byte(1),
+            spender: Address::with_last_byte(2),
+            amount: U256::from(U160::MAX),
+            ..Default::default()
+        };
+        assert!(approval.is_unlimited());
+        assert!(
+            !NormalizedApproval { kind: ApprovalKind::Approve, ..approval.clone() }.is_unlimited()
+        );
+
+        register_custom_action::<NormalizedApproval>();
+        let action = Action::Custom(approval.clone().into());
+        let value = serde_json::to_value(&action).unwrap();
+        let custom = serde_json::from_value::<CustomActionBox>(value).unwrap();
+        assert_eq!(custom.downcast_ref::<NormalizedApproval>(), Some(&approval));
+    }
+}
@@ -0,0 +1,91 @@
+use alloy_primitives::{Address, B256, U256};
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+use super::custom::CustomAction;
+use crate::{db::token_info::TokenInfoWithAddress, Protocol};
+
+/// A collateral auction started for an unsafe position, e.g. Maker
+/// `Dog.bark`.
+///
+/// Starting the auction only moves the collateral and debt of the position
+/// to the auction inside the protocol, the keeper is paid an incentive for
+/// it. The collateral is sold later, each purchase is classified as a
+/// liquidation of its own, so the action has no token deltas and isn't
+/// counted as a liquidation.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedAuctionStart {
+    pub protocol:         Protocol,
+    pub trace_index:      u64,
+    pub from:             Address,
+    /// Receiver of the keeper incentive
+    pub keeper:           Address,
+    /// The auction house the collateral is sold by
+    pub pool:             Address,
+    pub auction_id:       U256,
+    /// Collateral type of the position, the Maker `ilk`
+    pub collateral_type:  B256,
+    /// Owner of the position
+    pub debtor:           Address,
+    pub collateral_asset: TokenInfoWithAddress,
+    pub debt_asset:       TokenInfoWithAddress,
+    /// Collateral put up for auction
+    pub collateral:       Rational,
+    /// Debt of the position, without the liquidation penalty
+    pub debt:             Rational,
+    pub msg_value:        U256,
+}
+
+impl CustomAction for NormalizedAuctionStart {
+    const KIND: &'static str = "auction_start";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.pool
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::{
+        accounting::ActionAccounting, register_custom_action, Action, CustomActionBox,
+    };
+
+    #[test]
+    fn test_auction_start_round_trip() {
+        let start = NormalizedAuctionStart {
+            protocol: Protocol::MakerDog,
+            trace_index: 1,
+            keeper: Address::with_last_byte(1),
+            pool: Address::with_last_byte(2),
+            auction_id: U256::from(7),
+            debtor: Address::with_last_byte(3),
+            collateral: Rational::from(2),
+            debt: Rational::from(3000),
+            ..Default::default()
+        };
+
+        register_custom_action::<NormalizedAuctionStart>();
+        let action = Action::Custom(CustomActionBox::new(start.clone()));
+        // the collateral is only sold by the liquidations that follow
+        assert!(std::iter::once(action.clone())
+            .account_for_actions()
+            .is_empty());
+
+        let value = serde_json::to_value(&action).unwrap();
+        let custom = serde_json::from_value::<CustomActionBox>(value).unwrap();
+        assert_eq!(custom.downcast_ref::<NormalizedAuctionStart>(), Some(&start));
+    }
+}
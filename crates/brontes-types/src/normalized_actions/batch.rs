@@ -1,14 +1,18 @@
 use std::fmt::Debug;
 
 use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
 pub use super::{Action, NormalizedSwap};
 use crate::Protocol;
 
-#[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedBatch {
     pub protocol:            Protocol,
     pub trace_index:         u64,
@@ -17,6 +21,11 @@ pub struct NormalizedBatch {
     pub user_swaps:          Vec<NormalizedSwap>,
     pub solver_swaps:        Option<Vec<NormalizedSwap>>,
     pub msg_value:           U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:          Option<ActionProvenance>,
 }
 
 impl NormalizedBatch {
@@ -20,6 +20,8 @@ impl ActionCmp<Action> for Action {
             Action::Swap(s) => s.is_superior_action(other),
             Action::Mint(m) => m.is_superior_action(other),
             Action::Collect(c) => c.is_superior_action(other),
+            Action::VaultDeposit(v) => v.is_superior_action(other),
+            Action::VaultWithdraw(v) => v.is_superior_action(other),
             Action::SwapWithFee(s) => s.swap.is_superior_action(other),
             Action::FlashLoan(f) => f.child_actions.iter().any(|a| a.is_superior_action(other)),
             Action::Batch(b) => {
@@ -0,0 +1,354 @@
+//! Extension point for protocol specific actions that live outside of
+//! brontes. A downstream crate implements [`CustomAction`] for its own type,
+//! registers it once with [`register_custom_action`] and wraps it in
+//! [`Action::Custom`](super::Action::Custom). The tree, the accounting and
+//! serialization then handle it like any other action, without the `Action`
+//! enum or its matches having to change.
+//!
+//! Custom actions are serialized tagged with their [`CustomAction::KIND`],
+//! which is used to find the registered type when they are deserialized.
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use alloy_primitives::Address;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::accounting::AddressDeltas;
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+use crate::{FastHashMap, Protocol};
+
+pub trait CustomAction:
+    Debug + Clone + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    /// Unique name of the action, used as the serialization tag
+    const KIND: &'static str;
+
+    fn trace_index(&self) -> u64;
+
+    fn from_address(&self) -> Address {
+        Address::ZERO
+    }
+
+    fn to_address(&self) -> Address {
+        Address::ZERO
+    }
+
+    fn protocol(&self) -> Protocol {
+        Protocol::Unknown
+    }
+
+    /// Token balance changes caused by the action, none by default
+    fn apply_token_deltas(&self, _delta_map: &mut AddressDeltas) {}
+}
+
+/// Object safe version of [`CustomAction`], implemented for every custom
+/// action
+pub trait DynCustomAction: Debug + Send + Sync {
+    fn kind(&self) -> &'static str;
+    fn trace_index(&self) -> u64;
+    fn from_address(&self) -> Address;
+    fn to_address(&self) -> Address;
+    fn protocol(&self) -> Protocol;
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas);
+    fn clone_box(&self) -> Box<dyn DynCustomAction>;
+    fn eq_dyn(&self, other: &dyn DynCustomAction) -> bool;
+    fn as_any(&self) -> &dyn Any;
+    fn to_json(&self) -> serde_json::Result<serde_json::Value>;
+}
+
+impl<T: CustomAction> DynCustomAction for T {
+    fn kind(&self) -> &'static str {
+        T::KIND
+    }
+
+    fn trace_index(&self) -> u64 {
+        CustomAction::trace_index(self)
+    }
+
+    fn from_address(&self) -> Address {
+        CustomAction::from_address(self)
+    }
+
+    fn to_address(&self) -> Address {
+        CustomAction::to_address(self)
+    }
+
+    fn protocol(&self) -> Protocol {
+        CustomAction::protocol(self)
+    }
+
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        CustomAction::apply_token_deltas(self, delta_map)
+    }
+
+    fn clone_box(&self) -> Box<dyn DynCustomAction> {
+        Box::new(self.clone())
+    }
+
+    fn eq_dyn(&self, other: &dyn DynCustomAction) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<T>()
+            .is_some_and(|other| self == other)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
+
+type DeserializeFn = fn(serde_json::Value) -> serde_json::Result<Box<dyn DynCustomAction>>;
+
+static CUSTOM_ACTIONS: OnceLock<RwLock<FastHashMap<&'static str, DeserializeFn>>> = OnceLock::new();
+
+fn custom_actions() -> &'static RwLock<FastHashMap<&'static str, DeserializeFn>> {
+    CUSTOM_ACTIONS.get_or_init(Default::default)
+}
+
+/// Registers the custom action so that it can be deserialized. Registering
+/// the same action again is a no-op.
+pub fn register_custom_action<T: CustomAction>() {
+    fn deserialize<T: CustomAction>(
+        value: serde_json::Value,
+    ) -> serde_json::Result<Box<dyn DynCustomAction>> {
+        Ok(Box::new(serde_json::from_value::<T>(value)?))
+    }
+
+    custom_actions()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(T::KIND, deserialize::<T>);
+}
+
+pub fn is_custom_action_registered(kind: &str) -> bool {
+    custom_actions()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(kind)
+}
+
+/// A type erased [`CustomAction`]
+#[derive(Debug)]
+pub struct CustomActionBox {
+    action:         Box<dyn DynCustomAction>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    pub provenance: Option<ActionProvenance>,
+}
+
+impl CustomActionBox {
+    /// Columns of the tagged row a custom action is serialized as
+    pub const COLUMN_NAMES: &'static [&'static str] = &["kind", "action"];
+
+    pub fn new<T: CustomAction>(action: T) -> Self {
+        Self {
+            action: Box::new(action),
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.action.kind()
+    }
+
+    pub fn is<T: CustomAction>(&self) -> bool {
+        self.action.as_any().is::<T>()
+    }
+
+    pub fn downcast_ref<T: CustomAction>(&self) -> Option<&T> {
+        self.action.as_any().downcast_ref::<T>()
+    }
+
+    pub fn inner(&self) -> &dyn DynCustomAction {
+        self.action.as_ref()
+    }
+}
+
+impl<T: CustomAction> From<T> for CustomActionBox {
+    fn from(action: T) -> Self {
+        Self::new(action)
+    }
+}
+
+impl Clone for CustomActionBox {
+    fn clone(&self) -> Self {
+        Self {
+            action: self.action.clone_box(),
+            #[cfg(feature = "provenance")]
+            provenance: self.provenance.clone(),
+        }
+    }
+}
+
+impl PartialEq for CustomActionBox {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "provenance")]
+        if self.provenance != other.provenance {
+            return false
+        }
+
+        self.action.eq_dyn(other.action.as_ref())
+    }
+}
+
+impl Eq for CustomActionBox {}
+
+#[derive(Serialize, Deserialize)]
+struct TaggedCustomAction {
+    kind:   String,
+    action: serde_json::Value,
+}
+
+impl Serialize for CustomActionBox {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let action = self.action.to_json().map_err(serde::ser::Error::custom)?;
+        TaggedCustomAction { kind: self.kind().to_string(), action }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomActionBox {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let TaggedCustomAction { kind, action } = TaggedCustomAction::deserialize(deserializer)?;
+        let deserialize = custom_actions()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(kind.as_str())
+            .copied()
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("custom action {kind} is not registered"))
+            })?;
+
+        deserialize(action)
+            .map(|action| Self {
+                action,
+                #[cfg(feature = "provenance")]
+                provenance: None,
+            })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "native")]
+    use clickhouse::InsertRow;
+
+    use super::*;
+    use crate::{
+        db::normalized_actions::{ActionKind, TraceNode, TransactionRoot},
+        normalized_actions::{Action, NormalizedAction},
+        GasDetails,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Rebase {
+        trace_index: u64,
+        token:       Address,
+    }
+
+    impl CustomAction for Rebase {
+        const KIND: &'static str = "test_rebase";
+
+        fn trace_index(&self) -> u64 {
+            self.trace_index
+        }
+
+        fn to_address(&self) -> Address {
+            self.token
+        }
+    }
+
+    #[test]
+    fn custom_action_round_trip() {
+        let action =
+            Action::Custom(Rebase { trace_index: 3, token: Address::with_last_byte(1) }.into());
+        assert_eq!(action.get_trace_index(), 3);
+        assert_eq!(action.get_to_address(), Address::with_last_byte(1));
+        assert!(action.is_classified());
+
+        let json = serde_json::to_string(&action).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(serde_json::from_value::<CustomActionBox>(value.clone()).is_err());
+
+        register_custom_action::<Rebase>();
+        let custom = serde_json::from_value::<CustomActionBox>(value).unwrap();
+        assert_eq!(Action::Custom(custom), action);
+        assert!(action.try_custom_ref::<Rebase>().is_some());
+    }
+
+    /// Fails to serialize, like a custom action holding a map with non string
+    /// keys would
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct Unserializable {
+        trace_index: u64,
+    }
+
+    impl Serialize for Unserializable {
+        fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            Err(serde::ser::Error::custom("unserializable"))
+        }
+    }
+
+    impl CustomAction for Unserializable {
+        const KIND: &'static str = "test_unserializable";
+
+        fn trace_index(&self) -> u64 {
+            self.trace_index
+        }
+    }
+
+    #[test]
+    fn custom_action_row_columns() {
+        let action =
+            Action::Custom(Rebase { trace_index: 0, token: Address::with_last_byte(1) }.into());
+        assert_eq!(action.try_get_column_names().unwrap(), CustomActionBox::COLUMN_NAMES);
+        assert_eq!(action.get_column_names(), &["kind", "action"]);
+
+        assert!(matches!(
+            Action::Revert.try_get_column_names(),
+            Err(crate::normalized_actions::UnsupportedActionRow(ActionKind::Revert))
+        ));
+        assert!(Action::Revert.get_column_names().is_empty());
+    }
+
+    #[test]
+    fn unserializable_custom_action_errors_instead_of_panicking() {
+        let action = Action::Custom(Unserializable { trace_index: 0 }.into());
+        assert!(serde_json::to_string(&action).is_err());
+
+        let root = TransactionRoot {
+            block_number: 1,
+            tx_hash:      Default::default(),
+            tx_idx:       0,
+            from_address: Address::ZERO,
+            to_address:   None,
+            gas_details:  GasDetails::default(),
+            trace_nodes:  vec![TraceNode {
+                trace_idx:     0,
+                trace_address: vec![],
+                action_kind:   Some(ActionKind::Custom),
+                action:        Some(action),
+            }],
+        };
+        assert!(serde_json::to_string(&root).is_err());
+    }
+}
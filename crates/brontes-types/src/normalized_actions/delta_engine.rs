@@ -0,0 +1,408 @@
+use alloy_primitives::{Address, U256};
+use itertools::Itertools;
+use malachite::{
+    num::{arithmetic::traits::Abs, basic::traits::Zero},
+    Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accounting::{ActionAccounting, AddressDeltas},
+    Action, NormalizedSwap, NormalizedTransfer,
+};
+use crate::{constants::STETH_ADDRESS, FastHashMap, FastHashSet, ToScaledRational};
+
+/// Rebasing tokens compute the shares to move from the amount, so a transfer
+/// can move up to this many wei less than was requested
+pub const REBASING_ROUNDING_WEI: u64 = 2;
+
+/// Why a swap leg didn't match the transfer that settled it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiscrepancyKind {
+    /// Share rounding of a rebasing token, e.g. stETH or an aToken
+    RebasingRounding,
+    /// The token took a fee on the transfer. Depending on the pool the swap
+    /// reports the amount before or after the fee
+    TransferFee,
+    /// Nothing explains the difference, the action or the transfer was
+    /// likely misclassified
+    Unbalanced,
+}
+
+impl DiscrepancyKind {
+    pub fn is_expected(&self) -> bool {
+        !matches!(self, Self::Unbalanced)
+    }
+}
+
+/// A swap leg whose amount differs from the amount its transfer moved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaDiscrepancy {
+    /// Trace index of the swap
+    pub trace_index: u64,
+    /// The swapper for the input leg, the recipient for the output leg
+    pub address:     Address,
+    pub token:       Address,
+    /// Amount claimed by the swap minus the amount the transfer moved
+    pub amount:      Rational,
+    pub kind:        DiscrepancyKind,
+}
+
+/// The token deltas of a transaction, along with the swap legs that weren't
+/// balanced by their transfers and the tokens whose deltas don't net out
+#[derive(Debug, Default, Clone)]
+pub struct TxDeltas {
+    pub deltas:        AddressDeltas,
+    pub discrepancies: Vec<DeltaDiscrepancy>,
+    /// Tokens whose deltas don't sum to zero over all addresses, with the
+    /// amount they are off by. Something was only accounted for on one side,
+    /// e.g. a swap that skips the pool
+    pub unconserved:   Vec<(Address, Rational)>,
+}
+
+impl TxDeltas {
+    /// True if every discrepancy is explained by the token and the deltas of
+    /// every token net out
+    pub fn is_balanced(&self) -> bool {
+        self.unconserved.is_empty() && self.discrepancies.iter().all(|d| d.kind.is_expected())
+    }
+}
+
+/// Accounts for the actions of a transaction while enforcing double entry:
+/// every swap leg must be settled by a transfer of the same amount and the
+/// deltas of every token must sum to zero over all addresses.
+///
+/// The deltas are what the actions moved, the swaps are only checked against
+/// them, so share rounding and transfer fees end up in the deltas as they
+/// were settled. The input leg of a swap must be backed by a transfer of the
+/// input token into the pool, the output leg by a transfer of the output
+/// token from the pool to the recipient. A leg off by the rounding of a
+/// rebasing token or by a transfer fee is expected, any other difference is
+/// reported as [`DiscrepancyKind::Unbalanced`]. Legs of pools that settle
+/// without a transfer, e.g. through internal balances, have nothing to be
+/// checked against and are skipped.
+///
+/// Transfer fees leave the accounted addresses and tokens that are minted or
+/// burned in the transaction change their supply, so neither counts against
+/// the per token sum.
+#[derive(Debug, Clone)]
+pub struct DeltaEngine {
+    rebasing_tokens: FastHashSet<Address>,
+    taxed_tokens:    FastHashSet<Address>,
+}
+
+impl Default for DeltaEngine {
+    fn default() -> Self {
+        Self {
+            rebasing_tokens: FastHashSet::from_iter([STETH_ADDRESS]),
+            taxed_tokens:    FastHashSet::default(),
+        }
+    }
+}
+
+/// A swap leg with the transfer that settled it
+struct Leg<'a> {
+    address:  Address,
+    token:    Address,
+    decimals: u8,
+    claimed:  &'a Rational,
+    /// Amount the transfer moved for the leg's address
+    moved:    Rational,
+    has_fee:  bool,
+}
+
+impl DeltaEngine {
+    /// Adds rebasing tokens to the ones known by default, e.g. aTokens
+    pub fn with_rebasing_tokens(mut self, tokens: impl IntoIterator<Item = Address>) -> Self {
+        self.rebasing_tokens.extend(tokens);
+        self
+    }
+
+    /// Fee on transfer tokens, transfers that don't carry a fee of their own
+    /// can still be short for them
+    pub fn with_taxed_tokens(mut self, tokens: impl IntoIterator<Item = Address>) -> Self {
+        self.taxed_tokens.extend(tokens);
+        self
+    }
+
+    /// Accounts for the actions, checking the swaps against the transfers in
+    /// them. The swaps aren't accounted for themselves.
+    pub fn account_for_tx<'a>(
+        &self,
+        swaps: impl IntoIterator<Item = &'a NormalizedSwap>,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> TxDeltas {
+        let actions = actions.into_iter().collect_vec();
+        let transfers = actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::Transfer(transfer) => Some(transfer),
+                _ => None,
+            })
+            .collect_vec();
+
+        let discrepancies = swaps
+            .into_iter()
+            .flat_map(|swap| {
+                Self::legs(swap, &transfers)
+                    .into_iter()
+                    .filter_map(|leg| {
+                        let diff = leg.claimed - &leg.moved;
+                        (diff != Rational::ZERO).then(|| DeltaDiscrepancy {
+                            trace_index: swap.trace_index,
+                            address:     leg.address,
+                            token:       leg.token,
+                            kind:        self.classify(&leg, &diff),
+                            amount:      diff,
+                        })
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let deltas = actions.iter().cloned().account_for_actions();
+        let unconserved = Self::unconserved(&actions, &deltas);
+
+        TxDeltas { deltas, discrepancies, unconserved }
+    }
+
+    /// Sums the deltas of each token over all addresses, adding back the
+    /// transfer fees. Tokens minted or burned by the actions are skipped.
+    fn unconserved(actions: &[Action], deltas: &AddressDeltas) -> Vec<(Address, Rational)> {
+        let mut supply_changed = FastHashSet::default();
+        let mut totals: FastHashMap<Address, Rational> = FastHashMap::default();
+
+        for action in actions {
+            match action {
+                Action::Transfer(transfer)
+                    if transfer.from == Address::ZERO || transfer.to == Address::ZERO =>
+                {
+                    supply_changed.insert(transfer.token.address);
+                }
+                Action::Transfer(transfer) => {
+                    *totals
+                        .entry(transfer.token.address)
+                        .or_insert(Rational::ZERO) += &transfer.fee;
+                }
+                Action::VaultDeposit(deposit) => {
+                    supply_changed.insert(deposit.share.address);
+                }
+                Action::VaultWithdraw(withdraw) => {
+                    supply_changed.insert(withdraw.share.address);
+                }
+                _ => {}
+            }
+        }
+
+        for (token, amount) in deltas.values().flatten() {
+            *totals.entry(*token).or_insert(Rational::ZERO) += amount;
+        }
+
+        totals
+            .into_iter()
+            .filter(|(token, total)| *total != Rational::ZERO && !supply_changed.contains(token))
+            .sorted_by_key(|(token, _)| *token)
+            .collect()
+    }
+
+    /// Matches each leg of the swap with the closest transfer that could have
+    /// settled it
+    fn legs<'a>(swap: &'a NormalizedSwap, transfers: &[&NormalizedTransfer]) -> Vec<Leg<'a>> {
+        let input = transfers
+            .iter()
+            .filter(|t| t.token.address == swap.token_in.address && t.to == swap.pool)
+            .map(|t| (t, &t.amount + &t.fee))
+            .min_by_key(|(_, moved)| (&swap.amount_in - moved).abs())
+            .map(|(t, moved)| Leg {
+                address: swap.from,
+                token: swap.token_in.address,
+                decimals: swap.token_in.decimals,
+                claimed: &swap.amount_in,
+                moved,
+                has_fee: t.fee != Rational::ZERO,
+            });
+
+        let output = transfers
+            .iter()
+            .filter(|t| {
+                t.token.address == swap.token_out.address
+                    && t.from == swap.pool
+                    && t.to == swap.recipient
+            })
+            .min_by_key(|t| (&swap.amount_out - &t.amount).abs())
+            .map(|t| Leg {
+                address:  swap.recipient,
+                token:    swap.token_out.address,
+                decimals: swap.token_out.decimals,
+                claimed:  &swap.amount_out,
+                moved:    t.amount.clone(),
+                has_fee:  t.fee != Rational::ZERO,
+            });
+
+        input.into_iter().chain(output).collect()
+    }
+
+    fn classify(&self, leg: &Leg<'_>, diff: &Rational) -> DiscrepancyKind {
+        let tolerance = U256::from(REBASING_ROUNDING_WEI).to_scaled_rational(leg.decimals);
+
+        if self.rebasing_tokens.contains(&leg.token) && diff.abs() <= tolerance {
+            DiscrepancyKind::RebasingRounding
+        } else if leg.has_fee || self.taxed_tokens.contains(&leg.token) {
+            DiscrepancyKind::TransferFee
+        } else {
+            DiscrepancyKind::Unbalanced
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::token_info::TokenInfoWithAddress;
+
+    fn token(address: Address) -> TokenInfoWithAddress {
+        let mut token = TokenInfoWithAddress { address, ..Default::default() };
+        token.decimals = 18;
+        token
+    }
+
+    fn wei(amount: u64) -> Rational {
+        U256::from(amount).to_scaled_rational(18)
+    }
+
+    /// Swaps 1 token_in for 1 token_out, the input transfer moves `paid` and
+    /// the output transfer `received`
+    fn swap(
+        token_in: Address,
+        paid: Rational,
+        received: Rational,
+    ) -> (NormalizedSwap, Vec<Action>) {
+        let [user, pool, token_out] = [1, 2, 3].map(Address::with_last_byte);
+        let one = Rational::from(1);
+
+        let swap = NormalizedSwap {
+            trace_index: 1,
+            from: user,
+            recipient: user,
+            pool,
+            token_in: token(token_in),
+            token_out: token(token_out),
+            amount_in: one.clone(),
+            amount_out: one,
+            ..Default::default()
+        };
+        let transfers = vec![
+            NormalizedTransfer {
+                trace_index: 2,
+                from: user,
+                to: pool,
+                token: token(token_in),
+                amount: paid,
+                ..Default::default()
+            }
+            .into(),
+            NormalizedTransfer {
+                trace_index: 3,
+                from: pool,
+                to: user,
+                token: token(token_out),
+                amount: received,
+                ..Default::default()
+            }
+            .into(),
+        ];
+
+        (swap, transfers)
+    }
+
+    #[test]
+    fn balanced_swap_has_no_discrepancies() {
+        let one = Rational::from(1);
+        let (swap, transfers) = swap(Address::with_last_byte(4), one.clone(), one.clone());
+        let tx = DeltaEngine::default().account_for_tx([&swap], transfers);
+
+        assert!(tx.discrepancies.is_empty());
+        assert!(tx.is_balanced());
+        let user = &tx.deltas[&Address::with_last_byte(1)];
+        assert_eq!(user[&Address::with_last_byte(4)], -one.clone());
+        assert_eq!(user[&Address::with_last_byte(3)], one);
+    }
+
+    #[test]
+    fn rebasing_rounding_is_expected() {
+        let one = Rational::from(1);
+        let (swap, transfers) = swap(STETH_ADDRESS, &one - wei(1), one.clone());
+        let tx = DeltaEngine::default().account_for_tx([&swap], transfers);
+
+        assert!(tx.is_balanced());
+        assert_eq!(tx.discrepancies.len(), 1);
+        assert_eq!(tx.discrepancies[0].kind, DiscrepancyKind::RebasingRounding);
+        assert_eq!(tx.discrepancies[0].amount, wei(1));
+        // the user is only charged what the transfer moved
+        let user = &tx.deltas[&Address::with_last_byte(1)];
+        assert_eq!(user[&STETH_ADDRESS], wei(1) - one);
+    }
+
+    #[test]
+    fn unexplained_difference_is_unbalanced() {
+        let one = Rational::from(1);
+        let (swap, transfers) = swap(Address::with_last_byte(4), &one - wei(1), one);
+        let tx = DeltaEngine::default().account_for_tx([&swap], transfers);
+
+        assert!(!tx.is_balanced());
+        assert_eq!(tx.discrepancies[0].kind, DiscrepancyKind::Unbalanced);
+    }
+
+    #[test]
+    fn taxed_output_is_a_transfer_fee() {
+        let one = Rational::from(1);
+        let token_out = Address::with_last_byte(3);
+        let received = Rational::from_unsigneds(95u32, 100);
+        let (swap, transfers) = swap(Address::with_last_byte(4), one, received.clone());
+        let tx = DeltaEngine::default()
+            .with_taxed_tokens([token_out])
+            .account_for_tx([&swap], transfers);
+
+        assert!(tx.is_balanced());
+        assert_eq!(tx.discrepancies[0].kind, DiscrepancyKind::TransferFee);
+        assert_eq!(tx.deltas[&Address::with_last_byte(1)][&token_out], received);
+    }
+
+    #[test]
+    fn transfer_fees_and_mints_net_out() {
+        let [user, pool, token_in, share] = [1, 2, 4, 5].map(Address::with_last_byte);
+        let one = Rational::from(1);
+        let taxed = NormalizedTransfer {
+            from: user,
+            to: pool,
+            token: token(token_in),
+            amount: one.clone(),
+            fee: wei(5),
+            ..Default::default()
+        };
+        let mint = NormalizedTransfer {
+            from: Address::ZERO,
+            to: user,
+            token: token(share),
+            amount: one,
+            ..Default::default()
+        };
+        let tx = DeltaEngine::default().account_for_tx([], [taxed.into(), mint.into()]);
+
+        assert!(tx.unconserved.is_empty());
+    }
+
+    #[test]
+    fn single_sided_actions_are_unconserved() {
+        let one = Rational::from(1);
+        let (swap, _) = swap(Address::with_last_byte(4), one.clone(), one.clone());
+        // the swap leaves out the pool's side of both legs
+        let tx = DeltaEngine::default().account_for_tx([], [Action::Swap(swap)]);
+
+        assert!(!tx.is_balanced());
+        assert_eq!(
+            tx.unconserved,
+            vec![(Address::with_last_byte(3), one.clone()), (Address::with_last_byte(4), -one)]
+        );
+    }
+}
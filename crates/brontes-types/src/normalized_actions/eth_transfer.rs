@@ -1,20 +1,29 @@
 use std::fmt::Debug;
 
 use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
 pub use super::{Action, NormalizedSwap};
 use crate::{constants::ETH_ADDRESS, ToScaledRational};
 
-#[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedEthTransfer {
     pub trace_index:       u64,
     pub from:              Address,
     pub to:                Address,
     pub value:             U256,
     pub coinbase_transfer: bool,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:        Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedEthTransfer {
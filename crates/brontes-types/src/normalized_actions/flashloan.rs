@@ -1,15 +1,24 @@
 use std::fmt::Debug;
 
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
-use malachite::Rational;
-use reth_primitives::{Address, U256};
+use malachite::{num::basic::traits::Zero, Rational};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
 pub use super::{Action, NormalizedSwap, NormalizedTransfer};
 use crate::{db::token_info::TokenInfoWithAddress, Protocol};
 
-#[derive(Debug, Serialize, Clone, Row, Deserialize, PartialEq, Eq)]
+/// Flash loan fees are a fraction of a percent of the loan. Larger transfers of
+/// a borrowed asset to the pool aren't repayments, e.g. swaps through the same
+/// vault
+const MAX_FEE_BPS: u64 = 100;
+
+#[derive(Debug, Serialize, Clone, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedFlashLoan {
     pub protocol:          Protocol,
     pub trace_index:       u64,
@@ -33,6 +42,11 @@ pub struct NormalizedFlashLoan {
     pub repayments:    Vec<NormalizedTransfer>,
     pub fees_paid:     Vec<Rational>,
     pub msg_value:     U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:    Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedFlashLoan {
@@ -49,4 +63,103 @@ impl NormalizedFlashLoan {
             .into_iter()
             .chain(self.repayments.into_iter().map(Action::from))
     }
+
+    /// The index of the borrowed asset the transfer repays. The repayment has
+    /// to go to the pool and cover the loan plus at most the max fee. It can
+    /// come from any address, as the receiver can repay through another
+    /// contract.
+    pub fn repaid_asset(&self, transfer: &NormalizedTransfer) -> Option<usize> {
+        if transfer.to != self.pool {
+            return None
+        }
+
+        let i = (0..self.assets.len())
+            .find(|&i| self.assets[i] == transfer.token && !self.is_asset_repaid(i))?;
+        let borrowed = &self.amounts[i];
+        let max_repayment =
+            borrowed + borrowed * Rational::from(MAX_FEE_BPS) / Rational::from(10_000);
+
+        (transfer.amount >= *borrowed && transfer.amount <= max_repayment).then_some(i)
+    }
+
+    /// Adds the transfer to the repayments if it repays one of the borrowed
+    /// assets, noting the fee paid on top of the loan
+    pub fn try_add_repayment(&mut self, transfer: &NormalizedTransfer) -> bool {
+        let Some(i) = self.repaid_asset(transfer) else { return false };
+
+        if self.fees_paid.len() != self.assets.len() {
+            self.fees_paid = vec![Rational::ZERO; self.assets.len()];
+        }
+        self.fees_paid[i] = &transfer.amount - &self.amounts[i];
+        self.repayments.push(transfer.clone());
+
+        true
+    }
+
+    /// Whether every borrowed asset has a repayment
+    pub fn is_repaid(&self) -> bool {
+        (0..self.assets.len()).all(|i| self.is_asset_repaid(i))
+    }
+
+    /// An asset borrowed several times needs a repayment per loan
+    fn is_asset_repaid(&self, i: usize) -> bool {
+        let asset = &self.assets[i];
+        let loans_before = self.assets[..i]
+            .iter()
+            .filter(|other| *other == asset)
+            .count();
+
+        self.repayments
+            .iter()
+            .filter(|repayment| repayment.token == *asset)
+            .count()
+            > loans_before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flash_loan_repayments() {
+        let [pool, receiver, other] = [1, 2, 3].map(Address::with_last_byte);
+        let mut loan = NormalizedFlashLoan {
+            protocol: Protocol::BalancerV2,
+            trace_index: 0,
+            from: receiver,
+            pool,
+            receiver_contract: receiver,
+            assets: vec![TokenInfoWithAddress::weth(), TokenInfoWithAddress::usdt()],
+            amounts: vec![Rational::from(100), Rational::from(1_000)],
+            aave_mode: None,
+            child_actions: vec![],
+            repayments: vec![],
+            fees_paid: vec![],
+            msg_value: U256::ZERO,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        };
+        let transfer = |token: TokenInfoWithAddress, to: Address, amount: u64| NormalizedTransfer {
+            from: other,
+            to,
+            token,
+            amount: Rational::from(amount),
+            ..Default::default()
+        };
+
+        // too little, too much and to the wrong address
+        assert!(!loan.try_add_repayment(&transfer(TokenInfoWithAddress::weth(), pool, 99)));
+        assert!(!loan.try_add_repayment(&transfer(TokenInfoWithAddress::weth(), pool, 102)));
+        assert!(!loan.try_add_repayment(&transfer(TokenInfoWithAddress::weth(), other, 100)));
+
+        assert!(loan.try_add_repayment(&transfer(TokenInfoWithAddress::weth(), pool, 100)));
+        assert!(!loan.is_repaid());
+        // the weth loan is already repaid
+        assert!(!loan.try_add_repayment(&transfer(TokenInfoWithAddress::weth(), pool, 100)));
+
+        assert!(loan.try_add_repayment(&transfer(TokenInfoWithAddress::usdt(), pool, 1_005)));
+        assert!(loan.is_repaid());
+        assert_eq!(loan.fees_paid, vec![Rational::ZERO, Rational::from(5)]);
+    }
 }
@@ -1,14 +1,15 @@
 use std::fmt::Debug;
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use malachite::Rational;
-use reth_primitives::Address;
 use serde::{Deserialize, Serialize};
 
 use crate::{db::token_info::TokenInfoWithAddress, FastHashMap, Protocol};
 
-#[derive(Debug, Serialize, Clone, Row, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedLoan {
     pub protocol:     Protocol,
     pub trace_index:  u64,
@@ -20,7 +21,8 @@ pub struct NormalizedLoan {
     pub msg_value:    U256,
 }
 
-#[derive(Debug, Serialize, Clone, Row, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedRepayment {
     pub protocol:         Protocol,
     pub trace_index:      u64,
@@ -1,28 +1,39 @@
 use std::fmt::{self, Debug};
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use colored::Colorize;
 use malachite::Rational;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::Address;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+#[cfg(all(feature = "provenance", feature = "rkyv"))]
+use super::ActionProvenanceRedefined;
 pub use super::{Action, NormalizedSwap};
-use crate::{
-    db::{
-        redefined_types::{malachite::RationalRedefined, primitives::*},
-        token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
-    },
-    rational_to_u256_fraction, Protocol,
+#[cfg(feature = "rkyv")]
+use crate::db::{
+    redefined_types::{malachite::RationalRedefined, primitives::*},
+    token_info::TokenInfoWithAddressRedefined,
 };
-
-#[derive(Default, Debug, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+use crate::{db::token_info::TokenInfoWithAddress, rational_to_u256_fraction, Protocol};
+
+#[derive(Default, Debug, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct NormalizedLiquidation {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:              Protocol,
     pub trace_index:           u64,
     pub pool:                  Address,
@@ -33,6 +44,11 @@ pub struct NormalizedLiquidation {
     pub covered_debt:          Rational,
     pub liquidated_collateral: Rational,
     pub msg_value:             U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:            Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedLiquidation {
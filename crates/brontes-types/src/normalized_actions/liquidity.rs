@@ -1,27 +1,40 @@
 use std::fmt::{self, Debug};
 
-use alloy_primitives::TxHash;
+use alloy_primitives::{Address, TxHash};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use colored::Colorize;
 use itertools::Itertools;
 use malachite::Rational;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::Address;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+#[cfg(all(feature = "provenance", feature = "rkyv"))]
+use super::ActionProvenanceRedefined;
+#[cfg(feature = "rkyv")]
+use crate::db::{
+    redefined_types::{malachite::RationalRedefined, primitives::AddressRedefined},
+    token_info::TokenInfoWithAddressRedefined,
+};
 use crate::{
-    db::{
-        redefined_types::{malachite::RationalRedefined, primitives::AddressRedefined},
-        token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
-    },
-    rational_to_u256_fraction, Protocol, ToFloatNearest,
+    db::token_info::TokenInfoWithAddress, rational_to_u256_fraction, Protocol, ToFloatNearest,
 };
-#[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct NormalizedMint {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:    Protocol,
     pub trace_index: u64,
     pub from:        Address,
@@ -29,6 +42,11 @@ pub struct NormalizedMint {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedMint {
@@ -41,10 +59,16 @@ impl TokenAccounting for NormalizedMint {
     }
 }
 
-#[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct NormalizedBurn {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:    Protocol,
     pub trace_index: u64,
     pub from:        Address,
@@ -52,6 +76,11 @@ pub struct NormalizedBurn {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedBurn {
@@ -64,10 +93,15 @@ impl TokenAccounting for NormalizedBurn {
     }
 }
 
-#[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
 pub struct NormalizedCollect {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:    Protocol,
     pub trace_index: u64,
     pub from:        Address,
@@ -75,6 +109,11 @@ pub struct NormalizedCollect {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedCollect {
@@ -1,38 +1,63 @@
 pub mod accounting;
 pub mod aggregator;
+pub mod approval;
+pub mod auction;
 pub mod batch;
 pub mod comparison;
+pub mod custom;
+pub mod delta_engine;
 pub mod eth_transfer;
 pub mod flashloan;
 pub mod lending;
 pub mod liquidation;
 pub mod liquidity;
 pub mod multi_callframe;
+pub mod nft;
+pub mod order_fill;
 pub mod pool;
+pub mod provenance;
+pub mod selector_call;
 pub mod self_destruct;
+pub mod settlement;
+pub mod staking;
 pub mod swaps;
 pub mod transfer;
+pub mod vault;
 use std::fmt::Debug;
 
+#[cfg(feature = "native")]
 use ::clickhouse::DbRow;
 use accounting::{AddressDeltas, TokenAccounting};
 pub use aggregator::*;
 use alloy_primitives::{Address, Bytes, Log};
+use alloy_rpc_types_trace::parity::Action as TraceAction;
+pub use approval::*;
+pub use auction::*;
 pub use batch::*;
+#[cfg(feature = "native")]
 use clickhouse::InsertRow;
+pub use custom::*;
+pub use delta_engine::*;
 pub use eth_transfer::*;
 pub use flashloan::*;
 pub use lending::*;
 pub use liquidation::*;
 pub use liquidity::*;
 pub use multi_callframe::*;
+pub use nft::*;
+pub use order_fill::*;
 pub use pool::*;
-use reth_rpc_types::trace::parity::Action as TraceAction;
+pub use provenance::*;
+pub use selector_call::*;
 pub use self_destruct::*;
+pub use settlement::*;
+pub use staking::*;
 pub use swaps::*;
 pub use transfer::*;
+pub use vault::*;
 
 use crate::{
+    db::normalized_actions::ActionKind,
     structured_trace::{TraceActions, TransactionTraceWithLogs},
     Protocol,
 };
@@ -97,6 +122,9 @@ impl NormalizedAction for Action {
             Self::NewPool(p) => p.trace_index,
             Self::PoolConfigUpdate(p) => p.trace_index,
             Self::Aggregator(a) => a.trace_index,
+            Self::VaultDeposit(v) => v.trace_index,
+            Self::VaultWithdraw(v) => v.trace_index,
+            Self::Custom(c) => c.inner().trace_index(),
             Self::Revert => unreachable!("no trace index for revert"),
         }
     }
@@ -119,13 +147,23 @@ pub enum Action {
     NewPool(NormalizedNewPool),
     PoolConfigUpdate(NormalizedPoolConfigUpdate),
     Aggregator(NormalizedAggregator),
+    VaultDeposit(NormalizedVaultDeposit),
+    VaultWithdraw(NormalizedVaultWithdraw),
     Unclassified(TransactionTraceWithLogs),
+    /// Protocol specific action defined outside of brontes, see
+    /// [`CustomAction`]
+    Custom(CustomActionBox),
     Revert,
 }
 
-impl InsertRow for Action {
-    fn get_column_names(&self) -> &'static [&'static str] {
-        match self {
+/// Returned for actions that have no clickhouse row of their own
+#[derive(Debug, thiserror::Error)]
+#[error("{0:?} actions can't be inserted as a row")]
+pub struct UnsupportedActionRow(pub ActionKind);
+
+impl Action {
+    pub fn try_get_column_names(&self) -> Result<&'static [&'static str], UnsupportedActionRow> {
+        Ok(match self {
             Action::Swap(_) => NormalizedSwap::COLUMN_NAMES,
             Action::SwapWithFee(_) => NormalizedSwapWithFee::COLUMN_NAMES,
             Action::FlashLoan(_) => NormalizedFlashLoan::COLUMN_NAMES,
@@ -135,13 +173,27 @@ impl InsertRow for Action {
             Action::Burn(_) => NormalizedBurn::COLUMN_NAMES,
             Action::Collect(_) => NormalizedCollect::COLUMN_NAMES,
             Action::Liquidation(_) => NormalizedLiquidation::COLUMN_NAMES,
-            Action::SelfDestruct(_) => todo!("joe pls dome this"),
-            Action::EthTransfer(_) => todo!("joe pls dome this"),
-            Action::NewPool(_) => todo!(),
-            Action::PoolConfigUpdate(_) => todo!(),
-            Action::Unclassified(..) | Action::Revert => panic!(),
             Action::Aggregator(_) => NormalizedAggregator::COLUMN_NAMES,
-        }
+            Action::VaultDeposit(_) => NormalizedVaultDeposit::COLUMN_NAMES,
+            Action::VaultWithdraw(_) => NormalizedVaultWithdraw::COLUMN_NAMES,
+            Action::Custom(_) => CustomActionBox::COLUMN_NAMES,
+            Action::SelfDestruct(_)
+            | Action::EthTransfer(_)
+            | Action::NewPool(_)
+            | Action::PoolConfigUpdate(_)
+            | Action::Unclassified(..)
+            | Action::Revert => return Err(UnsupportedActionRow(self.into())),
+        })
+    }
+}
+
+#[cfg(feature = "native")]
+impl InsertRow for Action {
+    /// Actions without a row of their own have no columns, so that inserting
+    /// them fails in the client instead of panicking. Use
+    /// [`Action::try_get_column_names`] to check for this up front.
+    fn get_column_names(&self) -> &'static [&'static str] {
+        self.try_get_column_names().unwrap_or(&[])
     }
 }
 
@@ -163,7 +215,10 @@ impl serde::Serialize for Action {
             Action::Liquidation(c) => c.serialize(serializer),
             Action::SelfDestruct(sd) => sd.serialize(serializer),
             Action::EthTransfer(et) => et.serialize(serializer),
+            Action::VaultDeposit(v) => v.serialize(serializer),
+            Action::VaultWithdraw(v) => v.serialize(serializer),
             Action::Unclassified(trace) => (trace).serialize(serializer),
+            Action::Custom(c) => c.serialize(serializer),
             action => format!("{:?}", action).serialize(serializer),
             //action => unreachable!("no action serialization for {action:?}"),
         }
@@ -218,6 +273,18 @@ impl Action {
                     from: a.from,
                     ..Default::default()
                 }),
+                Self::VaultDeposit(v) => (!v.msg_value.is_zero()).then(|| NormalizedEthTransfer {
+                    value: v.msg_value,
+                    to: v.vault,
+                    from: v.from,
+                    ..Default::default()
+                }),
+                Self::VaultWithdraw(v) => (!v.msg_value.is_zero()).then(|| NormalizedEthTransfer {
+                    value: v.msg_value,
+                    to: v.vault,
+                    from: v.from,
+                    ..Default::default()
+                }),
                 Self::Mint(_) => None,
                 Self::Burn(_) => None,
                 Self::Transfer(_) => None,
@@ -226,6 +293,7 @@ impl Action {
                 Self::EthTransfer(_) => None,
                 Self::NewPool(_) => None,
                 Self::PoolConfigUpdate(_) => None,
+                Self::Custom(_) => None,
                 Self::Revert => None,
             };
         if res.is_some() {
@@ -258,10 +326,64 @@ impl Action {
             Self::NewPool(p) => p.trace_index,
             Self::PoolConfigUpdate(p) => p.trace_index,
             Self::Aggregator(a) => a.trace_index,
+            Self::VaultDeposit(v) => v.trace_index,
+            Self::VaultWithdraw(v) => v.trace_index,
+            Self::Custom(c) => c.inner().trace_index(),
             Self::Revert => return None,
         })
     }
 
+    /// The call and logs the action was classified from. Only set when built
+    /// with the `provenance` feature
+    #[cfg(feature = "provenance")]
+    pub fn provenance(&self) -> Option<&ActionProvenance> {
+        match self {
+            Self::Swap(s) => s.provenance.as_ref(),
+            Self::SwapWithFee(s) => s.provenance.as_ref(),
+            Self::FlashLoan(f) => f.provenance.as_ref(),
+            Self::Batch(b) => b.provenance.as_ref(),
+            Self::Mint(m) => m.provenance.as_ref(),
+            Self::Burn(b) => b.provenance.as_ref(),
+            Self::Transfer(t) => t.provenance.as_ref(),
+            Self::Liquidation(l) => l.provenance.as_ref(),
+            Self::Collect(c) => c.provenance.as_ref(),
+            Self::EthTransfer(e) => e.provenance.as_ref(),
+            Self::NewPool(p) => p.provenance.as_ref(),
+            Self::PoolConfigUpdate(p) => p.provenance.as_ref(),
+            Self::Aggregator(a) => a.provenance.as_ref(),
+            Self::VaultDeposit(v) => v.provenance.as_ref(),
+            Self::VaultWithdraw(v) => v.provenance.as_ref(),
+            Self::Custom(c) => c.provenance.as_ref(),
+            Self::SelfDestruct(_) | Self::Unclassified(_) | Self::Revert => None,
+        }
+    }
+
+    /// Records the call and logs the action was classified from. Unclassified
+    /// traces and self destructs are the trace itself and don't carry one
+    #[cfg(feature = "provenance")]
+    pub fn set_provenance(&mut self, provenance: ActionProvenance) {
+        let slot = match self {
+            Self::Swap(s) => &mut s.provenance,
+            Self::SwapWithFee(s) => &mut s.provenance,
+            Self::FlashLoan(f) => &mut f.provenance,
+            Self::Batch(b) => &mut b.provenance,
+            Self::Mint(m) => &mut m.provenance,
+            Self::Burn(b) => &mut b.provenance,
+            Self::Transfer(t) => &mut t.provenance,
+            Self::Liquidation(l) => &mut l.provenance,
+            Self::Collect(c) => &mut c.provenance,
+            Self::EthTransfer(e) => &mut e.provenance,
+            Self::NewPool(p) => &mut p.provenance,
+            Self::PoolConfigUpdate(p) => &mut p.provenance,
+            Self::Aggregator(a) => &mut a.provenance,
+            Self::VaultDeposit(v) => &mut v.provenance,
+            Self::VaultWithdraw(v) => &mut v.provenance,
+            Self::Custom(c) => &mut c.provenance,
+            Self::SelfDestruct(_) | Self::Unclassified(_) | Self::Revert => return,
+        };
+        *slot = Some(provenance);
+    }
+
     pub fn force_swap(self) -> NormalizedSwap {
         match self {
             Action::Swap(s) => s,
@@ -325,16 +447,19 @@ impl Action {
             Action::Transfer(t) => t.to,
             Action::Collect(c) => c.pool,
             Action::Liquidation(c) => c.pool,
+            Action::VaultDeposit(v) => v.vault,
+            Action::VaultWithdraw(v) => v.vault,
             Action::SelfDestruct(c) => c.get_refund_address(),
             Action::Unclassified(t) => match &t.trace.action {
-                reth_rpc_types::trace::parity::Action::Call(c) => c.to,
-                reth_rpc_types::trace::parity::Action::Create(_) => Address::ZERO,
-                reth_rpc_types::trace::parity::Action::Reward(_) => Address::ZERO,
-                reth_rpc_types::trace::parity::Action::Selfdestruct(s) => s.address,
+                alloy_rpc_types_trace::parity::Action::Call(c) => c.to,
+                alloy_rpc_types_trace::parity::Action::Create(_) => Address::ZERO,
+                alloy_rpc_types_trace::parity::Action::Reward(_) => Address::ZERO,
+                alloy_rpc_types_trace::parity::Action::Selfdestruct(s) => s.address,
             },
             Action::EthTransfer(t) => t.to,
             Action::NewPool(p) => p.pool_address,
             Action::PoolConfigUpdate(p) => p.pool_address,
+            Action::Custom(c) => c.inner().to_address(),
             Action::Revert => Address::ZERO,
         }
     }
@@ -351,17 +476,20 @@ impl Action {
             Action::Transfer(t) => t.from,
             Action::Collect(c) => c.from,
             Action::Liquidation(c) => c.liquidator,
+            Action::VaultDeposit(v) => v.from,
+            Action::VaultWithdraw(v) => v.from,
             Action::SelfDestruct(c) => c.get_address(),
             Action::Unclassified(t) => match &t.trace.action {
-                reth_rpc_types::trace::parity::Action::Call(c) => c.to,
-                reth_rpc_types::trace::parity::Action::Create(_) => Address::ZERO,
-                reth_rpc_types::trace::parity::Action::Reward(_) => Address::ZERO,
-                reth_rpc_types::trace::parity::Action::Selfdestruct(s) => s.address,
+                alloy_rpc_types_trace::parity::Action::Call(c) => c.to,
+                alloy_rpc_types_trace::parity::Action::Create(_) => Address::ZERO,
+                alloy_rpc_types_trace::parity::Action::Reward(_) => Address::ZERO,
+                alloy_rpc_types_trace::parity::Action::Selfdestruct(s) => s.address,
             },
             Action::EthTransfer(t) => t.from,
             Action::Revert => unreachable!(),
             Action::NewPool(_) => Address::ZERO,
             Action::PoolConfigUpdate(_) => Address::ZERO,
+            Action::Custom(c) => c.inner().from_address(),
         }
     }
 
@@ -415,6 +543,14 @@ impl Action {
         matches!(self, Action::Transfer(_))
     }
 
+    pub const fn is_vault_deposit(&self) -> bool {
+        matches!(self, Action::VaultDeposit(_))
+    }
+
+    pub const fn is_vault_withdraw(&self) -> bool {
+        matches!(self, Action::VaultWithdraw(_))
+    }
+
     pub const fn is_collect(&self) -> bool {
         matches!(self, Action::Collect(_))
     }
@@ -435,7 +571,20 @@ impl Action {
         matches!(self, Action::Unclassified(_))
     }
 
-    pub const fn get_protocol(&self) -> Protocol {
+    pub const fn is_custom(&self) -> bool {
+        matches!(self, Action::Custom(_))
+    }
+
+    /// Returns the custom action if it is of type `T`
+    pub fn try_custom_ref<T: CustomAction>(&self) -> Option<&T> {
+        if let Action::Custom(c) = self {
+            c.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_protocol(&self) -> Protocol {
         match self {
             Action::Swap(s) => s.protocol,
             Action::SwapWithFee(s) => s.swap.protocol,
@@ -448,6 +597,9 @@ impl Action {
             Action::NewPool(p) => p.protocol,
             Action::PoolConfigUpdate(p) => p.protocol,
             Action::Aggregator(a) => a.protocol,
+            Action::VaultDeposit(v) => v.protocol,
+            Action::VaultWithdraw(v) => v.protocol,
+            Action::Custom(c) => c.inner().protocol(),
             _ => Protocol::Unknown,
         }
     }
@@ -526,7 +678,9 @@ extra_impls!(
     (FlashLoan, NormalizedFlashLoan),
     (Aggregator, NormalizedAggregator),
     (Batch, NormalizedBatch),
-    (NewPool, NormalizedNewPool)
+    (NewPool, NormalizedNewPool),
+    (VaultDeposit, NormalizedVaultDeposit),
+    (VaultWithdraw, NormalizedVaultWithdraw)
 );
 
 /// Custom impl for itering over swaps and swap with fee
@@ -578,11 +732,14 @@ impl TokenAccounting for Action {
             Action::SwapWithFee(swap_with_fee) => swap_with_fee.swap.apply_token_deltas(delta_map),
             Action::Collect(collect) => collect.apply_token_deltas(delta_map),
             Action::EthTransfer(eth_transfer) => eth_transfer.apply_token_deltas(delta_map),
+            Action::VaultDeposit(deposit) => deposit.apply_token_deltas(delta_map),
+            Action::VaultWithdraw(withdraw) => withdraw.apply_token_deltas(delta_map),
             Action::Unclassified(_) => (), /* Potentially no token deltas to apply, adjust as */
             // necessary
             Action::SelfDestruct(_self_destruct) => (),
             Action::NewPool(_new_pool) => (),
             Action::PoolConfigUpdate(_pool_update) => (),
+            Action::Custom(custom) => custom.inner().apply_token_deltas(delta_map),
             Action::Revert => (), // No token deltas to apply for a revert
         }
     }
@@ -0,0 +1,161 @@
+use alloy_primitives::{Address, U256};
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+use super::{custom::CustomAction, NormalizedSwap};
+use crate::{
+    db::token_info::{TokenInfo, TokenInfoWithAddress},
+    Protocol,
+};
+
+/// A single NFT changing hands on a marketplace
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftFill {
+    pub seller:        Address,
+    pub buyer:         Address,
+    pub collection:    Address,
+    pub token_id:      U256,
+    /// Always 1 for ERC-721s
+    pub amount:        U256,
+    /// The zero address is mapped to native eth
+    pub payment_token: TokenInfoWithAddress,
+    /// Total paid by the buyer, including the marketplace fees and royalties
+    pub price:         Rational,
+}
+
+impl NftFill {
+    /// The collection as a token, so that fills can be stored and displayed
+    /// like swaps
+    pub fn collection_token(&self) -> TokenInfoWithAddress {
+        TokenInfoWithAddress {
+            address: self.collection,
+            inner:   TokenInfo::new(0, "NFT".to_string()),
+        }
+    }
+
+    pub fn is_buy_of(&self, address: Address) -> bool {
+        self.buyer == address
+    }
+
+    pub fn is_sale_of(&self, address: Address) -> bool {
+        self.seller == address
+    }
+}
+
+/// NFT trades settled by a marketplace call, e.g. Seaport `fulfillBasicOrder`
+/// or `matchOrders` and Blur `execute`. A single call can fill several orders,
+/// so the fills are kept together. The payment and the NFTs are moved by the
+/// transfers around the call, the action itself doesn't change any token
+/// balances.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedNftTrade {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    pub from:        Address,
+    pub marketplace: Address,
+    pub fills:       Vec<NftFill>,
+    pub msg_value:   U256,
+}
+
+impl NormalizedNftTrade {
+    /// Whether `address` bought or sold in any of the fills
+    pub fn involves(&self, address: Address) -> bool {
+        self.fills
+            .iter()
+            .any(|fill| fill.is_buy_of(address) || fill.is_sale_of(address))
+    }
+
+    /// The fills `address` took part in as swaps
+    pub fn swaps_of(&self, address: Address) -> Vec<NormalizedSwap> {
+        self.fills
+            .iter()
+            .filter_map(|fill| self.fill_as_swap(fill, address))
+            .collect()
+    }
+
+    /// The fill from the side of `address`, with the payment token going in
+    /// for a buy and coming out for a sale. `None` if `address` isn't part of
+    /// the fill
+    pub fn fill_as_swap(&self, fill: &NftFill, address: Address) -> Option<NormalizedSwap> {
+        let nft_amount = Rational::from(fill.amount.saturating_to::<u128>());
+        let (token_in, amount_in, token_out, amount_out) = if fill.is_buy_of(address) {
+            (fill.payment_token.clone(), fill.price.clone(), fill.collection_token(), nft_amount)
+        } else if fill.is_sale_of(address) {
+            (fill.collection_token(), nft_amount, fill.payment_token.clone(), fill.price.clone())
+        } else {
+            return None
+        };
+
+        Some(NormalizedSwap {
+            protocol: self.protocol,
+            trace_index: self.trace_index,
+            from: address,
+            recipient: address,
+            pool: self.marketplace,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: self.msg_value,
+            fee_tier: None,
+            #[cfg(feature = "provenance")]
+            provenance: None,
+        })
+    }
+}
+
+impl CustomAction for NormalizedNftTrade {
+    const KIND: &'static str = "nft_trade";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.marketplace
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nft_fills_as_swaps() {
+        let [seller, buyer, collection] = [1, 2, 3].map(Address::with_last_byte);
+        let trade = NormalizedNftTrade {
+            protocol: Protocol::Seaport,
+            marketplace: Address::with_last_byte(4),
+            fills: vec![NftFill {
+                seller,
+                buyer,
+                collection,
+                token_id: U256::from(42),
+                amount: U256::from(1),
+                payment_token: TokenInfoWithAddress::weth(),
+                price: Rational::from(3),
+            }],
+            ..Default::default()
+        };
+
+        let [buy] = trade.swaps_of(buyer).try_into().unwrap();
+        assert_eq!(buy.token_in.address, TokenInfoWithAddress::weth().address);
+        assert_eq!(buy.amount_in, Rational::from(3));
+        assert_eq!((buy.token_out.address, buy.amount_out), (collection, Rational::from(1)));
+
+        let [sale] = trade.swaps_of(seller).try_into().unwrap();
+        assert_eq!(sale.token_in.address, collection);
+        assert_eq!(sale.amount_out, Rational::from(3));
+
+        assert!(trade.swaps_of(collection).is_empty());
+        assert!(!trade.involves(collection));
+    }
+}
@@ -0,0 +1,50 @@
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use super::custom::CustomAction;
+use crate::Protocol;
+
+/// A signed order of a swapper filled by a third party, e.g. a UniswapX
+/// `execute` or a 1inch limit order `fillOrder`. The order never goes through
+/// the public mempool as a swap of the swapper, so the fill is what attributes
+/// the order flow to the filler. The tokens are moved by the transfers around
+/// the call, the action itself doesn't change any token balances.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedOrderFill {
+    pub protocol:            Protocol,
+    pub trace_index:         u64,
+    pub order_hash:          B256,
+    /// Executes the order and pays out the swapper
+    pub filler:              Address,
+    /// Signed the order
+    pub swapper:             Address,
+    /// The reactor or limit order contract the order is settled by
+    pub settlement_contract: Address,
+    pub msg_value:           U256,
+}
+
+impl NormalizedOrderFill {
+    pub fn involves(&self, address: Address) -> bool {
+        self.filler == address || self.swapper == address
+    }
+}
+
+impl CustomAction for NormalizedOrderFill {
+    const KIND: &'static str = "order_fill";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.filler
+    }
+
+    fn to_address(&self) -> Address {
+        self.settlement_contract
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+}
@@ -1,7 +1,9 @@
 use alloy_primitives::Address;
 use serde::Deserialize;
 
-use crate::Protocol;
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+use crate::{pair::Pair, Protocol};
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct NormalizedNewPool {
@@ -9,6 +11,15 @@ pub struct NormalizedNewPool {
     pub protocol:     Protocol,
     pub pool_address: Address,
     pub tokens:       Vec<Address>,
+    /// Swap fee of the pool in hundredths of a basis point, for the protocols
+    /// that deploy a pool per fee tier
+    #[serde(default)]
+    pub fee_tier:     Option<u32>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:   Option<ActionProvenance>,
 }
 
 impl TryFrom<NormalizedNewPool> for NormalizedPoolConfigUpdate {
@@ -21,9 +32,11 @@ impl TryFrom<NormalizedNewPool> for NormalizedPoolConfigUpdate {
 
         Ok(NormalizedPoolConfigUpdate {
             pool_address: value.pool_address,
-            trace_index:  value.trace_index,
-            protocol:     value.protocol,
-            tokens:       value.tokens,
+            trace_index: value.trace_index,
+            protocol: value.protocol,
+            tokens: value.tokens,
+            #[cfg(feature = "provenance")]
+            provenance: value.provenance,
         })
     }
 }
@@ -34,4 +47,20 @@ pub struct NormalizedPoolConfigUpdate {
     pub protocol:     Protocol,
     pub pool_address: Address,
     pub tokens:       Vec<Address>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:   Option<ActionProvenance>,
+}
+
+impl NormalizedPoolConfigUpdate {
+    /// The pair of the first two tokens, which the pricing graph knows the
+    /// pool by. `None` if the pool has fewer than two tokens
+    pub fn pair(&self) -> Option<Pair> {
+        match self.tokens[..] {
+            [token0, token1, ..] => Some(Pair(token0, token1)),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,129 @@
+use alloy_primitives::{FixedBytes, TxHash};
+#[cfg(feature = "provenance")]
+use alloy_rpc_types_trace::parity::Action as TraceAction;
+#[cfg(feature = "rkyv")]
+use redefined::Redefined;
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::*;
+use crate::serde_utils::txhash;
+#[cfg(feature = "provenance")]
+use crate::structured_trace::TransactionTraceWithLogs;
+
+/// Where in the transaction a normalized action was classified from, so that
+/// the action can be traced back to the exact call and logs that produced it.
+///
+/// Only collected when brontes is built with the `provenance` feature.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
+pub struct ActionProvenance {
+    #[serde(with = "txhash")]
+    pub tx_hash:       TxHash,
+    pub trace_idx:     u64,
+    pub trace_address: Vec<u64>,
+    /// Function selector of the call, none for creates, self destructs and
+    /// calls without calldata
+    pub selector:      Option<FixedBytes<4>>,
+    /// Indices in the transaction receipt of the logs emitted by the call
+    /// frame itself. Logs of its subcalls belong to their own frames. Empty
+    /// if the tracer didn't record them, see
+    /// [`TransactionTraceWithLogs::tx_log_indices`]
+    pub log_indices:   Vec<u64>,
+}
+
+#[cfg(feature = "provenance")]
+impl ActionProvenance {
+    pub fn new(tx_hash: TxHash, trace: &TransactionTraceWithLogs) -> Self {
+        let selector = match &trace.trace.action {
+            TraceAction::Call(call) if call.input.len() >= 4 => {
+                Some(FixedBytes::from_slice(&call.input[..4]))
+            }
+            _ => None,
+        };
+
+        Self {
+            tx_hash,
+            trace_idx: trace.trace_idx,
+            trace_address: trace
+                .trace
+                .trace_address
+                .iter()
+                .map(|i| *i as u64)
+                .collect(),
+            selector,
+            log_indices: trace.tx_log_indices.clone(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "provenance"))]
+mod tests {
+    use alloy_primitives::{Address, Log, B256};
+    use alloy_rpc_types_trace::parity::{CallAction, CallType, TransactionTrace};
+
+    use super::*;
+    use crate::normalized_actions::{Action, NormalizedSwap};
+
+    fn call_trace() -> TransactionTraceWithLogs {
+        let mut input = vec![0x12, 0x8a, 0xcb, 0x08];
+        input.extend([0u8; 32]);
+
+        TransactionTraceWithLogs {
+            trace:          TransactionTrace {
+                action:        TraceAction::Call(CallAction {
+                    from:      Address::with_last_byte(2),
+                    to:        Address::with_last_byte(3),
+                    input:     input.into(),
+                    call_type: CallType::Call,
+                    gas:       Default::default(),
+                    value:     Default::default(),
+                }),
+                error:         None,
+                result:        None,
+                subtraces:     0,
+                trace_address: vec![0, 2],
+            },
+            logs:           vec![Log::default(), Log::default()],
+            tx_log_indices: vec![3, 5],
+            msg_sender:     Address::with_last_byte(2),
+            trace_idx:      4,
+            decoded_data:   None,
+        }
+    }
+
+    #[test]
+    fn provenance_from_call_trace() {
+        let tx_hash = B256::with_last_byte(1);
+
+        let provenance = ActionProvenance::new(tx_hash, &call_trace());
+        assert_eq!(provenance.tx_hash, tx_hash);
+        assert_eq!(provenance.trace_idx, 4);
+        assert_eq!(provenance.trace_address, vec![0, 2]);
+        assert_eq!(provenance.selector, Some(FixedBytes::new([0x12, 0x8a, 0xcb, 0x08])));
+        assert_eq!(provenance.log_indices, vec![3, 5]);
+    }
+
+    #[test]
+    fn provenance_is_set_on_classified_actions() {
+        let provenance = ActionProvenance::new(B256::with_last_byte(1), &call_trace());
+
+        let mut swap = Action::Swap(NormalizedSwap::default());
+        swap.set_provenance(provenance.clone());
+        assert_eq!(swap.provenance(), Some(&provenance));
+        // written to its own columns, not as part of the action
+        assert!(!serde_json::to_string(&swap).unwrap().contains("provenance"));
+
+        // the trace is its own provenance
+        let mut unclassified = Action::Unclassified(call_trace());
+        unclassified.set_provenance(provenance);
+        assert_eq!(unclassified.provenance(), None);
+    }
+}
@@ -0,0 +1,35 @@
+use alloy_primitives::{Address, Selector, U256};
+use serde::{Deserialize, Serialize};
+
+use super::custom::CustomAction;
+use crate::db::function_selector::SelectorKind;
+
+/// A call to a contract no protocol classifier knows about, classified from
+/// its function selector alone. Only the kind of call is known, not the
+/// tokens or amounts it moved, so it doesn't affect the accounting.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedSelectorCall {
+    pub trace_index: u64,
+    pub from:        Address,
+    pub target:      Address,
+    pub selector:    Selector,
+    pub signature:   String,
+    pub kind:        SelectorKind,
+    pub msg_value:   U256,
+}
+
+impl CustomAction for NormalizedSelectorCall {
+    const KIND: &'static str = "selector_call";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.target
+    }
+}
@@ -1,11 +1,13 @@
 use std::fmt::Debug;
 
+use alloy_primitives::{Address, U256};
+use alloy_rpc_types_trace::parity::SelfdestructAction;
+#[cfg(feature = "native")]
 use clickhouse::Row;
-use reth_primitives::{Address, U256};
-use reth_rpc_types::trace::parity::SelfdestructAction;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Clone, Row, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct SelfdestructWithIndex {
     pub trace_index:   u64,
     pub self_destruct: SelfdestructAction,
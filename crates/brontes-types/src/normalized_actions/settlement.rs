@@ -0,0 +1,56 @@
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use super::custom::CustomAction;
+use crate::Protocol;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementKind {
+    /// Pays the currency owed to the pool manager
+    #[default]
+    Settle,
+    /// Withdraws the currency the pool manager owes
+    Take,
+}
+
+/// Settles a balance with a singleton pool manager, such as the Uniswap V4
+/// `PoolManager`. Swaps and liquidity changes only accrue deltas on the
+/// manager, which are paid with `settle` and withdrawn with `take`. The tokens
+/// are moved by the transfers around the call, so it doesn't affect the
+/// accounting itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedSettlement {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    pub kind:        SettlementKind,
+    pub from:        Address,
+    /// The pool manager
+    pub manager:     Address,
+    /// The currency taken. Settling pays the currency that was last synced,
+    /// which isn't part of the call, so it is `None` for settles
+    pub currency:    Option<Address>,
+    pub recipient:   Address,
+    /// Unscaled amount paid or taken
+    pub amount:      U256,
+    pub msg_value:   U256,
+}
+
+impl CustomAction for NormalizedSettlement {
+    const KIND: &'static str = "settlement";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.manager
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+}
@@ -0,0 +1,262 @@
+use alloy_primitives::{Address, U256};
+use malachite::{num::basic::traits::Zero, Rational};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accounting::{apply_delta, AddressDeltas},
+    custom::CustomAction,
+    Action,
+};
+use crate::{
+    constants::ETH_ADDRESS, db::token_info::TokenInfoWithAddress, Protocol, ToScaledRational,
+};
+
+/// Eth deposited into a liquid staking protocol in exchange for its staking
+/// token, e.g. Lido `submit` or the rETH minted for a RocketPool deposit.
+///
+/// The staking tokens are minted without a transfer call, so the action
+/// accounts for them. The eth is accounted for if it was sent with the call,
+/// otherwise the call that received it covers it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedStake {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    pub from:        Address,
+    /// Receiver of the staking token
+    pub recipient:   Address,
+    /// The staking contract
+    pub pool:        Address,
+    /// Eth staked
+    pub amount_in:   Rational,
+    /// The staking token
+    pub token:       TokenInfoWithAddress,
+    pub amount_out:  Rational,
+    pub msg_value:   U256,
+}
+
+impl CustomAction for NormalizedStake {
+    const KIND: &'static str = "stake";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.pool
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        if !self.msg_value.is_zero() {
+            let value = self.msg_value.to_scaled_rational(18);
+            apply_delta(self.from, ETH_ADDRESS, -value.clone(), delta_map);
+            apply_delta(self.pool, ETH_ADDRESS, value, delta_map);
+        }
+        apply_delta(self.recipient, self.token.address, self.amount_out.clone(), delta_map);
+    }
+}
+
+/// Staking tokens redeemed for eth, e.g. a rETH `burn`. The burned tokens are
+/// accounted for by the action, the eth is paid out by a transfer of its own.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedUnstake {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    /// Owner of the burned staking tokens and receiver of the eth
+    pub from:        Address,
+    /// The staking contract
+    pub pool:        Address,
+    /// The staking token
+    pub token:       TokenInfoWithAddress,
+    pub amount_in:   Rational,
+    /// Eth received
+    pub amount_out:  Rational,
+    pub msg_value:   U256,
+}
+
+impl CustomAction for NormalizedUnstake {
+    const KIND: &'static str = "unstake";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.pool
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        apply_delta(self.from, self.token.address, -self.amount_in.clone(), delta_map);
+    }
+}
+
+/// A rebasing staking token wrapped into its non rebasing version or unwrapped
+/// back, e.g. stETH to wstETH. The wrapped token is minted or burned by the
+/// wrapper without a transfer call, so the action accounts for it. The
+/// rebasing token is moved by a regular transfer.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizedWrap {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    pub from:        Address,
+    /// The wrapped token contract
+    pub wrapper:     Address,
+    pub token_in:    TokenInfoWithAddress,
+    pub amount_in:   Rational,
+    pub token_out:   TokenInfoWithAddress,
+    pub amount_out:  Rational,
+    pub msg_value:   U256,
+}
+
+impl NormalizedWrap {
+    pub fn is_unwrap(&self) -> bool {
+        self.token_in.address == self.wrapper
+    }
+}
+
+impl CustomAction for NormalizedWrap {
+    const KIND: &'static str = "wrap";
+
+    fn trace_index(&self) -> u64 {
+        self.trace_index
+    }
+
+    fn from_address(&self) -> Address {
+        self.from
+    }
+
+    fn to_address(&self) -> Address {
+        self.wrapper
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        if self.is_unwrap() {
+            apply_delta(self.from, self.wrapper, -self.amount_in.clone(), delta_map);
+        } else {
+            apply_delta(self.from, self.wrapper, self.amount_out.clone(), delta_map);
+        }
+    }
+}
+
+/// Exchange rate of a staking token to the token it is redeemed for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakingRate {
+    pub token:      Address,
+    /// [`ETH_ADDRESS`] for tokens redeemed for eth
+    pub underlying: Address,
+    /// Underlying tokens a single staking token is worth
+    pub rate:       Rational,
+}
+
+impl Action {
+    /// The exchange rate a staking action was executed at
+    pub fn staking_rate(&self) -> Option<StakingRate> {
+        let (token, underlying, token_amount, underlying_amount) =
+            if let Some(stake) = self.try_custom_ref::<NormalizedStake>() {
+                (stake.token.address, ETH_ADDRESS, &stake.amount_out, &stake.amount_in)
+            } else if let Some(unstake) = self.try_custom_ref::<NormalizedUnstake>() {
+                (unstake.token.address, ETH_ADDRESS, &unstake.amount_in, &unstake.amount_out)
+            } else if let Some(wrap) = self.try_custom_ref::<NormalizedWrap>() {
+                if wrap.is_unwrap() {
+                    (wrap.wrapper, wrap.token_out.address, &wrap.amount_in, &wrap.amount_out)
+                } else {
+                    (wrap.wrapper, wrap.token_in.address, &wrap.amount_out, &wrap.amount_in)
+                }
+            } else {
+                return None
+            };
+
+        if *token_amount == Rational::ZERO || *underlying_amount == Rational::ZERO {
+            return None
+        }
+
+        Some(StakingRate { token, underlying, rate: underlying_amount / token_amount })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::{accounting::ActionAccounting, NormalizedTransfer};
+
+    fn token(byte: u8) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address: Address::with_last_byte(byte), ..Default::default() }
+    }
+
+    #[test]
+    fn wrap_accounts_for_the_minted_token() {
+        let user = Address::with_last_byte(1);
+        let wrapper = Address::with_last_byte(2);
+        let wrap = NormalizedWrap {
+            from: user,
+            wrapper,
+            token_in: token(3),
+            amount_in: Rational::from(115),
+            token_out: token(2),
+            amount_out: Rational::from(100),
+            ..Default::default()
+        };
+        // the rebasing token is pulled with a transfer call
+        let transfer = NormalizedTransfer {
+            from: user,
+            to: wrapper,
+            token: token(3),
+            amount: Rational::from(115),
+            ..Default::default()
+        };
+
+        let deltas = [Action::Custom(wrap.clone().into()), transfer.into()]
+            .into_iter()
+            .account_for_actions();
+        assert_eq!(deltas[&user][&token(3).address], Rational::from(-115));
+        assert_eq!(deltas[&user][&wrapper], Rational::from(100));
+
+        let rate = Action::Custom(wrap.into()).staking_rate().unwrap();
+        assert_eq!(rate.token, wrapper);
+        assert_eq!(rate.underlying, token(3).address);
+        assert_eq!(rate.rate, Rational::from_unsigneds(23u32, 20));
+    }
+
+    #[test]
+    fn stake_accounts_for_the_eth_sent() {
+        let user = Address::with_last_byte(1);
+        let pool = Address::with_last_byte(2);
+        let one_eth = U256::from(10).pow(U256::from(18));
+        let stake = NormalizedStake {
+            from: user,
+            recipient: user,
+            pool,
+            amount_in: Rational::from(1),
+            token: token(2),
+            amount_out: Rational::from(1),
+            msg_value: one_eth,
+            ..Default::default()
+        };
+
+        let deltas = [Action::Custom(stake.into())]
+            .into_iter()
+            .account_for_actions();
+        assert_eq!(deltas[&user][&ETH_ADDRESS], Rational::from(-1));
+        assert_eq!(deltas[&user][&token(2).address], Rational::from(1));
+        assert_eq!(deltas[&pool][&ETH_ADDRESS], Rational::from(1));
+    }
+}
@@ -4,30 +4,38 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use alloy_primitives::{TxHash, U256};
+use alloy_primitives::{Address, TxHash, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use colored::Colorize;
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::Address;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+#[cfg(all(feature = "provenance", feature = "rkyv"))]
+use super::ActionProvenanceRedefined;
 use super::{
     accounting::{apply_delta, AddressDeltas, TokenAccounting},
     Action,
 };
+#[cfg(feature = "rkyv")]
+use crate::db::{
+    redefined_types::{malachite::*, primitives::*},
+    token_info::TokenInfoWithAddressRedefined,
+};
 use crate::{
-    db::{
-        redefined_types::{malachite::*, primitives::*},
-        token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
-    },
-    mev::ArbDetails,
-    rational_to_u256_fraction, Protocol, ToFloatNearest,
+    db::token_info::TokenInfoWithAddress, mev::ArbDetails, rational_to_u256_fraction, Protocol,
+    ToFloatNearest,
 };
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(Row))]
 pub struct NormalizedSwapWithFee {
     pub swap:       NormalizedSwap,
     pub fee_token:  TokenInfoWithAddress,
@@ -47,10 +55,16 @@ impl DerefMut for NormalizedSwapWithFee {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq, Redefined, Hash)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct NormalizedSwap {
-    #[redefined(same_fields)]
+    #[cfg_attr(feature = "rkyv", redefined(same_fields))]
     pub protocol:    Protocol,
     pub trace_index: u64,
     pub from:        Address,
@@ -63,6 +77,16 @@ pub struct NormalizedSwap {
     pub amount_in:   Rational,
     pub amount_out:  Rational,
     pub msg_value:   U256,
+    /// Fee tier of the pool in hundredths of a basis point, set for pools
+    /// that were discovered with one and for curve pools that changed their
+    /// fee
+    #[serde(default)]
+    pub fee_tier:    Option<u32>,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
 }
 
 impl NormalizedSwap {
@@ -125,6 +149,7 @@ pub struct ClickhouseVecNormalizedSwap {
     pub token_out:   Vec<(String, String)>,
     pub amount_in:   Vec<([u8; 32], [u8; 32])>,
     pub amount_out:  Vec<([u8; 32], [u8; 32])>,
+    pub fee_tier:    Vec<Option<u32>>,
 }
 
 impl TryFrom<Vec<NormalizedSwap>> for ClickhouseVecNormalizedSwap {
@@ -155,6 +180,7 @@ impl TryFrom<Vec<NormalizedSwap>> for ClickhouseVecNormalizedSwap {
                 .iter()
                 .map(|val| rational_to_u256_fraction(&val.amount_out))
                 .collect::<eyre::Result<Vec<_>>>()?,
+            fee_tier:    value.iter().map(|val| val.fee_tier).collect(),
         })
     }
 }
@@ -170,6 +196,7 @@ pub struct ClickhouseDoubleVecNormalizedSwap {
     pub token_out:   Vec<(String, String)>,
     pub amount_in:   Vec<([u8; 32], [u8; 32])>,
     pub amount_out:  Vec<([u8; 32], [u8; 32])>,
+    pub fee_tier:    Vec<Option<u32>>,
 }
 
 impl TryFrom<(Vec<TxHash>, Vec<Vec<NormalizedSwap>>)> for ClickhouseDoubleVecNormalizedSwap {
@@ -209,6 +236,7 @@ impl TryFrom<(Vec<TxHash>, Vec<Vec<NormalizedSwap>>)> for ClickhouseDoubleVecNor
             this.token_out.extend(inner_swaps.token_out);
             this.amount_in.extend(inner_swaps.amount_in);
             this.amount_out.extend(inner_swaps.amount_out);
+            this.fee_tier.extend(inner_swaps.fee_tier);
         });
 
         Ok(this)
@@ -1,23 +1,35 @@
 use std::fmt::Debug;
 
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use malachite::Rational;
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
-use reth_primitives::{Address, U256};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
-use crate::{
-    db::{
-        redefined_types::{malachite::*, primitives::*},
-        token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
-    },
-    rational_to_u256_fraction,
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+#[cfg(all(feature = "provenance", feature = "rkyv"))]
+use super::ActionProvenanceRedefined;
+#[cfg(feature = "rkyv")]
+use crate::db::{
+    redefined_types::{malachite::*, primitives::*},
+    token_info::TokenInfoWithAddressRedefined,
 };
+use crate::{db::token_info::TokenInfoWithAddress, rational_to_u256_fraction};
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq, Redefined)]
-#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(#[archive(check_bytes)])))]
 pub struct NormalizedTransfer {
     pub trace_index: u64,
     pub from:        Address,
@@ -26,6 +38,11 @@ pub struct NormalizedTransfer {
     pub amount:      Rational,
     pub fee:         Rational,
     pub msg_value:   U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
 }
 
 impl TokenAccounting for NormalizedTransfer {
@@ -0,0 +1,184 @@
+use alloy_primitives::{Address, U256};
+#[cfg(feature = "native")]
+use clickhouse::Row;
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "provenance")]
+use super::ActionProvenance;
+use super::{
+    accounting::{apply_delta, AddressDeltas, TokenAccounting},
+    comparison::ActionCmp,
+    Action, NormalizedTransfer,
+};
+use crate::{db::token_info::TokenInfoWithAddress, Protocol};
+
+/// Deposit of assets into an ERC-4626 vault, through either `deposit` or
+/// `mint`. The shares are minted to the owner.
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct NormalizedVaultDeposit {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    /// Caller that supplied the assets
+    pub from:        Address,
+    /// Receiver of the shares
+    pub owner:       Address,
+    pub vault:       Address,
+    pub asset:       TokenInfoWithAddress,
+    pub assets:      Rational,
+    /// The vault token
+    pub share:       TokenInfoWithAddress,
+    pub shares:      Rational,
+    pub msg_value:   U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
+}
+
+impl TokenAccounting for NormalizedVaultDeposit {
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        apply_delta(self.from, self.asset.address, -self.assets.clone(), delta_map);
+        apply_delta(self.vault, self.asset.address, self.assets.clone(), delta_map);
+        apply_delta(self.owner, self.share.address, self.shares.clone(), delta_map);
+    }
+}
+
+/// Withdrawal of assets from an ERC-4626 vault, through either `withdraw` or
+/// `redeem`. The shares of the owner are burned.
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(Row))]
+pub struct NormalizedVaultWithdraw {
+    pub protocol:    Protocol,
+    pub trace_index: u64,
+    /// Caller of the withdrawal, can differ from the owner if the owner
+    /// approved it
+    pub from:        Address,
+    /// Receiver of the assets
+    pub receiver:    Address,
+    /// Owner of the burned shares
+    pub owner:       Address,
+    pub vault:       Address,
+    pub asset:       TokenInfoWithAddress,
+    pub assets:      Rational,
+    /// The vault token
+    pub share:       TokenInfoWithAddress,
+    pub shares:      Rational,
+    pub msg_value:   U256,
+    /// Where the action was classified from, only kept when built with the
+    /// `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(skip)]
+    pub provenance:  Option<ActionProvenance>,
+}
+
+impl TokenAccounting for NormalizedVaultWithdraw {
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        apply_delta(self.owner, self.share.address, -self.shares.clone(), delta_map);
+        apply_delta(self.vault, self.asset.address, -self.assets.clone(), delta_map);
+        apply_delta(self.receiver, self.asset.address, self.assets.clone(), delta_map);
+    }
+}
+
+// the asset transfer into the vault and the minted shares are covered by the
+// deposit
+impl ActionCmp<NormalizedTransfer> for NormalizedVaultDeposit {
+    fn is_superior_action(&self, transfer: &NormalizedTransfer) -> bool {
+        (transfer.token == self.asset
+            && transfer.to == self.vault
+            && &transfer.amount + &transfer.fee == self.assets)
+            || (transfer.token == self.share
+                && transfer.from == Address::ZERO
+                && transfer.to == self.owner
+                && transfer.amount == self.shares)
+    }
+}
+
+impl ActionCmp<Action> for NormalizedVaultDeposit {
+    fn is_superior_action(&self, other: &Action) -> bool {
+        match other {
+            Action::Transfer(t) => self.is_superior_action(t),
+            _ => false,
+        }
+    }
+}
+
+// the asset transfer out of the vault and the burned shares are covered by
+// the withdrawal
+impl ActionCmp<NormalizedTransfer> for NormalizedVaultWithdraw {
+    fn is_superior_action(&self, transfer: &NormalizedTransfer) -> bool {
+        (transfer.token == self.asset
+            && transfer.from == self.vault
+            && transfer.to == self.receiver
+            && transfer.amount == self.assets)
+            || (transfer.token == self.share
+                && transfer.from == self.owner
+                && transfer.to == Address::ZERO
+                && transfer.amount == self.shares)
+    }
+}
+
+impl ActionCmp<Action> for NormalizedVaultWithdraw {
+    fn is_superior_action(&self, other: &Action) -> bool {
+        match other {
+            Action::Transfer(t) => self.is_superior_action(t),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::accounting::ActionAccounting;
+
+    fn token(byte: u8) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address: Address::with_last_byte(byte), ..Default::default() }
+    }
+
+    fn vault_token(vault: Address) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address: vault, ..Default::default() }
+    }
+
+    #[test]
+    fn deposit_covers_its_transfers() {
+        let user = Address::with_last_byte(1);
+        let vault = Address::with_last_byte(2);
+        let deposit = NormalizedVaultDeposit {
+            from: user,
+            owner: user,
+            vault,
+            asset: token(3),
+            assets: Rational::from(100),
+            share: vault_token(vault),
+            shares: Rational::from(90),
+            ..Default::default()
+        };
+        let asset_in = NormalizedTransfer {
+            from: user,
+            to: vault,
+            token: token(3),
+            amount: Rational::from(100),
+            ..Default::default()
+        };
+        let shares_minted = NormalizedTransfer {
+            from: Address::ZERO,
+            to: user,
+            token: vault_token(vault),
+            amount: Rational::from(90),
+            ..Default::default()
+        };
+
+        let deltas = [Action::from(deposit), asset_in.into(), shares_minted.into()]
+            .into_iter()
+            .account_for_actions();
+
+        assert_eq!(deltas[&user][&token(3).address], Rational::from(-100));
+        assert_eq!(deltas[&user][&vault], Rational::from(90));
+        assert_eq!(deltas[&vault][&token(3).address], Rational::from(100));
+        // the mint isn't accounted for twice
+        assert!(!deltas.contains_key(&Address::ZERO));
+    }
+}
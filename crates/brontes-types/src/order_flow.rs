@@ -0,0 +1,75 @@
+use alloy_primitives::TxHash;
+use serde::{Deserialize, Serialize};
+
+use crate::FastHashMap;
+
+/// The submission channels of the transactions of a block that are known
+pub type SubmissionChannels = FastHashMap<TxHash, SubmissionChannel>;
+
+/// How a transaction reached the builder of its block. The variants are
+/// ordered by how much they tell about the submission, so when sources
+/// disagree the most specific one is kept.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SubmissionChannel {
+    /// Seen in the public mempool, or nothing is known about it
+    #[default]
+    Public,
+    /// Not seen in the public mempool, but no relay reported how it was
+    /// submitted
+    Private,
+    /// Sent to a private rpc, e.g. Flashbots Protect or MEV Blocker
+    PrivateRpc,
+    /// Included as part of a bundle
+    Bundle,
+}
+
+impl SubmissionChannel {
+    pub fn is_private(&self) -> bool {
+        *self != Self::Public
+    }
+
+    /// Keeps the most specific of the two channels
+    pub fn merge(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_most_specific_channel() {
+        assert_eq!(
+            SubmissionChannel::Public.merge(SubmissionChannel::Bundle),
+            SubmissionChannel::Bundle
+        );
+        assert_eq!(
+            SubmissionChannel::PrivateRpc.merge(SubmissionChannel::Private),
+            SubmissionChannel::PrivateRpc
+        );
+        assert_eq!(
+            "private_rpc".parse::<SubmissionChannel>().unwrap(),
+            SubmissionChannel::PrivateRpc
+        );
+        assert!(!SubmissionChannel::default().is_private());
+    }
+}
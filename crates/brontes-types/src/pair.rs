@@ -2,41 +2,40 @@ use std::{hash::Hash, str::FromStr};
 
 use alloy_primitives::Address;
 use alloy_rlp::{BufMut, Decodable, Encodable};
+#[cfg(feature = "rkyv")]
 use redefined::Redefined;
+#[cfg(feature = "libmdbx")]
 use reth_db::table::{Decode, Encode};
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::{constants::USD_STABLES_BY_ADDRESS, db::redefined_types::primitives::AddressRedefined};
+use crate::constants::USD_STABLES_BY_ADDRESS;
+#[cfg(feature = "rkyv")]
+use crate::db::redefined_types::primitives::AddressRedefined;
 
 #[derive(
-    Debug,
-    Default,
-    Clone,
-    Copy,
-    Serialize,
-    Deserialize,
-    PartialOrd,
-    Ord,
-    Redefined,
-    PartialEq,
-    Eq,
-    Hash,
+    Debug, Default, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord, PartialEq, Eq, Hash,
 )]
-#[redefined_attr(derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    Hash,
-    Serialize,
-    rDeserialize,
-    rSerialize,
-    Archive,
-))]
-#[redefined_attr(other(
+#[cfg_attr(feature = "rkyv", derive(Redefined))]
+#[cfg_attr(
+    feature = "rkyv",
+    redefined_attr(derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        Hash,
+        Serialize,
+        rDeserialize,
+        rSerialize,
+        Archive,
+    ))
+)]
+#[cfg_attr(feature = "rkyv", redefined_attr(other(
+    #[archive(check_bytes)]
     #[archive_attr(derive(Hash, PartialEq, Eq))]
-))]
+)))]
 pub struct Pair(pub Address, pub Address);
 
 impl Pair {
@@ -95,6 +94,7 @@ impl Pair {
     }
 }
 
+#[cfg(feature = "libmdbx")]
 impl Encode for Pair {
     type Encoded = [u8; 40];
 
@@ -109,6 +109,7 @@ impl Encode for Pair {
     }
 }
 
+#[cfg(feature = "libmdbx")]
 impl Decode for Pair {
     fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, reth_db::DatabaseError> {
         let address0 = &value.as_ref()[0..20];
@@ -146,12 +147,6 @@ impl Decodable for Pair {
     }
 }
 
-#[derive(Debug)]
-pub struct ExtraProcessing {
-    // decimals that are missing that we want to fill
-    pub tokens_decimal_fill: Vec<Address>,
-}
-
 // #[cfg(test)]
 // mod tests {
 //     use std::collections::HashSet;
@@ -1,6 +1,8 @@
 use std::fmt;
 
+#[cfg(feature = "rkyv")]
 use redefined::self_convert_redefined;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +31,15 @@ macro_rules! utils {
                     ) +
                 }
             }
+
+            pub const fn from_byte(byte: u8) -> Option<Self> {
+                $(
+                    if byte == Self::$varient as u8 {
+                        return Some(Self::$varient)
+                    }
+                )+
+                None
+            }
             pub fn parse_string(str: String) -> Self {
                 let lower = str.to_lowercase();
                 paste::paste!(
@@ -56,13 +67,12 @@ utils!(
         Hash,
         Serialize,
         Deserialize,
-        rSerialize,
-        rDeserialize,
-        Archive,
         PartialOrd,
         Ord,
         strum::EnumString,
     )]
+    #[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
+    #[cfg_attr(feature = "rkyv", archive(check_bytes))]
     #[repr(u8)]
     pub enum Protocol {
         UniswapV2,
@@ -102,6 +112,16 @@ utils!(
         ClipperExchange,
         PropellerLabsSolver,
         Dodo,
+        CompoundV3,
+        MakerDog,
+        MakerClipper,
+        Erc4626,
+        UniswapV4,
+        Seaport,
+        Blur,
+        MaverickV2,
+        Lido,
+        RocketPool,
         #[default]
         Unknown,
     }
@@ -147,6 +167,16 @@ impl Protocol {
             Protocol::ClipperExchange => ("ClipperExchange", ""),
             Protocol::PropellerLabsSolver => ("Propeller Labs Solver", ""),
             Protocol::Dodo => ("Dodo", "V1/V2"),
+            Protocol::CompoundV3 => ("Compound", "V3"),
+            Protocol::MakerDog => ("Maker", "Dog"),
+            Protocol::MakerClipper => ("Maker", "Clipper"),
+            Protocol::Erc4626 => ("ERC-4626", "Vault"),
+            Protocol::UniswapV4 => ("Uniswap", "V4"),
+            Protocol::Seaport => ("OpenSea", "Seaport"),
+            Protocol::Blur => ("Blur", "Exchange"),
+            Protocol::MaverickV2 => ("Maverick", "V2"),
+            Protocol::Lido => ("Lido", ""),
+            Protocol::RocketPool => ("RocketPool", ""),
             Protocol::Unknown => ("Unknown", "Unknown"),
         }
     }
@@ -156,6 +186,9 @@ impl Protocol {
             "uniswapv2" => Protocol::UniswapV2,
             "sushiswapv2" => Protocol::SushiSwapV2,
             "uniswapv3" => Protocol::UniswapV3,
+            "uniswapv4" => Protocol::UniswapV4,
+            "seaport" => Protocol::Seaport,
+            "blur" => Protocol::Blur,
             "sushiswapv3" => Protocol::SushiSwapV3,
             "curve.fibase2" => Protocol::CurveBasePool2,
             "curve.fibase3" => Protocol::CurveBasePool3,
@@ -174,9 +207,45 @@ impl Protocol {
             "dodov1/v2" => Protocol::Dodo,
             "pancakeswapv2" => Protocol::PancakeSwapV2,
             "pancakeswapv3" => Protocol::PancakeSwapV3,
+            "maverickv2" => Protocol::MaverickV2,
             _ => Protocol::Unknown,
         }
     }
+
+    /// Curve pools using the stableswap invariant, which are parameterized by
+    /// an amplification coefficient
+    pub fn is_curve_stableswap(&self) -> bool {
+        matches!(
+            self,
+            Protocol::CurveBasePool2
+                | Protocol::CurveBasePool3
+                | Protocol::CurveBasePool4
+                | Protocol::CurveV1MetaPool
+                | Protocol::CurveV1MetapoolImpl
+                | Protocol::CurveV2MetaPool
+                | Protocol::CurveV2MetapoolImpl
+                | Protocol::CurveV2PlainPool
+                | Protocol::CurveV2PlainPoolImpl
+                | Protocol::CurvecrvUSDMetaPool
+                | Protocol::CurvecrvUSDMetapoolImpl
+                | Protocol::CurvecrvUSDPlainPool
+                | Protocol::CurvecrvUSDPlainPoolImpl
+        )
+    }
+
+    /// Curve pools that pair a coin with the LP token of a base pool, whose
+    /// tokens are the meta coin followed by the coins of the base pool
+    pub fn is_curve_metapool(&self) -> bool {
+        matches!(
+            self,
+            Protocol::CurveV1MetaPool
+                | Protocol::CurveV1MetapoolImpl
+                | Protocol::CurveV2MetaPool
+                | Protocol::CurveV2MetapoolImpl
+                | Protocol::CurvecrvUSDMetaPool
+                | Protocol::CurvecrvUSDMetapoolImpl
+        )
+    }
 }
 
 impl fmt::Display for Protocol {
@@ -222,11 +291,22 @@ impl fmt::Display for Protocol {
                 Protocol::ClipperExchange => "Clipper",
                 Protocol::PropellerLabsSolver => "Propeller Labs",
                 Protocol::Dodo => "Dodo",
+                Protocol::CompoundV3 => "Compound V3",
+                Protocol::MakerDog => "Maker Dog",
+                Protocol::MakerClipper => "Maker Clipper",
+                Protocol::Erc4626 => "ERC-4626 Vault",
+                Protocol::UniswapV4 => "Uni V4",
+                Protocol::Seaport => "Seaport",
+                Protocol::Blur => "Blur",
+                Protocol::MaverickV2 => "Maverick V2",
+                Protocol::Lido => "Lido",
+                Protocol::RocketPool => "Rocket Pool",
                 Protocol::Unknown => "Unknown",
             }
         )
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(Protocol);
 implement_table_value_codecs_with_zc!(Protocol);
@@ -10,9 +10,12 @@ pub fn init_thread_pools(max_tasks: usize) {
     let pricing_tasks = (max_tasks as f64 * 0.70) as usize + 1;
     // inspector runtime ~ 50ms
     let inspect_tasks = max_tasks;
+    // tree building, a tx at a time
+    let classification_tasks = (max_tasks as f64 * 0.5) as usize + 1;
 
     init_pricing_thread_pool(pricing_tasks);
     init_inspect_threadpool(inspect_tasks);
+    init_classification_thread_pool(classification_tasks);
 }
 
 /// To use
@@ -23,6 +26,7 @@ pub fn init_thread_pools(max_tasks: usize) {
 /// where ? can be,
 /// - pricing
 /// - inspect
+/// - classification
 #[macro_export]
 macro_rules! execute_on {
     (target=$t:tt, $block:block) => {
@@ -40,6 +44,9 @@ macro_rules! execute_on {
     (async_inspect, $block:block) => {
         ::brontes_types::execute_on_inspect_thread_pool_async(move || $block)
     };
+    (classification, $block:block) => {
+        ::brontes_types::execute_on_classification_thread_pool(|| $block)
+    };
 }
 
 /// ThreadPool for pricing operations
@@ -90,6 +97,7 @@ where
         .install(op)
 }
 
+#[cfg(feature = "native")]
 pub async fn execute_on_inspect_thread_pool_async<OP, R>(op: OP) -> R
 where
     OP: FnOnce() -> R + Send + 'static,
@@ -106,3 +114,35 @@ where
 
     rx.await.unwrap()
 }
+
+/// ThreadPool for classifying the txs of a block
+static RAYON_CLASSIFICATION_THREADPOOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+
+fn build_classification_thread_pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|idx| format!("Classification: {}", idx))
+        .build()
+        .unwrap()
+}
+
+fn init_classification_thread_pool(threads: usize) {
+    let _ = RAYON_CLASSIFICATION_THREADPOOL.set(build_classification_thread_pool(threads));
+}
+
+/// Unlike the other pools, falls back to a pool with a thread per core if it
+/// wasn't initialized, as the classifier is also used by tests and tooling
+/// that don't set up the thread pools
+pub fn execute_on_classification_thread_pool<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    RAYON_CLASSIFICATION_THREADPOOL
+        .get_or_init(|| {
+            build_classification_thread_pool(
+                std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            )
+        })
+        .install(op)
+}
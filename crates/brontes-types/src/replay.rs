@@ -0,0 +1,25 @@
+//! Deterministic replay mode. When enabled, the sources of nondeterminism in a
+//! run are fixed so that two runs over the same inputs produce identical
+//! outputs:
+//!
+//! - hash maps and sets are seeded with a constant seed, so their iteration
+//!   order is the same across processes
+//! - the pricing updates of a block are applied in transaction and trace order,
+//!   regardless of the order they were drained from the channel in
+//! - blocks are processed by a single range executor, one block at a time
+//!
+//! This trades throughput for reproducibility and is meant for debugging
+//! ordering issues in the pricing and the composer.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DETERMINISTIC_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enables deterministic replay mode. Needs to be called before any state is
+/// built, as hash maps created before keep their random seed.
+pub fn enable_deterministic_mode() {
+    DETERMINISTIC_MODE.store(true, Ordering::SeqCst);
+}
+
+pub fn is_deterministic_mode() -> bool {
+    DETERMINISTIC_MODE.load(Ordering::Relaxed)
+}
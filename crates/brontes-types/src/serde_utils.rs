@@ -128,6 +128,7 @@ pub(crate) mod vec_vec_u256 {
     }
 }
 
+#[cfg(feature = "native")]
 #[allow(dead_code)]
 pub(crate) mod vec_vec_fixed_string {
 
@@ -171,6 +172,7 @@ pub(crate) mod vec_vec_fixed_string {
     }
 }
 
+#[cfg(feature = "native")]
 #[allow(dead_code)]
 pub(crate) mod vec_vec_b256 {
 
@@ -282,12 +284,13 @@ pub mod vec_bls_pub_key {
 
     use std::{fmt::Debug, str::FromStr};
 
-    use reth_rpc_types::beacon::BlsPublicKey;
     use serde::{
         de::{Deserialize, Deserializer},
         ser::{Serialize, Serializer},
     };
 
+    use crate::db::builder::BlsPublicKey;
+
     pub fn serialize<S: Serializer, T: Into<BlsPublicKey> + Debug>(
         u: &[T],
         serializer: S,
@@ -512,6 +515,7 @@ pub mod txhash {
     }
 }
 
+#[cfg(feature = "rkyv")]
 pub mod option_r_address {
 
     use std::str::FromStr;
@@ -578,6 +582,7 @@ pub mod option_address {
     }
 }
 
+#[cfg(feature = "rkyv")]
 pub mod r_address {
 
     use std::str::FromStr;
@@ -1,11 +1,13 @@
 use std::str::FromStr;
 
-use alloy_primitives::{Address, Log, U256};
+use alloy_primitives::{Address, Bytes, Log, B256, U256};
+use alloy_rpc_types_trace::parity::*;
+#[cfg(feature = "native")]
 use clickhouse::DbRow;
 use itertools::Itertools;
+#[cfg(feature = "rkyv")]
 use redefined::self_convert_redefined;
-use reth_primitives::{Bytes, B256};
-use reth_rpc_types::trace::parity::*;
+#[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
@@ -94,7 +96,7 @@ impl TraceActions for TransactionTraceWithLogs {
             return Bytes::default();
         };
         match res {
-            reth_rpc_types::trace::parity::TraceOutput::Call(bytes) => bytes.output.clone(),
+            alloy_rpc_types_trace::parity::TraceOutput::Call(bytes) => bytes.output.clone(),
             _ => Bytes::default(),
         }
     }
@@ -114,9 +116,8 @@ impl TraceActions for TransactionTraceWithLogs {
     }
 }
 
-#[derive(
-    Debug, Clone, Serialize, Deserialize, PartialEq, Eq, rSerialize, rDeserialize, Archive,
-)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
 
 pub struct DecodedCallData {
     pub function_name: String,
@@ -124,17 +125,18 @@ pub struct DecodedCallData {
     pub return_data:   Vec<DecodedParams>,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(DecodedCallData);
 
-#[derive(
-    Debug, Clone, Serialize, Deserialize, PartialEq, Eq, rSerialize, rDeserialize, Archive,
-)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(rSerialize, rDeserialize, Archive))]
 pub struct DecodedParams {
     pub field_name: String,
     pub field_type: String,
     pub value:      String,
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(DecodedParams);
 
 #[derive(Debug, Clone)]
@@ -173,13 +175,21 @@ impl CallFrameInfo<'_> {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TransactionTraceWithLogs {
-    pub trace:        TransactionTrace,
-    pub logs:         Vec<Log>,
+    pub trace:          TransactionTrace,
+    pub logs:           Vec<Log>,
+    /// Index of each of the `logs` in the transaction receipt. Empty if the
+    /// tracer didn't record where the logs were emitted in between the
+    /// subcalls, which is the case for call tracer traces and for traces
+    /// loaded from clickhouse, or if the frame was rolled back. Only recorded
+    /// with the `provenance` feature
+    #[cfg(feature = "provenance")]
+    #[serde(default)]
+    pub tx_log_indices: Vec<u64>,
     /// the msg.sender of the trace. This allows us to properly deal with
     /// delegate calls and the headache they cause when it comes to proxies
-    pub msg_sender:   Address,
-    pub trace_idx:    u64,
-    pub decoded_data: Option<DecodedCallData>,
+    pub msg_sender:     Address,
+    pub trace_idx:      u64,
+    pub decoded_data:   Option<DecodedCallData>,
 }
 
 impl TransactionTraceWithLogs {
@@ -226,6 +236,19 @@ pub struct TxTrace {
     pub tx_index:        u64,
     // False if the transaction reverted
     pub is_success:      bool,
+    /// Gas refunded at the end of execution, already deducted from `gas_used`
+    #[serde(default)]
+    pub gas_refunded:    u128,
+    /// Intrinsic gas charged for the warm addresses and storage slots of the
+    /// transaction's access list
+    #[serde(default)]
+    pub access_list_gas: u128,
+    /// Blob gas of the blobs carried by the transaction, see EIP-4844
+    #[serde(default)]
+    pub blob_gas_used:   u128,
+    /// Blob base fee of the block
+    #[serde(default)]
+    pub blob_gas_price:  u128,
 }
 
 impl TxTrace {
@@ -238,7 +261,19 @@ impl TxTrace {
         effective_price: u128,
         is_success: bool,
     ) -> Self {
-        Self { block_number, trace, tx_hash, tx_index, effective_price, gas_used, is_success }
+        Self {
+            block_number,
+            trace,
+            tx_hash,
+            tx_index,
+            effective_price,
+            gas_used,
+            is_success,
+            gas_refunded: 0,
+            access_list_gas: 0,
+            blob_gas_used: 0,
+            blob_gas_price: 0,
+        }
     }
 }
 
@@ -364,6 +399,7 @@ impl Serialize for TxTrace {
     }
 }
 
+#[cfg(feature = "native")]
 impl DbRow for TxTrace {
     const COLUMN_NAMES: &'static [&'static str] = &[
         "block_number",
@@ -43,6 +43,15 @@ pub trait TracingProvider: Send + Sync + 'static {
         block_id: BlockId,
     ) -> eyre::Result<Option<Vec<TxTrace>>>;
 
+    /// Executes the raw signed transactions in order on top of the state of
+    /// `parent`, as if they were the transactions of the block after it, and
+    /// traces them. The traces have the number of that block.
+    async fn simulate_transactions(
+        &self,
+        parent: BlockId,
+        txs: Vec<Bytes>,
+    ) -> eyre::Result<Vec<TxTrace>>;
+
     async fn block_receipts(
         &self,
         number: BlockNumberOrTag,
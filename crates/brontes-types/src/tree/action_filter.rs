@@ -0,0 +1,130 @@
+use std::ops::Not;
+
+use alloy_primitives::Address;
+
+use crate::tree::NormalizedAction;
+
+/// A predicate on the actions of a tree, built from the `Action::is_*` fns
+/// and combined with [`and`](ActionFilter::and), [`or`](ActionFilter::or) and
+/// `!`.
+///
+/// ```ignore
+/// // swaps and transfers that aren't sent by the searcher
+/// let filter = ActionFilter::any_of([Action::is_swap, Action::is_transfer])
+///     .and(!ActionFilter::from_address(searcher));
+///
+/// TreeSearchBuilder::default().with_filter(filter)
+/// ```
+#[derive(Debug, Clone)]
+pub enum ActionFilter<V: NormalizedAction> {
+    Action(fn(&V) -> bool),
+    FromAddress(Address),
+    ToAddress(Vec<Address>),
+    All(Vec<ActionFilter<V>>),
+    Any(Vec<ActionFilter<V>>),
+    Not(Box<ActionFilter<V>>),
+}
+
+impl<V: NormalizedAction> ActionFilter<V> {
+    pub fn action(action_fn: fn(&V) -> bool) -> Self {
+        Self::Action(action_fn)
+    }
+
+    /// Matches actions that match any of the given functions
+    pub fn any_of<const N: usize>(action_fns: [fn(&V) -> bool; N]) -> Self {
+        Self::Any(action_fns.into_iter().map(Self::Action).collect())
+    }
+
+    /// Matches actions that match all of the given functions
+    pub fn all_of<const N: usize>(action_fns: [fn(&V) -> bool; N]) -> Self {
+        Self::All(action_fns.into_iter().map(Self::Action).collect())
+    }
+
+    pub fn from_address(address: Address) -> Self {
+        Self::FromAddress(address)
+    }
+
+    /// Matches actions that are sent to any of the given addresses
+    pub fn to_address(addresses: Vec<Address>) -> Self {
+        Self::ToAddress(addresses)
+    }
+
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::All(mut filters) => {
+                filters.push(other);
+                Self::All(filters)
+            }
+            filter => Self::All(vec![filter, other]),
+        }
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::Any(mut filters) => {
+                filters.push(other);
+                Self::Any(filters)
+            }
+            filter => Self::Any(vec![filter, other]),
+        }
+    }
+
+    /// Evaluates the filter, short circuiting on the first filter that decides
+    /// the result
+    pub fn matches(&self, action: &V) -> bool {
+        match self {
+            Self::Action(action_fn) => action_fn(action),
+            Self::FromAddress(address) => action.get_action().get_from_address() == *address,
+            Self::ToAddress(addresses) => addresses.contains(&action.get_action().get_to_address()),
+            Self::All(filters) => filters.iter().all(|filter| filter.matches(action)),
+            Self::Any(filters) => filters.iter().any(|filter| filter.matches(action)),
+            Self::Not(filter) => !filter.matches(action),
+        }
+    }
+}
+
+impl<V: NormalizedAction> Not for ActionFilter<V> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Not(filter) => *filter,
+            filter => Self::Not(Box::new(filter)),
+        }
+    }
+}
+
+impl<V: NormalizedAction> From<fn(&V) -> bool> for ActionFilter<V> {
+    fn from(action_fn: fn(&V) -> bool) -> Self {
+        Self::Action(action_fn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::{Action, NormalizedSwap, NormalizedTransfer};
+
+    #[test]
+    fn combinators() {
+        let searcher = Address::with_last_byte(1);
+        let swap = Action::Swap(NormalizedSwap { from: searcher, ..Default::default() });
+        let transfer = Action::Transfer(NormalizedTransfer::default());
+
+        let filter = ActionFilter::any_of([Action::is_swap, Action::is_transfer]);
+        assert!(filter.matches(&swap) && filter.matches(&transfer));
+
+        let not_searcher = filter.and(!ActionFilter::from_address(searcher));
+        assert!(!not_searcher.matches(&swap));
+        assert!(not_searcher.matches(&transfer));
+
+        let transfer_or_searcher =
+            ActionFilter::action(Action::is_transfer).or(ActionFilter::from_address(searcher));
+        assert!(transfer_or_searcher.matches(&swap));
+        assert!(!ActionFilter::all_of([Action::is_swap, Action::is_transfer]).matches(&swap));
+        assert!(matches!(
+            !!ActionFilter::<Action>::from_address(searcher),
+            ActionFilter::FromAddress(_)
+        ));
+    }
+}
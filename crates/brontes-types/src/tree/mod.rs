@@ -1,11 +1,16 @@
 use std::{panic::AssertUnwindSafe, sync::Arc};
 
+use alloy_primitives::{Address, B256};
 use itertools::Itertools;
-use reth_primitives::{Header, B256};
+#[cfg(feature = "native")]
+use reth_primitives::Header;
 use statrs::statistics::Statistics;
 use tracing::{error, info, span, Level};
 
-use crate::{normalized_actions::MultiCallFrameClassification, tree::types::NodeWithDataRef};
+use crate::{
+    normalized_actions::MultiCallFrameClassification, order_flow::SubmissionChannels,
+    tree::types::NodeWithDataRef,
+};
 
 pub mod frontend_prunes;
 pub use frontend_prunes::*;
@@ -21,14 +26,29 @@ pub mod tx_info;
 pub use node::*;
 pub use root::*;
 pub use tx_info::*;
+pub mod action_filter;
+pub use action_filter::*;
 pub mod search_args;
 pub use search_args::*;
+pub mod token_flow;
+pub use token_flow::*;
+pub mod user_ops;
+pub use user_ops::*;
 
 use crate::{db::metadata::Metadata, normalized_actions::NormalizedAction};
 
 type SpansAll<V> = TreeIterator<V, std::vec::IntoIter<(B256, Vec<Vec<V>>)>>;
 type ClassifyData<V> = Option<(usize, Vec<MultiCallFrameClassification<V>>)>;
 
+/// The fields of the block header the tree reads, stands in for the reth
+/// header in builds without the `native` feature
+#[cfg(not(feature = "native"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Header {
+    pub number:           u64,
+    pub base_fee_per_gas: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockTree<V: NormalizedAction> {
     pub tx_roots:             Vec<Root<V>>,
@@ -108,6 +128,49 @@ impl<V: NormalizedAction> BlockTree<V> {
         self.tx_roots.iter().find(|r| r.tx_hash == tx_hash)
     }
 
+    /// The user operations of all transactions of the block, each as its own
+    /// pseudo-root
+    pub fn user_op_roots(&self) -> impl Iterator<Item = UserOpRoot<'_, V>> + '_ {
+        self.tx_roots.iter().flat_map(|root| root.user_op_roots())
+    }
+
+    /// The user operation the trace of the transaction is part of
+    pub fn get_user_op(&self, tx_hash: B256, trace_index: u64) -> Option<&UserOperation> {
+        self.get_root(tx_hash)?.get_user_op(trace_index)
+    }
+
+    /// The user operation of the sender in the transaction, as a pseudo-root
+    pub fn get_user_op_root(&self, tx_hash: B256, sender: Address) -> Option<UserOpRoot<'_, V>> {
+        self.get_root(tx_hash)?
+            .user_op_roots()
+            .find(|user_op| user_op.get_from_address() == sender)
+    }
+
+    /// If the actions of the transaction that were executed in a user
+    /// operation are all part of the same one, returns the info of that
+    /// operation in place of the info of the transaction, so the actions are
+    /// attributed to the operation's sender rather than to the bundler. Actions
+    /// outside of any operation, such as the compensation of the bundler, are
+    /// the EntryPoint's own and don't count.
+    pub fn attribute_to_user_op(&self, tx_info: TxInfo, actions: &[V]) -> TxInfo {
+        let Some(root) = self.get_root(tx_info.tx_hash) else { return tx_info };
+        if root.user_ops.is_empty() {
+            return tx_info
+        }
+
+        let mut user_ops = actions
+            .iter()
+            .filter_map(|action| root.get_user_op(action.get_trace_index()));
+        let Some(user_op) = user_ops.next() else { return tx_info };
+        if !user_ops.all(|other| other == user_op) {
+            return tx_info
+        }
+
+        UserOpRoot::new(root, user_op)
+            .map(|user_op| user_op.tx_info(&tx_info))
+            .unwrap_or(tx_info)
+    }
+
     pub fn get_gas_details(&self, hash: B256) -> Option<&GasDetails> {
         self.tx_roots
             .iter()
@@ -298,6 +361,12 @@ impl<V: NormalizedAction> BlockTree<V> {
             .for_each(|root| root.label_private_tx(metadata));
     }
 
+    pub fn label_submission_channels(&mut self, channels: &SubmissionChannels) {
+        self.tx_roots
+            .iter_mut()
+            .for_each(|root| root.label_submission_channel(channels));
+    }
+
     /// Uses search args to collect two types of nodes. Nodes that could be a
     /// parent to a child node that we want to remove. and child nodes we
     /// want to remove. These are both collected and passed to the classifiy
@@ -376,7 +445,7 @@ pub mod test {
 
     use alloy_primitives::hex;
     use brontes_classifier::test_utils::ClassifierTestUtils;
-    use brontes_types::{normalized_actions::Action, BlockTree, TreeSearchBuilder};
+    use brontes_types::{normalized_actions::Action, ActionFilter, BlockTree, TreeSearchBuilder};
 
     async fn load_tree() -> Arc<BlockTree<Action>> {
         let classifier_utils = ClassifierTestUtils::new().await;
@@ -417,6 +486,44 @@ pub mod test {
         assert_eq!(spans.len(), 4);
     }
 
+    #[brontes_macros::test]
+    async fn test_collect_with_filters() {
+        let tx = hex!("31dedbae6a8e44ec25f660b3cd0e04524c6476a0431ab610bb4096f82271831b").into();
+        let tree = load_tree().await;
+
+        let swaps_and_burns = tree
+            .clone()
+            .collect(
+                &tx,
+                TreeSearchBuilder::default()
+                    .with_filter(ActionFilter::any_of([Action::is_swap, Action::is_burn])),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(swaps_and_burns.len(), 4);
+
+        let not_swaps = tree
+            .clone()
+            .collect(
+                &tx,
+                TreeSearchBuilder::default().with_filter(
+                    ActionFilter::any_of([Action::is_swap, Action::is_burn])
+                        .and(!ActionFilter::action(Action::is_swap)),
+                ),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(not_swaps.len(), 1);
+
+        let spans = tree
+            .collect_spans(
+                tx,
+                TreeSearchBuilder::default()
+                    .child_nodes_match(ActionFilter::action(Action::is_transfer))
+                    .child_nodes_match(ActionFilter::action(Action::is_swap)),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(spans.len(), 4);
+    }
+
     #[brontes_macros::test]
     async fn test_collect_and_classify() {
         let classifier_utils = ClassifierTestUtils::new().await;
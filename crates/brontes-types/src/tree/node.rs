@@ -1,11 +1,11 @@
+use alloy_primitives::Address;
 use itertools::Itertools;
-use reth_primitives::Address;
 use tracing::error;
 
 use super::{types::NodeWithDataRef, NodeData};
 use crate::{
     normalized_actions::{MultiCallFrameClassification, NodeDataIndex, NormalizedAction},
-    TreeSearchArgs, TreeSearchBuilder,
+    SearchMemo, TreeSearchArgs, TreeSearchBuilder,
 };
 
 #[derive(Debug, Clone)]
@@ -84,6 +84,7 @@ impl Node {
                     )
                 },
                 nodes,
+                &mut SearchMemo::default(),
             );
 
             // should always be the first index
@@ -517,16 +518,20 @@ impl Node {
         result: &mut Vec<Vec<V>>,
         call: &TreeSearchBuilder<V>,
         data: &NodeData<V>,
+        memo: &mut SearchMemo,
     ) -> bool {
         // the previous sub-action was the last one to meet the criteria
-        if !call.generate_search_args(self, data).child_node_to_collect {
+        if !call
+            .generate_search_args_memoized(self, data, memo)
+            .child_node_to_collect
+        {
             return false
         }
 
         let lower_has_better_collect = self
             .inner
             .iter()
-            .map(|i| i.collect_spans(result, call, data))
+            .map(|i| i.collect_spans(result, call, data, memo))
             .collect::<Vec<bool>>();
 
         let lower_has_better = lower_has_better_collect.into_iter().all(|f| f);
@@ -556,11 +561,12 @@ impl Node {
         call: &TreeSearchBuilder<V>,
         wanted_data: &T,
         data: &NodeData<V>,
+        memo: &mut SearchMemo,
     ) where
         T: Fn(NodeWithDataRef<'_, V>) -> R,
     {
         let TreeSearchArgs { collect_current_node, child_node_to_collect, collect_idxs } =
-            call.generate_search_args(self, data);
+            call.generate_search_args_memoized(self, data, memo);
         if collect_current_node {
             if let Some(datas) = data.get_ref(self.data) {
                 for idx in collect_idxs {
@@ -572,7 +578,7 @@ impl Node {
         if child_node_to_collect {
             self.inner
                 .iter()
-                .for_each(|i| i.collect(results, call, wanted_data, data))
+                .for_each(|i| i.collect(results, call, wanted_data, data, memo))
         }
     }
 }
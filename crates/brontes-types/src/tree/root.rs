@@ -1,11 +1,12 @@
 use std::{fmt, fmt::Display};
 
-use alloy_primitives::TxHash;
+use alloy_primitives::{Address, TxHash, B256};
+#[cfg(feature = "native")]
 use clickhouse::Row;
 use colored::Colorize;
 use itertools::Itertools;
+#[cfg(feature = "rkyv")]
 use redefined::self_convert_redefined;
-use reth_primitives::{Address, B256};
 use serde::{Deserialize, Serialize};
 
 use super::Node;
@@ -17,8 +18,9 @@ use crate::{
     normalized_actions::{
         Action, MultiCallFrameClassification, NormalizedAction, NormalizedEthTransfer,
     },
-    tree::types::NodeWithDataRef,
-    FastHashMap, FastHashSet, TreeSearchBuilder, TxInfo,
+    order_flow::{SubmissionChannel, SubmissionChannels},
+    tree::{types::NodeWithDataRef, UserOpRoot, UserOperation},
+    FastHashMap, FastHashSet, SearchMemo, TreeSearchBuilder, TxInfo,
 };
 
 #[derive(Debug, Clone)]
@@ -55,11 +57,16 @@ pub struct Root<V: NormalizedAction> {
     pub position: usize,
     pub tx_hash: B256,
     pub private: bool,
+    /// How the transaction was submitted, `Public` unless a relay or the
+    /// mempool data says otherwise
+    pub submission_channel: SubmissionChannel,
     pub gas_details: GasDetails,
     /// all msg.value transfers that aren't classified as
     /// eth transfers
     pub total_msg_value_transfers: Vec<NormalizedEthTransfer>,
     pub data_store: NodeData<V>,
+    /// ERC-4337 user operations executed by the transaction
+    pub user_ops: Vec<UserOperation>,
 }
 
 impl<V: NormalizedAction> Root<V> {
@@ -180,6 +187,7 @@ impl<V: NormalizedAction> Root<V> {
                 is_classified,
                 is_cex_dex_call,
                 self.private,
+                self.submission_channel,
                 is_verified_contract,
                 searcher_eoa_info,
                 None,
@@ -198,6 +206,7 @@ impl<V: NormalizedAction> Root<V> {
             is_classified,
             is_cex_dex_call,
             self.private,
+            self.submission_channel,
             is_verified_contract,
             searcher_eoa_info,
             searcher_contract_info,
@@ -233,6 +242,18 @@ impl<V: NormalizedAction> Root<V> {
         self.data_store.get_ref(0).unwrap().first().unwrap()
     }
 
+    pub fn user_op_roots(&self) -> impl Iterator<Item = UserOpRoot<'_, V>> + '_ {
+        self.user_ops
+            .iter()
+            .filter_map(|user_op| UserOpRoot::new(self, user_op))
+    }
+
+    pub fn get_user_op(&self, trace_index: u64) -> Option<&UserOperation> {
+        self.user_ops
+            .iter()
+            .find(|user_op| user_op.contains_trace(trace_index))
+    }
+
     pub fn get_block_position(&self) -> usize {
         self.position
     }
@@ -243,7 +264,8 @@ impl<V: NormalizedAction> Root<V> {
 
     pub fn collect_spans(&self, call: &TreeSearchBuilder<V>) -> Vec<Vec<V>> {
         let mut result = Vec::new();
-        self.head.collect_spans(&mut result, call, &self.data_store);
+        self.head
+            .collect_spans(&mut result, call, &self.data_store, &mut SearchMemo::default());
 
         result
     }
@@ -258,8 +280,13 @@ impl<V: NormalizedAction> Root<V> {
 
     pub fn collect(&self, call: &TreeSearchBuilder<V>) -> Vec<V> {
         let mut result = Vec::new();
-        self.head
-            .collect(&mut result, call, &|data| data.data.clone(), &self.data_store);
+        self.head.collect(
+            &mut result,
+            call,
+            &|data| data.data.clone(),
+            &self.data_store,
+            &mut SearchMemo::default(),
+        );
 
         result.sort_by_key(|a| a.get_trace_index());
 
@@ -292,9 +319,36 @@ impl<V: NormalizedAction> Root<V> {
     pub fn label_private_tx(&mut self, metadata: &Metadata) {
         if metadata.private_flow.contains(&self.tx_hash) {
             self.private = true;
+            self.submission_channel = self.submission_channel.merge(SubmissionChannel::Private);
+        }
+    }
+
+    /// Sets the submission channel reported for the transaction by the relay
+    /// data
+    pub fn label_submission_channel(&mut self, channels: &SubmissionChannels) {
+        if let Some(channel) = channels.get(&self.tx_hash) {
+            self.submission_channel = self.submission_channel.merge(*channel);
+            self.private |= channel.is_private();
         }
     }
 
+    /// Provenance of the classified actions of the transaction, in trace
+    /// order. Only available with the `provenance` feature
+    #[cfg(feature = "provenance")]
+    pub fn classified_provenance(&self) -> Vec<crate::normalized_actions::ActionProvenance> {
+        self.data_store
+            .0
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|action| action.is_classified())
+            .filter_map(|action| action.get_action().provenance())
+            .unique_by(|provenance| provenance.trace_idx)
+            .sorted_by_key(|provenance| provenance.trace_idx)
+            .cloned()
+            .collect()
+    }
+
     pub fn remove_duplicate_data<C, T, R>(
         &mut self,
         find: &TreeSearchBuilder<V>,
@@ -325,24 +379,28 @@ impl<V: NormalizedAction> Root<V> {
     }
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Serialize,
-    Deserialize,
-    Row,
-    Default,
-    rkyv::Serialize,
-    rkyv::Deserialize,
-    rkyv::Archive,
-)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive))]
+#[cfg_attr(feature = "native", derive(Row))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes))]
 pub struct GasDetails {
     pub coinbase_transfer:   Option<u128>,
     pub priority_fee:        u128,
     pub gas_used:            u128,
     pub effective_gas_price: u128,
+    /// Gas refunded for clearing storage, already deducted from `gas_used`
+    #[serde(default)]
+    pub gas_refunded:        u128,
+    /// Intrinsic gas paid for the access list, included in `gas_used`
+    #[serde(default)]
+    pub access_list_gas:     u128,
+    /// Blob gas of the blobs carried by the transaction, see EIP-4844. Blob
+    /// gas is priced separately from execution gas
+    #[serde(default)]
+    pub blob_gas_used:       u128,
+    /// Blob base fee of the block, all of it is burnt
+    #[serde(default)]
+    pub blob_gas_price:      u128,
 }
 //TODO: Fix this
 impl Display for GasDetails {
@@ -350,17 +408,29 @@ impl Display for GasDetails {
         write!(
             f,
             "GasDetails {{ coinbase_transfer: {:?}, priority_fee: {}, gas_used: {}, \
-             effective_gas_price: {} }}",
-            self.coinbase_transfer, self.priority_fee, self.gas_used, self.effective_gas_price
+             effective_gas_price: {}, gas_refunded: {}, access_list_gas: {}, blob_gas_used: {}, \
+             blob_gas_price: {} }}",
+            self.coinbase_transfer,
+            self.priority_fee,
+            self.gas_used,
+            self.effective_gas_price,
+            self.gas_refunded,
+            self.access_list_gas,
+            self.blob_gas_used,
+            self.blob_gas_price
         )
     }
 }
 
+#[cfg(feature = "rkyv")]
 self_convert_redefined!(GasDetails);
 
 impl GasDetails {
+    /// Total paid by the transaction: execution and blob gas plus the
+    /// coinbase transfer. `gas_used` is the gas of the receipt, so the refund
+    /// is already netted out of it and the access list gas is part of it
     pub fn gas_paid(&self) -> u128 {
-        let mut gas = self.gas_used * self.effective_gas_price;
+        let mut gas = self.gas_used * self.effective_gas_price + self.blob_gas_cost();
 
         if let Some(coinbase) = self.coinbase_transfer {
             gas += coinbase
@@ -381,6 +451,26 @@ impl GasDetails {
         self.coinbase_transfer.unwrap_or_default()
     }
 
+    /// Gas the transaction would have used without the refund
+    pub fn gas_used_before_refund(&self) -> u128 {
+        self.gas_used + self.gas_refunded
+    }
+
+    /// Wei the transaction saved through the gas refund
+    pub fn refund_value(&self) -> u128 {
+        self.gas_refunded * self.effective_gas_price
+    }
+
+    /// Wei the transaction paid for its access list
+    pub fn access_list_cost(&self) -> u128 {
+        self.access_list_gas * self.effective_gas_price
+    }
+
+    /// Wei the transaction paid for its blobs
+    pub fn blob_gas_cost(&self) -> u128 {
+        self.blob_gas_used * self.blob_gas_price
+    }
+
     pub fn merge(&mut self, other: &GasDetails) {
         self.coinbase_transfer = Some(
             self.coinbase_transfer.unwrap_or_default()
@@ -391,6 +481,11 @@ impl GasDetails {
         self.priority_fee += other.priority_fee;
         self.gas_used += other.gas_used;
         self.effective_gas_price += other.effective_gas_price;
+        self.gas_refunded += other.gas_refunded;
+        self.access_list_gas += other.access_list_gas;
+        self.blob_gas_used += other.blob_gas_used;
+        // the blob base fee is the same for every tx of the block
+        self.blob_gas_price = self.blob_gas_price.max(other.blob_gas_price);
     }
 
     // Pretty print after 'spaces' spaces
@@ -406,6 +501,10 @@ impl GasDetails {
             ("Priority Fee", format!("{} Wei", self.priority_fee)),
             ("Gas Used", self.gas_used.to_string()),
             ("Effective Gas Price", format!("{} Wei", self.effective_gas_price)),
+            ("Gas Refunded", self.gas_refunded.to_string()),
+            ("Access List Gas", self.access_list_gas.to_string()),
+            ("Blob Gas Used", self.blob_gas_used.to_string()),
+            ("Blob Gas Price", format!("{} Wei", self.blob_gas_price)),
             ("Total Gas Paid in ETH", format!("{:.7} ETH", self.gas_paid() as f64 / 1e18)),
         ];
 
@@ -445,6 +544,10 @@ pub struct ClickhouseVecGasDetails {
     pub priority_fee:        Vec<u128>,
     pub gas_used:            Vec<u128>,
     pub effective_gas_price: Vec<u128>,
+    pub gas_refunded:        Vec<u128>,
+    pub access_list_gas:     Vec<u128>,
+    pub blob_gas_used:       Vec<u128>,
+    pub blob_gas_price:      Vec<u128>,
 }
 
 impl From<(Vec<TxHash>, Vec<GasDetails>)> for ClickhouseVecGasDetails {
@@ -460,6 +563,10 @@ impl From<(Vec<TxHash>, Vec<GasDetails>)> for ClickhouseVecGasDetails {
                     gas.priority_fee,
                     gas.gas_used,
                     gas.effective_gas_price,
+                    gas.gas_refunded,
+                    gas.access_list_gas,
+                    gas.blob_gas_used,
+                    gas.blob_gas_price,
                 )
             })
             .collect::<Vec<_>>();
@@ -470,6 +577,10 @@ impl From<(Vec<TxHash>, Vec<GasDetails>)> for ClickhouseVecGasDetails {
             priority_fee:        vec_vals.iter().map(|val| val.2.to_owned()).collect_vec(),
             gas_used:            vec_vals.iter().map(|val| val.3.to_owned()).collect_vec(),
             effective_gas_price: vec_vals.iter().map(|val| val.4.to_owned()).collect_vec(),
+            gas_refunded:        vec_vals.iter().map(|val| val.5.to_owned()).collect_vec(),
+            access_list_gas:     vec_vals.iter().map(|val| val.6.to_owned()).collect_vec(),
+            blob_gas_used:       vec_vals.iter().map(|val| val.7.to_owned()).collect_vec(),
+            blob_gas_price:      vec_vals.iter().map(|val| val.8.to_owned()).collect_vec(),
         }
     }
 }
@@ -488,6 +599,116 @@ pub enum FalsePositiveEntity {
     MaestroBots,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_paid_nets_the_refund_and_includes_the_access_list() {
+        // 124.3k gas before the refund, 4300 of it for an access list of one
+        // address with one slot, and a 24k refund for clearing storage
+        let gas_details = GasDetails {
+            gas_used: 100_300,
+            effective_gas_price: 10,
+            gas_refunded: 24_000,
+            access_list_gas: 4_300,
+            ..Default::default()
+        };
+
+        assert_eq!(gas_details.gas_used_before_refund(), 124_300);
+        assert_eq!(gas_details.refund_value(), 240_000);
+        assert_eq!(gas_details.access_list_cost(), 43_000);
+        assert_eq!(
+            gas_details.gas_paid(),
+            gas_details.gas_used_before_refund() * 10 - gas_details.refund_value()
+        );
+    }
+
+    #[test]
+    fn gas_paid_includes_the_blob_fee() {
+        // two blobs of 131072 blob gas each at a blob base fee of 3 wei, on top
+        // of 21k execution gas at 10 wei and a coinbase transfer
+        let gas_details = GasDetails {
+            coinbase_transfer: Some(1_000),
+            gas_used: 21_000,
+            effective_gas_price: 10,
+            blob_gas_used: 2 * 131_072,
+            blob_gas_price: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(gas_details.blob_gas_cost(), 786_432);
+        assert_eq!(gas_details.gas_paid(), 210_000 + 786_432 + 1_000);
+        // the blob fee is burnt, none of it is paid to the builder
+        assert_eq!(gas_details.priority_fee_paid(8), 42_000);
+    }
+
+    #[test]
+    fn merged_gas_details_keep_the_blob_base_fee() {
+        let blob_tx = |blobs| GasDetails {
+            gas_used: 21_000,
+            effective_gas_price: 10,
+            blob_gas_used: blobs * 131_072,
+            blob_gas_price: 3,
+            ..Default::default()
+        };
+
+        let mut merged = blob_tx(1);
+        merged.merge(&blob_tx(2));
+        merged.merge(&GasDetails {
+            gas_used: 50_000,
+            effective_gas_price: 10,
+            ..Default::default()
+        });
+
+        assert_eq!(merged.blob_gas_used, 3 * 131_072);
+        assert_eq!(merged.blob_gas_price, 3);
+        assert_eq!(merged.blob_gas_cost(), blob_tx(1).blob_gas_cost() + blob_tx(2).blob_gas_cost());
+    }
+
+    #[test]
+    fn gas_details_serialize_the_refund_access_list_and_blob_gas() {
+        let gas_details = GasDetails {
+            gas_refunded: 24_000,
+            access_list_gas: 4_300,
+            blob_gas_used: 131_072,
+            blob_gas_price: 3,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(gas_details).unwrap();
+        assert_eq!(json["gas_refunded"], 24_000);
+        assert_eq!(json["access_list_gas"], 4_300);
+        assert_eq!(json["blob_gas_used"], 131_072);
+        assert_eq!(json["blob_gas_price"], 3);
+        assert_eq!(serde_json::from_value::<GasDetails>(json).unwrap(), gas_details);
+    }
+
+    #[test]
+    fn gas_details_written_without_blob_gas_deserialize() {
+        let json = serde_json::json!({
+            "coinbase_transfer": null,
+            "priority_fee": 2,
+            "gas_used": 21_000,
+            "effective_gas_price": 10,
+        });
+
+        let gas_details = serde_json::from_value::<GasDetails>(json).unwrap();
+        assert_eq!(gas_details.blob_gas_used, 0);
+        assert_eq!(gas_details.gas_paid(), 210_000);
+    }
+
+    #[test]
+    fn clickhouse_gas_details_carry_the_blob_gas() {
+        let gas_details =
+            GasDetails { blob_gas_used: 131_072, blob_gas_price: 3, ..Default::default() };
+
+        let vec_details: ClickhouseVecGasDetails = (vec![TxHash::ZERO], vec![gas_details]).into();
+        assert_eq!(vec_details.blob_gas_used, vec![131_072]);
+        assert_eq!(vec_details.blob_gas_price, vec![3]);
+    }
+}
+
 /*
 #[cfg(test)]
 pub mod test {
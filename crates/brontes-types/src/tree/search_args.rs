@@ -1,6 +1,9 @@
 use alloy_primitives::Address;
 
-use crate::{tree::NormalizedAction, Node, NodeData};
+use crate::{
+    tree::{ActionFilter, NormalizedAction},
+    FastHashMap, Node, NodeData,
+};
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TreeSearchArgs {
@@ -12,11 +15,11 @@ pub struct TreeSearchArgs {
 #[derive(Debug, Clone)]
 pub struct TreeSearchBuilder<V: NormalizedAction> {
     /// these get or'd together
-    with_actions:         Vec<fn(&V) -> bool>,
+    with_actions:         Vec<ActionFilter<V>>,
     /// get or'd together with contains
-    child_node_have:      Vec<fn(&V) -> bool>,
+    child_node_have:      Vec<ActionFilter<V>>,
     /// gets and'd together
-    child_nodes_contains: Vec<fn(&V) -> bool>,
+    child_nodes_contains: Vec<ActionFilter<V>>,
     /// gets and'd together
     has_from_address:     Option<Address>,
     /// gets and'd together
@@ -43,7 +46,7 @@ impl<V: NormalizedAction> TreeSearchBuilder<V> {
     /// given function arg. if no child node search args are set. The search
     /// will use this action as the default.
     pub fn with_action(mut self, action_fn: fn(&V) -> bool) -> Self {
-        self.with_actions.push(action_fn);
+        self.with_actions.push(ActionFilter::Action(action_fn));
         self
     }
 
@@ -51,7 +54,15 @@ impl<V: NormalizedAction> TreeSearchBuilder<V> {
     /// function args passed in. If no child node search args are set. These
     /// action fn will be used to search for child nodes
     pub fn with_actions<const N: usize>(mut self, action_fns: [fn(&V) -> bool; N]) -> Self {
-        self.with_actions.extend(action_fns);
+        self.with_actions
+            .extend(action_fns.map(ActionFilter::Action));
+        self
+    }
+
+    /// Same as [`with_action`](Self::with_action) but for a combined filter.
+    /// Or'd together with the other collected actions.
+    pub fn with_filter(mut self, filter: ActionFilter<V>) -> Self {
+        self.with_actions.push(filter);
         self
     }
 
@@ -65,7 +76,7 @@ impl<V: NormalizedAction> TreeSearchBuilder<V> {
             return self
         }
 
-        self.child_node_have = action_fns.to_vec();
+        self.child_node_have = action_fns.map(ActionFilter::Action).to_vec();
         self
     }
 
@@ -78,7 +89,21 @@ impl<V: NormalizedAction> TreeSearchBuilder<V> {
             );
             return self
         }
-        self.child_nodes_contains = action_fns.to_vec();
+        self.child_nodes_contains = action_fns.map(ActionFilter::Action).to_vec();
+        self
+    }
+
+    /// When searching for child nodes, makes sure that at least one action
+    /// matches the filter. Can be called multiple times, every filter needs to
+    /// be matched.
+    pub fn child_nodes_match(mut self, filter: ActionFilter<V>) -> Self {
+        if !self.child_node_have.is_empty() {
+            tracing::error!(
+                "child nodes have already set, only one of contains, or have is allowed"
+            );
+            return self
+        }
+        self.child_nodes_contains.push(filter);
         self
     }
 
@@ -96,134 +121,164 @@ impl<V: NormalizedAction> TreeSearchBuilder<V> {
     }
 
     pub fn generate_search_args(&self, node: &Node, node_data: &NodeData<V>) -> TreeSearchArgs {
+        let (collect_current_node, collect_idxs) = self.collect_current_node(node, node_data);
+        let child_node_to_collect = self.child_node_to_collect(
+            &node
+                .get_all_sub_actions()
+                .iter()
+                .filter_map(|node| node_data.get_ref(*node))
+                .flatten()
+                .fold(self.empty_matches(), |mut matches, action| {
+                    self.add_action(&mut matches, action);
+                    matches
+                }),
+        );
+
+        TreeSearchArgs { collect_current_node, child_node_to_collect, collect_idxs }
+    }
+
+    /// Same as [`generate_search_args`](Self::generate_search_args) but
+    /// reuses the child node results of earlier calls. Each action of the
+    /// tree is only evaluated once, instead of once for every parent node.
+    /// The memo is only valid as long as the tree isn't modified.
+    pub fn generate_search_args_memoized(
+        &self,
+        node: &Node,
+        node_data: &NodeData<V>,
+        memo: &mut SearchMemo,
+    ) -> TreeSearchArgs {
         let (collect_current_node, collect_idxs) = self.collect_current_node(node, node_data);
         let child_node_to_collect =
-            if self.child_nodes_contains.is_empty() && self.child_node_have.is_empty() {
-                self.has_child_nodes_default(node, node_data)
-            } else {
-                self.has_child_nodes(node, node_data)
-            };
+            self.child_node_to_collect(self.subtree_matches(node, node_data, memo));
 
         TreeSearchArgs { collect_current_node, child_node_to_collect, collect_idxs }
     }
 
     fn collect_current_node(&self, node: &Node, node_data: &NodeData<V>) -> (bool, Vec<usize>) {
-        node_data
+        let collect_idxs = node_data
             .get_ref(node.data)
             .map(|node_actions| {
-                let (is_good, idxs): (Vec<_>, Vec<_>) = node_actions
+                node_actions
                     .iter()
                     .enumerate()
-                    .map(|(idx, node_action)| {
-                        let is_true = self
-                            .with_actions
-                            .iter()
-                            .map(|ptr| {
-                                ptr(node_action)
-                                    && self
-                                        .has_from_address
-                                        .map(|addr| {
-                                            node_action.get_action().get_from_address() == addr
-                                        })
-                                        .unwrap_or(true)
-                                    && self
-                                        .has_to_address
-                                        .as_ref()
-                                        .map(|addrs| {
-                                            addrs.contains(
-                                                &node_action.get_action().get_to_address(),
-                                            )
-                                        })
-                                        .unwrap_or(true)
-                            })
-                            .reduce(|a, b| a | b)
-                            .unwrap_or(false);
-
-                        if is_true {
-                            (true, idx)
-                        } else {
-                            // rand number but shoudl be big enough
-                            (false, 694200)
-                        }
-                    })
-                    .unzip();
-                (
-                    is_good.iter().any(|f| *f),
-                    idxs.into_iter()
-                        .filter(|idx| *idx != 694200)
-                        .collect::<Vec<_>>(),
-                )
+                    .filter(|(_, action)| self.is_wanted(action))
+                    .map(|(idx, _)| idx)
+                    .collect::<Vec<_>>()
             })
-            .unwrap_or_default()
+            .unwrap_or_default();
+
+        (!collect_idxs.is_empty(), collect_idxs)
     }
 
-    fn has_child_nodes_default(&self, node: &Node, node_data: &NodeData<V>) -> bool {
-        node.get_all_sub_actions()
-            .iter()
-            .filter_map(|node| node_data.get_ref(*node))
-            .flatten()
-            .any(|action| {
-                self.with_actions
-                    .iter()
-                    .map(|ptr| {
-                        ptr(action)
-                            && self
-                                .has_from_address
-                                .map(|addr| action.get_action().get_from_address() == addr)
-                                .unwrap_or(true)
-                            && self
-                                .has_to_address
-                                .as_ref()
-                                .map(|addrs| addrs.contains(&action.get_action().get_to_address()))
-                                .unwrap_or(true)
-                    })
-                    .reduce(|a, b| a | b)
-                    .unwrap_or(false)
-            })
+    fn subtree_matches<'a>(
+        &self,
+        node: &Node,
+        node_data: &NodeData<V>,
+        memo: &'a mut SearchMemo,
+    ) -> &'a SubtreeMatches {
+        if !memo.subtrees.contains_key(&node.data) {
+            let mut matches = self.empty_matches();
+            node_data
+                .get_ref(node.data)
+                .into_iter()
+                .flatten()
+                .for_each(|action| self.add_action(&mut matches, action));
+
+            for inner in &node.inner {
+                matches.merge(self.subtree_matches(inner, node_data, memo));
+            }
+
+            memo.subtrees.insert(node.data, matches);
+        }
+
+        &memo.subtrees[&node.data]
+    }
+
+    fn empty_matches(&self) -> SubtreeMatches {
+        SubtreeMatches {
+            wanted:   false,
+            have:     false,
+            contains: vec![false; self.child_nodes_contains.len()],
+        }
     }
 
-    fn has_child_nodes(&self, node: &Node, node_data: &NodeData<V>) -> bool {
-        let mut all = Vec::new();
-        all.resize(self.child_nodes_contains.len(), false);
-        let mut have_any = false;
+    fn add_action(&self, matches: &mut SubtreeMatches, action: &V) {
+        if self.child_nodes_contains.is_empty() && self.child_node_have.is_empty() {
+            matches.wanted = matches.wanted || self.is_wanted(action);
+            return
+        }
 
-        node.get_all_sub_actions()
-            .iter()
-            .filter_map(|node| node_data.get_ref(*node))
-            .flatten()
-            .for_each(|action| {
-                // for have, its a or with the result
-                have_any |= self
-                    .child_node_have
-                    .iter()
-                    .map(|ptr| {
-                        ptr(action)
-                            && self
-                                .has_from_address
-                                .map(|addr| action.get_action().get_from_address() == addr)
-                                .unwrap_or(true)
-                            && self
-                                .has_to_address
-                                .as_ref()
-                                .map(|addrs| addrs.contains(&action.get_action().get_to_address()))
-                                .unwrap_or(true)
-                    })
-                    .reduce(|a, b| a | b)
-                    .unwrap_or_default();
-
-                self.child_nodes_contains
-                    .iter()
-                    .enumerate()
-                    .for_each(|(i, ptr)| {
-                        all[i] |= ptr(action);
-                    });
-            });
+        // for have, its a or with the result
+        matches.have = matches.have
+            || self
+                .child_node_have
+                .iter()
+                .any(|filter| filter.matches(action))
+                && self.address_matches(action);
 
-        // allows us to & these together
-        let all = if all.is_empty() { true } else { all.iter().all(|a| *a) };
+        matches
+            .contains
+            .iter_mut()
+            .zip(&self.child_nodes_contains)
+            .filter(|(found, _)| !**found)
+            .for_each(|(found, filter)| *found = filter.matches(action));
+    }
+
+    fn child_node_to_collect(&self, matches: &SubtreeMatches) -> bool {
+        if self.child_nodes_contains.is_empty() && self.child_node_have.is_empty() {
+            return matches.wanted
+        }
 
-        let has_any = if self.child_node_have.is_empty() { true } else { have_any };
+        // allows us to & these together
+        let all = matches.contains.iter().all(|found| *found);
+        let has_any = self.child_node_have.is_empty() || matches.have;
 
         all & has_any
     }
+
+    fn is_wanted(&self, action: &V) -> bool {
+        self.with_actions
+            .iter()
+            .any(|filter| filter.matches(action))
+            && self.address_matches(action)
+    }
+
+    fn address_matches(&self, action: &V) -> bool {
+        self.has_from_address
+            .map(|addr| action.get_action().get_from_address() == addr)
+            .unwrap_or(true)
+            && self
+                .has_to_address
+                .as_ref()
+                .map(|addrs| addrs.contains(&action.get_action().get_to_address()))
+                .unwrap_or(true)
+    }
+}
+
+/// Child node results of a search, keyed by the data index of the node. Lets
+/// a search that walks down the tree check the child nodes of each node
+/// without going over the same actions again.
+#[derive(Debug, Default)]
+pub struct SearchMemo {
+    subtrees: FastHashMap<usize, SubtreeMatches>,
+}
+
+/// Which of the search filters are matched by the actions of a node and its
+/// child nodes
+#[derive(Debug, Clone)]
+struct SubtreeMatches {
+    wanted:   bool,
+    have:     bool,
+    contains: Vec<bool>,
+}
+
+impl SubtreeMatches {
+    fn merge(&mut self, other: &Self) {
+        self.wanted |= other.wanted;
+        self.have |= other.have;
+        self.contains
+            .iter_mut()
+            .zip(&other.contains)
+            .for_each(|(found, other)| *found |= *other);
+    }
 }
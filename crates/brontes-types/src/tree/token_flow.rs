@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use alloy_primitives::{Address, B256};
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::BlockTree;
+use crate::{
+    constants::ETH_ADDRESS,
+    normalized_actions::{Action, NormalizedSwap},
+    FastHashSet, ToFloatNearest, ToScaledRational, TreeSearchBuilder,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TokenFlowKind {
+    Transfer,
+    EthTransfer,
+    /// The tokens sent into or received from a pool by a swap
+    Swap,
+}
+
+/// A movement of tokens between two addresses
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenFlowEdge {
+    pub tx_hash:     B256,
+    pub tx_index:    usize,
+    pub trace_index: u64,
+    pub from:        Address,
+    pub to:          Address,
+    pub token:       Address,
+    pub symbol:      String,
+    pub amount:      f64,
+    pub kind:        TokenFlowKind,
+}
+
+/// The token flows of a block, with the addresses that sent or received
+/// tokens as nodes and the classified transfers and swaps as edges. The token
+/// transfers that settle a swap are already represented by the swap's edges
+/// and are therefore left out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenFlowGraph {
+    pub block_number: u64,
+    pub nodes:        Vec<Address>,
+    pub edges:        Vec<TokenFlowEdge>,
+}
+
+impl TokenFlowGraph {
+    pub fn from_tree(tree: &BlockTree<Action>) -> Self {
+        let search = TreeSearchBuilder::default().with_actions([
+            Action::is_swap,
+            Action::is_transfer,
+            Action::is_eth_transfer,
+        ]);
+
+        let edges = tree
+            .tx_roots
+            .iter()
+            .flat_map(|root| {
+                let actions = root.collect(&search);
+                let swaps = actions
+                    .iter()
+                    .filter_map(|action| match action {
+                        Action::Swap(swap) => Some(swap),
+                        Action::SwapWithFee(swap) => Some(&swap.swap),
+                        _ => None,
+                    })
+                    .flat_map(|swap| swap_edges(root.tx_hash, root.position, swap))
+                    .collect_vec();
+
+                let transfer_edges = actions
+                    .iter()
+                    .filter_map(|action| match action {
+                        Action::Transfer(transfer) => Some(TokenFlowEdge {
+                            tx_hash:     root.tx_hash,
+                            tx_index:    root.position,
+                            trace_index: transfer.trace_index,
+                            from:        transfer.from,
+                            to:          transfer.to,
+                            token:       transfer.token.address,
+                            symbol:      transfer.token.symbol.clone(),
+                            amount:      transfer.amount.clone().to_float(),
+                            kind:        TokenFlowKind::Transfer,
+                        }),
+                        Action::EthTransfer(transfer) => Some(TokenFlowEdge {
+                            tx_hash:     root.tx_hash,
+                            tx_index:    root.position,
+                            trace_index: transfer.trace_index,
+                            from:        transfer.from,
+                            to:          transfer.to,
+                            token:       ETH_ADDRESS,
+                            symbol:      "ETH".to_string(),
+                            amount:      transfer.value.to_scaled_rational(18).to_float(),
+                            kind:        TokenFlowKind::EthTransfer,
+                        }),
+                        _ => None,
+                    })
+                    .filter(|transfer| {
+                        !swaps.iter().any(|swap| {
+                            swap.from == transfer.from
+                                && swap.to == transfer.to
+                                && swap.token == transfer.token
+                                && swap.amount == transfer.amount
+                        })
+                    })
+                    .collect_vec();
+
+                swaps.into_iter().chain(transfer_edges)
+            })
+            .collect_vec();
+
+        let nodes = edges
+            .iter()
+            .flat_map(|edge| [edge.from, edge.to])
+            .collect::<FastHashSet<_>>()
+            .into_iter()
+            .sorted()
+            .collect();
+
+        Self { block_number: tree.header.number, nodes, edges }
+    }
+
+    /// Renders the graph in the graphviz dot format
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph block_{} {{\n", self.block_number);
+
+        for node in &self.nodes {
+            let _ = writeln!(dot, "    \"{node:?}\";");
+        }
+
+        for edge in &self.edges {
+            let style = match edge.kind {
+                TokenFlowKind::Transfer => "solid",
+                TokenFlowKind::EthTransfer => "dashed",
+                TokenFlowKind::Swap => "bold",
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{:?}\" -> \"{:?}\" [label=\"{} {}\\ntx {}\", style={}];",
+                edge.from, edge.to, edge.amount, edge.symbol, edge.tx_index, style
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The swap as the token in flowing into the pool and the token out flowing
+/// to the recipient
+fn swap_edges(tx_hash: B256, tx_index: usize, swap: &NormalizedSwap) -> [TokenFlowEdge; 2] {
+    [
+        TokenFlowEdge {
+            tx_hash,
+            tx_index,
+            trace_index: swap.trace_index,
+            from: swap.from,
+            to: swap.pool,
+            token: swap.token_in.address,
+            symbol: swap.token_in.symbol.clone(),
+            amount: swap.amount_in.clone().to_float(),
+            kind: TokenFlowKind::Swap,
+        },
+        TokenFlowEdge {
+            tx_hash,
+            tx_index,
+            trace_index: swap.trace_index,
+            from: swap.pool,
+            to: swap.recipient,
+            token: swap.token_out.address,
+            symbol: swap.token_out.symbol.clone(),
+            amount: swap.amount_out.clone().to_float(),
+            kind: TokenFlowKind::Swap,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use alloy_primitives::hex;
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        normalized_actions::Action,
+        tree::token_flow::{TokenFlowGraph, TokenFlowKind},
+        BlockTree, TreeSearchBuilder,
+    };
+
+    #[brontes_macros::test]
+    async fn test_token_flow_graph() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let tx = hex!("31dedbae6a8e44ec25f660b3cd0e04524c6476a0431ab610bb4096f82271831b").into();
+        let tree: Arc<BlockTree<Action>> = classifier_utils.build_tree_tx(tx).await.unwrap().into();
+
+        let swaps = tree
+            .clone()
+            .collect(&tx, TreeSearchBuilder::default().with_action(Action::is_swap))
+            .collect::<Vec<_>>();
+        let graph = TokenFlowGraph::from_tree(&tree);
+        assert_eq!(graph.block_number, tree.header.number);
+
+        // every swap is a flow into its pool and one out of it
+        let swap_edges = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == TokenFlowKind::Swap)
+            .collect::<Vec<_>>();
+        assert_eq!(swap_edges.len(), swaps.len() * 2);
+        assert!(graph.edges.iter().all(|edge| edge.tx_hash == tx));
+
+        // the transfers settling the swaps aren't repeated as transfer edges
+        for edge in graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind != TokenFlowKind::Swap)
+        {
+            assert!(!swap_edges.iter().any(|swap| swap.from == edge.from
+                && swap.to == edge.to
+                && swap.token == edge.token
+                && swap.amount == edge.amount));
+        }
+
+        // the nodes are the sorted, distinct endpoints of the edges
+        let mut endpoints = graph
+            .edges
+            .iter()
+            .flat_map(|edge| [edge.from, edge.to])
+            .collect::<Vec<_>>();
+        endpoints.sort();
+        endpoints.dedup();
+        assert_eq!(graph.nodes, endpoints);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with(&format!("digraph block_{} {{", tree.header.number)));
+        assert_eq!(dot.matches(" -> ").count(), graph.edges.len());
+        assert_eq!(dot.matches("style=bold").count(), swap_edges.len());
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     db::{address_metadata::ContractType, searcher::SearcherInfo},
     mev::MevType,
     normalized_actions::NormalizedEthTransfer,
+    order_flow::SubmissionChannel,
     FastHashSet, GasDetails,
 };
 
@@ -22,10 +23,15 @@ pub struct TxInfo {
     pub is_classified:          bool,
     pub is_cex_dex_call:        bool,
     pub is_private:             bool,
+    pub submission_channel:     SubmissionChannel,
     pub is_verified_contract:   bool,
     pub searcher_eoa_info:      Option<SearcherInfo>,
     pub searcher_contract_info: Option<SearcherInfo>,
     pub total_eth_value:        Vec<NormalizedEthTransfer>,
+    /// Gas the user operation paid the bundler, if this is the info of a
+    /// user operation. `gas_details` stays the gas of the transaction, which
+    /// is what the builder was paid
+    pub user_op_gas:            Option<GasDetails>,
 }
 
 impl TxInfo {
@@ -41,6 +47,7 @@ impl TxInfo {
         is_classified: bool,
         is_cex_dex_call: bool,
         is_private: bool,
+        submission_channel: SubmissionChannel,
         is_verified_contract: bool,
         searcher_eoa_info: Option<SearcherInfo>,
         searcher_contract_info: Option<SearcherInfo>,
@@ -58,9 +65,11 @@ impl TxInfo {
             is_classified,
             is_cex_dex_call,
             is_private,
+            submission_channel,
             is_verified_contract,
             searcher_eoa_info,
             searcher_contract_info,
+            user_op_gas: None,
         }
     }
 
@@ -104,6 +113,11 @@ impl TxInfo {
         }
     }
 
+    /// See [`SearcherInfo::linked_eoa_group`]
+    pub fn linked_eoa_group(&self) -> Option<Address> {
+        self.searcher_eoa_info.as_ref()?.linked_eoa_group(self.eoa)
+    }
+
     pub fn is_searcher_of_type(&self, mev_type: MevType) -> bool {
         self.searcher_eoa_info
             .as_ref()
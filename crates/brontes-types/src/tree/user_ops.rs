@@ -0,0 +1,399 @@
+//! ERC-4337 user operations. A bundler submits the operations of many users in
+//! a single `handleOps` call to the EntryPoint, which executes each of them in
+//! its own `innerHandleOp` self call. The frame of that call is used as a
+//! pseudo-root of the operation, so the actions, gas and value flows of an
+//! operation can be attributed to its sender instead of to the bundler's
+//! transaction as a whole.
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::SolEvent;
+use serde::{Deserialize, Serialize};
+
+use super::{Node, Root};
+use crate::{
+    constants::{ENTRY_POINT_V06_ADDRESS, ENTRY_POINT_V07_ADDRESS},
+    normalized_actions::NormalizedAction,
+    structured_trace::{TraceActions, TransactionTraceWithLogs},
+    GasDetails, SearchMemo, TreeSearchBuilder, TxInfo,
+};
+
+alloy_sol_macro::sol!(
+    event UserOperationEvent(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        address indexed paymaster,
+        uint256 nonce,
+        bool success,
+        uint256 actualGasCost,
+        uint256 actualGasUsed
+    );
+);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserOperation {
+    pub entry_point:      Address,
+    pub user_op_hash:     B256,
+    /// The account the operation is executed for
+    pub sender:           Address,
+    pub paymaster:        Option<Address>,
+    pub nonce:            U256,
+    /// Whether the execution of the operation succeeded
+    pub success:          bool,
+    /// Wei the sender or paymaster paid the bundler for the operation
+    pub actual_gas_cost:  u128,
+    pub actual_gas_used:  u128,
+    /// Trace index of the `innerHandleOp` frame the operation is executed in
+    pub trace_index:      u64,
+    /// Trace index of the last frame nested in the operation
+    pub last_trace_index: u64,
+}
+
+impl UserOperation {
+    /// Finds the user operations executed in the transaction
+    pub fn from_traces(traces: &[TransactionTraceWithLogs]) -> Vec<Self> {
+        let events = traces
+            .iter()
+            .flat_map(|trace| &trace.logs)
+            .filter(|log| is_entry_point(log.address))
+            .filter_map(|log| {
+                let event = UserOperationEvent::decode_log_data(&log.data, false).ok()?;
+                Some((log.address, event))
+            })
+            .collect::<Vec<_>>();
+
+        if events.is_empty() {
+            return vec![]
+        }
+
+        traces
+            .iter()
+            .filter(|trace| {
+                let entry_point = trace.get_to_address();
+                is_entry_point(entry_point) && trace.get_from_addr() == entry_point
+            })
+            .filter_map(|trace| {
+                let entry_point = trace.get_to_address();
+                let user_op_hash = inner_handle_op_hash(entry_point, &trace.get_calldata())?;
+                let (_, event) = events.iter().find(|(address, event)| {
+                    *address == entry_point && event.userOpHash == user_op_hash
+                })?;
+
+                let trace_address = &trace.trace.trace_address;
+                let last_trace_index = traces
+                    .iter()
+                    .filter(|inner| inner.trace.trace_address.starts_with(trace_address))
+                    .map(|inner| inner.trace_idx)
+                    .max()
+                    .unwrap_or(trace.trace_idx);
+
+                Some(Self {
+                    entry_point,
+                    user_op_hash,
+                    sender: event.sender,
+                    paymaster: (event.paymaster != Address::ZERO).then_some(event.paymaster),
+                    nonce: event.nonce,
+                    success: event.success,
+                    actual_gas_cost: event.actualGasCost.saturating_to(),
+                    actual_gas_used: event.actualGasUsed.saturating_to(),
+                    trace_index: trace.trace_idx,
+                    last_trace_index,
+                })
+            })
+            .collect()
+    }
+
+    pub fn contains_trace(&self, trace_index: u64) -> bool {
+        (self.trace_index..=self.last_trace_index).contains(&trace_index)
+    }
+
+    /// The gas of the operation, paid by the sender or paymaster to the
+    /// bundler rather than to the block builder
+    pub fn gas_details(&self) -> GasDetails {
+        GasDetails {
+            gas_used: self.actual_gas_used,
+            effective_gas_price: self
+                .actual_gas_cost
+                .checked_div(self.actual_gas_used)
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn is_entry_point(address: Address) -> bool {
+    address == ENTRY_POINT_V06_ADDRESS || address == ENTRY_POINT_V07_ADDRESS
+}
+
+/// Reads the user operation hash out of the calldata of an `innerHandleOp`
+/// call. The operation info is a static tuple, so it is encoded in place after
+/// the offset of the call data, with the hash following the fields of the
+/// memory user operation. That struct has 8 fields in v0.6 and 10 in v0.7.
+fn inner_handle_op_hash(entry_point: Address, calldata: &[u8]) -> Option<B256> {
+    let mem_user_op_fields = if entry_point == ENTRY_POINT_V06_ADDRESS { 8 } else { 10 };
+    let offset = 4 + 32 * (1 + mem_user_op_fields);
+
+    calldata.get(offset..offset + 32).map(B256::from_slice)
+}
+
+/// A user operation together with the frame it is executed in. Treated as
+/// its own transaction, with the sender as the eoa.
+#[derive(Debug, Clone, Copy)]
+pub struct UserOpRoot<'a, V: NormalizedAction> {
+    pub user_op: &'a UserOperation,
+    pub head:    &'a Node,
+    pub root:    &'a Root<V>,
+}
+
+impl<'a, V: NormalizedAction> UserOpRoot<'a, V> {
+    pub fn new(root: &'a Root<V>, user_op: &'a UserOperation) -> Option<Self> {
+        let head = find_node(&root.head, user_op.trace_index)?;
+        Some(Self { user_op, head, root })
+    }
+
+    pub fn get_from_address(&self) -> Address {
+        self.user_op.sender
+    }
+
+    /// The bundler that submitted the operation
+    pub fn bundler(&self) -> Address {
+        self.root.get_from_address()
+    }
+
+    pub fn collect(&self, call: &TreeSearchBuilder<V>) -> Vec<V> {
+        let mut result = Vec::new();
+        self.head.collect(
+            &mut result,
+            call,
+            &|data| data.data.clone(),
+            &self.root.data_store,
+            &mut SearchMemo::default(),
+        );
+
+        result.sort_by_key(|a| a.get_trace_index());
+
+        result
+    }
+
+    pub fn collect_spans(&self, call: &TreeSearchBuilder<V>) -> Vec<Vec<V>> {
+        let mut result = Vec::new();
+        self.head.collect_spans(
+            &mut result,
+            call,
+            &self.root.data_store,
+            &mut SearchMemo::default(),
+        );
+
+        result
+    }
+
+    /// The gas the operation paid the bundler
+    pub fn gas_details(&self) -> GasDetails {
+        self.user_op.gas_details()
+    }
+
+    /// Info of the operation, derived from the info of its transaction. The
+    /// sender takes the place of the eoa. The gas details stay the ones of the
+    /// transaction, as the builder is paid by the bundler, and the gas of the
+    /// operation is kept next to them. The sender is a smart account and not a
+    /// searcher contract, so there is no mev contract.
+    pub fn tx_info(&self, tx_info: &TxInfo) -> TxInfo {
+        TxInfo {
+            eoa: self.user_op.sender,
+            mev_contract: None,
+            contract_type: None,
+            user_op_gas: Some(self.gas_details()),
+            is_verified_contract: false,
+            searcher_eoa_info: None,
+            searcher_contract_info: None,
+            total_eth_value: tx_info
+                .total_eth_value
+                .iter()
+                .filter(|transfer| self.user_op.contains_trace(transfer.trace_index))
+                .cloned()
+                .collect(),
+            ..tx_info.clone()
+        }
+    }
+}
+
+/// Trace indexes are assigned depth first, so the node is in the subtree of
+/// the last child that doesn't come after it
+fn find_node(node: &Node, index: u64) -> Option<&Node> {
+    if node.index == index {
+        return Some(node)
+    }
+
+    let child = node
+        .inner
+        .iter()
+        .take_while(|child| child.index <= index)
+        .last()?;
+
+    find_node(child, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Log;
+    use alloy_rpc_types_trace::parity::{Action, CallAction, CallType, TransactionTrace};
+
+    use super::*;
+    use crate::{normalized_actions::NormalizedEthTransfer, BlockTree, NodeData};
+
+    fn call(
+        trace_idx: u64,
+        trace_address: Vec<usize>,
+        from: Address,
+        to: Address,
+        input: Vec<u8>,
+        logs: Vec<Log>,
+    ) -> TransactionTraceWithLogs {
+        TransactionTraceWithLogs {
+            trace: TransactionTrace {
+                action: Action::Call(CallAction {
+                    from,
+                    to,
+                    input: input.into(),
+                    call_type: CallType::Call,
+                    gas: Default::default(),
+                    value: Default::default(),
+                }),
+                error: None,
+                result: None,
+                subtraces: 0,
+                trace_address,
+            },
+            logs,
+            #[cfg(feature = "provenance")]
+            tx_log_indices: vec![],
+            msg_sender: from,
+            trace_idx,
+            decoded_data: None,
+        }
+    }
+
+    #[test]
+    fn user_ops_from_handle_ops() {
+        let bundler = Address::with_last_byte(1);
+        let sender = Address::with_last_byte(2);
+        let user_op_hash = B256::with_last_byte(3);
+        let entry_point = ENTRY_POINT_V07_ADDRESS;
+
+        let mut inner_input = vec![0u8; 4 + 32 * 12];
+        inner_input[4 + 32 * 11..4 + 32 * 12].copy_from_slice(user_op_hash.as_slice());
+
+        let event = UserOperationEvent {
+            userOpHash: user_op_hash,
+            sender,
+            paymaster: Address::ZERO,
+            nonce: U256::from(7),
+            success: true,
+            actualGasCost: U256::from(2_000),
+            actualGasUsed: U256::from(100),
+        };
+        let log = Log { address: entry_point, data: event.encode_log_data() };
+
+        let traces = vec![
+            call(0, vec![], bundler, entry_point, vec![], vec![]),
+            call(1, vec![0], entry_point, sender, vec![], vec![]),
+            call(2, vec![1], entry_point, entry_point, inner_input, vec![log]),
+            call(3, vec![1, 0], entry_point, sender, vec![], vec![]),
+            call(4, vec![1, 0, 0], sender, Address::with_last_byte(9), vec![], vec![]),
+            call(5, vec![2], entry_point, bundler, vec![], vec![]),
+        ];
+
+        let user_ops = UserOperation::from_traces(&traces);
+        assert_eq!(user_ops.len(), 1);
+
+        let user_op = &user_ops[0];
+        assert_eq!(user_op.sender, sender);
+        assert_eq!(user_op.paymaster, None);
+        assert_eq!((user_op.trace_index, user_op.last_trace_index), (2, 4));
+        assert!(user_op.contains_trace(4) && !user_op.contains_trace(5));
+        assert_eq!(user_op.gas_details().effective_gas_price, 20);
+    }
+
+    #[test]
+    fn user_op_info_keeps_the_builder_payment() {
+        let [bundler, sender, beneficiary] = [1, 2, 3].map(Address::with_last_byte);
+        let entry_point = ENTRY_POINT_V07_ADDRESS;
+        let tx_hash = B256::with_last_byte(4);
+        let tx_gas = GasDetails { gas_used: 50_000, effective_gas_price: 10, ..Default::default() };
+
+        let mut head = Node::new(0, bundler, vec![]);
+        let mut inner_handle_op = Node::new(1, entry_point, vec![0]);
+        inner_handle_op
+            .inner
+            .push(Node::new(2, entry_point, vec![0, 0]));
+        head.inner.push(inner_handle_op);
+        head.inner.push(Node::new(3, entry_point, vec![1]));
+
+        let user_op = UserOperation {
+            entry_point,
+            user_op_hash: B256::with_last_byte(5),
+            sender,
+            paymaster: None,
+            nonce: U256::ZERO,
+            success: true,
+            actual_gas_cost: 2_000,
+            actual_gas_used: 100,
+            trace_index: 1,
+            last_trace_index: 2,
+        };
+        let mut tree = BlockTree::new(Default::default(), 1);
+        tree.tx_roots.push(Root {
+            head,
+            position: 0,
+            tx_hash,
+            private: false,
+            submission_channel: Default::default(),
+            gas_details: tx_gas,
+            total_msg_value_transfers: vec![],
+            data_store: NodeData(vec![]),
+            user_ops: vec![user_op],
+        });
+
+        let transfer = |trace_index, from, to| -> crate::normalized_actions::Action {
+            NormalizedEthTransfer {
+                trace_index,
+                from,
+                to,
+                value: U256::from(1),
+                ..Default::default()
+            }
+            .into()
+        };
+        let tx_info = TxInfo::new(
+            0,
+            0,
+            bundler,
+            None,
+            None,
+            tx_hash,
+            tx_gas,
+            false,
+            false,
+            false,
+            Default::default(),
+            false,
+            None,
+            None,
+            vec![],
+        );
+
+        // the bundler's compensation is paid outside of the operation
+        let info = tree.attribute_to_user_op(
+            tx_info.clone(),
+            &[
+                transfer(2, sender, Address::with_last_byte(9)),
+                transfer(3, entry_point, beneficiary),
+            ],
+        );
+        assert_eq!(info.eoa, sender);
+        assert_eq!(info.gas_details, tx_gas);
+        assert_eq!(info.user_op_gas.map(|gas| gas.effective_gas_price), Some(20));
+
+        let info = tree.attribute_to_user_op(tx_info, &[transfer(3, entry_point, beneficiary)]);
+        assert_eq!(info.eoa, bundler);
+        assert_eq!(info.user_op_gas, None);
+    }
+}
@@ -11,7 +11,6 @@ use malachite_q::arithmetic::traits::Approximate;
 use crate::{
     display::utils::display_sandwich,
     normalized_actions::{NormalizedBurn, NormalizedLiquidation, NormalizedMint, NormalizedSwap},
-    serde_utils::vec_vec_fixed_string,
     GasDetails,
 };
 
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use brontes_types::{structured_trace::TxTrace, BrontesTaskExecutor};
+use brontes_types::{chain::chain_spec, structured_trace::TxTrace, BrontesTaskExecutor};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_blockchain_tree::{
     externals::TreeExternals, BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree,
@@ -12,18 +12,23 @@ use reth_blockchain_tree::{
 use reth_db::{mdbx::DatabaseArguments, DatabaseEnv};
 use reth_network_api::noop::NoopNetwork;
 use reth_node_ethereum::EthEvmConfig;
-use reth_primitives::{BlockId, PruneModes, MAINNET};
-use reth_provider::{providers::BlockchainProvider, ProviderFactory};
-use reth_revm::{inspectors::GasInspector, EvmProcessorFactory};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, BlockId, PruneModes, TransactionSigned, MAINNET, U256,
+};
+use reth_provider::{providers::BlockchainProvider, BlockReaderIdExt, ProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase, db::CacheDB, inspectors::GasInspector, EvmProcessorFactory,
+};
 use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
-        error::EthResult,
+        error::{EthApiError, EthResult},
         gas_oracle::{GasPriceOracle, GasPriceOracleConfig},
         EthTransactions, FeeHistoryCache, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
     },
     EthApi, TraceApi,
 };
+use reth_rpc_types::TransactionInfo;
 use reth_tasks::pool::{BlockingTaskGuard, BlockingTaskPool};
 use reth_tracer::{
     arena::CallTraceArena,
@@ -34,6 +39,7 @@ use reth_transaction_pool::{
     blobstore::NoopBlobStore, validate::EthTransactionValidatorBuilder, CoinbaseTipOrdering,
     EthPooledTransaction, EthTransactionValidator, Pool, TransactionValidationTaskExecutor,
 };
+use revm::{primitives::EnvWithHandlerCfg, DatabaseCommit};
 mod provider;
 pub mod reth_tracer;
 
@@ -63,6 +69,13 @@ impl TracingClient {
         task_executor: BrontesTaskExecutor,
         static_files_path: PathBuf,
     ) -> Self {
+        // the local db is only read with the ethereum node types, other chains
+        // are traced over rpc
+        assert!(
+            chain_spec().is_mainnet(),
+            "the local reth db only supports mainnet, {} has to be traced over rpc",
+            chain_spec().name
+        );
         let chain = MAINNET.clone();
         let provider_factory =
             ProviderFactory::new(Arc::clone(&db), Arc::clone(&chain), static_files_path)
@@ -149,30 +162,92 @@ impl TracingClient {
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<Vec<TxTrace>>> {
-        let insp_setup = || BrontesTracingInspector {
-            config:                TracingInspectorConfig {
-                record_logs:              true,
-                record_steps:             false,
-                record_state_diff:        false,
-                record_stack_snapshots:   StackSnapshotType::None,
-                record_memory_snapshots:  false,
-                record_call_return_data:  true,
-                exclude_precompile_calls: true,
-            },
-            traces:                CallTraceArena::default(),
-            trace_stack:           Vec::new(),
-            step_stack:            Vec::new(),
-            last_call_return_data: None,
-            gas_inspector:         GasInspector::default(),
-            spec_id:               None,
-        };
-
         self.api
-            .trace_block_with_inspector(block_id, insp_setup, move |tx_info, inspector, res, _, _| {
-                Ok(inspector.into_trace_results(tx_info, &res))
-            })
+            .trace_block_with_inspector(
+                block_id,
+                tracing_inspector,
+                |tx_info, inspector, res, _, _| Ok(inspector.into_trace_results(tx_info, &res)),
+            )
             .await
     }
+
+    /// Executes the transactions in order on top of the state of `parent`
+    /// with the custom inspector. Each transaction sees the state changes of
+    /// the ones before it. The block env is the one of the parent moved to
+    /// the next block, with the base fee that block would have.
+    pub async fn simulate_transactions_with_inspector(
+        &self,
+        parent: BlockId,
+        txs: Vec<TransactionSigned>,
+    ) -> EthResult<Vec<TxTrace>> {
+        let parent_header = self
+            .trace
+            .provider()
+            .header_by_id(parent)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let (cfg, mut block_env, at) = self.api.evm_env_at(parent).await?;
+        block_env.number += U256::from(1);
+        block_env.timestamp += U256::from(chain_spec().block_time);
+        if let Some(base_fee) = chain_spec().next_block_base_fee(&parent_header) {
+            block_env.basefee = U256::from(base_fee);
+        }
+
+        let block_number = block_env.number.to::<u64>();
+        let base_fee = block_env.basefee.to::<u64>();
+        let mut db = CacheDB::new(StateProviderDatabase::new(self.api.state_at(at)?));
+
+        let mut traces = Vec::with_capacity(txs.len());
+        for (index, tx) in txs.into_iter().enumerate() {
+            let tx = tx
+                .into_ecrecovered()
+                .ok_or(EthApiError::InvalidTransactionSignature)?;
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                tx_env_with_recovered(&tx),
+            );
+
+            let mut inspector = tracing_inspector();
+            let (res, _) = self.api.inspect(&mut db, env, &mut inspector)?;
+            db.commit(res.state);
+
+            let tx_info = TransactionInfo {
+                hash:         Some(tx.hash()),
+                index:        Some(index as u64),
+                block_hash:   None,
+                block_number: Some(block_number),
+                base_fee:     Some(base_fee as u128),
+            };
+            let mut trace = inspector.into_trace_results(tx_info, &res.result);
+            trace.effective_price = tx.effective_gas_price(Some(base_fee));
+            traces.push(trace);
+        }
+
+        Ok(traces)
+    }
+}
+
+fn tracing_inspector() -> BrontesTracingInspector {
+    BrontesTracingInspector {
+        config:                TracingInspectorConfig {
+            record_logs:              true,
+            record_steps:             false,
+            record_state_diff:        false,
+            record_stack_snapshots:   StackSnapshotType::None,
+            record_memory_snapshots:  false,
+            record_call_return_data:  true,
+            exclude_precompile_calls: true,
+        },
+        traces:                CallTraceArena::default(),
+        trace_stack:           Vec::new(),
+        step_stack:            Vec::new(),
+        last_call_return_data: None,
+        gas_inspector:         GasInspector::default(),
+        spec_id:               None,
+        access_list_gas:       0,
+        blob_gas_used:         0,
+        blob_gas_price:        0,
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
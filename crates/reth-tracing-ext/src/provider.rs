@@ -4,8 +4,8 @@ use alloy_rpc_types::AnyReceiptEnvelope;
 use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
 use eyre::eyre;
 use reth_primitives::{
-    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
-    B256, U256,
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue,
+    TransactionSigned, TxHash, B256, U256,
 };
 use reth_provider::{BlockIdReader, BlockNumReader, HeaderProvider};
 use reth_revm::{database::StateProviderDatabase, db::CacheDB};
@@ -88,6 +88,21 @@ impl TracingProvider for TracingClient {
             .map_err(Into::into)
     }
 
+    async fn simulate_transactions(
+        &self,
+        parent: BlockId,
+        txs: Vec<Bytes>,
+    ) -> eyre::Result<Vec<TxTrace>> {
+        let txs = txs
+            .into_iter()
+            .map(|raw| TransactionSigned::decode_enveloped(&mut raw.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.simulate_transactions_with_inspector(parent, txs)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn block_receipts(
         &self,
         number: BlockNumberOrTag,
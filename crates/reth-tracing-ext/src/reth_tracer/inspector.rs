@@ -23,6 +23,13 @@ use utils::{gas_used, stack_push_count};
 
 use super::{arena, config, types, utils};
 
+/// Intrinsic gas per address in the access list, see EIP-2930
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+/// Intrinsic gas per storage key in the access list, see EIP-2930
+const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1900;
+/// Blob gas per blob carried by the transaction, see EIP-4844
+const GAS_PER_BLOB: u64 = 131_072;
+
 /// An inspector that collects call traces.
 ///
 /// This [Inspector] can be hooked into revm's EVM which then calls the
@@ -49,6 +56,18 @@ pub struct BrontesTracingInspector {
     ///
     /// This is filled during execution.
     pub spec_id:               Option<SpecId>,
+    /// Intrinsic gas paid for the transaction's access list.
+    ///
+    /// This is filled during execution.
+    pub access_list_gas:       u64,
+    /// Blob gas of the blobs the transaction carries.
+    ///
+    /// This is filled during execution.
+    pub blob_gas_used:         u64,
+    /// Blob base fee of the block.
+    ///
+    /// This is filled during execution.
+    pub blob_gas_price:        u128,
 }
 
 // === impl TracingInspector ===
@@ -64,6 +83,9 @@ impl BrontesTracingInspector {
             last_call_return_data: None,
             gas_inspector: Default::default(),
             spec_id: None,
+            access_list_gas: 0,
+            blob_gas_used: 0,
+            blob_gas_price: 0,
         }
     }
 
@@ -199,6 +221,17 @@ impl BrontesTracingInspector {
             // we set the spec id here because we only need to do this once and this
             // condition is hit exactly once
             self.spec_id = Some(context.spec_id());
+            self.access_list_gas = context
+                .env
+                .tx
+                .access_list
+                .iter()
+                .map(|(_, keys)| {
+                    ACCESS_LIST_ADDRESS_GAS + ACCESS_LIST_STORAGE_KEY_GAS * keys.len() as u64
+                })
+                .sum();
+            self.blob_gas_used = GAS_PER_BLOB * context.env.tx.blob_hashes.len() as u64;
+            self.blob_gas_price = context.env.block.get_blob_gasprice().unwrap_or_default();
         }
 
         self.trace_stack.push(self.traces.push_trace(
@@ -378,6 +411,10 @@ impl BrontesTracingInspector {
 impl BrontesTracingInspector {
     pub fn into_trace_results(self, info: TransactionInfo, res: &ExecutionResult) -> TxTrace {
         let gas_used = res.gas_used().into();
+        let gas_refunded = match res {
+            ExecutionResult::Success { gas_refunded, .. } => *gas_refunded as u128,
+            _ => 0,
+        };
         let trace = self.build_trace(info.hash.unwrap(), info.block_number.unwrap());
 
         TxTrace {
@@ -388,6 +425,10 @@ impl BrontesTracingInspector {
             effective_price: 0,
             tx_index: info.index.unwrap(),
             is_success: res.is_success(),
+            gas_refunded,
+            access_list_gas: self.access_list_gas as u128,
+            blob_gas_used: self.blob_gas_used as u128,
+            blob_gas_price: self.blob_gas_price,
         }
     }
 
@@ -413,6 +454,10 @@ impl BrontesTracingInspector {
 
         let mut traces: Vec<TransactionTraceWithLogs> =
             Vec::with_capacity(self.traces.nodes().len());
+        #[cfg(feature = "provenance")]
+        let mut tx_log_indices = vec![vec![]; self.traces.nodes().len()];
+        #[cfg(feature = "provenance")]
+        receipt_log_indices(self.traces.nodes(), 0, &mut 0, &mut tx_log_indices);
 
         for node in self.iter_traceable_nodes() {
             let trace_address = self.trace_address(self.traces.nodes(), node.idx);
@@ -461,6 +506,8 @@ impl BrontesTracingInspector {
             traces.push(TransactionTraceWithLogs {
                 trace,
                 logs,
+                #[cfg(feature = "provenance")]
+                tx_log_indices: std::mem::take(&mut tx_log_indices[node.idx]),
                 msg_sender,
                 decoded_data: None,
                 trace_idx: node.idx as u64,
@@ -485,6 +532,8 @@ impl BrontesTracingInspector {
                         msg_sender,
                         trace,
                         logs: vec![],
+                        #[cfg(feature = "provenance")]
+                        tx_log_indices: vec![],
                         decoded_data: None,
                         trace_idx: node.idx as u64,
                     });
@@ -777,3 +826,78 @@ impl From<CallKind> for CallType {
         }
     }
 }
+
+/// Sets the index in the transaction receipt of each log of the node and its
+/// subcalls, walking the calls and logs in the order they were executed. Logs
+/// of calls that failed, or whose parent did, were rolled back and aren't
+/// part of the receipt.
+#[cfg(any(test, feature = "provenance"))]
+fn receipt_log_indices(
+    nodes: &[CallTraceNode],
+    idx: usize,
+    next_log: &mut u64,
+    indices: &mut [Vec<u64>],
+) {
+    let node = &nodes[idx];
+    if !node.trace.success {
+        return
+    }
+
+    for order in &node.ordering {
+        match *order {
+            LogCallOrder::Log(_) => {
+                indices[idx].push(*next_log);
+                *next_log += 1;
+            }
+            LogCallOrder::Call(child) => {
+                receipt_log_indices(nodes, node.children[child], next_log, indices)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(idx: usize, success: bool, ordering: Vec<LogCallOrder>) -> CallTraceNode {
+        let children = ordering
+            .iter()
+            .filter(|order| matches!(order, LogCallOrder::Call(_)))
+            .count();
+        CallTraceNode {
+            idx,
+            trace: CallTrace { success, ..Default::default() },
+            // the children of the nodes below are laid out in the arena in order
+            children: (idx + 1..=idx + children).collect(),
+            ordering,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn receipt_log_indices_follow_execution_order() {
+        // root: log, call 1, log, call 2, log. Call 1 emits two logs and call
+        // 2 reverts after emitting one
+        let nodes = vec![
+            node(
+                0,
+                true,
+                vec![
+                    LogCallOrder::Log(0),
+                    LogCallOrder::Call(0),
+                    LogCallOrder::Log(1),
+                    LogCallOrder::Call(1),
+                    LogCallOrder::Log(2),
+                ],
+            ),
+            node(1, true, vec![LogCallOrder::Log(0), LogCallOrder::Log(1)]),
+            node(2, false, vec![LogCallOrder::Log(0)]),
+        ];
+
+        let mut indices = vec![vec![]; nodes.len()];
+        receipt_log_indices(&nodes, 0, &mut 0, &mut indices);
+
+        assert_eq!(indices, vec![vec![0, 3, 4], vec![1, 2], vec![]]);
+    }
+}